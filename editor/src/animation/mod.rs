@@ -383,7 +383,7 @@ impl AnimationEditor {
                     match msg {
                         RulerMessage::Value(value) => {
                             if let Some(animation) = animations.try_get_mut(selection.animation) {
-                                animation.set_time_position(*value);
+                                animation.seek(*value);
                             }
                         }
                         RulerMessage::AddSignal(time) => {
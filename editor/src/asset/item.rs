@@ -39,12 +39,13 @@ use crate::{
             text::TextBuilder,
             widget::{Widget, WidgetBuilder, WidgetMessage},
             BuildContext, Control, HorizontalAlignment, RcUiNodeHandle, Thickness, UiNode,
-            UserInterface, BRUSH_DARKER, BRUSH_DARKEST,
+            UserInterface, VerticalAlignment, BRUSH_DARKER, BRUSH_DARKEST,
         },
         material::Material,
         scene::tilemap::tileset::TileSet,
     },
     message::MessageSender,
+    version_control::FileStatus,
     Message,
 };
 use std::{
@@ -248,6 +249,7 @@ pub struct AssetItemBuilder {
     widget_builder: WidgetBuilder,
     path: Option<PathBuf>,
     icon: Option<UntypedResource>,
+    status: FileStatus,
 }
 
 fn make_tooltip(ctx: &mut BuildContext, text: &str) -> RcUiNodeHandle {
@@ -278,6 +280,7 @@ impl AssetItemBuilder {
             widget_builder,
             path: None,
             icon: None,
+            status: FileStatus::Unmodified,
         }
     }
 
@@ -291,6 +294,11 @@ impl AssetItemBuilder {
         self
     }
 
+    pub fn with_status(mut self, status: FileStatus) -> Self {
+        self.status = status;
+        self
+    }
+
     pub fn build(
         self,
         resource_manager: ResourceManager,
@@ -308,6 +316,16 @@ impl AssetItemBuilder {
         .with_opt_texture(self.icon)
         .build(ctx);
 
+        let status = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(2.0))
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_foreground(self.status.brush()),
+        )
+        .with_text(self.status.marker())
+        .build(ctx);
+
         let item = AssetItem {
             widget: self
                 .widget_builder
@@ -320,6 +338,7 @@ impl AssetItemBuilder {
                         WidgetBuilder::new()
                             .with_width(64.0)
                             .with_child(preview)
+                            .with_child(status)
                             .with_child(
                                 TextBuilder::new(
                                     WidgetBuilder::new()
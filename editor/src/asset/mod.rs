@@ -72,6 +72,7 @@ use crate::{
     message::MessageSender,
     preview::PreviewPanel,
     utils::window_content,
+    version_control::VersionControl,
     Message, Mode,
 };
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
@@ -554,6 +555,7 @@ pub struct AssetBrowser {
     preview_sender: Sender<IconRequest>,
     need_refresh: Arc<AtomicBool>,
     pub preview_generators: AssetPreviewGeneratorsCollection,
+    version_control: VersionControl,
 }
 
 fn is_supported_resource(ext: &OsStr, resource_manager: &ResourceManager) -> bool {
@@ -599,7 +601,7 @@ fn create_file_system_watcher(
 }
 
 impl AssetBrowser {
-    pub fn new(engine: &mut Engine) -> Self {
+    pub fn new(engine: &mut Engine, version_control: VersionControl) -> Self {
         let preview = PreviewPanel::new(engine, 250, 250);
         let ctx = &mut engine.user_interfaces.first_mut().build_ctx();
 
@@ -765,6 +767,7 @@ impl AssetBrowser {
             preview_generators: AssetPreviewGeneratorsCollection::new(),
             refresh,
             watcher,
+            version_control,
         }
     }
 
@@ -824,6 +827,7 @@ impl AssetBrowser {
             None
         })
         .with_path(path)
+        .with_status(self.version_control.status(path))
         .build(
             resource_manager.clone(),
             message_sender.clone(),
@@ -912,6 +916,10 @@ impl AssetBrowser {
         // Clean content panel first.
         self.clear_assets(ui);
 
+        // The content of the folder could be changed, so the statuses of the files must be
+        // fetched again.
+        self.version_control.refresh();
+
         // Add "return" item.
         if let Some(mut parent_path) = make_relative_path(&self.selected_path)
             .ok()
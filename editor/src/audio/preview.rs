@@ -36,7 +36,7 @@ use crate::fyrox::{
     },
     scene::{
         node::Node,
-        sound::{Sound, Status},
+        sound::{listener::Listener, Sound, Status},
     },
 };
 use crate::{
@@ -44,6 +44,22 @@ use crate::{
     send_sync_message, Message,
 };
 
+fn make_check_box(text: &str, column: usize, ctx: &mut BuildContext) -> Handle<UiNode> {
+    CheckBoxBuilder::new(
+        WidgetBuilder::new()
+            .on_column(column)
+            .with_vertical_alignment(VerticalAlignment::Center)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_content(
+        TextBuilder::new(WidgetBuilder::new().with_vertical_alignment(VerticalAlignment::Center))
+            .with_text(text)
+            .build(ctx),
+    )
+    .checked(Some(false))
+    .build(ctx)
+}
+
 pub struct AudioPreviewPanel {
     pub window: Handle<UiNode>,
     preview: Handle<UiNode>,
@@ -52,6 +68,9 @@ pub struct AudioPreviewPanel {
     stop: Handle<UiNode>,
     rewind: Handle<UiNode>,
     time: Handle<UiNode>,
+    play_on_select: Handle<UiNode>,
+    listener_follows_camera: Handle<UiNode>,
+    is_play_on_select: bool,
     sounds_state: Vec<(Handle<Node>, Node)>,
     scene_viewer_frame: Handle<UiNode>,
 }
@@ -64,11 +83,13 @@ impl AudioPreviewPanel {
         let stop;
         let rewind;
         let time;
+        let play_on_select;
+        let listener_follows_camera;
         let window = WindowBuilder::new(
             WidgetBuilder::new()
                 .with_name("AudioPreviewPanel")
                 .with_width(300.0)
-                .with_height(70.0),
+                .with_height(95.0),
         )
         .with_title(WindowTitle::text("Audio Preview Panel"))
         .open(false)
@@ -168,11 +189,31 @@ impl AudioPreviewPanel {
                         .add_column(Column::stretch())
                         .add_row(Row::strict(20.0))
                         .build(ctx),
+                    )
+                    .with_child(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(2)
+                                .with_child({
+                                    play_on_select = make_check_box("Play On Select", 0, ctx);
+                                    play_on_select
+                                })
+                                .with_child({
+                                    listener_follows_camera =
+                                        make_check_box("Listener Follows Camera", 1, ctx);
+                                    listener_follows_camera
+                                }),
+                        )
+                        .add_column(Column::stretch())
+                        .add_column(Column::stretch())
+                        .add_row(Row::strict(20.0))
+                        .build(ctx),
                     ),
             )
             .add_column(Column::stretch())
             .add_row(Row::stretch())
             .add_row(Row::strict(20.0))
+            .add_row(Row::strict(20.0))
             .build(ctx),
         )
         .build(ctx);
@@ -185,6 +226,9 @@ impl AudioPreviewPanel {
             stop,
             rewind,
             time,
+            play_on_select,
+            listener_follows_camera,
+            is_play_on_select: false,
             sounds_state: vec![],
             scene_viewer_frame,
         }
@@ -212,6 +256,18 @@ impl AudioPreviewPanel {
                     .iter()
                     .any(|n| scene.graph.try_get_of_type::<Sound>(*n).is_some());
                 if any_sound_selected {
+                    send_sync_message(
+                        engine.user_interfaces.first(),
+                        CheckBoxMessage::checked(
+                            self.listener_follows_camera,
+                            MessageDirection::ToWidget,
+                            Some(
+                                game_scene.graph_switches.active_listener
+                                    == Some(game_scene.camera_controller.listener),
+                            ),
+                        ),
+                    );
+
                     engine
                         .user_interfaces
                         .first_mut()
@@ -225,6 +281,10 @@ impl AudioPreviewPanel {
                             false,
                             false,
                         ));
+
+                    if self.is_play_on_select {
+                        self.audition(editor_selection, game_scene, engine);
+                    }
                 } else {
                     engine
                         .user_interfaces
@@ -233,6 +293,39 @@ impl AudioPreviewPanel {
                             self.window,
                             MessageDirection::ToWidget,
                         ));
+
+                    if self.is_play_on_select && self.is_in_preview_mode() {
+                        self.leave_preview_mode(game_scene, engine);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Restarts preview mode for the current selection and plays every selected sound.
+    fn audition(
+        &mut self,
+        editor_selection: &Selection,
+        game_scene: &mut GameScene,
+        engine: &mut Engine,
+    ) {
+        if self.is_in_preview_mode() {
+            self.leave_preview_mode(game_scene, engine);
+        }
+
+        self.enter_preview_mode(editor_selection, game_scene, engine);
+
+        send_sync_message(
+            engine.user_interfaces.first(),
+            CheckBoxMessage::checked(self.preview, MessageDirection::ToWidget, Some(true)),
+        );
+
+        if let Some(selection) = editor_selection.as_graph() {
+            let scene = &mut engine.scenes[game_scene.scene];
+            for &node in &selection.nodes {
+                if let Some(sound) = scene.graph.try_get_mut_of_type::<Sound>(node) {
+                    sound.set_playback_time(0.0);
+                    sound.set_status(Status::Playing);
                 }
             }
         }
@@ -340,6 +433,26 @@ impl AudioPreviewPanel {
         game_scene: &mut GameScene,
         engine: &mut Engine,
     ) {
+        if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.direction() == MessageDirection::FromWidget {
+                if message.destination() == self.play_on_select {
+                    self.is_play_on_select = *value;
+                } else if message.destination() == self.listener_follows_camera {
+                    let graph = &engine.scenes[game_scene.scene].graph;
+                    game_scene.graph_switches.active_listener = if *value {
+                        Some(game_scene.camera_controller.listener)
+                    } else {
+                        // Use the first listener of the scene itself, if any.
+                        graph
+                            .find(game_scene.scene_content_root, &mut |n| {
+                                n.cast::<Listener>().is_some()
+                            })
+                            .map(|(handle, _)| handle)
+                    };
+                }
+            }
+        }
+
         if let Some(selection) = editor_selection.as_graph() {
             if let Some(ButtonMessage::Click) = message.data() {
                 let scene = &mut engine.scenes[game_scene.scene];
@@ -79,6 +79,7 @@ pub struct CameraController {
     pub pivot: Handle<Node>,
     pub camera_hinge: Handle<Node>,
     pub camera: Handle<Node>,
+    pub listener: Handle<Node>,
     yaw: f32,
     pitch: f32,
     pub z_offset: f32,
@@ -140,15 +141,18 @@ impl CameraController {
 
         let camera;
         let camera_hinge;
+        let listener;
         let pivot = PivotBuilder::new(
             BaseBuilder::new()
                 .with_children(&[{
                     camera_hinge = PivotBuilder::new(BaseBuilder::new().with_children(&[{
                         camera = CameraBuilder::new(
                             BaseBuilder::new()
-                                .with_children(&[
-                                    ListenerBuilder::new(BaseBuilder::new()).build(graph)
-                                ])
+                                .with_children(&[{
+                                    listener =
+                                        ListenerBuilder::new(BaseBuilder::new()).build(graph);
+                                    listener
+                                }])
                                 .with_name("EditorCamera"),
                         )
                         .with_projection(settings.projection)
@@ -175,6 +179,7 @@ impl CameraController {
             pivot,
             camera_hinge,
             camera,
+            listener,
             yaw: settings.yaw,
             pitch: settings.pitch,
             mouse_control_mode: MouseControlMode::None,
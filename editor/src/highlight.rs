@@ -209,6 +209,7 @@ impl SceneRenderPass for HighlightRenderPass {
                 storage: &mut render_bundle_storage,
                 graph: &ctx.scene.graph,
                 render_pass_name: &render_pass_name,
+                use_gpu_skinning: true,
             };
 
             for &root_node_handle in self.nodes_to_highlight.iter() {
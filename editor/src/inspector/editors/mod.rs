@@ -58,7 +58,7 @@ use crate::{
             },
             camera::{
                 ColorGradingLut, Exposure, OrthographicProjection, PerspectiveProjection,
-                Projection, SkyBox,
+                PostProcessingSettings, Posterization, ProceduralSky, Projection, SkyBox, Vignette,
             },
             collider::{
                 BallShape, BitMask, CapsuleShape, ColliderShape, ConeShape, ConvexPolyhedronShape,
@@ -66,11 +66,12 @@ use crate::{
                 SegmentShape, TriangleShape, TrimeshShape,
             },
             dim2,
+            foliage::{FoliageImpostor, FoliageLayer},
             graph::physics::CoefficientCombineRule,
             joint::*,
             light::{
                 directional::{CsmOptions, FrustumSplitOptions},
-                BaseLight,
+                BaseLight, ShadowFilter, ShadowMapOptions, VolumetricLightOptions,
             },
             mesh::{
                 surface::{BlendShape, Surface, SurfaceResource},
@@ -79,15 +80,20 @@ use crate::{
             node::Node,
             particle_system::{
                 emitter::{
-                    base::BaseEmitter, cuboid::CuboidEmitter, cylinder::CylinderEmitter,
-                    sphere::SphereEmitter, Emitter,
+                    base::{BaseEmitter, ParticleSimulationMode},
+                    cuboid::CuboidEmitter,
+                    cylinder::CylinderEmitter,
+                    sphere::SphereEmitter,
+                    Emitter,
                 },
                 ParticleSystemRng,
             },
+            probe::{ReflectionProbeProjection, ReflectionProbeUpdateMode},
             ragdoll::Limb,
             rigidbody::RigidBodyType,
             sound::{
                 self,
+                compressor::Compressor,
                 filter::{
                     AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect,
                     HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
@@ -252,6 +258,11 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_vec_collection::<Layer>();
     container.register_inheritable_inspectable::<Layer>();
 
+    container.register_inheritable_vec_collection::<FoliageLayer>();
+    container.register_inheritable_inspectable::<FoliageLayer>();
+    container.register_inheritable_inspectable::<FoliageImpostor>();
+    container.register_inheritable_option::<FoliageImpostor>();
+
     container.register_inheritable_vec_collection::<Emitter>();
 
     container.register_inheritable_vec_collection::<LevelOfDetail>();
@@ -335,6 +346,9 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<TileMapBrush>();
 
     container.register_inheritable_inspectable::<ColorGradingLut>();
+    container.register_inheritable_inspectable::<PostProcessingSettings>();
+    container.insert(InspectablePropertyEditorDefinition::<Vignette>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Posterization>::new());
     container.register_inheritable_inspectable::<InteractionGroups>();
 
     container.register_inheritable_enum::<JointParams, _>();
@@ -347,6 +361,9 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<RevoluteJoint>();
     container.register_inheritable_inspectable::<PrismaticJoint>();
     container.register_inheritable_inspectable::<dim2::joint::PrismaticJoint>();
+    container.register_inheritable_inspectable::<SixDofJoint>();
+    container.register_inheritable_inspectable::<SixDofJointAxis>();
+    container.register_inheritable_inspectable::<JointMotor>();
 
     container.register_inheritable_inspectable::<Base>();
     container.register_inheritable_inspectable::<BaseLight>();
@@ -362,12 +379,14 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(InspectablePropertyEditorDefinition::<LowShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<HighShelfFilterEffect>::new());
     container.insert(InspectablePropertyEditorDefinition::<Reverb>::new());
+    container.insert(InspectablePropertyEditorDefinition::<Compressor>::new());
 
     container.register_inheritable_enum::<Emitter, _>();
 
     container.register_inheritable_inspectable::<Biquad>();
     container.register_inheritable_inspectable::<AudioBus>();
     container.register_inheritable_inspectable::<BaseEmitter>();
+    container.insert(EnumPropertyEditorDefinition::<ParticleSimulationMode>::new());
     container.register_inheritable_inspectable::<SphereEmitter>();
     container.register_inheritable_inspectable::<CylinderEmitter>();
     container.register_inheritable_inspectable::<CuboidEmitter>();
@@ -375,6 +394,8 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<OrthographicProjection>();
     container.register_inheritable_inspectable::<Transform>();
     container.register_inheritable_inspectable::<CsmOptions>();
+    container.register_inheritable_inspectable::<ShadowMapOptions>();
+    container.register_inheritable_inspectable::<VolumetricLightOptions>();
 
     container.register_inheritable_inspectable::<Chunk>();
     container.register_inheritable_vec_collection::<Chunk>();
@@ -385,8 +406,10 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_option::<ColorGradingLut>();
     container.register_inheritable_option::<Biquad>();
     container.register_inheritable_option::<SkyBox>();
+    container.register_inheritable_option::<ProceduralSky>();
 
     container.register_inheritable_inspectable::<SkyBox>();
+    container.register_inheritable_inspectable::<ProceduralSky>();
 
     container.register_inheritable_enum::<dim2::collider::ColliderShape, _>();
     container.register_inheritable_enum::<CoefficientCombineRule, _>();
@@ -401,10 +424,13 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<RigidBodyType, _>();
     container.register_inheritable_enum::<Exposure, _>();
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
+    container.register_inheritable_enum::<ShadowFilter, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<ReflectionProbeProjection, _>();
+    container.register_inheritable_enum::<ReflectionProbeUpdateMode, _>();
 
     container.insert(EnumPropertyEditorDefinition::<Vec<ScriptRecord>>::new_optional());
     container.insert(VecCollectionPropertyEditorDefinition::<ScriptRecord>::new());
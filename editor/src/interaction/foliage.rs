@@ -0,0 +1,485 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    fyrox::{
+        core::{
+            algebra::{Vector2, Vector3},
+            arrayvec::ArrayVec,
+            log::Log,
+            math::{plane::Plane, ray::Ray},
+            pool::Handle,
+            reflect::prelude::*,
+            uuid::{uuid, Uuid},
+            TypeUuidProvider,
+        },
+        engine::Engine,
+        graph::SceneGraph,
+        gui::{
+            inspector::{
+                editors::PropertyEditorDefinitionContainer, Inspector, InspectorBuilder,
+                InspectorContext, InspectorMessage, PropertyAction,
+            },
+            key::HotKey,
+            message::{MessageDirection, UiMessage},
+            widget::{WidgetBuilder, WidgetMessage},
+            window::{WindowBuilder, WindowMessage, WindowTitle},
+            BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+        },
+        scene::{
+            camera::Camera,
+            foliage::{Foliage, FoliageInstance},
+            graph::Graph,
+            node::Node,
+            terrain::{Terrain, TerrainRayCastResult},
+        },
+    },
+    interaction::{make_interaction_mode_button, terrain::BrushGizmo, InteractionMode},
+    message::MessageSender,
+    scene::{
+        commands::foliage::ModifyFoliageInstancesCommand, controller::SceneController, GameScene,
+        Selection, SelectionContainer,
+    },
+    settings::Settings,
+    MSG_SYNC_FLAG,
+};
+use std::sync::Arc;
+
+/// Options of the foliage brush. All distances are in local units of the foliage node.
+#[derive(Clone, Debug, Reflect)]
+pub struct FoliageBrush {
+    /// Index of the foliage layer to paint.
+    pub layer: usize,
+    /// Radius of the brush.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub radius: f32,
+    /// Desired amount of instances per square unit under the brush.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub density: f32,
+    /// How fast the brush reaches the desired density. 1.0 means that a single stamp of the brush
+    /// adds all the missing instances.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub strength: f32,
+}
+
+impl Default for FoliageBrush {
+    fn default() -> Self {
+        Self {
+            layer: 0,
+            radius: 2.0,
+            density: 2.0,
+            strength: 0.25,
+        }
+    }
+}
+
+struct FoliageStroke {
+    foliage: Handle<Node>,
+    layer: usize,
+    erase: bool,
+    // State of the layer before the stroke, it is used for undo.
+    instances: Vec<FoliageInstance>,
+    last_stamp: Option<Vector2<f32>>,
+}
+
+pub struct FoliageInteractionMode {
+    message_sender: MessageSender,
+    brush: FoliageBrush,
+    brush_panel: FoliageBrushPanel,
+    brush_gizmo: BrushGizmo,
+    stroke: Option<FoliageStroke>,
+    scene_viewer_frame: Handle<UiNode>,
+}
+
+impl FoliageInteractionMode {
+    pub fn new(
+        game_scene: &GameScene,
+        engine: &mut Engine,
+        message_sender: MessageSender,
+        scene_viewer_frame: Handle<UiNode>,
+    ) -> Self {
+        let brush = FoliageBrush::default();
+        let brush_panel =
+            FoliageBrushPanel::new(&mut engine.user_interfaces.first_mut().build_ctx(), &brush);
+
+        Self {
+            message_sender,
+            brush,
+            brush_panel,
+            brush_gizmo: BrushGizmo::new(game_scene, engine),
+            stroke: None,
+            scene_viewer_frame,
+        }
+    }
+
+    fn stamp(&mut self, foliage: &mut Foliage, position: Vector3<f32>) {
+        let Some(stroke) = self.stroke.as_mut() else {
+            return;
+        };
+        let Some(center) = foliage.project(position) else {
+            return;
+        };
+        let brush = &self.brush;
+        // Overlapping stamps are pointless, because the density is already reached there.
+        if let Some(last_stamp) = stroke.last_stamp {
+            if (center - last_stamp).norm() < brush.radius * 0.5 {
+                return;
+            }
+        }
+        stroke.last_stamp = Some(center);
+
+        if stroke.erase {
+            foliage.erase(stroke.layer, center, brush.radius);
+            return;
+        }
+
+        let Some(layer) = foliage.layers().get(stroke.layer) else {
+            return;
+        };
+        let existing = layer
+            .instances
+            .iter()
+            .filter(|instance| (instance.position - center).norm() <= brush.radius)
+            .count();
+        let desired = brush.density * std::f32::consts::PI * brush.radius * brush.radius;
+        let missing = ((desired - existing as f32) * brush.strength)
+            .ceil()
+            .max(0.0) as usize;
+        foliage.paint(stroke.layer, center, brush.radius, missing);
+    }
+}
+
+/// Returns a point under the cursor on the surface, that is covered by the foliage, and its normal.
+fn pick(graph: &Graph, foliage: &Foliage, ray: Ray) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    if let Some(terrain) = graph.try_get_of_type::<Terrain>(foliage.target()) {
+        let mut intersections = ArrayVec::<TerrainRayCastResult, 128>::new();
+        terrain.raycast(ray, &mut intersections, true);
+        return intersections
+            .first()
+            .map(|closest| (closest.position, closest.normal));
+    }
+    let normal = foliage.up_vector();
+    let plane = Plane::from_normal_and_point(&normal, &foliage.global_position())?;
+    ray.plane_intersection_point(&plane)
+        .map(|point| (point, normal))
+}
+
+impl TypeUuidProvider for FoliageInteractionMode {
+    fn type_uuid() -> Uuid {
+        uuid!("a6a9990c-56f2-44e0-8500-84fdf90aee68")
+    }
+}
+
+impl InteractionMode for FoliageInteractionMode {
+    fn on_left_mouse_button_down(
+        &mut self,
+        editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        mouse_pos: Vector2<f32>,
+        frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+        let Some(selection) = editor_selection.as_graph() else {
+            return;
+        };
+        if !selection.is_single_selection() {
+            return;
+        }
+
+        // Holding shift erases the instances.
+        let erase = engine
+            .user_interfaces
+            .first_mut()
+            .keyboard_modifiers()
+            .shift;
+        let graph = &mut engine.scenes[game_scene.scene].graph;
+        let handle = selection.nodes()[0];
+        let Some(ray) = graph
+            .try_get_of_type::<Camera>(game_scene.camera_controller.camera)
+            .map(|camera| camera.make_ray(mouse_pos, frame_size))
+        else {
+            return;
+        };
+        let Some(foliage) = graph.try_get_of_type::<Foliage>(handle) else {
+            return;
+        };
+        let Some(layer) = foliage.layers().get(self.brush.layer) else {
+            Log::err(format!(
+                "Invalid foliage brush stroke. There's no layer {}.",
+                self.brush.layer
+            ));
+            return;
+        };
+        let instances = layer.instances.clone();
+        let Some((position, _)) = pick(graph, foliage, ray) else {
+            return;
+        };
+
+        self.stroke = Some(FoliageStroke {
+            foliage: handle,
+            layer: self.brush.layer,
+            erase,
+            instances,
+            last_stamp: None,
+        });
+        if let Some(foliage) = graph.try_get_mut_of_type::<Foliage>(handle) {
+            self.stamp(foliage, position);
+        }
+    }
+
+    fn on_left_mouse_button_up(
+        &mut self,
+        _editor_selection: &Selection,
+        _controller: &mut dyn SceneController,
+        _engine: &mut Engine,
+        _mouse_pos: Vector2<f32>,
+        _frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        if let Some(stroke) = self.stroke.take() {
+            self.message_sender
+                .do_command(ModifyFoliageInstancesCommand::new(
+                    stroke.foliage,
+                    stroke.layer,
+                    stroke.instances,
+                ));
+        }
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        _mouse_offset: Vector2<f32>,
+        mouse_position: Vector2<f32>,
+        editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        frame_size: Vector2<f32>,
+        _settings: &Settings,
+    ) {
+        let Some(game_scene) = controller.downcast_mut::<GameScene>() else {
+            return;
+        };
+        let graph = &mut engine.scenes[game_scene.scene].graph;
+
+        let mut picked = None;
+        if let Some(selection) = editor_selection.as_graph() {
+            if selection.is_single_selection() {
+                let handle = selection.nodes()[0];
+                if let (Some(camera), Some(foliage)) = (
+                    graph.try_get_of_type::<Camera>(game_scene.camera_controller.camera),
+                    graph.try_get_of_type::<Foliage>(handle),
+                ) {
+                    let ray = camera.make_ray(mouse_position, frame_size);
+                    picked = pick(graph, foliage, ray).map(|picked| (handle, picked));
+                }
+            }
+        }
+
+        if let Some((handle, (position, normal))) = picked {
+            if self.stroke.is_some() {
+                if let Some(foliage) = graph.try_get_mut_of_type::<Foliage>(handle) {
+                    self.stamp(foliage, position);
+                }
+            }
+            let diameter = self.brush.radius * 2.0;
+            self.brush_gizmo
+                .place(graph, position, normal, Vector2::repeat(diameter));
+        }
+        self.brush_gizmo.set_visible(graph, picked.is_some());
+    }
+
+    fn activate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        let Some(game_scene) = controller.downcast_ref::<GameScene>() else {
+            return;
+        };
+
+        self.brush_gizmo
+            .set_visible(&mut engine.scenes[game_scene.scene].graph, true);
+
+        let ui = engine.user_interfaces.first_mut();
+        self.brush_panel.sync_to_model(ui, &self.brush);
+        ui.send_message(WindowMessage::open_and_align(
+            self.brush_panel.window,
+            MessageDirection::ToWidget,
+            self.scene_viewer_frame,
+            HorizontalAlignment::Right,
+            VerticalAlignment::Top,
+            Thickness::top_right(5.0),
+            false,
+            false,
+        ));
+    }
+
+    fn deactivate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
+        let Some(game_scene) = controller.downcast_ref::<GameScene>() else {
+            return;
+        };
+
+        self.brush_gizmo
+            .set_visible(&mut engine.scenes[game_scene.scene].graph, false);
+
+        engine
+            .user_interfaces
+            .first_mut()
+            .send_message(WindowMessage::close(
+                self.brush_panel.window,
+                MessageDirection::ToWidget,
+            ));
+    }
+
+    fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        _editor_selection: &Selection,
+        _controller: &mut dyn SceneController,
+        _engine: &mut Engine,
+    ) {
+        self.brush_panel.handle_ui_message(message, &mut self.brush);
+    }
+
+    fn on_drop(&mut self, engine: &mut Engine) {
+        engine
+            .user_interfaces
+            .first_mut()
+            .send_message(WidgetMessage::remove(
+                self.brush_panel.window,
+                MessageDirection::ToWidget,
+            ));
+    }
+
+    fn on_hot_key_pressed(
+        &mut self,
+        hotkey: &HotKey,
+        _controller: &mut dyn SceneController,
+        engine: &mut Engine,
+        settings: &Settings,
+    ) -> bool {
+        // Foliage brush shares the key bindings with the terrain brush.
+        let key_bindings = &settings.key_bindings.terrain_key_bindings;
+        if hotkey == &key_bindings.increase_brush_size {
+            self.brush.radius += 0.05;
+        } else if hotkey == &key_bindings.decrease_brush_size {
+            self.brush.radius = (self.brush.radius - 0.05).max(0.0);
+        } else if hotkey == &key_bindings.increase_brush_opacity {
+            self.brush.strength = (self.brush.strength + 0.01).min(1.0);
+        } else if hotkey == &key_bindings.decrease_brush_opacity {
+            self.brush.strength = (self.brush.strength - 0.01).max(0.0);
+        } else if hotkey == &key_bindings.prev_layer {
+            self.brush.layer = self.brush.layer.saturating_sub(1);
+        } else if hotkey == &key_bindings.next_layer {
+            self.brush.layer = self.brush.layer.saturating_add(1);
+        } else {
+            return false;
+        }
+
+        self.brush_panel
+            .sync_to_model(engine.user_interfaces.first_mut(), &self.brush);
+
+        true
+    }
+
+    fn make_button(&mut self, ctx: &mut BuildContext, selected: bool) -> Handle<UiNode> {
+        let foliage_mode_tooltip = "Paint Foliage\n\nFoliage paint mode allows you to add \
+        instances to the selected foliage. Hold Shift to erase instances.";
+
+        make_interaction_mode_button(
+            ctx,
+            include_bytes!("../../resources/brush.png"),
+            foliage_mode_tooltip,
+            selected,
+        )
+    }
+
+    fn uuid(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+struct FoliageBrushPanel {
+    window: Handle<UiNode>,
+    inspector: Handle<UiNode>,
+}
+
+impl FoliageBrushPanel {
+    fn new(ctx: &mut BuildContext, brush: &FoliageBrush) -> Self {
+        let context = InspectorContext::from_object(
+            brush,
+            ctx,
+            Arc::new(PropertyEditorDefinitionContainer::with_default_editors()),
+            None,
+            MSG_SYNC_FLAG,
+            0,
+            true,
+            Default::default(),
+            150.0,
+        );
+
+        let inspector;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(150.0))
+            .can_minimize(false)
+            .can_maximize(false)
+            .with_content({
+                inspector = InspectorBuilder::new(WidgetBuilder::new())
+                    .with_context(context)
+                    .build(ctx);
+                inspector
+            })
+            .open(false)
+            .with_title(WindowTitle::text("Foliage Brush Options"))
+            .build(ctx);
+
+        Self { window, inspector }
+    }
+
+    fn sync_to_model(&self, ui: &mut UserInterface, brush: &FoliageBrush) {
+        let Some(ctx) = ui
+            .try_get_of_type::<Inspector>(self.inspector)
+            .map(|inspector| inspector.context().clone())
+        else {
+            return;
+        };
+
+        if let Err(e) = ctx.sync(brush, ui, 0, true, Default::default()) {
+            Log::err(format!(
+                "Failed to sync FoliageBrushPanel's inspector. Reason: {e:?}"
+            ))
+        }
+    }
+
+    fn handle_ui_message(&self, message: &UiMessage, brush: &mut FoliageBrush) {
+        if message.destination() == self.inspector
+            && message.direction() == MessageDirection::FromWidget
+        {
+            if let Some(InspectorMessage::PropertyChanged(msg)) = message.data() {
+                PropertyAction::from_field_kind(&msg.value).apply(
+                    &msg.path(),
+                    brush,
+                    &mut |result| {
+                        Log::verify(result);
+                    },
+                );
+            }
+        }
+    }
+}
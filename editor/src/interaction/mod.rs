@@ -50,6 +50,7 @@ use crate::{
 };
 use std::any::Any;
 
+pub mod foliage;
 pub mod gizmo;
 pub mod move_mode;
 pub mod navmesh;
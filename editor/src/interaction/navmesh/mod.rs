@@ -24,6 +24,7 @@ use crate::fyrox::{
     core::{
         algebra::{Vector2, Vector3},
         color::Color,
+        log::Log,
         math::{ray::CylinderKind, TriangleEdge},
         pool::Handle,
         uuid::{uuid, Uuid},
@@ -40,7 +41,12 @@ use crate::fyrox::{
         BuildContext, Orientation, Thickness, UiNode, UserInterface,
     },
     gui::{HorizontalAlignment, VerticalAlignment},
-    scene::{camera::Camera, navmesh::NavigationalMesh},
+    scene::{
+        camera::Camera,
+        debug::{Line, SceneDrawingContext},
+        navmesh::NavigationalMesh,
+    },
+    utils::astar::PathKind,
 };
 use crate::scene::SelectionContainer;
 use crate::{
@@ -215,11 +221,43 @@ impl DragContext {
     }
 }
 
+// A path between two points on the navmesh, that is used to test path queries.
+#[derive(Default)]
+struct TestPath {
+    begin: Option<Vector3<f32>>,
+    end: Option<Vector3<f32>>,
+    path: Vec<Vector3<f32>>,
+}
+
+impl TestPath {
+    fn clear(&mut self) {
+        self.begin = None;
+        self.end = None;
+        self.path.clear();
+    }
+
+    fn draw(&self, ctx: &mut SceneDrawingContext, radius: f32) {
+        for (point, color) in [(self.begin, Color::YELLOW), (self.end, Color::CYAN)] {
+            if let Some(point) = point {
+                ctx.draw_sphere(point, 10, 10, radius, color);
+            }
+        }
+        for segment in self.path.windows(2) {
+            ctx.add_line(Line {
+                begin: segment[0],
+                end: segment[1],
+                color: Color::ORANGE,
+            });
+        }
+    }
+}
+
 pub struct EditNavmeshMode {
     move_gizmo: MoveGizmo,
     message_sender: MessageSender,
     drag_context: Option<DragContext>,
     plane_kind: PlaneKind,
+    test_path: TestPath,
 }
 
 impl EditNavmeshMode {
@@ -229,6 +267,7 @@ impl EditNavmeshMode {
             message_sender,
             drag_context: None,
             plane_kind: PlaneKind::X,
+            test_path: Default::default(),
         }
     }
 }
@@ -277,6 +316,42 @@ impl InteractionMode for EditNavmeshMode {
         if let Some(selection) = fetch_selection(editor_selection) {
             let graph = &mut engine.scenes[game_scene.scene].graph;
 
+            // Alt+Click sets the end points of a test path.
+            if engine.user_interfaces.first().keyboard_modifiers().alt {
+                if let Some(navmesh) = graph
+                    .try_get_of_type::<NavigationalMesh>(selection.navmesh_node())
+                    .map(|n| n.navmesh_ref())
+                {
+                    if let Some((point, _)) = navmesh.ray_cast(ray) {
+                        match self.test_path.begin {
+                            Some(begin) if self.test_path.end.is_none() => {
+                                self.test_path.end = Some(point);
+                                match navmesh.query_path(
+                                    begin,
+                                    point,
+                                    0.0,
+                                    std::f32::consts::FRAC_PI_2,
+                                    &mut self.test_path.path,
+                                ) {
+                                    Ok(PathKind::Full) => (),
+                                    Ok(PathKind::Partial) => {
+                                        Log::warn("The end point of the test path is unreachable.")
+                                    }
+                                    Err(err) => Log::err(format!(
+                                        "Unable to build the test path. Reason: {err:?}"
+                                    )),
+                                }
+                            }
+                            _ => {
+                                self.test_path.clear();
+                                self.test_path.begin = Some(point);
+                            }
+                        }
+                    }
+                }
+                return;
+            }
+
             if let Some(plane_kind) = self.move_gizmo.handle_pick(editor_node, graph) {
                 if let Some(navmesh) = graph
                     .try_get_of_type::<NavigationalMesh>(selection.navmesh_node())
@@ -519,6 +594,9 @@ impl InteractionMode for EditNavmeshMode {
         let scene = &mut engine.scenes[game_scene.scene];
         self.move_gizmo.set_visible(&mut scene.graph, false);
 
+        self.test_path
+            .draw(&mut scene.drawing_context, settings.navmesh.vertex_radius);
+
         let scale = calculate_gizmo_distance_scaling(
             &scene.graph,
             game_scene.camera_controller.camera,
@@ -600,6 +678,7 @@ impl InteractionMode for EditNavmeshMode {
 
         let scene = &mut engine.scenes[game_scene.scene];
         self.move_gizmo.set_visible(&mut scene.graph, false);
+        self.test_path.clear();
     }
 
     fn on_key_down(
@@ -681,7 +760,7 @@ impl InteractionMode for EditNavmeshMode {
     fn make_button(&mut self, ctx: &mut BuildContext, selected: bool) -> Handle<UiNode> {
         let navmesh_mode_tooltip =
             "Edit Navmesh\n\nNavmesh edit mode allows you to modify selected \
-        navigational mesh.";
+        navigational mesh. Alt+Click on the navmesh twice to test a path between two points.";
 
         make_interaction_mode_button(
             ctx,
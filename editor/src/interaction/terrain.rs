@@ -22,7 +22,7 @@ use fyrox::scene::terrain::brushstroke::{BrushSender, BrushThreadMessage, UndoDa
 
 use crate::fyrox::core::uuid::{uuid, Uuid};
 use crate::fyrox::core::TypeUuidProvider;
-use crate::fyrox::graph::BaseSceneGraph;
+use crate::fyrox::graph::{BaseSceneGraph, SceneGraph};
 use crate::fyrox::gui::{HorizontalAlignment, Thickness, VerticalAlignment};
 use crate::fyrox::{
     core::{
@@ -50,6 +50,7 @@ use crate::fyrox::{
     scene::{
         base::BaseBuilder,
         camera::Camera,
+        foliage::Foliage,
         graph::Graph,
         mesh::{
             surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
@@ -182,7 +183,7 @@ impl TerrainInteractionMode {
         // Reverse the behavior of a brush when shift is held.
         if shift {
             match &mut brush.mode {
-                BrushMode::Raise { amount } => {
+                BrushMode::Raise { amount } | BrushMode::Noise { amount, .. } => {
                     *amount *= -1.0;
                 }
                 BrushMode::Assign { value } => {
@@ -257,7 +258,25 @@ impl BrushGizmo {
     }
 
     pub fn set_visible(&self, graph: &mut Graph, visibility: bool) {
-        graph[self.brush].set_visibility(visibility);
+        let gizmo = &mut graph[self.brush];
+        if gizmo.visibility() != visibility {
+            gizmo.set_visibility(visibility);
+        }
+    }
+
+    /// Places the gizmo at the given point of a surface with the given normal.
+    pub fn place(
+        &self,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        normal: Vector3<f32>,
+        size: Vector2<f32>,
+    ) {
+        graph[self.brush]
+            .local_transform_mut()
+            .set_position(position)
+            .set_scale(Vector3::new(size.x, size.y, 1.0))
+            .set_rotation(vector_to_quat(normal));
     }
 }
 
@@ -332,9 +351,9 @@ impl InteractionMode for TerrainInteractionMode {
 
     fn on_left_mouse_button_up(
         &mut self,
-        _editor_selection: &Selection,
-        _controller: &mut dyn SceneController,
-        _engine: &mut Engine,
+        editor_selection: &Selection,
+        controller: &mut dyn SceneController,
+        engine: &mut Engine,
         _mouse_pos: Vector2<f32>,
         _frame_size: Vector2<f32>,
         _settings: &Settings,
@@ -344,6 +363,23 @@ impl InteractionMode for TerrainInteractionMode {
                 s.end_stroke()
             }
             self.interacting = false;
+
+            // Foliage does not track changes of the height map of its terrain, so it must be
+            // placed again.
+            if let (Some(game_scene), Some(selection), BrushTarget::HeightMap) = (
+                controller.downcast_mut::<GameScene>(),
+                editor_selection.as_graph(),
+                self.brush.target,
+            ) {
+                let graph = &engine.scenes[game_scene.scene].graph;
+                for (_, node) in graph.pair_iter() {
+                    if let Some(foliage) = node.cast::<Foliage>() {
+                        if selection.contains(foliage.target()) {
+                            foliage.invalidate();
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -383,29 +419,23 @@ impl InteractionMode for TerrainInteractionMode {
                                 self.draw(terrain);
                             }
 
-                            let scale = match self.brush.shape {
+                            let size = match self.brush.shape {
                                 BrushShape::Circle { radius } => {
-                                    Vector3::new(radius * 2.0, radius * 2.0, 1.0)
+                                    Vector2::new(radius * 2.0, radius * 2.0)
                                 }
                                 BrushShape::Rectangle { width, length } => {
-                                    Vector3::new(width, length, 1.0)
+                                    Vector2::new(width, length)
                                 }
                             };
 
-                            graph[self.brush_gizmo.brush]
-                                .local_transform_mut()
-                                .set_position(closest.position)
-                                .set_scale(scale)
-                                .set_rotation(vector_to_quat(closest.normal));
+                            self.brush_gizmo
+                                .place(graph, closest.position, closest.normal, size);
                         }
                     }
                 }
             }
         }
-        let gizmo = &mut graph[self.brush_gizmo.brush];
-        if gizmo.visibility() != gizmo_visible {
-            gizmo.set_visibility(gizmo_visible);
-        }
+        self.brush_gizmo.set_visible(graph, gizmo_visible);
     }
 
     fn activate(&mut self, controller: &dyn SceneController, engine: &mut Engine) {
@@ -572,6 +602,11 @@ fn make_brush_mode_enum_property_editor_definition() -> EnumPropertyEditorDefini
             1 => BrushMode::Assign { value: 0.0 },
             2 => BrushMode::Flatten,
             3 => BrushMode::Smooth { kernel_radius: 5 },
+            4 => BrushMode::Noise {
+                amount: 0.1,
+                scale: 8.0,
+                seed: 0,
+            },
             _ => unreachable!(),
         },
         index_generator: |v| match v {
@@ -579,6 +614,7 @@ fn make_brush_mode_enum_property_editor_definition() -> EnumPropertyEditorDefini
             BrushMode::Assign { .. } => 1,
             BrushMode::Flatten { .. } => 2,
             BrushMode::Smooth { .. } => 3,
+            BrushMode::Noise { .. } => 4,
         },
         names_generator: || {
             vec![
@@ -586,6 +622,7 @@ fn make_brush_mode_enum_property_editor_definition() -> EnumPropertyEditorDefini
                 "Assign Value".to_string(),
                 "Flatten".to_string(),
                 "Smooth".to_string(),
+                "Noise".to_string(),
             ]
         },
     }
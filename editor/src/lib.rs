@@ -45,8 +45,10 @@ pub mod highlight;
 pub mod inspector;
 pub mod interaction;
 pub mod light;
+pub mod localization;
 pub mod log;
 pub mod material;
+pub mod material_graph;
 pub mod menu;
 pub mod mesh;
 pub mod message;
@@ -62,6 +64,7 @@ pub mod settings;
 pub mod stats;
 pub mod ui_scene;
 pub mod utils;
+pub mod version_control;
 pub mod world;
 
 pub use fyrox;
@@ -134,6 +137,7 @@ use crate::{
     highlight::HighlightRenderPass,
     inspector::Inspector,
     interaction::{
+        foliage::FoliageInteractionMode,
         move_mode::MoveInteractionMode,
         navmesh::{EditNavmeshMode, NavmeshPanel},
         rotate_mode::RotateInteractionMode,
@@ -142,8 +146,10 @@ use crate::{
         terrain::TerrainInteractionMode,
     },
     light::LightPanel,
+    localization::LocalizationPanel,
     log::LogPanel,
     material::MaterialEditor,
+    material_graph::MaterialGraphEditor,
     menu::{Menu, MenuContext, Panels},
     mesh::{MeshControlPanel, SurfaceDataViewer},
     message::MessageSender,
@@ -153,6 +159,7 @@ use crate::{
     plugin::EditorPlugin,
     plugins::collider::ColliderShapePlugin,
     scene::{
+        collaboration::{SceneConflictDialog, SceneLock},
         commands::{
             make_delete_selection_command, ChangeSelectionCommand, GameSceneContext, PasteCommand,
         },
@@ -169,7 +176,11 @@ use crate::{
         commands::graph::PasteWidgetCommand, menu::WidgetContextMenu,
         utils::UiSceneWorldViewerDataProvider, UiScene,
     },
-    utils::{doc::DocWindow, path_fixer::PathFixer, ragdoll::RagdollWizard},
+    utils::{
+        doc::DocWindow, impostor::ImpostorBaker, navmesh_baker::NavmeshBaker,
+        path_fixer::PathFixer, ragdoll::RagdollWizard, resource_replacer::ResourceReplacer,
+    },
+    version_control::VersionControl,
     world::{graph::menu::SceneNodeContextMenu, graph::EditorSceneWrapper, WorldViewer},
 };
 use std::{
@@ -496,7 +507,10 @@ pub struct Editor {
     pub asset_browser: AssetBrowser,
     pub exit_message_box: Handle<UiNode>,
     pub save_scene_dialog: SaveSceneConfirmationDialog,
+    pub scene_conflict_dialog: SceneConflictDialog,
     pub light_panel: LightPanel,
+    pub localization_panel: LocalizationPanel,
+    pub version_control: VersionControl,
     pub menu: Menu,
     pub exit: bool,
     pub configurator: Configurator,
@@ -507,6 +521,7 @@ pub struct Editor {
     pub settings: Settings,
     pub path_fixer: PathFixer,
     pub material_editor: MaterialEditor,
+    pub material_graph_editor: MaterialGraphEditor,
     pub inspector: Inspector,
     pub curve_editor: CurveEditorWindow,
     pub audio_panel: AudioPanel,
@@ -528,6 +543,9 @@ pub struct Editor {
     pub update_loop_state: UpdateLoopState,
     pub is_suspended: bool,
     pub ragdoll_wizard: RagdollWizard,
+    pub impostor_baker: ImpostorBaker,
+    pub navmesh_baker: NavmeshBaker,
+    pub resource_replacer: ResourceReplacer,
     pub scene_node_context_menu: Rc<RefCell<SceneNodeContextMenu>>,
     pub widget_context_menu: Rc<RefCell<WidgetContextMenu>>,
     pub collider_control_panel: ColliderControlPanel,
@@ -578,6 +596,7 @@ impl Editor {
             window_attributes,
             vsync: true,
             msaa_sample_count: Some(4),
+            graphics_server_constructor: Default::default(),
         };
 
         let serialization_context = Arc::new(SerializationContext::new());
@@ -608,7 +627,9 @@ impl Editor {
         );
 
         let scene_viewer = SceneViewer::new(&mut engine, message_sender.clone(), &mut settings);
-        let asset_browser = AssetBrowser::new(&mut engine);
+        let version_control = VersionControl::default();
+        version_control.set_settings(&settings.version_control);
+        let asset_browser = AssetBrowser::new(&mut engine, version_control.clone());
         let menu = Menu::new(&mut engine, message_sender.clone(), &settings);
         let light_panel = LightPanel::new(&mut engine, message_sender.clone());
         let audio_panel = AudioPanel::new(&mut engine, message_sender.clone());
@@ -632,6 +653,10 @@ impl Editor {
         let doc_window = DocWindow::new(ctx);
         let node_removal_dialog = NodeRemovalDialog::new(ctx);
         let ragdoll_wizard = RagdollWizard::new(ctx, message_sender.clone());
+        let impostor_baker = ImpostorBaker::new(ctx, message_sender.clone());
+        let navmesh_baker = NavmeshBaker::new(ctx, message_sender.clone());
+        let localization_panel = LocalizationPanel::new(ctx);
+        let resource_replacer = ResourceReplacer::new(ctx);
 
         let docking_manager;
         let root_grid = GridBuilder::new(
@@ -762,6 +787,7 @@ impl Editor {
                             navmesh_panel.window,
                             doc_window.window,
                             light_panel.window,
+                            localization_panel.window,
                         ])
                         .build(ctx);
                     docking_manager
@@ -797,6 +823,7 @@ impl Editor {
         let curve_editor = CurveEditorWindow::new(ctx);
 
         let save_scene_dialog = SaveSceneConfirmationDialog::new(ctx);
+        let scene_conflict_dialog = SceneConflictDialog::new(ctx);
 
         let build_window = BuildWindow::new(ctx);
 
@@ -804,6 +831,8 @@ impl Editor {
 
         let material_editor = MaterialEditor::new(&mut engine, message_sender.clone());
 
+        let material_graph_editor = MaterialGraphEditor::new(&mut engine);
+
         if let Some(layout) = settings.windows.layout.as_ref() {
             engine
                 .user_interfaces
@@ -833,15 +862,19 @@ impl Editor {
             configurator,
             log,
             light_panel,
+            localization_panel,
+            version_control,
             command_stack_viewer,
             validation_message_box,
             settings,
             path_fixer,
             material_editor,
+            material_graph_editor,
             inspector,
             curve_editor,
             audio_panel,
             save_scene_dialog,
+            scene_conflict_dialog,
             mode: Mode::Edit,
             game_loop_data: GameLoopData {
                 clock: Instant::now(),
@@ -867,6 +900,9 @@ impl Editor {
             update_loop_state: UpdateLoopState::default(),
             is_suspended: false,
             ragdoll_wizard,
+            impostor_baker,
+            navmesh_baker,
+            resource_replacer,
             scene_node_context_menu,
             widget_context_menu,
             collider_control_panel,
@@ -945,9 +981,32 @@ impl Editor {
         }
     }
 
-    fn add_scene(&mut self, entry: EditorSceneEntry) {
+    fn add_scene(&mut self, mut entry: EditorSceneEntry) {
         self.try_leave_preview_mode();
 
+        if let Err(owner) = entry.acquire_lock() {
+            self.engine
+                .user_interfaces
+                .first_mut()
+                .send_message(MessageBoxMessage::open(
+                    self.validation_message_box,
+                    MessageDirection::ToWidget,
+                    None,
+                    Some(format!(
+                        "The {} scene is already opened by {owner}. Changes made in both \
+                        editors will overwrite each other. If the other editor was closed \
+                        abnormally, delete {} file.",
+                        entry.name(),
+                        entry
+                            .path
+                            .as_deref()
+                            .map(SceneLock::lock_path)
+                            .unwrap_or_default()
+                            .display()
+                    )),
+                ));
+        }
+
         self.sync_to_model();
         self.poll_ui_messages();
 
@@ -1028,6 +1087,10 @@ impl Editor {
                     sender.send(Message::SetInteractionMode(
                         TerrainInteractionMode::type_uuid(),
                     ));
+                } else if hot_key == key_bindings.enable_foliage_mode {
+                    sender.send(Message::SetInteractionMode(
+                        FoliageInteractionMode::type_uuid(),
+                    ));
                 } else if hot_key == key_bindings.load_scene {
                     sender.send(Message::OpenLoadSceneDialog);
                 } else if hot_key == key_bindings.run_game {
@@ -1169,6 +1232,11 @@ impl Editor {
 
         self.save_scene_dialog
             .handle_ui_message(message, &self.message_sender, &self.scenes);
+        self.scene_conflict_dialog.handle_ui_message(
+            message,
+            &self.message_sender,
+            &mut self.scenes,
+        );
 
         let current_scene_entry = self.scenes.current_scene_entry_mut();
 
@@ -1184,17 +1252,22 @@ impl Editor {
                     world_outliner_window: self.world_viewer.window,
                     asset_window: self.asset_browser.window,
                     light_panel: self.light_panel.window,
+                    localization_panel: self.localization_panel.window,
                     log_panel: self.log.window,
                     navmesh_panel: self.navmesh_panel.window,
                     audio_panel: self.audio_panel.window,
                     configurator_window: self.configurator.window,
                     path_fixer: self.path_fixer.window,
                     curve_editor: &self.curve_editor,
+                    material_graph_editor: &self.material_graph_editor,
                     absm_editor: &self.absm_editor,
                     command_stack_panel: self.command_stack_viewer.window,
                     scene_settings: &self.scene_settings,
                     animation_editor: &self.animation_editor,
                     ragdoll_wizard: &self.ragdoll_wizard,
+                    impostor_baker: &self.impostor_baker,
+                    navmesh_baker: &self.navmesh_baker,
+                    resource_replacer: &self.resource_replacer,
                     export_window: &mut self.export_window,
                     statistics_window: &mut self.statistics_window,
                 },
@@ -1216,6 +1289,8 @@ impl Editor {
             .handle_ui_message(message, engine, self.message_sender.clone());
         self.command_stack_viewer.handle_ui_message(message);
         self.curve_editor.handle_ui_message(message, engine);
+        self.material_graph_editor
+            .handle_ui_message(message, engine);
         self.path_fixer.handle_ui_message(
             message,
             engine.user_interfaces.first_mut(),
@@ -1243,6 +1318,8 @@ impl Editor {
                 self.statistics_window.take();
             }
         }
+        self.localization_panel
+            .handle_ui_message(message, engine.user_interfaces.first_mut());
 
         let current_scene_entry = self.scenes.current_scene_entry_mut();
 
@@ -1282,6 +1359,13 @@ impl Editor {
                     game_scene,
                     &self.message_sender,
                 );
+                self.resource_replacer.handle_ui_message(
+                    message,
+                    engine.user_interfaces.first_mut(),
+                    graph,
+                    &engine.resource_manager,
+                    &self.message_sender,
+                );
                 self.particle_system_control_panel.handle_ui_message(
                     message,
                     &current_scene_entry.selection,
@@ -1294,6 +1378,20 @@ impl Editor {
                     game_scene,
                     engine,
                 );
+                self.impostor_baker.handle_ui_message(
+                    message,
+                    &current_scene_entry.selection,
+                    game_scene,
+                    engine,
+                    &self.message_sender,
+                );
+                self.navmesh_baker.handle_ui_message(
+                    message,
+                    &current_scene_entry.selection,
+                    game_scene,
+                    engine,
+                    &self.message_sender,
+                );
                 self.mesh_control_panel.handle_ui_message(
                     message,
                     &current_scene_entry.selection,
@@ -1604,7 +1702,8 @@ impl Editor {
             engine.user_interfaces.first_mut(),
         );
 
-        self.scene_viewer.sync_to_model(&self.scenes, engine);
+        self.scene_viewer
+            .sync_to_model(&self.scenes, &self.version_control, engine);
         if let Some(exporter) = self.export_window.as_ref() {
             exporter.sync_to_model(engine.user_interfaces.first_mut());
         }
@@ -1865,6 +1964,13 @@ impl Editor {
 
         let engine = &mut self.engine;
         if let Some(entry) = self.scenes.entry_by_scene_id_mut(id) {
+            // Do not silently overwrite the changes made by someone else.
+            if entry.path.as_ref() == Some(&path) && entry.is_changed_on_disk() {
+                self.scene_conflict_dialog
+                    .open(engine.user_interfaces.first(), entry, Some(path));
+                return;
+            }
+
             if !self.settings.recent.scenes.contains(&path) {
                 self.settings.recent.scenes.push(path.clone());
                 self.menu
@@ -1872,6 +1978,23 @@ impl Editor {
                     .update_recent_files_list(engine.user_interfaces.first_mut(), &self.settings);
             }
 
+            // Files could be locked by the version control system, so they must be checked out
+            // first, otherwise the changes could be lost.
+            if let Err(err) = self.version_control.checkout(&path) {
+                let message = format!("Unable to save {}. {err}", path.display());
+                Log::err(message.clone());
+                engine
+                    .user_interfaces
+                    .first_mut()
+                    .send_message(MessageBoxMessage::open(
+                        self.validation_message_box,
+                        MessageDirection::ToWidget,
+                        None,
+                        Some(message),
+                    ));
+                return;
+            }
+
             match entry.save(path.clone(), &self.settings, engine) {
                 Ok(message) => {
                     self.scene_viewer.set_title(
@@ -1881,6 +2004,8 @@ impl Editor {
                     Log::info(message);
 
                     entry.has_unsaved_changes = false;
+
+                    self.version_control.refresh();
                 }
                 Err(message) => {
                     Log::err(message.clone());
@@ -1991,6 +2116,19 @@ impl Editor {
         }
     }
 
+    /// Checks whether any of the opened scenes was changed on disk by someone else and asks the
+    /// user what to do with it.
+    fn check_scenes_on_disk(&mut self) {
+        if self.scene_conflict_dialog.is_open() {
+            return;
+        }
+
+        if let Some(entry) = self.scenes.iter().find(|entry| entry.is_changed_on_disk()) {
+            self.scene_conflict_dialog
+                .open(self.engine.user_interfaces.first(), entry, None);
+        }
+    }
+
     fn close_scene(&mut self, id: Uuid) -> bool {
         self.try_leave_preview_mode();
 
@@ -2267,6 +2405,9 @@ impl Editor {
 
         self.log.update(&mut self.engine);
         self.material_editor.update(&mut self.engine);
+        self.material_graph_editor.update(&mut self.engine);
+        self.version_control
+            .set_settings(&self.settings.version_control);
         self.asset_browser
             .update(&mut self.engine, &self.message_sender);
         if let Some(export_window) = self.export_window.as_mut() {
@@ -2280,7 +2421,7 @@ impl Editor {
             .pre_update(&self.settings, &mut self.engine);
         if let Some(entry) = self.scenes.current_scene_entry_ref() {
             if let Some(game_scene) = entry.controller.downcast_ref::<GameScene>() {
-                if let Some(stats) = self.statistics_window.as_ref() {
+                if let Some(stats) = self.statistics_window.as_mut() {
                     stats.update(game_scene.scene, &self.engine);
                 }
 
@@ -2292,7 +2433,12 @@ impl Editor {
                 );
                 self.audio_preview_panel
                     .update(&entry.selection, game_scene, &self.engine);
-                self.scene_viewer.update(game_scene, &mut self.engine);
+                self.scene_viewer.update(
+                    game_scene,
+                    &entry.selection,
+                    &self.settings,
+                    &mut self.engine,
+                );
             } else if let Some(ui_scene) = entry.controller.downcast_ref::<UiScene>() {
                 self.animation_editor.update(
                     &entry.selection,
@@ -2302,6 +2448,14 @@ impl Editor {
             }
         }
 
+        let ui_scene = self
+            .scenes
+            .current_scene_controller_mut()
+            .and_then(|controller| controller.downcast_mut::<UiScene>())
+            .map(|ui_scene| &mut ui_scene.ui);
+        self.localization_panel
+            .update(ui_scene, self.engine.user_interfaces.first_mut());
+
         if let Some(overlay_pass) = self.overlay_pass.as_ref() {
             overlay_pass.borrow_mut().pictogram_size = self.settings.debugging.pictogram_size;
         }
@@ -2321,6 +2475,8 @@ impl Editor {
                 editor_messages_processed_count += 1;
                 self.path_fixer
                     .handle_message(&message, self.engine.user_interfaces.first());
+                self.resource_replacer
+                    .handle_message(&message, self.engine.user_interfaces.first());
 
                 self.save_scene_dialog
                     .handle_message(&message, &self.message_sender);
@@ -2834,6 +2990,10 @@ impl Editor {
                         }
                         WindowEvent::Focused(focused) => {
                             self.focused = *focused;
+
+                            if *focused {
+                                self.check_scenes_on_disk();
+                            }
                         }
                         WindowEvent::Moved(new_position) => {
                             // Allow the window to go outside the screen bounds by a little. This
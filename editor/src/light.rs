@@ -22,6 +22,7 @@ use crate::fyrox::{
     core::{log::Log, pool::Handle, reflect::prelude::*},
     gui::{
         button::{ButtonBuilder, ButtonMessage},
+        check_box::{CheckBoxBuilder, CheckBoxMessage},
         formatted_text::WrapMode,
         grid::{Column, GridBuilder, Row},
         inspector::{InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction},
@@ -30,12 +31,15 @@ use crate::fyrox::{
         scroll_viewer::ScrollViewerBuilder,
         text::TextBuilder,
         text::TextMessage,
+        utils::make_simple_tooltip,
         widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
     },
+    scene::{probe::ReflectionProbe, Scene},
     utils::lightmap::{
-        CancellationToken, Lightmap, LightmapGenerationError, LightmapInputData, ProgressIndicator,
+        CancellationToken, Lightmap, LightmapGenerationError, LightmapInputData, LightmapSettings,
+        ProgressIndicator,
     },
 };
 use crate::{
@@ -69,6 +73,19 @@ struct LightmapperSettings {
         step = 0.001
     )]
     spacing: f32,
+    #[reflect(
+        description = "Amount of indirect light bounces. Zero means that only direct lighting is calculated. Each \
+    bounce adds light reflected by the surfaces lit by the previous bounce, which makes shadowed areas brighter. Each \
+    bounce takes roughly the same time as calculation of the direct lighting.",
+        min_value = 0.0,
+        max_value = 8.0
+    )]
+    bounces: u32,
+    #[reflect(
+        description = "Whether to smooth out the noise of the generated light maps or not. The filter preserves \
+    edges between surfaces with different orientation."
+    )]
+    denoise: bool,
     #[reflect(
         description = "Path to the directory which will be used to save the generated light maps. Keep in mind, that \
     the lightmapper automatically generates names for the files."
@@ -81,6 +98,8 @@ impl Default for LightmapperSettings {
         Self {
             texels_per_unit: 64,
             spacing: 0.005,
+            bounces: 1,
+            denoise: true,
             path: Default::default(),
         }
     }
@@ -175,14 +194,13 @@ impl ProgressWindow {
             self.progress_indicator.progress_percent() as f32 / 100.0,
         ));
 
-        let stage = self.progress_indicator.stage();
         ui.send_message(TextMessage::text(
             self.text,
             MessageDirection::ToWidget,
             format!(
-                "Stage {} out of 4: {}",
-                stage as u32,
-                self.progress_indicator.stage()
+                "{}: {}%",
+                self.progress_indicator.stage(),
+                self.progress_indicator.progress_percent()
             ),
         ));
     }
@@ -208,7 +226,12 @@ pub struct LightPanel {
     pub window: Handle<UiNode>,
     inspector: Handle<UiNode>,
     generate: Handle<UiNode>,
+    bake_probes: Handle<UiNode>,
+    show_baked: Handle<UiNode>,
     settings: LightmapperSettings,
+    // A lightmap, that was temporarily removed from the scene to compare lighting with and
+    // without it.
+    hidden_lightmap: Option<(Handle<Scene>, Lightmap)>,
     progress_window: Option<ProgressWindow>,
     sender: Sender<Result<Lightmap, LightmapGenerationError>>,
     receiver: Receiver<Result<Lightmap, LightmapGenerationError>>,
@@ -220,6 +243,8 @@ impl LightPanel {
         let container = Arc::new(make_property_editors_container(sender));
 
         let generate;
+        let bake_probes;
+        let show_baked;
         let inspector;
         let ctx = &mut engine.user_interfaces.first_mut().build_ctx();
         let window = WindowBuilder::new(
@@ -260,20 +285,63 @@ impl LightPanel {
                         .build(ctx),
                     )
                     .with_child({
-                        generate = ButtonBuilder::new(
+                        show_baked = CheckBoxBuilder::new(
                             WidgetBuilder::new()
                                 .on_row(1)
-                                .on_column(0)
-                                .with_margin(Thickness::uniform(1.0)),
+                                .with_margin(Thickness::uniform(1.0))
+                                .with_tooltip(make_simple_tooltip(
+                                    ctx,
+                                    "Uncheck to temporarily remove the light map from the \
+                                    scene and compare the lighting with and without it.",
+                                )),
+                        )
+                        .checked(Some(true))
+                        .with_content(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_vertical_alignment(VerticalAlignment::Center),
+                            )
+                            .with_text("Show Baked Lighting")
+                            .build(ctx),
                         )
-                        .with_text("Generate Lightmap")
                         .build(ctx);
-                        generate
-                    }),
+                        show_baked
+                    })
+                    .with_child(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(2)
+                                .with_child({
+                                    generate = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Generate Lightmap")
+                                    .build(ctx);
+                                    generate
+                                })
+                                .with_child({
+                                    bake_probes = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(1)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Bake Light Probes")
+                                    .build(ctx);
+                                    bake_probes
+                                }),
+                        )
+                        .add_column(Column::stretch())
+                        .add_column(Column::stretch())
+                        .add_row(Row::stretch())
+                        .build(ctx),
+                    ),
             )
             .add_column(Column::stretch())
             .add_row(Row::stretch())
             .add_row(Row::strict(25.0))
+            .add_row(Row::strict(25.0))
             .build(ctx),
         )
         .build(ctx);
@@ -284,7 +352,10 @@ impl LightPanel {
             window,
             inspector,
             generate,
+            bake_probes,
+            show_baked,
             settings,
+            hidden_lightmap: None,
             progress_window: None,
             sender,
             receiver,
@@ -319,18 +390,21 @@ impl LightPanel {
                     progress_indicator.clone(),
                 ) {
                     let sender = self.sender.clone();
-                    let texels_per_unit = self.settings.texels_per_unit;
-                    let spacing = self.settings.spacing;
+                    let settings = LightmapSettings {
+                        texels_per_unit: self.settings.texels_per_unit,
+                        uv_spacing: self.settings.spacing,
+                        bounces: self.settings.bounces,
+                        denoise: self.settings.denoise,
+                    };
                     let path = self.settings.path.clone();
                     let resource_manager = engine.resource_manager.clone();
 
                     if let Err(e) = std::thread::Builder::new()
                         .name("LightmapGenerationThread".to_string())
                         .spawn(move || {
-                            match Lightmap::new(
+                            match Lightmap::new_with_settings(
                                 input_data,
-                                texels_per_unit,
-                                spacing,
+                                &settings,
                                 cancellation_token,
                                 progress_indicator,
                             ) {
@@ -356,11 +430,41 @@ impl LightPanel {
                 }
             }
 
+            if message.destination() == self.bake_probes {
+                let graph = &engine.scenes[game_scene.scene].graph;
+                let mut count = 0;
+                for probe in graph
+                    .linear_iter()
+                    .filter_map(|node| node.cast::<ReflectionProbe>())
+                {
+                    probe.force_update();
+                    count += 1;
+                }
+                Log::info(format!(
+                    "{count} light probes will be captured on the next frame."
+                ));
+            }
+
             if let Some(progress_window) = self.progress_window.as_ref() {
                 if message.destination() == progress_window.cancel {
                     progress_window.cancellation_token.cancel();
                 }
             }
+        } else if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.destination() == self.show_baked
+                && message.direction() == MessageDirection::FromWidget
+            {
+                let graph = &mut engine.scenes[game_scene.scene].graph;
+                if *value {
+                    if let Some((scene, lightmap)) = self.hidden_lightmap.take() {
+                        if scene == game_scene.scene {
+                            Log::verify(graph.set_lightmap(lightmap));
+                        }
+                    }
+                } else if let Some(lightmap) = graph.clear_lightmap() {
+                    self.hidden_lightmap = Some((game_scene.scene, lightmap));
+                }
+            }
         } else if let Some(InspectorMessage::PropertyChanged(args)) = message.data() {
             if message.destination() == self.inspector
                 && message.direction() == MessageDirection::FromWidget
@@ -385,6 +489,17 @@ impl LightPanel {
             let scene = &mut engine.scenes[game_scene.scene];
             match result {
                 Ok(lightmap) => {
+                    // The new lightmap is always shown, so the hidden one is not needed anymore.
+                    self.hidden_lightmap = None;
+                    engine
+                        .user_interfaces
+                        .first()
+                        .send_message(CheckBoxMessage::checked(
+                            self.show_baked,
+                            MessageDirection::ToWidget,
+                            Some(true),
+                        ));
+
                     if let Err(err) = scene.graph.set_lightmap(lightmap) {
                         Log::err(format!("Failed to set generated lightmap. Reason: {err}"));
                     }
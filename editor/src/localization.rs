@@ -0,0 +1,406 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Localization preview panel. It loads string tables of the project, switches the language of
+//! the currently edited UI scene and reports missing translations.
+
+use crate::fyrox::{
+    core::{log::Log, pool::Handle},
+    graph::BaseSceneGraph,
+    gui::{
+        button::{ButtonBuilder, ButtonMessage},
+        dropdown_list::{DropdownListBuilder, DropdownListMessage},
+        formatted_text::WrapMode,
+        grid::{Column, GridBuilder, Row},
+        localization::{LocalizationManager, StringTable},
+        message::{MessageDirection, UiMessage},
+        path::{PathEditorBuilder, PathEditorMessage},
+        scroll_viewer::ScrollViewerBuilder,
+        text::{Text, TextBuilder, TextMessage},
+        utils::make_simple_tooltip,
+        widget::WidgetBuilder,
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use crate::{gui::make_dropdown_list_option, send_sync_message};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// Returns a locale of a string table file. Every `*.txt` file is considered as a string table,
+/// its locale is the last part of the file name before the extension (`ja.txt` or `menu.ja.txt`).
+fn table_locale(path: &Path) -> Option<&str> {
+    if path.extension()? != "txt" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.rsplit('.').next()
+}
+
+fn load_tables(folder: &Path) -> LocalizationManager {
+    let mut manager = LocalizationManager::default();
+
+    let entries = match std::fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(err) => {
+            Log::warn(format!(
+                "Unable to read string tables from {}. Reason: {err}",
+                folder.display()
+            ));
+            return manager;
+        }
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(locale) = table_locale(&path) else {
+            continue;
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => match StringTable::parse(locale, &text) {
+                Ok(table) => manager.add_table(table),
+                Err(err) => Log::err(format!(
+                    "Unable to parse string table {}. Reason: {err}",
+                    path.display()
+                )),
+            },
+            Err(err) => Log::err(format!(
+                "Unable to load string table {}. Reason: {err}",
+                path.display()
+            )),
+        }
+    }
+
+    manager
+}
+
+fn make_label(text: &str, ctx: &mut BuildContext) -> Handle<UiNode> {
+    TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .with_text(text)
+        .build(ctx)
+}
+
+fn sorted_locales(manager: &LocalizationManager) -> Vec<String> {
+    let mut locales = manager.locales().map(ToOwned::to_owned).collect::<Vec<_>>();
+    locales.sort();
+    locales
+}
+
+/// Keys of the localized strings, that are used by the text widgets of the given UI.
+fn used_keys(ui: &UserInterface) -> BTreeSet<String> {
+    ui.nodes()
+        .iter()
+        .filter_map(|node| node.cast::<Text>())
+        .filter_map(|text| text.localization_key())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn make_report(manager: &LocalizationManager, used_keys: &BTreeSet<String>) -> String {
+    let locales = sorted_locales(manager);
+    if locales.is_empty() {
+        return "There are no string tables.".to_string();
+    }
+
+    let mut all_keys = used_keys.clone();
+    for locale in locales.iter() {
+        if let Some(table) = manager.table(locale) {
+            all_keys.extend(table.strings.keys().cloned());
+        }
+    }
+
+    let mut report = String::new();
+    for locale in locales.iter() {
+        let Some(table) = manager.table(locale) else {
+            continue;
+        };
+
+        let missing = all_keys
+            .iter()
+            .filter(|key| table.get(key).is_none())
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            report += &format!("{locale}: all {} strings are translated.\n", all_keys.len());
+        } else {
+            report += &format!(
+                "{locale}: {} missing translation(s): {}.\n",
+                missing.len(),
+                missing.join(", ")
+            );
+        }
+    }
+
+    let unknown = used_keys
+        .iter()
+        .filter(|key| {
+            locales
+                .iter()
+                .filter_map(|locale| manager.table(locale))
+                .all(|table| table.get(key).is_none())
+        })
+        .map(|key| key.as_str())
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        report += &format!(
+            "Keys used by text widgets, but missing in every table: {}.\n",
+            unknown.join(", ")
+        );
+    }
+
+    report
+}
+
+pub struct LocalizationPanel {
+    pub window: Handle<UiNode>,
+    folder: Handle<UiNode>,
+    languages: Handle<UiNode>,
+    reload: Handle<UiNode>,
+    report: Handle<UiNode>,
+    tables_folder: PathBuf,
+    manager: LocalizationManager,
+    locales: Vec<String>,
+    locale: Option<String>,
+    used_keys: BTreeSet<String>,
+    loaded: bool,
+    need_report: bool,
+}
+
+impl LocalizationPanel {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let tables_folder = PathBuf::from("data/localization");
+
+        let folder;
+        let languages;
+        let reload;
+        let report;
+        let window = WindowBuilder::new(
+            WidgetBuilder::new()
+                .with_name("LocalizationPanel")
+                .with_width(400.0)
+                .with_height(300.0),
+        )
+        .with_title(WindowTitle::text("Localization Preview"))
+        .open(false)
+        .with_content(
+            GridBuilder::new(
+                WidgetBuilder::new()
+                    .with_child(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(0)
+                                .with_child(make_label("Tables Folder", ctx))
+                                .with_child({
+                                    folder = PathEditorBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(1)
+                                            .with_margin(Thickness::uniform(1.0))
+                                            .with_tooltip(make_simple_tooltip(
+                                                ctx,
+                                                "A folder with string tables. Every *.txt file \
+                                                in the folder is a string table, its locale is \
+                                                the last part of the file name before the \
+                                                extension (ja.txt or menu.ja.txt).",
+                                            )),
+                                    )
+                                    .with_path(&tables_folder)
+                                    .build(ctx);
+                                    folder
+                                }),
+                        )
+                        .add_row(Row::stretch())
+                        .add_column(Column::strict(100.0))
+                        .add_column(Column::stretch())
+                        .build(ctx),
+                    )
+                    .with_child(
+                        GridBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(1)
+                                .with_child(make_label("Language", ctx))
+                                .with_child({
+                                    languages = DropdownListBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(1)
+                                            .with_margin(Thickness::uniform(1.0))
+                                            .with_tooltip(make_simple_tooltip(
+                                                ctx,
+                                                "Language of the currently edited UI scene. \
+                                                Text widgets bound to missing strings show \
+                                                their keys.",
+                                            )),
+                                    )
+                                    .build(ctx);
+                                    languages
+                                })
+                                .with_child({
+                                    reload = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .on_column(2)
+                                            .with_width(80.0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Reload")
+                                    .build(ctx);
+                                    reload
+                                }),
+                        )
+                        .add_row(Row::stretch())
+                        .add_column(Column::strict(100.0))
+                        .add_column(Column::stretch())
+                        .add_column(Column::auto())
+                        .build(ctx),
+                    )
+                    .with_child(
+                        ScrollViewerBuilder::new(
+                            WidgetBuilder::new()
+                                .on_row(2)
+                                .with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_content({
+                            report = TextBuilder::new(WidgetBuilder::new())
+                                .with_wrap(WrapMode::Word)
+                                .build(ctx);
+                            report
+                        })
+                        .build(ctx),
+                    ),
+            )
+            .add_row(Row::strict(24.0))
+            .add_row(Row::strict(24.0))
+            .add_row(Row::stretch())
+            .add_column(Column::stretch())
+            .build(ctx),
+        )
+        .build(ctx);
+
+        Self {
+            window,
+            folder,
+            languages,
+            reload,
+            report,
+            tables_folder,
+            manager: Default::default(),
+            locales: Default::default(),
+            locale: None,
+            used_keys: Default::default(),
+            loaded: false,
+            need_report: true,
+        }
+    }
+
+    fn reload(&mut self, ui: &mut UserInterface) {
+        self.loaded = true;
+        self.manager = load_tables(&self.tables_folder);
+        self.locales = sorted_locales(&self.manager);
+
+        let items = self
+            .locales
+            .iter()
+            .map(|locale| make_dropdown_list_option(&mut ui.build_ctx(), locale))
+            .collect();
+        send_sync_message(
+            ui,
+            DropdownListMessage::items(self.languages, MessageDirection::ToWidget, items),
+        );
+
+        // Keep the language selected if it still exists.
+        let selection = self
+            .locale
+            .as_ref()
+            .and_then(|locale| self.locales.iter().position(|l| l == locale));
+        if selection.is_none() {
+            self.locale = None;
+        }
+        send_sync_message(
+            ui,
+            DropdownListMessage::selection(self.languages, MessageDirection::ToWidget, selection),
+        );
+
+        self.need_report = true;
+    }
+
+    pub fn handle_ui_message(&mut self, message: &UiMessage, ui: &mut UserInterface) {
+        if message.direction() != MessageDirection::FromWidget {
+            return;
+        }
+
+        if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.reload {
+                self.reload(ui);
+            }
+        } else if let Some(PathEditorMessage::Path(path)) = message.data() {
+            if message.destination() == self.folder {
+                self.tables_folder.clone_from(path);
+                self.reload(ui);
+            }
+        } else if let Some(DropdownListMessage::SelectionChanged(selection)) = message.data() {
+            if message.destination() == self.languages {
+                self.locale = selection.and_then(|i| self.locales.get(i).cloned());
+            }
+        }
+    }
+
+    /// Applies the selected language to the UI scene (if any) and updates the report of missing
+    /// translations.
+    pub fn update(&mut self, ui_scene: Option<&mut UserInterface>, editor_ui: &mut UserInterface) {
+        if !editor_ui.node(self.window).visibility() {
+            return;
+        }
+
+        if !self.loaded {
+            self.reload(editor_ui);
+        }
+
+        let mut used = BTreeSet::new();
+        if let Some(ui_scene) = ui_scene {
+            if let Some(locale) = self.locale.as_deref() {
+                if ui_scene.localization.locale() != locale
+                    || ui_scene.localization.table(locale) != self.manager.table(locale)
+                {
+                    let fallback_fonts = ui_scene.localization.fallback_fonts().to_vec();
+                    ui_scene.localization = self.manager.clone();
+                    ui_scene.localization.set_fallback_fonts(fallback_fonts);
+                    ui_scene.set_locale(locale);
+                }
+            }
+
+            used = used_keys(ui_scene);
+        }
+
+        if used != self.used_keys {
+            self.used_keys = used;
+            self.need_report = true;
+        }
+
+        if self.need_report {
+            self.need_report = false;
+            editor_ui.send_message(TextMessage::text(
+                self.report,
+                MessageDirection::ToWidget,
+                make_report(&self.manager, &self.used_keys),
+            ));
+        }
+    }
+}
@@ -0,0 +1,864 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Material graph compiler. It walks the graph from the output node and produces a shader, that
+//! could be used with the standard renderer. Every render pass of the shader evaluates the same
+//! function, generated from the graph, so the material looks the same in every pass.
+
+use crate::{
+    fyrox::{
+        core::uuid::Uuid,
+        fxhash::{FxHashMap, FxHashSet},
+        renderer::framework::gpu_program::SamplerFallback,
+    },
+    material_graph::model::{
+        color_from_array, MaterialGraph, MaterialNode, MaterialNodeKind, SocketType, ValueType,
+    },
+};
+use std::fmt::{Display, Formatter, Write};
+
+#[derive(Debug, PartialEq)]
+pub enum CompileError {
+    /// There is no output node in the graph.
+    NoOutput,
+    /// The graph contains a cycle, that includes the node with the given title.
+    Cycle(&'static str),
+    /// A name of a property or a texture is not a valid GLSL identifier.
+    InvalidName(String),
+    /// Two properties or textures have the same name, but different types.
+    NameConflict(String),
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileError::NoOutput => write!(f, "The graph does not have an output node."),
+            CompileError::Cycle(node) => {
+                write!(
+                    f,
+                    "The graph contains a cycle that goes through {node} node."
+                )
+            }
+            CompileError::InvalidName(name) => {
+                write!(f, "{name} is not a valid property or texture name.")
+            }
+            CompileError::NameConflict(name) => write!(
+                f,
+                "There are multiple properties or textures named {name} with different types."
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MaterialResource {
+    Float(f32),
+    Color([u8; 4]),
+    Texture(SamplerFallback),
+}
+
+#[derive(Clone, Debug)]
+struct Value {
+    expr: String,
+    ty: ValueType,
+}
+
+fn convert(value: &Value, ty: ValueType) -> String {
+    use ValueType::*;
+
+    let expr = &value.expr;
+    match (value.ty, ty) {
+        (from, to) if from == to => expr.clone(),
+        (Float, to) => format!("{}({expr})", to.glsl_name()),
+        (_, Float) => format!("({expr}).x"),
+        (Vector3 | Vector4, Vector2) => format!("({expr}).xy"),
+        (Vector4, Vector3) => format!("({expr}).xyz"),
+        (Vector2, Vector3) => format!("vec3({expr}, 0.0)"),
+        (Vector2, Vector4) => format!("vec4({expr}, 0.0, 1.0)"),
+        (Vector3, Vector4) => format!("vec4({expr}, 1.0)"),
+        _ => unreachable!(),
+    }
+}
+
+fn float(value: f32) -> String {
+    format!("{value:?}")
+}
+
+fn vector(components: &[f32]) -> String {
+    let args = components
+        .iter()
+        .map(|c| float(*c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("vec{}({args})", components.len())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    const RESERVED: &[&str] = &[
+        "properties",
+        "blendShapesStorage",
+        "position",
+        "normal",
+        "texCoord",
+        "tangent",
+        "binormal",
+        "secondTexCoord",
+        "instanceColor",
+    ];
+
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("fyrox_")
+        && !name.starts_with("gl_")
+        && !RESERVED.contains(&name)
+}
+
+struct Compiler<'a> {
+    graph: &'a MaterialGraph,
+    statements: String,
+    outputs: FxHashMap<(Uuid, usize), Value>,
+    visiting: FxHashSet<Uuid>,
+    evaluated: FxHashSet<Uuid>,
+    resources: Vec<(String, MaterialResource)>,
+    next_variable: usize,
+}
+
+impl Compiler<'_> {
+    fn add_resource(&mut self, name: &str, resource: MaterialResource) -> Result<(), CompileError> {
+        if !is_valid_name(name) {
+            return Err(CompileError::InvalidName(name.to_string()));
+        }
+
+        if let Some((_, existing)) = self.resources.iter().find(|(n, _)| n == name) {
+            if std::mem::discriminant(existing) != std::mem::discriminant(&resource) {
+                return Err(CompileError::NameConflict(name.to_string()));
+            }
+        } else {
+            self.resources.push((name.to_string(), resource));
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates every input of the node and converts the values to the types of the inputs.
+    /// Returns the values and the type of the dynamic sockets of the node.
+    fn inputs(&mut self, node: &MaterialNode) -> Result<(Vec<String>, ValueType), CompileError> {
+        let definitions = node.kind.inputs();
+
+        let mut values = Vec::with_capacity(definitions.len());
+        for (index, definition) in definitions.iter().enumerate() {
+            let connected = match self.graph.input_connection(node.id, index) {
+                Some(connection) => {
+                    self.evaluate(connection.source_node)?;
+                    self.outputs
+                        .get(&(connection.source_node, connection.source_socket))
+                        .cloned()
+                }
+                None => None,
+            };
+
+            values.push(connected.unwrap_or_else(|| Value {
+                expr: definition.default.to_string(),
+                ty: match definition.ty {
+                    SocketType::Fixed(ty) => ty,
+                    SocketType::Dynamic => ValueType::Float,
+                },
+            }));
+        }
+
+        let dynamic_type = definitions
+            .iter()
+            .zip(values.iter())
+            .filter(|(d, _)| d.ty == SocketType::Dynamic)
+            .map(|(_, v)| v.ty)
+            .max()
+            .unwrap_or(ValueType::Float);
+
+        let converted = definitions
+            .iter()
+            .zip(values.iter())
+            .map(|(d, v)| match d.ty {
+                SocketType::Fixed(ty) => convert(v, ty),
+                SocketType::Dynamic => convert(v, dynamic_type),
+            })
+            .collect();
+
+        Ok((converted, dynamic_type))
+    }
+
+    fn declare(&mut self, ty: ValueType, expr: String) -> String {
+        let name = format!("v{}", self.next_variable);
+        self.next_variable += 1;
+        writeln!(
+            self.statements,
+            "                    {} {name} = {expr};",
+            ty.glsl_name()
+        )
+        .unwrap();
+        name
+    }
+
+    fn evaluate(&mut self, id: Uuid) -> Result<(), CompileError> {
+        let Some(node) = self.graph.node(id) else {
+            return Ok(());
+        };
+
+        if self.evaluated.contains(&id) {
+            return Ok(());
+        }
+
+        if !self.visiting.insert(id) {
+            return Err(CompileError::Cycle(node.kind.title()));
+        }
+
+        let (inputs, dynamic_type) = self.inputs(node)?;
+
+        use MaterialNodeKind as Kind;
+        use ValueType::*;
+
+        let (ty, expr) = match &node.kind {
+            Kind::Output => (Float, "0.0".to_string()),
+            Kind::Float(v) => (Float, float(*v)),
+            Kind::Vector2(v) => (Vector2, vector(v.as_slice())),
+            Kind::Vector3(v) => (Vector3, vector(v.as_slice())),
+            Kind::Vector4(v) => (Vector4, vector(v.as_slice())),
+            Kind::Color(color) => {
+                let linear = color_from_array(*color).srgb_to_linear_f32();
+                (Vector4, vector(linear.as_slice()))
+            }
+            Kind::FloatProperty { name, value } => {
+                self.add_resource(name, MaterialResource::Float(*value))?;
+                (Float, format!("properties.{name}"))
+            }
+            Kind::ColorProperty { name, value } => {
+                self.add_resource(name, MaterialResource::Color(*value))?;
+                (Vector4, format!("properties.{name}"))
+            }
+            Kind::Texture { name, fallback } => {
+                self.add_resource(name, MaterialResource::Texture(*fallback))?;
+                (Vector4, format!("texture({name}, {})", inputs[0]))
+            }
+            Kind::TexCoord => (Vector2, "texCoord".to_string()),
+            Kind::WorldPosition => (Vector3, "position".to_string()),
+            Kind::WorldNormal => (Vector3, "normalize(normal)".to_string()),
+            Kind::VertexColor => (Vector4, "instanceColor".to_string()),
+            Kind::Add => (dynamic_type, format!("{} + {}", inputs[0], inputs[1])),
+            Kind::Subtract => (dynamic_type, format!("{} - {}", inputs[0], inputs[1])),
+            Kind::Multiply => (dynamic_type, format!("{} * {}", inputs[0], inputs[1])),
+            Kind::Divide => (dynamic_type, format!("{} / {}", inputs[0], inputs[1])),
+            Kind::Lerp => (
+                dynamic_type,
+                format!("mix({}, {}, {})", inputs[0], inputs[1], inputs[2]),
+            ),
+            Kind::Dot => (Float, format!("dot({}, {})", inputs[0], inputs[1])),
+            Kind::Normalize => (dynamic_type, format!("normalize({})", inputs[0])),
+            Kind::OneMinus => (dynamic_type, format!("1.0 - {}", inputs[0])),
+            Kind::Power => (dynamic_type, format!("pow({}, {})", inputs[0], inputs[1])),
+            Kind::Saturate => (dynamic_type, format!("clamp({}, 0.0, 1.0)", inputs[0])),
+            Kind::UnpackNormal => (Vector3, format!("normalize({} * 2.0 - 1.0)", inputs[0])),
+            Kind::Split => (Vector4, inputs[0].clone()),
+            Kind::Compose => (Vector4, format!("vec4({})", inputs.join(", "))),
+        };
+
+        let variable = self.declare(ty, expr);
+
+        let outputs = match node.kind {
+            Kind::Texture { .. } => vec![
+                (Vector4, variable.clone()),
+                (Vector3, format!("{variable}.rgb")),
+                (Float, format!("{variable}.r")),
+                (Float, format!("{variable}.g")),
+                (Float, format!("{variable}.b")),
+                (Float, format!("{variable}.a")),
+            ],
+            Kind::Split => ["x", "y", "z", "w"]
+                .iter()
+                .map(|c| (Float, format!("{variable}.{c}")))
+                .collect(),
+            _ => vec![(ty, variable)],
+        };
+
+        for (index, (ty, expr)) in outputs.into_iter().enumerate() {
+            self.outputs.insert((id, index), Value { expr, ty });
+        }
+
+        self.visiting.remove(&id);
+        self.evaluated.insert(id);
+
+        Ok(())
+    }
+
+    fn compile_output(&mut self) -> Result<String, CompileError> {
+        let output = self
+            .graph
+            .nodes
+            .iter()
+            .find(|n| n.kind == MaterialNodeKind::Output)
+            .ok_or(CompileError::NoOutput)?;
+
+        let (inputs, _) = self.inputs(output)?;
+
+        let mut function = String::from(MATERIAL_FUNCTION_HEADER);
+        function += &self.statements;
+        function += "                    TMaterialGraphOutput result;\n";
+        for (field, value) in [
+            "baseColor",
+            "normal",
+            "metallic",
+            "roughness",
+            "occlusion",
+            "emission",
+            "opacity",
+        ]
+        .iter()
+        .zip(inputs)
+        {
+            writeln!(function, "                    result.{field} = {value};").unwrap();
+        }
+        function += MATERIAL_FUNCTION_FOOTER;
+
+        Ok(function)
+    }
+
+    fn resources(&self) -> String {
+        let mut resources = String::new();
+
+        let mut binding = 0;
+        for (name, resource) in self.resources.iter() {
+            if let MaterialResource::Texture(fallback) = resource {
+                write!(
+                    resources,
+                    r#"
+        (
+            name: "{name}",
+            kind: Texture(kind: Sampler2D, fallback: {fallback:?}),
+            binding: {binding}
+        ),"#
+                )
+                .unwrap();
+                binding += 1;
+            }
+        }
+
+        write!(
+            resources,
+            r#"
+        (
+            name: "blendShapesStorage",
+            kind: Texture(kind: Sampler3D, fallback: Volume),
+            binding: {binding}
+        ),"#
+        )
+        .unwrap();
+
+        let mut properties = String::new();
+        for (name, resource) in self.resources.iter() {
+            let kind = match resource {
+                MaterialResource::Float(value) => format!("Float({value:?})"),
+                MaterialResource::Color([r, g, b, a]) => {
+                    format!("Color(r: {r}, g: {g}, b: {b}, a: {a})")
+                }
+                MaterialResource::Texture(_) => continue,
+            };
+            write!(
+                properties,
+                r#"
+                (
+                    name: "{name}",
+                    kind: {kind},
+                ),"#
+            )
+            .unwrap();
+        }
+
+        // Empty property groups are not allowed by GLSL.
+        if !properties.is_empty() {
+            write!(
+                resources,
+                r#"
+        (
+            name: "properties",
+            kind: PropertyGroup([{properties}
+            ]),
+            binding: 0
+        ),"#
+            )
+            .unwrap();
+        }
+
+        for (binding, name) in [
+            "fyrox_instanceData",
+            "fyrox_boneMatrices",
+            "fyrox_graphicsSettings",
+            "fyrox_cameraData",
+            "fyrox_lightData",
+            "fyrox_instanceBatch",
+        ]
+        .iter()
+        .enumerate()
+        {
+            write!(
+                resources,
+                r#"
+        (
+            name: "{name}",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: {}
+        ),"#,
+                binding + 1
+            )
+            .unwrap();
+        }
+
+        resources
+    }
+}
+
+/// Compiles the graph into a source code of a shader (in the same format as `.shader` files),
+/// that could be loaded using [`crate::fyrox::material::shader::Shader::from_string`]. The shader
+/// contains `GBuffer`, `Forward` and the shadow passes of the standard pipeline.
+pub fn compile(graph: &MaterialGraph) -> Result<String, CompileError> {
+    let mut compiler = Compiler {
+        graph,
+        statements: Default::default(),
+        outputs: Default::default(),
+        visiting: Default::default(),
+        evaluated: Default::default(),
+        resources: Default::default(),
+        next_variable: 0,
+    };
+
+    let function = compiler.compile_output()?;
+
+    let mut passes = String::new();
+    for (name, draw_parameters, fragment_shader) in [
+        ("GBuffer", OPAQUE_DRAW_PARAMETERS, GBUFFER_FRAGMENT_SHADER),
+        ("Forward", FORWARD_DRAW_PARAMETERS, FORWARD_FRAGMENT_SHADER),
+        (
+            "DirectionalShadow",
+            SHADOW_DRAW_PARAMETERS,
+            SHADOW_FRAGMENT_SHADER,
+        ),
+        ("SpotShadow", SHADOW_DRAW_PARAMETERS, SHADOW_FRAGMENT_SHADER),
+        (
+            "PointShadow",
+            OPAQUE_DRAW_PARAMETERS,
+            POINT_SHADOW_FRAGMENT_SHADER,
+        ),
+    ] {
+        write!(
+            passes,
+            r#"
+        (
+            name: "{name}",
+            draw_parameters: {draw_parameters},
+            vertex_shader: r#"{VERTEX_SHADER}"{hash},
+            fragment_shader: r#"{FRAGMENT_SHADER_INPUTS}{function}{fragment_shader}"{hash},
+        ),"#,
+            hash = '#'
+        )
+        .unwrap();
+    }
+
+    Ok(format!(
+        r#"(
+    name: {:?},
+    resources: [{}
+    ],
+    passes: [{passes}
+    ],
+)
+"#,
+        graph.name,
+        compiler.resources()
+    ))
+}
+
+const OPAQUE_DRAW_PARAMETERS: &str = r#"DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(red: true, green: true, blue: true, alpha: true),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            )"#;
+
+const FORWARD_DRAW_PARAMETERS: &str = r#"DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(red: true, green: true, blue: true, alpha: true),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: Some(BlendParameters(
+                    func: BlendFunc(
+                        sfactor: SrcAlpha,
+                        dfactor: OneMinusSrcAlpha,
+                        alpha_sfactor: SrcAlpha,
+                        alpha_dfactor: OneMinusSrcAlpha,
+                    ),
+                    equation: BlendEquation(rgb: Add, alpha: Add)
+                )),
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            )"#;
+
+const SHADOW_DRAW_PARAMETERS: &str = r#"DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(red: false, green: false, blue: false, alpha: false),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            )"#;
+
+const VERTEX_SHADER: &str = r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 3) in vec4 vertexTangent;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+                layout(location = 6) in vec2 vertexSecondTexCoord;
+
+                out vec3 position;
+                out vec3 normal;
+                out vec2 texCoord;
+                out vec3 tangent;
+                out vec3 binormal;
+                out vec2 secondTexCoord;
+                out vec4 instanceColor;
+
+                void main()
+                {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    vec3 localTangent = vec3(0);
+
+                    vec4 inputPosition = vec4(vertexPosition, 1.0);
+                    vec3 inputNormal = vertexNormal;
+                    vec3 inputTangent = vertexTangent.xyz;
+
+                    for (int i = 0; i < fyrox_instanceData.blendShapesCount; ++i) {
+                        TBlendShapeOffsets offsets = S_FetchBlendShapeOffsets(blendShapesStorage, gl_VertexID, i);
+                        float weight = fyrox_instanceData.blendShapesWeights[i / 4][i % 4];
+                        inputPosition.xyz += offsets.position * weight;
+                        inputNormal += offsets.normal * weight;
+                        inputTangent += offsets.tangent * weight;
+                    }
+
+                    if (fyrox_instanceData.useSkeletalAnimation)
+                    {
+                        mat4 m0 = fyrox_boneMatrices.matrices[int(boneIndices.x)];
+                        mat4 m1 = fyrox_boneMatrices.matrices[int(boneIndices.y)];
+                        mat4 m2 = fyrox_boneMatrices.matrices[int(boneIndices.z)];
+                        mat4 m3 = fyrox_boneMatrices.matrices[int(boneIndices.w)];
+
+                        localPosition += m0 * inputPosition * boneWeights.x;
+                        localPosition += m1 * inputPosition * boneWeights.y;
+                        localPosition += m2 * inputPosition * boneWeights.z;
+                        localPosition += m3 * inputPosition * boneWeights.w;
+
+                        localNormal += mat3(m0) * inputNormal * boneWeights.x;
+                        localNormal += mat3(m1) * inputNormal * boneWeights.y;
+                        localNormal += mat3(m2) * inputNormal * boneWeights.z;
+                        localNormal += mat3(m3) * inputNormal * boneWeights.w;
+
+                        localTangent += mat3(m0) * inputTangent * boneWeights.x;
+                        localTangent += mat3(m1) * inputTangent * boneWeights.y;
+                        localTangent += mat3(m2) * inputTangent * boneWeights.z;
+                        localTangent += mat3(m3) * inputTangent * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = inputPosition;
+                        localNormal = inputNormal;
+                        localTangent = inputTangent;
+                    }
+
+                    mat3 nm = mat3(worldMatrix);
+                    normal = normalize(nm * localNormal);
+                    tangent = normalize(nm * localTangent);
+                    binormal = normalize(vertexTangent.w * cross(normal, tangent));
+                    texCoord = vertexTexCoord;
+                    position = vec3(worldMatrix * localPosition);
+                    secondTexCoord = vertexSecondTexCoord;
+                    instanceColor = fyrox_instanceBatch.count > 0 ? fyrox_instanceBatch.colors[gl_InstanceID] : fyrox_instanceData.color;
+
+                    gl_Position = worldViewProjection * localPosition;
+                }
+                "#;
+
+const FRAGMENT_SHADER_INPUTS: &str = r#"
+                in vec3 position;
+                in vec3 normal;
+                in vec2 texCoord;
+                in vec3 tangent;
+                in vec3 binormal;
+                in vec2 secondTexCoord;
+                in vec4 instanceColor;
+"#;
+
+const MATERIAL_FUNCTION_HEADER: &str = r#"
+                struct TMaterialGraphOutput
+                {
+                    vec3 baseColor;
+                    vec3 normal;
+                    float metallic;
+                    float roughness;
+                    float occlusion;
+                    vec3 emission;
+                    float opacity;
+                };
+
+                TMaterialGraphOutput M_EvaluateMaterialGraph()
+                {
+"#;
+
+const MATERIAL_FUNCTION_FOOTER: &str = r#"
+                    return result;
+                }
+"#;
+
+const GBUFFER_FRAGMENT_SHADER: &str = r#"
+                layout(location = 0) out vec4 outColor;
+                layout(location = 1) out vec4 outNormal;
+                layout(location = 2) out vec4 outAmbient;
+                layout(location = 3) out vec4 outMaterial;
+                layout(location = 4) out uint outDecalMask;
+
+                void main()
+                {
+                    TMaterialGraphOutput m = M_EvaluateMaterialGraph();
+
+                    // Alpha test.
+                    if (m.opacity < 0.5) {
+                        discard;
+                    }
+
+                    mat3 tangentSpace = mat3(tangent, binormal, normal);
+                    vec3 n = normalize(tangentSpace * m.normal);
+
+                    outColor = vec4(m.baseColor, 1.0);
+                    outNormal = vec4(n * 0.5 + 0.5, 1.0);
+                    outAmbient = vec4(m.emission, 1.0);
+                    outMaterial = vec4(m.metallic, m.roughness, m.occlusion, 1.0);
+                    outDecalMask = 0u;
+                }
+                "#;
+
+const FORWARD_FRAGMENT_SHADER: &str = r#"
+                out vec4 FragColor;
+
+                void main()
+                {
+                    TMaterialGraphOutput m = M_EvaluateMaterialGraph();
+                    FragColor = vec4(m.baseColor + m.emission, m.opacity);
+                }
+                "#;
+
+const SHADOW_FRAGMENT_SHADER: &str = r#"
+                void main()
+                {
+                    if (M_EvaluateMaterialGraph().opacity < 0.5) discard;
+                }
+                "#;
+
+const POINT_SHADOW_FRAGMENT_SHADER: &str = r#"
+                layout(location = 0) out float depth;
+
+                void main()
+                {
+                    if (M_EvaluateMaterialGraph().opacity < 0.5) discard;
+                    depth = length(fyrox_lightData.lightPosition - position);
+                }
+                "#;
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fyrox::{
+            core::uuid::Uuid,
+            gui::node_graph::{ConnectionDescriptor, NodeLayoutDescriptor},
+            material::shader::Shader,
+            renderer::framework::gpu_program::SamplerFallback,
+        },
+        material_graph::{
+            compiler::{compile, CompileError},
+            model::{MaterialGraph, MaterialNode, MaterialNodeKind},
+        },
+    };
+
+    fn add_node(graph: &mut MaterialGraph, kind: MaterialNodeKind) -> Uuid {
+        let id = Uuid::new_v4();
+        graph.nodes.push(MaterialNode { id, kind });
+        graph.layout.nodes.push(NodeLayoutDescriptor {
+            id,
+            position: Default::default(),
+        });
+        id
+    }
+
+    fn connect(graph: &mut MaterialGraph, source: (Uuid, usize), dest: (Uuid, usize)) {
+        graph.layout.connections.push(ConnectionDescriptor {
+            source_node: source.0,
+            source_socket: source.1,
+            dest_node: dest.0,
+            dest_socket: dest.1,
+        });
+    }
+
+    fn output_node(graph: &MaterialGraph) -> Uuid {
+        graph.nodes[0].id
+    }
+
+    #[test]
+    fn test_compile_default_graph() {
+        let source = compile(&MaterialGraph::default()).unwrap();
+        let shader = Shader::from_string(&source).unwrap();
+
+        let passes = shader
+            .definition
+            .passes
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            passes,
+            [
+                "GBuffer",
+                "Forward",
+                "DirectionalShadow",
+                "SpotShadow",
+                "PointShadow"
+            ]
+        );
+        // No properties - no property group.
+        assert!(shader
+            .definition
+            .resources
+            .iter()
+            .all(|r| r.name.as_str() != "properties"));
+    }
+
+    #[test]
+    fn test_compile_textured_graph() {
+        let mut graph = MaterialGraph::default();
+        let output = output_node(&graph);
+        let texture = add_node(
+            &mut graph,
+            MaterialNodeKind::Texture {
+                name: "albedo".to_string(),
+                fallback: SamplerFallback::White,
+            },
+        );
+        let tint = add_node(
+            &mut graph,
+            MaterialNodeKind::ColorProperty {
+                name: "tint".to_string(),
+                value: [255, 0, 0, 255],
+            },
+        );
+        let multiply = add_node(&mut graph, MaterialNodeKind::Multiply);
+        connect(&mut graph, (texture, 0), (multiply, 0));
+        connect(&mut graph, (tint, 0), (multiply, 1));
+        connect(&mut graph, (multiply, 0), (output, 0));
+        // Alpha channel of the texture.
+        connect(&mut graph, (texture, 5), (output, 6));
+
+        let source = compile(&graph).unwrap();
+        assert!(source.contains("vec4 v0 = texture(albedo, texCoord);"));
+        assert!(source.contains("vec4 v1 = properties.tint;"));
+        assert!(source.contains("vec4 v2 = v0 * v1;"));
+        assert!(source.contains("result.baseColor = (v2).xyz;"));
+        assert!(source.contains("result.opacity = v0.a;"));
+
+        let shader = Shader::from_string(&source).unwrap();
+        for name in [
+            "albedo",
+            "blendShapesStorage",
+            "properties",
+            "fyrox_instanceData",
+        ] {
+            assert!(shader
+                .definition
+                .resources
+                .iter()
+                .any(|r| r.name.as_str() == name));
+        }
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let mut graph = MaterialGraph::default();
+        let output = output_node(&graph);
+        let a = add_node(&mut graph, MaterialNodeKind::Add);
+        let b = add_node(&mut graph, MaterialNodeKind::OneMinus);
+        connect(&mut graph, (a, 0), (b, 0));
+        connect(&mut graph, (b, 0), (a, 0));
+        connect(&mut graph, (a, 0), (output, 2));
+        assert!(matches!(compile(&graph), Err(CompileError::Cycle(_))));
+
+        let mut graph = MaterialGraph::default();
+        let output = output_node(&graph);
+        let property = add_node(
+            &mut graph,
+            MaterialNodeKind::FloatProperty {
+                name: "1metallic".to_string(),
+                value: 0.0,
+            },
+        );
+        connect(&mut graph, (property, 0), (output, 2));
+        assert_eq!(
+            compile(&graph),
+            Err(CompileError::InvalidName("1metallic".to_string()))
+        );
+    }
+}
@@ -0,0 +1,854 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Material graph editor allows to create materials by connecting nodes with each other, instead
+//! of writing shaders by hand. The graph is compiled into a shader, that supports every pass of
+//! the standard renderer, and the result is shown on a preview sphere.
+
+use crate::{
+    fyrox::{
+        asset::untyped::ResourceKind,
+        core::{
+            algebra::{Matrix4, Vector2},
+            color::Color,
+            log::Log,
+            pool::Handle,
+            uuid::Uuid,
+        },
+        engine::Engine,
+        fxhash::FxHashMap,
+        graph::{BaseSceneGraph, SceneGraph},
+        gui::{
+            border::BorderBuilder,
+            color::{ColorFieldBuilder, ColorFieldMessage},
+            file_browser::{FileBrowserMode, FileSelectorMessage},
+            formatted_text::WrapMode,
+            grid::{Column, GridBuilder, Row},
+            menu::{ContextMenuBuilder, MenuBuilder, MenuItemMessage},
+            message::{MessageData, MessageDirection, UiMessage},
+            node_graph::{
+                ConnectionDescriptor, GraphConnection, GraphNode, GraphNodeBuilder, GraphSocket,
+                GraphSocketBuilder, NodeGraph, NodeGraphBuilder, NodeGraphMessage,
+            },
+            numeric::{NumericUpDownBuilder, NumericUpDownMessage},
+            popup::PopupBuilder,
+            stack_panel::StackPanelBuilder,
+            text::{TextBuilder, TextMessage},
+            text_box::{TextBoxBuilder, TextCommitMode},
+            vec::{
+                Vec2EditorBuilder, Vec2EditorMessage, Vec3EditorBuilder, Vec3EditorMessage,
+                Vec4EditorBuilder, Vec4EditorMessage,
+            },
+            widget::{WidgetBuilder, WidgetMessage},
+            window::{WindowBuilder, WindowMessage, WindowTitle},
+            BuildContext, RcUiNodeHandle, Thickness, UiNode, UserInterface,
+        },
+        material::{
+            shader::{Shader, ShaderResource},
+            Material, MaterialResource,
+        },
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                MeshBuilder,
+            },
+        },
+    },
+    material_graph::model::{
+        color_from_array, color_to_array, MaterialGraph, MaterialNode, MaterialNodeKind,
+        SocketType, ValueType,
+    },
+    menu::{create_menu_item, create_root_menu_item},
+    preview::PreviewPanel,
+    utils::create_file_selector,
+};
+use std::path::{Path, PathBuf};
+
+pub mod compiler;
+pub mod model;
+
+const GRAPH_EXTENSION: &str = "matgraph";
+
+fn socket_color(ty: SocketType) -> Color {
+    match ty {
+        SocketType::Fixed(ValueType::Float) => Color::opaque(160, 160, 160),
+        SocketType::Fixed(ValueType::Vector2) => Color::opaque(120, 200, 120),
+        SocketType::Fixed(ValueType::Vector3) => Color::opaque(220, 200, 80),
+        SocketType::Fixed(ValueType::Vector4) => Color::opaque(200, 120, 220),
+        SocketType::Dynamic => Color::opaque(100, 160, 220),
+    }
+}
+
+fn header_color(kind: &MaterialNodeKind) -> Color {
+    match kind {
+        MaterialNodeKind::Output => Color::opaque(120, 50, 50),
+        MaterialNodeKind::FloatProperty { .. }
+        | MaterialNodeKind::ColorProperty { .. }
+        | MaterialNodeKind::Texture { .. } => Color::opaque(50, 90, 50),
+        MaterialNodeKind::Float(_)
+        | MaterialNodeKind::Vector2(_)
+        | MaterialNodeKind::Vector3(_)
+        | MaterialNodeKind::Vector4(_)
+        | MaterialNodeKind::Color(_)
+        | MaterialNodeKind::TexCoord
+        | MaterialNodeKind::WorldPosition
+        | MaterialNodeKind::WorldNormal
+        | MaterialNodeKind::VertexColor => Color::opaque(50, 70, 110),
+        _ => Color::opaque(70, 70, 70),
+    }
+}
+
+fn make_name_editor(name: &str, ctx: &mut BuildContext) -> Handle<UiNode> {
+    TextBoxBuilder::new(WidgetBuilder::new().with_height(22.0))
+        .with_text_commit_mode(TextCommitMode::Changed)
+        .with_text(name)
+        .build(ctx)
+}
+
+/// Creates editors for the values of the node, that could be changed by a user. Returns a panel
+/// with the editors and the handles of every editor.
+fn make_value_editors(
+    kind: &MaterialNodeKind,
+    ctx: &mut BuildContext,
+) -> (Handle<UiNode>, Vec<Handle<UiNode>>) {
+    let editor_builder = || {
+        WidgetBuilder::new()
+            .with_height(22.0)
+            .with_width(160.0)
+            .with_margin(Thickness::uniform(1.0))
+    };
+
+    let editors = match kind {
+        MaterialNodeKind::Float(value) => vec![NumericUpDownBuilder::<f32>::new(editor_builder())
+            .with_value(*value)
+            .build(ctx)],
+        MaterialNodeKind::Vector2(value) => vec![Vec2EditorBuilder::<f32>::new(editor_builder())
+            .with_value(*value)
+            .build(ctx)],
+        MaterialNodeKind::Vector3(value) => vec![Vec3EditorBuilder::<f32>::new(editor_builder())
+            .with_value(*value)
+            .build(ctx)],
+        MaterialNodeKind::Vector4(value) => vec![Vec4EditorBuilder::<f32>::new(editor_builder())
+            .with_value(*value)
+            .build(ctx)],
+        MaterialNodeKind::Color(value) => vec![ColorFieldBuilder::new(editor_builder())
+            .with_color(color_from_array(*value))
+            .build(ctx)],
+        MaterialNodeKind::FloatProperty { name, value } => vec![
+            make_name_editor(name, ctx),
+            NumericUpDownBuilder::<f32>::new(editor_builder())
+                .with_value(*value)
+                .build(ctx),
+        ],
+        MaterialNodeKind::ColorProperty { name, value } => vec![
+            make_name_editor(name, ctx),
+            ColorFieldBuilder::new(editor_builder())
+                .with_color(color_from_array(*value))
+                .build(ctx),
+        ],
+        MaterialNodeKind::Texture { name, .. } => vec![make_name_editor(name, ctx)],
+        _ => vec![],
+    };
+
+    if editors.is_empty() {
+        return (Handle::NONE, editors);
+    }
+
+    let panel = StackPanelBuilder::new(
+        WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0))
+            .with_children(editors.iter().cloned()),
+    )
+    .build(ctx);
+
+    (panel, editors)
+}
+
+fn set_from_message<T: Clone + 'static, M: MessageData>(
+    value: &mut T,
+    message: &UiMessage,
+    extract: impl FnOnce(&M) -> Option<&T>,
+) -> bool {
+    match message.data::<M>().and_then(extract) {
+        Some(new_value) => {
+            value.clone_from(new_value);
+            true
+        }
+        None => false,
+    }
+}
+
+fn set_color_from_message(value: &mut [u8; 4], message: &UiMessage) -> bool {
+    match message.data() {
+        Some(ColorFieldMessage::Color(color)) => {
+            *value = color_to_array(*color);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn set_name_from_message(name: &mut String, message: &UiMessage) -> bool {
+    set_from_message(name, message, |m: &TextMessage| match m {
+        TextMessage::Text(text) => Some(text),
+        _ => None,
+    })
+}
+
+fn set_float_from_message(value: &mut f32, message: &UiMessage) -> bool {
+    set_from_message(value, message, |m: &NumericUpDownMessage<f32>| match m {
+        NumericUpDownMessage::Value(value) => Some(value),
+        _ => None,
+    })
+}
+
+/// Applies a message from a value editor with the given index (see [`make_value_editors`]) to
+/// the node. Returns `true` if the node has changed.
+fn apply_value(kind: &mut MaterialNodeKind, editor_index: usize, message: &UiMessage) -> bool {
+    match kind {
+        MaterialNodeKind::Float(value) => set_float_from_message(value, message),
+        MaterialNodeKind::Vector2(value) => {
+            set_from_message(value, message, |m: &Vec2EditorMessage<f32>| match m {
+                Vec2EditorMessage::Value(value) => Some(value),
+            })
+        }
+        MaterialNodeKind::Vector3(value) => {
+            set_from_message(value, message, |m: &Vec3EditorMessage<f32>| match m {
+                Vec3EditorMessage::Value(value) => Some(value),
+            })
+        }
+        MaterialNodeKind::Vector4(value) => {
+            set_from_message(value, message, |m: &Vec4EditorMessage<f32>| match m {
+                Vec4EditorMessage::Value(value) => Some(value),
+            })
+        }
+        MaterialNodeKind::Color(value) => set_color_from_message(value, message),
+        MaterialNodeKind::FloatProperty { name, value } => {
+            if editor_index == 0 {
+                set_name_from_message(name, message)
+            } else {
+                set_float_from_message(value, message)
+            }
+        }
+        MaterialNodeKind::ColorProperty { name, value } => {
+            if editor_index == 0 {
+                set_name_from_message(name, message)
+            } else {
+                set_color_from_message(value, message)
+            }
+        }
+        MaterialNodeKind::Texture { name, .. } => set_name_from_message(name, message),
+        _ => false,
+    }
+}
+
+/// Returns the id of the node and the index of the given socket.
+fn socket_info(socket: Handle<UiNode>, ui: &UserInterface) -> Option<(Uuid, usize)> {
+    let socket_ref = ui.try_get_of_type::<GraphSocket>(socket)?;
+    let node_ref = ui.try_get_of_type::<GraphNode>(socket_ref.parent_node)?;
+    Some((node_ref.id, socket_ref.index))
+}
+
+struct FileMenu {
+    new: Handle<UiNode>,
+    load: Handle<UiNode>,
+    save: Handle<UiNode>,
+    export: Handle<UiNode>,
+}
+
+struct ContextMenu {
+    menu: RcUiNodeHandle,
+    add_items: Vec<(Handle<UiNode>, MaterialNodeKind)>,
+    remove: Handle<UiNode>,
+}
+
+impl ContextMenu {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let add_items = MaterialNodeKind::templates()
+            .into_iter()
+            .map(|(name, kind)| (create_menu_item(name, vec![], ctx), kind))
+            .collect::<Vec<_>>();
+
+        let remove;
+        let menu = ContextMenuBuilder::new(
+            PopupBuilder::new(WidgetBuilder::new().with_visibility(false)).with_content(
+                StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(create_menu_item(
+                            "Add Node",
+                            add_items.iter().map(|(item, _)| *item).collect(),
+                            ctx,
+                        ))
+                        .with_child({
+                            remove = create_menu_item("Remove Selected", vec![], ctx);
+                            remove
+                        }),
+                )
+                .build(ctx),
+            ),
+        )
+        .build(ctx);
+        let menu = RcUiNodeHandle::new(menu, ctx.sender());
+
+        Self {
+            menu,
+            add_items,
+            remove,
+        }
+    }
+}
+
+struct NodeView {
+    node: Handle<UiNode>,
+    editors: Vec<Handle<UiNode>>,
+}
+
+pub struct MaterialGraphEditor {
+    pub window: Handle<UiNode>,
+    node_graph: Handle<UiNode>,
+    status: Handle<UiNode>,
+    file_menu: FileMenu,
+    context_menu: ContextMenu,
+    load_file_selector: Handle<UiNode>,
+    save_file_selector: Handle<UiNode>,
+    export_file_selector: Handle<UiNode>,
+    preview: PreviewPanel,
+    graph: MaterialGraph,
+    node_views: FxHashMap<Uuid, NodeView>,
+    path: PathBuf,
+    needs_compile: bool,
+}
+
+impl MaterialGraphEditor {
+    pub fn new(engine: &mut Engine) -> Self {
+        let mut preview = PreviewPanel::new(engine, 300, 300);
+
+        let graph = &mut engine.scenes[preview.scene()].graph;
+        let sphere = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_sphere(30, 30, 1.0, &Matrix4::identity()),
+            ))
+            .build()])
+            .build(graph);
+        preview.set_model(sphere, engine);
+
+        let ctx = &mut engine.user_interfaces.first_mut().build_ctx();
+
+        let load_file_selector = create_file_selector(ctx, GRAPH_EXTENSION, FileBrowserMode::Open);
+        let save_file_selector = create_file_selector(
+            ctx,
+            GRAPH_EXTENSION,
+            FileBrowserMode::Save {
+                default_file_name: PathBuf::from(format!("unnamed.{GRAPH_EXTENSION}")),
+            },
+        );
+        let export_file_selector = create_file_selector(
+            ctx,
+            "shader",
+            FileBrowserMode::Save {
+                default_file_name: PathBuf::from("unnamed.shader"),
+            },
+        );
+
+        let context_menu = ContextMenu::new(ctx);
+
+        let new;
+        let load;
+        let save;
+        let export;
+        let menu = MenuBuilder::new(WidgetBuilder::new().on_row(0).on_column(0))
+            .with_items(vec![create_root_menu_item(
+                "File",
+                vec![
+                    {
+                        new = create_menu_item("New", vec![], ctx);
+                        new
+                    },
+                    {
+                        load = create_menu_item("Load...", vec![], ctx);
+                        load
+                    },
+                    {
+                        save = create_menu_item("Save", vec![], ctx);
+                        save
+                    },
+                    {
+                        export = create_menu_item("Export Shader...", vec![], ctx);
+                        export
+                    },
+                ],
+                ctx,
+            )])
+            .build(ctx);
+
+        let node_graph = NodeGraphBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .on_column(0)
+                .with_context_menu(context_menu.menu.clone()),
+        )
+        .build(ctx);
+
+        let preview_panel;
+        let status;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(900.0).with_height(600.0))
+            .open(false)
+            .with_title(WindowTitle::text("Material Graph Editor"))
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(menu)
+                        .with_child(node_graph)
+                        .with_child(
+                            GridBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(1)
+                                    .on_column(1)
+                                    .with_child({
+                                        preview_panel =
+                                            BorderBuilder::new(WidgetBuilder::new().on_row(0))
+                                                .build(ctx);
+                                        preview_panel
+                                    })
+                                    .with_child({
+                                        status = TextBuilder::new(
+                                            WidgetBuilder::new()
+                                                .on_row(1)
+                                                .with_margin(Thickness::uniform(4.0)),
+                                        )
+                                        .with_wrap(WrapMode::Word)
+                                        .build(ctx);
+                                        status
+                                    }),
+                            )
+                            .add_row(Row::strict(300.0))
+                            .add_row(Row::stretch())
+                            .add_column(Column::stretch())
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(25.0))
+                .add_row(Row::stretch())
+                .add_column(Column::stretch())
+                .add_column(Column::strict(300.0))
+                .build(ctx),
+            )
+            .build(ctx);
+
+        ctx.link(preview.root, preview_panel);
+
+        let mut editor = Self {
+            window,
+            node_graph,
+            status,
+            file_menu: FileMenu {
+                new,
+                load,
+                save,
+                export,
+            },
+            context_menu,
+            load_file_selector,
+            save_file_selector,
+            export_file_selector,
+            preview,
+            graph: Default::default(),
+            node_views: Default::default(),
+            path: Default::default(),
+            needs_compile: false,
+        };
+
+        editor.set_graph(MaterialGraph::default(), engine.user_interfaces.first_mut());
+
+        editor
+    }
+
+    pub fn open(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+    }
+
+    fn add_node_view(
+        &mut self,
+        node: &MaterialNode,
+        position: Vector2<f32>,
+        ui: &mut UserInterface,
+    ) {
+        let ctx = &mut ui.build_ctx();
+
+        let (content, editors) = make_value_editors(&node.kind, ctx);
+
+        let mut builder =
+            GraphNodeBuilder::new(WidgetBuilder::new().with_desired_position(position))
+                .with_id(node.id)
+                .with_title(node.kind.title())
+                .with_header_color(header_color(&node.kind))
+                .with_content(content);
+        for input in node.kind.inputs() {
+            builder = builder.with_input(
+                GraphSocketBuilder::new(WidgetBuilder::new())
+                    .with_label(input.name)
+                    .with_color(socket_color(input.ty)),
+            );
+        }
+        for output in node.kind.outputs() {
+            builder = builder.with_output(
+                GraphSocketBuilder::new(WidgetBuilder::new())
+                    .with_label(output.name)
+                    .with_color(socket_color(output.ty)),
+            );
+        }
+        let view = builder.build(ctx);
+
+        ui.send_message(WidgetMessage::link(
+            view,
+            MessageDirection::ToWidget,
+            self.node_graph,
+        ));
+
+        self.node_views.insert(
+            node.id,
+            NodeView {
+                node: view,
+                editors,
+            },
+        );
+    }
+
+    fn set_graph(&mut self, graph: MaterialGraph, ui: &mut UserInterface) {
+        for (_, view) in self.node_views.drain() {
+            ui.send_message(WidgetMessage::remove(view.node, MessageDirection::ToWidget));
+        }
+
+        for node in graph.nodes.iter() {
+            let position = graph
+                .layout
+                .nodes
+                .iter()
+                .find(|d| d.id == node.id)
+                .map(|d| d.position)
+                .unwrap_or_default();
+            self.add_node_view(node, position, ui);
+        }
+
+        // Recreates the connections and moves the view.
+        ui.send_message(NodeGraphMessage::layout(
+            self.node_graph,
+            MessageDirection::ToWidget,
+            graph.layout.clone(),
+        ));
+
+        self.graph = graph;
+        self.needs_compile = true;
+    }
+
+    /// Fetches positions of the nodes and the view from the node graph widget. Connections are
+    /// not synced, because they're kept up to date by [`Self::handle_ui_message`].
+    fn sync_layout(&mut self, ui: &UserInterface) {
+        if let Some(node_graph) = ui.try_get_of_type::<NodeGraph>(self.node_graph) {
+            let layout = node_graph.layout(ui);
+            self.graph.layout.nodes = layout.nodes;
+            self.graph.layout.view_position = layout.view_position;
+            self.graph.layout.zoom = layout.zoom;
+        }
+    }
+
+    fn set_status(&self, text: String, ui: &UserInterface) {
+        ui.send_message(TextMessage::text(
+            self.status,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
+    fn compile(&mut self, engine: &mut Engine) {
+        let source = match compiler::compile(&self.graph) {
+            Ok(source) => source,
+            Err(err) => {
+                self.set_status(err.to_string(), engine.user_interfaces.first());
+                return;
+            }
+        };
+
+        match Shader::from_string(&source) {
+            Ok(shader) => {
+                let shader = ShaderResource::new_ok(ResourceKind::Embedded, shader);
+                let material =
+                    MaterialResource::new_ok(ResourceKind::Embedded, Material::from_shader(shader));
+
+                if let Some(surface) = engine.scenes[self.preview.scene()].graph
+                    [self.preview.model()]
+                .as_mesh_mut()
+                .surfaces_mut()
+                .first_mut()
+                {
+                    surface.set_material(material);
+                }
+
+                self.set_status(
+                    "The graph was compiled successfully.".to_string(),
+                    engine.user_interfaces.first(),
+                );
+            }
+            Err(err) => self.set_status(format!("{err:?}"), engine.user_interfaces.first()),
+        }
+    }
+
+    fn save(&mut self, path: &Path, ui: &UserInterface) {
+        self.sync_layout(ui);
+        match self.graph.save(path) {
+            Ok(()) => self.path = path.to_path_buf(),
+            Err(err) => Log::err(format!(
+                "Unable to save material graph to {}. Reason: {err}",
+                path.display()
+            )),
+        }
+    }
+
+    fn export(&mut self, path: &Path, ui: &UserInterface) {
+        self.sync_layout(ui);
+        match compiler::compile(&self.graph) {
+            Ok(source) => {
+                if let Err(err) = std::fs::write(path, source) {
+                    Log::err(format!(
+                        "Unable to export shader to {}. Reason: {err}",
+                        path.display()
+                    ));
+                }
+            }
+            Err(err) => Log::err(format!("Unable to compile material graph. Reason: {err}")),
+        }
+    }
+
+    fn open_file_selector(&self, file_selector: Handle<UiNode>, ui: &UserInterface) {
+        ui.send_message(FileSelectorMessage::root(
+            file_selector,
+            MessageDirection::ToWidget,
+            Some(std::env::current_dir().unwrap()),
+        ));
+
+        ui.send_message(WindowMessage::open_modal(
+            file_selector,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+    }
+
+    fn remove_selected(&mut self, ui: &mut UserInterface) {
+        let Some(node_graph) = ui.try_get_of_type::<NodeGraph>(self.node_graph) else {
+            return;
+        };
+
+        let mut removed = Vec::new();
+        for selected in node_graph.selection.iter() {
+            let Some((id, _)) = self.node_views.iter().find(|(_, v)| v.node == *selected) else {
+                continue;
+            };
+
+            // Every graph must have an output node.
+            if self
+                .graph
+                .node(*id)
+                .is_some_and(|n| n.kind == MaterialNodeKind::Output)
+            {
+                continue;
+            }
+
+            for connection in node_graph.node_connections(*selected, ui) {
+                ui.send_message(NodeGraphMessage::disconnect(
+                    self.node_graph,
+                    MessageDirection::ToWidget,
+                    connection,
+                ));
+            }
+
+            removed.push(*id);
+        }
+
+        for id in removed {
+            self.graph.nodes.retain(|n| n.id != id);
+            self.graph
+                .layout
+                .connections
+                .retain(|c| c.source_node != id && c.dest_node != id);
+            if let Some(view) = self.node_views.remove(&id) {
+                ui.send_message(WidgetMessage::remove(view.node, MessageDirection::ToWidget));
+            }
+        }
+
+        self.needs_compile = true;
+    }
+
+    pub fn handle_ui_message(&mut self, message: &UiMessage, engine: &mut Engine) {
+        self.preview.handle_message(message, engine);
+
+        let ui = engine.user_interfaces.first_mut();
+
+        if let Some(msg) = message.data::<NodeGraphMessage>() {
+            if message.destination() == self.node_graph
+                && message.direction() == MessageDirection::FromWidget
+            {
+                match msg {
+                    NodeGraphMessage::Connect { source, dest } => {
+                        let (Some((source_node, source_socket)), Some((dest_node, dest_socket))) =
+                            (socket_info(*source, ui), socket_info(*dest, ui))
+                        else {
+                            return;
+                        };
+
+                        // Every input could have only one incoming connection.
+                        self.graph
+                            .layout
+                            .connections
+                            .retain(|c| c.dest_node != dest_node || c.dest_socket != dest_socket);
+                        self.graph.layout.connections.push(ConnectionDescriptor {
+                            source_node,
+                            source_socket,
+                            dest_node,
+                            dest_socket,
+                        });
+
+                        if let Some(node_graph) = ui.try_get_of_type::<NodeGraph>(self.node_graph) {
+                            for connection in node_graph.socket_connections(*dest, ui) {
+                                ui.send_message(NodeGraphMessage::disconnect(
+                                    self.node_graph,
+                                    MessageDirection::ToWidget,
+                                    connection,
+                                ));
+                            }
+                        }
+
+                        ui.send_message(NodeGraphMessage::connect(
+                            self.node_graph,
+                            MessageDirection::ToWidget,
+                            *source,
+                            *dest,
+                        ));
+                        self.needs_compile = true;
+                    }
+                    NodeGraphMessage::Disconnect(connection) => {
+                        if let Some(connection_ref) =
+                            ui.try_get_of_type::<GraphConnection>(*connection)
+                        {
+                            if let (Some(source), Some(dest)) = (
+                                socket_info(connection_ref.source, ui),
+                                socket_info(connection_ref.dest, ui),
+                            ) {
+                                self.graph.layout.connections.retain(|c| {
+                                    (c.source_node, c.source_socket) != source
+                                        || (c.dest_node, c.dest_socket) != dest
+                                });
+                            }
+                        }
+
+                        ui.send_message(NodeGraphMessage::disconnect(
+                            self.node_graph,
+                            MessageDirection::ToWidget,
+                            *connection,
+                        ));
+                        self.needs_compile = true;
+                    }
+                    _ => (),
+                }
+            }
+        } else if let Some(MenuItemMessage::Click) = message.data() {
+            let destination = message.destination();
+            if let Some(kind) = self
+                .context_menu
+                .add_items
+                .iter()
+                .find(|(item, _)| *item == destination)
+                .map(|(_, kind)| kind.clone())
+            {
+                let screen_position = ui.node(self.context_menu.menu.handle()).screen_position();
+                let position = ui.node(self.node_graph).screen_to_local(screen_position);
+                let node = MaterialNode {
+                    id: Uuid::new_v4(),
+                    kind,
+                };
+                self.add_node_view(&node, position, ui);
+                self.graph.nodes.push(node);
+            } else if destination == self.context_menu.remove {
+                self.remove_selected(ui);
+            } else if destination == self.file_menu.new {
+                self.path = Default::default();
+                self.set_graph(MaterialGraph::default(), ui);
+            } else if destination == self.file_menu.load {
+                self.open_file_selector(self.load_file_selector, ui);
+            } else if destination == self.file_menu.save {
+                if self.path == PathBuf::default() {
+                    self.open_file_selector(self.save_file_selector, ui);
+                } else {
+                    let path = self.path.clone();
+                    self.save(&path, ui);
+                }
+            } else if destination == self.file_menu.export {
+                self.open_file_selector(self.export_file_selector, ui);
+            }
+        } else if let Some(FileSelectorMessage::Commit(path)) = message.data() {
+            if message.destination() == self.load_file_selector {
+                match MaterialGraph::load(path) {
+                    Ok(graph) => {
+                        self.path.clone_from(path);
+                        self.set_graph(graph, ui);
+                    }
+                    Err(err) => Log::err(format!(
+                        "Unable to load material graph from {}. Reason: {err}",
+                        path.display()
+                    )),
+                }
+            } else if message.destination() == self.save_file_selector {
+                self.save(path, ui);
+            } else if message.destination() == self.export_file_selector {
+                self.export(path, ui);
+            }
+        } else if message.direction() == MessageDirection::FromWidget {
+            let destination = message.destination();
+            let editor = self.node_views.iter().find_map(|(id, view)| {
+                view.editors
+                    .iter()
+                    .position(|e| *e == destination)
+                    .map(|index| (*id, index))
+            });
+            if let Some((id, index)) = editor {
+                if let Some(node) = self.graph.node_mut(id) {
+                    if apply_value(&mut node.kind, index, message) {
+                        self.needs_compile = true;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn update(&mut self, engine: &mut Engine) {
+        if self.needs_compile {
+            self.needs_compile = false;
+            self.compile(engine);
+        }
+
+        self.preview.update(engine)
+    }
+}
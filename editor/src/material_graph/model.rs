@@ -0,0 +1,427 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Data model of a material graph. The model is stored separately from the widgets of the graph
+//! editor, so it could be saved, loaded and compiled without any user interface.
+
+use crate::fyrox::{
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        color::Color,
+        uuid::Uuid,
+    },
+    gui::node_graph::{ConnectionDescriptor, NodeGraphLayout, NodeLayoutDescriptor},
+    renderer::framework::gpu_program::SamplerFallback,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::Write,
+    path::Path,
+};
+
+/// Type of a value, that is passed between the nodes of a material graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueType {
+    Float,
+    Vector2,
+    Vector3,
+    Vector4,
+}
+
+impl ValueType {
+    pub fn glsl_name(self) -> &'static str {
+        match self {
+            ValueType::Float => "float",
+            ValueType::Vector2 => "vec2",
+            ValueType::Vector3 => "vec3",
+            ValueType::Vector4 => "vec4",
+        }
+    }
+}
+
+/// Type of a socket. Dynamic sockets accept values of any type, the actual type of a dynamic
+/// output is the widest type of all dynamic inputs of the node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SocketType {
+    Fixed(ValueType),
+    Dynamic,
+}
+
+/// Description of an input or an output of a node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SocketDefinition {
+    pub name: &'static str,
+    pub ty: SocketType,
+    /// GLSL expression, that is used when an input is not connected. Not used for outputs.
+    pub default: &'static str,
+}
+
+const fn input(name: &'static str, ty: SocketType, default: &'static str) -> SocketDefinition {
+    SocketDefinition { name, ty, default }
+}
+
+const fn output(name: &'static str, ty: SocketType) -> SocketDefinition {
+    SocketDefinition {
+        name,
+        ty,
+        default: "",
+    }
+}
+
+const FLOAT: SocketType = SocketType::Fixed(ValueType::Float);
+const VEC2: SocketType = SocketType::Fixed(ValueType::Vector2);
+const VEC3: SocketType = SocketType::Fixed(ValueType::Vector3);
+const VEC4: SocketType = SocketType::Fixed(ValueType::Vector4);
+const DYNAMIC: SocketType = SocketType::Dynamic;
+
+/// Kind of a node of a material graph.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum MaterialNodeKind {
+    /// Final node of every graph, its inputs define the surface of an object.
+    Output,
+    Float(f32),
+    Vector2(Vector2<f32>),
+    Vector3(Vector3<f32>),
+    Vector4(Vector4<f32>),
+    /// sRGB color, it is converted to linear color space when compiled.
+    Color([u8; 4]),
+    /// A float property of the material, it could be changed without recompiling the shader.
+    FloatProperty {
+        name: String,
+        value: f32,
+    },
+    /// A color property of the material, it could be changed without recompiling the shader.
+    ColorProperty {
+        name: String,
+        value: [u8; 4],
+    },
+    /// A texture of the material.
+    Texture {
+        name: String,
+        fallback: SamplerFallback,
+    },
+    TexCoord,
+    WorldPosition,
+    WorldNormal,
+    VertexColor,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Lerp,
+    Dot,
+    Normalize,
+    OneMinus,
+    Power,
+    Saturate,
+    /// Converts a value from a normal map (`[0; 1]` range) to a tangent space normal.
+    UnpackNormal,
+    Split,
+    Compose,
+}
+
+impl MaterialNodeKind {
+    /// Names of every node, that could be created by a user, with the respective default kind.
+    pub fn templates() -> Vec<(&'static str, MaterialNodeKind)> {
+        vec![
+            ("Float", MaterialNodeKind::Float(0.0)),
+            ("Vector2", MaterialNodeKind::Vector2(Vector2::default())),
+            ("Vector3", MaterialNodeKind::Vector3(Vector3::default())),
+            ("Vector4", MaterialNodeKind::Vector4(Vector4::default())),
+            ("Color", MaterialNodeKind::Color([255; 4])),
+            (
+                "Float Property",
+                MaterialNodeKind::FloatProperty {
+                    name: "floatProperty".to_string(),
+                    value: 0.0,
+                },
+            ),
+            (
+                "Color Property",
+                MaterialNodeKind::ColorProperty {
+                    name: "colorProperty".to_string(),
+                    value: [255; 4],
+                },
+            ),
+            (
+                "Texture",
+                MaterialNodeKind::Texture {
+                    name: "texture".to_string(),
+                    fallback: SamplerFallback::White,
+                },
+            ),
+            ("Texture Coordinates", MaterialNodeKind::TexCoord),
+            ("World Position", MaterialNodeKind::WorldPosition),
+            ("World Normal", MaterialNodeKind::WorldNormal),
+            ("Vertex Color", MaterialNodeKind::VertexColor),
+            ("Add", MaterialNodeKind::Add),
+            ("Subtract", MaterialNodeKind::Subtract),
+            ("Multiply", MaterialNodeKind::Multiply),
+            ("Divide", MaterialNodeKind::Divide),
+            ("Lerp", MaterialNodeKind::Lerp),
+            ("Dot", MaterialNodeKind::Dot),
+            ("Normalize", MaterialNodeKind::Normalize),
+            ("One Minus", MaterialNodeKind::OneMinus),
+            ("Power", MaterialNodeKind::Power),
+            ("Saturate", MaterialNodeKind::Saturate),
+            ("Unpack Normal", MaterialNodeKind::UnpackNormal),
+            ("Split", MaterialNodeKind::Split),
+            ("Compose", MaterialNodeKind::Compose),
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            MaterialNodeKind::Output => "Output",
+            MaterialNodeKind::Float(_) => "Float",
+            MaterialNodeKind::Vector2(_) => "Vector2",
+            MaterialNodeKind::Vector3(_) => "Vector3",
+            MaterialNodeKind::Vector4(_) => "Vector4",
+            MaterialNodeKind::Color(_) => "Color",
+            MaterialNodeKind::FloatProperty { .. } => "Float Property",
+            MaterialNodeKind::ColorProperty { .. } => "Color Property",
+            MaterialNodeKind::Texture { .. } => "Texture",
+            MaterialNodeKind::TexCoord => "Texture Coordinates",
+            MaterialNodeKind::WorldPosition => "World Position",
+            MaterialNodeKind::WorldNormal => "World Normal",
+            MaterialNodeKind::VertexColor => "Vertex Color",
+            MaterialNodeKind::Add => "Add",
+            MaterialNodeKind::Subtract => "Subtract",
+            MaterialNodeKind::Multiply => "Multiply",
+            MaterialNodeKind::Divide => "Divide",
+            MaterialNodeKind::Lerp => "Lerp",
+            MaterialNodeKind::Dot => "Dot",
+            MaterialNodeKind::Normalize => "Normalize",
+            MaterialNodeKind::OneMinus => "One Minus",
+            MaterialNodeKind::Power => "Power",
+            MaterialNodeKind::Saturate => "Saturate",
+            MaterialNodeKind::UnpackNormal => "Unpack Normal",
+            MaterialNodeKind::Split => "Split",
+            MaterialNodeKind::Compose => "Compose",
+        }
+    }
+
+    pub fn inputs(&self) -> Vec<SocketDefinition> {
+        match self {
+            MaterialNodeKind::Output => vec![
+                input("Base Color", VEC3, "vec3(1.0)"),
+                input("Normal", VEC3, "vec3(0.0, 0.0, 1.0)"),
+                input("Metallic", FLOAT, "0.0"),
+                input("Roughness", FLOAT, "1.0"),
+                input("Occlusion", FLOAT, "1.0"),
+                input("Emission", VEC3, "vec3(0.0)"),
+                input("Opacity", FLOAT, "1.0"),
+            ],
+            MaterialNodeKind::Texture { .. } => vec![input("UV", VEC2, "texCoord")],
+            MaterialNodeKind::Add | MaterialNodeKind::Subtract => {
+                vec![input("A", DYNAMIC, "0.0"), input("B", DYNAMIC, "0.0")]
+            }
+            MaterialNodeKind::Multiply | MaterialNodeKind::Divide | MaterialNodeKind::Power => {
+                vec![input("A", DYNAMIC, "1.0"), input("B", DYNAMIC, "1.0")]
+            }
+            MaterialNodeKind::Dot => vec![input("A", DYNAMIC, "0.0"), input("B", DYNAMIC, "0.0")],
+            MaterialNodeKind::Lerp => vec![
+                input("A", DYNAMIC, "0.0"),
+                input("B", DYNAMIC, "1.0"),
+                input("T", DYNAMIC, "0.5"),
+            ],
+            MaterialNodeKind::Normalize
+            | MaterialNodeKind::OneMinus
+            | MaterialNodeKind::Saturate => vec![input("Value", DYNAMIC, "0.0")],
+            MaterialNodeKind::UnpackNormal => vec![input("Value", VEC3, "vec3(0.5, 0.5, 1.0)")],
+            MaterialNodeKind::Split => vec![input("Value", VEC4, "vec4(0.0)")],
+            MaterialNodeKind::Compose => vec![
+                input("X", FLOAT, "0.0"),
+                input("Y", FLOAT, "0.0"),
+                input("Z", FLOAT, "0.0"),
+                input("W", FLOAT, "1.0"),
+            ],
+            MaterialNodeKind::Float(_)
+            | MaterialNodeKind::Vector2(_)
+            | MaterialNodeKind::Vector3(_)
+            | MaterialNodeKind::Vector4(_)
+            | MaterialNodeKind::Color(_)
+            | MaterialNodeKind::FloatProperty { .. }
+            | MaterialNodeKind::ColorProperty { .. }
+            | MaterialNodeKind::TexCoord
+            | MaterialNodeKind::WorldPosition
+            | MaterialNodeKind::WorldNormal
+            | MaterialNodeKind::VertexColor => vec![],
+        }
+    }
+
+    pub fn outputs(&self) -> Vec<SocketDefinition> {
+        match self {
+            MaterialNodeKind::Output => vec![],
+            MaterialNodeKind::Float(_) | MaterialNodeKind::FloatProperty { .. } => {
+                vec![output("Value", FLOAT)]
+            }
+            MaterialNodeKind::Vector2(_) | MaterialNodeKind::TexCoord => {
+                vec![output("Value", VEC2)]
+            }
+            MaterialNodeKind::Vector3(_)
+            | MaterialNodeKind::WorldPosition
+            | MaterialNodeKind::WorldNormal
+            | MaterialNodeKind::UnpackNormal => vec![output("Value", VEC3)],
+            MaterialNodeKind::Vector4(_)
+            | MaterialNodeKind::Color(_)
+            | MaterialNodeKind::ColorProperty { .. }
+            | MaterialNodeKind::VertexColor
+            | MaterialNodeKind::Compose => vec![output("Value", VEC4)],
+            MaterialNodeKind::Texture { .. } => vec![
+                output("RGBA", VEC4),
+                output("RGB", VEC3),
+                output("R", FLOAT),
+                output("G", FLOAT),
+                output("B", FLOAT),
+                output("A", FLOAT),
+            ],
+            MaterialNodeKind::Add
+            | MaterialNodeKind::Subtract
+            | MaterialNodeKind::Multiply
+            | MaterialNodeKind::Divide
+            | MaterialNodeKind::Lerp
+            | MaterialNodeKind::Normalize
+            | MaterialNodeKind::OneMinus
+            | MaterialNodeKind::Power
+            | MaterialNodeKind::Saturate => vec![output("Result", DYNAMIC)],
+            MaterialNodeKind::Dot => vec![output("Result", FLOAT)],
+            MaterialNodeKind::Split => vec![
+                output("X", FLOAT),
+                output("Y", FLOAT),
+                output("Z", FLOAT),
+                output("W", FLOAT),
+            ],
+        }
+    }
+}
+
+/// A node of a material graph.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MaterialNode {
+    pub id: Uuid,
+    pub kind: MaterialNodeKind,
+}
+
+/// A material graph is a set of nodes connected with each other. The graph is compiled into a
+/// shader, see [`super::compiler::compile`] for more info.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MaterialGraph {
+    pub name: String,
+    pub nodes: Vec<MaterialNode>,
+    /// Positions of the nodes and connections between them.
+    pub layout: NodeGraphLayout,
+}
+
+impl Default for MaterialGraph {
+    fn default() -> Self {
+        let output = MaterialNode {
+            id: Uuid::new_v4(),
+            kind: MaterialNodeKind::Output,
+        };
+
+        Self {
+            name: "MaterialGraph".to_string(),
+            layout: NodeGraphLayout {
+                nodes: vec![NodeLayoutDescriptor {
+                    id: output.id,
+                    position: Vector2::new(400.0, 100.0),
+                }],
+                ..Default::default()
+            },
+            nodes: vec![output],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MaterialGraphError {
+    Io(std::io::Error),
+    RonSpanned(ron::error::SpannedError),
+    Ron(ron::Error),
+}
+
+impl Display for MaterialGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialGraphError::Io(e) => write!(f, "{e}"),
+            MaterialGraphError::RonSpanned(e) => write!(f, "{e}"),
+            MaterialGraphError::Ron(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for MaterialGraphError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for MaterialGraphError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::RonSpanned(e)
+    }
+}
+
+impl From<ron::Error> for MaterialGraphError {
+    fn from(e: ron::Error) -> Self {
+        Self::Ron(e)
+    }
+}
+
+impl MaterialGraph {
+    pub fn load(path: &Path) -> Result<Self, MaterialGraphError> {
+        let file = File::open(path)?;
+        Ok(ron::de::from_reader(file)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), MaterialGraphError> {
+        let mut file = File::create(path)?;
+        file.write_all(
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    pub fn node(&self, id: Uuid) -> Option<&MaterialNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    pub fn node_mut(&mut self, id: Uuid) -> Option<&mut MaterialNode> {
+        self.nodes.iter_mut().find(|n| n.id == id)
+    }
+
+    /// Returns a connection, that ends at the given input of the given node.
+    pub fn input_connection(&self, node: Uuid, input: usize) -> Option<&ConnectionDescriptor> {
+        self.layout
+            .connections
+            .iter()
+            .find(|c| c.dest_node == node && c.dest_socket == input)
+    }
+}
+
+pub(super) fn color_from_array(color: [u8; 4]) -> Color {
+    Color::from_rgba(color[0], color[1], color[2], color[3])
+}
+
+pub(super) fn color_to_array(color: Color) -> [u8; 4] {
+    [color.r, color.g, color.b, color.a]
+}
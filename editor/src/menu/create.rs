@@ -38,6 +38,8 @@ use crate::{
             base::BaseBuilder,
             camera::CameraBuilder,
             decal::DecalBuilder,
+            foliage::{FoliageBuilder, FoliageLayer},
+            impostor::ImpostorBuilder,
             light::{
                 directional::DirectionalLightBuilder, point::PointLightBuilder,
                 spot::SpotLightBuilder, BaseLightBuilder,
@@ -46,6 +48,7 @@ use crate::{
                 surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
                 MeshBuilder,
             },
+            mirror::MirrorBuilder,
             navmesh::NavigationalMeshBuilder,
             node::Node,
             particle_system::{
@@ -53,6 +56,7 @@ use crate::{
                 ParticleSystemBuilder,
             },
             pivot::PivotBuilder,
+            probe::ReflectionProbeBuilder,
             sound::{listener::ListenerBuilder, SoundBuilder},
             sprite::SpriteBuilder,
             terrain::{Layer, TerrainBuilder},
@@ -146,6 +150,10 @@ pub struct CreateEntityMenu {
     create_directional_light: Handle<UiNode>,
     create_navmesh: Handle<UiNode>,
     create_terrain: Handle<UiNode>,
+    create_foliage: Handle<UiNode>,
+    create_impostor: Handle<UiNode>,
+    create_mirror: Handle<UiNode>,
+    create_reflection_probe: Handle<UiNode>,
     create_camera: Handle<UiNode>,
     create_sprite: Handle<UiNode>,
     create_particle_system: Handle<UiNode>,
@@ -184,6 +192,10 @@ impl CreateEntityMenu {
         let create_navmesh;
         let create_particle_system;
         let create_terrain;
+        let create_foliage;
+        let create_impostor;
+        let create_mirror;
+        let create_reflection_probe;
         let create_pivot;
         let create_sound_source;
         let create_listener;
@@ -291,6 +303,22 @@ impl CreateEntityMenu {
                 create_terrain = create_menu_item("Terrain", vec![], ctx);
                 create_terrain
             },
+            {
+                create_foliage = create_menu_item("Foliage", vec![], ctx);
+                create_foliage
+            },
+            {
+                create_impostor = create_menu_item("Impostor", vec![], ctx);
+                create_impostor
+            },
+            {
+                create_mirror = create_menu_item("Mirror", vec![], ctx);
+                create_mirror
+            },
+            {
+                create_reflection_probe = create_menu_item("Reflection Probe", vec![], ctx);
+                create_reflection_probe
+            },
             {
                 create_decal = create_menu_item("Decal", vec![], ctx);
                 create_decal
@@ -316,6 +344,10 @@ impl CreateEntityMenu {
                 create_particle_system,
                 create_pivot,
                 create_terrain,
+                create_foliage,
+                create_impostor,
+                create_mirror,
+                create_reflection_probe,
                 create_sound_source,
                 create_listener,
                 create_navmesh,
@@ -350,6 +382,10 @@ impl CreateEntityMenu {
             self.create_particle_system,
             self.create_pivot,
             self.create_terrain,
+            self.create_foliage,
+            self.create_impostor,
+            self.create_mirror,
+            self.create_reflection_probe,
             self.sound_menu,
             self.create_navmesh,
             self.create_decal,
@@ -521,6 +557,36 @@ impl CreateEntityMenu {
                                 }])
                                 .build_node(),
                         )
+                    } else if message.destination() == self.create_foliage {
+                        Some(
+                            FoliageBuilder::new(BaseBuilder::new().with_name("Foliage"))
+                                .with_layers(vec![FoliageLayer {
+                                    surfaces: vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                                        ResourceKind::Embedded,
+                                        SurfaceData::make_cone(8, 0.15, 0.5, &Matrix4::identity()),
+                                    ))
+                                    .with_material(placeholder_material())
+                                    .build()],
+                                    ..Default::default()
+                                }])
+                                .build_node(),
+                        )
+                    } else if message.destination() == self.create_impostor {
+                        Some(
+                            ImpostorBuilder::new(BaseBuilder::new().with_name("Impostor"))
+                                .build_node(),
+                        )
+                    } else if message.destination() == self.create_mirror {
+                        Some(
+                            MirrorBuilder::new(BaseBuilder::new().with_name("Mirror")).build_node(),
+                        )
+                    } else if message.destination() == self.create_reflection_probe {
+                        Some(
+                            ReflectionProbeBuilder::new(
+                                BaseBuilder::new().with_name("Reflection Probe"),
+                            )
+                            .build_node(),
+                        )
                     } else if message.destination() == self.create_decal {
                         Some(DecalBuilder::new(BaseBuilder::new().with_name("Decal")).build_node())
                     } else if message.destination() == self.create_listener {
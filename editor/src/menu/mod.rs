@@ -30,6 +30,7 @@ use crate::{
             BuildContext, Thickness, UiNode, UserInterface,
         },
     },
+    material_graph::MaterialGraphEditor,
     menu::{
         create::CreateEntityRootMenu, edit::EditMenu, file::FileMenu, help::HelpMenu,
         utils::UtilsMenu, view::ViewMenu,
@@ -39,7 +40,10 @@ use crate::{
     send_sync_message,
     settings::Settings,
     stats::StatisticsWindow,
-    utils::ragdoll::RagdollWizard,
+    utils::{
+        impostor::ImpostorBaker, navmesh_baker::NavmeshBaker, ragdoll::RagdollWizard,
+        resource_replacer::ResourceReplacer,
+    },
     AbsmEditor, CurveEditorWindow, Engine, Mode, SceneSettingsWindow,
 };
 use std::path::PathBuf;
@@ -70,6 +74,7 @@ pub struct Menu {
 pub struct Panels<'b> {
     pub scene_frame: Handle<UiNode>,
     pub light_panel: Handle<UiNode>,
+    pub localization_panel: Handle<UiNode>,
     pub log_panel: Handle<UiNode>,
     pub navmesh_panel: Handle<UiNode>,
     pub audio_panel: Handle<UiNode>,
@@ -80,10 +85,14 @@ pub struct Panels<'b> {
     pub configurator_window: Handle<UiNode>,
     pub path_fixer: Handle<UiNode>,
     pub curve_editor: &'b CurveEditorWindow,
+    pub material_graph_editor: &'b MaterialGraphEditor,
     pub absm_editor: &'b AbsmEditor,
     pub scene_settings: &'b SceneSettingsWindow,
     pub animation_editor: &'b AnimationEditor,
     pub ragdoll_wizard: &'b RagdollWizard,
+    pub impostor_baker: &'b ImpostorBaker,
+    pub navmesh_baker: &'b NavmeshBaker,
+    pub resource_replacer: &'b ResourceReplacer,
     pub export_window: &'b mut Option<ExportWindow>,
     pub statistics_window: &'b mut Option<StatisticsWindow>,
 }
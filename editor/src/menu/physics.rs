@@ -22,8 +22,9 @@ use crate::fyrox::{
     core::pool::Handle,
     gui::{menu::MenuItemMessage, message::UiMessage, BuildContext, UiNode},
     scene::{
-        base::BaseBuilder, collider::*, joint::*, node::Node, ragdoll::RagdollBuilder,
-        rigidbody::RigidBodyBuilder,
+        base::BaseBuilder, collider::*, destructible::DestructibleBuilder,
+        fluid::FluidVolumeBuilder, joint::*, node::Node, ragdoll::RagdollBuilder,
+        rigidbody::RigidBodyBuilder, soft_body::SoftBodyBuilder,
     },
 };
 use crate::menu::create_menu_item;
@@ -35,8 +36,12 @@ pub struct PhysicsMenu {
     create_ball_joint: Handle<UiNode>,
     create_prismatic_joint: Handle<UiNode>,
     create_fixed_joint: Handle<UiNode>,
+    create_six_dof_joint: Handle<UiNode>,
     create_collider: Handle<UiNode>,
     create_ragdoll: Handle<UiNode>,
+    create_destructible: Handle<UiNode>,
+    create_fluid_volume: Handle<UiNode>,
+    create_soft_body: Handle<UiNode>,
 }
 
 impl PhysicsMenu {
@@ -47,7 +52,11 @@ impl PhysicsMenu {
         let create_ball_joint;
         let create_prismatic_joint;
         let create_fixed_joint;
+        let create_six_dof_joint;
         let create_ragdoll;
+        let create_destructible;
+        let create_fluid_volume;
+        let create_soft_body;
         let menu = create_menu_item(
             "Physics",
             vec![
@@ -75,10 +84,26 @@ impl PhysicsMenu {
                     create_fixed_joint = create_menu_item("Fixed Joint", vec![], ctx);
                     create_fixed_joint
                 },
+                {
+                    create_six_dof_joint = create_menu_item("6-DOF Joint", vec![], ctx);
+                    create_six_dof_joint
+                },
                 {
                     create_ragdoll = create_menu_item("Ragdoll", vec![], ctx);
                     create_ragdoll
                 },
+                {
+                    create_destructible = create_menu_item("Destructible", vec![], ctx);
+                    create_destructible
+                },
+                {
+                    create_fluid_volume = create_menu_item("Fluid Volume", vec![], ctx);
+                    create_fluid_volume
+                },
+                {
+                    create_soft_body = create_menu_item("Soft Body", vec![], ctx);
+                    create_soft_body
+                },
             ],
             ctx,
         );
@@ -90,8 +115,12 @@ impl PhysicsMenu {
             create_ball_joint,
             create_prismatic_joint,
             create_fixed_joint,
+            create_six_dof_joint,
             create_collider,
             create_ragdoll,
+            create_destructible,
+            create_fluid_volume,
+            create_soft_body,
         }
     }
 
@@ -123,6 +152,12 @@ impl PhysicsMenu {
                         .with_params(JointParams::FixedJoint(Default::default()))
                         .build_node(),
                 )
+            } else if message.destination() == self.create_six_dof_joint {
+                Some(
+                    JointBuilder::new(BaseBuilder::new().with_name("6-DOF Joint"))
+                        .with_params(JointParams::SixDofJoint(Default::default()))
+                        .build_node(),
+                )
             } else if message.destination == self.create_collider {
                 Some(
                     ColliderBuilder::new(BaseBuilder::new().with_name("Collider"))
@@ -131,6 +166,18 @@ impl PhysicsMenu {
                 )
             } else if message.destination == self.create_ragdoll {
                 Some(RagdollBuilder::new(BaseBuilder::new().with_name("Ragdoll")).build_node())
+            } else if message.destination == self.create_destructible {
+                Some(
+                    DestructibleBuilder::new(BaseBuilder::new().with_name("Destructible"))
+                        .build_node(),
+                )
+            } else if message.destination == self.create_fluid_volume {
+                Some(
+                    FluidVolumeBuilder::new(BaseBuilder::new().with_name("Fluid Volume"))
+                        .build_node(),
+                )
+            } else if message.destination == self.create_soft_body {
+                Some(SoftBodyBuilder::new(BaseBuilder::new().with_name("Soft Body")).build_node())
             } else {
                 None
             }
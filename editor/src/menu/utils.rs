@@ -34,9 +34,13 @@ pub struct UtilsMenu {
     pub menu: Handle<UiNode>,
     open_path_fixer: Handle<UiNode>,
     open_curve_editor: Handle<UiNode>,
+    material_graph_editor: Handle<UiNode>,
     absm_editor: Handle<UiNode>,
     animation_editor: Handle<UiNode>,
     ragdoll_wizard: Handle<UiNode>,
+    impostor_baker: Handle<UiNode>,
+    navmesh_baker: Handle<UiNode>,
+    resource_replacer: Handle<UiNode>,
     rendering_statistics: Handle<UiNode>,
 }
 
@@ -44,9 +48,13 @@ impl UtilsMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let open_path_fixer;
         let open_curve_editor;
+        let material_graph_editor;
         let absm_editor;
         let animation_editor;
         let ragdoll_wizard;
+        let impostor_baker;
+        let navmesh_baker;
+        let resource_replacer;
         let rendering_statistics;
         let menu = create_root_menu_item(
             "Utils",
@@ -59,6 +67,10 @@ impl UtilsMenu {
                     open_curve_editor = create_menu_item("Curve Editor", vec![], ctx);
                     open_curve_editor
                 },
+                {
+                    material_graph_editor = create_menu_item("Material Graph Editor", vec![], ctx);
+                    material_graph_editor
+                },
                 {
                     absm_editor = create_menu_item("ABSM Editor", vec![], ctx);
                     absm_editor
@@ -71,6 +83,18 @@ impl UtilsMenu {
                     ragdoll_wizard = create_menu_item("Ragdoll Wizard", vec![], ctx);
                     ragdoll_wizard
                 },
+                {
+                    impostor_baker = create_menu_item("Impostor Baker", vec![], ctx);
+                    impostor_baker
+                },
+                {
+                    navmesh_baker = create_menu_item("Navmesh Baker", vec![], ctx);
+                    navmesh_baker
+                },
+                {
+                    resource_replacer = create_menu_item("Resource Replacer", vec![], ctx);
+                    resource_replacer
+                },
                 {
                     rendering_statistics = create_menu_item("Rendering Statistics", vec![], ctx);
                     rendering_statistics
@@ -83,9 +107,13 @@ impl UtilsMenu {
             menu,
             open_path_fixer,
             open_curve_editor,
+            material_graph_editor,
             absm_editor,
             animation_editor,
             ragdoll_wizard,
+            impostor_baker,
+            navmesh_baker,
+            resource_replacer,
             rendering_statistics,
         }
     }
@@ -106,12 +134,20 @@ impl UtilsMenu {
                 ));
             } else if message.destination() == self.open_curve_editor {
                 panels.curve_editor.open(ui);
+            } else if message.destination() == self.material_graph_editor {
+                panels.material_graph_editor.open(ui);
             } else if message.destination() == self.absm_editor {
                 panels.absm_editor.open(ui);
             } else if message.destination() == self.animation_editor {
                 panels.animation_editor.open(ui);
             } else if message.destination() == self.ragdoll_wizard {
                 panels.ragdoll_wizard.open(ui);
+            } else if message.destination() == self.impostor_baker {
+                panels.impostor_baker.open(ui);
+            } else if message.destination() == self.navmesh_baker {
+                panels.navmesh_baker.open(ui);
+            } else if message.destination() == self.resource_replacer {
+                panels.resource_replacer.open(ui);
             } else if message.destination() == self.rendering_statistics {
                 *panels.statistics_window = Some(StatisticsWindow::new(
                     &mut ui.build_ctx(),
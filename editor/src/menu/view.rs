@@ -40,6 +40,7 @@ pub struct ViewMenu {
     world_viewer: Handle<UiNode>,
     asset_browser: Handle<UiNode>,
     light_panel: Handle<UiNode>,
+    localization_panel: Handle<UiNode>,
     log_panel: Handle<UiNode>,
     nav_mesh: Handle<UiNode>,
     audio: Handle<UiNode>,
@@ -63,6 +64,7 @@ impl ViewMenu {
         let asset_browser;
         let world_viewer;
         let light_panel;
+        let localization_panel;
         let log_panel;
         let nav_mesh;
         let audio;
@@ -88,6 +90,10 @@ impl ViewMenu {
                     light_panel = create_menu_item("Light Panel", vec![], ctx);
                     light_panel
                 },
+                {
+                    localization_panel = create_menu_item("Localization Panel", vec![], ctx);
+                    localization_panel
+                },
                 {
                     log_panel = create_menu_item("Log Panel", vec![], ctx);
                     log_panel
@@ -122,6 +128,7 @@ impl ViewMenu {
             world_viewer,
             asset_browser,
             light_panel,
+            localization_panel,
             log_panel,
             nav_mesh,
             audio,
@@ -143,6 +150,8 @@ impl ViewMenu {
                 switch_window_state(panels.asset_window, ui, false);
             } else if message.destination() == self.light_panel {
                 switch_window_state(panels.light_panel, ui, true);
+            } else if message.destination() == self.localization_panel {
+                switch_window_state(panels.localization_panel, ui, true);
             } else if message.destination() == self.world_viewer {
                 switch_window_state(panels.world_outliner_window, ui, false);
             } else if message.destination() == self.inspector {
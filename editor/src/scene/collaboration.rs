@@ -0,0 +1,340 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Helpers for teams, that work on the same scenes: advisory lock files, that tell other editors
+//! that a scene is opened, and detection of scenes changed on disk by someone else.
+
+use crate::{
+    fyrox::{
+        core::{log::Log, pool::Handle, uuid::Uuid},
+        gui::{
+            message::{MessageDirection, UiMessage},
+            messagebox::{
+                MessageBoxBuilder, MessageBoxButtons, MessageBoxMessage, MessageBoxResult,
+            },
+            widget::WidgetBuilder,
+            window::{WindowBuilder, WindowTitle},
+            BuildContext, UiNode, UserInterface,
+        },
+    },
+    message::MessageSender,
+    scene::container::{EditorSceneEntry, SceneContainer},
+    Message,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Returns the last modification time of the file.
+pub fn modification_time(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn env_or_unknown(names: &[&str]) -> String {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// An editor instance, that holds a scene lock.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SceneLockOwner {
+    pub user: String,
+    pub host: String,
+    pub process_id: u32,
+    /// Time (in seconds since the Unix epoch) when the lock was acquired.
+    pub since: u64,
+}
+
+impl SceneLockOwner {
+    fn current() -> Self {
+        Self {
+            user: env_or_unknown(&["USER", "USERNAME"]),
+            host: env_or_unknown(&["HOSTNAME", "COMPUTERNAME"]),
+            process_id: std::process::id(),
+            since: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+        }
+    }
+
+    fn is_current_process(&self) -> bool {
+        let current = Self::current();
+        self.process_id == current.process_id && self.host == current.host
+    }
+}
+
+impl Display for SceneLockOwner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        write!(
+            f,
+            "{} on {} (process {}, {} min ago)",
+            self.user,
+            self.host,
+            self.process_id,
+            now.saturating_sub(self.since) / 60
+        )
+    }
+}
+
+/// Advisory lock of a scene file. It is a file next to the scene (`<scene file>.lock`), which
+/// tells other editors that the scene is opened. The lock does not prevent anyone from changing
+/// the scene, it is used only to warn users. The lock file is removed when the lock is dropped.
+#[derive(Debug)]
+pub struct SceneLock {
+    lock_path: PathBuf,
+    owner: SceneLockOwner,
+}
+
+impl SceneLock {
+    /// Returns a path of the lock file of the scene.
+    pub fn lock_path(scene_path: &Path) -> PathBuf {
+        let mut file_name = scene_path.file_name().unwrap_or_default().to_owned();
+        file_name.push(".lock");
+        scene_path.with_file_name(file_name)
+    }
+
+    /// Returns the current owner of the lock of the scene (if any).
+    pub fn owner_of(scene_path: &Path) -> Option<SceneLockOwner> {
+        let lock_path = Self::lock_path(scene_path);
+        let content = std::fs::read_to_string(lock_path).ok()?;
+        ron::de::from_str(&content).ok()
+    }
+
+    /// Tries to lock the scene. Returns the owner of the lock, if the scene is already locked by
+    /// another editor.
+    pub fn acquire(scene_path: &Path) -> Result<Self, SceneLockOwner> {
+        if let Some(owner) = Self::owner_of(scene_path) {
+            if !owner.is_current_process() {
+                return Err(owner);
+            }
+        }
+
+        let lock = Self {
+            lock_path: Self::lock_path(scene_path),
+            owner: SceneLockOwner::current(),
+        };
+
+        match ron::ser::to_string(&lock.owner) {
+            Ok(content) => Log::verify(std::fs::write(&lock.lock_path, content)),
+            Err(err) => Log::err(format!("Unable to write a scene lock. Reason: {err}")),
+        }
+
+        Ok(lock)
+    }
+
+    /// Returns a path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.lock_path
+    }
+}
+
+impl Drop for SceneLock {
+    fn drop(&mut self) {
+        // The lock file could be taken over by someone else (for example, if it was deleted
+        // manually), in this case it must be left intact.
+        let content = std::fs::read_to_string(&self.lock_path)
+            .ok()
+            .and_then(|content| ron::de::from_str::<SceneLockOwner>(&content).ok());
+        if content.as_ref() == Some(&self.owner) {
+            Log::verify(std::fs::remove_file(&self.lock_path));
+        }
+    }
+}
+
+/// A dialog, that is shown when a scene was changed on disk since it was loaded, which usually
+/// means that it was saved by another editor. A user can reload the scene from disk or keep the
+/// current version, which will overwrite the file on save.
+pub struct SceneConflictDialog {
+    message_box: Handle<UiNode>,
+    scene: Option<Uuid>,
+    pending_save: Option<PathBuf>,
+}
+
+impl SceneConflictDialog {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let message_box = MessageBoxBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(160.0))
+                .can_close(false)
+                .can_minimize(false)
+                .open(false)
+                .with_title(WindowTitle::text("Scene Changed On Disk")),
+        )
+        .with_buttons(MessageBoxButtons::YesNoCancel)
+        .build(ctx);
+
+        Self {
+            message_box,
+            scene: None,
+            pending_save: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.scene.is_some()
+    }
+
+    /// Opens the dialog for the scene. `pending_save` is a path, to which the scene was about
+    /// to be saved; the saving is resumed, if the user decides to keep the current version.
+    pub fn open(
+        &mut self,
+        ui: &UserInterface,
+        entry: &EditorSceneEntry,
+        pending_save: Option<PathBuf>,
+    ) {
+        self.scene = Some(entry.id);
+        self.pending_save = pending_save;
+
+        ui.send_message(MessageBoxMessage::open(
+            self.message_box,
+            MessageDirection::ToWidget,
+            None,
+            Some(format!(
+                "The {} scene was changed on disk since it was loaded, probably by another \
+                editor. Do you want to reload it?\n\nYes - reload the scene, your changes will \
+                be lost.\nNo - keep your version, it will overwrite the file on save.\nCancel - \
+                decide later.",
+                entry.name()
+            )),
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        sender: &MessageSender,
+        scenes: &mut SceneContainer,
+    ) {
+        let Some(MessageBoxMessage::Close(result)) = message.data() else {
+            return;
+        };
+
+        if message.destination() != self.message_box {
+            return;
+        }
+
+        let pending_save = self.pending_save.take();
+        let Some(entry) = self
+            .scene
+            .take()
+            .and_then(|id| scenes.entry_by_scene_id_mut(id))
+        else {
+            return;
+        };
+
+        match result {
+            MessageBoxResult::Yes => {
+                if let Some(path) = entry.path.clone() {
+                    sender.send(Message::CloseScene(entry.id));
+                    sender.send(Message::LoadScene(path));
+                }
+            }
+            MessageBoxResult::No => {
+                entry.sync_disk_state();
+                if let Some(path) = pending_save {
+                    sender.send(Message::SaveScene { id: entry.id, path });
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn temp_scene_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fyrox-scene-lock-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("scene.rgs")
+    }
+
+    fn other_owner() -> SceneLockOwner {
+        SceneLockOwner {
+            user: "someone".to_string(),
+            host: "other-host".to_string(),
+            process_id: std::process::id().wrapping_add(1),
+            since: 0,
+        }
+    }
+
+    fn write_lock(scene_path: &Path, owner: &SceneLockOwner) {
+        std::fs::write(
+            SceneLock::lock_path(scene_path),
+            ron::ser::to_string(owner).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_scene_lock_acquire_and_release() {
+        let scene_path = temp_scene_path();
+        assert_eq!(SceneLock::owner_of(&scene_path), None);
+
+        let lock = SceneLock::acquire(&scene_path).unwrap();
+        assert_eq!(lock.path(), SceneLock::lock_path(&scene_path));
+        assert!(lock.path().exists());
+        assert_eq!(SceneLock::owner_of(&scene_path), Some(lock.owner.clone()));
+
+        drop(lock);
+        assert!(!SceneLock::lock_path(&scene_path).exists());
+        assert_eq!(SceneLock::owner_of(&scene_path), None);
+
+        std::fs::remove_dir_all(scene_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_scene_lock_of_another_owner() {
+        let scene_path = temp_scene_path();
+        let owner = other_owner();
+        write_lock(&scene_path, &owner);
+
+        assert_eq!(SceneLock::owner_of(&scene_path), Some(owner.clone()));
+        assert_eq!(SceneLock::acquire(&scene_path).unwrap_err(), owner);
+        // A failed attempt must not touch the lock of another owner.
+        assert_eq!(SceneLock::owner_of(&scene_path), Some(owner.clone()));
+
+        std::fs::remove_dir_all(scene_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_scene_lock_taken_over_is_not_removed() {
+        let scene_path = temp_scene_path();
+        let lock = SceneLock::acquire(&scene_path).unwrap();
+
+        // Someone else has taken the lock over, so it must be left intact on drop.
+        let owner = other_owner();
+        write_lock(&scene_path, &owner);
+        drop(lock);
+        assert_eq!(SceneLock::owner_of(&scene_path), Some(owner));
+
+        std::fs::remove_dir_all(scene_path.parent().unwrap()).unwrap();
+    }
+}
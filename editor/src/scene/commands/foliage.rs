@@ -0,0 +1,80 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    command::{CommandContext, CommandTrait},
+    fyrox::{
+        core::pool::Handle,
+        scene::{
+            foliage::{Foliage, FoliageInstance},
+            node::Node,
+        },
+    },
+    scene::commands::GameSceneContext,
+};
+
+#[derive(Debug)]
+pub struct ModifyFoliageInstancesCommand {
+    foliage: Handle<Node>,
+    layer: usize,
+    instances: Vec<FoliageInstance>,
+    skip_first_execute: bool,
+}
+
+impl ModifyFoliageInstancesCommand {
+    /// Creates a command for the changes that were already made by painting. `instances` is the
+    /// state of the layer before the painting.
+    pub fn new(foliage: Handle<Node>, layer: usize, instances: Vec<FoliageInstance>) -> Self {
+        Self {
+            foliage,
+            layer,
+            instances,
+            skip_first_execute: true,
+        }
+    }
+
+    fn swap(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        if let Some(layer) = context.scene.graph[self.foliage]
+            .cast_mut::<Foliage>()
+            .and_then(|foliage| foliage.layers_mut().get_mut(self.layer))
+        {
+            std::mem::swap(&mut layer.instances, &mut self.instances);
+        }
+    }
+}
+
+impl CommandTrait for ModifyFoliageInstancesCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Paint Foliage".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        if self.skip_first_execute {
+            self.skip_first_execute = false;
+            return;
+        }
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.swap(context);
+    }
+}
@@ -21,6 +21,7 @@
 use crate::{
     command::{CommandContext, CommandTrait},
     fyrox::{
+        asset::{self, untyped::UntypedResource},
         core::{
             algebra::{UnitQuaternion, Vector3},
             pool::{Handle, Ticket},
@@ -587,3 +588,45 @@ impl CommandTrait for SetGraphRootCommand {
         self.root = std::mem::replace(context.scene_content_root, self.root);
     }
 }
+
+#[derive(Debug)]
+pub struct ReplaceResourceCommand {
+    old_resource: UntypedResource,
+    new_resource: UntypedResource,
+    nodes: Vec<Handle<Node>>,
+}
+
+impl ReplaceResourceCommand {
+    pub fn new(old_resource: UntypedResource, new_resource: UntypedResource) -> Self {
+        Self {
+            old_resource,
+            new_resource,
+            nodes: Default::default(),
+        }
+    }
+}
+
+impl CommandTrait for ReplaceResourceCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Replace Resource".to_string()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        self.nodes = context
+            .scene
+            .graph
+            .replace_resource(&self.old_resource, &self.new_resource);
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let context = context.get_mut::<GameSceneContext>();
+        // Restore the references only in the changed nodes, other nodes could reference the new
+        // resource before the replacement.
+        for node in self.nodes.drain(..) {
+            if let Some(node) = context.scene.graph.try_get_mut(node) {
+                asset::replace_used_resource(node, &self.new_resource, &self.old_resource);
+            }
+        }
+    }
+}
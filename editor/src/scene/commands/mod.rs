@@ -38,6 +38,7 @@ use crate::{
 use std::sync::Arc;
 
 pub mod effect;
+pub mod foliage;
 pub mod graph;
 pub mod material;
 pub mod mesh;
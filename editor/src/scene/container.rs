@@ -20,7 +20,7 @@
 
 use crate::command::CommandStack;
 use crate::fyrox::{
-    core::{algebra::Vector2, math::Rect, pool::Handle, uuid::Uuid, TypeUuidProvider},
+    core::{algebra::Vector2, log::Log, math::Rect, pool::Handle, uuid::Uuid, TypeUuidProvider},
     engine::Engine,
     gui::{
         message::{KeyCode, MouseButton},
@@ -31,13 +31,17 @@ use crate::fyrox::{
 use crate::{
     highlight::HighlightRenderPass,
     interaction::{
-        move_mode::MoveInteractionMode, navmesh::EditNavmeshMode,
+        foliage::FoliageInteractionMode, move_mode::MoveInteractionMode, navmesh::EditNavmeshMode,
         rotate_mode::RotateInteractionMode, scale_mode::ScaleInteractionMode,
         select_mode::SelectInteractionMode, terrain::TerrainInteractionMode,
         InteractionModeContainer,
     },
     message::MessageSender,
-    scene::{controller::SceneController, GameScene, Selection},
+    scene::{
+        collaboration::{modification_time, SceneLock, SceneLockOwner},
+        controller::SceneController,
+        GameScene, Selection,
+    },
     scene_viewer::SceneViewer,
     settings::{keys::KeyBindings, Settings},
     ui_scene::{
@@ -45,7 +49,7 @@ use crate::{
         UiScene,
     },
 };
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, path::PathBuf, rc::Rc, time::SystemTime};
 
 pub struct EditorSceneEntry {
     pub has_unsaved_changes: bool,
@@ -60,6 +64,10 @@ pub struct EditorSceneEntry {
     pub click_mouse_pos: Option<Vector2<f32>>,
     pub sender: MessageSender,
     pub id: Uuid,
+    /// Advisory lock of the scene file, see [`SceneLock`] docs for more info.
+    pub lock: Option<SceneLock>,
+    /// Modification time of the scene file at the moment when it was loaded or saved.
+    pub disk_modification_time: Option<SystemTime>,
 }
 
 impl EditorSceneEntry {
@@ -113,6 +121,12 @@ impl EditorSceneEntry {
             message_sender.clone(),
             scene_viewer.frame(),
         ));
+        interaction_modes.add(FoliageInteractionMode::new(
+            &game_scene,
+            engine,
+            message_sender.clone(),
+            scene_viewer.frame(),
+        ));
         interaction_modes.sender = Some(message_sender.clone());
 
         let mut entry = EditorSceneEntry {
@@ -124,6 +138,8 @@ impl EditorSceneEntry {
             click_mouse_pos: None,
             sender: message_sender,
             id: Uuid::new_v4(),
+            lock: None,
+            disk_modification_time: path.as_deref().and_then(modification_time),
             path,
             selection: Default::default(),
             command_stack: CommandStack::new(false, settings.general.max_history_entries),
@@ -160,6 +176,8 @@ impl EditorSceneEntry {
             click_mouse_pos: None,
             sender: message_sender,
             id: Uuid::new_v4(),
+            lock: None,
+            disk_modification_time: path.as_deref().and_then(modification_time),
             path,
             selection: Default::default(),
             command_stack: CommandStack::new(false, settings.general.max_history_entries),
@@ -221,9 +239,53 @@ impl EditorSceneEntry {
     ) -> Result<String, String> {
         let result = self.controller.save(&path, settings, engine);
         self.path = Some(path);
+        if result.is_ok() {
+            self.sync_disk_state();
+            // The scene could be saved to a new file.
+            if let Err(owner) = self.acquire_lock() {
+                Log::warn(format!(
+                    "The {} scene is also opened by {owner}.",
+                    self.name()
+                ));
+            }
+        }
         result
     }
 
+    /// Locks the scene file (if it is not locked by this editor already). Returns the owner of the
+    /// lock, if the file is locked by another editor.
+    pub fn acquire_lock(&mut self) -> Result<(), SceneLockOwner> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+
+        if self
+            .lock
+            .as_ref()
+            .is_some_and(|lock| lock.path() == SceneLock::lock_path(path))
+        {
+            return Ok(());
+        }
+
+        self.lock = None;
+        self.lock = Some(SceneLock::acquire(path)?);
+        Ok(())
+    }
+
+    /// Returns `true` if the scene file was changed on disk since it was loaded or saved by this
+    /// editor.
+    pub fn is_changed_on_disk(&self) -> bool {
+        self.path
+            .as_deref()
+            .and_then(modification_time)
+            .is_some_and(|time| Some(time) != self.disk_modification_time)
+    }
+
+    /// Remembers the current state of the scene file on disk, so it won't be reported as changed.
+    pub fn sync_disk_state(&mut self) {
+        self.disk_modification_time = self.path.as_deref().and_then(modification_time);
+    }
+
     #[must_use]
     pub fn on_key_up(
         &mut self,
@@ -29,7 +29,7 @@ use crate::{
     camera::{CameraController, PickingOptions},
     command::{make_command, Command, CommandGroup, CommandStack},
     fyrox::{
-        asset::manager::ResourceManager,
+        asset::{manager::ResourceManager, ResourceData},
         core::{
             algebra::{Matrix4, Vector2, Vector3},
             color::Color,
@@ -60,7 +60,7 @@ use crate::{
             animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
             base::BaseBuilder,
             camera::{Camera, Projection},
-            debug::{Line, SceneDrawingContext},
+            debug::{Line, SceneDrawingContext, SkeletonDrawingOptions},
             graph::{Graph, GraphUpdateSwitches},
             light::{point::PointLight, spot::SpotLight},
             mesh::RenderPath,
@@ -71,7 +71,7 @@ use crate::{
             navmesh::NavigationalMesh,
             node::Node,
             pivot::PivotBuilder,
-            sound::AudioBus,
+            sound::{AudioBus, Sound},
             terrain::Terrain,
             Scene, SceneContainer,
         },
@@ -113,6 +113,7 @@ use std::{
 };
 
 pub mod clipboard;
+pub mod collaboration;
 pub mod dialog;
 pub mod property;
 pub mod selector;
@@ -210,6 +211,9 @@ impl GameScene {
             scene_content_root,
         );
 
+        // Sounds are heard from the editor's camera by default.
+        let editor_listener = camera_controller.listener;
+
         // Freeze physics simulation in while editing scene by setting time step to zero.
         scene.graph.physics.integration_parameters.dt = Some(0.0);
         scene.graph.physics2d.integration_parameters.dt = Some(0.0);
@@ -231,6 +235,7 @@ impl GameScene {
                 // Update only editor's camera.
                 node_overrides: Some(Default::default()),
                 paused: false,
+                active_listener: Some(editor_listener),
             },
             sender,
             camera_state: Default::default(),
@@ -303,6 +308,42 @@ impl GameScene {
                         node.global_transform(),
                         Color::GREEN,
                     );
+
+                    if let Some(sound) = node.cast::<Sound>() {
+                        // Show attenuation of the sound: full volume within the radius and
+                        // silence beyond the max distance.
+                        let position = sound.global_position();
+                        scene.drawing_context.draw_sphere(
+                            position,
+                            16,
+                            16,
+                            sound.radius(),
+                            Color::opaque(0, 200, 255),
+                        );
+                        if sound.max_distance() < f32::MAX {
+                            scene.drawing_context.draw_sphere(
+                                position,
+                                16,
+                                16,
+                                sound.max_distance(),
+                                Color::opaque(0, 80, 255),
+                            );
+                        }
+                    }
+
+                    if debug_settings.show_skeletons || debug_settings.show_bind_poses {
+                        if let Some(mesh) = node.cast::<Mesh>() {
+                            mesh.debug_draw_skeleton(
+                                &scene.graph,
+                                &mut scene.drawing_context,
+                                &SkeletonDrawingOptions {
+                                    draw_bones: debug_settings.show_skeletons,
+                                    draw_bind_pose: debug_settings.show_bind_poses,
+                                    ..Default::default()
+                                },
+                            );
+                        }
+                    }
                 }
             }
         }
@@ -433,30 +474,17 @@ impl GameScene {
 
     fn try_save_selection_as_prefab(&self, path: &Path, selection: &Selection, engine: &Engine) {
         let source_scene = &engine.scenes[self.scene];
-        let mut dest_scene = Scene::new();
         if let Some(graph_selection) = selection.as_graph() {
-            for root_node in graph_selection.root_nodes(&source_scene.graph) {
-                source_scene.graph.copy_node(
-                    root_node,
-                    &mut dest_scene.graph,
-                    &mut |_, _| true,
-                    &mut |_, _| {},
-                    &mut |_, _, _| {},
-                );
-            }
+            let mut prefab = Model::from_subgraph(
+                &source_scene.graph,
+                &graph_selection.root_nodes(&source_scene.graph),
+            );
 
-            let mut visitor = Visitor::new();
-            match dest_scene.save("Scene", &mut visitor) {
+            match prefab.save(path) {
                 Err(e) => Log::err(format!("Failed to save selection as prefab! Reason: {e:?}")),
-                Ok(_) => {
-                    if let Err(e) = visitor.save_binary(path) {
-                        Log::err(format!("Failed to save selection as prefab! Reason: {e:?}"));
-                    } else {
-                        Log::info(format!(
-                            "Selection was successfully saved as prefab to {path:?}!"
-                        ))
-                    }
-                }
+                Ok(_) => Log::info(format!(
+                    "Selection was successfully saved as prefab to {path:?}!"
+                )),
             }
         } else {
             Log::warn("Unable to selection to prefab, because selection is not scene selection!");
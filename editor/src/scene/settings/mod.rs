@@ -37,10 +37,12 @@ use crate::fyrox::{
     resource::texture::TextureResource,
     scene::{
         dim2,
+        fog::{Fog, FogMode},
         graph::{
             physics::{IntegrationParameters, PhysicsWorld},
             Graph, NodePool,
         },
+        weather::{Precipitation, PrecipitationLayer, Weather},
         SceneRenderingOptions,
     },
     utils::lightmap::Lightmap,
@@ -85,6 +87,12 @@ impl SceneSettingsWindow {
         container.register_inheritable_inspectable::<PhysicsWorld>();
         container.register_inheritable_inspectable::<dim2::physics::PhysicsWorld>();
         container.register_inheritable_inspectable::<SceneRenderingOptions>();
+        container.register_inheritable_option::<Fog>();
+        container.register_inheritable_inspectable::<Fog>();
+        container.register_inheritable_enum::<FogMode, _>();
+        container.register_inheritable_inspectable::<Weather>();
+        container.register_inheritable_inspectable::<PrecipitationLayer>();
+        container.register_inheritable_enum::<Precipitation, _>();
         container.insert(EnumPropertyEditorDefinition::<Color>::new_optional());
 
         Self {
@@ -52,7 +52,10 @@ use crate::{
         },
         renderer::framework::PolygonFillMode,
         resource::texture::TextureResource,
-        scene::camera::Projection,
+        scene::{
+            camera::{Camera, Projection},
+            mesh::Mesh,
+        },
     },
     gui::{
         make_dropdown_list_option, make_dropdown_list_option_universal,
@@ -60,11 +63,12 @@ use crate::{
     },
     load_image,
     message::MessageSender,
-    scene::container::EditorSceneEntry,
+    scene::{container::EditorSceneEntry, Selection},
     scene_viewer::gizmo::{SceneGizmo, SceneGizmoAction},
     send_sync_message,
     settings::SettingsMessage,
     utils::enable_widget,
+    version_control::{FileStatus, VersionControl},
     DropdownListBuilder, GameScene, Message, Mode, SaveSceneConfirmationDialogAction,
     SceneContainer, Settings,
 };
@@ -292,6 +296,8 @@ pub struct SceneViewer {
     scene_gizmo_image: Handle<UiNode>,
     debug_switches: Handle<UiNode>,
     grid_snap_menu: GridSnappingMenu,
+    bone_names_canvas: Handle<UiNode>,
+    bone_names: Vec<Handle<UiNode>>,
 }
 
 impl SceneViewer {
@@ -302,6 +308,7 @@ impl SceneViewer {
 
         let frame;
         let selection_frame;
+        let bone_names_canvas;
         let camera_projection;
         let play;
         let stop;
@@ -535,7 +542,14 @@ impl SceneViewer {
                                             selection_frame
                                         }))
                                         .build(ctx),
-                                    ),
+                                    )
+                                    .with_child({
+                                        bone_names_canvas = CanvasBuilder::new(
+                                            WidgetBuilder::new().with_hit_test_visibility(false),
+                                        )
+                                        .build(ctx);
+                                        bone_names_canvas
+                                    }),
                             )
                             .add_row(Row::stretch())
                             .add_column(Column::stretch())
@@ -569,6 +583,8 @@ impl SceneViewer {
             scene_gizmo_image,
             debug_switches,
             grid_snap_menu,
+            bone_names_canvas,
+            bone_names: Default::default(),
         }
     }
 }
@@ -644,6 +660,12 @@ impl SceneViewer {
         engine: &mut Engine,
     ) {
         let ui = engine.user_interfaces.first_mut();
+
+        // Bone names belong to the previous scene.
+        for label in self.bone_names.drain(..) {
+            ui.send_message(WidgetMessage::remove(label, MessageDirection::ToWidget));
+        }
+
         let index = new_scene
             .as_ref()
             .and_then(|entry| entry.controller.downcast_ref::<GameScene>())
@@ -918,7 +940,12 @@ impl SceneViewer {
         }
     }
 
-    pub fn sync_to_model(&self, scenes: &SceneContainer, engine: &mut Engine) {
+    pub fn sync_to_model(
+        &self,
+        scenes: &SceneContainer,
+        version_control: &VersionControl,
+        engine: &mut Engine,
+    ) {
         // Sync tabs first.
         fn fetch_tab_id(tab: &Tab) -> Uuid {
             tab.user_data
@@ -992,6 +1019,11 @@ impl SceneViewer {
 
         for tab in tabs.iter() {
             if let Some(scene) = scenes.entry_by_scene_id(fetch_tab_id(tab)) {
+                let status = scene
+                    .path
+                    .as_ref()
+                    .map(|path| version_control.status(path))
+                    .unwrap_or_default();
                 engine
                     .user_interfaces
                     .first_mut()
@@ -999,9 +1031,14 @@ impl SceneViewer {
                         tab.header_content,
                         MessageDirection::ToWidget,
                         format!(
-                            "{}{}",
+                            "{}{}{}",
                             scene.name(),
-                            if scene.need_save() { "*" } else { "" }
+                            if scene.need_save() { "*" } else { "" },
+                            if status == FileStatus::Unmodified {
+                                String::new()
+                            } else {
+                                format!(" [{}]", status.marker())
+                            }
                         ),
                     ));
             }
@@ -1125,7 +1162,81 @@ impl SceneViewer {
             .update(settings, engine.user_interfaces.first());
     }
 
-    pub fn update(&self, game_scene: &GameScene, engine: &mut Engine) {
+    pub fn update(
+        &mut self,
+        game_scene: &GameScene,
+        editor_selection: &Selection,
+        settings: &Settings,
+        engine: &mut Engine,
+    ) {
         self.scene_gizmo.sync_rotations(game_scene, engine);
+        self.update_bone_names(game_scene, editor_selection, settings, engine);
+    }
+
+    fn update_bone_names(
+        &mut self,
+        game_scene: &GameScene,
+        editor_selection: &Selection,
+        settings: &Settings,
+        engine: &mut Engine,
+    ) {
+        let ui = engine.user_interfaces.first_mut();
+        let graph = &engine.scenes[game_scene.scene].graph;
+
+        let mut labels = Vec::new();
+        if settings.debugging.show_bone_names {
+            if let (Some(selection), Some(camera)) = (
+                editor_selection.as_graph(),
+                graph.try_get_of_type::<Camera>(game_scene.camera_controller.camera),
+            ) {
+                let frame_size = ui.node(self.frame).actual_local_size();
+                for mesh in selection
+                    .nodes()
+                    .iter()
+                    .filter_map(|handle| graph.try_get_of_type::<Mesh>(*handle))
+                {
+                    for bone in mesh.bones() {
+                        if let Some(bone) = graph.try_get(bone) {
+                            if let Some(position) =
+                                camera.project(bone.global_position(), frame_size)
+                            {
+                                labels.push((bone.name_owned(), position));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        while self.bone_names.len() > labels.len() {
+            if let Some(label) = self.bone_names.pop() {
+                ui.send_message(WidgetMessage::remove(label, MessageDirection::ToWidget));
+            }
+        }
+
+        while self.bone_names.len() < labels.len() {
+            let label = TextBuilder::new(
+                WidgetBuilder::new()
+                    .with_hit_test_visibility(false)
+                    .with_foreground(Brush::Solid(Color::opaque(255, 200, 0))),
+            )
+            .with_shadow(true)
+            .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                label,
+                MessageDirection::ToWidget,
+                self.bone_names_canvas,
+            ));
+            self.bone_names.push(label);
+        }
+
+        for (label, (name, position)) in self.bone_names.iter().zip(labels) {
+            ui.send_message(TextMessage::text(*label, MessageDirection::ToWidget, name));
+            ui.send_message(WidgetMessage::desired_position(
+                *label,
+                MessageDirection::ToWidget,
+                position,
+            ));
+        }
     }
 }
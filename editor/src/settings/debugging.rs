@@ -40,6 +40,15 @@ pub struct DebuggingSettings {
     )]
     #[serde(default)]
     pub save_scene_in_text_form: bool,
+    #[reflect(description = "Draws skeletons of selected skinned meshes.")]
+    #[serde(default)]
+    pub show_skeletons: bool,
+    #[reflect(description = "Draws bind poses of skeletons of selected skinned meshes.")]
+    #[serde(default)]
+    pub show_bind_poses: bool,
+    #[reflect(description = "Shows names of the bones of selected skinned meshes.")]
+    #[serde(default)]
+    pub show_bone_names: bool,
 }
 
 impl Default for DebuggingSettings {
@@ -53,6 +62,9 @@ impl Default for DebuggingSettings {
             show_camera_bounds: true,
             pictogram_size: 0.33,
             save_scene_in_text_form: false,
+            show_skeletons: false,
+            show_bind_poses: false,
+            show_bone_names: false,
         }
     }
 }
@@ -60,6 +60,8 @@ pub struct KeyBindings {
     pub enable_scale_mode: HotKey,
     pub enable_navmesh_mode: HotKey,
     pub enable_terrain_mode: HotKey,
+    #[serde(default = "default_foliage_mode_hotkey")]
+    pub enable_foliage_mode: HotKey,
     pub save_scene: HotKey,
     #[serde(default = "default_save_scene_as_hotkey")]
     pub save_scene_as: HotKey,
@@ -109,6 +111,10 @@ fn default_run_hotkey() -> HotKey {
     HotKey::from_key_code(KeyCode::F5)
 }
 
+fn default_foliage_mode_hotkey() -> HotKey {
+    HotKey::from_key_code(KeyCode::Digit7)
+}
+
 fn default_terrain_key_bindings() -> TerrainKeyBindings {
     TerrainKeyBindings {
         modify_height_map_mode: HotKey::from_key_code(KeyCode::F1),
@@ -143,6 +149,7 @@ impl Default for KeyBindings {
             enable_scale_mode: HotKey::from_key_code(KeyCode::Digit4),
             enable_navmesh_mode: HotKey::from_key_code(KeyCode::Digit5),
             enable_terrain_mode: HotKey::from_key_code(KeyCode::Digit6),
+            enable_foliage_mode: default_foliage_mode_hotkey(),
             save_scene: HotKey::ctrl_key(KeyCode::KeyS),
             save_scene_as: default_save_scene_as_hotkey(),
             save_all_scenes: default_save_all_scenes_hotkey(),
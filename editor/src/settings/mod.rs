@@ -41,7 +41,7 @@ use crate::{
             window::{WindowBuilder, WindowMessage, WindowTitle},
             HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
         },
-        renderer::{CsmSettings, QualitySettings, ShadowMapPrecision},
+        renderer::{CsmSettings, QualitySettings, ShadowMapPrecision, TextureStreamingSettings},
     },
     inspector::editors::make_property_editors_container,
     message::MessageSender,
@@ -59,6 +59,7 @@ use crate::{
         rotate_mode::RotateInteractionModeSettings,
         scene::SceneSettings,
         selection::SelectionSettings,
+        version_control::{VersionControlSettings, VersionControlSystem},
         windows::WindowsSettings,
     },
     Engine, MSG_SYNC_FLAG,
@@ -89,6 +90,7 @@ pub mod recent;
 pub mod rotate_mode;
 pub mod scene;
 pub mod selection;
+pub mod version_control;
 pub mod windows;
 
 pub struct SettingsWindow {
@@ -113,6 +115,8 @@ pub struct SettingsData {
     pub camera: CameraSettings,
     pub navmesh: NavmeshSettings,
     pub key_bindings: KeyBindings,
+    #[serde(default)]
+    pub version_control: VersionControlSettings,
     #[reflect(hidden)]
     pub scene_settings: HashMap<PathBuf, SceneSettings>,
     #[reflect(hidden)]
@@ -233,6 +237,9 @@ impl SettingsData {
         container.insert(EnumPropertyEditorDefinition::<ScriptEditor>::new());
         container.insert(InspectablePropertyEditorDefinition::<DebuggingSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CsmSettings>::new());
+        container.insert(InspectablePropertyEditorDefinition::<
+            TextureStreamingSettings,
+        >::new());
         container.insert(InspectablePropertyEditorDefinition::<QualitySettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CameraSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<
@@ -246,6 +253,8 @@ impl SettingsData {
         container.insert(InspectablePropertyEditorDefinition::<KeyBindings>::new());
         container.insert(InspectablePropertyEditorDefinition::<TerrainKeyBindings>::new());
         container.insert(InspectablePropertyEditorDefinition::<BuildSettings>::new());
+        container.insert(InspectablePropertyEditorDefinition::<VersionControlSettings>::new());
+        container.insert(EnumPropertyEditorDefinition::<VersionControlSystem>::new());
         container.insert(VecCollectionPropertyEditorDefinition::<EnvironmentVariable>::new());
         container.insert(InspectablePropertyEditorDefinition::<EnvironmentVariable>::new());
         container.insert(VecCollectionPropertyEditorDefinition::<BuildProfile>::new());
@@ -0,0 +1,67 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::fyrox::core::{reflect::prelude::*, uuid_provider};
+use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum VersionControlSystem {
+    #[default]
+    Disabled,
+    Git,
+    Perforce,
+}
+
+uuid_provider!(VersionControlSystem = "5b0e9f57-0c3c-4c56-9f1e-cf3d0f8a7e21");
+
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug, Default, Reflect)]
+pub struct VersionControlSettings {
+    #[reflect(
+        description = "Version control system of the project. It is used to show statuses of \
+    files in the asset browser and scene tabs and to check out files before saving them."
+    )]
+    #[serde(default)]
+    pub system: VersionControlSystem,
+
+    #[reflect(
+        description = "A command that checks out (makes writable or locks) a file before the editor \
+    saves it, `{path}` is replaced with the path of the file. For example, `git lfs lock {path}`. \
+    If empty, `p4 edit {path}` is used for Perforce and nothing is done for Git."
+    )]
+    #[serde(default)]
+    pub checkout_command: String,
+}
@@ -19,9 +19,12 @@
 // SOFTWARE.
 
 use crate::fyrox::{
-    core::pool::Handle,
+    core::{algebra::Vector2, color::Color, pool::Handle},
     engine::{Engine, GraphicsContext},
     gui::{
+        brush::Brush,
+        chart::{ChartBuilder, ChartMessage, ChartSeries, ChartSeriesKind},
+        grid::{Column, GridBuilder, Row},
         message::{MessageDirection, UiMessage},
         scroll_viewer::ScrollViewerBuilder,
         text::{TextBuilder, TextMessage},
@@ -35,6 +38,8 @@ use fyrox::scene::Scene;
 pub struct StatisticsWindow {
     pub window: Handle<UiNode>,
     text: Handle<UiNode>,
+    frame_time_chart: Handle<UiNode>,
+    frame: usize,
 }
 
 pub enum StatisticsWindowAction {
@@ -45,18 +50,43 @@ pub enum StatisticsWindowAction {
 impl StatisticsWindow {
     pub fn new(ctx: &mut BuildContext, anchor: Handle<UiNode>) -> Self {
         let text;
-        let window = WindowBuilder::new(WidgetBuilder::new().with_width(215.0).with_height(300.0))
+        let frame_time_chart;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(215.0).with_height(400.0))
             .open(false)
             .with_content(
-                ScrollViewerBuilder::new(WidgetBuilder::new())
-                    .with_content({
-                        text = TextBuilder::new(
-                            WidgetBuilder::new().with_margin(Thickness::uniform(2.0)),
-                        )
-                        .build(ctx);
-                        text
-                    })
-                    .build(ctx),
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child({
+                            frame_time_chart = ChartBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(0)
+                                    .with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_series(vec![ChartSeries::new(
+                                "Frame Time (ms)",
+                                ChartSeriesKind::Line,
+                                Brush::Solid(Color::opaque(0, 200, 0)),
+                            )])
+                            .with_show_values(false)
+                            .build(ctx);
+                            frame_time_chart
+                        })
+                        .with_child(
+                            ScrollViewerBuilder::new(WidgetBuilder::new().on_row(1))
+                                .with_content({
+                                    text = TextBuilder::new(
+                                        WidgetBuilder::new().with_margin(Thickness::uniform(2.0)),
+                                    )
+                                    .build(ctx);
+                                    text
+                                })
+                                .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(100.0))
+                .add_row(Row::stretch())
+                .add_column(Column::stretch())
+                .build(ctx),
             )
             .with_title(WindowTitle::text("Rendering Statistics"))
             .build(ctx);
@@ -74,7 +104,12 @@ impl StatisticsWindow {
             ))
             .unwrap();
 
-        Self { window, text }
+        Self {
+            window,
+            text,
+            frame_time_chart,
+            frame: 0,
+        }
     }
 
     pub fn handle_ui_message(
@@ -95,22 +130,36 @@ impl StatisticsWindow {
         StatisticsWindowAction::None
     }
 
-    pub fn update(&self, current_scene: Handle<Scene>, engine: &Engine) {
+    pub fn update(&mut self, current_scene: Handle<Scene>, engine: &Engine) {
         if let GraphicsContext::Initialized(ref graphics_context) = engine.graphics_context {
             if let Some(stats) = graphics_context.renderer.scene_data_map.get(&current_scene) {
                 let global_stats = graphics_context.renderer.get_statistics();
-                let statistics = format!(
+                let mut statistics = format!(
                     "FPS: {}\nFrame Time:{}\n{}",
                     global_stats.frames_per_second, global_stats.pure_frame_time, stats.statistics
                 );
-                engine
-                    .user_interfaces
-                    .first()
-                    .send_message(TextMessage::text(
-                        self.text,
-                        MessageDirection::ToWidget,
-                        statistics,
-                    ));
+                if let Some(breakdown) = global_stats.scene(current_scene) {
+                    statistics += &breakdown.to_string();
+                }
+                let ui = engine.user_interfaces.first();
+                ui.send_message(TextMessage::text(
+                    self.text,
+                    MessageDirection::ToWidget,
+                    statistics,
+                ));
+
+                self.frame += 1;
+                ui.send_message(ChartMessage::push_point(
+                    self.frame_time_chart,
+                    MessageDirection::ToWidget,
+                    0,
+                    Vector2::new(self.frame as f32, global_stats.pure_frame_time * 1000.0),
+                    200,
+                ));
+                ui.send_message(ChartMessage::fit_to_data(
+                    self.frame_time_chart,
+                    MessageDirection::ToWidget,
+                ));
             }
         }
     }
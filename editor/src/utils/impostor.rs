@@ -0,0 +1,385 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    command::{CommandContext, CommandTrait},
+    fyrox::{
+        core::{
+            algebra::Vector3,
+            log::Log,
+            pool::{Handle, Ticket},
+            reflect::prelude::*,
+        },
+        engine::{Engine, GraphicsContext},
+        graph::{BaseSceneGraph, SceneGraph},
+        gui::{
+            button::{ButtonBuilder, ButtonMessage},
+            grid::{Column, GridBuilder, Row},
+            inspector::{InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction},
+            message::{MessageDirection, UiMessage},
+            stack_panel::StackPanelBuilder,
+            widget::WidgetBuilder,
+            window::{WindowBuilder, WindowMessage, WindowTitle},
+            BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        },
+        material::MaterialResource,
+        scene::{
+            base::BaseBuilder,
+            impostor::{Impostor, ImpostorBuilder},
+            node::Node,
+            transform::Transform,
+        },
+        utils::impostor::{self, ImpostorBakeOptions},
+    },
+    inspector::editors::make_property_editors_container,
+    message::MessageSender,
+    scene::{commands::GameSceneContext, GameScene, Selection},
+    MSG_SYNC_FLAG,
+};
+use std::sync::Arc;
+
+#[derive(Reflect, Debug)]
+pub struct ImpostorBakerSettings {
+    #[reflect(
+        description = "Amount of views of the object from different angles around its Y axis.",
+        min_value = 1.0,
+        max_value = 64.0
+    )]
+    frames: u32,
+    #[reflect(
+        description = "Size of a single view in pixels.",
+        min_value = 16.0,
+        max_value = 2048.0
+    )]
+    frame_size: u32,
+    #[reflect(
+        description = "Distance from the camera at which the object will be replaced with its impostor.",
+        min_value = 0.0
+    )]
+    distance: f32,
+}
+
+impl Default for ImpostorBakerSettings {
+    fn default() -> Self {
+        let options = ImpostorBakeOptions::default();
+        Self {
+            frames: options.frames,
+            frame_size: options.frame_size,
+            distance: 50.0,
+        }
+    }
+}
+
+/// Creates an impostor node for a node, that was baked by the impostor baker. The impostor takes the
+/// place of the node in the hierarchy and the node becomes a child of the impostor.
+#[derive(Debug)]
+struct InsertImpostorCommand {
+    ticket: Option<Ticket<Node>>,
+    handle: Handle<Node>,
+    impostor: Option<Node>,
+    node: Handle<Node>,
+    parent: Handle<Node>,
+    transform: Transform,
+}
+
+impl CommandTrait for InsertImpostorCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Insert Impostor".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+        match self.ticket.take() {
+            None => {
+                self.handle = graph.add_node(self.impostor.take().unwrap());
+            }
+            Some(ticket) => {
+                let handle = graph.put_back(ticket, self.impostor.take().unwrap());
+                assert_eq!(handle, self.handle);
+            }
+        }
+        graph.link_nodes(self.handle, self.parent);
+        graph.link_nodes(self.node, self.handle);
+        *graph[self.node].local_transform_mut() = Transform::identity();
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+        graph.link_nodes(self.node, self.parent);
+        *graph[self.node].local_transform_mut() = self.transform.clone();
+        let (ticket, impostor) = graph.take_reserve(self.handle);
+        self.ticket = Some(ticket);
+        self.impostor = Some(impostor);
+    }
+
+    fn finalize(&mut self, context: &mut dyn CommandContext) {
+        if let Some(ticket) = self.ticket.take() {
+            context
+                .get_mut::<GameSceneContext>()
+                .scene
+                .graph
+                .forget_ticket(ticket, self.impostor.take().unwrap());
+        }
+    }
+}
+
+/// Replaces the atlas of an existing impostor with a freshly baked one.
+#[derive(Debug)]
+struct SetImpostorAtlasCommand {
+    handle: Handle<Node>,
+    material: MaterialResource,
+    frames: u32,
+    size: f32,
+    center: Vector3<f32>,
+}
+
+impl SetImpostorAtlasCommand {
+    fn swap(&mut self, context: &mut dyn CommandContext) {
+        let graph = &mut context.get_mut::<GameSceneContext>().scene.graph;
+        if let Some(impostor) = graph.try_get_mut_of_type::<Impostor>(self.handle) {
+            self.material = impostor.set_material(self.material.clone());
+            self.frames = impostor.set_frames(self.frames);
+            self.size = impostor.set_size(self.size);
+            self.center = impostor.set_center(self.center);
+        }
+    }
+}
+
+impl CommandTrait for SetImpostorAtlasCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Set Impostor Atlas".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.swap(context);
+    }
+}
+
+pub struct ImpostorBaker {
+    pub window: Handle<UiNode>,
+    settings: ImpostorBakerSettings,
+    inspector: Handle<UiNode>,
+    bake: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+}
+
+impl ImpostorBaker {
+    pub fn new(ctx: &mut BuildContext, sender: MessageSender) -> Self {
+        let settings = ImpostorBakerSettings::default();
+        let container = Arc::new(make_property_editors_container(sender));
+
+        let inspector;
+        let bake;
+        let cancel;
+        let window = WindowBuilder::new(
+            WidgetBuilder::new()
+                .with_width(350.0)
+                .with_height(160.0)
+                .with_name("ImpostorBaker"),
+        )
+        .open(false)
+        .with_title(WindowTitle::text("Impostor Baker"))
+        .with_content(
+            GridBuilder::new(
+                WidgetBuilder::new()
+                    .with_child({
+                        inspector = InspectorBuilder::new(
+                            WidgetBuilder::new().with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_context(InspectorContext::from_object(
+                            &settings,
+                            ctx,
+                            container,
+                            None,
+                            MSG_SYNC_FLAG,
+                            0,
+                            true,
+                            Default::default(),
+                            150.0,
+                        ))
+                        .build(ctx);
+                        inspector
+                    })
+                    .with_child(
+                        StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_horizontal_alignment(HorizontalAlignment::Right)
+                                .on_row(1)
+                                .with_margin(Thickness::uniform(1.0))
+                                .with_child({
+                                    bake = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_width(100.0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Bake")
+                                    .build(ctx);
+                                    bake
+                                })
+                                .with_child({
+                                    cancel = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_width(100.0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Cancel")
+                                    .build(ctx);
+                                    cancel
+                                }),
+                        )
+                        .with_orientation(Orientation::Horizontal)
+                        .build(ctx),
+                    ),
+            )
+            .add_row(Row::stretch())
+            .add_row(Row::strict(24.0))
+            .add_column(Column::stretch())
+            .build(ctx),
+        )
+        .build(ctx);
+
+        Self {
+            window,
+            settings,
+            inspector,
+            bake,
+            cancel,
+        }
+    }
+
+    pub fn open(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+    }
+
+    fn bake(
+        &self,
+        editor_selection: &Selection,
+        game_scene: &GameScene,
+        engine: &mut Engine,
+        sender: &MessageSender,
+    ) {
+        let Some(node) = editor_selection
+            .as_graph()
+            .and_then(|s| s.nodes().first().cloned())
+        else {
+            Log::warn("Select a node to bake an impostor for.");
+            return;
+        };
+
+        let GraphicsContext::Initialized(ref mut graphics_context) = engine.graphics_context else {
+            Log::warn("Cannot bake an impostor when the renderer is not initialized!");
+            return;
+        };
+
+        let graph = &engine.scenes[game_scene.scene].graph;
+        let options = ImpostorBakeOptions {
+            frames: self.settings.frames,
+            frame_size: self.settings.frame_size,
+        };
+        let atlas = match impostor::bake(&mut graphics_context.renderer, graph, node, &options) {
+            Ok(atlas) => atlas,
+            Err(e) => {
+                Log::err(format!("Unable to bake an impostor. Reason: {e}"));
+                return;
+            }
+        };
+
+        if graph.try_get_of_type::<Impostor>(node).is_some() {
+            sender.do_command(SetImpostorAtlasCommand {
+                handle: node,
+                material: atlas.make_material(),
+                frames: atlas.frames,
+                size: atlas.size,
+                center: atlas.center,
+            });
+        } else {
+            let transform = graph[node].local_transform().clone();
+            let mut impostor = ImpostorBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("{}Impostor", graph[node].name()))
+                    .with_local_transform(transform.clone()),
+            )
+            .with_distance(self.settings.distance)
+            .build_impostor();
+            atlas.apply(&mut impostor);
+
+            sender.do_command(InsertImpostorCommand {
+                ticket: None,
+                handle: Default::default(),
+                impostor: Some(Node::new(impostor)),
+                node,
+                parent: graph[node].parent(),
+                transform,
+            });
+        }
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_selection: &Selection,
+        game_scene: &GameScene,
+        engine: &mut Engine,
+        sender: &MessageSender,
+    ) {
+        if let Some(InspectorMessage::PropertyChanged(args)) = message.data() {
+            if message.destination() == self.inspector
+                && message.direction() == MessageDirection::FromWidget
+            {
+                PropertyAction::from_field_kind(&args.value).apply(
+                    &args.path(),
+                    &mut self.settings,
+                    &mut |result| {
+                        Log::verify(result);
+                    },
+                );
+            }
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.bake {
+                self.bake(editor_selection, game_scene, engine, sender);
+
+                engine
+                    .user_interfaces
+                    .first_mut()
+                    .send_message(WindowMessage::close(
+                        self.window,
+                        MessageDirection::ToWidget,
+                    ));
+            } else if message.destination() == self.cancel {
+                engine
+                    .user_interfaces
+                    .first_mut()
+                    .send_message(WindowMessage::close(
+                        self.window,
+                        MessageDirection::ToWidget,
+                    ));
+            }
+        }
+    }
+}
@@ -32,8 +32,11 @@ use crate::fyrox::{
 use std::{fs::File, io::Read, path::Path};
 
 pub mod doc;
+pub mod impostor;
+pub mod navmesh_baker;
 pub mod path_fixer;
 pub mod ragdoll;
+pub mod resource_replacer;
 
 /// True if `a` and `b` have the same length, and every element of `a` is equal to some element of `b`
 /// and every element of `b` is equal to some element of `a`.
@@ -0,0 +1,260 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    fyrox::{
+        core::{log::Log, pool::Handle},
+        engine::Engine,
+        fxhash::FxHashSet,
+        graph::SceneGraph,
+        gui::{
+            button::{ButtonBuilder, ButtonMessage},
+            grid::{Column, GridBuilder, Row},
+            inspector::{InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction},
+            message::{MessageDirection, UiMessage},
+            stack_panel::StackPanelBuilder,
+            widget::WidgetBuilder,
+            window::{WindowBuilder, WindowMessage, WindowTitle},
+            BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        },
+        scene::{
+            base::BaseBuilder,
+            mesh::Mesh,
+            navmesh::{NavigationalMesh, NavigationalMeshBuilder},
+        },
+        utils::navmesh_bake::{self, NavmeshBakeSettings},
+    },
+    inspector::editors::make_property_editors_container,
+    message::MessageSender,
+    scene::{
+        commands::{graph::AddNodeCommand, navmesh::SetNavmeshCommand},
+        GameScene, Selection,
+    },
+    MSG_SYNC_FLAG,
+};
+use std::sync::Arc;
+
+/// Bakes a navigational mesh from the meshes of the selected nodes (and their descendants). If
+/// a navigational mesh node is selected as well, its navmesh is replaced with the baked one,
+/// otherwise a new navigational mesh node is added to the scene.
+pub struct NavmeshBaker {
+    pub window: Handle<UiNode>,
+    settings: NavmeshBakeSettings,
+    inspector: Handle<UiNode>,
+    bake: Handle<UiNode>,
+    cancel: Handle<UiNode>,
+}
+
+impl NavmeshBaker {
+    pub fn new(ctx: &mut BuildContext, sender: MessageSender) -> Self {
+        let settings = NavmeshBakeSettings::default();
+        let container = Arc::new(make_property_editors_container(sender));
+
+        let inspector;
+        let bake;
+        let cancel;
+        let window = WindowBuilder::new(
+            WidgetBuilder::new()
+                .with_width(350.0)
+                .with_height(200.0)
+                .with_name("NavmeshBaker"),
+        )
+        .open(false)
+        .with_title(WindowTitle::text("Navmesh Baker"))
+        .with_content(
+            GridBuilder::new(
+                WidgetBuilder::new()
+                    .with_child({
+                        inspector = InspectorBuilder::new(
+                            WidgetBuilder::new().with_margin(Thickness::uniform(1.0)),
+                        )
+                        .with_context(InspectorContext::from_object(
+                            &settings,
+                            ctx,
+                            container,
+                            None,
+                            MSG_SYNC_FLAG,
+                            0,
+                            true,
+                            Default::default(),
+                            150.0,
+                        ))
+                        .build(ctx);
+                        inspector
+                    })
+                    .with_child(
+                        StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_horizontal_alignment(HorizontalAlignment::Right)
+                                .on_row(1)
+                                .with_margin(Thickness::uniform(1.0))
+                                .with_child({
+                                    bake = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_width(100.0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Bake")
+                                    .build(ctx);
+                                    bake
+                                })
+                                .with_child({
+                                    cancel = ButtonBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_width(100.0)
+                                            .with_margin(Thickness::uniform(1.0)),
+                                    )
+                                    .with_text("Cancel")
+                                    .build(ctx);
+                                    cancel
+                                }),
+                        )
+                        .with_orientation(Orientation::Horizontal)
+                        .build(ctx),
+                    ),
+            )
+            .add_row(Row::stretch())
+            .add_row(Row::strict(24.0))
+            .add_column(Column::stretch())
+            .build(ctx),
+        )
+        .build(ctx);
+
+        Self {
+            window,
+            settings,
+            inspector,
+            bake,
+            cancel,
+        }
+    }
+
+    pub fn open(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+    }
+
+    fn bake(
+        &self,
+        editor_selection: &Selection,
+        game_scene: &GameScene,
+        engine: &mut Engine,
+        sender: &MessageSender,
+    ) {
+        let Some(selection) = editor_selection.as_graph() else {
+            Log::warn("Select the nodes to bake a navmesh from.");
+            return;
+        };
+
+        let graph = &engine.scenes[game_scene.scene].graph;
+        let mut navmesh_node = Handle::NONE;
+        let mut meshes = FxHashSet::default();
+        for &node in selection.nodes() {
+            if graph.try_get_of_type::<NavigationalMesh>(node).is_some() {
+                navmesh_node = node;
+                continue;
+            }
+            meshes.extend(
+                graph
+                    .traverse_handle_iter(node)
+                    .filter(|handle| graph.try_get_of_type::<Mesh>(*handle).is_some()),
+            );
+        }
+
+        let mut triangles = Vec::new();
+        for mesh in meshes {
+            if let Some(mesh) = graph.try_get_of_type::<Mesh>(mesh) {
+                navmesh_bake::collect_mesh_triangles(mesh, &mut triangles);
+            }
+        }
+
+        if triangles.is_empty() {
+            Log::warn("Selected nodes have no geometry to bake a navmesh from.");
+            return;
+        }
+
+        let navmesh = navmesh_bake::bake_navmesh(&triangles, &self.settings);
+        Log::info(format!(
+            "Navmesh was baked successfully. Triangles: {}, vertices: {}.",
+            navmesh.triangles().len(),
+            navmesh.vertices().len()
+        ));
+
+        if navmesh_node.is_some() {
+            sender.do_command(SetNavmeshCommand::new(navmesh_node, navmesh));
+        } else {
+            let node = NavigationalMeshBuilder::new(BaseBuilder::new().with_name("Navmesh"))
+                .with_navmesh(navmesh)
+                .build_node();
+            sender.do_command(AddNodeCommand::new(
+                node,
+                game_scene.scene_content_root,
+                true,
+            ));
+        }
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        editor_selection: &Selection,
+        game_scene: &GameScene,
+        engine: &mut Engine,
+        sender: &MessageSender,
+    ) {
+        if let Some(InspectorMessage::PropertyChanged(args)) = message.data() {
+            if message.destination() == self.inspector
+                && message.direction() == MessageDirection::FromWidget
+            {
+                PropertyAction::from_field_kind(&args.value).apply(
+                    &args.path(),
+                    &mut self.settings,
+                    &mut |result| {
+                        Log::verify(result);
+                    },
+                );
+            }
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.bake {
+                self.bake(editor_selection, game_scene, engine, sender);
+
+                engine
+                    .user_interfaces
+                    .first_mut()
+                    .send_message(WindowMessage::close(
+                        self.window,
+                        MessageDirection::ToWidget,
+                    ));
+            } else if message.destination() == self.cancel {
+                engine
+                    .user_interfaces
+                    .first_mut()
+                    .send_message(WindowMessage::close(
+                        self.window,
+                        MessageDirection::ToWidget,
+                    ));
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Special utility that allows you to find all scene nodes that use a resource and to replace the
+//! resource with another one. It is useful to upgrade assets or to replace placeholders.
+
+use crate::fyrox::{
+    asset::{manager::ResourceManager, untyped::UntypedResource},
+    core::{futures::executor::block_on, make_relative_path, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        button::{ButtonBuilder, ButtonMessage},
+        decorator::DecoratorBuilder,
+        file_browser::{FileSelectorBuilder, FileSelectorMessage},
+        formatted_text::WrapMode,
+        grid::{Column, GridBuilder, Row},
+        list_view::{ListViewBuilder, ListViewMessage},
+        message::{MessageDirection, UiMessage},
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowMessage, WindowTitle},
+        BuildContext, HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+    scene::{graph::Graph, node::Node},
+};
+use crate::{
+    message::MessageSender,
+    scene::{
+        commands::{graph::ReplaceResourceCommand, ChangeSelectionCommand},
+        Selection,
+    },
+    world::graph::selection::GraphSelection,
+    Message,
+};
+use std::path::Path;
+
+pub struct ResourceReplacer {
+    pub window: Handle<UiNode>,
+    old_resource_selector: Handle<UiNode>,
+    new_resource_selector: Handle<UiNode>,
+    old_resource_path: Handle<UiNode>,
+    new_resource_path: Handle<UiNode>,
+    select_old_resource: Handle<UiNode>,
+    select_new_resource: Handle<UiNode>,
+    users_list: Handle<UiNode>,
+    status: Handle<UiNode>,
+    replace: Handle<UiNode>,
+    close: Handle<UiNode>,
+    old_resource: Option<UntypedResource>,
+    new_resource: Option<UntypedResource>,
+    users: Vec<Handle<Node>>,
+}
+
+fn make_resource_selector(ctx: &mut BuildContext, title: &str) -> Handle<UiNode> {
+    FileSelectorBuilder::new(
+        WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+            .open(false)
+            .with_title(WindowTitle::text(title)),
+    )
+    .build(ctx)
+}
+
+fn make_resource_field(
+    ctx: &mut BuildContext,
+    row: usize,
+    label: &str,
+    path: &mut Handle<UiNode>,
+    select: &mut Handle<UiNode>,
+) -> Handle<UiNode> {
+    GridBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .with_child(
+                TextBuilder::new(WidgetBuilder::new().on_column(0))
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .with_text(label)
+                    .build(ctx),
+            )
+            .with_child({
+                *path = TextBuilder::new(WidgetBuilder::new().on_column(1))
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .with_text("No resource selected")
+                    .build(ctx);
+                *path
+            })
+            .with_child({
+                *select = ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .with_width(40.0)
+                        .on_column(2)
+                        .with_margin(Thickness::uniform(1.0)),
+                )
+                .with_text("...")
+                .build(ctx);
+                *select
+            }),
+    )
+    .add_column(Column::strict(100.0))
+    .add_column(Column::stretch())
+    .add_column(Column::auto())
+    .add_row(Row::stretch())
+    .build(ctx)
+}
+
+fn load_resource(
+    path: &Path,
+    resource_manager: &ResourceManager,
+) -> Result<UntypedResource, String> {
+    let path = make_relative_path(path).map_err(|e| e.to_string())?;
+    block_on(resource_manager.request_untyped(path)).map_err(|e| format!("{e:?}"))
+}
+
+impl ResourceReplacer {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let old_resource_selector = make_resource_selector(ctx, "Select a resource to find");
+        let new_resource_selector = make_resource_selector(ctx, "Select a replacement resource");
+
+        let mut old_resource_path = Handle::NONE;
+        let mut new_resource_path = Handle::NONE;
+        let mut select_old_resource = Handle::NONE;
+        let mut select_new_resource = Handle::NONE;
+        let users_list;
+        let status;
+        let replace;
+        let close;
+        let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
+            .with_title(WindowTitle::text("Resource Replacer"))
+            .open(false)
+            .with_content(
+                GridBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(make_resource_field(
+                            ctx,
+                            0,
+                            "Find",
+                            &mut old_resource_path,
+                            &mut select_old_resource,
+                        ))
+                        .with_child(make_resource_field(
+                            ctx,
+                            1,
+                            "Replace With",
+                            &mut new_resource_path,
+                            &mut select_new_resource,
+                        ))
+                        .with_child({
+                            users_list =
+                                ListViewBuilder::new(WidgetBuilder::new().on_row(2)).build(ctx);
+                            users_list
+                        })
+                        .with_child({
+                            status = TextBuilder::new(WidgetBuilder::new().on_row(3))
+                                .with_wrap(WrapMode::Word)
+                                .build(ctx);
+                            status
+                        })
+                        .with_child(
+                            StackPanelBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_horizontal_alignment(HorizontalAlignment::Right)
+                                    .on_row(4)
+                                    .with_child({
+                                        replace = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .with_enabled(false)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Replace")
+                                        .build(ctx);
+                                        replace
+                                    })
+                                    .with_child({
+                                        close = ButtonBuilder::new(
+                                            WidgetBuilder::new()
+                                                .with_width(100.0)
+                                                .with_margin(Thickness::uniform(1.0)),
+                                        )
+                                        .with_text("Close")
+                                        .build(ctx);
+                                        close
+                                    }),
+                            )
+                            .with_orientation(Orientation::Horizontal)
+                            .build(ctx),
+                        ),
+                )
+                .add_row(Row::strict(28.0))
+                .add_row(Row::strict(28.0))
+                .add_row(Row::stretch())
+                .add_row(Row::auto())
+                .add_row(Row::strict(28.0))
+                .add_column(Column::stretch())
+                .build(ctx),
+            )
+            .build(ctx);
+
+        Self {
+            window,
+            old_resource_selector,
+            new_resource_selector,
+            old_resource_path,
+            new_resource_path,
+            select_old_resource,
+            select_new_resource,
+            users_list,
+            status,
+            replace,
+            close,
+            old_resource: None,
+            new_resource: None,
+            users: Default::default(),
+        }
+    }
+
+    pub fn open(&self, ui: &UserInterface) {
+        ui.send_message(WindowMessage::open(
+            self.window,
+            MessageDirection::ToWidget,
+            true,
+            true,
+        ));
+    }
+
+    fn set_status(&self, ui: &UserInterface, text: String) {
+        ui.send_message(TextMessage::text(
+            self.status,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
+    fn sync(&mut self, ui: &mut UserInterface, graph: &Graph) {
+        self.users = self
+            .old_resource
+            .as_ref()
+            .map(|resource| graph.find_resource_users(resource))
+            .unwrap_or_default();
+
+        let ctx = &mut ui.build_ctx();
+        let items = self
+            .users
+            .iter()
+            .map(|handle| {
+                DecoratorBuilder::new(BorderBuilder::new(
+                    WidgetBuilder::new().with_height(22.0).with_child(
+                        TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .with_text(format!("{} ({})", graph[*handle].name(), handle))
+                            .build(ctx),
+                    ),
+                ))
+                .build(ctx)
+            })
+            .collect::<Vec<_>>();
+
+        ui.send_message(ListViewMessage::items(
+            self.users_list,
+            MessageDirection::ToWidget,
+            items,
+        ));
+
+        let mut can_replace = false;
+        match (self.old_resource.as_ref(), self.new_resource.as_ref()) {
+            (Some(old_resource), Some(new_resource)) => {
+                if old_resource.type_uuid() != new_resource.type_uuid() {
+                    self.set_status(ui, "Resources must have the same type!".to_string());
+                } else if old_resource == new_resource {
+                    self.set_status(ui, "Resources must be different!".to_string());
+                } else {
+                    can_replace = !self.users.is_empty();
+                    self.set_status(ui, format!("{} node(s) will be changed.", self.users.len()));
+                }
+            }
+            (Some(_), None) => {
+                self.set_status(
+                    ui,
+                    format!("{} node(s) use the resource.", self.users.len()),
+                );
+            }
+            _ => self.set_status(ui, Default::default()),
+        }
+
+        ui.send_message(WidgetMessage::enabled(
+            self.replace,
+            MessageDirection::ToWidget,
+            can_replace,
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        ui: &mut UserInterface,
+        graph: &Graph,
+        resource_manager: &ResourceManager,
+        sender: &MessageSender,
+    ) {
+        if let Some(ButtonMessage::Click) = message.data() {
+            if message.destination() == self.select_old_resource
+                || message.destination() == self.select_new_resource
+            {
+                let selector = if message.destination() == self.select_old_resource {
+                    self.old_resource_selector
+                } else {
+                    self.new_resource_selector
+                };
+                ui.send_message(WindowMessage::open_modal(
+                    selector,
+                    MessageDirection::ToWidget,
+                    true,
+                    true,
+                ));
+            } else if message.destination() == self.replace {
+                if let (Some(old_resource), Some(new_resource)) =
+                    (self.old_resource.clone(), self.new_resource.clone())
+                {
+                    sender.do_command(ReplaceResourceCommand::new(old_resource, new_resource));
+                }
+
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+            } else if message.destination() == self.close {
+                ui.send_message(WindowMessage::close(
+                    self.window,
+                    MessageDirection::ToWidget,
+                ));
+            }
+        } else if let Some(FileSelectorMessage::Commit(path)) = message.data() {
+            if message.destination() == self.old_resource_selector
+                || message.destination() == self.new_resource_selector
+            {
+                let is_old = message.destination() == self.old_resource_selector;
+                let path_text = if is_old {
+                    self.old_resource_path
+                } else {
+                    self.new_resource_path
+                };
+
+                let resource = match load_resource(path, resource_manager) {
+                    Ok(resource) => {
+                        ui.send_message(TextMessage::text(
+                            path_text,
+                            MessageDirection::ToWidget,
+                            resource.kind().to_string(),
+                        ));
+                        Some(resource)
+                    }
+                    Err(err) => {
+                        ui.send_message(TextMessage::text(
+                            path_text,
+                            MessageDirection::ToWidget,
+                            "No resource selected".to_string(),
+                        ));
+                        self.set_status(
+                            ui,
+                            format!("Unable to load {}. Reason: {}", path.display(), err),
+                        );
+                        None
+                    }
+                };
+
+                if is_old {
+                    self.old_resource = resource;
+                } else {
+                    self.new_resource = resource;
+                }
+
+                self.sync(ui, graph);
+            }
+        } else if let Some(ListViewMessage::SelectionChanged(selection)) = message.data() {
+            if message.destination() == self.users_list
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Some(node) = selection.first().and_then(|i| self.users.get(*i)) {
+                    sender.do_command(ChangeSelectionCommand::new(Selection::new(
+                        GraphSelection::single_or_empty(*node),
+                    )));
+                }
+            }
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &Message, ui: &UserInterface) {
+        if let Message::Configure { working_directory } = message {
+            for selector in [self.old_resource_selector, self.new_resource_selector] {
+                ui.send_message(FileSelectorMessage::root(
+                    selector,
+                    MessageDirection::ToWidget,
+                    Some(working_directory.to_owned()),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Version control integration. It shows statuses of files (modified, added, etc.) in the asset
+//! browser and scene tabs and checks out files before saving them, which is needed for systems
+//! with exclusive checkouts (like Perforce), where files are read-only until checked out.
+
+use crate::{
+    fyrox::{
+        core::{color::Color, log::Log, parking_lot::Mutex},
+        fxhash::FxHashMap,
+        gui::brush::Brush,
+    },
+    settings::version_control::{VersionControlSettings, VersionControlSystem},
+};
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+
+/// Status of a file in a version control system.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FileStatus {
+    /// The file is not changed (or there's no version control system).
+    #[default]
+    Unmodified,
+    /// The file is modified.
+    Modified,
+    /// The file is added to the version control system, but not committed yet.
+    Added,
+    /// The file is not tracked by the version control system.
+    Untracked,
+    /// The file is deleted.
+    Deleted,
+    /// The file has unresolved merge conflicts.
+    Conflicted,
+    /// The file is read-only, it must be checked out before saving.
+    Locked,
+}
+
+impl FileStatus {
+    /// Returns a short marker of the status, that is shown next to the file name.
+    pub fn marker(self) -> &'static str {
+        match self {
+            FileStatus::Unmodified => "",
+            FileStatus::Modified => "M",
+            FileStatus::Added => "A",
+            FileStatus::Untracked => "?",
+            FileStatus::Deleted => "D",
+            FileStatus::Conflicted => "!",
+            FileStatus::Locked => "L",
+        }
+    }
+
+    /// Returns a color of the status marker.
+    pub fn brush(self) -> Brush {
+        match self {
+            FileStatus::Unmodified => Brush::Solid(Color::TRANSPARENT),
+            FileStatus::Modified => Brush::Solid(Color::opaque(230, 180, 60)),
+            FileStatus::Added => Brush::Solid(Color::opaque(90, 200, 90)),
+            FileStatus::Untracked => Brush::Solid(Color::opaque(160, 160, 160)),
+            FileStatus::Deleted | FileStatus::Conflicted => {
+                Brush::Solid(Color::opaque(230, 80, 80))
+            }
+            FileStatus::Locked => Brush::Solid(Color::opaque(100, 150, 230)),
+        }
+    }
+
+    fn from_git(code: &[u8]) -> Self {
+        match code {
+            [b'?', b'?'] => FileStatus::Untracked,
+            [b'U', _] | [_, b'U'] | [b'A', b'A'] | [b'D', b'D'] => FileStatus::Conflicted,
+            [b'A', _] => FileStatus::Added,
+            [b'D', _] | [_, b'D'] => FileStatus::Deleted,
+            _ => FileStatus::Modified,
+        }
+    }
+}
+
+/// An error, that may occur when checking out a file.
+#[derive(Debug)]
+pub enum VersionControlError {
+    /// Unable to run the checkout command.
+    Io(std::io::Error),
+    /// The checkout command has failed.
+    CommandFailed {
+        /// The command, that has failed.
+        command: String,
+        /// Error output of the command.
+        output: String,
+    },
+    /// The file is still read-only after the checkout.
+    Locked(PathBuf),
+}
+
+impl From<std::io::Error> for VersionControlError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Display for VersionControlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionControlError::Io(err) => {
+                write!(f, "Unable to run the checkout command. Reason: {err}")
+            }
+            VersionControlError::CommandFailed { command, output } => {
+                write!(f, "Checkout command \"{command}\" has failed: {output}")
+            }
+            VersionControlError::Locked(path) => write!(
+                f,
+                "File {} is locked (read-only). Check it out in your version control system \
+                and try again.",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VersionControlError {}
+
+#[derive(Default)]
+struct State {
+    settings: VersionControlSettings,
+    statuses: FxHashMap<PathBuf, FileStatus>,
+}
+
+/// Shared handle to the version control integration. It caches statuses of the files, the cache
+/// is updated by [`Self::refresh`].
+#[derive(Clone, Default)]
+pub struct VersionControl {
+    state: Arc<Mutex<State>>,
+}
+
+fn run(command: &mut Command) -> Result<String, VersionControlError> {
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(VersionControlError::CommandFailed {
+            command: format!("{command:?}"),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn is_read_only(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|metadata| metadata.permissions().readonly())
+}
+
+fn git_statuses() -> Result<FxHashMap<PathBuf, FileStatus>, VersionControlError> {
+    let root = run(Command::new("git").args(["rev-parse", "--show-toplevel"]))?;
+    let root = canonicalize(Path::new(root.trim()));
+
+    let output = run(Command::new("git").args([
+        "status",
+        "--porcelain=v1",
+        "-z",
+        "--untracked-files=all",
+        "--no-renames",
+    ]))?;
+
+    Ok(parse_git_statuses(&root, &output))
+}
+
+fn parse_git_statuses(root: &Path, output: &str) -> FxHashMap<PathBuf, FileStatus> {
+    // Every entry is `XY path`, separated by zeros. Git always uses `/` as a separator, so the
+    // path is rebuilt from its components to use the separator of the platform.
+    output
+        .split('\0')
+        .filter(|entry| entry.len() > 3)
+        .map(|entry| {
+            let mut path = root.to_path_buf();
+            path.extend(Path::new(&entry[3..]).components());
+            (path, FileStatus::from_git(&entry.as_bytes()[..2]))
+        })
+        .collect()
+}
+
+/// Splits the checkout command into a program and its arguments, replacing `{path}` placeholders
+/// with the given path.
+fn checkout_command_line(command: &str, path: &Path) -> Vec<String> {
+    let path = path.to_string_lossy();
+    command
+        .split_whitespace()
+        .map(|arg| arg.replace("{path}", &path))
+        .collect()
+}
+
+impl VersionControl {
+    /// Applies new settings and refreshes the statuses if the settings have changed.
+    pub fn set_settings(&self, settings: &VersionControlSettings) {
+        let changed = {
+            let mut state = self.state.lock();
+            let changed = &state.settings != settings;
+            state.settings = settings.clone();
+            changed
+        };
+        if changed {
+            self.refresh();
+        }
+    }
+
+    /// Returns `true` if a version control system is selected.
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().settings.system != VersionControlSystem::Disabled
+    }
+
+    /// Updates the cached statuses of the files. Does nothing if there's no version control
+    /// system selected.
+    pub fn refresh(&self) {
+        let system = self.state.lock().settings.system;
+        let statuses = match system {
+            VersionControlSystem::Disabled => Ok(Default::default()),
+            VersionControlSystem::Git => git_statuses(),
+            // Perforce keeps the files, that aren't checked out, read-only, so there's no need
+            // to ask the server about them.
+            VersionControlSystem::Perforce => Ok(Default::default()),
+        };
+        match statuses {
+            Ok(statuses) => self.state.lock().statuses = statuses,
+            Err(err) => Log::warn(format!("Unable to fetch version control statuses. {err}")),
+        }
+    }
+
+    /// Returns the status of the file or the folder. A folder is considered modified if there is
+    /// any changed file in it.
+    pub fn status(&self, path: &Path) -> FileStatus {
+        let state = self.state.lock();
+        if state.settings.system == VersionControlSystem::Disabled {
+            return FileStatus::Unmodified;
+        }
+
+        let path = canonicalize(path);
+        if let Some(status) = state.statuses.get(&path) {
+            return *status;
+        }
+        if path.is_dir() {
+            if state.statuses.keys().any(|file| file.starts_with(&path)) {
+                FileStatus::Modified
+            } else {
+                FileStatus::Unmodified
+            }
+        } else if is_read_only(&path) {
+            FileStatus::Locked
+        } else {
+            FileStatus::Unmodified
+        }
+    }
+
+    /// Checks out the file before saving it: runs the checkout command (if any) and makes sure
+    /// that the file is writable. New files do not need to be checked out.
+    pub fn checkout(&self, path: &Path) -> Result<(), VersionControlError> {
+        let settings = self.state.lock().settings.clone();
+        if settings.system == VersionControlSystem::Disabled || !path.exists() {
+            return Ok(());
+        }
+
+        let command = if settings.checkout_command.is_empty() {
+            match settings.system {
+                VersionControlSystem::Perforce => "p4 edit {path}",
+                _ => "",
+            }
+        } else {
+            settings.checkout_command.as_str()
+        };
+
+        if let Some((program, args)) = checkout_command_line(command, path).split_first() {
+            let output = run(Command::new(program).args(args))?;
+            Log::info(format!("{command} - {}", output.trim()));
+        }
+
+        if is_read_only(path) {
+            Err(VersionControlError::Locked(path.to_path_buf()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_status_from_git() {
+        assert_eq!(FileStatus::from_git(b"??"), FileStatus::Untracked);
+        assert_eq!(FileStatus::from_git(b" M"), FileStatus::Modified);
+        assert_eq!(FileStatus::from_git(b"M "), FileStatus::Modified);
+        assert_eq!(FileStatus::from_git(b"MM"), FileStatus::Modified);
+        assert_eq!(FileStatus::from_git(b"A "), FileStatus::Added);
+        assert_eq!(FileStatus::from_git(b"AM"), FileStatus::Added);
+        assert_eq!(FileStatus::from_git(b"D "), FileStatus::Deleted);
+        assert_eq!(FileStatus::from_git(b" D"), FileStatus::Deleted);
+        assert_eq!(FileStatus::from_git(b"UU"), FileStatus::Conflicted);
+        assert_eq!(FileStatus::from_git(b"AU"), FileStatus::Conflicted);
+        assert_eq!(FileStatus::from_git(b"AA"), FileStatus::Conflicted);
+        assert_eq!(FileStatus::from_git(b"DD"), FileStatus::Conflicted);
+    }
+
+    #[test]
+    fn test_parse_git_statuses() {
+        let root = Path::new("project");
+        let statuses = parse_git_statuses(
+            root,
+            " M data/scene.rgs\0?? data/new file.png\0D  old.txt\0",
+        );
+
+        let expected = [
+            (root.join("data").join("scene.rgs"), FileStatus::Modified),
+            (
+                root.join("data").join("new file.png"),
+                FileStatus::Untracked,
+            ),
+            (root.join("old.txt"), FileStatus::Deleted),
+        ]
+        .into_iter()
+        .collect::<FxHashMap<_, _>>();
+        assert_eq!(statuses, expected);
+
+        assert!(parse_git_statuses(root, "").is_empty());
+    }
+
+    #[test]
+    fn test_checkout_command_line() {
+        let path = Path::new("data").join("scene.rgs");
+        let path_str = path.to_string_lossy().to_string();
+        assert_eq!(
+            checkout_command_line("p4 edit {path}", &path),
+            ["p4", "edit", path_str.as_str()]
+        );
+        let file_arg = format!("--file={path_str}");
+        assert_eq!(
+            checkout_command_line("  tool --file={path} --force ", &path),
+            ["tool", file_arg.as_str(), "--force"]
+        );
+        assert!(checkout_command_line("", &path).is_empty());
+    }
+}
@@ -31,9 +31,13 @@ use fyrox_impl::{
         pool::Handle,
         sstorage::ImmutableString,
     },
-    engine::{executor::Executor, GraphicsContextParams},
-    event::{ElementState, Event, WindowEvent},
+    engine::{
+        executor::Executor,
+        input::{AxisBinding, InputBinding},
+        GraphicsContextParams,
+    },
     event_loop::EventLoop,
+    gui::message::KeyCode,
     material::{Material, MaterialResource, PropertyValue},
     plugin::{Plugin, PluginConstructor, PluginContext},
     resource::texture::Texture,
@@ -47,7 +51,6 @@ use fyrox_impl::{
         Scene,
     },
 };
-use winit::keyboard::{KeyCode, PhysicalKey};
 
 struct SceneLoader {
     scene: Scene,
@@ -137,15 +140,7 @@ impl SceneLoader {
     }
 }
 
-struct InputController {
-    move_forward: bool,
-    move_backward: bool,
-    move_left: bool,
-    move_right: bool,
-}
-
 struct Game {
-    input_controller: InputController,
     scene: Handle<Scene>,
     camera: Handle<Node>,
     spot_light: Handle<Node>,
@@ -153,19 +148,11 @@ struct Game {
 
 impl Plugin for Game {
     fn update(&mut self, context: &mut PluginContext) {
-        let mut offset = Vector3::default();
-        if self.input_controller.move_forward {
-            offset.y += 1.0
-        }
-        if self.input_controller.move_backward {
-            offset.y -= 1.0
-        }
-        if self.input_controller.move_left {
-            offset.x += 1.0
-        }
-        if self.input_controller.move_right {
-            offset.x -= 1.0
-        }
+        let offset = Vector3::new(
+            context.input.axis_value("MoveX"),
+            context.input.axis_value("MoveY"),
+            0.0,
+        );
 
         let graph = &mut context.scenes[self.scene].graph;
 
@@ -180,32 +167,6 @@ impl Plugin for Game {
             * UnitQuaternion::from_euler_angles(0.0, 0.0, 1.0f32.to_radians());
         local_transform.set_rotation(new_rotation);
     }
-
-    fn on_os_event(&mut self, event: &Event<()>, _context: PluginContext) {
-        if let Event::WindowEvent {
-            event: WindowEvent::KeyboardInput { event: input, .. },
-            ..
-        } = event
-        {
-            if let PhysicalKey::Code(code) = input.physical_key {
-                match code {
-                    KeyCode::KeyW => {
-                        self.input_controller.move_forward = input.state == ElementState::Pressed
-                    }
-                    KeyCode::KeyS => {
-                        self.input_controller.move_backward = input.state == ElementState::Pressed
-                    }
-                    KeyCode::KeyA => {
-                        self.input_controller.move_left = input.state == ElementState::Pressed
-                    }
-                    KeyCode::KeyD => {
-                        self.input_controller.move_right = input.state == ElementState::Pressed
-                    }
-                    _ => (),
-                }
-            }
-        }
-    }
 }
 
 struct GameConstructor;
@@ -219,14 +180,23 @@ impl PluginConstructor for GameConstructor {
         // Create test scene.
         let loader = SceneLoader::load_with(context.resource_manager.clone());
 
+        // Bind keys to the movement axes, the engine will track their state.
+        context.input.set_axis(
+            "MoveX",
+            vec![AxisBinding::Buttons {
+                negative: InputBinding::Key(KeyCode::KeyD),
+                positive: InputBinding::Key(KeyCode::KeyA),
+            }],
+        );
+        context.input.set_axis(
+            "MoveY",
+            vec![AxisBinding::Buttons {
+                negative: InputBinding::Key(KeyCode::KeyS),
+                positive: InputBinding::Key(KeyCode::KeyW),
+            }],
+        );
+
         Box::new(Game {
-            // Create input controller - it will hold information about needed actions.
-            input_controller: InputController {
-                move_forward: false,
-                move_backward: false,
-                move_left: false,
-                move_right: false,
-            },
             // Add scene to engine - engine will take ownership over scene and will return
             // you a handle to scene which can be used later on to borrow it and do some
             // actions you need.
@@ -244,6 +214,7 @@ fn main() {
             window_attributes: Default::default(),
             vsync: true,
             msaa_sample_count: None,
+            graphics_server_constructor: Default::default(),
         },
     );
     executor.add_plugin_constructor(GameConstructor);
@@ -30,9 +30,8 @@ use fyrox_impl::{
     },
     dpi::LogicalPosition,
     engine::{executor::Executor, GraphicsContext, GraphicsContextParams},
-    event::{ElementState, Event, WindowEvent},
+    event::{Event, WindowEvent},
     event_loop::EventLoop,
-    keyboard::KeyCode,
     material::{Material, MaterialResource, PropertyValue},
     plugin::{Plugin, PluginConstructor, PluginContext},
     resource::model::{Model, ModelResourceExtension},
@@ -51,7 +50,6 @@ use fyrox_impl::{
     },
     utils::navmesh::NavmeshAgent,
 };
-use winit::keyboard::PhysicalKey;
 
 struct GameScene {
     scene: Scene,
@@ -135,14 +133,7 @@ async fn create_scene(resource_manager: ResourceManager) -> GameScene {
     }
 }
 
-#[derive(Default)]
-struct InputController {
-    rotate_left: bool,
-    rotate_right: bool,
-}
-
 struct Game {
-    input_controller: InputController,
     scene_handle: Handle<Scene>,
     agent: Handle<Node>,
     cursor: Handle<Node>,
@@ -209,27 +200,15 @@ impl Plugin for Game {
     }
 
     fn on_os_event(&mut self, event: &Event<()>, context: PluginContext) {
-        if let Event::WindowEvent { event, .. } = event {
-            match event {
-                WindowEvent::KeyboardInput { event: input, .. } => match input.physical_key {
-                    PhysicalKey::Code(KeyCode::KeyA) => {
-                        self.input_controller.rotate_left = input.state == ElementState::Pressed
-                    }
-                    PhysicalKey::Code(KeyCode::KeyD) => {
-                        self.input_controller.rotate_right = input.state == ElementState::Pressed
-                    }
-                    _ => (),
-                },
-                WindowEvent::CursorMoved { position, .. } => {
-                    if let GraphicsContext::Initialized(ref graphics_context) =
-                        context.graphics_context
-                    {
-                        let p: LogicalPosition<f32> =
-                            position.to_logical(graphics_context.window.scale_factor());
-                        self.mouse_position = Vector2::new(p.x, p.y);
-                    }
-                }
-                _ => (),
+        if let Event::WindowEvent {
+            event: WindowEvent::CursorMoved { position, .. },
+            ..
+        } = event
+        {
+            if let GraphicsContext::Initialized(ref graphics_context) = context.graphics_context {
+                let p: LogicalPosition<f32> =
+                    position.to_logical(graphics_context.window.scale_factor());
+                self.mouse_position = Vector2::new(p.x, p.y);
             }
         }
     }
@@ -262,7 +241,6 @@ impl PluginConstructor for GameConstructor {
         navmesh_agent.set_speed(0.75);
 
         Box::new(Game {
-            input_controller: Default::default(),
             scene_handle,
             agent,
             cursor,
@@ -281,6 +259,7 @@ fn main() {
             window_attributes: Default::default(),
             vsync: true,
             msaa_sample_count: None,
+            graphics_server_constructor: Default::default(),
         },
     );
     executor.add_plugin_constructor(GameConstructor);
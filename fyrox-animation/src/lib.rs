@@ -194,6 +194,8 @@ pub struct Animation<T: EntityId> {
     time_slice: Range<f32>,
     speed: f32,
     looped: bool,
+    #[visit(optional)]
+    ping_pong: bool,
     enabled: bool,
     signals: Vec<AnimationSignal>,
 
@@ -214,6 +216,10 @@ pub struct Animation<T: EntityId> {
     events: VecDeque<AnimationEvent>,
     #[visit(optional)]
     max_event_capacity: usize,
+    // Non-serialized. Current playback direction of ping-pong animations, either `1.0` or `-1.0`.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    ping_pong_direction: f32,
 }
 
 impl<T: EntityId> TypeUuidProvider for Animation<T> {
@@ -288,6 +294,7 @@ impl<T: EntityId> Clone for Animation<T> {
             speed: self.speed,
             time_position: self.time_position,
             looped: self.looped,
+            ping_pong: self.ping_pong,
             enabled: self.enabled,
             pose: Default::default(),
             signals: self.signals.clone(),
@@ -296,6 +303,7 @@ impl<T: EntityId> Clone for Animation<T> {
             time_slice: self.time_slice.clone(),
             root_motion: self.root_motion.clone(),
             max_event_capacity: 32,
+            ping_pong_direction: self.ping_pong_direction,
         }
     }
 }
@@ -369,8 +377,10 @@ impl<T: EntityId> Animation<T> {
     /// wraps the input value on the given time slice).
     /// - If the animation is **not** looping and the new time position is outside of the time slice of the animation,
     /// then the actual time position will be clamped to the time clice of the animation.
+    ///
+    /// Ping-pong animations always clamp the new time position.
     pub fn set_time_position(&mut self, time: f32) -> &mut Self {
-        if self.looped {
+        if self.looped && !self.ping_pong {
             self.time_position = wrapf(time, self.time_slice.start, self.time_slice.end);
         } else {
             self.time_position = time.clamp(self.time_slice.start, self.time_slice.end);
@@ -406,20 +416,172 @@ impl<T: EntityId> Animation<T> {
         self.time_slice.end - self.time_slice.start
     }
 
+    /// Returns duration of a single playback cycle in seconds, taking the playback speed into account.
+    /// Returns [`f32::INFINITY`] if the speed is zero.
+    pub fn duration(&self) -> f32 {
+        if self.speed == 0.0 {
+            f32::INFINITY
+        } else {
+            self.length() / self.speed.abs()
+        }
+    }
+
+    /// Sets new time position using a normalized value in `[0.0; 1.0]` range, where `0.0` is the
+    /// beginning of the time slice and `1.0` - its end. This method does not emit any events.
+    pub fn set_normalized_time_position(&mut self, normalized_time: f32) -> &mut Self {
+        self.set_time_position(
+            self.time_slice.start + normalized_time.clamp(0.0, 1.0) * self.length(),
+        )
+    }
+
+    /// Returns current time position as a normalized value in `[0.0; 1.0]` range. Returns `0.0` for
+    /// animations with zero-length time slice.
+    pub fn normalized_time_position(&self) -> f32 {
+        let length = self.length();
+        if length > 0.0 {
+            (self.time_position - self.time_slice.start) / length
+        } else {
+            0.0
+        }
+    }
+
+    /// Moves the animation to the given time position and immediately updates the output pose, so
+    /// it matches the new position. Unlike [`Self::tick`], this method does not emit any events and
+    /// does not calculate root motion, which makes it suitable for timeline scrubbing.
+    pub fn seek(&mut self, time: f32) -> &mut Self {
+        self.set_time_position(time);
+        self.update_pose();
+        self
+    }
+
+    /// Steps the animation exactly by the given amount of frames (negative values step backwards)
+    /// at the given frame rate and updates the output pose. Time position is snapped to the
+    /// nearest frame first, so repeated steps always stay on the frame grid. This method does not
+    /// emit any events. Does nothing if the frame rate is not a positive finite number.
+    pub fn step_frames(&mut self, frames: i32, frame_rate: f32) -> &mut Self {
+        if !(frame_rate.is_finite() && frame_rate > 0.0) {
+            return self;
+        }
+        let frame_duration = 1.0 / frame_rate;
+        let current_frame = ((self.time_position - self.time_slice.start) / frame_duration).round();
+        self.seek(self.time_slice.start + (current_frame + frames as f32) * frame_duration)
+    }
+
+    /// Evaluates a pose of the animation at the given time position without modifying the animation
+    /// itself. Could be used to preview the animation or to sample a pose for gameplay needs.
+    pub fn evaluate_pose(&self, time: f32) -> AnimationPose<T> {
+        let mut pose = AnimationPose::default();
+        for track in self.tracks.iter() {
+            if track.is_enabled() {
+                if let Some(bound_value) = track.fetch(time) {
+                    pose.add_to_node_pose(track.target(), bound_value);
+                }
+            }
+        }
+        pose
+    }
+
+    /// Returns an iterator over enabled signals, that are located in the given time range (start
+    /// is inclusive, end is exclusive).
+    pub fn signals_in_range(&self, range: Range<f32>) -> impl Iterator<Item = &AnimationSignal> {
+        self.signals
+            .iter()
+            .filter(move |signal| signal.enabled && range.contains(&signal.time))
+    }
+
     /// Performs a single update tick and calculates an output pose. This method is low level, you should not use it
     /// in normal circumstances - the engine will call it for you.
     pub fn tick(&mut self, dt: f32) {
         self.update_pose();
 
+        let was_ended = self.has_ended();
         let current_time_position = self.time_position();
-        let new_time_position = current_time_position + dt * self.speed();
-
-        for signal in self.signals.iter_mut().filter(|s| s.enabled) {
-            if self.speed >= 0.0
-                && (current_time_position < signal.time && new_time_position >= signal.time)
-                || self.speed < 0.0
-                    && (current_time_position > signal.time && new_time_position <= signal.time)
-                    && self.events.len() < self.max_event_capacity
+        let prev_time_position = current_time_position;
+
+        if self.looped && self.ping_pong && self.length() > 0.0 {
+            self.tick_ping_pong(dt * self.speed);
+        } else {
+            let new_time_position = current_time_position + dt * self.speed;
+            self.collect_signals(current_time_position, new_time_position);
+            self.set_time_position(new_time_position);
+        }
+
+        if !was_ended && self.has_ended() && self.events.len() < self.max_event_capacity {
+            self.events.push_back(AnimationEvent {
+                signal_id: AnimationEvent::FINISHED_SIGNAL_ID,
+                name: AnimationEvent::FINISHED_SIGNAL_NAME.to_string(),
+            });
+        }
+
+        self.update_root_motion(prev_time_position);
+    }
+
+    // Moves a ping-pong animation by the given distance. The path of the animation is "unfolded"
+    // into a line, where `[0; length]` is a forward pass and `[length; 2 * length]` is a backward
+    // pass, so the new position and direction are found in closed form, regardless of the amount
+    // of bounces.
+    fn tick_ping_pong(&mut self, distance: f32) {
+        if !distance.is_finite() {
+            return;
+        }
+
+        let start = self.time_slice.start;
+        let length = self.length();
+        let period = 2.0 * length;
+        let fold = |unfolded: f32| {
+            let offset = unfolded.rem_euclid(period);
+            if offset <= length {
+                start + offset
+            } else {
+                start + period - offset
+            }
+        };
+
+        let offset = self.time_position - start;
+        let from = if self.ping_pong_direction > 0.0 {
+            offset
+        } else {
+            period - offset
+        };
+        let to = from + distance;
+
+        // Signals are collected for every segment between two bounces, so none of them is skipped.
+        // Every full period emits each signal of the time slice at least once, so there's no need
+        // to go through more segments than the event queue can hold.
+        let step = distance.signum();
+        let mut index = if distance > 0.0 {
+            (from / length).floor()
+        } else {
+            (from / length).ceil()
+        };
+        let mut segment_start = from;
+        for _ in 0..2 * (self.max_event_capacity + 1) {
+            index += step;
+            let bound = index * length;
+            if (bound - to) * step >= 0.0 {
+                break;
+            }
+            self.collect_signals(fold(segment_start), fold(bound));
+            segment_start = bound;
+        }
+        self.collect_signals(fold(segment_start), fold(to));
+
+        self.ping_pong_direction = if to.rem_euclid(period) <= length {
+            1.0
+        } else {
+            -1.0
+        };
+        self.set_time_position(fold(to));
+    }
+
+    fn collect_signals(&mut self, from: f32, to: f32) {
+        for signal in self.signals.iter().filter(|s| s.enabled) {
+            if self.events.len() >= self.max_event_capacity {
+                break;
+            }
+
+            if from < to && (from < signal.time && to >= signal.time)
+                || from > to && (from > signal.time && to <= signal.time)
             {
                 self.events.push_back(AnimationEvent {
                     signal_id: signal.id,
@@ -427,12 +589,6 @@ impl<T: EntityId> Animation<T> {
                 });
             }
         }
-
-        let prev_time_position = current_time_position;
-
-        self.set_time_position(new_time_position);
-
-        self.update_root_motion(prev_time_position);
     }
 
     fn update_root_motion(&mut self, prev_time_position: f32) {
@@ -668,10 +824,32 @@ impl<T: EntityId> Animation<T> {
         self.looped
     }
 
+    /// Enables or disables ping-pong playback of looping animations. When enabled, the animation
+    /// reverses its playback direction every time it reaches either end of its time slice, instead
+    /// of wrapping to the opposite end. The speed of the animation is left untouched. Has no effect
+    /// on non-looping animations, which are always clamped at the end of the time slice.
+    pub fn set_ping_pong(&mut self, state: bool) -> &mut Self {
+        self.ping_pong = state;
+        self.ping_pong_direction = 1.0;
+        self
+    }
+
+    /// Returns `true` if the animation uses ping-pong playback, `false` - otherwise.
+    pub fn is_ping_pong(&self) -> bool {
+        self.ping_pong
+    }
+
     /// Returns `true` if the animation was played until the end of current time slice of the animation, `false` -
-    /// otherwise. Looping animations will always return `false`.
+    /// otherwise. The end is the end of the time slice for forward playback and the beginning of the time slice
+    /// for reverse playback (negative speed). Looping animations will always return `false`. When an animation
+    /// ends, it emits an event with [`AnimationEvent::FINISHED_SIGNAL_ID`] id.
     pub fn has_ended(&self) -> bool {
-        !self.looped && (self.time_position - self.time_slice.end).abs() <= f32::EPSILON
+        let end = if self.speed < 0.0 {
+            self.time_slice.start
+        } else {
+            self.time_slice.end
+        };
+        !self.looped && (self.time_position - end).abs() <= f32::EPSILON
     }
 
     /// Enables or disables the animation, disabled animations does not updated and their output pose will remain
@@ -810,6 +988,7 @@ impl<T: EntityId> Default for Animation<T> {
             time_position: 0.0,
             enabled: true,
             looped: true,
+            ping_pong: false,
             pose: Default::default(),
             signals: Default::default(),
             root_motion_settings: None,
@@ -817,6 +996,7 @@ impl<T: EntityId> Default for Animation<T> {
             time_slice: Default::default(),
             root_motion: None,
             max_event_capacity: 32,
+            ping_pong_direction: 1.0,
         }
     }
 }
@@ -1007,3 +1187,157 @@ impl<T: EntityId> IndexMut<Handle<Animation<T>>> for AnimationContainer<T> {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        container::{TrackDataContainer, TrackValueKind},
+        core::{
+            math::curve::{Curve, CurveKey, CurveKeyKind},
+            pool::ErasedHandle,
+            uuid::Uuid,
+        },
+        track::Track,
+        value::{TrackValue, ValueBinding},
+        Animation, AnimationEvent, AnimationSignal,
+    };
+
+    fn make_animation() -> Animation<ErasedHandle> {
+        let mut container = TrackDataContainer::new(TrackValueKind::Vector3);
+        container.curves_mut()[0] = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::Linear),
+            CurveKey::new(2.0, 4.0, CurveKeyKind::Linear),
+        ]);
+        let mut track = Track::new(container, ValueBinding::Position);
+        track.set_target(ErasedHandle::new(1, 1));
+
+        let mut animation = Animation::default();
+        animation.add_track(track);
+        animation.set_time_slice(0.0..2.0);
+        animation.set_loop(false);
+        animation.add_signal(AnimationSignal::new(Uuid::new_v4(), "A", 0.5));
+        animation.add_signal(AnimationSignal::new(Uuid::new_v4(), "B", 1.5));
+        animation
+    }
+
+    fn pose_x(animation: &Animation<ErasedHandle>) -> f32 {
+        let pose = &animation.pose().poses()[&ErasedHandle::new(1, 1)];
+        match pose.values.values[0].value {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_scrubbing() {
+        let mut animation = make_animation();
+        animation.set_speed(2.0);
+        assert_eq!(animation.duration(), 1.0);
+
+        animation.set_normalized_time_position(0.25);
+        assert_eq!(animation.time_position(), 0.5);
+        assert_eq!(animation.normalized_time_position(), 0.25);
+
+        animation.seek(1.0);
+        assert_eq!(pose_x(&animation), 2.0);
+        assert!(animation.events_ref().is_empty());
+
+        animation.step_frames(1, 10.0);
+        assert!((animation.time_position() - 1.1).abs() < 1.0e-5);
+        animation.step_frames(-2, 10.0);
+        assert!((animation.time_position() - 0.9).abs() < 1.0e-5);
+        assert!((pose_x(&animation) - 1.8).abs() < 1.0e-5);
+
+        // Invalid frame rates must be ignored.
+        for frame_rate in [0.0, -10.0, f32::NAN, f32::INFINITY] {
+            animation.step_frames(1, frame_rate);
+            assert!((animation.time_position() - 0.9).abs() < 1.0e-5);
+        }
+
+        let pose = animation.evaluate_pose(0.5);
+        match pose.poses()[&ErasedHandle::new(1, 1)].values.values[0].value {
+            TrackValue::Vector3(v) => assert_eq!(v.x, 1.0),
+            _ => unreachable!(),
+        }
+        // Evaluation must not affect the animation.
+        assert!((animation.time_position() - 0.9).abs() < 1.0e-5);
+
+        let names = animation
+            .signals_in_range(0.0..1.0)
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["A"]);
+        assert_eq!(animation.signals_in_range(0.0..2.0).count(), 2);
+    }
+
+    #[test]
+    fn test_reverse_playback_and_finished_event() {
+        let mut animation = make_animation();
+        animation.set_speed(-1.0);
+        animation.set_time_position(2.0);
+
+        animation.tick(1.0);
+        assert_eq!(animation.time_position(), 1.0);
+        assert!(!animation.has_ended());
+
+        animation.tick(1.5);
+        assert_eq!(animation.time_position(), 0.0);
+        assert!(animation.has_ended());
+
+        let events = animation.take_events();
+        assert_eq!(
+            events.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            ["B", "A", AnimationEvent::FINISHED_SIGNAL_NAME]
+        );
+        assert!(events.back().unwrap().is_finished());
+
+        // The event must be emitted only once.
+        animation.tick(1.0);
+        assert!(animation.events_ref().is_empty());
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        fn take_event_names(animation: &mut Animation<ErasedHandle>) -> Vec<String> {
+            animation
+                .take_events()
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        }
+
+        let mut animation = make_animation();
+        animation.set_loop(true).set_ping_pong(true);
+
+        animation.tick(1.0);
+        assert_eq!(animation.time_position(), 1.0);
+        assert_eq!(take_event_names(&mut animation), ["A"]);
+
+        // Bounces off the end, passing "B" on the way forward and then on the way back.
+        animation.tick(1.5);
+        assert_eq!(animation.time_position(), 1.5);
+        assert_eq!(animation.speed(), 1.0);
+        assert_eq!(take_event_names(&mut animation), ["B", "B"]);
+
+        // Bounces off the start, passing "A" in both directions as well.
+        animation.tick(2.0);
+        assert_eq!(animation.time_position(), 0.5);
+        assert_eq!(animation.speed(), 1.0);
+        assert_eq!(take_event_names(&mut animation), ["A", "A"]);
+        assert!(!animation.has_ended());
+
+        // Huge time steps must not hang, the amount of events is limited by the event queue.
+        animation.tick(1.0e9);
+        let time_position = animation.time_position();
+        assert!((0.0..=2.0).contains(&time_position));
+        assert_eq!(
+            animation.take_events().len(),
+            animation.get_max_event_capacity()
+        );
+
+        // Non-finite time steps are ignored.
+        animation.tick(f32::INFINITY);
+        assert_eq!(animation.time_position(), time_position);
+        assert!(animation.events_ref().is_empty());
+    }
+}
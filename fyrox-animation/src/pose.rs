@@ -20,7 +20,9 @@
 
 //! Pose is a set of property values of a node ([`NodePose`]) or a set of nodes ([`AnimationPose`]).
 
-use crate::{value::BoundValue, value::BoundValueCollection, EntityId, RootMotion};
+use crate::{
+    machine::LayerMask, value::BoundValue, value::BoundValueCollection, EntityId, RootMotion,
+};
 use fxhash::FxHashMap;
 use std::collections::hash_map::Entry;
 
@@ -87,6 +89,92 @@ impl<T: EntityId> AnimationPose<T> {
             .blend_with(&other.root_motion.clone().unwrap_or_default(), weight);
     }
 
+    /// Blends current animation pose with another using a weight coefficient, but only for the nodes
+    /// that pass the given mask. Node poses that are excluded by the mask are left unchanged. Missing
+    /// node poses will become a simple copies of a respective node pose. Root motion is not affected.
+    pub fn blend_with_mask(&mut self, other: &AnimationPose<T>, weight: f32, mask: &LayerMask<T>) {
+        self.blend_with_node_weights(other, |node| {
+            if mask.should_animate(node) {
+                weight
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Blends current animation pose with another using a weight coefficient provided by the given
+    /// function for every node. It could be used to smoothly fade an animation over a chain of bones,
+    /// for example - to blend aiming animation only for spine bones with decreasing weight. Node poses
+    /// with zero weight are left unchanged. Root motion is not affected.
+    pub fn blend_with_node_weights<F>(&mut self, other: &AnimationPose<T>, mut weight: F)
+    where
+        F: FnMut(T) -> f32,
+    {
+        for (handle, other_pose) in other.poses.iter() {
+            let weight = weight(*handle);
+            if weight == 0.0 {
+                continue;
+            }
+            if let Some(current_pose) = self.poses.get_mut(handle) {
+                current_pose.blend_with(other_pose, weight);
+            } else {
+                self.add_node_pose(other_pose.clone());
+            }
+        }
+    }
+
+    /// Blends an arbitrary amount of poses using the given weights. Weights are normalized, so their
+    /// sum does not need to be equal to one. Poses with zero (or negative) weight are ignored. Returns
+    /// an empty pose if there's nothing to blend.
+    pub fn blend_many<'a, I>(poses: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a AnimationPose<T>, f32)>,
+    {
+        let mut result = AnimationPose::default();
+        let mut total_weight = 0.0;
+        for (pose, weight) in poses {
+            if weight <= 0.0 {
+                continue;
+            }
+            total_weight += weight;
+            if total_weight == weight {
+                pose.clone_into(&mut result);
+            } else {
+                // Incremental weighted average.
+                result.blend_with(pose, weight / total_weight);
+            }
+        }
+        result
+    }
+
+    /// Applies an additive pose on top of the current pose. Additive pose is a difference between
+    /// the `additive` and the `reference` pose (usually the first frame of the additive animation),
+    /// that is added to the current pose with the given weight. Typical example is an aiming offset,
+    /// that is applied on top of any locomotion animation. Nodes that are excluded by the optional
+    /// mask are left unchanged.
+    pub fn apply_additive(
+        &mut self,
+        additive: &AnimationPose<T>,
+        reference: &AnimationPose<T>,
+        weight: f32,
+        mask: Option<&LayerMask<T>>,
+    ) {
+        for (handle, additive_pose) in additive.poses.iter() {
+            if mask.is_some_and(|mask| !mask.should_animate(*handle)) {
+                continue;
+            }
+            if let (Some(current_pose), Some(reference_pose)) =
+                (self.poses.get_mut(handle), reference.poses.get(handle))
+            {
+                current_pose.values.add_difference(
+                    &additive_pose.values,
+                    &reference_pose.values,
+                    weight,
+                );
+            }
+        }
+    }
+
     fn add_node_pose(&mut self, local_pose: NodePose<T>) {
         self.poses.insert(local_pose.node, local_pose);
     }
@@ -122,3 +210,102 @@ impl<T: EntityId> AnimationPose<T> {
         &mut self.poses
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::ErasedHandle,
+        },
+        machine::LayerMask,
+        value::{BoundValue, TrackValue, ValueBinding},
+        AnimationPose,
+    };
+
+    fn node(index: u32) -> ErasedHandle {
+        ErasedHandle::new(index, 1)
+    }
+
+    fn make_pose(position: f32, angle: f32) -> AnimationPose<ErasedHandle> {
+        let mut pose = AnimationPose::default();
+        for index in 1..=2 {
+            pose.add_to_node_pose(
+                node(index),
+                BoundValue {
+                    binding: ValueBinding::Position,
+                    value: TrackValue::Vector3(Vector3::repeat(position)),
+                },
+            );
+            pose.add_to_node_pose(
+                node(index),
+                BoundValue {
+                    binding: ValueBinding::Rotation,
+                    value: TrackValue::UnitQuaternion(UnitQuaternion::from_euler_angles(
+                        0.0, angle, 0.0,
+                    )),
+                },
+            );
+        }
+        pose
+    }
+
+    fn position(pose: &AnimationPose<ErasedHandle>, index: u32) -> f32 {
+        match pose.poses()[&node(index)].values.values[0].value {
+            TrackValue::Vector3(v) => v.x,
+            _ => unreachable!(),
+        }
+    }
+
+    fn angle(pose: &AnimationPose<ErasedHandle>, index: u32) -> f32 {
+        match pose.poses()[&node(index)].values.values[1].value {
+            TrackValue::UnitQuaternion(q) => q.euler_angles().1,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_blend_many() {
+        let a = make_pose(0.0, 0.0);
+        let b = make_pose(2.0, 0.0);
+        let c = make_pose(4.0, 0.0);
+
+        let result = AnimationPose::blend_many([(&a, 1.0), (&b, 2.0), (&c, 1.0)]);
+        assert!((position(&result, 1) - 2.0).abs() < 1.0e-5);
+
+        let result = AnimationPose::blend_many([(&a, 0.0), (&c, 3.0)]);
+        assert!((position(&result, 1) - 4.0).abs() < 1.0e-5);
+
+        assert!(AnimationPose::<ErasedHandle>::blend_many([])
+            .poses()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_blend_with_mask() {
+        let mut a = make_pose(0.0, 0.0);
+        let b = make_pose(2.0, 0.0);
+
+        a.blend_with_mask(&b, 0.5, &LayerMask::from(vec![node(2)]));
+        assert_eq!(position(&a, 1), 1.0);
+        assert_eq!(position(&a, 2), 0.0);
+    }
+
+    #[test]
+    fn test_apply_additive() {
+        let mut base = make_pose(1.0, 0.5);
+        let reference = make_pose(0.0, 0.0);
+        let additive = make_pose(2.0, 0.25);
+
+        base.apply_additive(
+            &additive,
+            &reference,
+            1.0,
+            Some(&LayerMask::from(vec![node(2)])),
+        );
+        assert!((position(&base, 1) - 3.0).abs() < 1.0e-5);
+        assert!((angle(&base, 1) - 0.75).abs() < 1.0e-5);
+        assert_eq!(position(&base, 2), 1.0);
+        assert!((angle(&base, 2) - 0.5).abs() < 1.0e-5);
+    }
+}
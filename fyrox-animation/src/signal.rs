@@ -20,7 +20,11 @@
 
 //! Signal is a named marker on specific time position on the animation timeline. See [`AnimationSignal`] docs for more info.
 
-use crate::core::{reflect::prelude::*, uuid::Uuid, visitor::prelude::*};
+use crate::core::{
+    reflect::prelude::*,
+    uuid::{uuid, Uuid},
+    visitor::prelude::*,
+};
 use fyrox_core::NameProvider;
 
 /// An event happened in an animation.
@@ -33,6 +37,19 @@ pub struct AnimationEvent {
     pub name: String,
 }
 
+impl AnimationEvent {
+    /// A special id of an event, that is emitted by a non-looping animation when it reaches its end.
+    pub const FINISHED_SIGNAL_ID: Uuid = uuid!("8bb8eb5a-4a91-497c-9674-90ed8d5f0efd");
+
+    /// Name of an event, that is emitted by a non-looping animation when it reaches its end.
+    pub const FINISHED_SIGNAL_NAME: &'static str = "Finished";
+
+    /// Returns `true` if the event was emitted by an animation when it has finished playing.
+    pub fn is_finished(&self) -> bool {
+        self.signal_id == Self::FINISHED_SIGNAL_ID
+    }
+}
+
 /// Signal is a named marker on specific time position on the animation timeline. Signal will emit an event if the animation playback
 /// time passes signal's position from left-to-right (or vice versa depending on playback direction). Signals are usually used to
 /// attach some specific actions to a position in time. For example, you can have a walking animation and you want to emit sounds
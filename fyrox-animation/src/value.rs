@@ -174,6 +174,25 @@ impl TrackValue {
         }
     }
 
+    /// Adds a difference between `additive` and `reference` values to the current value, scaled by the
+    /// given weight. Rotations are combined using quaternion multiplication, any other values are
+    /// combined using simple addition. This is a base operation of additive animation blending, when
+    /// some animation (for example - aiming offset) is applied on top of any other animation.
+    /// Additive blending is possible only if the types are the same.
+    pub fn add_difference(&mut self, additive: &Self, reference: &Self, weight: f32) {
+        match (self, additive, reference) {
+            (Self::Real(a), Self::Real(b), Self::Real(r)) => *a += (*b - *r) * weight,
+            (Self::Vector2(a), Self::Vector2(b), Self::Vector2(r)) => *a += (b - r) * weight,
+            (Self::Vector3(a), Self::Vector3(b), Self::Vector3(r)) => *a += (b - r) * weight,
+            (Self::Vector4(a), Self::Vector4(b), Self::Vector4(r)) => *a += (b - r) * weight,
+            (Self::UnitQuaternion(a), Self::UnitQuaternion(b), Self::UnitQuaternion(r)) => {
+                let delta = r.inverse() * b;
+                *a *= nlerp(UnitQuaternion::identity(), &delta, weight);
+            }
+            _ => (),
+        }
+    }
+
     /// Tries to perform a numeric type casting of the current value to some other and returns a boxed value, that can
     /// be used to set the value using reflection.
     pub fn numeric_type_cast(&self, value_type: ValueType) -> Option<Box<dyn Reflect>> {
@@ -327,6 +346,31 @@ impl BoundValue {
         self.value.blend_with(&other.value, weight);
     }
 
+    /// Adds a difference between `additive` and `reference` values to the current value, scaled by
+    /// the given weight. Scale is combined multiplicatively, any other values are combined as described
+    /// in [`TrackValue::add_difference`].
+    pub fn add_difference(&mut self, additive: &Self, reference: &Self, weight: f32) {
+        assert_eq!(self.binding, additive.binding);
+        assert_eq!(self.binding, reference.binding);
+        if let (
+            ValueBinding::Scale,
+            TrackValue::Vector3(a),
+            TrackValue::Vector3(b),
+            TrackValue::Vector3(r),
+        ) = (
+            &self.binding,
+            &mut self.value,
+            &additive.value,
+            &reference.value,
+        ) {
+            let ratio = b.zip_map(r, |b, r| if r != 0.0 { b / r } else { 1.0 });
+            a.component_mul_assign(&Vector3::repeat(1.0).lerp(&ratio, weight));
+        } else {
+            self.value
+                .add_difference(&additive.value, &reference.value, weight);
+        }
+    }
+
     /// Sets a property of the given object.
     pub fn apply_to_object(
         &self,
@@ -375,6 +419,20 @@ impl BoundValueCollection {
             }
         }
     }
+
+    /// Adds a difference between each value of the `additive` collection and a respective (by binding)
+    /// value of the `reference` collection to a respective value of the current collection. See
+    /// [`BoundValue::add_difference`] docs for more info.
+    pub fn add_difference(&mut self, additive: &Self, reference: &Self, weight: f32) {
+        for value in self.values.iter_mut() {
+            if let (Some(additive_value), Some(reference_value)) = (
+                additive.values.iter().find(|v| v.binding == value.binding),
+                reference.values.iter().find(|v| v.binding == value.binding),
+            ) {
+                value.add_difference(additive_value, reference_value, weight);
+            }
+        }
+    }
 }
 
 /// Interpolates from `a` to `b` using nlerp, including an additional check to ensure
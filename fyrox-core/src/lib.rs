@@ -60,12 +60,14 @@ pub mod math;
 pub mod net;
 pub mod numeric_range;
 pub mod pool;
+pub mod profiler;
 pub mod quadtree;
 pub mod rectpack;
 pub mod reflect;
 pub mod sparse;
 pub mod sstorage;
 pub mod task;
+pub mod telemetry;
 pub mod type_traits;
 pub mod variable;
 pub mod visitor;
@@ -22,6 +22,7 @@
 
 use crate::lazy_static::lazy_static;
 use crate::parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::fmt::{Debug, Display};
 
 use crate::instant::Instant;
@@ -43,6 +44,7 @@ extern "C" {
 }
 
 /// A message that could be sent by the logger to all listeners.
+#[derive(Clone, Debug)]
 pub struct LogMessage {
     /// Kind of the message: information, warning or error.
     pub kind: MessageKind,
@@ -59,12 +61,14 @@ lazy_static! {
         file: std::fs::File::create("fyrox.log").unwrap(),
         verbosity: MessageKind::Information,
         listeners: Default::default(),
-        time_origin: Instant::now()
+        time_origin: Instant::now(),
+        history: Default::default(),
+        history_capacity: 0,
     });
 }
 
 /// A kind of message.
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Ord, Hash, Debug)]
 #[repr(u32)]
 pub enum MessageKind {
     /// Some useful information.
@@ -76,7 +80,8 @@ pub enum MessageKind {
 }
 
 impl MessageKind {
-    fn as_str(self) -> &'static str {
+    /// Returns a prefix that is used by the logger for messages of this kind.
+    pub fn as_str(self) -> &'static str {
         match self {
             MessageKind::Information => "[INFO]: ",
             MessageKind::Warning => "[WARNING]: ",
@@ -92,6 +97,8 @@ pub struct Log {
     verbosity: MessageKind,
     listeners: Vec<Sender<LogMessage>>,
     time_origin: Instant,
+    history: VecDeque<LogMessage>,
+    history_capacity: usize,
 }
 
 impl Log {
@@ -112,6 +119,17 @@ impl Log {
                     .is_ok()
             });
 
+            if self.history_capacity > 0 {
+                if self.history.len() >= self.history_capacity {
+                    self.history.pop_front();
+                }
+                self.history.push_back(LogMessage {
+                    kind,
+                    content: msg.clone(),
+                    time: Instant::now() - self.time_origin,
+                });
+            }
+
             msg.insert_str(0, kind.as_str());
 
             #[cfg(target_arch = "wasm32")]
@@ -191,6 +209,26 @@ impl Log {
         LOG.lock().listeners.push(listener)
     }
 
+    /// Sets the maximum amount of recent messages the logger keeps in memory. Zero (default) disables
+    /// the history. The history is used by crash reporters to attach the last log lines to a report.
+    pub fn set_history_capacity(capacity: usize) {
+        let mut log = LOG.lock();
+        log.history_capacity = capacity;
+        while log.history.len() > capacity {
+            log.history.pop_front();
+        }
+    }
+
+    /// Returns a copy of recent messages (oldest first), see [`Self::set_history_capacity`]. The method
+    /// does not block for long: if the logger is locked for more than a hundred milliseconds (for example,
+    /// when it is called from a panic hook while the panicking thread holds the logger), an empty list is
+    /// returned.
+    pub fn recent_messages() -> Vec<LogMessage> {
+        LOG.try_lock_for(Duration::from_millis(100))
+            .map(|log| log.history.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Allows you to verify that the result of operation is Ok, or print the error in the log.
     ///
     /// # Use cases
@@ -0,0 +1,262 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Built-in frame profiler. It measures CPU time of scopes marked with [`crate::profile_scope`]
+//! macro and collects timings of render passes measured on GPU (they're reported by the renderer).
+//! The profiler is disabled by default and costs almost nothing in this case, enable it using
+//! [`Profiler::set_enabled`].
+//!
+//! Only scopes executed on the thread, that drives the frames (see [`Profiler::begin_frame`]), are
+//! recorded. The engine starts a new frame after each rendered frame, so a frame profile contains
+//! every update and the rendering of a frame.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use fyrox_core::{profile_scope, profiler::Profiler};
+//!
+//! fn update_ai() {
+//!     profile_scope!("AI");
+//!
+//!     // ...
+//! }
+//!
+//! Profiler::set_enabled(true);
+//! Profiler::begin_frame();
+//! update_ai();
+//! Profiler::end_frame();
+//! assert_eq!(Profiler::last_frame().samples[0].name, "AI");
+//! ```
+
+use crate::{instant::Instant, parking_lot::Mutex};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread::ThreadId,
+    time::Duration,
+};
+
+/// Timing of a profiled scope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileSample {
+    /// Name of the scope.
+    pub name: &'static str,
+    /// Nesting level of the scope, zero means that the scope is a top-level one.
+    pub depth: usize,
+    /// Start time of the scope relative to the start of the frame.
+    pub start: Duration,
+    /// Duration of the scope.
+    pub duration: Duration,
+}
+
+/// Statistics of a render pass.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderPassProfile {
+    /// Name of the render pass.
+    pub name: &'static str,
+    /// Time spent by GPU to execute the pass. It is `None` if the GPU does not support timer
+    /// queries.
+    pub gpu_time: Option<Duration>,
+    /// Amount of draw calls made by the pass.
+    pub draw_calls: usize,
+    /// Amount of triangles rendered by the pass.
+    pub triangles: usize,
+}
+
+/// Profile of a single frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameProfile {
+    /// Total duration of the frame.
+    pub duration: Duration,
+    /// Timings of the scopes sorted by their start time.
+    pub samples: Vec<ProfileSample>,
+    /// Statistics of render passes. GPU timings are available only a few frames later, so they
+    /// could be slightly outdated.
+    pub render_passes: Vec<RenderPassProfile>,
+}
+
+impl FrameProfile {
+    /// Returns total time of the scopes with the given name.
+    pub fn total_time(&self, name: &str) -> Duration {
+        self.samples
+            .iter()
+            .filter(|sample| sample.name == name)
+            .map(|sample| sample.duration)
+            .sum()
+    }
+}
+
+#[derive(Default)]
+struct ProfilerState {
+    thread: Option<ThreadId>,
+    frame_start: Option<Instant>,
+    depth: usize,
+    samples: Vec<ProfileSample>,
+    render_passes: Vec<RenderPassProfile>,
+    last_frame: FrameProfile,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref PROFILER: Mutex<ProfilerState> = Mutex::new(Default::default());
+}
+
+/// A scope, that records its lifetime when dropped. See [`Profiler::scope`].
+#[must_use = "the scope records its duration when dropped"]
+pub struct ProfilerScope {
+    name: &'static str,
+    start: Option<(Instant, usize)>,
+}
+
+impl Drop for ProfilerScope {
+    fn drop(&mut self) {
+        let Some((start, depth)) = self.start else {
+            return;
+        };
+
+        let mut state = PROFILER.lock();
+        state.depth = depth;
+        if let Some(frame_start) = state.frame_start {
+            let sample = ProfileSample {
+                name: self.name,
+                depth,
+                start: if start > frame_start {
+                    start - frame_start
+                } else {
+                    Duration::default()
+                },
+                duration: start.elapsed(),
+            };
+            state.samples.push(sample);
+        }
+    }
+}
+
+/// See module docs.
+pub struct Profiler;
+
+impl Profiler {
+    /// Enables or disables the profiler.
+    pub fn set_enabled(enabled: bool) {
+        ENABLED.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            let mut state = PROFILER.lock();
+            state.frame_start = None;
+            state.samples.clear();
+        }
+    }
+
+    /// Returns `true` if the profiler is enabled.
+    pub fn is_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Starts a new scope, that records its duration when dropped. Prefer [`crate::profile_scope`]
+    /// macro instead of this method.
+    pub fn scope(name: &'static str) -> ProfilerScope {
+        let mut scope = ProfilerScope { name, start: None };
+        if Self::is_enabled() {
+            let mut state = PROFILER.lock();
+            if state.frame_start.is_some() && state.thread == Some(std::thread::current().id()) {
+                scope.start = Some((Instant::now(), state.depth));
+                state.depth += 1;
+            }
+        }
+        scope
+    }
+
+    /// Starts a new frame on the current thread. The engine calls this method automatically.
+    pub fn begin_frame() {
+        if !Self::is_enabled() {
+            return;
+        }
+
+        let mut state = PROFILER.lock();
+        state.thread = Some(std::thread::current().id());
+        state.frame_start = Some(Instant::now());
+        state.depth = 0;
+        state.samples.clear();
+    }
+
+    /// Finishes the current frame, its profile will be available via [`Self::last_frame`]. The
+    /// engine calls this method automatically.
+    pub fn end_frame() {
+        let mut state = PROFILER.lock();
+        if let Some(frame_start) = state.frame_start.take() {
+            let mut samples = std::mem::take(&mut state.samples);
+            samples.sort_by_key(|sample| sample.start);
+            state.last_frame = FrameProfile {
+                duration: frame_start.elapsed(),
+                samples,
+                render_passes: state.render_passes.clone(),
+            };
+        }
+    }
+
+    /// Sets statistics of render passes of the latest frame, that has all the GPU timings
+    /// available. The renderer calls this method automatically.
+    pub fn set_render_passes(render_passes: Vec<RenderPassProfile>) {
+        PROFILER.lock().render_passes = render_passes;
+    }
+
+    /// Returns the profile of the last finished frame.
+    pub fn last_frame() -> FrameProfile {
+        PROFILER.lock().last_frame.clone()
+    }
+}
+
+/// Measures CPU time of the rest of the current scope (until the end of the block), when the
+/// profiler is enabled. See [`crate::profiler`] module docs for more info.
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profiler_scope = $crate::profiler::Profiler::scope($name);
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::profiler::Profiler;
+
+    #[test]
+    fn test_nested_scopes() {
+        Profiler::set_enabled(true);
+        Profiler::begin_frame();
+        {
+            profile_scope!("Outer");
+            {
+                profile_scope!("Inner");
+            }
+            profile_scope!("Inner");
+        }
+        Profiler::end_frame();
+        Profiler::set_enabled(false);
+
+        let frame = Profiler::last_frame();
+        let names = frame
+            .samples
+            .iter()
+            .map(|sample| (sample.name, sample.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("Outer", 0), ("Inner", 1), ("Inner", 1)]);
+        assert!(frame.total_time("Outer") >= frame.total_time("Inner"));
+        assert!(frame.duration >= frame.total_time("Outer"));
+    }
+}
@@ -0,0 +1,539 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Lightweight telemetry, that allows you to collect performance and gameplay metrics (counters,
+//! timers and custom events) from playtests. Recorded events are buffered in memory and sent to
+//! sinks (files, HTTP endpoints, etc.) only when [`Telemetry::flush`] is called. The engine calls
+//! it once per frame. On platforms that support threads, the sinks are written on a separate
+//! thread, so slow I/O never eats the frame budget.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use fyrox_core::telemetry::{FileSink, Telemetry, TelemetryValue};
+//!
+//! fn on_enemy_killed(weapon: &str) {
+//!     Telemetry::counter("enemies_killed", 1);
+//!     Telemetry::event(
+//!         "enemy_killed",
+//!         vec![("weapon".to_string(), TelemetryValue::from(weapon))],
+//!     );
+//! }
+//!
+//! fn update_ai() {
+//!     // The timer will record its duration when dropped.
+//!     let _timer = Telemetry::begin_timer("ai_update");
+//!
+//!     // ...
+//! }
+//! ```
+
+use crate::{instant::Instant, parking_lot::Mutex};
+use fxhash::FxHashMap;
+use std::{
+    fmt::{Display, Formatter, Write as _},
+    fs::File,
+    io::{self, BufWriter, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+/// A value of a field of a custom telemetry event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TelemetryValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Signed integer value.
+    Integer(i64),
+    /// Floating point value.
+    Float(f64),
+    /// String value.
+    String(String),
+}
+
+impl From<bool> for TelemetryValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for TelemetryValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for TelemetryValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for TelemetryValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for TelemetryValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+fn write_json_string(f: &mut impl std::fmt::Write, string: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+impl Display for TelemetryValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TelemetryValue::Bool(v) => write!(f, "{v}"),
+            TelemetryValue::Integer(v) => write!(f, "{v}"),
+            // JSON does not support NaN and infinities.
+            TelemetryValue::Float(v) if v.is_finite() => write!(f, "{v}"),
+            TelemetryValue::Float(_) => write!(f, "null"),
+            TelemetryValue::String(v) => write_json_string(f, v),
+        }
+    }
+}
+
+/// Kind of a telemetry event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TelemetryEventKind {
+    /// Total value of a counter at the moment of flush. Counters are aggregated in memory, and only
+    /// their values are sent to sinks on every flush.
+    Counter {
+        /// Name of the counter.
+        name: String,
+        /// Total value of the counter.
+        value: i64,
+    },
+    /// Duration of some operation.
+    Timer {
+        /// Name of the timer.
+        name: String,
+        /// Measured duration.
+        duration: Duration,
+    },
+    /// A custom event with arbitrary set of fields.
+    Custom {
+        /// Name of the event.
+        name: String,
+        /// A set of `(name, value)` pairs.
+        fields: Vec<(String, TelemetryValue)>,
+    },
+}
+
+/// A telemetry event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TelemetryEvent {
+    /// Time point at which the event was recorded. It is relative to the moment when the telemetry
+    /// was initialized.
+    pub time: Duration,
+    /// Kind of the event.
+    pub kind: TelemetryEventKind,
+}
+
+impl Display for TelemetryEvent {
+    /// Formats the event as a single-line JSON object.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{\"time\":{}", self.time.as_secs_f64())?;
+        match self.kind {
+            TelemetryEventKind::Counter { ref name, value } => {
+                f.write_str(",\"type\":\"counter\",\"name\":")?;
+                write_json_string(f, name)?;
+                write!(f, ",\"value\":{value}")?;
+            }
+            TelemetryEventKind::Timer { ref name, duration } => {
+                f.write_str(",\"type\":\"timer\",\"name\":")?;
+                write_json_string(f, name)?;
+                write!(f, ",\"duration\":{}", duration.as_secs_f64())?;
+            }
+            TelemetryEventKind::Custom {
+                ref name,
+                ref fields,
+            } => {
+                f.write_str(",\"type\":\"event\",\"name\":")?;
+                write_json_string(f, name)?;
+                f.write_str(",\"fields\":{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_json_string(f, name)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')?;
+            }
+        }
+        f.write_char('}')
+    }
+}
+
+/// Telemetry sink is a destination of telemetry events.
+pub trait TelemetrySink: Send {
+    /// Writes a batch of events. It is called on a telemetry thread (if the platform supports threads),
+    /// so it could perform blocking I/O.
+    fn write(&mut self, events: &[TelemetryEvent]) -> io::Result<()>;
+}
+
+/// A sink that writes events to a file, one JSON object per line.
+pub struct FileSink {
+    writer: BufWriter<File>,
+}
+
+impl FileSink {
+    /// Creates a new file sink. Existing file will be overwritten.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl TelemetrySink for FileSink {
+    fn write(&mut self, events: &[TelemetryEvent]) -> io::Result<()> {
+        for event in events {
+            writeln!(self.writer, "{event}")?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// A sink that sends events to an HTTP endpoint. Every batch of events is sent as a JSON array
+/// in the body of a `POST` request. Only plain HTTP is supported, use a local proxy if you need
+/// TLS.
+pub struct HttpSink {
+    host: String,
+    path: String,
+}
+
+impl HttpSink {
+    /// Creates a new HTTP sink. `host` must be in `address:port` form, `path` is a path of the
+    /// endpoint on the server (for example, `/telemetry`).
+    pub fn new<H: Into<String>, P: Into<String>>(host: H, path: P) -> Self {
+        Self {
+            host: host.into(),
+            path: path.into(),
+        }
+    }
+}
+
+impl TelemetrySink for HttpSink {
+    fn write(&mut self, events: &[TelemetryEvent]) -> io::Result<()> {
+        let mut body = String::from("[");
+        for (i, event) in events.iter().enumerate() {
+            if i > 0 {
+                body.push(',');
+            }
+            let _ = write!(body, "{event}");
+        }
+        body.push(']');
+
+        let address = self.host.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "Unable to resolve the host!")
+        })?;
+        let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(5))?;
+        stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\n\
+            Host: {}\r\n\
+            Content-Type: application/json\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n\r\n\
+            {}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+/// Maximum amount of events that will be buffered between two flushes by default.
+pub const DEFAULT_TELEMETRY_CAPACITY: usize = 4096;
+
+struct TelemetryState {
+    enabled: bool,
+    time_origin: Instant,
+    counters: FxHashMap<String, i64>,
+    dirty_counters: bool,
+    events: Vec<TelemetryEvent>,
+    capacity: usize,
+    dropped: usize,
+    sinks: Arc<Mutex<Vec<Box<dyn TelemetrySink>>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<std::sync::mpsc::Sender<Vec<TelemetryEvent>>>,
+}
+
+lazy_static! {
+    static ref TELEMETRY: Mutex<TelemetryState> = Mutex::new(TelemetryState {
+        enabled: false,
+        time_origin: Instant::now(),
+        counters: Default::default(),
+        dirty_counters: false,
+        events: Default::default(),
+        capacity: DEFAULT_TELEMETRY_CAPACITY,
+        dropped: 0,
+        sinks: Default::default(),
+        #[cfg(not(target_arch = "wasm32"))]
+        worker: None,
+    });
+}
+
+impl TelemetryState {
+    fn push(&mut self, kind: TelemetryEventKind) {
+        if !self.enabled {
+            return;
+        }
+        if self.events.len() >= self.capacity {
+            self.dropped += 1;
+            return;
+        }
+        let time = Instant::now() - self.time_origin;
+        self.events.push(TelemetryEvent { time, kind });
+    }
+}
+
+/// A timer that records its lifetime as a telemetry event when dropped. See [`Telemetry::begin_timer`].
+#[must_use = "the timer records its duration when dropped"]
+pub struct TelemetryTimer {
+    name: Option<String>,
+    start: Instant,
+}
+
+impl TelemetryTimer {
+    /// Stops the timer and records its duration.
+    pub fn stop(self) {
+        drop(self)
+    }
+
+    /// Stops the timer without recording anything.
+    pub fn cancel(mut self) {
+        self.name = None;
+    }
+}
+
+impl Drop for TelemetryTimer {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            Telemetry::timer(name, Instant::now() - self.start);
+        }
+    }
+}
+
+/// See module docs.
+pub struct Telemetry;
+
+impl Telemetry {
+    /// Adds a new sink and enables telemetry. Events are never collected until at least one sink
+    /// is added, so telemetry costs nothing if it is unused.
+    pub fn add_sink<S: TelemetrySink + 'static>(sink: S) {
+        let mut state = TELEMETRY.lock();
+        state.enabled = true;
+        state.sinks.lock().push(Box::new(sink));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if state.worker.is_none() {
+            let (sender, receiver) = std::sync::mpsc::channel::<Vec<TelemetryEvent>>();
+            let sinks = state.sinks.clone();
+            std::thread::Builder::new()
+                .name("Telemetry".to_string())
+                .spawn(move || {
+                    for events in receiver {
+                        for sink in sinks.lock().iter_mut() {
+                            if let Err(err) = sink.write(&events) {
+                                crate::log::Log::warn(format!(
+                                    "Unable to write telemetry events. Reason: {err:?}"
+                                ));
+                            }
+                        }
+                    }
+                })
+                .expect("Unable to create telemetry thread!");
+            state.worker = Some(sender);
+        }
+    }
+
+    /// Returns `true` if telemetry is collecting events (at least one sink was added).
+    pub fn is_enabled() -> bool {
+        TELEMETRY.lock().enabled
+    }
+
+    /// Sets the maximum amount of events that can be buffered between two flushes. Events that do
+    /// not fit into the buffer are dropped, see [`Self::dropped_events`].
+    pub fn set_capacity(capacity: usize) {
+        TELEMETRY.lock().capacity = capacity;
+    }
+
+    /// Returns total amount of events that were dropped because of buffer overflow.
+    pub fn dropped_events() -> usize {
+        TELEMETRY.lock().dropped
+    }
+
+    /// Adds the given value to a counter with the given name.
+    pub fn counter<S: AsRef<str>>(name: S, value: i64) {
+        let mut state = TELEMETRY.lock();
+        if !state.enabled {
+            return;
+        }
+        state.dirty_counters = true;
+        if let Some(counter) = state.counters.get_mut(name.as_ref()) {
+            *counter += value;
+        } else {
+            state.counters.insert(name.as_ref().to_string(), value);
+        }
+    }
+
+    /// Returns current value of a counter with the given name.
+    pub fn counter_value<S: AsRef<str>>(name: S) -> i64 {
+        TELEMETRY
+            .lock()
+            .counters
+            .get(name.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Records a duration of some operation.
+    pub fn timer<S: Into<String>>(name: S, duration: Duration) {
+        TELEMETRY.lock().push(TelemetryEventKind::Timer {
+            name: name.into(),
+            duration,
+        })
+    }
+
+    /// Starts a new timer, that will record its duration when dropped.
+    pub fn begin_timer<S: Into<String>>(name: S) -> TelemetryTimer {
+        TelemetryTimer {
+            name: Some(name.into()),
+            start: Instant::now(),
+        }
+    }
+
+    /// Records a custom event.
+    pub fn event<S: Into<String>>(name: S, fields: Vec<(String, TelemetryValue)>) {
+        TELEMETRY.lock().push(TelemetryEventKind::Custom {
+            name: name.into(),
+            fields,
+        })
+    }
+
+    /// Sends all buffered events (and changed counters) to sinks. This method is called by the
+    /// engine once per frame, you don't need to call it manually unless you're using telemetry
+    /// without the engine.
+    pub fn flush() {
+        let mut state = TELEMETRY.lock();
+        if !state.enabled {
+            return;
+        }
+
+        if state.dirty_counters {
+            state.dirty_counters = false;
+            let time = Instant::now() - state.time_origin;
+            let counters = state
+                .counters
+                .iter()
+                .map(|(name, value)| TelemetryEvent {
+                    time,
+                    kind: TelemetryEventKind::Counter {
+                        name: name.clone(),
+                        value: *value,
+                    },
+                })
+                .collect::<Vec<_>>();
+            state.events.extend(counters);
+        }
+
+        if state.events.is_empty() {
+            return;
+        }
+
+        let events = std::mem::take(&mut state.events);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(worker) = state.worker.as_ref() {
+                let _ = worker.send(events);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            for sink in state.sinks.lock().iter_mut() {
+                let _ = sink.write(&events);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TelemetryEvent, TelemetryEventKind, TelemetryValue};
+    use std::time::Duration;
+
+    #[test]
+    fn test_event_json() {
+        let event = TelemetryEvent {
+            time: Duration::from_millis(1500),
+            kind: TelemetryEventKind::Custom {
+                name: "level \"1\"".to_string(),
+                fields: vec![
+                    ("kills".to_string(), TelemetryValue::from(3i64)),
+                    ("weapon".to_string(), TelemetryValue::from("gun")),
+                    ("accuracy".to_string(), TelemetryValue::from(f64::NAN)),
+                ],
+            },
+        };
+        assert_eq!(
+            event.to_string(),
+            r#"{"time":1.5,"type":"event","name":"level \"1\"","fields":{"kills":3,"weapon":"gun","accuracy":null}}"#
+        );
+
+        let counter = TelemetryEvent {
+            time: Duration::from_secs(2),
+            kind: TelemetryEventKind::Counter {
+                name: "deaths".to_string(),
+                value: 7,
+            },
+        };
+        assert_eq!(
+            counter.to_string(),
+            r#"{"time":2,"type":"counter","name":"deaths","value":7}"#
+        );
+    }
+}
@@ -25,7 +25,7 @@ use crate::{
 };
 use glow::HasContext;
 use std::any::Any;
-use std::{cell::Cell, rc::Weak};
+use std::{cell::Cell, rc::Weak, time::Duration};
 
 #[derive(Debug)]
 pub struct GlQuery {
@@ -96,6 +96,9 @@ impl Query for GlQuery {
                     QueryKind::AnySamplesPassed => {
                         Some(QueryResult::AnySamplesPassed(query_result > 0))
                     }
+                    QueryKind::TimeElapsed => Some(QueryResult::TimeElapsed(Duration::from_nanos(
+                        query_result as u64,
+                    ))),
                 }
             } else {
                 None
@@ -1156,6 +1156,13 @@ impl GraphicsServer for GlGraphicsServer {
                     .gl
                     .get_parameter_i32(glow::UNIFORM_BUFFER_OFFSET_ALIGNMENT)
                     as usize,
+                // Timer queries are part of OpenGL 3.3 core, but they're only available as an
+                // extension on OpenGL ES and WebGL.
+                timer_queries: self.gl_kind() == GlKind::OpenGL || {
+                    let extensions = self.gl.supported_extensions();
+                    extensions.contains("GL_EXT_disjoint_timer_query")
+                        || extensions.contains("EXT_disjoint_timer_query_webgl2")
+                },
             }
         }
     }
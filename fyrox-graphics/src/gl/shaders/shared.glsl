@@ -139,6 +139,51 @@ vec3 S_PBR_CalculateLight(TPBRContext ctx) {
     return (kD * ctx.albedo / PI + specular) * ctx.lightColor * NdotL;
 }
 
+// A bit of the decal mask in G-Buffer, that indicates that a fragment must be lit using toon shading.
+const uint S_TOON_SHADING_BIT = 128u;
+
+// Returns true if a fragment with the given decal mask value must be lit using toon shading.
+bool S_IsToonShaded(uint decalMask) {
+    return (decalMask & S_TOON_SHADING_BIT) != 0u;
+}
+
+// A bit of the decal mask in G-Buffer, that indicates that a fragment must not be affected by fog.
+const uint S_NO_FOG_BIT = 64u;
+
+// Returns true if a fragment with the given decal mask value must not be affected by fog.
+bool S_IsFogDisabled(uint decalMask) {
+    return (decalMask & S_NO_FOG_BIT) != 0u;
+}
+
+// Calculates stylized (toon or cel) lighting using provided light and fragment parameters. Consists of
+// ramped (two bands) diffuse part, quantized specular highlight and rim light. Just like the PBR version
+// it does not apply any distance or direction attenuation.
+vec3 S_Toon_CalculateLight(TPBRContext ctx) {
+    vec3 N = ctx.fragmentNormal;
+    vec3 L = ctx.fragmentToLight;
+    vec3 V = ctx.viewVector;
+    vec3 H = normalize(V + L);
+
+    float NdotL = dot(N, L);
+
+    // Ramped diffuse - fully lit, half lit and unlit bands with thin smooth transitions.
+    float diffuse = 0.5 * smoothstep(0.0, 0.02, NdotL) + 0.5 * smoothstep(0.45, 0.47, NdotL);
+
+    // Quantized specular - a highlight with hard edges, which size depends on roughness.
+    float shininess = mix(128.0, 8.0, ctx.roughness);
+    float specularIntensity = pow(max(dot(N, H), 0.0), shininess) * step(0.0, NdotL);
+    float specular = smoothstep(0.5, 0.52, specularIntensity) * (1.0 - ctx.roughness);
+
+    // Rim light - thin bright outline on the lit side of an object.
+    float rimIntensity = (1.0 - max(dot(N, V), 0.0)) * pow(max(NdotL, 0.0), 0.25);
+    float rim = smoothstep(0.7, 0.72, rimIntensity);
+
+    vec3 F0 = mix(vec3(0.04), ctx.albedo, ctx.metallic);
+    vec3 albedo = ctx.albedo * (1.0 - ctx.metallic);
+
+    return (albedo * diffuse + F0 * specular + ctx.albedo * 0.5 * rim) * ctx.lightColor / PI;
+}
+
 // Returns scatter amount for given parameters.
 // https://cseweb.ucsd.edu/~ravir/papers/singlescat/scattering.pdf
 // https://blog.mmacklin.com/2010/05/29/in-scattering-demo/
@@ -179,56 +224,203 @@ bool S_RaySphereIntersection(vec3 origin, vec3 dir, vec3 center, float radius, o
     return S_SolveQuadraticEq(a, b, c, minT, maxT);
 }
 
+// Henyey-Greenstein phase function, approximates the angular distribution of the light scattered by
+// a participating media. Anisotropy defines the distribution: positive values - forward scattering,
+// negative - backward scattering, zero - isotropic scattering.
+float S_HenyeyGreensteinPhase(float cosTheta, float anisotropy)
+{
+    float g2 = anisotropy * anisotropy;
+    return (1.0 - g2) / (4.0 * PI * pow(max(1.0 + g2 - 2.0 * anisotropy * cosTheta, 0.0001), 1.5));
+}
+
+// http://www.iryoku.com/next-generation-post-processing-in-call-of-duty-advanced-warfare
+// Returns per-pixel noise in [0; 1] range, that is used to jitter sampling positions.
+float S_InterleavedGradientNoise(vec2 fragCoord)
+{
+    return fract(52.9829189 * fract(dot(fragCoord, vec2(0.06711056, 0.00583715))));
+}
+
+// Shadow filtering modes, must be in sync with `ShadowFilter` enum.
+const int S_SHADOW_FILTER_HARD = 0;
+const int S_SHADOW_FILTER_PCF = 1;
+const int S_SHADOW_FILTER_PCSS = 2;
+
+const int Internal_ShadowSampleCount = 16;
+
+const vec2 Internal_ShadowPoissonDisk[Internal_ShadowSampleCount] = vec2[Internal_ShadowSampleCount](
+vec2(-0.94201624, -0.39906216), vec2(0.94558609, -0.76890725),
+vec2(-0.09418410, -0.92938870), vec2(0.34495938, 0.29387760),
+vec2(-0.91588581, 0.45771432), vec2(-0.81544232, -0.87912464),
+vec2(-0.38277543, 0.27676845), vec2(0.97484398, 0.75648379),
+vec2(0.44323325, -0.97511554), vec2(0.53742981, -0.47373420),
+vec2(-0.26496911, -0.41893023), vec2(0.79197514, 0.19090188),
+vec2(-0.24188840, 0.99706507), vec2(-0.81409955, 0.91437590),
+vec2(0.19984126, 0.78641367), vec2(0.14383161, -0.14100790)
+);
+
+// Maximum radius of a shadow filtering kernel in texels.
+const float Internal_MaxShadowKernelRadius = 32.0;
+
+// Calculates radius (in texels) of the PCF kernel for the given filtering mode. For PCSS the radius
+// depends on the average distance to the occluders, found in the blocker search step.
+float Internal_ShadowKernelRadius(int shadowFilter, float shadowSoftness, float receiverDistance, float blockerDistance)
+{
+    float radius = shadowSoftness;
+    if (shadowFilter == S_SHADOW_FILTER_PCSS)
+    {
+        radius = shadowSoftness * (receiverDistance - blockerDistance) / max(blockerDistance, 0.0001);
+    }
+    return clamp(radius, 0.5, Internal_MaxShadowKernelRadius);
+}
+
 // Calculates point shadow factor where 1.0 - no shadow, 0.0 - fully in shadow.
 // Why value is inversed? To be able to directly multiply color to shadow factor.
+// Shadow softness is a radius of PCF kernel in texels or a size of the light source for PCSS.
 float S_PointShadow(
     bool shadowsEnabled,
-    bool softShadows,
+    int shadowFilter,
+    float shadowSoftness,
     float fragmentDistance,
     float shadowBias,
     vec3 toLight,
+    float shadowMapInvSize,
 in samplerCube shadowMap)
 {
-    if (shadowsEnabled)
+    if (!shadowsEnabled)
     {
-        float biasedFragmentDistance = fragmentDistance - shadowBias;
+        return 1.0; // No shadow
+    }
 
-        if (softShadows)
+    float biasedFragmentDistance = fragmentDistance - shadowBias;
+    vec3 direction = -toLight;
+
+    if (shadowFilter == S_SHADOW_FILTER_HARD)
+    {
+        float shadowDistanceToLight = texture(shadowMap, direction).r;
+        return biasedFragmentDistance > shadowDistanceToLight ? 0.0 : 1.0;
+    }
+
+    // Samples are distributed on a plane, that is perpendicular to the fetch direction. Size of
+    // a texel of a cube map face at unit distance is 2 / size.
+    vec3 up = abs(direction.y) < 0.99 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(up, direction));
+    vec3 bitangent = cross(direction, tangent);
+    float texelSize = 2.0 * shadowMapInvSize;
+
+    float blockerDistance = 0.0;
+    if (shadowFilter == S_SHADOW_FILTER_PCSS)
+    {
+        float searchRadius = clamp(shadowSoftness * 4.0, 1.0, Internal_MaxShadowKernelRadius) * texelSize;
+        float blockerCount = 0.0;
+        for (int i = 0; i < Internal_ShadowSampleCount; ++i)
         {
-            const int samples = 20;
+            vec2 offset = Internal_ShadowPoissonDisk[i] * searchRadius;
+            float shadowDistanceToLight = texture(shadowMap, direction + tangent * offset.x + bitangent * offset.y).r;
+            if (biasedFragmentDistance > shadowDistanceToLight)
+            {
+                blockerDistance += shadowDistanceToLight;
+                blockerCount += 1.0;
+            }
+        }
+
+        if (blockerCount == 0.0)
+        {
+            return 1.0;
+        }
 
-            const vec3 directions[samples] = vec3[samples](
-            vec3(1, 1, 1), vec3(1, -1, 1), vec3(-1, -1, 1), vec3(-1, 1, 1),
-            vec3(1, 1, -1), vec3(1, -1, -1), vec3(-1, -1, -1), vec3(-1, 1, -1),
-            vec3(1, 1, 0), vec3(1, -1, 0), vec3(-1, -1, 0), vec3(-1, 1, 0),
-            vec3(1, 0, 1), vec3(-1, 0, 1), vec3(1, 0, -1), vec3(-1, 0, -1),
-            vec3(0, 1, 1), vec3(0, -1, 1), vec3(0, -1, -1), vec3(0, 1, -1)
-            );
+        blockerDistance /= blockerCount;
+    }
 
-            const float diskRadius = 0.0025;
+    float kernelRadius = texelSize * Internal_ShadowKernelRadius(shadowFilter, shadowSoftness, biasedFragmentDistance, blockerDistance);
 
-            float accumulator = 0.0;
+    float accumulator = 0.0;
+    for (int i = 0; i < Internal_ShadowSampleCount; ++i)
+    {
+        vec2 offset = Internal_ShadowPoissonDisk[i] * kernelRadius;
+        float shadowDistanceToLight = texture(shadowMap, direction + tangent * offset.x + bitangent * offset.y).r;
+        if (biasedFragmentDistance > shadowDistanceToLight)
+        {
+            accumulator += 1.0;
+        }
+    }
+
+    return clamp(1.0 - accumulator / float(Internal_ShadowSampleCount), 0.0, 1.0);
+}
 
-            for (int i = 0; i < samples; ++i)
+// Converts depth from a shadow map of a spot light to the linear distance.
+float Internal_LinearizeShadowDepth(float depth, float zNear, float zFar)
+{
+    return zNear * zFar / (zFar - depth * (zFar - zNear));
+}
+
+// Calculates spot light shadow factor using the given filtering mode (see S_SHADOW_FILTER_XXX),
+// where 1.0 - no shadow, 0.0 - fully in shadow. Shadow softness is a radius of PCF kernel in texels
+// or a size of the light source for PCSS. zNear and zFar are the clipping planes of the light
+// projection matrix.
+float S_FilteredSpotShadowFactor(
+    bool shadowsEnabled,
+    int shadowFilter,
+    float shadowSoftness,
+    float shadowBias,
+    vec3 fragmentPosition,
+    mat4 lightViewProjMatrix,
+    float shadowMapInvSize,
+    float zNear,
+    float zFar,
+in sampler2D spotShadowTexture)
+{
+    if (!shadowsEnabled)
+    {
+        return 1.0; // No shadow
+    }
+
+    vec3 lightSpacePosition = S_Project(fragmentPosition, lightViewProjMatrix);
+    float biasedLightSpaceFragmentDepth = lightSpacePosition.z - shadowBias;
+
+    if (shadowFilter == S_SHADOW_FILTER_HARD)
+    {
+        return biasedLightSpaceFragmentDepth > texture(spotShadowTexture, lightSpacePosition.xy).r ? 0.0 : 1.0;
+    }
+
+    float receiverDistance = Internal_LinearizeShadowDepth(biasedLightSpaceFragmentDepth, zNear, zFar);
+
+    float blockerDistance = 0.0;
+    if (shadowFilter == S_SHADOW_FILTER_PCSS)
+    {
+        float searchRadius = clamp(shadowSoftness * 4.0, 1.0, Internal_MaxShadowKernelRadius) * shadowMapInvSize;
+        float blockerCount = 0.0;
+        for (int i = 0; i < Internal_ShadowSampleCount; ++i)
+        {
+            vec2 fetchTexCoord = lightSpacePosition.xy + Internal_ShadowPoissonDisk[i] * searchRadius;
+            float depth = texture(spotShadowTexture, fetchTexCoord).r;
+            if (biasedLightSpaceFragmentDepth > depth)
             {
-                vec3 fetchDirection = -toLight + directions[i] * diskRadius;
-                float shadowDistanceToLight = texture(shadowMap, fetchDirection).r;
-                if (biasedFragmentDistance > shadowDistanceToLight)
-                {
-                    accumulator += 1.0;
-                }
+                blockerDistance += Internal_LinearizeShadowDepth(depth, zNear, zFar);
+                blockerCount += 1.0;
             }
+        }
 
-            return clamp(1.0 - accumulator / float(samples), 0.0, 1.0);
+        if (blockerCount == 0.0)
+        {
+            return 1.0;
         }
-        else
+
+        blockerDistance /= blockerCount;
+    }
+
+    float kernelRadius = shadowMapInvSize * Internal_ShadowKernelRadius(shadowFilter, shadowSoftness, receiverDistance, blockerDistance);
+
+    float accumulator = 0.0;
+    for (int i = 0; i < Internal_ShadowSampleCount; ++i)
+    {
+        vec2 fetchTexCoord = lightSpacePosition.xy + Internal_ShadowPoissonDisk[i] * kernelRadius;
+        if (biasedLightSpaceFragmentDepth > texture(spotShadowTexture, fetchTexCoord).r)
         {
-            float shadowDistanceToLight = texture(shadowMap, -toLight).r;
-            return biasedFragmentDistance > shadowDistanceToLight ? 0.0 : 1.0;
+            accumulator += 1.0;
         }
-    } else {
-        return 1.0; // No shadow
     }
+
+    return clamp(1.0 - accumulator / float(Internal_ShadowSampleCount), 0.0, 1.0);
 }
 
 // Calculates spot light shadow factor where 1.0 - no shadow, 0.0 - fully in shadow.
@@ -279,10 +471,13 @@ float Internal_FetchHeight(in sampler2D heightTexture, vec2 texCoords, float cen
     return clamp(texture(heightTexture, texCoords).r - center, 0.0, 1.0);
 }
 
-vec2 S_ComputeParallaxTextureCoordinates(in sampler2D heightTexture, vec3 eyeVec, vec2 texCoords, float center, float scale) {
-    const float minLayers = 8.0;
-    const float maxLayers = 15.0;
-    const int maxIterations = 15;
+// Ray marches the height map in tangent space to find displaced texture coordinates. Amount of steps
+// varies between the given min and max depending on the view angle (grazing angles need more steps),
+// it is clamped to 128 steps at most.
+vec2 S_ComputeParallaxOcclusionTextureCoordinates(in sampler2D heightTexture, vec3 eyeVec, vec2 texCoords, float center, float scale, float minSteps, float maxSteps) {
+    const int maxIterations = 128;
+    float minLayers = clamp(minSteps, 1.0, float(maxIterations));
+    float maxLayers = clamp(maxSteps, minLayers, float(maxIterations));
 
     float t = max(0.0, abs(dot(vec3(0.0, 0.0, 1.0), eyeVec)));
     float numLayers = mix(maxLayers, minLayers, t);
@@ -294,7 +489,8 @@ vec2 S_ComputeParallaxTextureCoordinates(in sampler2D heightTexture, vec3 eyeVec
     vec2 currentTexCoords = texCoords;
     float currentDepthMapValue = Internal_FetchHeight(heightTexture, currentTexCoords, center);
 
-    for (int i = 0; i < maxIterations; i++) {
+    int iterations = int(ceil(numLayers));
+    for (int i = 0; i < maxIterations && i < iterations; i++) {
         if (currentLayerDepth < currentDepthMapValue) {
             currentTexCoords -= deltaTexCoords;
             currentDepthMapValue = Internal_FetchHeight(heightTexture, currentTexCoords, center);
@@ -313,6 +509,16 @@ vec2 S_ComputeParallaxTextureCoordinates(in sampler2D heightTexture, vec3 eyeVec
     return prev * weight + currentTexCoords * (1.0 - weight);
 }
 
+// Returns true if the given texture coordinates went outside of the [0; scale] range after parallax
+// displacement, such fragments should be discarded to make silhouettes of parallax-mapped surfaces.
+bool S_IsParallaxSilhouetteClipped(vec2 texCoords, vec2 scale) {
+    return any(lessThan(texCoords, vec2(0.0))) || any(greaterThan(texCoords, scale));
+}
+
+vec2 S_ComputeParallaxTextureCoordinates(in sampler2D heightTexture, vec3 eyeVec, vec2 texCoords, float center, float scale) {
+    return S_ComputeParallaxOcclusionTextureCoordinates(heightTexture, eyeVec, texCoords, center, scale, 8.0, 15.0);
+}
+
 vec4 S_LinearToSRGB(vec4 color) {
     vec3 a = 12.92 * color.rgb;
     vec3 b = 1.055 * pow(color.rgb, vec3(1.0 / 2.4)) - 0.055;
@@ -372,4 +578,70 @@ vec2 S_RotateVec2(vec2 v, float angle)
     float s = sin(angle);
     mat2 m = mat2(c, -s, s, c);
     return m * v;
-}
\ No newline at end of file
+}
+
+// Calculates intensity of the glowing edge of the dissolve effect. Fragments with the noise value below
+// the threshold are expected to be discarded. Returns zero if the threshold is zero (no dissolve).
+float S_DissolveEdge(float noise, float threshold, float edgeWidth)
+{
+    if (threshold <= 0.0) {
+        return 0.0;
+    }
+    return 1.0 - smoothstep(threshold, threshold + max(edgeWidth, 0.0001), noise);
+}
+
+// Extrudes a world-space vertex position along its normal to build an inverted hull for outlines. In
+// screen-space mode the width is scaled by the distance to the camera, so the apparent thickness of the
+// outline stays the same regardless of the distance.
+vec3 S_OutlineExtrude(vec3 worldPosition, vec3 worldNormal, vec3 cameraPosition, float width, bool screenSpace)
+{
+    if (screenSpace) {
+        width *= distance(cameraPosition, worldPosition);
+    }
+    return worldPosition + worldNormal * width;
+}
+
+// Calculates fade factor of detail textures, it is 1.0 near the camera and smoothly goes to 0.0 between
+// the given distances.
+float S_DetailFade(float distanceToCamera, float fadeStart, float fadeEnd)
+{
+    return 1.0 - smoothstep(fadeStart, max(fadeEnd, fadeStart + 0.0001), distanceToCamera);
+}
+
+// Blends a tangent-space detail normal with a tangent-space base normal using "whiteout" blending.
+vec3 S_BlendDetailNormal(vec3 baseNormal, vec3 detailNormal, float factor)
+{
+    detailNormal = mix(vec3(0.0, 0.0, 1.0), detailNormal, factor);
+    return normalize(vec3(baseNormal.xy + detailNormal.xy, baseNormal.z * detailNormal.z));
+}
+
+// Calculates blending weights of three planar projections (YZ, XZ, XY) for triplanar mapping. Higher
+// sharpness makes transitions between the projections narrower.
+vec3 S_TriplanarWeights(vec3 worldNormal, float sharpness)
+{
+    vec3 weights = pow(abs(worldNormal), vec3(max(sharpness, 1.0)));
+    return weights / max(weights.x + weights.y + weights.z, 0.0001);
+}
+
+// Samples a texture using triplanar projection of a world-space position.
+vec4 S_TriplanarSample(in sampler2D s, vec3 worldPosition, vec3 weights, vec2 scale)
+{
+    return texture(s, worldPosition.zy * scale) * weights.x
+        + texture(s, worldPosition.xz * scale) * weights.y
+        + texture(s, worldPosition.xy * scale) * weights.z;
+}
+
+// Samples a tangent-space normal map using triplanar projection and returns world-space normal. Normals
+// of every projection are combined with the surface normal using "whiteout" blending.
+vec3 S_TriplanarNormal(in sampler2D s, vec3 worldPosition, vec3 worldNormal, vec3 weights, vec2 scale)
+{
+    vec3 nx = texture(s, worldPosition.zy * scale).xyz * 2.0 - 1.0;
+    vec3 ny = texture(s, worldPosition.xz * scale).xyz * 2.0 - 1.0;
+    vec3 nz = texture(s, worldPosition.xy * scale).xyz * 2.0 - 1.0;
+
+    nx = vec3(nx.xy + worldNormal.zy, abs(nx.z) * worldNormal.x);
+    ny = vec3(ny.xy + worldNormal.xz, abs(ny.z) * worldNormal.y);
+    nz = vec3(nz.xy + worldNormal.xy, abs(nz.z) * worldNormal.z);
+
+    return normalize(nx.zyx * weights.x + ny.xzy * weights.y + nz.xyz * weights.z);
+}
@@ -748,6 +748,16 @@ impl GpuTexture for GlTexture {
         Ok(())
     }
 
+    fn generate_mipmap(&mut self) {
+        let temp_binding = self.make_temp_binding();
+        unsafe {
+            temp_binding
+                .server
+                .gl
+                .generate_mipmap(self.kind.gl_texture_target());
+        }
+    }
+
     fn get_image(&self, level: usize) -> Vec<u8> {
         let temp_binding = self.make_temp_binding();
         unsafe {
@@ -404,6 +404,7 @@ pub trait GpuTexture: Any {
         mip_count: usize,
         data: Option<&[u8]>,
     ) -> Result<(), FrameworkError>;
+    fn generate_mipmap(&mut self);
     fn get_image(&self, level: usize) -> Vec<u8>;
     fn read_pixels(&self) -> Vec<u8>;
     fn kind(&self) -> GpuTextureKind;
@@ -1,16 +1,20 @@
-use std::{any::Any, fmt::Debug};
+use std::{any::Any, fmt::Debug, time::Duration};
 
 #[repr(u32)]
 #[derive(Copy, Clone, Debug)]
 pub enum QueryKind {
     SamplesPassed = glow::SAMPLES_PASSED,
     AnySamplesPassed = glow::ANY_SAMPLES_PASSED,
+    /// Measures the time that the GPU spent executing the commands between the beginning and the
+    /// end of the query. Check [`crate::server::ServerCapabilities::timer_queries`] before using it.
+    TimeElapsed = glow::TIME_ELAPSED,
 }
 
 #[derive(Debug)]
 pub enum QueryResult {
     SamplesPassed(u32),
     AnySamplesPassed(bool),
+    TimeElapsed(Duration),
 }
 
 pub trait Query: Any + Debug {
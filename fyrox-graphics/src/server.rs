@@ -26,6 +26,7 @@ use crate::{
     error::FrameworkError,
     framebuffer::{Attachment, FrameBuffer},
     geometry_buffer::{GeometryBuffer, GeometryBufferDescriptor},
+    gl::server::GlGraphicsServer,
     gpu_program::{GpuProgram, ShaderResourceDefinition},
     gpu_texture::{GpuTexture, GpuTextureDescriptor},
     query::Query,
@@ -39,10 +40,16 @@ use std::{
     fmt::{Display, Formatter},
     rc::{Rc, Weak},
 };
+use winit::{
+    event_loop::EventLoopWindowTarget,
+    window::{Window, WindowBuilder},
+};
 
 pub struct ServerCapabilities {
     pub max_uniform_block_size: usize,
     pub uniform_buffer_offset_alignment: usize,
+    /// Whether the server supports [`crate::query::QueryKind::TimeElapsed`] queries or not.
+    pub timer_queries: bool,
 }
 
 impl Display for ServerCapabilities {
@@ -57,12 +64,62 @@ impl Display for ServerCapabilities {
             "\tUniform Block Offset Alignment: {}",
             self.uniform_buffer_offset_alignment
         )?;
+        writeln!(f, "\tTimer Queries: {}", self.timer_queries)?;
         Ok(())
     }
 }
 
 pub type SharedGraphicsServer = Rc<dyn GraphicsServer>;
 
+/// A function, that creates a window and a graphics server, that renders into the window. It accepts
+/// vertical synchronization flag, amount of samples for MSAA, window target and window builder.
+pub type GraphicsServerConstructorCallback =
+    dyn Fn(
+        bool,
+        Option<u8>,
+        &EventLoopWindowTarget<()>,
+        WindowBuilder,
+    ) -> Result<(Window, SharedGraphicsServer), FrameworkError>;
+
+/// Graphics server constructor defines which graphics backend will be used by the renderer. By
+/// default, it creates [`GlGraphicsServer`] (OpenGL on desktop, OpenGL ES on mobile and WebGL on
+/// WebAssembly). Custom graphics servers could be used to add support for other graphics APIs.
+#[derive(Clone)]
+pub struct GraphicsServerConstructor(Rc<GraphicsServerConstructorCallback>);
+
+impl Default for GraphicsServerConstructor {
+    fn default() -> Self {
+        Self::new(GlGraphicsServer::new)
+    }
+}
+
+impl GraphicsServerConstructor {
+    /// Creates a new constructor from the given function.
+    pub fn new<F>(func: F) -> Self
+    where
+        F: Fn(
+                bool,
+                Option<u8>,
+                &EventLoopWindowTarget<()>,
+                WindowBuilder,
+            ) -> Result<(Window, SharedGraphicsServer), FrameworkError>
+            + 'static,
+    {
+        Self(Rc::new(func))
+    }
+
+    /// Creates a window and a graphics server for it.
+    pub fn construct(
+        &self,
+        vsync: bool,
+        msaa_sample_count: Option<u8>,
+        window_target: &EventLoopWindowTarget<()>,
+        window_builder: WindowBuilder,
+    ) -> Result<(Window, SharedGraphicsServer), FrameworkError> {
+        (self.0)(vsync, msaa_sample_count, window_target, window_builder)
+    }
+}
+
 pub trait GraphicsServer: Any {
     fn create_buffer(
         &self,
@@ -93,6 +93,18 @@ pub struct RenderPassStatistics {
     pub draw_calls: usize,
     /// Amount of triangles per frame.
     pub triangles_rendered: usize,
+    /// Amount of draw calls that rendered geometry of multiple primitives (sprites, particle
+    /// systems, etc.) merged by dynamic batching. Batches with a single primitive aren't counted.
+    pub dynamic_batches: usize,
+    /// Amount of primitives (a sprite, a whole particle system, etc.) that were merged into the
+    /// batches counted in [`Self::dynamic_batches`]. The ratio between this value and
+    /// [`Self::dynamic_batches`] shows how effective the batching is.
+    pub batched_primitives: usize,
+    /// Amount of draw calls that rendered multiple mesh instances using hardware instancing.
+    pub instanced_draw_calls: usize,
+    /// Amount of mesh instances that were rendered by instanced draw calls. The ratio between this
+    /// value and [`Self::instanced_draw_calls`] shows how effective the instancing is.
+    pub instanced_meshes: usize,
 }
 
 impl Display for RenderPassStatistics {
@@ -100,8 +112,17 @@ impl Display for RenderPassStatistics {
         write!(
             f,
             "Draw Calls: {}\n\
-            Triangles Rendered: {}",
-            self.draw_calls, self.triangles_rendered
+            Triangles Rendered: {}\n\
+            Dynamic Batches: {}\n\
+            Batched Primitives: {}\n\
+            Instanced Draw Calls: {}\n\
+            Instanced Meshes: {}",
+            self.draw_calls,
+            self.triangles_rendered,
+            self.dynamic_batches,
+            self.batched_primitives,
+            self.instanced_draw_calls,
+            self.instanced_meshes
         )
     }
 }
@@ -110,6 +131,10 @@ impl std::ops::AddAssign for RenderPassStatistics {
     fn add_assign(&mut self, rhs: Self) {
         self.draw_calls += rhs.draw_calls;
         self.triangles_rendered += rhs.triangles_rendered;
+        self.dynamic_batches += rhs.dynamic_batches;
+        self.batched_primitives += rhs.batched_primitives;
+        self.instanced_draw_calls += rhs.instanced_draw_calls;
+        self.instanced_meshes += rhs.instanced_meshes;
     }
 }
 
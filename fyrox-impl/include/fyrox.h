@@ -0,0 +1,141 @@
+/*
+ * Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+/*
+ * C interface of the Fyrox engine. It is available when the engine is built with `ffi` feature,
+ * see documentation of `fyrox::ffi` module for more info.
+ */
+
+#ifndef FYROX_H
+#define FYROX_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct FyroxEngine FyroxEngine;
+
+typedef enum FyroxResult {
+    FYROX_RESULT_OK = 0,
+    FYROX_RESULT_NULL_POINTER = 1,
+    FYROX_RESULT_INVALID_HANDLE = 2,
+    FYROX_RESULT_INVALID_ARGUMENT = 3,
+    FYROX_RESULT_LOAD_FAILED = 4,
+    FYROX_RESULT_RENDER_FAILED = 5,
+    FYROX_RESULT_PANIC = 6,
+} FyroxResult;
+
+typedef struct FyroxHandle {
+    uint32_t index;
+    uint32_t generation;
+} FyroxHandle;
+
+typedef struct FyroxVector3 {
+    float x;
+    float y;
+    float z;
+} FyroxVector3;
+
+typedef struct FyroxQuaternion {
+    float x;
+    float y;
+    float z;
+    float w;
+} FyroxQuaternion;
+
+typedef void (*FyroxUpdateCallback)(FyroxEngine *engine, float dt, void *user_data);
+
+/* Errors */
+
+const char *fyrox_last_error_message(void);
+
+/* Engine */
+
+FyroxEngine *fyrox_engine_create(void);
+FyroxResult fyrox_engine_destroy(FyroxEngine *engine);
+FyroxResult fyrox_engine_update(FyroxEngine *engine, float dt);
+FyroxResult fyrox_engine_render(FyroxEngine *engine);
+float fyrox_engine_elapsed_time(const FyroxEngine *engine);
+/* Takes ownership of the engine, even on failure. The engine can't be destroyed in `update`. */
+FyroxResult fyrox_engine_run(FyroxEngine *engine,
+                             const char *title,
+                             FyroxUpdateCallback update,
+                             void *user_data);
+
+/* Scenes and nodes */
+
+FyroxResult fyrox_scene_create(FyroxEngine *engine, FyroxHandle *out_scene);
+FyroxResult fyrox_scene_load(FyroxEngine *engine, const char *path, FyroxHandle *out_scene);
+FyroxResult fyrox_scene_remove(FyroxEngine *engine, FyroxHandle scene);
+FyroxResult fyrox_scene_find_node(FyroxEngine *engine,
+                                  FyroxHandle scene,
+                                  const char *name,
+                                  FyroxHandle *out_node);
+
+FyroxResult fyrox_node_get_position(FyroxEngine *engine,
+                                    FyroxHandle scene,
+                                    FyroxHandle node,
+                                    FyroxVector3 *out_position);
+FyroxResult fyrox_node_set_position(FyroxEngine *engine,
+                                    FyroxHandle scene,
+                                    FyroxHandle node,
+                                    FyroxVector3 position);
+FyroxResult fyrox_node_get_rotation(FyroxEngine *engine,
+                                    FyroxHandle scene,
+                                    FyroxHandle node,
+                                    FyroxQuaternion *out_rotation);
+FyroxResult fyrox_node_set_rotation(FyroxEngine *engine,
+                                    FyroxHandle scene,
+                                    FyroxHandle node,
+                                    FyroxQuaternion rotation);
+FyroxResult fyrox_node_get_scale(FyroxEngine *engine,
+                                 FyroxHandle scene,
+                                 FyroxHandle node,
+                                 FyroxVector3 *out_scale);
+FyroxResult fyrox_node_set_scale(FyroxEngine *engine,
+                                 FyroxHandle scene,
+                                 FyroxHandle node,
+                                 FyroxVector3 scale);
+FyroxResult fyrox_node_get_global_position(FyroxEngine *engine,
+                                           FyroxHandle scene,
+                                           FyroxHandle node,
+                                           FyroxVector3 *out_position);
+
+/* Input */
+
+FyroxResult fyrox_input_key(FyroxEngine *engine, const char *key, bool pressed);
+FyroxResult fyrox_input_mouse_button(FyroxEngine *engine, uint32_t button, bool pressed);
+FyroxResult fyrox_input_mouse_motion(FyroxEngine *engine, float dx, float dy);
+FyroxResult fyrox_input_gamepad_button(FyroxEngine *engine, uint32_t button, bool pressed);
+FyroxResult fyrox_input_gamepad_axis(FyroxEngine *engine, uint32_t axis, float value);
+FyroxResult fyrox_input_release_all(FyroxEngine *engine);
+bool fyrox_input_is_action_pressed(const FyroxEngine *engine, const char *action);
+float fyrox_input_axis_value(const FyroxEngine *engine, const char *axis);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FYROX_H */
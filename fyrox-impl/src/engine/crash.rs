@@ -0,0 +1,339 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Crash reporter is an optional panic hook that writes a crash report file with a backtrace,
+//! engine and renderer statistics, a short summary of the active scenes and recent log lines.
+//! See [`CrashReporter`] docs for more info.
+
+use crate::{
+    core::{
+        log::{Log, LogMessage},
+        parking_lot::Mutex,
+        pool::Handle,
+    },
+    engine::{Engine, GraphicsContext, PerformanceStatistics},
+    renderer::Statistics,
+    scene::Scene,
+};
+use std::{
+    backtrace::Backtrace,
+    fmt::{Display, Formatter},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A callback that is called with every crash report right after it was saved. It could be used to
+/// upload the report to a remote server, but it is up to the game to ask the player for a consent
+/// first. The engine itself never sends crash reports anywhere.
+pub type CrashReportHook = Arc<dyn Fn(&CrashReport, Option<&Path>) + Send + Sync>;
+
+/// A set of options for the crash reporter.
+#[derive(Clone)]
+pub struct CrashReporterSettings {
+    /// A folder where crash reports will be saved.
+    pub directory: PathBuf,
+    /// Maximum amount of recent log messages that will be attached to a report.
+    pub log_history: usize,
+    /// An optional hook that will be called for every report, see [`CrashReportHook`] docs for more
+    /// info.
+    pub hook: Option<CrashReportHook>,
+}
+
+impl Default for CrashReporterSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("crash_reports"),
+            log_history: 64,
+            hook: None,
+        }
+    }
+}
+
+/// A short summary of a scene.
+#[derive(Clone, Debug, Default)]
+pub struct SceneSummary {
+    /// A handle of the scene.
+    pub handle: Handle<Scene>,
+    /// Whether the scene is enabled or not.
+    pub enabled: bool,
+    /// Total amount of nodes in the scene.
+    pub node_count: u32,
+    /// Whether the scene renders into a custom render target or not.
+    pub has_render_target: bool,
+}
+
+/// A snapshot of the engine state, it is collected by the engine once per frame and then written
+/// to a crash report.
+#[derive(Clone, Debug, Default)]
+pub struct EngineStateSnapshot {
+    /// Amount of time (in seconds) that passed from creation of the engine.
+    pub elapsed_time: f32,
+    /// Performance statistics of the engine.
+    pub performance_statistics: PerformanceStatistics,
+    /// Renderer statistics, if the graphics context was initialized.
+    pub renderer_statistics: Option<Statistics>,
+    /// A summary of every scene in the engine.
+    pub scenes: Vec<SceneSummary>,
+}
+
+impl EngineStateSnapshot {
+    /// Fills the snapshot with the data from the given engine.
+    pub fn capture(&mut self, engine: &Engine) {
+        self.elapsed_time = engine.elapsed_time;
+        self.performance_statistics = engine.performance_statistics.clone();
+        self.renderer_statistics =
+            if let GraphicsContext::Initialized(ref ctx) = engine.graphics_context {
+                Some(ctx.renderer.get_statistics())
+            } else {
+                None
+            };
+        self.scenes.clear();
+        self.scenes.extend(
+            engine
+                .scenes
+                .pair_iter()
+                .map(|(handle, scene)| SceneSummary {
+                    handle,
+                    enabled: *scene.enabled,
+                    node_count: scene.graph.node_count(),
+                    has_render_target: scene.rendering_options.render_target.is_some(),
+                }),
+        );
+    }
+}
+
+impl Display for EngineStateSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Elapsed Time: {:.3} s", self.elapsed_time)?;
+        writeln!(f, "{}", self.performance_statistics)?;
+        match self.renderer_statistics {
+            Some(ref statistics) => writeln!(f, "Renderer Statistics:\n{statistics}")?,
+            None => writeln!(
+                f,
+                "Renderer Statistics: <graphics context is uninitialized>"
+            )?,
+        }
+        writeln!(f, "Scenes ({}):", self.scenes.len())?;
+        for scene in self.scenes.iter() {
+            writeln!(
+                f,
+                "\t{}: Enabled: {}, Nodes: {}, Render Target: {}",
+                scene.handle, scene.enabled, scene.node_count, scene.has_render_target
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A crash report, that contains all the information that was collected at the moment of panic.
+#[derive(Clone, Debug)]
+pub struct CrashReport {
+    /// Panic message.
+    pub message: String,
+    /// Source code location of the panic (if any).
+    pub location: Option<String>,
+    /// Name of the thread that panicked.
+    pub thread: Option<String>,
+    /// Captured backtrace.
+    pub backtrace: String,
+    /// Last known state of the engine.
+    pub state: EngineStateSnapshot,
+    /// Recent log messages (oldest first).
+    pub log: Vec<LogMessage>,
+}
+
+impl Display for CrashReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Crash Report")?;
+        writeln!(f, "Message: {}", self.message)?;
+        writeln!(
+            f,
+            "Location: {}",
+            self.location.as_deref().unwrap_or("<unknown>")
+        )?;
+        writeln!(
+            f,
+            "Thread: {}",
+            self.thread.as_deref().unwrap_or("<unnamed>")
+        )?;
+        writeln!(f, "\n{}", self.state)?;
+        writeln!(f, "Backtrace:\n{}", self.backtrace)?;
+        writeln!(f, "Recent Log Messages:")?;
+        for message in self.log.iter() {
+            write!(
+                f,
+                "[{:.3}] {}{}",
+                message.time.as_secs_f32(),
+                message.kind.as_str(),
+                message.content
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl CrashReport {
+    fn capture(message: String, location: Option<String>, state: EngineStateSnapshot) -> Self {
+        Self {
+            message,
+            location,
+            thread: std::thread::current().name().map(|n| n.to_string()),
+            backtrace: Backtrace::force_capture().to_string(),
+            state,
+            log: Log::recent_messages(),
+        }
+    }
+
+    /// Writes the report into a new file in the given directory. The file name is generated from
+    /// the current time. Returns a full path of the saved report.
+    pub fn save(&self, directory: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = directory.join(format!("crash_{timestamp}.txt"));
+        let mut file = std::fs::File::create(&path)?;
+        write!(file, "{self}")?;
+        Ok(path)
+    }
+}
+
+/// Crash reporter is an optional panic hook, that writes a crash report file with a backtrace,
+/// engine and renderer statistics, a short summary of the active scenes and recent log lines. It
+/// is disabled by default and could be enabled using [`Engine::enable_crash_reporter`]. The engine
+/// updates the state snapshot once per frame, so the report contains the state of the last
+/// rendered frame.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use fyrox_impl::engine::{crash::CrashReporterSettings, Engine};
+/// # use std::sync::Arc;
+/// fn enable_crash_reporter(engine: &mut Engine) {
+///     engine.enable_crash_reporter(CrashReporterSettings {
+///         hook: Some(Arc::new(|report, path| {
+///             // Ask the player for a consent and upload the report here.
+///             println!("Crash report saved to {path:?}: {}", report.message);
+///         })),
+///         ..Default::default()
+///     });
+/// }
+/// ```
+pub struct CrashReporter {
+    state: Arc<Mutex<EngineStateSnapshot>>,
+}
+
+impl CrashReporter {
+    /// Installs a new panic hook, that will create crash reports. Previous panic hook will be
+    /// called after the report is saved.
+    pub fn install(settings: CrashReporterSettings) -> Self {
+        Log::set_history_capacity(settings.log_history);
+
+        let state = Arc::new(Mutex::new(EngineStateSnapshot::default()));
+        let hook_state = state.clone();
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // Do not block on the state, the panic could happen while the engine is updating it.
+            let state = hook_state
+                .try_lock()
+                .map(|state| state.clone())
+                .unwrap_or_default();
+            let payload = info.payload();
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "<non-string panic payload>".to_string()
+            };
+            let location = info.location().map(|l| l.to_string());
+            let report = CrashReport::capture(message, location, state);
+            let path = match report.save(&settings.directory) {
+                Ok(path) => {
+                    eprintln!("Crash report was saved to {}", path.display());
+                    Some(path)
+                }
+                Err(err) => {
+                    eprintln!("Unable to save crash report. Reason: {err:?}");
+                    None
+                }
+            };
+            if let Some(hook) = settings.hook.as_ref() {
+                hook(&report, path.as_deref());
+            }
+            previous_hook(info);
+        }));
+
+        Self { state }
+    }
+
+    /// Updates the state snapshot from the given engine.
+    pub fn update(&self, engine: &Engine) {
+        self.state.lock().capture(engine);
+    }
+
+    /// Returns a copy of the current state snapshot.
+    pub fn snapshot(&self) -> EngineStateSnapshot {
+        self.state.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::log::{LogMessage, MessageKind},
+        engine::crash::{CrashReport, EngineStateSnapshot, SceneSummary},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_crash_report_save() {
+        let report = CrashReport {
+            message: "Something went wrong".to_string(),
+            location: Some("src/main.rs:1:1".to_string()),
+            thread: Some("main".to_string()),
+            backtrace: "<backtrace>".to_string(),
+            state: EngineStateSnapshot {
+                scenes: vec![SceneSummary {
+                    node_count: 123,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            log: vec![LogMessage {
+                kind: MessageKind::Warning,
+                content: "Last message\n".to_string(),
+                time: Duration::from_secs(1),
+            }],
+        };
+
+        let directory = std::env::temp_dir().join("fyrox_crash_report_test");
+        let path = report.save(&directory).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("Message: Something went wrong"));
+        assert!(content.contains("Location: src/main.rs:1:1"));
+        assert!(content.contains("Nodes: 123"));
+        assert!(content.contains("[WARNING]: Last message"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -128,6 +128,7 @@ impl Executor {
                 window_attributes,
                 vsync: true,
                 msaa_sample_count: None,
+                graphics_server_constructor: Default::default(),
             },
         )
     }
@@ -150,7 +151,8 @@ impl Executor {
 
     /// Defines whether the executor should initialize graphics context or not. Headless mode could
     /// be useful for game servers, where you don't need to have a window, renderer, sound, etc.
-    /// By default, headless mode is off.
+    /// By default, headless mode is off. If you don't need an event loop at all (for example, in
+    /// automated tests of gameplay logic), use [`Engine::update_headless`] directly.
     pub fn set_headless(&mut self, headless: bool) {
         self.headless = headless;
     }
@@ -250,6 +252,8 @@ impl Executor {
         run_executor(event_loop, move |event, window_target| {
             window_target.set_control_flow(ControlFlow::Wait);
 
+            engine.input.process_os_event(&event);
+
             engine.handle_os_event_by_plugins(&event, fixed_time_step, window_target, &mut lag);
 
             let scenes = engine
@@ -294,10 +298,38 @@ impl Executor {
                     previous = Instant::now();
                     lag += elapsed.as_secs_f32();
 
+                    // Gamepads are not handled by the window system, so they're polled manually
+                    // and their events are delivered the same way as OS events.
+                    for gamepad_event in engine.gamepads.poll() {
+                        engine.input.process_gamepad_event(&gamepad_event);
+
+                        engine.handle_gamepad_event_by_plugins(
+                            &gamepad_event,
+                            fixed_time_step,
+                            window_target,
+                            &mut lag,
+                        );
+
+                        for &scene_handle in scenes.iter() {
+                            engine.handle_gamepad_event_by_scripts(
+                                &gamepad_event,
+                                scene_handle,
+                                fixed_time_step,
+                            );
+                        }
+                    }
+
+                    // The game logic must always be updated with the same time step in the
+                    // deterministic mode, so it is never fast-forwarded.
+                    let deterministic_time_step =
+                        engine.deterministic_simulation().map(|s| s.time_step);
+                    let fixed_time_step = deterministic_time_step.unwrap_or(fixed_time_step);
+
                     // Update rate stabilization loop.
                     while lag >= fixed_time_step {
                         let time_step;
-                        if lag >= throttle_threshold
+                        if deterministic_time_step.is_none()
+                            && lag >= throttle_threshold
                             && (frame_counter - last_throttle_frame_number
                                 >= throttle_frame_interval)
                         {
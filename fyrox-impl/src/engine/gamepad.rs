@@ -0,0 +1,685 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Gamepad (controller) input. Gamepads are polled by the engine once per frame and every change
+//! of their state is delivered to plugins and scripts as a [`GamepadEvent`], alongside regular
+//! keyboard and mouse events. See [`GamepadManager`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{log::Log, visitor::prelude::*},
+    gui::message::ButtonState,
+};
+use fxhash::FxHashMap;
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks},
+    EventType, Gilrs, GilrsBuilder,
+};
+use std::time::Duration;
+
+/// Unique identifier of a gamepad. Identifiers are reused when a gamepad is reconnected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub usize);
+
+/// A button of a gamepad. Names of the face buttons follow their physical location, because
+/// different vendors use different labels for them (`A`/`Cross` is [`GamepadButton::South`], etc.).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Visit)]
+pub enum GamepadButton {
+    /// Bottom face button (`A` on Xbox controllers, `Cross` on PlayStation controllers).
+    #[default]
+    South,
+    /// Right face button (`B` on Xbox controllers, `Circle` on PlayStation controllers).
+    East,
+    /// Top face button (`Y` on Xbox controllers, `Triangle` on PlayStation controllers).
+    North,
+    /// Left face button (`X` on Xbox controllers, `Square` on PlayStation controllers).
+    West,
+    /// Left shoulder button.
+    LeftTrigger,
+    /// Left analog trigger.
+    LeftTrigger2,
+    /// Right shoulder button.
+    RightTrigger,
+    /// Right analog trigger.
+    RightTrigger2,
+    /// `Select`, `Back` or `Share` button.
+    Select,
+    /// `Start`, `Menu` or `Options` button.
+    Start,
+    /// Vendor-specific button in the middle of a gamepad (`Xbox`, `PS`, etc.).
+    Mode,
+    /// Left stick click.
+    LeftThumb,
+    /// Right stick click.
+    RightThumb,
+    /// Up button of the directional pad.
+    DPadUp,
+    /// Down button of the directional pad.
+    DPadDown,
+    /// Left button of the directional pad.
+    DPadLeft,
+    /// Right button of the directional pad.
+    DPadRight,
+}
+
+impl GamepadButton {
+    fn from_gilrs(button: gilrs::Button) -> Option<Self> {
+        use gilrs::Button;
+        Some(match button {
+            Button::South => Self::South,
+            Button::East => Self::East,
+            Button::North => Self::North,
+            Button::West => Self::West,
+            Button::LeftTrigger => Self::LeftTrigger,
+            Button::LeftTrigger2 => Self::LeftTrigger2,
+            Button::RightTrigger => Self::RightTrigger,
+            Button::RightTrigger2 => Self::RightTrigger2,
+            Button::Select => Self::Select,
+            Button::Start => Self::Start,
+            Button::Mode => Self::Mode,
+            Button::LeftThumb => Self::LeftThumb,
+            Button::RightThumb => Self::RightThumb,
+            Button::DPadUp => Self::DPadUp,
+            Button::DPadDown => Self::DPadDown,
+            Button::DPadLeft => Self::DPadLeft,
+            Button::DPadRight => Self::DPadRight,
+            _ => return None,
+        })
+    }
+
+    fn is_analog_trigger(self) -> bool {
+        matches!(self, Self::LeftTrigger2 | Self::RightTrigger2)
+    }
+}
+
+/// An analog axis of a gamepad. Stick axes are in `[-1; 1]` range (positive Y is up), trigger axes
+/// are in `[0; 1]` range.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Visit)]
+pub enum GamepadAxis {
+    /// Horizontal axis of the left stick.
+    #[default]
+    LeftStickX,
+    /// Vertical axis of the left stick.
+    LeftStickY,
+    /// Horizontal axis of the right stick.
+    RightStickX,
+    /// Vertical axis of the right stick.
+    RightStickY,
+    /// Left analog trigger, on gamepads that report it as an axis.
+    LeftZ,
+    /// Right analog trigger, on gamepads that report it as an axis.
+    RightZ,
+}
+
+impl GamepadAxis {
+    fn from_gilrs(axis: gilrs::Axis) -> Option<Self> {
+        use gilrs::Axis;
+        Some(match axis {
+            Axis::LeftStickX => Self::LeftStickX,
+            Axis::LeftStickY => Self::LeftStickY,
+            Axis::RightStickX => Self::RightStickX,
+            Axis::RightStickY => Self::RightStickY,
+            Axis::LeftZ => Self::LeftZ,
+            Axis::RightZ => Self::RightZ,
+            _ => return None,
+        })
+    }
+
+    /// Returns the other axis of the same stick, or `None` if the axis is not a stick axis.
+    fn stick_pair(self) -> Option<Self> {
+        match self {
+            Self::LeftStickX => Some(Self::LeftStickY),
+            Self::LeftStickY => Some(Self::LeftStickX),
+            Self::RightStickX => Some(Self::RightStickY),
+            Self::RightStickY => Some(Self::RightStickX),
+            Self::LeftZ | Self::RightZ => None,
+        }
+    }
+
+    fn is_x(self) -> bool {
+        matches!(self, Self::LeftStickX | Self::RightStickX)
+    }
+}
+
+/// Kind of a gamepad event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GamepadEventKind {
+    /// A gamepad was connected.
+    Connected {
+        /// Name of the gamepad, reported by its driver.
+        name: String,
+    },
+    /// A gamepad was disconnected.
+    Disconnected,
+    /// A button was pressed or released.
+    ButtonInput {
+        /// The button.
+        button: GamepadButton,
+        /// New state of the button.
+        state: ButtonState,
+    },
+    /// Value of a pressure-sensitive button (for example - an analog trigger) has changed. The
+    /// value is in `[0; 1]` range with the trigger dead zone applied.
+    ButtonValue {
+        /// The button.
+        button: GamepadButton,
+        /// New value of the button.
+        value: f32,
+    },
+    /// Value of an analog axis has changed. The value has the dead zone applied (see
+    /// [`GamepadSettings`]), which means that a stick in the neutral position always reports
+    /// exactly zero.
+    AxisMotion {
+        /// The axis.
+        axis: GamepadAxis,
+        /// New value of the axis.
+        value: f32,
+    },
+}
+
+/// An event produced by a gamepad.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadEvent {
+    /// The gamepad, that has produced the event.
+    pub gamepad: GamepadId,
+    /// Actual event.
+    pub kind: GamepadEventKind,
+}
+
+/// Gamepad input settings.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamepadSettings {
+    /// Radius of the radial dead zone of analog sticks in `[0; 1]` range. Stick deflection below
+    /// this radius is reported as zero, deflection above it is rescaled to the full range, so there
+    /// is no "jump" at the border of the dead zone.
+    pub stick_dead_zone: f32,
+    /// Dead zone of analog triggers in `[0; 1]` range. Works the same as the stick dead zone.
+    pub trigger_dead_zone: f32,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            stick_dead_zone: 0.15,
+            trigger_dead_zone: 0.05,
+        }
+    }
+}
+
+fn apply_stick_dead_zone(x: f32, y: f32, dead_zone: f32) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length <= dead_zone || length <= f32::EPSILON {
+        (0.0, 0.0)
+    } else {
+        let scale = ((length - dead_zone) / (1.0 - dead_zone)).min(1.0) / length;
+        (x * scale, y * scale)
+    }
+}
+
+fn apply_trigger_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value <= dead_zone {
+        0.0
+    } else {
+        ((value - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+#[derive(Default, Debug)]
+struct GamepadState {
+    native: Option<gilrs::GamepadId>,
+    name: String,
+    raw_axes: FxHashMap<GamepadAxis, f32>,
+    axes: FxHashMap<GamepadAxis, f32>,
+    button_values: FxHashMap<GamepadButton, f32>,
+    pressed: Vec<GamepadButton>,
+}
+
+/// Gamepad manager tracks connected gamepads, converts their raw input into [`GamepadEvent`]s
+/// and allows to play rumble (force feedback) effects on them. It is owned by the engine, which
+/// polls it every frame and delivers the events to [`crate::plugin::Plugin::on_gamepad_event`] and
+/// [`crate::script::ScriptTrait::on_gamepad_event`].
+///
+/// The manager can also be queried directly, which is handy for continuous input such as movement:
+///
+/// ```rust
+/// # use fyrox_impl::engine::gamepad::{GamepadButton, GamepadManager};
+/// fn update_player(gamepads: &GamepadManager) {
+///     for gamepad in gamepads.gamepads() {
+///         let (_x, _y) = gamepads.left_stick(gamepad);
+///         let _jump = gamepads.is_button_pressed(gamepad, GamepadButton::South);
+///         // Move the player...
+///     }
+/// }
+/// ```
+///
+/// Gamepad support might be unavailable on some platforms, in this case the manager will never
+/// report any gamepads.
+pub struct GamepadManager {
+    gilrs: Option<Gilrs>,
+    gamepads: FxHashMap<GamepadId, GamepadState>,
+    rumble: FxHashMap<GamepadId, Effect>,
+    /// Gamepad input settings. Changes will take effect on the next input event.
+    pub settings: GamepadSettings,
+}
+
+impl Default for GamepadManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GamepadManager {
+    /// Creates new gamepad manager and registers every gamepad, that is already connected. Such
+    /// gamepads will be reported with [`GamepadEventKind::Connected`] on the first poll.
+    pub fn new() -> Self {
+        // Default filters of gilrs are disabled, because dead zones are handled by the manager.
+        let gilrs = match GilrsBuilder::new().with_default_filters(false).build() {
+            Ok(gilrs) => Some(gilrs),
+            Err(gilrs::Error::NotImplemented(gilrs)) => {
+                Log::warn("Gamepads are not supported on this platform.");
+                Some(gilrs)
+            }
+            Err(err) => {
+                Log::warn(format!(
+                    "Unable to initialize gamepad support. Reason: {err}"
+                ));
+                None
+            }
+        };
+
+        Self {
+            gilrs,
+            gamepads: Default::default(),
+            rumble: Default::default(),
+            settings: Default::default(),
+        }
+    }
+
+    /// Reads pending input of every gamepad and returns a list of events. The engine calls this
+    /// method once per frame, there is no need to call it manually.
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return events;
+        };
+
+        // Gamepads that were connected before the manager was created do not produce connection
+        // events, so they are reported here.
+        let mut new_gamepads = Vec::new();
+        for (native, gamepad) in gilrs.gamepads() {
+            let id = GamepadId(native.into());
+            if !self.gamepads.contains_key(&id) {
+                new_gamepads.push((id, native, gamepad.name().to_string()));
+            }
+        }
+        for (id, native, name) in new_gamepads {
+            self.on_connected(id, Some(native), name, &mut events);
+        }
+
+        while let Some(gilrs::Event {
+            id: native, event, ..
+        }) = self.gilrs.as_mut().and_then(|gilrs| gilrs.next_event())
+        {
+            let id = GamepadId(native.into());
+            match event {
+                EventType::Connected => {
+                    let name = self
+                        .gilrs
+                        .as_ref()
+                        .map(|gilrs| gilrs.gamepad(native).name().to_string())
+                        .unwrap_or_default();
+                    self.on_connected(id, Some(native), name, &mut events);
+                }
+                EventType::Disconnected => self.on_disconnected(id, &mut events),
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(button) = GamepadButton::from_gilrs(button) {
+                        self.on_button(id, button, ButtonState::Pressed, &mut events);
+                    }
+                }
+                EventType::ButtonReleased(button, _) => {
+                    if let Some(button) = GamepadButton::from_gilrs(button) {
+                        self.on_button(id, button, ButtonState::Released, &mut events);
+                    }
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    if let Some(button) = GamepadButton::from_gilrs(button) {
+                        self.on_button_value(id, button, value, &mut events);
+                    }
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    if let Some(axis) = GamepadAxis::from_gilrs(axis) {
+                        self.on_axis(id, axis, value, &mut events);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        events
+    }
+
+    fn on_connected(
+        &mut self,
+        id: GamepadId,
+        native: Option<gilrs::GamepadId>,
+        name: String,
+        events: &mut Vec<GamepadEvent>,
+    ) {
+        if self.gamepads.contains_key(&id) {
+            return;
+        }
+
+        self.gamepads.insert(
+            id,
+            GamepadState {
+                native,
+                name: name.clone(),
+                ..Default::default()
+            },
+        );
+
+        events.push(GamepadEvent {
+            gamepad: id,
+            kind: GamepadEventKind::Connected { name },
+        });
+    }
+
+    fn on_disconnected(&mut self, id: GamepadId, events: &mut Vec<GamepadEvent>) {
+        self.rumble.remove(&id);
+
+        if self.gamepads.remove(&id).is_some() {
+            events.push(GamepadEvent {
+                gamepad: id,
+                kind: GamepadEventKind::Disconnected,
+            });
+        }
+    }
+
+    fn on_button(
+        &mut self,
+        id: GamepadId,
+        button: GamepadButton,
+        state: ButtonState,
+        events: &mut Vec<GamepadEvent>,
+    ) {
+        let Some(gamepad) = self.gamepads.get_mut(&id) else {
+            return;
+        };
+
+        let was_pressed = gamepad.pressed.contains(&button);
+        match state {
+            ButtonState::Pressed if !was_pressed => gamepad.pressed.push(button),
+            ButtonState::Released if was_pressed => gamepad.pressed.retain(|b| *b != button),
+            // Repeated events are ignored.
+            _ => return,
+        }
+
+        events.push(GamepadEvent {
+            gamepad: id,
+            kind: GamepadEventKind::ButtonInput { button, state },
+        });
+    }
+
+    fn on_button_value(
+        &mut self,
+        id: GamepadId,
+        button: GamepadButton,
+        value: f32,
+        events: &mut Vec<GamepadEvent>,
+    ) {
+        let dead_zone = self.settings.trigger_dead_zone;
+        let Some(gamepad) = self.gamepads.get_mut(&id) else {
+            return;
+        };
+
+        let value = if button.is_analog_trigger() {
+            apply_trigger_dead_zone(value, dead_zone)
+        } else {
+            value.clamp(0.0, 1.0)
+        };
+
+        let previous = gamepad.button_values.insert(button, value).unwrap_or(0.0);
+        if previous != value {
+            events.push(GamepadEvent {
+                gamepad: id,
+                kind: GamepadEventKind::ButtonValue { button, value },
+            });
+        }
+    }
+
+    fn on_axis(
+        &mut self,
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+        events: &mut Vec<GamepadEvent>,
+    ) {
+        let settings = self.settings;
+        let Some(gamepad) = self.gamepads.get_mut(&id) else {
+            return;
+        };
+
+        gamepad.raw_axes.insert(axis, value);
+
+        let mut filtered = Vec::with_capacity(2);
+        if let Some(pair) = axis.stick_pair() {
+            // Dead zone of a stick is radial, so both of its axes must be filtered together.
+            let pair_value = gamepad.raw_axes.get(&pair).cloned().unwrap_or(0.0);
+            let (x, y) = if axis.is_x() {
+                (value, pair_value)
+            } else {
+                (pair_value, value)
+            };
+            let (x, y) = apply_stick_dead_zone(x, y, settings.stick_dead_zone);
+            if axis.is_x() {
+                filtered.push((axis, x));
+                filtered.push((pair, y));
+            } else {
+                filtered.push((pair, x));
+                filtered.push((axis, y));
+            }
+        } else {
+            filtered.push((
+                axis,
+                apply_trigger_dead_zone(value, settings.trigger_dead_zone),
+            ));
+        }
+
+        for (axis, value) in filtered {
+            let previous = gamepad.axes.insert(axis, value).unwrap_or(0.0);
+            if previous != value {
+                events.push(GamepadEvent {
+                    gamepad: id,
+                    kind: GamepadEventKind::AxisMotion { axis, value },
+                });
+            }
+        }
+    }
+
+    /// Returns an iterator over identifiers of every connected gamepad.
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.keys().cloned()
+    }
+
+    /// Returns `true` if a gamepad with the given id is connected.
+    pub fn is_connected(&self, gamepad: GamepadId) -> bool {
+        self.gamepads.contains_key(&gamepad)
+    }
+
+    /// Returns name of a gamepad, or `None` if there's no such gamepad.
+    pub fn name(&self, gamepad: GamepadId) -> Option<&str> {
+        self.gamepads.get(&gamepad).map(|state| state.name.as_str())
+    }
+
+    /// Returns `true` if the button of the gamepad is pressed.
+    pub fn is_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|state| state.pressed.contains(&button))
+    }
+
+    /// Returns value of a pressure-sensitive button of the gamepad with the dead zone applied.
+    pub fn button_value(&self, gamepad: GamepadId, button: GamepadButton) -> f32 {
+        self.gamepads
+            .get(&gamepad)
+            .and_then(|state| state.button_values.get(&button).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Returns value of an axis of the gamepad with the dead zone applied.
+    pub fn axis_value(&self, gamepad: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .get(&gamepad)
+            .and_then(|state| state.axes.get(&axis).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Returns position of the left stick of the gamepad with the dead zone applied.
+    pub fn left_stick(&self, gamepad: GamepadId) -> (f32, f32) {
+        (
+            self.axis_value(gamepad, GamepadAxis::LeftStickX),
+            self.axis_value(gamepad, GamepadAxis::LeftStickY),
+        )
+    }
+
+    /// Returns position of the right stick of the gamepad with the dead zone applied.
+    pub fn right_stick(&self, gamepad: GamepadId) -> (f32, f32) {
+        (
+            self.axis_value(gamepad, GamepadAxis::RightStickX),
+            self.axis_value(gamepad, GamepadAxis::RightStickY),
+        )
+    }
+
+    /// Starts rumble on the gamepad. `strong` and `weak` are magnitudes of the low-frequency
+    /// (heavy) and high-frequency (light) motors respectively, both in `[0; 1]` range. Previous
+    /// rumble effect of the gamepad is replaced. Does nothing if the gamepad does not support
+    /// force feedback.
+    pub fn set_rumble(&mut self, gamepad: GamepadId, strong: f32, weak: f32, duration: Duration) {
+        let (Some(gilrs), Some(native)) = (
+            self.gilrs.as_mut(),
+            self.gamepads.get(&gamepad).and_then(|state| state.native),
+        ) else {
+            return;
+        };
+
+        if !gilrs.gamepad(native).is_ff_supported() {
+            return;
+        }
+
+        let scheduling = Replay {
+            play_for: Ticks::from_ms(duration.as_millis().min(u32::MAX as u128) as u32),
+            ..Default::default()
+        };
+        let magnitude = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+
+        let result = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: magnitude(strong),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: magnitude(weak),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(&[native])
+            .finish(gilrs)
+            .and_then(|effect| effect.play().map(|_| effect));
+
+        match result {
+            Ok(effect) => {
+                self.rumble.insert(gamepad, effect);
+            }
+            Err(err) => Log::warn(format!("Unable to play rumble effect. Reason: {err}")),
+        }
+    }
+
+    /// Stops rumble on the gamepad.
+    pub fn stop_rumble(&mut self, gamepad: GamepadId) {
+        if let Some(effect) = self.rumble.remove(&gamepad) {
+            let _ = effect.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        engine::gamepad::{
+            apply_stick_dead_zone, GamepadAxis, GamepadButton, GamepadEventKind, GamepadId,
+            GamepadManager, GamepadSettings,
+        },
+        gui::message::ButtonState,
+    };
+
+    fn manager() -> GamepadManager {
+        GamepadManager {
+            gilrs: None,
+            gamepads: Default::default(),
+            rumble: Default::default(),
+            settings: GamepadSettings::default(),
+        }
+    }
+
+    #[test]
+    fn test_stick_dead_zone() {
+        assert_eq!(apply_stick_dead_zone(0.1, 0.1, 0.15), (0.0, 0.0));
+        let (x, y) = apply_stick_dead_zone(1.0, 0.0, 0.15);
+        assert!((x - 1.0).abs() < 1.0e-6 && y == 0.0);
+        // Just above the dead zone the value must start from zero, not from the dead zone radius.
+        let (x, _) = apply_stick_dead_zone(0.2, 0.0, 0.15);
+        assert!(x > 0.0 && x < 0.1);
+    }
+
+    #[test]
+    fn test_axis_events() {
+        let mut manager = manager();
+        let id = GamepadId(0);
+        let mut events = Vec::new();
+        manager.on_connected(id, None, "Test".to_string(), &mut events);
+        assert_eq!(events.len(), 1);
+
+        // Stick jitter in the dead zone produces no events.
+        events.clear();
+        manager.on_axis(id, GamepadAxis::LeftStickX, 0.05, &mut events);
+        manager.on_axis(id, GamepadAxis::LeftStickY, -0.05, &mut events);
+        assert!(events.is_empty());
+
+        manager.on_axis(id, GamepadAxis::LeftStickX, 1.0, &mut events);
+        assert_eq!(events.len(), 2);
+        assert!(manager.left_stick(id).0 > 0.9);
+
+        manager.on_button(id, GamepadButton::South, ButtonState::Pressed, &mut events);
+        manager.on_button(id, GamepadButton::South, ButtonState::Pressed, &mut events);
+        assert!(manager.is_button_pressed(id, GamepadButton::South));
+        assert_eq!(events.len(), 3);
+
+        manager.on_disconnected(id, &mut events);
+        assert_eq!(events.last().unwrap().kind, GamepadEventKind::Disconnected);
+        assert!(!manager.is_connected(id));
+    }
+}
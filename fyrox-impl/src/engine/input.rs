@@ -0,0 +1,600 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Action mapping - a layer between physical inputs (keys, mouse buttons, gamepad buttons and
+//! axes) and game logic. See [`InputMap`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        algebra::Vector2,
+        visitor::{prelude::*, Visitor},
+    },
+    engine::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadEventKind},
+    event::{DeviceEvent, ElementState, Event, WindowEvent},
+    gui::message::{ButtonState, KeyCode, MouseButton},
+    keyboard::PhysicalKey,
+    utils::{translate_button, translate_key_to_ui},
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::path::Path;
+
+/// A physical input, that has two states - pressed and released.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Visit)]
+pub enum InputBinding {
+    /// A key on keyboard.
+    Key(KeyCode),
+    /// A mouse button.
+    MouseButton(MouseButton),
+    /// A button of any connected gamepad.
+    GamepadButton(GamepadButton),
+}
+
+impl Default for InputBinding {
+    fn default() -> Self {
+        Self::Key(KeyCode::default())
+    }
+}
+
+/// A source of values for a named axis.
+#[derive(Copy, Clone, Debug, PartialEq, Visit)]
+pub enum AxisBinding {
+    /// A pair of digital inputs, the axis is `-1.0` when the negative one is pressed, `1.0` when
+    /// the positive one is pressed, and zero when both or none of them are pressed.
+    Buttons {
+        /// An input, that moves the axis in negative direction.
+        negative: InputBinding,
+        /// An input, that moves the axis in positive direction.
+        positive: InputBinding,
+    },
+    /// An analog axis of any connected gamepad, multiplied by the scale.
+    GamepadAxis {
+        /// The axis.
+        axis: GamepadAxis,
+        /// A multiplier of the axis value. Negative scale could be used to invert the axis.
+        scale: f32,
+    },
+    /// Horizontal mouse movement since the last update, multiplied by the scale. Unlike the other
+    /// bindings, it is unbounded.
+    MouseMotionX {
+        /// A multiplier of the mouse movement (sensitivity).
+        scale: f32,
+    },
+    /// Vertical mouse movement since the last update, multiplied by the scale. Unlike the other
+    /// bindings, it is unbounded.
+    MouseMotionY {
+        /// A multiplier of the mouse movement (sensitivity).
+        scale: f32,
+    },
+}
+
+impl Default for AxisBinding {
+    fn default() -> Self {
+        Self::Buttons {
+            negative: Default::default(),
+            positive: Default::default(),
+        }
+    }
+}
+
+/// A change of the state of [`InputMap`]. Every physical input is converted to one of these events
+/// before it is applied to the map, which makes it possible to record the input and play it back
+/// (see [`crate::engine::replay`] for more info).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Visit)]
+pub enum InputEvent {
+    /// A physical input was pressed.
+    Pressed(InputBinding),
+    /// A physical input was released.
+    Released(InputBinding),
+    /// Value of a gamepad axis has changed.
+    GamepadAxisMotion {
+        /// The axis.
+        axis: GamepadAxis,
+        /// New value of the axis.
+        value: f32,
+    },
+    /// Mouse has moved by the given delta.
+    MouseMotion(Vector2<f32>),
+    /// Every pressed input was released (for example, when the main window has lost focus).
+    #[default]
+    ReleaseAll,
+}
+
+/// Input map maps physical inputs to named actions (digital inputs, such as "Jump" or "Fire") and
+/// named axes (analog inputs, such as "MoveForward" or "LookAround"). Game logic asks the map for
+/// state of the actions and axes, instead of listening for raw OS events and tracking state of
+/// every key manually. Bindings could be changed at runtime (for example - in a settings menu of a
+/// game) and saved to a file.
+///
+/// The engine owns an instance of the map, feeds it with every OS and gamepad event and makes it
+/// available to plugins via [`crate::plugin::PluginContext::input`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{reflect::prelude::*, visitor::prelude::*},
+/// #     engine::input::{AxisBinding, InputBinding, InputMap},
+/// #     gui::message::KeyCode,
+/// #     plugin::{Plugin, PluginContext},
+/// # };
+/// fn setup_bindings(input: &mut InputMap) {
+///     input.set_action("Jump", vec![InputBinding::Key(KeyCode::Space)]);
+///     input.set_axis(
+///         "MoveForward",
+///         vec![AxisBinding::Buttons {
+///             negative: InputBinding::Key(KeyCode::KeyS),
+///             positive: InputBinding::Key(KeyCode::KeyW),
+///         }],
+///     );
+/// }
+///
+/// #[derive(Default, Visit, Reflect, Debug)]
+/// struct Game {}
+///
+/// impl Plugin for Game {
+///     fn update(&mut self, context: &mut PluginContext) {
+///         let _speed = context.input.axis_value("MoveForward");
+///
+///         if context.input.is_action_just_pressed("Jump") {
+///             // Jump...
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Rebinding
+///
+/// Call [`InputMap::capture_next_binding`] when a player wants to change a binding, the next
+/// pressed key or button will be available via [`InputMap::captured_binding`] and could be then
+/// passed to [`InputMap::rebind_action`].
+#[derive(Default, Debug, Visit)]
+pub struct InputMap {
+    actions: FxHashMap<String, Vec<InputBinding>>,
+    axes: FxHashMap<String, Vec<AxisBinding>>,
+    #[visit(skip)]
+    pressed: FxHashSet<InputBinding>,
+    #[visit(skip)]
+    just_pressed: FxHashSet<InputBinding>,
+    #[visit(skip)]
+    just_released: FxHashSet<InputBinding>,
+    #[visit(skip)]
+    gamepad_axes: FxHashMap<GamepadAxis, f32>,
+    #[visit(skip)]
+    mouse_motion: Vector2<f32>,
+    #[visit(skip)]
+    capturing: bool,
+    #[visit(skip)]
+    captured: Option<InputBinding>,
+    #[visit(skip)]
+    recording: bool,
+    #[visit(skip)]
+    recorded: Vec<InputEvent>,
+    #[visit(skip)]
+    ignore_physical_input: bool,
+}
+
+impl InputMap {
+    /// Creates new empty input map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets bindings of an action, replacing the previous ones (if any).
+    pub fn set_action<S: Into<String>>(&mut self, name: S, bindings: Vec<InputBinding>) {
+        self.actions.insert(name.into(), bindings);
+    }
+
+    /// Sets bindings of an action and returns self. Useful for initial setup of a map.
+    pub fn with_action<S: Into<String>>(mut self, name: S, bindings: Vec<InputBinding>) -> Self {
+        self.set_action(name, bindings);
+        self
+    }
+
+    /// Removes an action and returns its bindings.
+    pub fn remove_action(&mut self, name: &str) -> Option<Vec<InputBinding>> {
+        self.actions.remove(name)
+    }
+
+    /// Returns bindings of an action.
+    pub fn action_bindings(&self, name: &str) -> Option<&[InputBinding]> {
+        self.actions.get(name).map(|bindings| bindings.as_slice())
+    }
+
+    /// Returns an iterator over names of every action.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(|name| name.as_str())
+    }
+
+    /// Replaces a binding of an action with the new one. Returns `false` if there's no such action
+    /// or the action does not have the old binding.
+    pub fn rebind_action(&mut self, name: &str, old: InputBinding, new: InputBinding) -> bool {
+        if let Some(binding) = self
+            .actions
+            .get_mut(name)
+            .and_then(|bindings| bindings.iter_mut().find(|binding| **binding == old))
+        {
+            *binding = new;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets bindings of an axis, replacing the previous ones (if any).
+    pub fn set_axis<S: Into<String>>(&mut self, name: S, bindings: Vec<AxisBinding>) {
+        self.axes.insert(name.into(), bindings);
+    }
+
+    /// Sets bindings of an axis and returns self. Useful for initial setup of a map.
+    pub fn with_axis<S: Into<String>>(mut self, name: S, bindings: Vec<AxisBinding>) -> Self {
+        self.set_axis(name, bindings);
+        self
+    }
+
+    /// Removes an axis and returns its bindings.
+    pub fn remove_axis(&mut self, name: &str) -> Option<Vec<AxisBinding>> {
+        self.axes.remove(name)
+    }
+
+    /// Returns bindings of an axis. Bindings could be changed directly using the returned
+    /// reference.
+    pub fn axis_bindings_mut(&mut self, name: &str) -> Option<&mut Vec<AxisBinding>> {
+        self.axes.get_mut(name)
+    }
+
+    /// Returns bindings of an axis.
+    pub fn axis_bindings(&self, name: &str) -> Option<&[AxisBinding]> {
+        self.axes.get(name).map(|bindings| bindings.as_slice())
+    }
+
+    /// Returns an iterator over names of every axis.
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(|name| name.as_str())
+    }
+
+    fn any_binding(&self, name: &str, set: &FxHashSet<InputBinding>) -> bool {
+        self.actions
+            .get(name)
+            .is_some_and(|bindings| bindings.iter().any(|binding| set.contains(binding)))
+    }
+
+    /// Returns `true` if any of the bindings of the action is pressed.
+    pub fn is_action_pressed(&self, name: &str) -> bool {
+        self.any_binding(name, &self.pressed)
+    }
+
+    /// Returns `true` if any of the bindings of the action was pressed since the last update.
+    pub fn is_action_just_pressed(&self, name: &str) -> bool {
+        self.any_binding(name, &self.just_pressed)
+    }
+
+    /// Returns `true` if any of the bindings of the action was released since the last update.
+    pub fn is_action_just_released(&self, name: &str) -> bool {
+        self.any_binding(name, &self.just_released)
+    }
+
+    /// Returns `true` if the physical input is pressed.
+    pub fn is_pressed(&self, binding: InputBinding) -> bool {
+        self.pressed.contains(&binding)
+    }
+
+    /// Returns current value of an axis, which is a sum of the values of all its bindings. Returns
+    /// zero if there's no such axis.
+    pub fn axis_value(&self, name: &str) -> f32 {
+        let Some(bindings) = self.axes.get(name) else {
+            return 0.0;
+        };
+
+        bindings
+            .iter()
+            .map(|binding| match *binding {
+                AxisBinding::Buttons { negative, positive } => {
+                    let mut value = 0.0;
+                    if self.pressed.contains(&negative) {
+                        value -= 1.0;
+                    }
+                    if self.pressed.contains(&positive) {
+                        value += 1.0;
+                    }
+                    value
+                }
+                AxisBinding::GamepadAxis { axis, scale } => {
+                    self.gamepad_axes.get(&axis).cloned().unwrap_or_default() * scale
+                }
+                AxisBinding::MouseMotionX { scale } => self.mouse_motion.x * scale,
+                AxisBinding::MouseMotionY { scale } => self.mouse_motion.y * scale,
+            })
+            .sum()
+    }
+
+    /// Starts waiting for the next pressed physical input. When it is pressed, it will be
+    /// available via [`Self::captured_binding`]. Pressed input is not delivered to actions while
+    /// capturing.
+    pub fn capture_next_binding(&mut self) {
+        self.capturing = true;
+        self.captured = None;
+    }
+
+    /// Returns `true` if the map is waiting for an input to capture.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    /// Returns captured input (if any) and resets it. See [`Self::capture_next_binding`].
+    pub fn captured_binding(&mut self) -> Option<InputBinding> {
+        self.captured.take()
+    }
+
+    fn press(&mut self, binding: InputBinding) {
+        if self.capturing {
+            self.capturing = false;
+            self.captured = Some(binding);
+        } else if self.pressed.insert(binding) {
+            self.just_pressed.insert(binding);
+        }
+    }
+
+    fn release(&mut self, binding: InputBinding) {
+        if self.pressed.remove(&binding) {
+            self.just_released.insert(binding);
+        }
+    }
+
+    fn set_state(&mut self, binding: InputBinding, state: ElementState) {
+        self.apply_event(match state {
+            ElementState::Pressed => InputEvent::Pressed(binding),
+            ElementState::Released => InputEvent::Released(binding),
+        })
+    }
+
+    /// Applies the event to the map. If recording is enabled, the event is also added to the
+    /// recorded events. See [`Self::set_recording`].
+    pub fn apply_event(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::Pressed(binding) => self.press(binding),
+            InputEvent::Released(binding) => self.release(binding),
+            InputEvent::GamepadAxisMotion { axis, value } => {
+                self.gamepad_axes.insert(axis, value);
+            }
+            InputEvent::MouseMotion(delta) => self.mouse_motion += delta,
+            InputEvent::ReleaseAll => {
+                let pressed = std::mem::take(&mut self.pressed);
+                self.just_released.extend(pressed);
+            }
+        }
+
+        if self.recording {
+            self.recorded.push(event);
+        }
+    }
+
+    /// Enables or disables recording of the events applied to the map. Recorded events could be
+    /// fetched using [`Self::take_recorded_events`].
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+        if !recording {
+            self.recorded.clear();
+        }
+    }
+
+    /// Returns `true` if the recording is enabled.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Returns every event recorded since the last call of this method.
+    pub fn take_recorded_events(&mut self) -> Vec<InputEvent> {
+        std::mem::take(&mut self.recorded)
+    }
+
+    /// Defines whether the map ignores physical input (OS and gamepad events) or not. It is used
+    /// to play back recorded input, so the actual input won't interfere with it.
+    pub fn set_ignore_physical_input(&mut self, ignore: bool) {
+        self.ignore_physical_input = ignore;
+    }
+
+    /// Returns `true` if the map ignores physical input.
+    pub fn is_ignoring_physical_input(&self) -> bool {
+        self.ignore_physical_input
+    }
+
+    /// Releases every pressed input. It is used when the main window loses focus, because release
+    /// events won't be delivered to it.
+    pub fn release_all(&mut self) {
+        self.apply_event(InputEvent::ReleaseAll)
+    }
+
+    /// Updates the state of the map using the OS event. The engine calls this method for every
+    /// event, there's no need to call it manually.
+    pub fn process_os_event(&mut self, event: &Event<()>) {
+        if self.ignore_physical_input {
+            return;
+        }
+
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::KeyboardInput { event, .. } => {
+                    if let PhysicalKey::Code(key) = event.physical_key {
+                        if !event.repeat {
+                            self.set_state(
+                                InputBinding::Key(translate_key_to_ui(key)),
+                                event.state,
+                            );
+                        }
+                    }
+                }
+                WindowEvent::MouseInput { state, button, .. } => {
+                    self.set_state(InputBinding::MouseButton(translate_button(*button)), *state);
+                }
+                WindowEvent::Focused(false) => self.release_all(),
+                _ => (),
+            },
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                self.apply_event(InputEvent::MouseMotion(Vector2::new(
+                    delta.0 as f32,
+                    delta.1 as f32,
+                )));
+            }
+            _ => (),
+        }
+    }
+
+    /// Updates the state of the map using the gamepad event. The engine calls this method for
+    /// every event, there's no need to call it manually.
+    pub fn process_gamepad_event(&mut self, event: &GamepadEvent) {
+        if self.ignore_physical_input {
+            return;
+        }
+
+        match event.kind {
+            GamepadEventKind::ButtonInput { button, state } => {
+                let binding = InputBinding::GamepadButton(button);
+                self.apply_event(match state {
+                    ButtonState::Pressed => InputEvent::Pressed(binding),
+                    ButtonState::Released => InputEvent::Released(binding),
+                });
+            }
+            GamepadEventKind::AxisMotion { axis, value } => {
+                self.apply_event(InputEvent::GamepadAxisMotion { axis, value });
+            }
+            _ => (),
+        }
+    }
+
+    /// Resets per-update state of the map ("just pressed" and "just released" inputs and mouse
+    /// movement). The engine calls this method at the end of every update, there's no need to call
+    /// it manually.
+    pub fn end_update(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.mouse_motion = Vector2::default();
+    }
+
+    /// Saves bindings of the map to a file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.visit("InputMap", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+
+    /// Loads bindings of the map from a file, replacing the current ones.
+    pub async fn load<P: AsRef<Path>>(&mut self, path: P) -> VisitResult {
+        let mut visitor = Visitor::load_binary(path).await?;
+        self.actions.clear();
+        self.axes.clear();
+        self.visit("InputMap", &mut visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::{prelude::*, Visitor},
+        engine::{
+            gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadEventKind, GamepadId},
+            input::{AxisBinding, InputBinding, InputMap},
+        },
+        gui::message::{ButtonState, KeyCode},
+    };
+
+    fn gamepad_button(button: GamepadButton, state: ButtonState) -> GamepadEvent {
+        GamepadEvent {
+            gamepad: GamepadId(0),
+            kind: GamepadEventKind::ButtonInput { button, state },
+        }
+    }
+
+    #[test]
+    fn test_actions_and_axes() {
+        let mut map = InputMap::new()
+            .with_action(
+                "Jump",
+                vec![
+                    InputBinding::Key(KeyCode::Space),
+                    InputBinding::GamepadButton(GamepadButton::South),
+                ],
+            )
+            .with_axis(
+                "Move",
+                vec![
+                    AxisBinding::Buttons {
+                        negative: InputBinding::GamepadButton(GamepadButton::DPadLeft),
+                        positive: InputBinding::GamepadButton(GamepadButton::DPadRight),
+                    },
+                    AxisBinding::GamepadAxis {
+                        axis: GamepadAxis::LeftStickX,
+                        scale: 0.5,
+                    },
+                ],
+            );
+
+        map.process_gamepad_event(&gamepad_button(GamepadButton::South, ButtonState::Pressed));
+        assert!(map.is_action_pressed("Jump"));
+        assert!(map.is_action_just_pressed("Jump"));
+        map.end_update();
+        assert!(map.is_action_pressed("Jump"));
+        assert!(!map.is_action_just_pressed("Jump"));
+        map.process_gamepad_event(&gamepad_button(GamepadButton::South, ButtonState::Released));
+        assert!(map.is_action_just_released("Jump"));
+
+        map.process_gamepad_event(&gamepad_button(
+            GamepadButton::DPadRight,
+            ButtonState::Pressed,
+        ));
+        map.process_gamepad_event(&GamepadEvent {
+            gamepad: GamepadId(0),
+            kind: GamepadEventKind::AxisMotion {
+                axis: GamepadAxis::LeftStickX,
+                value: -0.5,
+            },
+        });
+        assert_eq!(map.axis_value("Move"), 0.75);
+        assert_eq!(map.axis_value("Unknown"), 0.0);
+    }
+
+    #[test]
+    fn test_rebinding() {
+        let mut map = InputMap::new().with_action("Fire", vec![InputBinding::Key(KeyCode::KeyF)]);
+
+        map.capture_next_binding();
+        map.process_gamepad_event(&gamepad_button(GamepadButton::West, ButtonState::Pressed));
+        let captured = map.captured_binding().unwrap();
+        assert_eq!(captured, InputBinding::GamepadButton(GamepadButton::West));
+        // Captured input must not trigger actions.
+        assert!(!map.is_pressed(captured));
+
+        assert!(map.rebind_action("Fire", InputBinding::Key(KeyCode::KeyF), captured));
+        assert_eq!(map.action_bindings("Fire"), Some([captured].as_slice()));
+
+        // Bindings survive serialization.
+        let mut visitor = Visitor::new();
+        map.visit("InputMap", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut loaded = InputMap::new();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        loaded.visit("InputMap", &mut visitor).unwrap();
+        assert_eq!(loaded.action_bindings("Fire"), Some([captured].as_slice()));
+    }
+}
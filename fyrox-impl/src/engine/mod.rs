@@ -23,8 +23,15 @@
 
 #![warn(missing_docs)]
 
+pub mod crash;
 pub mod error;
 pub mod executor;
+pub mod gamepad;
+pub mod input;
+pub mod overlay;
+pub mod profiler;
+pub mod replay;
+pub mod simulation;
 pub mod task;
 
 mod hotreload;
@@ -43,12 +50,25 @@ use crate::{
         instant,
         log::Log,
         pool::Handle,
+        profile_scope,
+        profiler::Profiler,
         reflect::Reflect,
         task::TaskPool,
+        telemetry::Telemetry,
         variable::try_inherit_properties,
         visitor::VisitError,
     },
-    engine::{error::EngineError, task::TaskPoolHandler},
+    engine::{
+        crash::{CrashReporter, CrashReporterSettings},
+        error::EngineError,
+        gamepad::{GamepadEvent, GamepadManager},
+        input::InputMap,
+        overlay::ScreenOverlay,
+        profiler::ProfilerOverlay,
+        replay::{Replay, ReplayService},
+        simulation::{DeterministicSimulation, SimulationRng},
+        task::TaskPoolHandler,
+    },
     event::Event,
     graph::{BaseSceneGraph, NodeMapping, SceneGraph},
     gui::{
@@ -67,7 +87,10 @@ use crate::{
         dylib::DyLibDynamicPlugin, DynamicPlugin, Plugin, PluginContainer, PluginContext,
         PluginRegistrationContext,
     },
-    renderer::{framework::error::FrameworkError, Renderer},
+    renderer::{
+        framework::{error::FrameworkError, server::GraphicsServerConstructor},
+        Renderer,
+    },
     resource::{
         curve::{loader::CurveLoader, CurveResourceState},
         model::{loader::ModelLoader, Model, ModelResource},
@@ -97,7 +120,10 @@ use crate::{
 use fxhash::{FxHashMap, FxHashSet};
 use fyrox_sound::{
     buffer::{loader::SoundBufferLoader, SoundBuffer},
-    renderer::hrtf::{HrirSphereLoader, HrirSphereResourceData},
+    renderer::{
+        hrir_set::HrirSetLoader,
+        hrtf::{HrirSphereLoader, HrirSphereResourceData},
+    },
 };
 use std::{
     any::TypeId,
@@ -144,7 +170,7 @@ impl SerializationContext {
 }
 
 /// Performance statistics.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct PerformanceStatistics {
     /// Amount of time spent in the UI system.
     pub ui_time: Duration,
@@ -293,6 +319,9 @@ struct SceneLoadingOptions {
 /// plugin context. `Game::new` requests a new scene, which internally asks a resource manager to
 /// load the scene. Then, when the scene is fully loaded, the engine calls `Plugin::on_scene_loaded`
 /// method which allows you to do something with the newly loaded scene by taking a reference of it.
+///
+/// If you don't need the notifications, use [`PluginContext::load_scene`] instead, it loads a scene
+/// using the task pool and gives you its handle in a closure.
 pub struct AsyncSceneLoader {
     resource_manager: ResourceManager,
     serialization_context: Arc<SerializationContext>,
@@ -348,32 +377,10 @@ impl AsyncSceneLoader {
             let serialization_context = self.serialization_context.clone();
             let resource_manager = self.resource_manager.clone();
 
-            // Aquire the resource IO from the resource manager
-            let io = resource_manager.resource_io();
-
             let future = async move {
-                match SceneLoader::from_file(
-                    path.clone(),
-                    io.as_ref(),
-                    serialization_context,
-                    resource_manager.clone(),
-                )
-                .await
-                {
-                    Ok((loader, data)) => {
-                        let scene = loader.finish().await;
-                        Log::verify(sender.send(SceneLoadingResult {
-                            path,
-                            result: Ok((scene, data)),
-                        }));
-                    }
-                    Err(e) => {
-                        Log::verify(sender.send(SceneLoadingResult {
-                            path,
-                            result: Err(e),
-                        }));
-                    }
-                }
+                let result =
+                    load_scene(path.clone(), serialization_context, resource_manager).await;
+                Log::verify(sender.send(SceneLoadingResult { path, result }));
             };
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -409,6 +416,102 @@ impl AsyncSceneLoader {
     }
 }
 
+/// Loads a scene from the given path. The scene must be prepared using [`prepare_loaded_scene`]
+/// before adding it to the engine.
+pub(crate) async fn load_scene(
+    path: PathBuf,
+    serialization_context: Arc<SerializationContext>,
+    resource_manager: ResourceManager,
+) -> Result<(Scene, Vec<u8>), VisitError> {
+    // Aquire the resource IO from the resource manager
+    let io = resource_manager.resource_io();
+
+    let (loader, data) = SceneLoader::from_file(
+        path,
+        io.as_ref(),
+        serialization_context,
+        resource_manager.clone(),
+    )
+    .await?;
+    Ok((loader.finish().await, data))
+}
+
+/// Prepares a freshly loaded scene for use. Derived scene nodes will inherit their properties from
+/// the nodes of the source scene, raw scene takes scene data from its source scene. See
+/// [`AsyncSceneLoader::request`] docs for more info about raw and derived scenes.
+pub(crate) fn prepare_loaded_scene(
+    scene: &mut Scene,
+    path: &Path,
+    derived: bool,
+    resource_manager: &ResourceManager,
+) {
+    if derived {
+        // Create a resource, that will point to the scene we've loaded the
+        // scene from and force scene nodes to inherit data from them.
+        let model = Resource::new_ok(
+            ResourceKind::External(path.to_path_buf()),
+            Model {
+                mapping: NodeMapping::UseHandles,
+                // We have to create a full copy of the scene, because otherwise
+                // some methods (`Base::root_resource` in particular) won't work
+                // correctly.
+                scene: scene
+                    .clone(
+                        scene.graph.get_root(),
+                        &mut |_, _| true,
+                        &mut |_, _| {},
+                        &mut |_, _, _| {},
+                    )
+                    .0,
+            },
+        );
+
+        Log::verify(resource_manager.register(
+            model.clone().into_untyped(),
+            path,
+            |_, _| true,
+        ));
+
+        for (handle, node) in scene.graph.pair_iter_mut() {
+            node.set_inheritance_data(handle, model.clone());
+        }
+
+        // Reset modified flags in every inheritable property of the scene.
+        // Except nodes, they're inherited in a separate place.
+        (scene as &mut dyn Reflect).apply_recursively_mut(
+            &mut |object| {
+                let type_id = (*object).type_id();
+                if type_id != TypeId::of::<NodePool>() {
+                    object.as_inheritable_variable_mut(&mut |variable| {
+                        if let Some(variable) = variable {
+                            variable.reset_modified_flag();
+                        }
+                    });
+                }
+            },
+            &[
+                TypeId::of::<UntypedResource>(),
+                TypeId::of::<navmesh::Container>(),
+            ],
+        )
+    } else {
+        // Take scene data from the source scene.
+        if let Some(source_asset) = scene.graph[scene.graph.get_root()].root_resource() {
+            let source_asset_ref = source_asset.data_ref();
+            let source_scene_ref = &source_asset_ref.scene;
+            Log::verify(try_inherit_properties(
+                scene,
+                source_scene_ref,
+                &[
+                    TypeId::of::<NodePool>(),
+                    TypeId::of::<UntypedResource>(),
+                    TypeId::of::<navmesh::Container>(),
+                ],
+            ));
+        }
+    }
+}
+
 /// See module docs.
 pub struct Engine {
     /// Graphics context of the engine. See [`GraphicsContext`] docs for more info.
@@ -432,6 +535,30 @@ pub struct Engine {
     /// Task pool for asynchronous task management.
     pub task_pool: TaskPoolHandler,
 
+    /// Screen overlay with common full-screen effects, such as fading and letterbox bars. See
+    /// [`ScreenOverlay`] docs for more info.
+    pub screen_overlay: ScreenOverlay,
+
+    /// In-game overlay of the built-in frame profiler. See [`ProfilerOverlay`] docs for more info.
+    pub profiler_overlay: ProfilerOverlay,
+
+    /// Gamepad manager, that tracks connected gamepads. See [`GamepadManager`] docs for more info.
+    pub gamepads: GamepadManager,
+
+    /// Input map, that maps physical inputs to named actions and axes. See [`InputMap`] docs for
+    /// more info.
+    pub input: InputMap,
+
+    /// Seedable random number generator, that should be used for every random decision that affects
+    /// the game state. See [`SimulationRng`] docs for more info.
+    pub rng: SimulationRng,
+
+    deterministic_simulation: Option<DeterministicSimulation>,
+
+    simulation_tick: u64,
+
+    replay: ReplayService,
+
     performance_statistics: PerformanceStatistics,
 
     model_events_receiver: Receiver<ResourceEvent>,
@@ -456,6 +583,8 @@ pub struct Engine {
 
     /// Script processor is used to run script methods in a strict order.
     pub script_processor: ScriptProcessor,
+
+    crash_reporter: Option<CrashReporter>,
 }
 
 /// Performs dispatch of script messages.
@@ -651,6 +780,16 @@ impl ScriptProcessor {
         self.scripted_scenes.iter().any(|s| s.handle == scene)
     }
 
+    /// Returns a script message sender of the given scene, if the scene is registered for script
+    /// processing. It could be used to send messages to scripts from plugins or any other code
+    /// outside of scripts. The messages will be delivered on the next update of the scene scripts.
+    pub fn message_sender(&self, scene: Handle<Scene>) -> Option<&ScriptMessageSender> {
+        self.scripted_scenes
+            .iter()
+            .find(|s| s.handle == scene)
+            .map(|s| &s.message_sender)
+    }
+
     fn register_scripted_scene(
         &mut self,
         scene: Handle<Scene>,
@@ -678,6 +817,7 @@ impl ScriptProcessor {
         task_pool: &mut TaskPoolHandler,
         graphics_context: &mut GraphicsContext,
         user_interfaces: &mut UiContainer,
+        rng: &mut SimulationRng,
         dt: f32,
         elapsed_time: f32,
     ) {
@@ -750,6 +890,7 @@ impl ScriptProcessor {
                     task_pool,
                     graphics_context,
                     user_interfaces,
+                    rng,
                     script_index: 0,
                 };
 
@@ -875,6 +1016,7 @@ impl ScriptProcessor {
                 user_interfaces,
                 graphics_context,
                 task_pool,
+                rng,
                 script_index: 0,
             };
             while let Some((handle, mut script, index)) = destruction_queue.pop_front() {
@@ -904,6 +1046,7 @@ impl ScriptProcessor {
                     task_pool,
                     graphics_context,
                     user_interfaces,
+                    rng,
                     script_index: 0,
                 };
 
@@ -1008,6 +1151,10 @@ pub struct GraphicsContextParams {
     /// Amount of samples for MSAA. Must be a power of two (1, 2, 4, 8). `None` means disabled.
     /// MSAA works only for forward rendering and does not work for deferred rendering.
     pub msaa_sample_count: Option<u8>,
+
+    /// Graphics server constructor defines which graphics backend will be used by the renderer.
+    /// See [`GraphicsServerConstructor`] docs for more info.
+    pub graphics_server_constructor: GraphicsServerConstructor,
 }
 
 impl Default for GraphicsContextParams {
@@ -1016,6 +1163,7 @@ impl Default for GraphicsContextParams {
             window_attributes: Default::default(),
             vsync: true,
             msaa_sample_count: None,
+            graphics_server_constructor: Default::default(),
         }
     }
 }
@@ -1114,6 +1262,7 @@ pub(crate) fn process_scripts<T>(
     task_pool: &mut TaskPoolHandler,
     graphics_context: &mut GraphicsContext,
     user_interfaces: &mut UiContainer,
+    rng: &mut SimulationRng,
     dt: f32,
     elapsed_time: f32,
     mut func: T,
@@ -1133,6 +1282,7 @@ pub(crate) fn process_scripts<T>(
         task_pool,
         graphics_context,
         user_interfaces,
+        rng,
         script_index: 0,
     };
 
@@ -1183,6 +1333,7 @@ pub(crate) fn initialize_resource_manager_loaders(
         &*material::STANDARD_TERRAIN,
         &*material::STANDARD_TWOSIDES,
         &*material::STANDARD_PARTICLE_SYSTEM,
+        &*material::STANDARD_GPU_PARTICLE_SYSTEM,
     ] {
         state.built_in_resources.add(material.clone());
     }
@@ -1215,6 +1366,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     loaders.set(model_loader);
     loaders.set(TextureLoader {
         default_import_options: Default::default(),
+        import_cache: None,
     });
     loaders.set(SoundBufferLoader {
         default_import_options: Default::default(),
@@ -1222,6 +1374,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     loaders.set(ShaderLoader);
     loaders.set(CurveLoader);
     loaders.set(HrirSphereLoader);
+    loaders.set(HrirSetLoader);
     loaders.set(MaterialLoader {
         resource_manager: resource_manager.clone(),
     });
@@ -1263,7 +1416,8 @@ impl Engine {
     /// let graphics_context_params = GraphicsContextParams {
     ///     window_attributes,
     ///     vsync: true,
-    ///     msaa_sample_count: None
+    ///     msaa_sample_count: None,
+    ///     graphics_server_constructor: Default::default(),
     /// };
     /// let task_pool = Arc::new(TaskPool::new());
     ///
@@ -1316,6 +1470,15 @@ impl Engine {
             plugins_enabled: false,
             elapsed_time: 0.0,
             task_pool: TaskPoolHandler::new(task_pool),
+            screen_overlay: Default::default(),
+            profiler_overlay: Default::default(),
+            gamepads: Default::default(),
+            input: Default::default(),
+            rng: Default::default(),
+            deterministic_simulation: None,
+            simulation_tick: 0,
+            replay: Default::default(),
+            crash_reporter: None,
         })
     }
 
@@ -1433,6 +1596,7 @@ impl Engine {
                 window_attributes,
                 vsync: params.vsync,
                 msaa_sample_count: params.msaa_sample_count,
+                graphics_server_constructor: params.graphics_server_constructor.clone(),
             });
 
             self.sound_engine.destroy_audio_output_device();
@@ -1462,9 +1626,77 @@ impl Engine {
         self.elapsed_time
     }
 
+    /// Enables deterministic fixed-step simulation mode. The random number generator of the engine
+    /// is re-seeded with the seed from the settings and the simulation tick counter is reset to
+    /// zero. See [`DeterministicSimulation`] docs for more info.
+    pub fn enable_deterministic_simulation(&mut self, settings: DeterministicSimulation) {
+        self.rng.reseed(settings.seed);
+        self.simulation_tick = 0;
+        self.deterministic_simulation = Some(settings);
+    }
+
+    /// Disables deterministic fixed-step simulation mode, the engine will use the time deltas
+    /// passed to the update methods again.
+    pub fn disable_deterministic_simulation(&mut self) {
+        self.deterministic_simulation = None;
+    }
+
+    /// Returns current settings of the deterministic simulation mode, `None` means that the mode
+    /// is disabled.
+    pub fn deterministic_simulation(&self) -> Option<&DeterministicSimulation> {
+        self.deterministic_simulation.as_ref()
+    }
+
+    /// Returns the number of simulation ticks performed since the creation of the engine or since
+    /// the last call of [`Self::enable_deterministic_simulation`]. It could be used to synchronize
+    /// inputs in lockstep networking or to index recorded inputs in replay systems.
+    pub fn simulation_tick(&self) -> u64 {
+        self.simulation_tick
+    }
+
+    /// Starts recording of the input to a replay. The deterministic simulation mode is enabled with
+    /// the given settings, which resets the simulation tick counter and re-seeds the random number
+    /// generator of the engine. See [`Replay`] docs for more info.
+    pub fn start_replay_recording(&mut self, settings: DeterministicSimulation) {
+        self.replay.start_recording(&settings, &mut self.input);
+        self.enable_deterministic_simulation(settings);
+    }
+
+    /// Stops recording of the input and returns the recorded replay. Returns `None` if there was no
+    /// recording. The deterministic simulation mode stays enabled.
+    pub fn stop_replay_recording(&mut self) -> Option<Replay> {
+        self.replay.stop_recording(&mut self.input)
+    }
+
+    /// Starts playback of the replay. The deterministic simulation mode is enabled with the
+    /// settings of the replay and physical input is ignored until the playback is finished. The
+    /// game must be in the same state as it was when the recording has started.
+    pub fn play_replay(&mut self, replay: Replay) {
+        self.enable_deterministic_simulation(replay.simulation_settings());
+        self.replay.start_playback(replay, &mut self.input);
+    }
+
+    /// Stops playback of the current replay (if any).
+    pub fn stop_replay_playback(&mut self) {
+        self.replay.stop_playback(&mut self.input);
+    }
+
+    /// Returns the replay service of the engine, that could be used to check whether the input is
+    /// being recorded or played back.
+    pub fn replay(&self) -> &ReplayService {
+        &self.replay
+    }
+
+    fn simulation_time_step(&self, dt: f32) -> f32 {
+        self.deterministic_simulation
+            .as_ref()
+            .map_or(dt, |settings| settings.time_step)
+    }
+
     /// Performs single update tick with given time delta. Engine internally will perform update
     /// of all scenes, sub-systems, user interface, etc. Must be called in order to get engine
-    /// functioning.
+    /// functioning. If the deterministic simulation mode is enabled, `dt` is ignored and the fixed
+    /// time step from [`DeterministicSimulation`] settings is used instead.
     ///
     /// ## Parameters
     ///
@@ -1482,12 +1714,66 @@ impl Engine {
         lag: &mut f32,
         switches: FxHashMap<Handle<Scene>, GraphUpdateSwitches>,
     ) {
-        self.handle_async_scene_loading(dt, lag, window_target);
+        let dt = self.simulation_time_step(dt);
+        self.handle_async_scene_loading(dt, lag, Some(window_target));
         self.pre_update(dt, window_target, lag, switches);
         self.post_update(dt, &Default::default(), lag, window_target);
         self.handle_plugins_hot_reloading(dt, window_target, lag, |_| {});
     }
 
+    /// Performs single update tick without an event loop. It is meant to be used for dedicated game
+    /// servers and automated tests of gameplay logic, where there's no window and no graphics context.
+    /// Only scenes (animation, physics, sound, etc.), scripts, plugins and async tasks are updated;
+    /// user interfaces and rendering-related parts of the engine are skipped. Plugins will get `None`
+    /// in [`PluginContext::window_target`]. Dynamic plugins hot reloading is not supported in this
+    /// mode.
+    ///
+    /// See [`Self::update`] docs for the meaning of the parameters.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use fyrox_impl::{asset::manager::ResourceManager, engine::{Engine, EngineInitParams}};
+    /// # use fyrox_core::task::TaskPool;
+    /// # use std::sync::Arc;
+    /// let task_pool = Arc::new(TaskPool::new());
+    /// let mut engine = Engine::new(EngineInitParams {
+    ///     // Graphics context is never initialized in headless mode.
+    ///     graphics_context_params: Default::default(),
+    ///     resource_manager: ResourceManager::new(task_pool.clone()),
+    ///     serialization_context: Arc::new(Default::default()),
+    ///     widget_constructors: Arc::new(Default::default()),
+    ///     task_pool,
+    /// })
+    /// .unwrap();
+    ///
+    /// let dt = 1.0 / 60.0;
+    /// let mut lag = 0.0;
+    /// loop {
+    ///     engine.update_headless(dt, &mut lag, Default::default());
+    /// #   break;
+    /// }
+    /// ```
+    pub fn update_headless(
+        &mut self,
+        dt: f32,
+        lag: &mut f32,
+        switches: FxHashMap<Handle<Scene>, GraphUpdateSwitches>,
+    ) {
+        let dt = self.simulation_time_step(dt);
+        self.handle_async_scene_loading(dt, lag, None);
+        self.update_scenes_and_plugins(dt, None, lag, switches);
+
+        self.elapsed_time += dt;
+        self.input.end_update();
+
+        Telemetry::flush();
+
+        // There's no rendering in headless mode, so every tick is a frame for the profiler.
+        Profiler::end_frame();
+        Profiler::begin_frame();
+    }
+
     /// Tries to hot-reload dynamic plugins marked for reloading.
     ///
     /// ## Platform-specific
@@ -1518,7 +1804,7 @@ impl Engine {
         &mut self,
         dt: f32,
         lag: &mut f32,
-        window_target: &EventLoopWindowTarget<()>,
+        window_target: Option<&EventLoopWindowTarget<()>>,
     ) {
         let len = self.async_scene_loader.loading_scenes.len();
         let mut n = 0;
@@ -1543,8 +1829,13 @@ impl Engine {
                             elapsed_time: self.elapsed_time,
                             script_processor: &self.script_processor,
                             async_scene_loader: &mut self.async_scene_loader,
-                            window_target: Some(window_target),
+                            window_target,
                             task_pool: &mut self.task_pool,
+                            screen_overlay: &mut self.screen_overlay,
+                            profiler_overlay: &mut self.profiler_overlay,
+                            gamepads: &mut self.gamepads,
+                            input: &mut self.input,
+                            rng: &mut self.rng,
                         };
 
                         for plugin in self.plugins.iter_mut() {
@@ -1576,79 +1867,23 @@ impl Engine {
                     elapsed_time: self.elapsed_time,
                     script_processor: &self.script_processor,
                     async_scene_loader: &mut self.async_scene_loader,
-                    window_target: Some(window_target),
+                    window_target,
                     task_pool: &mut self.task_pool,
+                    screen_overlay: &mut self.screen_overlay,
+                    profiler_overlay: &mut self.profiler_overlay,
+                    gamepads: &mut self.gamepads,
+                    input: &mut self.input,
+                    rng: &mut self.rng,
                 };
 
                 match loading_result.result {
                     Ok((mut scene, data)) => {
-                        if request.options.derived {
-                            // Create a resource, that will point to the scene we've loaded the
-                            // scene from and force scene nodes to inherit data from them.
-                            let model = Resource::new_ok(
-                                ResourceKind::External(request.path.clone()),
-                                Model {
-                                    mapping: NodeMapping::UseHandles,
-                                    // We have to create a full copy of the scene, because otherwise
-                                    // some methods (`Base::root_resource` in particular) won't work
-                                    // correctly.
-                                    scene: scene
-                                        .clone(
-                                            scene.graph.get_root(),
-                                            &mut |_, _| true,
-                                            &mut |_, _| {},
-                                            &mut |_, _, _| {},
-                                        )
-                                        .0,
-                                },
-                            );
-
-                            Log::verify(self.resource_manager.register(
-                                model.clone().into_untyped(),
-                                request.path.clone(),
-                                |_, _| true,
-                            ));
-
-                            for (handle, node) in scene.graph.pair_iter_mut() {
-                                node.set_inheritance_data(handle, model.clone());
-                            }
-
-                            // Reset modified flags in every inheritable property of the scene.
-                            // Except nodes, they're inherited in a separate place.
-                            (&mut scene as &mut dyn Reflect).apply_recursively_mut(
-                                &mut |object| {
-                                    let type_id = (*object).type_id();
-                                    if type_id != TypeId::of::<NodePool>() {
-                                        object.as_inheritable_variable_mut(&mut |variable| {
-                                            if let Some(variable) = variable {
-                                                variable.reset_modified_flag();
-                                            }
-                                        });
-                                    }
-                                },
-                                &[
-                                    TypeId::of::<UntypedResource>(),
-                                    TypeId::of::<navmesh::Container>(),
-                                ],
-                            )
-                        } else {
-                            // Take scene data from the source scene.
-                            if let Some(source_asset) =
-                                scene.graph[scene.graph.get_root()].root_resource()
-                            {
-                                let source_asset_ref = source_asset.data_ref();
-                                let source_scene_ref = &source_asset_ref.scene;
-                                Log::verify(try_inherit_properties(
-                                    &mut scene,
-                                    source_scene_ref,
-                                    &[
-                                        TypeId::of::<NodePool>(),
-                                        TypeId::of::<UntypedResource>(),
-                                        TypeId::of::<navmesh::Container>(),
-                                    ],
-                                ));
-                            }
-                        }
+                        prepare_loaded_scene(
+                            &mut scene,
+                            &request.path,
+                            request.options.derived,
+                            &self.resource_manager,
+                        );
 
                         let scene_handle = context.scenes.add(scene);
 
@@ -1709,6 +1944,20 @@ impl Engine {
         lag: &mut f32,
         switches: FxHashMap<Handle<Scene>, GraphUpdateSwitches>,
     ) {
+        let dt = self.simulation_time_step(dt);
+        self.update_scenes_and_plugins(dt, Some(window_target), lag, switches);
+    }
+
+    fn update_scenes_and_plugins(
+        &mut self,
+        dt: f32,
+        window_target: Option<&EventLoopWindowTarget<()>>,
+        lag: &mut f32,
+        switches: FxHashMap<Handle<Scene>, GraphUpdateSwitches>,
+    ) {
+        self.replay
+            .process_tick(self.simulation_tick, &mut self.input);
+
         self.resource_manager.state().update(dt);
         self.handle_model_events();
 
@@ -1722,6 +1971,8 @@ impl Engine {
         };
 
         for (handle, scene) in self.scenes.pair_iter_mut().filter(|(_, s)| *s.enabled) {
+            profile_scope!("Scene");
+
             let frame_size =
                 scene
                     .rendering_options
@@ -1744,6 +1995,8 @@ impl Engine {
 
         self.update_plugins(dt, window_target, lag);
         self.handle_scripts(dt);
+
+        self.simulation_tick += 1;
     }
 
     /// Performs post update for the engine.
@@ -1755,8 +2008,16 @@ impl Engine {
             let inner_size = ctx.window.inner_size();
             let window_size = Vector2::new(inner_size.width as f32, inner_size.height as f32);
 
+            self.screen_overlay.update(dt, self.user_interfaces.first_mut());
+            self.profiler_overlay.update(
+                dt,
+                self.user_interfaces.first_mut(),
+                Some(&ctx.renderer.get_statistics()),
+            );
+
             let time = instant::Instant::now();
             for ui in self.user_interfaces.iter_mut() {
+                profile_scope!("UI");
                 ui.update(window_size, dt, ui_update_switches);
             }
             self.performance_statistics.ui_time = instant::Instant::now() - time;
@@ -1764,6 +2025,10 @@ impl Engine {
 
             self.post_update_plugins(dt, window_target, lag);
         }
+
+        self.input.end_update();
+
+        Telemetry::flush();
     }
 
     /// Returns true if the scene is registered for script processing.
@@ -1777,7 +2042,14 @@ impl Engine {
             .register_scripted_scene(scene, &self.resource_manager)
     }
 
+    /// Returns a script message sender of the given scene, if the scene is registered for script
+    /// processing. See [`ScriptProcessor::message_sender`] for more info.
+    pub fn script_message_sender(&self, scene: Handle<Scene>) -> Option<&ScriptMessageSender> {
+        self.script_processor.message_sender(scene)
+    }
+
     fn handle_scripts(&mut self, dt: f32) {
+        profile_scope!("Scripts");
         let time = instant::Instant::now();
 
         self.script_processor.handle_scripts(
@@ -1787,6 +2059,7 @@ impl Engine {
             &mut self.task_pool,
             &mut self.graphics_context,
             &mut self.user_interfaces,
+            &mut self.rng,
             dt,
             self.elapsed_time,
         );
@@ -1797,7 +2070,7 @@ impl Engine {
     fn handle_async_tasks(
         &mut self,
         dt: f32,
-        window_target: &EventLoopWindowTarget<()>,
+        window_target: Option<&EventLoopWindowTarget<()>>,
         lag: &mut f32,
     ) {
         while let Some(result) = self.task_pool.inner().next_task_result() {
@@ -1819,8 +2092,13 @@ impl Engine {
                         elapsed_time: self.elapsed_time,
                         script_processor: &self.script_processor,
                         async_scene_loader: &mut self.async_scene_loader,
-                        window_target: Some(window_target),
+                        window_target,
                         task_pool: &mut self.task_pool,
+                        screen_overlay: &mut self.screen_overlay,
+                        profiler_overlay: &mut self.profiler_overlay,
+                        gamepads: &mut self.gamepads,
+                        input: &mut self.input,
+                        rng: &mut self.rng,
                     },
                 )
             } else if let Some(node_task_handler) = self.task_pool.pop_node_task_handler(result.id)
@@ -1856,6 +2134,7 @@ impl Engine {
                                         task_pool: &mut self.task_pool,
                                         graphics_context: &mut self.graphics_context,
                                         user_interfaces: &mut self.user_interfaces,
+                                        rng: &mut self.rng,
                                         script_index: node_task_handler.script_index,
                                     },
                                 );
@@ -1890,9 +2169,10 @@ impl Engine {
     fn update_plugins(
         &mut self,
         dt: f32,
-        window_target: &EventLoopWindowTarget<()>,
+        window_target: Option<&EventLoopWindowTarget<()>>,
         lag: &mut f32,
     ) {
+        profile_scope!("Plugins");
         let time = instant::Instant::now();
 
         if self.plugins_enabled {
@@ -1913,8 +2193,13 @@ impl Engine {
                 elapsed_time: self.elapsed_time,
                 script_processor: &self.script_processor,
                 async_scene_loader: &mut self.async_scene_loader,
-                window_target: Some(window_target),
+                window_target,
                 task_pool: &mut self.task_pool,
+                screen_overlay: &mut self.screen_overlay,
+                profiler_overlay: &mut self.profiler_overlay,
+                gamepads: &mut self.gamepads,
+                input: &mut self.input,
+                rng: &mut self.rng,
             };
 
             for plugin in self.plugins.iter_mut() {
@@ -1946,8 +2231,13 @@ impl Engine {
                         elapsed_time: self.elapsed_time,
                         script_processor: &self.script_processor,
                         async_scene_loader: &mut self.async_scene_loader,
-                        window_target: Some(window_target),
+                        window_target,
                         task_pool: &mut self.task_pool,
+                        screen_overlay: &mut self.screen_overlay,
+                        profiler_overlay: &mut self.profiler_overlay,
+                        gamepads: &mut self.gamepads,
+                        input: &mut self.input,
+                        rng: &mut self.rng,
                     };
 
                     for plugin in self.plugins.iter_mut() {
@@ -1966,6 +2256,7 @@ impl Engine {
         window_target: &EventLoopWindowTarget<()>,
         lag: &mut f32,
     ) {
+        profile_scope!("Plugins (Post Update)");
         let time = instant::Instant::now();
 
         if self.plugins_enabled {
@@ -1985,6 +2276,11 @@ impl Engine {
                 async_scene_loader: &mut self.async_scene_loader,
                 window_target: Some(window_target),
                 task_pool: &mut self.task_pool,
+                screen_overlay: &mut self.screen_overlay,
+                profiler_overlay: &mut self.profiler_overlay,
+                gamepads: &mut self.gamepads,
+                input: &mut self.input,
+                rng: &mut self.rng,
             };
 
             for plugin in self.plugins.iter_mut() {
@@ -2021,6 +2317,48 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target: Some(window_target),
                         task_pool: &mut self.task_pool,
+                        screen_overlay: &mut self.screen_overlay,
+                        profiler_overlay: &mut self.profiler_overlay,
+                        gamepads: &mut self.gamepads,
+                        input: &mut self.input,
+                        rng: &mut self.rng,
+                    },
+                );
+            }
+        }
+    }
+
+    pub(crate) fn handle_gamepad_event_by_plugins(
+        &mut self,
+        event: &GamepadEvent,
+        dt: f32,
+        window_target: &EventLoopWindowTarget<()>,
+        lag: &mut f32,
+    ) {
+        if self.plugins_enabled {
+            for plugin in self.plugins.iter_mut() {
+                plugin.on_gamepad_event(
+                    event,
+                    PluginContext {
+                        scenes: &mut self.scenes,
+                        resource_manager: &self.resource_manager,
+                        graphics_context: &mut self.graphics_context,
+                        dt,
+                        lag,
+                        user_interfaces: &mut self.user_interfaces,
+                        serialization_context: &self.serialization_context,
+                        widget_constructors: &self.widget_constructors,
+                        performance_statistics: &self.performance_statistics,
+                        elapsed_time: self.elapsed_time,
+                        script_processor: &self.script_processor,
+                        async_scene_loader: &mut self.async_scene_loader,
+                        window_target: Some(window_target),
+                        task_pool: &mut self.task_pool,
+                        screen_overlay: &mut self.screen_overlay,
+                        profiler_overlay: &mut self.profiler_overlay,
+                        gamepads: &mut self.gamepads,
+                        input: &mut self.input,
+                        rng: &mut self.rng,
                     },
                 );
             }
@@ -2050,6 +2388,11 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    screen_overlay: &mut self.screen_overlay,
+                    profiler_overlay: &mut self.profiler_overlay,
+                    gamepads: &mut self.gamepads,
+                    input: &mut self.input,
+                    rng: &mut self.rng,
                 });
             }
         }
@@ -2078,6 +2421,11 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    screen_overlay: &mut self.screen_overlay,
+                    profiler_overlay: &mut self.profiler_overlay,
+                    gamepads: &mut self.gamepads,
+                    input: &mut self.input,
+                    rng: &mut self.rng,
                 });
             }
         }
@@ -2106,6 +2454,11 @@ impl Engine {
                     async_scene_loader: &mut self.async_scene_loader,
                     window_target: Some(window_target),
                     task_pool: &mut self.task_pool,
+                    screen_overlay: &mut self.screen_overlay,
+                    profiler_overlay: &mut self.profiler_overlay,
+                    gamepads: &mut self.gamepads,
+                    input: &mut self.input,
+                    rng: &mut self.rng,
                 });
             }
         }
@@ -2142,6 +2495,7 @@ impl Engine {
                     &mut self.task_pool,
                     &mut self.graphics_context,
                     &mut self.user_interfaces,
+                    &mut self.rng,
                     dt,
                     self.elapsed_time,
                     |script, context| {
@@ -2154,6 +2508,50 @@ impl Engine {
         }
     }
 
+    /// Passes specified gamepad event to every script of the specified scene.
+    ///
+    /// # Important notes
+    ///
+    /// This method is intended to be used by the editor and game runner. If you're using the
+    /// engine as a framework, then you should not call this method because you'll most likely
+    /// do something wrong.
+    pub(crate) fn handle_gamepad_event_by_scripts(
+        &mut self,
+        event: &GamepadEvent,
+        scene_handle: Handle<Scene>,
+        dt: f32,
+    ) {
+        if let Some(scripted_scene) = self
+            .script_processor
+            .scripted_scenes
+            .iter_mut()
+            .find(|s| s.handle == scene_handle)
+        {
+            let scene = &mut self.scenes[scene_handle];
+            if *scene.enabled {
+                process_scripts(
+                    scene,
+                    scene_handle,
+                    &mut self.plugins,
+                    &self.resource_manager,
+                    &scripted_scene.message_sender,
+                    &mut scripted_scene.message_dispatcher,
+                    &mut self.task_pool,
+                    &mut self.graphics_context,
+                    &mut self.user_interfaces,
+                    &mut self.rng,
+                    dt,
+                    self.elapsed_time,
+                    |script, context| {
+                        if script.initialized && script.started {
+                            script.on_gamepad_event(event, context);
+                        }
+                    },
+                )
+            }
+        }
+    }
+
     /// Handle hot-reloading of resources.
     ///
     /// Normally, this is called from `Engine::update()`.
@@ -2188,10 +2586,13 @@ impl Engine {
     #[inline]
     pub fn render(&mut self) -> Result<(), FrameworkError> {
         for ui in self.user_interfaces.iter_mut() {
+            profile_scope!("UI Drawing");
             ui.draw();
         }
 
         if let GraphicsContext::Initialized(ref mut ctx) = self.graphics_context {
+            profile_scope!("Render");
+
             // Process queued messages from scene nodes before rendering, this is mandatory to prevent
             // "teleportation" bug (when an object is drawn at (0,0,0) for one frame and on the one
             // draws where it should be).
@@ -2208,9 +2609,29 @@ impl Engine {
             )?;
         }
 
+        // Rendering is the last stage of a frame, so the profiler starts a new one.
+        Profiler::end_frame();
+        Profiler::begin_frame();
+
+        if let Some(crash_reporter) = self.crash_reporter.as_ref() {
+            crash_reporter.update(self);
+        }
+
         Ok(())
     }
 
+    /// Installs a panic hook that writes a crash report (backtrace, engine and renderer statistics,
+    /// scene summary and recent log lines) to a file when the game panics. The engine state is
+    /// captured once per frame in [`Self::render`]. See [`CrashReporter`] docs for more info.
+    pub fn enable_crash_reporter(&mut self, settings: CrashReporterSettings) {
+        self.crash_reporter = Some(CrashReporter::install(settings));
+    }
+
+    /// Returns a reference to the crash reporter, if it was enabled by [`Self::enable_crash_reporter`].
+    pub fn crash_reporter(&self) -> Option<&CrashReporter> {
+        self.crash_reporter.as_ref()
+    }
+
     /// Enables or disables registered plugins.
     pub(crate) fn enable_plugins(
         &mut self,
@@ -2241,6 +2662,11 @@ impl Engine {
                             async_scene_loader: &mut self.async_scene_loader,
                             window_target,
                             task_pool: &mut self.task_pool,
+                            screen_overlay: &mut self.screen_overlay,
+                            profiler_overlay: &mut self.profiler_overlay,
+                            gamepads: &mut self.gamepads,
+                            input: &mut self.input,
+                            rng: &mut self.rng,
                         },
                     );
                 }
@@ -2264,6 +2690,11 @@ impl Engine {
                         async_scene_loader: &mut self.async_scene_loader,
                         window_target,
                         task_pool: &mut self.task_pool,
+                        screen_overlay: &mut self.screen_overlay,
+                        profiler_overlay: &mut self.profiler_overlay,
+                        gamepads: &mut self.gamepads,
+                        input: &mut self.input,
+                        rng: &mut self.rng,
                     });
                 }
             }
@@ -2570,6 +3001,11 @@ impl Engine {
             async_scene_loader: &mut self.async_scene_loader,
             window_target: Some(window_target),
             task_pool: &mut self.task_pool,
+            screen_overlay: &mut self.screen_overlay,
+            profiler_overlay: &mut self.profiler_overlay,
+            gamepads: &mut self.gamepads,
+            input: &mut self.input,
+            rng: &mut self.rng,
         });
 
         Log::info(format!("Plugin {plugin_index} was successfully reloaded!"));
@@ -2645,8 +3081,8 @@ mod test {
         graph::BaseSceneGraph,
         scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
         script::{
-            ScriptContext, ScriptDeinitContext, ScriptMessageContext, ScriptMessagePayload,
-            ScriptTrait,
+            RoutingStrategy, ScriptContext, ScriptDeinitContext, ScriptMessageContext,
+            ScriptMessagePayload, ScriptTrait,
         },
     };
     use fyrox_ui::UiContainer;
@@ -2846,6 +3282,7 @@ mod test {
                 &mut task_pool,
                 &mut gc,
                 &mut user_interfaces,
+                &mut Default::default(),
                 0.0,
                 0.0,
             );
@@ -3011,6 +3448,7 @@ mod test {
                 &mut task_pool,
                 &mut gc,
                 &mut user_interfaces,
+                &mut Default::default(),
                 0.0,
                 0.0,
             );
@@ -3035,6 +3473,96 @@ mod test {
         }
     }
 
+    #[derive(Debug)]
+    struct Damage(u32);
+
+    #[derive(Debug, Clone, Reflect, Visit, TypeUuidProvider, ComponentProvider)]
+    #[type_uuid(id = "2f5c8e1a-7b3d-4c9e-a1f6-0d4b8e2c7a95")]
+    struct ScriptReceivingDamage {
+        health: u32,
+    }
+
+    impl ScriptTrait for ScriptReceivingDamage {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            ctx.message_dispatcher.subscribe_to::<Damage>(ctx.handle);
+        }
+
+        fn on_message(
+            &mut self,
+            message: &mut dyn ScriptMessagePayload,
+            _ctx: &mut ScriptMessageContext,
+        ) {
+            if let Some(Damage(amount)) = message.downcast_ref::<Damage>() {
+                self.health = self.health.saturating_sub(*amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_messages_from_outside_of_scripts() {
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        let mut scene = Scene::new();
+
+        let child = PivotBuilder::new(
+            BaseBuilder::new().with_script(ScriptReceivingDamage { health: 100 }),
+        )
+        .build(&mut scene.graph);
+        let root = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_script(ScriptReceivingDamage { health: 100 })
+                .with_children(&[child]),
+        )
+        .build(&mut scene.graph);
+        let other = PivotBuilder::new(
+            BaseBuilder::new().with_script(ScriptReceivingDamage { health: 100 }),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        let mut task_pool = TaskPoolHandler::new(Arc::new(TaskPool::new()));
+        let mut gc = GraphicsContext::Uninitialized(Default::default());
+        let mut user_interfaces = UiContainer::default();
+
+        assert!(script_processor.message_sender(scene_handle).is_none());
+
+        script_processor.register_scripted_scene(scene_handle, &resource_manager);
+
+        for iteration in 0..2 {
+            if iteration == 1 {
+                script_processor
+                    .message_sender(scene_handle)
+                    .unwrap()
+                    .send_hierarchical(root, RoutingStrategy::Down, Damage(30));
+            }
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Vec::new(),
+                &resource_manager,
+                &mut task_pool,
+                &mut gc,
+                &mut user_interfaces,
+                &mut Default::default(),
+                0.0,
+                0.0,
+            );
+        }
+
+        let health = |handle: Handle<Node>| {
+            scene_container[scene_handle].graph[handle]
+                .try_get_script::<ScriptReceivingDamage>()
+                .unwrap()
+                .health
+        };
+        assert_eq!(health(root), 70);
+        assert_eq!(health(child), 70);
+        assert_eq!(health(other), 100);
+    }
+
     #[derive(Clone, Debug, PartialEq, Reflect, Visit, TypeUuidProvider, ComponentProvider)]
     #[type_uuid(id = "7bcbf9b4-9546-42d3-965a-de055ab85475")]
     pub struct ScriptSpawningAsyncTasks {
@@ -3274,6 +3802,7 @@ mod test {
                 &mut task_pool,
                 &mut gc,
                 &mut user_interfaces,
+                &mut Default::default(),
                 0.0,
                 0.0,
             );
@@ -3352,4 +3881,92 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_headless_update() {
+        use crate::{
+            engine::{Engine, EngineInitParams},
+            scene::{collider::ColliderBuilder, rigidbody::RigidBodyBuilder},
+        };
+
+        let task_pool = Arc::new(TaskPool::default());
+        let mut engine = Engine::new(EngineInitParams {
+            graphics_context_params: Default::default(),
+            serialization_context: Arc::new(Default::default()),
+            widget_constructors: Arc::new(Default::default()),
+            resource_manager: ResourceManager::new(task_pool.clone()),
+            task_pool,
+        })
+        .unwrap();
+        engine.enable_plugins(None, true, None);
+
+        let mut scene = Scene::new();
+        let collider = ColliderBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .build(&mut scene.graph);
+        let scene_handle = engine.scenes.add(scene);
+
+        let dt = 1.0 / 60.0;
+        let mut lag = 0.0;
+        for _ in 0..60 {
+            engine.update_headless(dt, &mut lag, Default::default());
+        }
+
+        assert!(matches!(
+            engine.graphics_context,
+            GraphicsContext::Uninitialized(_)
+        ));
+        assert!((engine.elapsed_time() - 1.0).abs() < 0.001);
+        // The body must fall down under gravity, since physics is updated in the headless mode.
+        assert!(engine.scenes[scene_handle].graph[body].global_position().y < -1.0);
+    }
+
+    #[test]
+    fn test_deterministic_simulation() {
+        use crate::{
+            core::{algebra::Vector3, rand::Rng},
+            engine::{simulation::DeterministicSimulation, Engine, EngineInitParams},
+            scene::{collider::ColliderBuilder, rigidbody::RigidBodyBuilder},
+        };
+
+        let simulate = || {
+            let task_pool = Arc::new(TaskPool::default());
+            let mut engine = Engine::new(EngineInitParams {
+                graphics_context_params: Default::default(),
+                serialization_context: Arc::new(Default::default()),
+                widget_constructors: Arc::new(Default::default()),
+                resource_manager: ResourceManager::new(task_pool.clone()),
+                task_pool,
+            })
+            .unwrap();
+            engine.enable_deterministic_simulation(DeterministicSimulation {
+                time_step: 1.0 / 60.0,
+                seed: 42,
+            });
+
+            let mut scene = Scene::new();
+            let collider = ColliderBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+            let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+                .with_lin_vel(Vector3::new(
+                    engine.rng.gen_range(-5.0..5.0),
+                    engine.rng.gen_range(0.0..5.0),
+                    engine.rng.gen_range(-5.0..5.0),
+                ))
+                .build(&mut scene.graph);
+            let scene_handle = engine.scenes.add(scene);
+
+            let mut lag = 0.0;
+            for _ in 0..60 {
+                // The time delta must be ignored in the deterministic mode.
+                engine.update_headless(0.5, &mut lag, Default::default());
+            }
+
+            assert_eq!(engine.simulation_tick(), 60);
+            assert!((engine.elapsed_time() - 1.0).abs() < 0.001);
+
+            engine.scenes[scene_handle].graph[body].global_position()
+        };
+
+        assert_eq!(simulate(), simulate());
+    }
 }
@@ -0,0 +1,442 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Screen overlay is a tiny service that provides common full-screen presentation effects, such as
+//! fading to/from a color and letterbox bars for cutscenes. See [`ScreenOverlay`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector2, color::Color, pool::Handle},
+    graph::BaseSceneGraph,
+    gui::{
+        border::BorderBuilder, brush::Brush, message::MessageDirection, widget::WidgetBuilder,
+        widget::WidgetMessage, BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface,
+        VerticalAlignment,
+    },
+};
+use std::collections::VecDeque;
+
+/// An event that is produced by [`ScreenOverlay`] when an effect is finished.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScreenOverlayEvent {
+    /// A fade (either to or from a color) was finished.
+    FadeFinished,
+    /// Letterbox bars reached their target size.
+    LetterboxFinished,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Transition {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Transition {
+    fn value(&self) -> f32 {
+        // Finished transition must end exactly at the target value, interpolation is not precise.
+        if self.is_finished() {
+            self.to
+        } else {
+            let t = (self.elapsed / self.duration).clamp(0.0, 1.0);
+            self.from + (self.to - self.from) * t
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+struct OverlayWidgets {
+    root: Handle<UiNode>,
+    fade: Handle<UiNode>,
+    top_bar: Handle<UiNode>,
+    bottom_bar: Handle<UiNode>,
+}
+
+fn make_bar(ctx: &mut BuildContext, vertical_alignment: VerticalAlignment) -> Handle<UiNode> {
+    BorderBuilder::new(
+        WidgetBuilder::new()
+            .with_hit_test_visibility(false)
+            .with_height(0.0)
+            .with_vertical_alignment(vertical_alignment)
+            .with_horizontal_alignment(HorizontalAlignment::Stretch)
+            .with_background(Brush::Solid(Color::BLACK)),
+    )
+    .with_stroke_thickness(Thickness::uniform(0.0))
+    .build(ctx)
+}
+
+impl OverlayWidgets {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let top_bar = make_bar(ctx, VerticalAlignment::Top);
+        let bottom_bar = make_bar(ctx, VerticalAlignment::Bottom);
+        let fade = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_background(Brush::Solid(Color::TRANSPARENT)),
+        )
+        .with_stroke_thickness(Thickness::uniform(0.0))
+        .build(ctx);
+        let root = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_background(Brush::Solid(Color::TRANSPARENT))
+                .with_child(top_bar)
+                .with_child(bottom_bar)
+                .with_child(fade),
+        )
+        .with_stroke_thickness(Thickness::uniform(0.0))
+        .build(ctx);
+
+        Self {
+            root,
+            fade,
+            top_bar,
+            bottom_bar,
+        }
+    }
+
+    fn is_valid(&self, ui: &UserInterface) -> bool {
+        [self.root, self.fade, self.top_bar, self.bottom_bar]
+            .into_iter()
+            .all(|handle| ui.try_get(handle).is_some())
+    }
+}
+
+/// Screen overlay is a tiny service that provides common full-screen presentation effects without a
+/// need to create custom full-screen widgets in every project. It supports:
+///
+/// - Fading to and from a color with the given duration. Fade covers everything in the first user
+/// interface of the engine, including scenes.
+/// - Letterbox bars for cutscenes. The size of each bar is defined as a fraction of the screen height
+/// and could be changed smoothly.
+///
+/// Every effect produces a [`ScreenOverlayEvent`] when it is finished, use [`Self::pop_event`] to
+/// fetch them. The overlay widgets are created on demand in the first user interface of the engine,
+/// they never block mouse input and they're hidden when no effect is active.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{core::color::Color, engine::overlay::ScreenOverlayEvent, plugin::PluginContext};
+/// fn start_cutscene(ctx: &mut PluginContext) {
+///     // Cover 12% of the screen by each bar in one second.
+///     ctx.screen_overlay.set_letterbox(0.12, 1.0);
+/// }
+///
+/// fn change_level(ctx: &mut PluginContext) {
+///     ctx.screen_overlay.fade_to(Color::BLACK, 0.5);
+/// }
+///
+/// fn update(ctx: &mut PluginContext) {
+///     while let Some(event) = ctx.screen_overlay.pop_event() {
+///         if event == ScreenOverlayEvent::FadeFinished && ctx.screen_overlay.fade_amount() == 1.0 {
+///             // The screen is fully covered, it is safe to switch levels here and then reveal the
+///             // new level.
+///             ctx.screen_overlay.fade_from(Color::BLACK, 0.5);
+///         }
+///     }
+/// }
+/// ```
+pub struct ScreenOverlay {
+    fade_color: Color,
+    fade_amount: f32,
+    fade_transition: Option<Transition>,
+    letterbox: f32,
+    letterbox_transition: Option<Transition>,
+    events: VecDeque<ScreenOverlayEvent>,
+    widgets: Option<OverlayWidgets>,
+    raise: bool,
+}
+
+impl Default for ScreenOverlay {
+    fn default() -> Self {
+        Self {
+            fade_color: Color::BLACK,
+            fade_amount: 0.0,
+            fade_transition: None,
+            letterbox: 0.0,
+            letterbox_transition: None,
+            events: Default::default(),
+            widgets: None,
+            raise: false,
+        }
+    }
+}
+
+impl ScreenOverlay {
+    /// Starts fading the screen to the given color. The fade starts from the current fade amount and
+    /// reaches full coverage in `duration` seconds. Zero duration covers the screen immediately.
+    pub fn fade_to(&mut self, color: Color, duration: f32) {
+        self.fade_color = color;
+        self.start_fade(self.fade_amount, 1.0, duration);
+    }
+
+    /// Starts fading the screen from the given color. The screen is fully covered with the color at
+    /// first and becomes fully visible in `duration` seconds.
+    pub fn fade_from(&mut self, color: Color, duration: f32) {
+        self.fade_color = color;
+        self.start_fade(1.0, 0.0, duration);
+    }
+
+    fn start_fade(&mut self, from: f32, to: f32, duration: f32) {
+        self.fade_amount = from;
+        self.fade_transition = Some(Transition {
+            from,
+            to,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+        });
+        self.raise = true;
+    }
+
+    /// Returns current fade amount in `[0; 1]` range, where 0 means that the screen is fully visible
+    /// and 1 means that the screen is fully covered with the fade color.
+    pub fn fade_amount(&self) -> f32 {
+        self.fade_amount
+    }
+
+    /// Returns current fade color.
+    pub fn fade_color(&self) -> Color {
+        self.fade_color
+    }
+
+    /// Returns `true` if there's a fade in progress.
+    pub fn is_fading(&self) -> bool {
+        self.fade_transition.is_some()
+    }
+
+    /// Smoothly changes the size of letterbox bars in `duration` seconds. The size of each bar is
+    /// defined as a fraction of the screen height and is clamped to `[0; 0.5]` range. Use zero size
+    /// to remove the bars.
+    pub fn set_letterbox(&mut self, size: f32, duration: f32) {
+        self.letterbox_transition = Some(Transition {
+            from: self.letterbox,
+            to: size.clamp(0.0, 0.5),
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+        });
+        self.raise = true;
+    }
+
+    /// Returns current size of each letterbox bar as a fraction of the screen height.
+    pub fn letterbox(&self) -> f32 {
+        self.letterbox
+    }
+
+    /// Returns `true` if letterbox bars are changing their size.
+    pub fn is_letterbox_changing(&self) -> bool {
+        self.letterbox_transition.is_some()
+    }
+
+    /// Pops the oldest overlay event.
+    pub fn pop_event(&mut self) -> Option<ScreenOverlayEvent> {
+        self.events.pop_front()
+    }
+
+    fn update_transitions(&mut self, dt: f32) {
+        if let Some(transition) = self.fade_transition.as_mut() {
+            transition.elapsed += dt;
+            self.fade_amount = transition.value();
+            if transition.is_finished() {
+                self.fade_transition = None;
+                self.events.push_back(ScreenOverlayEvent::FadeFinished);
+            }
+        }
+
+        if let Some(transition) = self.letterbox_transition.as_mut() {
+            transition.elapsed += dt;
+            self.letterbox = transition.value();
+            if transition.is_finished() {
+                self.letterbox_transition = None;
+                self.events.push_back(ScreenOverlayEvent::LetterboxFinished);
+            }
+        }
+    }
+
+    /// Advances all active effects and synchronizes the overlay widgets with the given user
+    /// interface. This method is called automatically by the engine.
+    pub fn update(&mut self, dt: f32, ui: &mut UserInterface) {
+        self.update_transitions(dt);
+
+        let is_active = self.fade_amount > 0.0 || self.letterbox > 0.0;
+
+        if self.widgets.as_ref().is_some_and(|w| !w.is_valid(ui)) {
+            // The user interface was cleared.
+            self.widgets = None;
+        }
+
+        if self.widgets.is_none() {
+            if !is_active {
+                return;
+            }
+            self.widgets = Some(OverlayWidgets::new(&mut ui.build_ctx()));
+            self.raise = true;
+        }
+
+        let raise = std::mem::take(&mut self.raise);
+        let Some(widgets) = self.widgets.as_ref() else {
+            return;
+        };
+
+        let screen_size = ui.screen_size();
+        let bar_height = self.letterbox * screen_size.y;
+        let fade_color = Color {
+            a: (self.fade_color.a as f32 * self.fade_amount).round() as u8,
+            ..self.fade_color
+        };
+
+        let root = ui.node(widgets.root);
+        let root_needs_resize = root.width() != screen_size.x || root.height() != screen_size.y;
+        let needs_visibility_change = root.visibility() != is_active;
+        let top_bar_needs_resize = ui.node(widgets.top_bar).height() != bar_height;
+        let fade_needs_update = ui.node(widgets.fade).background() != Brush::Solid(fade_color);
+
+        if root_needs_resize {
+            resize(ui, widgets.root, screen_size);
+        }
+        if needs_visibility_change {
+            ui.send_message(WidgetMessage::visibility(
+                widgets.root,
+                MessageDirection::ToWidget,
+                is_active,
+            ));
+        }
+        if top_bar_needs_resize {
+            for bar in [widgets.top_bar, widgets.bottom_bar] {
+                ui.send_message(WidgetMessage::height(
+                    bar,
+                    MessageDirection::ToWidget,
+                    bar_height,
+                ));
+            }
+        }
+        if fade_needs_update {
+            ui.send_message(WidgetMessage::background(
+                widgets.fade,
+                MessageDirection::ToWidget,
+                Brush::Solid(fade_color),
+            ));
+        }
+        if raise {
+            // Keep the overlay on top of everything else, including widgets that were added after
+            // the overlay was created.
+            ui.send_message(WidgetMessage::topmost(
+                widgets.root,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+}
+
+fn resize(ui: &UserInterface, widget: Handle<UiNode>, size: Vector2<f32>) {
+    ui.send_message(WidgetMessage::width(
+        widget,
+        MessageDirection::ToWidget,
+        size.x,
+    ));
+    ui.send_message(WidgetMessage::height(
+        widget,
+        MessageDirection::ToWidget,
+        size.y,
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, color::Color},
+        engine::overlay::{ScreenOverlay, ScreenOverlayEvent},
+        graph::BaseSceneGraph,
+        gui::{brush::Brush, UserInterface},
+    };
+
+    fn update(overlay: &mut ScreenOverlay, ui: &mut UserInterface, dt: f32) {
+        overlay.update(dt, ui);
+        while ui.poll_message().is_some() {}
+    }
+
+    #[test]
+    fn test_fade() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let mut overlay = ScreenOverlay::default();
+
+        // Nothing should be created until an effect is used.
+        update(&mut overlay, &mut ui, 0.1);
+        assert!(overlay.widgets.is_none());
+
+        overlay.fade_to(Color::BLACK, 1.0);
+        update(&mut overlay, &mut ui, 0.5);
+        assert_eq!(overlay.fade_amount(), 0.5);
+        assert!(overlay.is_fading());
+        assert_eq!(overlay.pop_event(), None);
+
+        let widgets = overlay.widgets.as_ref().unwrap();
+        assert!(ui.node(widgets.root).visibility());
+        assert_eq!(ui.node(widgets.root).width(), 100.0);
+        assert_eq!(
+            ui.node(widgets.fade).background(),
+            Brush::Solid(Color::from_rgba(0, 0, 0, 128))
+        );
+
+        update(&mut overlay, &mut ui, 0.6);
+        assert_eq!(overlay.fade_amount(), 1.0);
+        assert!(!overlay.is_fading());
+        assert_eq!(overlay.pop_event(), Some(ScreenOverlayEvent::FadeFinished));
+        assert_eq!(overlay.pop_event(), None);
+
+        overlay.fade_from(Color::BLACK, 0.0);
+        update(&mut overlay, &mut ui, 0.1);
+        assert_eq!(overlay.fade_amount(), 0.0);
+        assert_eq!(overlay.pop_event(), Some(ScreenOverlayEvent::FadeFinished));
+
+        let widgets = overlay.widgets.as_ref().unwrap();
+        assert!(!ui.node(widgets.root).visibility());
+    }
+
+    #[test]
+    fn test_letterbox() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 200.0));
+        let mut overlay = ScreenOverlay::default();
+
+        overlay.set_letterbox(1.0, 1.0);
+        update(&mut overlay, &mut ui, 2.0);
+        // Clamped to a half of the screen.
+        assert_eq!(overlay.letterbox(), 0.5);
+        assert_eq!(
+            overlay.pop_event(),
+            Some(ScreenOverlayEvent::LetterboxFinished)
+        );
+
+        overlay.set_letterbox(0.1, 1.0);
+        update(&mut overlay, &mut ui, 1.0);
+        assert_eq!(overlay.letterbox(), 0.1);
+
+        let widgets = overlay.widgets.as_ref().unwrap();
+        assert_eq!(ui.node(widgets.top_bar).height(), 20.0);
+        assert_eq!(ui.node(widgets.bottom_bar).height(), 20.0);
+        // Fade must not cover the screen.
+        assert_eq!(
+            ui.node(widgets.fade).background(),
+            Brush::Solid(Color::TRANSPARENT)
+        );
+    }
+}
@@ -0,0 +1,408 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Profiler overlay shows the results of the built-in frame profiler on top of everything else in
+//! the game. See [`ProfilerOverlay`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Vector2,
+        color::Color,
+        pool::Handle,
+        profiler::{FrameProfile, Profiler},
+    },
+    graph::BaseSceneGraph,
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        canvas::CanvasBuilder,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        BuildContext, HorizontalAlignment, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+    renderer::Statistics,
+};
+use std::{cmp::Reverse, fmt::Write, time::Duration};
+
+/// Width of the bars area in pixels, it corresponds to the duration of a frame.
+const BARS_WIDTH: f32 = 300.0;
+/// Height of a single bar in pixels.
+const BAR_HEIGHT: f32 = 6.0;
+/// Maximum amount of bars shown at once, the shortest scopes are not shown.
+const MAX_BARS: usize = 64;
+/// Colors of the bars at the respective nesting level.
+const BAR_COLORS: [Color; 4] = [
+    Color::opaque(230, 120, 60),
+    Color::opaque(80, 170, 230),
+    Color::opaque(120, 200, 90),
+    Color::opaque(200, 110, 200),
+];
+
+struct OverlayWidgets {
+    root: Handle<UiNode>,
+    text: Handle<UiNode>,
+    bars_canvas: Handle<UiNode>,
+    bars: Vec<Handle<UiNode>>,
+}
+
+impl OverlayWidgets {
+    fn new(ctx: &mut BuildContext) -> Self {
+        let text = TextBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_foreground(Brush::Solid(Color::WHITE)),
+        )
+        .with_font_size(13.0)
+        .build(ctx);
+        let bars_canvas = CanvasBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::top(4.0))
+                .with_width(BARS_WIDTH),
+        )
+        .build(ctx);
+        let root = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_hit_test_visibility(false)
+                .with_margin(Thickness::uniform(8.0))
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 180)))
+                .with_child(
+                    StackPanelBuilder::new(
+                        WidgetBuilder::new()
+                            .with_hit_test_visibility(false)
+                            .with_margin(Thickness::uniform(6.0))
+                            .with_child(text)
+                            .with_child(bars_canvas),
+                    )
+                    .build(ctx),
+                ),
+        )
+        .with_stroke_thickness(Thickness::uniform(0.0))
+        .build(ctx);
+
+        Self {
+            root,
+            text,
+            bars_canvas,
+            bars: Default::default(),
+        }
+    }
+
+    fn is_valid(&self, ui: &UserInterface) -> bool {
+        [self.root, self.text, self.bars_canvas]
+            .into_iter()
+            .all(|handle| ui.try_get(handle).is_some())
+    }
+
+    fn sync(
+        &mut self,
+        ui: &mut UserInterface,
+        frame: &FrameProfile,
+        statistics: Option<&Statistics>,
+    ) {
+        ui.send_message(TextMessage::text(
+            self.text,
+            MessageDirection::ToWidget,
+            make_report(frame, statistics),
+        ));
+
+        for bar in self.bars.drain(..) {
+            ui.send_message(WidgetMessage::remove(bar, MessageDirection::ToWidget));
+        }
+
+        let frame_time = frame.duration.as_secs_f32();
+        if frame_time <= 0.0 {
+            return;
+        }
+
+        let mut samples = frame.samples.iter().collect::<Vec<_>>();
+        samples.sort_by_key(|sample| Reverse(sample.duration));
+        samples.truncate(MAX_BARS);
+
+        let mut max_depth = 0;
+        for sample in samples {
+            let x = sample.start.as_secs_f32() / frame_time * BARS_WIDTH;
+            let width = (sample.duration.as_secs_f32() / frame_time * BARS_WIDTH).max(1.0);
+            max_depth = max_depth.max(sample.depth);
+            let bar = BorderBuilder::new(
+                WidgetBuilder::new()
+                    .with_hit_test_visibility(false)
+                    .with_desired_position(Vector2::new(x, sample.depth as f32 * BAR_HEIGHT))
+                    .with_width(width.min(BARS_WIDTH - x).max(0.0))
+                    .with_height(BAR_HEIGHT - 1.0)
+                    .with_background(Brush::Solid(BAR_COLORS[sample.depth % BAR_COLORS.len()])),
+            )
+            .with_stroke_thickness(Thickness::uniform(0.0))
+            .build(&mut ui.build_ctx());
+            ui.send_message(WidgetMessage::link(
+                bar,
+                MessageDirection::ToWidget,
+                self.bars_canvas,
+            ));
+            self.bars.push(bar);
+        }
+
+        ui.send_message(WidgetMessage::height(
+            self.bars_canvas,
+            MessageDirection::ToWidget,
+            (max_depth + 1) as f32 * BAR_HEIGHT,
+        ));
+    }
+}
+
+fn ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+fn make_report(frame: &FrameProfile, statistics: Option<&Statistics>) -> String {
+    let mut report = String::new();
+
+    let _ = writeln!(report, "Frame: {:.2} ms", ms(frame.duration));
+    if let Some(statistics) = statistics {
+        let _ = writeln!(
+            report,
+            "FPS: {}, draw calls: {}, triangles: {}",
+            statistics.frames_per_second,
+            statistics.geometry.draw_calls,
+            statistics.geometry.triangles_rendered
+        );
+    }
+
+    // Scopes with the same name at the same nesting level (for example, a scope in a loop) are
+    // merged together.
+    let mut scopes: Vec<(&str, usize, Duration)> = Vec::new();
+    for sample in frame.samples.iter() {
+        match scopes
+            .iter_mut()
+            .find(|(name, depth, _)| *name == sample.name && *depth == sample.depth)
+        {
+            Some((_, _, duration)) => *duration += sample.duration,
+            None => scopes.push((sample.name, sample.depth, sample.duration)),
+        }
+    }
+    if !scopes.is_empty() {
+        let _ = writeln!(report, "CPU:");
+        for (name, depth, duration) in scopes {
+            let indent = "  ".repeat(depth + 1);
+            let _ = writeln!(report, "{indent}{name}: {:.2} ms", ms(duration));
+        }
+    }
+
+    if !frame.render_passes.is_empty() {
+        let _ = writeln!(report, "GPU:");
+        for pass in frame.render_passes.iter() {
+            let time = pass
+                .gpu_time
+                .map_or_else(|| "n/a".to_string(), |time| format!("{:.2} ms", ms(time)));
+            let _ = writeln!(
+                report,
+                "  {}: {time}, {} draw calls, {} triangles",
+                pass.name, pass.draw_calls, pass.triangles
+            );
+        }
+    }
+
+    report.trim_end().to_string()
+}
+
+/// Profiler overlay is an in-game panel, that shows the results of the built-in frame profiler (see
+/// [`crate::core::profiler`] module docs). It shows frame time, CPU time of every profiled scope,
+/// GPU time with the amount of draw calls and triangles of every render pass and a timeline of the
+/// frame, where each bar is a profiled scope.
+///
+/// The overlay is hidden by default, showing the overlay enables the profiler and hiding it
+/// disables the profiler. The results are refreshed a few times per second to keep them readable.
+/// The overlay widgets are created on demand in the first user interface of the engine and they
+/// never block mouse input.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{profile_scope, profiler::Profiler},
+/// #     event::{ElementState, Event, WindowEvent},
+/// #     keyboard::{KeyCode, PhysicalKey},
+/// #     plugin::PluginContext,
+/// # };
+/// fn on_os_event(event: &Event<()>, ctx: &mut PluginContext) {
+///     if let Event::WindowEvent {
+///         event: WindowEvent::KeyboardInput { event, .. },
+///         ..
+///     } = event
+///     {
+///         if event.state == ElementState::Pressed
+///             && event.physical_key == PhysicalKey::Code(KeyCode::F3)
+///         {
+///             ctx.profiler_overlay.toggle();
+///         }
+///     }
+/// }
+///
+/// fn update_ai() {
+///     // The time spent in this function will be shown in the overlay.
+///     profile_scope!("AI");
+///
+///     // ...
+/// }
+/// ```
+pub struct ProfilerOverlay {
+    visible: bool,
+    refresh_interval: f32,
+    refresh_timer: f32,
+    widgets: Option<OverlayWidgets>,
+    raise: bool,
+}
+
+impl Default for ProfilerOverlay {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            refresh_interval: 0.25,
+            refresh_timer: 0.0,
+            widgets: None,
+            raise: false,
+        }
+    }
+}
+
+impl ProfilerOverlay {
+    /// Shows or hides the overlay. It also enables or disables the profiler.
+    pub fn set_visible(&mut self, visible: bool) {
+        if self.visible != visible {
+            self.visible = visible;
+            self.refresh_timer = 0.0;
+            self.raise = visible;
+            Profiler::set_enabled(visible);
+        }
+    }
+
+    /// Toggles visibility of the overlay. See [`Self::set_visible`] for more info.
+    pub fn toggle(&mut self) {
+        self.set_visible(!self.visible);
+    }
+
+    /// Returns `true` if the overlay is visible.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Sets how often (in seconds) the results are refreshed. Default is 0.25 seconds.
+    pub fn set_refresh_interval(&mut self, interval: f32) {
+        self.refresh_interval = interval.max(0.0);
+    }
+
+    /// Returns how often (in seconds) the results are refreshed.
+    pub fn refresh_interval(&self) -> f32 {
+        self.refresh_interval
+    }
+
+    /// Synchronizes the overlay widgets with the latest frame profile. This method is called
+    /// automatically by the engine.
+    pub fn update(&mut self, dt: f32, ui: &mut UserInterface, statistics: Option<&Statistics>) {
+        if self.widgets.as_ref().is_some_and(|w| !w.is_valid(ui)) {
+            // The user interface was cleared.
+            self.widgets = None;
+        }
+
+        if self.widgets.is_none() {
+            if !self.visible {
+                return;
+            }
+            self.widgets = Some(OverlayWidgets::new(&mut ui.build_ctx()));
+            self.raise = true;
+        }
+
+        let Some(widgets) = self.widgets.as_mut() else {
+            return;
+        };
+
+        if ui.node(widgets.root).visibility() != self.visible {
+            ui.send_message(WidgetMessage::visibility(
+                widgets.root,
+                MessageDirection::ToWidget,
+                self.visible,
+            ));
+        }
+
+        if self.visible {
+            self.refresh_timer -= dt;
+            if self.refresh_timer <= 0.0 {
+                self.refresh_timer = self.refresh_interval;
+                widgets.sync(ui, &Profiler::last_frame(), statistics);
+            }
+        }
+
+        if std::mem::take(&mut self.raise) {
+            ui.send_message(WidgetMessage::topmost(
+                widgets.root,
+                MessageDirection::ToWidget,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, profile_scope, profiler::Profiler},
+        engine::profiler::ProfilerOverlay,
+        graph::BaseSceneGraph,
+        gui::{text::Text, UserInterface},
+    };
+
+    #[test]
+    fn test_profiler_overlay() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let mut overlay = ProfilerOverlay::default();
+
+        // Nothing should be created until the overlay is shown.
+        overlay.update(0.1, &mut ui, None);
+        assert!(overlay.widgets.is_none());
+
+        overlay.set_visible(true);
+        assert!(Profiler::is_enabled());
+
+        Profiler::begin_frame();
+        {
+            profile_scope!("Overlay Test");
+        }
+        Profiler::end_frame();
+
+        overlay.update(0.1, &mut ui, None);
+        while ui.poll_message().is_some() {}
+
+        let widgets = overlay.widgets.as_ref().unwrap();
+        assert!(ui.node(widgets.root).visibility());
+        let text = ui.node(widgets.text).cast::<Text>().unwrap().text();
+        assert!(text.starts_with("Frame:"));
+        assert!(text.contains("Overlay Test"));
+        assert_eq!(widgets.bars.len(), 1);
+
+        overlay.set_visible(false);
+        assert!(!Profiler::is_enabled());
+        overlay.update(0.1, &mut ui, None);
+        while ui.poll_message().is_some() {}
+        assert!(!ui.node(overlay.widgets.as_ref().unwrap().root).visibility());
+    }
+}
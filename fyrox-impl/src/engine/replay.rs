@@ -0,0 +1,291 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Replay recording and playback. See [`Replay`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        log::Log,
+        visitor::{prelude::*, Visitor},
+    },
+    engine::{
+        input::{InputEvent, InputMap},
+        simulation::DeterministicSimulation,
+    },
+};
+use std::path::Path;
+
+/// Input events applied at a simulation tick.
+#[derive(Clone, Debug, Default, PartialEq, Visit)]
+pub struct ReplayFrame {
+    /// Index of the simulation tick.
+    pub tick: u64,
+    /// Input events applied right before the tick.
+    pub events: Vec<InputEvent>,
+}
+
+/// A recording of the input of a game, that could be played back deterministically. It is useful
+/// for bug reproduction and demo recording.
+///
+/// The engine records the events applied to its [`InputMap`] at every simulation tick while the
+/// [deterministic simulation mode](DeterministicSimulation) is enabled, so the playback repeats
+/// the recorded session exactly, as long as the game:
+///
+/// - Starts the recording and the playback from the same state (for example, right after loading
+///   the same level).
+/// - Reads the input only via [`InputMap`] (raw OS events are not recorded).
+/// - Uses [`crate::engine::simulation::SimulationRng`] for every random decision.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_impl::engine::{replay::Replay, simulation::DeterministicSimulation, Engine};
+/// fn record(engine: &mut Engine) {
+///     engine.start_replay_recording(DeterministicSimulation::default());
+/// }
+///
+/// fn save(engine: &mut Engine) {
+///     if let Some(mut replay) = engine.stop_replay_recording() {
+///         replay.save("demo.replay").unwrap();
+///     }
+/// }
+///
+/// async fn play(engine: &mut Engine) {
+///     let replay = Replay::load("demo.replay").await.unwrap();
+///     engine.play_replay(replay);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit)]
+pub struct Replay {
+    /// Time step of the simulation.
+    pub time_step: f32,
+    /// Initial seed of the random number generator of the engine.
+    pub seed: u64,
+    /// Total number of recorded simulation ticks.
+    pub length: u64,
+    /// Frames with input events, sorted by the tick index. Ticks without any input are omitted.
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Returns the settings of the deterministic simulation mode used for the recording.
+    pub fn simulation_settings(&self) -> DeterministicSimulation {
+        DeterministicSimulation {
+            time_step: self.time_step,
+            seed: self.seed,
+        }
+    }
+
+    /// Saves the replay to a file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.visit("Replay", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+
+    /// Loads a replay from a file.
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, VisitError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut replay = Self::default();
+        replay.visit("Replay", &mut visitor)?;
+        Ok(replay)
+    }
+}
+
+/// Current state of the replay service.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ReplayState {
+    /// Nothing is recorded or played.
+    #[default]
+    Idle,
+    /// The input is being recorded.
+    Recording(Replay),
+    /// The replay is being played back.
+    Playing {
+        /// The replay.
+        replay: Replay,
+        /// Index of the next frame to play.
+        position: usize,
+    },
+}
+
+/// Records input events to a [`Replay`] and plays them back. The engine owns an instance of the
+/// service, use [`crate::engine::Engine::start_replay_recording`] and
+/// [`crate::engine::Engine::play_replay`] to control it.
+#[derive(Default, Debug)]
+pub struct ReplayService {
+    state: ReplayState,
+}
+
+impl ReplayService {
+    /// Returns current state of the service.
+    pub fn state(&self) -> &ReplayState {
+        &self.state
+    }
+
+    /// Returns `true` if the input is being recorded.
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, ReplayState::Recording(_))
+    }
+
+    /// Returns `true` if a replay is being played back.
+    pub fn is_playing(&self) -> bool {
+        matches!(self.state, ReplayState::Playing { .. })
+    }
+
+    /// Starts recording of the input. Every pressed input is released first, so the recording
+    /// always starts from the same input state. Any active recording or playback is discarded.
+    pub fn start_recording(&mut self, settings: &DeterministicSimulation, input: &mut InputMap) {
+        self.stop_playback(input);
+        input.release_all();
+        input.set_recording(true);
+        self.state = ReplayState::Recording(Replay {
+            time_step: settings.time_step,
+            seed: settings.seed,
+            length: 0,
+            frames: Default::default(),
+        });
+    }
+
+    /// Stops recording of the input and returns the recorded replay.
+    pub fn stop_recording(&mut self, input: &mut InputMap) -> Option<Replay> {
+        if let ReplayState::Recording(replay) = std::mem::take(&mut self.state) {
+            input.set_recording(false);
+            Some(replay)
+        } else {
+            None
+        }
+    }
+
+    /// Starts playback of the replay. Physical input is ignored until the playback is finished or
+    /// stopped. Any active recording or playback is discarded.
+    pub fn start_playback(&mut self, replay: Replay, input: &mut InputMap) {
+        self.stop_recording(input);
+        input.release_all();
+        input.set_ignore_physical_input(true);
+        self.state = ReplayState::Playing {
+            replay,
+            position: 0,
+        };
+    }
+
+    /// Stops playback of the replay.
+    pub fn stop_playback(&mut self, input: &mut InputMap) {
+        if self.is_playing() {
+            self.state = ReplayState::Idle;
+            input.release_all();
+            input.set_ignore_physical_input(false);
+        }
+    }
+
+    /// Records or plays back the input of the given simulation tick. The engine calls this method
+    /// right before every simulation tick, there's no need to call it manually.
+    pub fn process_tick(&mut self, tick: u64, input: &mut InputMap) {
+        match &mut self.state {
+            ReplayState::Idle => (),
+            ReplayState::Recording(replay) => {
+                let events = input.take_recorded_events();
+                if !events.is_empty() {
+                    replay.frames.push(ReplayFrame { tick, events });
+                }
+                replay.length = tick + 1;
+            }
+            ReplayState::Playing { replay, position } => {
+                if tick >= replay.length {
+                    Log::info("Replay playback is finished.");
+                    self.stop_playback(input);
+                    return;
+                }
+
+                while let Some(frame) = replay.frames.get(*position) {
+                    if frame.tick > tick {
+                        break;
+                    }
+                    if frame.tick == tick {
+                        for event in frame.events.iter() {
+                            input.apply_event(*event);
+                        }
+                    }
+                    *position += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::{prelude::*, Visitor},
+        engine::{
+            input::{InputBinding, InputEvent, InputMap},
+            replay::{Replay, ReplayService},
+            simulation::DeterministicSimulation,
+        },
+        gui::message::KeyCode,
+    };
+
+    fn run(service: &mut ReplayService, input: &mut InputMap, ticks: u64) -> Vec<bool> {
+        let key = InputBinding::Key(KeyCode::Space);
+        (0..ticks)
+            .map(|tick| {
+                // Emulates physical input, that is ignored during the playback.
+                if tick % 3 == 0 && !input.is_ignoring_physical_input() {
+                    input.apply_event(InputEvent::Pressed(key));
+                } else if tick % 3 == 1 && !input.is_ignoring_physical_input() {
+                    input.apply_event(InputEvent::Released(key));
+                }
+                service.process_tick(tick, input);
+                let pressed = input.is_pressed(key);
+                input.end_update();
+                pressed
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_recording_and_playback() {
+        let mut input = InputMap::new();
+        let mut service = ReplayService::default();
+
+        service.start_recording(&DeterministicSimulation::default(), &mut input);
+        let recorded = run(&mut service, &mut input, 10);
+        let mut replay = service.stop_recording(&mut input).unwrap();
+        assert_eq!(replay.length, 10);
+        assert_eq!(replay.frames.len(), 7);
+
+        let mut visitor = Visitor::new();
+        replay.visit("Replay", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut loaded = Replay::default();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        loaded.visit("Replay", &mut visitor).unwrap();
+        assert_eq!(loaded, replay);
+
+        service.start_playback(loaded, &mut input);
+        assert!(service.is_playing());
+        let played = run(&mut service, &mut input, 11);
+        assert_eq!(&played[..10], recorded.as_slice());
+        assert!(!service.is_playing());
+        assert!(!input.is_ignoring_physical_input());
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Deterministic fixed-step simulation mode. See [`DeterministicSimulation`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::core::rand::{self, prelude::StdRng, Error, Rng, RngCore, SeedableRng};
+
+/// Settings of the deterministic simulation mode of the engine. When the mode is enabled (see
+/// [`crate::engine::Engine::enable_deterministic_simulation`]), the engine:
+///
+/// - Updates scenes (physics, animation, sound, etc.), scripts and plugins with the fixed time step,
+///   ignoring the time delta passed to the update methods. The executor also stops "fast-forwarding"
+///   the game logic when it lags behind real time.
+/// - Re-seeds [`SimulationRng`] with the given seed and resets the simulation tick counter.
+///
+/// Scenes and scripts are always updated in the order of their handles, so the game logic that
+/// takes every random decision from [`SimulationRng`] will behave the same way in two runs with
+/// the same seed, the same input and the same initial state. This is the requirement for lockstep
+/// networking and replay systems. This mode does **not** make everything reproducible: particle
+/// systems and some other parts of the engine use their own random number generators, so visual
+/// effects may differ between runs and must not affect the simulation state. Results of
+/// asynchronous tasks are delivered in order of their completion, so they should not affect the
+/// simulation state directly either. Also, floating-point math may differ across platforms; enable
+/// the `enhanced_determinism` feature of the engine to make physics cross-platform deterministic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeterministicSimulation {
+    /// Time step (in seconds) of every simulation tick. Default is `1.0 / 60.0`.
+    pub time_step: f32,
+    /// Initial seed of the random number generator. Default is `0`.
+    pub seed: u64,
+}
+
+impl Default for DeterministicSimulation {
+    fn default() -> Self {
+        Self {
+            time_step: 1.0 / 60.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Seedable random number generator of the engine. Use it instead of `thread_rng` for every random
+/// decision that affects the game state, so the simulation could be reproduced by using the same
+/// seed. It implements [`RngCore`], so all the methods of [`Rng`] (such as `gen_range`) are
+/// available for it.
+///
+/// ```rust
+/// # use fyrox_impl::{engine::simulation::SimulationRng, rand::Rng};
+/// let mut a = SimulationRng::new(123);
+/// let mut b = SimulationRng::new(123);
+/// assert_eq!(a.gen_range(0..100), b.gen_range(0..100));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SimulationRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Default for SimulationRng {
+    /// Creates a random number generator with a random seed.
+    fn default() -> Self {
+        Self::new(rand::thread_rng().gen())
+    }
+}
+
+impl SimulationRng {
+    /// Creates a new random number generator with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the seed, that was used to initialize the generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Resets the generator to the initial state defined by the given seed.
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+}
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{core::rand::Rng, engine::simulation::SimulationRng};
+
+    #[test]
+    fn test_simulation_rng_reseed() {
+        let mut rng = SimulationRng::new(42);
+        let first = (0..16).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+
+        rng.reseed(42);
+        let second = (0..16).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+
+        assert_eq!(rng.seed(), 42);
+        assert_eq!(first, second);
+    }
+}
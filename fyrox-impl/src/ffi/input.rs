@@ -0,0 +1,239 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Input injection functions of the C interface. Injected input is applied to the input map of
+//! the engine (see [`crate::engine::input::InputMap`]) the same way as physical input, so the game
+//! logic can't tell the difference.
+
+use crate::{
+    core::algebra::Vector2,
+    engine::{
+        gamepad::{GamepadAxis, GamepadButton},
+        input::{InputBinding, InputEvent},
+    },
+    ffi::{call, engine_mut, str_arg, FfiError, FyroxEngine, FyroxResult},
+    gui::message::{KeyCode, MouseButton},
+};
+use std::{ffi::c_char, str::FromStr};
+
+fn mouse_button(index: u32) -> MouseButton {
+    match index {
+        0 => MouseButton::Left,
+        1 => MouseButton::Right,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Back,
+        4 => MouseButton::Forward,
+        _ => MouseButton::Other(index.min(u16::MAX as u32) as u16),
+    }
+}
+
+fn gamepad_button(index: u32) -> Option<GamepadButton> {
+    Some(match index {
+        0 => GamepadButton::South,
+        1 => GamepadButton::East,
+        2 => GamepadButton::North,
+        3 => GamepadButton::West,
+        4 => GamepadButton::LeftTrigger,
+        5 => GamepadButton::LeftTrigger2,
+        6 => GamepadButton::RightTrigger,
+        7 => GamepadButton::RightTrigger2,
+        8 => GamepadButton::Select,
+        9 => GamepadButton::Start,
+        10 => GamepadButton::Mode,
+        11 => GamepadButton::LeftThumb,
+        12 => GamepadButton::RightThumb,
+        13 => GamepadButton::DPadUp,
+        14 => GamepadButton::DPadDown,
+        15 => GamepadButton::DPadLeft,
+        16 => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+fn gamepad_axis(index: u32) -> Option<GamepadAxis> {
+    Some(match index {
+        0 => GamepadAxis::LeftStickX,
+        1 => GamepadAxis::LeftStickY,
+        2 => GamepadAxis::RightStickX,
+        3 => GamepadAxis::RightStickY,
+        4 => GamepadAxis::LeftZ,
+        5 => GamepadAxis::RightZ,
+        _ => return None,
+    })
+}
+
+unsafe fn apply_binding(
+    engine: *mut FyroxEngine,
+    binding: InputBinding,
+    pressed: bool,
+) -> Result<(), FfiError> {
+    let event = if pressed {
+        InputEvent::Pressed(binding)
+    } else {
+        InputEvent::Released(binding)
+    };
+    engine_mut(engine)?.input.apply_event(event);
+    Ok(())
+}
+
+/// Presses or releases a key. The key is specified by its name, that matches the name of
+/// [`KeyCode`] variant (for example, `"KeyW"`, `"Space"` or `"ArrowLeft"`).
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_key(
+    engine: *mut FyroxEngine,
+    key: *const c_char,
+    pressed: bool,
+) -> FyroxResult {
+    call(|| {
+        let name = str_arg(key)?;
+        let key = KeyCode::from_str(name).map_err(|_| {
+            FfiError::new(FyroxResult::InvalidArgument, format!("Unknown key {name}."))
+        })?;
+        apply_binding(engine, InputBinding::Key(key), pressed)
+    })
+}
+
+/// Presses or releases a mouse button. Buttons are numbered as follows: 0 - left, 1 - right,
+/// 2 - middle, 3 - back, 4 - forward, any other number is passed as is.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_mouse_button(
+    engine: *mut FyroxEngine,
+    button: u32,
+    pressed: bool,
+) -> FyroxResult {
+    call(|| {
+        apply_binding(
+            engine,
+            InputBinding::MouseButton(mouse_button(button)),
+            pressed,
+        )
+    })
+}
+
+/// Moves the mouse by the given delta.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_mouse_motion(
+    engine: *mut FyroxEngine,
+    dx: f32,
+    dy: f32,
+) -> FyroxResult {
+    call(|| {
+        engine_mut(engine)?
+            .input
+            .apply_event(InputEvent::MouseMotion(Vector2::new(dx, dy)));
+        Ok(())
+    })
+}
+
+/// Presses or releases a gamepad button. Buttons are numbered in the order of [`GamepadButton`]
+/// variants (0 - south, 1 - east, ..., 16 - right button of the directional pad).
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_gamepad_button(
+    engine: *mut FyroxEngine,
+    button: u32,
+    pressed: bool,
+) -> FyroxResult {
+    call(|| {
+        let button = gamepad_button(button).ok_or_else(|| {
+            FfiError::new(
+                FyroxResult::InvalidArgument,
+                format!("Unknown gamepad button {button}."),
+            )
+        })?;
+        apply_binding(engine, InputBinding::GamepadButton(button), pressed)
+    })
+}
+
+/// Sets value of a gamepad axis. Axes are numbered in the order of [`GamepadAxis`] variants
+/// (0 - horizontal axis of the left stick, ..., 5 - right trigger).
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_gamepad_axis(
+    engine: *mut FyroxEngine,
+    axis: u32,
+    value: f32,
+) -> FyroxResult {
+    call(|| {
+        let axis = gamepad_axis(axis).ok_or_else(|| {
+            FfiError::new(
+                FyroxResult::InvalidArgument,
+                format!("Unknown gamepad axis {axis}."),
+            )
+        })?;
+        engine_mut(engine)?
+            .input
+            .apply_event(InputEvent::GamepadAxisMotion { axis, value });
+        Ok(())
+    })
+}
+
+/// Releases every pressed input.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_release_all(engine: *mut FyroxEngine) -> FyroxResult {
+    call(|| {
+        engine_mut(engine)?.input.release_all();
+        Ok(())
+    })
+}
+
+/// Returns `true` if the action with the given name is pressed. Returns `false` if the arguments
+/// are invalid.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_is_action_pressed(
+    engine: *const FyroxEngine,
+    action: *const c_char,
+) -> bool {
+    match (engine.as_ref(), str_arg(action)) {
+        (Some(engine), Ok(action)) => engine.input.is_action_pressed(action),
+        _ => false,
+    }
+}
+
+/// Returns value of the axis with the given name. Returns zero if the arguments are invalid.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_input_axis_value(
+    engine: *const FyroxEngine,
+    axis: *const c_char,
+) -> f32 {
+    match (engine.as_ref(), str_arg(axis)) {
+        (Some(engine), Ok(axis)) => engine.input.axis_value(axis),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        engine::gamepad::{GamepadAxis, GamepadButton},
+        ffi::input::{gamepad_axis, gamepad_button, mouse_button},
+        gui::message::MouseButton,
+    };
+
+    #[test]
+    fn test_input_index_mapping() {
+        assert_eq!(mouse_button(0), MouseButton::Left);
+        assert_eq!(mouse_button(4), MouseButton::Forward);
+        assert_eq!(mouse_button(7), MouseButton::Other(7));
+        assert_eq!(gamepad_button(0), Some(GamepadButton::South));
+        assert_eq!(gamepad_button(16), Some(GamepadButton::DPadRight));
+        assert_eq!(gamepad_button(17), None);
+        assert_eq!(gamepad_axis(5), Some(GamepadAxis::RightZ));
+        assert_eq!(gamepad_axis(6), None);
+    }
+}
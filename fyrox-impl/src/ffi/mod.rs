@@ -0,0 +1,595 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Optional C-compatible interface of the engine (enabled by `ffi` feature). It allows to embed the
+//! engine into other runtimes or to script it from other languages (C, C#, Lua, etc.) using their
+//! FFI bindings. The interface covers the core functionality of the engine:
+//!
+//! - Engine creation, updating and rendering (see [`fyrox_engine_create`], [`fyrox_engine_run`]).
+//! - Scene loading and node transform access (see [`scene`] module).
+//! - Input injection (see [`input`] module).
+//!
+//! To use the interface, create a `cdylib` crate, that depends on the engine with `ffi` feature
+//! enabled, every function of the interface will be exported from the library. C declarations of
+//! the interface could be found in `include/fyrox.h` file of `fyrox-impl` crate.
+//!
+//! Every function, that could fail, returns [`FyroxResult`]. A human-readable description of the
+//! last error on the current thread could be fetched using [`fyrox_last_error_message`]. Panics
+//! never cross the interface boundary, they are converted to [`FyroxResult::Panic`].
+//!
+//! ## Safety
+//!
+//! Every pointer passed to the interface must be either null or valid. Strings must be
+//! null-terminated. The engine is not thread-safe, it must be used from the thread it was created
+//! on.
+//!
+//! ## Example
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include "fyrox.h"
+//!
+//! static void update(FyroxEngine *engine, float dt, void *user_data) {
+//!     if (fyrox_input_is_action_pressed(engine, "Jump")) {
+//!         // ...
+//!     }
+//! }
+//!
+//! int main() {
+//!     FyroxEngine *engine = fyrox_engine_create();
+//!     FyroxHandle scene;
+//!     if (fyrox_scene_load(engine, "data/scene.rgs", &scene) != FYROX_RESULT_OK) {
+//!         printf("%s\n", fyrox_last_error_message());
+//!     }
+//!     // The engine is destroyed when the window is closed.
+//!     return fyrox_engine_run(engine, "My Game", update, NULL);
+//! }
+//! ```
+
+#![allow(clippy::missing_safety_doc)]
+
+use crate::{
+    asset::manager::ResourceManager,
+    core::{
+        algebra::{Quaternion, UnitQuaternion, Vector3},
+        instant::Instant,
+        log::Log,
+        pool::Handle,
+        task::TaskPool,
+    },
+    engine::{Engine, EngineInitParams, GraphicsContext, SerializationContext},
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    gui::constructor::WidgetConstructorContainer,
+    utils::translate_event,
+};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_void, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+    sync::Arc,
+};
+
+pub mod input;
+pub mod scene;
+
+/// An opaque engine instance.
+pub type FyroxEngine = Engine;
+
+/// A callback, that is called on every update tick of [`fyrox_engine_run`], right before the
+/// engine update.
+pub type FyroxUpdateCallback =
+    extern "C" fn(engine: *mut FyroxEngine, dt: f32, user_data: *mut c_void);
+
+/// A result of every function, that could fail.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FyroxResult {
+    /// The function has succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// A handle of a scene or a node was invalid.
+    InvalidHandle = 2,
+    /// An argument was invalid (for example, a string is not valid UTF-8).
+    InvalidArgument = 3,
+    /// Unable to load a resource.
+    LoadFailed = 4,
+    /// Unable to initialize the engine, to create a window or to render a frame.
+    RenderFailed = 5,
+    /// A panic has occurred inside the engine.
+    Panic = 6,
+}
+
+/// A handle of a scene or a node.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FyroxHandle {
+    /// Index of the object.
+    pub index: u32,
+    /// Generation of the object.
+    pub generation: u32,
+}
+
+impl<T> From<Handle<T>> for FyroxHandle {
+    fn from(handle: Handle<T>) -> Self {
+        Self {
+            index: handle.index(),
+            generation: handle.generation(),
+        }
+    }
+}
+
+impl FyroxHandle {
+    pub(crate) fn to_handle<T>(self) -> Handle<T> {
+        Handle::new(self.index, self.generation)
+    }
+}
+
+/// A three-dimensional vector.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FyroxVector3 {
+    /// X component.
+    pub x: f32,
+    /// Y component.
+    pub y: f32,
+    /// Z component.
+    pub z: f32,
+}
+
+impl From<Vector3<f32>> for FyroxVector3 {
+    fn from(v: Vector3<f32>) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<FyroxVector3> for Vector3<f32> {
+    fn from(v: FyroxVector3) -> Self {
+        Vector3::new(v.x, v.y, v.z)
+    }
+}
+
+/// A rotation quaternion.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FyroxQuaternion {
+    /// X component of the vector part.
+    pub x: f32,
+    /// Y component of the vector part.
+    pub y: f32,
+    /// Z component of the vector part.
+    pub z: f32,
+    /// Scalar part.
+    pub w: f32,
+}
+
+impl From<UnitQuaternion<f32>> for FyroxQuaternion {
+    fn from(q: UnitQuaternion<f32>) -> Self {
+        Self {
+            x: q.i,
+            y: q.j,
+            z: q.k,
+            w: q.w,
+        }
+    }
+}
+
+impl From<FyroxQuaternion> for UnitQuaternion<f32> {
+    fn from(q: FyroxQuaternion) -> Self {
+        UnitQuaternion::from_quaternion(Quaternion::new(q.w, q.x, q.y, q.z))
+    }
+}
+
+pub(crate) struct FfiError {
+    result: FyroxResult,
+    message: String,
+}
+
+impl FfiError {
+    pub(crate) fn new<S: Into<String>>(result: FyroxResult, message: S) -> Self {
+        Self {
+            result,
+            message: message.into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    // Engines, that are currently owned by `fyrox_engine_run` on this thread.
+    static RUNNING_ENGINES: RefCell<Vec<*const FyroxEngine>> = const { RefCell::new(Vec::new()) };
+}
+
+fn is_running(engine: *const FyroxEngine) -> bool {
+    RUNNING_ENGINES.with(|engines| engines.borrow().contains(&engine))
+}
+
+// Marks the engine as running while alive, so it can't be destroyed or run again from the update
+// callback.
+struct RunningEngineGuard(*const FyroxEngine);
+
+impl RunningEngineGuard {
+    fn new(engine: *const FyroxEngine) -> Self {
+        RUNNING_ENGINES.with(|engines| engines.borrow_mut().push(engine));
+        Self(engine)
+    }
+}
+
+impl Drop for RunningEngineGuard {
+    fn drop(&mut self) {
+        RUNNING_ENGINES.with(|engines| engines.borrow_mut().retain(|engine| *engine != self.0));
+    }
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|error| {
+        *error.borrow_mut() = CString::new(message.replace('\0', " ")).ok();
+    });
+}
+
+/// Runs the given function, converts its errors and panics into [`FyroxResult`].
+pub(crate) fn call<F>(func: F) -> FyroxResult
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    match catch_unwind(AssertUnwindSafe(func)) {
+        Ok(Ok(())) => FyroxResult::Ok,
+        Ok(Err(error)) => {
+            set_last_error(error.message);
+            error.result
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic.".to_string());
+            set_last_error(message);
+            FyroxResult::Panic
+        }
+    }
+}
+
+pub(crate) unsafe fn engine_mut<'a>(engine: *mut FyroxEngine) -> Result<&'a mut Engine, FfiError> {
+    engine
+        .as_mut()
+        .ok_or_else(|| FfiError::new(FyroxResult::NullPointer, "Engine pointer is null."))
+}
+
+pub(crate) unsafe fn str_arg<'a>(string: *const c_char) -> Result<&'a str, FfiError> {
+    if string.is_null() {
+        return Err(FfiError::new(
+            FyroxResult::NullPointer,
+            "String pointer is null.",
+        ));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| FfiError::new(FyroxResult::InvalidArgument, "String is not valid UTF-8."))
+}
+
+pub(crate) unsafe fn write_out<T>(out: *mut T, value: T) -> Result<(), FfiError> {
+    if out.is_null() {
+        Err(FfiError::new(
+            FyroxResult::NullPointer,
+            "Output pointer is null.",
+        ))
+    } else {
+        out.write(value);
+        Ok(())
+    }
+}
+
+/// Returns a description of the last error on the current thread or null if there was no errors.
+/// The string is valid until the next failed call on the same thread.
+#[no_mangle]
+pub extern "C" fn fyrox_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Creates a new engine instance. Graphics context is not initialized until the engine is run by
+/// [`fyrox_engine_run`], so an engine, that is never run, could be used in headless mode (for
+/// example, on a game server). Returns null on failure. The engine must be destroyed by
+/// [`fyrox_engine_destroy`], unless it was run by [`fyrox_engine_run`].
+#[no_mangle]
+pub extern "C" fn fyrox_engine_create() -> *mut FyroxEngine {
+    let mut engine = ptr::null_mut();
+    call(|| {
+        let task_pool = Arc::new(TaskPool::new());
+        let instance = Engine::new(EngineInitParams {
+            graphics_context_params: Default::default(),
+            resource_manager: ResourceManager::new(task_pool.clone()),
+            serialization_context: Arc::new(SerializationContext::new()),
+            task_pool,
+            widget_constructors: Arc::new(WidgetConstructorContainer::new()),
+        })
+        .map_err(|e| FfiError::new(FyroxResult::RenderFailed, e.to_string()))?;
+        engine = Box::into_raw(Box::new(instance));
+        Ok(())
+    });
+    engine
+}
+
+/// Destroys the engine. Null pointer is ignored. An engine, that was passed to
+/// [`fyrox_engine_run`], is owned by that function and can't be destroyed, even from its update
+/// callback - [`FyroxResult::InvalidArgument`] is returned in this case and the engine stays alive.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_engine_destroy(engine: *mut FyroxEngine) -> FyroxResult {
+    call(|| {
+        if engine.is_null() {
+            return Ok(());
+        }
+        if is_running(engine) {
+            return Err(FfiError::new(
+                FyroxResult::InvalidArgument,
+                "The engine is owned by fyrox_engine_run and can't be destroyed.",
+            ));
+        }
+        drop(Box::from_raw(engine));
+        Ok(())
+    })
+}
+
+/// Performs a single update tick of the engine in headless mode (without a window). See
+/// [`Engine::update_headless`] docs for more info.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_engine_update(engine: *mut FyroxEngine, dt: f32) -> FyroxResult {
+    call(|| {
+        let engine = engine_mut(engine)?;
+        engine.update_headless(dt, &mut 0.0, Default::default());
+        Ok(())
+    })
+}
+
+/// Renders a frame. Does nothing if the graphics context is not initialized.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_engine_render(engine: *mut FyroxEngine) -> FyroxResult {
+    call(|| {
+        engine_mut(engine)?
+            .render()
+            .map_err(|e| FfiError::new(FyroxResult::RenderFailed, format!("{e:?}")))
+    })
+}
+
+/// Returns the total amount of time (in seconds), that the engine was updated for.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_engine_elapsed_time(engine: *const FyroxEngine) -> f32 {
+    engine.as_ref().map_or(0.0, |engine| engine.elapsed_time())
+}
+
+/// Creates a window with the given title (could be null) and runs the engine until the window is
+/// closed. The game logic is updated 60 times per second, the optional `update` callback is called
+/// on every update tick with the given user data. This function takes ownership of the engine (even
+/// if it fails), the engine is destroyed when this function returns, so the engine pointer must
+/// not be used after the call.
+///
+/// The engine can't be destroyed or run again from inside the `update` callback, such calls fail
+/// with [`FyroxResult::InvalidArgument`]. Close the window instead.
+#[cfg(not(target_arch = "wasm32"))]
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_engine_run(
+    engine: *mut FyroxEngine,
+    title: *const c_char,
+    update: Option<FyroxUpdateCallback>,
+    user_data: *mut c_void,
+) -> FyroxResult {
+    call(|| {
+        engine_mut(engine)?;
+        if is_running(engine) {
+            return Err(FfiError::new(
+                FyroxResult::InvalidArgument,
+                "The engine is already running.",
+            ));
+        }
+        let _guard = RunningEngineGuard::new(engine);
+        let mut engine = Box::from_raw(engine);
+        let title = if title.is_null() {
+            None
+        } else {
+            Some(str_arg(title)?.to_string())
+        };
+        if let (Some(title), GraphicsContext::Uninitialized(params)) =
+            (title, &mut engine.graphics_context)
+        {
+            params.window_attributes.title = title;
+        }
+
+        let event_loop = EventLoop::new()
+            .map_err(|e| FfiError::new(FyroxResult::RenderFailed, format!("{e:?}")))?;
+
+        let time_step = 1.0 / 60.0;
+        let mut previous = Instant::now();
+        let mut lag = 0.0;
+
+        event_loop
+            .run(move |event, window_target| {
+                window_target.set_control_flow(ControlFlow::Wait);
+
+                engine.input.process_os_event(&event);
+
+                match event {
+                    Event::Resumed => {
+                        if let Err(e) = engine.initialize_graphics_context(window_target) {
+                            Log::err(format!("Unable to initialize graphics context: {e:?}"));
+                            window_target.exit();
+                        }
+                    }
+                    Event::Suspended => {
+                        Log::verify(engine.destroy_graphics_context());
+                    }
+                    Event::AboutToWait => {
+                        lag += previous.elapsed().as_secs_f32();
+                        previous = Instant::now();
+
+                        for gamepad_event in engine.gamepads.poll() {
+                            engine.input.process_gamepad_event(&gamepad_event);
+                        }
+
+                        while lag >= time_step {
+                            lag -= time_step;
+                            if let Some(update) = update {
+                                update(&mut *engine, time_step, user_data);
+                            }
+                            engine.update(time_step, window_target, &mut lag, Default::default());
+                        }
+
+                        if let GraphicsContext::Initialized(ref ctx) = engine.graphics_context {
+                            ctx.window.request_redraw();
+                        }
+                    }
+                    Event::WindowEvent { event, .. } => {
+                        match event {
+                            WindowEvent::CloseRequested => window_target.exit(),
+                            WindowEvent::Resized(size) => {
+                                Log::verify(engine.set_frame_size(size.into()));
+                            }
+                            WindowEvent::RedrawRequested => {
+                                Log::verify(engine.render());
+                            }
+                            _ => (),
+                        }
+
+                        if let Some(os_event) = translate_event(&event) {
+                            for ui in engine.user_interfaces.iter_mut() {
+                                ui.process_os_event(&os_event);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            })
+            .map_err(|e| FfiError::new(FyroxResult::RenderFailed, format!("{e:?}")))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ffi::{
+        call, fyrox_engine_create, fyrox_engine_destroy, fyrox_engine_elapsed_time,
+        fyrox_engine_render, fyrox_engine_run, fyrox_engine_update, fyrox_last_error_message,
+        is_running, FfiError, FyroxResult, RunningEngineGuard,
+    };
+    use std::{
+        ffi::{CStr, CString},
+        ptr,
+    };
+
+    fn last_error_message() -> String {
+        let message = fyrox_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_call_converts_errors_and_panics() {
+        assert_eq!(call(|| Ok(())), FyroxResult::Ok);
+
+        assert_eq!(
+            call(|| Err(FfiError::new(FyroxResult::InvalidArgument, "Bad argument."))),
+            FyroxResult::InvalidArgument
+        );
+        assert_eq!(last_error_message(), "Bad argument.");
+
+        assert_eq!(call(|| panic!("Something went wrong.")), FyroxResult::Panic);
+        assert_eq!(last_error_message(), "Something went wrong.");
+    }
+
+    #[test]
+    fn test_null_engine() {
+        unsafe {
+            assert_eq!(
+                fyrox_engine_update(ptr::null_mut(), 1.0),
+                FyroxResult::NullPointer
+            );
+            assert_eq!(last_error_message(), "Engine pointer is null.");
+            assert_eq!(
+                fyrox_engine_render(ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+            assert_eq!(fyrox_engine_elapsed_time(ptr::null()), 0.0);
+            assert_eq!(fyrox_engine_destroy(ptr::null_mut()), FyroxResult::Ok);
+            assert_eq!(
+                fyrox_engine_run(ptr::null_mut(), ptr::null(), None, ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+        }
+    }
+
+    #[test]
+    fn test_engine_lifecycle() {
+        let engine = fyrox_engine_create();
+        assert!(!engine.is_null());
+        unsafe {
+            assert_eq!(fyrox_engine_elapsed_time(engine), 0.0);
+            assert_eq!(fyrox_engine_update(engine, 1.0 / 60.0), FyroxResult::Ok);
+            assert!(fyrox_engine_elapsed_time(engine) > 0.0);
+            // Graphics context is not initialized in headless mode, so there's nothing to render.
+            assert_eq!(fyrox_engine_render(engine), FyroxResult::Ok);
+            assert_eq!(fyrox_engine_destroy(engine), FyroxResult::Ok);
+        }
+    }
+
+    #[test]
+    fn test_running_engine_cannot_be_destroyed() {
+        let engine = fyrox_engine_create();
+        unsafe {
+            let guard = RunningEngineGuard::new(engine);
+            assert!(is_running(engine));
+            assert_eq!(fyrox_engine_destroy(engine), FyroxResult::InvalidArgument);
+            assert_eq!(
+                fyrox_engine_run(engine, ptr::null(), None, ptr::null_mut()),
+                FyroxResult::InvalidArgument
+            );
+            // The engine must stay alive.
+            assert_eq!(fyrox_engine_update(engine, 1.0 / 60.0), FyroxResult::Ok);
+
+            drop(guard);
+            assert!(!is_running(engine));
+            assert_eq!(fyrox_engine_destroy(engine), FyroxResult::Ok);
+        }
+    }
+
+    #[test]
+    fn test_run_takes_ownership_on_failure() {
+        let engine = fyrox_engine_create();
+        let invalid_title = CString::new(vec![0xFF]).unwrap();
+        unsafe {
+            // The engine is destroyed by the run function, even if it fails.
+            assert_eq!(
+                fyrox_engine_run(engine, invalid_title.as_ptr(), None, ptr::null_mut()),
+                FyroxResult::InvalidArgument
+            );
+        }
+        assert!(!is_running(engine));
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Scene management and node transform access functions of the C interface.
+
+use crate::{
+    core::{algebra::Vector3, futures::executor::block_on, pool::Handle},
+    engine::{load_scene, prepare_loaded_scene, Engine},
+    ffi::{
+        call, engine_mut, str_arg, write_out, FfiError, FyroxEngine, FyroxHandle, FyroxQuaternion,
+        FyroxResult, FyroxVector3,
+    },
+    graph::BaseSceneGraph,
+    scene::{node::Node, Scene},
+};
+use std::{ffi::c_char, path::PathBuf};
+
+fn node_mut(
+    engine: &mut Engine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+) -> Result<&mut Node, FfiError> {
+    engine
+        .scenes
+        .try_get_mut(scene.to_handle())
+        .ok_or_else(|| FfiError::new(FyroxResult::InvalidHandle, "Invalid scene handle."))?
+        .graph
+        .try_get_mut(node.to_handle())
+        .ok_or_else(|| FfiError::new(FyroxResult::InvalidHandle, "Invalid node handle."))
+}
+
+unsafe fn with_node<F>(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    func: F,
+) -> FyroxResult
+where
+    F: FnOnce(&mut Node) -> Result<(), FfiError>,
+{
+    call(|| func(node_mut(engine_mut(engine)?, scene, node)?))
+}
+
+/// Creates a new empty scene and writes its handle to `out_scene`.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_scene_create(
+    engine: *mut FyroxEngine,
+    out_scene: *mut FyroxHandle,
+) -> FyroxResult {
+    call(|| {
+        let engine = engine_mut(engine)?;
+        let handle = engine.scenes.add(Scene::new());
+        write_out(out_scene, handle.into())
+    })
+}
+
+/// Loads a scene from the given path (blocking the current thread until the scene and all its
+/// resources are loaded) and writes its handle to `out_scene`.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_scene_load(
+    engine: *mut FyroxEngine,
+    path: *const c_char,
+    out_scene: *mut FyroxHandle,
+) -> FyroxResult {
+    call(|| {
+        let engine = engine_mut(engine)?;
+        let path = PathBuf::from(str_arg(path)?);
+        if out_scene.is_null() {
+            // Check it early to not load the scene for nothing.
+            return Err(FfiError::new(
+                FyroxResult::NullPointer,
+                "Output pointer is null.",
+            ));
+        }
+        let (mut scene, _) = block_on(load_scene(
+            path.clone(),
+            engine.serialization_context.clone(),
+            engine.resource_manager.clone(),
+        ))
+        .map_err(|e| {
+            FfiError::new(
+                FyroxResult::LoadFailed,
+                format!("Unable to load {} scene. Reason: {e:?}", path.display()),
+            )
+        })?;
+        prepare_loaded_scene(&mut scene, &path, false, &engine.resource_manager);
+        let handle = engine.scenes.add(scene);
+        write_out(out_scene, handle.into())
+    })
+}
+
+/// Removes the scene from the engine and destroys it.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_scene_remove(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+) -> FyroxResult {
+    call(|| {
+        let engine = engine_mut(engine)?;
+        let handle = scene.to_handle();
+        if engine.scenes.try_get(handle).is_none() {
+            return Err(FfiError::new(
+                FyroxResult::InvalidHandle,
+                "Invalid scene handle.",
+            ));
+        }
+        engine.scenes.remove(handle);
+        Ok(())
+    })
+}
+
+/// Searches for a node with the given name in the scene and writes its handle to `out_node`.
+/// Writes a handle with zero generation if there's no such node.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_scene_find_node(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    name: *const c_char,
+    out_node: *mut FyroxHandle,
+) -> FyroxResult {
+    call(|| {
+        let engine = engine_mut(engine)?;
+        let name = str_arg(name)?;
+        let scene = engine
+            .scenes
+            .try_get(scene.to_handle())
+            .ok_or_else(|| FfiError::new(FyroxResult::InvalidHandle, "Invalid scene handle."))?;
+        let handle = scene
+            .graph
+            .find_by_name_from_root(name)
+            .map(|(handle, _)| handle)
+            .unwrap_or(Handle::NONE);
+        write_out(out_node, handle.into())
+    })
+}
+
+/// Writes local position of the node to `out_position`.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_get_position(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    out_position: *mut FyroxVector3,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        write_out(out_position, (**node.local_transform().position()).into())
+    })
+}
+
+/// Sets local position of the node.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_set_position(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    position: FyroxVector3,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        node.local_transform_mut()
+            .set_position(Vector3::from(position));
+        Ok(())
+    })
+}
+
+/// Writes local rotation of the node to `out_rotation`.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_get_rotation(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    out_rotation: *mut FyroxQuaternion,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        write_out(out_rotation, (**node.local_transform().rotation()).into())
+    })
+}
+
+/// Sets local rotation of the node. The quaternion is normalized before use.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_set_rotation(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    rotation: FyroxQuaternion,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        node.local_transform_mut().set_rotation(rotation.into());
+        Ok(())
+    })
+}
+
+/// Writes local scale of the node to `out_scale`.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_get_scale(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    out_scale: *mut FyroxVector3,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        write_out(out_scale, (**node.local_transform().scale()).into())
+    })
+}
+
+/// Sets local scale of the node.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_set_scale(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    scale: FyroxVector3,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        node.local_transform_mut().set_scale(Vector3::from(scale));
+        Ok(())
+    })
+}
+
+/// Writes global position of the node to `out_position`. Global transform is calculated at the
+/// end of every update tick, so the value lags behind local transform changes made in between.
+#[no_mangle]
+pub unsafe extern "C" fn fyrox_node_get_global_position(
+    engine: *mut FyroxEngine,
+    scene: FyroxHandle,
+    node: FyroxHandle,
+    out_position: *mut FyroxVector3,
+) -> FyroxResult {
+    with_node(engine, scene, node, |node| {
+        write_out(out_position, node.global_position().into())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ffi::{
+            fyrox_engine_create, fyrox_engine_destroy,
+            scene::{
+                fyrox_node_get_global_position, fyrox_node_get_position, fyrox_node_set_position,
+                fyrox_scene_create, fyrox_scene_find_node, fyrox_scene_load, fyrox_scene_remove,
+            },
+            FyroxHandle, FyroxResult, FyroxVector3,
+        },
+        scene::{base::BaseBuilder, pivot::PivotBuilder},
+    };
+    use std::{ffi::CString, ptr};
+
+    #[test]
+    fn test_null_pointers() {
+        let name = CString::new("Pivot").unwrap();
+        let mut handle = FyroxHandle::default();
+        unsafe {
+            assert_eq!(
+                fyrox_scene_create(ptr::null_mut(), &mut handle),
+                FyroxResult::NullPointer
+            );
+
+            let engine = fyrox_engine_create();
+            assert_eq!(
+                fyrox_scene_create(engine, ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+            assert_eq!(
+                fyrox_scene_load(engine, ptr::null(), &mut handle),
+                FyroxResult::NullPointer
+            );
+            assert_eq!(
+                fyrox_scene_load(engine, name.as_ptr(), ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+
+            assert_eq!(fyrox_scene_create(engine, &mut handle), FyroxResult::Ok);
+            assert_eq!(
+                fyrox_scene_find_node(engine, handle, ptr::null(), &mut handle),
+                FyroxResult::NullPointer
+            );
+            assert_eq!(
+                fyrox_scene_find_node(engine, handle, name.as_ptr(), ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+
+            let invalid_utf8 = CString::new(vec![0xFF]).unwrap();
+            assert_eq!(
+                fyrox_scene_find_node(engine, handle, invalid_utf8.as_ptr(), &mut handle),
+                FyroxResult::InvalidArgument
+            );
+
+            fyrox_engine_destroy(engine);
+        }
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        let name = CString::new("Pivot").unwrap();
+        let mut out_handle = FyroxHandle::default();
+        let mut out_position = FyroxVector3::default();
+        unsafe {
+            let engine = fyrox_engine_create();
+            let invalid = FyroxHandle {
+                index: 123,
+                generation: 1,
+            };
+
+            assert_eq!(
+                fyrox_scene_find_node(engine, invalid, name.as_ptr(), &mut out_handle),
+                FyroxResult::InvalidHandle
+            );
+            assert_eq!(
+                fyrox_node_get_position(engine, invalid, invalid, &mut out_position),
+                FyroxResult::InvalidHandle
+            );
+            assert_eq!(
+                fyrox_scene_remove(engine, invalid),
+                FyroxResult::InvalidHandle
+            );
+
+            let mut scene = FyroxHandle::default();
+            assert_eq!(fyrox_scene_create(engine, &mut scene), FyroxResult::Ok);
+            assert_eq!(
+                fyrox_node_set_position(engine, scene, invalid, FyroxVector3::default()),
+                FyroxResult::InvalidHandle
+            );
+
+            // A removed scene must not be accessible anymore.
+            assert_eq!(fyrox_scene_remove(engine, scene), FyroxResult::Ok);
+            assert_eq!(
+                fyrox_scene_remove(engine, scene),
+                FyroxResult::InvalidHandle
+            );
+            assert_eq!(
+                fyrox_scene_find_node(engine, scene, name.as_ptr(), &mut out_handle),
+                FyroxResult::InvalidHandle
+            );
+
+            fyrox_engine_destroy(engine);
+        }
+    }
+
+    #[test]
+    fn test_scene_and_node_lifecycle() {
+        let name = CString::new("Pivot").unwrap();
+        let unknown_name = CString::new("Unknown").unwrap();
+        unsafe {
+            let engine = fyrox_engine_create();
+
+            let mut scene = FyroxHandle::default();
+            assert_eq!(fyrox_scene_create(engine, &mut scene), FyroxResult::Ok);
+            let pivot = PivotBuilder::new(BaseBuilder::new().with_name("Pivot"))
+                .build(&mut (*engine).scenes[scene.to_handle()].graph);
+
+            let mut node = FyroxHandle::default();
+            assert_eq!(
+                fyrox_scene_find_node(engine, scene, name.as_ptr(), &mut node),
+                FyroxResult::Ok
+            );
+            assert_eq!(node, FyroxHandle::from(pivot));
+
+            let mut missing = FyroxHandle::from(pivot);
+            assert_eq!(
+                fyrox_scene_find_node(engine, scene, unknown_name.as_ptr(), &mut missing),
+                FyroxResult::Ok
+            );
+            assert_eq!(missing, FyroxHandle::default());
+
+            let position = FyroxVector3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            };
+            assert_eq!(
+                fyrox_node_set_position(engine, scene, node, position),
+                FyroxResult::Ok
+            );
+            let mut out_position = FyroxVector3::default();
+            assert_eq!(
+                fyrox_node_get_position(engine, scene, node, &mut out_position),
+                FyroxResult::Ok
+            );
+            assert_eq!(out_position, position);
+            assert_eq!(
+                fyrox_node_get_global_position(engine, scene, node, ptr::null_mut()),
+                FyroxResult::NullPointer
+            );
+
+            assert_eq!(fyrox_scene_remove(engine, scene), FyroxResult::Ok);
+            assert_eq!(
+                fyrox_node_get_position(engine, scene, node, &mut out_position),
+                FyroxResult::InvalidHandle
+            );
+
+            fyrox_engine_destroy(engine);
+        }
+    }
+}
@@ -34,7 +34,10 @@
 #![allow(clippy::mutable_key_type)]
 
 pub mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod material;
+pub mod net;
 pub mod plugin;
 pub mod renderer;
 pub mod resource;
@@ -43,7 +43,10 @@ use crate::{
         visitor::{prelude::*, RegionGuard},
         TypeUuidProvider,
     },
-    material::shader::{SamplerFallback, ShaderResource, ShaderResourceExtension},
+    material::shader::{
+        SamplerFallback, ShaderResource, ShaderResourceExtension, ShaderResourceKind,
+    },
+    renderer::framework::gpu_program::ShaderPropertyKind,
     resource::texture::TextureResource,
 };
 use fxhash::FxHashMap;
@@ -103,7 +106,7 @@ impl MaterialResourceBinding {
 
 /// Property group stores a bunch of named values of a fixed set of types, that will be used for
 /// rendering with some shader.
-#[derive(Default, Debug, Visit, Clone, Reflect)]
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
 pub struct MaterialPropertyGroup {
     properties: FxHashMap<ImmutableString, MaterialProperty>,
 }
@@ -189,8 +192,94 @@ impl MaterialPropertyGroup {
     }
 }
 
+/// A lightweight set of per-instance overrides for a material. Property block allows you to change
+/// some properties (colors, scalars, etc.) and textures of a material for a single mesh instance,
+/// without creating a copy of the material resource. This is useful for tinting, dissolve effects,
+/// damage masks and so on. The values of the block are merged over the values of the material at
+/// draw time.
+///
+/// Properties of the block override the properties from the standard `properties` property group
+/// of the material (see [`Material::set_property`]). Textures of the block override respective
+/// texture bindings of the material (see [`Material::bind`]).
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{core::color::Color, scene::mesh::Mesh};
+/// fn apply_damage(mesh: &mut Mesh, damage: f32) {
+///     let block = mesh.property_block_mut();
+///     block.set_property("diffuseColor", Color::opaque(255, (255.0 * (1.0 - damage)) as u8, 0));
+/// }
+/// ```
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct MaterialPropertyBlock {
+    properties: MaterialPropertyGroup,
+    textures: FxHashMap<ImmutableString, TextureResource>,
+}
+
+impl MaterialPropertyBlock {
+    /// Sets a new value of the property with the given name. There's no type checking here, the renderer
+    /// will use shader default value if the type of the new value does not match the type of the property
+    /// in the shader.
+    pub fn set_property(
+        &mut self,
+        name: impl Into<ImmutableString>,
+        new_value: impl Into<MaterialProperty>,
+    ) {
+        self.properties.set_property(name, new_value);
+    }
+
+    /// Searches for a property override with the given name.
+    pub fn property_ref(&self, name: impl Into<ImmutableString>) -> Option<&MaterialProperty> {
+        self.properties.property_ref(name)
+    }
+
+    /// Removes the property override. The renderer will use the value from the material.
+    pub fn unset_property(&mut self, name: impl Into<ImmutableString>) -> Option<MaterialProperty> {
+        self.properties.unset_property(name)
+    }
+
+    /// Returns a reference to the property overrides.
+    pub fn properties(&self) -> &MaterialPropertyGroup {
+        &self.properties
+    }
+
+    /// Overrides a texture binding with the given name.
+    pub fn set_texture(&mut self, name: impl Into<ImmutableString>, texture: TextureResource) {
+        self.textures.insert(name.into(), texture);
+    }
+
+    /// Searches for a texture override with the given name.
+    pub fn texture(&self, name: impl Into<ImmutableString>) -> Option<&TextureResource> {
+        self.textures.get(&name.into())
+    }
+
+    /// Removes the texture override. The renderer will use the texture from the material.
+    pub fn unset_texture(&mut self, name: impl Into<ImmutableString>) -> Option<TextureResource> {
+        self.textures.remove(&name.into())
+    }
+
+    /// Returns a reference to the texture overrides.
+    pub fn textures(&self) -> &FxHashMap<ImmutableString, TextureResource> {
+        &self.textures
+    }
+
+    /// Returns `true` if the block does not override anything.
+    pub fn is_empty(&self) -> bool {
+        self.properties.properties().is_empty() && self.textures.is_empty()
+    }
+
+    /// Removes all overrides from the block.
+    pub fn clear(&mut self) {
+        self.properties = Default::default();
+        self.textures.clear();
+    }
+}
+
 /// A set of possible material property types.
-#[derive(Debug, Visit, Clone, Reflect, AsRefStr, EnumString, VariantNames, TypeUuidProvider)]
+#[derive(
+    Debug, Visit, Clone, Reflect, AsRefStr, EnumString, VariantNames, TypeUuidProvider, PartialEq,
+)]
 #[type_uuid(id = "1c25018d-ab6e-4dca-99a6-e3d9639bc33c")]
 pub enum MaterialProperty {
     /// Real number.
@@ -665,6 +754,25 @@ impl ResourceData for Material {
 pub enum MaterialError {
     /// Unable to read data source.
     Visit(VisitError),
+    /// The shader of the material is not loaded (yet or due to an error), so the property cannot
+    /// be validated.
+    ShaderNotLoaded,
+    /// The shader of the material does not define a property with the given name.
+    NoSuchProperty {
+        /// Name of the property group.
+        group: ImmutableString,
+        /// Name of the property.
+        name: ImmutableString,
+    },
+    /// The type of the new value does not match the type of the property defined in the shader.
+    TypeMismatch {
+        /// Name of the property.
+        name: ImmutableString,
+        /// Name of the type, defined in the shader.
+        expected: &'static str,
+        /// Name of the type of the new value.
+        actual: String,
+    },
 }
 
 impl From<VisitError> for MaterialError {
@@ -685,10 +793,55 @@ impl Display for MaterialError {
             MaterialError::Visit(e) => {
                 write!(f, "Failed to visit data source. Reason: {e:?}")
             }
+            MaterialError::ShaderNotLoaded => {
+                write!(f, "Shader of the material is not loaded.")
+            }
+            MaterialError::NoSuchProperty { group, name } => {
+                write!(
+                    f,
+                    "Shader does not have {name} property in {group} property group."
+                )
+            }
+            MaterialError::TypeMismatch {
+                name,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Type mismatch for {name} property. Expected {expected}, got {actual}."
+                )
+            }
         }
     }
 }
 
+fn shader_property_kind_name(kind: &ShaderPropertyKind) -> &'static str {
+    // Names must match the names of respective variants of `MaterialProperty`.
+    match kind {
+        ShaderPropertyKind::Float(_) => "Float",
+        ShaderPropertyKind::FloatArray { .. } => "FloatArray",
+        ShaderPropertyKind::Int(_) => "Int",
+        ShaderPropertyKind::IntArray { .. } => "IntArray",
+        ShaderPropertyKind::UInt(_) => "UInt",
+        ShaderPropertyKind::UIntArray { .. } => "UIntArray",
+        ShaderPropertyKind::Bool(_) => "Bool",
+        ShaderPropertyKind::Vector2(_) => "Vector2",
+        ShaderPropertyKind::Vector2Array { .. } => "Vector2Array",
+        ShaderPropertyKind::Vector3(_) => "Vector3",
+        ShaderPropertyKind::Vector3Array { .. } => "Vector3Array",
+        ShaderPropertyKind::Vector4(_) => "Vector4",
+        ShaderPropertyKind::Vector4Array { .. } => "Vector4Array",
+        ShaderPropertyKind::Matrix2(_) => "Matrix2",
+        ShaderPropertyKind::Matrix2Array { .. } => "Matrix2Array",
+        ShaderPropertyKind::Matrix3(_) => "Matrix3",
+        ShaderPropertyKind::Matrix3Array { .. } => "Matrix3Array",
+        ShaderPropertyKind::Matrix4(_) => "Matrix4",
+        ShaderPropertyKind::Matrix4Array { .. } => "Matrix4Array",
+        ShaderPropertyKind::Color { .. } => "Color",
+    }
+}
+
 lazy_static! {
     /// Standard PBR material. Keep in mind that this material is global, any modification
     /// of it will reflect on every other usage of it.
@@ -717,6 +870,15 @@ lazy_static! {
         )
     );
 
+    /// Standard material for particles, that are simulated on GPU. Keep in mind that this material
+    /// is global, any modification of it will reflect on every other usage of it.
+    pub static ref STANDARD_GPU_PARTICLE_SYSTEM: BuiltInResource<Material> = BuiltInResource::new_no_source(
+        MaterialResource::new_ok(
+            "__StandardGpuParticleSystemMaterial".into(),
+            Material::from_shader(ShaderResource::standard_gpu_particle_system()),
+        )
+    );
+
     /// Standard sprite material. Keep in mind that this material is global, any modification
     /// of it will reflect on every other usage of it.
     pub static ref STANDARD_SPRITE: BuiltInResource<Material> = BuiltInResource::new_no_source(
@@ -784,6 +946,11 @@ impl Material {
         Self::from_shader(ShaderResource::standard_particle_system())
     }
 
+    /// Creates new instance of standard material for particles, that are simulated on GPU.
+    pub fn standard_gpu_particle_system() -> Self {
+        Self::from_shader(ShaderResource::standard_gpu_particle_system())
+    }
+
     /// Creates new instance of standard sprite material.
     pub fn standard_sprite() -> Self {
         Self::from_shader(ShaderResource::standard_sprite())
@@ -1046,6 +1213,78 @@ impl Material {
             .set_property(name, new_value);
     }
 
+    /// Same as [`Self::set_property`], but checks that the shader of the material has the property
+    /// with the given name and that the type of the new value matches the type of the property.
+    /// The material is left unchanged if the validation has failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fyrox_impl::material::Material;
+    /// # use fyrox_impl::core::color::Color;
+    ///
+    /// let mut material = Material::standard();
+    ///
+    /// assert!(material.try_set_property("diffuseColor", Color::WHITE).is_ok());
+    /// // Type mismatch.
+    /// assert!(material.try_set_property("diffuseColor", 1.0f32).is_err());
+    /// // Unknown property.
+    /// assert!(material.try_set_property("foobar", 1.0f32).is_err());
+    /// ```
+    pub fn try_set_property(
+        &mut self,
+        name: impl Into<ImmutableString>,
+        new_value: impl Into<MaterialProperty>,
+    ) -> Result<(), MaterialError> {
+        let group = ImmutableString::new("properties");
+        let name = name.into();
+        let new_value = new_value.into();
+
+        let mut shader_state = self.shader.state();
+        let Some(shader) = shader_state.data() else {
+            return Err(MaterialError::ShaderNotLoaded);
+        };
+
+        let property = shader
+            .definition
+            .resources
+            .iter()
+            .filter(|resource| resource.name == group)
+            .find_map(|resource| match resource.kind {
+                ShaderResourceKind::PropertyGroup(ref properties) => {
+                    properties.iter().find(|property| property.name == name)
+                }
+                ShaderResourceKind::Texture { .. } => None,
+            })
+            .ok_or_else(|| MaterialError::NoSuchProperty {
+                group: group.clone(),
+                name: name.clone(),
+            })?;
+
+        let expected = shader_property_kind_name(&property.kind);
+        if expected != new_value.as_ref() {
+            return Err(MaterialError::TypeMismatch {
+                name,
+                expected,
+                actual: new_value.as_ref().to_string(),
+            });
+        }
+
+        drop(shader_state);
+
+        self.try_get_or_insert_property_group(group)
+            .set_property(name, new_value);
+
+        Ok(())
+    }
+
+    /// Searches for a property with the given name in the standard `properties` property group.
+    /// See [`Self::set_property`] for more info.
+    pub fn property_ref(&self, name: impl Into<ImmutableString>) -> Option<&MaterialProperty> {
+        self.property_group_ref("properties")
+            .and_then(|group| group.property_ref(name))
+    }
+
     /// Returns a reference to current shader.
     pub fn shader(&self) -> &ShaderResource {
         &self.shader
@@ -1155,3 +1394,43 @@ where
     }
     None
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::color::Color,
+        material::{Material, MaterialError, MaterialProperty},
+    };
+
+    #[test]
+    fn test_try_set_property() {
+        let mut material = Material::standard();
+
+        assert!(material
+            .try_set_property("diffuseColor", Color::RED)
+            .is_ok());
+        assert!(matches!(
+            material.property_ref("diffuseColor"),
+            Some(MaterialProperty::Color(Color::RED))
+        ));
+
+        assert!(matches!(
+            material.try_set_property("diffuseColor", 1.0f32),
+            Err(MaterialError::TypeMismatch {
+                expected: "Color",
+                ..
+            })
+        ));
+        assert!(matches!(
+            material.try_set_property("foobar", 1.0f32),
+            Err(MaterialError::NoSuchProperty { .. })
+        ));
+        assert!(material.property_ref("foobar").is_none());
+
+        // Failed validation must not change the material.
+        assert!(matches!(
+            material.property_ref("diffuseColor"),
+            Some(MaterialProperty::Color(Color::RED))
+        ));
+    }
+}
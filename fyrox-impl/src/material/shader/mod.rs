@@ -201,6 +201,10 @@
 //! limiting, it does not support lighting, shadows, etc. It should be only used to render
 //! translucent objects.
 //!
+//! - `Outline` - A pass that is rendered right after the `Forward` pass for every object, regardless
+//! of its render path. It is used to draw outlines around objects. Objects are drawn in this pass
+//! only if their `outlineWidth` property (in a material or in a property block) is greater than zero.
+//!
 //! - `SpotShadow` - A pass that emits depth values for an object, later this depth map will be
 //! used to render shadows.
 //!
@@ -255,6 +259,22 @@
 //! | blendShapesCount     | `int`      | Total amount of blend shapes.               |
 //! | useSkeletalAnimation | `bool`     | Whether skinned meshes is rendering or not. |
 //! | blendShapesWeights   | `vec4[32]` | Blend shape weights.                        |
+//! | color                | `vec4`     | Color of the instance.                      |
+//!
+//! ### `fyrox_instanceBatch`
+//!
+//! Property group. Contains world matrices and colors of a group of surface instances, that are
+//! drawn using a single instanced draw call. If a shader defines this group, the renderer will
+//! merge all instances of a render bundle that have no skinning, no blend shapes and no material
+//! property overrides into instanced draw calls. Use `gl_InstanceID` to fetch the data of the
+//! current instance. `count` is zero if the draw call is not instanced, in this case the data from
+//! `fyrox_instanceData` must be used instead.
+//!
+//! | Name          | Type        | Description                                    |
+//! |---------------|-------------|------------------------------------------------|
+//! | count         | `int`       | Amount of instances in the draw call.          |
+//! | worldMatrices | `mat4[128]` | Local-to-world transformation of each instance |
+//! | colors        | `vec4[128]` | Color of each instance.                        |
 //!
 //! ### `fyrox_boneMatrices`
 //!
@@ -435,6 +455,139 @@
 //!
 //! Usually you don't need to get this shader manually, using of [Material::standard](super::Material::standard)
 //! is enough.
+//!
+//! ## Dissolve
+//!
+//! Standard shaders (including the two-sided one) support the classic spawn/despawn dissolve effect. Every
+//! fragment, which value in the red channel of the `dissolveTexture` (usually some noise) is less than the
+//! `dissolveThreshold` property, is discarded. Fragments near the threshold (within `dissolveEdgeWidth`)
+//! glow with `dissolveEdgeColor` multiplied by `dissolveEdgeStrength`. The default threshold is zero, which
+//! means that the effect is disabled. Animate the threshold from 0 to 1 to dissolve an object. Use
+//! [`MaterialPropertyBlock`](super::MaterialPropertyBlock) to control the effect per instance without
+//! copying the material:
+//!
+//! ```no_run
+//! # use fyrox_impl::{
+//! #     asset::manager::ResourceManager, resource::texture::Texture, scene::mesh::Mesh,
+//! # };
+//! fn dissolve(mesh: &mut Mesh, resource_manager: &ResourceManager, progress: f32) {
+//!     let block = mesh.property_block_mut();
+//!     block.set_texture("dissolveTexture", resource_manager.request::<Texture>("noise.png"));
+//!     block.set_property("dissolveThreshold", progress);
+//! }
+//! ```
+//!
+//! ## Toon shading
+//!
+//! Standard shaders (including the two-sided one) support two lighting models, which can be selected
+//! using `lightingModel` property: `0` - physically-based lighting (default), `1` - toon (cel)
+//! shading. Toon shading consists of ramped diffuse lighting with hard transitions between lit and
+//! unlit areas, quantized specular highlights (their size depends on roughness) and rim light. It
+//! works well together with outlines (see below) and posterization (see
+//! [`crate::scene::camera::Posterization`]). The lighting model is stored in the highest bit of the
+//! decal mask in the G-Buffer, which means that layer indices of decals must be less than 64 (the
+//! next bit is used by the fog opt-out, see below).
+//!
+//! ```no_run
+//! # use fyrox_impl::material::Material;
+//! fn make_toon_material() -> Material {
+//!     let mut material = Material::standard();
+//!     material.set_property("lightingModel", 1u32);
+//!     material
+//! }
+//! ```
+//!
+//! ## Fog
+//!
+//! Standard shaders (including the two-sided one) are affected by the scene fog (see
+//! [`crate::scene::fog::Fog`]) by default. It could be disabled per material by setting
+//! `fogEnabled` property to `false`, which is useful for things like distant billboards of the sky
+//! or in-world UI.
+//!
+//! ## Outline
+//!
+//! Standard shaders (including the two-sided one) can draw an outline around an object, which is
+//! useful for toon or stylized rendering. The outline is drawn using the "inverted hull" technique in
+//! the `Outline` render pass: the back faces of an object are extruded along their normals by
+//! `outlineWidth` and filled with `outlineColor`. The default width is zero, which means that the
+//! outline is disabled. When `outlineScreenSpace` is set, the width is multiplied by the distance to
+//! the camera, which keeps the apparent thickness of the outline constant (`0.005` is a good starting
+//! point in this mode). Otherwise the width is defined in world units. Note that the outline requires
+//! smooth normals, otherwise it will have gaps at sharp edges. Just like the dissolve effect, the
+//! outline can be controlled per instance:
+//!
+//! ```no_run
+//! # use fyrox_impl::{core::color::Color, scene::mesh::Mesh};
+//! fn set_outline(mesh: &mut Mesh, enabled: bool) {
+//!     let block = mesh.property_block_mut();
+//!     block.set_property("outlineWidth", if enabled { 0.005f32 } else { 0.0 });
+//!     block.set_property("outlineColor", Color::opaque(255, 200, 0));
+//!     block.set_property("outlineScreenSpace", true);
+//! }
+//! ```
+//!
+//! ## Detail textures and triplanar mapping
+//!
+//! Standard shaders (including the two-sided one) can layer detail textures on top of the main ones to
+//! hide visible tiling on large surfaces, such as terrains or cliffs. `detailDiffuseTexture` is
+//! multiplied with the diffuse color and `detailNormalTexture` is blended with the normal map. Detail
+//! textures use their own texture coordinates scale (`detailTexCoordScale`) and their influence
+//! (`detailStrength`) fades out between `detailFadeStart` and `detailFadeEnd` distances to the camera.
+//! The fallback textures are neutral, which means that nothing changes until a detail texture is set.
+//!
+//! Setting `useTriplanarMapping` makes the shader to project all the textures (except the lightmap) on
+//! three world axes instead of using texture coordinates of a mesh. It removes stretching on steep
+//! surfaces with poor UV mapping, in this mode `texCoordScale` defines the number of texture
+//! repetitions per world unit. `triplanarSharpness` controls the width of transitions between the
+//! projections. Parallax mapping is not supported in this mode.
+//!
+//! ```no_run
+//! # use fyrox_impl::{
+//! #     asset::manager::ResourceManager, core::algebra::Vector2, material::Material,
+//! #     resource::texture::Texture,
+//! # };
+//! fn make_cliff_material(resource_manager: &ResourceManager) -> Material {
+//!     let mut material = Material::standard();
+//!     material.bind("diffuseTexture", resource_manager.request::<Texture>("rock.png"));
+//!     material.bind("detailDiffuseTexture", resource_manager.request::<Texture>("rock_detail.png"));
+//!     material.bind("detailNormalTexture", resource_manager.request::<Texture>("rock_detail_normal.png"));
+//!     material.set_property("texCoordScale", Vector2::new(0.25f32, 0.25));
+//!     material.set_property("detailTexCoordScale", Vector2::new(4.0f32, 4.0));
+//!     material.set_property("useTriplanarMapping", true);
+//!     material
+//! }
+//! ```
+//!
+//! `wetness` property (in `[0; 1]` range) makes surfaces darker and glossier, it is set automatically
+//! by scene weather (see [`crate::scene::weather::Weather`]).
+//!
+//! ## Parallax occlusion mapping
+//!
+//! Standard shaders (including the two-sided one) use `heightTexture` to displace texture coordinates
+//! when parallax occlusion mapping is enabled in the graphics settings of the renderer (see `usePom`
+//! above). `parallaxScale` defines the depth of the effect and `parallaxCenter` shifts the "zero" level
+//! of the height map. The height map is ray marched with the amount of steps between `parallaxMinSteps`
+//! (when a surface is viewed head-on) and `parallaxMaxSteps` (at grazing angles), more steps give less
+//! visible layering but cost more; the amount is clamped to 128 steps. `parallaxClipSilhouette` discards
+//! fragments, which texture coordinates went outside of the `[0; texCoordScale]` range after
+//! displacement, which makes jagged silhouettes on the edges of a surface (it works only for meshes
+//! with the texture coordinates in `[0; 1]` range, such as quads or boxes).
+//!
+//! ```no_run
+//! # use fyrox_impl::{
+//! #     asset::manager::ResourceManager, material::Material, resource::texture::Texture,
+//! # };
+//! fn make_brick_material(resource_manager: &ResourceManager) -> Material {
+//!     let mut material = Material::standard();
+//!     material.bind("diffuseTexture", resource_manager.request::<Texture>("bricks.png"));
+//!     material.bind("normalTexture", resource_manager.request::<Texture>("bricks_normal.png"));
+//!     material.bind("heightTexture", resource_manager.request::<Texture>("bricks_height.png"));
+//!     material.set_property("parallaxScale", 0.05f32);
+//!     material.set_property("parallaxMaxSteps", 32.0f32);
+//!     material.set_property("parallaxClipSilhouette", true);
+//!     material
+//! }
+//! ```
 
 use crate::{
     asset::{
@@ -488,6 +641,13 @@ pub const STANDARD_PARTICLE_SYSTEM_SHADER_NAME: &str = "StandardParticleSystem";
 pub const STANDARD_PARTICLE_SYSTEM_SHADER_SRC: &str =
     include_str!("standard/standard_particle_system.shader");
 
+/// A name of the standard shader for particles, that are simulated on GPU.
+pub const STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME: &str = "StandardGpuParticleSystem";
+
+/// A source code of the standard shader for particles, that are simulated on GPU.
+pub const STANDARD_GPU_PARTICLE_SYSTEM_SHADER_SRC: &str =
+    include_str!("standard/standard_gpu_particle_system.shader");
+
 /// A source code of the standard sprite shader.
 pub const STANDARD_SPRITE_SHADER_SRC: &str = include_str!("standard/standard_sprite.shader");
 
@@ -507,20 +667,22 @@ pub const STANDARD_SPRITE_SHADER_NAME: &str = "StandardSprite";
 pub const STANDARD_TERRAIN_SHADER_SRC: &str = include_str!("standard/terrain.shader");
 
 /// A list of names of standard shaders.
-pub const STANDARD_SHADER_NAMES: [&str; 6] = [
+pub const STANDARD_SHADER_NAMES: [&str; 7] = [
     STANDARD_SHADER_NAME,
     STANDARD_2D_SHADER_NAME,
     STANDARD_PARTICLE_SYSTEM_SHADER_NAME,
+    STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME,
     STANDARD_SPRITE_SHADER_NAME,
     STANDARD_TWOSIDES_SHADER_NAME,
     STANDARD_TERRAIN_SHADER_NAME,
 ];
 
 /// A list of source code of standard shaders.
-pub const STANDARD_SHADER_SOURCES: [&str; 6] = [
+pub const STANDARD_SHADER_SOURCES: [&str; 7] = [
     STANDARD_SHADER_SRC,
     STANDARD_2D_SHADER_SRC,
     STANDARD_PARTICLE_SYSTEM_SHADER_SRC,
+    STANDARD_GPU_PARTICLE_SYSTEM_SHADER_SRC,
     STANDARD_SPRITE_SHADER_SRC,
     STANDARD_TWOSIDES_SHADER_SRC,
     STANDARD_TERRAIN_SHADER_SRC,
@@ -584,6 +746,9 @@ impl ShaderDefinition {
     /// Maximum amount of blend shape weight groups (packed weights of blend shapes into vec4).
     pub const MAX_BLEND_SHAPE_WEIGHT_GROUPS: usize = 32;
 
+    /// Maximum amount of surface instances that can be drawn using a single instanced draw call.
+    pub const MAX_INSTANCES: usize = 128;
+
     fn from_buf(buf: Vec<u8>) -> Result<Self, ShaderError> {
         let mut definition: ShaderDefinition = ron::de::from_reader(Cursor::new(buf))?;
         definition.generate_built_in_resources();
@@ -681,6 +846,27 @@ impl ShaderDefinition {
                                 max_len: Self::MAX_BLEND_SHAPE_WEIGHT_GROUPS,
                             },
                         ),
+                        ShaderProperty::new("color", Vector4(algebra::Vector4::repeat(1.0))),
+                    ]);
+                }
+                "fyrox_instanceBatch" => {
+                    properties.clear();
+                    properties.extend([
+                        ShaderProperty::new("count", Int(0)),
+                        ShaderProperty::new(
+                            "worldMatrices",
+                            Matrix4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_INSTANCES,
+                            },
+                        ),
+                        ShaderProperty::new(
+                            "colors",
+                            Vector4Array {
+                                value: Default::default(),
+                                max_len: Self::MAX_INSTANCES,
+                            },
+                        ),
                     ]);
                 }
                 "fyrox_boneMatrices" => {
@@ -817,6 +1003,9 @@ pub trait ShaderResourceExtension: Sized {
     /// Returns an instance of standard particle system shader.
     fn standard_particle_system() -> Self;
 
+    /// Returns an instance of standard shader for particles, that are simulated on GPU.
+    fn standard_gpu_particle_system() -> Self;
+
     /// Returns an instance of standard sprite shader.
     fn standard_sprite() -> Self;
 
@@ -827,7 +1016,7 @@ pub trait ShaderResourceExtension: Sized {
     fn standard_twosides() -> Self;
 
     /// Returns a list of standard shader.
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 6];
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 7];
 }
 
 impl ShaderResourceExtension for ShaderResource {
@@ -847,6 +1036,10 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_PARTICLE_SYSTEM.resource()
     }
 
+    fn standard_gpu_particle_system() -> Self {
+        STANDARD_GPU_PARTICLE_SYSTEM.resource()
+    }
+
     fn standard_sprite() -> Self {
         STANDARD_SPRITE.resource()
     }
@@ -859,11 +1052,12 @@ impl ShaderResourceExtension for ShaderResource {
         STANDARD_TWOSIDES.resource()
     }
 
-    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 6] {
+    fn standard_shaders() -> [&'static BuiltInResource<Shader>; 7] {
         [
             &STANDARD,
             &STANDARD_2D,
             &STANDARD_PARTICLE_SYSTEM,
+            &STANDARD_GPU_PARTICLE_SYSTEM,
             &STANDARD_SPRITE,
             &STANDARD_TERRAIN,
             &STANDARD_TWOSIDES,
@@ -893,6 +1087,13 @@ lazy_static! {
             Shader::from_string_bytes(data).unwrap(),
         )
     );
+    static ref STANDARD_GPU_PARTICLE_SYSTEM: BuiltInResource<Shader> = BuiltInResource::new(
+        embedded_data_source!("standard/standard_gpu_particle_system.shader"),
+        |data| ShaderResource::new_ok(
+            STANDARD_GPU_PARTICLE_SYSTEM_SHADER_NAME.into(),
+            Shader::from_string_bytes(data).unwrap(),
+        )
+    );
     static ref STANDARD_SPRITE: BuiltInResource<Shader> = BuiltInResource::new(
         embedded_data_source!("standard/standard_sprite.shader"),
         |data| ShaderResource::new_ok(
@@ -920,7 +1121,8 @@ lazy_static! {
 mod test {
     use crate::material::shader::{
         RenderPassDefinition, SamplerFallback, ShaderDefinition, ShaderResource,
-        ShaderResourceDefinition, ShaderResourceExtension, ShaderResourceKind,
+        ShaderResourceDefinition, ShaderResourceExtension, ShaderResourceKind, STANDARD_SHADER_SRC,
+        STANDARD_TWOSIDES_SHADER_SRC,
     };
     use fyrox_graphics::gpu_program::SamplerKind;
 
@@ -991,4 +1193,143 @@ mod test {
 
         assert_eq!(data.definition, reference_definition);
     }
+
+    #[test]
+    fn test_standard_shaders_dissolve() {
+        for source in [STANDARD_SHADER_SRC, STANDARD_TWOSIDES_SHADER_SRC] {
+            let definition = ShaderDefinition::from_str(source).unwrap();
+
+            assert!(definition
+                .resources
+                .iter()
+                .any(|resource| resource.name.as_str() == "dissolveTexture"));
+
+            let properties = definition
+                .resources
+                .iter()
+                .find_map(|resource| match resource.kind {
+                    ShaderResourceKind::PropertyGroup(ref properties)
+                        if resource.name.as_str() == "properties" =>
+                    {
+                        Some(properties)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            for name in [
+                "dissolveThreshold",
+                "dissolveEdgeWidth",
+                "dissolveEdgeColor",
+                "dissolveEdgeStrength",
+            ] {
+                assert!(properties
+                    .iter()
+                    .any(|property| property.name.as_str() == name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_standard_shaders_outline_and_toon_shading() {
+        for source in [STANDARD_SHADER_SRC, STANDARD_TWOSIDES_SHADER_SRC] {
+            let definition = ShaderDefinition::from_str(source).unwrap();
+
+            assert!(definition.passes.iter().any(|pass| pass.name == "Outline"));
+
+            let properties = definition
+                .resources
+                .iter()
+                .find_map(|resource| match resource.kind {
+                    ShaderResourceKind::PropertyGroup(ref properties)
+                        if resource.name.as_str() == "properties" =>
+                    {
+                        Some(properties)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            for name in [
+                "outlineWidth",
+                "outlineColor",
+                "outlineScreenSpace",
+                "lightingModel",
+                "fogEnabled",
+            ] {
+                assert!(properties
+                    .iter()
+                    .any(|property| property.name.as_str() == name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_standard_shaders_detail_textures_and_triplanar_mapping() {
+        for source in [STANDARD_SHADER_SRC, STANDARD_TWOSIDES_SHADER_SRC] {
+            let definition = ShaderDefinition::from_str(source).unwrap();
+
+            for name in ["detailDiffuseTexture", "detailNormalTexture"] {
+                assert!(definition
+                    .resources
+                    .iter()
+                    .any(|resource| resource.name.as_str() == name));
+            }
+
+            let properties = definition
+                .resources
+                .iter()
+                .find_map(|resource| match resource.kind {
+                    ShaderResourceKind::PropertyGroup(ref properties)
+                        if resource.name.as_str() == "properties" =>
+                    {
+                        Some(properties)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            for name in [
+                "detailTexCoordScale",
+                "detailStrength",
+                "detailFadeStart",
+                "detailFadeEnd",
+                "useTriplanarMapping",
+                "triplanarSharpness",
+                "wetness",
+            ] {
+                assert!(properties
+                    .iter()
+                    .any(|property| property.name.as_str() == name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_standard_shaders_parallax_occlusion_mapping() {
+        for source in [STANDARD_SHADER_SRC, STANDARD_TWOSIDES_SHADER_SRC] {
+            let definition = ShaderDefinition::from_str(source).unwrap();
+
+            let properties = definition
+                .resources
+                .iter()
+                .find_map(|resource| match resource.kind {
+                    ShaderResourceKind::PropertyGroup(ref properties)
+                        if resource.name.as_str() == "properties" =>
+                    {
+                        Some(properties)
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            for name in [
+                "parallaxCenter",
+                "parallaxScale",
+                "parallaxMinSteps",
+                "parallaxMaxSteps",
+                "parallaxClipSilhouette",
+            ] {
+                assert!(properties
+                    .iter()
+                    .any(|property| property.name.as_str() == name));
+            }
+        }
+    }
 }
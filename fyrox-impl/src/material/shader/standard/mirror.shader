@@ -0,0 +1,98 @@
+(
+    name: "MirrorShader",
+
+    resources: [
+        (
+            name: "reflectionTexture",
+            kind: Texture(kind: Sampler2D, fallback: Black),
+            binding: 0
+        ),
+        (
+            name: "fyrox_instanceData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 0
+        ),
+        (
+            name: "fyrox_cameraData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 1
+        ),
+    ],
+
+    passes: [
+        (
+            name: "Forward",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: Some(BlendParameters(
+                    func: BlendFunc(
+                        sfactor: SrcAlpha,
+                        dfactor: OneMinusSrcAlpha,
+                        alpha_sfactor: SrcAlpha,
+                        alpha_dfactor: OneMinusSrcAlpha,
+                    ),
+                    equation: BlendEquation(
+                        rgb: Add,
+                        alpha: Add
+                    )
+                )),
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+            vertex_shader:
+               r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec2 vertexParams;
+                layout(location = 3) in vec4 vertexColor;
+
+                out vec4 clipPosition;
+                out vec4 color;
+
+                void main()
+                {
+                    color = vertexColor;
+                    vec4 worldPosition = fyrox_instanceData.worldMatrix * vec4(vertexPosition, 1.0);
+                    clipPosition = fyrox_cameraData.viewProjectionMatrix * worldPosition;
+                    gl_Position = clipPosition;
+                }
+               "#,
+
+           fragment_shader:
+               r#"
+                out vec4 FragColor;
+
+                in vec4 clipPosition;
+                in vec4 color;
+
+                void main()
+                {
+                    // Reflection is rendered with the same projection as the current camera, so
+                    // projective texturing is used to fetch it.
+                    vec2 texCoord = clipPosition.xy / clipPosition.w * 0.5 + 0.5;
+                    // Reflection camera is flipped horizontally to keep its basis right-handed.
+                    texCoord.x = 1.0 - texCoord.x;
+                    FragColor = color * vec4(texture(reflectionTexture, texCoord).rgb, 1.0);
+                }
+               "#,
+        )
+    ],
+)
@@ -47,6 +47,21 @@
             kind: Texture(kind: Sampler3D, fallback: Volume),
             binding: 8
         ),
+        (
+            name: "dissolveTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 9
+        ),
+        (
+            name: "detailDiffuseTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 10
+        ),
+        (
+            name: "detailNormalTexture",
+            kind: Texture(kind: Sampler2D, fallback: Normal),
+            binding: 11
+        ),
         (
             name: "properties",
             kind: PropertyGroup([
@@ -74,6 +89,82 @@
                     name: "parallaxScale",
                     kind: Float(0.08),
                 ),
+                (
+                    name: "parallaxMinSteps",
+                    kind: Float(8.0),
+                ),
+                (
+                    name: "parallaxMaxSteps",
+                    kind: Float(15.0),
+                ),
+                (
+                    name: "parallaxClipSilhouette",
+                    kind: Bool(false),
+                ),
+                (
+                    name: "dissolveThreshold",
+                    kind: Float(0.0),
+                ),
+                (
+                    name: "dissolveEdgeWidth",
+                    kind: Float(0.05),
+                ),
+                (
+                    name: "dissolveEdgeColor",
+                    kind: Color(r: 255, g: 128, b: 0, a: 255),
+                ),
+                (
+                    name: "dissolveEdgeStrength",
+                    kind: Float(4.0),
+                ),
+                (
+                    name: "outlineWidth",
+                    kind: Float(0.0),
+                ),
+                (
+                    name: "outlineColor",
+                    kind: Color(r: 0, g: 0, b: 0, a: 255),
+                ),
+                (
+                    name: "outlineScreenSpace",
+                    kind: Bool(false),
+                ),
+                (
+                    name: "lightingModel",
+                    kind: UInt(0),
+                ),
+                (
+                    name: "fogEnabled",
+                    kind: Bool(true),
+                ),
+                (
+                    name: "detailTexCoordScale",
+                    kind: Vector2((8.0, 8.0)),
+                ),
+                (
+                    name: "detailStrength",
+                    kind: Float(1.0),
+                ),
+                (
+                    name: "detailFadeStart",
+                    kind: Float(10.0),
+                ),
+                (
+                    name: "detailFadeEnd",
+                    kind: Float(30.0),
+                ),
+                (
+                    name: "useTriplanarMapping",
+                    kind: Bool(false),
+                ),
+                (
+                    name: "triplanarSharpness",
+                    kind: Float(4.0),
+                ),
+                (
+                    name: "wetness",
+                    kind: Float(0.0),
+                ),
             ]),
             binding: 0
         ),
@@ -112,6 +203,13 @@
             ]),
             binding: 5
         ),
+        (
+            name: "fyrox_instanceBatch",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 6
+        ),
     ],
 
     passes: [
@@ -153,9 +251,18 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec4 instanceColor;
 
                 void main()
                 {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
                     vec4 localPosition = vec4(0);
                     vec3 localNormal = vec3(0);
                     vec3 localTangent = vec3(0);
@@ -206,15 +313,16 @@
                         localTangent = inputTangent;
                     }
 
-                    mat3 nm = mat3(fyrox_instanceData.worldMatrix);
+                    mat3 nm = mat3(worldMatrix);
                     normal = normalize(nm * localNormal);
                     tangent = normalize(nm * localTangent);
                     binormal = normalize(vertexTangent.w * cross(normal, tangent));
                     texCoord = vertexTexCoord;
-                    position = vec3(fyrox_instanceData.worldMatrix * localPosition);
+                    position = vec3(worldMatrix * localPosition);
                     secondTexCoord = vertexSecondTexCoord;
+                    instanceColor = fyrox_instanceBatch.count > 0 ? fyrox_instanceBatch.colors[gl_InstanceID] : fyrox_instanceData.color;
 
-                    gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
                 }
                 "#,
             fragment_shader:
@@ -231,46 +339,107 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec4 instanceColor;
+
+                vec2 tc;
+                vec3 triplanarWeights;
+
+                vec4 SampleTexture(sampler2D s)
+                {
+                    if (properties.useTriplanarMapping) {
+                        return S_TriplanarSample(s, position, triplanarWeights, properties.texCoordScale);
+                    }
+                    return texture(s, tc);
+                }
+
+                vec4 SampleDetailTexture(sampler2D s)
+                {
+                    if (properties.useTriplanarMapping) {
+                        return S_TriplanarSample(s, position, triplanarWeights, properties.detailTexCoordScale);
+                    }
+                    return texture(s, texCoord * properties.detailTexCoordScale);
+                }
 
                 void main()
                 {
                     mat3 tangentSpace = mat3(tangent, binormal, normal);
                     vec3 toFragment = normalize(position - fyrox_cameraData.position);
+                    vec3 worldNormal = normalize(normal);
 
-                    vec2 tc;
-                    if (fyrox_graphicsSettings.usePOM) {
+                    triplanarWeights = S_TriplanarWeights(worldNormal, properties.triplanarSharpness);
+
+                    // Parallax mapping is not supported in triplanar mode, since there's no tangent space.
+                    if (fyrox_graphicsSettings.usePOM && !properties.useTriplanarMapping) {
                         vec3 toFragmentTangentSpace = normalize(transpose(tangentSpace) * toFragment);
-                        tc = S_ComputeParallaxTextureCoordinates(
+                        tc = S_ComputeParallaxOcclusionTextureCoordinates(
                             heightTexture,
                             toFragmentTangentSpace,
                             texCoord * properties.texCoordScale,
                             properties.parallaxCenter,
-                            properties.parallaxScale
+                            properties.parallaxScale,
+                            properties.parallaxMinSteps,
+                            properties.parallaxMaxSteps
                         );
+                        if (properties.parallaxClipSilhouette && S_IsParallaxSilhouetteClipped(tc, properties.texCoordScale)) {
+                            discard;
+                        }
                     } else {
                         tc = texCoord * properties.texCoordScale;
                     }
 
-                    outColor = properties.diffuseColor * texture(diffuseTexture, tc);
+                    float detailFactor = properties.detailStrength * S_DetailFade(
+                        distance(position, fyrox_cameraData.position),
+                        properties.detailFadeStart,
+                        properties.detailFadeEnd
+                    );
+
+                    outColor = properties.diffuseColor * instanceColor * SampleTexture(diffuseTexture);
+                    outColor.rgb *= mix(vec3(1.0), SampleDetailTexture(detailDiffuseTexture).rgb, detailFactor);
 
                     // Alpha test.
                     if (outColor.a < 0.5) {
                         discard;
                     }
-                    outColor.a = 1.0;
 
-                    vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                    // Dissolve test.
+                    float dissolveNoise = SampleTexture(dissolveTexture).r;
+                    if (dissolveNoise < properties.dissolveThreshold) {
+                        discard;
+                    }
+                    outColor.a = 1.0;
 
-                    outMaterial.x = texture(metallicTexture, tc).r;
-                    outMaterial.y = texture(roughnessTexture, tc).r;
-                    outMaterial.z = texture(aoTexture, tc).r;
+                    vec3 n;
+                    if (properties.useTriplanarMapping) {
+                        vec3 baseNormal = S_TriplanarNormal(normalTexture, position, worldNormal, triplanarWeights, properties.texCoordScale);
+                        vec3 detailNormal = S_TriplanarNormal(detailNormalTexture, position, worldNormal, triplanarWeights, properties.detailTexCoordScale);
+                        n = normalize(baseNormal + (detailNormal - worldNormal) * detailFactor);
+                    } else {
+                        vec3 baseNormal = normalize(texture(normalTexture, tc).xyz * 2.0 - 1.0);
+                        vec3 detailNormal = normalize(SampleDetailTexture(detailNormalTexture).xyz * 2.0 - 1.0);
+                        n = normalize(tangentSpace * S_BlendDetailNormal(baseNormal, detailNormal, detailFactor));
+                    }
+                    outNormal = vec4(n * 0.5 + 0.5, 1.0);
+
+                    outMaterial.x = SampleTexture(metallicTexture).r;
+                    outMaterial.y = SampleTexture(roughnessTexture).r;
+                    // Wet surfaces are darker and glossier.
+                    outColor.rgb *= mix(1.0, 0.6, properties.wetness);
+                    outMaterial.y = mix(outMaterial.y, 0.1, properties.wetness);
+                    outMaterial.z = SampleTexture(aoTexture).r;
                     outMaterial.a = 1.0;
 
-                    outAmbient.xyz = properties.emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz = properties.emissionStrength * SampleTexture(emissionTexture).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz += S_DissolveEdge(dissolveNoise, properties.dissolveThreshold, properties.dissolveEdgeWidth)
+                        * properties.dissolveEdgeStrength * properties.dissolveEdgeColor.rgb;
                     outAmbient.a = 1.0;
 
                     outDecalMask = properties.layerIndex;
+                    if (properties.lightingModel == 1u) {
+                        outDecalMask |= S_TOON_SHADING_BIT;
+                    }
+                    if (!properties.fogEnabled) {
+                        outDecalMask |= S_NO_FOG_BIT;
+                    }
                 }
                 "#,
         ),
@@ -316,9 +485,18 @@
 
                 out vec3 position;
                 out vec2 texCoord;
+                out vec4 instanceColor;
 
                 void main()
                 {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
                     vec4 localPosition = vec4(0);
 
                     vec4 inputPosition = vec4(vertexPosition, 1.0);
@@ -350,8 +528,9 @@
                     {
                         localPosition = inputPosition;
                     }
-                    gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
                     texCoord = vertexTexCoord;
+                    instanceColor = fyrox_instanceBatch.count > 0 ? fyrox_instanceBatch.colors[gl_InstanceID] : fyrox_instanceData.color;
                 }
                "#,
 
@@ -360,10 +539,129 @@
                 out vec4 FragColor;
 
                 in vec2 texCoord;
+                in vec4 instanceColor;
 
                 void main()
                 {
-                    FragColor = properties.diffuseColor * texture(diffuseTexture, texCoord);
+                    float dissolveNoise = texture(dissolveTexture, texCoord * properties.texCoordScale).r;
+                    if (dissolveNoise < properties.dissolveThreshold) discard;
+
+                    FragColor = properties.diffuseColor * instanceColor * texture(diffuseTexture, texCoord);
+                    FragColor.rgb += S_DissolveEdge(dissolveNoise, properties.dissolveThreshold, properties.dissolveEdgeWidth)
+                        * properties.dissolveEdgeStrength * properties.dissolveEdgeColor.rgb;
+                }
+               "#,
+        ),
+        (
+            name: "Outline",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Front),
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+            vertex_shader:
+               r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                out vec2 texCoord;
+
+                void main()
+                {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
+                    texCoord = vertexTexCoord;
+
+                    if (properties.outlineWidth <= 0.0) {
+                        // Outline is disabled, move the vertex out of the clip volume.
+                        gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+                        return;
+                    }
+
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+
+                    vec4 inputPosition = vec4(vertexPosition, 1.0);
+                    vec3 inputNormal = vertexNormal;
+
+                    for (int i = 0; i < fyrox_instanceData.blendShapesCount; ++i) {
+                        TBlendShapeOffsets offsets = S_FetchBlendShapeOffsets(blendShapesStorage, gl_VertexID, i);
+                        float weight = fyrox_instanceData.blendShapesWeights[i / 4][i % 4];
+                        inputPosition.xyz += offsets.position * weight;
+                        inputNormal += offsets.normal * weight;
+                    }
+
+                    if (fyrox_instanceData.useSkeletalAnimation)
+                    {
+                        mat4 m0 = fyrox_boneMatrices.matrices[int(boneIndices.x)];
+                        mat4 m1 = fyrox_boneMatrices.matrices[int(boneIndices.y)];
+                        mat4 m2 = fyrox_boneMatrices.matrices[int(boneIndices.z)];
+                        mat4 m3 = fyrox_boneMatrices.matrices[int(boneIndices.w)];
+
+                        localPosition += m0 * inputPosition * boneWeights.x;
+                        localPosition += m1 * inputPosition * boneWeights.y;
+                        localPosition += m2 * inputPosition * boneWeights.z;
+                        localPosition += m3 * inputPosition * boneWeights.w;
+
+                        localNormal += mat3(m0) * inputNormal * boneWeights.x;
+                        localNormal += mat3(m1) * inputNormal * boneWeights.y;
+                        localNormal += mat3(m2) * inputNormal * boneWeights.z;
+                        localNormal += mat3(m3) * inputNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = inputPosition;
+                        localNormal = inputNormal;
+                    }
+
+                    vec3 worldPosition = (worldMatrix * localPosition).xyz;
+                    vec3 worldNormal = normalize(mat3(worldMatrix) * localNormal);
+
+                    gl_Position = fyrox_cameraData.viewProjectionMatrix * vec4(S_OutlineExtrude(
+                        worldPosition,
+                        worldNormal,
+                        fyrox_cameraData.position,
+                        properties.outlineWidth,
+                        properties.outlineScreenSpace
+                    ), 1.0);
+                }
+               "#,
+
+           fragment_shader:
+               r#"
+                out vec4 FragColor;
+
+                in vec2 texCoord;
+
+                void main()
+                {
+                    if (texture(dissolveTexture, texCoord * properties.texCoordScale).r < properties.dissolveThreshold) discard;
+
+                    FragColor = properties.outlineColor;
                 }
                "#,
         ),
@@ -402,6 +700,14 @@
 
                 void main()
                 {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
                     vec4 localPosition = vec4(0);
 
                     vec4 inputPosition = vec4(vertexPosition, 1.0);
@@ -431,7 +737,7 @@
                         localPosition = inputPosition;
                     }
 
-                    gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -443,6 +749,7 @@
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (texture(dissolveTexture, texCoord * properties.texCoordScale).r < properties.dissolveThreshold) discard;
                 }
                 "#,
         ),
@@ -481,6 +788,14 @@
 
                 void main()
                 {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
                     vec4 localPosition = vec4(0);
 
                     vec4 inputPosition = vec4(vertexPosition, 1.0);
@@ -510,7 +825,7 @@
                         localPosition = inputPosition;
                     }
 
-                    gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
+                    gl_Position = worldViewProjection * localPosition;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -522,6 +837,7 @@
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (texture(dissolveTexture, texCoord * properties.texCoordScale).r < properties.dissolveThreshold) discard;
                 }
                 "#,
         ),
@@ -561,6 +877,14 @@
 
                 void main()
                 {
+                    mat4 worldMatrix = fyrox_instanceData.worldMatrix;
+                    mat4 worldViewProjection = fyrox_instanceData.worldViewProjection;
+                    if (fyrox_instanceBatch.count > 0)
+                    {
+                        worldMatrix = fyrox_instanceBatch.worldMatrices[gl_InstanceID];
+                        worldViewProjection = fyrox_cameraData.viewProjectionMatrix * worldMatrix;
+                    }
+
                     vec4 localPosition = vec4(0);
 
                     vec4 inputPosition = vec4(vertexPosition, 1.0);
@@ -590,8 +914,8 @@
                         localPosition = inputPosition;
                     }
 
-                    gl_Position = fyrox_instanceData.worldViewProjection * localPosition;
-                    worldPosition = (fyrox_instanceData.worldMatrix * localPosition).xyz;
+                    gl_Position = worldViewProjection * localPosition;
+                    worldPosition = (worldMatrix * localPosition).xyz;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -606,6 +930,7 @@
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (texture(dissolveTexture, texCoord * properties.texCoordScale).r < properties.dissolveThreshold) discard;
                     depth = length(fyrox_lightData.lightPosition - worldPosition);
                 }
                 "#,
@@ -0,0 +1,163 @@
+(
+    name: "StandardGpuParticleSystemShader",
+
+    resources: [
+        (
+            name: "diffuseTexture",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 0
+        ),
+        (
+            name: "fyrox_sceneDepth",
+            kind: Texture(kind: Sampler2D, fallback: White),
+            binding: 1
+        ),
+        (
+            name: "properties",
+            kind: PropertyGroup([
+                (
+                    name: "softBoundarySharpnessFactor",
+                    kind: Float(100.0),
+                ),
+                // Current simulation time of the particle system.
+                (
+                    name: "particleSystemTime",
+                    kind: Float(0.0),
+                ),
+                (
+                    name: "particleAcceleration",
+                    kind: Vector3((0.0, 0.0, 0.0)),
+                ),
+                (
+                    name: "particleAlphaFactor",
+                    kind: Float(1.0),
+                ),
+                // Evenly spaced samples of the color-over-lifetime gradient.
+                (
+                    name: "particleColorOverLifetime",
+                    kind: Vector4Array(value: [], max_len: 16),
+                ),
+            ]),
+            binding: 0
+        ),
+        (
+            name: "fyrox_instanceData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 1
+        ),
+        (
+            name: "fyrox_cameraData",
+            kind: PropertyGroup([
+                // Autogenerated
+            ]),
+            binding: 2
+        ),
+    ],
+
+    passes: [
+        (
+            name: "Forward",
+            draw_parameters: DrawParameters(
+                cull_face: None,
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: Some(Less),
+                blend: Some(BlendParameters(
+                    func: BlendFunc(
+                        sfactor: SrcAlpha,
+                        dfactor: OneMinusSrcAlpha,
+                        alpha_sfactor: SrcAlpha,
+                        alpha_dfactor: OneMinusSrcAlpha,
+                    ),
+                    equation: BlendEquation(
+                        rgb: Add,
+                        alpha: Add
+                    )
+                )),
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+                scissor_box: None
+            ),
+            vertex_shader:
+               r#"
+               layout(location = 0) in vec3 vertexPosition;
+               layout(location = 1) in vec2 vertexTexCoord;
+               layout(location = 2) in float particleSize;
+               layout(location = 3) in float particleRotation;
+               layout(location = 4) in vec4 particleVelocitySpawnTime;
+               layout(location = 5) in vec3 particleLifetimeSizeModifierRotationSpeed;
+
+               out vec2 texCoord;
+               out vec4 color;
+
+               void main()
+               {
+                   texCoord = vertexTexCoord;
+
+                   float age = properties.particleSystemTime - particleVelocitySpawnTime.w;
+                   float lifetime = particleLifetimeSizeModifierRotationSpeed.x;
+                   if (age < 0.0 || age >= lifetime)
+                   {
+                       // Dead particle, collapse it into a point so it won't be rasterized.
+                       color = vec4(0.0);
+                       gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+                       return;
+                   }
+
+                   float k = (age / lifetime) * 15.0;
+                   int index = int(k);
+                   vec4 gradientColor = mix(
+                       properties.particleColorOverLifetime[index],
+                       properties.particleColorOverLifetime[min(index + 1, 15)],
+                       fract(k));
+                   color = S_SRGBToLinear(gradientColor);
+                   color.a *= properties.particleAlphaFactor;
+
+                   vec3 position = vertexPosition + particleVelocitySpawnTime.xyz * age + 0.5 * properties.particleAcceleration * age * age;
+                   float size = max(particleSize + particleLifetimeSizeModifierRotationSpeed.y * age, 0.0);
+                   float rotation = particleRotation + particleLifetimeSizeModifierRotationSpeed.z * age;
+
+                   vec2 vertexOffset = S_RotateVec2(vertexTexCoord * 2.0 - 1.0, rotation);
+                   vec4 worldPosition = fyrox_instanceData.worldMatrix * vec4(position, 1.0);
+                   vec3 offset = (vertexOffset.x * fyrox_cameraData.sideVector + vertexOffset.y * fyrox_cameraData.upVector) * size;
+                   gl_Position = fyrox_cameraData.viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
+               }
+               "#,
+
+           fragment_shader:
+               r#"
+               out vec4 FragColor;
+               in vec2 texCoord;
+               in vec4 color;
+
+               float toProjSpace(float z)
+               {
+                   return (fyrox_cameraData.zFar * fyrox_cameraData.zNear) / (fyrox_cameraData.zFar - z * fyrox_cameraData.zRange);
+               }
+
+               void main()
+               {
+                   ivec2 depthTextureSize = textureSize(fyrox_sceneDepth, 0);
+                   vec2 pixelSize = vec2(1.0 / float(depthTextureSize.x), 1.0 / float(depthTextureSize.y));
+                   float sceneDepth = toProjSpace(texture(fyrox_sceneDepth, gl_FragCoord.xy * pixelSize).r);
+                   float fragmentDepth = toProjSpace(gl_FragCoord.z);
+                   float depthOpacity = smoothstep((sceneDepth - fragmentDepth) * properties.softBoundarySharpnessFactor, 0.0, 1.0);
+                   FragColor = color * S_SRGBToLinear(texture(diffuseTexture, texCoord)).r;
+                   FragColor.a *= depthOpacity;
+               }
+               "#,
+        )
+    ],
+)
\ No newline at end of file
@@ -0,0 +1,35 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Optional networking subsystem. It consists of three layers:
+//!
+//! - [`transport`] - a connection-based UDP transport with reliable (ordered) and unreliable
+//! channels.
+//! - [`snapshot`] - serializable snapshots of the state of scene nodes with delta compression.
+//! - [`replication`] - server-side replication of scene nodes with ownership and interest
+//! management, and its client-side counterpart.
+//!
+//! The subsystem is opt-in: the engine does not create any sockets by itself, a game decides
+//! whether it is a server or a client and drives the networking from its plugin (usually in
+//! [`crate::plugin::Plugin::update`]).
+
+pub mod replication;
+pub mod snapshot;
+pub mod transport;
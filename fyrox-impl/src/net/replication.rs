@@ -0,0 +1,613 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Server-side replication of scene nodes with ownership and interest management, and its
+//! client-side counterpart. See [`Replicator`] and [`SnapshotReceiver`] docs for more info.
+
+use crate::{
+    core::{log::Log, pool::Handle},
+    fxhash::{FxHashMap, FxHashSet},
+    graph::BaseSceneGraph,
+    net::snapshot::{NetworkId, NodeState, Snapshot, SnapshotDelta, SnapshotError},
+    scene::{graph::Graph, node::Node},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, net::SocketAddr};
+
+/// An owner of a replicated node. The owner is the only peer, that is allowed to change the state
+/// of the node.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Owner {
+    /// The node is owned by the server (default).
+    #[default]
+    Server,
+    /// The node is owned by the client with the given address, for example it could be the
+    /// character of a player. The client sends the state of the node to the server, which then
+    /// replicates it to other clients.
+    Client(SocketAddr),
+}
+
+/// A node, that is replicated by a [`Replicator`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplicatedNode {
+    /// Handle of the node in the server's scene graph.
+    pub handle: Handle<Node>,
+    /// Owner of the node.
+    pub owner: Owner,
+    /// If `true`, the node is replicated to every client, regardless of the distance from the
+    /// client's focus.
+    pub always_relevant: bool,
+}
+
+/// Interest management settings, they define which nodes are replicated to a client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterestSettings {
+    /// Nodes farther from the client's focus node than this radius are not replicated to the
+    /// client. Default is 100 units.
+    pub radius: f32,
+}
+
+impl Default for InterestSettings {
+    fn default() -> Self {
+        Self { radius: 100.0 }
+    }
+}
+
+/// A message of the replication protocol. It should be sent using
+/// [`crate::net::transport::NetSocket::send_message`]; snapshots and owner states should be sent
+/// over the unreliable channel, since they're sent every tick anyway.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReplicationMessage {
+    /// A snapshot delta sent from the server to a client.
+    Snapshot {
+        /// The snapshot delta.
+        delta: SnapshotDelta,
+        /// The nodes, that are owned by the receiving client.
+        owned: Vec<NetworkId>,
+    },
+    /// Acknowledgement of a received snapshot sent from a client to the server. The acknowledged
+    /// snapshot is used as the baseline for the next deltas.
+    Ack {
+        /// Tick of the acknowledged snapshot.
+        tick: u64,
+    },
+    /// A state of a node owned by a client, sent from the client to the server.
+    OwnerState {
+        /// Identifier of the node.
+        id: NetworkId,
+        /// New state of the node.
+        state: NodeState,
+    },
+}
+
+struct ClientState {
+    focus: Handle<Node>,
+    acknowledged: Option<u64>,
+    history: VecDeque<Snapshot>,
+}
+
+/// Server-side part of the scene replication. It assigns [network identifiers](NetworkId) to
+/// nodes, tracks their owners, decides which nodes are relevant for each client and makes
+/// delta-compressed snapshots for every client against the last snapshot acknowledged by the
+/// client.
+///
+/// A typical server loop looks like this:
+///
+/// ```rust,no_run
+/// use fyrox_impl::{
+///     net::{
+///         replication::{ReplicationMessage, Replicator},
+///         transport::{Channel, NetEvent, NetSocket},
+///     },
+///     scene::graph::Graph,
+/// };
+///
+/// fn server_tick(
+///     socket: &mut NetSocket,
+///     replicator: &mut Replicator,
+///     graph: &mut Graph,
+///     tick: u64,
+/// ) {
+///     for event in socket.update(1.0 / 60.0) {
+///         match event {
+///             NetEvent::Connected(peer) => replicator.add_client(peer, Default::default()),
+///             NetEvent::Disconnected(peer) => replicator.remove_client(peer),
+///             NetEvent::Message { peer, payload, .. } => {
+///                 if let Ok(message) = bincode::deserialize(&payload) {
+///                     replicator.handle_message(peer, message, graph);
+///                 }
+///             }
+///         }
+///     }
+///
+///     for (peer, message) in replicator.make_messages(graph, tick) {
+///         // Snapshots that do not fit into a datagram are dropped.
+///         let _ = socket.send_message(peer, Channel::Unreliable, &message);
+///     }
+/// }
+/// ```
+pub struct Replicator {
+    nodes: FxHashMap<NetworkId, ReplicatedNode>,
+    ids: FxHashMap<Handle<Node>, NetworkId>,
+    next_id: u64,
+    clients: FxHashMap<SocketAddr, ClientState>,
+    /// Interest management settings.
+    pub interest: InterestSettings,
+    /// Maximum amount of snapshots, that are kept per client to be used as baselines. If a client
+    /// does not acknowledge any of them, it receives full snapshots. Default is 32.
+    pub history_size: usize,
+}
+
+impl Default for Replicator {
+    fn default() -> Self {
+        Self {
+            nodes: Default::default(),
+            ids: Default::default(),
+            next_id: 1,
+            clients: Default::default(),
+            interest: Default::default(),
+            history_size: 32,
+        }
+    }
+}
+
+impl Replicator {
+    /// Starts replication of the given node and returns its network identifier. If the node is
+    /// already replicated, its current identifier is returned and the owner is changed.
+    pub fn replicate(&mut self, handle: Handle<Node>, owner: Owner) -> NetworkId {
+        if let Some(id) = self.ids.get(&handle).cloned() {
+            self.set_owner(id, owner);
+            return id;
+        }
+
+        let id = NetworkId(self.next_id);
+        self.next_id += 1;
+        self.nodes.insert(
+            id,
+            ReplicatedNode {
+                handle,
+                owner,
+                always_relevant: false,
+            },
+        );
+        self.ids.insert(handle, id);
+        id
+    }
+
+    /// Stops replication of the node with the given identifier. The node will be removed from the
+    /// next snapshots.
+    pub fn stop_replication(&mut self, id: NetworkId) -> Option<ReplicatedNode> {
+        let node = self.nodes.remove(&id)?;
+        self.ids.remove(&node.handle);
+        Some(node)
+    }
+
+    /// Returns a replicated node with the given identifier.
+    pub fn node(&self, id: NetworkId) -> Option<&ReplicatedNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Returns a network identifier of the node with the given handle.
+    pub fn id_of(&self, handle: Handle<Node>) -> Option<NetworkId> {
+        self.ids.get(&handle).cloned()
+    }
+
+    /// Sets new owner of the node with the given identifier.
+    pub fn set_owner(&mut self, id: NetworkId, owner: Owner) {
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.owner = owner;
+        }
+    }
+
+    /// Returns the owner of the node with the given identifier.
+    pub fn owner(&self, id: NetworkId) -> Option<Owner> {
+        self.nodes.get(&id).map(|node| node.owner)
+    }
+
+    /// Defines whether the node is replicated to every client, regardless of its distance from
+    /// the client's focus.
+    pub fn set_always_relevant(&mut self, id: NetworkId, always_relevant: bool) {
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.always_relevant = always_relevant;
+        }
+    }
+
+    /// Registers a new client. `focus` is a node, around which the interest area of the client
+    /// is defined (usually it is the player's character or camera). If the focus is not set, every
+    /// node is relevant for the client.
+    pub fn add_client(&mut self, client: SocketAddr, focus: Handle<Node>) {
+        self.clients.insert(
+            client,
+            ClientState {
+                focus,
+                acknowledged: None,
+                history: Default::default(),
+            },
+        );
+    }
+
+    /// Removes the client. Nodes owned by the client are transferred to the server.
+    pub fn remove_client(&mut self, client: SocketAddr) {
+        self.clients.remove(&client);
+        for node in self.nodes.values_mut() {
+            if node.owner == Owner::Client(client) {
+                node.owner = Owner::Server;
+            }
+        }
+    }
+
+    /// Sets new focus node of the client. See [`Self::add_client`] for more info.
+    pub fn set_client_focus(&mut self, client: SocketAddr, focus: Handle<Node>) {
+        if let Some(state) = self.clients.get_mut(&client) {
+            state.focus = focus;
+        }
+    }
+
+    /// Returns an iterator over the addresses of the registered clients.
+    pub fn clients(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.clients.keys().cloned()
+    }
+
+    /// Checks whether the node with the given identifier should be replicated to the client.
+    pub fn is_relevant(&self, graph: &Graph, client: SocketAddr, id: NetworkId) -> bool {
+        let (Some(state), Some(node)) = (self.clients.get(&client), self.nodes.get(&id)) else {
+            return false;
+        };
+
+        if node.always_relevant || node.owner == Owner::Client(client) {
+            return true;
+        }
+
+        let Some(focus) = graph.try_get(state.focus) else {
+            return true;
+        };
+
+        graph.try_get(node.handle).is_some_and(|node| {
+            node.global_position()
+                .metric_distance(&focus.global_position())
+                <= self.interest.radius
+        })
+    }
+
+    /// Captures the state of every replicated node. Nodes, that were removed from the graph, are
+    /// skipped.
+    pub fn capture(&self, graph: &Graph, tick: u64) -> Snapshot {
+        let mut snapshot = Snapshot::new(tick);
+        for (id, node) in self.nodes.iter() {
+            if let Some(node) = graph.try_get(node.handle) {
+                snapshot.nodes.insert(*id, NodeState::capture(node));
+            }
+        }
+        snapshot
+    }
+
+    /// Makes snapshot messages for every client. Every snapshot contains only the nodes relevant
+    /// for the client and it is delta-compressed against the last snapshot acknowledged by the
+    /// client.
+    pub fn make_messages(
+        &mut self,
+        graph: &Graph,
+        tick: u64,
+    ) -> Vec<(SocketAddr, ReplicationMessage)> {
+        let full = self.capture(graph, tick);
+
+        let clients = self.clients.keys().cloned().collect::<Vec<_>>();
+        let mut messages = Vec::with_capacity(clients.len());
+        for client in clients {
+            let mut snapshot = Snapshot::new(tick);
+            snapshot.nodes = full
+                .nodes
+                .iter()
+                .filter(|(id, _)| self.is_relevant(graph, client, **id))
+                .map(|(id, state)| (*id, state.clone()))
+                .collect();
+
+            let owned = snapshot
+                .nodes
+                .keys()
+                .filter(|id| self.owner(**id) == Some(Owner::Client(client)))
+                .cloned()
+                .collect();
+
+            let Some(state) = self.clients.get_mut(&client) else {
+                continue;
+            };
+
+            let baseline = state.acknowledged.and_then(|acknowledged| {
+                state
+                    .history
+                    .iter()
+                    .find(|snapshot| snapshot.tick == acknowledged)
+            });
+            let delta = snapshot.delta(baseline);
+
+            state.history.push_back(snapshot);
+            while state.history.len() > self.history_size {
+                state.history.pop_front();
+            }
+
+            messages.push((client, ReplicationMessage::Snapshot { delta, owned }));
+        }
+        messages
+    }
+
+    /// Handles a message received from a client. Owner states are applied to the graph only if
+    /// the client owns the node.
+    pub fn handle_message(
+        &mut self,
+        client: SocketAddr,
+        message: ReplicationMessage,
+        graph: &mut Graph,
+    ) {
+        match message {
+            ReplicationMessage::Ack { tick } => {
+                if let Some(state) = self.clients.get_mut(&client) {
+                    if state
+                        .acknowledged
+                        .map_or(true, |acknowledged| tick > acknowledged)
+                    {
+                        state.acknowledged = Some(tick);
+                        // Older snapshots won't be used as baselines anymore.
+                        state.history.retain(|snapshot| snapshot.tick >= tick);
+                    }
+                }
+            }
+            ReplicationMessage::OwnerState { id, state } => match self.nodes.get(&id) {
+                Some(node) if node.owner == Owner::Client(client) => {
+                    if let Some(node) = graph.try_get_mut(node.handle) {
+                        state.apply(node);
+                    }
+                }
+                _ => Log::warn(format!(
+                    "Client {client} tried to change the state of node {id}, that it does not own."
+                )),
+            },
+            ReplicationMessage::Snapshot { .. } => {
+                Log::warn(format!("Unexpected snapshot message from client {client}."))
+            }
+        }
+    }
+}
+
+/// Client-side part of the scene replication. It reconstructs snapshots from the deltas sent by
+/// the server and applies them to the local scene graph.
+///
+/// Replicated nodes must be bound to local nodes using [`Self::bind`], usually it is done when
+/// [`Self::apply`] reports an unknown node: the game decides what kind of node to spawn (for
+/// example, using the payload of the node state) and binds it. Nodes owned by the client are not
+/// changed by the snapshots, instead their state should be sent to the server using
+/// [`Self::owner_states`].
+#[derive(Default)]
+pub struct SnapshotReceiver {
+    history: VecDeque<Snapshot>,
+    mapping: FxHashMap<NetworkId, Handle<Node>>,
+    owned: FxHashSet<NetworkId>,
+    removed: Vec<NetworkId>,
+}
+
+impl SnapshotReceiver {
+    /// Maximum amount of received snapshots, that are kept to be used as baselines.
+    pub const HISTORY_SIZE: usize = 32;
+
+    /// Handles a snapshot message received from the server. Returns an acknowledgement message,
+    /// that should be sent back to the server, if the snapshot is newer than the latest one.
+    /// Other messages are ignored.
+    pub fn receive(
+        &mut self,
+        message: &ReplicationMessage,
+    ) -> Result<Option<ReplicationMessage>, SnapshotError> {
+        let ReplicationMessage::Snapshot { delta, owned } = message else {
+            return Ok(None);
+        };
+
+        if self
+            .latest()
+            .is_some_and(|latest| delta.tick <= latest.tick)
+        {
+            // Outdated snapshot.
+            return Ok(None);
+        }
+
+        let baseline = delta.baseline.and_then(|baseline| {
+            self.history
+                .iter()
+                .find(|snapshot| snapshot.tick == baseline)
+        });
+        let snapshot = delta.apply(baseline)?;
+
+        self.removed = self
+            .latest()
+            .map(|latest| {
+                latest
+                    .nodes
+                    .keys()
+                    .filter(|id| !snapshot.nodes.contains_key(id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.owned = owned.iter().cloned().collect();
+
+        let tick = snapshot.tick;
+        self.history.push_back(snapshot);
+        while self.history.len() > Self::HISTORY_SIZE {
+            self.history.pop_front();
+        }
+
+        Ok(Some(ReplicationMessage::Ack { tick }))
+    }
+
+    /// Returns the latest received snapshot.
+    pub fn latest(&self) -> Option<&Snapshot> {
+        self.history.back()
+    }
+
+    /// Binds the replicated node to a local node.
+    pub fn bind(&mut self, id: NetworkId, handle: Handle<Node>) {
+        self.mapping.insert(id, handle);
+    }
+
+    /// Unbinds the replicated node from its local node and returns the handle of the local node.
+    pub fn unbind(&mut self, id: NetworkId) -> Option<Handle<Node>> {
+        self.mapping.remove(&id)
+    }
+
+    /// Returns a handle of the local node, that is bound to the replicated node.
+    pub fn handle_of(&self, id: NetworkId) -> Option<Handle<Node>> {
+        self.mapping.get(&id).cloned()
+    }
+
+    /// Returns `true` if the node is owned by this client.
+    pub fn is_owned(&self, id: NetworkId) -> bool {
+        self.owned.contains(&id)
+    }
+
+    /// Returns the nodes, that were removed from the latest snapshot (they were destroyed on the
+    /// server or left the interest area of the client).
+    pub fn removed(&self) -> &[NetworkId] {
+        &self.removed
+    }
+
+    /// Applies the latest snapshot to the bound nodes, except the ones owned by this client.
+    /// Returns the identifiers of the nodes, that are not bound to local nodes yet.
+    pub fn apply(&self, graph: &mut Graph) -> Vec<NetworkId> {
+        let mut unknown = Vec::new();
+        if let Some(snapshot) = self.latest() {
+            for (id, state) in snapshot.nodes.iter() {
+                if self.is_owned(*id) {
+                    continue;
+                }
+                match self.mapping.get(id).and_then(|h| graph.try_get_mut(*h)) {
+                    Some(node) => state.apply(node),
+                    None => unknown.push(*id),
+                }
+            }
+        }
+        unknown
+    }
+
+    /// Makes messages with the states of the nodes owned by this client, they should be sent to
+    /// the server.
+    pub fn owner_states(&self, graph: &Graph) -> Vec<ReplicationMessage> {
+        self.owned
+            .iter()
+            .filter_map(|id| {
+                let node = graph.try_get(*self.mapping.get(id)?)?;
+                Some(ReplicationMessage::OwnerState {
+                    id: *id,
+                    state: NodeState::capture(node),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        net::replication::{Owner, ReplicationMessage, Replicator, SnapshotReceiver},
+        scene::{
+            base::BaseBuilder, graph::Graph, node::Node, pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+    use std::net::SocketAddr;
+
+    fn make_node(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(graph)
+    }
+
+    #[test]
+    fn test_replication_with_interest_and_ownership() {
+        let client: SocketAddr = "127.0.0.1:5000".parse().unwrap();
+
+        let mut server_graph = Graph::new();
+        let player = make_node(&mut server_graph, Vector3::new(0.0, 0.0, 0.0));
+        let near = make_node(&mut server_graph, Vector3::new(10.0, 0.0, 0.0));
+        let far = make_node(&mut server_graph, Vector3::new(1000.0, 0.0, 0.0));
+        server_graph.update_hierarchical_data();
+
+        let mut replicator = Replicator::default();
+        let player_id = replicator.replicate(player, Owner::Client(client));
+        let near_id = replicator.replicate(near, Owner::Server);
+        let far_id = replicator.replicate(far, Owner::Server);
+        replicator.add_client(client, player);
+
+        let mut client_graph = Graph::new();
+        let client_player = make_node(&mut client_graph, Vector3::new(1.0, 2.0, 3.0));
+        let client_near = make_node(&mut client_graph, Vector3::default());
+        let mut receiver = SnapshotReceiver::default();
+        receiver.bind(player_id, client_player);
+
+        // The first snapshot is sent in full, the far node is out of the interest area.
+        let (peer, message) = replicator.make_messages(&server_graph, 1).pop().unwrap();
+        assert_eq!(peer, client);
+        let ReplicationMessage::Snapshot { delta, .. } = &message else {
+            unreachable!()
+        };
+        assert_eq!(delta.baseline, None);
+        let ack = receiver.receive(&message).unwrap().unwrap();
+        assert!(receiver.is_owned(player_id));
+        assert_eq!(receiver.apply(&mut client_graph), vec![near_id]);
+        assert!(!receiver.latest().unwrap().nodes.contains_key(&far_id));
+        receiver.bind(near_id, client_near);
+        replicator.handle_message(client, ack, &mut server_graph);
+
+        // Move the near node on the server, the next snapshot is a delta.
+        server_graph[near]
+            .local_transform_mut()
+            .set_position(Vector3::new(20.0, 0.0, 0.0));
+        server_graph.update_hierarchical_data();
+        let (_, message) = replicator.make_messages(&server_graph, 2).pop().unwrap();
+        let ReplicationMessage::Snapshot { delta, .. } = &message else {
+            unreachable!()
+        };
+        assert_eq!(delta.baseline, Some(1));
+        assert_eq!(delta.changed.len(), 1);
+        receiver.receive(&message).unwrap();
+        assert!(receiver.apply(&mut client_graph).is_empty());
+        assert_eq!(
+            **client_graph[client_near].local_transform().position(),
+            Vector3::new(20.0, 0.0, 0.0)
+        );
+        // The owned node is not changed by the server.
+        assert_eq!(
+            **client_graph[client_player].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+
+        // The state of the owned node is accepted by the server.
+        for message in receiver.owner_states(&client_graph) {
+            replicator.handle_message(client, message, &mut server_graph);
+        }
+        assert_eq!(
+            **server_graph[player].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Scene state snapshots with delta compression. See [`Snapshot`] docs for more info.
+
+use crate::{
+    core::algebra::{UnitQuaternion, Vector3},
+    scene::node::Node,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// A unique identifier of a replicated node, it is the same on every peer (unlike node handles).
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct NetworkId(pub u64);
+
+impl Display for NetworkId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Replicated state of a single node.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeState {
+    /// Local position of the node.
+    pub position: Vector3<f32>,
+    /// Local rotation of the node.
+    pub rotation: UnitQuaternion<f32>,
+    /// Local scale of the node.
+    pub scale: Vector3<f32>,
+    /// Visibility of the node.
+    pub visible: bool,
+    /// Game-specific state of the node (health, animation state, etc.), it is not interpreted by
+    /// the engine.
+    pub payload: Vec<u8>,
+}
+
+impl NodeState {
+    /// Captures the state of the given node. The payload is left empty.
+    pub fn capture(node: &Node) -> Self {
+        let transform = node.local_transform();
+        Self {
+            position: **transform.position(),
+            rotation: **transform.rotation(),
+            scale: **transform.scale(),
+            visible: node.visibility(),
+            payload: Default::default(),
+        }
+    }
+
+    /// Applies the state to the given node. The payload is ignored, it should be applied by the
+    /// game.
+    pub fn apply(&self, node: &mut Node) {
+        node.local_transform_mut()
+            .set_position(self.position)
+            .set_rotation(self.rotation)
+            .set_scale(self.scale);
+        node.set_visibility(self.visible);
+    }
+}
+
+/// Changed parts of a [`NodeState`]. `None` means that the respective part was not changed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeStateDelta {
+    /// New local position of the node.
+    pub position: Option<Vector3<f32>>,
+    /// New local rotation of the node.
+    pub rotation: Option<UnitQuaternion<f32>>,
+    /// New local scale of the node.
+    pub scale: Option<Vector3<f32>>,
+    /// New visibility of the node.
+    pub visible: Option<bool>,
+    /// New payload of the node.
+    pub payload: Option<Vec<u8>>,
+}
+
+impl NodeStateDelta {
+    /// Creates a delta, that contains the entire state.
+    pub fn full(state: &NodeState) -> Self {
+        Self {
+            position: Some(state.position),
+            rotation: Some(state.rotation),
+            scale: Some(state.scale),
+            visible: Some(state.visible),
+            payload: Some(state.payload.clone()),
+        }
+    }
+
+    /// Creates a delta between two states. Returns `None` if the states are equal.
+    pub fn between(new: &NodeState, old: &NodeState) -> Option<Self> {
+        fn changed<T: PartialEq + Clone>(new: &T, old: &T) -> Option<T> {
+            (new != old).then(|| new.clone())
+        }
+
+        (new != old).then(|| Self {
+            position: changed(&new.position, &old.position),
+            rotation: changed(&new.rotation, &old.rotation),
+            scale: changed(&new.scale, &old.scale),
+            visible: changed(&new.visible, &old.visible),
+            payload: changed(&new.payload, &old.payload),
+        })
+    }
+
+    /// Applies the delta to the given state. Returns `None` if there's no base state and the
+    /// delta does not contain the entire state.
+    pub fn apply(&self, base: Option<&NodeState>) -> Option<NodeState> {
+        Some(NodeState {
+            position: self.position.or_else(|| base.map(|b| b.position))?,
+            rotation: self.rotation.or_else(|| base.map(|b| b.rotation))?,
+            scale: self.scale.or_else(|| base.map(|b| b.scale))?,
+            visible: self.visible.or_else(|| base.map(|b| b.visible))?,
+            payload: self
+                .payload
+                .clone()
+                .or_else(|| base.map(|b| b.payload.clone()))?,
+        })
+    }
+}
+
+/// An error, that may occur when a [`SnapshotDelta`] is applied.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotError {
+    /// The delta was made against a baseline snapshot, that is missing on the receiving side.
+    MissingBaseline(u64),
+    /// The delta does not contain the entire state of a new node.
+    IncompleteState(NetworkId),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::MissingBaseline(tick) => {
+                write!(f, "Baseline snapshot of tick {tick} is missing.")
+            }
+            SnapshotError::IncompleteState(id) => {
+                write!(f, "State of a new node {id} is incomplete.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// A snapshot of the state of replicated nodes at a particular simulation tick.
+///
+/// Snapshots are not sent as is, instead the sender makes a [`SnapshotDelta`] against the latest
+/// snapshot acknowledged by the receiver (the baseline). The delta contains only the changed
+/// parts of the state, which makes it much smaller than the full snapshot when only a few nodes
+/// are moving. The receiver reconstructs the full snapshot by applying the delta to the same
+/// baseline.
+///
+/// ```rust
+/// use fyrox_impl::{
+///     core::algebra::{UnitQuaternion, Vector3},
+///     net::snapshot::{NetworkId, NodeState, Snapshot},
+/// };
+///
+/// let state = NodeState {
+///     position: Vector3::new(1.0, 2.0, 3.0),
+///     rotation: UnitQuaternion::identity(),
+///     scale: Vector3::repeat(1.0),
+///     visible: true,
+///     payload: vec![],
+/// };
+///
+/// let mut baseline = Snapshot::new(1);
+/// baseline.nodes.insert(NetworkId(1), state.clone());
+///
+/// let mut current = Snapshot::new(2);
+/// current.nodes.insert(
+///     NetworkId(1),
+///     NodeState {
+///         position: Vector3::new(1.0, 3.0, 3.0),
+///         ..state
+///     },
+/// );
+///
+/// let delta = current.delta(Some(&baseline));
+/// assert_eq!(delta.apply(Some(&baseline)).unwrap(), current);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Simulation tick at which the snapshot was made.
+    pub tick: u64,
+    /// States of the replicated nodes.
+    pub nodes: BTreeMap<NetworkId, NodeState>,
+}
+
+impl Snapshot {
+    /// Creates an empty snapshot for the given tick.
+    pub fn new(tick: u64) -> Self {
+        Self {
+            tick,
+            nodes: Default::default(),
+        }
+    }
+
+    /// Makes a delta of this snapshot against the given baseline. If there's no baseline, the
+    /// delta contains the entire snapshot.
+    pub fn delta(&self, baseline: Option<&Snapshot>) -> SnapshotDelta {
+        let mut changed = Vec::new();
+        for (id, state) in self.nodes.iter() {
+            match baseline.and_then(|baseline| baseline.nodes.get(id)) {
+                Some(old) => {
+                    if let Some(delta) = NodeStateDelta::between(state, old) {
+                        changed.push((*id, delta));
+                    }
+                }
+                None => changed.push((*id, NodeStateDelta::full(state))),
+            }
+        }
+
+        let removed = baseline
+            .map(|baseline| {
+                baseline
+                    .nodes
+                    .keys()
+                    .filter(|id| !self.nodes.contains_key(id))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        SnapshotDelta {
+            tick: self.tick,
+            baseline: baseline.map(|baseline| baseline.tick),
+            changed,
+            removed,
+        }
+    }
+}
+
+/// Difference between a snapshot and its baseline. See [`Snapshot`] docs for more info.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    /// Simulation tick of the snapshot.
+    pub tick: u64,
+    /// Simulation tick of the baseline snapshot, `None` means that the delta contains the entire
+    /// snapshot.
+    pub baseline: Option<u64>,
+    /// Changed states of the nodes.
+    pub changed: Vec<(NetworkId, NodeStateDelta)>,
+    /// Nodes, that were removed from the snapshot.
+    pub removed: Vec<NetworkId>,
+}
+
+impl SnapshotDelta {
+    /// Reconstructs the full snapshot by applying the delta to the given baseline. The baseline
+    /// must be the same snapshot, that was used to make the delta.
+    pub fn apply(&self, baseline: Option<&Snapshot>) -> Result<Snapshot, SnapshotError> {
+        let mut snapshot = Snapshot::new(self.tick);
+
+        if let Some(baseline_tick) = self.baseline {
+            let baseline = baseline
+                .filter(|baseline| baseline.tick == baseline_tick)
+                .ok_or(SnapshotError::MissingBaseline(baseline_tick))?;
+            snapshot.nodes.clone_from(&baseline.nodes);
+        }
+
+        for id in self.removed.iter() {
+            snapshot.nodes.remove(id);
+        }
+
+        for (id, delta) in self.changed.iter() {
+            let state = delta
+                .apply(snapshot.nodes.get(id))
+                .ok_or(SnapshotError::IncompleteState(*id))?;
+            snapshot.nodes.insert(*id, state);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{UnitQuaternion, Vector3},
+        net::snapshot::{NetworkId, NodeState, Snapshot, SnapshotError},
+    };
+
+    fn state(x: f32) -> NodeState {
+        NodeState {
+            position: Vector3::new(x, 0.0, 0.0),
+            rotation: UnitQuaternion::identity(),
+            scale: Vector3::repeat(1.0),
+            visible: true,
+            payload: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_delta_compression() {
+        let mut baseline = Snapshot::new(10);
+        baseline.nodes.insert(NetworkId(1), state(0.0));
+        baseline.nodes.insert(NetworkId(2), state(1.0));
+        baseline.nodes.insert(NetworkId(3), state(2.0));
+
+        let mut current = Snapshot::new(11);
+        current.nodes.insert(NetworkId(1), state(0.0));
+        current.nodes.insert(NetworkId(2), state(5.0));
+        current.nodes.insert(NetworkId(4), state(3.0));
+
+        let delta = current.delta(Some(&baseline));
+        assert_eq!(delta.baseline, Some(10));
+        assert_eq!(delta.removed, vec![NetworkId(3)]);
+        assert_eq!(delta.changed.len(), 2);
+        // Only the position of the moved node is sent.
+        let (id, moved) = &delta.changed[0];
+        assert_eq!(*id, NetworkId(2));
+        assert!(moved.position.is_some());
+        assert!(moved.rotation.is_none() && moved.scale.is_none() && moved.payload.is_none());
+
+        // Serialized delta is smaller than the full snapshot.
+        let full = bincode::serialize(&current.delta(None)).unwrap();
+        let compressed = bincode::serialize(&delta).unwrap();
+        assert!(compressed.len() < full.len());
+
+        assert_eq!(delta.apply(Some(&baseline)).unwrap(), current);
+        assert_eq!(delta.apply(None), Err(SnapshotError::MissingBaseline(10)));
+        assert_eq!(current.delta(None).apply(None).unwrap(), current);
+    }
+}
@@ -0,0 +1,928 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Connection-oriented transport over UDP with reliable and unreliable channels. See [`NetSocket`]
+//! docs for more info.
+
+use crate::core::log::Log;
+use fxhash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+/// A magic number, that is used to filter out datagrams of other protocols.
+const PROTOCOL_ID: u32 = 0x4659_524E;
+
+/// Maximum size of a UDP datagram payload.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// Approximate size of the packet header and of the message header (in bytes), it is used to split
+/// outgoing messages into packets.
+const PACKET_OVERHEAD: usize = 32;
+const MESSAGE_OVERHEAD: usize = 16;
+
+/// Maximum size of a message payload (in bytes). Larger payloads do not fit into a single UDP
+/// datagram and are rejected by [`NetSocket::send`].
+pub const MAX_PAYLOAD_SIZE: usize = MAX_DATAGRAM_SIZE - PACKET_OVERHEAD - MESSAGE_OVERHEAD;
+
+/// Maximum amount of reliable messages, that could be received ahead of the next expected one.
+/// Senders never send messages beyond this window, receivers drop them.
+const RELIABLE_WINDOW: u16 = 256;
+
+/// Maximum total size (in bytes) of reliable messages in the window. It limits the amount of
+/// memory, that a peer could force the receiver to keep for out-of-order messages.
+const MAX_RELIABLE_WINDOW_BYTES: usize = 1024 * 1024;
+
+/// A channel, that is used to deliver a message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Messages could be lost, but they're never duplicated and a message is dropped if a newer
+    /// one was already received. Use it for frequently updated state, such as scene snapshots.
+    Unreliable,
+    /// Messages are guaranteed to be delivered exactly once and in the order they were sent. Use
+    /// it for events and commands, such as chat messages or spawn requests.
+    Reliable,
+}
+
+/// An event of a [`NetSocket`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetEvent {
+    /// The connection with the peer was established. It happens when the peer accepts a connection
+    /// request of this socket (see [`NetSocket::connect`]) or when the peer requests a connection
+    /// itself.
+    Connected(SocketAddr),
+    /// The peer was disconnected. It happens when the peer disconnects explicitly or when there
+    /// was no packets from it for [`NetSettings::timeout`] seconds.
+    Disconnected(SocketAddr),
+    /// A message was received from the peer.
+    Message {
+        /// Address of the peer.
+        peer: SocketAddr,
+        /// A channel, that was used to deliver the message.
+        channel: Channel,
+        /// Content of the message.
+        payload: Vec<u8>,
+    },
+}
+
+/// Settings of a [`NetSocket`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetSettings {
+    /// Time (in seconds) without incoming packets after which a peer is considered disconnected.
+    /// Default is 5 seconds.
+    pub timeout: f32,
+    /// Time (in seconds) after which an empty packet is sent to a peer, if there was nothing to
+    /// send. It keeps the connection alive. Default is 0.25 seconds.
+    pub heartbeat_interval: f32,
+    /// Minimal time (in seconds) after which an unacknowledged reliable message is sent again.
+    /// The actual interval also depends on the round-trip time of the connection. Default is 0.1
+    /// seconds.
+    pub resend_interval: f32,
+    /// Preferred maximum size of a packet (in bytes). Messages are packed into packets of this
+    /// size, a message that is larger than this size is sent in a separate packet. Default is 1200
+    /// bytes, which fits into MTU of most networks.
+    pub max_packet_size: usize,
+    /// Maximum amount of connections. Packets from new peers are ignored if the limit is reached.
+    /// Default is 64.
+    pub max_connections: usize,
+}
+
+impl Default for NetSettings {
+    fn default() -> Self {
+        Self {
+            timeout: 5.0,
+            heartbeat_interval: 0.25,
+            resend_interval: 0.1,
+            max_packet_size: 1200,
+            max_connections: 64,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+enum WireMessage {
+    Unreliable { sequence: u16, payload: Vec<u8> },
+    Reliable { id: u16, payload: Vec<u8> },
+    Connect,
+    Accept,
+    Disconnect,
+}
+
+impl WireMessage {
+    fn size(&self) -> usize {
+        MESSAGE_OVERHEAD
+            + match self {
+                WireMessage::Unreliable { payload, .. } | WireMessage::Reliable { payload, .. } => {
+                    payload.len()
+                }
+                WireMessage::Connect | WireMessage::Accept | WireMessage::Disconnect => 0,
+            }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Packet {
+    protocol: u32,
+    sequence: u16,
+    // The latest sequence number received from the remote side, if any.
+    ack: Option<u16>,
+    // Every bit `n` tells whether packet `ack - n - 1` was received or not.
+    ack_bits: u32,
+    messages: Vec<WireMessage>,
+}
+
+/// Checks whether sequence number `a` is newer than `b`, taking wrapping into account.
+fn sequence_greater_than(a: u16, b: u16) -> bool {
+    const HALF: u16 = u16::MAX / 2 + 1;
+    (a > b && a - b <= HALF) || (a < b && b - a > HALF)
+}
+
+struct PendingReliable {
+    id: u16,
+    payload: Vec<u8>,
+    last_sent: Option<f64>,
+}
+
+struct SentPacket {
+    time: f64,
+    reliable_ids: Vec<u16>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ConnectionState {
+    // A connection request was sent to the peer, but it wasn't accepted yet.
+    Connecting,
+    Connected,
+}
+
+struct Connection {
+    state: ConnectionState,
+    // Tells whether the connection request of the peer should be accepted in the next packet.
+    need_accept: bool,
+    local_sequence: u16,
+    next_reliable_id: u16,
+    next_unreliable_sequence: u16,
+    pending_reliable: VecDeque<PendingReliable>,
+    unreliable_queue: Vec<Vec<u8>>,
+    sent_packets: FxHashMap<u16, SentPacket>,
+    remote_sequence: Option<u16>,
+    ack_bits: u32,
+    next_expected_reliable: u16,
+    reliable_buffer: FxHashMap<u16, Vec<u8>>,
+    reliable_buffer_size: usize,
+    last_unreliable_sequence: Option<u16>,
+    need_ack: bool,
+    last_receive_time: f64,
+    last_send_time: f64,
+    rtt: f32,
+    disconnect: bool,
+}
+
+impl Connection {
+    fn new(state: ConnectionState, time: f64) -> Self {
+        Self {
+            state,
+            need_accept: false,
+            local_sequence: 0,
+            next_reliable_id: 0,
+            next_unreliable_sequence: 0,
+            pending_reliable: Default::default(),
+            unreliable_queue: Default::default(),
+            sent_packets: Default::default(),
+            remote_sequence: None,
+            ack_bits: 0,
+            next_expected_reliable: 0,
+            reliable_buffer: Default::default(),
+            reliable_buffer_size: 0,
+            last_unreliable_sequence: None,
+            need_ack: true,
+            last_receive_time: time,
+            last_send_time: f64::NEG_INFINITY,
+            rtt: 0.1,
+            disconnect: false,
+        }
+    }
+
+    fn acknowledge(&mut self, sequence: u16, time: f64) {
+        if let Some(packet) = self.sent_packets.remove(&sequence) {
+            let sample = (time - packet.time) as f32;
+            self.rtt += (sample - self.rtt) * 0.1;
+            self.pending_reliable
+                .retain(|message| !packet.reliable_ids.contains(&message.id));
+        }
+    }
+
+    fn on_packet_received(&mut self, sequence: u16) {
+        match self.remote_sequence {
+            None => {
+                self.remote_sequence = Some(sequence);
+            }
+            Some(remote) if sequence_greater_than(sequence, remote) => {
+                let shift = sequence.wrapping_sub(remote) as u32;
+                self.ack_bits = if shift > 32 {
+                    0
+                } else {
+                    // The previous "latest" packet goes to the bit field as well.
+                    (self.ack_bits.checked_shl(shift).unwrap_or(0)) | (1 << (shift - 1))
+                };
+                self.remote_sequence = Some(sequence);
+            }
+            Some(remote) => {
+                let diff = remote.wrapping_sub(sequence) as u32;
+                if (1..=32).contains(&diff) {
+                    self.ack_bits |= 1 << (diff - 1);
+                }
+            }
+        }
+        self.need_ack = true;
+    }
+
+    fn on_reliable_received(&mut self, id: u16, payload: Vec<u8>, output: &mut Vec<Vec<u8>>) {
+        // Messages, that are too far ahead, are dropped. Honest peers never send them (see
+        // `collect_outgoing`), so it only limits the memory a malicious peer could occupy.
+        let ahead = id.wrapping_sub(self.next_expected_reliable);
+        if ahead < RELIABLE_WINDOW
+            && !self.reliable_buffer.contains_key(&id)
+            && (ahead == 0
+                || self.reliable_buffer_size + payload.len() <= MAX_RELIABLE_WINDOW_BYTES)
+        {
+            self.reliable_buffer_size += payload.len();
+            self.reliable_buffer.insert(id, payload);
+        }
+
+        while let Some(payload) = self.reliable_buffer.remove(&self.next_expected_reliable) {
+            self.reliable_buffer_size -= payload.len();
+            output.push(payload);
+            self.next_expected_reliable = self.next_expected_reliable.wrapping_add(1);
+        }
+    }
+
+    fn on_unreliable_received(&mut self, sequence: u16) -> bool {
+        match self.last_unreliable_sequence {
+            Some(last) if !sequence_greater_than(sequence, last) => false,
+            _ => {
+                self.last_unreliable_sequence = Some(sequence);
+                true
+            }
+        }
+    }
+
+    fn resend_timeout(&self, settings: &NetSettings) -> f64 {
+        settings.resend_interval.max(self.rtt * 1.5) as f64
+    }
+
+    fn collect_outgoing(&mut self, time: f64, settings: &NetSettings) -> Vec<WireMessage> {
+        let resend_timeout = self.resend_timeout(settings);
+        let mut messages = Vec::new();
+        // Only the messages within the window of the oldest unacknowledged one are sent, the peer
+        // drops everything beyond it.
+        let first_id = self
+            .pending_reliable
+            .front()
+            .map_or(0, |message| message.id);
+        let mut window_size = 0;
+        for message in self.pending_reliable.iter_mut() {
+            window_size += message.payload.len();
+            if message.id.wrapping_sub(first_id) >= RELIABLE_WINDOW
+                || window_size > MAX_RELIABLE_WINDOW_BYTES
+            {
+                break;
+            }
+            if message
+                .last_sent
+                .map_or(true, |last_sent| time - last_sent >= resend_timeout)
+            {
+                message.last_sent = Some(time);
+                messages.push(WireMessage::Reliable {
+                    id: message.id,
+                    payload: message.payload.clone(),
+                });
+            }
+        }
+        for payload in self.unreliable_queue.drain(..) {
+            messages.push(WireMessage::Unreliable {
+                sequence: self.next_unreliable_sequence,
+                payload,
+            });
+            self.next_unreliable_sequence = self.next_unreliable_sequence.wrapping_add(1);
+        }
+        if self.disconnect {
+            messages.push(WireMessage::Disconnect);
+        }
+        messages
+    }
+
+    fn make_packet(&mut self, mut messages: Vec<WireMessage>, time: f64) -> Packet {
+        // Handshake messages are tiny, so they're put in every packet until the handshake is done.
+        if self.state == ConnectionState::Connecting {
+            messages.push(WireMessage::Connect);
+        }
+        if self.need_accept {
+            messages.push(WireMessage::Accept);
+            self.need_accept = false;
+        }
+
+        let sequence = self.local_sequence;
+        self.local_sequence = self.local_sequence.wrapping_add(1);
+
+        let reliable_ids = messages
+            .iter()
+            .filter_map(|message| match message {
+                WireMessage::Reliable { id, .. } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        self.sent_packets
+            .insert(sequence, SentPacket { time, reliable_ids });
+
+        self.need_ack = false;
+        self.last_send_time = time;
+
+        Packet {
+            protocol: PROTOCOL_ID,
+            sequence,
+            ack: self.remote_sequence,
+            ack_bits: self.ack_bits,
+            messages,
+        }
+    }
+}
+
+/// A UDP socket, that maintains virtual connections with remote peers and delivers messages over
+/// [reliable and unreliable](Channel) channels. The socket is non-blocking, it sends and receives
+/// packets only in [`NetSocket::update`], which should be called every frame.
+///
+/// A client connects to a server by calling [`NetSocket::connect`], which sends a connection
+/// request to the server until the server accepts it. The server creates a connection only when it
+/// receives such a request, packets of unknown peers are ignored. Every packet acknowledges the
+/// packets received from the peer, so reliable messages are re-sent until they're acknowledged.
+///
+/// ```rust,no_run
+/// use fyrox_impl::net::transport::{Channel, NetEvent, NetSettings, NetSocket};
+///
+/// let mut server = NetSocket::bind("127.0.0.1:7777", NetSettings::default()).unwrap();
+/// let mut client = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+/// let server_address = server.local_address().unwrap();
+/// client.connect(server_address);
+/// client
+///     .send(server_address, Channel::Reliable, b"Hello!".to_vec())
+///     .unwrap();
+///
+/// loop {
+///     client.update(1.0 / 60.0);
+///     for event in server.update(1.0 / 60.0) {
+///         if let NetEvent::Message { payload, .. } = event {
+///             assert_eq!(payload, b"Hello!");
+///             return;
+///         }
+///     }
+/// }
+/// ```
+pub struct NetSocket {
+    socket: UdpSocket,
+    settings: NetSettings,
+    connections: FxHashMap<SocketAddr, Connection>,
+    time: f64,
+    buffer: Vec<u8>,
+}
+
+impl NetSocket {
+    /// Binds a new socket to the given address. Use port `0` to let the OS pick a free port.
+    pub fn bind<A: ToSocketAddrs>(address: A, settings: NetSettings) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            settings,
+            connections: Default::default(),
+            time: 0.0,
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        })
+    }
+
+    /// Returns the local address of the socket.
+    pub fn local_address(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Returns current settings of the socket.
+    pub fn settings(&self) -> &NetSettings {
+        &self.settings
+    }
+
+    /// Sets new settings of the socket.
+    pub fn set_settings(&mut self, settings: NetSettings) {
+        self.settings = settings;
+    }
+
+    /// Creates a connection with the given peer and sends a connection request to it.
+    /// [`NetEvent::Connected`] will be emitted when the peer accepts the request. Messages could be
+    /// sent to the peer right away, they will be delivered once the connection is established.
+    pub fn connect(&mut self, peer: SocketAddr) {
+        let time = self.time;
+        self.connections
+            .entry(peer)
+            .or_insert_with(|| Connection::new(ConnectionState::Connecting, time));
+    }
+
+    /// Notifies the peer about disconnection and removes the connection.
+    pub fn disconnect(&mut self, peer: SocketAddr) {
+        if let Some(connection) = self.connections.get_mut(&peer) {
+            connection.disconnect = true;
+        }
+    }
+
+    /// Returns `true` if there's a connection with the given peer.
+    pub fn is_connected(&self, peer: SocketAddr) -> bool {
+        self.connections
+            .get(&peer)
+            .is_some_and(|connection| connection.state == ConnectionState::Connected)
+    }
+
+    /// Returns an iterator over the addresses of the connected peers.
+    pub fn peers(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.connections
+            .iter()
+            .filter(|(_, connection)| connection.state == ConnectionState::Connected)
+            .map(|(address, _)| *address)
+    }
+
+    /// Returns smoothed round-trip time (in seconds) of the connection with the given peer.
+    pub fn rtt(&self, peer: SocketAddr) -> Option<f32> {
+        self.connections.get(&peer).map(|connection| connection.rtt)
+    }
+
+    /// Queues a message for sending to the given peer. The message will be sent on the next
+    /// [`Self::update`] call. Fails if the peer is unknown or if the payload is larger than
+    /// [`MAX_PAYLOAD_SIZE`].
+    pub fn send(&mut self, peer: SocketAddr, channel: Channel, payload: Vec<u8>) -> io::Result<()> {
+        let Some(connection) = self.connections.get_mut(&peer) else {
+            return Err(io::Error::new(
+                ErrorKind::NotConnected,
+                format!("Unable to send a message to unknown peer {peer}."),
+            ));
+        };
+
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Unable to send a message of {} bytes to {peer}, it exceeds maximum payload \
+                    size of {MAX_PAYLOAD_SIZE} bytes.",
+                    payload.len()
+                ),
+            ));
+        }
+
+        match channel {
+            Channel::Unreliable => connection.unreliable_queue.push(payload),
+            Channel::Reliable => {
+                let id = connection.next_reliable_id;
+                connection.next_reliable_id = id.wrapping_add(1);
+                connection.pending_reliable.push_back(PendingReliable {
+                    id,
+                    payload,
+                    last_sent: None,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the given message and queues it for sending. See [`Self::send`] for more info.
+    pub fn send_message<T>(
+        &mut self,
+        peer: SocketAddr,
+        channel: Channel,
+        message: &T,
+    ) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        let payload = bincode::serialize(message).map_err(|err| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Unable to serialize a network message. Reason: {err}"),
+            )
+        })?;
+        self.send(peer, channel, payload)
+    }
+
+    /// Same as [`Self::send_message`], but sends the message to every connected peer.
+    pub fn broadcast_message<T>(&mut self, channel: Channel, message: &T) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        let peers = self.peers().collect::<Vec<_>>();
+        for peer in peers {
+            self.send_message(peer, channel, message)?;
+        }
+        Ok(())
+    }
+
+    /// Receives all pending packets, sends all queued messages and returns a list of events since
+    /// the last call. `dt` is the time passed since the last call (in seconds).
+    pub fn update(&mut self, dt: f32) -> Vec<NetEvent> {
+        self.time += dt as f64;
+
+        let mut events = Vec::new();
+        self.receive(&mut events);
+        self.check_timeouts(&mut events);
+        self.flush(&mut events);
+        events
+    }
+
+    fn receive(&mut self, events: &mut Vec<NetEvent>) {
+        loop {
+            let (size, peer) = match self.socket.recv_from(&mut self.buffer) {
+                Ok(result) => result,
+                Err(err) => match err.kind() {
+                    ErrorKind::WouldBlock => break,
+                    // Some platforms report ICMP "port unreachable" responses as errors of the
+                    // next receive call, they could be safely ignored.
+                    ErrorKind::Interrupted | ErrorKind::ConnectionReset => continue,
+                    _ => {
+                        Log::err(format!("Unable to receive a packet. Reason: {err}"));
+                        break;
+                    }
+                },
+            };
+
+            let packet = match bincode::deserialize::<Packet>(&self.buffer[..size]) {
+                Ok(packet) if packet.protocol == PROTOCOL_ID => packet,
+                _ => continue,
+            };
+
+            self.handle_packet(peer, packet, events);
+        }
+    }
+
+    fn handle_packet(&mut self, peer: SocketAddr, packet: Packet, events: &mut Vec<NetEvent>) {
+        let time = self.time;
+        let has_message =
+            |predicate: fn(&WireMessage) -> bool| packet.messages.iter().any(predicate);
+        let is_connect = has_message(|message| matches!(message, WireMessage::Connect));
+        let is_accept = has_message(|message| matches!(message, WireMessage::Accept));
+
+        if !self.connections.contains_key(&peer) {
+            // Only a connection request could create a new connection.
+            if !is_connect || self.connections.len() >= self.settings.max_connections {
+                return;
+            }
+            self.connections
+                .insert(peer, Connection::new(ConnectionState::Connected, time));
+            events.push(NetEvent::Connected(peer));
+        }
+        let Some(connection) = self.connections.get_mut(&peer) else {
+            return;
+        };
+
+        if connection.state == ConnectionState::Connecting {
+            // Wait until the peer accepts the connection request.
+            if !is_accept {
+                return;
+            }
+            connection.state = ConnectionState::Connected;
+            events.push(NetEvent::Connected(peer));
+        }
+
+        if is_connect {
+            // Previous acceptance could be lost, so every request must be answered.
+            connection.need_accept = true;
+        }
+
+        connection.last_receive_time = time;
+        connection.on_packet_received(packet.sequence);
+
+        if let Some(ack) = packet.ack {
+            connection.acknowledge(ack, time);
+            for bit in 0..32u16 {
+                if packet.ack_bits & (1 << bit) != 0 {
+                    connection.acknowledge(ack.wrapping_sub(bit + 1), time);
+                }
+            }
+        }
+
+        let mut reliable = Vec::new();
+        let mut disconnected = false;
+        for message in packet.messages {
+            match message {
+                WireMessage::Unreliable { sequence, payload } => {
+                    if connection.on_unreliable_received(sequence) {
+                        events.push(NetEvent::Message {
+                            peer,
+                            channel: Channel::Unreliable,
+                            payload,
+                        });
+                    }
+                }
+                WireMessage::Reliable { id, payload } => {
+                    connection.on_reliable_received(id, payload, &mut reliable);
+                }
+                WireMessage::Connect | WireMessage::Accept => (),
+                WireMessage::Disconnect => {
+                    disconnected = true;
+                }
+            }
+        }
+
+        events.extend(reliable.into_iter().map(|payload| NetEvent::Message {
+            peer,
+            channel: Channel::Reliable,
+            payload,
+        }));
+
+        if disconnected {
+            self.connections.remove(&peer);
+            events.push(NetEvent::Disconnected(peer));
+        }
+    }
+
+    fn check_timeouts(&mut self, events: &mut Vec<NetEvent>) {
+        let time = self.time;
+        let timeout = self.settings.timeout as f64;
+        self.connections.retain(|peer, connection| {
+            // Forget about old packets, they won't be acknowledged anyway.
+            connection
+                .sent_packets
+                .retain(|_, packet| time - packet.time < timeout);
+
+            if time - connection.last_receive_time > timeout {
+                events.push(NetEvent::Disconnected(*peer));
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn flush(&mut self, events: &mut Vec<NetEvent>) {
+        let time = self.time;
+        let max_packet_size = self.settings.max_packet_size.min(MAX_DATAGRAM_SIZE);
+        let mut disconnected = Vec::new();
+        for (peer, connection) in self.connections.iter_mut() {
+            let messages = connection.collect_outgoing(time, &self.settings);
+
+            let mut packets = Vec::new();
+            let mut current = Vec::new();
+            let mut current_size = PACKET_OVERHEAD;
+            for message in messages {
+                let size = message.size();
+                if !current.is_empty() && current_size + size > max_packet_size {
+                    packets.push(std::mem::take(&mut current));
+                    current_size = PACKET_OVERHEAD;
+                }
+                current_size += size;
+                current.push(message);
+            }
+            if !current.is_empty() {
+                packets.push(current);
+            }
+
+            if packets.is_empty()
+                && (connection.need_ack
+                    || time - connection.last_send_time >= self.settings.heartbeat_interval as f64)
+            {
+                // An empty packet still carries acknowledgements.
+                packets.push(Vec::new());
+            }
+
+            for messages in packets {
+                let packet = connection.make_packet(messages, time);
+                match bincode::serialize(&packet) {
+                    Ok(bytes) if bytes.len() <= MAX_DATAGRAM_SIZE => {
+                        if let Err(err) = self.socket.send_to(&bytes, peer) {
+                            if err.kind() != ErrorKind::WouldBlock {
+                                Log::err(format!(
+                                    "Unable to send a packet to {peer}. Reason: {err}"
+                                ));
+                            }
+                        }
+                    }
+                    Ok(bytes) => Log::err(format!(
+                        "Unable to send a packet of {} bytes to {peer}, it exceeds maximum \
+                        datagram size.",
+                        bytes.len()
+                    )),
+                    Err(err) => Log::err(format!("Unable to serialize a packet. Reason: {err}")),
+                }
+            }
+
+            if connection.disconnect {
+                disconnected.push(*peer);
+            }
+        }
+
+        for peer in disconnected {
+            self.connections.remove(&peer);
+            events.push(NetEvent::Disconnected(peer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::net::transport::{
+        sequence_greater_than, Channel, Connection, ConnectionState, NetEvent, NetSettings,
+        NetSocket, Packet, WireMessage, MAX_PAYLOAD_SIZE, MAX_RELIABLE_WINDOW_BYTES, PROTOCOL_ID,
+        RELIABLE_WINDOW,
+    };
+    use std::{io::ErrorKind, net::UdpSocket};
+
+    #[test]
+    fn test_sequence_wrapping() {
+        assert!(sequence_greater_than(1, 0));
+        assert!(!sequence_greater_than(0, 1));
+        assert!(sequence_greater_than(0, u16::MAX));
+        assert!(sequence_greater_than(10, u16::MAX - 10));
+        assert!(!sequence_greater_than(u16::MAX - 10, 10));
+    }
+
+    #[test]
+    fn test_reliable_window_is_bounded() {
+        let mut connection = Connection::new(ConnectionState::Connected, 0.0);
+        let mut output = Vec::new();
+
+        // Messages beyond the window are dropped.
+        connection.on_reliable_received(RELIABLE_WINDOW, vec![0; 16], &mut output);
+        assert!(connection.reliable_buffer.is_empty());
+
+        // Out-of-order messages are buffered until the total size limit is reached.
+        let payload_size = MAX_PAYLOAD_SIZE;
+        let mut id = 1;
+        while connection.reliable_buffer_size + payload_size <= MAX_RELIABLE_WINDOW_BYTES {
+            connection.on_reliable_received(id, vec![0; payload_size], &mut output);
+            id += 1;
+        }
+        let buffered = connection.reliable_buffer.len();
+        connection.on_reliable_received(id, vec![0; payload_size], &mut output);
+        assert_eq!(connection.reliable_buffer.len(), buffered);
+        assert!(connection.reliable_buffer_size <= MAX_RELIABLE_WINDOW_BYTES);
+        assert!(output.is_empty());
+
+        // The missing message releases everything buffered.
+        connection.on_reliable_received(0, vec![1], &mut output);
+        assert_eq!(output.len(), buffered + 1);
+        assert!(connection.reliable_buffer.is_empty());
+        assert_eq!(connection.reliable_buffer_size, 0);
+    }
+
+    #[test]
+    fn test_reliable_delivery_over_loopback() {
+        let mut server = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+        let mut client = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+        let server_address = server.local_address().unwrap();
+        let client_address = client.local_address().unwrap();
+
+        client.connect(server_address);
+        for i in 0..100u8 {
+            client
+                .send(server_address, Channel::Reliable, vec![i])
+                .unwrap();
+        }
+
+        let mut connected = false;
+        let mut received = Vec::new();
+        for _ in 0..200 {
+            client.update(0.01);
+            for event in server.update(0.01) {
+                match event {
+                    NetEvent::Connected(peer) => {
+                        assert_eq!(peer, client_address);
+                        connected = true;
+                    }
+                    NetEvent::Message {
+                        channel, payload, ..
+                    } => {
+                        assert_eq!(channel, Channel::Reliable);
+                        received.extend(payload);
+                    }
+                    NetEvent::Disconnected(_) => unreachable!(),
+                }
+            }
+            if received.len() == 100 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        assert!(connected);
+        assert_eq!(received, (0..100u8).collect::<Vec<_>>());
+
+        client.disconnect(server_address);
+        client.update(0.01);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(server
+            .update(0.01)
+            .contains(&NetEvent::Disconnected(client_address)));
+    }
+
+    #[test]
+    fn test_send_rejects_oversized_payload() {
+        let mut server = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+        let mut client = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+        let server_address = server.local_address().unwrap();
+
+        assert_eq!(
+            client
+                .send(server_address, Channel::Reliable, vec![0])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotConnected
+        );
+
+        client.connect(server_address);
+        for channel in [Channel::Reliable, Channel::Unreliable] {
+            assert_eq!(
+                client
+                    .send(server_address, channel, vec![0; MAX_PAYLOAD_SIZE + 1])
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidInput
+            );
+        }
+
+        // The largest payload must fit into a single datagram.
+        client
+            .send(server_address, Channel::Reliable, vec![1; MAX_PAYLOAD_SIZE])
+            .unwrap();
+        let mut received = None;
+        for _ in 0..200 {
+            client.update(0.01);
+            for event in server.update(0.01) {
+                if let NetEvent::Message { payload, .. } = event {
+                    received = Some(payload);
+                }
+            }
+            if received.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(received, Some(vec![1; MAX_PAYLOAD_SIZE]));
+    }
+
+    #[test]
+    fn test_connection_requires_handshake() {
+        let mut server = NetSocket::bind("127.0.0.1:0", NetSettings::default()).unwrap();
+        let server_address = server.local_address().unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let peer = socket.local_addr().unwrap();
+
+        let send = |messages| {
+            let packet = Packet {
+                protocol: PROTOCOL_ID,
+                sequence: 0,
+                ack: None,
+                ack_bits: 0,
+                messages,
+            };
+            socket
+                .send_to(&bincode::serialize(&packet).unwrap(), server_address)
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+
+        // A packet without a connection request must not create a connection.
+        send(vec![WireMessage::Reliable {
+            id: 0,
+            payload: vec![1],
+        }]);
+        assert!(server.update(0.01).is_empty());
+        assert!(!server.is_connected(peer));
+
+        send(vec![WireMessage::Connect]);
+        assert_eq!(server.update(0.01), [NetEvent::Connected(peer)]);
+        assert!(server.is_connected(peer));
+
+        // The server must accept the request.
+        let mut buffer = vec![0; 1024];
+        let (size, _) = socket.recv_from(&mut buffer).unwrap();
+        let packet = bincode::deserialize::<Packet>(&buffer[..size]).unwrap();
+        assert!(packet
+            .messages
+            .iter()
+            .any(|message| matches!(message, WireMessage::Accept)));
+    }
+}
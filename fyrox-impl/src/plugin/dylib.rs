@@ -25,6 +25,7 @@ use crate::{
     core::{
         log::Log,
         notify::{self, EventKind, RecursiveMode, Watcher},
+        parking_lot::Mutex,
     },
     plugin::Plugin,
 };
@@ -33,10 +34,8 @@ use std::{
     fs::File,
     io::Read,
     path::{Path, PathBuf},
-    sync::{
-        atomic::{self, AtomicBool},
-        Arc,
-    },
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use crate::plugin::DynamicPlugin;
@@ -99,6 +98,10 @@ pub struct DyLibDynamicPlugin {
     state: PluginState,
     /// Target path of the library of the plugin.
     lib_path: PathBuf,
+    /// Two paths, that are used in turns to load new versions of the library. New version of the
+    /// library is loaded while the previous one is still loaded, so the previous version could be
+    /// used if the new one has failed to load.
+    lib_paths: [PathBuf; 2],
     /// Path to the source file, that is emitted by the compiler. If hot reloading is enabled,
     /// this library will be cloned to `lib_path` and loaded. This is needed, because usually
     /// OS locks the library and it is not possible to overwrite it while it is loaded in a process.  
@@ -107,9 +110,14 @@ pub struct DyLibDynamicPlugin {
     /// the plugin if the source library has changed. If the watcher is `None`, then hot reloading
     /// is disabled.
     _watcher: Option<RecommendedWatcher>,
-    /// A flag, that tells the engine that the plugin needs to be reloaded. Usually the engine
-    /// will do that at the end of the update tick.
-    need_reload: Arc<AtomicBool>,
+    /// Time of the last change of the source library. The engine will reload the plugin when the
+    /// library stops changing for [`Self::reload_delay`]. Usually the engine will do that at the
+    /// end of the update tick.
+    last_change: Arc<Mutex<Option<Instant>>>,
+    /// Minimal amount of time between the last change of the source library and reloading. This is
+    /// needed, because the compiler (or the linker) could write the library in multiple steps and an
+    /// attempt to load partially written library will fail.
+    reload_delay: Duration,
 }
 
 impl DyLibDynamicPlugin {
@@ -123,7 +131,9 @@ impl DyLibDynamicPlugin {
     /// This method can enable hot reloading for the plugin, by setting `reload_when_changed` parameter
     /// to `true`. When enabled, the engine will clone the library to implementation-defined path
     /// and load it. It will setup file system watcher to receive changes from the OS and reload
-    /// the plugin.
+    /// the plugin. The state of the plugin and the content of the scenes, that belongs to the
+    /// plugin (scripts, custom nodes, etc.) is preserved across reloads using serialization. If the
+    /// new version of the library cannot be loaded, the previous version will continue to work.
     pub fn new<P>(
         path: P,
         reload_when_changed: bool,
@@ -154,24 +164,31 @@ impl DyLibDynamicPlugin {
                 .ok()
                 .and_then(|p| p.file_stem().map(|s| s.to_owned()))
                 .unwrap_or_default();
+            let mut second_suffix = suffix.clone();
             suffix.push(".module");
+            second_suffix.push(".module2");
             let lib_path = source_lib_path.with_extension(suffix);
+            let second_lib_path = source_lib_path.with_extension(second_suffix);
             try_copy_library(&source_lib_path, &lib_path)?;
 
-            let need_reload = Arc::new(AtomicBool::new(false));
-            let need_reload_clone = need_reload.clone();
+            let last_change = Arc::new(Mutex::new(None));
+            let last_change_clone = last_change.clone();
             let source_lib_path_clone = source_lib_path.clone();
 
             let mut watcher =
                 notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
                     if let Ok(event) = event {
                         if let EventKind::Modify(_) | EventKind::Create(_) = event.kind {
-                            need_reload_clone.store(true, atomic::Ordering::Relaxed);
+                            let mut last_change = last_change_clone.lock();
+
+                            if last_change.is_none() {
+                                Log::warn(format!(
+                                    "Plugin {} was changed. Performing hot reloading...",
+                                    source_lib_path_clone.display()
+                                ))
+                            }
 
-                            Log::warn(format!(
-                                "Plugin {} was changed. Performing hot reloading...",
-                                source_lib_path_clone.display()
-                            ))
+                            *last_change = Some(Instant::now());
                         }
                     }
                 })
@@ -188,22 +205,50 @@ impl DyLibDynamicPlugin {
 
             DyLibDynamicPlugin {
                 state: PluginState::Loaded(DyLibHandle::load(lib_path.as_os_str())?),
+                lib_paths: [lib_path.clone(), second_lib_path],
                 lib_path,
                 source_lib_path: source_lib_path.clone(),
                 _watcher: Some(watcher),
-                need_reload,
+                last_change,
+                reload_delay: Self::DEFAULT_RELOAD_DELAY,
             }
         } else {
             DyLibDynamicPlugin {
                 state: PluginState::Loaded(DyLibHandle::load(source_lib_path.as_os_str())?),
                 lib_path: source_lib_path.clone(),
+                lib_paths: [source_lib_path.clone(), source_lib_path.clone()],
                 source_lib_path: source_lib_path.clone(),
                 _watcher: None,
-                need_reload: Default::default(),
+                last_change: Default::default(),
+                reload_delay: Self::DEFAULT_RELOAD_DELAY,
             }
         };
         Ok(plugin)
     }
+
+    /// Default amount of time between the last change of the source library and reloading.
+    pub const DEFAULT_RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+    /// Sets the minimal amount of time between the last change of the source library and reloading.
+    /// Increase this value, if the engine tries to load the library before the compiler has finished
+    /// writing it.
+    pub fn set_reload_delay(&mut self, delay: Duration) {
+        self.reload_delay = delay;
+    }
+
+    /// Returns the minimal amount of time between the last change of the source library and
+    /// reloading.
+    pub fn reload_delay(&self) -> Duration {
+        self.reload_delay
+    }
+
+    fn next_lib_path(&self) -> PathBuf {
+        if self.lib_path == self.lib_paths[0] {
+            self.lib_paths[1].clone()
+        } else {
+            self.lib_paths[0].clone()
+        }
+    }
 }
 
 impl DynamicPlugin for DyLibDynamicPlugin {
@@ -216,7 +261,9 @@ impl DynamicPlugin for DyLibDynamicPlugin {
     }
 
     fn is_reload_needed_now(&self) -> bool {
-        self.need_reload.load(atomic::Ordering::Relaxed)
+        self.last_change
+            .lock()
+            .is_some_and(|time| time.elapsed() >= self.reload_delay)
     }
 
     fn display_name(&self) -> String {
@@ -231,40 +278,56 @@ impl DynamicPlugin for DyLibDynamicPlugin {
         &mut self,
         fill_and_register: &mut dyn FnMut(&mut dyn Plugin) -> Result<(), String>,
     ) -> Result<(), String> {
-        // Unload the plugin.
         let PluginState::Loaded(_) = &mut self.state else {
             return Err("cannot unload non-loaded plugin".to_string());
         };
 
-        self.state = PluginState::Unloaded;
-
-        Log::info(format!(
-            "Plugin {:?} was unloaded successfully!",
-            self.source_lib_path
-        ));
+        // The next reload will happen only when the library changes again.
+        *self.last_change.lock() = None;
 
-        // Replace the module.
-        try_copy_library(&self.source_lib_path, &self.lib_path)?;
+        // Load new version of the module side by side with the current one.
+        let lib_path = self.next_lib_path();
+        let new_version = try_copy_library(&self.source_lib_path, &lib_path)
+            .and_then(|_| DyLibHandle::load(&lib_path));
 
-        Log::info(format!(
-            "{:?} plugin's module {} was successfully cloned to {}.",
-            self.source_lib_path,
-            self.source_lib_path.display(),
-            self.lib_path.display()
-        ));
+        match new_version {
+            Ok(mut dynamic) => {
+                Log::info(format!(
+                    "{:?} plugin's module {} was successfully cloned to {} and loaded.",
+                    self.source_lib_path,
+                    self.source_lib_path.display(),
+                    lib_path.display()
+                ));
 
-        let mut dynamic = DyLibHandle::load(&self.lib_path)?;
+                // Unload the previous version of the plugin.
+                self.state = PluginState::Unloaded;
 
-        fill_and_register(dynamic.plugin_mut())?;
+                Log::info(format!(
+                    "Plugin {:?} was unloaded successfully!",
+                    self.source_lib_path
+                ));
 
-        self.state = PluginState::Loaded(dynamic);
+                fill_and_register(dynamic.plugin_mut())?;
 
-        self.need_reload.store(false, atomic::Ordering::Relaxed);
+                self.state = PluginState::Loaded(dynamic);
+                self.lib_path = lib_path;
 
-        Log::info(format!(
-            "Plugin {:?} was reloaded successfully!",
-            self.source_lib_path
-        ));
+                Log::info(format!(
+                    "Plugin {:?} was reloaded successfully!",
+                    self.source_lib_path
+                ));
+            }
+            Err(err) => {
+                Log::err(format!(
+                    "Unable to load new version of plugin {:?}. Reason: {err}. \
+                    The previous version will be used.",
+                    self.source_lib_path
+                ));
+
+                // Restore the state of the current version, the engine has already serialized it.
+                fill_and_register(self.state.as_loaded_mut().plugin_mut())?;
+            }
+        }
 
         Ok(())
     }
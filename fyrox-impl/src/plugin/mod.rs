@@ -32,8 +32,16 @@ use crate::{
         visitor::{Visit, VisitError},
     },
     engine::{
-        task::TaskPoolHandler, AsyncSceneLoader, GraphicsContext, PerformanceStatistics,
-        ScriptProcessor, SerializationContext,
+        gamepad::{GamepadEvent, GamepadManager},
+        input::InputMap,
+        load_scene,
+        overlay::ScreenOverlay,
+        prepare_loaded_scene,
+        profiler::ProfilerOverlay,
+        simulation::SimulationRng,
+        task::TaskPoolHandler,
+        AsyncSceneLoader, GraphicsContext, PerformanceStatistics, ScriptProcessor,
+        SerializationContext,
     },
     event::Event,
     gui::{
@@ -185,6 +193,86 @@ pub struct PluginContext<'a, 'b> {
 
     /// Task pool for asynchronous task management.
     pub task_pool: &'a mut TaskPoolHandler,
+
+    /// Screen overlay with common full-screen effects, such as fading and letterbox bars. See
+    /// [`ScreenOverlay`] docs for more info.
+    pub screen_overlay: &'a mut ScreenOverlay,
+
+    /// In-game overlay of the built-in frame profiler. See [`ProfilerOverlay`] docs for more info.
+    pub profiler_overlay: &'a mut ProfilerOverlay,
+
+    /// Gamepad manager, that could be used to query state of connected gamepads and to play rumble
+    /// effects. See [`GamepadManager`] docs for more info.
+    pub gamepads: &'a mut GamepadManager,
+
+    /// Input map, that maps physical inputs to named actions and axes. See [`InputMap`] docs for
+    /// more info.
+    pub input: &'a mut InputMap,
+
+    /// Seedable random number generator, that should be used for every random decision that affects
+    /// the game state. See [`SimulationRng`] docs for more info.
+    pub rng: &'a mut SimulationRng,
+}
+
+impl PluginContext<'_, '_> {
+    /// Loads a scene from the given path on a background thread using the task pool, adds it to
+    /// the engine and then calls `on_complete` closure with the handle of the new scene. The closure
+    /// is called on the main thread, at the beginning of the next update iteration after the scene
+    /// was loaded. The scene is loaded as derived scene, see [`AsyncSceneLoader::request`] for more
+    /// info.
+    ///
+    /// Unlike [`AsyncSceneLoader`], this method does not require implementing any [`Plugin`]
+    /// methods to get the loaded scene, and it does not notify plugins about the loading.
+    ///
+    /// ## Example
+    ///
+    /// ```rust ,no_run
+    /// # use fyrox_impl::{
+    /// #     core::{log::Log, pool::Handle, reflect::prelude::*, visitor::prelude::*},
+    /// #     plugin::{Plugin, PluginContext},
+    /// #     scene::Scene,
+    /// # };
+    /// #[derive(Visit, Reflect, Default, Debug)]
+    /// struct MyGame {
+    ///     scene: Handle<Scene>,
+    /// }
+    ///
+    /// impl MyGame {
+    ///     pub fn new(mut context: PluginContext) -> Self {
+    ///         context.load_scene("data/scene.rgs", |result, game: &mut MyGame, _context| {
+    ///             match result {
+    ///                 Ok(scene) => game.scene = scene,
+    ///                 Err(e) => Log::err(format!("Unable to load the scene: {e:?}")),
+    ///             }
+    ///         });
+    ///
+    ///         Self::default()
+    ///     }
+    /// }
+    ///
+    /// impl Plugin for MyGame {}
+    /// ```
+    pub fn load_scene<P, C>(&mut self, path: impl AsRef<Path>, on_complete: C)
+    where
+        P: Plugin,
+        for<'a, 'b> C:
+            Fn(Result<Handle<Scene>, VisitError>, &mut P, &mut PluginContext<'a, 'b>) + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let future = load_scene(
+            path.clone(),
+            self.serialization_context.clone(),
+            self.resource_manager.clone(),
+        );
+        self.task_pool
+            .spawn_plugin_task(future, move |result, plugin: &mut P, context| {
+                let result = result.map(|(mut scene, _)| {
+                    prepare_loaded_scene(&mut scene, &path, true, context.resource_manager);
+                    context.scenes.add(scene)
+                });
+                on_complete(result, plugin, context)
+            });
+    }
 }
 
 /// Base plugin automatically implements type casting for plugins.
@@ -313,6 +401,16 @@ pub trait Plugin: BasePlugin + Visit + Reflect {
     ) {
     }
 
+    /// The method is called when a gamepad was connected or disconnected, or when its state has
+    /// changed (a button was pressed, a stick was moved, etc.). See [`GamepadEvent`] docs for more
+    /// info.
+    fn on_gamepad_event(
+        &mut self,
+        #[allow(unused_variables)] event: &GamepadEvent,
+        #[allow(unused_variables)] context: PluginContext,
+    ) {
+    }
+
     /// The method is called when a graphics context was successfully created. It could be useful
     /// to catch the moment when it was just created and do something in response.
     fn on_graphics_context_initialized(
@@ -36,7 +36,9 @@ use crate::{
     },
     graph::BaseSceneGraph,
     material::{
-        self, shader::ShaderDefinition, MaterialProperty, MaterialPropertyGroup, MaterialResource,
+        self,
+        shader::{Shader, ShaderDefinition},
+        Material, MaterialProperty, MaterialPropertyBlock, MaterialPropertyGroup, MaterialResource,
     },
     renderer::{
         cache::{
@@ -56,16 +58,16 @@ use crate::{
             uniform::{ByteStorage, UniformBuffer},
             ElementRange,
         },
-        FallbackResources, LightData, RenderPassStatistics,
+        CullingStatistics, FallbackResources, LightData, RenderPassStatistics,
     },
     resource::texture::TextureResource,
     scene::{
-        graph::Graph,
+        graph::{bvh::BvhCulling, Graph},
         light::{
             directional::{CsmOptions, DirectionalLight},
             point::PointLight,
             spot::SpotLight,
-            BaseLight,
+            BaseLight, ShadowMapOptions, VolumetricLightOptions,
         },
         mesh::{
             buffer::{
@@ -86,8 +88,14 @@ use std::{
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
     rc::Rc,
+    sync::Arc,
 };
 
+/// Size of the `fyrox_instanceBatch` uniform block: instance count (padded to 16 bytes) followed by
+/// an array of world matrices and an array of colors.
+pub(crate) const INSTANCE_BATCH_BLOCK_SIZE: usize =
+    16 + ShaderDefinition::MAX_INSTANCES * (size_of::<Matrix4<f32>>() + size_of::<Vector4<f32>>());
+
 /// Observer info contains all the data, that describes an observer. It could be a real camera, light source's
 /// "virtual camera" that is used for shadow mapping, etc.
 #[derive(Clone, Default)]
@@ -119,6 +127,9 @@ pub struct RenderContext<'a> {
     pub graph: &'a Graph,
     /// A name of the render pass for which the context was created for.
     pub render_pass_name: &'a ImmutableString,
+    /// Whether skinned meshes could be skinned on the GPU or not. If `false`, nodes must transform
+    /// vertices of skinned surfaces on the CPU. See [`crate::renderer::QualitySettings::use_gpu_skinning`].
+    pub use_gpu_skinning: bool,
 }
 
 impl<'a> RenderContext<'a> {
@@ -168,6 +179,26 @@ pub struct SurfaceInstanceData {
     pub element_range: ElementRange,
     /// A handle of a node that emitted this surface data. Could be none, if there's no info about scene node.
     pub node_handle: Handle<Node>,
+    /// Per-instance overrides of the material properties and textures. See [`MaterialPropertyBlock`]
+    /// docs for more info. The block is shared with the node that emitted the instance, so it is not
+    /// copied every frame.
+    pub property_block: Option<Arc<MaterialPropertyBlock>>,
+    /// A color of the instance. It is available in shaders as `fyrox_instanceData.color` (or as
+    /// `fyrox_instanceBatch.colors[gl_InstanceID]` for instanced draw calls) and it is multiplied
+    /// with the diffuse color by the standard shader.
+    pub color: Color,
+}
+
+impl SurfaceInstanceData {
+    /// Returns `true` if the instance could be drawn together with other instances of the same
+    /// bundle using a single instanced draw call. Only instances that have no skinning, no blend
+    /// shapes, no material property overrides and that are drawn entirely could be instanced.
+    pub fn can_be_instanced(&self) -> bool {
+        self.bone_matrices.is_empty()
+            && self.blend_shapes_weights.is_empty()
+            && self.property_block.is_none()
+            && matches!(self.element_range, ElementRange::Full)
+    }
 }
 
 /// A set of surface instances that share the same vertex/index data and a material.
@@ -183,6 +214,10 @@ pub struct RenderDataBundle {
     pub material: MaterialResource,
     /// A render path of the bundle.
     pub render_path: RenderPath,
+    /// Amount of primitives (a sprite, a whole particle system, etc.) that were merged into this
+    /// bundle by dynamic batching. Every call of [`RenderDataBundleStorageTrait::push_triangles`]
+    /// adds one primitive. Zero for regular bundles.
+    pub batched_primitives: usize,
     sort_index: u64,
 }
 
@@ -197,13 +232,29 @@ impl Debug for RenderDataBundle {
     }
 }
 
+/// Describes a group of surface instances, that are drawn using a single instanced draw call.
+pub struct InstanceBatchUniformData {
+    /// Amount of instances in the batch.
+    pub count: usize,
+    /// Location of the block with world matrices and colors of the instances.
+    pub block: UniformBlockLocation,
+}
+
 /// Describes where to the actual uniform data is located in the memory backed by the uniform
 /// memory allocator on per-instance basis.
 pub struct InstanceUniformData {
+    /// Index of the instance in the bundle.
+    pub instance_index: usize,
+    /// Instanced batch, that starts from the instance. Could be [`None`], if the instance is drawn
+    /// on its own.
+    pub batch: Option<InstanceBatchUniformData>,
     /// Instance info block location.
     pub instance_block: UniformBlockLocation,
     /// Bone matrices block location. Could be [`None`], if there's no bone matrices.
     pub bone_matrices_block: Option<UniformBlockLocation>,
+    /// Binding point and location of the material property group with per-instance overrides. Could be
+    /// [`None`], if the instance does not override any material properties.
+    pub property_block_group: Option<(usize, UniformBlockLocation)>,
 }
 
 /// Describes where to the actual uniform data is located in the memory backed by the uniform
@@ -213,7 +264,7 @@ pub struct BundleUniformData {
     pub material_property_group_blocks: Vec<(usize, UniformBlockLocation)>,
     /// Lights info block location.
     pub light_data_block: UniformBlockLocation,
-    /// Block locations for each instance in a bundle.
+    /// Block locations for each visible instance (or a batch of instances) in a bundle.
     pub instance_blocks: Vec<InstanceUniformData>,
 }
 
@@ -228,13 +279,19 @@ pub struct GlobalUniformData {
 
 fn write_with_material<T: ByteStorage>(
     shader_property_group: &[ShaderProperty],
-    material_property_group: &MaterialPropertyGroup,
+    material_property_group: Option<&MaterialPropertyGroup>,
+    overrides: Option<&MaterialPropertyGroup>,
     buf: &mut UniformBuffer<T>,
 ) {
     // The order of fields is strictly defined in shader, so we must iterate over shader definition
     // of a structure and look for respective values in the material.
     for shader_property in shader_property_group {
-        let material_property = material_property_group.property_ref(shader_property.name.clone());
+        let material_property = overrides
+            .and_then(|overrides| overrides.property_ref(shader_property.name.clone()))
+            .or_else(|| {
+                material_property_group
+                    .and_then(|group| group.property_ref(shader_property.name.clone()))
+            });
 
         macro_rules! push_value {
             ($variant:ident, $shader_value:ident) => {
@@ -334,13 +391,99 @@ fn write_shader_values<T: ByteStorage>(
     }
 }
 
+fn write_instance_uniforms(
+    instance_index: usize,
+    instance: &SurfaceInstanceData,
+    material: &Material,
+    shader: &Shader,
+    view_projection_matrix: &Matrix4<f32>,
+    render_context: &mut BundleRenderContext,
+) -> InstanceUniformData {
+    let instance_buffer = StaticUniformBuffer::<1024>::new()
+        .with(&instance.world_transform)
+        .with(&(view_projection_matrix * instance.world_transform))
+        .with(&(instance.blend_shapes_weights.len() as i32))
+        .with(&(!instance.bone_matrices.is_empty()))
+        .with_slice_with_max_size(
+            &instance.blend_shapes_weights,
+            ShaderDefinition::MAX_BLEND_SHAPE_WEIGHT_GROUPS,
+        )
+        .with(&instance.color);
+
+    let mut instance_uniform_data = InstanceUniformData {
+        instance_index,
+        batch: None,
+        instance_block: render_context
+            .uniform_memory_allocator
+            .allocate(instance_buffer),
+        bone_matrices_block: None,
+        property_block_group: None,
+    };
+
+    if let Some(property_block) = instance.property_block.as_ref() {
+        if !property_block.properties().properties().is_empty() {
+            // Property block overrides the standard property group only.
+            let group_name = ImmutableString::new("properties");
+            for resource_definition in shader.definition.resources.iter() {
+                let ShaderResourceKind::PropertyGroup(ref shader_property_group) =
+                    resource_definition.kind
+                else {
+                    continue;
+                };
+
+                if resource_definition.name != group_name {
+                    continue;
+                }
+
+                let mut buf = StaticUniformBuffer::<16384>::new();
+                write_with_material(
+                    shader_property_group,
+                    material.property_group_ref(group_name.clone()),
+                    Some(property_block.properties()),
+                    &mut buf,
+                );
+                instance_uniform_data.property_block_group = Some((
+                    resource_definition.binding,
+                    render_context.uniform_memory_allocator.allocate(buf),
+                ));
+            }
+        }
+    }
+
+    if !instance.bone_matrices.is_empty() {
+        const INIT: Matrix4<f32> = Matrix4::new(
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        );
+        let mut matrices = [INIT; ShaderDefinition::MAX_BONE_MATRICES];
+        const SIZE: usize = ShaderDefinition::MAX_BONE_MATRICES * size_of::<Matrix4<f32>>();
+        // Extra bones are ignored, such meshes are skinned on the CPU by the nodes themselves.
+        let count = instance
+            .bone_matrices
+            .len()
+            .min(ShaderDefinition::MAX_BONE_MATRICES);
+        matrices[0..count].copy_from_slice(&instance.bone_matrices[0..count]);
+
+        let bone_matrices_block = render_context
+            .uniform_memory_allocator
+            .allocate(StaticUniformBuffer::<SIZE>::new().with_slice(&matrices));
+        instance_uniform_data.bone_matrices_block = Some(bone_matrices_block);
+    }
+
+    instance_uniform_data
+}
+
 impl RenderDataBundle {
-    /// Writes all the required uniform data of the bundle to uniform memory allocator.
-    pub fn write_uniforms(
+    /// Writes all the required uniform data of the bundle to uniform memory allocator. Instances,
+    /// that do not pass the given filter, are skipped.
+    pub fn write_uniforms<F>(
         &self,
         view_projection_matrix: &Matrix4<f32>,
+        instance_filter: &mut F,
         render_context: &mut BundleRenderContext,
-    ) -> Option<BundleUniformData> {
+    ) -> Option<BundleUniformData>
+    where
+        F: FnMut(&SurfaceInstanceData) -> bool,
+    {
         let mut material_state = self.material.state();
 
         let material = material_state.data()?;
@@ -365,7 +508,12 @@ impl RenderDataBundle {
             if let Some(material_property_group) =
                 material.property_group_ref(resource_definition.name.clone())
             {
-                write_with_material(shader_property_group, material_property_group, &mut buf);
+                write_with_material(
+                    shader_property_group,
+                    Some(material_property_group),
+                    None,
+                    &mut buf,
+                );
             } else {
                 // No respective resource bound in the material, use shader defaults. This is very
                 // important, because some drivers will crash if uniform buffer has insufficient
@@ -384,38 +532,65 @@ impl RenderDataBundle {
             .with(&render_context.ambient_light.as_frgba());
         let light_data_block = render_context.uniform_memory_allocator.allocate(light_data);
 
-        // Upload instance uniforms.
+        // Upload instance uniforms. Instances that could be instanced are merged into batches, if
+        // the shader supports instancing.
+        let supports_instancing = shader
+            .definition
+            .resources
+            .iter()
+            .any(|resource| resource.name.as_str() == "fyrox_instanceBatch");
         let mut instance_blocks = Vec::with_capacity(self.instances.len());
-        for instance in self.instances.iter() {
-            let instance_buffer = StaticUniformBuffer::<1024>::new()
-                .with(&instance.world_transform)
-                .with(&(view_projection_matrix * instance.world_transform))
-                .with(&(instance.blend_shapes_weights.len() as i32))
-                .with(&(!instance.bone_matrices.is_empty()))
-                .with_slice_with_max_size(
-                    &instance.blend_shapes_weights,
-                    ShaderDefinition::MAX_BLEND_SHAPE_WEIGHT_GROUPS,
-                );
+        let mut instanceable = Vec::new();
+        for (instance_index, instance) in self.instances.iter().enumerate() {
+            if !instance_filter(instance) {
+                continue;
+            }
 
-            let mut instance_uniform_data = InstanceUniformData {
-                instance_block: render_context
-                    .uniform_memory_allocator
-                    .allocate(instance_buffer),
-                bone_matrices_block: None,
-            };
+            if supports_instancing && instance.can_be_instanced() {
+                instanceable.push(instance_index);
+            } else {
+                instance_blocks.push(write_instance_uniforms(
+                    instance_index,
+                    instance,
+                    material,
+                    &shader,
+                    view_projection_matrix,
+                    render_context,
+                ));
+            }
+        }
 
-            if !instance.bone_matrices.is_empty() {
-                const INIT: Matrix4<f32> = Matrix4::new(
-                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-                );
-                let mut matrices = [INIT; ShaderDefinition::MAX_BONE_MATRICES];
-                const SIZE: usize = ShaderDefinition::MAX_BONE_MATRICES * size_of::<Matrix4<f32>>();
-                matrices[0..instance.bone_matrices.len()].copy_from_slice(&instance.bone_matrices);
-
-                let bone_matrices_block = render_context
-                    .uniform_memory_allocator
-                    .allocate(StaticUniformBuffer::<SIZE>::new().with_slice(&matrices));
-                instance_uniform_data.bone_matrices_block = Some(bone_matrices_block);
+        for batch in instanceable.chunks(ShaderDefinition::MAX_INSTANCES) {
+            let first = batch[0];
+            let mut instance_uniform_data = write_instance_uniforms(
+                first,
+                &self.instances[first],
+                material,
+                &shader,
+                view_projection_matrix,
+                render_context,
+            );
+
+            if batch.len() > 1 {
+                let mut world_matrices =
+                    ArrayVec::<Matrix4<f32>, { ShaderDefinition::MAX_INSTANCES }>::new();
+                let mut colors = ArrayVec::<Color, { ShaderDefinition::MAX_INSTANCES }>::new();
+                for index in batch {
+                    let instance = &self.instances[*index];
+                    world_matrices.push(instance.world_transform);
+                    colors.push(instance.color);
+                }
+
+                let batch_buffer = StaticUniformBuffer::<INSTANCE_BATCH_BLOCK_SIZE>::new()
+                    .with(&(batch.len() as i32))
+                    .with_slice_with_max_size(&world_matrices, ShaderDefinition::MAX_INSTANCES)
+                    .with_slice_with_max_size(&colors, ShaderDefinition::MAX_INSTANCES);
+                instance_uniform_data.batch = Some(InstanceBatchUniformData {
+                    count: batch.len(),
+                    block: render_context
+                        .uniform_memory_allocator
+                        .allocate(batch_buffer),
+                });
             }
 
             instance_blocks.push(instance_uniform_data);
@@ -429,19 +604,15 @@ impl RenderDataBundle {
     }
 
     /// Draws the entire bundle to the specified frame buffer with the specified rendering environment.
-    pub fn render_to_frame_buffer<F>(
+    pub fn render_to_frame_buffer(
         &self,
         server: &dyn GraphicsServer,
         geometry_cache: &mut GeometryCache,
         shader_cache: &mut ShaderCache,
-        instance_filter: &mut F,
         render_context: &mut BundleRenderContext,
         bundle_uniform_data: BundleUniformData,
         global_uniform_data: &GlobalUniformData,
-    ) -> Result<RenderPassStatistics, FrameworkError>
-    where
-        F: FnMut(&SurfaceInstanceData) -> bool,
-    {
+    ) -> Result<RenderPassStatistics, FrameworkError> {
         let mut stats = RenderPassStatistics::default();
 
         let mut material_state = self.material.state();
@@ -563,14 +734,8 @@ impl RenderDataBundle {
             }
         }
 
-        for (instance, uniform_data) in self
-            .instances
-            .iter()
-            .zip(bundle_uniform_data.instance_blocks)
-        {
-            if !instance_filter(instance) {
-                continue;
-            }
+        for uniform_data in bundle_uniform_data.instance_blocks {
+            let instance = &self.instances[uniform_data.instance_index];
             let mut instance_bindings = ArrayVec::<ResourceBinding, 32>::new();
 
             for resource_definition in shader.definition.resources.iter() {
@@ -584,6 +749,27 @@ impl RenderDataBundle {
                             ),
                         );
                     }
+                    "fyrox_instanceBatch" => match uniform_data.batch {
+                        Some(ref batch) => {
+                            instance_bindings.push(
+                                render_context
+                                    .uniform_memory_allocator
+                                    .block_to_binding(batch.block, resource_definition.binding),
+                            );
+                        }
+                        None => {
+                            // Zero instance count tells the shader to use the instance data.
+                            instance_bindings.push(ResourceBinding::Buffer {
+                                buffer: &*render_context
+                                    .fallback_resources
+                                    .instance_batch_stub_uniform_buffer,
+                                binding: BufferLocation::Explicit {
+                                    binding: resource_definition.binding,
+                                },
+                                data_usage: Default::default(),
+                            });
+                        }
+                    },
                     "fyrox_boneMatrices" => {
                         match uniform_data.bone_matrices_block {
                             Some(block) => {
@@ -608,25 +794,81 @@ impl RenderDataBundle {
                             }
                         }
                     }
-                    _ => (),
+                    // Per-instance overrides must be bound after the material resources, so they
+                    // will replace the respective material resources at the same binding points.
+                    _ => match resource_definition.kind {
+                        ShaderResourceKind::Texture { fallback, .. } => {
+                            let Some(texture) = instance
+                                .property_block
+                                .as_ref()
+                                .and_then(|block| block.texture(resource_definition.name.clone()))
+                            else {
+                                continue;
+                            };
+
+                            let texture = render_context
+                                .texture_cache
+                                .get(server, texture)
+                                .unwrap_or_else(|| {
+                                    render_context.fallback_resources.sampler_fallback(fallback)
+                                });
+
+                            instance_bindings.push(ResourceBinding::texture_with_binding(
+                                texture,
+                                resource_definition.binding,
+                            ));
+                        }
+                        ShaderResourceKind::PropertyGroup(_) => {
+                            if let Some((binding, block)) = uniform_data.property_block_group {
+                                if binding == resource_definition.binding {
+                                    instance_bindings.push(
+                                        render_context
+                                            .uniform_memory_allocator
+                                            .block_to_binding(block, binding),
+                                    );
+                                }
+                            }
+                        }
+                    },
                 };
             }
 
-            stats += render_context.frame_buffer.draw(
-                geometry,
-                render_context.viewport,
-                &*render_pass.program,
-                &render_pass.draw_params,
-                &[
-                    ResourceBindGroup {
-                        bindings: &material_bindings,
-                    },
-                    ResourceBindGroup {
-                        bindings: &instance_bindings,
-                    },
-                ],
-                instance.element_range,
-            )?;
+            let resources = [
+                ResourceBindGroup {
+                    bindings: &material_bindings,
+                },
+                ResourceBindGroup {
+                    bindings: &instance_bindings,
+                },
+            ];
+
+            if let Some(batch) = uniform_data.batch {
+                stats += render_context.frame_buffer.draw_instances(
+                    batch.count,
+                    geometry,
+                    render_context.viewport,
+                    &*render_pass.program,
+                    &render_pass.draw_params,
+                    &resources,
+                );
+                stats.instanced_draw_calls += 1;
+                stats.instanced_meshes += batch.count;
+            } else {
+                stats += render_context.frame_buffer.draw(
+                    geometry,
+                    render_context.viewport,
+                    &*render_pass.program,
+                    &render_pass.draw_params,
+                    &resources,
+                    instance.element_range,
+                )?;
+            }
+
+            // A single primitive is drawn the same way without batching.
+            if self.batched_primitives > 1 {
+                stats.dynamic_batches += 1;
+                stats.batched_primitives += self.batched_primitives;
+            }
         }
 
         Ok(stats)
@@ -686,10 +928,14 @@ pub enum LightSourceKind {
         distance: f32,
         shadow_bias: f32,
         cookie_texture: Option<TextureResource>,
+        shadow_map_options: ShadowMapOptions,
+        volumetric_options: VolumetricLightOptions,
     },
     Point {
         radius: f32,
         shadow_bias: f32,
+        shadow_map_options: ShadowMapOptions,
+        volumetric_options: VolumetricLightOptions,
     },
     Directional {
         csm_options: CsmOptions,
@@ -722,16 +968,20 @@ pub struct RenderDataBundleStorage {
     /// A sorted list of bundles.
     pub bundles: Vec<RenderDataBundle>,
     pub light_sources: Vec<LightSource>,
+    /// Frustum culling statistics, that was gathered while the storage was filled with render data.
+    pub culling: CullingStatistics,
 }
 
 pub struct RenderDataBundleStorageOptions {
     pub collect_lights: bool,
+    pub use_gpu_skinning: bool,
 }
 
 impl Default for RenderDataBundleStorageOptions {
     fn default() -> Self {
         Self {
             collect_lights: true,
+            use_gpu_skinning: true,
         }
     }
 }
@@ -739,7 +989,11 @@ impl Default for RenderDataBundleStorageOptions {
 impl RenderDataBundleStorage {
     /// Creates a new render bundle storage from the given graph and observer info. It "asks" every node in the
     /// graph one-by-one to give render data which is then put in the storage, sorted and ready for rendering.
-    /// Frustum culling is done on scene node side ([`crate::scene::node::NodeTrait::collect_render_data`]).
+    /// Nodes with frustum culling enabled ([`crate::scene::base::Base::frustum_culling`]) and valid bounds are
+    /// tested against the observer's frustum using the bounding volume hierarchy of the graph ([`Graph::bvh`])
+    /// before asking them for render data, nodes outside the frustum are skipped (but not their
+    /// descendants). Nodes can also do additional culling on their side
+    /// ([`crate::scene::node::NodeTrait::collect_render_data`]).
     pub fn from_graph(
         graph: &Graph,
         observer_info: ObserverInfo,
@@ -753,6 +1007,7 @@ impl RenderDataBundleStorage {
             observer_info: observer_info.clone(),
             bundles: Vec::with_capacity(capacity),
             light_sources: Default::default(),
+            culling: Default::default(),
         };
 
         let frustum = Frustum::from_view_projection_matrix(
@@ -792,11 +1047,15 @@ impl RenderDataBundleStorage {
                                 distance: spot_light.distance(),
                                 shadow_bias: spot_light.shadow_bias(),
                                 cookie_texture: spot_light.cookie_texture(),
+                                shadow_map_options: spot_light.shadow_map_options().clone(),
+                                volumetric_options: spot_light.volumetric_options().clone(),
                             }
                         } else if let Some(point_light) = node.cast::<PointLight>() {
                             LightSourceKind::Point {
                                 radius: point_light.radius(),
                                 shadow_bias: point_light.shadow_bias(),
+                                shadow_map_options: point_light.shadow_map_options().clone(),
+                                volumetric_options: point_light.volumetric_options().clone(),
                             }
                         } else if let Some(directional_light) = node.cast::<DirectionalLight>() {
                             LightSourceKind::Directional {
@@ -834,6 +1093,7 @@ impl RenderDataBundleStorage {
             storage: &mut storage,
             graph,
             render_pass_name: &render_pass_name,
+            use_gpu_skinning: options.use_gpu_skinning,
         };
 
         #[inline(always)]
@@ -841,19 +1101,34 @@ impl RenderDataBundleStorage {
             node_handle: Handle<Node>,
             graph: &Graph,
             lod_filter: &[bool],
+            culling: &BvhCulling,
             ctx: &mut RenderContext,
         ) {
             if lod_filter[node_handle.index() as usize] {
                 let node = graph.node(node_handle);
-                if let RdcControlFlow::Continue = node.collect_render_data(ctx) {
+
+                // The hierarchy could be built before the latest changes of the node, so the flag
+                // is checked once more to not cull a node that has frustum culling disabled.
+                let visible = !culling.is_culled(node_handle) || !node.frustum_culling();
+
+                if !visible || node.collect_render_data(ctx) == RdcControlFlow::Continue {
                     for child in node.children() {
-                        iterate_recursive(*child, graph, lod_filter, ctx);
+                        iterate_recursive(*child, graph, lod_filter, culling, ctx);
                     }
                 }
             }
         }
 
-        iterate_recursive(graph.root(), graph, &lod_filter, &mut ctx);
+        // Nodes that are not in the hierarchy (for example, the ones that were added after the
+        // last graph update) are never culled here.
+        let culling = graph.bvh().cull(&frustum);
+
+        iterate_recursive(graph.root(), graph, &lod_filter, &culling, &mut ctx);
+
+        storage.culling = CullingStatistics {
+            tested_objects: culling.tested_count(),
+            culled_objects: culling.culled_count(),
+        };
 
         storage.sort();
 
@@ -862,7 +1137,8 @@ impl RenderDataBundleStorage {
 
     /// Sorts the bundles by their respective sort index.
     pub fn sort(&mut self) {
-        self.bundles.sort_unstable_by_key(|b| b.sort_index);
+        // Stable sorting is used to preserve the order of bundles with the same sort index.
+        self.bundles.sort_by_key(|b| b.sort_index);
     }
 
     pub fn write_global_uniform_blocks(
@@ -975,8 +1251,11 @@ impl RenderDataBundleStorage {
             if !bundle_filter(bundle) {
                 continue;
             }
-            bundle_uniform_data_set
-                .push(bundle.write_uniforms(&view_projection, &mut render_context));
+            bundle_uniform_data_set.push(bundle.write_uniforms(
+                &view_projection,
+                &mut instance_filter,
+                &mut render_context,
+            ));
         }
         render_context.uniform_memory_allocator.upload(server)?;
 
@@ -992,7 +1271,6 @@ impl RenderDataBundleStorage {
                     server,
                     geometry_cache,
                     shader_cache,
-                    &mut instance_filter,
                     &mut render_context,
                     bundle_uniform_data,
                     &global_uniforms,
@@ -1073,16 +1351,21 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
                         blend_shapes_weights: Default::default(),
                         element_range: Default::default(),
                         node_handle,
+                        property_block: None,
+                        color: Color::WHITE,
                     },
                 ],
                 material: material.clone(),
                 render_path,
                 // Temporary buffer lives one frame.
                 time_to_live: TimeToLive(0.0),
+                batched_primitives: 0,
             });
             self.bundles.last_mut().unwrap()
         };
 
+        bundle.batched_primitives += 1;
+
         let mut data = bundle.data.data_ref();
         let data = &mut *data;
 
@@ -1120,6 +1403,7 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
                 material: material.clone(),
                 render_path,
                 time_to_live: Default::default(),
+                batched_primitives: 0,
             });
             self.bundles.last_mut().unwrap()
         };
@@ -1127,3 +1411,244 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
         bundle.instances.push(instance_data)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{
+            algebra::{Matrix4, Point3, Vector2, Vector3},
+            color::Color,
+            pool::Handle,
+            sstorage::ImmutableString,
+        },
+        material::{
+            shader::Shader, Material, MaterialPropertyBlock, MaterialPropertyGroup,
+            MaterialResource,
+        },
+        renderer::{
+            bundle::{
+                write_shader_values, write_with_material, ObserverInfo, RenderDataBundleStorage,
+                RenderDataBundleStorageOptions, SurfaceInstanceData, INSTANCE_BATCH_BLOCK_SIZE,
+            },
+            framework::{
+                gpu_program::{ShaderProperty, ShaderPropertyKind, ShaderResourceKind},
+                uniform::StaticUniformBuffer,
+                ElementRange,
+            },
+        },
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                MeshBuilder,
+            },
+            node::Node,
+            sprite::SpriteBuilder,
+            transform::TransformBuilder,
+        },
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_write_with_material_overrides() {
+        let shader_property_group = [
+            ShaderProperty::new("a", ShaderPropertyKind::Float(1.0)),
+            ShaderProperty::new("b", ShaderPropertyKind::Float(1.0)),
+            ShaderProperty::new("c", ShaderPropertyKind::Float(1.0)),
+        ];
+
+        let mut material_group = MaterialPropertyGroup::default();
+        material_group.set_property("a", 2.0f32);
+        material_group.set_property("b", 2.0f32);
+
+        let mut overrides = MaterialPropertyGroup::default();
+        overrides.set_property("b", 3.0f32);
+
+        let mut buf = StaticUniformBuffer::<256>::new();
+        write_with_material(
+            &shader_property_group,
+            Some(&material_group),
+            Some(&overrides),
+            &mut buf,
+        );
+
+        let expected = StaticUniformBuffer::<256>::new()
+            .with(&2.0f32)
+            .with(&3.0f32)
+            .with(&1.0f32);
+
+        assert_eq!(buf.finish(), expected.finish());
+    }
+
+    #[test]
+    fn test_can_be_instanced() {
+        let instance = || SurfaceInstanceData {
+            world_transform: Matrix4::identity(),
+            bone_matrices: Default::default(),
+            blend_shapes_weights: Default::default(),
+            element_range: ElementRange::Full,
+            node_handle: Handle::NONE,
+            property_block: None,
+            color: Color::WHITE,
+        };
+
+        assert!(instance().can_be_instanced());
+        assert!(!SurfaceInstanceData {
+            bone_matrices: vec![Matrix4::identity()],
+            ..instance()
+        }
+        .can_be_instanced());
+        assert!(!SurfaceInstanceData {
+            blend_shapes_weights: vec![1.0],
+            ..instance()
+        }
+        .can_be_instanced());
+        assert!(!SurfaceInstanceData {
+            element_range: ElementRange::Specific {
+                offset: 0,
+                count: 1
+            },
+            ..instance()
+        }
+        .can_be_instanced());
+        assert!(!SurfaceInstanceData {
+            property_block: Some(Arc::new(MaterialPropertyBlock::default())),
+            ..instance()
+        }
+        .can_be_instanced());
+    }
+
+    #[test]
+    fn test_instance_batch_block_size() {
+        let shader = Shader::from_string(
+            r#"(
+                name: "Test",
+                resources: [
+                    (
+                        name: "fyrox_instanceBatch",
+                        kind: PropertyGroup([]),
+                        binding: 0
+                    ),
+                ],
+                passes: [],
+            )"#,
+        )
+        .unwrap();
+
+        let ShaderResourceKind::PropertyGroup(ref properties) = shader.definition.resources[0].kind
+        else {
+            unreachable!()
+        };
+
+        // The block written by the renderer must match the layout generated for shaders.
+        let mut buf = StaticUniformBuffer::<16384>::new();
+        write_shader_values(properties, &mut buf);
+        assert_eq!(buf.len(), INSTANCE_BATCH_BLOCK_SIZE);
+    }
+
+    fn cube(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .build()])
+        .build(graph)
+    }
+
+    fn rendered_nodes(graph: &Graph) -> (RenderDataBundleStorage, Vec<Handle<Node>>) {
+        // The observer is at the origin and looks along -Z axis.
+        let observer_info = ObserverInfo {
+            observer_position: Default::default(),
+            z_near: 0.1,
+            z_far: 100.0,
+            view_matrix: Matrix4::look_at_rh(
+                &Point3::new(0.0, 0.0, 0.0),
+                &Point3::new(0.0, 0.0, -1.0),
+                &Vector3::y(),
+            ),
+            projection_matrix: Matrix4::new_perspective(1.0, 90.0f32.to_radians(), 0.1, 100.0),
+        };
+
+        let storage = RenderDataBundleStorage::from_graph(
+            graph,
+            observer_info,
+            ImmutableString::new("Forward"),
+            RenderDataBundleStorageOptions {
+                collect_lights: false,
+                use_gpu_skinning: false,
+            },
+        );
+
+        let nodes = storage
+            .bundles
+            .iter()
+            .flat_map(|bundle| bundle.instances.iter())
+            .map(|instance| instance.node_handle)
+            .collect();
+
+        (storage, nodes)
+    }
+
+    #[test]
+    fn test_from_graph_frustum_culling() {
+        let mut graph = Graph::new();
+        let front = cube(&mut graph, Vector3::new(0.0, 0.0, -5.0));
+        let behind = cube(&mut graph, Vector3::new(0.0, 0.0, 5.0));
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+        assert_eq!(graph.bvh().len(), 2);
+
+        let (storage, nodes) = rendered_nodes(&graph);
+        assert_eq!(storage.culling.tested_objects, 2);
+        assert_eq!(storage.culling.culled_objects, 1);
+        assert!(nodes.contains(&front));
+        assert!(!nodes.contains(&behind));
+
+        // The hierarchy must follow the changes of the graph.
+        graph[behind]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 0.0, -10.0));
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+
+        let (storage, nodes) = rendered_nodes(&graph);
+        assert_eq!(storage.culling.tested_objects, 2);
+        assert_eq!(storage.culling.culled_objects, 0);
+        assert!(nodes.contains(&front));
+        assert!(nodes.contains(&behind));
+    }
+
+    #[test]
+    fn test_push_triangles_batches_regardless_of_distance() {
+        let mut graph = Graph::new();
+        let material = MaterialResource::new_ok(Default::default(), Material::standard_sprite());
+        let sprites = [-5.0, -10.0].map(|z| {
+            SpriteBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, z))
+                        .build(),
+                ),
+            )
+            .with_material(material.clone())
+            .build(&mut graph)
+        });
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+
+        let (storage, _) = rendered_nodes(&graph);
+        let bundles = storage
+            .bundles
+            .iter()
+            .filter(|bundle| bundle.material == material)
+            .collect::<Vec<_>>();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].batched_primitives, sprites.len());
+    }
+}
@@ -19,16 +19,24 @@
 // SOFTWARE.
 
 use crate::{
-    core::log::{Log, MessageKind},
+    core::{
+        algebra::Vector3,
+        log::{Log, MessageKind},
+    },
+    graph::BaseSceneGraph,
+    material::MaterialResourceBinding,
     renderer::{
+        bundle::{ObserverInfo, RenderDataBundle},
         cache::{TemporaryCache, TimeToLive},
         framework::{
             error::FrameworkError,
             gpu_texture::{Coordinate, GpuTexture, PixelKind},
             server::GraphicsServer,
         },
+        TextureStreamingSettings,
     },
-    resource::texture::{Texture, TextureResource},
+    resource::texture::{Texture, TextureKind, TextureResource},
+    scene::graph::Graph,
 };
 use fyrox_graphics::gpu_texture::GpuTextureDescriptor;
 use std::{cell::RefCell, rc::Rc};
@@ -36,37 +44,115 @@ use std::{cell::RefCell, rc::Rc};
 pub(crate) struct TextureRenderData {
     pub gpu_texture: Rc<RefCell<dyn GpuTexture>>,
     pub modifications_counter: u64,
+    /// The most detailed mip level, that is resident in video memory.
+    pub base_mip: usize,
+    /// Size of the resident mip levels in bytes.
+    pub resident_bytes: usize,
+    /// The most detailed mip level requested by the renderer since the last streaming update.
+    pub requested_mip: Option<usize>,
+    /// A texture resource of the streamed texture. It is needed to upload (or evict) mip levels on
+    /// demand, `None` for the textures that are not streamed.
+    pub streamed_resource: Option<TextureResource>,
 }
 
 #[derive(Default)]
 pub struct TextureCache {
     cache: TemporaryCache<TextureRenderData>,
+    streaming: TextureStreamingSettings,
+}
+
+/// Calculates the mip level of a texture with the given size (in texels, the largest side), that is
+/// detailed enough to render an object that covers the given amount of pixels on screen (the largest
+/// side as well). The bias is added to the result, positive values produce less detailed levels.
+pub fn desired_mip_level(texture_size: u32, screen_size: f32, mip_bias: f32) -> usize {
+    let texels_per_pixel = texture_size as f32 / screen_size.max(1.0);
+    (texels_per_pixel.max(1.0).log2() + mip_bias)
+        .max(0.0)
+        .floor() as usize
+}
+
+fn is_streamable(texture: &Texture) -> bool {
+    !texture.is_render_target()
+        && texture.mip_count() > 1
+        && matches!(texture.kind(), TextureKind::Rectangle { .. })
+}
+
+/// Returns the most detailed mip level of the texture, that must always be resident in video memory.
+fn always_resident_mip(texture: &Texture, always_resident_size: u32) -> usize {
+    let last_mip = texture.mip_count().saturating_sub(1) as usize;
+    match texture.kind() {
+        TextureKind::Rectangle { width, height } => {
+            let mut mip = 0;
+            while mip < last_mip && (width.max(height) >> mip) > always_resident_size {
+                mip += 1;
+            }
+            mip
+        }
+        _ => 0,
+    }
+}
+
+fn mip_chain_kind(kind: TextureKind, base_mip: usize) -> TextureKind {
+    match kind {
+        TextureKind::Rectangle { width, height } => TextureKind::Rectangle {
+            width: (width >> base_mip).max(1),
+            height: (height >> base_mip).max(1),
+        },
+        _ => kind,
+    }
+}
+
+fn upload_mip_chain(
+    gpu_texture: &mut dyn GpuTexture,
+    texture: &Texture,
+    base_mip: usize,
+) -> Result<(), FrameworkError> {
+    gpu_texture.set_data(
+        mip_chain_kind(texture.kind(), base_mip).into(),
+        texture.pixel_kind().into(),
+        texture.mip_count() as usize - base_mip,
+        Some(texture.mip_chain_data(base_mip)),
+    )
 }
 
 fn create_gpu_texture(
     server: &dyn GraphicsServer,
     texture: &Texture,
+    base_mip: usize,
 ) -> Result<TextureRenderData, FrameworkError> {
+    let data = texture.mip_chain_data(base_mip);
     server
         .create_texture(GpuTextureDescriptor {
-            kind: texture.kind().into(),
+            kind: mip_chain_kind(texture.kind(), base_mip).into(),
             pixel_kind: PixelKind::from(texture.pixel_kind()),
             mag_filter: texture.magnification_filter().into(),
             min_filter: texture.minification_filter().into(),
-            mip_count: texture.mip_count() as usize,
+            mip_count: texture.mip_count() as usize - base_mip,
             s_wrap_mode: texture.s_wrap_mode().into(),
             t_wrap_mode: texture.t_wrap_mode().into(),
             r_wrap_mode: texture.r_wrap_mode().into(),
             anisotropy: texture.anisotropy_level(),
-            data: Some(texture.data()),
+            data: Some(data),
         })
         .map(|gpu_texture| TextureRenderData {
             gpu_texture,
             modifications_counter: texture.modifications_count(),
+            base_mip,
+            resident_bytes: data.len(),
+            requested_mip: None,
+            streamed_resource: None,
         })
 }
 
 impl TextureCache {
+    fn initial_base_mip(&self, texture: &Texture) -> usize {
+        if self.streaming.enabled && is_streamable(texture) {
+            always_resident_mip(texture, self.streaming.always_resident_size)
+        } else {
+            0
+        }
+    }
+
     /// Unconditionally uploads requested texture into GPU memory, previous GPU texture will be automatically
     /// destroyed.
     pub fn upload(
@@ -76,10 +162,11 @@ impl TextureCache {
     ) -> Result<(), FrameworkError> {
         let mut texture = texture.state();
         if let Some(texture) = texture.data() {
+            let base_mip = self.initial_base_mip(texture);
             self.cache.get_entry_mut_or_insert_with(
                 &texture.cache_index,
                 Default::default(),
-                || create_gpu_texture(server, texture),
+                || create_gpu_texture(server, texture, base_mip),
             )?;
             Ok(())
         } else {
@@ -97,10 +184,11 @@ impl TextureCache {
         let mut texture_data_guard = texture_resource.state();
 
         if let Some(texture) = texture_data_guard.data() {
+            let base_mip = self.initial_base_mip(texture);
             match self.cache.get_mut_or_insert_with(
                 &texture.cache_index,
                 Default::default(),
-                || create_gpu_texture(server, texture),
+                || create_gpu_texture(server, texture, base_mip),
             ) {
                 Ok(entry) => {
                     // Check if some value has changed in resource.
@@ -108,13 +196,14 @@ impl TextureCache {
                     // Data might change from last frame, so we have to check it and upload new if so.
                     let modifications_count = texture.modifications_count();
                     if entry.modifications_counter != modifications_count {
+                        // Mip count could change too, so make sure that the base mip is still valid.
+                        entry.base_mip = entry
+                            .base_mip
+                            .min(texture.mip_count().saturating_sub(1) as usize);
+                        entry.resident_bytes = texture.mip_chain_data(entry.base_mip).len();
                         let mut gpu_texture = entry.gpu_texture.borrow_mut();
-                        if let Err(e) = gpu_texture.set_data(
-                            texture.kind().into(),
-                            texture.pixel_kind().into(),
-                            texture.mip_count() as usize,
-                            Some(texture.data()),
-                        ) {
+                        if let Err(e) = upload_mip_chain(&mut *gpu_texture, texture, entry.base_mip)
+                        {
                             Log::writeln(
                                 MessageKind::Error,
                                 format!("Unable to upload new texture data to GPU. Reason: {e:?}"),
@@ -196,16 +285,304 @@ impl TextureCache {
     ) {
         let data = texture.data_ref();
         let index = data.cache_index.clone();
-        let entry = self.cache.get_mut(&index);
-        if entry.is_none() {
+        if let Some(entry) = self.cache.get_mut(&index) {
+            // The render target could be re-created (for example, when its size has changed), so
+            // make sure that the cache points to the actual GPU texture.
+            entry.gpu_texture = gpu_texture;
+        } else {
             self.cache.spawn(
                 TextureRenderData {
                     gpu_texture,
                     modifications_counter: data.modifications_count(),
+                    base_mip: 0,
+                    resident_bytes: 0,
+                    requested_mip: None,
+                    streamed_resource: None,
                 },
                 index,
                 TimeToLive::default(),
             );
         }
     }
+
+    /// Sets new texture streaming settings. Changes will be applied on the next call of
+    /// [`Self::update_streaming`].
+    pub fn set_streaming_settings(&mut self, settings: TextureStreamingSettings) {
+        self.streaming = settings;
+    }
+
+    /// Returns current texture streaming settings.
+    pub fn streaming_settings(&self) -> &TextureStreamingSettings {
+        &self.streaming
+    }
+
+    /// Returns total amount of video memory (in bytes) used by streamed textures.
+    pub fn streaming_memory_usage(&self) -> usize {
+        self.cache
+            .buffer
+            .iter()
+            .filter(|entry| entry.streamed_resource.is_some())
+            .map(|entry| entry.resident_bytes)
+            .sum()
+    }
+
+    /// Requests the given mip level of the texture to be resident in video memory. The request will
+    /// be processed on the next call of [`Self::update_streaming`], the most detailed mip level is
+    /// used if there were multiple requests. Does nothing if the streaming is disabled or the texture
+    /// wasn't uploaded to GPU yet.
+    pub fn request_mip(&mut self, texture: &TextureResource, mip: usize) {
+        if !self.streaming.enabled {
+            return;
+        }
+
+        let mut state = texture.state();
+        let Some(data) = state.data() else {
+            return;
+        };
+
+        if !is_streamable(data) {
+            return;
+        }
+
+        if let Some(entry) = self.cache.get_mut(&data.cache_index) {
+            entry.requested_mip = Some(entry.requested_mip.map_or(mip, |m| m.min(mip)));
+            if entry.streamed_resource.is_none() {
+                entry.streamed_resource = Some(texture.clone());
+            }
+        }
+    }
+
+    /// Requests mip levels for every texture used by the given bundles. Desired mip level of each
+    /// texture is defined by the screen size of the objects, that use the texture.
+    pub fn request_bundle_mips(
+        &mut self,
+        bundles: &[RenderDataBundle],
+        graph: &Graph,
+        observer_info: &ObserverInfo,
+        viewport_height: f32,
+    ) {
+        if !self.streaming.enabled {
+            return;
+        }
+
+        // Orthographic projection matrices have 1.0 in this element.
+        let is_perspective = observer_info.projection_matrix[(3, 3)] == 0.0;
+        let vertical_scale = observer_info.projection_matrix[(1, 1)].abs() * viewport_height;
+
+        for bundle in bundles {
+            let screen_size = bundle
+                .instances
+                .iter()
+                .map(|instance| {
+                    let (center, radius) = match graph.try_get(instance.node_handle) {
+                        Some(node) => {
+                            let aabb = node.world_bounding_box();
+                            (aabb.center(), aabb.half_extents().max())
+                        }
+                        None => (
+                            instance.world_transform.column(3).xyz(),
+                            Vector3::new(
+                                instance.world_transform.column(0).xyz().norm(),
+                                instance.world_transform.column(1).xyz().norm(),
+                                instance.world_transform.column(2).xyz().norm(),
+                            )
+                            .max(),
+                        ),
+                    };
+
+                    if is_perspective {
+                        let distance = ((center - observer_info.observer_position).norm() - radius)
+                            .max(observer_info.z_near);
+                        radius * vertical_scale / distance
+                    } else {
+                        radius * vertical_scale
+                    }
+                })
+                .fold(0.0f32, f32::max);
+
+            let mut textures = Vec::new();
+            if let Some(material) = bundle.material.state().data() {
+                textures.extend(
+                    material
+                        .bindings()
+                        .values()
+                        .filter_map(|binding| match binding {
+                            MaterialResourceBinding::Texture(binding) => binding.value.clone(),
+                            _ => None,
+                        }),
+                );
+            }
+            for instance in bundle.instances.iter() {
+                if let Some(block) = instance.property_block.as_ref() {
+                    textures.extend(block.textures().values().cloned());
+                }
+            }
+
+            for texture in textures {
+                let texture_size = match texture.state().data() {
+                    Some(data) => match data.kind() {
+                        TextureKind::Rectangle { width, height } => width.max(height),
+                        _ => continue,
+                    },
+                    None => continue,
+                };
+
+                self.request_mip(
+                    &texture,
+                    desired_mip_level(texture_size, screen_size, self.streaming.mip_bias),
+                );
+            }
+        }
+    }
+
+    /// Processes mip level requests (see [`Self::request_mip`]) and uploads (or evicts) mip levels of
+    /// streamed textures while keeping the total amount of video memory used by them in the budget.
+    /// Textures, that weren't requested since the last update, are reduced to their always resident
+    /// mip levels. Disabling the streaming restores all mip levels of all streamed textures.
+    pub fn update_streaming(&mut self) {
+        struct Candidate {
+            buffer_index: usize,
+            resident_mip: usize,
+            desired_mip: usize,
+            target_mip: usize,
+        }
+
+        let settings = self.streaming;
+        let mut candidates = Vec::new();
+        let mut total_bytes = 0;
+
+        for buffer_index in 0..self.cache.buffer.len() {
+            let Some(entry) = self.cache.buffer.get_mut_raw(buffer_index) else {
+                continue;
+            };
+            let Some(resource) = entry.streamed_resource.clone() else {
+                continue;
+            };
+            let mut state = resource.state();
+            let Some(texture) = state.data() else {
+                continue;
+            };
+
+            let resident_mip = if settings.enabled {
+                always_resident_mip(texture, settings.always_resident_size)
+            } else {
+                0
+            };
+            let desired_mip = entry
+                .requested_mip
+                .take()
+                .map_or(resident_mip, |mip| mip.min(resident_mip));
+            total_bytes += texture.mip_chain_data(resident_mip).len();
+
+            candidates.push(Candidate {
+                buffer_index,
+                resident_mip,
+                desired_mip,
+                target_mip: resident_mip,
+            });
+        }
+
+        // Textures that need the most detailed mip levels (usually the closest ones to the camera) have
+        // the highest priority.
+        candidates.sort_by_key(|candidate| candidate.desired_mip);
+
+        for candidate in candidates.iter_mut() {
+            let Some(resource) = self
+                .cache
+                .buffer
+                .get_raw(candidate.buffer_index)
+                .and_then(|entry| entry.streamed_resource.clone())
+            else {
+                continue;
+            };
+            let mut state = resource.state();
+            let Some(texture) = state.data() else {
+                continue;
+            };
+
+            let mut current_bytes = texture.mip_chain_data(candidate.target_mip).len();
+            while candidate.target_mip > candidate.desired_mip {
+                let bytes = texture.mip_chain_data(candidate.target_mip - 1).len();
+                if settings.enabled && total_bytes + bytes - current_bytes > settings.memory_budget
+                {
+                    break;
+                }
+                total_bytes += bytes - current_bytes;
+                current_bytes = bytes;
+                candidate.target_mip -= 1;
+            }
+        }
+
+        // Evictions go first, they free video memory for the new mip levels.
+        candidates.sort_by_key(|candidate| {
+            let base_mip = self
+                .cache
+                .buffer
+                .get_raw(candidate.buffer_index)
+                .map_or(0, |entry| entry.base_mip);
+            candidate.target_mip <= base_mip
+        });
+
+        let mut uploaded = 0;
+        for candidate in candidates {
+            if uploaded >= settings.max_uploads_per_frame && settings.enabled {
+                break;
+            }
+
+            let Some(entry) = self.cache.buffer.get_mut_raw(candidate.buffer_index) else {
+                continue;
+            };
+
+            if entry.base_mip != candidate.target_mip {
+                let Some(resource) = entry.streamed_resource.clone() else {
+                    continue;
+                };
+                let mut state = resource.state();
+                let Some(texture) = state.data() else {
+                    continue;
+                };
+
+                let mut gpu_texture = entry.gpu_texture.borrow_mut();
+                match upload_mip_chain(&mut *gpu_texture, texture, candidate.target_mip) {
+                    Ok(_) => {
+                        drop(gpu_texture);
+                        entry.base_mip = candidate.target_mip;
+                        entry.resident_bytes = texture.mip_chain_data(entry.base_mip).len();
+                        uploaded += 1;
+                    }
+                    Err(e) => Log::err(format!(
+                        "Unable to stream mip levels of a texture. Reason: {e:?}"
+                    )),
+                }
+            }
+
+            // Keep the resource only while it is streamed.
+            if !settings.enabled && entry.base_mip == 0 {
+                entry.streamed_resource = None;
+            } else if entry.base_mip == candidate.resident_mip
+                && candidate.desired_mip == candidate.resident_mip
+            {
+                // Nothing to stream, release the resource, so it could be unloaded if unused.
+                entry.streamed_resource = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::cache::texture::desired_mip_level;
+
+    #[test]
+    fn test_desired_mip_level() {
+        // An object covers the entire texture, the most detailed level is needed.
+        assert_eq!(desired_mip_level(1024, 1024.0, 0.0), 0);
+        assert_eq!(desired_mip_level(1024, 2048.0, 0.0), 0);
+        // An object is 4 times smaller than the texture.
+        assert_eq!(desired_mip_level(1024, 256.0, 0.0), 2);
+        assert_eq!(desired_mip_level(1024, 256.0, 1.0), 3);
+        assert_eq!(desired_mip_level(1024, 256.0, -4.0), 0);
+        // Tiny (or invisible) objects need the least detailed level.
+        assert_eq!(desired_mip_level(1024, 0.0, 0.0), 10);
+    }
 }
@@ -0,0 +1,354 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Automatic selection of quality settings based on measured GPU frame time. See
+//! [`crate::renderer::Renderer::begin_quality_calibration`] docs for more info.
+
+use crate::{
+    core::instant,
+    renderer::{
+        framework::{
+            query::{Query, QueryKind, QueryResult},
+            server::GraphicsServer,
+        },
+        QualitySettings,
+    },
+};
+use ron::ser::PrettyConfig;
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Options of quality calibration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QualityCalibrationOptions {
+    /// A set of quality levels to check, sorted from the highest quality to the lowest. The first
+    /// level that fits in [`Self::target_frame_time`] will be recommended.
+    pub levels: Vec<QualitySettings>,
+    /// Amount of frames to skip after switching to a new level. It is needed to exclude one-time
+    /// costs, such as shader compilation or shadow maps allocation, from the measurements.
+    pub warm_up_frames: usize,
+    /// Amount of frames to measure for each level.
+    pub frames_per_level: usize,
+    /// Desired time that the GPU spends on a single frame. It should be less than the frame time
+    /// at desired frame rate, to leave some room for heavier scenes.
+    pub target_frame_time: Duration,
+}
+
+impl Default for QualityCalibrationOptions {
+    fn default() -> Self {
+        Self {
+            levels: vec![
+                QualitySettings::ultra(),
+                QualitySettings::high(),
+                QualitySettings::medium(),
+                QualitySettings::low(),
+            ],
+            warm_up_frames: 10,
+            frames_per_level: 30,
+            // ~75% of a frame at 60 FPS.
+            target_frame_time: Duration::from_micros(12_500),
+        }
+    }
+}
+
+/// A result of quality calibration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QualityCalibrationResult {
+    /// Median frame time of each measured level. Levels after the recommended one are not measured.
+    pub frame_times: Vec<Duration>,
+    /// Index of the recommended level in [`QualityCalibrationOptions::levels`].
+    pub recommended_level: usize,
+    /// Recommended quality settings.
+    pub recommended: QualitySettings,
+}
+
+/// An error that may occur when quality settings are loaded or saved.
+#[derive(Debug)]
+pub enum QualitySettingsFileError {
+    /// An i/o error has occurred.
+    Io(std::io::Error),
+    /// Unable to parse the file.
+    Parse(ron::error::SpannedError),
+    /// Unable to serialize the settings.
+    Serialize(ron::Error),
+}
+
+impl Display for QualitySettingsFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QualitySettingsFileError::Io(e) => write!(f, "An i/o error has occurred: {e}"),
+            QualitySettingsFileError::Parse(e) => write!(f, "Unable to parse the file: {e}"),
+            QualitySettingsFileError::Serialize(e) => {
+                write!(f, "Unable to serialize the settings: {e}")
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for QualitySettingsFileError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for QualitySettingsFileError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<ron::Error> for QualitySettingsFileError {
+    fn from(e: ron::Error) -> Self {
+        Self::Serialize(e)
+    }
+}
+
+impl QualitySettings {
+    /// Tries to load quality settings from the given RON file.
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, QualitySettingsFileError> {
+        let str = std::fs::read_to_string(path)?;
+        Ok(ron::de::from_str(&str)?)
+    }
+
+    /// Saves quality settings to the given RON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), QualitySettingsFileError> {
+        let str = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        std::fs::write(path, str)?;
+        Ok(())
+    }
+}
+
+pub(crate) enum CalibrationStep {
+    Measuring,
+    SwitchLevel(QualitySettings),
+    Finished(QualityCalibrationResult),
+}
+
+enum FrameTimer {
+    None,
+    Gpu(Box<dyn Query>),
+    Cpu(instant::Instant),
+}
+
+pub(crate) struct QualityCalibrator {
+    options: QualityCalibrationOptions,
+    use_timer_queries: bool,
+    level: usize,
+    frames_rendered: usize,
+    timer: FrameTimer,
+    pending_queries: VecDeque<(usize, Box<dyn Query>)>,
+    samples: Vec<Duration>,
+    frame_times: Vec<Duration>,
+    pub(crate) save_path: Option<PathBuf>,
+}
+
+impl QualityCalibrator {
+    pub(crate) fn new(options: QualityCalibrationOptions, use_timer_queries: bool) -> Self {
+        assert!(!options.levels.is_empty());
+        Self {
+            options,
+            use_timer_queries,
+            level: 0,
+            frames_rendered: 0,
+            timer: FrameTimer::None,
+            pending_queries: Default::default(),
+            samples: Default::default(),
+            frame_times: Default::default(),
+            save_path: None,
+        }
+    }
+
+    pub(crate) fn current_level(&self) -> QualitySettings {
+        self.options.levels[self.level]
+    }
+
+    pub(crate) fn begin_frame(&mut self, server: &dyn GraphicsServer) {
+        self.frames_rendered += 1;
+        if self.frames_rendered <= self.options.warm_up_frames {
+            return;
+        }
+
+        if self.use_timer_queries {
+            if let Ok(query) = server.create_query() {
+                query.begin(QueryKind::TimeElapsed);
+                self.timer = FrameTimer::Gpu(query);
+                return;
+            }
+        }
+
+        // Fallback to CPU time measurement, the GPU work is forced to complete at the end of the
+        // frame so the measured time is close to the GPU time.
+        server.finish();
+        self.timer = FrameTimer::Cpu(instant::Instant::now());
+    }
+
+    pub(crate) fn end_frame(&mut self, server: &dyn GraphicsServer) -> CalibrationStep {
+        match std::mem::replace(&mut self.timer, FrameTimer::None) {
+            FrameTimer::None => (),
+            FrameTimer::Gpu(query) => {
+                query.end();
+                self.pending_queries.push_back((self.level, query));
+            }
+            FrameTimer::Cpu(start) => {
+                server.finish();
+                if let Some(step) = self.push_sample(self.level, start.elapsed()) {
+                    return step;
+                }
+            }
+        }
+
+        // Results of timer queries become available with a delay of a few frames.
+        while let Some((level, query)) = self.pending_queries.front() {
+            let Some(QueryResult::TimeElapsed(time)) = query.try_get_result() else {
+                break;
+            };
+            let level = *level;
+            self.pending_queries.pop_front();
+            if let Some(step) = self.push_sample(level, time) {
+                return step;
+            }
+        }
+
+        CalibrationStep::Measuring
+    }
+
+    fn push_sample(&mut self, level: usize, time: Duration) -> Option<CalibrationStep> {
+        // Ignore late results from the previous level.
+        if level != self.level {
+            return None;
+        }
+
+        self.samples.push(time);
+        if self.samples.len() < self.options.frames_per_level.max(1) {
+            return None;
+        }
+
+        self.samples.sort();
+        let median = self.samples[self.samples.len() / 2];
+        self.frame_times.push(median);
+
+        if median <= self.options.target_frame_time || self.level + 1 == self.options.levels.len() {
+            Some(CalibrationStep::Finished(QualityCalibrationResult {
+                frame_times: std::mem::take(&mut self.frame_times),
+                recommended_level: self.level,
+                recommended: self.current_level(),
+            }))
+        } else {
+            self.level += 1;
+            self.frames_rendered = 0;
+            self.samples.clear();
+            self.pending_queries.clear();
+            Some(CalibrationStep::SwitchLevel(self.current_level()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::{
+        calibration::{CalibrationStep, QualityCalibrationOptions, QualityCalibrator},
+        QualitySettings,
+    };
+    use std::time::Duration;
+
+    fn options() -> QualityCalibrationOptions {
+        QualityCalibrationOptions {
+            frames_per_level: 3,
+            target_frame_time: Duration::from_millis(10),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_calibration_picks_first_fitting_level() {
+        let mut calibrator = QualityCalibrator::new(options(), false);
+
+        // Ultra is too slow, even though one frame is fast enough.
+        for ms in [20, 5, 30] {
+            let step = calibrator.push_sample(0, Duration::from_millis(ms));
+            if ms == 30 {
+                assert!(
+                    matches!(step, Some(CalibrationStep::SwitchLevel(s)) if s == QualitySettings::high())
+                );
+            } else {
+                assert!(step.is_none());
+            }
+        }
+
+        // Late results of the previous level must be ignored.
+        assert!(calibrator
+            .push_sample(0, Duration::from_millis(1))
+            .is_none());
+
+        assert!(calibrator
+            .push_sample(1, Duration::from_millis(9))
+            .is_none());
+        assert!(calibrator
+            .push_sample(1, Duration::from_millis(11))
+            .is_none());
+        let Some(CalibrationStep::Finished(result)) =
+            calibrator.push_sample(1, Duration::from_millis(8))
+        else {
+            panic!("Calibration must be finished.")
+        };
+        assert_eq!(result.recommended_level, 1);
+        assert_eq!(result.recommended, QualitySettings::high());
+        assert_eq!(
+            result.frame_times,
+            vec![Duration::from_millis(20), Duration::from_millis(9)]
+        );
+    }
+
+    #[test]
+    fn test_calibration_falls_back_to_lowest_level() {
+        let options = options();
+        let levels = options.levels.len();
+        let mut calibrator = QualityCalibrator::new(options, false);
+
+        let mut result = None;
+        'outer: for level in 0..levels {
+            for _ in 0..3 {
+                if let Some(CalibrationStep::Finished(r)) =
+                    calibrator.push_sample(level, Duration::from_millis(50))
+                {
+                    result = Some(r);
+                    break 'outer;
+                }
+            }
+        }
+
+        let result = result.unwrap();
+        assert_eq!(result.recommended_level, levels - 1);
+        assert_eq!(result.recommended, QualitySettings::low());
+        assert_eq!(result.frame_times.len(), levels);
+    }
+
+    #[test]
+    fn test_quality_settings_ron_roundtrip() {
+        let settings = QualitySettings::medium();
+        let str = ron::ser::to_string(&settings).unwrap();
+        let loaded: QualitySettings = ron::de::from_str(&str).unwrap();
+        assert_eq!(settings, loaded);
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Color blindness post-processing filters. See [`ColorBlindnessFilter`] docs for more info.
+
+use crate::{
+    core::{algebra::Matrix3, math::Rect, sstorage::ImmutableString},
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            buffer::BufferUsage,
+            error::FrameworkError,
+            framebuffer::{BufferLocation, FrameBuffer, ResourceBindGroup, ResourceBinding},
+            geometry_buffer::GeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange, GeometryBufferExt,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A type of color vision deficiency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorBlindnessKind {
+    /// Absence of red photoreceptors.
+    Protanopia,
+    /// Absence of green photoreceptors.
+    Deuteranopia,
+    /// Absence of blue photoreceptors.
+    Tritanopia,
+}
+
+impl ColorBlindnessKind {
+    /// Returns a matrix, that simulates the color vision deficiency in linear RGB color space.
+    /// Coefficients are taken from "A Physiologically-based Model for Simulation of Color Vision
+    /// Deficiency" by Machado et al. (2009).
+    pub fn simulation_matrix(self) -> Matrix3<f32> {
+        match self {
+            ColorBlindnessKind::Protanopia => Matrix3::new(
+                0.152286, 1.052583, -0.204868, //
+                0.114503, 0.786281, 0.099216, //
+                -0.003882, -0.048116, 1.051998,
+            ),
+            ColorBlindnessKind::Deuteranopia => Matrix3::new(
+                0.367322, 0.860646, -0.227968, //
+                0.280085, 0.672501, 0.047413, //
+                -0.011820, 0.042940, 0.968881,
+            ),
+            ColorBlindnessKind::Tritanopia => Matrix3::new(
+                1.255528, -0.076749, -0.178779, //
+                -0.078411, 0.930809, 0.147602, //
+                0.004733, 0.691367, 0.303900,
+            ),
+        }
+    }
+}
+
+/// Defines what a color blindness filter should do with a frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ColorBlindnessMode {
+    /// Simulates the color vision deficiency, which is useful to check how a game looks like for
+    /// people with such deficiency.
+    #[default]
+    Simulation,
+    /// Shifts colors, that cannot be distinguished by people with the color vision deficiency, to
+    /// the colors that can be distinguished (also known as "daltonization").
+    Compensation,
+}
+
+/// Color blindness filter is a post-processing effect, that is applied to every rendered scene. It
+/// can be used either to simulate a color vision deficiency or to compensate it. The filter is applied
+/// to scene frames only, the user interface is left intact. Use
+/// [`crate::renderer::Renderer::set_color_blindness_filter`] to change the filter at runtime.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorBlindnessFilter {
+    /// A type of color vision deficiency.
+    pub kind: ColorBlindnessKind,
+    /// Defines what the filter should do with a frame.
+    pub mode: ColorBlindnessMode,
+    /// Strength of the filter in `[0.0; 1.0]` range, where `0.0` - no effect, `1.0` - full effect.
+    pub strength: f32,
+}
+
+impl ColorBlindnessFilter {
+    /// Creates new color blindness filter with full strength.
+    pub fn new(kind: ColorBlindnessKind, mode: ColorBlindnessMode) -> Self {
+        Self {
+            kind,
+            mode,
+            strength: 1.0,
+        }
+    }
+
+    /// Returns a simulation matrix blended with the identity matrix using the strength of the filter.
+    pub fn color_matrix(&self) -> Matrix3<f32> {
+        let identity = Matrix3::identity();
+        identity + (self.kind.simulation_matrix() - identity) * self.strength.clamp(0.0, 1.0)
+    }
+}
+
+struct ColorBlindnessShader {
+    program: Box<dyn GpuProgram>,
+    uniform_buffer_binding: usize,
+    screen_texture: UniformLocation,
+}
+
+impl ColorBlindnessShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/color_blindness_fs.glsl");
+        let vertex_source = include_str!("shaders/color_blindness_vs.glsl");
+
+        let program =
+            server.create_program("ColorBlindnessShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            screen_texture: program.uniform_location(&ImmutableString::new("screenTexture"))?,
+            program,
+        })
+    }
+}
+
+pub struct ColorBlindnessRenderer {
+    shader: ColorBlindnessShader,
+    quad: Box<dyn GeometryBuffer>,
+}
+
+impl ColorBlindnessRenderer {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: ColorBlindnessShader::new(server)?,
+            quad: <dyn GeometryBuffer>::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                BufferUsage::StaticDraw,
+                server,
+            )?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        filter: &ColorBlindnessFilter,
+        viewport: Rect<i32>,
+        frame_texture: Rc<RefCell<dyn GpuTexture>>,
+        frame_buffer: &mut dyn FrameBuffer,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        let frame_matrix = make_viewport_matrix(viewport);
+
+        statistics += frame_buffer.draw(
+            &*self.quad,
+            viewport,
+            &*self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&frame_texture, &self.shader.screen_texture),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<256>::new()
+                                .with(&frame_matrix)
+                                .with(&filter.color_matrix())
+                                .with(&(filter.mode == ColorBlindnessMode::Compensation)),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: self.shader.uniform_buffer_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+}
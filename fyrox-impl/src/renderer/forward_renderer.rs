@@ -19,11 +19,12 @@
 // SOFTWARE.
 
 //! Forward renderer is used to render transparent meshes and meshes with custom blending options.
+//! It also renders outlines (`Outline` render pass) of all meshes, that support them.
 
 use crate::{
     core::{color::Color, math::Rect, sstorage::ImmutableString},
     renderer::{
-        bundle::{BundleRenderContext, RenderDataBundleStorage},
+        bundle::{BundleRenderContext, RenderDataBundle, RenderDataBundleStorage},
         cache::{shader::ShaderCache, texture::TextureCache, uniform::UniformMemoryAllocator},
         framework::{
             error::FrameworkError, framebuffer::FrameBuffer, gpu_texture::GpuTexture,
@@ -37,6 +38,7 @@ use std::{cell::RefCell, rc::Rc};
 
 pub(crate) struct ForwardRenderer {
     render_pass_name: ImmutableString,
+    outline_pass_name: ImmutableString,
 }
 
 pub(crate) struct ForwardRenderContext<'a> {
@@ -58,6 +60,7 @@ impl ForwardRenderer {
     pub(crate) fn new() -> Self {
         Self {
             render_pass_name: ImmutableString::new("Forward"),
+            outline_pass_name: ImmutableString::new("Outline"),
         }
     }
 
@@ -102,6 +105,57 @@ impl ForwardRenderer {
             },
         )?;
 
+        // Outlines are drawn for every mesh regardless of its render path, meshes with shaders
+        // that do not have the outline pass are skipped.
+        statistics += bundle_storage.render_to_frame_buffer(
+            state,
+            geom_cache,
+            shader_cache,
+            has_outline,
+            |instance| {
+                instance
+                    .property_block
+                    .as_ref()
+                    .and_then(|block| block.property_ref(OUTLINE_WIDTH))
+                    .and_then(|width| width.as_float())
+                    .map_or(true, |width| width > 0.0)
+            },
+            BundleRenderContext {
+                texture_cache,
+                render_pass_name: &self.outline_pass_name,
+                frame_buffer: framebuffer,
+                viewport,
+                uniform_memory_allocator,
+                use_pom: false,
+                light_position: &Default::default(),
+                fallback_resources,
+                ambient_light,
+                scene_depth: Some(&scene_depth),
+            },
+        )?;
+
         Ok(statistics)
     }
 }
+
+const OUTLINE_WIDTH: &str = "outlineWidth";
+
+fn has_outline(bundle: &RenderDataBundle) -> bool {
+    let material_width = bundle
+        .material
+        .state()
+        .data()
+        .and_then(|material| material.property_ref(OUTLINE_WIDTH))
+        .and_then(|width| width.as_float())
+        .unwrap_or_default();
+
+    material_width > 0.0
+        || bundle.instances.iter().any(|instance| {
+            instance
+                .property_block
+                .as_ref()
+                .and_then(|block| block.property_ref(OUTLINE_WIDTH))
+                .and_then(|width| width.as_float())
+                .is_some_and(|width| width > 0.0)
+        })
+}
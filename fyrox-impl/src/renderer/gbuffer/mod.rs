@@ -291,7 +291,27 @@ impl GBuffer {
         // for rendering. We'll render in the G-Buffer, but depth will be used from final frame, since
         // decals do not modify depth (only diffuse and normal maps).
         let unit_cube = &self.cube;
-        for decal in graph.linear_iter().filter_map(|n| n.cast::<Decal>()) {
+
+        let mut decals = graph
+            .linear_iter()
+            .filter_map(|n| n.cast::<Decal>())
+            .filter(|d| d.global_visibility() && d.is_globally_enabled() && d.fade_factor() > 0.0)
+            .collect::<Vec<_>>();
+
+        // Keep only the closest decals to fit in the budget.
+        if decals.len() > quality_settings.max_decals {
+            let camera_position = camera.global_position();
+            decals.sort_by(|a, b| {
+                let a = a.global_position().metric_distance(&camera_position);
+                let b = b.global_position().metric_distance(&camera_position);
+                a.total_cmp(&b)
+            });
+            decals.truncate(quality_settings.max_decals);
+        }
+
+        decals.sort_by_key(|d| d.render_order());
+
+        for decal in decals {
             let shader = &self.decal_shader;
             let program = &*self.decal_shader.program;
 
@@ -341,7 +361,11 @@ impl GBuffer {
                                         &decal.global_transform().try_inverse().unwrap_or_default(),
                                     )
                                     .with(&resolution)
-                                    .with(&decal.color().srgb_to_linear_f32())
+                                    .with(&{
+                                        let mut color = decal.color().srgb_to_linear_f32();
+                                        color.w *= decal.fade_factor();
+                                        color
+                                    })
                                     .with(&(decal.layer() as u32)),
                             )?,
                             binding: BufferLocation::Auto {
@@ -367,6 +367,7 @@ impl HighDynamicRangeRenderer {
         exposure: Exposure,
         color_grading_lut: Option<&ColorGradingLut>,
         use_color_grading: bool,
+        use_tone_mapping: bool,
         texture_cache: &mut TextureCache,
         uniform_buffer_cache: &mut UniformBufferCache,
     ) -> Result<DrawCallStatistics, FrameworkError> {
@@ -394,7 +395,8 @@ impl HighDynamicRangeRenderer {
                 .with(&min_luminance)
                 .with(&max_luminance)
                 .with(&is_auto)
-                .with(&fixed_exposure),
+                .with(&fixed_exposure)
+                .with(&use_tone_mapping),
         )?;
 
         ldr_framebuffer.draw(
@@ -445,6 +447,7 @@ impl HighDynamicRangeRenderer {
         exposure: Exposure,
         color_grading_lut: Option<&ColorGradingLut>,
         use_color_grading: bool,
+        use_tone_mapping: bool,
         texture_cache: &mut TextureCache,
         uniform_buffer_cache: &mut UniformBufferCache,
     ) -> Result<RenderPassStatistics, FrameworkError> {
@@ -463,6 +466,7 @@ impl HighDynamicRangeRenderer {
             exposure,
             color_grading_lut,
             use_color_grading,
+            use_tone_mapping,
             texture_cache,
             uniform_buffer_cache,
         )?;
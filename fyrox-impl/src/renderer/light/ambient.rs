@@ -33,6 +33,10 @@ pub struct AmbientLightShader {
     pub diffuse_texture: UniformLocation,
     pub ao_sampler: UniformLocation,
     pub ambient_texture: UniformLocation,
+    pub depth_texture: UniformLocation,
+    pub normal_texture: UniformLocation,
+    pub material_texture: UniformLocation,
+    pub environment_map: UniformLocation,
 }
 
 impl AmbientLightShader {
@@ -47,6 +51,10 @@ impl AmbientLightShader {
             diffuse_texture: program.uniform_location(&ImmutableString::new("diffuseTexture"))?,
             ao_sampler: program.uniform_location(&ImmutableString::new("aoSampler"))?,
             ambient_texture: program.uniform_location(&ImmutableString::new("ambientTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            normal_texture: program.uniform_location(&ImmutableString::new("normalTexture"))?,
+            material_texture: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            environment_map: program.uniform_location(&ImmutableString::new("environmentMap"))?,
             program,
         })
     }
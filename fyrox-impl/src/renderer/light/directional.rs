@@ -33,6 +33,7 @@ pub struct DirectionalLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub decal_mask_sampler: UniformLocation,
     pub shadow_cascade0: UniformLocation,
     pub shadow_cascade1: UniformLocation,
     pub shadow_cascade2: UniformLocation,
@@ -50,6 +51,8 @@ impl DirectionalLightShader {
             color_sampler: program.uniform_location(&ImmutableString::new("colorTexture"))?,
             normal_sampler: program.uniform_location(&ImmutableString::new("normalTexture"))?,
             material_sampler: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            decal_mask_sampler: program
+                .uniform_location(&ImmutableString::new("decalMaskTexture"))?,
             shadow_cascade0: program.uniform_location(&ImmutableString::new("shadowCascade0"))?,
             shadow_cascade1: program.uniform_location(&ImmutableString::new("shadowCascade1"))?,
             shadow_cascade2: program.uniform_location(&ImmutableString::new("shadowCascade2"))?,
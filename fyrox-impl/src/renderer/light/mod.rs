@@ -36,6 +36,7 @@ use crate::{
             error::FrameworkError,
             framebuffer::{FrameBuffer, ResourceBindGroup, ResourceBinding},
             geometry_buffer::GeometryBuffer,
+            gpu_texture::GpuTextureKind,
             server::GraphicsServer,
             uniform::StaticUniformBuffer,
             BlendFactor, BlendFunc, BlendParameters, ColorMask, CompareFunc, CullFace,
@@ -43,10 +44,15 @@ use crate::{
         },
         gbuffer::GBuffer,
         light::{
-            ambient::AmbientLightShader, directional::DirectionalLightShader,
-            point::PointLightShader, spot::SpotLightShader,
+            ambient::AmbientLightShader,
+            directional::DirectionalLightShader,
+            fog::FogShader,
+            point::PointLightShader,
+            reflection_probe::{ReflectionProbeRenderData, ReflectionProbeShader},
+            spot::SpotLightShader,
         },
-        light_volume::LightVolumeRenderer,
+        light_volume::{LightVolumeRenderer, RayMarchedVolumeContext},
+        procedural_sky::ProceduralSkyRenderer,
         shadow::{
             csm::{CsmRenderContext, CsmRenderer},
             point::{PointShadowMapRenderContext, PointShadowMapRenderer},
@@ -60,11 +66,14 @@ use crate::{
     },
     scene::{
         camera::Camera,
+        fog::FogMode,
+        light::ShadowFilter,
         mesh::{
             buffer::{TriangleBuffer, VertexBuffer},
             surface::SurfaceData,
             vertex::SimpleVertex,
         },
+        probe::ReflectionProbeProjection,
         Scene,
     },
 };
@@ -72,21 +81,29 @@ use fyrox_graphics::framebuffer::BufferLocation;
 
 pub mod ambient;
 pub mod directional;
+pub mod fog;
 pub mod point;
+pub mod reflection_probe;
 pub mod spot;
 
+// Near clipping plane of the projection matrix of spot light shadow maps.
+const SPOT_SHADOW_Z_NEAR: f32 = 0.01;
+
 pub struct DeferredLightRenderer {
     pub ssao_renderer: ScreenSpaceAmbientOcclusionRenderer,
     spot_light_shader: SpotLightShader,
     point_light_shader: PointLightShader,
     directional_light_shader: DirectionalLightShader,
     ambient_light_shader: AmbientLightShader,
+    reflection_probe_shader: ReflectionProbeShader,
+    fog_shader: FogShader,
     quad: Box<dyn GeometryBuffer>,
     sphere: Box<dyn GeometryBuffer>,
     cone: Box<dyn GeometryBuffer>,
     skybox: Box<dyn GeometryBuffer>,
     flat_shader: FlatShader,
     skybox_shader: SkyboxShader,
+    procedural_sky_renderer: ProceduralSkyRenderer,
     spot_shadow_map_renderer: SpotShadowMapRenderer,
     point_shadow_map_renderer: PointShadowMapRenderer,
     csm_renderer: CsmRenderer,
@@ -109,6 +126,8 @@ pub(crate) struct DeferredRendererContext<'a> {
     pub uniform_buffer_cache: &'a mut UniformBufferCache,
     pub visibility_cache: &'a mut ObserverVisibilityCache,
     pub uniform_memory_allocator: &'a mut UniformMemoryAllocator,
+    /// Captured reflection probes sorted by their size from the largest to the smallest one.
+    pub reflection_probes: &'a [ReflectionProbeRenderData],
 }
 
 impl DeferredLightRenderer {
@@ -162,6 +181,8 @@ impl DeferredLightRenderer {
             point_light_shader: PointLightShader::new(server)?,
             directional_light_shader: DirectionalLightShader::new(server)?,
             ambient_light_shader: AmbientLightShader::new(server)?,
+            reflection_probe_shader: ReflectionProbeShader::new(server)?,
+            fog_shader: FogShader::new(server)?,
             quad: <dyn GeometryBuffer>::from_surface_data(
                 &SurfaceData::make_unit_xy_quad(),
                 BufferUsage::StaticDraw,
@@ -205,6 +226,7 @@ impl DeferredLightRenderer {
             )?,
             flat_shader: FlatShader::new(server)?,
             skybox_shader: SkyboxShader::new(server)?,
+            procedural_sky_renderer: ProceduralSkyRenderer::new(server)?,
             spot_shadow_map_renderer: SpotShadowMapRenderer::new(
                 server,
                 settings.spot_shadow_map_size,
@@ -215,7 +237,7 @@ impl DeferredLightRenderer {
                 settings.point_shadow_map_size,
                 quality_defaults.point_shadow_map_precision,
             )?,
-            light_volume: LightVolumeRenderer::new(server)?,
+            light_volume: LightVolumeRenderer::new(server, frame_size)?,
             csm_renderer: CsmRenderer::new(
                 server,
                 quality_defaults.csm_settings.size,
@@ -270,6 +292,7 @@ impl DeferredLightRenderer {
             frame_size.0 as usize,
             frame_size.1 as usize,
         )?;
+        self.light_volume.set_frame_size(server, frame_size)?;
         Ok(())
     }
 
@@ -296,6 +319,7 @@ impl DeferredLightRenderer {
             uniform_buffer_cache,
             visibility_cache,
             uniform_memory_allocator,
+            reflection_probes,
         } = args;
 
         let viewport = Rect::new(0, 0, gbuffer.width, gbuffer.height);
@@ -320,8 +344,30 @@ impl DeferredLightRenderer {
             )?;
         }
 
-        // Render skybox (if any).
-        if let Some(skybox) = camera.skybox_ref() {
+        // Render procedural sky or skybox (if any).
+        if let Some(sky) = camera.procedural_sky_ref() {
+            let size = camera.projection().z_far() / 2.0f32.sqrt();
+            let wvp = view_projection
+                * Matrix4::new_translation(&camera.global_position())
+                * Matrix4::new_scaling(size);
+
+            pass_stats += self.procedural_sky_renderer.render_background(
+                sky,
+                &*self.skybox,
+                wvp,
+                viewport,
+                frame_buffer,
+                uniform_buffer_cache,
+            )?;
+
+            if sky.use_as_environment && camera.environment_ref().is_none() {
+                pass_stats += self.procedural_sky_renderer.update_environment(
+                    sky,
+                    &*self.skybox,
+                    uniform_buffer_cache,
+                )?;
+            }
+        } else if let Some(skybox) = camera.skybox_ref() {
             let size = camera.projection().z_far() / 2.0f32.sqrt();
             let scale = Matrix4::new_scaling(size);
             let wvp = Matrix4::new_translation(&camera.global_position()) * scale;
@@ -373,9 +419,36 @@ impl DeferredLightRenderer {
         let gbuffer_diffuse_map = gbuffer.diffuse_texture();
         let gbuffer_normal_map = gbuffer.normal_texture();
         let gbuffer_material_map = gbuffer.material_texture();
+        let gbuffer_decal_mask = gbuffer.decal_mask_texture();
         let gbuffer_ambient_map = gbuffer.ambient_texture();
         let ao_map = self.ssao_renderer.ao_map();
 
+        // Image-based lighting uses the environment map of the camera (if any). It must be a cube map,
+        // any other textures are ignored.
+        // Procedural sky could be used as the environment map, if the camera has no environment
+        // map.
+        let environment = match camera.environment_ref() {
+            Some(environment) => {
+                let max_lod = environment
+                    .state()
+                    .data()
+                    .map_or(0, |texture| texture.mip_count().saturating_sub(1));
+                textures
+                    .get(server, environment)
+                    .filter(|gpu_texture| {
+                        matches!(gpu_texture.borrow().kind(), GpuTextureKind::Cube { .. })
+                    })
+                    .map(|gpu_texture| (gpu_texture.clone(), max_lod as f32))
+            }
+            None => camera
+                .procedural_sky_ref()
+                .filter(|sky| sky.use_as_environment)
+                .and_then(|_| self.procedural_sky_renderer.environment()),
+        };
+        let (environment_map, environment_max_lod) = environment
+            .clone()
+            .unwrap_or_else(|| (fallback_resources.environment_dummy.clone(), 0.0));
+
         pass_stats += frame_buffer.draw(
             &*self.quad,
             viewport,
@@ -411,11 +484,31 @@ impl DeferredLightRenderer {
                         &gbuffer_ambient_map,
                         &self.ambient_light_shader.ambient_texture,
                     ),
+                    ResourceBinding::texture(
+                        &gbuffer_depth_map,
+                        &self.ambient_light_shader.depth_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &gbuffer_normal_map,
+                        &self.ambient_light_shader.normal_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &gbuffer_material_map,
+                        &self.ambient_light_shader.material_texture,
+                    ),
+                    ResourceBinding::texture(
+                        &environment_map,
+                        &self.ambient_light_shader.environment_map,
+                    ),
                     ResourceBinding::Buffer {
                         buffer: uniform_buffer_cache.write(
                             StaticUniformBuffer::<256>::new()
                                 .with(&frame_matrix)
-                                .with(&ambient_color.srgb_to_linear_f32()),
+                                .with(&ambient_color.srgb_to_linear_f32())
+                                .with(&inv_view_projection)
+                                .with(&camera_global_position)
+                                .with(&environment_max_lod)
+                                .with(&environment.is_some()),
                         )?,
                         binding: BufferLocation::Auto {
                             shader_location: self.ambient_light_shader.uniform_buffer_binding,
@@ -427,6 +520,73 @@ impl DeferredLightRenderer {
             ElementRange::Full,
         )?;
 
+        // Reflection probes replace the image-based lighting of the camera within their bounds. The
+        // ambient lighting is recomputed with the cube map of each probe and blended over the
+        // previous result, so smaller probes (drawn last) override the larger ones.
+        for probe in reflection_probes {
+            if !frustum.is_intersects_aabb(&probe.world_bounding_box) {
+                continue;
+            }
+
+            let shader = &self.reflection_probe_shader;
+            pass_stats += frame_buffer.draw(
+                &*self.quad,
+                viewport,
+                &*shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: None,
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                    scissor_box: None,
+                },
+                &[ResourceBindGroup {
+                    bindings: &[
+                        ResourceBinding::texture(&gbuffer_diffuse_map, &shader.diffuse_texture),
+                        ResourceBinding::texture(
+                            if settings.use_ssao {
+                                &ao_map
+                            } else {
+                                &fallback_resources.white_dummy
+                            },
+                            &shader.ao_sampler,
+                        ),
+                        ResourceBinding::texture(&gbuffer_ambient_map, &shader.ambient_texture),
+                        ResourceBinding::texture(&gbuffer_depth_map, &shader.depth_texture),
+                        ResourceBinding::texture(&gbuffer_normal_map, &shader.normal_texture),
+                        ResourceBinding::texture(&gbuffer_material_map, &shader.material_texture),
+                        ResourceBinding::texture(&probe.cube_map, &shader.environment_map),
+                        ResourceBinding::Buffer {
+                            buffer: uniform_buffer_cache.write(
+                                StaticUniformBuffer::<512>::new()
+                                    .with(&frame_matrix)
+                                    .with(&inv_view_projection)
+                                    .with(&probe.world_matrix)
+                                    .with(&probe.inv_world_matrix)
+                                    .with(&ambient_color.srgb_to_linear_f32())
+                                    .with(&camera_global_position)
+                                    .with(&probe.max_lod)
+                                    .with(&probe.half_size)
+                                    .with(&probe.blend_distance)
+                                    .with(&(probe.projection == ReflectionProbeProjection::Sphere)),
+                            )?,
+                            binding: BufferLocation::Auto {
+                                shader_location: shader.uniform_buffer_binding,
+                            },
+                            data_usage: Default::default(),
+                        },
+                    ],
+                }],
+                ElementRange::Full,
+            )?;
+        }
+
         for light in render_data_bundle.light_sources.iter() {
             let distance_to_camera = (light.position - camera.global_position()).norm();
 
@@ -622,12 +782,33 @@ impl DeferredLightRenderer {
                 }
             }
 
-            if needs_lighting && shadows_enabled {
+            // Ray-marched volumetric light replaces analytical light scattering.
+            let ray_marched_volume = match light.kind {
+                LightSourceKind::Spot {
+                    ref volumetric_options,
+                    ..
+                }
+                | LightSourceKind::Point {
+                    ref volumetric_options,
+                    ..
+                } if settings.light_scatter_enabled
+                    && light.scatter_enabled
+                    && volumetric_options.enabled =>
+                {
+                    Some(volumetric_options)
+                }
+                _ => None,
+            };
+
+            // Volumetric light needs a shadow map even if there's no pixels lit by the light source.
+            if (needs_lighting || ray_marched_volume.is_some()) && shadows_enabled {
                 match light.kind {
                     LightSourceKind::Spot {
-                        full_cone_angle, ..
+                        full_cone_angle,
+                        ref shadow_map_options,
+                        ..
                     } => {
-                        let z_near = 0.01;
+                        let z_near = SPOT_SHADOW_Z_NEAR;
                         let z_far = light_radius;
                         let light_projection_matrix =
                             Matrix4::new_perspective(1.0, full_cone_angle, z_near, z_far);
@@ -647,7 +828,7 @@ impl DeferredLightRenderer {
 
                         light_view_projection = light_projection_matrix * light_view_matrix;
 
-                        pass_stats += self.spot_shadow_map_renderer.render(
+                        let shadow_stats = self.spot_shadow_map_renderer.render(
                             server,
                             &scene.graph,
                             light.position,
@@ -657,16 +838,23 @@ impl DeferredLightRenderer {
                             light_projection_matrix,
                             geometry_cache,
                             cascade_index,
+                            shadow_map_options.size,
                             shader_cache,
                             textures,
                             fallback_resources,
                             uniform_memory_allocator,
+                            settings.use_gpu_skinning,
                         )?;
 
+                        pass_stats += shadow_stats;
+                        light_stats.shadows += shadow_stats;
                         light_stats.spot_shadow_maps_rendered += 1;
                     }
-                    LightSourceKind::Point { .. } => {
-                        pass_stats +=
+                    LightSourceKind::Point {
+                        ref shadow_map_options,
+                        ..
+                    } => {
+                        let shadow_stats =
                             self.point_shadow_map_renderer
                                 .render(PointShadowMapRenderContext {
                                     state: server,
@@ -675,16 +863,20 @@ impl DeferredLightRenderer {
                                     light_radius,
                                     geom_cache: geometry_cache,
                                     cascade: cascade_index,
+                                    custom_size: shadow_map_options.size,
                                     shader_cache,
                                     texture_cache: textures,
                                     fallback_resources,
                                     uniform_memory_allocator,
+                                    use_gpu_skinning: settings.use_gpu_skinning,
                                 })?;
 
+                        pass_stats += shadow_stats;
+                        light_stats.shadows += shadow_stats;
                         light_stats.point_shadow_maps_rendered += 1;
                     }
                     LightSourceKind::Directional { .. } => {
-                        pass_stats += self.csm_renderer.render(CsmRenderContext {
+                        let shadow_stats = self.csm_renderer.render(CsmRenderContext {
                             frame_size: Vector2::new(gbuffer.width as f32, gbuffer.height as f32),
                             state: server,
                             graph: &scene.graph,
@@ -695,8 +887,11 @@ impl DeferredLightRenderer {
                             texture_cache: textures,
                             fallback_resources,
                             uniform_memory_allocator,
+                            use_gpu_skinning: settings.use_gpu_skinning,
                         })?;
 
+                        pass_stats += shadow_stats;
+                        light_stats.shadows += shadow_stats;
                         light_stats.csm_rendered += 1;
                     }
                     LightSourceKind::Unknown => {}
@@ -732,6 +927,7 @@ impl DeferredLightRenderer {
                         hotspot_cone_angle,
                         shadow_bias,
                         ref cookie_texture,
+                        ref shadow_map_options,
                         ..
                     } => {
                         let shader = &self.spot_light_shader;
@@ -750,7 +946,15 @@ impl DeferredLightRenderer {
                         light_stats.spot_lights_rendered += 1;
 
                         let inv_size = 1.0
-                            / (self.spot_shadow_map_renderer.cascade_size(cascade_index) as f32);
+                            / (self
+                                .spot_shadow_map_renderer
+                                .shadow_map_size(cascade_index, shadow_map_options.size)
+                                as f32);
+                        let shadow_filter = if settings.spot_soft_shadows {
+                            shadow_map_options.filter
+                        } else {
+                            ShadowFilter::Hard
+                        };
                         let uniform_buffer = uniform_buffer_cache.write(
                             StaticUniformBuffer::<1024>::new()
                                 .with(&frame_matrix)
@@ -769,7 +973,11 @@ impl DeferredLightRenderer {
                                 .with(&shadows_alpha)
                                 .with(&cookie_enabled)
                                 .with(&shadows_enabled)
-                                .with(&settings.spot_soft_shadows),
+                                .with(&(shadow_filter as i32))
+                                .with(&shadow_map_options.softness)
+                                .with(&shadow_map_options.normal_bias)
+                                .with(&SPOT_SHADOW_Z_NEAR)
+                                .with(&light_radius),
                         )?;
 
                         frame_buffer.draw(
@@ -796,9 +1004,14 @@ impl DeferredLightRenderer {
                                         &shader.material_sampler,
                                     ),
                                     ResourceBinding::texture(
-                                        &self
-                                            .spot_shadow_map_renderer
-                                            .cascade_texture(cascade_index),
+                                        &gbuffer_decal_mask,
+                                        &shader.decal_mask_sampler,
+                                    ),
+                                    ResourceBinding::texture(
+                                        &self.spot_shadow_map_renderer.shadow_map_texture(
+                                            cascade_index,
+                                            shadow_map_options.size,
+                                        ),
                                         &shader.spot_shadow_texture,
                                     ),
                                     ResourceBinding::texture(
@@ -817,11 +1030,26 @@ impl DeferredLightRenderer {
                             ElementRange::Full,
                         )?
                     }
-                    LightSourceKind::Point { shadow_bias, .. } => {
+                    LightSourceKind::Point {
+                        shadow_bias,
+                        ref shadow_map_options,
+                        ..
+                    } => {
                         let shader = &self.point_light_shader;
 
                         light_stats.point_lights_rendered += 1;
 
+                        let inv_size = 1.0
+                            / (self
+                                .point_shadow_map_renderer
+                                .shadow_map_size(cascade_index, shadow_map_options.size)
+                                as f32);
+                        let shadow_filter = if settings.point_soft_shadows {
+                            shadow_map_options.filter
+                        } else {
+                            ShadowFilter::Hard
+                        };
+
                         let uniform_buffer = uniform_buffer_cache.write(
                             StaticUniformBuffer::<1024>::new()
                                 .with(&frame_matrix)
@@ -833,8 +1061,11 @@ impl DeferredLightRenderer {
                                 .with(&shadow_bias)
                                 .with(&light.intensity)
                                 .with(&shadows_alpha)
-                                .with(&settings.point_soft_shadows)
-                                .with(&shadows_enabled),
+                                .with(&(shadow_filter as i32))
+                                .with(&shadows_enabled)
+                                .with(&inv_size)
+                                .with(&shadow_map_options.softness)
+                                .with(&shadow_map_options.normal_bias),
                         )?;
 
                         frame_buffer.draw(
@@ -861,9 +1092,14 @@ impl DeferredLightRenderer {
                                         &shader.material_sampler,
                                     ),
                                     ResourceBinding::texture(
-                                        &self
-                                            .point_shadow_map_renderer
-                                            .cascade_texture(cascade_index),
+                                        &gbuffer_decal_mask,
+                                        &shader.decal_mask_sampler,
+                                    ),
+                                    ResourceBinding::texture(
+                                        &self.point_shadow_map_renderer.shadow_map_texture(
+                                            cascade_index,
+                                            shadow_map_options.size,
+                                        ),
                                         &shader.point_shadow_texture,
                                     ),
                                     ResourceBinding::Buffer {
@@ -946,6 +1182,10 @@ impl DeferredLightRenderer {
                                         &gbuffer_material_map,
                                         &shader.material_sampler,
                                     ),
+                                    ResourceBinding::texture(
+                                        &gbuffer_decal_mask,
+                                        &shader.decal_mask_sampler,
+                                    ),
                                     ResourceBinding::texture(
                                         &self.csm_renderer.cascades()[0].texture(),
                                         &shader.shadow_cascade0,
@@ -976,7 +1216,39 @@ impl DeferredLightRenderer {
 
             // Light scattering should still be renderer no matter if there's no pixels lit by the
             // light source.
-            if settings.light_scatter_enabled && light.scatter_enabled {
+            if let Some(options) = ray_marched_volume {
+                let shadow_map = match light.kind {
+                    LightSourceKind::Spot {
+                        ref shadow_map_options,
+                        ..
+                    } => self
+                        .spot_shadow_map_renderer
+                        .shadow_map_texture(cascade_index, shadow_map_options.size),
+                    LightSourceKind::Point {
+                        ref shadow_map_options,
+                        ..
+                    } => self
+                        .point_shadow_map_renderer
+                        .shadow_map_texture(cascade_index, shadow_map_options.size),
+                    _ => unreachable!(),
+                };
+
+                pass_stats +=
+                    self.light_volume
+                        .render_ray_marched_volume(RayMarchedVolumeContext {
+                            light,
+                            options,
+                            gbuffer,
+                            quad: &*self.quad,
+                            inv_view_projection,
+                            camera_position: camera_global_position,
+                            light_radius,
+                            light_view_projection,
+                            shadow_map: &shadow_map,
+                            shadows_enabled,
+                            uniform_buffer_cache,
+                        })?;
+            } else if settings.light_scatter_enabled && light.scatter_enabled {
                 pass_stats += self.light_volume.render_volume(
                     light,
                     gbuffer,
@@ -992,6 +1264,79 @@ impl DeferredLightRenderer {
             }
         }
 
+        // Apply fog on top of the lit scene. Light shafts are added after the fog, otherwise they
+        // would be hidden by it.
+        if let Some(fog) = scene.rendering_options.fog.as_ref() {
+            let mode = match fog.mode {
+                FogMode::Linear => 0,
+                FogMode::Exponential => 1,
+                FogMode::ExponentialSquared => 2,
+            };
+
+            pass_stats += frame_buffer.draw(
+                &*self.quad,
+                viewport,
+                &*self.fog_shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: ColorMask {
+                        alpha: false,
+                        ..ColorMask::all(true)
+                    },
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: None,
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                    scissor_box: None,
+                },
+                &[ResourceBindGroup {
+                    bindings: &[
+                        ResourceBinding::texture(
+                            &gbuffer_depth_map,
+                            &self.fog_shader.depth_texture,
+                        ),
+                        ResourceBinding::texture(
+                            &gbuffer_decal_mask,
+                            &self.fog_shader.decal_mask_texture,
+                        ),
+                        ResourceBinding::Buffer {
+                            buffer: uniform_buffer_cache.write(
+                                StaticUniformBuffer::<256>::new()
+                                    .with(&frame_matrix)
+                                    .with(&inv_view_projection)
+                                    .with(&fog.color.srgb_to_linear_f32())
+                                    .with(&camera_global_position)
+                                    .with(&fog.density)
+                                    .with(&fog.start)
+                                    .with(&fog.end)
+                                    .with(&fog.height_falloff)
+                                    .with(&fog.base_height)
+                                    .with(&mode),
+                            )?,
+                            binding: BufferLocation::Auto {
+                                shader_location: self.fog_shader.uniform_buffer_binding,
+                            },
+                            data_usage: Default::default(),
+                        },
+                    ],
+                }],
+                ElementRange::Full,
+            )?;
+        }
+
+        pass_stats += self.light_volume.resolve_ray_marched_volumes(
+            gbuffer,
+            &*self.quad,
+            inv_projection,
+            viewport,
+            frame_buffer,
+            uniform_buffer_cache,
+        )?;
+
         Ok((pass_stats, light_stats))
     }
 }
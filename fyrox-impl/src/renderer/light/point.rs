@@ -33,6 +33,7 @@ pub struct PointLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub decal_mask_sampler: UniformLocation,
     pub point_shadow_texture: UniformLocation,
     pub uniform_buffer_binding: usize,
 }
@@ -47,6 +48,8 @@ impl PointLightShader {
             color_sampler: program.uniform_location(&ImmutableString::new("colorTexture"))?,
             normal_sampler: program.uniform_location(&ImmutableString::new("normalTexture"))?,
             material_sampler: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            decal_mask_sampler: program
+                .uniform_location(&ImmutableString::new("decalMaskTexture"))?,
             point_shadow_texture: program
                 .uniform_location(&ImmutableString::new("pointShadowTexture"))?,
             uniform_buffer_binding: program
@@ -0,0 +1,153 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        sstorage::ImmutableString,
+    },
+    renderer::framework::{
+        error::FrameworkError,
+        framebuffer::{Attachment, AttachmentKind, FrameBuffer},
+        gpu_program::{GpuProgram, UniformLocation},
+        gpu_texture::{
+            GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
+            MinificationFilter, PixelKind, WrapMode,
+        },
+        server::GraphicsServer,
+    },
+    scene::{
+        node::NodeTrait,
+        probe::{ReflectionProbe, ReflectionProbeProjection},
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub struct ReflectionProbeShader {
+    pub program: Box<dyn GpuProgram>,
+    pub uniform_buffer_binding: usize,
+    pub diffuse_texture: UniformLocation,
+    pub ao_sampler: UniformLocation,
+    pub ambient_texture: UniformLocation,
+    pub depth_texture: UniformLocation,
+    pub normal_texture: UniformLocation,
+    pub material_texture: UniformLocation,
+    pub environment_map: UniformLocation,
+}
+
+impl ReflectionProbeShader {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/reflection_probe_fs.glsl");
+        let vertex_source = include_str!("../shaders/reflection_probe_vs.glsl");
+        let program =
+            server.create_program("ReflectionProbeShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            diffuse_texture: program.uniform_location(&ImmutableString::new("diffuseTexture"))?,
+            ao_sampler: program.uniform_location(&ImmutableString::new("aoSampler"))?,
+            ambient_texture: program.uniform_location(&ImmutableString::new("ambientTexture"))?,
+            depth_texture: program.uniform_location(&ImmutableString::new("depthTexture"))?,
+            normal_texture: program.uniform_location(&ImmutableString::new("normalTexture"))?,
+            material_texture: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            environment_map: program.uniform_location(&ImmutableString::new("environmentMap"))?,
+            program,
+        })
+    }
+}
+
+/// A cube map of a reflection probe with a frame buffer, that is used to capture it face by face.
+pub struct ReflectionProbeCubeMap {
+    pub framebuffer: Box<dyn FrameBuffer>,
+    pub resolution: usize,
+    pub mip_count: usize,
+    /// `false` until every face of the cube map is captured.
+    pub is_captured: bool,
+}
+
+impl ReflectionProbeCubeMap {
+    pub fn new(server: &dyn GraphicsServer, resolution: usize) -> Result<Self, FrameworkError> {
+        let mip_count = resolution.max(1).ilog2() as usize + 1;
+
+        let cube_map = server.create_texture(GpuTextureDescriptor {
+            kind: GpuTextureKind::Cube {
+                width: resolution,
+                height: resolution,
+            },
+            pixel_kind: PixelKind::RGBA16F,
+            min_filter: MinificationFilter::LinearMipMapLinear,
+            mag_filter: MagnificationFilter::Linear,
+            mip_count,
+            s_wrap_mode: WrapMode::ClampToEdge,
+            t_wrap_mode: WrapMode::ClampToEdge,
+            r_wrap_mode: WrapMode::ClampToEdge,
+            anisotropy: 1.0,
+            data: None,
+        })?;
+
+        Ok(Self {
+            framebuffer: server.create_frame_buffer(
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: cube_map,
+                }],
+            )?,
+            resolution,
+            mip_count,
+            is_captured: false,
+        })
+    }
+
+    pub fn texture(&self) -> &Rc<RefCell<dyn GpuTexture>> {
+        &self.framebuffer.color_attachments()[0].texture
+    }
+}
+
+/// Everything that is needed to apply a captured reflection probe in the lighting pass.
+pub struct ReflectionProbeRenderData {
+    pub cube_map: Rc<RefCell<dyn GpuTexture>>,
+    pub max_lod: f32,
+    pub world_matrix: Matrix4<f32>,
+    pub inv_world_matrix: Matrix4<f32>,
+    pub half_size: Vector3<f32>,
+    pub projection: ReflectionProbeProjection,
+    pub blend_distance: f32,
+    pub world_bounding_box: AxisAlignedBoundingBox,
+}
+
+impl ReflectionProbeRenderData {
+    pub fn new(probe: &ReflectionProbe, cube_map: &ReflectionProbeCubeMap) -> Option<Self> {
+        // The cube map is captured in world space, so only the bounds of the probe are
+        // transformed.
+        let world_matrix = probe.global_transform();
+        Some(Self {
+            cube_map: cube_map.texture().clone(),
+            max_lod: cube_map.mip_count.saturating_sub(1) as f32,
+            inv_world_matrix: world_matrix.try_inverse()?,
+            world_matrix,
+            half_size: probe.size().scale(0.5),
+            projection: probe.projection(),
+            blend_distance: probe.blend_distance(),
+            world_bounding_box: probe.world_bounding_box(),
+        })
+    }
+}
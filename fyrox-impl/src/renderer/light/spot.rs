@@ -33,6 +33,7 @@ pub struct SpotLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub decal_mask_sampler: UniformLocation,
     pub spot_shadow_texture: UniformLocation,
     pub cookie_texture: UniformLocation,
     pub uniform_buffer_binding: usize,
@@ -48,6 +49,8 @@ impl SpotLightShader {
             color_sampler: program.uniform_location(&ImmutableString::new("colorTexture"))?,
             normal_sampler: program.uniform_location(&ImmutableString::new("normalTexture"))?,
             material_sampler: program.uniform_location(&ImmutableString::new("materialTexture"))?,
+            decal_mask_sampler: program
+                .uniform_location(&ImmutableString::new("decalMaskTexture"))?,
             spot_shadow_texture: program
                 .uniform_location(&ImmutableString::new("spotShadowTexture"))?,
             cookie_texture: program.uniform_location(&ImmutableString::new("cookieTexture"))?,
@@ -22,7 +22,8 @@ use crate::renderer::bundle::{LightSource, LightSourceKind};
 use crate::renderer::make_viewport_matrix;
 use crate::{
     core::{
-        algebra::{Isometry3, Matrix4, Point3, Translation, Vector3},
+        algebra::{Isometry3, Matrix4, Point3, Translation, Vector2, Vector3},
+        color::Color,
         math::Rect,
         sstorage::ImmutableString,
     },
@@ -32,9 +33,12 @@ use crate::{
         framework::{
             buffer::BufferUsage,
             error::FrameworkError,
-            framebuffer::{FrameBuffer, ResourceBindGroup, ResourceBinding},
+            framebuffer::{
+                Attachment, AttachmentKind, FrameBuffer, ResourceBindGroup, ResourceBinding,
+            },
             geometry_buffer::GeometryBuffer,
             gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{GpuTexture, PixelKind},
             server::GraphicsServer,
             uniform::StaticUniformBuffer,
             BlendFactor, BlendFunc, BlendParameters, ColorMask, CompareFunc, DrawParameters,
@@ -43,9 +47,10 @@ use crate::{
         gbuffer::GBuffer,
         RenderPassStatistics,
     },
-    scene::{graph::Graph, mesh::surface::SurfaceData},
+    scene::{graph::Graph, light::VolumetricLightOptions, mesh::surface::SurfaceData},
 };
 use fyrox_graphics::framebuffer::BufferLocation;
+use std::{cell::RefCell, rc::Rc};
 
 struct SpotLightShader {
     program: Box<dyn GpuProgram>,
@@ -89,19 +94,144 @@ impl PointLightShader {
     }
 }
 
+struct RayMarchedSpotLightShader {
+    program: Box<dyn GpuProgram>,
+    depth_sampler: UniformLocation,
+    shadow_sampler: UniformLocation,
+    uniform_block_binding: usize,
+}
+
+impl RayMarchedSpotLightShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/spot_volumetric_ray_marched_fs.glsl");
+        let vertex_source = include_str!("shaders/spot_volumetric_ray_marched_vs.glsl");
+        let program = server.create_program(
+            "RayMarchedSpotVolumetricLight",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            depth_sampler: program.uniform_location(&ImmutableString::new("depthSampler"))?,
+            shadow_sampler: program.uniform_location(&ImmutableString::new("spotShadowTexture"))?,
+            uniform_block_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+struct RayMarchedPointLightShader {
+    program: Box<dyn GpuProgram>,
+    depth_sampler: UniformLocation,
+    shadow_sampler: UniformLocation,
+    uniform_block_binding: usize,
+}
+
+impl RayMarchedPointLightShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/point_volumetric_ray_marched_fs.glsl");
+        let vertex_source = include_str!("shaders/point_volumetric_ray_marched_vs.glsl");
+        let program = server.create_program(
+            "RayMarchedPointVolumetricLight",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            depth_sampler: program.uniform_location(&ImmutableString::new("depthSampler"))?,
+            shadow_sampler: program
+                .uniform_location(&ImmutableString::new("pointShadowTexture"))?,
+            uniform_block_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+struct UpsampleShader {
+    program: Box<dyn GpuProgram>,
+    depth_sampler: UniformLocation,
+    volume_texture: UniformLocation,
+    uniform_block_binding: usize,
+}
+
+impl UpsampleShader {
+    fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/volumetric_upsample_fs.glsl");
+        let vertex_source = include_str!("shaders/volumetric_upsample_vs.glsl");
+        let program =
+            server.create_program("VolumetricLightUpsample", vertex_source, fragment_source)?;
+        Ok(Self {
+            depth_sampler: program.uniform_location(&ImmutableString::new("depthSampler"))?,
+            volume_texture: program.uniform_location(&ImmutableString::new("volumeTexture"))?,
+            uniform_block_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+/// Everything that is needed to render ray-marched volumetric light of a light source.
+pub(crate) struct RayMarchedVolumeContext<'a> {
+    pub light: &'a LightSource,
+    pub options: &'a VolumetricLightOptions,
+    pub gbuffer: &'a GBuffer,
+    pub quad: &'a dyn GeometryBuffer,
+    pub inv_view_projection: Matrix4<f32>,
+    pub camera_position: Vector3<f32>,
+    /// Radius of the light with the scale of the light taken into account.
+    pub light_radius: f32,
+    /// Light view-projection matrix, used only by spot lights.
+    pub light_view_projection: Matrix4<f32>,
+    pub shadow_map: &'a Rc<RefCell<dyn GpuTexture>>,
+    pub shadows_enabled: bool,
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
 pub struct LightVolumeRenderer {
     spot_light_shader: SpotLightShader,
     point_light_shader: PointLightShader,
+    ray_marched_spot_light_shader: RayMarchedSpotLightShader,
+    ray_marched_point_light_shader: RayMarchedPointLightShader,
+    upsample_shader: UpsampleShader,
     flat_shader: FlatShader,
     cone: Box<dyn GeometryBuffer>,
     sphere: Box<dyn GeometryBuffer>,
+    // Ray-marched volumetric light is rendered in half resolution to this frame buffer and then
+    // upsampled to the full resolution.
+    half_res_frame_buffer: Box<dyn FrameBuffer>,
+    half_res_width: usize,
+    half_res_height: usize,
+    has_ray_marched_volumes: bool,
+}
+
+fn make_half_res_frame_buffer(
+    server: &dyn GraphicsServer,
+    width: usize,
+    height: usize,
+) -> Result<Box<dyn FrameBuffer>, FrameworkError> {
+    server.create_frame_buffer(
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: server.create_2d_render_target(PixelKind::RGBA16F, width, height)?,
+        }],
+    )
 }
 
 impl LightVolumeRenderer {
-    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+    pub fn new(
+        server: &dyn GraphicsServer,
+        frame_size: (u32, u32),
+    ) -> Result<Self, FrameworkError> {
+        let half_res_width = (frame_size.0 as usize / 2).max(1);
+        let half_res_height = (frame_size.1 as usize / 2).max(1);
+
         Ok(Self {
             spot_light_shader: SpotLightShader::new(server)?,
             point_light_shader: PointLightShader::new(server)?,
+            ray_marched_spot_light_shader: RayMarchedSpotLightShader::new(server)?,
+            ray_marched_point_light_shader: RayMarchedPointLightShader::new(server)?,
+            upsample_shader: UpsampleShader::new(server)?,
             flat_shader: FlatShader::new(server)?,
             cone: <dyn GeometryBuffer>::from_surface_data(
                 &SurfaceData::make_cone(
@@ -118,9 +248,244 @@ impl LightVolumeRenderer {
                 BufferUsage::StaticDraw,
                 server,
             )?,
+            half_res_frame_buffer: make_half_res_frame_buffer(
+                server,
+                half_res_width,
+                half_res_height,
+            )?,
+            half_res_width,
+            half_res_height,
+            has_ray_marched_volumes: false,
         })
     }
 
+    pub fn set_frame_size(
+        &mut self,
+        server: &dyn GraphicsServer,
+        frame_size: (u32, u32),
+    ) -> Result<(), FrameworkError> {
+        self.half_res_width = (frame_size.0 as usize / 2).max(1);
+        self.half_res_height = (frame_size.1 as usize / 2).max(1);
+        self.half_res_frame_buffer =
+            make_half_res_frame_buffer(server, self.half_res_width, self.half_res_height)?;
+        Ok(())
+    }
+
+    fn half_res_viewport(&self) -> Rect<i32> {
+        Rect::new(
+            0,
+            0,
+            self.half_res_width as i32,
+            self.half_res_height as i32,
+        )
+    }
+
+    /// Ray-marches volumetric light of a spot or point light source in half resolution. The result
+    /// is accumulated and must be applied to the frame by [`Self::resolve_ray_marched_volumes`].
+    pub(crate) fn render_ray_marched_volume(
+        &mut self,
+        args: RayMarchedVolumeContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let RayMarchedVolumeContext {
+            light,
+            options,
+            gbuffer,
+            quad,
+            inv_view_projection,
+            camera_position,
+            light_radius,
+            light_view_projection,
+            shadow_map,
+            shadows_enabled,
+            uniform_buffer_cache,
+        } = args;
+
+        let viewport = self.half_res_viewport();
+
+        if !self.has_ray_marched_volumes {
+            self.half_res_frame_buffer
+                .clear(viewport, Some(Color::TRANSPARENT), None, None);
+            self.has_ray_marched_volumes = true;
+        }
+
+        let frame_matrix = make_viewport_matrix(viewport);
+        let draw_params = DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: None,
+            blend: Some(BlendParameters {
+                func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                ..Default::default()
+            }),
+            stencil_op: Default::default(),
+            scissor_box: None,
+        };
+        let color = light.color.srgb_to_linear_f32().xyz();
+        let step_count = options.step_count.max(1) as i32;
+
+        let mut stats = RenderPassStatistics::default();
+
+        match light.kind {
+            LightSourceKind::Spot {
+                full_cone_angle,
+                hotspot_cone_angle,
+                shadow_bias,
+                ..
+            } => {
+                let direction = light
+                    .up_vector
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::z);
+                let shader = &self.ray_marched_spot_light_shader;
+                stats += self.half_res_frame_buffer.draw(
+                    quad,
+                    viewport,
+                    &*shader.program,
+                    &draw_params,
+                    &[ResourceBindGroup {
+                        bindings: &[
+                            ResourceBinding::texture(&gbuffer.depth(), &shader.depth_sampler),
+                            ResourceBinding::texture(shadow_map, &shader.shadow_sampler),
+                            ResourceBinding::Buffer {
+                                buffer: uniform_buffer_cache.write(
+                                    StaticUniformBuffer::<512>::new()
+                                        .with(&frame_matrix)
+                                        .with(&inv_view_projection)
+                                        .with(&light_view_projection)
+                                        .with(&camera_position)
+                                        .with(&light.position)
+                                        .with(&direction)
+                                        .with(&color)
+                                        .with(&light.intensity)
+                                        .with(&(hotspot_cone_angle * 0.5).cos())
+                                        .with(&(full_cone_angle * 0.5).cos())
+                                        .with(&light_radius)
+                                        .with(&options.density)
+                                        .with(&options.anisotropy)
+                                        .with(&step_count)
+                                        .with(&shadows_enabled)
+                                        .with(&shadow_bias),
+                                )?,
+                                binding: BufferLocation::Auto {
+                                    shader_location: shader.uniform_block_binding,
+                                },
+                                data_usage: Default::default(),
+                            },
+                        ],
+                    }],
+                    ElementRange::Full,
+                )?;
+            }
+            LightSourceKind::Point { shadow_bias, .. } => {
+                let shader = &self.ray_marched_point_light_shader;
+                stats += self.half_res_frame_buffer.draw(
+                    quad,
+                    viewport,
+                    &*shader.program,
+                    &draw_params,
+                    &[ResourceBindGroup {
+                        bindings: &[
+                            ResourceBinding::texture(&gbuffer.depth(), &shader.depth_sampler),
+                            ResourceBinding::texture(shadow_map, &shader.shadow_sampler),
+                            ResourceBinding::Buffer {
+                                buffer: uniform_buffer_cache.write(
+                                    StaticUniformBuffer::<512>::new()
+                                        .with(&frame_matrix)
+                                        .with(&inv_view_projection)
+                                        .with(&camera_position)
+                                        .with(&light.position)
+                                        .with(&color)
+                                        .with(&light.intensity)
+                                        .with(&light_radius)
+                                        .with(&options.density)
+                                        .with(&options.anisotropy)
+                                        .with(&step_count)
+                                        .with(&shadows_enabled)
+                                        .with(&shadow_bias),
+                                )?,
+                                binding: BufferLocation::Auto {
+                                    shader_location: shader.uniform_block_binding,
+                                },
+                                data_usage: Default::default(),
+                            },
+                        ],
+                    }],
+                    ElementRange::Full,
+                )?;
+            }
+            _ => (),
+        }
+
+        Ok(stats)
+    }
+
+    /// Upsamples accumulated ray-marched volumetric light (if any) to the full resolution using
+    /// depth-aware (bilateral) filter and adds it to the given frame buffer.
+    pub(crate) fn resolve_ray_marched_volumes(
+        &mut self,
+        gbuffer: &GBuffer,
+        quad: &dyn GeometryBuffer,
+        inv_proj: Matrix4<f32>,
+        viewport: Rect<i32>,
+        frame_buffer: &mut dyn FrameBuffer,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut stats = RenderPassStatistics::default();
+
+        if !std::mem::take(&mut self.has_ray_marched_volumes) {
+            return Ok(stats);
+        }
+
+        let shader = &self.upsample_shader;
+        stats += frame_buffer.draw(
+            quad,
+            viewport,
+            &*shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: Some(BlendParameters {
+                    func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                    ..Default::default()
+                }),
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &[
+                    ResourceBinding::texture(&gbuffer.depth(), &shader.depth_sampler),
+                    ResourceBinding::texture(
+                        &self.half_res_frame_buffer.color_attachments()[0].texture,
+                        &shader.volume_texture,
+                    ),
+                    ResourceBinding::Buffer {
+                        buffer: uniform_buffer_cache.write(
+                            StaticUniformBuffer::<256>::new()
+                                .with(&make_viewport_matrix(viewport))
+                                .with(&inv_proj)
+                                .with(&Vector2::new(
+                                    self.half_res_width as f32,
+                                    self.half_res_height as f32,
+                                )),
+                        )?,
+                        binding: BufferLocation::Auto {
+                            shader_location: shader.uniform_block_binding,
+                        },
+                        data_usage: Default::default(),
+                    },
+                ],
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(stats)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn render_volume(
         &mut self,
@@ -32,12 +32,15 @@ pub mod framework;
 
 pub mod bundle;
 pub mod cache;
+pub mod calibration;
 pub mod debug_renderer;
+pub mod post_effect;
 pub mod storage;
 pub mod ui_renderer;
 pub mod visibility;
 
 mod bloom;
+mod color_blindness;
 mod flat_shader;
 mod forward_renderer;
 mod fxaa;
@@ -46,6 +49,8 @@ mod hdr;
 mod light;
 mod light_volume;
 mod occlusion;
+mod procedural_sky;
+mod profiler;
 mod shadow;
 mod skybox_shader;
 mod ssao;
@@ -71,11 +76,18 @@ use crate::{
     material::shader::{Shader, ShaderDefinition, ShaderResource, ShaderResourceExtension},
     renderer::{
         bloom::BloomRenderer,
-        bundle::{ObserverInfo, RenderDataBundleStorage, RenderDataBundleStorageOptions},
+        bundle::{
+            ObserverInfo, RenderDataBundleStorage, RenderDataBundleStorageOptions,
+            INSTANCE_BATCH_BLOCK_SIZE,
+        },
         cache::{
             geometry::GeometryCache, shader::ShaderCache, texture::TextureCache,
-            uniform::UniformBufferCache, uniform::UniformMemoryAllocator,
+            uniform::UniformBufferCache, uniform::UniformMemoryAllocator, TimeToLive,
+        },
+        calibration::{
+            CalibrationStep, QualityCalibrationOptions, QualityCalibrationResult, QualityCalibrator,
         },
+        color_blindness::ColorBlindnessRenderer,
         debug_renderer::DebugRenderer,
         flat_shader::FlatShader,
         forward_renderer::{ForwardRenderContext, ForwardRenderer},
@@ -87,10 +99,9 @@ use crate::{
                 ResourceBinding,
             },
             geometry_buffer::{DrawCallStatistics, GeometryBuffer},
-            gl::server::GlGraphicsServer,
             gpu_program::SamplerFallback,
             gpu_texture::{
-                GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
+                CubeMapFace, GpuTexture, GpuTextureDescriptor, GpuTextureKind, MagnificationFilter,
                 MinificationFilter, PixelKind,
             },
             server::{GraphicsServer, SharedGraphicsServer},
@@ -100,18 +111,47 @@ use crate::{
         fxaa::FxaaRenderer,
         gbuffer::{GBuffer, GBufferRenderContext},
         hdr::HighDynamicRangeRenderer,
-        light::{DeferredLightRenderer, DeferredRendererContext},
+        light::{
+            reflection_probe::{ReflectionProbeCubeMap, ReflectionProbeRenderData},
+            DeferredLightRenderer, DeferredRendererContext,
+        },
+        post_effect::{
+            make_posterization_effect, make_vignette_effect, PostEffect, PostEffectContext,
+            ShaderPostEffect,
+        },
+        profiler::PassProfiler,
         ui_renderer::{UiRenderContext, UiRenderer},
         visibility::VisibilityCache,
     },
-    resource::texture::{Texture, TextureKind, TextureResource},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
+    resource::{
+        model::Model,
+        texture::{Texture, TextureKind, TextureResource},
+    },
+    scene::{
+        camera::Camera,
+        mesh::{
+            surface::{SurfaceData, SurfaceResource},
+            Mesh,
+        },
+        mirror::Mirror,
+        node::Node,
+        probe::ReflectionProbe,
+        Scene, SceneContainer,
+    },
 };
+pub use color_blindness::{ColorBlindnessFilter, ColorBlindnessKind, ColorBlindnessMode};
 use fxhash::FxHashMap;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 pub use stats::*;
-use std::{any::TypeId, cell::RefCell, collections::hash_map::Entry, rc::Rc, sync::mpsc::Receiver};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::{hash_map::Entry, VecDeque},
+    path::Path,
+    rc::Rc,
+    sync::mpsc::Receiver,
+};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 use winit::{
     event_loop::EventLoopWindowTarget,
@@ -189,6 +229,48 @@ impl Default for CsmSettings {
     }
 }
 
+/// Texture streaming settings. When texture streaming is enabled, the renderer keeps only those mip
+/// levels of textures in video memory, that are actually needed to render visible objects. Required
+/// mip level of a texture is defined by the distance from a camera and screen coverage of the objects
+/// that use the texture. The most detailed mip levels are uploaded on demand while the total amount of
+/// video memory used by streamed textures fits in the budget. Low-resolution mips are always resident,
+/// so there's always something to render while high-resolution mips are missing.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct TextureStreamingSettings {
+    /// Whether texture streaming is enabled or not. When disabled, all mip levels of all textures are
+    /// uploaded to video memory.
+    pub enabled: bool,
+
+    /// Maximum amount of video memory (in bytes) that could be used by streamed textures. Low-resolution
+    /// mips that are always resident are included in this amount too, so the budget could be exceeded
+    /// if there are lots of textures.
+    pub memory_budget: usize,
+
+    /// Mip levels that have their largest side less or equal to this value are always resident in video
+    /// memory.
+    pub always_resident_size: u32,
+
+    /// Bias that is added to the desired mip level of every texture. Positive values makes textures less
+    /// detailed and save video memory, negative - the opposite.
+    pub mip_bias: f32,
+
+    /// Maximum amount of textures that could be re-uploaded to video memory per frame. Prevents lag
+    /// spikes when a camera moves fast.
+    pub max_uploads_per_frame: usize,
+}
+
+impl Default for TextureStreamingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            memory_budget: 1024 * 1024 * 1024,
+            always_resident_size: 128,
+            mip_bias: 0.0,
+            max_uploads_per_frame: 8,
+        }
+    }
+}
+
 /// Quality settings allows you to find optimal balance between performance and
 /// graphics quality.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
@@ -251,6 +333,30 @@ pub struct QualitySettings {
     /// Whether to use occlusion culling technique or not.
     #[serde(default)]
     pub use_occlusion_culling: bool,
+
+    /// Maximum amount of decals, that can be rendered per camera. If there are more decals, only the
+    /// closest ones to the camera will be rendered.
+    #[serde(default = "default_max_decals")]
+    pub max_decals: usize,
+
+    /// Whether to do skinning of skinned meshes on the GPU or not. When disabled, vertices of skinned
+    /// meshes are transformed on the CPU every frame, which is slow, but works on hardware that
+    /// cannot handle large bone matrix buffers. Meshes with more bones than
+    /// [`ShaderDefinition::MAX_BONE_MATRICES`] are always skinned on the CPU.
+    #[serde(default = "default_use_gpu_skinning")]
+    pub use_gpu_skinning: bool,
+
+    /// Texture streaming settings.
+    #[serde(default)]
+    pub texture_streaming: TextureStreamingSettings,
+}
+
+fn default_max_decals() -> usize {
+    256
+}
+
+fn default_use_gpu_skinning() -> bool {
+    true
 }
 
 impl Default for QualitySettings {
@@ -290,6 +396,14 @@ impl QualitySettings {
             use_occlusion_culling: true,
             use_parallax_mapping: true,
 
+            max_decals: 512,
+            use_gpu_skinning: true,
+
+            texture_streaming: TextureStreamingSettings {
+                memory_budget: 2048 * 1024 * 1024,
+                ..Default::default()
+            },
+
             csm_settings: Default::default(),
         }
     }
@@ -324,6 +438,14 @@ impl QualitySettings {
             use_occlusion_culling: true,
             use_parallax_mapping: true,
 
+            max_decals: 256,
+            use_gpu_skinning: true,
+
+            texture_streaming: TextureStreamingSettings {
+                memory_budget: 1024 * 1024 * 1024,
+                ..Default::default()
+            },
+
             csm_settings: CsmSettings {
                 enabled: true,
                 size: 2048,
@@ -363,6 +485,14 @@ impl QualitySettings {
             use_occlusion_culling: true,
             use_parallax_mapping: false,
 
+            max_decals: 128,
+            use_gpu_skinning: true,
+
+            texture_streaming: TextureStreamingSettings {
+                memory_budget: 512 * 1024 * 1024,
+                ..Default::default()
+            },
+
             csm_settings: CsmSettings {
                 enabled: true,
                 size: 512,
@@ -402,6 +532,14 @@ impl QualitySettings {
             use_occlusion_culling: true,
             use_parallax_mapping: false,
 
+            max_decals: 64,
+            use_gpu_skinning: true,
+
+            texture_streaming: TextureStreamingSettings {
+                memory_budget: 256 * 1024 * 1024,
+                ..Default::default()
+            },
+
             csm_settings: CsmSettings {
                 enabled: true,
                 size: 512,
@@ -418,6 +556,9 @@ impl Statistics {
         self.frame_start_time = instant::Instant::now();
         self.geometry = Default::default();
         self.lighting = Default::default();
+        self.culling = Default::default();
+        self.scenes.clear();
+        self.passes.clear();
     }
 
     /// Must be called before SwapBuffers but after all rendering is done.
@@ -454,6 +595,7 @@ impl Default for Statistics {
             pipeline: Default::default(),
             lighting: Default::default(),
             geometry: Default::default(),
+            culling: Default::default(),
             pure_frame_time: 0.0,
             capped_frame_time: 0.0,
             frames_per_second: 0,
@@ -461,6 +603,8 @@ impl Default for Statistics {
             geometry_cache_size: 0,
             shader_cache_size: 0,
             uniform_buffer_cache_size: 0,
+            scenes: Default::default(),
+            passes: Default::default(),
             frame_counter: 0,
             frame_start_time: instant::Instant::now(),
             last_fps_commit_time: instant::Instant::now(),
@@ -617,6 +761,10 @@ impl AssociatedSceneData {
     }
 }
 
+fn has_full_viewport(camera: &Camera) -> bool {
+    camera.viewport() == Rect::new(0.0, 0.0, 1.0, 1.0)
+}
+
 /// Creates a view-projection matrix that projects unit quad a screen with the specified viewport.
 pub fn make_viewport_matrix(viewport: Rect<i32>) -> Matrix4<f32> {
     Matrix4::new_orthographic(
@@ -653,6 +801,8 @@ pub struct FallbackResources {
     pub volume_dummy: Rc<RefCell<dyn GpuTexture>>,
     /// A stub uniform buffer for situation when there's no actual bone matrices.
     pub bone_matrices_stub_uniform_buffer: Box<dyn Buffer>,
+    /// A stub uniform buffer for draw calls that are not instanced. It has zero instance count.
+    pub instance_batch_stub_uniform_buffer: Box<dyn Buffer>,
 }
 
 impl FallbackResources {
@@ -685,6 +835,9 @@ pub struct Renderer {
     quad: Box<dyn GeometryBuffer>,
     frame_size: (u32, u32),
     quality_settings: QualitySettings,
+    quality_calibrator: Option<QualityCalibrator>,
+    pass_profiler: PassProfiler,
+    quality_calibration_result: Option<QualityCalibrationResult>,
     /// Debug renderer instance can be used for debugging purposes
     pub debug_renderer: DebugRenderer,
     /// Screen space debug renderer instance can be used for debugging purposes to draw lines directly
@@ -692,6 +845,10 @@ pub struct Renderer {
     pub screen_space_debug_renderer: DebugRenderer,
     /// A set of associated data for each scene that was rendered.
     pub scene_data_map: FxHashMap<Handle<Scene>, AssociatedSceneData>,
+    /// A set of associated data for each camera that renders into its own render target. See
+    /// [`Camera::set_render_target`] for more info.
+    pub camera_data_map: FxHashMap<(Handle<Scene>, Handle<Node>), AssociatedSceneData>,
+    reflection_probe_map: FxHashMap<(Handle<Scene>, Handle<Node>), ReflectionProbeCubeMap>,
     backbuffer_clear_color: Color,
     /// Texture cache with GPU textures.
     pub texture_cache: TextureCache,
@@ -701,8 +858,16 @@ pub struct Renderer {
     geometry_cache: GeometryCache,
     forward_renderer: ForwardRenderer,
     fxaa_renderer: FxaaRenderer,
+    color_blindness_renderer: ColorBlindnessRenderer,
+    color_blindness_filter: Option<ColorBlindnessFilter>,
+    vignette_effect: ShaderPostEffect,
+    posterization_effect: ShaderPostEffect,
+    post_effects: Vec<Rc<RefCell<dyn PostEffect>>>,
     texture_event_receiver: Receiver<ResourceEvent>,
     shader_event_receiver: Receiver<ResourceEvent>,
+    model_event_receiver: Receiver<ResourceEvent>,
+    // Surfaces of loaded models, that are waiting to be uploaded to GPU.
+    pending_geometry: VecDeque<SurfaceResource>,
     // TextureId -> FrameBuffer mapping. This mapping is used for temporal frame buffers
     // like ones used to render UI instances.
     ui_frame_buffers: FxHashMap<u64, Box<dyn FrameBuffer>>,
@@ -858,6 +1023,27 @@ pub trait SceneRenderPass {
     fn source_type_id(&self) -> TypeId;
 }
 
+/// Defines what a camera renders in the current frame.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum CameraPurpose {
+    /// A camera from the scene graph.
+    Main,
+    /// A temporary camera, that renders a reflection of a mirror.
+    Reflection,
+    /// A temporary camera, that renders a face of the cube map of a reflection probe.
+    ProbeFace(CubeMapFace),
+}
+
+/// Faces of a cube map in the order of the capture cameras of a reflection probe.
+const CUBE_MAP_FACES: [CubeMapFace; 6] = [
+    CubeMapFace::PositiveX,
+    CubeMapFace::NegativeX,
+    CubeMapFace::PositiveY,
+    CubeMapFace::NegativeY,
+    CubeMapFace::PositiveZ,
+    CubeMapFace::NegativeZ,
+];
+
 fn blit_pixels(
     uniform_buffer_cache: &mut UniformBufferCache,
     framebuffer: &mut dyn FrameBuffer,
@@ -943,7 +1129,14 @@ impl Renderer {
             .event_broadcaster
             .add(shader_event_sender);
 
-        let (window, server) = GlGraphicsServer::new(
+        let (model_event_sender, model_event_receiver) = std::sync::mpsc::channel();
+
+        resource_manager
+            .state()
+            .event_broadcaster
+            .add(model_event_sender);
+
+        let (window, server) = params.graphics_server_constructor.construct(
             params.vsync,
             params.msaa_sample_count,
             window_target,
@@ -1076,6 +1269,15 @@ impl Renderer {
                 buffer.write_data(array_as_u8_slice(&zeros))?;
                 buffer
             },
+            instance_batch_stub_uniform_buffer: {
+                let buffer = server.create_buffer(
+                    INSTANCE_BATCH_BLOCK_SIZE,
+                    BufferKind::Uniform,
+                    BufferUsage::StaticDraw,
+                )?;
+                buffer.write_data(&[0; INSTANCE_BATCH_BLOCK_SIZE])?;
+                buffer
+            },
         };
 
         let renderer = Self {
@@ -1092,18 +1294,34 @@ impl Renderer {
 
             ui_renderer: UiRenderer::new(&*server)?,
             quality_settings: settings,
+            quality_calibrator: None,
+            pass_profiler: Default::default(),
+            quality_calibration_result: None,
             debug_renderer: DebugRenderer::new(&*server)?,
             screen_space_debug_renderer: DebugRenderer::new(&*server)?,
             scene_data_map: Default::default(),
+            camera_data_map: Default::default(),
+            reflection_probe_map: Default::default(),
             backbuffer_clear_color: Color::BLACK,
-            texture_cache: Default::default(),
+            texture_cache: {
+                let mut texture_cache = TextureCache::default();
+                texture_cache.set_streaming_settings(settings.texture_streaming);
+                texture_cache
+            },
             geometry_cache: Default::default(),
             forward_renderer: ForwardRenderer::new(),
             ui_frame_buffers: Default::default(),
             fxaa_renderer: FxaaRenderer::new(&*server)?,
+            color_blindness_renderer: ColorBlindnessRenderer::new(&*server)?,
+            color_blindness_filter: None,
+            vignette_effect: make_vignette_effect(),
+            posterization_effect: make_posterization_effect(),
+            post_effects: Default::default(),
             statistics: Statistics::default(),
             shader_event_receiver,
             texture_event_receiver,
+            model_event_receiver,
+            pending_geometry: Default::default(),
             shader_cache,
             scene_render_passes: Default::default(),
             uniform_buffer_cache: UniformBufferCache::new(server.clone()),
@@ -1141,9 +1359,38 @@ impl Renderer {
         self.scene_render_passes.clear()
     }
 
+    /// Adds a new post effect to the end of the post-processing chain. See [`PostEffect`] docs for
+    /// more info.
+    pub fn add_post_effect(&mut self, effect: Rc<RefCell<dyn PostEffect>>) {
+        self.post_effects.push(effect);
+    }
+
+    /// Inserts a new post effect at the given position in the post-processing chain. The index
+    /// will be clamped to the length of the chain.
+    pub fn insert_post_effect(&mut self, index: usize, effect: Rc<RefCell<dyn PostEffect>>) {
+        let index = index.min(self.post_effects.len());
+        self.post_effects.insert(index, effect);
+    }
+
+    /// Removes the specified post effect from the post-processing chain.
+    pub fn remove_post_effect(&mut self, effect: Rc<RefCell<dyn PostEffect>>) {
+        if let Some(index) = self
+            .post_effects
+            .iter()
+            .position(|e| Rc::ptr_eq(e, &effect))
+        {
+            self.post_effects.remove(index);
+        }
+    }
+
+    /// Returns a slice with every registered post effect in the order of their execution.
+    pub fn post_effects(&self) -> &[Rc<RefCell<dyn PostEffect>>] {
+        &self.post_effects
+    }
+
     /// Returns statistics for last frame.
     pub fn get_statistics(&self) -> Statistics {
-        self.statistics
+        self.statistics.clone()
     }
 
     /// Unloads texture from GPU memory.
@@ -1199,6 +1446,8 @@ impl Renderer {
         settings: &QualitySettings,
     ) -> Result<(), FrameworkError> {
         self.quality_settings = *settings;
+        self.texture_cache
+            .set_streaming_settings(settings.texture_streaming);
         self.deferred_light_renderer
             .set_quality_settings(&*self.server, settings)
     }
@@ -1208,6 +1457,111 @@ impl Renderer {
         self.quality_settings
     }
 
+    /// Starts quality calibration. The renderer will go through every level from
+    /// [`QualityCalibrationOptions::levels`] (from the highest quality to the lowest), render a few
+    /// frames with each level and measure how much time the GPU spends on each frame. The first
+    /// level that fits in the [`QualityCalibrationOptions::target_frame_time`] will be applied and
+    /// stored as the calibration result (see [`Self::quality_calibration_result`]).
+    ///
+    /// GPU timer queries are used to measure frame time, if they are not supported, the renderer
+    /// falls back to CPU time measurement with forced synchronization with the GPU. The
+    /// measurements are done on the scenes that are currently rendered, so calibration should be
+    /// performed on a scene that is representative for your game.
+    pub fn begin_quality_calibration(
+        &mut self,
+        options: QualityCalibrationOptions,
+    ) -> Result<(), FrameworkError> {
+        if options.levels.is_empty() {
+            Log::warn("Quality calibration requires at least one quality level!");
+            return Ok(());
+        }
+
+        let calibrator = QualityCalibrator::new(options, self.server.capabilities().timer_queries);
+        self.set_quality_settings(&calibrator.current_level())?;
+        self.quality_calibrator = Some(calibrator);
+        self.quality_calibration_result = None;
+        Ok(())
+    }
+
+    /// Loads quality settings from the given file, or, if there's no such file (for example, when
+    /// the game runs for the first time), starts quality calibration (see
+    /// [`Self::begin_quality_calibration`]) and saves the recommended settings to the file once the
+    /// calibration is finished.
+    pub fn load_or_calibrate_quality_settings<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: QualityCalibrationOptions,
+    ) -> Result<(), FrameworkError> {
+        let path = path.as_ref();
+        match QualitySettings::load_from_file(path) {
+            Ok(settings) => self.set_quality_settings(&settings),
+            Err(e) => {
+                Log::info(format!(
+                    "Unable to load quality settings from {}, starting calibration. Reason: {e}",
+                    path.display()
+                ));
+                self.begin_quality_calibration(options)?;
+                if let Some(calibrator) = self.quality_calibrator.as_mut() {
+                    calibrator.save_path = Some(path.to_path_buf());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns `true` if quality calibration is in progress, `false` - otherwise.
+    pub fn is_quality_calibration_in_progress(&self) -> bool {
+        self.quality_calibrator.is_some()
+    }
+
+    /// Returns the result of the last finished quality calibration (if any).
+    pub fn quality_calibration_result(&self) -> Option<&QualityCalibrationResult> {
+        self.quality_calibration_result.as_ref()
+    }
+
+    fn update_quality_calibration(&mut self, step: CalibrationStep) -> Result<(), FrameworkError> {
+        match step {
+            CalibrationStep::Measuring => (),
+            CalibrationStep::SwitchLevel(settings) => self.set_quality_settings(&settings)?,
+            CalibrationStep::Finished(result) => {
+                self.set_quality_settings(&result.recommended)?;
+
+                Log::info(format!(
+                    "Quality calibration is finished. Recommended level: {}, frame times: {:?}",
+                    result.recommended_level, result.frame_times
+                ));
+
+                if let Some(path) = self
+                    .quality_calibrator
+                    .take()
+                    .and_then(|calibrator| calibrator.save_path)
+                {
+                    if let Err(e) = result.recommended.save_to_file(&path) {
+                        Log::err(format!(
+                            "Unable to save quality settings to {}. Reason: {e}",
+                            path.display()
+                        ));
+                    }
+                }
+
+                self.quality_calibration_result = Some(result);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets new color blindness filter, that will be applied to every rendered scene. `None` disables
+    /// the filter. Unlike quality settings, the filter can be changed every frame without any
+    /// performance penalty.
+    pub fn set_color_blindness_filter(&mut self, filter: Option<ColorBlindnessFilter>) {
+        self.color_blindness_filter = filter;
+    }
+
+    /// Returns current color blindness filter.
+    pub fn color_blindness_filter(&self) -> Option<ColorBlindnessFilter> {
+        self.color_blindness_filter
+    }
+
     /// Removes all cached GPU data, forces renderer to re-upload data to GPU.
     /// Do not call this method until you absolutely need! It may cause **significant**
     /// performance lag!
@@ -1316,6 +1670,7 @@ impl Renderer {
             }
         }
 
+        self.texture_cache.update_streaming();
         self.texture_cache.update(dt);
     }
 
@@ -1333,6 +1688,44 @@ impl Renderer {
         self.shader_cache.update(dt)
     }
 
+    fn update_geometry_cache(&mut self, dt: f32) {
+        // Maximum amount of surfaces uploaded to GPU per frame. Geometry of loaded models is
+        // uploaded in batches ahead of time, instead of uploading everything at once on the first
+        // frame when a large scene becomes visible.
+        const THROUGHPUT: usize = 32;
+
+        while let Ok(event) = self.model_event_receiver.try_recv() {
+            if let ResourceEvent::Loaded(resource) | ResourceEvent::Reloaded(resource) = event {
+                if let Some(model) = resource.try_cast::<Model>() {
+                    if !model.is_ok() {
+                        continue;
+                    }
+                    let model = model.data_ref();
+                    for node in model.get_scene().graph.linear_iter() {
+                        if let Some(mesh) = node.cast::<Mesh>() {
+                            self.pending_geometry
+                                .extend(mesh.surfaces().iter().map(|surface| surface.data()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut uploaded = 0;
+        while uploaded < THROUGHPUT {
+            let Some(surface) = self.pending_geometry.pop_front() else {
+                break;
+            };
+            if surface.is_ok() {
+                self.geometry_cache
+                    .get(&*self.server, &surface, TimeToLive::default());
+                uploaded += 1;
+            }
+        }
+
+        self.geometry_cache.update(dt);
+    }
+
     /// Update caches - this will remove timed out resources.
     ///
     /// Normally, this is called from `Engine::update()`.
@@ -1340,7 +1733,7 @@ impl Renderer {
     pub fn update_caches(&mut self, dt: f32) {
         self.update_texture_cache(dt);
         self.update_shader_cache(dt);
-        self.geometry_cache.update(dt);
+        self.update_geometry_cache(dt);
     }
 
     /// Unconditionally renders a scene and returns a reference to a [`AssociatedSceneData`] instance
@@ -1410,6 +1803,7 @@ impl Renderer {
             });
 
         let pipeline_stats = server.pipeline_statistics();
+        let scene_start_time = instant::Instant::now();
         scene_associated_data.statistics = Default::default();
 
         // If we specified a texture to draw to, we have to register it in texture cache
@@ -1420,19 +1814,218 @@ impl Renderer {
                 .try_register(&rt, scene_associated_data.ldr_scene_frame_texture());
         }
 
-        for (camera_handle, camera) in graph.pair_iter().filter_map(|(handle, node)| {
-            if node.is_globally_enabled() {
-                if let Some(camera) = node.cast::<Camera>() {
-                    if camera.is_enabled() {
-                        return Some((handle, camera));
+        let mut cameras = graph
+            .pair_iter()
+            .filter_map(|(handle, node)| {
+                if node.is_globally_enabled() {
+                    if let Some(camera) = node.cast::<Camera>() {
+                        if camera.is_enabled() {
+                            return Some((handle, camera));
+                        }
                     }
                 }
+                None
+            })
+            .collect::<Vec<_>>();
+
+        // Cameras with render targets must be rendered first, so their results could be used by
+        // other cameras in the same frame. Cameras with partial viewports (split screen,
+        // picture-in-picture) must be rendered after the cameras that cover the entire frame.
+        cameras.sort_by_key(|(_, camera)| {
+            (
+                camera.render_target().is_none(),
+                camera.render_target().is_none() && !has_full_viewport(camera),
+            )
+        });
+
+        // Reflections of mirrors are rendered by temporary cameras with render targets, before any
+        // other camera, so they could be used in the main pass.
+        let reflection_cameras = cameras
+            .iter()
+            .find(|(_, camera)| camera.render_target().is_none())
+            .map(|(_, camera)| {
+                graph
+                    .pair_iter()
+                    .filter_map(|(handle, node)| {
+                        node.cast::<Mirror>()?
+                            .make_reflection_camera(camera)
+                            .map(|reflection_camera| (handle, reflection_camera))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        // Reflection probes are captured by six temporary cameras (one per face of the cube map)
+        // before any other camera, so the cube maps could be used in the same frame.
+        let mut probe_cameras = Vec::new();
+        let mut reflection_probes = Vec::new();
+        if let Some((_, main_camera)) = cameras
+            .iter()
+            .find(|(_, camera)| camera.render_target().is_none())
+        {
+            for (handle, node) in graph.pair_iter() {
+                let Some(probe) = node.cast::<ReflectionProbe>() else {
+                    continue;
+                };
+                if !probe.is_globally_enabled() {
+                    continue;
+                }
+
+                let resolution = probe.resolution() as usize;
+                let (cube_map, is_new) =
+                    match self.reflection_probe_map.entry((scene_handle, handle)) {
+                        Entry::Occupied(entry) => {
+                            let cube_map = entry.into_mut();
+                            let is_new = cube_map.resolution != resolution;
+                            if is_new {
+                                *cube_map = ReflectionProbeCubeMap::new(server, resolution)?;
+                            }
+                            (cube_map, is_new)
+                        }
+                        Entry::Vacant(entry) => (
+                            entry.insert(ReflectionProbeCubeMap::new(server, resolution)?),
+                            true,
+                        ),
+                    };
+
+                if probe.take_update_request() || is_new {
+                    probe_cameras.extend(
+                        probe
+                            .make_capture_cameras(main_camera)
+                            .into_iter()
+                            .zip(CUBE_MAP_FACES)
+                            .map(|(camera, face)| (handle, camera, face)),
+                    );
+                }
+
+                reflection_probes.extend(ReflectionProbeRenderData::new(probe, cube_map));
             }
-            None
-        }) {
+        }
+        // Smaller probes must be drawn on top of the larger ones.
+        reflection_probes.sort_by(|a, b| {
+            b.world_bounding_box
+                .volume()
+                .total_cmp(&a.world_bounding_box.volume())
+        });
+
+        let cameras = probe_cameras
+            .iter()
+            .map(|(handle, camera, face)| (*handle, camera, CameraPurpose::ProbeFace(*face)))
+            .chain(
+                reflection_cameras
+                    .iter()
+                    .map(|(handle, camera)| (*handle, camera, CameraPurpose::Reflection)),
+            )
+            .chain(
+                cameras
+                    .into_iter()
+                    .map(|(handle, camera)| (handle, camera, CameraPurpose::Main)),
+            )
+            .collect::<Vec<_>>();
+
+        let clear_color = scene
+            .rendering_options
+            .clear_color
+            .unwrap_or(self.backbuffer_clear_color);
+
+        // Cameras could cover only a part of the frame, so the rest must be cleared.
+        scene_associated_data.ldr_scene_framebuffer.clear(
+            Rect::new(0, 0, frame_size.x as i32, frame_size.y as i32),
+            Some(clear_color),
+            None,
+            None,
+        );
+
+        // Debug geometry is the same for every camera, so it is enough to upload it once.
+        self.debug_renderer.set_lines(&scene.drawing_context.lines);
+
+        for (camera_handle, camera, purpose) in cameras {
             let visibility_cache = self.visibility_cache.get_or_register(graph, camera_handle);
 
-            let viewport = camera.viewport_pixels(frame_size);
+            // Reflections are rendered from a point of view that differs from the observer, so
+            // the visibility cache cannot be used for them. SSAO is disabled because oblique
+            // clipping breaks depth linearization.
+            let is_reflection = purpose != CameraPurpose::Main;
+            let quality_settings = if is_reflection {
+                QualitySettings {
+                    use_ssao: false,
+                    use_occlusion_culling: false,
+                    ..self.quality_settings
+                }
+            } else {
+                self.quality_settings
+            };
+            let scene_frame_data = &mut *scene_associated_data;
+
+            // Cameras with render targets or partial viewports have their own associated data with
+            // the size of the render target or the viewport respectively. Such cameras render the
+            // scene in the entire frame of their associated data. Results of the cameras with
+            // partial viewports are then copied in the scene frame.
+            let (camera_data_size, composition_viewport) = match (purpose, camera.render_target()) {
+                (CameraPurpose::ProbeFace(_), _) => {
+                    let resolution =
+                        self.reflection_probe_map[&(scene_handle, camera_handle)].resolution;
+                    (Some((resolution, resolution)), None)
+                }
+                (_, Some(render_target)) => {
+                    let TextureKind::Rectangle { width, height } = render_target.data_ref().kind()
+                    else {
+                        Log::err("Only rectangle textures can be used as camera render target!");
+                        continue;
+                    };
+                    (Some((width.max(1) as usize, height.max(1) as usize)), None)
+                }
+                (_, None) if !has_full_viewport(camera) => {
+                    let viewport = camera.viewport_pixels(frame_size);
+                    (
+                        Some((viewport.w() as usize, viewport.h() as usize)),
+                        Some(viewport),
+                    )
+                }
+                (_, None) => (None, None),
+            };
+
+            let scene_associated_data = match camera_data_size {
+                Some((width, height)) => {
+                    let camera_data =
+                        match self.camera_data_map.entry((scene_handle, camera_handle)) {
+                            Entry::Occupied(entry) => {
+                                let data = entry.into_mut();
+                                if data.gbuffer.width != width as i32
+                                    || data.gbuffer.height != height as i32
+                                {
+                                    *data = AssociatedSceneData::new(server, width, height)?;
+                                }
+                                data
+                            }
+                            Entry::Vacant(entry) => {
+                                entry.insert(AssociatedSceneData::new(server, width, height)?)
+                            }
+                        };
+                    camera_data.statistics = Default::default();
+
+                    if let Some(render_target) = camera.render_target() {
+                        // Mirrors are drawn in the main HDR pass, so they use the reflection before
+                        // tone mapping.
+                        let texture = if is_reflection {
+                            camera_data.hdr_scene_frame_texture()
+                        } else {
+                            camera_data.ldr_scene_frame_texture()
+                        };
+                        self.texture_cache.try_register(render_target, texture);
+                    }
+
+                    camera_data
+                }
+                None => &mut *scene_frame_data,
+            };
+
+            let viewport = Rect::new(
+                0,
+                0,
+                scene_associated_data.gbuffer.width,
+                scene_associated_data.gbuffer.height,
+            );
 
             let bundle_storage = RenderDataBundleStorage::from_graph(
                 graph,
@@ -1446,14 +2039,29 @@ impl Renderer {
                 GBUFFER_PASS_NAME.clone(),
                 RenderDataBundleStorageOptions {
                     collect_lights: true,
+                    use_gpu_skinning: quality_settings.use_gpu_skinning,
                 },
             );
 
+            scene_associated_data.statistics += bundle_storage.culling;
+
+            self.texture_cache.request_bundle_mips(
+                &bundle_storage.bundles,
+                graph,
+                &bundle_storage.observer_info,
+                viewport.h() as f32,
+            );
+
             server.set_polygon_fill_mode(
                 PolygonFace::FrontAndBack,
                 scene.rendering_options.polygon_rasterization_mode,
             );
 
+            self.pass_profiler.begin_pass(
+                server,
+                "GBuffer",
+                scene_associated_data.statistics.geometry,
+            );
             scene_associated_data.statistics +=
                 scene_associated_data.gbuffer.fill(GBufferRenderContext {
                     server,
@@ -1462,7 +2070,7 @@ impl Renderer {
                     bundle_storage: &bundle_storage,
                     texture_cache: &mut self.texture_cache,
                     shader_cache: &mut self.shader_cache,
-                    quality_settings: &self.quality_settings,
+                    quality_settings: &quality_settings,
                     fallback_resources: &self.fallback_resources,
                     graph,
                     uniform_buffer_cache: &mut self.uniform_buffer_cache,
@@ -1471,22 +2079,25 @@ impl Renderer {
                     unit_quad: &*self.quad,
                 })?;
 
+            self.pass_profiler
+                .end_pass(server, scene_associated_data.statistics.geometry);
+
             server.set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
             scene_associated_data.copy_depth_stencil_to_scene_framebuffer();
 
             scene_associated_data.hdr_scene_framebuffer.clear(
                 viewport,
-                Some(
-                    scene
-                        .rendering_options
-                        .clear_color
-                        .unwrap_or(self.backbuffer_clear_color),
-                ),
+                Some(clear_color),
                 None, // Keep depth, we've just copied valid data in it.
                 Some(0),
             );
 
+            self.pass_profiler.begin_pass(
+                server,
+                "Lighting",
+                scene_associated_data.statistics.geometry,
+            );
             let (pass_stats, light_stats) =
                 self.deferred_light_renderer
                     .render(DeferredRendererContext {
@@ -1496,7 +2107,7 @@ impl Renderer {
                         gbuffer: &mut scene_associated_data.gbuffer,
                         ambient_color: scene.rendering_options.ambient_lighting_color,
                         render_data_bundle: &bundle_storage,
-                        settings: &self.quality_settings,
+                        settings: &quality_settings,
                         textures: &mut self.texture_cache,
                         geometry_cache: &mut self.geometry_cache,
                         frame_buffer: &mut *scene_associated_data.hdr_scene_framebuffer,
@@ -1505,13 +2116,28 @@ impl Renderer {
                         uniform_buffer_cache: &mut self.uniform_buffer_cache,
                         visibility_cache,
                         uniform_memory_allocator: &mut self.uniform_memory_allocator,
+                        // Probes do not see each other to avoid feedback loops.
+                        reflection_probes: if matches!(purpose, CameraPurpose::ProbeFace(_)) {
+                            &[]
+                        } else {
+                            &reflection_probes
+                        },
                     })?;
 
             scene_associated_data.statistics += light_stats;
             scene_associated_data.statistics += pass_stats;
 
+            self.pass_profiler
+                .end_pass(server, scene_associated_data.statistics.geometry);
+
             let depth = scene_associated_data.gbuffer.depth();
 
+            self.pass_profiler.begin_pass(
+                server,
+                "Forward",
+                scene_associated_data.statistics.geometry,
+            );
+
             scene_associated_data.statistics +=
                 self.forward_renderer.render(ForwardRenderContext {
                     state: server,
@@ -1521,7 +2147,7 @@ impl Renderer {
                     bundle_storage: &bundle_storage,
                     framebuffer: &mut *scene_associated_data.hdr_scene_framebuffer,
                     viewport,
-                    quality_settings: &self.quality_settings,
+                    quality_settings: &quality_settings,
                     fallback_resources: &self.fallback_resources,
                     scene_depth: depth,
                     ambient_light: scene.rendering_options.ambient_lighting_color,
@@ -1537,7 +2163,7 @@ impl Renderer {
                             texture_cache: &mut self.texture_cache,
                             geometry_cache: &mut self.geometry_cache,
                             shader_cache: &mut self.shader_cache,
-                            quality_settings: &self.quality_settings,
+                            quality_settings: &quality_settings,
                             bundle_storage: &bundle_storage,
                             viewport,
                             scene,
@@ -1554,20 +2180,58 @@ impl Renderer {
                         })?;
             }
 
+            self.pass_profiler
+                .end_pass(server, scene_associated_data.statistics.geometry);
+
+            // Faces of reflection probes are copied in the cube map before tone mapping, the
+            // rest of the pipeline is not needed for them.
+            if let CameraPurpose::ProbeFace(face) = purpose {
+                let cube_map = self
+                    .reflection_probe_map
+                    .get_mut(&(scene_handle, camera_handle))
+                    .unwrap();
+                cube_map.framebuffer.set_cubemap_face(0, face);
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &mut *cube_map.framebuffer,
+                    scene_associated_data.hdr_scene_frame_texture(),
+                    &self.flat_shader,
+                    viewport,
+                    &*self.quad,
+                )?;
+                if face == CubeMapFace::NegativeZ {
+                    cube_map.texture().borrow_mut().generate_mipmap();
+                }
+                self.statistics += scene_associated_data.statistics;
+                continue;
+            }
+
             let quad = &self.quad;
+            let post_processing = camera.post_processing();
+
+            self.pass_profiler.begin_pass(
+                server,
+                "PostEffects",
+                scene_associated_data.statistics.geometry,
+            );
 
             // Prepare glow map.
-            scene_associated_data.statistics += scene_associated_data.bloom_renderer.render(
-                &**quad,
-                scene_associated_data.hdr_scene_frame_texture(),
-                &mut self.uniform_buffer_cache,
-            )?;
+            let bloom_texture = if post_processing.bloom {
+                scene_associated_data.statistics += scene_associated_data.bloom_renderer.render(
+                    &**quad,
+                    scene_associated_data.hdr_scene_frame_texture(),
+                    &mut self.uniform_buffer_cache,
+                )?;
+                scene_associated_data.bloom_renderer.result()
+            } else {
+                self.fallback_resources.black_dummy.clone()
+            };
 
             // Convert high dynamic range frame to low dynamic range (sRGB) with tone mapping and gamma correction.
             scene_associated_data.statistics += scene_associated_data.hdr_renderer.render(
                 server,
                 scene_associated_data.hdr_scene_frame_texture(),
-                scene_associated_data.bloom_renderer.result(),
+                bloom_texture,
                 &mut *scene_associated_data.ldr_scene_framebuffer,
                 viewport,
                 &**quad,
@@ -1575,12 +2239,83 @@ impl Renderer {
                 camera.exposure(),
                 camera.color_grading_lut_ref(),
                 camera.color_grading_enabled(),
+                post_processing.tone_mapping,
                 &mut self.texture_cache,
                 &mut self.uniform_buffer_cache,
             )?;
 
+            // Apply vignette and user-defined post effects. Each effect reads current frame and
+            // writes the result into the temporary frame buffer, which is then copied back.
+            let vignette = post_processing.vignette;
+            if vignette.enabled {
+                self.vignette_effect.set_parameter(
+                    0,
+                    Vector4::new(
+                        vignette.intensity,
+                        vignette.radius,
+                        vignette.smoothness,
+                        0.0,
+                    ),
+                );
+            }
+            let posterization = post_processing.posterization;
+            if posterization.enabled {
+                self.posterization_effect
+                    .set_parameter(0, Vector4::new(posterization.levels as f32, 0.0, 0.0, 0.0));
+            }
+            let effects = vignette
+                .enabled
+                .then_some(&mut self.vignette_effect as &mut dyn PostEffect)
+                .into_iter()
+                .chain(
+                    posterization
+                        .enabled
+                        .then_some(&mut self.posterization_effect as &mut dyn PostEffect),
+                );
+            let mut custom_effects = self
+                .post_effects
+                .iter()
+                .map(|e| e.borrow_mut())
+                .filter(|e| e.is_enabled() && post_processing.is_effect_enabled(e.name()))
+                .collect::<Vec<_>>();
+            for effect in effects.chain(custom_effects.iter_mut().map(|e| &mut **e)) {
+                let result = effect.render(PostEffectContext {
+                    server,
+                    scene,
+                    camera,
+                    frame_texture: scene_associated_data.ldr_scene_frame_texture(),
+                    depth_texture: scene_associated_data.gbuffer.depth(),
+                    framebuffer: &mut *scene_associated_data.ldr_temp_framebuffer,
+                    viewport,
+                    quad: &*self.quad,
+                    uniform_buffer_cache: &mut self.uniform_buffer_cache,
+                });
+
+                match result {
+                    Ok(statistics) => {
+                        scene_associated_data.statistics += statistics;
+
+                        let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                        scene_associated_data.statistics += blit_pixels(
+                            &mut self.uniform_buffer_cache,
+                            &mut *scene_associated_data.ldr_scene_framebuffer,
+                            temp_frame_texture,
+                            &self.flat_shader,
+                            viewport,
+                            &*self.quad,
+                        )?;
+                    }
+                    Err(err) => Log::err(format!(
+                        "Unable to apply {} post effect. Reason: {:?}",
+                        effect.name(),
+                        err
+                    )),
+                }
+            }
+            drop(custom_effects);
+
             // Apply FXAA if needed.
-            if self.quality_settings.fxaa {
+            if self.quality_settings.fxaa && post_processing.fxaa {
                 scene_associated_data.statistics += self.fxaa_renderer.render(
                     viewport,
                     scene_associated_data.ldr_scene_frame_texture(),
@@ -1600,8 +2335,32 @@ impl Renderer {
                 )?;
             }
 
+            // Apply color blindness filter if needed.
+            if let Some(filter) = self.color_blindness_filter.as_ref() {
+                scene_associated_data.statistics += self.color_blindness_renderer.render(
+                    filter,
+                    viewport,
+                    scene_associated_data.ldr_scene_frame_texture(),
+                    &mut *scene_associated_data.ldr_temp_framebuffer,
+                    &mut self.uniform_buffer_cache,
+                )?;
+
+                let quad = &self.quad;
+                let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                scene_associated_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &mut *scene_associated_data.ldr_scene_framebuffer,
+                    temp_frame_texture,
+                    &self.flat_shader,
+                    viewport,
+                    &**quad,
+                )?;
+            }
+
+            self.pass_profiler
+                .end_pass(server, scene_associated_data.statistics.geometry);
+
             // Render debug geometry in the LDR frame buffer.
-            self.debug_renderer.set_lines(&scene.drawing_context.lines);
             scene_associated_data.statistics += self.debug_renderer.render(
                 &mut self.uniform_buffer_cache,
                 viewport,
@@ -1618,7 +2377,7 @@ impl Renderer {
                             texture_cache: &mut self.texture_cache,
                             geometry_cache: &mut self.geometry_cache,
                             shader_cache: &mut self.shader_cache,
-                            quality_settings: &self.quality_settings,
+                            quality_settings: &quality_settings,
                             bundle_storage: &bundle_storage,
                             viewport,
                             scene,
@@ -1634,8 +2393,40 @@ impl Renderer {
                             uniform_memory_allocator: &mut self.uniform_memory_allocator,
                         })?;
             }
+
+            if camera_data_size.is_some() {
+                self.statistics += scene_associated_data.statistics;
+            }
+
+            if let Some(composition_viewport) = composition_viewport {
+                let camera_frame_texture =
+                    self.camera_data_map[&(scene_handle, camera_handle)].ldr_scene_frame_texture();
+                scene_frame_data.statistics += blit_pixels(
+                    &mut self.uniform_buffer_cache,
+                    &mut *scene_frame_data.ldr_scene_framebuffer,
+                    camera_frame_texture,
+                    &self.flat_shader,
+                    composition_viewport,
+                    &*self.quad,
+                )?;
+            }
         }
 
+        // Drop associated data of cameras, that were destroyed or do not need it anymore.
+        self.camera_data_map.retain(|(scene, camera), _| {
+            *scene != scene_handle
+                || graph
+                    .try_get_of_type::<Camera>(*camera)
+                    .is_some_and(|camera| {
+                        camera.render_target().is_some() || !has_full_viewport(camera)
+                    })
+                || graph.try_get_of_type::<Mirror>(*camera).is_some()
+                || graph.try_get_of_type::<ReflectionProbe>(*camera).is_some()
+        });
+        self.reflection_probe_map.retain(|(scene, probe), _| {
+            *scene != scene_handle || graph.try_get_of_type::<ReflectionProbe>(*probe).is_some()
+        });
+
         self.visibility_cache.update(graph);
 
         // Optionally render everything into back buffer.
@@ -1654,6 +2445,13 @@ impl Renderer {
         self.statistics += scene_associated_data.statistics;
         scene_associated_data.statistics.pipeline = server.pipeline_statistics() - pipeline_stats;
 
+        self.statistics.scenes.push(SceneStatisticsBreakdown {
+            scene: scene_handle,
+            statistics: scene_associated_data.statistics,
+            passes: self.pass_profiler.take_passes(),
+            time: scene_start_time.elapsed(),
+        });
+
         Ok(scene_associated_data)
     }
 
@@ -1672,6 +2470,10 @@ impl Renderer {
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
             .retain(|h, _| scenes.is_valid_handle(*h));
+        self.camera_data_map
+            .retain(|(h, _), _| scenes.is_valid_handle(*h));
+        self.reflection_probe_map
+            .retain(|(h, _), _| scenes.is_valid_handle(*h));
 
         // We have to invalidate resource bindings cache because some textures or programs,
         // or other GL resources can be destroyed and then on their "names" some new resource
@@ -1692,6 +2494,12 @@ impl Renderer {
         let backbuffer_width = self.frame_size.0 as f32;
         let backbuffer_height = self.frame_size.1 as f32;
 
+        if let Some(calibrator) = self.quality_calibrator.as_mut() {
+            calibrator.begin_frame(&*self.server);
+        }
+        self.pass_profiler
+            .begin_frame(&*self.server, self.quality_calibrator.is_none());
+
         for (scene_handle, scene) in scenes.pair_iter().filter(|(_, s)| *s.enabled) {
             self.render_scene(scene_handle, scene, dt)?;
         }
@@ -1700,6 +2508,8 @@ impl Renderer {
             .set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
         // Render UI on top of everything without gamma correction.
+        self.pass_profiler
+            .begin_pass(&*self.server, "UI", self.statistics.geometry);
         for drawing_context in drawing_contexts {
             self.statistics += self.ui_renderer.render(UiRenderContext {
                 server: &*self.server,
@@ -1714,6 +2524,9 @@ impl Renderer {
                 flat_shader: &self.flat_shader,
             })?;
         }
+        self.pass_profiler
+            .end_pass(&*self.server, self.statistics.geometry);
+        self.statistics.passes = self.pass_profiler.take_passes();
 
         let screen_matrix =
             Matrix4::new_orthographic(0.0, backbuffer_width, backbuffer_height, 0.0, -1.0, 1.0);
@@ -1724,6 +2537,13 @@ impl Renderer {
             screen_matrix,
         )?;
 
+        self.pass_profiler.end_frame(&*self.server);
+
+        if let Some(calibrator) = self.quality_calibrator.as_mut() {
+            let step = calibrator.end_frame(&*self.server);
+            self.update_quality_calibration(step)?;
+        }
+
         self.statistics.geometry_cache_size = self.geometry_cache.alive_count();
         self.statistics.texture_cache_size = self.texture_cache.alive_count();
         self.statistics.shader_cache_size = self.shader_cache.alive_count();
@@ -0,0 +1,324 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Post-processing effects, that are applied to the final (low dynamic range) frame of a camera.
+//! See [`PostEffect`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector4},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            error::FrameworkError,
+            framebuffer::{BufferLocation, FrameBuffer, ResourceBindGroup, ResourceBinding},
+            geometry_buffer::GeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange,
+        },
+        make_viewport_matrix, RenderPassStatistics,
+    },
+    scene::{camera::Camera, Scene},
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A set of data, that is available for a post effect during rendering.
+pub struct PostEffectContext<'a> {
+    /// A graphics server that is used as a wrapper to underlying graphics API.
+    pub server: &'a dyn GraphicsServer,
+    /// A scene being rendered.
+    pub scene: &'a Scene,
+    /// A camera, that is used to render the scene.
+    pub camera: &'a Camera,
+    /// Current frame (low dynamic range, sRGB) of the camera. Post effect should read it and
+    /// write the result to [`Self::framebuffer`].
+    pub frame_texture: Rc<RefCell<dyn GpuTexture>>,
+    /// Depth buffer of the scene.
+    pub depth_texture: Rc<RefCell<dyn GpuTexture>>,
+    /// A frame buffer, that should receive the result of the post effect.
+    pub framebuffer: &'a mut dyn FrameBuffer,
+    /// Viewport of the camera.
+    pub viewport: Rect<i32>,
+    /// A unit quad, that could be used to render fullscreen passes.
+    pub quad: &'a dyn GeometryBuffer,
+    /// A cache of uniform buffers.
+    pub uniform_buffer_cache: &'a mut UniformBufferCache,
+}
+
+/// Post effect is a fullscreen pass, that is applied to the final frame of a camera. Post effects
+/// are executed in the order they were added to the renderer (see [`crate::renderer::Renderer::add_post_effect`])
+/// after tone mapping, color grading, vignette and posterization, but before FXAA. Every effect can be disabled
+/// per camera, using its name (see [`crate::scene::camera::PostProcessingSettings::disabled_effects`]).
+///
+/// In most cases there's no need to implement this trait manually, use [`ShaderPostEffect`] which
+/// allows you to write a post effect using a GLSL fragment shader.
+pub trait PostEffect {
+    /// Returns a name of the post effect, it is used to enable or disable the effect per camera.
+    fn name(&self) -> &str;
+
+    /// Returns `true` if the post effect should be applied, `false` - otherwise. Disabled effects are
+    /// skipped for every camera.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Renders the post effect. The implementation must write every pixel of the viewport of
+    /// [`PostEffectContext::framebuffer`]. If an error is returned, the result of the effect is
+    /// discarded and the error is written to the log.
+    fn render(&mut self, ctx: PostEffectContext) -> Result<RenderPassStatistics, FrameworkError>;
+}
+
+struct ShaderPostEffectProgram {
+    program: Box<dyn GpuProgram>,
+    frame_texture: UniformLocation,
+    depth_texture: Option<UniformLocation>,
+    uniform_buffer_binding: usize,
+}
+
+/// Maximum amount of parameters of [`ShaderPostEffect`].
+pub const MAX_POST_EFFECT_PARAMETERS: usize = 8;
+
+/// A post effect, that uses a GLSL fragment shader to process the frame. The shader has access to
+/// the following inputs:
+///
+/// - `in vec2 texCoord` - texture coordinates of the current pixel.
+/// - `uniform sampler2D frameTexture` - current frame of the camera.
+/// - `uniform sampler2D depthTexture` - depth buffer of the scene.
+/// - `vec4 parameters[8]` - a set of user-defined parameters, see [`Self::set_parameter`].
+/// - `vec2 frameSize` - size of the frame in pixels.
+///
+/// Result of the shader should be written to `FragColor` output. All standard shader functions
+/// (`S_LinearToSRGB`, `S_SRGBToLinear`, etc.) are available.
+///
+/// ## Example
+///
+/// The following example creates a post effect that converts the frame to grayscale.
+///
+/// ```rust
+/// # use fyrox_impl::renderer::post_effect::ShaderPostEffect;
+/// fn create_grayscale_effect() -> ShaderPostEffect {
+///     ShaderPostEffect::new(
+///         "Grayscale",
+///         r#"
+///         void main()
+///         {
+///             vec4 color = texture(frameTexture, texCoord);
+///             float luminance = dot(color.rgb, vec3(0.299, 0.587, 0.114));
+///             FragColor = vec4(mix(color.rgb, vec3(luminance), parameters[0].x), color.a);
+///         }
+///         "#,
+///     )
+///     .with_parameter(0, [1.0, 0.0, 0.0, 0.0].into())
+/// }
+/// ```
+pub struct ShaderPostEffect {
+    name: String,
+    fragment_source: String,
+    parameters: [Vector4<f32>; MAX_POST_EFFECT_PARAMETERS],
+    program: Option<ShaderPostEffectProgram>,
+    // The effect is disabled if its shader has failed to compile, so the error will be reported
+    // only once.
+    failed: bool,
+}
+
+const SHADER_POST_EFFECT_HEADER: &str = r#"
+uniform sampler2D frameTexture;
+uniform sampler2D depthTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    vec4 parameters[8];
+    vec2 frameSize;
+};
+
+in vec2 texCoord;
+out vec4 FragColor;
+"#;
+
+impl ShaderPostEffect {
+    /// Creates new post effect using the given name and the source code of a fragment shader. The
+    /// shader will be compiled on first use. See the type docs for more info about the shader inputs.
+    pub fn new<N: AsRef<str>, S: AsRef<str>>(name: N, fragment_source: S) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            fragment_source: fragment_source.as_ref().to_owned(),
+            parameters: Default::default(),
+            program: None,
+            failed: false,
+        }
+    }
+
+    /// Sets the value of a parameter with the given index. The index must be in
+    /// `0..MAX_POST_EFFECT_PARAMETERS` range, otherwise the value will be ignored.
+    pub fn set_parameter(&mut self, index: usize, value: Vector4<f32>) {
+        if let Some(parameter) = self.parameters.get_mut(index) {
+            *parameter = value;
+        }
+    }
+
+    /// Sets the value of a parameter with the given index in builder manner. See [`Self::set_parameter`]
+    /// for more info.
+    pub fn with_parameter(mut self, index: usize, value: Vector4<f32>) -> Self {
+        self.set_parameter(index, value);
+        self
+    }
+
+    /// Returns the value of a parameter with the given index.
+    pub fn parameter(&self, index: usize) -> Option<Vector4<f32>> {
+        self.parameters.get(index).cloned()
+    }
+
+    fn compile(
+        &self,
+        server: &dyn GraphicsServer,
+    ) -> Result<ShaderPostEffectProgram, FrameworkError> {
+        let vertex_source = include_str!("shaders/post_effect_vs.glsl");
+        let fragment_source = format!("{SHADER_POST_EFFECT_HEADER}{}", self.fragment_source);
+        let program = server.create_program(&self.name, vertex_source, &fragment_source)?;
+        Ok(ShaderPostEffectProgram {
+            frame_texture: program.uniform_location(&ImmutableString::new("frameTexture"))?,
+            // Depth texture could be optimized out by the shader compiler, if it is not used.
+            depth_texture: program
+                .uniform_location(&ImmutableString::new("depthTexture"))
+                .ok(),
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+impl PostEffect for ShaderPostEffect {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.failed
+    }
+
+    fn render(&mut self, ctx: PostEffectContext) -> Result<RenderPassStatistics, FrameworkError> {
+        if self.program.is_none() {
+            match self.compile(ctx.server) {
+                Ok(program) => self.program = Some(program),
+                Err(err) => {
+                    self.failed = true;
+                    return Err(err);
+                }
+            }
+        }
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let Some(program) = self.program.as_ref() else {
+            return Ok(statistics);
+        };
+
+        let uniform_buffer = ctx.uniform_buffer_cache.write(
+            StaticUniformBuffer::<512>::new()
+                .with(&make_viewport_matrix(ctx.viewport))
+                .with_slice(&self.parameters)
+                .with(&Vector2::new(
+                    ctx.viewport.w() as f32,
+                    ctx.viewport.h() as f32,
+                )),
+        )?;
+
+        let mut bindings = vec![
+            ResourceBinding::texture(&ctx.frame_texture, &program.frame_texture),
+            ResourceBinding::Buffer {
+                buffer: uniform_buffer,
+                binding: BufferLocation::Auto {
+                    shader_location: program.uniform_buffer_binding,
+                },
+                data_usage: Default::default(),
+            },
+        ];
+        if let Some(depth_texture) = program.depth_texture.as_ref() {
+            bindings.push(ResourceBinding::texture(&ctx.depth_texture, depth_texture));
+        }
+
+        statistics += ctx.framebuffer.draw(
+            ctx.quad,
+            ctx.viewport,
+            &*program.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: None,
+                blend: None,
+                stencil_op: Default::default(),
+                scissor_box: None,
+            },
+            &[ResourceBindGroup {
+                bindings: &bindings,
+            }],
+            ElementRange::Full,
+        )?;
+
+        Ok(statistics)
+    }
+}
+
+/// Creates a built-in vignette effect. Its parameters are taken from
+/// [`crate::scene::camera::Vignette`] settings of a camera.
+pub(crate) fn make_vignette_effect() -> ShaderPostEffect {
+    ShaderPostEffect::new(
+        "Vignette",
+        r#"
+        void main()
+        {
+            vec4 color = texture(frameTexture, texCoord);
+            float intensity = parameters[0].x;
+            float radius = parameters[0].y;
+            float smoothness = max(parameters[0].z, 0.0001);
+            vec2 offset = texCoord - vec2(0.5);
+            // Keep the vignette round regardless of the aspect ratio of the frame.
+            offset.x *= frameSize.x / max(frameSize.y, 1.0);
+            float vignette = smoothstep(radius, radius - smoothness, length(offset));
+            FragColor = vec4(color.rgb * mix(1.0 - intensity, 1.0, vignette), color.a);
+        }
+        "#,
+    )
+}
+
+/// Creates a built-in posterization effect. Its parameters are taken from
+/// [`crate::scene::camera::Posterization`] settings of a camera.
+pub(crate) fn make_posterization_effect() -> ShaderPostEffect {
+    ShaderPostEffect::new(
+        "Posterization",
+        r#"
+        void main()
+        {
+            vec4 color = texture(frameTexture, texCoord);
+            float steps = max(parameters[0].x - 1.0, 1.0);
+            FragColor = vec4(floor(color.rgb * steps + 0.5) / steps, color.a);
+        }
+        "#,
+    )
+}
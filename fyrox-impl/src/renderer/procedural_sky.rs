@@ -0,0 +1,218 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        cache::uniform::UniformBufferCache,
+        framework::{
+            error::FrameworkError,
+            framebuffer::{BufferLocation, FrameBuffer, ResourceBindGroup, ResourceBinding},
+            geometry_buffer::GeometryBuffer,
+            gpu_program::GpuProgram,
+            gpu_texture::{CubeMapFace, GpuTexture},
+            server::GraphicsServer,
+            uniform::StaticUniformBuffer,
+            DrawParameters, ElementRange,
+        },
+        light::reflection_probe::ReflectionProbeCubeMap,
+        RenderPassStatistics,
+    },
+    scene::camera::ProceduralSky,
+};
+use std::{cell::RefCell, rc::Rc};
+
+// Size of a face of the environment map. The sky has no high-frequency details (except the sun
+// disc), so low resolution is enough.
+const ENVIRONMENT_MAP_SIZE: usize = 64;
+
+pub struct ProceduralSkyShader {
+    pub program: Box<dyn GpuProgram>,
+    pub uniform_buffer_binding: usize,
+}
+
+impl ProceduralSkyShader {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/procedural_sky_fs.glsl");
+        let vertex_source = include_str!("shaders/procedural_sky_vs.glsl");
+
+        let program =
+            server.create_program("ProceduralSkyShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            uniform_buffer_binding: program
+                .uniform_block_index(&ImmutableString::new("Uniforms"))?,
+            program,
+        })
+    }
+}
+
+fn draw_sky(
+    shader: &ProceduralSkyShader,
+    sky: &ProceduralSky,
+    cube: &dyn GeometryBuffer,
+    wvp: Matrix4<f32>,
+    viewport: Rect<i32>,
+    frame_buffer: &mut dyn FrameBuffer,
+    uniform_buffer_cache: &mut UniformBufferCache,
+) -> Result<RenderPassStatistics, FrameworkError> {
+    let mut stats = RenderPassStatistics::default();
+    stats += frame_buffer.draw(
+        cube,
+        viewport,
+        &*shader.program,
+        &DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: None,
+            blend: None,
+            stencil_op: Default::default(),
+            scissor_box: None,
+        },
+        &[ResourceBindGroup {
+            bindings: &[ResourceBinding::Buffer {
+                buffer: uniform_buffer_cache.write(
+                    StaticUniformBuffer::<256>::new()
+                        .with(&wvp)
+                        .with(&sky.sun_direction())
+                        .with(&sky.sun_color.srgb_to_linear_f32().xyz())
+                        .with(&sky.ground_color.srgb_to_linear_f32().xyz())
+                        .with(&sky.sun_intensity)
+                        .with(&sky.sun_size)
+                        .with(&sky.rayleigh)
+                        .with(&sky.turbidity),
+                )?,
+                binding: BufferLocation::Auto {
+                    shader_location: shader.uniform_buffer_binding,
+                },
+                data_usage: Default::default(),
+            }],
+        }],
+        ElementRange::Specific {
+            offset: 0,
+            count: 12,
+        },
+    )?;
+    Ok(stats)
+}
+
+/// Renders procedural sky as a background and captures it into an environment map, that is used
+/// for image-based lighting.
+pub struct ProceduralSkyRenderer {
+    shader: ProceduralSkyShader,
+    environment: ReflectionProbeCubeMap,
+    // The sky, that is currently captured in the environment map. It is used to re-capture the
+    // environment map only when the parameters of the sky change.
+    captured_sky: Option<ProceduralSky>,
+}
+
+impl ProceduralSkyRenderer {
+    pub fn new(server: &dyn GraphicsServer) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: ProceduralSkyShader::new(server)?,
+            environment: ReflectionProbeCubeMap::new(server, ENVIRONMENT_MAP_SIZE)?,
+            captured_sky: None,
+        })
+    }
+
+    /// Draws the sky as a background. The cube must be centered at the camera.
+    pub fn render_background(
+        &self,
+        sky: &ProceduralSky,
+        cube: &dyn GeometryBuffer,
+        wvp: Matrix4<f32>,
+        viewport: Rect<i32>,
+        frame_buffer: &mut dyn FrameBuffer,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        draw_sky(
+            &self.shader,
+            sky,
+            cube,
+            wvp,
+            viewport,
+            frame_buffer,
+            uniform_buffer_cache,
+        )
+    }
+
+    /// Captures the sky in the environment map, if the sky has changed since the last capture.
+    pub fn update_environment(
+        &mut self,
+        sky: &ProceduralSky,
+        cube: &dyn GeometryBuffer,
+        uniform_buffer_cache: &mut UniformBufferCache,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut stats = RenderPassStatistics::default();
+
+        if self.captured_sky.as_ref() == Some(sky) {
+            return Ok(stats);
+        }
+
+        let size = self.environment.resolution as i32;
+        let viewport = Rect::new(0, 0, size, size);
+        let projection = Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, 0.1, 10.0);
+
+        // Must be in sync with the faces of point shadow maps.
+        for (face, look, up) in [
+            (CubeMapFace::PositiveX, Vector3::x(), -Vector3::y()),
+            (CubeMapFace::NegativeX, -Vector3::x(), -Vector3::y()),
+            (CubeMapFace::PositiveY, Vector3::y(), Vector3::z()),
+            (CubeMapFace::NegativeY, -Vector3::y(), -Vector3::z()),
+            (CubeMapFace::PositiveZ, Vector3::z(), -Vector3::y()),
+            (CubeMapFace::NegativeZ, -Vector3::z(), -Vector3::y()),
+        ] {
+            let view = Matrix4::look_at_rh(&Point3::origin(), &Point3::from(look), &up);
+            self.environment.framebuffer.set_cubemap_face(0, face);
+            stats += draw_sky(
+                &self.shader,
+                sky,
+                cube,
+                projection * view,
+                viewport,
+                &mut *self.environment.framebuffer,
+                uniform_buffer_cache,
+            )?;
+        }
+
+        self.environment.texture().borrow_mut().generate_mipmap();
+        self.environment.is_captured = true;
+        self.captured_sky = Some(sky.clone());
+
+        Ok(stats)
+    }
+
+    /// Returns the environment map with the captured sky and its max mip level (if any).
+    pub fn environment(&self) -> Option<(Rc<RefCell<dyn GpuTexture>>, f32)> {
+        if self.environment.is_captured {
+            Some((
+                self.environment.texture().clone(),
+                self.environment.mip_count.saturating_sub(1) as f32,
+            ))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Pass profiler collects statistics of every render pass (see [`PassStatistics`]) and measures GPU
+//! time of render passes using timer queries for the built-in frame profiler. See
+//! [`crate::core::profiler`] module docs for more info.
+
+use crate::{
+    core::{
+        instant,
+        profiler::{Profiler, RenderPassProfile},
+    },
+    renderer::{
+        framework::{
+            query::{Query, QueryKind, QueryResult},
+            server::GraphicsServer,
+            stats::{PipelineStatistics, RenderPassStatistics},
+        },
+        stats::PassStatistics,
+    },
+};
+use std::collections::VecDeque;
+
+/// Maximum amount of frames waiting for the results of their timer queries. Older frames are
+/// discarded, if the GPU lags behind.
+const MAX_PENDING_FRAMES: usize = 4;
+
+struct PassRecord {
+    profile: RenderPassProfile,
+    query: Option<Box<dyn Query>>,
+}
+
+struct ActivePass {
+    name: &'static str,
+    start_time: instant::Instant,
+    geometry: RenderPassStatistics,
+    pipeline: PipelineStatistics,
+    record: Option<usize>,
+}
+
+#[derive(Default)]
+pub(crate) struct PassProfiler {
+    enabled: bool,
+    use_timer_queries: bool,
+    active: Option<ActivePass>,
+    passes: Vec<PassStatistics>,
+    current: Vec<PassRecord>,
+    pending: VecDeque<Vec<PassRecord>>,
+    free_queries: Vec<Box<dyn Query>>,
+}
+
+fn sub_geometry(a: RenderPassStatistics, b: RenderPassStatistics) -> RenderPassStatistics {
+    RenderPassStatistics {
+        draw_calls: a.draw_calls.saturating_sub(b.draw_calls),
+        triangles_rendered: a.triangles_rendered.saturating_sub(b.triangles_rendered),
+        dynamic_batches: a.dynamic_batches.saturating_sub(b.dynamic_batches),
+        batched_primitives: a.batched_primitives.saturating_sub(b.batched_primitives),
+        instanced_draw_calls: a
+            .instanced_draw_calls
+            .saturating_sub(b.instanced_draw_calls),
+        instanced_meshes: a.instanced_meshes.saturating_sub(b.instanced_meshes),
+    }
+}
+
+impl PassProfiler {
+    /// Starts a new frame. Timer queries cannot be nested, so `allow_timer_queries` must be
+    /// `false` if there's another timer query active during the frame.
+    pub(crate) fn begin_frame(&mut self, server: &dyn GraphicsServer, allow_timer_queries: bool) {
+        self.enabled = Profiler::is_enabled();
+        self.use_timer_queries =
+            self.enabled && allow_timer_queries && server.capabilities().timer_queries;
+        self.active = None;
+        self.passes.clear();
+        self.current.clear();
+        if !self.enabled {
+            self.pending.clear();
+        }
+    }
+
+    /// Starts a render pass. `geometry` is the current value of the statistics, that is
+    /// accumulated by the pass. Nested passes are ignored.
+    pub(crate) fn begin_pass(
+        &mut self,
+        server: &dyn GraphicsServer,
+        name: &'static str,
+        geometry: RenderPassStatistics,
+    ) {
+        if self.active.is_some() {
+            return;
+        }
+
+        let record = if self.enabled {
+            let query = if self.use_timer_queries {
+                self.free_queries
+                    .pop()
+                    .or_else(|| server.create_query().ok())
+            } else {
+                None
+            };
+            if let Some(query) = query.as_ref() {
+                query.begin(QueryKind::TimeElapsed);
+            }
+            self.current.push(PassRecord {
+                profile: RenderPassProfile {
+                    name,
+                    ..Default::default()
+                },
+                query,
+            });
+            Some(self.current.len() - 1)
+        } else {
+            None
+        };
+
+        self.active = Some(ActivePass {
+            name,
+            start_time: instant::Instant::now(),
+            geometry,
+            pipeline: server.pipeline_statistics(),
+            record,
+        });
+    }
+
+    /// Finishes the active render pass. `geometry` is the current value of the statistics, that
+    /// is accumulated by the pass.
+    pub(crate) fn end_pass(&mut self, server: &dyn GraphicsServer, geometry: RenderPassStatistics) {
+        let Some(active) = self.active.take() else {
+            return;
+        };
+
+        let statistics = PassStatistics {
+            name: active.name,
+            geometry: sub_geometry(geometry, active.geometry),
+            pipeline: server.pipeline_statistics() - active.pipeline,
+            time: active.start_time.elapsed(),
+        };
+
+        if let Some(record) = active.record.and_then(|index| self.current.get_mut(index)) {
+            if let Some(query) = record.query.as_ref() {
+                query.end();
+            }
+            record.profile.draw_calls = statistics.geometry.draw_calls;
+            record.profile.triangles = statistics.geometry.triangles_rendered;
+        }
+
+        self.passes.push(statistics);
+    }
+
+    /// Takes statistics of every finished pass since the last call. Passes with the same name
+    /// (for example, rendered by different cameras) are merged.
+    pub(crate) fn take_passes(&mut self) -> Vec<PassStatistics> {
+        let mut passes: Vec<PassStatistics> = Vec::new();
+        for pass in self.passes.drain(..) {
+            match passes.iter_mut().find(|p| p.name == pass.name) {
+                Some(existing) => {
+                    existing.geometry += pass.geometry;
+                    existing.pipeline += pass.pipeline;
+                    existing.time += pass.time;
+                }
+                None => passes.push(pass),
+            }
+        }
+        passes
+    }
+
+    /// Finishes the frame and reports the statistics of the oldest frame, that has all the
+    /// results of its timer queries available, to the frame profiler.
+    pub(crate) fn end_frame(&mut self, server: &dyn GraphicsServer) {
+        if self.active.is_some() {
+            // A pass has failed, finish it anyway to keep the queries in a valid state.
+            self.end_pass(server, Default::default());
+        }
+
+        if !self.enabled {
+            return;
+        }
+
+        self.pending.push_back(std::mem::take(&mut self.current));
+        while self.pending.len() > MAX_PENDING_FRAMES {
+            self.pending.pop_front();
+        }
+
+        let mut ready = None;
+        while let Some(frame) = self.pending.front_mut() {
+            let is_ready = frame.iter_mut().all(|record| {
+                if record.profile.gpu_time.is_some() {
+                    return true;
+                }
+                match record.query.as_ref() {
+                    Some(query) => match query.try_get_result() {
+                        Some(QueryResult::TimeElapsed(time)) => {
+                            record.profile.gpu_time = Some(time);
+                            true
+                        }
+                        _ => false,
+                    },
+                    None => true,
+                }
+            });
+            if !is_ready {
+                break;
+            }
+            ready = self.pending.pop_front();
+        }
+
+        if let Some(frame) = ready {
+            // Passes with the same name (for example, rendered by different cameras) are merged.
+            let mut passes: Vec<RenderPassProfile> = Vec::new();
+            for record in frame {
+                if let Some(query) = record.query {
+                    self.free_queries.push(query);
+                }
+                match passes.iter_mut().find(|p| p.name == record.profile.name) {
+                    Some(pass) => {
+                        pass.draw_calls += record.profile.draw_calls;
+                        pass.triangles += record.profile.triangles;
+                        pass.gpu_time = match (pass.gpu_time, record.profile.gpu_time) {
+                            (Some(a), Some(b)) => Some(a + b),
+                            (a, b) => a.or(b),
+                        };
+                    }
+                    None => passes.push(record.profile),
+                }
+            }
+            Profiler::set_render_passes(passes);
+        }
+    }
+}
@@ -1,22 +1,68 @@
 uniform sampler2D diffuseTexture;
 uniform sampler2D aoSampler;
 uniform sampler2D ambientTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform samplerCube environmentMap;
 
 layout (std140) uniform Uniforms {
     mat4 worldViewProjection;
     vec4 ambientColor;
+    mat4 invViewProj;
+    vec3 cameraPosition;
+    float environmentMaxLod;
+    bool useEnvironmentMap;
 };
 
 out vec4 FragColor;
 in vec2 texCoord;
 
+// Analytical approximation of the split-sum environment BRDF (Karis, "Physically Based Shading on Mobile").
+vec2 EnvBRDFApprox(float roughness, float NdotV)
+{
+    const vec4 c0 = vec4(-1.0, -0.0275, -0.572, 0.022);
+    const vec4 c1 = vec4(1.0, 0.0425, 1.04, -0.04);
+    vec4 r = roughness * c0 + c1;
+    float a004 = min(r.x * r.x, exp2(-9.28 * NdotV)) * r.x + r.y;
+    return vec2(-1.04, 1.04) * a004 + r.zw;
+}
+
+vec3 FresnelSchlickRoughness(float cosTheta, vec3 F0, float roughness)
+{
+    return F0 + (max(vec3(1.0 - roughness), F0) - F0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
 void main()
 {
     float ambientOcclusion = texture(aoSampler, texCoord).r;
     vec4 ambientPixel = texture(ambientTexture, texCoord);
-    FragColor = (ambientColor + ambientPixel) * S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    vec4 albedo = S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    FragColor = (ambientColor + ambientPixel) * albedo;
+
+    if (useEnvironmentMap) {
+        vec3 material = texture(materialTexture, texCoord).rgb;
+        float metallic = material.x;
+        float roughness = material.y;
+
+        vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
+        vec3 N = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+        vec3 V = normalize(cameraPosition - fragmentPosition);
+        vec3 R = reflect(-V, N);
+        float NdotV = max(dot(N, V), 0.0);
+
+        vec3 F0 = mix(vec3(0.04), albedo.rgb, metallic);
+        vec3 F = FresnelSchlickRoughness(NdotV, F0, roughness);
+        vec3 kD = (1.0 - F) * (1.0 - metallic);
+
+        // There's no pre-filtered irradiance map, so the most blurred mip level is used instead.
+        vec3 irradiance = textureLod(environmentMap, N, environmentMaxLod).rgb;
+        vec3 prefiltered = textureLod(environmentMap, R, roughness * environmentMaxLod).rgb;
+        vec2 envBRDF = EnvBRDFApprox(roughness, NdotV);
+
+        FragColor.rgb += kD * irradiance * albedo.rgb + prefiltered * (F0 * envBRDF.x + envBRDF.y);
+    }
+
     FragColor.rgb *= ambientOcclusion;
     FragColor.a = ambientPixel.a;
-
-    // TODO: Implement IBL.
 }
\ No newline at end of file
@@ -4,6 +4,10 @@ layout (location = 1) in vec2 vertexTexCoord;
 layout (std140) uniform Uniforms {
     mat4 worldViewProjection;
     vec4 ambientColor;
+    mat4 invViewProj;
+    vec3 cameraPosition;
+    float environmentMaxLod;
+    bool useEnvironmentMap;
 };
 
 out vec2 texCoord;
@@ -0,0 +1,29 @@
+uniform sampler2D screenTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat3 colorMatrix;
+    bool compensate;
+};
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+void main() {
+    vec4 color = S_SRGBToLinear(texture(screenTexture, texCoord));
+
+    vec3 simulated = colorMatrix * color.rgb;
+
+    vec3 result;
+    if (compensate) {
+        // Daltonization - shift the information, that cannot be perceived, to the channels that
+        // can be perceived.
+        vec3 error = color.rgb - simulated;
+        vec3 correction = vec3(0.0, 0.7 * error.r + error.g, 0.7 * error.r + error.b);
+        result = clamp(color.rgb + correction, 0.0, 1.0);
+    } else {
+        result = simulated;
+    }
+
+    fragColor = S_LinearToSRGB(vec4(result, color.a));
+}
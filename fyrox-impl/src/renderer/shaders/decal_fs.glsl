@@ -28,8 +28,9 @@ void main()
 
     uvec4 maskIndex = texture(decalMask, texCoord);
 
-    // Masking.
-    if (maskIndex.r != layerIndex) {
+    // Masking. The two highest bits of the mask define the lighting model and fog opt-out and must
+    // be ignored.
+    if ((maskIndex.r & ~(S_TOON_SHADING_BIT | S_NO_FOG_BIT)) != layerIndex) {
         discard;
     }
 
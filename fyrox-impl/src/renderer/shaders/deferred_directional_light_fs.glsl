@@ -2,6 +2,7 @@ uniform sampler2D depthTexture;
 uniform sampler2D colorTexture;
 uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
+uniform usampler2D decalMaskTexture;
 uniform sampler2D shadowCascade0;
 uniform sampler2D shadowCascade1;
 uniform sampler2D shadowCascade2;
@@ -49,7 +50,12 @@ void main()
     ctx.roughness = material.y;
     ctx.viewVector = normalize(cameraPosition - fragmentPosition);
 
-    vec3 lighting = S_PBR_CalculateLight(ctx);
+    vec3 lighting;
+    if (S_IsToonShaded(texture(decalMaskTexture, texCoord).r)) {
+        lighting = S_Toon_CalculateLight(ctx);
+    } else {
+        lighting = S_PBR_CalculateLight(ctx);
+    }
 
     float fragmentZViewSpace = abs((viewMatrix * vec4(fragmentPosition, 1.0)).z);
 
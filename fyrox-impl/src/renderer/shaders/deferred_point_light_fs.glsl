@@ -2,6 +2,7 @@ uniform sampler2D depthTexture;
 uniform sampler2D colorTexture;
 uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
+uniform usampler2D decalMaskTexture;
 uniform samplerCube pointShadowTexture;
 
 layout (std140) uniform Uniforms {
@@ -14,8 +15,11 @@ layout (std140) uniform Uniforms {
     float shadowBias;
     float lightIntensity;
     float shadowAlpha;
-    bool softShadows;
+    int shadowFilter;
     bool shadowsEnabled;
+    float shadowMapInvSize;
+    float shadowSoftness;
+    float normalBias;
 };
 
 in vec2 texCoord;
@@ -40,12 +44,20 @@ void main()
     ctx.roughness = material.y;
     ctx.viewVector = normalize(cameraPosition - fragmentPosition);
 
-    vec3 lighting = S_PBR_CalculateLight(ctx);
+    vec3 lighting;
+    if (S_IsToonShaded(texture(decalMaskTexture, texCoord).r)) {
+        lighting = S_Toon_CalculateLight(ctx);
+    } else {
+        lighting = S_PBR_CalculateLight(ctx);
+    }
 
     float distanceAttenuation = S_LightDistanceAttenuation(distance, lightRadius);
 
+    vec3 shadowFragmentToLight = fragmentToLight - ctx.fragmentNormal * normalBias;
+    float shadowFragmentDistance = length(shadowFragmentToLight);
     float shadow = S_PointShadow(
-        shadowsEnabled, softShadows, distance, shadowBias, ctx.fragmentToLight, pointShadowTexture);
+        shadowsEnabled, shadowFilter, shadowSoftness, shadowFragmentDistance, shadowBias,
+        shadowFragmentToLight / shadowFragmentDistance, shadowMapInvSize, pointShadowTexture);
     float finalShadow = mix(1.0, shadow, shadowAlpha);
 
     FragColor = vec4(lightIntensity * distanceAttenuation * finalShadow * lighting, diffuseColor.a);
@@ -11,8 +11,11 @@ layout (std140) uniform Uniforms {
     float shadowBias;
     float lightIntensity;
     float shadowAlpha;
-    bool softShadows;
+    int shadowFilter;
     bool shadowsEnabled;
+    float shadowMapInvSize;
+    float shadowSoftness;
+    float normalBias;
 };
 
 out vec2 texCoord;
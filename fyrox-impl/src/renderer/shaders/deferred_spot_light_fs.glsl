@@ -2,6 +2,7 @@ uniform sampler2D depthTexture;
 uniform sampler2D colorTexture;
 uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
+uniform usampler2D decalMaskTexture;
 uniform sampler2D spotShadowTexture;
 uniform sampler2D cookieTexture;
 
@@ -22,7 +23,11 @@ layout (std140) uniform Uniforms {
     float shadowAlpha;
     bool cookieEnabled;
     bool shadowsEnabled;
-    bool softShadows;
+    int shadowFilter;
+    float shadowSoftness;
+    float normalBias;
+    float zNear;
+    float zFar;
 };
 
 in vec2 texCoord;
@@ -46,16 +51,22 @@ void main()
     ctx.roughness = material.y;
     ctx.viewVector = normalize(cameraPosition - fragmentPosition);
 
-    vec3 lighting = S_PBR_CalculateLight(ctx);
+    vec3 lighting;
+    if (S_IsToonShaded(texture(decalMaskTexture, texCoord).r)) {
+        lighting = S_Toon_CalculateLight(ctx);
+    } else {
+        lighting = S_PBR_CalculateLight(ctx);
+    }
 
     float distanceAttenuation = S_LightDistanceAttenuation(distance, lightRadius);
 
     float spotAngleCos = dot(lightDirection, ctx.fragmentToLight);
     float coneFactor = smoothstep(halfConeAngleCos, halfHotspotConeAngleCos, spotAngleCos);
 
-    float shadow = S_SpotShadowFactor(
-        shadowsEnabled, softShadows, shadowBias, fragmentPosition,
-        lightViewProjMatrix, shadowMapInvSize, spotShadowTexture);
+    vec3 shadowFragmentPosition = fragmentPosition + ctx.fragmentNormal * normalBias;
+    float shadow = S_FilteredSpotShadowFactor(
+        shadowsEnabled, shadowFilter, shadowSoftness, shadowBias, shadowFragmentPosition,
+        lightViewProjMatrix, shadowMapInvSize, zNear, zFar, spotShadowTexture);
     float finalShadow = mix(1.0, shadow, shadowAlpha);
 
     vec4 cookieAttenuation = vec4(1.0);
@@ -18,7 +18,11 @@ layout (std140) uniform Uniforms {
     float shadowAlpha;
     bool cookieEnabled;
     bool shadowsEnabled;
-    bool softShadows;
+    int shadowFilter;
+    float shadowSoftness;
+    float normalBias;
+    float zNear;
+    float zFar;
 };
 
 out vec2 texCoord;
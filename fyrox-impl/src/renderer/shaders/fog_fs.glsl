@@ -0,0 +1,48 @@
+uniform sampler2D depthTexture;
+uniform usampler2D decalMaskTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    vec4 fogColor;
+    vec3 cameraPosition;
+    float fogDensity;
+    float fogStart;
+    float fogEnd;
+    float heightFalloff;
+    float baseHeight;
+    int fogMode;
+};
+
+out vec4 FragColor;
+in vec2 texCoord;
+
+void main()
+{
+    float depth = texture(depthTexture, texCoord).r;
+
+    // Background (skybox or procedural sky) and surfaces with disabled fog are left untouched.
+    if (depth >= 1.0 || S_IsFogDisabled(texture(decalMaskTexture, texCoord).r)) {
+        discard;
+    }
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, depth), invViewProj);
+    float distance = max(length(fragmentPosition - cameraPosition) - fogStart, 0.0);
+
+    float factor;
+    if (fogMode == 0) {
+        // Linear
+        factor = distance / max(fogEnd - fogStart, 0.0001);
+    } else if (fogMode == 1) {
+        // Exponential
+        factor = 1.0 - exp(-fogDensity * distance);
+    } else {
+        // Exponential squared
+        float d = fogDensity * distance;
+        factor = 1.0 - exp(-d * d);
+    }
+
+    factor *= exp(-heightFalloff * max(fragmentPosition.y - baseHeight, 0.0));
+
+    FragColor = vec4(fogColor.rgb, clamp(factor, 0.0, 1.0));
+}
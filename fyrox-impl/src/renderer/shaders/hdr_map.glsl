@@ -11,6 +11,7 @@ layout (std140) uniform Uniforms {
     float maxLuminance;
     bool autoExposure;
     float fixedExposure;
+    bool useToneMapping;
 };
 
 in vec2 texCoord;
@@ -40,7 +41,12 @@ void main() {
         exposure = fixedExposure;
     }
 
-    vec4 ldrColor = vec4(vec3(1.0) - exp(-hdrColor.rgb * exposure), hdrColor.a);
+    vec4 ldrColor;
+    if (useToneMapping) {
+        ldrColor = vec4(vec3(1.0) - exp(-hdrColor.rgb * exposure), hdrColor.a);
+    } else {
+        ldrColor = clamp(hdrColor, 0.0, 1.0);
+    }
 
     if (useColorGrading) {
         outLdrColor = vec4(ColorGrading(S_LinearToSRGB(ldrColor).rgb), ldrColor.a);
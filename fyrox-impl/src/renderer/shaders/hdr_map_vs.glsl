@@ -9,6 +9,7 @@ layout (std140) uniform Uniforms {
     float maxLuminance;
     bool autoExposure;
     float fixedExposure;
+    bool useToneMapping;
 };
 
 
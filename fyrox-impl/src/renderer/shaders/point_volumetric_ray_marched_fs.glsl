@@ -0,0 +1,69 @@
+uniform sampler2D depthSampler;
+uniform samplerCube pointShadowTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    // Warning! All coordinates are given in *world* space.
+    vec3 cameraPosition;
+    vec3 lightPosition;
+    vec3 lightColor;
+    float intensity;
+    float lightRadius;
+    float density;
+    float anisotropy;
+    int stepCount;
+    bool shadowsEnabled;
+    float shadowBias;
+};
+
+out vec4 FragColor;
+
+in vec2 texCoord;
+
+void main()
+{
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthSampler, texCoord).r), invViewProj);
+    vec3 rayDirection = fragmentPosition - cameraPosition;
+    float fragmentDistance = length(rayDirection);
+    rayDirection /= fragmentDistance;
+
+    float minT, maxT;
+    if (!S_RaySphereIntersection(cameraPosition, rayDirection, lightPosition, lightRadius, minT, maxT))
+    {
+        discard;
+    }
+
+    minT = max(minT, 0.0);
+    maxT = min(maxT, fragmentDistance);
+    if (maxT <= minT)
+    {
+        discard;
+    }
+
+    float stepSize = (maxT - minT) / float(stepCount);
+    // Jitter the start of the ray to replace banding with noise, the noise is smoothed out by the
+    // upsampling.
+    float jitter = S_InterleavedGradientNoise(gl_FragCoord.xy);
+    float stepTransmittance = exp(-density * stepSize);
+
+    vec3 scatter = vec3(0.0);
+    float transmittance = exp(-density * minT);
+    for (int i = 0; i < stepCount; ++i)
+    {
+        vec3 samplePosition = cameraPosition + rayDirection * (minT + (float(i) + jitter) * stepSize);
+        vec3 fromLight = samplePosition - lightPosition;
+        float distanceToLight = length(fromLight);
+        vec3 l = fromLight / max(distanceToLight, 0.0001);
+
+        float attenuation = S_LightDistanceAttenuation(distanceToLight, lightRadius);
+        float shadow = S_PointShadow(
+            shadowsEnabled, S_SHADOW_FILTER_HARD, 0.0, distanceToLight, shadowBias, -fromLight, 0.0, pointShadowTexture);
+        float phase = S_HenyeyGreensteinPhase(dot(l, -rayDirection), anisotropy);
+        scatter += vec3(transmittance * density * stepSize * attenuation * shadow * phase);
+
+        transmittance *= stepTransmittance;
+    }
+
+    FragColor = vec4(lightColor * intensity * scatter, 1.0);
+}
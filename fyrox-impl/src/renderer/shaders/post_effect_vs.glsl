@@ -0,0 +1,16 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    vec4 parameters[8];
+    vec2 frameSize;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
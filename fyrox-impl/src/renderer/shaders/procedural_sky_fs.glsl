@@ -0,0 +1,68 @@
+// Simple analytical model of the sky with single scattering. It is not physically accurate, but it
+// gives plausible blue sky at day, red sunsets and dark sky at night.
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    vec3 sunDirection;
+    vec3 sunColor;
+    vec3 groundColor;
+    float sunIntensity;
+    float sunSize;
+    float rayleigh;
+    float turbidity;
+};
+
+out vec4 FragColor;
+
+in vec3 direction;
+
+// Scattering coefficients of the whole atmosphere in zenith direction.
+const vec3 RayleighCoefficients = vec3(0.058, 0.135, 0.331);
+const vec3 MieCoefficients = vec3(0.21);
+const float MieAnisotropy = 0.76;
+const float SkyBrightness = 20.0;
+const float SunDiscBrightness = 50.0;
+
+// Relative optical air mass (Kasten-Young formula), 1.0 in zenith, ~38 at horizon.
+float AirMass(float cosZenith)
+{
+    float elevation = max(degrees(asin(clamp(cosZenith, 0.0, 1.0))), 0.0);
+    return 1.0 / (max(cosZenith, 0.0) + 0.50572 * pow(elevation + 6.07995, -1.6364));
+}
+
+void main()
+{
+    vec3 view = normalize(direction);
+    vec3 sun = normalize(sunDirection);
+    float cosTheta = dot(view, sun);
+
+    vec3 betaR = RayleighCoefficients * rayleigh;
+    vec3 betaM = MieCoefficients * turbidity;
+    vec3 extinction = max(betaR + betaM, vec3(0.0001));
+
+    // Sun light gets redder when it goes through a thicker layer of atmosphere at sunset.
+    vec3 sunTransmittance = exp(-extinction * AirMass(sun.y));
+    // Smoothly fade the sky out when the sun goes below the horizon.
+    float daylight = smoothstep(-0.1, 0.02, sun.y);
+    vec3 sunLight = sunColor * sunIntensity * sunTransmittance * daylight;
+
+    float phaseR = 3.0 / (16.0 * PI) * (1.0 + cosTheta * cosTheta);
+    float phaseM = S_HenyeyGreensteinPhase(cosTheta, MieAnisotropy);
+
+    // Directions below the horizon use the color of the horizon to blend with the ground.
+    float viewAirMass = AirMass(max(view.y, 0.0));
+    vec3 inScatter = sunLight * (betaR * phaseR + betaM * phaseM) / extinction
+        * (1.0 - exp(-extinction * viewAirMass)) * SkyBrightness;
+
+    vec3 color = inScatter;
+
+    // Sun disc.
+    float sunDisc = smoothstep(cos(sunSize * 1.2), cos(sunSize), cosTheta);
+    color += sunLight * sunDisc * SunDiscBrightness * step(0.0, view.y);
+
+    // Ground is lit by the sun and the sky.
+    vec3 ground = groundColor * (sunLight * max(sun.y, 0.0) + inScatter * 0.25);
+    color = mix(color, ground, smoothstep(0.0, -0.05, view.y));
+
+    FragColor = vec4(color, 1.0);
+}
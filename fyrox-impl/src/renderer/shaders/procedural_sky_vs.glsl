@@ -0,0 +1,20 @@
+layout (location = 0) in vec3 vertexPosition;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    vec3 sunDirection;
+    vec3 sunColor;
+    vec3 groundColor;
+    float sunIntensity;
+    float sunSize;
+    float rayleigh;
+    float turbidity;
+};
+
+out vec3 direction;
+
+void main()
+{
+    direction = vertexPosition;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
@@ -0,0 +1,119 @@
+uniform sampler2D diffuseTexture;
+uniform sampler2D aoSampler;
+uniform sampler2D ambientTexture;
+uniform sampler2D depthTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform samplerCube environmentMap;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    mat4 probeWorldMatrix;
+    mat4 probeInvWorldMatrix;
+    vec4 ambientColor;
+    vec3 cameraPosition;
+    float environmentMaxLod;
+    vec3 probeHalfSize;
+    float blendDistance;
+    bool sphereProjection;
+};
+
+out vec4 FragColor;
+in vec2 texCoord;
+
+// Analytical approximation of the split-sum environment BRDF (Karis, "Physically Based Shading on Mobile").
+vec2 EnvBRDFApprox(float roughness, float NdotV)
+{
+    const vec4 c0 = vec4(-1.0, -0.0275, -0.572, 0.022);
+    const vec4 c1 = vec4(1.0, 0.0425, 1.04, -0.04);
+    vec4 r = roughness * c0 + c1;
+    float a004 = min(r.x * r.x, exp2(-9.28 * NdotV)) * r.x + r.y;
+    return vec2(-1.04, 1.04) * a004 + r.zw;
+}
+
+vec3 FresnelSchlickRoughness(float cosTheta, vec3 F0, float roughness)
+{
+    return F0 + (max(vec3(1.0 - roughness), F0) - F0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
+// Returns a distance along the given direction from the given point (both in the local space of
+// the probe) to the bounds of the probe. The point must be inside the bounds.
+float DistanceToBounds(vec3 localPosition, vec3 localDirection)
+{
+    if (sphereProjection) {
+        // Ellipsoid is transformed to a unit sphere.
+        vec3 p = localPosition / probeHalfSize;
+        vec3 d = localDirection / probeHalfSize;
+        float a = dot(d, d);
+        float b = dot(p, d);
+        float c = dot(p, p) - 1.0;
+        return (-b + sqrt(max(b * b - a * c, 0.0))) / a;
+    } else {
+        vec3 first = (probeHalfSize - localPosition) / localDirection;
+        vec3 second = (-probeHalfSize - localPosition) / localDirection;
+        vec3 furthest = max(first, second);
+        return min(min(furthest.x, furthest.y), furthest.z);
+    }
+}
+
+// Returns a distance from the given point (in the local space of the probe) to the bounds of the
+// probe. Negative values mean that the point is outside the bounds.
+float DistanceInsideBounds(vec3 localPosition)
+{
+    if (sphereProjection) {
+        float minHalfSize = min(min(probeHalfSize.x, probeHalfSize.y), probeHalfSize.z);
+        return (1.0 - length(localPosition / probeHalfSize)) * minHalfSize;
+    } else {
+        vec3 distances = probeHalfSize - abs(localPosition);
+        return min(min(distances.x, distances.y), distances.z);
+    }
+}
+
+vec3 ParallaxCorrect(vec3 localPosition, vec3 worldDirection)
+{
+    vec3 localDirection = (probeInvWorldMatrix * vec4(worldDirection, 0.0)).xyz;
+    vec3 localHitPoint = localPosition + localDirection * DistanceToBounds(localPosition, localDirection);
+    // The cube map is captured at the origin of the probe and it is aligned with the world axes.
+    return (probeWorldMatrix * vec4(localHitPoint, 0.0)).xyz;
+}
+
+void main()
+{
+    vec4 ambientPixel = texture(ambientTexture, texCoord);
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
+    vec3 localPosition = (probeInvWorldMatrix * vec4(fragmentPosition, 1.0)).xyz;
+
+    float distanceInside = DistanceInsideBounds(localPosition);
+    if (distanceInside <= 0.0) {
+        discard;
+    }
+    float weight = blendDistance > 0.0 ? clamp(distanceInside / blendDistance, 0.0, 1.0) : 1.0;
+
+    float ambientOcclusion = texture(aoSampler, texCoord).r;
+    vec4 albedo = S_SRGBToLinear(texture(diffuseTexture, texCoord));
+    vec3 material = texture(materialTexture, texCoord).rgb;
+    float metallic = material.x;
+    float roughness = material.y;
+
+    vec3 N = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    vec3 V = normalize(cameraPosition - fragmentPosition);
+    vec3 R = reflect(-V, N);
+    float NdotV = max(dot(N, V), 0.0);
+
+    vec3 F0 = mix(vec3(0.04), albedo.rgb, metallic);
+    vec3 F = FresnelSchlickRoughness(NdotV, F0, roughness);
+    vec3 kD = (1.0 - F) * (1.0 - metallic);
+
+    // There's no pre-filtered irradiance map, so the most blurred mip level is used instead. It
+    // is very blurry, so there's no need to correct the normal.
+    vec3 irradiance = textureLod(environmentMap, N, environmentMaxLod).rgb;
+    vec3 prefiltered = textureLod(environmentMap, ParallaxCorrect(localPosition, R), roughness * environmentMaxLod).rgb;
+    vec2 envBRDF = EnvBRDFApprox(roughness, NdotV);
+
+    FragColor = (ambientColor + ambientPixel) * albedo;
+    FragColor.rgb += kD * irradiance * albedo.rgb + prefiltered * (F0 * envBRDF.x + envBRDF.y);
+    FragColor.rgb *= ambientOcclusion;
+    FragColor.a = ambientPixel.a * weight;
+}
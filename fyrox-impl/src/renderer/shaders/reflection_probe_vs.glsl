@@ -0,0 +1,23 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    mat4 probeWorldMatrix;
+    mat4 probeInvWorldMatrix;
+    vec4 ambientColor;
+    vec3 cameraPosition;
+    float environmentMaxLod;
+    vec3 probeHalfSize;
+    float blendDistance;
+    bool sphereProjection;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
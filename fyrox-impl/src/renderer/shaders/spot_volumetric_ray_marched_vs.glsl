@@ -0,0 +1,29 @@
+layout (location = 0) in vec3 vertexPosition;
+layout (location = 1) in vec2 vertexTexCoord;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invViewProj;
+    mat4 lightViewProjMatrix;
+    vec3 cameraPosition;
+    vec3 lightPosition;
+    vec3 lightDirection;
+    vec3 lightColor;
+    float intensity;
+    float hotspotCosine;
+    float coneAngleCos;
+    float lightRadius;
+    float density;
+    float anisotropy;
+    int stepCount;
+    bool shadowsEnabled;
+    float shadowBias;
+};
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    gl_Position = worldViewProjection * vec4(vertexPosition, 1.0);
+}
@@ -0,0 +1,50 @@
+// Bilateral upsampling of half-resolution volumetric light. Each pixel is reconstructed from four
+// closest low-resolution samples, samples with different depth are rejected to prevent light
+// "leaking" over the edges of objects.
+
+uniform sampler2D depthSampler;
+uniform sampler2D volumeTexture;
+
+layout (std140) uniform Uniforms {
+    mat4 worldViewProjection;
+    mat4 invProj;
+    vec2 volumeSize;
+};
+
+out vec4 FragColor;
+
+in vec2 texCoord;
+
+float LinearDepth(vec2 uv)
+{
+    return -S_UnProject(vec3(uv, texture(depthSampler, uv).r), invProj).z;
+}
+
+void main()
+{
+    float depth = LinearDepth(texCoord);
+    // Depth difference tolerance grows with the distance to keep the weights stable on far surfaces.
+    float tolerance = max(depth * 0.05, 0.001);
+
+    vec2 samplePosition = texCoord * volumeSize - 0.5;
+    vec2 base = floor(samplePosition);
+    vec2 fraction = samplePosition - base;
+    ivec2 maxTexel = ivec2(volumeSize) - 1;
+
+    vec3 color = vec3(0.0);
+    float weightSum = 0.0;
+    for (int y = 0; y <= 1; ++y)
+    {
+        for (int x = 0; x <= 1; ++x)
+        {
+            ivec2 texel = clamp(ivec2(base) + ivec2(x, y), ivec2(0), maxTexel);
+            vec2 bilinear = mix(1.0 - fraction, fraction, vec2(x, y));
+            float sampleDepth = LinearDepth((vec2(texel) + 0.5) / volumeSize);
+            float weight = bilinear.x * bilinear.y * exp(-abs(depth - sampleDepth) / tolerance) + 0.00001;
+            color += texelFetch(volumeTexture, texel, 0).rgb * weight;
+            weightSum += weight;
+        }
+    }
+
+    FragColor = vec4(color / weightSum, 1.0);
+}
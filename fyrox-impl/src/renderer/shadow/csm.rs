@@ -109,6 +109,7 @@ pub(crate) struct CsmRenderContext<'a, 'c> {
     pub texture_cache: &'a mut TextureCache,
     pub fallback_resources: &'a FallbackResources,
     pub uniform_memory_allocator: &'a mut UniformMemoryAllocator,
+    pub use_gpu_skinning: bool,
 }
 
 impl CsmRenderer {
@@ -157,6 +158,7 @@ impl CsmRenderer {
             texture_cache,
             fallback_resources,
             uniform_memory_allocator,
+            use_gpu_skinning,
         } = ctx;
 
         let LightSourceKind::Directional { ref csm_options } = light.kind else {
@@ -260,6 +262,7 @@ impl CsmRenderer {
                 DIRECTIONAL_SHADOW_PASS_NAME.clone(),
                 RenderDataBundleStorageOptions {
                     collect_lights: false,
+                    use_gpu_skinning,
                 },
             );
 
@@ -32,3 +32,8 @@ pub fn cascade_size(base_size: usize, cascade: usize) -> usize {
         _ => unreachable!(),
     }
 }
+
+/// Clamps custom shadow map size of a light to sane limits.
+pub fn custom_shadow_map_size(size: usize) -> usize {
+    size.clamp(16, 8192)
+}
@@ -39,18 +39,21 @@ use crate::{
             },
             server::GraphicsServer,
         },
-        shadow::cascade_size,
+        shadow::{cascade_size, custom_shadow_map_size},
         FallbackResources, GeometryCache, RenderPassStatistics, ShadowMapPrecision,
         POINT_SHADOW_PASS_NAME,
     },
     scene::graph::Graph,
 };
-use std::{cell::RefCell, rc::Rc};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc};
 
 pub struct PointShadowMapRenderer {
     precision: ShadowMapPrecision,
     cascades: [Box<dyn FrameBuffer>; 3],
     size: usize,
+    // Shadow maps of lights with custom shadow map size, created on demand.
+    custom: FxHashMap<usize, Box<dyn FrameBuffer>>,
     faces: [PointShadowCubeMapFace; 6],
 }
 
@@ -67,10 +70,54 @@ pub(crate) struct PointShadowMapRenderContext<'a> {
     pub light_radius: f32,
     pub geom_cache: &'a mut GeometryCache,
     pub cascade: usize,
+    pub custom_size: Option<usize>,
     pub shader_cache: &'a mut ShaderCache,
     pub texture_cache: &'a mut TextureCache,
     pub fallback_resources: &'a FallbackResources,
     pub uniform_memory_allocator: &'a mut UniformMemoryAllocator,
+    pub use_gpu_skinning: bool,
+}
+
+fn make_shadow_map(
+    server: &dyn GraphicsServer,
+    size: usize,
+    precision: ShadowMapPrecision,
+) -> Result<Box<dyn FrameBuffer>, FrameworkError> {
+    let depth = server.create_2d_render_target(
+        match precision {
+            ShadowMapPrecision::Full => PixelKind::D32F,
+            ShadowMapPrecision::Half => PixelKind::D16,
+        },
+        size,
+        size,
+    )?;
+
+    let cube_map = server.create_texture(GpuTextureDescriptor {
+        kind: GpuTextureKind::Cube {
+            width: size,
+            height: size,
+        },
+        pixel_kind: PixelKind::R16F,
+        min_filter: MinificationFilter::Nearest,
+        mag_filter: MagnificationFilter::Nearest,
+        mip_count: 1,
+        s_wrap_mode: WrapMode::ClampToEdge,
+        t_wrap_mode: WrapMode::ClampToEdge,
+        r_wrap_mode: WrapMode::ClampToEdge,
+        anisotropy: 1.0,
+        data: None,
+    })?;
+
+    server.create_frame_buffer(
+        Some(Attachment {
+            kind: AttachmentKind::Depth,
+            texture: depth,
+        }),
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: cube_map,
+        }],
+    )
 }
 
 impl PointShadowMapRenderer {
@@ -79,56 +126,15 @@ impl PointShadowMapRenderer {
         size: usize,
         precision: ShadowMapPrecision,
     ) -> Result<Self, FrameworkError> {
-        fn make_cascade(
-            server: &dyn GraphicsServer,
-            size: usize,
-            precision: ShadowMapPrecision,
-        ) -> Result<Box<dyn FrameBuffer>, FrameworkError> {
-            let depth = server.create_2d_render_target(
-                match precision {
-                    ShadowMapPrecision::Full => PixelKind::D32F,
-                    ShadowMapPrecision::Half => PixelKind::D16,
-                },
-                size,
-                size,
-            )?;
-
-            let cube_map = server.create_texture(GpuTextureDescriptor {
-                kind: GpuTextureKind::Cube {
-                    width: size,
-                    height: size,
-                },
-                pixel_kind: PixelKind::R16F,
-                min_filter: MinificationFilter::Nearest,
-                mag_filter: MagnificationFilter::Nearest,
-                mip_count: 1,
-                s_wrap_mode: WrapMode::ClampToEdge,
-                t_wrap_mode: WrapMode::ClampToEdge,
-                r_wrap_mode: WrapMode::ClampToEdge,
-                anisotropy: 1.0,
-                data: None,
-            })?;
-
-            server.create_frame_buffer(
-                Some(Attachment {
-                    kind: AttachmentKind::Depth,
-                    texture: depth,
-                }),
-                vec![Attachment {
-                    kind: AttachmentKind::Color,
-                    texture: cube_map,
-                }],
-            )
-        }
-
         Ok(Self {
             precision,
             cascades: [
-                make_cascade(server, cascade_size(size, 0), precision)?,
-                make_cascade(server, cascade_size(size, 1), precision)?,
-                make_cascade(server, cascade_size(size, 2), precision)?,
+                make_shadow_map(server, cascade_size(size, 0), precision)?,
+                make_shadow_map(server, cascade_size(size, 1), precision)?,
+                make_shadow_map(server, cascade_size(size, 2), precision)?,
             ],
             size,
+            custom: Default::default(),
             faces: [
                 PointShadowCubeMapFace {
                     face: CubeMapFace::PositiveX,
@@ -178,6 +184,29 @@ impl PointShadowMapRenderer {
             .clone()
     }
 
+    /// Returns the shadow map, that was used to render shadows of a light with the given
+    /// custom shadow map size. Falls back to the cascade if there's no custom size.
+    pub fn shadow_map_texture(
+        &self,
+        cascade: usize,
+        custom_size: Option<usize>,
+    ) -> Rc<RefCell<dyn GpuTexture>> {
+        match custom_size
+            .map(custom_shadow_map_size)
+            .and_then(|size| self.custom.get(&size))
+        {
+            Some(framebuffer) => framebuffer.color_attachments()[0].texture.clone(),
+            None => self.cascade_texture(cascade),
+        }
+    }
+
+    pub fn shadow_map_size(&self, cascade: usize, custom_size: Option<usize>) -> usize {
+        match custom_size.map(custom_shadow_map_size) {
+            Some(size) if self.custom.contains_key(&size) => size,
+            _ => cascade_size(self.size, cascade),
+        }
+    }
+
     pub(crate) fn render(
         &mut self,
         args: PointShadowMapRenderContext,
@@ -191,16 +220,31 @@ impl PointShadowMapRenderer {
             light_radius,
             geom_cache,
             cascade,
+            custom_size,
             shader_cache,
             texture_cache,
             fallback_resources,
             uniform_memory_allocator,
+            use_gpu_skinning,
         } = args;
 
-        let framebuffer = &mut *self.cascades[cascade];
-        let cascade_size = cascade_size(self.size, cascade);
+        let (framebuffer, shadow_map_size) = match custom_size.map(custom_shadow_map_size) {
+            Some(size) => {
+                let framebuffer = match self.custom.entry(size) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(make_shadow_map(state, size, self.precision)?)
+                    }
+                };
+                (&mut **framebuffer, size)
+            }
+            None => (
+                &mut *self.cascades[cascade],
+                cascade_size(self.size, cascade),
+            ),
+        };
 
-        let viewport = Rect::new(0, 0, cascade_size as i32, cascade_size as i32);
+        let viewport = Rect::new(0, 0, shadow_map_size as i32, shadow_map_size as i32);
 
         let z_near = 0.01;
         let z_far = light_radius;
@@ -230,6 +274,7 @@ impl PointShadowMapRenderer {
                 POINT_SHADOW_PASS_NAME.clone(),
                 RenderDataBundleStorageOptions {
                     collect_lights: false,
+                    use_gpu_skinning,
                 },
             );
 
@@ -36,13 +36,14 @@ use crate::{
             gpu_texture::{GpuTexture, PixelKind},
             server::GraphicsServer,
         },
-        shadow::cascade_size,
+        shadow::{cascade_size, custom_shadow_map_size},
         FallbackResources, GeometryCache, RenderPassStatistics, ShadowMapPrecision,
         SPOT_SHADOW_PASS_NAME,
     },
     scene::graph::Graph,
 };
-use std::{cell::RefCell, rc::Rc};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc};
 
 pub struct SpotShadowMapRenderer {
     precision: ShadowMapPrecision,
@@ -52,6 +53,31 @@ pub struct SpotShadowMapRenderer {
     //  2 - small, for farthest lights.
     cascades: [Box<dyn FrameBuffer>; 3],
     size: usize,
+    // Shadow maps of lights with custom shadow map size, created on demand.
+    custom: FxHashMap<usize, Box<dyn FrameBuffer>>,
+}
+
+fn make_shadow_map(
+    server: &dyn GraphicsServer,
+    size: usize,
+    precision: ShadowMapPrecision,
+) -> Result<Box<dyn FrameBuffer>, FrameworkError> {
+    let depth = server.create_2d_render_target(
+        match precision {
+            ShadowMapPrecision::Full => PixelKind::D32F,
+            ShadowMapPrecision::Half => PixelKind::D16,
+        },
+        size,
+        size,
+    )?;
+
+    server.create_frame_buffer(
+        Some(Attachment {
+            kind: AttachmentKind::Depth,
+            texture: depth,
+        }),
+        vec![],
+    )
 }
 
 impl SpotShadowMapRenderer {
@@ -60,37 +86,15 @@ impl SpotShadowMapRenderer {
         size: usize,
         precision: ShadowMapPrecision,
     ) -> Result<Self, FrameworkError> {
-        fn make_cascade(
-            server: &dyn GraphicsServer,
-            size: usize,
-            precision: ShadowMapPrecision,
-        ) -> Result<Box<dyn FrameBuffer>, FrameworkError> {
-            let depth = server.create_2d_render_target(
-                match precision {
-                    ShadowMapPrecision::Full => PixelKind::D32F,
-                    ShadowMapPrecision::Half => PixelKind::D16,
-                },
-                size,
-                size,
-            )?;
-
-            server.create_frame_buffer(
-                Some(Attachment {
-                    kind: AttachmentKind::Depth,
-                    texture: depth,
-                }),
-                vec![],
-            )
-        }
-
         Ok(Self {
             precision,
             size,
             cascades: [
-                make_cascade(server, cascade_size(size, 0), precision)?,
-                make_cascade(server, cascade_size(size, 1), precision)?,
-                make_cascade(server, cascade_size(size, 2), precision)?,
+                make_shadow_map(server, cascade_size(size, 0), precision)?,
+                make_shadow_map(server, cascade_size(size, 1), precision)?,
+                make_shadow_map(server, cascade_size(size, 2), precision)?,
             ],
+            custom: Default::default(),
         })
     }
 
@@ -114,6 +118,29 @@ impl SpotShadowMapRenderer {
         cascade_size(self.size, cascade)
     }
 
+    /// Returns the shadow map, that was used to render shadows of a light with the given
+    /// custom shadow map size. Falls back to the cascade if there's no custom size.
+    pub fn shadow_map_texture(
+        &self,
+        cascade: usize,
+        custom_size: Option<usize>,
+    ) -> Rc<RefCell<dyn GpuTexture>> {
+        match custom_size
+            .map(custom_shadow_map_size)
+            .and_then(|size| self.custom.get(&size))
+        {
+            Some(framebuffer) => framebuffer.depth_attachment().unwrap().texture.clone(),
+            None => self.cascade_texture(cascade),
+        }
+    }
+
+    pub fn shadow_map_size(&self, cascade: usize, custom_size: Option<usize>) -> usize {
+        match custom_size.map(custom_shadow_map_size) {
+            Some(size) if self.custom.contains_key(&size) => size,
+            _ => self.cascade_size(cascade),
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn render(
         &mut self,
@@ -126,17 +153,32 @@ impl SpotShadowMapRenderer {
         light_projection_matrix: Matrix4<f32>,
         geom_cache: &mut GeometryCache,
         cascade: usize,
+        custom_size: Option<usize>,
         shader_cache: &mut ShaderCache,
         texture_cache: &mut TextureCache,
         fallback_resources: &FallbackResources,
         uniform_memory_allocator: &mut UniformMemoryAllocator,
+        use_gpu_skinning: bool,
     ) -> Result<RenderPassStatistics, FrameworkError> {
         let mut statistics = RenderPassStatistics::default();
 
-        let framebuffer = &mut *self.cascades[cascade];
-        let cascade_size = cascade_size(self.size, cascade);
-
-        let viewport = Rect::new(0, 0, cascade_size as i32, cascade_size as i32);
+        let (framebuffer, shadow_map_size) = match custom_size.map(custom_shadow_map_size) {
+            Some(size) => {
+                let framebuffer = match self.custom.entry(size) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(make_shadow_map(server, size, self.precision)?)
+                    }
+                };
+                (&mut **framebuffer, size)
+            }
+            None => (
+                &mut *self.cascades[cascade],
+                cascade_size(self.size, cascade),
+            ),
+        };
+
+        let viewport = Rect::new(0, 0, shadow_map_size as i32, shadow_map_size as i32);
 
         framebuffer.clear(viewport, None, Some(1.0), None);
 
@@ -152,6 +194,7 @@ impl SpotShadowMapRenderer {
             SPOT_SHADOW_PASS_NAME.clone(),
             RenderDataBundleStorageOptions {
                 collect_lights: false,
+                use_gpu_skinning,
             },
         );
 
@@ -18,11 +18,14 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::renderer::framework::geometry_buffer::DrawCallStatistics;
+use crate::{
+    core::pool::Handle, renderer::framework::geometry_buffer::DrawCallStatistics, scene::Scene,
+};
 use fyrox_core::instant;
 pub use fyrox_graphics::stats::*;
 use std::fmt::{Display, Formatter};
 use std::ops::AddAssign;
+use std::time::Duration;
 
 /// Lighting statistics.
 #[derive(Debug, Copy, Clone, Default)]
@@ -39,6 +42,8 @@ pub struct LightingStatistics {
     pub spot_shadow_maps_rendered: usize,
     /// How many directional lights were rendered.
     pub directional_lights_rendered: usize,
+    /// Draw calls and triangles of every shadow map pass.
+    pub shadows: RenderPassStatistics,
 }
 
 impl AddAssign for LightingStatistics {
@@ -49,6 +54,7 @@ impl AddAssign for LightingStatistics {
         self.spot_shadow_maps_rendered += rhs.spot_shadow_maps_rendered;
         self.directional_lights_rendered += rhs.directional_lights_rendered;
         self.csm_rendered += rhs.csm_rendered;
+        self.shadows += rhs.shadows;
     }
 }
 
@@ -62,18 +68,51 @@ impl Display for LightingStatistics {
             \tDirectional Lights: {}\n\
             \tPoint Shadow Maps: {}\n\
             \tSpot Shadow Maps: {}\n\
-            \tSpot Shadow Maps: {}\n",
+            \tSpot Shadow Maps: {}\n\
+            \tShadow Draw Calls: {}\n\
+            \tShadow Triangles: {}\n",
             self.point_lights_rendered,
             self.spot_lights_rendered,
             self.directional_lights_rendered,
             self.point_shadow_maps_rendered,
             self.spot_shadow_maps_rendered,
-            self.csm_rendered
+            self.csm_rendered,
+            self.shadows.draw_calls,
+            self.shadows.triangles_rendered
         )
     }
 }
 
-/// Renderer statistics for a scene.
+/// Shows how many scene objects were tested against viewing frustums and how many of them were
+/// rejected.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CullingStatistics {
+    /// How many objects were tested against a viewing frustum.
+    pub tested_objects: usize,
+    /// How many objects were rejected, because they're outside of a viewing frustum.
+    pub culled_objects: usize,
+}
+
+impl AddAssign for CullingStatistics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.tested_objects += rhs.tested_objects;
+        self.culled_objects += rhs.culled_objects;
+    }
+}
+
+impl Display for CullingStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Culling Statistics:\n\
+            \tTested Objects: {}\n\
+            \tCulled Objects: {}\n",
+            self.tested_objects, self.culled_objects
+        )
+    }
+}
+
+/// Rendering statistics for a single scene.
 #[derive(Debug, Copy, Clone, Default)]
 pub struct SceneStatistics {
     /// Shows how many pipeline state changes was made during scene rendering.
@@ -82,6 +121,8 @@ pub struct SceneStatistics {
     pub lighting: LightingStatistics,
     /// Shows how many draw calls was made and how many triangles were rendered.
     pub geometry: RenderPassStatistics,
+    /// Shows how many objects were culled by main (camera) render passes.
+    pub culling: CullingStatistics,
 }
 
 impl Display for SceneStatistics {
@@ -90,12 +131,19 @@ impl Display for SceneStatistics {
             f,
             "{}\n\
             {}\n\
+            {}\n\
             {}\n",
-            self.geometry, self.lighting, self.pipeline
+            self.geometry, self.lighting, self.culling, self.pipeline
         )
     }
 }
 
+impl AddAssign<CullingStatistics> for SceneStatistics {
+    fn add_assign(&mut self, rhs: CullingStatistics) {
+        self.culling += rhs;
+    }
+}
+
 impl AddAssign<DrawCallStatistics> for SceneStatistics {
     fn add_assign(&mut self, rhs: DrawCallStatistics) {
         self.geometry += rhs;
@@ -120,9 +168,74 @@ impl AddAssign<LightingStatistics> for SceneStatistics {
     }
 }
 
+/// Statistics of a single render pass.
+#[derive(Debug, Clone, Default)]
+pub struct PassStatistics {
+    /// Name of the pass, for example `GBuffer` or `Lighting`.
+    pub name: &'static str,
+    /// Shows how many draw calls was made and how many triangles were rendered by the pass.
+    pub geometry: RenderPassStatistics,
+    /// Shows how many pipeline state changes (including texture binds) was made by the pass.
+    pub pipeline: PipelineStatistics,
+    /// CPU time spent to render the pass. GPU time of the passes could be measured by the frame
+    /// profiler, see [`crate::core::profiler`] module docs.
+    pub time: Duration,
+}
+
+impl Display for PassStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} ms, {} draw calls, {} triangles, {} texture binds, {} shader changes",
+            self.name,
+            self.time.as_secs_f32() * 1000.0,
+            self.geometry.draw_calls,
+            self.geometry.triangles_rendered,
+            self.pipeline.texture_binding_changes,
+            self.pipeline.program_binding_changes
+        )
+    }
+}
+
+/// Rendering statistics of a scene with the breakdown by render passes.
+#[derive(Debug, Clone, Default)]
+pub struct SceneStatisticsBreakdown {
+    /// A handle of the scene.
+    pub scene: Handle<Scene>,
+    /// Total statistics of the scene.
+    pub statistics: SceneStatistics,
+    /// Statistics of every render pass of the scene. Passes with the same name (for example,
+    /// rendered by different cameras) are merged.
+    pub passes: Vec<PassStatistics>,
+    /// CPU time spent to render the scene.
+    pub time: Duration,
+}
+
+impl SceneStatisticsBreakdown {
+    /// Returns statistics of a render pass with the given name.
+    pub fn pass(&self, name: &str) -> Option<&PassStatistics> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+}
+
+impl Display for SceneStatisticsBreakdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Scene {}: {:.3} ms",
+            self.scene,
+            self.time.as_secs_f32() * 1000.0
+        )?;
+        for pass in self.passes.iter() {
+            writeln!(f, "\t{pass}")?;
+        }
+        Ok(())
+    }
+}
+
 /// Renderer statistics for one frame, also includes current frames per second
 /// amount.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Statistics {
     /// Shows how many pipeline state changes was made per frame.
     pub pipeline: PipelineStatistics,
@@ -130,6 +243,8 @@ pub struct Statistics {
     pub lighting: LightingStatistics,
     /// Shows how many draw calls was made and how many triangles were rendered.
     pub geometry: RenderPassStatistics,
+    /// Shows how many objects were culled by main (camera) render passes.
+    pub culling: CullingStatistics,
     /// Real time consumed to render frame. Time given in **seconds**.
     pub pure_frame_time: f32,
     /// Total time renderer took to process single frame, usually includes
@@ -146,16 +261,35 @@ pub struct Statistics {
     pub shader_cache_size: usize,
     /// Total amount of uniform buffers in the cache.
     pub uniform_buffer_cache_size: usize,
+    /// Detailed statistics of every rendered scene.
+    pub scenes: Vec<SceneStatisticsBreakdown>,
+    /// Statistics of the render passes, that are not related to any scene (for example, UI
+    /// rendering).
+    pub passes: Vec<PassStatistics>,
     pub(super) frame_counter: usize,
     pub(super) frame_start_time: instant::Instant,
     pub(super) last_fps_commit_time: instant::Instant,
 }
 
+impl Statistics {
+    /// Returns detailed statistics of the given scene, if it was rendered in the last frame.
+    pub fn scene(&self, scene: Handle<Scene>) -> Option<&SceneStatisticsBreakdown> {
+        self.scenes.iter().find(|entry| entry.scene == scene)
+    }
+
+    /// Returns statistics of a render pass with the given name, that is not related to any
+    /// scene.
+    pub fn pass(&self, name: &str) -> Option<&PassStatistics> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+}
+
 impl std::ops::AddAssign<SceneStatistics> for Statistics {
     fn add_assign(&mut self, rhs: SceneStatistics) {
         self.pipeline += rhs.pipeline;
         self.lighting += rhs.lighting;
         self.geometry += rhs.geometry;
+        self.culling += rhs.culling;
     }
 }
 
@@ -166,6 +300,7 @@ impl Display for Statistics {
         let capped_frame_time = self.capped_frame_time * 1000.0;
         let geometry_stats = &self.geometry;
         let lighting_stats = &self.lighting;
+        let culling_stats = &self.culling;
         let pipeline_stats = &self.pipeline;
         let texture_cache_size = self.texture_cache_size;
         let geometry_cache_size = self.geometry_cache_size;
@@ -178,12 +313,20 @@ impl Display for Statistics {
             Capped Frame Time: {capped_frame_time:.2} ms\n\
             {geometry_stats}\n\
             {lighting_stats}\n\
+            {culling_stats}\n\
             {pipeline_stats}\n\
             Texture Cache Size: {texture_cache_size}\n\
             Geometry Cache Size: {geometry_cache_size}\n\
             Shader Cache Size: {shader_cache_size}\n
             Uniform Buffer Cache Size: {uniform_buffer_cache_size}\n",
-        )
+        )?;
+        for scene in self.scenes.iter() {
+            write!(f, "{scene}")?;
+        }
+        for pass in self.passes.iter() {
+            writeln!(f, "{pass}")?;
+        }
+        Ok(())
     }
 }
 
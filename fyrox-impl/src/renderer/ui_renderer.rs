@@ -22,16 +22,20 @@
 
 use crate::renderer::FallbackResources;
 use crate::{
-    asset::untyped::ResourceKind,
+    asset::untyped::{ResourceHeader, ResourceKind, UntypedResource},
     core::{
         algebra::{Matrix4, Vector2, Vector4},
         color::Color,
         math::Rect,
+        parking_lot::Mutex,
+        rectpack::RectPacker,
         sstorage::ImmutableString,
     },
+    fxhash::FxHashMap,
     gui::{
         brush::Brush,
-        draw::{CommandTexture, DrawingContext},
+        draw::{CommandTexture, DrawingContext, Vertex},
+        font::{FontHeight, FontResource},
     },
     renderer::{
         cache::uniform::UniformBufferCache,
@@ -45,6 +49,7 @@ use crate::{
                 VertexBufferData, VertexBufferDescriptor,
             },
             gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
             server::GraphicsServer,
             uniform::StaticUniformBuffer,
             BlendFactor, BlendFunc, BlendParameters, ColorMask, CompareFunc, DrawParameters,
@@ -52,9 +57,19 @@ use crate::{
         },
         RenderPassStatistics, TextureCache,
     },
-    resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
+    resource::texture::{
+        Texture, TextureKind, TextureMagnificationFilter, TextureMinificationFilter,
+        TexturePixelKind, TextureResource,
+    },
 };
 use fyrox_graphics::framebuffer::BufferLocation;
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    ops::Range,
+    rc::Rc,
+    sync::{Arc, Weak},
+};
 
 struct UiShader {
     program: Box<dyn GpuProgram>,
@@ -75,11 +90,280 @@ impl UiShader {
     }
 }
 
+/// Size (in pixels) of a side of the texture atlas for widget images.
+const ATLAS_SIZE: usize = 2048;
+/// Max size (in pixels) of a side of an image, that could be put in the texture atlas. Larger
+/// images are drawn using their own textures.
+const MAX_ATLAS_IMAGE_SIZE: u32 = 256;
+/// Amount of frames an image could stay unused, before it could be evicted from the full atlas.
+/// It is also the minimal interval (in frames) between compactions of the atlas.
+const ATLAS_EVICTION_FRAMES: u64 = 120;
+
+struct AtlasEntry {
+    // Does not keep the texture alive. The address of the texture (which is used as a key) could
+    // be reused by some other texture only when this one is dead, so a live reference here means
+    // that the key is still valid.
+    texture: Weak<Mutex<ResourceHeader>>,
+    modifications_count: u64,
+    bounds: Rect<usize>,
+    last_used_frame: u64,
+}
+
+impl AtlasEntry {
+    fn tex_coords_rect(&self) -> Rect<f32> {
+        let scale = 1.0 / ATLAS_SIZE as f32;
+        Rect::new(
+            self.bounds.x() as f32 * scale,
+            self.bounds.y() as f32 * scale,
+            self.bounds.w() as f32 * scale,
+            self.bounds.h() as f32 * scale,
+        )
+    }
+}
+
+/// Texture atlas for small images of widgets. It allows the renderer to draw widgets with different
+/// images using a single draw call. Images are put in the atlas on demand. When the atlas is full,
+/// images that weren't used for [`ATLAS_EVICTION_FRAMES`] frames (or were destroyed) are evicted
+/// and the rest of the images are packed again. If the images in use do not fit in the atlas, the
+/// atlas is left as is and the rest of the images are drawn using their own textures.
+struct UiTextureAtlas {
+    texture: TextureResource,
+    packer: RectPacker<usize>,
+    entries: FxHashMap<usize, AtlasEntry>,
+    is_full: bool,
+    frame: u64,
+    last_compaction_frame: u64,
+}
+
+impl UiTextureAtlas {
+    fn new() -> Self {
+        let mut texture = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: ATLAS_SIZE as u32,
+                height: ATLAS_SIZE as u32,
+            },
+            TexturePixelKind::RGBA8,
+            vec![0; ATLAS_SIZE * ATLAS_SIZE * 4],
+        )
+        .unwrap();
+        texture.set_minification_filter(TextureMinificationFilter::Linear);
+
+        Self {
+            texture: TextureResource::new_ok(ResourceKind::Embedded, texture),
+            packer: RectPacker::new(ATLAS_SIZE, ATLAS_SIZE),
+            entries: Default::default(),
+            is_full: false,
+            frame: 0,
+            last_compaction_frame: 0,
+        }
+    }
+
+    fn begin_frame(&mut self) {
+        self.frame += 1;
+        if self.is_full && self.frame - self.last_compaction_frame >= ATLAS_EVICTION_FRAMES {
+            self.compact();
+        }
+    }
+
+    /// Evicts unused images and packs the rest of the images again, moving their pixels to the new
+    /// places. Does nothing if there's nothing to evict.
+    fn compact(&mut self) {
+        let frame = self.frame;
+        self.last_compaction_frame = frame;
+
+        let count = self.entries.len();
+        self.entries.retain(|_, entry| {
+            entry.texture.strong_count() > 0
+                && frame - entry.last_used_frame < ATLAS_EVICTION_FRAMES
+        });
+        if self.entries.len() == count {
+            return;
+        }
+
+        let mut atlas = self.texture.data_ref();
+        let mut atlas = atlas.modify();
+        let pixels = atlas.data_mut();
+        let old_pixels = pixels.to_vec();
+
+        // Large images first, it makes packing tighter.
+        let mut keys = self.entries.keys().cloned().collect::<Vec<_>>();
+        keys.sort_by_key(|key| Reverse(self.entries[key].bounds.h()));
+
+        self.packer = RectPacker::new(ATLAS_SIZE, ATLAS_SIZE);
+        for key in keys {
+            let entry = self.entries.get_mut(&key).unwrap();
+            let (width, height) = (entry.bounds.w() + 2, entry.bounds.h() + 2);
+            let Some(bounds) = self.packer.find_free(width, height) else {
+                self.entries.remove(&key);
+                continue;
+            };
+
+            // Copy the image together with its border.
+            for y in 0..height {
+                let src = ((entry.bounds.y() + y - 1) * ATLAS_SIZE + entry.bounds.x() - 1) * 4;
+                let dest = ((bounds.y() + y) * ATLAS_SIZE + bounds.x()) * 4;
+                pixels[dest..dest + width * 4].copy_from_slice(&old_pixels[src..src + width * 4]);
+            }
+
+            entry.bounds = Rect::new(
+                bounds.x() + 1,
+                bounds.y() + 1,
+                entry.bounds.w(),
+                entry.bounds.h(),
+            );
+        }
+
+        self.is_full = false;
+    }
+
+    /// Tries to put the given texture in the atlas and returns the area (in normalized texture
+    /// coordinates) it occupies in the atlas.
+    fn get_or_insert(&mut self, resource: &UntypedResource) -> Option<Rect<f32>> {
+        let texture_resource = resource.try_cast::<Texture>()?;
+        let mut state = texture_resource.state();
+        let texture = state.data()?;
+
+        let TextureKind::Rectangle { width, height } = texture.kind() else {
+            return None;
+        };
+        let (width, height) = (width as usize, height as usize);
+        if width > MAX_ATLAS_IMAGE_SIZE as usize
+            || height > MAX_ATLAS_IMAGE_SIZE as usize
+            || texture.pixel_kind() != TexturePixelKind::RGBA8
+            || texture.is_render_target()
+            // Pixel-art images must stay crisp.
+            || texture.magnification_filter() != TextureMagnificationFilter::Linear
+            || texture.data().len() < width * height * 4
+        {
+            return None;
+        }
+
+        let key = resource.key();
+        if self
+            .entries
+            .get(&key)
+            .is_some_and(|entry| entry.texture.strong_count() == 0)
+        {
+            // The key belongs to a destroyed texture. Its space will be reclaimed on compaction.
+            self.entries.remove(&key);
+        }
+
+        let modifications_count = texture.modifications_count();
+        let bounds = match self.entries.get_mut(&key) {
+            Some(entry) if entry.modifications_count == modifications_count => {
+                entry.last_used_frame = self.frame;
+                return Some(entry.tex_coords_rect());
+            }
+            Some(entry) if entry.bounds.w() == width && entry.bounds.h() == height => entry.bounds,
+            _ => {
+                if self.is_full {
+                    return None;
+                }
+                // One pixel border around each image prevents bleeding of neighbouring images
+                // when filtering is used.
+                let Some(bounds) = self.packer.find_free(width + 2, height + 2) else {
+                    self.is_full = true;
+                    return None;
+                };
+                Rect::new(bounds.x() + 1, bounds.y() + 1, width, height)
+            }
+        };
+
+        let source = texture.data();
+        let mut atlas = self.texture.data_ref();
+        let mut atlas = atlas.modify();
+        let pixels = atlas.data_mut();
+        for y in 0..height + 2 {
+            let source_y = y.saturating_sub(1).min(height - 1);
+            for x in 0..width + 2 {
+                let source_x = x.saturating_sub(1).min(width - 1);
+                let src = (source_y * width + source_x) * 4;
+                let dest = ((bounds.y() + y - 1) * ATLAS_SIZE + bounds.x() + x - 1) * 4;
+                pixels[dest..dest + 4].copy_from_slice(&source[src..src + 4]);
+            }
+        }
+
+        let entry = AtlasEntry {
+            texture: Arc::downgrade(&resource.0),
+            modifications_count,
+            bounds,
+            last_used_frame: self.frame,
+        };
+        let tex_coords_rect = entry.tex_coords_rect();
+        self.entries.insert(key, entry);
+        Some(tex_coords_rect)
+    }
+}
+
+/// A texture of a command, that is used to batch commands.
+#[derive(Clone)]
+struct CommandGpuTexture {
+    texture: Rc<RefCell<dyn GpuTexture>>,
+    is_font: bool,
+}
+
+impl PartialEq for CommandGpuTexture {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.texture, &other.texture) && self.is_font == other.is_font
+    }
+}
+
+fn font_page_texture<'a>(
+    server: &dyn GraphicsServer,
+    texture_cache: &'a mut TextureCache,
+    font: &FontResource,
+    height: &FontHeight,
+    page_index: usize,
+) -> Option<&'a Rc<RefCell<dyn GpuTexture>>> {
+    let mut font = font.state();
+    let font = font.data()?;
+    let page_size = font.page_size() as u32;
+    let page = font
+        .atlases
+        .get_mut(height)
+        .and_then(|atlas| atlas.pages.get_mut(page_index))?;
+    if page.texture.is_none() || page.modified {
+        if let Some(details) = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: page_size,
+                height: page_size,
+            },
+            TexturePixelKind::R8,
+            page.pixels.clone(),
+        ) {
+            page.texture = Some(TextureResource::new_ok(ResourceKind::Embedded, details).into());
+            page.modified = false;
+        }
+    }
+    texture_cache.get(server, &page.texture.as_ref()?.try_cast::<Texture>()?)
+}
+
+fn vertex_range(drawing_context: &DrawingContext, triangles: Range<usize>) -> Range<usize> {
+    let mut min = usize::MAX;
+    let mut max = 0;
+    for triangle in &drawing_context.get_triangles()[triangles] {
+        for &index in triangle.as_ref() {
+            min = min.min(index as usize);
+            max = max.max(index as usize);
+        }
+    }
+    if min > max {
+        0..0
+    } else {
+        min..max + 1
+    }
+}
+
 /// User interface renderer allows you to render drawing context in specified render target.
+/// Consecutive commands with the same texture and compatible clipping are drawn using a single
+/// draw call, small images of widgets are packed into a texture atlas to increase the amount of
+/// such commands.
 pub struct UiRenderer {
     shader: UiShader,
     geometry_buffer: Box<dyn GeometryBuffer>,
     clipping_geometry_buffer: Box<dyn GeometryBuffer>,
+    atlas: UiTextureAtlas,
+    vertices: Vec<Vertex>,
 }
 
 /// A set of parameters to render a specified user interface drawing context.
@@ -162,6 +446,8 @@ impl UiRenderer {
             clipping_geometry_buffer: server
                 .create_geometry_buffer(clipping_geometry_buffer_desc)?,
             shader: UiShader::new(server)?,
+            atlas: UiTextureAtlas::new(),
+            vertices: Default::default(),
         })
     }
 
@@ -185,19 +471,82 @@ impl UiRenderer {
 
         let mut statistics = RenderPassStatistics::default();
 
+        self.atlas.begin_frame();
+
+        self.vertices.clear();
+        self.vertices
+            .extend_from_slice(drawing_context.get_vertices());
+
+        // Find textures of every command first. Images, that were put in the atlas, require their
+        // texture coordinates to be remapped to the atlas space.
+        let mut textures = Vec::with_capacity(drawing_context.get_commands().len());
+        for cmd in drawing_context.get_commands() {
+            let mut texture = CommandGpuTexture {
+                texture: fallback_resources.white_dummy.clone(),
+                is_font: false,
+            };
+
+            match &cmd.texture {
+                CommandTexture::Font {
+                    font,
+                    page_index,
+                    height,
+                } => {
+                    if let Some(page_texture) =
+                        font_page_texture(server, texture_cache, font, height, *page_index)
+                    {
+                        texture.texture = page_texture.clone();
+                    }
+                    texture.is_font = true;
+                }
+                CommandTexture::Texture(resource) => {
+                    let vertices = vertex_range(drawing_context, cmd.triangles.clone());
+                    // Repeating images can't be put in the atlas.
+                    let is_atlas_compatible = self.vertices[vertices.clone()]
+                        .iter()
+                        .all(|v| v.tex_coord.iter().all(|c| (0.0..=1.0).contains(c)));
+                    let atlas_rect = if is_atlas_compatible {
+                        self.atlas.get_or_insert(resource)
+                    } else {
+                        None
+                    };
+
+                    if let Some(atlas_rect) = atlas_rect {
+                        for vertex in &mut self.vertices[vertices] {
+                            vertex.tex_coord = atlas_rect.position
+                                + vertex.tex_coord.component_mul(&atlas_rect.size);
+                        }
+                        if let Some(atlas_texture) = texture_cache.get(server, &self.atlas.texture)
+                        {
+                            texture.texture = atlas_texture.clone();
+                        }
+                    } else if let Some(resource) = resource.try_cast::<Texture>() {
+                        if let Some(gpu_texture) = texture_cache.get(server, &resource) {
+                            texture.texture = gpu_texture.clone();
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            textures.push(texture);
+        }
+
         self.geometry_buffer
-            .set_buffer_data_of_type(0, drawing_context.get_vertices());
+            .set_buffer_data_of_type(0, &self.vertices);
         self.geometry_buffer
             .set_triangles(drawing_context.get_triangles());
 
         let ortho = Matrix4::new_orthographic(0.0, frame_width, frame_height, 0.0, -1.0, 1.0);
         let resolution = Vector2::new(frame_width, frame_height);
 
-        for cmd in drawing_context.get_commands() {
-            let mut diffuse_texture = &fallback_resources.white_dummy;
-            let mut is_font_texture = false;
+        for batch in drawing_context.batches(&textures) {
+            // Batches with multiple commands contain only commands with solid brushes, which colors
+            // are baked into vertices, so it is enough to use the first command of a batch.
+            let cmd = &drawing_context.get_commands()[batch.commands.start];
+            let texture = &textures[batch.commands.start];
 
-            let mut clip_bounds = cmd.clip_bounds;
+            let mut clip_bounds = batch.clip_bounds;
             clip_bounds.position.x = clip_bounds.position.x.floor();
             clip_bounds.position.y = clip_bounds.position.y.floor();
             clip_bounds.size.x = clip_bounds.size.x.ceil();
@@ -264,60 +613,6 @@ impl UiRenderer {
                 });
             }
 
-            match &cmd.texture {
-                CommandTexture::Font {
-                    font,
-                    page_index,
-                    height,
-                } => {
-                    if let Some(font) = font.state().data() {
-                        let page_size = font.page_size() as u32;
-                        if let Some(page) = font
-                            .atlases
-                            .get_mut(height)
-                            .and_then(|atlas| atlas.pages.get_mut(*page_index))
-                        {
-                            if page.texture.is_none() || page.modified {
-                                if let Some(details) = Texture::from_bytes(
-                                    TextureKind::Rectangle {
-                                        width: page_size,
-                                        height: page_size,
-                                    },
-                                    TexturePixelKind::R8,
-                                    page.pixels.clone(),
-                                ) {
-                                    page.texture = Some(
-                                        TextureResource::new_ok(ResourceKind::Embedded, details)
-                                            .into(),
-                                    );
-                                    page.modified = false;
-                                }
-                            }
-                            if let Some(texture) = texture_cache.get(
-                                server,
-                                &page
-                                    .texture
-                                    .as_ref()
-                                    .unwrap()
-                                    .try_cast::<Texture>()
-                                    .unwrap(),
-                            ) {
-                                diffuse_texture = texture;
-                            }
-                            is_font_texture = true;
-                        }
-                    }
-                }
-                CommandTexture::Texture(texture) => {
-                    if let Some(resource) = texture.try_cast::<Texture>() {
-                        if let Some(texture) = texture_cache.get(server, &resource) {
-                            diffuse_texture = texture;
-                        }
-                    }
-                }
-                _ => (),
-            }
-
             let mut raw_stops = [0.0; 16];
             let mut raw_colors = [Vector4::default(); 16];
             let bounds_max = cmd.bounds.right_bottom_corner();
@@ -342,9 +637,11 @@ impl UiRenderer {
                 scissor_box,
             };
 
-            let solid_color = match cmd.brush {
-                Brush::Solid(color) => color,
-                _ => Color::WHITE,
+            // Color and opacity of solid brushes are baked into vertices.
+            let solid_color = Color::WHITE;
+            let opacity = match cmd.brush {
+                Brush::Solid(_) => 1.0,
+                _ => cmd.opacity,
             };
             let gradient_colors = match cmd.brush {
                 Brush::Solid(_) => &raw_colors,
@@ -388,8 +685,8 @@ impl UiRenderer {
                     .with(&resolution)
                     .with(&cmd.bounds.position)
                     .with(&bounds_max)
-                    .with(&is_font_texture)
-                    .with(&cmd.opacity)
+                    .with(&texture.is_font)
+                    .with(&opacity)
                     .with(&brush_type)
                     .with(&gradient_point_count),
             )?;
@@ -402,7 +699,7 @@ impl UiRenderer {
                 &params,
                 &[ResourceBindGroup {
                     bindings: &[
-                        ResourceBinding::texture(diffuse_texture, &shader.diffuse_texture),
+                        ResourceBinding::texture(&texture.texture, &shader.diffuse_texture),
                         ResourceBinding::Buffer {
                             buffer: uniform_buffer,
                             binding: BufferLocation::Auto {
@@ -413,8 +710,8 @@ impl UiRenderer {
                     ],
                 }],
                 ElementRange::Specific {
-                    offset: cmd.triangles.start,
-                    count: cmd.triangles.end - cmd.triangles.start,
+                    offset: batch.triangles.start,
+                    count: batch.triangles.end - batch.triangles.start,
                 },
             )?;
         }
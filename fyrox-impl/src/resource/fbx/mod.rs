@@ -80,6 +80,7 @@ use crate::{
 use fxhash::{FxHashMap, FxHashSet};
 use fyrox_resource::io::ResourceIo;
 use fyrox_resource::untyped::ResourceKind;
+use rayon::prelude::*;
 use std::{cmp::Ordering, path::Path};
 
 /// Input angles in degrees
@@ -280,9 +281,23 @@ fn make_blend_shapes_container(
     }
 }
 
+/// Geometry of a surface, that is converted to the engine's representation.
+struct ConvertedSurfaceData {
+    data: SurfaceData,
+    skin_data: Vec<VertexWeightSet>,
+}
+
+/// Converted geometry of a mesh model. Geometry conversion depends only on the FBX scene, so it is
+/// done for all meshes of a model in parallel.
+struct ConvertedMeshGeometry {
+    // A set of surfaces per each geometry of the model.
+    geoms: Vec<Vec<ConvertedSurfaceData>>,
+    blend_shapes: Vec<BlendShape>,
+}
+
 async fn create_surfaces(
     fbx_scene: &FbxScene,
-    data_set: Vec<FbxSurfaceData>,
+    data_set: Vec<ConvertedSurfaceData>,
     resource_manager: ResourceManager,
     model: &FbxModel,
     model_path: &Path,
@@ -294,27 +309,22 @@ async fn create_surfaces(
     if model.materials.is_empty() {
         assert_eq!(data_set.len(), 1);
         let data = data_set.into_iter().next().unwrap();
-        let mut surface_data = data.base_mesh_builder.build();
-        surface_data.blend_shapes_container =
-            make_blend_shapes_container(&surface_data.vertex_buffer, data.blend_shapes);
         let mut surface = Surface::new(SurfaceResource::new_ok(
             ResourceKind::External(model_path.to_path_buf()),
-            surface_data,
+            data.data,
         ));
         surface.vertex_weights = data.skin_data;
         surfaces.push(surface);
     } else {
         assert_eq!(data_set.len(), model.materials.len());
         for (&material_handle, data) in model.materials.iter().zip(data_set.into_iter()) {
-            let mut surface_data = data.base_mesh_builder.build();
-            surface_data.blend_shapes_container =
-                make_blend_shapes_container(&surface_data.vertex_buffer, data.blend_shapes);
             let mut surface = Surface::new(SurfaceResource::new_ok(
                 ResourceKind::External(model_path.to_path_buf()),
-                surface_data,
+                data.data,
             ));
             surface.vertex_weights = data.skin_data;
             let material = fbx_scene.get(material_handle).as_material()?;
+            surface.set_name(material.name.clone());
             surface
                 .material()
                 .data_ref()
@@ -451,15 +461,10 @@ async fn create_surfaces(
     Ok(surfaces)
 }
 
-async fn convert_mesh(
-    base: BaseBuilder,
+fn convert_mesh_geometry(
     fbx_scene: &FbxScene,
-    resource_manager: ResourceManager,
     model: &FbxModel,
-    graph: &mut Graph,
-    model_path: &Path,
-    model_import_options: &ModelImportOptions,
-) -> Result<Handle<Node>, FbxError> {
+) -> Result<ConvertedMeshGeometry, FbxError> {
     let geometric_transform = Matrix4::new_translation(&model.geometric_translation)
         * quat_from_euler(model.geometric_rotation).to_homogeneous()
         * Matrix4::new_nonuniform_scaling(&model.geometric_scale);
@@ -471,7 +476,7 @@ async fn convert_mesh(
     // triangulated polygon.
     let mut face_triangles = Vec::new();
 
-    let mut mesh_surfaces = Vec::new();
+    let mut geoms = Vec::new();
     let mut mesh_blend_shapes = Vec::new();
 
     for &geom_handle in &model.geoms {
@@ -598,7 +603,46 @@ async fn convert_mesh(
             }
         }
 
-        let mut surfaces = create_surfaces(
+        geoms.push(
+            data_set
+                .into_iter()
+                .map(|data| {
+                    let mut surface_data = data.base_mesh_builder.build();
+                    surface_data.blend_shapes_container =
+                        make_blend_shapes_container(&surface_data.vertex_buffer, data.blend_shapes);
+                    if geom.tangents.is_none() {
+                        surface_data.calculate_tangents().unwrap();
+                    }
+                    ConvertedSurfaceData {
+                        data: surface_data,
+                        skin_data: data.skin_data,
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    Ok(ConvertedMeshGeometry {
+        geoms,
+        blend_shapes: mesh_blend_shapes,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn convert_mesh(
+    base: BaseBuilder,
+    fbx_scene: &FbxScene,
+    resource_manager: ResourceManager,
+    model: &FbxModel,
+    geometry: ConvertedMeshGeometry,
+    graph: &mut Graph,
+    model_path: &Path,
+    model_import_options: &ModelImportOptions,
+) -> Result<Handle<Node>, FbxError> {
+    let mut mesh_surfaces = Vec::new();
+
+    for data_set in geometry.geoms {
+        let surfaces = create_surfaces(
             fbx_scene,
             data_set,
             resource_manager.clone(),
@@ -608,19 +652,11 @@ async fn convert_mesh(
         )
         .await?;
 
-        if geom.tangents.is_none() {
-            for surface in surfaces.iter_mut() {
-                surface.data().data_ref().calculate_tangents().unwrap();
-            }
-        }
-
-        for surface in surfaces {
-            mesh_surfaces.push(surface);
-        }
+        mesh_surfaces.extend(surfaces);
     }
 
     Ok(MeshBuilder::new(base)
-        .with_blend_shapes(mesh_blend_shapes)
+        .with_blend_shapes(geometry.blend_shapes)
         .with_surfaces(mesh_surfaces)
         .build(graph))
 }
@@ -644,9 +680,11 @@ fn convert_model_to_base(model: &FbxModel) -> BaseBuilder {
         )
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn convert_model(
     fbx_scene: &FbxScene,
     model: &FbxModel,
+    geometry: Option<ConvertedMeshGeometry>,
     resource_manager: ResourceManager,
     graph: &mut Graph,
     animation: &mut Animation,
@@ -656,12 +694,13 @@ async fn convert_model(
     let base = convert_model_to_base(model);
 
     // Create node with correct kind.
-    let node_handle = if !model.geoms.is_empty() {
+    let node_handle = if let Some(geometry) = geometry {
         convert_mesh(
             base,
             fbx_scene,
             resource_manager,
             model,
+            geometry,
             graph,
             model_path,
             model_import_options,
@@ -810,12 +849,26 @@ async fn convert(
     let mut animation = Animation::default();
     animation.set_name("Animation");
 
+    // Geometry conversion is the most expensive part of the loading, convert geometry of every
+    // mesh in parallel first.
+    let mut geometries = fbx_scene
+        .pair_iter()
+        .filter_map(|(handle, component)| match component {
+            FbxComponent::Model(model) if !model.geoms.is_empty() => Some((handle, model)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(handle, model)| Ok((handle, convert_mesh_geometry(fbx_scene, model)?)))
+        .collect::<Result<FxHashMap<_, _>, FbxError>>()?;
+
     let mut fbx_model_to_node_map = FxHashMap::default();
     for (component_handle, component) in fbx_scene.pair_iter() {
         if let FbxComponent::Model(model) = component {
             let node = convert_model(
                 fbx_scene,
                 model,
+                geometries.remove(&component_handle),
                 resource_manager.clone(),
                 &mut scene.graph,
                 &mut animation,
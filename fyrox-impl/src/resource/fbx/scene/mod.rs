@@ -369,6 +369,7 @@ impl FbxCluster {
 }
 
 pub struct FbxMaterial {
+    pub name: String,
     pub textures: Vec<(String, Handle<FbxComponent>)>,
     pub diffuse_color: Color,
 }
@@ -380,6 +381,16 @@ impl FbxMaterial {
     ) -> Result<FbxMaterial, FbxError> {
         let mut diffuse_color = Color::WHITE;
 
+        let mut name = String::new();
+        if let Ok(name_attrib) = nodes.get(material_node_handle).get_attrib(1) {
+            name = name_attrib.as_string();
+        }
+
+        // Remove prefix
+        if name.starts_with("Material::") {
+            name = name.chars().skip(10).collect();
+        }
+
         let props = nodes.get_by_name(material_node_handle, "Properties70")?;
         for prop_handle in props.children() {
             let prop = nodes.get(*prop_handle);
@@ -392,6 +403,7 @@ impl FbxMaterial {
         }
 
         Ok(FbxMaterial {
+            name,
             textures: Default::default(),
             diffuse_color,
         })
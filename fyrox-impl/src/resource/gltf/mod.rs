@@ -559,6 +559,9 @@ fn import_surface(
             data,
         ));
         if let Some(mat_index) = prim.material().index() {
+            if let Some(name) = prim.material().name() {
+                surf.set_name(name.to_owned());
+            }
             surf.set_material(
                 mats.get(mat_index)
                     .ok_or(GltfLoadError::InvalidIndex)?
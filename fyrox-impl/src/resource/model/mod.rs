@@ -709,6 +709,28 @@ impl Model {
         Self { mapping, scene }
     }
 
+    /// Creates a new prefab from the given sub-graphs of a scene graph. Every node in `roots` is copied
+    /// (with all its descendants) into a new scene and attached to its root. Nodes that are instances
+    /// of other prefabs keep their links to their prefabs, which makes it possible to create nested
+    /// prefabs. The result can be saved to a file (using [`ResourceData::save`]) and then loaded and
+    /// instantiated in any other scene as usual. Property overrides of the instances (modified
+    /// [`InheritableVariable`]s) survive further edits of the prefab.
+    ///
+    /// Keep in mind, that references to the nodes outside the copied hierarchies won't be preserved.
+    pub fn from_subgraph(graph: &Graph, roots: &[Handle<Node>]) -> Self {
+        let mut scene = Scene::new();
+        for &root in roots {
+            graph.copy_node(
+                root,
+                &mut scene.graph,
+                &mut |_, _| true,
+                &mut |_, _| {},
+                &mut |_, _, _| {},
+            );
+        }
+        Self::new(NodeMapping::UseHandles, scene)
+    }
+
     pub(crate) async fn load<P: AsRef<Path>>(
         path: P,
         io: &dyn ResourceIo,
@@ -785,3 +807,66 @@ impl Model {
         &mut self.scene
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        graph::BaseSceneGraph,
+        resource::model::Model,
+        scene::{
+            base::BaseBuilder, graph::Graph, node::Node, pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn pivot(graph: &mut Graph, name: &str, position: Vector3<f32>) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new().with_name(name).with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(graph)
+    }
+
+    #[test]
+    fn test_model_from_subgraph() {
+        let mut graph = Graph::new();
+        let root = pivot(&mut graph, "Root", Vector3::new(1.0, 0.0, 0.0));
+        let child = pivot(&mut graph, "Child", Vector3::new(0.0, 2.0, 0.0));
+        let grandchild = pivot(&mut graph, "Grandchild", Vector3::new(0.0, 0.0, 3.0));
+        pivot(&mut graph, "Other", Vector3::new(5.0, 5.0, 5.0));
+        graph.link_nodes(child, root);
+        graph.link_nodes(grandchild, child);
+
+        let model = Model::from_subgraph(&graph, &[root]);
+        let prefab_graph = &model.get_scene().graph;
+
+        // Scene root + the copied hierarchy, the unrelated node must not be copied.
+        assert_eq!(prefab_graph.node_count(), 4);
+        assert!(model.find_node_by_name("Other").is_none());
+
+        let (prefab_root, prefab_root_ref) = model.find_node_by_name("Root").unwrap();
+        assert_eq!(prefab_root_ref.parent(), prefab_graph.get_root());
+        assert_eq!(
+            **prefab_root_ref.local_transform().position(),
+            Vector3::new(1.0, 0.0, 0.0)
+        );
+
+        let (prefab_child, prefab_child_ref) = model.find_node_by_name("Child").unwrap();
+        assert_eq!(prefab_child_ref.parent(), prefab_root);
+        assert_eq!(
+            **prefab_child_ref.local_transform().position(),
+            Vector3::new(0.0, 2.0, 0.0)
+        );
+
+        let (_, prefab_grandchild_ref) = model.find_node_by_name("Grandchild").unwrap();
+        assert_eq!(prefab_grandchild_ref.parent(), prefab_child);
+        assert_eq!(
+            **prefab_grandchild_ref.local_transform().position(),
+            Vector3::new(0.0, 0.0, 3.0)
+        );
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! On-disk cache of processed textures. See [`TextureImportCache`] docs for more info.
+
+use crate::{
+    core::{log::Log, visitor::prelude::*},
+    resource::texture::{ktx2, CompressionOptions, Texture, TextureError, TextureImportOptions},
+};
+use fxhash::FxHasher;
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+/// Version of the cache entries. It must be incremented every time when texture processing or
+/// [`Texture`] serialization changes, so the stale entries will be ignored.
+const CACHE_VERSION: u32 = 1;
+
+/// On-disk cache of processed textures. Mip-map generation and especially compression of large
+/// textures could take a lot of time, the cache allows to do it only once - processed textures
+/// are stored in a directory and reused on subsequent loads.
+///
+/// Cache entries are identified by the hash of source data and import options, so any change of
+/// a texture or its import options leads to re-processing. Textures, that do not require any
+/// processing (DDS and KTX2 containers, textures without mip-maps and compression), bypass the
+/// cache.
+///
+/// ## Example
+///
+/// The cache is disabled by default, it could be enabled like so:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     asset::manager::ResourceManager,
+/// #     resource::texture::{cache::TextureImportCache, loader::TextureLoader},
+/// # };
+/// fn enable_texture_cache(resource_manager: &ResourceManager) {
+///     let mut state = resource_manager.state();
+///     if let Some(loader) = state.loaders.find_mut::<TextureLoader>() {
+///         loader.import_cache = Some(TextureImportCache::new(".cache/textures"));
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextureImportCache {
+    directory: PathBuf,
+}
+
+fn needs_processing(data: &[u8], import_options: &TextureImportOptions) -> bool {
+    // Containers are loaded as is.
+    if ktx2::is_ktx2(data) || data.starts_with(b"DDS ") {
+        return false;
+    }
+
+    import_options.compression != CompressionOptions::NoCompression
+        || import_options.minification_filter.is_using_mip_mapping()
+}
+
+impl TextureImportCache {
+    /// Creates new cache, that stores processed textures in the given directory. The directory
+    /// will be created on first write if it does not exist.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the directory of the cache.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Removes every entry of the cache.
+    pub fn clear(&self) -> std::io::Result<()> {
+        if self.directory.exists() {
+            std::fs::remove_dir_all(&self.directory)?;
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, data: &[u8], import_options: &TextureImportOptions) -> PathBuf {
+        let mut hasher = FxHasher::default();
+        CACHE_VERSION.hash(&mut hasher);
+        data.hash(&mut hasher);
+        // Import options do not implement `Hash`, their textual representation is used instead.
+        ron::to_string(import_options)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        self.directory.join(format!("{:016x}.tex", hasher.finish()))
+    }
+
+    fn read_entry(path: &Path) -> Result<Texture, VisitError> {
+        let data = std::fs::read(path)?;
+        let mut visitor = Visitor::load_from_memory(&data)?;
+        let mut texture = Texture::default();
+        texture.visit("Texture", &mut visitor)?;
+        Ok(texture)
+    }
+
+    fn write_entry(&self, path: &Path, texture: &mut Texture) -> Result<(), VisitError> {
+        std::fs::create_dir_all(&self.directory)?;
+        let mut visitor = Visitor::new();
+        texture.visit("Texture", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+
+    /// Loads a texture from the given data using the given import options (see
+    /// [`Texture::load_from_memory`] for more info). If the texture requires processing, the
+    /// processed texture is taken from the cache or, if there's no such entry, it is processed
+    /// and stored in the cache. Cache failures are not fatal, they're reported to the log.
+    pub fn load_or_process(
+        &self,
+        data: &[u8],
+        import_options: TextureImportOptions,
+    ) -> Result<Texture, TextureError> {
+        if !needs_processing(data, &import_options) {
+            return Texture::load_from_memory(data, import_options);
+        }
+
+        let path = self.entry_path(data, &import_options);
+
+        if path.exists() {
+            match Self::read_entry(&path) {
+                Ok(mut texture) => {
+                    texture.apply_import_options(&import_options);
+                    return Ok(texture);
+                }
+                Err(err) => Log::warn(format!(
+                    "Unable to read texture cache entry {}. Reason: {err:?}",
+                    path.display()
+                )),
+            }
+        }
+
+        let mut texture = Texture::load_from_memory(data, import_options)?;
+        if let Err(err) = self.write_entry(&path, &mut texture) {
+            Log::warn(format!(
+                "Unable to write texture cache entry {}. Reason: {err:?}",
+                path.display()
+            ));
+        }
+        Ok(texture)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::resource::texture::{
+        cache::TextureImportCache, CompressionOptions, TextureImportOptions, TexturePixelKind,
+    };
+    use image::{ImageFormat, RgbaImage};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_texture_import_cache() {
+        let mut png = Vec::new();
+        RgbaImage::from_fn(8, 8, |x, y| {
+            image::Rgba([x as u8 * 30, y as u8 * 30, 0, 255])
+        })
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+        let cache = TextureImportCache::new(
+            std::env::temp_dir().join(format!("fyrox_texture_cache_{}", std::process::id())),
+        );
+        let options = TextureImportOptions::default().with_compression(CompressionOptions::Quality);
+
+        let processed = cache.load_or_process(&png, options.clone()).unwrap();
+        assert_eq!(std::fs::read_dir(cache.directory()).unwrap().count(), 1);
+
+        let cached = cache.load_or_process(&png, options).unwrap();
+        assert_eq!(cached.pixel_kind(), TexturePixelKind::DXT5RGBA);
+        assert_eq!(cached.mip_count(), processed.mip_count());
+        assert_eq!(cached.data(), processed.data());
+
+        // Different options must not hit the same entry.
+        cache
+            .load_or_process(&png, TextureImportOptions::default())
+            .unwrap();
+        assert_eq!(std::fs::read_dir(cache.directory()).unwrap().count(), 2);
+
+        cache.clear().unwrap();
+        assert!(!cache.directory().exists());
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reader of KTX2 texture containers. See
+//! [the specification](https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html) for more info
+//! about the format.
+
+use crate::resource::texture::{TextureError, TextureKind, TexturePixelKind};
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n',
+];
+const HEADER_SIZE: usize = 80;
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+/// Contents of a KTX2 container. Mip levels are stored one after another, starting from the
+/// largest one, every level contains all faces of a cube map.
+pub(crate) struct Ktx2Image {
+    pub kind: TextureKind,
+    pub pixel_kind: TexturePixelKind,
+    pub mip_count: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Returns `true` if the data starts with KTX2 file identifier.
+pub(crate) fn is_ktx2(data: &[u8]) -> bool {
+    data.starts_with(&IDENTIFIER)
+}
+
+fn malformed(reason: &str) -> TextureError {
+    TextureError::MalformedData(format!("Malformed KTX2 data. Reason: {reason}"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, TextureError> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| malformed("unexpected end of data"))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<usize, TextureError> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or_else(|| malformed("unexpected end of data"))
+}
+
+// Values are taken from `VkFormat` enumeration. sRGB formats are treated as linear ones, the same
+// way as it is done for DDS textures.
+fn pixel_kind_from_vk_format(vk_format: u32) -> Option<TexturePixelKind> {
+    Some(match vk_format {
+        9 => TexturePixelKind::R8,
+        16 => TexturePixelKind::RG8,
+        23 | 29 => TexturePixelKind::RGB8,
+        30 | 36 => TexturePixelKind::BGR8,
+        37 | 43 => TexturePixelKind::RGBA8,
+        44 | 50 => TexturePixelKind::BGRA8,
+        70 => TexturePixelKind::R16,
+        76 => TexturePixelKind::R16F,
+        77 => TexturePixelKind::RG16,
+        84 => TexturePixelKind::RGB16,
+        90 => TexturePixelKind::RGB16F,
+        91 => TexturePixelKind::RGBA16,
+        100 => TexturePixelKind::R32F,
+        106 => TexturePixelKind::RGB32F,
+        109 => TexturePixelKind::RGBA32F,
+        131 | 132 => TexturePixelKind::DXT1RGB,
+        133 | 134 => TexturePixelKind::DXT1RGBA,
+        135 | 136 => TexturePixelKind::DXT3RGBA,
+        137 | 138 => TexturePixelKind::DXT5RGBA,
+        139 => TexturePixelKind::R8RGTC,
+        141 => TexturePixelKind::RG8RGTC,
+        _ => return None,
+    })
+}
+
+/// Reads a KTX2 container. Array textures and supercompressed data (BasisLZ, Zstandard, etc.)
+/// are not supported.
+pub(crate) fn read(data: &[u8]) -> Result<Ktx2Image, TextureError> {
+    if !is_ktx2(data) {
+        return Err(malformed("invalid identifier"));
+    }
+    if data.len() < HEADER_SIZE {
+        return Err(malformed("unexpected end of data"));
+    }
+
+    let vk_format = read_u32(data, 12)?;
+    let width = read_u32(data, 20)?;
+    let height = read_u32(data, 24)?;
+    let depth = read_u32(data, 28)?;
+    let layer_count = read_u32(data, 32)?;
+    let face_count = read_u32(data, 36)?;
+    let level_count = read_u32(data, 40)?;
+    let supercompression_scheme = read_u32(data, 44)?;
+
+    if supercompression_scheme != 0 || layer_count > 1 {
+        return Err(TextureError::UnsupportedFormat);
+    }
+
+    let pixel_kind = pixel_kind_from_vk_format(vk_format).ok_or(TextureError::UnsupportedFormat)?;
+
+    let kind = match (face_count, height, depth) {
+        (6, _, 0) if width == height => TextureKind::Cube { width, height },
+        (1, 0, 0) => TextureKind::Line { length: width },
+        (1, _, 0) => TextureKind::Rectangle { width, height },
+        (1, _, _) => TextureKind::Volume {
+            width,
+            height,
+            depth,
+        },
+        _ => return Err(malformed("invalid dimensions")),
+    };
+
+    // Zero level count means that mip levels should be generated at loading, which is not
+    // supported - only the base level will be used.
+    let mip_count = level_count.max(1);
+
+    let mut bytes = Vec::new();
+    for level in 0..mip_count as usize {
+        let entry = HEADER_SIZE + level * LEVEL_INDEX_ENTRY_SIZE;
+        let offset = read_u64(data, entry)?;
+        let length = read_u64(data, entry + 8)?;
+        let level_data = offset
+            .checked_add(length)
+            .and_then(|end| data.get(offset..end))
+            .ok_or_else(|| malformed("level data is out of bounds"))?;
+        bytes.extend_from_slice(level_data);
+    }
+
+    Ok(Ktx2Image {
+        kind,
+        pixel_kind,
+        mip_count,
+        bytes,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::resource::texture::{
+        ktx2::{self, HEADER_SIZE, IDENTIFIER, LEVEL_INDEX_ENTRY_SIZE},
+        TextureError,
+    };
+
+    /// Writes a KTX2 container with the given header values and level data.
+    pub(crate) fn write_ktx2(
+        vk_format: u32,
+        width: u32,
+        height: u32,
+        face_count: u32,
+        levels: &[&[u8]],
+    ) -> Vec<u8> {
+        let mut data = IDENTIFIER.to_vec();
+        for value in [
+            vk_format,
+            1,
+            width,
+            height,
+            0,
+            0,
+            face_count,
+            levels.len() as u32,
+            0,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        // Empty data format descriptor, key/value data and supercompression global data.
+        data.extend_from_slice(&[0; 32]);
+
+        let mut offset = HEADER_SIZE + levels.len() * LEVEL_INDEX_ENTRY_SIZE;
+        for level in levels {
+            for value in [offset, level.len(), level.len()] {
+                data.extend_from_slice(&(value as u64).to_le_bytes());
+            }
+            offset += level.len();
+        }
+        for level in levels {
+            data.extend_from_slice(level);
+        }
+        data
+    }
+
+    #[test]
+    fn test_ktx2_read() {
+        let level0 = [1u8; 16];
+        let level1 = [2u8; 4];
+        let image = ktx2::read(&write_ktx2(37, 2, 2, 1, &[&level0, &level1])).unwrap();
+        assert_eq!(image.mip_count, 2);
+        assert_eq!(image.bytes, [level0.as_slice(), level1.as_slice()].concat());
+        assert_eq!(image.kind.rectangle_size().unwrap().x, 2);
+
+        assert!(matches!(
+            ktx2::read(&write_ktx2(1, 2, 2, 1, &[&level0])),
+            Err(TextureError::UnsupportedFormat)
+        ));
+
+        let mut truncated = write_ktx2(37, 2, 2, 1, &[&level0]);
+        truncated.truncate(truncated.len() - 1);
+        assert!(matches!(
+            ktx2::read(&truncated),
+            Err(TextureError::MalformedData(_))
+        ));
+    }
+}
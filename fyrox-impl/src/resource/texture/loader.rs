@@ -30,7 +30,7 @@ use crate::{
         state::LoadError,
     },
     core::{uuid::Uuid, TypeUuidProvider},
-    resource::texture::{Texture, TextureImportOptions},
+    resource::texture::{cache::TextureImportCache, Texture, TextureImportOptions},
 };
 use std::{path::PathBuf, sync::Arc};
 
@@ -38,12 +38,15 @@ use std::{path::PathBuf, sync::Arc};
 pub struct TextureLoader {
     /// Default import options for textures.
     pub default_import_options: TextureImportOptions,
+    /// Optional on-disk cache of processed textures. See [`TextureImportCache`] docs for more
+    /// info. `None` disables caching.
+    pub import_cache: Option<TextureImportCache>,
 }
 
 impl ResourceLoader for TextureLoader {
     fn extensions(&self) -> &[&str] {
         &[
-            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds",
+            "jpg", "jpeg", "tga", "gif", "bmp", "png", "tiff", "tif", "dds", "ktx2",
         ]
     }
 
@@ -53,6 +56,7 @@ impl ResourceLoader for TextureLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         let default_import_options = self.default_import_options.clone();
+        let import_cache = self.import_cache.clone();
         Box::pin(async move {
             let io = io.as_ref();
 
@@ -60,9 +64,14 @@ impl ResourceLoader for TextureLoader {
                 .await
                 .unwrap_or(default_import_options);
 
-            let raw_texture = Texture::load_from_file(&path, io, import_options)
-                .await
-                .map_err(LoadError::new)?;
+            let raw_texture = match import_cache {
+                Some(import_cache) => {
+                    let data = io.load_file(&path).await.map_err(LoadError::new)?;
+                    import_cache.load_or_process(&data, import_options)
+                }
+                None => Texture::load_from_file(&path, io, import_options).await,
+            }
+            .map_err(LoadError::new)?;
 
             Ok(LoaderPayload::new(raw_texture))
         })
@@ -27,11 +27,15 @@
 //! ## Supported formats
 //!
 //! To load images and decode them, Fyrox uses image and ddsfile crates. Here is the list of
-//! supported formats: png, tga, bmp, dds, jpg, gif, tiff, dds.
+//! supported formats: png, tga, bmp, dds, ktx2, jpg, gif, tiff.
 //!
 //! ## Compressed textures
 //!
-//! Fyrox supports most commonly used formats of compressed textures: DXT1, DXT3, DXT5.
+//! Fyrox supports most commonly used formats of compressed textures: DXT1 (BC1), DXT3 (BC2),
+//! DXT5 (BC3), RGTC (BC4, BC5). Such textures could be loaded directly from DDS and KTX2
+//! containers, or textures in other formats could be compressed at import (see
+//! [`CompressionOptions`]). Processed textures could be stored in an on-disk cache to not repeat
+//! the processing on every load, see [`cache::TextureImportCache`] for more info.
 //!
 //! ## Render target
 //!
@@ -52,7 +56,7 @@ use crate::{
         TypeUuidProvider,
     },
 };
-use ddsfile::{Caps2, D3DFormat};
+use ddsfile::{Caps2, D3DFormat, DxgiFormat};
 use fast_image_resize as fr;
 use fast_image_resize::ResizeOptions;
 use fxhash::FxHasher;
@@ -77,6 +81,8 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod cache;
+mod ktx2;
 pub mod loader;
 
 /// Texture kind.
@@ -1025,6 +1031,8 @@ pub enum TextureError {
     Image(image::ImageError),
     /// An error occurred during file loading.
     FileLoadError(FileLoadError),
+    /// Data of a texture container (DDS, KTX2) is malformed.
+    MalformedData(String),
 }
 
 impl Display for TextureError {
@@ -1042,6 +1050,9 @@ impl Display for TextureError {
             TextureError::FileLoadError(v) => {
                 write!(f, "A file load error has occurred {v:?}")
             }
+            TextureError::MalformedData(v) => {
+                write!(f, "{v}")
+            }
         }
     }
 }
@@ -1272,17 +1283,11 @@ fn bytes_in_mip_level(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usiz
     }
 }
 
-fn mip_byte_offset(kind: TextureKind, pixel_kind: TexturePixelKind, mut mip: usize) -> usize {
+fn mip_byte_offset(kind: TextureKind, pixel_kind: TexturePixelKind, mip: usize) -> usize {
     // TODO: This could be done without loop.
-    let mut offset = 0;
-    loop {
-        offset += bytes_in_mip_level(kind, pixel_kind, mip) as usize;
-        mip = mip.saturating_sub(1);
-        if mip == 0 {
-            break;
-        }
-    }
-    offset
+    (0..=mip)
+        .map(|level| bytes_in_mip_level(kind, pixel_kind, level) as usize)
+        .sum()
 }
 
 fn convert_pixel_type_enum(pixel_kind: TexturePixelKind) -> fr::PixelType {
@@ -1311,16 +1316,74 @@ where
     }
 }
 
+fn pixel_kind_from_dxgi_format(dxgi_format: DxgiFormat) -> Option<TexturePixelKind> {
+    Some(match dxgi_format {
+        DxgiFormat::BC1_UNorm | DxgiFormat::BC1_UNorm_sRGB => TexturePixelKind::DXT1RGBA,
+        DxgiFormat::BC2_UNorm | DxgiFormat::BC2_UNorm_sRGB => TexturePixelKind::DXT3RGBA,
+        DxgiFormat::BC3_UNorm | DxgiFormat::BC3_UNorm_sRGB => TexturePixelKind::DXT5RGBA,
+        DxgiFormat::BC4_UNorm => TexturePixelKind::R8RGTC,
+        DxgiFormat::BC5_UNorm => TexturePixelKind::RG8RGTC,
+        DxgiFormat::R8_UNorm => TexturePixelKind::R8,
+        DxgiFormat::R8G8_UNorm => TexturePixelKind::RG8,
+        DxgiFormat::R8G8B8A8_UNorm | DxgiFormat::R8G8B8A8_UNorm_sRGB => TexturePixelKind::RGBA8,
+        DxgiFormat::B8G8R8A8_UNorm | DxgiFormat::B8G8R8A8_UNorm_sRGB => TexturePixelKind::BGRA8,
+        DxgiFormat::R16_UNorm => TexturePixelKind::R16,
+        DxgiFormat::R16_Float => TexturePixelKind::R16F,
+        DxgiFormat::R16G16_UNorm => TexturePixelKind::RG16,
+        DxgiFormat::R16G16B16A16_UNorm => TexturePixelKind::RGBA16,
+        DxgiFormat::R32_Float => TexturePixelKind::R32F,
+        DxgiFormat::R32G32B32_Float => TexturePixelKind::RGB32F,
+        DxgiFormat::R32G32B32A32_Float => TexturePixelKind::RGBA32F,
+        _ => return None,
+    })
+}
+
+/// DDS stores cube maps face by face (every face with all its mip levels), while the engine
+/// expects mip levels one after another, where each level contains all six faces.
+fn dds_cube_map_to_mip_major(
+    bytes: Vec<u8>,
+    width: u32,
+    height: u32,
+    pixel_kind: TexturePixelKind,
+    mip_count: u32,
+) -> Vec<u8> {
+    let level_sizes = (0..mip_count)
+        .map(|mip| {
+            let level_kind = TextureKind::Rectangle {
+                width: width.checked_shr(mip).unwrap_or_default(),
+                height: height.checked_shr(mip).unwrap_or_default(),
+            };
+            bytes_in_mip_level(level_kind, pixel_kind, 0) as usize
+        })
+        .collect::<Vec<_>>();
+    let face_size = level_sizes.iter().sum::<usize>();
+
+    if mip_count <= 1 || 6 * face_size != bytes.len() {
+        return bytes;
+    }
+
+    let mut reordered = Vec::with_capacity(bytes.len());
+    let mut level_offset = 0;
+    for level_size in level_sizes {
+        for face in 0..6 {
+            let begin = face * face_size + level_offset;
+            reordered.extend_from_slice(&bytes[begin..begin + level_size]);
+        }
+        level_offset += level_size;
+    }
+    reordered
+}
+
 impl Texture {
-    /// Tries to load a texture from given data in one of the following formats: PNG, BMP, TGA, JPG, DDS, GIF. Use
-    /// this method if you want to load a texture from embedded data.
+    /// Tries to load a texture from given data in one of the following formats: PNG, BMP, TGA, JPG, DDS, KTX2,
+    /// GIF. Use this method if you want to load a texture from embedded data.
     ///
     /// # On-demand compression and mip-map generation
     ///
     /// The data can be compressed if needed to improve performance on GPU side. Mip-maps can be generated as well.
-    /// **CAVEAT:** Compression and mip-map generation **won't** be taken into account in case of **DDS** textures,
-    /// because DDS can already contain such data, you should generate mips and compress DDS textures manually using
-    /// some offline tool like DirectXTexTool or similar.
+    /// **CAVEAT:** Compression and mip-map generation **won't** be taken into account in case of **DDS** and
+    /// **KTX2** textures, because these containers can already contain such data, you should generate mips and
+    /// compress such textures manually using some offline tool like DirectXTexTool, KTX-Software or similar.
     ///
     /// # Important notes
     ///
@@ -1337,60 +1400,102 @@ impl Texture {
         data: &[u8],
         import_options: TextureImportOptions,
     ) -> Result<Self, TextureError> {
+        // KTX2 is a container too, its data is used as is.
+        if ktx2::is_ktx2(data) {
+            let image = ktx2::read(data)?;
+            let mut texture = Self {
+                pixel_kind: image.pixel_kind,
+                kind: image.kind,
+                mip_count: image.mip_count,
+                bytes: image.bytes.into(),
+                ..Default::default()
+            };
+            texture.apply_import_options(&import_options);
+            return Ok(texture);
+        }
+
         // DDS is special. It can contain various kinds of textures as well as textures with
         // various pixel formats.
-        //
-        // TODO: Add support for DXGI formats.
         if let Ok(dds) = ddsfile::Dds::read(&mut Cursor::new(data)) {
-            let d3dformat = dds
-                .get_d3d_format()
-                .ok_or(TextureError::UnsupportedFormat)?;
+            // Query everything that needs the whole container before its data is moved out.
             let mip_count = dds.get_num_mipmap_levels();
+            let d3d_format = dds.get_d3d_format();
             let mut bytes = dds.data;
 
             // Try to use as much formats as possible.
-            let pixel_kind = match d3dformat {
-                D3DFormat::DXT1 => TexturePixelKind::DXT1RGBA,
-                D3DFormat::DXT3 => TexturePixelKind::DXT3RGBA,
-                D3DFormat::DXT5 => TexturePixelKind::DXT5RGBA,
-                D3DFormat::L8 | D3DFormat::A8 => TexturePixelKind::R8,
-                D3DFormat::L16 => TexturePixelKind::R16,
-                D3DFormat::R8G8B8 => TexturePixelKind::RGB8,
-                D3DFormat::A8L8 => TexturePixelKind::RG8,
-                D3DFormat::A8R8G8B8 => {
-                    // // ARGB8 -> RGBA8
-                    // assert_eq!(bytes.len() % 4, 0);
-                    // for chunk in bytes.chunks_exact_mut(4) {
-                    //     let a = chunk[0];
-                    //     let r = chunk[1];
-                    //     let g = chunk[2];
-                    //     let b = chunk[3];
-                    //     chunk[0] = r;
-                    //     chunk[1] = g;
-                    //     chunk[2] = b;
-                    //     chunk[3] = a;
-                    // }
-                    TexturePixelKind::RGBA8
-                }
-                D3DFormat::G16R16 => {
-                    // GR16 -> RG16
-                    assert_eq!(bytes.len() % 4, 0);
-                    for chunk in bytes.chunks_exact_mut(4) {
-                        // Red Hi + Lo bytes
-                        let gh = chunk[0];
-                        let gl = chunk[1];
-                        // Green Hi + Lo bytes
-                        let rh = chunk[2];
-                        let rl = chunk[3];
-                        // Swap
-                        chunk[0] = rh;
-                        chunk[1] = rl;
-                        chunk[2] = gh;
-                        chunk[3] = gl;
+            let pixel_kind = if let Some(dxgi_format) = dds.header10.as_ref().map(|h| h.dxgi_format)
+            {
+                pixel_kind_from_dxgi_format(dxgi_format).ok_or(TextureError::UnsupportedFormat)?
+            } else {
+                let d3dformat = d3d_format.ok_or(TextureError::UnsupportedFormat)?;
+                match d3dformat {
+                    D3DFormat::DXT1 => TexturePixelKind::DXT1RGBA,
+                    D3DFormat::DXT3 => TexturePixelKind::DXT3RGBA,
+                    D3DFormat::DXT5 => TexturePixelKind::DXT5RGBA,
+                    D3DFormat::L8 | D3DFormat::A8 => TexturePixelKind::R8,
+                    D3DFormat::L16 => TexturePixelKind::R16,
+                    D3DFormat::R8G8B8 => TexturePixelKind::RGB8,
+                    D3DFormat::A8L8 => TexturePixelKind::RG8,
+                    D3DFormat::A8R8G8B8 => {
+                        // // ARGB8 -> RGBA8
+                        // assert_eq!(bytes.len() % 4, 0);
+                        // for chunk in bytes.chunks_exact_mut(4) {
+                        //     let a = chunk[0];
+                        //     let r = chunk[1];
+                        //     let g = chunk[2];
+                        //     let b = chunk[3];
+                        //     chunk[0] = r;
+                        //     chunk[1] = g;
+                        //     chunk[2] = b;
+                        //     chunk[3] = a;
+                        // }
+                        TexturePixelKind::RGBA8
+                    }
+                    D3DFormat::G16R16 => {
+                        // GR16 -> RG16
+                        assert_eq!(bytes.len() % 4, 0);
+                        for chunk in bytes.chunks_exact_mut(4) {
+                            // Red Hi + Lo bytes
+                            let gh = chunk[0];
+                            let gl = chunk[1];
+                            // Green Hi + Lo bytes
+                            let rh = chunk[2];
+                            let rl = chunk[3];
+                            // Swap
+                            chunk[0] = rh;
+                            chunk[1] = rl;
+                            chunk[2] = gh;
+                            chunk[3] = gl;
+                        }
+                        TexturePixelKind::RG16
                     }
-                    TexturePixelKind::RG16
+                    _ => return Err(TextureError::UnsupportedFormat),
+                }
+            };
+
+            let kind = if dds.header.caps2 & Caps2::CUBEMAP == Caps2::CUBEMAP {
+                bytes = dds_cube_map_to_mip_major(
+                    bytes,
+                    dds.header.width,
+                    dds.header.height,
+                    pixel_kind,
+                    mip_count,
+                );
+                TextureKind::Cube {
+                    width: dds.header.width,
+                    height: dds.header.height,
+                }
+            } else if dds.header.caps2 & Caps2::VOLUME == Caps2::VOLUME {
+                TextureKind::Volume {
+                    width: dds.header.width,
+                    height: dds.header.height,
+                    depth: dds.header.depth.unwrap(),
+                }
+            } else {
+                TextureKind::Rectangle {
+                    width: dds.header.width,
+                    height: dds.header.height,
                 }
-                _ => return Err(TextureError::UnsupportedFormat),
             };
 
             Ok(Self {
@@ -1404,23 +1509,7 @@ impl Texture {
                 anisotropy: import_options.anisotropy,
                 mip_count,
                 bytes: bytes.into(),
-                kind: if dds.header.caps2 & Caps2::CUBEMAP == Caps2::CUBEMAP {
-                    TextureKind::Cube {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                    }
-                } else if dds.header.caps2 & Caps2::VOLUME == Caps2::VOLUME {
-                    TextureKind::Volume {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                        depth: dds.header.depth.unwrap(),
-                    }
-                } else {
-                    TextureKind::Rectangle {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                    }
-                },
+                kind,
                 is_render_target: false,
                 cache_index: Default::default(),
             })
@@ -1575,6 +1664,16 @@ impl Texture {
         Self::load_from_memory(&data, import_options)
     }
 
+    /// Sets sampling parameters of the texture from the given import options.
+    pub(crate) fn apply_import_options(&mut self, import_options: &TextureImportOptions) {
+        self.minification_filter = import_options.minification_filter;
+        self.magnification_filter = import_options.magnification_filter;
+        self.s_wrap_mode = import_options.s_wrap_mode;
+        self.t_wrap_mode = import_options.t_wrap_mode;
+        self.r_wrap_mode = import_options.r_wrap_mode;
+        self.anisotropy = import_options.anisotropy;
+    }
+
     /// Creates new texture instance from given parameters.
     ///
     /// # Limitations
@@ -1710,6 +1809,16 @@ impl Texture {
         &self.bytes[mip_begin..mip_end]
     }
 
+    /// Returns data of all mip levels starting from the given one. It could be used to upload only a
+    /// part of the mip chain (for example, without the most detailed levels) to GPU.
+    pub fn mip_chain_data(&self, first_mip: usize) -> &[u8] {
+        let begin = first_mip
+            .checked_sub(1)
+            .map(|prev| mip_byte_offset(self.kind, self.pixel_kind, prev))
+            .unwrap_or_default();
+        &self.bytes[begin.min(self.bytes.len())..]
+    }
+
     /// Tries to cast the specific mip level data of the internal data buffer to the given type. Type casting
     /// will succeed only if the the size of the type `T` is equal with the size of the pixel.
     ///
@@ -1805,7 +1914,8 @@ impl<'a> TextureDataRefMut<'a> {
 #[cfg(test)]
 pub mod test {
     use crate::resource::texture::{
-        TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension,
+        dds_cube_map_to_mip_major, mip_byte_offset, TextureKind, TexturePixelKind, TextureResource,
+        TextureResourceExtension,
     };
 
     pub fn create_test_texture() -> TextureResource {
@@ -1820,4 +1930,29 @@ pub mod test {
         )
         .unwrap()
     }
+
+    #[test]
+    fn test_mip_byte_offset() {
+        let kind = TextureKind::Rectangle {
+            width: 4,
+            height: 4,
+        };
+        assert_eq!(mip_byte_offset(kind, TexturePixelKind::RGBA8, 0), 64);
+        assert_eq!(mip_byte_offset(kind, TexturePixelKind::RGBA8, 1), 80);
+        assert_eq!(mip_byte_offset(kind, TexturePixelKind::RGBA8, 2), 84);
+    }
+
+    #[test]
+    fn test_dds_cube_map_to_mip_major() {
+        // 2x2 R8 cube map with two mip levels, every byte is `face * 10 + mip`.
+        let face_major = (0..6u8)
+            .flat_map(|face| [face * 10; 4].into_iter().chain([face * 10 + 1]))
+            .collect::<Vec<_>>();
+        let mip_major = dds_cube_map_to_mip_major(face_major, 2, 2, TexturePixelKind::R8, 2);
+        let expected = (0..6u8)
+            .flat_map(|face| [face * 10; 4])
+            .chain((0..6u8).map(|face| face * 10 + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(mip_major, expected);
+    }
 }
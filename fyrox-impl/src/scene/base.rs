@@ -1028,6 +1028,17 @@ impl Base {
     }
 
     /// Enables or disables scene node. Disabled scene nodes won't be updated (including scripts) or rendered.
+    /// Unlike visibility, which only affects rendering, the enabled flag is meant to switch off whole features of
+    /// a scene while keeping them in memory. Disabled nodes are handled by engine systems like so:
+    ///
+    /// - Scripts - no updates, messages or OS events are passed to scripts of disabled nodes.
+    /// - Animations - animation players and blending state machines stop updating their animations.
+    /// - Particle systems - particles are not simulated and not rendered.
+    /// - Sounds - native sound sources are removed and re-created with the same playback position when the node
+    ///   is enabled again.
+    /// - Physics - native rigid bodies, colliders and joints are removed from the physics world, they're re-created
+    ///   from the scene node's state when the node is enabled again.
+    /// - Rendering - meshes, lights, decals, terrains, etc. are not rendered.
     ///
     /// # Important notes
     ///
@@ -25,7 +25,7 @@ use crate::{
         embedded_data_source, manager::BuiltInResource, state::LoadError, untyped::ResourceKind,
     },
     core::{
-        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3, Vector4},
         color::Color,
         log::Log,
         math::{aabb::AxisAlignedBoundingBox, frustum::Frustum, ray::Ray, Rect},
@@ -122,8 +122,8 @@ pub struct OrthographicProjection {
     /// treated like near clipping plane.
     #[reflect(min_value = 0.0, step = 0.1)]
     pub z_far: f32,
-    /// Vertical size of the "view box". Horizontal size is derived value and depends on the aspect
-    /// ratio of the viewport. Any values very close to zero (from both sides) will be clamped to
+    /// Vertical size of the "view box" (a half of the height of the visible area). Horizontal size is
+    /// derived value and depends on the aspect ratio of the viewport. Any values very close to zero (from both sides) will be clamped to
     /// some minimal value to prevent singularities from occuring.
     #[reflect(step = 0.1)]
     pub vertical_size: f32,
@@ -140,6 +140,29 @@ impl Default for OrthographicProjection {
 }
 
 impl OrthographicProjection {
+    /// Creates a new orthographic projection, that maps exactly `pixels_per_unit` pixels of the frame
+    /// to a single world unit. It is useful for pixel-art 2D games, where each pixel of a sprite must
+    /// match a pixel on the screen. `viewport_height` is the height of the viewport of the camera in
+    /// pixels, so the projection must be updated when the size of the frame changes. See also
+    /// [`Self::snap_to_pixel_grid`].
+    pub fn pixel_perfect(pixels_per_unit: f32, viewport_height: f32) -> Self {
+        Self {
+            vertical_size: viewport_height / (2.0 * pixels_per_unit),
+            ..Default::default()
+        }
+    }
+
+    /// Rounds X and Y coordinates of the given position to the nearest pixel using the given amount
+    /// of pixels per world unit. Snapping the position of a camera with [`Self::pixel_perfect`]
+    /// projection removes jittering of sprites when the camera moves.
+    pub fn snap_to_pixel_grid(position: Vector3<f32>, pixels_per_unit: f32) -> Vector3<f32> {
+        Vector3::new(
+            (position.x * pixels_per_unit).round() / pixels_per_unit,
+            (position.y * pixels_per_unit).round() / pixels_per_unit,
+            position.z,
+        )
+    }
+
     /// Returns orthographic projection matrix.
     #[inline]
     pub fn matrix(&self, frame_size: Vector2<f32>) -> Matrix4<f32> {
@@ -329,6 +352,98 @@ impl Default for Exposure {
     }
 }
 
+/// Vignette darkens the edges of the frame, drawing attention to its center.
+#[derive(Visit, Reflect, Copy, Clone, PartialEq, Debug)]
+pub struct Vignette {
+    /// Defines whether the vignette is enabled or not. Disabled by default.
+    pub enabled: bool,
+    /// Darkness of the edges of the frame in `[0; 1]` range. Default is 0.4.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub intensity: f32,
+    /// Distance from the center of the frame (in fractions of its height) at which the darkening
+    /// starts to fade in. Default is 0.8.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub radius: f32,
+    /// Width of the transition between the darkened and the clear areas. Default is 0.5.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub smoothness: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.4,
+            radius: 0.8,
+            smoothness: 0.5,
+        }
+    }
+}
+
+/// Posterization reduces the amount of distinct colors of the frame, giving it a flat, "painted" look.
+/// It is usually used together with toon shading (see [`crate::material::shader`] docs).
+#[derive(Visit, Reflect, Copy, Clone, PartialEq, Debug)]
+pub struct Posterization {
+    /// Defines whether the posterization is enabled or not. Disabled by default.
+    pub enabled: bool,
+    /// Amount of distinct levels of each color channel. Default is 6.
+    #[reflect(min_value = 2.0, step = 1.0)]
+    pub levels: u32,
+}
+
+impl Default for Posterization {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            levels: 6,
+        }
+    }
+}
+
+/// A set of post-processing effects of a camera. Effects are applied in the following order:
+/// bloom, tone mapping (together with color grading), vignette, posterization, user-defined post
+/// effects (see [`crate::renderer::post_effect::PostEffect`]), FXAA.
+#[derive(Visit, Reflect, Clone, PartialEq, Debug)]
+pub struct PostProcessingSettings {
+    /// Defines whether bright parts of the frame should glow or not. Enabled by default.
+    pub bloom: bool,
+    /// Defines whether the frame should be tone mapped from high dynamic range to low dynamic range,
+    /// using camera exposure settings. If disabled, the colors will be just clamped to `[0; 1]` range.
+    /// Enabled by default.
+    pub tone_mapping: bool,
+    /// Vignette settings.
+    pub vignette: Vignette,
+    /// Posterization settings.
+    #[visit(optional)]
+    pub posterization: Posterization,
+    /// Defines whether FXAA should be applied to the frame or not. FXAA must also be enabled in the
+    /// quality settings of the renderer. Enabled by default.
+    pub fxaa: bool,
+    /// Names of user-defined post effects, that should not be applied to the frame of the camera.
+    pub disabled_effects: Vec<String>,
+}
+
+impl Default for PostProcessingSettings {
+    fn default() -> Self {
+        Self {
+            bloom: true,
+            tone_mapping: true,
+            vignette: Default::default(),
+            posterization: Default::default(),
+            fxaa: true,
+            disabled_effects: Default::default(),
+        }
+    }
+}
+
+impl PostProcessingSettings {
+    /// Returns `true` if a user-defined post effect with the given name should be applied to the
+    /// frame, `false` - otherwise.
+    pub fn is_effect_enabled(&self, name: &str) -> bool {
+        !self.disabled_effects.iter().any(|n| n == name)
+    }
+}
+
 /// Camera allows you to see world from specific point in world. You must have at least one camera in
 /// your scene to see anything.
 ///
@@ -336,7 +451,33 @@ impl Default for Exposure {
 ///
 /// There are two main projection modes supported by Camera node: perspective and orthogonal projections.
 /// Perspective projection is used primarily to display 3D scenes, while orthogonal projection could be
-/// used for both 3D and 2D. Orthogonal projection could also be used in CAD software.
+/// used for both 3D and 2D. Orthogonal projection could also be used in CAD software. See [`Projection`]
+/// docs for more info. For example, a camera for a 2D game, that shows 10 units vertically, could be
+/// created like so:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         camera::{CameraBuilder, OrthographicProjection, Projection},
+/// #         graph::Graph,
+/// #         node::Node,
+/// #     },
+/// # };
+/// fn create_2d_camera(graph: &mut Graph) -> Handle<Node> {
+///     CameraBuilder::new(BaseBuilder::new())
+///         .with_projection(Projection::Orthographic(OrthographicProjection {
+///             z_near: -100.0,
+///             z_far: 100.0,
+///             // Vertical size is a half of the height of the visible area.
+///             vertical_size: 5.0,
+///         }))
+///         .build(graph)
+/// }
+/// ```
+///
+/// Picking works the same for both projection modes, see [`Camera::make_ray`].
 ///
 /// ## Skybox
 ///
@@ -346,7 +487,50 @@ impl Default for Exposure {
 /// ## Multiple cameras
 ///
 /// Fyrox supports multiple cameras per scene, it means that you can create split screen games, make
-/// picture-in-picture insertions in your main camera view and any other combinations you need.
+/// picture-in-picture insertions in your main camera view and any other combinations you need. Every
+/// enabled camera renders the scene into its own viewport (see [`Camera::set_viewport`]), which is
+/// defined in normalized coordinates (origin is at the bottom left corner of the frame). Cameras that
+/// cover the entire frame are rendered first, then every other camera is drawn on top of them. For
+/// example, a split screen for two players could be created like so:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{math::Rect, pool::Handle},
+/// #     scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph, node::Node},
+/// # };
+/// fn create_split_screen_cameras(graph: &mut Graph) -> [Handle<Node>; 2] {
+///     // Left half of the screen for the first player and right half for the second.
+///     [Rect::new(0.0, 0.0, 0.5, 1.0), Rect::new(0.5, 0.0, 0.5, 1.0)]
+///         .map(|viewport| CameraBuilder::new(BaseBuilder::new()).with_viewport(viewport).build(graph))
+/// }
+/// ```
+///
+/// ## Post-processing
+///
+/// Every camera has its own set of post-processing effects (bloom, tone mapping, vignette, etc.),
+/// see [`PostProcessingSettings`] for more info.
+///
+/// ## Render to texture
+///
+/// A camera could render its view into an offscreen texture instead of the screen, see
+/// [`Camera::set_render_target`]. Such texture could then be used as any other texture: in a material
+/// of a mesh (mirrors, security monitors, portals) or in an image widget (minimaps). For example:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     resource::texture::{TextureResource, TextureResourceExtension},
+/// #     scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph, node::Node},
+/// #     core::pool::Handle,
+/// # };
+/// fn create_security_camera(graph: &mut Graph) -> (Handle<Node>, TextureResource) {
+///     let render_target = TextureResource::new_render_target(256, 256);
+///     let camera = CameraBuilder::new(BaseBuilder::new())
+///         .with_render_target(render_target.clone())
+///         .build(graph);
+///     // Assign the texture to a material of a monitor mesh.
+///     (camera, render_target)
+/// }
+/// ```
 ///
 /// ## Performance
 ///
@@ -368,6 +552,10 @@ pub struct Camera {
     #[reflect(setter = "set_skybox")]
     sky_box: InheritableVariable<Option<SkyBox>>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_procedural_sky")]
+    procedural_sky: InheritableVariable<Option<ProceduralSky>>,
+
     #[reflect(setter = "set_environment")]
     environment: InheritableVariable<Option<TextureResource>>,
 
@@ -380,6 +568,14 @@ pub struct Camera {
     #[reflect(setter = "set_color_grading_enabled")]
     color_grading_enabled: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_post_processing")]
+    post_processing: InheritableVariable<PostProcessingSettings>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    render_target: Option<TextureResource>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     view_matrix: Matrix4<f32>,
@@ -447,7 +643,34 @@ impl Camera {
         let up = self.base.up_vector();
 
         self.view_matrix = Matrix4::look_at_rh(&Point3::from(pos), &Point3::from(pos + look), &up);
-        self.projection_matrix = self.projection.matrix(frame_size);
+
+        // Cameras with render targets always render into the entire render target, while other
+        // cameras render into their viewport, so the aspect ratio must be calculated accordingly.
+        let render_target_size = self.render_target.as_ref().and_then(|render_target| {
+            if let TextureKind::Rectangle { width, height } = render_target.data_ref().kind() {
+                Some(Vector2::new(width as f32, height as f32))
+            } else {
+                None
+            }
+        });
+        let target_size = render_target_size.unwrap_or_else(|| {
+            let viewport = self.viewport_pixels(frame_size);
+            Vector2::new(viewport.w() as f32, viewport.h() as f32)
+        });
+
+        self.projection_matrix = self.projection.matrix(target_size);
+    }
+
+    /// Sets view and projection matrices directly. The matrices will be overwritten on the next
+    /// update of the camera, so it is useful only for temporary cameras that are not in a graph
+    /// (for example - reflection cameras of mirrors).
+    pub(crate) fn set_matrices(
+        &mut self,
+        view_matrix: Matrix4<f32>,
+        projection_matrix: Matrix4<f32>,
+    ) {
+        self.view_matrix = view_matrix;
+        self.projection_matrix = projection_matrix;
     }
 
     /// Sets new viewport in resolution-independent format. In other words
@@ -571,7 +794,28 @@ impl Camera {
         std::mem::replace(self.sky_box.get_value_mut_and_mark_modified(), new)
     }
 
-    /// Sets new environment.
+    /// Sets new procedural sky. When set, the procedural sky is rendered instead of the skybox. See
+    /// [`ProceduralSky`] docs for more info.
+    pub fn set_procedural_sky(&mut self, sky: Option<ProceduralSky>) -> Option<ProceduralSky> {
+        self.procedural_sky.set_value_and_mark_modified(sky)
+    }
+
+    /// Return optional mutable reference to current procedural sky.
+    pub fn procedural_sky_mut(&mut self) -> Option<&mut ProceduralSky> {
+        self.procedural_sky
+            .get_value_mut_and_mark_modified()
+            .as_mut()
+    }
+
+    /// Return optional shared reference to current procedural sky.
+    pub fn procedural_sky_ref(&self) -> Option<&ProceduralSky> {
+        self.procedural_sky.as_ref()
+    }
+
+    /// Sets new environment map. The environment map must be a cube map, it is used for image-based
+    /// lighting: diffuse ambient lighting and specular reflections of physically-based materials.
+    /// The map should have mip levels, the renderer uses them to emulate reflections on rough
+    /// surfaces.
     pub fn set_environment(
         &mut self,
         environment: Option<TextureResource>,
@@ -594,13 +838,19 @@ impl Camera {
         (*self.environment).clone()
     }
 
-    /// Creates picking ray from given screen coordinates.
+    /// Creates picking ray from given screen coordinates. The ray starts at the near clipping plane
+    /// and ends at the far clipping plane. In case of perspective projection all rays start from the
+    /// camera's position, while in case of orthographic projection all rays are parallel to the look
+    /// vector of the camera and start at the point on the near clipping plane, that corresponds to
+    /// the given screen coordinates.
     pub fn make_ray(&self, screen_coord: Vector2<f32>, screen_size: Vector2<f32>) -> Ray {
         let viewport = self.viewport_pixels(screen_size);
-        let nx = screen_coord.x / (viewport.w() as f32) * 2.0 - 1.0;
+        let nx = (screen_coord.x - viewport.x() as f32) / (viewport.w() as f32) * 2.0 - 1.0;
         // Invert y here because OpenGL has origin at left bottom corner,
         // but window coordinates starts from left *upper* corner.
-        let ny = (viewport.h() as f32 - screen_coord.y) / (viewport.h() as f32) * 2.0 - 1.0;
+        let ny = (screen_size.y - screen_coord.y - viewport.y() as f32) / (viewport.h() as f32)
+            * 2.0
+            - 1.0;
         let inv_view_proj = self
             .view_projection_matrix()
             .try_inverse()
@@ -612,6 +862,22 @@ impl Camera {
         Ray::from_two_points(begin, end)
     }
 
+    /// Calculates the size (in world units) of a single pixel of the frame at the given world position.
+    /// In case of orthographic projection, the size does not depend on the position, and it could be
+    /// used, for example, to make pixel-perfect 2D games. In case of perspective projection, the size
+    /// grows with the distance from the camera.
+    pub fn pixel_world_size(&self, world_position: Vector3<f32>, frame_size: Vector2<f32>) -> f32 {
+        let viewport_height = self.viewport_pixels(frame_size).h() as f32;
+        let visible_height = match self.projection() {
+            Projection::Perspective(perspective) => {
+                let depth = (world_position - self.global_position()).dot(&self.look_vector());
+                2.0 * depth.abs() * (perspective.fov * 0.5).tan()
+            }
+            Projection::Orthographic(orthographic) => 2.0 * orthographic.vertical_size.abs(),
+        };
+        visible_height / viewport_height
+    }
+
     /// Calculates new fitting parameters for the given axis-aligned bounding box using current camera's
     /// global transform and provided aspect ratio. See [`FitParameters`] docs for more info.
     ///
@@ -686,8 +952,7 @@ impl Camera {
             let k = (1.0 / proj.w) * 0.5;
             Some(Vector2::new(
                 viewport.x() as f32 + viewport.w() as f32 * (proj.x * k + 0.5),
-                viewport.h() as f32
-                    - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
+                screen_size.y - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
             ))
         } else {
             None
@@ -723,6 +988,37 @@ impl Camera {
         *self.color_grading_enabled
     }
 
+    /// Sets new post-processing settings of the camera. See [`PostProcessingSettings`] docs for more
+    /// info.
+    pub fn set_post_processing(
+        &mut self,
+        settings: PostProcessingSettings,
+    ) -> PostProcessingSettings {
+        self.post_processing.set_value_and_mark_modified(settings)
+    }
+
+    /// Returns current post-processing settings of the camera.
+    pub fn post_processing(&self) -> &PostProcessingSettings {
+        &self.post_processing
+    }
+
+    /// Sets a texture, into which the camera will render its view instead of the screen. The texture
+    /// must be created using [`TextureResourceExtension::new_render_target`](crate::resource::texture::TextureResourceExtension::new_render_target),
+    /// its size defines the size of the rendered frame. The camera always renders into the entire
+    /// texture, its viewport is ignored. `None` means that the camera renders into the scene frame as usual. Render target is
+    /// a runtime-only property, it is not serialized.
+    pub fn set_render_target(
+        &mut self,
+        render_target: Option<TextureResource>,
+    ) -> Option<TextureResource> {
+        std::mem::replace(&mut self.render_target, render_target)
+    }
+
+    /// Returns current render target of the camera (if any).
+    pub fn render_target(&self) -> Option<&TextureResource> {
+        self.render_target.as_ref()
+    }
+
     /// Sets new exposure. See `Exposure` struct docs for more info.
     pub fn set_exposure(&mut self, exposure: Exposure) -> Exposure {
         self.exposure.set_value_and_mark_modified(exposure)
@@ -1060,11 +1356,14 @@ pub struct CameraBuilder {
     viewport: Rect<f32>,
     enabled: bool,
     skybox: SkyBoxKind,
+    procedural_sky: Option<ProceduralSky>,
     environment: Option<TextureResource>,
     exposure: Exposure,
     color_grading_lut: Option<ColorGradingLut>,
     color_grading_enabled: bool,
     projection: Projection,
+    post_processing: PostProcessingSettings,
+    render_target: Option<TextureResource>,
 }
 
 impl CameraBuilder {
@@ -1078,11 +1377,14 @@ impl CameraBuilder {
             z_far: 2048.0,
             viewport: Rect::new(0.0, 0.0, 1.0, 1.0),
             skybox: SkyBoxKind::Builtin,
+            procedural_sky: None,
             environment: None,
             exposure: Exposure::Manual(std::f32::consts::E),
             color_grading_lut: None,
             color_grading_enabled: false,
             projection: Projection::default(),
+            post_processing: Default::default(),
+            render_target: None,
         }
     }
 
@@ -1128,6 +1430,12 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets desired procedural sky.
+    pub fn with_procedural_sky(mut self, sky: ProceduralSky) -> Self {
+        self.procedural_sky = Some(sky);
+        self
+    }
+
     /// Sets desired environment map.
     pub fn with_environment(mut self, environment: TextureResource) -> Self {
         self.environment = Some(environment);
@@ -1152,6 +1460,18 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets desired post-processing settings.
+    pub fn with_post_processing(mut self, settings: PostProcessingSettings) -> Self {
+        self.post_processing = settings;
+        self
+    }
+
+    /// Sets desired render target. See [`Camera::set_render_target`] for more info.
+    pub fn with_render_target(mut self, render_target: TextureResource) -> Self {
+        self.render_target = Some(render_target);
+        self
+    }
+
     /// Sets desired projection mode.
     pub fn with_projection(mut self, projection: Projection) -> Self {
         self.projection = projection;
@@ -1174,10 +1494,13 @@ impl CameraBuilder {
                 SkyBoxKind::None => None,
                 SkyBoxKind::Specific(skybox) => Some(skybox),
             }),
+            procedural_sky: self.procedural_sky.into(),
             environment: self.environment.into(),
             exposure: self.exposure.into(),
             color_grading_lut: self.color_grading_lut.into(),
             color_grading_enabled: self.color_grading_enabled.into(),
+            post_processing: self.post_processing.into(),
+            render_target: self.render_target,
         }
     }
 
@@ -1302,6 +1625,95 @@ pub struct SkyBox {
 
 uuid_provider!(SkyBox = "45f359f1-e26f-4ace-81df-097f63474c72");
 
+/// Procedural sky is an analytical model of the sky with a sun, that can be used instead of a
+/// skybox. The color of the sky is calculated from the position of the sun, which in its turn is
+/// defined by the time of day. This allows you to create day-night cycles by just changing
+/// [`Self::time_of_day`].
+///
+/// Procedural sky could also be used as a source of image-based lighting (ambient lighting and
+/// reflections), see [`Self::use_as_environment`].
+#[derive(Debug, Clone, PartialEq, Reflect, Visit)]
+#[visit(optional)]
+pub struct ProceduralSky {
+    /// Time of day in hours in `[0; 24)` range. The sun rises at 6, reaches its highest point at
+    /// 12 and sets at 18.
+    #[reflect(min_value = 0.0, max_value = 24.0, step = 0.1)]
+    pub time_of_day: f32,
+
+    /// Elevation angle of the sun (in radians) at noon. Lower values could be used to emulate
+    /// high latitudes or winter.
+    #[reflect(min_value = 0.0, max_value = 1.5707964, step = 0.01)]
+    pub max_sun_elevation: f32,
+
+    /// Rotation of the path of the sun around the vertical axis in radians. With zero azimuth the
+    /// sun rises at -X, is above -Z at noon and sets at +X.
+    #[reflect(step = 0.01)]
+    pub sun_azimuth: f32,
+
+    /// Angular radius of the sun disc in radians.
+    #[reflect(min_value = 0.0, max_value = 0.5, step = 0.001)]
+    pub sun_size: f32,
+
+    /// Color of the sun.
+    pub sun_color: Color,
+
+    /// Brightness of the sun and the sky.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub sun_intensity: f32,
+
+    /// Amount of Rayleigh scattering. It defines how much of the blue light is scattered in the
+    /// atmosphere, higher values give deeper blue sky and more red sunsets.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub rayleigh: f32,
+
+    /// Amount of haze in the atmosphere (Mie scattering). Higher values make the sky whiter and
+    /// the glow around the sun larger.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub turbidity: f32,
+
+    /// Color of the lower hemisphere.
+    pub ground_color: Color,
+
+    /// If `true`, the sky is used as the environment map for image-based lighting when the camera
+    /// does not have an environment map.
+    pub use_as_environment: bool,
+}
+
+uuid_provider!(ProceduralSky = "07afd7e9-30b3-4c24-a6cd-778c5e985a49");
+
+impl Default for ProceduralSky {
+    fn default() -> Self {
+        Self {
+            time_of_day: 10.0,
+            max_sun_elevation: 60.0f32.to_radians(),
+            sun_azimuth: 0.0,
+            sun_size: 0.01,
+            sun_color: Color::opaque(255, 244, 214),
+            sun_intensity: 1.0,
+            rayleigh: 1.0,
+            turbidity: 0.1,
+            ground_color: Color::opaque(60, 55, 50),
+            use_as_environment: true,
+        }
+    }
+}
+
+impl ProceduralSky {
+    /// Returns normalized world-space direction from the observer to the sun. It could be used to
+    /// orient a directional light, that should match the sun.
+    pub fn sun_direction(&self) -> Vector3<f32> {
+        // Hour angle: zero at noon, -pi/2 at sunrise and pi/2 at sunset.
+        let hour_angle = (self.time_of_day - 12.0) / 24.0 * std::f32::consts::TAU;
+        let (sin_elevation, cos_elevation) = self.max_sun_elevation.sin_cos();
+        let direction = Vector3::new(
+            hour_angle.sin(),
+            hour_angle.cos() * sin_elevation,
+            -hour_angle.cos() * cos_elevation,
+        );
+        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.sun_azimuth) * direction
+    }
+}
+
 /// An error that may occur during skybox creation.
 #[derive(Debug)]
 pub enum SkyBoxError {
@@ -1584,3 +1996,130 @@ impl SkyBox {
         self.back.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            math::Rect,
+        },
+        scene::{
+            base::BaseBuilder,
+            camera::{CameraBuilder, OrthographicProjection, ProceduralSky, Projection},
+        },
+    };
+
+    #[test]
+    fn test_split_screen_viewport() {
+        let frame_size = Vector2::new(800.0, 600.0);
+        let point = Vector3::new(0.5, 0.25, 5.0);
+
+        for viewport in [
+            Rect::new(0.0, 0.0, 1.0, 1.0),
+            Rect::new(0.5, 0.0, 0.5, 1.0),
+            Rect::new(0.0, 0.5, 1.0, 0.5),
+        ] {
+            let mut camera = CameraBuilder::new(BaseBuilder::new())
+                .with_viewport(viewport)
+                .build_camera();
+            camera.calculate_matrices(frame_size);
+
+            let screen_position = camera.project(point, frame_size).unwrap();
+
+            // Projected point must be inside the viewport (in window coordinates).
+            let viewport_pixels = camera.viewport_pixels(frame_size);
+            assert!(screen_position.x >= viewport_pixels.x() as f32);
+            assert!(screen_position.x <= (viewport_pixels.x() + viewport_pixels.w()) as f32);
+            assert!(
+                screen_position.y
+                    >= frame_size.y - (viewport_pixels.y() + viewport_pixels.h()) as f32
+            );
+            assert!(screen_position.y <= frame_size.y - viewport_pixels.y() as f32);
+
+            // Picking ray must pass through the original point.
+            let ray = camera.make_ray(screen_position, frame_size);
+            let distance = (point - ray.origin).cross(&ray.dir.normalize()).norm();
+            assert!(distance < 0.001, "{viewport:?} - {distance}");
+        }
+    }
+
+    #[test]
+    fn test_orthographic_picking() {
+        let frame_size = Vector2::new(800.0, 600.0);
+
+        let mut camera = CameraBuilder::new(BaseBuilder::new())
+            .with_projection(Projection::Orthographic(OrthographicProjection {
+                z_near: -10.0,
+                z_far: 10.0,
+                vertical_size: 3.0,
+            }))
+            .build_camera();
+        camera.calculate_matrices(frame_size);
+
+        // Every ray must be parallel to the look vector of the camera.
+        for screen_position in [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(400.0, 300.0),
+            Vector2::new(800.0, 150.0),
+        ] {
+            let ray = camera.make_ray(screen_position, frame_size);
+            let dir = ray.dir.normalize();
+            assert!((dir - camera.look_vector()).norm() < 0.001);
+        }
+
+        // Center of the screen is at the origin, top edge - at the vertical size.
+        let ray = camera.make_ray(Vector2::new(400.0, 300.0), frame_size);
+        assert!(ray.origin.xy().norm() < 0.001);
+        let ray = camera.make_ray(Vector2::new(400.0, 0.0), frame_size);
+        assert!((ray.origin.y - 3.0).abs() < 0.001);
+
+        assert_eq!(
+            camera.pixel_world_size(Vector3::new(1.0, 2.0, 3.0), frame_size),
+            camera.pixel_world_size(Vector3::new(4.0, 5.0, 6.0), frame_size),
+        );
+        assert!((camera.pixel_world_size(Vector3::default(), frame_size) - 0.01).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_pixel_perfect_projection() {
+        let frame_size = Vector2::new(640.0, 360.0);
+        let pixels_per_unit = 16.0;
+
+        let mut camera = CameraBuilder::new(BaseBuilder::new())
+            .with_projection(Projection::Orthographic(
+                OrthographicProjection::pixel_perfect(pixels_per_unit, frame_size.y),
+            ))
+            .build_camera();
+        camera.calculate_matrices(frame_size);
+
+        assert_eq!(
+            camera.pixel_world_size(Vector3::default(), frame_size),
+            1.0 / pixels_per_unit
+        );
+        assert_eq!(
+            OrthographicProjection::snap_to_pixel_grid(Vector3::new(0.51, -1.02, 3.0), 10.0),
+            Vector3::new(0.5, -1.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_procedural_sky_sun_direction() {
+        let mut sky = ProceduralSky {
+            time_of_day: 12.0,
+            max_sun_elevation: 45.0f32.to_radians(),
+            ..Default::default()
+        };
+        let noon = sky.sun_direction();
+        assert!((noon.y - 45.0f32.to_radians().sin()).abs() < 0.0001);
+        assert!(noon.z < 0.0);
+
+        sky.time_of_day = 6.0;
+        let sunrise = sky.sun_direction();
+        assert!(sunrise.y.abs() < 0.0001);
+        assert!((sunrise.x + 1.0).abs() < 0.0001);
+
+        sky.time_of_day = 0.0;
+        assert!(sky.sun_direction().y < 0.0);
+    }
+}
@@ -40,6 +40,35 @@ pub struct Line {
     pub color: Color,
 }
 
+/// A set of options that defines how skeletons of skinned meshes will be drawn. See
+/// [`crate::scene::mesh::Mesh::debug_draw_skeleton`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkeletonDrawingOptions {
+    /// Whether to draw bones in their current pose or not.
+    pub draw_bones: bool,
+    /// Whether to draw bones in their bind pose or not. Bind pose is the pose in which the mesh was
+    /// attached to the skeleton, it is very useful to find issues with retargeting and weighting.
+    pub draw_bind_pose: bool,
+    /// Radius of a sphere, that is used to show joints of the skeleton.
+    pub joint_radius: f32,
+    /// Color of the bones in their current pose.
+    pub bone_color: Color,
+    /// Color of the bones in their bind pose.
+    pub bind_pose_color: Color,
+}
+
+impl Default for SkeletonDrawingOptions {
+    fn default() -> Self {
+        Self {
+            draw_bones: true,
+            draw_bind_pose: false,
+            joint_radius: 0.015,
+            bone_color: Color::opaque(255, 200, 0),
+            bind_pose_color: Color::opaque(0, 200, 255),
+        }
+    }
+}
+
 /// Drawing context for simple graphics, it allows you to draw simple figures using a set of lines. Most
 /// common use of the context is to draw some debug geometry in your game, draw physics info (contacts,
 /// meshes, shapes, etc.), draw temporary geometry in editor and so on.
@@ -60,6 +60,12 @@ use std::ops::{Deref, DerefMut};
 /// should not affect any surrounding objects, this can be achieved by using decal mask. Each decal has layer index,
 /// it will be drawn only if the index matches the index of the object that inside of decal bounds.
 ///
+/// # Ordering and fading
+///
+/// Overlapping decals are drawn in the order defined by their render order (see [`Decal::set_render_order`]),
+/// decals with higher render order are drawn on top. Temporary decals (the ones with limited lifetime) could
+/// smoothly fade out before removal, see [`Decal::set_fade_out_time`].
+///
 /// # Supported maps
 ///
 /// Currently, only diffuse and normal maps are supported. Diffuse and normal maps will be automatically projected
@@ -73,7 +79,9 @@ use std::ops::{Deref, DerefMut};
 /// # Performance
 ///
 /// It should be noted that decals are not cheap, keep amount (and size) of decals at reasonable values! This
-/// means that unused decals (bullet holes for example) must be removed after some time.
+/// means that unused decals (bullet holes for example) must be removed after some time. The renderer draws
+/// only a limited amount of the closest decals, the limit is defined by
+/// [`crate::renderer::QualitySettings::max_decals`].
 ///
 /// # Example
 ///
@@ -120,6 +128,15 @@ pub struct Decal {
     #[reflect(min_value = 0.0)]
     #[reflect(setter = "set_layer")]
     layer: InheritableVariable<u8>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_render_order")]
+    render_order: InheritableVariable<i32>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0)]
+    #[reflect(setter = "set_fade_out_time")]
+    fade_out_time: InheritableVariable<f32>,
 }
 
 impl Deref for Decal {
@@ -199,7 +216,8 @@ impl Decal {
     /// surfaces. For example, static geometry could have `index == 0` and dynamic `index == 1`.
     /// To "filter" decals all you need to do is to set appropriate layer index to decal, for
     /// example blood splatter decal will have `index == 0` in this case. In case of dynamic
-    /// objects (like bots, etc.) index will be 1.
+    /// objects (like bots, etc.) index will be 1. Layer index must be less than 64, the two highest
+    /// bits are reserved for the lighting model and fog opt-out of surfaces.
     pub fn set_layer(&mut self, layer: u8) -> u8 {
         self.layer.set_value_and_mark_modified(layer)
     }
@@ -208,6 +226,42 @@ impl Decal {
     pub fn layer(&self) -> u8 {
         *self.layer
     }
+
+    /// Sets render order of the decal. Decals with higher render order are drawn on top of the decals with
+    /// lower render order, decals with the same render order are drawn in an unspecified order. It could be
+    /// used, for example, to always draw bullet holes on top of blood splatters.
+    pub fn set_render_order(&mut self, render_order: i32) -> i32 {
+        self.render_order.set_value_and_mark_modified(render_order)
+    }
+
+    /// Returns current render order of the decal.
+    pub fn render_order(&self) -> i32 {
+        *self.render_order
+    }
+
+    /// Sets fade out time (in seconds) of the decal. If the decal has limited lifetime (see
+    /// [`Base::set_lifetime`]), it will gradually become transparent during the last `fade_out_time`
+    /// seconds of its life. Zero means no fading.
+    pub fn set_fade_out_time(&mut self, fade_out_time: f32) -> f32 {
+        self.fade_out_time
+            .set_value_and_mark_modified(fade_out_time.max(0.0))
+    }
+
+    /// Returns current fade out time (in seconds) of the decal.
+    pub fn fade_out_time(&self) -> f32 {
+        *self.fade_out_time
+    }
+
+    /// Returns current opacity multiplier of the decal in `[0.0; 1.0]` range, it is calculated using
+    /// remaining lifetime of the decal and its fade out time.
+    pub fn fade_factor(&self) -> f32 {
+        match self.lifetime() {
+            Some(lifetime) if *self.fade_out_time > 0.0 => {
+                (lifetime / *self.fade_out_time).clamp(0.0, 1.0)
+            }
+            _ => 1.0,
+        }
+    }
 }
 
 impl NodeTrait for Decal {
@@ -237,6 +291,8 @@ pub struct DecalBuilder {
     normal_texture: Option<TextureResource>,
     color: Color,
     layer: u8,
+    render_order: i32,
+    fade_out_time: f32,
 }
 
 impl DecalBuilder {
@@ -248,6 +304,8 @@ impl DecalBuilder {
             normal_texture: None,
             color: Color::opaque(255, 255, 255),
             layer: 0,
+            render_order: 0,
+            fade_out_time: 0.0,
         }
     }
 
@@ -275,6 +333,18 @@ impl DecalBuilder {
         self
     }
 
+    /// Sets desired render order.
+    pub fn with_render_order(mut self, render_order: i32) -> Self {
+        self.render_order = render_order;
+        self
+    }
+
+    /// Sets desired fade out time (in seconds).
+    pub fn with_fade_out_time(mut self, fade_out_time: f32) -> Self {
+        self.fade_out_time = fade_out_time.max(0.0);
+        self
+    }
+
     /// Creates new Decal node.
     pub fn build_decal(self) -> Decal {
         Decal {
@@ -283,6 +353,8 @@ impl DecalBuilder {
             normal_texture: self.normal_texture.into(),
             color: self.color.into(),
             layer: self.layer.into(),
+            render_order: self.render_order.into(),
+            fade_out_time: self.fade_out_time.into(),
         }
     }
 
@@ -0,0 +1,660 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Destructible is an object that breaks into a set of pre-fractured pieces (debris) when it takes
+//! enough damage. See [`Destructible`] docs for more info and usage examples.
+
+use crate::{
+    core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    impl_query_component,
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::{ColliderBuilder, ColliderShape, ConvexPolyhedronShape, GeometrySource},
+        graph::Graph,
+        node::{Node, NodeTrait, UpdateContext},
+        particle_system::ParticleSystem,
+        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        sound::Sound,
+        transform::TransformBuilder,
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+#[derive(Clone, Debug, Default)]
+struct PendingDamage {
+    amount: f32,
+    point: Option<Vector3<f32>>,
+}
+
+/// Destructible is an object that breaks into a set of pre-fractured pieces (debris) when it takes
+/// enough damage. The object consists of an intact node, that is shown while the object is not
+/// broken, and a set of fragments - rigid bodies with colliders and meshes of the pieces.
+///
+/// ## Fracturing
+///
+/// Fragments are disabled while the object is intact. Use [`Destructible::apply_damage`] to damage
+/// the object, when its health drops to zero, the object will be fractured on the next update: the
+/// intact node will be disabled and the fragments will be enabled as dynamic rigid bodies, that fly
+/// away from the damage point with [`Destructible::explosion_speed`]. [`Destructible::fracture`]
+/// breaks the object regardless of its health.
+///
+/// Pre-fractured pieces could be made in any 3D modelling software (for example, using Cell Fracture
+/// add-on in Blender) and then turned into fragments using [`DestructibleBuilder::build_from_fragment_meshes`].
+///
+/// ## Debris management
+///
+/// Debris could be very expensive for physics, especially when there are lots of destructible objects
+/// in a scene. [`Destructible::debris_lifetime`] defines how long the fragments will live after
+/// fracturing, they're removed from the scene graph when the time is out. [`Destructible::max_debris`]
+/// limits the amount of fragments that will be activated - only the closest to the damage point
+/// fragments are activated, the rest stay disabled.
+///
+/// ## Sound and particles
+///
+/// [`Destructible::fracture_sound`] and [`Destructible::fracture_effect`] are the handles of a sound
+/// and a particle system respectively, they're played when the object is fractured.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     graph::SceneGraph,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         destructible::{Destructible, DestructibleBuilder},
+/// #         graph::Graph,
+/// #         node::Node,
+/// #     },
+/// # };
+/// fn make_crate(
+///     graph: &mut Graph,
+///     intact: Handle<Node>,
+///     fragment_meshes: &[Handle<Node>],
+/// ) -> Handle<Node> {
+///     DestructibleBuilder::new(BaseBuilder::new().with_name("Crate"))
+///         .with_intact(intact)
+///         .with_max_health(50.0)
+///         .with_debris_lifetime(Some(10.0))
+///         .build_from_fragment_meshes(graph, fragment_meshes, 10.0)
+/// }
+///
+/// fn on_hit(graph: &mut Graph, destructible: Handle<Node>, damage: f32, point: Vector3<f32>) {
+///     if let Some(destructible) = graph.try_get_mut_of_type::<Destructible>(destructible) {
+///         destructible.apply_damage(damage, Some(point));
+///     }
+/// }
+/// ```
+#[derive(Clone, Reflect, Visit, Debug)]
+#[visit(optional)]
+pub struct Destructible {
+    base: Base,
+
+    /// A handle of a node, that represents the object while it is not fractured. The node will be
+    /// disabled when the object is fractured.
+    pub intact: InheritableVariable<Handle<Node>>,
+
+    /// A set of handles of rigid bodies, that represents the pieces of the object. The bodies are
+    /// disabled until the object is fractured.
+    pub fragments: InheritableVariable<Vec<Handle<Node>>>,
+
+    /// Initial amount of health of the object.
+    #[reflect(min_value = 0.0)]
+    pub max_health: InheritableVariable<f32>,
+
+    /// Initial speed (in m/s) of the fragments. Every fragment moves away from the damage point.
+    #[reflect(min_value = 0.0)]
+    pub explosion_speed: InheritableVariable<f32>,
+
+    /// Amount of time (in seconds) after which the fragments will be removed from the scene graph.
+    /// [`None`] means that the fragments will live forever.
+    pub debris_lifetime: InheritableVariable<Option<f32>>,
+
+    /// Maximum amount of fragments, that will be activated when the object is fractured. [`None`]
+    /// means that every fragment will be activated.
+    pub max_debris: InheritableVariable<Option<usize>>,
+
+    /// A handle of a sound node, that will be played when the object is fractured.
+    pub fracture_sound: InheritableVariable<Handle<Node>>,
+
+    /// A handle of a particle system, that will be played when the object is fractured.
+    pub fracture_effect: InheritableVariable<Handle<Node>>,
+
+    #[reflect(read_only)]
+    health: f32,
+
+    #[reflect(read_only)]
+    is_fractured: bool,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pending_damage: Option<PendingDamage>,
+}
+
+impl Default for Destructible {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            intact: Default::default(),
+            fragments: Default::default(),
+            max_health: 100.0.into(),
+            explosion_speed: 2.0.into(),
+            debris_lifetime: Some(15.0).into(),
+            max_debris: Default::default(),
+            fracture_sound: Default::default(),
+            fracture_effect: Default::default(),
+            health: 100.0,
+            is_fractured: false,
+            pending_damage: None,
+        }
+    }
+}
+
+impl Deref for Destructible {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Destructible {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Destructible {
+    fn type_uuid() -> Uuid {
+        uuid!("3c0b1c3e-4d0f-4a39-9a3c-8b5a2f6e0d71")
+    }
+}
+
+impl Destructible {
+    /// Damages the object by the given amount of health. The point (in world coordinates) defines
+    /// where the damage was applied, fragments will fly away from it. If the point is not specified,
+    /// the position of the object will be used. The damage is applied on the next update.
+    pub fn apply_damage(&mut self, amount: f32, point: Option<Vector3<f32>>) {
+        let pending_damage = self.pending_damage.get_or_insert_with(Default::default);
+        pending_damage.amount += amount.max(0.0);
+        if point.is_some() {
+            pending_damage.point = point;
+        }
+    }
+
+    /// Breaks the object on the next update regardless of its health. See [`Self::apply_damage`]
+    /// for more info about the point.
+    pub fn fracture(&mut self, point: Option<Vector3<f32>>) {
+        self.apply_damage(f32::INFINITY, point);
+    }
+
+    /// Returns `true` if the object is fractured, `false` - otherwise.
+    pub fn is_fractured(&self) -> bool {
+        self.is_fractured
+    }
+
+    /// Returns current amount of health of the object.
+    pub fn health(&self) -> f32 {
+        self.health
+    }
+
+    /// Sets the initial amount of health of the object. Current health is reset to the new value
+    /// if the object is not fractured.
+    pub fn set_max_health(&mut self, max_health: f32) -> f32 {
+        let max_health = max_health.max(0.0);
+        if !self.is_fractured {
+            self.health = max_health;
+        }
+        self.max_health.set_value_and_mark_modified(max_health)
+    }
+
+    /// Returns the initial amount of health of the object.
+    pub fn max_health(&self) -> f32 {
+        *self.max_health
+    }
+
+    /// Sets the initial speed (in m/s) of the fragments.
+    pub fn set_explosion_speed(&mut self, speed: f32) -> f32 {
+        self.explosion_speed
+            .set_value_and_mark_modified(speed.max(0.0))
+    }
+
+    /// Returns the initial speed (in m/s) of the fragments.
+    pub fn explosion_speed(&self) -> f32 {
+        *self.explosion_speed
+    }
+
+    /// Sets the amount of time (in seconds) after which the fragments will be removed from the
+    /// scene graph. [`None`] means that the fragments will live forever.
+    pub fn set_debris_lifetime(&mut self, lifetime: Option<f32>) -> Option<f32> {
+        self.debris_lifetime.set_value_and_mark_modified(lifetime)
+    }
+
+    /// Returns the amount of time (in seconds) after which the fragments will be removed from the
+    /// scene graph.
+    pub fn debris_lifetime(&self) -> Option<f32> {
+        *self.debris_lifetime
+    }
+
+    /// Sets the maximum amount of fragments, that will be activated when the object is fractured.
+    pub fn set_max_debris(&mut self, max_debris: Option<usize>) -> Option<usize> {
+        self.max_debris.set_value_and_mark_modified(max_debris)
+    }
+
+    /// Returns the maximum amount of fragments, that will be activated when the object is fractured.
+    pub fn max_debris(&self) -> Option<usize> {
+        *self.max_debris
+    }
+
+    fn break_apart(&mut self, ctx: &mut UpdateContext, point: Option<Vector3<f32>>) {
+        self.is_fractured = true;
+        self.health = 0.0;
+
+        let origin = point.unwrap_or_else(|| self.global_position());
+
+        if let Some(intact) = ctx.nodes.try_borrow_mut(*self.intact) {
+            intact.set_enabled(false);
+        }
+
+        // Closest fragments are the most noticeable, so they're activated first.
+        let mut fragments = self
+            .fragments
+            .iter()
+            .filter_map(|handle| {
+                ctx.nodes
+                    .try_borrow(*handle)
+                    .map(|fragment| (*handle, fragment.global_position().metric_distance(&origin)))
+            })
+            .collect::<Vec<_>>();
+        fragments.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let max_debris = self.max_debris.unwrap_or(usize::MAX);
+        for (handle, _) in fragments.into_iter().take(max_debris) {
+            let Some(fragment) = ctx.nodes.try_borrow_mut(handle) else {
+                continue;
+            };
+
+            fragment.set_enabled(true);
+            fragment.set_lifetime(*self.debris_lifetime);
+
+            if let Some(body) = fragment.query_component_mut::<RigidBody>() {
+                let direction = (body.global_position() - origin)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::y);
+                body.set_body_type(RigidBodyType::Dynamic);
+                body.set_lin_vel(direction.scale(*self.explosion_speed));
+                body.wake_up();
+            }
+        }
+
+        if let Some(sound) = ctx
+            .nodes
+            .try_borrow_mut(*self.fracture_sound)
+            .and_then(|n| n.query_component_mut::<Sound>())
+        {
+            sound.play();
+        }
+
+        if let Some(effect) = ctx
+            .nodes
+            .try_borrow_mut(*self.fracture_effect)
+            .and_then(|n| n.query_component_mut::<ParticleSystem>())
+        {
+            effect.clear_particles();
+            effect.play(true);
+        }
+    }
+}
+
+impl NodeTrait for Destructible {
+    impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if self.is_fractured {
+            return;
+        }
+
+        // Keep the fragments disabled until the object is fractured.
+        for fragment in self.fragments.iter() {
+            if let Some(fragment) = ctx.nodes.try_borrow_mut(*fragment) {
+                if fragment.is_enabled() {
+                    fragment.set_enabled(false);
+                }
+            }
+        }
+
+        if let Some(damage) = self.pending_damage.take() {
+            self.health -= damage.amount;
+            if self.health <= 0.0 {
+                self.break_apart(ctx, damage.point);
+            }
+        }
+    }
+}
+
+/// Allows you to create [`Destructible`] scene nodes.
+pub struct DestructibleBuilder {
+    base_builder: BaseBuilder,
+    intact: Handle<Node>,
+    fragments: Vec<Handle<Node>>,
+    max_health: f32,
+    explosion_speed: f32,
+    debris_lifetime: Option<f32>,
+    max_debris: Option<usize>,
+    fracture_sound: Handle<Node>,
+    fracture_effect: Handle<Node>,
+}
+
+impl DestructibleBuilder {
+    /// Creates a new destructible builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            intact: Default::default(),
+            fragments: Default::default(),
+            max_health: 100.0,
+            explosion_speed: 2.0,
+            debris_lifetime: Some(15.0),
+            max_debris: None,
+            fracture_sound: Default::default(),
+            fracture_effect: Default::default(),
+        }
+    }
+
+    /// Sets the desired intact node.
+    pub fn with_intact(mut self, intact: Handle<Node>) -> Self {
+        self.intact = intact;
+        self
+    }
+
+    /// Sets the desired fragments. Every fragment should be a rigid body.
+    pub fn with_fragments(mut self, fragments: Vec<Handle<Node>>) -> Self {
+        self.fragments = fragments;
+        self
+    }
+
+    /// Sets the desired initial amount of health.
+    pub fn with_max_health(mut self, max_health: f32) -> Self {
+        self.max_health = max_health.max(0.0);
+        self
+    }
+
+    /// Sets the desired initial speed of the fragments.
+    pub fn with_explosion_speed(mut self, speed: f32) -> Self {
+        self.explosion_speed = speed.max(0.0);
+        self
+    }
+
+    /// Sets the desired lifetime of the fragments.
+    pub fn with_debris_lifetime(mut self, lifetime: Option<f32>) -> Self {
+        self.debris_lifetime = lifetime;
+        self
+    }
+
+    /// Sets the desired maximum amount of fragments, that will be activated on fracturing.
+    pub fn with_max_debris(mut self, max_debris: Option<usize>) -> Self {
+        self.max_debris = max_debris;
+        self
+    }
+
+    /// Sets the desired sound node, that will be played on fracturing.
+    pub fn with_fracture_sound(mut self, sound: Handle<Node>) -> Self {
+        self.fracture_sound = sound;
+        self
+    }
+
+    /// Sets the desired particle system, that will be played on fracturing.
+    pub fn with_fracture_effect(mut self, effect: Handle<Node>) -> Self {
+        self.fracture_effect = effect;
+        self
+    }
+
+    /// Creates new [`Destructible`] instance.
+    pub fn build_destructible(self) -> Destructible {
+        Destructible {
+            base: self.base_builder.build_base(),
+            intact: self.intact.into(),
+            fragments: self.fragments.into(),
+            max_health: self.max_health.into(),
+            explosion_speed: self.explosion_speed.into(),
+            debris_lifetime: self.debris_lifetime.into(),
+            max_debris: self.max_debris.into(),
+            fracture_sound: self.fracture_sound.into(),
+            fracture_effect: self.fracture_effect.into(),
+            health: self.max_health,
+            is_fractured: false,
+            pending_damage: None,
+        }
+    }
+
+    /// Creates new [`Destructible`] node, but does not add it to a graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_destructible())
+    }
+
+    /// Creates new [`Destructible`] node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+
+    /// Creates new [`Destructible`] node and automatically creates its fragments from the given
+    /// meshes of pre-fractured pieces. Every mesh gets a disabled rigid body with a convex collider,
+    /// the body is placed at the center of the mesh and linked to the destructible node. The given
+    /// mass is distributed across the fragments proportionally to their volume. Fragments of the
+    /// builder are ignored.
+    pub fn build_from_fragment_meshes(
+        self,
+        graph: &mut Graph,
+        fragment_meshes: &[Handle<Node>],
+        total_mass: f32,
+    ) -> Handle<Node> {
+        graph.update_hierarchical_data();
+
+        let destructible = self.build(graph);
+        let destructible_transform_inv = graph[destructible]
+            .global_transform()
+            .try_inverse()
+            .unwrap_or_default();
+
+        let valid_meshes = fragment_meshes
+            .iter()
+            .filter_map(|mesh| {
+                graph
+                    .try_get(*mesh)
+                    .map(|node| (*mesh, node.world_bounding_box()))
+            })
+            .collect::<Vec<_>>();
+        let total_volume = valid_meshes
+            .iter()
+            .map(|(_, aabb)| aabb.volume())
+            .sum::<f32>()
+            .max(f32::EPSILON);
+
+        let mut fragments = Vec::with_capacity(valid_meshes.len());
+        for (mesh, aabb) in valid_meshes {
+            let name = graph[mesh].name().to_owned();
+
+            let collider =
+                ColliderBuilder::new(BaseBuilder::new().with_name(format!("{name}Collider")))
+                    .with_shape(ColliderShape::Polyhedron(ConvexPolyhedronShape {
+                        geometry_source: GeometrySource(mesh),
+                    }))
+                    .build(graph);
+
+            let body = RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("{name}Body"))
+                    .with_enabled(false)
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(
+                                destructible_transform_inv
+                                    .transform_point(&aabb.center().into())
+                                    .coords,
+                            )
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_mass(total_mass * aabb.volume() / total_volume)
+            .with_body_type(RigidBodyType::Dynamic)
+            .build(graph);
+
+            graph.link_nodes(body, destructible);
+            graph.update_hierarchical_data();
+            graph.link_nodes_keep_global_position_rotation(mesh, body);
+
+            fragments.push(body);
+        }
+
+        if let Some(destructible) = graph.try_get_mut_of_type::<Destructible>(destructible) {
+            destructible
+                .fragments
+                .set_value_and_mark_modified(fragments);
+        }
+
+        destructible
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector2, Vector3},
+            pool::Handle,
+        },
+        graph::SceneGraph,
+        scene::{
+            base::BaseBuilder,
+            destructible::{Destructible, DestructibleBuilder},
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                MeshBuilder,
+            },
+            node::Node,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBody, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+    use fyrox_resource::untyped::ResourceKind;
+
+    fn make_cube(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .build()])
+        .build(graph)
+    }
+
+    #[test]
+    fn test_destructible_fracture() {
+        let mut graph = Graph::new();
+
+        let intact = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let near = make_cube(&mut graph, Vector3::new(1.0, 0.0, 0.0));
+        let far = make_cube(&mut graph, Vector3::new(-3.0, 0.0, 0.0));
+
+        let destructible = DestructibleBuilder::new(BaseBuilder::new())
+            .with_intact(intact)
+            .with_max_health(10.0)
+            .with_max_debris(Some(1))
+            .with_debris_lifetime(Some(5.0))
+            .build_from_fragment_meshes(&mut graph, &[near, far], 2.0);
+
+        let fragments = graph
+            .try_get_of_type::<Destructible>(destructible)
+            .unwrap()
+            .fragments
+            .clone_inner();
+        assert_eq!(fragments.len(), 2);
+        for (fragment, mesh) in fragments.iter().zip([near, far]) {
+            let body = graph.try_get_of_type::<RigidBody>(*fragment).unwrap();
+            assert!(!body.is_enabled());
+            assert_eq!(body.mass(), 1.0);
+            assert_eq!(graph[mesh].parent(), *fragment);
+        }
+
+        let dt = 1.0 / 60.0;
+
+        let destructible_mut = graph
+            .try_get_mut_of_type::<Destructible>(destructible)
+            .unwrap();
+        destructible_mut.apply_damage(4.0, Some(Vector3::new(0.0, 0.0, 0.0)));
+        graph.update(Vector2::new(100.0, 100.0), dt, Default::default());
+
+        let destructible_ref = graph.try_get_of_type::<Destructible>(destructible).unwrap();
+        assert!(!destructible_ref.is_fractured());
+        assert_eq!(destructible_ref.health(), 6.0);
+        assert!(graph[intact].is_enabled());
+
+        graph
+            .try_get_mut_of_type::<Destructible>(destructible)
+            .unwrap()
+            .apply_damage(6.0, Some(Vector3::new(0.0, 0.0, 0.0)));
+        graph.update(Vector2::new(100.0, 100.0), dt, Default::default());
+
+        assert!(graph
+            .try_get_of_type::<Destructible>(destructible)
+            .unwrap()
+            .is_fractured());
+        assert!(!graph[intact].is_enabled());
+
+        // Only the closest fragment must be activated because of the debris budget.
+        let near_body = graph.try_get_of_type::<RigidBody>(fragments[0]).unwrap();
+        assert!(near_body.is_enabled());
+        assert_eq!(near_body.body_type(), RigidBodyType::Dynamic);
+        assert!(near_body.lifetime().is_some_and(|lifetime| lifetime > 4.9));
+        assert!(near_body.lin_vel().x > 0.0);
+        assert!(!graph[fragments[1]].is_enabled());
+    }
+}
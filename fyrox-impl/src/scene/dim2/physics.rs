@@ -56,6 +56,7 @@ use crate::{
         tilemap::{tileset::TileCollider, TileMap},
     },
 };
+use fxhash::FxHashMap;
 pub use rapier2d::geometry::shape::*;
 use rapier2d::{
     dynamics::{
@@ -278,6 +279,38 @@ pub struct IntersectionPair {
     pub has_any_active_contact: bool,
 }
 
+/// A collision event, that was generated during a simulation step. See
+/// [`PhysicsWorld::collision_events`] docs for more info.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollisionEvent {
+    /// Two colliders started touching each other. Contains contact points and impulses of the
+    /// contact at the moment of the event.
+    ContactStarted(ContactPair),
+    /// Two colliders stopped touching each other. The handles could point to deleted colliders,
+    /// if the contact was stopped because of deletion of a collider.
+    ContactStopped {
+        /// The first collider involved in the contact pair.
+        collider1: Handle<Node>,
+        /// The second collider involved in the contact pair.
+        collider2: Handle<Node>,
+    },
+    /// A collider entered a trigger volume (a sensor collider).
+    TriggerEntered {
+        /// A sensor collider.
+        sensor: Handle<Node>,
+        /// A collider, that entered the sensor.
+        collider: Handle<Node>,
+    },
+    /// A collider left a trigger volume (a sensor collider). The handles could point to deleted
+    /// colliders, if the intersection was stopped because of deletion of a collider.
+    TriggerExited {
+        /// A sensor collider.
+        sensor: Handle<Node>,
+        /// A collider, that left the sensor.
+        collider: Handle<Node>,
+    },
+}
+
 pub(super) struct Container<S, A>
 where
     A: Hash + Eq + Clone,
@@ -504,6 +537,17 @@ pub struct PhysicsWorld {
     #[visit(skip)]
     #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
+    // Contact and intersection pairs of the previous simulation step, they're used to generate
+    // collision events.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_contacts: FxHashMap<(ColliderHandle, ColliderHandle), (Handle<Node>, Handle<Node>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_intersections: FxHashMap<(ColliderHandle, ColliderHandle), (Handle<Node>, Handle<Node>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    collision_events: Vec<CollisionEvent>,
 }
 
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry2<f32> {
@@ -602,6 +646,9 @@ impl PhysicsWorld {
             query: RefCell::new(Default::default()),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
+            active_contacts: Default::default(),
+            active_intersections: Default::default(),
+            collision_events: Default::default(),
         }
     }
 
@@ -657,11 +704,105 @@ impl PhysicsWorld {
                 &(),
                 &*self.event_handler,
             );
+
+            self.update_collision_events();
+        } else {
+            self.collision_events.clear();
         }
 
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    fn update_collision_events(&mut self) {
+        let mut events = Vec::new();
+
+        let mut previous_contacts = std::mem::take(&mut self.active_contacts);
+        let mut contacts = FxHashMap::default();
+        for pair in self
+            .narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+        {
+            let key = (pair.collider1, pair.collider2);
+            if let Some(nodes) = previous_contacts.remove(&key) {
+                contacts.insert(key, nodes);
+            } else if let Some(contact) = ContactPair::from_native(pair, self) {
+                contacts.insert(key, (contact.collider1, contact.collider2));
+                events.push(CollisionEvent::ContactStarted(contact));
+            }
+        }
+        events.extend(
+            previous_contacts
+                .into_values()
+                .map(|(collider1, collider2)| CollisionEvent::ContactStopped {
+                    collider1,
+                    collider2,
+                }),
+        );
+
+        let mut previous_intersections = std::mem::take(&mut self.active_intersections);
+        let mut intersections = FxHashMap::default();
+        for (collider1, collider2, _) in self
+            .narrow_phase
+            .intersection_pairs()
+            .filter(|(_, _, intersecting)| *intersecting)
+        {
+            let key = (collider1, collider2);
+            if let Some(nodes) = previous_intersections.remove(&key) {
+                intersections.insert(key, nodes);
+            } else if let (Some(native1), Some(native2)) =
+                (self.colliders.get(collider1), self.colliders.get(collider2))
+            {
+                let node1 = Handle::decode_from_u128(native1.user_data);
+                let node2 = Handle::decode_from_u128(native2.user_data);
+                let (sensor, collider) = if native1.is_sensor() {
+                    (node1, node2)
+                } else {
+                    (node2, node1)
+                };
+                intersections.insert(key, (sensor, collider));
+                events.push(CollisionEvent::TriggerEntered { sensor, collider });
+            }
+        }
+        events.extend(
+            previous_intersections
+                .into_values()
+                .map(|(sensor, collider)| CollisionEvent::TriggerExited { sensor, collider }),
+        );
+
+        self.active_contacts = contacts;
+        self.active_intersections = intersections;
+        self.collision_events = events;
+    }
+
+    /// Returns collision events (started and stopped contacts, trigger enter and exit), that were
+    /// generated during the last simulation step. The events are generated for every collider in
+    /// the world and stay available until the next simulation step.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{
+    /// #     core::pool::Handle,
+    /// #     scene::{dim2::physics::CollisionEvent, graph::Graph, node::Node},
+    /// # };
+    /// fn is_pickup_touched(graph: &Graph, player: Handle<Node>, pickup: Handle<Node>) -> bool {
+    ///     graph.physics2d.collision_events().iter().any(|event| {
+    ///         matches!(event, CollisionEvent::TriggerEntered { sensor, collider }
+    ///             if *sensor == pickup && *collider == player)
+    ///     })
+    /// }
+    /// ```
+    pub fn collision_events(&self) -> &[CollisionEvent] {
+        &self.collision_events
+    }
+
+    /// Takes collision events of the last simulation step, leaving the queue empty. See
+    /// [`Self::collision_events`] for more info.
+    pub fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
     pub(crate) fn add_body(&mut self, owner: Handle<Node>, mut body: RigidBody) -> RigidBodyHandle {
         body.user_data = owner.encode_to_u128();
         self.bodies.insert(body)
@@ -982,6 +1123,9 @@ impl PhysicsWorld {
                     rigid_body_node
                         .ccd_enabled
                         .try_sync_model(|v| native.enable_ccd(v));
+                    rigid_body_node
+                        .soft_ccd_prediction
+                        .try_sync_model(|v| native.set_soft_ccd_prediction(v));
                     rigid_body_node.can_sleep.try_sync_model(|v| {
                         let activation = native.activation_mut();
                         if v {
@@ -1047,6 +1191,7 @@ impl PhysicsWorld {
                     &rigid_body_node.global_transform(),
                 ))
                 .ccd_enabled(rigid_body_node.is_ccd_enabled())
+                .soft_ccd_prediction(rigid_body_node.soft_ccd_prediction())
                 .additional_mass(rigid_body_node.mass())
                 .angvel(*rigid_body_node.ang_vel)
                 .linvel(*rigid_body_node.lin_vel)
@@ -152,9 +152,30 @@ impl Hash for RectangleVertex {
 /// The same property could also be changed in the editor using the Material Editor invoked from
 /// the `Material` property in the Inspector.
 ///
+/// ## Sorting
+///
+/// Drawing order of rectangles is defined by their sorting layer and order in the layer, see
+/// [`Rectangle::set_sorting_layer`] and [`Rectangle::set_order_in_layer`]. It allows you to put
+/// backgrounds, characters, effects, etc. on separate layers without relying on their positions:
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     scene::{base::BaseBuilder, dim2::rectangle::RectangleBuilder, graph::Graph, node::Node},
+/// # };
+/// fn create_character(graph: &mut Graph) -> Handle<Node> {
+///     // Background is on the layer 0, so the character will always be drawn on top of it.
+///     RectangleBuilder::new(BaseBuilder::new())
+///         .with_sorting_layer(1)
+///         .build(graph)
+/// }
+/// ```
+///
 /// ## Performance
 ///
-/// Rectangles use batching to let you draw tons of rectangles with high performance.
+/// Rectangles use batching to let you draw tons of rectangles with high performance. Rectangles
+/// with the same material are drawn in a single draw call, in the order of their appearance in the
+/// scene graph. Use separate materials for rectangles that must be sorted against each other.
 ///
 /// ## Specifying region for rendering
 ///
@@ -174,6 +195,19 @@ pub struct Rectangle {
     uv_rect: InheritableVariable<Rect<f32>>,
 
     material: InheritableVariable<MaterialResource>,
+
+    #[reflect(
+        setter = "set_sorting_layer",
+        description = "Rectangles on higher sorting layers are drawn on top of rectangles on lower layers."
+    )]
+    sorting_layer: InheritableVariable<i16>,
+
+    #[reflect(
+        setter = "set_order_in_layer",
+        description = "Defines drawing order of rectangles within the same sorting layer. Rectangles \
+        with higher order are drawn on top of rectangles with lower order."
+    )]
+    order_in_layer: InheritableVariable<i16>,
 }
 
 impl Visit for Rectangle {
@@ -195,6 +229,8 @@ impl Visit for Rectangle {
         self.base.visit("Base", &mut region)?;
         self.color.visit("Color", &mut region)?;
         let _ = self.uv_rect.visit("UvRect", &mut region);
+        let _ = self.sorting_layer.visit("SortingLayer", &mut region);
+        let _ = self.order_in_layer.visit("OrderInLayer", &mut region);
 
         Ok(())
     }
@@ -210,6 +246,8 @@ impl Default for Rectangle {
                 Default::default(),
                 Material::standard_2d(),
             )),
+            sorting_layer: Default::default(),
+            order_in_layer: Default::default(),
         }
     }
 }
@@ -273,6 +311,38 @@ impl Rectangle {
     pub fn set_uv_rect(&mut self, uv_rect: Rect<f32>) -> Rect<f32> {
         self.uv_rect.set_value_and_mark_modified(uv_rect)
     }
+
+    /// Sets new sorting layer of the rectangle. Rectangles on higher sorting layers are drawn on top
+    /// of rectangles on lower layers, regardless of their position. Default is 0.
+    pub fn set_sorting_layer(&mut self, layer: i16) -> i16 {
+        self.sorting_layer.set_value_and_mark_modified(layer)
+    }
+
+    /// Returns current sorting layer of the rectangle.
+    pub fn sorting_layer(&self) -> i16 {
+        *self.sorting_layer
+    }
+
+    /// Sets new order of the rectangle within its sorting layer. Rectangles with higher order are
+    /// drawn on top of rectangles with lower order in the same layer. Rectangles with the same layer
+    /// and order are drawn in the order of their appearance in the scene graph. Default is 0.
+    pub fn set_order_in_layer(&mut self, order: i16) -> i16 {
+        self.order_in_layer.set_value_and_mark_modified(order)
+    }
+
+    /// Returns current order of the rectangle within its sorting layer.
+    pub fn order_in_layer(&self) -> i16 {
+        *self.order_in_layer
+    }
+
+    /// Returns sorting index of the rectangle. Its sorting layer and order in the layer are stored
+    /// in the high bits of the index, so they take precedence over the given distance-based index
+    /// (see [`RenderContext::calculate_sorting_index`]), which is stored in the low 32 bits.
+    pub fn sorting_index(&self, distance_index: u64) -> u64 {
+        let layer = (*self.sorting_layer as i32 - i16::MIN as i32) as u64;
+        let order = (*self.order_in_layer as i32 - i16::MIN as i32) as u64;
+        (layer << 48) | (order << 32) | (distance_index & u32::MAX as u64)
+    }
 }
 
 impl NodeTrait for Rectangle {
@@ -337,7 +407,7 @@ impl NodeTrait for Rectangle {
 
         let triangles = [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])];
 
-        let sort_index = ctx.calculate_sorting_index(self.global_position());
+        let sort_index = self.sorting_index(ctx.calculate_sorting_index(self.global_position()));
 
         ctx.storage.push_triangles(
             Vertex::layout(),
@@ -369,6 +439,8 @@ pub struct RectangleBuilder {
     color: Color,
     uv_rect: Rect<f32>,
     material: MaterialResource,
+    sorting_layer: i16,
+    order_in_layer: i16,
 }
 
 impl RectangleBuilder {
@@ -379,6 +451,8 @@ impl RectangleBuilder {
             color: Color::WHITE,
             uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
             material: MaterialResource::new_ok(Default::default(), Material::standard_2d()),
+            sorting_layer: 0,
+            order_in_layer: 0,
         }
     }
 
@@ -401,6 +475,20 @@ impl RectangleBuilder {
         self
     }
 
+    /// Sets the desired sorting layer of the rectangle. See [`Rectangle::set_sorting_layer`] for
+    /// more info.
+    pub fn with_sorting_layer(mut self, layer: i16) -> Self {
+        self.sorting_layer = layer;
+        self
+    }
+
+    /// Sets the desired order of the rectangle within its sorting layer. See
+    /// [`Rectangle::set_order_in_layer`] for more info.
+    pub fn with_order_in_layer(mut self, order: i16) -> Self {
+        self.order_in_layer = order;
+        self
+    }
+
     /// Creates new [`Rectangle`] instance.
     pub fn build_rectangle(self) -> Rectangle {
         Rectangle {
@@ -408,6 +496,8 @@ impl RectangleBuilder {
             color: self.color.into(),
             uv_rect: self.uv_rect.into(),
             material: self.material.into(),
+            sorting_layer: self.sorting_layer.into(),
+            order_in_layer: self.order_in_layer.into(),
         }
     }
 
@@ -421,3 +511,30 @@ impl RectangleBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{base::BaseBuilder, dim2::rectangle::RectangleBuilder};
+
+    #[test]
+    fn test_rectangle_sorting_index() {
+        let index = |layer, order, distance_index| {
+            RectangleBuilder::new(BaseBuilder::new())
+                .with_sorting_layer(layer)
+                .with_order_in_layer(order)
+                .build_rectangle()
+                .sorting_index(distance_index)
+        };
+
+        assert!(index(0, 0, 0) < index(0, 1, 0));
+        assert!(index(0, i16::MAX, 0) < index(1, i16::MIN, 0));
+        assert!(index(-1, 5, 0) < index(0, -5, 0));
+        assert!(index(i16::MIN, i16::MIN, 0) < index(i16::MAX, i16::MAX, 0));
+        assert_eq!(index(2, 3, 0), index(2, 3, 0));
+
+        // Distance is used to sort rectangles within the same layer and order.
+        assert!(index(0, 0, u64::MAX - 2000) < index(0, 0, u64::MAX - 1000));
+        assert!(index(0, 0, u64::MAX) < index(0, 1, u64::MAX - 1000));
+        assert!(index(0, 0, u64::MAX) < index(1, 0, 0));
+    }
+}
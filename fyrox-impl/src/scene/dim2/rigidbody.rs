@@ -115,6 +115,11 @@ pub struct RigidBody {
     #[reflect(setter = "enable_ccd")]
     pub(crate) ccd_enabled: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_soft_ccd_prediction")]
+    pub(crate) soft_ccd_prediction: InheritableVariable<f32>,
+
     #[reflect(setter = "set_can_sleep")]
     pub(crate) can_sleep: InheritableVariable<bool>,
 
@@ -161,6 +166,7 @@ impl Default for RigidBody {
             rotation_locked: Default::default(),
             translation_locked: Default::default(),
             ccd_enabled: Default::default(),
+            soft_ccd_prediction: Default::default(),
             can_sleep: InheritableVariable::new_modified(true),
             dominance: Default::default(),
             gravity_scale: InheritableVariable::new_modified(1.0),
@@ -199,6 +205,7 @@ impl Clone for RigidBody {
             rotation_locked: self.rotation_locked.clone(),
             translation_locked: self.translation_locked.clone(),
             ccd_enabled: self.ccd_enabled.clone(),
+            soft_ccd_prediction: self.soft_ccd_prediction.clone(),
             can_sleep: self.can_sleep.clone(),
             dominance: self.dominance.clone(),
             gravity_scale: self.gravity_scale.clone(),
@@ -319,6 +326,21 @@ impl RigidBody {
         self.ccd_enabled.set_value_and_mark_modified(enable)
     }
 
+    /// Returns the distance used by soft (predictive) continuous collision detection.
+    pub fn soft_ccd_prediction(&self) -> f32 {
+        *self.soft_ccd_prediction
+    }
+
+    /// Sets the distance used by soft (predictive) continuous collision detection. Soft CCD
+    /// enlarges the area in which contacts are searched for, so fast moving objects are less
+    /// likely to tunnel through thin obstacles. It is cheaper than the regular CCD (see
+    /// [`Self::enable_ccd`]), but it is not guaranteed to prevent tunnelling. Zero disables
+    /// soft CCD.
+    pub fn set_soft_ccd_prediction(&mut self, prediction: f32) -> f32 {
+        self.soft_ccd_prediction
+            .set_value_and_mark_modified(prediction.max(0.0))
+    }
+
     /// Sets a gravity scale coefficient. Zero can be used to disable gravity.
     pub fn set_gravity_scale(&mut self, scale: f32) -> f32 {
         self.gravity_scale.set_value_and_mark_modified(scale)
@@ -415,6 +437,7 @@ impl RigidBody {
             || self.rotation_locked.need_sync()
             || self.translation_locked.need_sync()
             || self.ccd_enabled.need_sync()
+            || self.soft_ccd_prediction.need_sync()
             || self.can_sleep.need_sync()
             || self.dominance.need_sync()
             || self.gravity_scale.need_sync()
@@ -497,6 +520,7 @@ pub struct RigidBodyBuilder {
     rotation_locked: bool,
     translation_locked: bool,
     ccd_enabled: bool,
+    soft_ccd_prediction: f32,
     can_sleep: bool,
     dominance: i8,
     gravity_scale: f32,
@@ -517,6 +541,7 @@ impl RigidBodyBuilder {
             rotation_locked: false,
             translation_locked: false,
             ccd_enabled: false,
+            soft_ccd_prediction: 0.0,
             can_sleep: true,
             dominance: 0,
             gravity_scale: 1.0,
@@ -541,6 +566,13 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the distance used by soft continuous collision detection. See
+    /// [`RigidBody::set_soft_ccd_prediction`] for more info.
+    pub fn with_soft_ccd_prediction(mut self, prediction: f32) -> Self {
+        self.soft_ccd_prediction = prediction;
+        self
+    }
+
     /// Sets desired linear velocity.
     pub fn with_lin_vel(mut self, lin_vel: Vector2<f32>) -> Self {
         self.lin_vel = lin_vel;
@@ -615,6 +647,7 @@ impl RigidBodyBuilder {
             rotation_locked: self.rotation_locked.into(),
             translation_locked: self.translation_locked.into(),
             ccd_enabled: self.ccd_enabled.into(),
+            soft_ccd_prediction: self.soft_ccd_prediction.into(),
             can_sleep: self.can_sleep.into(),
             dominance: self.dominance.into(),
             gravity_scale: self.gravity_scale.into(),
@@ -0,0 +1,600 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Fluid volume is a box-shaped volume of fluid (water, for example), that applies buoyancy and
+//! drag to rigid bodies inside it. See [`FluidVolume`] docs for more info and usage examples.
+
+use crate::{
+    core::{
+        algebra::{Isometry3, Point3, Translation3, UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::BaseSceneGraph,
+    impl_query_component,
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::Collider,
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{Node, NodeTrait, UpdateContext},
+        rigidbody::{RigidBody, RigidBodyType},
+    },
+};
+use fxhash::FxHashSet;
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+/// An event, produced by [`FluidVolume`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FluidEvent {
+    /// A rigid body has entered the fluid. It could be used to play a splash sound or to spawn
+    /// a particle effect.
+    Splash {
+        /// A handle of the rigid body.
+        body: Handle<Node>,
+        /// A point on the surface of the fluid where the body has entered it.
+        position: Vector3<f32>,
+        /// Velocity of the body at the moment of entering.
+        velocity: Vector3<f32>,
+    },
+    /// A rigid body has left the fluid.
+    Exited {
+        /// A handle of the rigid body.
+        body: Handle<Node>,
+    },
+}
+
+/// Fluid volume is a box-shaped volume of fluid (water, for example), that applies buoyancy and
+/// drag to dynamic rigid bodies inside it. It does not render anything, use it together with a
+/// mesh or a [`crate::scene::mirror::Mirror`] that represents the surface of the fluid.
+///
+/// ## Surface
+///
+/// The volume occupies a box of [`FluidVolume::size`] centered at the position of the node (scale
+/// of the node is ignored). The surface of the fluid is the top face of the box. The surface could
+/// be made uneven (for example, to match waves of water) using a heightfield - a grid of height
+/// offsets that is stretched over the top face of the box, see [`FluidVolume::set_heightfield`].
+/// Use [`FluidVolume::surface_height`] to get the height of the surface at any point.
+///
+/// ## Buoyancy and drag
+///
+/// Every collider of a dynamic rigid body inside the volume is pushed up by the force that is equal
+/// to the weight of the fluid displaced by the submerged part of the collider. The submerged part
+/// is estimated using the bounding box of the collider, which is good enough for games. The force
+/// is applied at the center of the submerged part, so floating bodies tend to stabilize. Bodies
+/// are also slowed down by [`FluidVolume::linear_drag`] and [`FluidVolume::angular_drag`].
+///
+/// ## Events
+///
+/// The volume produces [`FluidEvent`]s when bodies enter or leave it, use [`FluidVolume::pop_event`]
+/// to fetch them.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     graph::SceneGraph,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         fluid::{FluidEvent, FluidVolume, FluidVolumeBuilder},
+/// #         graph::Graph,
+/// #         node::Node,
+/// #     },
+/// # };
+/// fn make_lake(graph: &mut Graph) -> Handle<Node> {
+///     FluidVolumeBuilder::new(BaseBuilder::new().with_name("Lake"))
+///         .with_size(Vector3::new(100.0, 5.0, 100.0))
+///         .with_density(1000.0)
+///         .build(graph)
+/// }
+///
+/// fn handle_events(graph: &mut Graph, lake: Handle<Node>) {
+///     if let Some(lake) = graph.try_get_mut_of_type::<FluidVolume>(lake) {
+///         while let Some(event) = lake.pop_event() {
+///             if let FluidEvent::Splash { position, .. } = event {
+///                 println!("Splash at {position:?}");
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Reflect, Visit, Debug)]
+#[visit(optional)]
+pub struct FluidVolume {
+    base: Base,
+
+    /// Size of the volume along each axis.
+    pub size: InheritableVariable<Vector3<f32>>,
+
+    /// Density of the fluid in kg/m³. Water has the density of 1000 kg/m³. Bodies with lower
+    /// density will float, bodies with higher density will sink.
+    #[reflect(min_value = 0.0)]
+    pub density: InheritableVariable<f32>,
+
+    /// Linear drag of the fluid. It defines how fast a body with the density of the fluid loses
+    /// its linear velocity.
+    #[reflect(min_value = 0.0)]
+    pub linear_drag: InheritableVariable<f32>,
+
+    /// Angular drag of the fluid. It defines how fast a body with the density of the fluid loses
+    /// its angular velocity.
+    #[reflect(min_value = 0.0)]
+    pub angular_drag: InheritableVariable<f32>,
+
+    /// Minimal speed of a body, that is required to produce [`FluidEvent::Splash`] when the body
+    /// enters the fluid.
+    #[reflect(min_value = 0.0)]
+    pub min_splash_speed: InheritableVariable<f32>,
+
+    /// Size of the heightfield grid. See [`Self::set_heightfield`] for more info.
+    pub heightfield_size: InheritableVariable<Vector2<u32>>,
+
+    /// Height offsets of the surface. See [`Self::set_heightfield`] for more info.
+    pub heights: InheritableVariable<Vec<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    bodies_inside: FxHashSet<Handle<Node>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    events: VecDeque<FluidEvent>,
+}
+
+impl Default for FluidVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            size: Vector3::new(10.0, 2.0, 10.0).into(),
+            density: 1000.0.into(),
+            linear_drag: 1.0.into(),
+            angular_drag: 1.0.into(),
+            min_splash_speed: 1.0.into(),
+            heightfield_size: Default::default(),
+            heights: Default::default(),
+            bodies_inside: Default::default(),
+            events: Default::default(),
+        }
+    }
+}
+
+impl Deref for FluidVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for FluidVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for FluidVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("b7d0f5a2-6c1e-4f0b-9e53-2a4c8d91e6f3")
+    }
+}
+
+impl FluidVolume {
+    /// Sets the size of the volume along each axis.
+    pub fn set_size(&mut self, size: Vector3<f32>) -> Vector3<f32> {
+        self.size
+            .set_value_and_mark_modified(size.sup(&Vector3::zeros()))
+    }
+
+    /// Returns the size of the volume along each axis.
+    pub fn size(&self) -> Vector3<f32> {
+        *self.size
+    }
+
+    /// Sets the density of the fluid in kg/m³.
+    pub fn set_density(&mut self, density: f32) -> f32 {
+        self.density.set_value_and_mark_modified(density.max(0.0))
+    }
+
+    /// Returns the density of the fluid in kg/m³.
+    pub fn density(&self) -> f32 {
+        *self.density
+    }
+
+    /// Sets the linear drag of the fluid.
+    pub fn set_linear_drag(&mut self, drag: f32) -> f32 {
+        self.linear_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns the linear drag of the fluid.
+    pub fn linear_drag(&self) -> f32 {
+        *self.linear_drag
+    }
+
+    /// Sets the angular drag of the fluid.
+    pub fn set_angular_drag(&mut self, drag: f32) -> f32 {
+        self.angular_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns the angular drag of the fluid.
+    pub fn angular_drag(&self) -> f32 {
+        *self.angular_drag
+    }
+
+    /// Sets a heightfield of the surface. The heightfield is a grid of `size.x * size.y` height
+    /// offsets (row by row, rows go along Z axis), that is stretched over the top face of the
+    /// volume. The offsets are added to the height of the top face, heights between the grid points
+    /// are interpolated. The heightfield could be changed every frame to simulate waves. An empty
+    /// heightfield (or a heightfield with mismatching amount of heights) means flat surface.
+    pub fn set_heightfield(&mut self, size: Vector2<u32>, heights: Vec<f32>) {
+        self.heightfield_size.set_value_and_mark_modified(size);
+        self.heights.set_value_and_mark_modified(heights);
+    }
+
+    /// Tries to fetch the next event of the volume.
+    pub fn pop_event(&mut self) -> Option<FluidEvent> {
+        self.events.pop_front()
+    }
+
+    /// Returns `true` if the given rigid body is inside the fluid.
+    pub fn contains_body(&self, body: Handle<Node>) -> bool {
+        self.bodies_inside.contains(&body)
+    }
+
+    fn isometry(&self) -> Isometry3<f32> {
+        let transform = self.global_transform();
+        Isometry3 {
+            translation: Translation3::from(transform.position()),
+            rotation: UnitQuaternion::from_matrix_eps(
+                &transform.basis(),
+                f32::EPSILON,
+                16,
+                Default::default(),
+            ),
+        }
+    }
+
+    fn local_height_offset(&self, local_point: Vector2<f32>) -> f32 {
+        let size = *self.heightfield_size;
+        let (width, depth) = (size.x as usize, size.y as usize);
+        if width == 0 || depth == 0 || self.heights.len() != width * depth {
+            return 0.0;
+        }
+
+        // Normalized coordinates of the point on the top face.
+        let u = (local_point.x / self.size.x + 0.5).clamp(0.0, 1.0) * (width - 1) as f32;
+        let v = (local_point.y / self.size.z + 0.5).clamp(0.0, 1.0) * (depth - 1) as f32;
+
+        let x0 = (u.floor() as usize).min(width - 1);
+        let z0 = (v.floor() as usize).min(depth - 1);
+        let x1 = (x0 + 1).min(width - 1);
+        let z1 = (z0 + 1).min(depth - 1);
+        let tx = u - x0 as f32;
+        let tz = v - z0 as f32;
+
+        let height = |x: usize, z: usize| self.heights[z * width + x];
+        let near = height(x0, z0) + (height(x1, z0) - height(x0, z0)) * tx;
+        let far = height(x0, z1) + (height(x1, z1) - height(x0, z1)) * tx;
+        near + (far - near) * tz
+    }
+
+    /// Returns the height (world Y coordinate) of the surface of the fluid at the given point. Only
+    /// X and Z coordinates of the point are used.
+    pub fn surface_height(&self, point: Vector3<f32>) -> f32 {
+        let isometry = self.isometry();
+        let local_point = isometry.inverse_transform_point(&Point3::from(point));
+        let local_height = self.size.y * 0.5
+            + self.local_height_offset(Vector2::new(local_point.x, local_point.z));
+        isometry
+            .transform_point(&Point3::new(local_point.x, local_height, local_point.z))
+            .y
+    }
+}
+
+impl NodeTrait for FluidVolume {
+    impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_size = self.size.scale(0.5);
+        AxisAlignedBoundingBox::from_min_max(-half_size, half_size)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &self.local_bounding_box(),
+            self.isometry().to_homogeneous(),
+            Color::opaque(0, 120, 255),
+        );
+    }
+
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let isometry = self.isometry();
+        let hits = ctx.physics.overlap_box(
+            Point3::from(isometry.translation.vector),
+            self.size.scale(0.5),
+            isometry.rotation,
+            Default::default(),
+        );
+
+        let gravity = *ctx.physics.gravity;
+
+        let mut bodies_inside = FxHashSet::default();
+        for hit in hits {
+            let Some(collider) = ctx
+                .nodes
+                .try_borrow(hit.collider)
+                .and_then(|n| n.query_component_ref::<Collider>())
+            else {
+                continue;
+            };
+            let body_handle = collider.parent();
+            let Some((aabb, volume)) = ctx.physics.collider_bounds(collider.native.get()) else {
+                continue;
+            };
+            let Some(body) = ctx
+                .nodes
+                .try_borrow_mut(body_handle)
+                .and_then(|n| n.query_component_mut::<RigidBody>())
+            else {
+                continue;
+            };
+            if body.body_type() != RigidBodyType::Dynamic {
+                continue;
+            }
+
+            let center = aabb.center();
+            let surface = self.surface_height(center);
+            let height = (aabb.max.y - aabb.min.y).max(f32::EPSILON);
+            let submerged = ((surface - aabb.min.y) / height).clamp(0.0, 1.0);
+            if submerged <= 0.0 {
+                continue;
+            }
+
+            // Archimedes' principle - the force is equal to the weight of the displaced fluid.
+            let displaced_mass = *self.density * volume * submerged;
+            let point = Vector3::new(center.x, aabb.min.y + height * submerged * 0.5, center.z);
+            body.apply_force_at_point(-gravity.scale(displaced_mass), point);
+            body.apply_force(-body.lin_vel().scale(*self.linear_drag * displaced_mass));
+            body.apply_torque(-body.ang_vel().scale(*self.angular_drag * displaced_mass));
+
+            if bodies_inside.insert(body_handle) && !self.bodies_inside.contains(&body_handle) {
+                let velocity = body.lin_vel();
+                if velocity.norm() >= *self.min_splash_speed {
+                    self.events.push_back(FluidEvent::Splash {
+                        body: body_handle,
+                        position: Vector3::new(center.x, surface, center.z),
+                        velocity,
+                    });
+                }
+            }
+        }
+
+        for body in self.bodies_inside.iter() {
+            if !bodies_inside.contains(body) {
+                self.events.push_back(FluidEvent::Exited { body: *body });
+            }
+        }
+        self.bodies_inside = bodies_inside;
+    }
+}
+
+/// Allows you to create [`FluidVolume`] scene nodes.
+pub struct FluidVolumeBuilder {
+    base_builder: BaseBuilder,
+    size: Vector3<f32>,
+    density: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+    min_splash_speed: f32,
+    heightfield_size: Vector2<u32>,
+    heights: Vec<f32>,
+}
+
+impl FluidVolumeBuilder {
+    /// Creates a new fluid volume builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            size: Vector3::new(10.0, 2.0, 10.0),
+            density: 1000.0,
+            linear_drag: 1.0,
+            angular_drag: 1.0,
+            min_splash_speed: 1.0,
+            heightfield_size: Default::default(),
+            heights: Default::default(),
+        }
+    }
+
+    /// Sets the desired size of the volume.
+    pub fn with_size(mut self, size: Vector3<f32>) -> Self {
+        self.size = size.sup(&Vector3::zeros());
+        self
+    }
+
+    /// Sets the desired density of the fluid.
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.max(0.0);
+        self
+    }
+
+    /// Sets the desired linear drag of the fluid.
+    pub fn with_linear_drag(mut self, drag: f32) -> Self {
+        self.linear_drag = drag.max(0.0);
+        self
+    }
+
+    /// Sets the desired angular drag of the fluid.
+    pub fn with_angular_drag(mut self, drag: f32) -> Self {
+        self.angular_drag = drag.max(0.0);
+        self
+    }
+
+    /// Sets the desired minimal speed of a body, that is required to produce a splash event.
+    pub fn with_min_splash_speed(mut self, speed: f32) -> Self {
+        self.min_splash_speed = speed.max(0.0);
+        self
+    }
+
+    /// Sets the desired heightfield of the surface. See [`FluidVolume::set_heightfield`] for more
+    /// info.
+    pub fn with_heightfield(mut self, size: Vector2<u32>, heights: Vec<f32>) -> Self {
+        self.heightfield_size = size;
+        self.heights = heights;
+        self
+    }
+
+    /// Creates new [`FluidVolume`] instance.
+    pub fn build_fluid_volume(self) -> FluidVolume {
+        FluidVolume {
+            base: self.base_builder.build_base(),
+            size: self.size.into(),
+            density: self.density.into(),
+            linear_drag: self.linear_drag.into(),
+            angular_drag: self.angular_drag.into(),
+            min_splash_speed: self.min_splash_speed.into(),
+            heightfield_size: self.heightfield_size.into(),
+            heights: self.heights.into(),
+            bodies_inside: Default::default(),
+            events: Default::default(),
+        }
+    }
+
+    /// Creates new [`FluidVolume`] node, but does not add it to a graph.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_fluid_volume())
+    }
+
+    /// Creates new [`FluidVolume`] node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        graph::SceneGraph,
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            fluid::{FluidEvent, FluidVolume, FluidVolumeBuilder},
+            graph::Graph,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_surface_height() {
+        let mut graph = Graph::new();
+        let volume = FluidVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, -1.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_size(Vector3::new(10.0, 2.0, 10.0))
+        .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let volume = graph.try_get_mut_of_type::<FluidVolume>(volume).unwrap();
+        assert_eq!(volume.surface_height(Vector3::new(3.0, 5.0, -2.0)), 0.0);
+
+        // Heights grow along Z axis.
+        volume.set_heightfield(Vector2::new(2, 2), vec![0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(volume.surface_height(Vector3::new(0.0, 0.0, -5.0)), 0.0);
+        assert_eq!(volume.surface_height(Vector3::new(0.0, 0.0, 0.0)), 0.5);
+        assert_eq!(volume.surface_height(Vector3::new(4.0, 0.0, 5.0)), 1.0);
+
+        // Mismatching heightfield means flat surface.
+        volume.set_heightfield(Vector2::new(3, 3), vec![1.0]);
+        assert_eq!(volume.surface_height(Vector3::new(0.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn test_buoyancy() {
+        let mut graph = Graph::new();
+
+        let volume = FluidVolumeBuilder::new(BaseBuilder::new())
+            .with_size(Vector3::new(10.0, 10.0, 10.0))
+            .build(&mut graph);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 8.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        // About a half of the density of water, so the body stays partially submerged.
+        .with_mass(300.0)
+        .build(&mut graph);
+
+        let mut splashed = false;
+        for _ in 0..300 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+            let volume = graph.try_get_mut_of_type::<FluidVolume>(volume).unwrap();
+            while let Some(event) = volume.pop_event() {
+                if let FluidEvent::Splash {
+                    body: splashed_body,
+                    ..
+                } = event
+                {
+                    assert_eq!(splashed_body, body);
+                    splashed = true;
+                }
+            }
+        }
+
+        assert!(splashed);
+        // The body is lighter than water, so it must float near the surface instead of falling
+        // through the volume.
+        let position = graph[body].global_position();
+        assert!(position.y > 3.0 && position.y < 7.0);
+        assert!(graph
+            .try_get_of_type::<FluidVolume>(volume)
+            .unwrap()
+            .contains_body(body));
+    }
+}
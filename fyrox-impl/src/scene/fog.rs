@@ -0,0 +1,155 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Scene-level fog. See [`Fog`] docs for more info.
+
+use crate::core::{color::Color, reflect::prelude::*, uuid_provider, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how the amount of fog depends on the distance from the camera.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum FogMode {
+    /// The amount of fog grows linearly from [`Fog::start`] to [`Fog::end`].
+    Linear,
+    /// The amount of fog grows exponentially with the distance beyond [`Fog::start`].
+    #[default]
+    Exponential,
+    /// The same as [`FogMode::Exponential`], but the distance is squared, which gives clearer
+    /// space near the camera and sharper transition to the fog.
+    ExponentialSquared,
+}
+
+uuid_provider!(FogMode = "d3ade9f4-8698-440c-940d-a6520a7bab7b");
+
+/// Fog is applied to every opaque object of a scene in the lighting pass, it blends the lit color
+/// of a pixel with the color of the fog depending on the distance to the camera and (optionally) on
+/// the height of the pixel. Skybox, procedural sky and everything that is rendered in the forward
+/// pass (transparent objects, sprites, etc.) are not affected by the fog. Standard materials could
+/// opt out from the fog by setting their `fogEnabled` property to `false`.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+#[visit(optional)]
+pub struct Fog {
+    /// Defines how the amount of fog depends on the distance. See [`FogMode`] docs for more info.
+    pub mode: FogMode,
+
+    /// Color of the fog.
+    pub color: Color,
+
+    /// Density of the fog. Used only by exponential modes.
+    #[reflect(min_value = 0.0, step = 0.005)]
+    pub density: f32,
+
+    /// A distance from the camera at which the fog starts.
+    #[reflect(min_value = 0.0)]
+    pub start: f32,
+
+    /// A distance from the camera at which the fog becomes fully opaque. Used only by
+    /// [`FogMode::Linear`].
+    #[reflect(min_value = 0.0)]
+    pub end: f32,
+
+    /// Defines how fast the fog thins out above [`Fog::base_height`]. Zero value disables height
+    /// falloff, which makes the fog uniform.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub height_falloff: f32,
+
+    /// Height (in world coordinates) below which the fog has its full density. Used only if
+    /// [`Fog::height_falloff`] is non-zero.
+    pub base_height: f32,
+}
+
+uuid_provider!(Fog = "e259aaf0-cdfe-487f-a2c3-cb433a21eeae");
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            color: Color::opaque(170, 180, 190),
+            density: 0.02,
+            start: 0.0,
+            end: 100.0,
+            height_falloff: 0.0,
+            base_height: 0.0,
+        }
+    }
+}
+
+impl Fog {
+    /// Calculates the amount of fog (in `[0; 1]` range) for a point at the given distance from the
+    /// camera and with the given height in world coordinates. This method matches the fog shader,
+    /// so it could be used to check whether an object is hidden by the fog or not.
+    pub fn factor(&self, distance: f32, height: f32) -> f32 {
+        let distance = (distance - self.start).max(0.0);
+        let factor = match self.mode {
+            FogMode::Linear => distance / (self.end - self.start).max(f32::EPSILON),
+            FogMode::Exponential => 1.0 - (-self.density * distance).exp(),
+            FogMode::ExponentialSquared => 1.0 - (-(self.density * distance).powi(2)).exp(),
+        };
+        let height_factor = (-self.height_falloff * (height - self.base_height).max(0.0)).exp();
+        (factor * height_factor).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::fog::{Fog, FogMode};
+
+    #[test]
+    fn test_fog_factor() {
+        let linear = Fog {
+            mode: FogMode::Linear,
+            start: 10.0,
+            end: 20.0,
+            ..Default::default()
+        };
+        assert_eq!(linear.factor(5.0, 0.0), 0.0);
+        assert_eq!(linear.factor(15.0, 0.0), 0.5);
+        assert_eq!(linear.factor(30.0, 0.0), 1.0);
+
+        let exponential = Fog {
+            mode: FogMode::Exponential,
+            density: 0.1,
+            ..Default::default()
+        };
+        assert!(exponential.factor(10.0, 0.0) < exponential.factor(20.0, 0.0));
+        assert!(exponential.factor(1000.0, 0.0) > 0.99);
+
+        let height = Fog {
+            height_falloff: 0.5,
+            base_height: 2.0,
+            ..linear
+        };
+        assert_eq!(height.factor(15.0, 1.0), 0.5);
+        assert!(height.factor(15.0, 10.0) < 0.5);
+    }
+}
@@ -0,0 +1,951 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Foliage is a set of instanced meshes (grass, bushes, flowers, etc.) scattered over a terrain or
+//! a flat area. See [`Foliage`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, TriangleDefinition},
+        parking_lot::Mutex,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider, value_as_u8_slice,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    material::{self, MaterialPropertyBlock, MaterialResource},
+    rand::{prelude::StdRng, Rng, SeedableRng},
+    renderer::{
+        self,
+        bundle::{RenderContext, SurfaceInstanceData},
+        framework::ElementRange,
+    },
+    resource::texture::{TextureKind, TexturePixelKind, TextureResource},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexTrait},
+            surface::Surface,
+            RenderPath,
+        },
+        node::{Node, NodeTrait, RdcControlFlow},
+        sprite::SpriteVertex,
+        terrain::Terrain,
+    },
+};
+use fxhash::FxHasher;
+use std::{
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// Maximum amount of procedurally generated instances per layer. Density of a layer is reduced
+/// automatically if the amount of instances exceeds this limit.
+pub const MAX_GENERATED_INSTANCES: usize = 1_000_000;
+
+/// A single instance of a foliage layer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct FoliageInstance {
+    /// Position of the instance in local coordinates of the foliage node on XZ plane. Height of
+    /// the instance is defined by the target of the foliage (see [`Foliage::set_target`]).
+    pub position: Vector2<f32>,
+    /// Rotation of the instance around the up axis (in radians).
+    pub rotation: f32,
+    /// Uniform scale of the instance.
+    pub scale: f32,
+}
+
+/// Impostor is a billboard, that is drawn instead of a foliage mesh on large distances from the
+/// observer. Its material should use the standard sprite shader (or any other shader with the same
+/// vertex layout).
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct FoliageImpostor {
+    /// Material of the impostor, usually it has a pre-rendered image of the foliage mesh.
+    pub material: MaterialResource,
+    /// Size of the impostor of an instance with unit scale.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub size: f32,
+    /// Color of the impostor.
+    pub color: Color,
+}
+
+uuid_provider!(FoliageImpostor = "1b0b2dc4-3e7f-4e2c-b6a1-0f0e5e8a9c3d");
+
+impl Default for FoliageImpostor {
+    fn default() -> Self {
+        Self {
+            material: material::STANDARD_SPRITE.resource(),
+            size: 1.0,
+            color: Color::WHITE,
+        }
+    }
+}
+
+/// Foliage layer is a set of instances of a mesh. Instances are either generated procedurally (see
+/// [`Self::density`] and [`Self::density_map`]) or painted manually (see [`Foliage::paint`]).
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct FoliageLayer {
+    /// Name of the layer.
+    pub name: String,
+
+    /// Surfaces of the mesh, that will be instanced. Every surface is drawn using the instancing,
+    /// so the amount of draw calls does not depend on the amount of instances.
+    pub surfaces: Vec<Surface>,
+
+    /// Amount of procedurally generated instances per square meter. Zero disables procedural
+    /// generation.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub density: f32,
+
+    /// Optional density map, that covers the entire area of the foliage. Red channel of the map
+    /// defines the probability of an instance to appear at a point. It is useful to make clearings,
+    /// paths, etc.
+    pub density_map: Option<TextureResource>,
+
+    /// Seed of the random numbers generator, that is used for procedural generation.
+    pub seed: u64,
+
+    /// Minimal scale of generated instances.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub min_scale: f32,
+
+    /// Maximal scale of generated instances.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub max_scale: f32,
+
+    /// Whether generated instances should be randomly rotated around the up axis or not.
+    pub random_rotation: bool,
+
+    /// Distance from the observer, at which instances switch from the mesh to the impostor. Only used
+    /// if the layer has an impostor.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub impostor_distance: f32,
+
+    /// Length of the smooth transition between the mesh and the impostor (or between the mesh and
+    /// nothing, if there's no impostor). Meshes are faded out using dissolve effect, so their
+    /// material must have a dissolve texture (see standard shader docs).
+    #[reflect(min_value = 0.0, step = 0.5)]
+    pub fade_length: f32,
+
+    /// Maximum distance from the observer at which instances are visible.
+    #[reflect(min_value = 0.0, step = 1.0)]
+    pub max_distance: f32,
+
+    /// Optional impostor of the layer.
+    pub impostor: Option<FoliageImpostor>,
+
+    /// Manually placed instances.
+    #[reflect(hidden)]
+    pub instances: Vec<FoliageInstance>,
+}
+
+uuid_provider!(FoliageLayer = "8c5e2f0e-6b3e-4d7e-9a55-2f6a4b7e1c90");
+
+impl Default for FoliageLayer {
+    fn default() -> Self {
+        Self {
+            name: "Layer".to_string(),
+            surfaces: Default::default(),
+            density: 1.0,
+            density_map: None,
+            seed: 0,
+            min_scale: 0.8,
+            max_scale: 1.2,
+            random_rotation: true,
+            impostor_distance: 30.0,
+            fade_length: 5.0,
+            max_distance: 100.0,
+            impostor: None,
+            instances: Default::default(),
+        }
+    }
+}
+
+impl FoliageLayer {
+    fn random_instance(&self, position: Vector2<f32>, rng: &mut impl Rng) -> FoliageInstance {
+        let scale_factor = rng.gen::<f32>();
+        let rotation_factor = rng.gen::<f32>();
+        FoliageInstance {
+            position,
+            rotation: if self.random_rotation {
+                rotation_factor * std::f32::consts::TAU
+            } else {
+                0.0
+            },
+            scale: self.min_scale + (self.max_scale - self.min_scale) * scale_factor,
+        }
+    }
+
+    fn hash_parameters(&self, hasher: &mut impl Hasher) {
+        self.density.to_bits().hash(hasher);
+        self.density_map.as_ref().map(|m| m.key()).hash(hasher);
+        self.seed.hash(hasher);
+        self.min_scale.to_bits().hash(hasher);
+        self.max_scale.to_bits().hash(hasher);
+        self.random_rotation.hash(hasher);
+        for instance in self.instances.iter() {
+            instance.position.x.to_bits().hash(hasher);
+            instance.position.y.to_bits().hash(hasher);
+            instance.rotation.to_bits().hash(hasher);
+            instance.scale.to_bits().hash(hasher);
+        }
+    }
+
+    /// Returns all the instances of the layer (both generated and painted) or [`None`] if the
+    /// density map is not loaded yet.
+    fn collect_instances(&self, size: Vector2<f32>) -> Option<Vec<FoliageInstance>> {
+        let mut instances = self.instances.clone();
+
+        if self.density <= 0.0 || size.x <= 0.0 || size.y <= 0.0 {
+            return Some(instances);
+        }
+
+        let density_map = match self.density_map.as_ref() {
+            Some(texture) => Some(DensityMap::new(texture)?),
+            None => None,
+        };
+
+        let mut step = 1.0 / self.density.sqrt();
+        let area = size.x * size.y;
+        if area / (step * step) > MAX_GENERATED_INSTANCES as f32 {
+            step = (area / MAX_GENERATED_INSTANCES as f32).sqrt();
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let count_x = (size.x / step).ceil() as usize;
+        let count_y = (size.y / step).ceil() as usize;
+        for y in 0..count_y {
+            for x in 0..count_x {
+                // Jittered grid gives quite uniform, but still natural-looking distribution.
+                let position = Vector2::new(
+                    (x as f32 + rng.gen::<f32>()) * step,
+                    (y as f32 + rng.gen::<f32>()) * step,
+                );
+                let probability = rng.gen::<f32>();
+                let instance = self.random_instance(position, &mut rng);
+
+                if position.x > size.x || position.y > size.y {
+                    continue;
+                }
+
+                if let Some(density_map) = density_map.as_ref() {
+                    if probability >= density_map.sample(position.component_div(&size)) {
+                        continue;
+                    }
+                }
+
+                instances.push(instance);
+            }
+        }
+
+        Some(instances)
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        let mut bounding_box = AxisAlignedBoundingBox::default();
+        for surface in self.surfaces.iter() {
+            let data = surface.data_ref();
+            let data = data.data_ref();
+            if let Some(view) = data
+                .vertex_buffer
+                .attribute_view::<Vector3<f32>>(VertexAttributeUsage::Position)
+            {
+                for i in 0..data.vertex_buffer.vertex_count() as usize {
+                    bounding_box.add_point(*view.get(i).unwrap());
+                }
+            }
+        }
+        if bounding_box.is_invalid_or_degenerate() {
+            0.0
+        } else {
+            bounding_box.min.norm().max(bounding_box.max.norm())
+        }
+    }
+}
+
+#[derive(Default)]
+struct DensityMap {
+    size: Vector2<usize>,
+    values: Vec<f32>,
+}
+
+impl DensityMap {
+    /// Reads the red channel of the given texture. Returns [`None`] if the texture is still loading.
+    /// Textures that failed to load or have unsupported pixel format are treated as uniform maps.
+    fn new(texture: &TextureResource) -> Option<Self> {
+        if texture.is_loading() {
+            return None;
+        }
+
+        let mut state = texture.state();
+        let Some(texture) = state.data() else {
+            return Some(Default::default());
+        };
+
+        let TextureKind::Rectangle { width, height } = texture.kind() else {
+            return Some(Default::default());
+        };
+
+        let (stride, offset) = match texture.pixel_kind() {
+            TexturePixelKind::R8 | TexturePixelKind::Luminance8 => (1, 0),
+            TexturePixelKind::RG8 | TexturePixelKind::LuminanceAlpha8 => (2, 0),
+            TexturePixelKind::RGB8 => (3, 0),
+            TexturePixelKind::BGR8 => (3, 2),
+            TexturePixelKind::RGBA8 => (4, 0),
+            TexturePixelKind::BGRA8 => (4, 2),
+            _ => return Some(Default::default()),
+        };
+
+        let data = texture.mip_level_data(0);
+        let values = (0..(width * height) as usize)
+            .map(|i| {
+                data.get(i * stride + offset)
+                    .map_or(1.0, |value| *value as f32 / 255.0)
+            })
+            .collect();
+
+        Some(Self {
+            size: Vector2::new(width as usize, height as usize),
+            values,
+        })
+    }
+
+    fn sample(&self, uv: Vector2<f32>) -> f32 {
+        if self.values.is_empty() {
+            return 1.0;
+        }
+        let x = ((uv.x * self.size.x as f32) as usize).min(self.size.x - 1);
+        let y = ((uv.y * self.size.y as f32) as usize).min(self.size.y - 1);
+        self.values[y * self.size.x + x]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CachedInstance {
+    transform: Matrix4<f32>,
+    position: Vector3<f32>,
+    scale: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct CachedLayer {
+    instances: Vec<CachedInstance>,
+    radius: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct FoliageCache {
+    key: Option<u64>,
+    layers: Vec<CachedLayer>,
+    world_bounding_box: AxisAlignedBoundingBox,
+}
+
+#[derive(Debug, Default)]
+struct FoliageCacheWrapper(Mutex<FoliageCache>);
+
+impl Clone for FoliageCacheWrapper {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().clone()))
+    }
+}
+
+/// Foliage is a set of layers of instanced meshes (grass, bushes, flowers, etc.), scattered over
+/// a rectangular area, that starts at the origin of the node and spans along its local X and Z axes
+/// (see [`Self::set_size`]).
+///
+/// ## Distribution
+///
+/// Instances of every layer are either generated procedurally using the density and an optional
+/// density map of the layer, or painted manually using [`Self::paint`] and [`Self::erase`]
+/// methods. Every instance is projected on the target terrain (see [`Self::set_target`]), instances
+/// outside the terrain or in its holes are discarded. If there's no target, the instances are placed
+/// on the XZ plane of the node. Instances are regenerated automatically when the parameters of the
+/// foliage change, call [`Self::invalidate`] after modifying the height map of the target terrain.
+///
+/// ## Rendering
+///
+/// All instances of a layer are drawn using the instancing. Instances that are further than
+/// [`FoliageLayer::impostor_distance`] from the observer are drawn as billboards (impostors), if the
+/// layer has an impostor. Instances further than [`FoliageLayer::max_distance`] are not drawn at
+/// all.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::{Matrix4, Vector2}, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         foliage::{FoliageBuilder, FoliageLayer},
+/// #         graph::Graph,
+/// #         mesh::surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+/// #         node::Node,
+/// #     },
+/// #     asset::untyped::ResourceKind,
+/// # };
+/// fn create_bushes(terrain: Handle<Node>, graph: &mut Graph) -> Handle<Node> {
+///     FoliageBuilder::new(BaseBuilder::new())
+///         .with_size(Vector2::new(100.0, 100.0))
+///         .with_target(terrain)
+///         .with_layers(vec![FoliageLayer {
+///             surfaces: vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+///                 ResourceKind::Embedded,
+///                 SurfaceData::make_sphere(8, 8, 0.5, &Matrix4::identity()),
+///             ))
+///             .build()],
+///             density: 0.2,
+///             ..Default::default()
+///         }])
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Default, Clone, Reflect)]
+pub struct Foliage {
+    base: Base,
+
+    #[reflect(setter = "set_size")]
+    size: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(setter = "set_target")]
+    target: InheritableVariable<Handle<Node>>,
+
+    #[reflect(setter = "set_layers")]
+    layers: InheritableVariable<Vec<FoliageLayer>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    cache: FoliageCacheWrapper,
+}
+
+impl Deref for Foliage {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Foliage {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Foliage {
+    fn type_uuid() -> Uuid {
+        uuid!("0f3d8a7e-2c4b-4f6d-8e9a-5b1c7d3e2f40")
+    }
+}
+
+impl Foliage {
+    /// Sets the size of the area of the foliage along local X and Z axes.
+    pub fn set_size(&mut self, size: Vector2<f32>) -> Vector2<f32> {
+        self.size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns the size of the area of the foliage.
+    pub fn size(&self) -> Vector2<f32> {
+        *self.size
+    }
+
+    /// Sets a terrain on which the instances will be placed. Instances are placed on the XZ plane
+    /// of the node, if the handle is invalid or does not point to a terrain.
+    pub fn set_target(&mut self, target: Handle<Node>) -> Handle<Node> {
+        self.target.set_value_and_mark_modified(target)
+    }
+
+    /// Returns a handle of the target terrain.
+    pub fn target(&self) -> Handle<Node> {
+        *self.target
+    }
+
+    /// Sets new layers of the foliage.
+    pub fn set_layers(&mut self, layers: Vec<FoliageLayer>) -> Vec<FoliageLayer> {
+        self.layers.set_value_and_mark_modified(layers)
+    }
+
+    /// Returns a reference to the layers of the foliage.
+    pub fn layers(&self) -> &[FoliageLayer] {
+        &self.layers
+    }
+
+    /// Returns a mutable reference to the layers of the foliage.
+    pub fn layers_mut(&mut self) -> &mut Vec<FoliageLayer> {
+        self.layers.get_value_mut_and_mark_modified()
+    }
+
+    /// Forces the foliage to re-create its instances. It should be called when the height map of
+    /// the target terrain was changed.
+    pub fn invalidate(&self) {
+        self.cache.0.lock().key = None;
+    }
+
+    /// Projects the given world-space point on the XZ plane of the foliage and returns local
+    /// coordinates of the point, that could be used for painting.
+    pub fn project(&self, point: Vector3<f32>) -> Option<Vector2<f32>> {
+        let local = self
+            .global_transform()
+            .try_inverse()?
+            .transform_point(&Point3::from(point));
+        Some(Vector2::new(local.x, local.z))
+    }
+
+    /// Adds the given amount of instances to the given layer in a circle with the given center
+    /// (in local coordinates, see [`Self::project`]) and radius. Scale and rotation of new instances
+    /// are defined by the layer.
+    pub fn paint(&mut self, layer_index: usize, center: Vector2<f32>, radius: f32, count: usize) {
+        let size = *self.size;
+        let Some(layer) = self
+            .layers
+            .get_value_mut_and_mark_modified()
+            .get_mut(layer_index)
+        else {
+            return;
+        };
+
+        let mut rng = crate::rand::thread_rng();
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let distance = radius * rng.gen::<f32>().sqrt();
+            let position = center + Vector2::new(angle.cos(), angle.sin()).scale(distance);
+            if position.x < 0.0 || position.y < 0.0 || position.x > size.x || position.y > size.y {
+                continue;
+            }
+            let instance = layer.random_instance(position, &mut rng);
+            layer.instances.push(instance);
+        }
+    }
+
+    /// Removes all manually placed instances of the given layer in the circle with the given center
+    /// (in local coordinates, see [`Self::project`]) and radius. Returns the amount of removed
+    /// instances.
+    pub fn erase(&mut self, layer_index: usize, center: Vector2<f32>, radius: f32) -> usize {
+        let Some(layer) = self
+            .layers
+            .get_value_mut_and_mark_modified()
+            .get_mut(layer_index)
+        else {
+            return 0;
+        };
+
+        let count = layer.instances.len();
+        layer
+            .instances
+            .retain(|instance| (instance.position - center).norm() > radius);
+        count - layer.instances.len()
+    }
+
+    fn cache_key(&self, terrain: Option<&Terrain>) -> u64 {
+        let mut hasher = FxHasher::default();
+        self.size.x.to_bits().hash(&mut hasher);
+        self.size.y.to_bits().hash(&mut hasher);
+        for transform in std::iter::once(self.global_transform())
+            .chain(terrain.map(|terrain| terrain.global_transform()))
+        {
+            for value in transform.iter() {
+                value.to_bits().hash(&mut hasher);
+            }
+        }
+        for layer in self.layers.iter() {
+            layer.hash_parameters(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn update_cache(&self, cache: &mut FoliageCache, graph: &Graph) {
+        let terrain = graph.try_get_of_type::<Terrain>(*self.target);
+        let key = self.cache_key(terrain);
+        if cache.key == Some(key) {
+            return;
+        }
+
+        let global_transform = self.global_transform();
+        let basis = global_transform.basis();
+
+        let mut layers = Vec::with_capacity(self.layers.len());
+        let mut world_bounding_box = AxisAlignedBoundingBox::default();
+        for layer in self.layers.iter() {
+            let Some(instances) = layer.collect_instances(*self.size) else {
+                // Try again on the next frame, when the density map is loaded.
+                return;
+            };
+
+            let radius = layer.bounding_radius();
+            let instances = instances
+                .into_iter()
+                .filter_map(|instance| {
+                    let mut position = global_transform
+                        .transform_point(&Point3::new(
+                            instance.position.x,
+                            0.0,
+                            instance.position.y,
+                        ))
+                        .coords;
+                    if let Some(terrain) = terrain {
+                        position = terrain.surface_point(position)?;
+                    }
+
+                    let transform = Matrix4::new_translation(&position)
+                        * basis.to_homogeneous()
+                        * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), instance.rotation)
+                            .to_homogeneous()
+                        * Matrix4::new_scaling(instance.scale);
+
+                    let extent = Vector3::repeat(radius * instance.scale);
+                    world_bounding_box.add_point(position - extent);
+                    world_bounding_box.add_point(position + extent);
+
+                    Some(CachedInstance {
+                        transform,
+                        position,
+                        scale: instance.scale,
+                    })
+                })
+                .collect();
+
+            layers.push(CachedLayer { instances, radius });
+        }
+
+        cache.key = Some(key);
+        cache.layers = layers;
+        cache.world_bounding_box = world_bounding_box;
+    }
+}
+
+impl NodeTrait for Foliage {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_min_max(
+            Vector3::default(),
+            Vector3::new(self.size.x, 0.0, self.size.y),
+        )
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let cache = self.cache.0.lock();
+        if cache.key.is_some() && !cache.world_bounding_box.is_invalid_or_degenerate() {
+            cache.world_bounding_box
+        } else {
+            self.local_bounding_box()
+                .transform(&self.global_transform())
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if !self.global_visibility() || !self.is_globally_enabled() {
+            return RdcControlFlow::Continue;
+        }
+
+        let is_shadow_pass = renderer::is_shadow_pass(ctx.render_pass_name);
+        if is_shadow_pass && !self.cast_shadows() {
+            return RdcControlFlow::Continue;
+        }
+
+        let mut cache = self.cache.0.lock();
+        self.update_cache(&mut cache, ctx.graph);
+
+        let frustum = if self.frustum_culling() {
+            ctx.frustum
+        } else {
+            None
+        };
+
+        if let Some(frustum) = frustum {
+            if !frustum.is_intersects_aabb(&cache.world_bounding_box) {
+                return RdcControlFlow::Continue;
+            }
+        }
+
+        let observer_position = ctx.observer_info.observer_position;
+
+        for (layer, cached_layer) in self.layers.iter().zip(cache.layers.iter()) {
+            let impostor = if is_shadow_pass {
+                None
+            } else {
+                layer.impostor.as_ref()
+            };
+
+            // Defines a distance range in which the mesh fades out.
+            let fade_length = layer.fade_length.max(f32::EPSILON);
+            let fade_start = if layer.impostor.is_some() {
+                layer.impostor_distance - fade_length * 0.5
+            } else {
+                layer.max_distance - fade_length
+            };
+
+            let mut impostor_vertices = Vec::new();
+
+            for instance in cached_layer.instances.iter() {
+                let distance = (instance.position - observer_position).norm();
+                if distance > layer.max_distance {
+                    continue;
+                }
+
+                if let Some(frustum) = frustum {
+                    if !frustum.is_intersects_sphere(
+                        instance.position,
+                        cached_layer.radius * instance.scale,
+                    ) {
+                        continue;
+                    }
+                }
+
+                let fade = ((distance - fade_start) / fade_length).clamp(0.0, 1.0);
+
+                if fade < 1.0 {
+                    let property_block = if fade > 0.0 {
+                        let mut block = MaterialPropertyBlock::default();
+                        block.set_property("dissolveThreshold", fade);
+                        Some(Arc::new(block))
+                    } else {
+                        None
+                    };
+
+                    for surface in layer.surfaces.iter() {
+                        ctx.storage.push(
+                            surface.data_ref(),
+                            surface.material(),
+                            RenderPath::Deferred,
+                            surface.material().key(),
+                            SurfaceInstanceData {
+                                world_transform: instance.transform,
+                                bone_matrices: Default::default(),
+                                blend_shapes_weights: Default::default(),
+                                element_range: ElementRange::Full,
+                                node_handle: self.handle(),
+                                property_block: property_block.clone(),
+                                color: Color::WHITE,
+                            },
+                        );
+                    }
+                }
+
+                if let Some(impostor) = impostor {
+                    if fade > 0.0 {
+                        let size = impostor.size * instance.scale;
+                        let mut color = impostor.color;
+                        color.a = (color.a as f32 * fade) as u8;
+                        impostor_vertices.push(SpriteVertex {
+                            position: instance.position + Vector3::new(0.0, size * 0.5, 0.0),
+                            tex_coord: Default::default(),
+                            params: Vector2::new(size, 0.0),
+                            color,
+                        });
+                    }
+                }
+            }
+
+            if let Some(impostor) = impostor {
+                if impostor_vertices.is_empty() {
+                    continue;
+                }
+
+                ctx.storage.push_triangles(
+                    SpriteVertex::layout(),
+                    &impostor.material,
+                    RenderPath::Forward,
+                    0,
+                    self.handle(),
+                    &mut |mut vertex_buffer, mut triangle_buffer| {
+                        for vertex in impostor_vertices.iter() {
+                            let start_vertex_index = vertex_buffer.vertex_count();
+
+                            for tex_coord in [
+                                Vector2::new(1.0, 0.0),
+                                Vector2::new(0.0, 0.0),
+                                Vector2::new(0.0, 1.0),
+                                Vector2::new(1.0, 1.0),
+                            ] {
+                                let vertex = SpriteVertex {
+                                    tex_coord,
+                                    ..*vertex
+                                };
+                                vertex_buffer
+                                    .push_vertex_raw(value_as_u8_slice(&vertex))
+                                    .unwrap();
+                            }
+
+                            triangle_buffer.push_triangles_iter_with_offset(
+                                start_vertex_index,
+                                [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])]
+                                    .into_iter(),
+                            );
+                        }
+                    },
+                );
+            }
+        }
+
+        RdcControlFlow::Continue
+    }
+}
+
+/// Allows you to create a foliage node in a declarative manner.
+pub struct FoliageBuilder {
+    base_builder: BaseBuilder,
+    size: Vector2<f32>,
+    target: Handle<Node>,
+    layers: Vec<FoliageLayer>,
+}
+
+impl FoliageBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            size: Vector2::new(16.0, 16.0),
+            target: Default::default(),
+            layers: Default::default(),
+        }
+    }
+
+    /// Sets desired size of the area of the foliage.
+    pub fn with_size(mut self, size: Vector2<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets desired target terrain.
+    pub fn with_target(mut self, target: Handle<Node>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets desired layers.
+    pub fn with_layers(mut self, layers: Vec<FoliageLayer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Creates new foliage.
+    pub fn build_foliage(self) -> Foliage {
+        Foliage {
+            base: self.base_builder.build_base(),
+            size: self.size.into(),
+            target: self.target.into(),
+            layers: self.layers.into(),
+            cache: Default::default(),
+        }
+    }
+
+    /// Creates new foliage node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_foliage())
+    }
+
+    /// Creates new foliage node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
+        scene::{
+            base::BaseBuilder,
+            foliage::{Foliage, FoliageBuilder, FoliageCache, FoliageLayer},
+            graph::Graph,
+        },
+    };
+
+    #[test]
+    fn test_foliage_generation() {
+        let layer = FoliageLayer {
+            density: 4.0,
+            ..Default::default()
+        };
+
+        let instances = layer.collect_instances(Vector2::new(10.0, 10.0)).unwrap();
+        assert_eq!(instances.len(), 400);
+        assert!(instances
+            .iter()
+            .all(|i| i.position.x <= 10.0 && i.position.y <= 10.0));
+        assert!(instances.iter().all(|i| i.scale >= 0.8 && i.scale <= 1.2));
+
+        // Generation must be deterministic.
+        assert_eq!(
+            instances,
+            layer.collect_instances(Vector2::new(10.0, 10.0)).unwrap()
+        );
+
+        // Left half of the density map is empty.
+        let density_map = TextureResource::new_ok(
+            Default::default(),
+            Texture::from_bytes(
+                TextureKind::Rectangle {
+                    width: 2,
+                    height: 1,
+                },
+                TexturePixelKind::R8,
+                vec![0, 255],
+            )
+            .unwrap(),
+        );
+        let layer = FoliageLayer {
+            density_map: Some(density_map),
+            ..layer
+        };
+        let instances = layer.collect_instances(Vector2::new(10.0, 10.0)).unwrap();
+        assert_eq!(instances.len(), 200);
+        assert!(instances.iter().all(|i| i.position.x >= 5.0));
+    }
+
+    #[test]
+    fn test_foliage_paint() {
+        let mut graph = Graph::new();
+        let handle = FoliageBuilder::new(BaseBuilder::new())
+            .with_size(Vector2::new(10.0, 10.0))
+            .with_layers(vec![FoliageLayer {
+                density: 0.0,
+                ..Default::default()
+            }])
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let foliage = graph[handle].cast_mut::<Foliage>().unwrap();
+        foliage.paint(0, Vector2::new(5.0, 5.0), 1.0, 10);
+        assert_eq!(foliage.layers()[0].instances.len(), 10);
+
+        let mut cache = FoliageCache::default();
+        foliage.update_cache(&mut cache, &Graph::new());
+        assert_eq!(cache.layers[0].instances.len(), 10);
+        let key = cache.key;
+
+        assert_eq!(foliage.erase(0, Vector2::new(5.0, 5.0), 1.0), 10);
+        foliage.update_cache(&mut cache, &Graph::new());
+        assert_ne!(cache.key, key);
+        assert!(cache.layers[0].instances.is_empty());
+    }
+}
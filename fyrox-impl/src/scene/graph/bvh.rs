@@ -0,0 +1,497 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Bounding volume hierarchy of scene nodes, that is used to quickly find out which nodes are
+//! outside of a frustum. See [`NodeBvh`] docs for more info.
+
+use crate::{
+    core::{
+        math::{aabb::AxisAlignedBoundingBox, frustum::Frustum},
+        pool::Handle,
+    },
+    scene::node::Node,
+};
+use std::ops::Range;
+
+/// A node of a graph with its world-space bounds.
+#[derive(Clone, Debug)]
+pub struct BvhEntry {
+    /// A handle of the scene node.
+    pub node: Handle<Node>,
+    /// World-space bounding box of the scene node.
+    pub bounds: AxisAlignedBoundingBox,
+}
+
+impl PartialEq for BvhEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node && same_bounds(&self.bounds, &other.bounds)
+    }
+}
+
+fn same_bounds(a: &AxisAlignedBoundingBox, b: &AxisAlignedBoundingBox) -> bool {
+    a.min == b.min && a.max == b.max
+}
+
+const NONE: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct BvhNode {
+    bounds: AxisAlignedBoundingBox,
+    // Range of entries in the subtree of this node. Entries of every subtree are stored
+    // contiguously, so whole subtrees can be accepted or rejected without visiting the children.
+    first: usize,
+    count: usize,
+    parent: usize,
+    children: Option<[usize; 2]>,
+}
+
+/// A result of [`NodeBvh::update`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BvhUpdate {
+    /// Nothing has changed.
+    Unchanged,
+    /// Bounds of some entries have changed, the bounds of their ancestors were refitted.
+    Refitted,
+    /// The set of entries has changed (or the tree was refitted too many times), the tree was
+    /// rebuilt from scratch.
+    Rebuilt,
+}
+
+/// Bounding volume hierarchy (BVH) of scene nodes. It is a binary tree of bounding boxes, where
+/// every branch encloses the bounds of its descendants. It allows to reject (or accept) large
+/// groups of nodes with a single frustum test, instead of testing every node separately.
+///
+/// The hierarchy is maintained by [`super::Graph`]. It contains every node with frustum culling
+/// enabled and valid local bounds, see [`super::Graph::bvh`]. The tree is rebuilt only when the
+/// set of nodes changes. When nodes just move, only the bounds of their ancestors in the tree are
+/// refitted. Refitting does not change the structure of the tree, so it is rebuilt once the amount
+/// of refitted entries exceeds the amount of entries, which keeps the culling efficient.
+#[derive(Default, Clone, Debug)]
+pub struct NodeBvh {
+    // Entries reordered so that every tree node refers to a contiguous range.
+    entries: Vec<BvhEntry>,
+    nodes: Vec<BvhNode>,
+    // Position of an entry for every index of a scene node, `NONE` if there's no entry.
+    positions: Vec<usize>,
+    // Index of the leaf for every entry.
+    leaves: Vec<usize>,
+    refitted_since_rebuild: usize,
+    // Buffers that are reused between updates to avoid allocations.
+    scratch: Vec<BvhEntry>,
+    dirty: Vec<usize>,
+    dirty_flags: Vec<bool>,
+}
+
+impl NodeBvh {
+    /// Maximum amount of entries in a leaf of the tree.
+    pub const LEAF_SIZE: usize = 4;
+
+    /// Creates a new hierarchy from the given set of entries.
+    pub fn new(entries: Vec<BvhEntry>) -> Self {
+        let mut bvh = Self::default();
+        bvh.update(entries);
+        bvh
+    }
+
+    /// Updates the hierarchy using the new set of entries. If the set of nodes is the same, only
+    /// the bounds of the changed entries and their ancestors are updated, otherwise the tree is
+    /// rebuilt. Entries are collected into an internal buffer, that is reused between the calls.
+    pub fn update<I>(&mut self, entries: I) -> BvhUpdate
+    where
+        I: IntoIterator<Item = BvhEntry>,
+    {
+        self.scratch.clear();
+        self.scratch.extend(entries);
+
+        let same_set = self.scratch.len() == self.entries.len()
+            && self
+                .scratch
+                .iter()
+                .all(|entry| self.position(entry.node).is_some());
+        if !same_set {
+            self.rebuild();
+            return BvhUpdate::Rebuilt;
+        }
+
+        self.dirty.clear();
+        for entry in self.scratch.iter() {
+            let position = self.positions[entry.node.index() as usize];
+            let existing = &mut self.entries[position];
+            if !same_bounds(&existing.bounds, &entry.bounds) {
+                existing.bounds = entry.bounds;
+                self.dirty.push(self.leaves[position]);
+            }
+        }
+
+        if self.dirty.is_empty() {
+            return BvhUpdate::Unchanged;
+        }
+
+        self.refitted_since_rebuild += self.dirty.len();
+        if self.refitted_since_rebuild > self.entries.len() {
+            self.scratch.clone_from(&self.entries);
+            self.rebuild();
+            BvhUpdate::Rebuilt
+        } else {
+            self.refit();
+            BvhUpdate::Refitted
+        }
+    }
+
+    /// Returns a total amount of entries in the hierarchy.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the hierarchy has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a slice with every entry of the hierarchy, the order is unspecified.
+    pub fn entries(&self) -> &[BvhEntry] {
+        &self.entries
+    }
+
+    /// Tests the entries of the hierarchy against the given frustum. The result of the test is
+    /// the same as if [`Frustum::is_intersects_aabb`] was called for every entry, but whole
+    /// subtrees that are completely inside or completely outside of the frustum are accepted or
+    /// rejected at once, without visiting their entries. See [`BvhCulling`] docs for more info.
+    pub fn cull(&self, frustum: &Frustum) -> BvhCulling<'_> {
+        let mut culling = BvhCulling {
+            bvh: self,
+            culled: Vec::new(),
+        };
+        if !self.nodes.is_empty() {
+            self.cull_recursive(0, frustum, &mut culling.culled);
+        }
+        culling
+    }
+
+    fn position(&self, node: Handle<Node>) -> Option<usize> {
+        self.positions
+            .get(node.index() as usize)
+            .copied()
+            .filter(|position| {
+                self.entries
+                    .get(*position)
+                    .is_some_and(|entry| entry.node == node)
+            })
+    }
+
+    fn cull_recursive(&self, index: usize, frustum: &Frustum, culled: &mut Vec<Range<usize>>) {
+        let node = &self.nodes[index];
+
+        if !frustum.is_intersects_aabb(&node.bounds) {
+            // Every descendant box is inside the parent box, so it is outside too.
+            push_range(culled, node.first..(node.first + node.count));
+        } else if is_inside_frustum(frustum, &node.bounds) {
+            // Everything is visible.
+        } else if let Some([left, right]) = node.children {
+            self.cull_recursive(left, frustum, culled);
+            self.cull_recursive(right, frustum, culled);
+        } else {
+            for position in node.first..(node.first + node.count) {
+                if !frustum.is_intersects_aabb(&self.entries[position].bounds) {
+                    push_range(culled, position..(position + 1));
+                }
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        std::mem::swap(&mut self.entries, &mut self.scratch);
+        self.nodes.clear();
+        self.leaves.clear();
+        self.leaves.resize(self.entries.len(), NONE);
+        self.refitted_since_rebuild = 0;
+        if !self.entries.is_empty() {
+            self.build_recursive(0, self.entries.len(), NONE);
+        }
+
+        self.positions.clear();
+        for (position, entry) in self.entries.iter().enumerate() {
+            let index = entry.node.index() as usize;
+            if index >= self.positions.len() {
+                self.positions.resize(index + 1, NONE);
+            }
+            self.positions[index] = position;
+        }
+    }
+
+    // Updates the bounds of the dirty leaves and of all their ancestors. Parents are always stored
+    // before their children, so the nodes are processed in the reverse order of their indices.
+    fn refit(&mut self) {
+        self.dirty_flags.clear();
+        self.dirty_flags.resize(self.nodes.len(), false);
+        let leaf_count = self.dirty.len();
+        for i in 0..leaf_count {
+            let mut index = self.dirty[i];
+            while index != NONE && !self.dirty_flags[index] {
+                self.dirty_flags[index] = true;
+                self.dirty.push(index);
+                index = self.nodes[index].parent;
+            }
+        }
+        // Leaves were added once again while walking up, so the initial list is not needed.
+        self.dirty.drain(..leaf_count);
+
+        self.dirty.sort_unstable_by(|a, b| b.cmp(a));
+        for &index in self.dirty.iter() {
+            let node = &self.nodes[index];
+            let mut bounds = AxisAlignedBoundingBox::default();
+            if let Some([left, right]) = node.children {
+                bounds.add_box(self.nodes[left].bounds);
+                bounds.add_box(self.nodes[right].bounds);
+            } else {
+                for entry in self.entries[node.first..(node.first + node.count)].iter() {
+                    bounds.add_box(entry.bounds);
+                }
+            }
+            self.nodes[index].bounds = bounds;
+        }
+    }
+
+    fn build_recursive(&mut self, first: usize, count: usize, parent: usize) -> usize {
+        let entries = &mut self.entries[first..(first + count)];
+
+        let mut bounds = AxisAlignedBoundingBox::default();
+        let mut centers = AxisAlignedBoundingBox::default();
+        for entry in entries.iter() {
+            bounds.add_box(entry.bounds);
+            centers.add_point(entry.bounds.center());
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds,
+            first,
+            count,
+            parent,
+            children: None,
+        });
+
+        if count > Self::LEAF_SIZE {
+            // Split by the median of the entry centers along the longest axis.
+            let extents = centers.max - centers.min;
+            let axis = if extents.x >= extents.y && extents.x >= extents.z {
+                0
+            } else if extents.y >= extents.z {
+                1
+            } else {
+                2
+            };
+
+            let half = count / 2;
+            entries.select_nth_unstable_by(half, |a, b| {
+                center_on_axis(a, axis).total_cmp(&center_on_axis(b, axis))
+            });
+
+            let left = self.build_recursive(first, half, index);
+            let right = self.build_recursive(first + half, count - half, index);
+            self.nodes[index].children = Some([left, right]);
+        } else {
+            for leaf in self.leaves[first..(first + count)].iter_mut() {
+                *leaf = index;
+            }
+        }
+
+        index
+    }
+}
+
+/// A result of [`NodeBvh::cull`]. It stores ranges of culled entries (subtrees of the hierarchy are
+/// stored as a single range), so its size depends on the amount of tree nodes intersecting the
+/// frustum boundary, not on the amount of entries.
+#[derive(Clone, Debug)]
+pub struct BvhCulling<'a> {
+    bvh: &'a NodeBvh,
+    // Sorted ranges of positions of culled entries.
+    culled: Vec<Range<usize>>,
+}
+
+impl<'a> BvhCulling<'a> {
+    /// Returns `true` if the given node is in the hierarchy and it is completely outside of the
+    /// frustum. Nodes that are not in the hierarchy are never culled.
+    pub fn is_culled(&self, node: Handle<Node>) -> bool {
+        let Some(position) = self.bvh.position(node) else {
+            return false;
+        };
+        let index = self.culled.partition_point(|range| range.end <= position);
+        self.culled
+            .get(index)
+            .is_some_and(|range| range.contains(&position))
+    }
+
+    /// Returns the amount of culled entries.
+    pub fn culled_count(&self) -> usize {
+        self.culled.iter().map(|range| range.len()).sum()
+    }
+
+    /// Returns the amount of tested entries (it is the total amount of entries in the hierarchy).
+    pub fn tested_count(&self) -> usize {
+        self.bvh.len()
+    }
+}
+
+// Adds a range of culled entries, merging it with the previous one if they're adjacent. Ranges are
+// always added in increasing order.
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+fn center_on_axis(entry: &BvhEntry, axis: usize) -> f32 {
+    entry.bounds.center()[axis]
+}
+
+fn is_inside_frustum(frustum: &Frustum, aabb: &AxisAlignedBoundingBox) -> bool {
+    let corners = aabb.corners();
+    frustum
+        .planes()
+        .iter()
+        .all(|plane| corners.iter().all(|corner| plane.dot(corner) > 0.0))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Point3, Vector3},
+            math::{aabb::AxisAlignedBoundingBox, frustum::Frustum},
+            pool::Handle,
+        },
+        scene::graph::bvh::{BvhEntry, BvhUpdate, NodeBvh},
+    };
+
+    fn frustum() -> Frustum {
+        let projection = Matrix4::new_perspective(1.0, 90.0f32.to_radians(), 0.1, 100.0);
+        let view = Matrix4::look_at_rh(
+            &Point3::new(0.0, 0.0, 0.0),
+            &Point3::new(0.0, 0.0, -1.0),
+            &Vector3::y(),
+        );
+        Frustum::from_view_projection_matrix(projection * view).unwrap()
+    }
+
+    fn entry(index: u32, position: Vector3<f32>) -> BvhEntry {
+        BvhEntry {
+            node: Handle::new(index, 1),
+            bounds: AxisAlignedBoundingBox::from_min_max(
+                position - Vector3::repeat(0.5),
+                position + Vector3::repeat(0.5),
+            ),
+        }
+    }
+
+    fn grid() -> Vec<BvhEntry> {
+        let mut entries = Vec::new();
+        for x in -10..10 {
+            for z in -10..10 {
+                let index = entries.len() as u32;
+                let position = Vector3::new(x as f32 * 3.0, 0.0, z as f32 * 3.0);
+                entries.push(entry(index, position));
+            }
+        }
+        entries
+    }
+
+    fn assert_matches_brute_force(bvh: &NodeBvh, entries: &[BvhEntry]) -> usize {
+        let frustum = frustum();
+        let culling = bvh.cull(&frustum);
+        let mut culled = 0;
+        for entry in entries.iter() {
+            let expected = !frustum.is_intersects_aabb(&entry.bounds);
+            assert_eq!(culling.is_culled(entry.node), expected);
+            if expected {
+                culled += 1;
+            }
+        }
+        assert_eq!(culling.culled_count(), culled);
+        assert_eq!(culling.tested_count(), entries.len());
+        culled
+    }
+
+    #[test]
+    fn test_bvh_cull_matches_brute_force() {
+        let entries = grid();
+        let bvh = NodeBvh::new(entries.clone());
+        assert_eq!(bvh.len(), entries.len());
+
+        let culled = assert_matches_brute_force(&bvh, &entries);
+        assert!(culled > 0 && culled < entries.len());
+
+        // Nodes that are not in the hierarchy are never culled.
+        assert!(!bvh.cull(&frustum()).is_culled(Handle::new(1000, 1)));
+        assert!(!bvh.cull(&frustum()).is_culled(Handle::new(0, 2)));
+    }
+
+    #[test]
+    fn test_bvh_refits_moved_entries() {
+        let mut entries = grid();
+        let mut bvh = NodeBvh::new(entries.clone());
+        assert_eq!(bvh.update(entries.clone()), BvhUpdate::Unchanged);
+
+        // Move a few entries from behind the observer in front of it and vice versa.
+        for (index, z) in [(5, -20.0), (250, 20.0), (399, -5.0)] {
+            entries[index] = entry(index as u32, Vector3::new(0.0, 0.0, z));
+        }
+        assert_eq!(bvh.update(entries.clone()), BvhUpdate::Refitted);
+        assert_matches_brute_force(&bvh, &entries);
+
+        // Order of the entries does not matter.
+        entries.reverse();
+        assert_eq!(bvh.update(entries.clone()), BvhUpdate::Unchanged);
+
+        // Moving every entry is cheaper to handle by a rebuild.
+        for entry in entries.iter_mut() {
+            entry.bounds.offset(Vector3::new(0.0, 0.0, -30.0));
+        }
+        assert_eq!(bvh.update(entries.clone()), BvhUpdate::Rebuilt);
+        assert_matches_brute_force(&bvh, &entries);
+    }
+
+    #[test]
+    fn test_bvh_rebuilds_on_set_change() {
+        let entries = vec![
+            entry(0, Vector3::new(0.0, 0.0, -5.0)),
+            entry(1, Vector3::new(0.0, 0.0, 5.0)),
+        ];
+
+        let mut bvh = NodeBvh::new(entries.clone());
+        assert_eq!(bvh.update(entries.clone()), BvhUpdate::Unchanged);
+
+        let mut changed = entries.clone();
+        changed.push(entry(2, Vector3::new(0.0, 0.0, -10.0)));
+        assert_eq!(bvh.update(changed.clone()), BvhUpdate::Rebuilt);
+        assert_matches_brute_force(&bvh, &changed);
+
+        // Same amount of entries, but a different node.
+        changed[2].node = Handle::new(2, 2);
+        assert_eq!(bvh.update(changed.clone()), BvhUpdate::Rebuilt);
+        assert_matches_brute_force(&bvh, &changed);
+
+        assert_eq!(bvh.update(Vec::new()), BvhUpdate::Rebuilt);
+        assert!(bvh.is_empty());
+        assert_eq!(bvh.cull(&frustum()).culled_count(), 0);
+    }
+}
@@ -44,13 +44,14 @@
 
 use crate::scene::base::NodeMessageKind;
 use crate::{
-    asset::untyped::UntypedResource,
+    asset::{self, untyped::UntypedResource},
     core::{
         algebra::{Matrix4, Rotation3, UnitQuaternion, Vector2, Vector3},
         instant,
         log::{Log, MessageKind},
         math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
         pool::{ErasedHandle, Handle, MultiBorrowContext, Pool, Ticket},
+        profile_scope,
         reflect::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
@@ -62,6 +63,7 @@ use crate::{
         camera::Camera,
         dim2::{self},
         graph::{
+            bvh::{BvhEntry, NodeBvh},
             event::{GraphEvent, GraphEventBroadcaster},
             physics::{PhysicsPerformanceStatistics, PhysicsWorld},
         },
@@ -86,6 +88,7 @@ use std::{
     time::Duration,
 };
 
+pub mod bvh;
 pub mod event;
 pub mod physics;
 
@@ -125,6 +128,33 @@ impl GraphPerformanceStatistics {
 /// A helper type alias for node pool.
 pub type NodePool = Pool<Node, NodeContainer>;
 
+/// Origin shifting settings. Single-precision floating point numbers lose precision quickly when they
+/// get far from zero, which results in vertex jitter, unstable physics and so on in large worlds (a few
+/// kilometers from the origin is enough to notice). Origin shifting solves this by moving the entire
+/// scene back to the origin when an anchor node (usually a camera or a player) travels too far from it.
+/// The accumulated offset is stored in the graph with double precision, see
+/// [`Graph::origin_offset`] and [`Graph::absolute_position`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OriginShifting {
+    /// A node, which position is tracked. When the distance between the node and the origin exceeds
+    /// the threshold, the scene is shifted so the node ends up at the origin. Origin shifting is
+    /// disabled if the handle is invalid. Default is [`Handle::NONE`].
+    pub anchor: Handle<Node>,
+
+    /// Maximum distance from the origin to the anchor, at which the scene will be shifted. Default is
+    /// 1000 units.
+    pub threshold: f32,
+}
+
+impl Default for OriginShifting {
+    fn default() -> Self {
+        Self {
+            anchor: Handle::NONE,
+            threshold: 1000.0,
+        }
+    }
+}
+
 /// See module docs.
 #[derive(Debug, Reflect)]
 pub struct Graph {
@@ -168,6 +198,16 @@ pub struct Graph {
     pub(crate) message_receiver: Receiver<NodeMessage>,
 
     instance_id_map: FxHashMap<SceneNodeId, Handle<Node>>,
+
+    /// Origin shifting settings, see [`OriginShifting`] docs for more info.
+    #[reflect(hidden)]
+    pub origin_shifting: OriginShifting,
+
+    #[reflect(hidden)]
+    origin_offset: Vector3<f64>,
+
+    #[reflect(hidden)]
+    bvh: NodeBvh,
 }
 
 impl Default for Graph {
@@ -190,6 +230,9 @@ impl Default for Graph {
             lightmap: None,
             instance_id_map: Default::default(),
             message_receiver,
+            origin_shifting: Default::default(),
+            origin_offset: Default::default(),
+            bvh: Default::default(),
         }
     }
 }
@@ -269,6 +312,10 @@ pub struct GraphUpdateSwitches {
     /// Whether the graph update is paused or not. Paused graphs won't be updated and their sound content will be also paused
     /// so it won't emit any sounds.
     pub paused: bool,
+    /// A listener node, that defines position and orientation of the sound listener. Every other listener
+    /// node will be ignored. `None` means that every listener is used (the last one wins). It could be useful
+    /// for editors, where the listener should follow the editor's camera.
+    pub active_listener: Option<Handle<Node>>,
 }
 
 impl Default for GraphUpdateSwitches {
@@ -279,6 +326,7 @@ impl Default for GraphUpdateSwitches {
             node_overrides: Default::default(),
             delete_dead_nodes: true,
             paused: false,
+            active_listener: None,
         }
     }
 }
@@ -321,6 +369,9 @@ impl Graph {
             lightmap: None,
             instance_id_map,
             message_receiver,
+            origin_shifting: Default::default(),
+            origin_offset: Default::default(),
+            bvh: Default::default(),
         }
     }
 
@@ -369,6 +420,44 @@ impl Graph {
         references
     }
 
+    /// Returns handles of every scene node that references the given resource (model, texture, sound buffer,
+    /// etc.), including references from embedded resources used by the nodes (for example, textures of embedded
+    /// materials). This method is reflection-based, so it is quite slow and should not be used every frame.
+    pub fn find_resource_users(&self, resource: &UntypedResource) -> Vec<Handle<Node>> {
+        self.pair_iter()
+            .filter(|(_, node)| asset::is_resource_used(*node as &dyn Reflect, resource))
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    /// Replaces every reference to the `what` resource with the `with` resource in every scene node of the
+    /// graph and returns handles of the nodes that were changed. It could be used to upgrade assets or to
+    /// replace placeholders with actual assets. This method is reflection-based, so it is quite slow and should
+    /// not be used every frame.
+    ///
+    /// # Important notes
+    ///
+    /// Both resources must have the same type, otherwise the method does nothing and returns an empty array.
+    /// Replacing a model resource changes only references to the model, existing instances of the model won't
+    /// be re-instantiated.
+    pub fn replace_resource(
+        &mut self,
+        what: &UntypedResource,
+        with: &UntypedResource,
+    ) -> Vec<Handle<Node>> {
+        if what == with || what.type_uuid() != with.type_uuid() {
+            return Vec::new();
+        }
+
+        self.pool
+            .pair_iter_mut()
+            .filter_map(|(handle, node)| {
+                (asset::replace_used_resource(node as &mut dyn Reflect, what, with) > 0)
+                    .then_some(handle)
+            })
+            .collect()
+    }
+
     /// Tries to borrow mutable references to two nodes at the same time by given handles. Will
     /// panic if handles overlaps (points to same node).
     #[inline]
@@ -726,6 +815,23 @@ impl Graph {
         Ok(std::mem::replace(&mut self.lightmap, Some(lightmap)))
     }
 
+    /// Removes current lightmap from the scene and unbinds its textures from the surfaces. The
+    /// lightmap could be set back using [`Self::set_lightmap`].
+    pub fn clear_lightmap(&mut self) -> Option<Lightmap> {
+        let lightmap = self.lightmap.take()?;
+        for handle in lightmap.map.keys() {
+            if let Some(mesh) = self.pool.try_borrow(*handle).and_then(|n| n.cast::<Mesh>()) {
+                for surface in mesh.surfaces() {
+                    let mut material_state = surface.material().state();
+                    if let Some(material) = material_state.data() {
+                        material.unbind("lightmapTexture");
+                    }
+                }
+            }
+        }
+        Some(lightmap)
+    }
+
     /// Returns current lightmap.
     pub fn lightmap(&self) -> Option<&Lightmap> {
         self.lightmap.as_ref()
@@ -1110,6 +1216,7 @@ impl Graph {
 
         let last_time = instant::Instant::now();
         self.process_node_messages();
+        self.update_origin_shifting();
         self.performance_statistics.hierarchical_properties_time =
             instant::Instant::now() - last_time;
 
@@ -1118,17 +1225,22 @@ impl Graph {
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
         if switches.physics {
+            profile_scope!("Physics");
             self.physics.performance_statistics.reset();
             self.physics.update(dt);
             self.performance_statistics.physics = self.physics.performance_statistics.clone();
         }
 
         if switches.physics2d {
+            profile_scope!("Physics 2D");
             self.physics2d.performance_statistics.reset();
             self.physics2d.update(dt);
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
         }
 
+        self.sound_context
+            .update_occlusion(&self.pool, &self.physics, dt);
+
         self.performance_statistics.sound_update_time =
             self.sound_context.state().full_render_duration();
 
@@ -1146,6 +1258,82 @@ impl Graph {
                 );
             }
         }
+
+        self.update_bvh();
+    }
+
+    /// Updates the bounding volume hierarchy of the graph (see [`Self::bvh`]). This method is
+    /// called automatically at the end of [`Self::update`], there's no need to call it manually
+    /// unless you need the hierarchy to reflect the changes made after the update.
+    pub fn update_bvh(&mut self) {
+        self.bvh.update(
+            self.pool
+                .pair_iter()
+                .filter(|(_, node)| {
+                    node.frustum_culling() && !node.local_bounding_box().is_invalid_or_degenerate()
+                })
+                .map(|(handle, node)| BvhEntry {
+                    node: handle,
+                    bounds: node.world_bounding_box(),
+                }),
+        );
+    }
+
+    /// Returns a reference to the bounding volume hierarchy of the nodes with frustum culling
+    /// enabled and valid bounds. The renderer uses it to quickly reject the nodes that are outside
+    /// of the observer's frustum. The hierarchy is updated in [`Self::update`].
+    pub fn bvh(&self) -> &NodeBvh {
+        &self.bvh
+    }
+
+    fn update_origin_shifting(&mut self) {
+        if let Some(anchor) = self.pool.try_borrow(self.origin_shifting.anchor) {
+            let position = anchor.global_position();
+            if position.norm() > self.origin_shifting.threshold {
+                self.shift_origin(position);
+            }
+        }
+    }
+
+    /// Moves every node of the graph by the negated `offset`, so the point at `offset` becomes the new
+    /// origin. Only the direct descendants of the root node are moved, the rest of the nodes are moved
+    /// with them. Physical entities and sound sources are moved as well. The offset is accumulated and
+    /// could be fetched using [`Self::origin_offset`]. Usually there's no need to call this method
+    /// manually, see [`OriginShifting`] docs for automatic origin shifting.
+    ///
+    /// # Important Notes
+    ///
+    /// The method updates hierarchical data of the entire graph, so it could be slow for large graphs
+    /// and should not be called every frame. Any world-space positions cached by your game (for
+    /// example, in scripts) must be shifted too.
+    pub fn shift_origin(&mut self, offset: Vector3<f32>) {
+        let root = self.root;
+        for child in self.pool[root].children().to_vec() {
+            self.pool[child].local_transform_mut().offset(-offset);
+        }
+        self.origin_offset += offset.cast::<f64>();
+        self.update_hierarchical_data();
+    }
+
+    /// Returns total offset of the origin (with double precision), that was accumulated by all
+    /// [`Self::shift_origin`] calls.
+    pub fn origin_offset(&self) -> Vector3<f64> {
+        self.origin_offset
+    }
+
+    /// Returns "absolute" world-space position of the node with double precision, it is not affected by
+    /// origin shifting. Could be used to save positions of objects or to show world coordinates to a
+    /// player.
+    pub fn absolute_position(&self, handle: Handle<Node>) -> Option<Vector3<f64>> {
+        self.pool
+            .try_borrow(handle)
+            .map(|node| node.global_position().cast::<f64>() + self.origin_offset)
+    }
+
+    /// Converts the given "absolute" position (see [`Self::absolute_position`]) to world-space
+    /// position relative to the current origin.
+    pub fn absolute_to_relative_position(&self, absolute_position: Vector3<f64>) -> Vector3<f32> {
+        (absolute_position - self.origin_offset).cast::<f32>()
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -1796,13 +1984,17 @@ mod test {
         },
         engine::{self, SerializationContext},
         graph::{BaseSceneGraph, SceneGraph},
-        resource::model::{Model, ModelResourceExtension},
+        material::{Material, MaterialResource},
+        resource::{
+            model::{Model, ModelResourceExtension},
+            texture::{TextureResource, TextureResourceExtension},
+        },
         scene::{
             base::BaseBuilder,
             graph::Graph,
             mesh::{
                 surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
-                MeshBuilder,
+                Mesh, MeshBuilder,
             },
             node::Node,
             pivot::{Pivot, PivotBuilder},
@@ -2223,4 +2415,97 @@ mod test {
         assert!(!graph[c].is_globally_enabled());
         assert!(!graph[d].is_globally_enabled());
     }
+
+    #[test]
+    fn test_origin_shifting() {
+        let mut graph = Graph::new();
+
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let player = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[child])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(1500.0, 0.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        let other = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 20.0, 30.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+
+        graph.origin_shifting.anchor = player;
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        assert_eq!(graph[player].global_position(), Vector3::zeros());
+        assert_eq!(graph[child].global_position(), Vector3::zeros());
+        assert_eq!(
+            graph[other].global_position(),
+            Vector3::new(-1490.0, 20.0, 30.0)
+        );
+        assert_eq!(graph.origin_offset(), Vector3::new(1500.0, 0.0, 0.0));
+        assert_eq!(
+            graph.absolute_position(other),
+            Some(Vector3::new(10.0, 20.0, 30.0))
+        );
+        assert_eq!(
+            graph.absolute_to_relative_position(Vector3::new(1500.0, 0.0, 0.0)),
+            Vector3::zeros()
+        );
+
+        // The anchor is close to the origin, nothing must change.
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        assert_eq!(graph.origin_offset(), Vector3::new(1500.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_replace_resource() {
+        let mut graph = Graph::new();
+
+        let old_texture = TextureResource::new_render_target(1, 1);
+        let new_texture = TextureResource::new_render_target(2, 2);
+
+        // Texture is referenced indirectly by an embedded material.
+        let mut material = Material::standard();
+        material.bind("diffuseTexture", old_texture.clone());
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_material(MaterialResource::new_ok(ResourceKind::Embedded, material))
+            .build()])
+            .build(&mut graph);
+        PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let old = old_texture.into_untyped();
+        let new = new_texture.clone().into_untyped();
+        assert_eq!(graph.find_resource_users(&old), vec![mesh]);
+        assert!(graph.find_resource_users(&new).is_empty());
+
+        // Resources of different types must not be replaced.
+        let surface_data = graph[mesh].cast::<Mesh>().unwrap().surfaces()[0]
+            .data()
+            .into_untyped();
+        assert!(graph.replace_resource(&old, &surface_data).is_empty());
+        assert_eq!(graph.find_resource_users(&old), vec![mesh]);
+
+        assert_eq!(graph.replace_resource(&old, &new), vec![mesh]);
+        assert!(graph.find_resource_users(&old).is_empty());
+        assert_eq!(graph.find_resource_users(&new), vec![mesh]);
+
+        let material = graph[mesh].cast::<Mesh>().unwrap().surfaces()[0]
+            .material()
+            .clone();
+        assert_eq!(
+            material.data_ref().texture("diffuseTexture"),
+            Some(new_texture)
+        );
+    }
 }
@@ -29,7 +29,7 @@ use crate::{
         arrayvec::ArrayVec,
         instant,
         log::{Log, MessageKind},
-        math::Matrix4Ext,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
         parking_lot::Mutex,
         pool::Handle,
         reflect::prelude::*,
@@ -43,7 +43,7 @@ use crate::{
         collider::{self, ColliderShape, GeometrySource},
         debug::SceneDrawingContext,
         graph::{isometric_global_transform, Graph, NodePool},
-        joint::{JointLocalFrames, JointParams},
+        joint::{JointLocalFrames, JointMotor, JointParams},
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
             Mesh,
@@ -54,7 +54,9 @@ use crate::{
     },
     utils::raw_mesh::{RawMeshBuilder, RawVertex},
 };
+use fxhash::FxHashMap;
 use rapier3d::{
+    control::{CharacterAutostep, CharacterLength, KinematicCharacterController},
     dynamics::{
         CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
         IslandManager, JointAxesMask, MultibodyJointHandle, MultibodyJointSet, RigidBody,
@@ -64,7 +66,10 @@ use rapier3d::{
         Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid, DefaultBroadPhase,
         InteractionGroups, NarrowPhase, Ray, SharedShape,
     },
-    parry::{query::ShapeCastOptions, shape::HeightField},
+    parry::{
+        query::ShapeCastOptions as NativeShapeCastOptions,
+        shape::{Capsule, HeightField},
+    },
     pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline, QueryPipeline},
     prelude::{HeightFieldCellStatus, JointAxis},
 };
@@ -247,6 +252,82 @@ pub struct RayCastOptions {
     pub sort_results: bool,
 }
 
+/// A set of options for the shape cast (also known as sweep test).
+pub struct ShapeCastOptions {
+    /// A shape to cast. Only primitive shapes are supported (ball, cylinder, cone, cuboid, capsule,
+    /// segment, triangle), the shape cast will not be performed for any other shape.
+    pub shape: ColliderShape,
+
+    /// Initial position of the shape in world coordinates.
+    pub shape_position: Point3<f32>,
+
+    /// Initial rotation of the shape in world coordinates.
+    pub shape_rotation: UnitQuaternion<f32>,
+
+    /// A direction of the cast. Can be non-normalized.
+    pub direction: Vector3<f32>,
+
+    /// Maximum distance of the cast.
+    pub max_len: f32,
+
+    /// Groups to check.
+    pub groups: collider::InteractionGroups,
+}
+
+/// A shape cast result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeCastHit {
+    /// A handle of the collider, that was hit by the shape.
+    pub collider: Handle<Node>,
+
+    /// A normal at the contact point in world coordinates. Undefined if `status` is `Penetrating`.
+    pub normal: Vector3<f32>,
+
+    /// A contact point on the hit collider in world coordinates. Undefined if `status` is
+    /// `Penetrating`.
+    pub position: Point3<f32>,
+
+    /// Distance traveled by the shape before the hit.
+    pub toi: f32,
+
+    /// The way the time-of-impact computation algorithm terminated.
+    pub status: collider::TOIStatus,
+}
+
+/// A set of options for the overlap test.
+pub struct OverlapOptions {
+    /// A shape to test. Only primitive shapes are supported (ball, cylinder, cone, cuboid, capsule,
+    /// segment, triangle), the test will not be performed for any other shape.
+    pub shape: ColliderShape,
+
+    /// Position of the shape in world coordinates.
+    pub shape_position: Point3<f32>,
+
+    /// Rotation of the shape in world coordinates.
+    pub shape_rotation: UnitQuaternion<f32>,
+
+    /// Groups to check.
+    pub groups: collider::InteractionGroups,
+}
+
+/// An overlap test result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapHit {
+    /// A handle of the collider, that overlaps the shape.
+    pub collider: Handle<Node>,
+
+    /// The deepest point of the collider inside the shape in world coordinates.
+    pub position: Point3<f32>,
+
+    /// A normal at the contact point in world coordinates. It points from the collider towards
+    /// the tested shape.
+    pub normal: Vector3<f32>,
+
+    /// Penetration depth of the shape and the collider. Could be zero, if the shapes just touch
+    /// each other or if the penetration depth could not be calculated for the pair of shapes.
+    pub penetration_depth: f32,
+}
+
 /// A trait for ray cast results storage. It has two implementations: Vec and ArrayVec.
 /// Latter is needed for the cases where you need to avoid runtime memory allocations
 /// and do everything on stack.
@@ -400,6 +481,38 @@ pub struct IntersectionPair {
     pub has_any_active_contact: bool,
 }
 
+/// A collision event, that was generated during a simulation step. See
+/// [`PhysicsWorld::collision_events`] docs for more info.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollisionEvent {
+    /// Two colliders started touching each other. Contains contact points and impulses of the
+    /// contact at the moment of the event.
+    ContactStarted(ContactPair),
+    /// Two colliders stopped touching each other. The handles could point to deleted colliders,
+    /// if the contact was stopped because of deletion of a collider.
+    ContactStopped {
+        /// The first collider involved in the contact pair.
+        collider1: Handle<Node>,
+        /// The second collider involved in the contact pair.
+        collider2: Handle<Node>,
+    },
+    /// A collider entered a trigger volume (a sensor collider).
+    TriggerEntered {
+        /// A sensor collider.
+        sensor: Handle<Node>,
+        /// A collider, that entered the sensor.
+        collider: Handle<Node>,
+    },
+    /// A collider left a trigger volume (a sensor collider). The handles could point to deleted
+    /// colliders, if the intersection was stopped because of deletion of a collider.
+    TriggerExited {
+        /// A sensor collider.
+        sensor: Handle<Node>,
+        /// A collider, that left the sensor.
+        collider: Handle<Node>,
+    },
+}
+
 pub(super) struct Container<S, A>
 where
     A: Hash + Eq + Clone,
@@ -408,6 +521,20 @@ where
     map: BiDirHashMap<A, Handle<Node>>,
 }
 
+fn set_joint_motor(joint: &mut GenericJoint, axis: JointAxis, motor: &JointMotor) {
+    if motor.enabled {
+        joint
+            .set_motor(
+                axis,
+                motor.target_position,
+                motor.target_velocity,
+                motor.stiffness,
+                motor.damping,
+            )
+            .set_motor_max_force(axis, motor.max_force);
+    }
+}
+
 fn convert_joint_params(
     params: scene::joint::JointParams,
     local_frame1: Isometry3<f32>,
@@ -418,6 +545,19 @@ fn convert_joint_params(
         JointParams::FixedJoint(_) => JointAxesMask::LOCKED_FIXED_AXES,
         JointParams::PrismaticJoint(_) => JointAxesMask::LOCKED_PRISMATIC_AXES,
         JointParams::RevoluteJoint(_) => JointAxesMask::LOCKED_REVOLUTE_AXES,
+        JointParams::SixDofJoint(ref v) => [
+            (&v.linear_x, JointAxesMask::LIN_X),
+            (&v.linear_y, JointAxesMask::LIN_Y),
+            (&v.linear_z, JointAxesMask::LIN_Z),
+            (&v.angular_x, JointAxesMask::ANG_X),
+            (&v.angular_y, JointAxesMask::ANG_Y),
+            (&v.angular_z, JointAxesMask::ANG_Z),
+        ]
+        .into_iter()
+        .filter(|(axis, _)| axis.locked)
+        .fold(JointAxesMask::empty(), |mask, (_, axis_mask)| {
+            mask | axis_mask
+        }),
     };
 
     let mut joint = GenericJointBuilder::new(locked_axis)
@@ -451,11 +591,31 @@ fn convert_joint_params(
             if v.limits_enabled {
                 joint.set_limits(JointAxis::LinX, [v.limits.start, v.limits.end]);
             }
+            set_joint_motor(&mut joint, JointAxis::LinX, &v.motor);
         }
         scene::joint::JointParams::RevoluteJoint(v) => {
             if v.limits_enabled {
                 joint.set_limits(JointAxis::AngX, [v.limits.start, v.limits.end]);
             }
+            set_joint_motor(&mut joint, JointAxis::AngX, &v.motor);
+        }
+        scene::joint::JointParams::SixDofJoint(v) => {
+            for (axis, params) in [
+                (JointAxis::LinX, &v.linear_x),
+                (JointAxis::LinY, &v.linear_y),
+                (JointAxis::LinZ, &v.linear_z),
+                (JointAxis::AngX, &v.angular_x),
+                (JointAxis::AngY, &v.angular_y),
+                (JointAxis::AngZ, &v.angular_z),
+            ] {
+                if params.locked {
+                    continue;
+                }
+                if params.limits_enabled {
+                    joint.set_limits(axis, [params.limits.start, params.limits.end]);
+                }
+                set_joint_motor(&mut joint, axis, &params.motor);
+            }
         }
     }
 
@@ -722,12 +882,8 @@ fn make_heightfield(terrain: &Terrain) -> Option<SharedShape> {
 }
 
 // Converts descriptor in a shared shape.
-fn collider_shape_into_native_shape(
-    shape: &ColliderShape,
-    owner_inv_global_transform: Matrix4<f32>,
-    owner_collider: Handle<Node>,
-    pool: &NodePool,
-) -> Option<SharedShape> {
+// Converts primitive shapes (the ones that do not depend on other scene nodes) into native shapes.
+fn primitive_shape_into_native_shape(shape: &ColliderShape) -> Option<SharedShape> {
     match shape {
         ColliderShape::Ball(ball) => Some(SharedShape::ball(ball.radius)),
 
@@ -752,6 +908,19 @@ fn collider_shape_into_native_shape(
             Point3::from(triangle.b),
             Point3::from(triangle.c),
         )),
+        ColliderShape::Trimesh(_)
+        | ColliderShape::Heightfield(_)
+        | ColliderShape::Polyhedron(_) => None,
+    }
+}
+
+fn collider_shape_into_native_shape(
+    shape: &ColliderShape,
+    owner_inv_global_transform: Matrix4<f32>,
+    owner_collider: Handle<Node>,
+    pool: &NodePool,
+) -> Option<SharedShape> {
+    match shape {
         ColliderShape::Trimesh(trimesh) => {
             if trimesh.sources.is_empty() {
                 None
@@ -772,6 +941,7 @@ fn collider_shape_into_native_shape(
             .try_borrow(polyhedron.geometry_source.0)
             .and_then(|n| n.cast::<Mesh>())
             .map(|mesh| make_polyhedron_shape(owner_inv_global_transform, mesh)),
+        primitive => primitive_shape_into_native_shape(primitive),
     }
 }
 
@@ -1014,6 +1184,17 @@ pub struct PhysicsWorld {
     #[visit(skip)]
     #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
+    // Contact and intersection pairs of the previous simulation step, they're used to generate
+    // collision events.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_contacts: FxHashMap<(ColliderHandle, ColliderHandle), (Handle<Node>, Handle<Node>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_intersections: FxHashMap<(ColliderHandle, ColliderHandle), (Handle<Node>, Handle<Node>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    collision_events: Vec<CollisionEvent>,
 }
 
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry3<f32> {
@@ -1087,6 +1268,396 @@ pub struct TOI {
     pub status: collider::TOIStatus,
 }
 
+/// Kinematic character controller, that moves a capsule through the physics world and resolves
+/// collisions with the static and dynamic colliders along the way. It handles sliding along walls,
+/// climbing slopes up to [`CharacterController::max_slope`], stepping over small obstacles up to
+/// [`CharacterController::step_offset`] and snapping to the ground when going down stairs or slopes.
+///
+/// The controller does not create any scene nodes, it stores the position of the capsule's center
+/// and it is up to you to sync it with your character node (for example, a kinematic rigid body).
+/// Gravity is not applied automatically - add it to the desired translation yourself.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::algebra::Vector3,
+/// #     scene::graph::{physics::{CharacterControllerBuilder, QueryFilter}, Graph},
+/// # };
+/// fn move_character(graph: &Graph, dt: f32, input: Vector3<f32>) {
+///     let mut controller = CharacterControllerBuilder::new()
+///         .with_radius(0.3)
+///         .with_height(1.8)
+///         .with_max_slope(45.0f32.to_radians())
+///         .with_step_offset(0.25)
+///         .build();
+///
+///     let gravity = Vector3::new(0.0, -9.81, 0.0);
+///     controller.move_and_slide(graph, (input + gravity) * dt, dt, QueryFilter::default());
+///
+///     if controller.is_on_ground() {
+///         // Allow jumping, etc.
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharacterController {
+    radius: f32,
+    height: f32,
+    up: Vector3<f32>,
+    max_slope: f32,
+    step_offset: f32,
+    snap_distance: f32,
+    skin_width: f32,
+    position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    on_ground: bool,
+    sliding_down_slope: bool,
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterControllerBuilder::new().build()
+    }
+}
+
+impl CharacterController {
+    /// Sets the radius of the character's capsule.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.max(0.0);
+    }
+
+    /// Returns the radius of the character's capsule.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Sets the total height of the character's capsule (including the hemispheres). The height
+    /// cannot be less than the diameter of the capsule.
+    pub fn set_height(&mut self, height: f32) {
+        self.height = height.max(0.0);
+    }
+
+    /// Returns the total height of the character's capsule.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Sets the up vector of the character. It defines what is "ground" and what is "wall" for
+    /// the controller. Default is (0.0, 1.0, 0.0).
+    pub fn set_up_vector(&mut self, up: Vector3<f32>) {
+        self.up = up.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::y);
+    }
+
+    /// Returns the up vector of the character.
+    pub fn up_vector(&self) -> Vector3<f32> {
+        self.up
+    }
+
+    /// Sets the max angle (in radians) of a slope, that the character can climb. Steeper slopes
+    /// are treated as walls and the character slides down on them.
+    pub fn set_max_slope(&mut self, max_slope: f32) {
+        self.max_slope = max_slope.clamp(0.0, std::f32::consts::FRAC_PI_2);
+    }
+
+    /// Returns the max angle (in radians) of a slope, that the character can climb.
+    pub fn max_slope(&self) -> f32 {
+        self.max_slope
+    }
+
+    /// Sets the max height of an obstacle (a stair step, for example), that the character can
+    /// step over automatically. Zero disables stepping.
+    pub fn set_step_offset(&mut self, step_offset: f32) {
+        self.step_offset = step_offset.max(0.0);
+    }
+
+    /// Returns the max height of an obstacle, that the character can step over automatically.
+    pub fn step_offset(&self) -> f32 {
+        self.step_offset
+    }
+
+    /// Sets the max distance to the ground, at which the character will be snapped to it. It
+    /// prevents the character from "flying" off when going down stairs or slopes. Zero disables
+    /// ground snapping.
+    pub fn set_snap_distance(&mut self, snap_distance: f32) {
+        self.snap_distance = snap_distance.max(0.0);
+    }
+
+    /// Returns the max distance to the ground, at which the character will be snapped to it.
+    pub fn snap_distance(&self) -> f32 {
+        self.snap_distance
+    }
+
+    /// Sets a small gap, that will be kept between the character's capsule and the environment.
+    /// It is used to prevent numerical issues, when the capsule touches other colliders.
+    pub fn set_skin_width(&mut self, skin_width: f32) {
+        self.skin_width = skin_width.max(0.0);
+    }
+
+    /// Returns a small gap, that will be kept between the character's capsule and the environment.
+    pub fn skin_width(&self) -> f32 {
+        self.skin_width
+    }
+
+    /// Teleports the character to the given position (center of the capsule), without collision
+    /// checks. Resets the velocity of the character.
+    pub fn set_position(&mut self, position: Vector3<f32>) {
+        self.position = position;
+        self.velocity = Vector3::default();
+    }
+
+    /// Returns current position of the character (center of the capsule).
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    /// Returns the velocity of the character, calculated from its effective movement during
+    /// the last [`Self::move_and_slide`] call.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Returns `true` if the character was standing on the ground (on a slope that is not steeper
+    /// than the max slope) after the last [`Self::move_and_slide`] call.
+    pub fn is_on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    /// Returns `true` if the character was sliding down a slope that is too steep to climb
+    /// after the last [`Self::move_and_slide`] call.
+    pub fn is_sliding_down_slope(&self) -> bool {
+        self.sliding_down_slope
+    }
+
+    fn capsule(&self) -> Capsule {
+        let half_height = (self.height * 0.5 - self.radius).max(0.0);
+        Capsule::new(
+            Point3::from(-self.up.scale(half_height)),
+            Point3::from(self.up.scale(half_height)),
+            self.radius,
+        )
+    }
+
+    /// Tries to move the character by the given translation, resolving all collisions on the way
+    /// with the colliders of the given graph. Returns actual translation of the character. Use
+    /// `filter` to exclude the character's own collider (if any) from collision checks.
+    pub fn move_and_slide(
+        &mut self,
+        graph: &Graph,
+        desired_translation: Vector3<f32>,
+        dt: f32,
+        filter: QueryFilter,
+    ) -> Vector3<f32> {
+        let physics = &graph.physics;
+
+        let predicate = |handle: ColliderHandle, _: &Collider| -> bool {
+            if let Some(pred) = filter.predicate {
+                let h = Handle::decode_from_u128(physics.colliders.get(handle).unwrap().user_data);
+                pred(
+                    h,
+                    graph.node(h).component_ref::<collider::Collider>().unwrap(),
+                )
+            } else {
+                true
+            }
+        };
+
+        let native_filter = rapier3d::pipeline::QueryFilter {
+            flags: rapier3d::pipeline::QueryFilterFlags::from_bits(filter.flags.bits()).unwrap(),
+            groups: filter.groups.map(|g| {
+                InteractionGroups::new(u32_to_group(g.memberships.0), u32_to_group(g.filter.0))
+            }),
+            exclude_collider: filter
+                .exclude_collider
+                .and_then(|h| graph.try_get(h))
+                .and_then(|n| n.component_ref::<collider::Collider>())
+                .map(|c| c.native.get()),
+            exclude_rigid_body: filter
+                .exclude_rigid_body
+                .and_then(|h| graph.try_get(h))
+                .and_then(|n| n.component_ref::<rigidbody::RigidBody>())
+                .map(|c| c.native.get()),
+            predicate: Some(&predicate),
+        };
+
+        let mut controller = KinematicCharacterController {
+            up: UnitVector3::new_normalize(self.up),
+            offset: CharacterLength::Absolute(self.skin_width),
+            slide: true,
+            autostep: (self.step_offset > 0.0).then_some(CharacterAutostep {
+                max_height: CharacterLength::Absolute(self.step_offset),
+                min_width: CharacterLength::Absolute(self.radius * 0.5),
+                include_dynamic_bodies: false,
+            }),
+            max_slope_climb_angle: self.max_slope,
+            min_slope_slide_angle: self.max_slope,
+            snap_to_ground: (self.snap_distance > 0.0)
+                .then_some(CharacterLength::Absolute(self.snap_distance)),
+            ..Default::default()
+        };
+
+        let mut query = physics.query.borrow_mut();
+        query.update(&physics.colliders);
+
+        let capsule = self.capsule();
+        let move_shape = |controller: &KinematicCharacterController,
+                          position: Vector3<f32>,
+                          translation: Vector3<f32>| {
+            controller.move_shape(
+                dt,
+                &physics.bodies,
+                &physics.colliders,
+                &query,
+                &capsule,
+                &Isometry3::translation(position.x, position.y, position.z),
+                translation,
+                native_filter,
+                |_| {},
+            )
+        };
+
+        // Horizontal and vertical parts of the movement are resolved separately, otherwise the
+        // vertical part (gravity, usually) pushes the character back from the edge of a step
+        // right after the step was taken.
+        let vertical_translation = self.up.scale(desired_translation.dot(&self.up));
+        let horizontal_translation = desired_translation - vertical_translation;
+
+        let horizontal = move_shape(&controller, self.position, horizontal_translation);
+        let stepped = horizontal.translation.dot(&self.up) > f32::EPSILON;
+
+        // Do not slide down from the edge of a step, that was just taken. The capsule's bottom
+        // will be pushed over the edge by the consecutive horizontal movement.
+        controller.slide = !stepped;
+        let vertical = move_shape(
+            &controller,
+            self.position + horizontal.translation,
+            vertical_translation,
+        );
+
+        let translation = horizontal.translation + vertical.translation;
+        let on_ground = horizontal.grounded || vertical.grounded;
+        let sliding_down_slope = !stepped && vertical.is_sliding_down_slope;
+
+        self.position += translation;
+        self.velocity = if dt > 0.0 {
+            translation.scale(1.0 / dt)
+        } else {
+            Vector3::default()
+        };
+        self.on_ground = on_ground;
+        self.sliding_down_slope = sliding_down_slope;
+
+        translation
+    }
+}
+
+/// Character controller builder allows you to create [`CharacterController`] in declarative manner.
+pub struct CharacterControllerBuilder {
+    radius: f32,
+    height: f32,
+    up: Vector3<f32>,
+    max_slope: f32,
+    step_offset: f32,
+    snap_distance: f32,
+    skin_width: f32,
+    position: Vector3<f32>,
+}
+
+impl Default for CharacterControllerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharacterControllerBuilder {
+    /// Creates new builder instance.
+    pub fn new() -> Self {
+        Self {
+            radius: 0.3,
+            height: 1.8,
+            up: Vector3::y(),
+            max_slope: std::f32::consts::FRAC_PI_4,
+            step_offset: 0.3,
+            snap_distance: 0.2,
+            skin_width: 0.01,
+            position: Vector3::default(),
+        }
+    }
+
+    /// Sets the radius of the character's capsule.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the total height of the character's capsule.
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets the up vector of the character.
+    pub fn with_up_vector(mut self, up: Vector3<f32>) -> Self {
+        self.up = up;
+        self
+    }
+
+    /// Sets the max angle (in radians) of a slope, that the character can climb.
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
+    /// Sets the max height of an obstacle, that the character can step over automatically.
+    pub fn with_step_offset(mut self, step_offset: f32) -> Self {
+        self.step_offset = step_offset;
+        self
+    }
+
+    /// Sets the max distance to the ground, at which the character will be snapped to it.
+    pub fn with_snap_distance(mut self, snap_distance: f32) -> Self {
+        self.snap_distance = snap_distance;
+        self
+    }
+
+    /// Sets a small gap, that will be kept between the character's capsule and the environment.
+    pub fn with_skin_width(mut self, skin_width: f32) -> Self {
+        self.skin_width = skin_width;
+        self
+    }
+
+    /// Sets initial position of the character (center of the capsule).
+    pub fn with_position(mut self, position: Vector3<f32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Creates new character controller instance.
+    pub fn build(self) -> CharacterController {
+        let mut controller = CharacterController {
+            radius: 0.0,
+            height: 0.0,
+            up: Vector3::y(),
+            max_slope: 0.0,
+            step_offset: 0.0,
+            snap_distance: 0.0,
+            skin_width: 0.0,
+            position: self.position,
+            velocity: Vector3::default(),
+            on_ground: false,
+            sliding_down_slope: false,
+        };
+        controller.set_radius(self.radius);
+        controller.set_height(self.height);
+        controller.set_up_vector(self.up);
+        controller.set_max_slope(self.max_slope);
+        controller.set_step_offset(self.step_offset);
+        controller.set_snap_distance(self.snap_distance);
+        controller.set_skin_width(self.skin_width);
+        controller
+    }
+}
+
 impl PhysicsWorld {
     /// Creates a new instance of the physics world.
     pub(super) fn new() -> Self {
@@ -1113,6 +1684,9 @@ impl PhysicsWorld {
             query: RefCell::new(Default::default()),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
+            active_contacts: Default::default(),
+            active_intersections: Default::default(),
+            collision_events: Default::default(),
         }
     }
 
@@ -1168,11 +1742,105 @@ impl PhysicsWorld {
                 &(),
                 &*self.event_handler,
             );
+
+            self.update_collision_events();
+        } else {
+            self.collision_events.clear();
         }
 
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    fn update_collision_events(&mut self) {
+        let mut events = Vec::new();
+
+        let mut previous_contacts = std::mem::take(&mut self.active_contacts);
+        let mut contacts = FxHashMap::default();
+        for pair in self
+            .narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+        {
+            let key = (pair.collider1, pair.collider2);
+            if let Some(nodes) = previous_contacts.remove(&key) {
+                contacts.insert(key, nodes);
+            } else if let Some(contact) = ContactPair::from_native(pair, self) {
+                contacts.insert(key, (contact.collider1, contact.collider2));
+                events.push(CollisionEvent::ContactStarted(contact));
+            }
+        }
+        events.extend(
+            previous_contacts
+                .into_values()
+                .map(|(collider1, collider2)| CollisionEvent::ContactStopped {
+                    collider1,
+                    collider2,
+                }),
+        );
+
+        let mut previous_intersections = std::mem::take(&mut self.active_intersections);
+        let mut intersections = FxHashMap::default();
+        for (collider1, collider2, _) in self
+            .narrow_phase
+            .intersection_pairs()
+            .filter(|(_, _, intersecting)| *intersecting)
+        {
+            let key = (collider1, collider2);
+            if let Some(nodes) = previous_intersections.remove(&key) {
+                intersections.insert(key, nodes);
+            } else if let (Some(native1), Some(native2)) =
+                (self.colliders.get(collider1), self.colliders.get(collider2))
+            {
+                let node1 = Handle::decode_from_u128(native1.user_data);
+                let node2 = Handle::decode_from_u128(native2.user_data);
+                let (sensor, collider) = if native1.is_sensor() {
+                    (node1, node2)
+                } else {
+                    (node2, node1)
+                };
+                intersections.insert(key, (sensor, collider));
+                events.push(CollisionEvent::TriggerEntered { sensor, collider });
+            }
+        }
+        events.extend(
+            previous_intersections
+                .into_values()
+                .map(|(sensor, collider)| CollisionEvent::TriggerExited { sensor, collider }),
+        );
+
+        self.active_contacts = contacts;
+        self.active_intersections = intersections;
+        self.collision_events = events;
+    }
+
+    /// Returns collision events (started and stopped contacts, trigger enter and exit), that were
+    /// generated during the last simulation step. The events are generated for every collider in
+    /// the world and stay available until the next simulation step.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{
+    /// #     core::pool::Handle,
+    /// #     scene::{graph::{physics::CollisionEvent, Graph}, node::Node},
+    /// # };
+    /// fn is_pickup_touched(graph: &Graph, player: Handle<Node>, pickup: Handle<Node>) -> bool {
+    ///     graph.physics.collision_events().iter().any(|event| {
+    ///         matches!(event, CollisionEvent::TriggerEntered { sensor, collider }
+    ///             if *sensor == pickup && *collider == player)
+    ///     })
+    /// }
+    /// ```
+    pub fn collision_events(&self) -> &[CollisionEvent] {
+        &self.collision_events
+    }
+
+    /// Takes collision events of the last simulation step, leaving the queue empty. See
+    /// [`Self::collision_events`] for more info.
+    pub fn take_collision_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.collision_events)
+    }
+
     pub(super) fn add_body(&mut self, owner: Handle<Node>, mut body: RigidBody) -> RigidBodyHandle {
         body.user_data = owner.encode_to_u128();
         self.bodies.insert(body)
@@ -1358,7 +2026,7 @@ impl PhysicsWorld {
 
         let query = self.query.borrow_mut();
 
-        let opts = ShapeCastOptions {
+        let opts = NativeShapeCastOptions {
             max_time_of_impact: max_toi,
             target_distance: 0.0,
             stop_at_penetration,
@@ -1390,6 +2058,265 @@ impl PhysicsWorld {
             })
     }
 
+    /// Casts a shape along the given direction and returns the first collider it hits (if any).
+    /// Unlike [`Self::cast_shape`], this method works with high-level shapes and returns all the
+    /// hit data in world coordinates. See [`ShapeCastOptions`] docs for more info.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{
+    /// #     core::{algebra::{Point3, UnitQuaternion, Vector3}, pool::Handle},
+    /// #     scene::{
+    /// #         collider::{ColliderShape, InteractionGroups},
+    /// #         graph::{physics::ShapeCastOptions, Graph},
+    /// #         node::Node,
+    /// #     },
+    /// # };
+    /// fn find_obstacle(graph: &Graph, position: Point3<f32>) -> Option<Handle<Node>> {
+    ///     graph
+    ///         .physics
+    ///         .sweep_shape(ShapeCastOptions {
+    ///             shape: ColliderShape::ball(0.5),
+    ///             shape_position: position,
+    ///             shape_rotation: UnitQuaternion::identity(),
+    ///             direction: Vector3::new(0.0, 0.0, 1.0),
+    ///             max_len: 10.0,
+    ///             groups: InteractionGroups::default(),
+    ///         })
+    ///         .map(|hit| hit.collider)
+    /// }
+    /// ```
+    pub fn sweep_shape(&self, opts: ShapeCastOptions) -> Option<ShapeCastHit> {
+        let time = instant::Instant::now();
+
+        let shape = primitive_shape_into_native_shape(&opts.shape)?;
+
+        let direction = opts.direction.try_normalize(f32::EPSILON)?;
+
+        let mut query = self.query.borrow_mut();
+
+        // See comment in `cast_ray`.
+        query.update(&self.colliders);
+
+        let result = query
+            .cast_shape(
+                &self.bodies,
+                &self.colliders,
+                &Isometry3 {
+                    translation: Translation3::from(opts.shape_position.coords),
+                    rotation: opts.shape_rotation,
+                },
+                &direction,
+                &*shape,
+                NativeShapeCastOptions {
+                    max_time_of_impact: opts.max_len,
+                    target_distance: 0.0,
+                    stop_at_penetration: true,
+                    compute_impact_geometry_on_penetration: true,
+                },
+                rapier3d::pipeline::QueryFilter::new().groups(InteractionGroups::new(
+                    u32_to_group(opts.groups.memberships.0),
+                    u32_to_group(opts.groups.filter.0),
+                )),
+            )
+            .map(|(handle, hit)| ShapeCastHit {
+                collider: Handle::decode_from_u128(self.colliders.get(handle).unwrap().user_data),
+                normal: hit.normal1.into_inner(),
+                position: hit.witness1,
+                toi: hit.time_of_impact,
+                status: hit.status.into(),
+            });
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+
+        result
+    }
+
+    /// Returns every collider that overlaps the given shape, along with the contact data. This
+    /// method could be used to detect melee weapon hits, or to check whether there is enough free
+    /// space to place an object. See [`OverlapOptions`] docs for more info.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{
+    /// #     core::{algebra::{Point3, UnitQuaternion, Vector3}, pool::Handle},
+    /// #     scene::{
+    /// #         collider::{BitMask, ColliderShape, InteractionGroups},
+    /// #         graph::{physics::OverlapOptions, Graph},
+    /// #         node::Node,
+    /// #     },
+    /// # };
+    /// fn melee_hits(graph: &Graph, sword_tip: Point3<f32>) -> Vec<Handle<Node>> {
+    ///     graph
+    ///         .physics
+    ///         .overlap_shape(OverlapOptions {
+    ///             shape: ColliderShape::cuboid(0.1, 0.1, 0.6),
+    ///             shape_position: sword_tip,
+    ///             shape_rotation: UnitQuaternion::identity(),
+    ///             // Enemies are in the second collision layer.
+    ///             groups: InteractionGroups::new(BitMask(0xFFFF_FFFF), BitMask(0b10)),
+    ///         })
+    ///         .into_iter()
+    ///         .map(|hit| hit.collider)
+    ///         .collect()
+    /// }
+    /// ```
+    pub fn overlap_shape(&self, opts: OverlapOptions) -> Vec<OverlapHit> {
+        let time = instant::Instant::now();
+
+        let mut hits = Vec::new();
+
+        if let Some(shape) = primitive_shape_into_native_shape(&opts.shape) {
+            let shape_position = Isometry3 {
+                translation: Translation3::from(opts.shape_position.coords),
+                rotation: opts.shape_rotation,
+            };
+
+            let mut query = self.query.borrow_mut();
+
+            // See comment in `cast_ray`.
+            query.update(&self.colliders);
+
+            query.intersections_with_shape(
+                &self.bodies,
+                &self.colliders,
+                &shape_position,
+                &*shape,
+                rapier3d::pipeline::QueryFilter::new().groups(InteractionGroups::new(
+                    u32_to_group(opts.groups.memberships.0),
+                    u32_to_group(opts.groups.filter.0),
+                )),
+                |handle| {
+                    let collider = &self.colliders[handle];
+                    let contact = rapier3d::parry::query::contact(
+                        &shape_position,
+                        &*shape,
+                        collider.position(),
+                        collider.shape(),
+                        0.0,
+                    )
+                    .ok()
+                    .flatten();
+                    let (position, normal, penetration_depth) = match contact {
+                        Some(contact) => (
+                            contact.point2,
+                            contact.normal2.into_inner(),
+                            (-contact.dist).max(0.0),
+                        ),
+                        None => {
+                            // Some pairs of shapes (for example, a shape and a triangle mesh) do
+                            // not support contact computation, use the closest point instead.
+                            let projection = collider.shape().project_point(
+                                collider.position(),
+                                &opts.shape_position,
+                                true,
+                            );
+                            (
+                                projection.point,
+                                (opts.shape_position - projection.point)
+                                    .try_normalize(f32::EPSILON)
+                                    .unwrap_or_default(),
+                                0.0,
+                            )
+                        }
+                    };
+                    hits.push(OverlapHit {
+                        collider: Handle::decode_from_u128(collider.user_data),
+                        position,
+                        normal,
+                        penetration_depth,
+                    });
+                    true
+                },
+            );
+        }
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+
+        hits
+    }
+
+    /// Returns every collider that overlaps a sphere with the given center and radius. See
+    /// [`Self::overlap_shape`] for more info.
+    pub fn overlap_sphere(
+        &self,
+        center: Point3<f32>,
+        radius: f32,
+        groups: collider::InteractionGroups,
+    ) -> Vec<OverlapHit> {
+        self.overlap_shape(OverlapOptions {
+            shape: ColliderShape::ball(radius),
+            shape_position: center,
+            shape_rotation: UnitQuaternion::identity(),
+            groups,
+        })
+    }
+
+    /// Returns every collider that overlaps an oriented box with the given center, half extents
+    /// and rotation. See [`Self::overlap_shape`] for more info.
+    pub fn overlap_box(
+        &self,
+        center: Point3<f32>,
+        half_extents: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        groups: collider::InteractionGroups,
+    ) -> Vec<OverlapHit> {
+        self.overlap_shape(OverlapOptions {
+            shape: ColliderShape::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            shape_position: center,
+            shape_rotation: rotation,
+            groups,
+        })
+    }
+
+    /// Projects every given point on the closest fixed (static) collider, that passes the given
+    /// collision groups filter. The callback is called for every point, that has a projection, with
+    /// the index of the point, the projected point and a flag, that tells whether the point is inside
+    /// the collider or not.
+    pub(crate) fn project_points_on_fixed_colliders(
+        &self,
+        points: &[Vector3<f32>],
+        groups: collider::InteractionGroups,
+        mut func: impl FnMut(usize, Point3<f32>, bool),
+    ) {
+        let time = instant::Instant::now();
+
+        let mut query = self.query.borrow_mut();
+
+        // See comment in `cast_ray`.
+        query.update(&self.colliders);
+
+        let filter = rapier3d::pipeline::QueryFilter::only_fixed().groups(InteractionGroups::new(
+            u32_to_group(groups.memberships.0),
+            u32_to_group(groups.filter.0),
+        ));
+
+        for (index, point) in points.iter().enumerate() {
+            if let Some((_, projection)) = query.project_point(
+                &self.bodies,
+                &self.colliders,
+                &Point3::from(*point),
+                false,
+                filter,
+            ) {
+                func(index, projection.point, projection.is_inside);
+            }
+        }
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+    }
+
     pub(crate) fn set_rigid_body_position(
         &mut self,
         rigid_body: &scene::rigidbody::RigidBody,
@@ -1496,6 +2423,9 @@ impl PhysicsWorld {
                     rigid_body_node
                         .ccd_enabled
                         .try_sync_model(|v| native.enable_ccd(v));
+                    rigid_body_node
+                        .soft_ccd_prediction
+                        .try_sync_model(|v| native.set_soft_ccd_prediction(v));
                     rigid_body_node.can_sleep.try_sync_model(|v| {
                         let activation = native.activation_mut();
                         if v {
@@ -1582,6 +2512,7 @@ impl PhysicsWorld {
                     &rigid_body_node.global_transform(),
                 ))
                 .ccd_enabled(rigid_body_node.is_ccd_enabled())
+                .soft_ccd_prediction(rigid_body_node.soft_ccd_prediction())
                 .additional_mass(rigid_body_node.mass())
                 .angvel(*rigid_body_node.ang_vel)
                 .linvel(*rigid_body_node.lin_vel)
@@ -1896,6 +2827,19 @@ impl PhysicsWorld {
             .filter_map(|c| ContactPair::from_native(c, self))
     }
 
+    /// Returns world-space bounding box and volume of the given collider.
+    pub(crate) fn collider_bounds(
+        &self,
+        collider: ColliderHandle,
+    ) -> Option<(AxisAlignedBoundingBox, f32)> {
+        let collider = self.colliders.get(collider)?;
+        let aabb = collider.compute_aabb();
+        Some((
+            AxisAlignedBoundingBox::from_min_max(aabb.mins.coords, aabb.maxs.coords),
+            collider.shape().mass_properties(1.0).mass(),
+        ))
+    }
+
     /// Returns an iterator over all contact pairs generated in this frame.
     pub fn contacts(&self) -> impl Iterator<Item = ContactPair> + '_ {
         self.narrow_phase
@@ -1915,3 +2859,413 @@ impl Debug for PhysicsWorld {
         write!(f, "PhysicsWorld")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::graph::{BaseSceneGraph, SceneGraph};
+    use crate::{
+        core::{
+            algebra::{Point3, UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{BitMask, ColliderBuilder, ColliderShape, InteractionGroups},
+            graph::{
+                physics::{
+                    CharacterControllerBuilder, CollisionEvent, OverlapOptions, QueryFilter,
+                    ShapeCastOptions,
+                },
+                Graph,
+            },
+            joint::{JointBuilder, JointMotor, JointParams, RevoluteJoint, SixDofJoint},
+            node::Node,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    fn add_box(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        half_extents: Vector3<f32>,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(graph);
+        collider
+    }
+
+    fn add_motorized_body(graph: &mut Graph, params: JointParams) -> Handle<Node> {
+        let fixed_collider = add_box(graph, Vector3::new(0.0, 5.0, 0.0), Vector3::repeat(0.5));
+        let fixed_body = graph[fixed_collider].parent();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_gravity_scale(0.0)
+            .build(graph);
+
+        JointBuilder::new(BaseBuilder::new())
+            .with_params(params)
+            .with_body1(fixed_body)
+            .with_body2(body)
+            .build(graph);
+
+        body
+    }
+
+    #[test]
+    fn test_joint_motors() {
+        let mut graph = Graph::new();
+
+        let wheel = add_motorized_body(
+            &mut graph,
+            JointParams::RevoluteJoint(RevoluteJoint {
+                motor: JointMotor::velocity(2.0, 1.0),
+                ..Default::default()
+            }),
+        );
+
+        let mut six_dof = SixDofJoint::default();
+        for axis in [
+            &mut six_dof.linear_y,
+            &mut six_dof.linear_z,
+            &mut six_dof.angular_x,
+            &mut six_dof.angular_y,
+            &mut six_dof.angular_z,
+        ] {
+            axis.locked = true;
+        }
+        six_dof.linear_x.motor = JointMotor::position(1.0, 100.0, 20.0);
+        let slider = add_motorized_body(&mut graph, JointParams::SixDofJoint(six_dof));
+
+        for _ in 0..240 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let wheel = graph.try_get_of_type::<RigidBody>(wheel).unwrap();
+        assert!((wheel.ang_vel().x.abs() - 2.0).abs() < 0.05);
+        assert!(wheel.ang_vel().yz().norm() < 0.05);
+
+        let slider_position = graph[slider].global_position();
+        assert!((slider_position.x.abs() - 1.0).abs() < 0.05);
+        assert!(slider_position.yz().norm() < 0.05);
+    }
+
+    #[test]
+    fn test_disabled_subtree_physics() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let body =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+        let pivot = PivotBuilder::new(BaseBuilder::new().with_children(&[body])).build(&mut graph);
+
+        graph[pivot].set_enabled(false);
+
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // A body in a disabled subtree must not be simulated.
+        let rigid_body = graph.try_get_of_type::<RigidBody>(body).unwrap();
+        assert!(!rigid_body.is_globally_enabled());
+        assert!(graph.physics.bodies.is_empty());
+        assert!(graph.physics.colliders.is_empty());
+        assert_eq!(graph[body].global_position(), Vector3::default());
+
+        graph[pivot].set_enabled(true);
+
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert_eq!(graph.physics.bodies.len(), 1);
+        assert_eq!(graph.physics.colliders.len(), 1);
+        assert!(graph[body].global_position().y < -1.0);
+    }
+
+    #[test]
+    fn test_character_controller() {
+        let mut graph = Graph::new();
+
+        // Floor with the top at y = 0.0.
+        add_box(
+            &mut graph,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(20.0, 0.5, 20.0),
+        );
+        // A small step with the top at y = 0.2.
+        add_box(
+            &mut graph,
+            Vector3::new(3.0, 0.1, 0.0),
+            Vector3::new(1.0, 0.1, 5.0),
+        );
+
+        // Native colliders are created on the second update, after their rigid bodies.
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let mut controller = CharacterControllerBuilder::new()
+            .with_radius(0.3)
+            .with_height(1.8)
+            .with_step_offset(0.3)
+            .with_position(Vector3::new(0.0, 2.0, 0.0))
+            .build();
+
+        assert!(!controller.is_on_ground());
+
+        let dt = 1.0 / 60.0;
+        for _ in 0..120 {
+            controller.move_and_slide(
+                &graph,
+                Vector3::new(0.0, -5.0 * dt, 0.0),
+                dt,
+                QueryFilter::default(),
+            );
+        }
+
+        assert!(controller.is_on_ground());
+        assert!((controller.position().y - 0.9).abs() < 0.05);
+        assert!(controller.velocity().norm() < 0.01);
+
+        // Walk forward onto the step.
+        for _ in 0..120 {
+            controller.move_and_slide(
+                &graph,
+                Vector3::new(2.0 * dt, -5.0 * dt, 0.0),
+                dt,
+                QueryFilter::default(),
+            );
+        }
+
+        assert!(controller.position().x > 2.5);
+        assert!((controller.position().y - 1.1).abs() < 0.05);
+        assert!(controller.is_on_ground());
+    }
+
+    #[test]
+    fn test_sweep_shape() {
+        let mut graph = Graph::new();
+
+        let floor = add_box(
+            &mut graph,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(20.0, 0.5, 20.0),
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let options = |groups| ShapeCastOptions {
+            shape: ColliderShape::ball(0.5),
+            shape_position: Point3::new(1.0, 5.0, 0.0),
+            shape_rotation: UnitQuaternion::identity(),
+            direction: Vector3::new(0.0, -2.0, 0.0),
+            max_len: 10.0,
+            groups,
+        };
+
+        let hit = graph
+            .physics
+            .sweep_shape(options(InteractionGroups::default()))
+            .unwrap();
+        assert_eq!(hit.collider, floor);
+        assert!((hit.toi - 4.5).abs() < 0.01);
+        assert!((hit.normal - Vector3::y()).norm() < 0.01);
+        assert!((hit.position - Point3::new(1.0, 0.0, 0.0)).norm() < 0.01);
+
+        assert!(graph
+            .physics
+            .sweep_shape(options(InteractionGroups::new(
+                BitMask(u32::MAX),
+                BitMask(0)
+            )))
+            .is_none());
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let mut graph = Graph::new();
+
+        let floor = add_box(
+            &mut graph,
+            Vector3::new(0.0, -0.5, 0.0),
+            Vector3::new(20.0, 0.5, 20.0),
+        );
+
+        let trigger = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(1.0, 0.5, 1.0))
+            .with_sensor(true)
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[trigger]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let ball = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.25))
+            .build(&mut graph);
+        let ball_body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 4.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[ball]),
+        )
+        .with_body_type(RigidBodyType::Dynamic)
+        .build(&mut graph);
+
+        let mut events = Vec::new();
+        for _ in 0..180 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            events.extend(graph.physics.take_collision_events());
+        }
+        assert!(graph.physics.collision_events().is_empty());
+
+        let position = |event: &CollisionEvent| events.iter().position(|e| e == event);
+        let entered = position(&CollisionEvent::TriggerEntered {
+            sensor: trigger,
+            collider: ball,
+        })
+        .unwrap();
+        let exited = position(&CollisionEvent::TriggerExited {
+            sensor: trigger,
+            collider: ball,
+        })
+        .unwrap();
+        assert!(entered < exited);
+
+        let started = events
+            .iter()
+            .position(|event| match event {
+                CollisionEvent::ContactStarted(pair) => {
+                    (pair.collider1 == floor && pair.collider2 == ball)
+                        || (pair.collider1 == ball && pair.collider2 == floor)
+                }
+                _ => false,
+            })
+            .unwrap();
+        assert!(exited < started);
+
+        // Removing the ball must stop the contact.
+        graph.remove_node(ball_body);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert!(graph
+            .physics
+            .collision_events()
+            .iter()
+            .any(|event| matches!(event, CollisionEvent::ContactStopped { .. })));
+    }
+
+    #[test]
+    fn test_soft_ccd_sync() {
+        let mut graph = Graph::new();
+
+        let body = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_ccd_enabled(true)
+            .with_soft_ccd_prediction(0.5)
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let native = |graph: &Graph| {
+            let handle = graph
+                .try_get_of_type::<RigidBody>(body)
+                .unwrap()
+                .native
+                .get();
+            graph.physics.bodies.get(handle).unwrap().clone()
+        };
+        assert!(native(&graph).is_ccd_enabled());
+        assert_eq!(native(&graph).soft_ccd_prediction(), 0.5);
+
+        graph
+            .try_get_mut_of_type::<RigidBody>(body)
+            .unwrap()
+            .set_soft_ccd_prediction(-1.0);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        assert_eq!(native(&graph).soft_ccd_prediction(), 0.0);
+    }
+
+    #[test]
+    fn test_overlap_queries() {
+        let mut graph = Graph::new();
+
+        let near = add_box(
+            &mut graph,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::repeat(0.5),
+        );
+        let far = add_box(
+            &mut graph,
+            Vector3::new(10.0, 0.0, 0.0),
+            Vector3::repeat(0.5),
+        );
+        graph[far]
+            .as_collider_mut()
+            .set_collision_groups(InteractionGroups::new(BitMask(0b10), BitMask(0xFFFF_FFFF)));
+
+        // Native colliders are created on the next update after their rigid bodies.
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let hits = graph
+            .physics
+            .overlap_sphere(Point3::origin(), 1.0, Default::default());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].collider, near);
+        assert!((hits[0].position.x - 0.5).abs() < 0.01);
+        assert!((hits[0].normal.x + 1.0).abs() < 0.01);
+        assert!((hits[0].penetration_depth - 0.5).abs() < 0.01);
+
+        // Layer filter must exclude the far box, even though it overlaps the shape.
+        let hits = graph.physics.overlap_shape(OverlapOptions {
+            shape: ColliderShape::cuboid(6.0, 1.0, 1.0),
+            shape_position: Point3::new(5.0, 0.0, 0.0),
+            shape_rotation: UnitQuaternion::identity(),
+            groups: InteractionGroups::new(BitMask(0xFFFF_FFFF), BitMask(0b01)),
+        });
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].collider, near);
+
+        let hits = graph.physics.overlap_box(
+            Point3::new(10.0, 0.0, 0.0),
+            Vector3::repeat(0.1),
+            UnitQuaternion::identity(),
+            Default::default(),
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].collider, far);
+    }
+}
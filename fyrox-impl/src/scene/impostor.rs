@@ -0,0 +1,450 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Impostor is a camera-facing quad with a pre-rendered image of its descendant nodes, that replaces
+//! them when they're far away from the observer. See [`Impostor`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, Rect, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        value_as_u8_slice,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::BaseSceneGraph,
+    material::{self, MaterialResource},
+    renderer::{self, bundle::RenderContext},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        mesh::{buffer::VertexTrait, RenderPath},
+        node::{Node, NodeTrait, RdcControlFlow},
+        sprite::SpriteVertex,
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Returns the amount of columns (and rows) of an impostor atlas with the given amount of frames.
+/// Frames are stored in a square grid, row by row, starting from the top-left corner of the atlas.
+pub fn atlas_columns(frames: u32) -> u32 {
+    (frames.max(1) as f32).sqrt().ceil() as u32
+}
+
+/// Returns a rectangle in normalized texture coordinates, that is occupied by a frame with the given
+/// index in an impostor atlas with the given amount of frames.
+pub fn frame_uv_rect(frames: u32, index: u32) -> Rect<f32> {
+    let columns = atlas_columns(frames);
+    let frame_size = 1.0 / columns as f32;
+    let index = index.min(frames.max(1) - 1);
+    Rect::new(
+        (index % columns) as f32 * frame_size,
+        (index / columns) as f32 * frame_size,
+        frame_size,
+        frame_size,
+    )
+}
+
+/// Returns an index of a frame that was rendered from the given direction. The direction is defined
+/// in local coordinates of an impostor and points from the center of the impostor towards the
+/// observer. Frame `i` is rendered from `i * 2π / frames` angle around the local Y axis, angle `0`
+/// corresponds to the local Z axis.
+pub fn frame_index(frames: u32, direction: Vector3<f32>) -> u32 {
+    let frames = frames.max(1);
+    let step = std::f32::consts::TAU / frames as f32;
+    let angle = direction.x.atan2(direction.z);
+    ((angle / step).round() as i64).rem_euclid(frames as i64) as u32
+}
+
+/// Impostor is a camera-facing quad with a pre-rendered image of its descendant nodes, that replaces
+/// them when they're further than [`Self::distance`] from the observer. It is a cheap way of
+/// rendering distant complex objects (trees, buildings, rocks, etc.) - instead of thousands of
+/// triangles only two are drawn.
+///
+/// The image is taken from an atlas with multiple frames, each frame is a view of the object from
+/// an angle around the local Y axis (see [`frame_index`] and [`frame_uv_rect`] for the layout of the
+/// atlas). The frame, that is closest to the current view direction, is used for rendering and the
+/// quad rotates only around the local Y axis, so the image does not "lean" when the camera looks
+/// down at it.
+///
+/// Atlases are usually baked using [`crate::utils::impostor::bake`] (the editor has a tool for
+/// this called "Impostor Baker"), but any texture with the layout described above will work.
+///
+/// ## Shadows
+///
+/// The descendant nodes are always rendered in the shadow passes, so shadows of the object stay
+/// correct when it is replaced by its impostor.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     graph::BaseSceneGraph,
+/// #     scene::{base::BaseBuilder, graph::Graph, impostor::ImpostorBuilder, node::Node},
+/// # };
+/// fn wrap_in_impostor(tree: Handle<Node>, graph: &mut Graph) -> Handle<Node> {
+///     let impostor = ImpostorBuilder::new(BaseBuilder::new())
+///         .with_distance(50.0)
+///         .build(graph);
+///     graph.link_nodes(tree, impostor);
+///     impostor
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct Impostor {
+    base: Base,
+
+    #[reflect(setter = "set_material")]
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(min_value = 1.0, setter = "set_frames")]
+    frames: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_size")]
+    size: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_center")]
+    center: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_distance")]
+    distance: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+}
+
+impl Deref for Impostor {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Impostor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for Impostor {
+    fn default() -> Self {
+        ImpostorBuilder::new(BaseBuilder::new()).build_impostor()
+    }
+}
+
+impl TypeUuidProvider for Impostor {
+    fn type_uuid() -> Uuid {
+        uuid!("8c3f2b71-5d4e-4a9b-b6f0-2e7d1c9a4f53")
+    }
+}
+
+impl Impostor {
+    /// Sets a material of the impostor. The material must use a shader with the same vertex layout
+    /// as the standard sprite shader and its `diffuseTexture` must be an impostor atlas.
+    pub fn set_material(&mut self, material: MaterialResource) -> MaterialResource {
+        self.material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns a reference to the current material of the impostor.
+    pub fn material(&self) -> &MaterialResource {
+        &self.material
+    }
+
+    /// Sets the amount of frames in the atlas of the impostor.
+    pub fn set_frames(&mut self, frames: u32) -> u32 {
+        self.frames.set_value_and_mark_modified(frames.max(1))
+    }
+
+    /// Returns the amount of frames in the atlas of the impostor.
+    pub fn frames(&self) -> u32 {
+        *self.frames
+    }
+
+    /// Sets the size (width and height) of the impostor quad in local coordinates.
+    pub fn set_size(&mut self, size: f32) -> f32 {
+        self.size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns the size of the impostor quad in local coordinates.
+    pub fn size(&self) -> f32 {
+        *self.size
+    }
+
+    /// Sets the center of the impostor quad in local coordinates.
+    pub fn set_center(&mut self, center: Vector3<f32>) -> Vector3<f32> {
+        self.center.set_value_and_mark_modified(center)
+    }
+
+    /// Returns the center of the impostor quad in local coordinates.
+    pub fn center(&self) -> Vector3<f32> {
+        *self.center
+    }
+
+    /// Sets the distance from the observer at which the descendant nodes are replaced with the
+    /// impostor.
+    pub fn set_distance(&mut self, distance: f32) -> f32 {
+        self.distance.set_value_and_mark_modified(distance)
+    }
+
+    /// Returns the distance from the observer at which the descendant nodes are replaced with the
+    /// impostor.
+    pub fn distance(&self) -> f32 {
+        *self.distance
+    }
+
+    /// Sets the color of the impostor, it is multiplied with the color of the atlas.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns the color of the impostor.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+}
+
+impl NodeTrait for Impostor {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_size = Vector3::repeat(*self.size * 0.5);
+        AxisAlignedBoundingBox::from_min_max(*self.center - half_size, *self.center + half_size)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || renderer::is_shadow_pass(ctx.render_pass_name)
+        {
+            return RdcControlFlow::Continue;
+        }
+
+        let global_transform = self.global_transform();
+        let center = global_transform
+            .transform_point(&Point3::from(*self.center))
+            .coords;
+        let to_observer = ctx.observer_info.observer_position - center;
+        if to_observer.norm() < *self.distance {
+            return RdcControlFlow::Continue;
+        }
+
+        let side = global_transform.side();
+        let up = global_transform.up();
+        let up_axis = up.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::y);
+
+        // The quad rotates only around the up axis, so the view direction is projected on the
+        // horizontal plane of the impostor.
+        let forward = (to_observer - up_axis.scale(to_observer.dot(&up_axis)))
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| global_transform.look().normalize());
+        let local_forward = global_transform
+            .try_inverse()
+            .map(|inv| inv.transform_vector(&forward))
+            .unwrap_or(forward);
+        let uv_rect = frame_uv_rect(*self.frames, frame_index(*self.frames, local_forward));
+
+        let half_width = up_axis
+            .cross(&forward)
+            .scale(*self.size * 0.5 * side.norm());
+        let half_height = up.scale(*self.size * 0.5);
+
+        let vertices = [
+            (center - half_width + half_height, uv_rect.left_top_corner()),
+            (
+                center + half_width + half_height,
+                uv_rect.right_top_corner(),
+            ),
+            (
+                center + half_width - half_height,
+                uv_rect.right_bottom_corner(),
+            ),
+            (
+                center - half_width - half_height,
+                uv_rect.left_bottom_corner(),
+            ),
+        ]
+        .map(|(position, tex_coord)| SpriteVertex {
+            position,
+            tex_coord,
+            // Zero size disables billboarding in the sprite shader, the quad is already built in
+            // world space.
+            params: Vector2::default(),
+            color: *self.color,
+        });
+
+        let triangles = [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])];
+
+        let sort_index = ctx.calculate_sorting_index(center);
+
+        ctx.storage.push_triangles(
+            SpriteVertex::layout(),
+            &self.material,
+            RenderPath::Forward,
+            sort_index,
+            self.handle(),
+            &mut move |mut vertex_buffer, mut triangle_buffer| {
+                let start_vertex_index = vertex_buffer.vertex_count();
+
+                for vertex in vertices.iter() {
+                    vertex_buffer
+                        .push_vertex_raw(value_as_u8_slice(vertex))
+                        .unwrap();
+                }
+
+                triangle_buffer
+                    .push_triangles_iter_with_offset(start_vertex_index, triangles.into_iter());
+            },
+        );
+
+        // Descendant nodes are replaced by the impostor.
+        RdcControlFlow::Break
+    }
+}
+
+/// Allows you to create an impostor node in a declarative manner.
+pub struct ImpostorBuilder {
+    base_builder: BaseBuilder,
+    material: MaterialResource,
+    frames: u32,
+    size: f32,
+    center: Vector3<f32>,
+    distance: f32,
+    color: Color,
+}
+
+impl ImpostorBuilder {
+    /// Creates a new instance of the builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            material: material::STANDARD_SPRITE.resource(),
+            frames: 8,
+            size: 1.0,
+            center: Vector3::new(0.0, 0.5, 0.0),
+            distance: 50.0,
+            color: Color::WHITE,
+        }
+    }
+
+    /// Sets desired material of the impostor.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Sets desired amount of frames in the atlas of the impostor.
+    pub fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = frames.max(1);
+        self
+    }
+
+    /// Sets desired size of the impostor quad.
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets desired center of the impostor quad.
+    pub fn with_center(mut self, center: Vector3<f32>) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Sets desired switch distance.
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Sets desired color of the impostor.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Creates new impostor.
+    pub fn build_impostor(self) -> Impostor {
+        Impostor {
+            base: self.base_builder.build_base(),
+            material: self.material.into(),
+            frames: self.frames.into(),
+            size: self.size.into(),
+            center: self.center.into(),
+            distance: self.distance.into(),
+            color: self.color.into(),
+        }
+    }
+
+    /// Creates new impostor node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_impostor())
+    }
+
+    /// Creates new impostor node and puts it in the given graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, math::Rect},
+        scene::impostor::{atlas_columns, frame_index, frame_uv_rect},
+    };
+
+    #[test]
+    fn test_frame_selection() {
+        assert_eq!(frame_index(8, Vector3::new(0.0, 0.0, 1.0)), 0);
+        assert_eq!(frame_index(8, Vector3::new(1.0, 0.0, 0.0)), 2);
+        assert_eq!(frame_index(8, Vector3::new(0.0, 0.0, -1.0)), 4);
+        assert_eq!(frame_index(8, Vector3::new(-1.0, 0.0, 0.0)), 6);
+        assert_eq!(frame_index(8, Vector3::new(-1.0, 0.0, 10.0)), 0);
+        assert_eq!(frame_index(1, Vector3::new(1.0, 0.0, 0.0)), 0);
+    }
+
+    #[test]
+    fn test_atlas_layout() {
+        assert_eq!(atlas_columns(1), 1);
+        assert_eq!(atlas_columns(4), 2);
+        assert_eq!(atlas_columns(8), 3);
+        assert_eq!(atlas_columns(16), 4);
+
+        assert_eq!(frame_uv_rect(4, 0), Rect::new(0.0, 0.0, 0.5, 0.5));
+        assert_eq!(frame_uv_rect(4, 3), Rect::new(0.5, 0.5, 0.5, 0.5));
+        assert_eq!(frame_uv_rect(1, 5), Rect::new(0.0, 0.0, 1.0, 1.0));
+    }
+}
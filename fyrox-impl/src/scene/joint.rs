@@ -52,6 +52,93 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+/// Joint motor drives relative motion of two rigid bodies along (or around) an axis of a joint. It
+/// could be used to reach some target velocity (for example - to spin wheels of a vehicle), or a
+/// target position (for example - to open a door or to move a crane arm). Both modes could be
+/// combined. The motor is modelled as a spring: `stiffness` defines how strongly the motor pulls
+/// the bodies to the target position, and `damping` defines how strongly the motor pulls the bodies
+/// to the target velocity.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct JointMotor {
+    /// Whether the motor is enabled or not. Default is `false`.
+    #[reflect(description = "Whether the motor is enabled or not.")]
+    pub enabled: bool,
+
+    /// Target relative velocity of the bodies (in radians per second for angular axes and units per
+    /// second for linear axes).
+    #[reflect(
+        description = "Target relative velocity of the bodies (in radians per second for \
+    angular axes and units per second for linear axes)."
+    )]
+    pub target_velocity: f32,
+
+    /// Target relative position of the bodies (in radians for angular axes and units for linear axes).
+    #[reflect(
+        description = "Target relative position of the bodies (in radians for angular axes \
+    and units for linear axes)."
+    )]
+    pub target_position: f32,
+
+    /// Stiffness of the motor, defines how strongly the motor pulls the bodies to the target position.
+    #[reflect(
+        description = "Stiffness of the motor, defines how strongly the motor pulls the bodies to the target position.",
+        min_value = 0.0
+    )]
+    pub stiffness: f32,
+
+    /// Damping of the motor, defines how strongly the motor pulls the bodies to the target velocity.
+    #[reflect(
+        description = "Damping of the motor, defines how strongly the motor pulls the bodies to the target velocity.",
+        min_value = 0.0
+    )]
+    pub damping: f32,
+
+    /// Maximum force (or torque for angular axes) the motor can deliver.
+    #[reflect(
+        description = "Maximum force (or torque for angular axes) the motor can deliver.",
+        min_value = 0.0
+    )]
+    pub max_force: f32,
+}
+
+impl Default for JointMotor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_velocity: 0.0,
+            target_position: 0.0,
+            stiffness: 0.0,
+            damping: 1.0,
+            max_force: f32::MAX,
+        }
+    }
+}
+
+impl JointMotor {
+    /// Creates a new enabled motor, that tries to reach the given velocity. `factor` defines how
+    /// fast the velocity will be reached.
+    pub fn velocity(target_velocity: f32, factor: f32) -> Self {
+        Self {
+            enabled: true,
+            target_velocity,
+            damping: factor,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new enabled motor, that tries to reach the given position using a spring with the
+    /// given stiffness and damping.
+    pub fn position(target_position: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            enabled: true,
+            target_position,
+            stiffness,
+            damping,
+            ..Default::default()
+        }
+    }
+}
+
 /// Ball joint locks any translational moves between two objects on the axis between objects, but
 /// allows rigid bodies to perform relative rotations. The real world example is a human shoulder,
 /// pendulum, etc.
@@ -121,6 +208,13 @@ pub struct PrismaticJoint {
     )]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// The motor, that drives the attached bodies along local X axis of the joint.
+    #[reflect(
+        description = "The motor, that drives the attached bodies along local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for PrismaticJoint {
@@ -128,6 +222,7 @@ impl Default for PrismaticJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
@@ -148,6 +243,13 @@ pub struct RevoluteJoint {
     #[reflect(description = "Allowed angle range around local X axis of the joint (in radians).")]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// The motor, that rotates the attached bodies around local X axis of the joint.
+    #[reflect(
+        description = "The motor, that rotates the attached bodies around local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for RevoluteJoint {
@@ -155,10 +257,83 @@ impl Default for RevoluteJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
+        }
+    }
+}
+
+/// Parameters of a single degree of freedom of [`SixDofJoint`].
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct SixDofJointAxis {
+    /// Whether the relative motion along (or around) the axis is prohibited or not. Default is `false`.
+    #[reflect(
+        description = "Whether the relative motion along (or around) the axis is prohibited or not."
+    )]
+    pub locked: bool,
+
+    /// Whether the limits of the axis are enabled or not. Default is `false`.
+    #[reflect(description = "Whether the limits of the axis are enabled or not.")]
+    pub limits_enabled: bool,
+
+    /// Allowed relative position of the bodies along (or around) the axis (in radians for angular
+    /// axes and units for linear axes).
+    #[reflect(
+        description = "Allowed relative position of the bodies along (or around) the axis \
+        (in radians for angular axes and units for linear axes)."
+    )]
+    pub limits: Range<f32>,
+
+    /// The motor, that drives the attached bodies along (or around) the axis.
+    #[reflect(
+        description = "The motor, that drives the attached bodies along (or around) the axis."
+    )]
+    pub motor: JointMotor,
+}
+
+impl Default for SixDofJointAxis {
+    fn default() -> Self {
+        Self {
+            locked: false,
+            limits_enabled: false,
+            limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
 
+/// 6-DOF (six degrees of freedom) joint allows to configure every linear and angular degree of
+/// freedom of the relative motion of two rigid bodies individually. Every axis could be locked,
+/// limited or driven by a motor. It is the most flexible joint, that could be used when none of
+/// the other joints are suitable; for example to simulate vehicle suspension (a linear axis with
+/// limits and a spring-like motor combined with a free angular axis for a wheel). By default, all
+/// the axes are free.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect, Default)]
+pub struct SixDofJoint {
+    /// Translation along local X axis of the joint.
+    #[reflect(description = "Translation along local X axis of the joint.")]
+    pub linear_x: SixDofJointAxis,
+
+    /// Translation along local Y axis of the joint.
+    #[reflect(description = "Translation along local Y axis of the joint.")]
+    pub linear_y: SixDofJointAxis,
+
+    /// Translation along local Z axis of the joint.
+    #[reflect(description = "Translation along local Z axis of the joint.")]
+    pub linear_z: SixDofJointAxis,
+
+    /// Rotation around local X axis of the joint.
+    #[reflect(description = "Rotation around local X axis of the joint.")]
+    pub angular_x: SixDofJointAxis,
+
+    /// Rotation around local Y axis of the joint.
+    #[reflect(description = "Rotation around local Y axis of the joint.")]
+    pub angular_y: SixDofJointAxis,
+
+    /// Rotation around local Z axis of the joint.
+    #[reflect(description = "Rotation around local Z axis of the joint.")]
+    pub angular_z: SixDofJointAxis,
+}
+
 /// The exact kind of the joint.
 #[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, VariantNames)]
 pub enum JointParams {
@@ -170,6 +345,8 @@ pub enum JointParams {
     PrismaticJoint(PrismaticJoint),
     /// See [`RevoluteJoint`] for more info.
     RevoluteJoint(RevoluteJoint),
+    /// See [`SixDofJoint`] for more info.
+    SixDofJoint(SixDofJoint),
 }
 
 uuid_provider!(JointParams = "a3e09303-9de4-4123-9492-05e27f29aaa3");
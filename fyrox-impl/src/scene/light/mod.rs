@@ -41,12 +41,15 @@ use crate::{
         algebra::Vector3,
         color::Color,
         reflect::prelude::*,
+        uuid::{uuid, Uuid},
         variable::InheritableVariable,
         visitor::{Visit, VisitResult, Visitor},
+        TypeUuidProvider,
     },
     scene::base::{Base, BaseBuilder},
 };
 use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 pub mod directional;
 pub mod point;
@@ -64,6 +67,113 @@ pub const DEFAULT_SCATTER_G: f32 = 0.03;
 /// significant value and you'll clearly see light volume with such settings.
 pub const DEFAULT_SCATTER_B: f32 = 0.03;
 
+/// Defines how the edges of shadows are filtered.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "941df899-9aeb-4861-80ab-db7e0a6ae139")]
+#[repr(u32)]
+pub enum ShadowFilter {
+    /// Single fetch from a shadow map, produces hard aliased edges. This is the fastest option.
+    Hard = 0,
+    /// Percentage-closer filtering - averages a set of fetches around the fragment, which gives
+    /// smooth edges of constant width. Width of the edges is defined by
+    /// [`ShadowMapOptions::softness`].
+    #[default]
+    Pcf = 1,
+    /// Percentage-closer soft shadows - searches for occluders first and scales the PCF kernel
+    /// by the estimated distance between an occluder and a receiver. This mode gives
+    /// "contact-hardening" shadows, that are sharp near the occluders and blurry far from them.
+    /// The size of the light source is defined by [`ShadowMapOptions::softness`]. This is the
+    /// slowest option.
+    Pcss = 2,
+}
+
+/// Per-light shadow map options.
+#[derive(Reflect, Clone, Visit, PartialEq, Debug)]
+pub struct ShadowMapOptions {
+    /// Size of the shadow map of the light in texels. `None` means that the size will be taken from
+    /// the current quality settings of the renderer.
+    pub size: Option<usize>,
+
+    /// Normal bias moves a fragment along its normal before fetching the shadow map. It allows you
+    /// to remove "shadow acne" on surfaces, that are almost parallel to the light direction,
+    /// without introducing "peter panning" that a large shadow bias causes.
+    #[reflect(min_value = 0.0, step = 0.005)]
+    pub normal_bias: f32,
+
+    /// See [`ShadowFilter`].
+    pub filter: ShadowFilter,
+
+    /// Radius of PCF kernel in texels for [`ShadowFilter::Pcf`] or size of the light source for
+    /// [`ShadowFilter::Pcss`]. Larger values give softer shadows.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub softness: f32,
+}
+
+impl Default for ShadowMapOptions {
+    fn default() -> Self {
+        Self {
+            size: None,
+            normal_bias: 0.0,
+            filter: ShadowFilter::Pcf,
+            softness: 1.0,
+        }
+    }
+}
+
+/// Options of volumetric (light shafts) rendering of a light source. Unlike the default light
+/// scattering, that is calculated analytically, volumetric light is ray-marched through the light
+/// volume and takes shadows into account, which gives visible light shafts behind the occluders.
+/// Volumetric light is rendered in half resolution and then upsampled, so it is relatively cheap.
+/// Scattering must be enabled on the light (see [`BaseLight::enable_scatter`]) and in the quality
+/// settings of the renderer, otherwise these options have no effect.
+#[derive(Reflect, Clone, Visit, PartialEq, Debug)]
+pub struct VolumetricLightOptions {
+    /// Enables or disables ray-marched volumetric light. When disabled, the light uses analytical
+    /// scattering, that ignores shadows.
+    pub enabled: bool,
+
+    /// Density of the participating media (fog, dust, etc.). Higher values make the light shafts
+    /// brighter and more opaque.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub density: f32,
+
+    /// Anisotropy of the scattering in `[-1; 1]` range. Positive values scatter the light forward
+    /// (the light shafts are brighter when looking towards the light source), negative values
+    /// scatter it backward and zero scatters it uniformly in all directions.
+    #[reflect(min_value = -1.0, max_value = 1.0, step = 0.01)]
+    pub anisotropy: f32,
+
+    /// Amount of ray-marching steps per pixel. More steps give better quality at the cost of
+    /// performance.
+    #[reflect(min_value = 1.0, max_value = 256.0, step = 1.0)]
+    pub step_count: u32,
+}
+
+impl Default for VolumetricLightOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            density: 0.1,
+            anisotropy: 0.3,
+            step_count: 32,
+        }
+    }
+}
+
 /// Light scene node. It contains common properties of light such as color,
 /// scattering factor (per color channel) and other useful properties. Exact
 /// behavior defined by specific light kind.
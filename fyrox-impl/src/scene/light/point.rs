@@ -52,7 +52,7 @@ use crate::{
         base::Base,
         debug::SceneDrawingContext,
         graph::Graph,
-        light::{BaseLight, BaseLightBuilder},
+        light::{BaseLight, BaseLightBuilder, ShadowMapOptions, VolumetricLightOptions},
         node::{Node, NodeTrait},
     },
 };
@@ -71,6 +71,14 @@ pub struct PointLight {
     #[reflect(min_value = 0.0, step = 0.1)]
     #[reflect(setter = "set_radius")]
     radius: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_shadow_map_options")]
+    shadow_map_options: InheritableVariable<ShadowMapOptions>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_volumetric_options")]
+    volumetric_options: InheritableVariable<VolumetricLightOptions>,
 }
 
 impl Deref for PointLight {
@@ -127,6 +135,29 @@ impl PointLight {
     pub fn shadow_bias(&self) -> f32 {
         *self.shadow_bias
     }
+
+    /// Sets new shadow map options of the light. See [`ShadowMapOptions`] docs for more info.
+    pub fn set_shadow_map_options(&mut self, options: ShadowMapOptions) -> ShadowMapOptions {
+        self.shadow_map_options.set_value_and_mark_modified(options)
+    }
+
+    /// Returns current shadow map options of the light.
+    pub fn shadow_map_options(&self) -> &ShadowMapOptions {
+        &self.shadow_map_options
+    }
+
+    /// Sets new volumetric light options. See [`VolumetricLightOptions`] docs for more info.
+    pub fn set_volumetric_options(
+        &mut self,
+        options: VolumetricLightOptions,
+    ) -> VolumetricLightOptions {
+        self.volumetric_options.set_value_and_mark_modified(options)
+    }
+
+    /// Returns current volumetric light options.
+    pub fn volumetric_options(&self) -> &VolumetricLightOptions {
+        &self.volumetric_options
+    }
 }
 
 impl NodeTrait for PointLight {
@@ -157,6 +188,8 @@ impl Default for PointLight {
             base_light: Default::default(),
             shadow_bias: InheritableVariable::new_modified(0.025),
             radius: InheritableVariable::new_modified(10.0),
+            shadow_map_options: Default::default(),
+            volumetric_options: Default::default(),
         }
     }
 }
@@ -166,6 +199,8 @@ pub struct PointLightBuilder {
     base_light_builder: BaseLightBuilder,
     shadow_bias: f32,
     radius: f32,
+    shadow_map_options: ShadowMapOptions,
+    volumetric_options: VolumetricLightOptions,
 }
 
 impl PointLightBuilder {
@@ -175,6 +210,8 @@ impl PointLightBuilder {
             base_light_builder,
             shadow_bias: 0.025,
             radius: 10.0,
+            shadow_map_options: Default::default(),
+            volumetric_options: Default::default(),
         }
     }
 
@@ -190,12 +227,26 @@ impl PointLightBuilder {
         self
     }
 
+    /// Sets desired shadow map options.
+    pub fn with_shadow_map_options(mut self, options: ShadowMapOptions) -> Self {
+        self.shadow_map_options = options;
+        self
+    }
+
+    /// Sets desired volumetric light options.
+    pub fn with_volumetric_options(mut self, options: VolumetricLightOptions) -> Self {
+        self.volumetric_options = options;
+        self
+    }
+
     /// Builds new instance of point light.
     pub fn build_point_light(self) -> PointLight {
         PointLight {
             base_light: self.base_light_builder.build(),
             radius: self.radius.into(),
             shadow_bias: self.shadow_bias.into(),
+            shadow_map_options: self.shadow_map_options.into(),
+            volumetric_options: self.volumetric_options.into(),
         }
     }
 
@@ -59,7 +59,7 @@ use crate::{
         base::Base,
         debug::SceneDrawingContext,
         graph::Graph,
-        light::{BaseLight, BaseLightBuilder},
+        light::{BaseLight, BaseLightBuilder, ShadowMapOptions, VolumetricLightOptions},
         node::{Node, NodeTrait},
     },
 };
@@ -89,6 +89,14 @@ pub struct SpotLight {
 
     #[reflect(setter = "set_cookie_texture")]
     cookie_texture: InheritableVariable<Option<TextureResource>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_shadow_map_options")]
+    shadow_map_options: InheritableVariable<ShadowMapOptions>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_volumetric_options")]
+    volumetric_options: InheritableVariable<VolumetricLightOptions>,
 }
 
 impl Deref for SpotLight {
@@ -114,6 +122,8 @@ impl Default for SpotLight {
             shadow_bias: InheritableVariable::new_modified(0.00005),
             distance: InheritableVariable::new_modified(10.0),
             cookie_texture: InheritableVariable::new_modified(None),
+            shadow_map_options: Default::default(),
+            volumetric_options: Default::default(),
         }
     }
 }
@@ -213,6 +223,29 @@ impl SpotLight {
     pub fn cookie_texture_ref(&self) -> Option<&TextureResource> {
         self.cookie_texture.as_ref()
     }
+
+    /// Sets new shadow map options of the light. See [`ShadowMapOptions`] docs for more info.
+    pub fn set_shadow_map_options(&mut self, options: ShadowMapOptions) -> ShadowMapOptions {
+        self.shadow_map_options.set_value_and_mark_modified(options)
+    }
+
+    /// Returns current shadow map options of the light.
+    pub fn shadow_map_options(&self) -> &ShadowMapOptions {
+        &self.shadow_map_options
+    }
+
+    /// Sets new volumetric light options. See [`VolumetricLightOptions`] docs for more info.
+    pub fn set_volumetric_options(
+        &mut self,
+        options: VolumetricLightOptions,
+    ) -> VolumetricLightOptions {
+        self.volumetric_options.set_value_and_mark_modified(options)
+    }
+
+    /// Returns current volumetric light options.
+    pub fn volumetric_options(&self) -> &VolumetricLightOptions {
+        &self.volumetric_options
+    }
 }
 
 impl NodeTrait for SpotLight {
@@ -260,6 +293,8 @@ pub struct SpotLightBuilder {
     shadow_bias: f32,
     distance: f32,
     cookie_texture: Option<TextureResource>,
+    shadow_map_options: ShadowMapOptions,
+    volumetric_options: VolumetricLightOptions,
 }
 
 impl SpotLightBuilder {
@@ -272,6 +307,8 @@ impl SpotLightBuilder {
             shadow_bias: 0.00005,
             distance: 10.0,
             cookie_texture: None,
+            shadow_map_options: Default::default(),
+            volumetric_options: Default::default(),
         }
     }
 
@@ -305,6 +342,18 @@ impl SpotLightBuilder {
         self
     }
 
+    /// Sets desired shadow map options.
+    pub fn with_shadow_map_options(mut self, options: ShadowMapOptions) -> Self {
+        self.shadow_map_options = options;
+        self
+    }
+
+    /// Sets desired volumetric light options.
+    pub fn with_volumetric_options(mut self, options: VolumetricLightOptions) -> Self {
+        self.volumetric_options = options;
+        self
+    }
+
     /// Creates new spot light.
     pub fn build_spot_light(self) -> SpotLight {
         SpotLight {
@@ -314,6 +363,8 @@ impl SpotLightBuilder {
             shadow_bias: self.shadow_bias.into(),
             distance: self.distance.into(),
             cookie_texture: self.cookie_texture.into(),
+            shadow_map_options: self.shadow_map_options.into(),
+            volumetric_options: self.volumetric_options.into(),
         }
     }
 
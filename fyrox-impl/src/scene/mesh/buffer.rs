@@ -1196,6 +1196,13 @@ pub enum VertexFetchError {
     },
     /// IO error.
     Io(std::io::Error),
+    /// Trying to access a vertex with an index, that is out of bounds.
+    NoSuchVertex {
+        /// Index of the vertex.
+        index: usize,
+        /// Total amount of vertices in the buffer.
+        count: usize,
+    },
 }
 
 impl std::error::Error for VertexFetchError {}
@@ -1212,6 +1219,9 @@ impl Display for VertexFetchError {
             VertexFetchError::SizeMismatch { expected, actual } => {
                 write!(f, "Size mismatch. Expected {expected}, got {actual}")
             }
+            VertexFetchError::NoSuchVertex { index, count } => {
+                write!(f, "No vertex with index {index}. Vertex count is {count}")
+            }
         }
     }
 }
@@ -26,7 +26,7 @@ use crate::{
     core::{
         algebra::{Matrix4, Point3, Vector3, Vector4},
         color::Color,
-        math::aabb::AxisAlignedBoundingBox,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
         parking_lot::Mutex,
         pool::Handle,
         reflect::prelude::*,
@@ -36,7 +36,7 @@ use crate::{
         TypeUuidProvider,
     },
     graph::{BaseSceneGraph, SceneGraph},
-    material::MaterialResource,
+    material::{shader::ShaderDefinition, MaterialPropertyBlock, MaterialResource},
     renderer::{
         self,
         bundle::{RenderContext, RenderDataBundleStorageTrait, SurfaceInstanceData},
@@ -44,7 +44,7 @@ use crate::{
     },
     scene::{
         base::{Base, BaseBuilder},
-        debug::{Line, SceneDrawingContext},
+        debug::{Line, SceneDrawingContext, SkeletonDrawingOptions},
         graph::Graph,
         mesh::{
             buffer::{
@@ -63,6 +63,7 @@ use std::{
     cell::Cell,
     hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
@@ -120,6 +121,24 @@ fn transform_vertex(mut vertex: VertexViewMut, world: &Matrix4<f32>) {
     }
 }
 
+fn skin_vertex(vertex: VertexViewMut, bone_matrices: &[Matrix4<f32>]) {
+    let (Ok(indices), Ok(weights)) = (
+        vertex.read_4_u8(VertexAttributeUsage::BoneIndices),
+        vertex.read_4_f32(VertexAttributeUsage::BoneWeight),
+    ) else {
+        return;
+    };
+
+    let mut transform = Matrix4::zeros();
+    for (index, weight) in indices.iter().zip(weights.iter()) {
+        if let Some(bone_matrix) = bone_matrices.get(*index as usize) {
+            transform += bone_matrix.scale(*weight);
+        }
+    }
+
+    transform_vertex(vertex, &transform);
+}
+
 /// Batching mode defines how the mesh data will be grouped before rendering.
 #[derive(
     Default,
@@ -178,6 +197,7 @@ impl BatchContainer {
                 storage: self,
                 graph: ctx.graph,
                 render_pass_name: ctx.render_pass_name,
+                use_gpu_skinning: ctx.use_gpu_skinning,
             });
         }
     }
@@ -338,6 +358,12 @@ pub struct Mesh {
     #[visit(optional)]
     blend_shapes: InheritableVariable<Vec<BlendShape>>,
 
+    // Per-instance overrides of material properties and textures of every surface of the mesh. It
+    // is shared with the renderer, so it won't be copied every frame.
+    #[visit(optional)]
+    #[reflect(hidden)]
+    property_block: InheritableVariable<Arc<MaterialPropertyBlock>>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     local_bounding_box: Cell<AxisAlignedBoundingBox>,
@@ -367,6 +393,7 @@ impl Default for Mesh {
             batching_mode: Default::default(),
             blend_shapes_property_name: Mesh::DEFAULT_BLEND_SHAPES_PROPERTY_NAME.to_string(),
             blend_shapes: Default::default(),
+            property_block: Default::default(),
             batch_container: Default::default(),
         }
     }
@@ -414,6 +441,23 @@ impl Mesh {
         self.surfaces.get_value_mut_silent()
     }
 
+    /// Searches for a surface with the given material slot name (see [`Surface::name`]). Meshes imported
+    /// from 3D models have one surface per material, and the name of the slot is the name of the material.
+    #[inline]
+    pub fn surface_by_name(&self, name: &str) -> Option<&Surface> {
+        self.surfaces.iter().find(|surface| surface.name() == name)
+    }
+
+    /// Searches for a surface with the given material slot name (see [`Surface::name`]) and returns a
+    /// mutable reference to it. Use [`Surface::override_material`] to change the material of the slot
+    /// for this mesh only, without affecting other instances of the same model.
+    #[inline]
+    pub fn surface_by_name_mut(&mut self, name: &str) -> Option<&mut Surface> {
+        self.surfaces_mut()
+            .iter_mut()
+            .find(|surface| surface.name() == name)
+    }
+
     /// Removes all surfaces from mesh.
     #[inline]
     pub fn clear_surfaces(&mut self) {
@@ -440,6 +484,36 @@ impl Mesh {
         self.blend_shapes.get_value_mut_and_mark_modified()
     }
 
+    fn instance_property_block(&self) -> Option<Arc<MaterialPropertyBlock>> {
+        if self.property_block.is_empty() {
+            None
+        } else {
+            Some((*self.property_block).clone())
+        }
+    }
+
+    /// Returns a reference to the material property block of the mesh. See [`MaterialPropertyBlock`] docs
+    /// for more info.
+    pub fn property_block(&self) -> &MaterialPropertyBlock {
+        &self.property_block
+    }
+
+    /// Returns a reference to the material property block of the mesh, that could be used to override
+    /// material properties and textures of every surface of this mesh, without affecting other meshes
+    /// that use the same materials.
+    pub fn property_block_mut(&mut self) -> &mut MaterialPropertyBlock {
+        Arc::make_mut(self.property_block.get_value_mut_and_mark_modified())
+    }
+
+    /// Sets new material property block for the mesh. See [`MaterialPropertyBlock`] docs for more info.
+    /// Keep in mind, that meshes with non-empty property block cannot be batched dynamically.
+    pub fn set_property_block(&mut self, block: MaterialPropertyBlock) -> MaterialPropertyBlock {
+        let old = self
+            .property_block
+            .set_value_and_mark_modified(Arc::new(block));
+        Arc::try_unwrap(old).unwrap_or_else(|old| (*old).clone())
+    }
+
     /// Sets new render path for the mesh.
     pub fn set_render_path(&mut self, render_path: RenderPath) -> RenderPath {
         self.render_path.set_value_and_mark_modified(render_path)
@@ -508,6 +582,85 @@ impl Mesh {
         bounding_box
     }
 
+    /// Returns a list of unique bones, that affect any of the surfaces of the mesh. The order of
+    /// the bones is the same as they appear in the surfaces.
+    pub fn bones(&self) -> Vec<Handle<Node>> {
+        let mut bones = Vec::new();
+        for surface in self.surfaces.iter() {
+            for &bone in surface.bones() {
+                if !bones.contains(&bone) {
+                    bones.push(bone);
+                }
+            }
+        }
+        bones
+    }
+
+    /// Draws the skeleton of the mesh (if any) using the given drawing context. Every bone is drawn
+    /// as a sphere at the position of the bone and a line that connects it with its parent bone.
+    /// Bind pose of the skeleton is drawn as well, if [`SkeletonDrawingOptions::draw_bind_pose`] is
+    /// set. Bind pose is defined in the local space of the mesh, so it follows the mesh when it is
+    /// moved. The method does nothing for meshes without skin.
+    ///
+    /// This method is meant to be used for debugging purposes, for example to diagnose issues with
+    /// animation retargeting or vertex weights. It is not called automatically, so it could be
+    /// switched on and off at any time.
+    pub fn debug_draw_skeleton(
+        &self,
+        graph: &Graph,
+        ctx: &mut SceneDrawingContext,
+        options: &SkeletonDrawingOptions,
+    ) {
+        let mesh_transform = self.global_transform();
+        let bind_pose_position = |node: &Node| -> Vector3<f32> {
+            (mesh_transform
+                * node
+                    .inv_bind_pose_transform()
+                    .try_inverse()
+                    .unwrap_or_else(Matrix4::identity))
+            .position()
+        };
+
+        let bones = self.bones();
+
+        for &bone in bones.iter() {
+            let Some(bone_node) = graph.try_get(bone) else {
+                continue;
+            };
+
+            let parent = bone_node.parent();
+            let parent_node = if bones.contains(&parent) {
+                graph.try_get(parent)
+            } else {
+                None
+            };
+
+            if options.draw_bones {
+                let position = bone_node.global_position();
+                ctx.draw_wire_sphere(position, options.joint_radius, 8, options.bone_color);
+                if let Some(parent_node) = parent_node {
+                    ctx.add_line(Line {
+                        begin: parent_node.global_position(),
+                        end: position,
+                        color: options.bone_color,
+                    });
+                }
+            }
+
+            if options.draw_bind_pose {
+                let position = bind_pose_position(bone_node);
+                ctx.draw_wire_sphere(position, options.joint_radius, 8, options.bind_pose_color);
+                if let Some(parent_node) = parent_node {
+                    ctx.add_line(Line {
+                        begin: bind_pose_position(parent_node),
+                        end: position,
+                        color: options.bind_pose_color,
+                    });
+                }
+            }
+        }
+    }
+
     /// Enable or disable dynamic batching. It could be useful to reduce amount of draw calls per
     /// frame if you have lots of meshes with small vertex count. Does not work with meshes, that
     /// have skin or blend shapes. Such meshes will be drawn in a separate draw call.
@@ -578,11 +731,9 @@ impl NodeTrait for Mesh {
         Self::type_uuid()
     }
 
-    fn sync_transform(&self, _new_global_transform: &Matrix4<f32>, context: &mut SyncContext) {
+    fn sync_transform(&self, new_global_transform: &Matrix4<f32>, context: &mut SyncContext) {
         if self.surfaces.iter().any(|s| !s.bones.is_empty()) {
-            let mut world_aabb = self
-                .local_bounding_box()
-                .transform(&self.global_transform());
+            let mut world_aabb = self.local_bounding_box().transform(new_global_transform);
 
             // Special case for skinned meshes.
             for surface in self.surfaces.iter() {
@@ -595,10 +746,8 @@ impl NodeTrait for Mesh {
 
             self.world_bounding_box.set(world_aabb)
         } else {
-            self.world_bounding_box.set(
-                self.local_bounding_box()
-                    .transform(&self.global_transform()),
-            );
+            self.world_bounding_box
+                .set(self.local_bounding_box().transform(new_global_transform));
         }
     }
 
@@ -630,6 +779,8 @@ impl NodeTrait for Mesh {
                         blend_shapes_weights: Default::default(),
                         element_range: ElementRange::Full,
                         node_handle: self.handle(),
+                        property_block: self.instance_property_block(),
+                        color: Color::WHITE,
                     },
                 );
             }
@@ -639,6 +790,56 @@ impl NodeTrait for Mesh {
             for surface in self.surfaces().iter() {
                 let is_skinned = !surface.bones.is_empty();
 
+                let bone_matrices = surface
+                    .bones
+                    .iter()
+                    .map(|bone_handle| {
+                        if let Some(bone_node) = ctx.graph.try_get(*bone_handle) {
+                            bone_node.global_transform() * bone_node.inv_bind_pose_transform()
+                        } else {
+                            Matrix4::identity()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                // Fallback to CPU skinning if the GPU skinning is disabled or there are too many
+                // bones to fit in the bone matrices buffer. Blend shapes are ignored in this case.
+                if is_skinned
+                    && (!ctx.use_gpu_skinning
+                        || bone_matrices.len() > ShaderDefinition::MAX_BONE_MATRICES)
+                {
+                    let surface_data_guard = surface.data_ref().data_ref();
+
+                    ctx.storage.push_triangles(
+                        &surface_data_guard
+                            .vertex_buffer
+                            .layout_descriptor()
+                            .collect::<Vec<_>>(),
+                        surface.material(),
+                        *self.render_path,
+                        0,
+                        self.handle(),
+                        &mut |mut vertex_buffer, mut triangle_buffer| {
+                            let start_vertex_index = vertex_buffer.vertex_count();
+
+                            for vertex in surface_data_guard.vertex_buffer.iter() {
+                                vertex_buffer
+                                    .push_vertex_raw(&vertex.transform(&mut |vertex| {
+                                        skin_vertex(vertex, &bone_matrices)
+                                    }))
+                                    .unwrap();
+                            }
+
+                            triangle_buffer.push_triangles_with_offset(
+                                start_vertex_index,
+                                surface_data_guard.geometry_buffer.triangles_ref(),
+                            )
+                        },
+                    );
+
+                    continue;
+                }
+
                 let world = if is_skinned {
                     Matrix4::identity()
                 } else {
@@ -651,6 +852,7 @@ impl NodeTrait for Mesh {
                     BatchingMode::Dynamic => {
                         let surface_data_guard = surface.data_ref().data_ref();
                         if self.blend_shapes().is_empty()
+                            && self.property_block.is_empty()
                             && surface.bones().is_empty()
                             && surface_data_guard.vertex_buffer.vertex_count() < 256
                         {
@@ -687,18 +889,7 @@ impl NodeTrait for Mesh {
                             surface.material().key(),
                             SurfaceInstanceData {
                                 world_transform: world,
-                                bone_matrices: surface
-                                    .bones
-                                    .iter()
-                                    .map(|bone_handle| {
-                                        if let Some(bone_node) = ctx.graph.try_get(*bone_handle) {
-                                            bone_node.global_transform()
-                                                * bone_node.inv_bind_pose_transform()
-                                        } else {
-                                            Matrix4::identity()
-                                        }
-                                    })
-                                    .collect::<Vec<_>>(),
+                                bone_matrices,
                                 blend_shapes_weights: self
                                     .blend_shapes()
                                     .iter()
@@ -706,6 +897,8 @@ impl NodeTrait for Mesh {
                                     .collect(),
                                 element_range: ElementRange::Full,
                                 node_handle: self.handle(),
+                                property_block: self.instance_property_block(),
+                                color: Color::WHITE,
                             },
                         );
                     }
@@ -809,6 +1002,7 @@ pub struct MeshBuilder {
     blend_shapes: Vec<BlendShape>,
     batching_mode: BatchingMode,
     blend_shapes_property_name: String,
+    property_block: MaterialPropertyBlock,
 }
 
 impl MeshBuilder {
@@ -821,6 +1015,7 @@ impl MeshBuilder {
             blend_shapes: Default::default(),
             batching_mode: BatchingMode::None,
             blend_shapes_property_name: Mesh::DEFAULT_BLEND_SHAPES_PROPERTY_NAME.to_string(),
+            property_block: Default::default(),
         }
     }
 
@@ -856,6 +1051,12 @@ impl MeshBuilder {
         self
     }
 
+    /// Sets the desired material property block. See [`MaterialPropertyBlock`] docs for more info.
+    pub fn with_property_block(mut self, block: MaterialPropertyBlock) -> Self {
+        self.property_block = block;
+        self
+    }
+
     /// Creates new mesh.
     pub fn build_node(self) -> Node {
         Node::new(Mesh {
@@ -869,6 +1070,7 @@ impl MeshBuilder {
             batching_mode: self.batching_mode.into(),
             batch_container: Default::default(),
             blend_shapes_property_name: self.blend_shapes_property_name,
+            property_block: Arc::new(self.property_block).into(),
         })
     }
 
@@ -877,3 +1079,163 @@ impl MeshBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::{Matrix4, Vector2, Vector3, Vector4},
+        scene::{
+            base::BaseBuilder,
+            debug::{SceneDrawingContext, SkeletonDrawingOptions},
+            graph::Graph,
+            mesh::{
+                buffer::{VertexAttributeUsage, VertexBuffer, VertexReadTrait},
+                skin_vertex,
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                vertex::AnimatedVertex,
+                MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_debug_draw_skeleton() {
+        let mut graph = Graph::new();
+
+        let child_bone = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                        .build(),
+                )
+                .with_inv_bind_pose_transform(Matrix4::new_translation(&Vector3::new(
+                    0.0, -2.0, 0.0,
+                ))),
+        )
+        .build(&mut graph);
+        let root_bone =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[child_bone])).build(&mut graph);
+
+        let mesh = MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .with_bones(vec![root_bone, child_bone, root_bone])
+        .build()])
+        .build(&mut graph);
+
+        graph.update_hierarchical_data();
+
+        let mesh = graph[mesh].as_mesh();
+        assert_eq!(mesh.bones(), vec![root_bone, child_bone]);
+
+        let is_bone_line = |ctx: &SceneDrawingContext, begin: Vector3<f32>, end: Vector3<f32>| {
+            ctx.lines
+                .iter()
+                .any(|line| line.begin == begin && line.end == end)
+        };
+
+        // Bones are drawn at their actual positions in the world.
+        let mut ctx = SceneDrawingContext::default();
+        mesh.debug_draw_skeleton(&graph, &mut ctx, &SkeletonDrawingOptions::default());
+        assert!(is_bone_line(
+            &ctx,
+            Vector3::default(),
+            Vector3::new(0.0, 1.0, 0.0)
+        ));
+        assert!(!is_bone_line(
+            &ctx,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 2.0, 0.0)
+        ));
+
+        let mut ctx = SceneDrawingContext::default();
+        mesh.debug_draw_skeleton(
+            &graph,
+            &mut ctx,
+            &SkeletonDrawingOptions {
+                draw_bones: false,
+                draw_bind_pose: true,
+                ..Default::default()
+            },
+        );
+        // Bind pose is relative to the mesh.
+        assert!(!is_bone_line(
+            &ctx,
+            Vector3::default(),
+            Vector3::new(0.0, 1.0, 0.0)
+        ));
+        assert!(is_bone_line(
+            &ctx,
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 2.0, 0.0)
+        ));
+
+        let mut ctx = SceneDrawingContext::default();
+        let not_skinned = MeshBuilder::new(BaseBuilder::new()).build_node();
+        not_skinned.as_mesh().debug_draw_skeleton(
+            &graph,
+            &mut ctx,
+            &SkeletonDrawingOptions::default(),
+        );
+        assert!(ctx.lines.is_empty());
+    }
+
+    #[test]
+    fn test_cpu_skinning() {
+        let vertex_buffer = VertexBuffer::new(
+            1,
+            vec![AnimatedVertex {
+                position: Vector3::new(1.0, 0.0, 0.0),
+                tex_coord: Vector2::default(),
+                normal: Vector3::new(0.0, 1.0, 0.0),
+                tangent: Vector4::new(1.0, 0.0, 0.0, -1.0),
+                bone_weights: [0.25, 0.75, 0.0, 0.0],
+                bone_indices: [0, 1, 0, 0],
+            }],
+        )
+        .unwrap();
+
+        let bone_matrices = [
+            Matrix4::new_translation(&Vector3::new(4.0, 0.0, 0.0)),
+            Matrix4::new_translation(&Vector3::new(0.0, 4.0, 0.0)),
+        ];
+
+        let vertex = vertex_buffer.get(0).unwrap();
+        let bytes = vertex.transform(&mut |vertex| skin_vertex(vertex, &bone_matrices));
+
+        let mut skinned = vertex_buffer.clone();
+        skinned.modify().push_vertex_raw(&bytes).unwrap();
+        let skinned_vertex = skinned.get(1).unwrap();
+        assert_eq!(
+            skinned_vertex
+                .read_3_f32(VertexAttributeUsage::Position)
+                .unwrap(),
+            Vector3::new(2.0, 3.0, 0.0)
+        );
+        // Translation must not affect directions.
+        assert_eq!(
+            skinned_vertex
+                .read_3_f32(VertexAttributeUsage::Normal)
+                .unwrap(),
+            Vector3::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            skinned_vertex
+                .read_4_f32(VertexAttributeUsage::Tangent)
+                .unwrap(),
+            Vector4::new(1.0, 0.0, 0.0, -1.0)
+        );
+    }
+}
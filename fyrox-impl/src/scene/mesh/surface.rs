@@ -1062,6 +1062,120 @@ impl SurfaceData {
         self.geometry_buffer.modify().clear();
         self.vertex_buffer.modify().clear();
     }
+
+    /// Returns `true` if the vertices of the surface have bone indices and bone weights, `false` -
+    /// otherwise.
+    pub fn is_skinned(&self) -> bool {
+        self.vertex_buffer
+            .has_attribute(VertexAttributeUsage::BoneIndices)
+            && self
+                .vertex_buffer
+                .has_attribute(VertexAttributeUsage::BoneWeight)
+    }
+
+    /// Returns bone influences of a vertex with the given index. Bone indices point to the bones
+    /// of a [`Surface`] (see [`Surface::bones`]).
+    pub fn bone_influences(&self, vertex: usize) -> Result<BoneInfluences, VertexFetchError> {
+        let view = self
+            .vertex_buffer
+            .get(vertex)
+            .ok_or(VertexFetchError::NoSuchVertex {
+                index: vertex,
+                count: self.vertex_buffer.vertex_count() as usize,
+            })?;
+        Ok(BoneInfluences {
+            indices: view.read_4_u8(VertexAttributeUsage::BoneIndices)?.into(),
+            weights: view.read_4_f32(VertexAttributeUsage::BoneWeight)?.into(),
+        })
+    }
+
+    /// Sets bone influences of a vertex with the given index. The changes will be uploaded to GPU
+    /// automatically.
+    pub fn set_bone_influences(
+        &mut self,
+        vertex: usize,
+        influences: BoneInfluences,
+    ) -> Result<(), VertexFetchError> {
+        let count = self.vertex_buffer.vertex_count() as usize;
+        let mut vertex_buffer = self.vertex_buffer.modify();
+        let mut view = vertex_buffer
+            .get_mut(vertex)
+            .ok_or(VertexFetchError::NoSuchVertex {
+                index: vertex,
+                count,
+            })?;
+        view.write_4_u8(VertexAttributeUsage::BoneIndices, influences.indices.into())?;
+        view.write_4_f32(VertexAttributeUsage::BoneWeight, influences.weights.into())
+    }
+
+    /// Modifies bone influences of every vertex of the surface using the given function. The
+    /// function receives an index of a vertex and its bone influences. The changes will be uploaded
+    /// to GPU automatically. This method could be used to fix weighting of a mesh at runtime or to
+    /// create gameplay effects like detachable limbs (see [`BoneInfluences::replace_bone`]).
+    pub fn modify_bone_influences<F>(&mut self, mut func: F) -> Result<(), VertexFetchError>
+    where
+        F: FnMut(usize, &mut BoneInfluences),
+    {
+        if !self.is_skinned() {
+            return Err(VertexFetchError::NoSuchAttribute(
+                VertexAttributeUsage::BoneIndices,
+            ));
+        }
+
+        for (index, mut view) in self.vertex_buffer.modify().iter_mut().enumerate() {
+            let mut influences = BoneInfluences {
+                indices: view.read_4_u8(VertexAttributeUsage::BoneIndices)?.into(),
+                weights: view.read_4_f32(VertexAttributeUsage::BoneWeight)?.into(),
+            };
+            func(index, &mut influences);
+            view.write_4_u8(VertexAttributeUsage::BoneIndices, influences.indices.into())?;
+            view.write_4_f32(VertexAttributeUsage::BoneWeight, influences.weights.into())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A set of up to four bones, that affect a single vertex of a skinned surface. Every bone index
+/// points to a bone in the [`Surface::bones`] array, a weight defines how much the bone affects the
+/// vertex. Unused influences must have zero weight.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct BoneInfluences {
+    /// Indices of the bones in the [`Surface::bones`] array.
+    pub indices: [u8; 4],
+    /// Weights of the bones. Sum of the weights should be equal to one.
+    pub weights: [f32; 4],
+}
+
+impl BoneInfluences {
+    /// Returns total weight of the given bone.
+    pub fn weight(&self, bone_index: u8) -> f32 {
+        self.indices
+            .iter()
+            .zip(self.weights.iter())
+            .filter_map(|(index, weight)| (*index == bone_index).then_some(*weight))
+            .sum()
+    }
+
+    /// Makes the sum of the weights equal to one. Does nothing if every weight is zero.
+    pub fn normalize(&mut self) {
+        let sum = self.weights.iter().sum::<f32>();
+        if sum >= f32::EPSILON {
+            for weight in self.weights.iter_mut() {
+                *weight /= sum;
+            }
+        }
+    }
+
+    /// Transfers the influence of a bone to another bone. It could be used, for example, to detach
+    /// a limb: the vertices of the limb will follow the parent bone instead.
+    pub fn replace_bone(&mut self, bone_index: u8, new_bone_index: u8) {
+        for index in self.indices.iter_mut() {
+            if *index == bone_index {
+                *index = new_bone_index;
+            }
+        }
+    }
 }
 
 impl Visit for SurfaceData {
@@ -1252,6 +1366,13 @@ impl SurfaceResourceExtension for SurfaceResource {
 /// methods.
 #[derive(Debug, Reflect, PartialEq)]
 pub struct Surface {
+    #[reflect(
+        setter = "set_name",
+        description = "Name of the material slot of the surface. Usually it is the name of the material \
+        assigned to the surface in a 3D modelling software."
+    )]
+    name: InheritableVariable<String>,
+
     pub(crate) data: InheritableVariable<SurfaceResource>,
 
     pub(crate) material: InheritableVariable<MaterialResource>,
@@ -1266,6 +1387,10 @@ pub struct Surface {
     )]
     unique_material: InheritableVariable<bool>,
 
+    // Whether the current material is a copy owned by this surface, see `Surface::override_material`.
+    #[reflect(hidden)]
+    material_override: bool,
+
     // Temporal array for FBX conversion needs, it holds skinning data (weight + bone handle)
     // and will be used to fill actual bone indices and weight in vertices that will be
     // sent to GPU. The idea is very simple: GPU needs to know only indices of matrices of
@@ -1281,6 +1406,7 @@ uuid_provider!(Surface = "485caf12-4e7d-4b1a-b6bd-0681fd92f789");
 impl Clone for Surface {
     fn clone(&self) -> Self {
         Self {
+            name: self.name.clone(),
             data: self.data.clone(),
             material: if *self.unique_material {
                 // Create unique instance.
@@ -1291,6 +1417,8 @@ impl Clone for Surface {
             },
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
+            // The material copy is shared with the clone, so the clone must make its own copy.
+            material_override: false,
             vertex_weights: self.vertex_weights.clone(),
         }
     }
@@ -1314,6 +1442,10 @@ impl Visit for Surface {
         self.data.visit("Data", &mut region)?;
         self.bones.visit("Bones", &mut region)?;
         let _ = self.unique_material.visit("UniqueMaterial", &mut region); // Backward compatibility.
+        let _ = self.name.visit("Name", &mut region); // Backward compatibility.
+        let _ = self
+            .material_override
+            .visit("MaterialOverride", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -1322,6 +1454,7 @@ impl Visit for Surface {
 impl Default for Surface {
     fn default() -> Self {
         Self {
+            name: Default::default(),
             data: SurfaceResource::new_ok(
                 ResourceKind::Embedded,
                 SurfaceData::make_cube(Matrix4::identity()),
@@ -1331,6 +1464,7 @@ impl Default for Surface {
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            material_override: false,
         }
     }
 }
@@ -1345,6 +1479,18 @@ impl Surface {
         }
     }
 
+    /// Returns name of the material slot of the surface. See [`Mesh::surface_by_name`](super::Mesh::surface_by_name)
+    /// for more info.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets new name of the material slot of the surface.
+    pub fn set_name(&mut self, name: String) -> String {
+        self.name.set_value_and_mark_modified(name)
+    }
+
     /// Calculates material id.
     pub fn material_id(&self) -> u64 {
         self.material.key()
@@ -1378,6 +1524,38 @@ impl Surface {
     /// Sets new material for the surface.
     pub fn set_material(&mut self, material: MaterialResource) {
         self.material.set_value_and_mark_modified(material);
+        self.material_override = false;
+    }
+
+    /// Returns `true` if the surface has its own copy of the material, made by [`Self::override_material`].
+    pub fn has_material_override(&self) -> bool {
+        self.material_override
+    }
+
+    /// Returns a material that belongs only to this surface instance, so it could be changed without
+    /// affecting any other instance that shares the same material (for example - every instance of a
+    /// model). On the first call, the current material is copied and the copy is assigned to the surface,
+    /// subsequent calls return the same copy. It is useful to make small per-instance changes, such as
+    /// team colors of characters that were created from a single model.
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{core::color::Color, scene::mesh::Mesh};
+    /// fn paint_team_color(mesh: &mut Mesh, color: Color) {
+    ///     if let Some(surface) = mesh.surface_by_name_mut("TeamColor") {
+    ///         surface
+    ///             .override_material()
+    ///             .data_ref()
+    ///             .set_property("diffuseColor", color);
+    ///     }
+    /// }
+    /// ```
+    pub fn override_material(&mut self) -> &MaterialResource {
+        if !self.has_material_override() {
+            let copy = self.material.deep_copy_as_embedded();
+            self.material.set_value_and_mark_modified(copy);
+            self.material_override = true;
+        }
+        &self.material
     }
 
     /// Returns list of bones that affects the surface.
@@ -1386,6 +1564,12 @@ impl Surface {
         &self.bones
     }
 
+    /// Sets new list of bones that affects the surface. Bone indices of the vertices (see
+    /// [`SurfaceData::bone_influences`]) point to this list, so make sure they are valid.
+    pub fn set_bones(&mut self, bones: Vec<Handle<Node>>) -> Vec<Handle<Node>> {
+        self.bones.set_value_and_mark_modified(bones)
+    }
+
     /// Returns true if the material will be a unique instance when cloning the surface.
     pub fn is_unique_material(&self) -> bool {
         *self.unique_material
@@ -1399,6 +1583,7 @@ impl Surface {
 
 /// Surface builder allows you to create surfaces in declarative manner.
 pub struct SurfaceBuilder {
+    name: String,
     data: SurfaceResource,
     material: Option<MaterialResource>,
     bones: Vec<Handle<Node>>,
@@ -1409,6 +1594,7 @@ impl SurfaceBuilder {
     /// Creates new builder instance with given data and no textures or bones.
     pub fn new(data: SurfaceResource) -> Self {
         Self {
+            name: Default::default(),
             data,
             material: None,
             bones: Default::default(),
@@ -1416,6 +1602,12 @@ impl SurfaceBuilder {
         }
     }
 
+    /// Sets desired name of the material slot of the surface.
+    pub fn with_name<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.name = name.as_ref().to_owned();
+        self
+    }
+
     /// Sets desired diffuse texture.
     pub fn with_material(mut self, material: MaterialResource) -> Self {
         self.material = Some(material);
@@ -1437,6 +1629,7 @@ impl SurfaceBuilder {
     /// Creates new instance of surface.
     pub fn build(self) -> Surface {
         Surface {
+            name: self.name.into(),
             data: self.data.into(),
             material: self
                 .material
@@ -1447,6 +1640,7 @@ impl SurfaceBuilder {
             vertex_weights: Default::default(),
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
+            material_override: false,
         }
     }
 }
@@ -1527,3 +1721,122 @@ lazy_static! {
         )
     );
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::{algebra::Matrix4, color::Color},
+        material::{Material, MaterialProperty, MaterialResource},
+        scene::mesh::{
+            buffer::{VertexAttributeUsage, VertexBuffer, VertexFetchError},
+            surface::{BoneInfluences, SurfaceBuilder, SurfaceData, SurfaceResource},
+            vertex::{AnimatedVertex, StaticVertex},
+        },
+    };
+
+    #[test]
+    fn test_material_override() {
+        let material = MaterialResource::new_ok(ResourceKind::Embedded, Material::standard());
+        let data = SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::make_cube(Matrix4::identity()),
+        );
+        let a = SurfaceBuilder::new(data)
+            .with_name("Body")
+            .with_material(material.clone())
+            .build();
+        let mut b = a.clone();
+        assert_eq!(b.name(), "Body");
+        assert!(!b.has_material_override());
+
+        b.override_material()
+            .data_ref()
+            .set_property("diffuseColor", Color::RED);
+        assert!(b.has_material_override());
+        assert_ne!(b.material().key(), material.key());
+        assert_eq!(a.material().key(), material.key());
+
+        // Subsequent calls must not create new copies.
+        let key = b.material().key();
+        assert_eq!(b.override_material().key(), key);
+
+        // Clones share the copy, so they must not modify it in place.
+        let mut c = b.clone();
+        assert!(!c.has_material_override());
+        assert_ne!(c.override_material().key(), key);
+
+        assert!(matches!(
+            b.material().data_ref().property_ref("diffuseColor"),
+            Some(MaterialProperty::Color(color)) if *color == Color::RED
+        ));
+        assert!(!matches!(
+            material.data_ref().property_ref("diffuseColor"),
+            Some(MaterialProperty::Color(color)) if *color == Color::RED
+        ));
+    }
+
+    #[test]
+    fn test_bone_influences() {
+        let vertices = vec![
+            AnimatedVertex {
+                bone_indices: [0, 1, 0, 0],
+                bone_weights: [0.5, 0.5, 0.0, 0.0],
+                ..Default::default()
+            };
+            3
+        ];
+        let mut data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            Default::default(),
+        );
+        assert!(data.is_skinned());
+
+        let influences = data.bone_influences(1).unwrap();
+        assert_eq!(influences.weight(1), 0.5);
+        assert_eq!(influences.weight(2), 0.0);
+        assert!(matches!(
+            data.bone_influences(3),
+            Err(VertexFetchError::NoSuchVertex { index: 3, count: 3 })
+        ));
+
+        let hash = data.vertex_buffer.content_hash();
+        data.set_bone_influences(
+            2,
+            BoneInfluences {
+                indices: [2, 0, 0, 0],
+                weights: [1.0, 0.0, 0.0, 0.0],
+            },
+        )
+        .unwrap();
+        assert_ne!(data.vertex_buffer.content_hash(), hash);
+        assert_eq!(data.bone_influences(2).unwrap().weight(2), 1.0);
+
+        // Detach the second bone.
+        data.modify_bone_influences(|_, influences| influences.replace_bone(1, 0))
+            .unwrap();
+        assert_eq!(data.bone_influences(0).unwrap().weight(0), 1.0);
+        assert_eq!(data.bone_influences(2).unwrap().weight(2), 1.0);
+
+        let mut influences = BoneInfluences {
+            indices: [0, 1, 2, 3],
+            weights: [1.0, 1.0, 2.0, 0.0],
+        };
+        influences.normalize();
+        assert_eq!(influences.weights, [0.25, 0.25, 0.5, 0.0]);
+
+        let vertices = vec![StaticVertex::default(); 3];
+        let mut data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            Default::default(),
+        );
+        assert!(!data.is_skinned());
+        assert!(data.bone_influences(0).is_err());
+        assert!(matches!(
+            data.modify_bone_influences(|_, _| ()),
+            Err(VertexFetchError::NoSuchAttribute(
+                VertexAttributeUsage::BoneIndices
+            ))
+        ));
+    }
+}
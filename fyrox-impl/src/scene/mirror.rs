@@ -0,0 +1,593 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Mirror is a flat rectangular surface that reflects the scene. See [`Mirror`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        value_as_u8_slice,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::BaseSceneGraph,
+    material::{
+        shader::{ShaderResource, ShaderResourceExtension},
+        Material, MaterialResource,
+    },
+    renderer::{self, bundle::RenderContext},
+    resource::texture::{TextureKind, TextureResource, TextureResourceExtension},
+    scene::{
+        base::{Base, BaseBuilder},
+        camera::Camera,
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        mesh::{buffer::VertexTrait, RenderPath},
+        node::{Node, NodeTrait, RdcControlFlow, UpdateContext},
+        sprite::SpriteVertex,
+    },
+};
+use lazy_static::lazy_static;
+use std::ops::{Deref, DerefMut};
+
+lazy_static! {
+    static ref MIRROR_SHADER: ShaderResource = ShaderResource::from_str(
+        include_str!("../material/shader/standard/mirror.shader"),
+        ResourceKind::Embedded,
+    )
+    .unwrap();
+}
+
+/// Runtime-only rendering data of a mirror. It is not cloned, so every copy of a mirror has its own
+/// reflection texture.
+#[derive(Debug, Default)]
+struct MirrorSurface {
+    render_target: Option<TextureResource>,
+    material: Option<MaterialResource>,
+}
+
+impl Clone for MirrorSurface {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Mirror is a flat rectangular surface that reflects the scene. It is useful for bathroom mirrors,
+/// shiny floors, calm water and other places where screen-space reflections are insufficient
+/// (screen-space reflections cannot show anything that is not visible on screen).
+///
+/// The mirror lies in the local XY plane of the node and reflects everything in front of it (in the
+/// direction of the local Z axis). Its back side is invisible. Rotate the node to make a floor
+/// mirror, for example by -90 degrees around the X axis.
+///
+/// ## How it works
+///
+/// Every frame the scene is rendered once more for each visible mirror, from the point of view of
+/// the current camera reflected about the plane of the mirror. Everything behind the mirror is cut
+/// off using oblique near plane clipping. The result is rendered into an offscreen texture, which
+/// is then projected on the surface of the mirror in the main pass, so the reflection is masked by
+/// the visible part of the mirror.
+///
+/// ## Performance
+///
+/// Each visible mirror forces the engine to render the scene one more time, which may almost double
+/// the load of your GPU. Use [`Mirror::set_resolution_scale`] to decrease the size of the reflection
+/// texture. Screen-space ambient occlusion and occlusion culling are disabled for reflections.
+///
+/// ## Limitations
+///
+/// - Reflection is rendered for the first camera that renders into the screen, cameras with render
+/// targets see the same reflection.
+/// - Mirrors do not reflect each other recursively, other mirrors show their reflection from the
+/// previous frame in a reflection.
+/// - Oblique clipping changes the far plane of the reflection camera, so the objects that are far
+/// from the mirror may be clipped.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::{UnitQuaternion, Vector2, Vector3}, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder, graph::Graph, mirror::MirrorBuilder, node::Node,
+/// #         transform::TransformBuilder,
+/// #     },
+/// # };
+/// fn create_shiny_floor(graph: &mut Graph) -> Handle<Node> {
+///     MirrorBuilder::new(
+///         BaseBuilder::new().with_local_transform(
+///             TransformBuilder::new()
+///                 .with_local_rotation(UnitQuaternion::from_axis_angle(
+///                     &Vector3::x_axis(),
+///                     -90.0f32.to_radians(),
+///                 ))
+///                 .build(),
+///         ),
+///     )
+///     .with_size(Vector2::new(10.0, 10.0))
+///     .with_resolution_scale(0.5)
+///     .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct Mirror {
+    base: Base,
+
+    #[reflect(setter = "set_size")]
+    size: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(
+        min_value = 0.05,
+        max_value = 1.0,
+        step = 0.05,
+        setter = "set_resolution_scale"
+    )]
+    resolution_scale: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    surface: MirrorSurface,
+}
+
+impl Deref for Mirror {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Mirror {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        MirrorBuilder::new(BaseBuilder::new()).build_mirror()
+    }
+}
+
+impl TypeUuidProvider for Mirror {
+    fn type_uuid() -> Uuid {
+        uuid!("4f1b9c2e-7a63-4d8e-9b05-c3e2a17f6d48")
+    }
+}
+
+impl Mirror {
+    /// Sets new size of the mirror in local coordinates.
+    pub fn set_size(&mut self, size: Vector2<f32>) -> Vector2<f32> {
+        self.size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns current size of the mirror in local coordinates.
+    pub fn size(&self) -> Vector2<f32> {
+        *self.size
+    }
+
+    /// Sets new size of the reflection texture relative to the size of the frame. Lower values
+    /// improve performance, but make the reflection blurry.
+    pub fn set_resolution_scale(&mut self, scale: f32) -> f32 {
+        self.resolution_scale
+            .set_value_and_mark_modified(scale.clamp(0.05, 1.0))
+    }
+
+    /// Returns current size of the reflection texture relative to the size of the frame.
+    pub fn resolution_scale(&self) -> f32 {
+        *self.resolution_scale
+    }
+
+    /// Sets new tint color of the reflection. Alpha channel defines the opacity of the mirror.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current tint color of the reflection.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Returns a texture with the reflection, it is available only after the first update of the
+    /// mirror.
+    pub fn reflection_texture(&self) -> Option<&TextureResource> {
+        self.surface.render_target.as_ref()
+    }
+
+    fn plane(&self) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let normal = self.global_transform().look().try_normalize(f32::EPSILON)?;
+        Some((self.global_position(), normal))
+    }
+
+    /// Creates a temporary camera, that sees the reflection of the scene in the mirror from the
+    /// point of view of the given camera. Returns `None` if the mirror is not visible by the camera.
+    pub(crate) fn make_reflection_camera(&self, camera: &Camera) -> Option<Camera> {
+        let render_target = self.surface.render_target.clone()?;
+
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || !camera
+                .frustum()
+                .is_intersects_aabb(&self.world_bounding_box())
+        {
+            return None;
+        }
+
+        let (center, normal) = self.plane()?;
+
+        // Back side of the mirror is invisible.
+        let camera_position = camera.global_position();
+        if (camera_position - center).dot(&normal) <= 0.0 {
+            return None;
+        }
+
+        let reflect_point = |p: Vector3<f32>| p - normal.scale(2.0 * (p - center).dot(&normal));
+        let reflect_vector = |v: Vector3<f32>| v - normal.scale(2.0 * v.dot(&normal));
+
+        // Reflection changes handedness of the basis, so the side vector is flipped to keep the
+        // basis right-handed (otherwise the winding order of every triangle will be flipped too). It
+        // makes the reflection flipped horizontally, which is compensated in the mirror shader.
+        let transform = camera.global_transform();
+        let side = -reflect_vector(transform.side());
+        let up = reflect_vector(transform.up());
+        let look = reflect_vector(transform.look());
+        let position = reflect_point(camera_position);
+
+        let global_transform = Matrix4::from_columns(&[
+            side.push(0.0),
+            up.push(0.0),
+            look.push(0.0),
+            position.push(1.0),
+        ]);
+        let view_matrix =
+            Matrix4::look_at_rh(&Point3::from(position), &Point3::from(position + look), &up);
+
+        let plane = Vector4::new(normal.x, normal.y, normal.z, -normal.dot(&center));
+        let projection_matrix =
+            make_oblique_projection(camera.projection_matrix(), view_matrix, plane)?;
+
+        let mut reflection_camera = camera.clone();
+        reflection_camera.global_transform.set(global_transform);
+        reflection_camera.set_matrices(view_matrix, projection_matrix);
+        reflection_camera.set_render_target(Some(render_target));
+        Some(reflection_camera)
+    }
+}
+
+/// Modifies the given projection matrix so its near plane matches the given world-space clipping
+/// plane. Everything on the negative side of the plane will be clipped. See "Oblique View Frustum
+/// Depth Projection and Clipping" by Eric Lengyel for more info.
+fn make_oblique_projection(
+    mut projection_matrix: Matrix4<f32>,
+    view_matrix: Matrix4<f32>,
+    plane: Vector4<f32>,
+) -> Option<Matrix4<f32>> {
+    let view_space_plane = view_matrix.try_inverse()?.transpose() * plane;
+    let corner = projection_matrix.try_inverse()?
+        * Vector4::new(
+            view_space_plane.x.signum(),
+            view_space_plane.y.signum(),
+            1.0,
+            1.0,
+        );
+    let denominator = view_space_plane.dot(&corner);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+    let scaled_plane = view_space_plane.scale(2.0 / denominator);
+    let row = scaled_plane.transpose() - projection_matrix.row(3);
+    projection_matrix.set_row(2, &row);
+    Some(projection_matrix)
+}
+
+impl NodeTrait for Mirror {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_size = self.size.scale(0.5);
+        AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(-half_size.x, -half_size.y, 0.0),
+            Vector3::new(half_size.x, half_size.y, 0.0),
+        )
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let size = context.frame_size.scale(*self.resolution_scale);
+        let width = (size.x as u32).max(1);
+        let height = (size.y as u32).max(1);
+
+        let is_size_changed = self.surface.render_target.as_ref().map_or(true, |rt| {
+            !matches!(rt.data_ref().kind(), TextureKind::Rectangle { width: w, height: h }
+                if w == width && h == height)
+        });
+        if is_size_changed {
+            let render_target = TextureResource::new_render_target(width, height);
+            let mut material = Material::from_shader(MIRROR_SHADER.clone());
+            material.bind("reflectionTexture", render_target.clone());
+            self.surface = MirrorSurface {
+                render_target: Some(render_target),
+                material: Some(MaterialResource::new_ok(ResourceKind::Embedded, material)),
+            };
+        }
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if !self.global_visibility()
+            || !self.is_globally_enabled()
+            || renderer::is_shadow_pass(ctx.render_pass_name)
+        {
+            return RdcControlFlow::Continue;
+        }
+
+        let Some(material) = self.surface.material.as_ref() else {
+            return RdcControlFlow::Continue;
+        };
+
+        // Observers behind the mirror (including reflection cameras) do not see it.
+        let Some((center, normal)) = self.plane() else {
+            return RdcControlFlow::Continue;
+        };
+        if (ctx.observer_info.observer_position - center).dot(&normal) <= 0.0 {
+            return RdcControlFlow::Continue;
+        }
+
+        if !ctx
+            .frustum
+            .map_or(true, |f| f.is_intersects_aabb(&self.world_bounding_box()))
+        {
+            return RdcControlFlow::Continue;
+        }
+
+        let global_transform = self.global_transform();
+        let half_size = self.size.scale(0.5);
+        let vertices = [
+            (
+                Vector2::new(-half_size.x, half_size.y),
+                Vector2::new(0.0, 0.0),
+            ),
+            (
+                Vector2::new(half_size.x, half_size.y),
+                Vector2::new(1.0, 0.0),
+            ),
+            (
+                Vector2::new(half_size.x, -half_size.y),
+                Vector2::new(1.0, 1.0),
+            ),
+            (
+                Vector2::new(-half_size.x, -half_size.y),
+                Vector2::new(0.0, 1.0),
+            ),
+        ]
+        .map(|(position, tex_coord)| SpriteVertex {
+            position: global_transform
+                .transform_point(&Point3::new(position.x, position.y, 0.0))
+                .coords,
+            tex_coord,
+            params: Vector2::default(),
+            color: *self.color,
+        });
+
+        // Front side faces the local Z axis.
+        let triangles = [TriangleDefinition([0, 2, 1]), TriangleDefinition([2, 0, 3])];
+
+        let sort_index = ctx.calculate_sorting_index(center);
+
+        ctx.storage.push_triangles(
+            SpriteVertex::layout(),
+            material,
+            RenderPath::Forward,
+            sort_index,
+            self.handle(),
+            &mut move |mut vertex_buffer, mut triangle_buffer| {
+                let start_vertex_index = vertex_buffer.vertex_count();
+
+                for vertex in vertices.iter() {
+                    vertex_buffer
+                        .push_vertex_raw(value_as_u8_slice(vertex))
+                        .unwrap();
+                }
+
+                triangle_buffer
+                    .push_triangles_iter_with_offset(start_vertex_index, triangles.into_iter());
+            },
+        );
+
+        RdcControlFlow::Continue
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let transform = self.global_transform();
+        let half_size = self.size.scale(0.5);
+        ctx.draw_rectangle(half_size.x, half_size.y, transform, Color::WHITE);
+        let center = self.global_position();
+        ctx.add_line(Line {
+            begin: center,
+            end: center + transform.look().normalize().scale(half_size.min()),
+            color: Color::GREEN,
+        });
+    }
+}
+
+/// Allows you to create a mirror in declarative manner.
+pub struct MirrorBuilder {
+    base_builder: BaseBuilder,
+    size: Vector2<f32>,
+    resolution_scale: f32,
+    color: Color,
+}
+
+impl MirrorBuilder {
+    /// Creates new mirror builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            size: Vector2::new(1.0, 1.0),
+            resolution_scale: 1.0,
+            color: Color::WHITE,
+        }
+    }
+
+    /// Sets desired size of the mirror.
+    pub fn with_size(mut self, size: Vector2<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets desired size of the reflection texture relative to the size of the frame.
+    pub fn with_resolution_scale(mut self, scale: f32) -> Self {
+        self.resolution_scale = scale.clamp(0.05, 1.0);
+        self
+    }
+
+    /// Sets desired tint color of the reflection.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Creates new mirror instance.
+    pub fn build_mirror(self) -> Mirror {
+        Mirror {
+            base: self.base_builder.build_base(),
+            size: self.size.into(),
+            resolution_scale: self.resolution_scale.into(),
+            color: self.color.into(),
+            surface: Default::default(),
+        }
+    }
+
+    /// Creates new mirror node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_mirror())
+    }
+
+    /// Creates new mirror node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Point3, UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            camera::{Camera, CameraBuilder},
+            graph::Graph,
+            mirror::{Mirror, MirrorBuilder},
+            node::Node,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn make_scene() -> (Graph, Handle<Node>, Handle<Node>) {
+        let mut graph = Graph::new();
+        let mirror = MirrorBuilder::new(BaseBuilder::new())
+            .with_size(Vector2::new(4.0, 4.0))
+            .build(&mut graph);
+        let camera = CameraBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 0.5, 5.0))
+                    .with_local_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::y_axis(),
+                        std::f32::consts::PI,
+                    ))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+        (graph, mirror, camera)
+    }
+
+    fn project(camera: &Camera, point: Vector3<f32>) -> Vector3<f32> {
+        let clip = camera.view_projection_matrix() * Point3::from(point).to_homogeneous();
+        clip.xyz().scale(1.0 / clip.w)
+    }
+
+    #[test]
+    fn test_reflection_camera() {
+        let (graph, mirror, camera) = make_scene();
+        let camera = graph[camera].as_camera();
+        let reflection_camera = graph[mirror]
+            .cast::<Mirror>()
+            .unwrap()
+            .make_reflection_camera(camera)
+            .unwrap();
+
+        assert!(
+            (reflection_camera.global_position() - Vector3::new(1.0, 0.5, -5.0)).norm() < 1.0e-5
+        );
+        assert!((reflection_camera.look_vector().normalize() - Vector3::z()).norm() < 1.0e-5);
+        assert!(reflection_camera.render_target().is_some());
+
+        // A point in front of the mirror is visible in the reflection, and it is flipped
+        // horizontally relative to the view of the camera.
+        let point = Vector3::new(1.5, 0.0, 1.0);
+        let projected = project(&reflection_camera, point);
+        assert!(projected.z > -1.0 && projected.z < 1.0);
+        let mirrored = project(camera, Vector3::new(point.x, point.y, -point.z));
+        assert!((projected.x + mirrored.x).abs() < 1.0e-4);
+        assert!((projected.y - mirrored.y).abs() < 1.0e-4);
+
+        // A point behind the mirror must be clipped by the oblique near plane.
+        assert!(project(&reflection_camera, Vector3::new(1.0, 0.5, -1.0)).z < -1.0);
+    }
+
+    #[test]
+    fn test_back_side_is_not_reflected() {
+        let (mut graph, mirror, camera) = make_scene();
+        graph[camera]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 0.0, -5.0))
+            .set_rotation(UnitQuaternion::identity());
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+        let camera = graph[camera].as_camera();
+        assert!(graph[mirror]
+            .cast::<Mirror>()
+            .unwrap()
+            .make_reflection_camera(camera)
+            .is_none());
+    }
+}
@@ -31,22 +31,31 @@ pub mod camera;
 pub mod collider;
 pub mod debug;
 pub mod decal;
+pub mod destructible;
 pub mod dim2;
+pub mod fluid;
+pub mod fog;
+pub mod foliage;
 pub mod graph;
+pub mod impostor;
 pub mod joint;
 pub mod light;
 pub mod mesh;
+pub mod mirror;
 pub mod navmesh;
 pub mod node;
 pub mod particle_system;
 pub mod pivot;
+pub mod probe;
 pub mod ragdoll;
 pub mod rigidbody;
+pub mod soft_body;
 pub mod sound;
 pub mod sprite;
 pub mod terrain;
 pub mod tilemap;
 pub mod transform;
+pub mod weather;
 
 use crate::renderer::framework::PolygonFillMode;
 use crate::{
@@ -67,10 +76,12 @@ use crate::{
         base::BaseBuilder,
         camera::Camera,
         debug::SceneDrawingContext,
+        fog::Fog,
         graph::{Graph, GraphPerformanceStatistics, GraphUpdateSwitches},
         navmesh::NavigationalMeshBuilder,
         node::Node,
         sound::SoundEngine,
+        weather::Weather,
     },
     utils::navmesh::Navmesh,
 };
@@ -182,6 +193,11 @@ pub struct SceneRenderingOptions {
 
     /// Color of the ambient lighting.
     pub ambient_lighting_color: Color,
+
+    /// Fog of the scene. Default is [`None`], which means that there is no fog. See [`Fog`] docs
+    /// for more info.
+    #[visit(optional)]
+    pub fog: Option<Fog>,
 }
 
 impl Default for SceneRenderingOptions {
@@ -191,6 +207,7 @@ impl Default for SceneRenderingOptions {
             clear_color: None,
             polygon_rasterization_mode: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
+            fog: None,
         }
     }
 }
@@ -202,6 +219,7 @@ impl Clone for SceneRenderingOptions {
             clear_color: self.clear_color,
             polygon_rasterization_mode: self.polygon_rasterization_mode,
             ambient_lighting_color: self.ambient_lighting_color,
+            fog: self.fog.clone(),
         }
     }
 }
@@ -233,6 +251,10 @@ pub struct Scene {
     /// to false for menu's scene and when you need to open a menu - set it to true and
     /// set `enabled` flag to false for level's scene.
     pub enabled: InheritableVariable<bool>,
+
+    /// Weather of the scene (precipitation, wetness, lightning, etc.). See [`Weather`] docs for
+    /// more info.
+    pub weather: Weather,
 }
 
 impl Default for Scene {
@@ -243,6 +265,7 @@ impl Default for Scene {
             drawing_context: Default::default(),
             performance_statistics: Default::default(),
             enabled: true.into(),
+            weather: Default::default(),
         }
     }
 }
@@ -402,6 +425,7 @@ impl Scene {
             drawing_context: Default::default(),
             performance_statistics: Default::default(),
             enabled: true.into(),
+            weather: Default::default(),
         }
     }
 
@@ -426,7 +450,15 @@ impl Scene {
     /// it updates physics, animations, and each graph node. In most cases there is
     /// no need to call it directly, engine automatically updates all available scenes.
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
+        // Weather should not be updated if the scene is paused or only a part of it is updated
+        // (this is the case when the scene is being edited in the editor).
+        let update_weather = !switches.paused && switches.node_overrides.is_none();
+
         self.graph.update(frame_size, dt, switches);
+
+        if update_weather {
+            self.weather.update(&mut self.graph, dt);
+        }
         self.performance_statistics.graph = self.graph.performance_statistics.clone();
     }
 
@@ -448,6 +480,11 @@ impl Scene {
             self.graph
                 .clone(root, filter, pre_process_callback, post_process_callback);
 
+        let mut weather = self.weather.clone();
+        old_new_map.try_map(&mut weather.rain.particle_system);
+        old_new_map.try_map(&mut weather.snow.particle_system);
+        old_new_map.try_map(&mut weather.lightning_light);
+
         (
             Self {
                 graph,
@@ -455,6 +492,7 @@ impl Scene {
                 drawing_context: self.drawing_context.clone(),
                 performance_statistics: Default::default(),
                 enabled: self.enabled.clone(),
+                weather,
             },
             old_new_map,
         )
@@ -479,6 +517,7 @@ impl Scene {
         let _ = self
             .rendering_options
             .visit("RenderingOptions", &mut region);
+        let _ = self.weather.visit("Weather", &mut region);
 
         // Backward compatibility.
         let mut navmeshes = NavMeshContainer::default();
@@ -32,14 +32,21 @@ use crate::{
         animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
         camera::Camera,
         decal::Decal,
+        destructible::Destructible,
         dim2::{self, rectangle::Rectangle},
+        fluid::FluidVolume,
+        foliage::Foliage,
+        impostor::Impostor,
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
         mesh::Mesh,
+        mirror::Mirror,
         navmesh::NavigationalMesh,
         node::{Node, NodeTrait},
         particle_system::ParticleSystem,
         pivot::Pivot,
+        probe::ReflectionProbe,
         ragdoll::Ragdoll,
+        soft_body::SoftBody,
         sound::{listener::Listener, Sound},
         sprite::Sprite,
         terrain::Terrain,
@@ -91,6 +98,13 @@ impl NodeConstructorContainer {
         container.add::<NavigationalMesh>();
         container.add::<Ragdoll>();
         container.add::<TileMap>();
+        container.add::<Foliage>();
+        container.add::<Impostor>();
+        container.add::<Mirror>();
+        container.add::<Destructible>();
+        container.add::<FluidVolume>();
+        container.add::<ReflectionProbe>();
+        container.add::<SoftBody>();
 
         container
     }
@@ -86,3 +86,79 @@ impl VertexTrait for Vertex {
         ]
     }
 }
+
+/// Vertex of a particle, that is simulated on GPU (see
+/// [`super::emitter::base::ParticleSimulationMode`]). It contains the initial state of the
+/// particle, the actual state is calculated in vertex shader using the age of the particle. OpenGL
+/// expects this structure packed as in C.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, Pod, Zeroable)]
+pub struct GpuVertex {
+    pub position: Vector3<f32>,
+    pub tex_coord: Vector2<f32>,
+    pub size: f32,
+    pub rotation: f32,
+    /// Velocity of the particle (per second).
+    pub velocity: Vector3<f32>,
+    /// Simulation time of the particle system at which the particle was spawned.
+    pub spawn_time: f32,
+    /// Lifetime of the particle (in seconds).
+    pub lifetime: f32,
+    pub size_modifier: f32,
+    pub rotation_speed: f32,
+}
+
+impl VertexTrait for GpuVertex {
+    fn layout() -> &'static [VertexAttributeDescriptor] {
+        &[
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Position,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 0,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::TexCoord0,
+                data_type: VertexAttributeDataType::F32,
+                size: 2,
+                divisor: 0,
+                shader_location: 1,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom0,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 2,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom1,
+                data_type: VertexAttributeDataType::F32,
+                size: 1,
+                divisor: 0,
+                shader_location: 3,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom2,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 0,
+                shader_location: 4,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom3,
+                data_type: VertexAttributeDataType::F32,
+                size: 3,
+                divisor: 0,
+                shader_location: 5,
+                normalized: false,
+            },
+        ]
+    }
+}
@@ -27,7 +27,45 @@ use crate::{
     },
     scene::particle_system::{Particle, ParticleSystemRng},
 };
+use fyrox_core::uuid_provider;
 use std::ops::Range;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines where the particles of an emitter are simulated.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ParticleSimulationMode {
+    /// Every particle is simulated on CPU on each update tick. This mode is the most flexible one,
+    /// particles have their actual state at any time and they're sorted back-to-front before
+    /// rendering. This mode is slow for large amounts of particles (more than ~10k).
+    #[default]
+    Cpu,
+    /// Particles are spawned on CPU, but their motion (position, size, rotation, color) is
+    /// calculated in vertex shader, using the initial state of a particle and its age. CPU only
+    /// tracks the age of particles, which is much cheaper, and writes the vertices of a particle
+    /// only once, when the particle is spawned. Downsides of this mode are: [`Particle::position`]
+    /// (and other properties) contains initial state of the particle, [`Particle::velocity`] is
+    /// measured in units per second (not per tick), particles aren't sorted, so this mode works
+    /// best with order-independent blending (additive, for example). Such
+    /// particles are rendered with a separate material (see
+    /// [`crate::scene::particle_system::ParticleSystem::set_gpu_material`]), custom shaders must
+    /// support the vertex layout and the properties of the standard GPU particle system shader.
+    Gpu,
+}
+
+uuid_provider!(ParticleSimulationMode = "4dcafc7f-6070-4d05-8d63-a51f15b10d59");
 
 /// See module docs.
 #[derive(Debug, Visit, PartialEq, Reflect)]
@@ -69,6 +107,9 @@ pub struct BaseEmitter {
     resurrect_particles: bool,
     #[reflect(hidden)]
     pub(crate) spawned_particles: u64,
+    /// Defines where the particles of the emitter are simulated.
+    #[visit(optional)]
+    simulation_mode: ParticleSimulationMode,
 }
 
 /// Emitter builder allows you to construct emitter in declarative manner.
@@ -86,6 +127,7 @@ pub struct BaseEmitterBuilder {
     rotation_speed: Range<f32>,
     rotation: Range<f32>,
     resurrect_particles: bool,
+    simulation_mode: ParticleSimulationMode,
 }
 
 impl Default for BaseEmitterBuilder {
@@ -110,6 +152,7 @@ impl BaseEmitterBuilder {
             rotation_speed: -0.02..0.02,
             rotation: -std::f32::consts::PI..std::f32::consts::PI,
             resurrect_particles: true,
+            simulation_mode: ParticleSimulationMode::Cpu,
         }
     }
 
@@ -185,6 +228,13 @@ impl BaseEmitterBuilder {
         self
     }
 
+    /// Sets desired simulation mode of the emitter. See [`ParticleSimulationMode`] docs for more
+    /// info.
+    pub fn with_simulation_mode(mut self, mode: ParticleSimulationMode) -> Self {
+        self.simulation_mode = mode;
+        self
+    }
+
     /// Creates new instance of emitter.
     pub fn build(self) -> BaseEmitter {
         BaseEmitter {
@@ -204,6 +254,7 @@ impl BaseEmitterBuilder {
             particles_to_spawn: 0,
             resurrect_particles: self.resurrect_particles,
             spawned_particles: 0,
+            simulation_mode: self.simulation_mode,
         }
     }
 }
@@ -397,6 +448,17 @@ impl BaseEmitter {
     pub fn spawned_particles(&self) -> u64 {
         self.spawned_particles
     }
+
+    /// Sets new simulation mode of the emitter. See [`ParticleSimulationMode`] docs for more info.
+    pub fn set_simulation_mode(&mut self, mode: ParticleSimulationMode) -> &mut Self {
+        self.simulation_mode = mode;
+        self
+    }
+
+    /// Returns current simulation mode of the emitter.
+    pub fn simulation_mode(&self) -> ParticleSimulationMode {
+        self.simulation_mode
+    }
 }
 
 impl Clone for BaseEmitter {
@@ -418,6 +480,7 @@ impl Clone for BaseEmitter {
             particles_to_spawn: 0,
             resurrect_particles: self.resurrect_particles,
             spawned_particles: self.spawned_particles,
+            simulation_mode: self.simulation_mode,
         }
     }
 }
@@ -441,6 +504,7 @@ impl Default for BaseEmitter {
             particles_to_spawn: 0,
             resurrect_particles: true,
             spawned_particles: 0,
+            simulation_mode: ParticleSimulationMode::Cpu,
         }
     }
 }
@@ -24,8 +24,9 @@
 use crate::scene::mesh::buffer::VertexTrait;
 use crate::scene::node::RdcControlFlow;
 use crate::{
+    asset::untyped::ResourceKind,
     core::{
-        algebra::{Point3, Vector2, Vector3},
+        algebra::{Point3, Vector2, Vector3, Vector4},
         color_gradient::ColorGradient,
         math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
         pool::Handle,
@@ -35,17 +36,25 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
-    material::{self, Material, MaterialResource},
+    material::{self, Material, MaterialPropertyBlock, MaterialResource},
     rand::{prelude::StdRng, Error, RngCore, SeedableRng},
-    renderer::{self, bundle::RenderContext},
+    renderer::{
+        self,
+        bundle::{RenderContext, SurfaceInstanceData},
+        framework::ElementRange,
+    },
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        mesh::RenderPath,
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceResource},
+            RenderPath,
+        },
         node::{Node, NodeTrait, UpdateContext},
         particle_system::{
-            draw::Vertex,
-            emitter::{Emit, Emitter},
+            draw::{GpuVertex, Vertex},
+            emitter::{base::ParticleSimulationMode, Emit, Emitter},
             particle::Particle,
         },
     },
@@ -57,6 +66,7 @@ use std::{
     cmp::Ordering,
     fmt::Debug,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 pub(crate) mod draw;
@@ -152,6 +162,18 @@ impl Visit for ParticleSystemRng {
 /// enough, alternatively amount of particles can be defined by some coefficient based on
 /// graphics quality settings.
 ///
+/// Emitters with large amounts of particles (more than ~10k) could use GPU simulation mode
+/// (see [`ParticleSimulationMode`]), in this mode the motion of particles is calculated in
+/// vertex shader and CPU only tracks the age of particles. Such particles are rendered using a
+/// separate material (see [`ParticleSystem::set_gpu_material`]) with its own vertex layout, the
+/// vertices of a particle are written only once - when the particle is spawned.
+///
+/// # Soft particles
+///
+/// Standard particle system material fades out particles near the scene geometry, which hides
+/// hard edges at the places where particles intersect other objects. Sharpness of the fading
+/// could be controlled by `softBoundarySharpnessFactor` property of the material.
+///
 /// # Example
 ///
 /// Simple smoke effect can be create like so:
@@ -228,6 +250,9 @@ pub struct ParticleSystem {
     #[reflect(setter = "set_material")]
     material: InheritableVariable<MaterialResource>,
 
+    #[reflect(setter = "set_gpu_material")]
+    gpu_material: InheritableVariable<MaterialResource>,
+
     #[reflect(setter = "set_acceleration")]
     acceleration: InheritableVariable<Vector3<f32>>,
 
@@ -252,6 +277,102 @@ pub struct ParticleSystem {
     visible_distance: InheritableVariable<f32>,
 
     rng: ParticleSystemRng,
+
+    // Simulation time, it is used to calculate the age of particles that are simulated on GPU.
+    #[reflect(hidden)]
+    time: f32,
+
+    #[reflect(hidden)]
+    gpu_particles: GpuParticleBuffer,
+}
+
+// Vertices of the particles that are simulated on GPU. Every particle occupies four vertices at
+// the offset defined by its index in the particles array. Dead particles are discarded by the
+// shader, so the buffer is modified only when a particle is spawned.
+#[derive(Debug)]
+struct GpuParticleBuffer(SurfaceResource);
+
+impl Default for GpuParticleBuffer {
+    fn default() -> Self {
+        Self(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::new(
+                VertexBuffer::new::<GpuVertex>(0, vec![]).unwrap(),
+                TriangleBuffer::default(),
+            ),
+        ))
+    }
+}
+
+impl Clone for GpuParticleBuffer {
+    fn clone(&self) -> Self {
+        // Surface resources are shared on clone, but every particle system needs its own buffer.
+        let data = self.0.data_ref();
+        Self(SurfaceResource::new_ok(
+            ResourceKind::Embedded,
+            SurfaceData::new(data.vertex_buffer.clone(), data.geometry_buffer.clone()),
+        ))
+    }
+}
+
+impl GpuParticleBuffer {
+    fn clear(&mut self) {
+        let mut data = self.0.data_ref();
+        data.vertex_buffer.modify().clear();
+        data.geometry_buffer.modify().clear();
+    }
+
+    fn write(&mut self, index: usize, particle: &Particle, spawn_time: f32) {
+        let mut data = self.0.data_ref();
+        let data = &mut *data;
+
+        let required_vertex_count = (index + 1) * 4;
+        let vertex_count = data.vertex_buffer.vertex_count() as usize;
+        if vertex_count < required_vertex_count {
+            let mut vertex_buffer = data.vertex_buffer.modify();
+            let mut geometry_buffer = data.geometry_buffer.modify();
+            for base_index in (vertex_count..required_vertex_count).step_by(4) {
+                for _ in 0..4 {
+                    // Zero lifetime makes the particle dead.
+                    vertex_buffer.push_vertex(&GpuVertex::default()).unwrap();
+                }
+                let base_index = base_index as u32;
+                geometry_buffer.push_triangles(&[
+                    TriangleDefinition([base_index, base_index + 1, base_index + 2]),
+                    TriangleDefinition([base_index, base_index + 2, base_index + 3]),
+                ]);
+            }
+        }
+
+        let vertex = GpuVertex {
+            position: particle.position,
+            tex_coord: Vector2::default(),
+            size: particle.size,
+            rotation: particle.rotation,
+            velocity: particle.velocity,
+            spawn_time,
+            lifetime: particle.initial_lifetime,
+            size_modifier: particle.size_modifier,
+            rotation_speed: particle.rotation_speed,
+        };
+
+        let mut vertex_buffer = data.vertex_buffer.modify();
+        let vertices = vertex_buffer.cast_data_mut::<GpuVertex>().unwrap();
+        for (i, tex_coord) in [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 1.0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            vertices[index * 4 + i] = GpuVertex {
+                tex_coord,
+                ..vertex
+            };
+        }
+    }
 }
 
 impl Visit for ParticleSystem {
@@ -282,6 +403,7 @@ impl Visit for ParticleSystem {
         } else {
             self.material.visit("Material", &mut region)?;
         }
+        let _ = self.gpu_material.visit("GpuMaterial", &mut region);
 
         let mut soft_boundary_sharpness_factor = 100.0;
         if soft_boundary_sharpness_factor
@@ -294,6 +416,11 @@ impl Visit for ParticleSystem {
             );
         }
 
+        if region.is_reading() {
+            // The buffer is not serialized, it is restored from the loaded particles instead.
+            self.rebuild_gpu_particles();
+        }
+
         Ok(())
     }
 }
@@ -321,6 +448,9 @@ impl TypeUuidProvider for ParticleSystem {
 impl ParticleSystem {
     const FADEOUT_MARGIN: f32 = 1.5;
 
+    // Must match the size of `particleColorOverLifetime` array in the GPU particle system shader.
+    const COLOR_OVER_LIFETIME_SAMPLES: usize = 16;
+
     /// Returns current acceleration for particles in particle system.
     pub fn acceleration(&self) -> Vector3<f32> {
         *self.acceleration
@@ -350,10 +480,13 @@ impl ParticleSystem {
     }
 
     /// Replaces the particles in the particle system with pre-generated set. It could be useful
-    /// to create procedural particle effects; when particles cannot be pre-made.
+    /// to create procedural particle effects; when particles cannot be pre-made. Particles of the
+    /// emitters that are simulated on GPU (see [`ParticleSimulationMode::Gpu`]) must have their
+    /// velocity in units per second.
     pub fn set_particles(&mut self, particles: Vec<Particle>) {
         self.free_particles.clear();
         self.particles = particles;
+        self.rebuild_gpu_particles();
     }
 
     /// Returns a reference to a slice to the current set of particles, generated by the particle system.
@@ -365,6 +498,7 @@ impl ParticleSystem {
     pub fn clear_particles(&mut self) {
         self.particles.clear();
         self.free_particles.clear();
+        self.gpu_particles.clear();
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.alive_particles = 0;
             emitter.spawned_particles = 0;
@@ -386,6 +520,18 @@ impl ParticleSystem {
         &self.material
     }
 
+    /// Sets the new material for the particles, that are simulated on GPU (see
+    /// [`ParticleSimulationMode::Gpu`]). The material must use a shader that supports vertex
+    /// layout of such particles, see the standard GPU particle system shader for an example.
+    pub fn set_gpu_material(&mut self, material: MaterialResource) -> MaterialResource {
+        self.gpu_material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns current material used by the particles, that are simulated on GPU.
+    pub fn gpu_material(&self) -> &MaterialResource {
+        &self.gpu_material
+    }
+
     fn tick(&mut self, dt: f32) {
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
             emitter.tick(dt);
@@ -399,6 +545,21 @@ impl ParticleSystem {
                 };
                 emitter.alive_particles += 1;
                 emitter.emit(&mut particle, &mut self.rng);
+
+                if emitter.simulation_mode() == ParticleSimulationMode::Gpu {
+                    // Velocities of particles are defined per tick, while the shader integrates
+                    // motion over time in seconds. The additional term compensates the difference
+                    // between per-tick and continuous integration of the acceleration. The
+                    // converted velocity is kept in the particle to be able to rebuild the buffer.
+                    particle.velocity = particle.velocity.scale(1.0 / dt.max(f32::EPSILON))
+                        + self.acceleration.scale(0.5 * dt);
+                    let index = self
+                        .free_particles
+                        .last()
+                        .map_or(self.particles.len(), |index| *index as usize);
+                    self.gpu_particles.write(index, &particle, self.time);
+                }
+
                 if let Some(free_index) = self.free_particles.pop() {
                     self.particles[free_index as usize] = particle;
                 } else {
@@ -407,7 +568,10 @@ impl ParticleSystem {
             }
         }
 
+        self.time += dt;
+
         let acceleration_offset = self.acceleration.scale(dt * dt);
+        let gpu_simulated = self.gpu_simulated_emitters();
 
         for (i, particle) in self.particles.iter_mut().enumerate() {
             if particle.alive {
@@ -423,7 +587,11 @@ impl ParticleSystem {
                     }
                     particle.alive = false;
                     particle.lifetime = particle.initial_lifetime;
-                } else {
+                } else if !gpu_simulated
+                    .get(particle.emitter_index as usize)
+                    .cloned()
+                    .unwrap_or_default()
+                {
                     particle.velocity += acceleration_offset;
                     particle.position += particle.velocity;
                     particle.size += particle.size_modifier * dt;
@@ -439,6 +607,32 @@ impl ParticleSystem {
         }
     }
 
+    // Writes every alive particle of the emitters that are simulated on GPU to the buffer. It is
+    // used when the particles were replaced all at once, the age of a particle defines its spawn
+    // time.
+    fn rebuild_gpu_particles(&mut self) {
+        self.gpu_particles.clear();
+        let gpu_simulated = self.gpu_simulated_emitters();
+        for (index, particle) in self.particles.iter().enumerate() {
+            if particle.alive
+                && gpu_simulated
+                    .get(particle.emitter_index as usize)
+                    .cloned()
+                    .unwrap_or_default()
+            {
+                self.gpu_particles
+                    .write(index, particle, self.time - particle.lifetime);
+            }
+        }
+    }
+
+    fn gpu_simulated_emitters(&self) -> Vec<bool> {
+        self.emitters
+            .iter()
+            .map(|emitter| emitter.simulation_mode() == ParticleSimulationMode::Gpu)
+            .collect()
+    }
+
     /// Simulates particle system for the given `time` with given time step (`dt`). `dt` is usually `1.0 / 60.0`.
     pub fn rewind(&mut self, dt: f32, time: f32) {
         assert!(dt > 0.0);
@@ -525,9 +719,22 @@ impl NodeTrait for ParticleSystem {
             1.0
         };
 
+        let gpu_simulated = self.gpu_simulated_emitters();
+        let is_gpu_simulated = |particle: &Particle| {
+            gpu_simulated
+                .get(particle.emitter_index as usize)
+                .cloned()
+                .unwrap_or_default()
+        };
+
         let mut sorted_particles = Vec::new();
+        let mut has_gpu_particles = false;
         for (i, particle) in self.particles.iter().enumerate() {
             if particle.alive {
+                if is_gpu_simulated(particle) {
+                    has_gpu_particles = true;
+                    continue;
+                }
                 let actual_position = particle.position + self.base.global_position();
                 particle
                     .sqr_distance_to_camera
@@ -555,6 +762,43 @@ impl NodeTrait for ParticleSystem {
         let global_transform = self.global_transform();
         let sort_index = ctx.calculate_sorting_index(self.global_position());
 
+        if has_gpu_particles {
+            // Actual positions of such particles are known only on GPU, so they aren't sorted.
+            let mut property_block = MaterialPropertyBlock::default();
+            property_block.set_property("particleSystemTime", self.time);
+            property_block.set_property("particleAcceleration", *self.acceleration);
+            property_block.set_property("particleAlphaFactor", particle_alpha_factor);
+            property_block.set_property(
+                "particleColorOverLifetime",
+                (0..Self::COLOR_OVER_LIFETIME_SAMPLES)
+                    .map(|i| {
+                        let k = i as f32 / (Self::COLOR_OVER_LIFETIME_SAMPLES - 1) as f32;
+                        self.color_over_lifetime.get_color(k).as_frgba()
+                    })
+                    .collect::<Vec<Vector4<f32>>>(),
+            );
+
+            ctx.storage.push(
+                &self.gpu_particles.0,
+                &self.gpu_material,
+                RenderPath::Forward,
+                sort_index,
+                SurfaceInstanceData {
+                    world_transform: global_transform,
+                    bone_matrices: Default::default(),
+                    blend_shapes_weights: Default::default(),
+                    element_range: ElementRange::Full,
+                    node_handle: self.handle(),
+                    property_block: Some(Arc::new(property_block)),
+                    color: Color::WHITE,
+                },
+            );
+        }
+
+        if sorted_particles.is_empty() {
+            return RdcControlFlow::Continue;
+        }
+
         ctx.storage.push_triangles(
             Vertex::layout(),
             &self.material,
@@ -577,34 +821,27 @@ impl NodeTrait for ParticleSystem {
                         alpha,
                     );
 
+                    let vertex = Vertex {
+                        position,
+                        tex_coord: Vector2::default(),
+                        size: particle.size,
+                        rotation: particle.rotation,
+                        color,
+                    };
+
                     [
+                        vertex,
                         Vertex {
-                            position,
-                            tex_coord: Vector2::default(),
-                            size: particle.size,
-                            rotation: particle.rotation,
-                            color,
-                        },
-                        Vertex {
-                            position,
                             tex_coord: Vector2::new(1.0, 0.0),
-                            size: particle.size,
-                            rotation: particle.rotation,
-                            color,
+                            ..vertex
                         },
                         Vertex {
-                            position,
                             tex_coord: Vector2::new(1.0, 1.0),
-                            size: particle.size,
-                            rotation: particle.rotation,
-                            color,
+                            ..vertex
                         },
                         Vertex {
-                            position,
                             tex_coord: Vector2::new(0.0, 1.0),
-                            size: particle.size,
-                            rotation: particle.rotation,
-                            color,
+                            ..vertex
                         },
                     ]
                 });
@@ -640,6 +877,7 @@ pub struct ParticleSystemBuilder {
     base_builder: BaseBuilder,
     emitters: Vec<Emitter>,
     material: MaterialResource,
+    gpu_material: MaterialResource,
     acceleration: Vector3<f32>,
     particles: Vec<Particle>,
     color_over_lifetime: ColorGradient,
@@ -658,6 +896,10 @@ impl ParticleSystemBuilder {
                 Default::default(),
                 Material::standard_particle_system(),
             ),
+            gpu_material: MaterialResource::new_ok(
+                Default::default(),
+                Material::standard_gpu_particle_system(),
+            ),
             particles: Default::default(),
             acceleration: Vector3::new(0.0, -9.81, 0.0),
             color_over_lifetime: Default::default(),
@@ -679,6 +921,13 @@ impl ParticleSystemBuilder {
         self
     }
 
+    /// Sets desired material for the particles, that are simulated on GPU. See
+    /// [`ParticleSystem::set_gpu_material`] for more info.
+    pub fn with_gpu_material(mut self, material: MaterialResource) -> Self {
+        self.gpu_material = material;
+        self
+    }
+
     /// Sets desired acceleration for particle system.
     pub fn with_acceleration(mut self, acceleration: Vector3<f32>) -> Self {
         self.acceleration = acceleration;
@@ -724,11 +973,14 @@ impl ParticleSystemBuilder {
             free_particles: Vec::new(),
             emitters: self.emitters.into(),
             material: self.material.into(),
+            gpu_material: self.gpu_material.into(),
             acceleration: self.acceleration.into(),
             color_over_lifetime: self.color_over_lifetime.into(),
             is_playing: self.is_playing.into(),
             rng: self.rng,
             visible_distance: self.visible_distance.into(),
+            time: 0.0,
+            gpu_particles: Default::default(),
         }
     }
 
@@ -742,3 +994,123 @@ impl ParticleSystemBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::manager::ResourceManager,
+        core::{
+            algebra::Vector3,
+            visitor::{Visit, Visitor},
+        },
+        engine::{self, SerializationContext},
+        scene::{
+            base::BaseBuilder,
+            particle_system::{
+                draw::GpuVertex,
+                emitter::{
+                    base::{BaseEmitterBuilder, ParticleSimulationMode},
+                    sphere::SphereEmitterBuilder,
+                },
+                ParticleSystem, ParticleSystemBuilder,
+            },
+        },
+    };
+    use std::sync::Arc;
+
+    fn particle_system(mode: ParticleSimulationMode) -> ParticleSystem {
+        ParticleSystemBuilder::new(BaseBuilder::new())
+            .with_emitters(vec![SphereEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(10)
+                    .with_max_particles(1)
+                    .resurrect_particles(false)
+                    .with_lifetime_range(10.0..10.1)
+                    .with_y_velocity_range(1.0..1.1)
+                    .with_simulation_mode(mode),
+            )
+            .with_radius(0.0)
+            .build()])
+            .build_particle_system()
+    }
+
+    #[test]
+    fn test_gpu_simulated_particles_are_not_integrated_on_cpu() {
+        let mut particle_system = particle_system(ParticleSimulationMode::Gpu);
+
+        particle_system.rewind(0.1, 1.0);
+
+        let particle = &particle_system.particles()[0];
+        assert_eq!(particle.position, Vector3::default());
+        assert!(particle.lifetime > 0.0);
+
+        // Vertices of the particle are written once, when the particle is spawned.
+        let data = particle_system.gpu_particles.0.data_ref();
+        assert_eq!(data.vertex_buffer.vertex_count(), 4);
+        assert_eq!(data.geometry_buffer.len(), 2);
+        let vertex = data.vertex_buffer.cast_data_ref::<GpuVertex>().unwrap()[0];
+        assert_eq!(vertex.lifetime, particle.initial_lifetime);
+        assert!(vertex.spawn_time < particle_system.time);
+        assert!(vertex.velocity.y > 0.0);
+        drop(data);
+
+        particle_system.clear_particles();
+        let data = particle_system.gpu_particles.0.data_ref();
+        assert_eq!(data.vertex_buffer.vertex_count(), 0);
+        assert_eq!(data.geometry_buffer.len(), 0);
+    }
+
+    #[test]
+    fn test_cpu_particles_do_not_use_gpu_buffer() {
+        let mut particle_system = particle_system(ParticleSimulationMode::Cpu);
+
+        particle_system.rewind(0.1, 1.0);
+
+        assert!(particle_system.particles()[0].position.y > 0.0);
+        let data = particle_system.gpu_particles.0.data_ref();
+        assert_eq!(data.vertex_buffer.vertex_count(), 0);
+    }
+
+    #[test]
+    fn test_gpu_buffer_is_rebuilt_from_particles() {
+        let mut particle_system = particle_system(ParticleSimulationMode::Gpu);
+        particle_system.rewind(0.1, 1.0);
+        let particles = particle_system.particles().to_vec();
+
+        let check = |particle_system: &ParticleSystem| {
+            let particle = &particle_system.particles()[0];
+            let data = particle_system.gpu_particles.0.data_ref();
+            assert_eq!(data.vertex_buffer.vertex_count(), 4);
+            assert_eq!(data.geometry_buffer.len(), 2);
+            let vertex = data.vertex_buffer.cast_data_ref::<GpuVertex>().unwrap()[0];
+            assert_eq!(vertex.lifetime, particle.initial_lifetime);
+            assert_eq!(vertex.velocity, particle.velocity);
+            assert_eq!(vertex.spawn_time, particle_system.time - particle.lifetime);
+        };
+
+        // The buffer is not serialized.
+        let mut visitor = Visitor::new();
+        particle_system
+            .visit("ParticleSystem", &mut visitor)
+            .unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+        let mut loaded = self::particle_system(ParticleSimulationMode::Gpu);
+        let resource_manager = ResourceManager::new(Arc::new(Default::default()));
+        engine::initialize_resource_manager_loaders(
+            &resource_manager,
+            Arc::new(SerializationContext::new()),
+        );
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        visitor.blackboard.register(Arc::new(resource_manager));
+        loaded.visit("ParticleSystem", &mut visitor).unwrap();
+        check(&loaded);
+
+        let mut particle_system = self::particle_system(ParticleSimulationMode::Gpu);
+        particle_system.set_particles(particles);
+        check(&particle_system);
+
+        particle_system.set_particles(Vec::new());
+        let data = particle_system.gpu_particles.0.data_ref();
+        assert_eq!(data.vertex_buffer.vertex_count(), 0);
+    }
+}
@@ -0,0 +1,583 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reflection probe is a node, that captures surroundings in a cube map and uses it for
+//! image-based lighting of the objects within its bounds. See [`ReflectionProbe`] docs for
+//! more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        uuid_provider,
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::BaseSceneGraph,
+    scene::{
+        base::{Base, BaseBuilder},
+        camera::{Camera, PerspectiveProjection, Projection},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{Node, NodeTrait, UpdateContext},
+    },
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how the captured cube map is projected on the objects within the bounds of a probe.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "1e6b47fa-8139-4f70-addd-e934806684d3")]
+#[repr(u32)]
+pub enum ReflectionProbeProjection {
+    /// Reflections are projected on the bounding box of the probe. It is the best option for
+    /// rooms and corridors, since their walls usually match the bounds of the probe.
+    #[default]
+    Box = 0,
+    /// Reflections are projected on the ellipsoid inscribed in the bounding box of the probe. It
+    /// works better for open spaces and round rooms.
+    Sphere = 1,
+}
+
+/// Defines when a reflection probe captures its surroundings.
+#[derive(
+    Default, Copy, Clone, PartialEq, Debug, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum ReflectionProbeUpdateMode {
+    /// The probe captures its surroundings only once (when it is created, loaded or moved), or
+    /// when [`ReflectionProbe::force_update`] is called. It is the cheapest option, which is
+    /// suitable for static environments.
+    #[default]
+    Once,
+    /// The probe captures its surroundings periodically. Keep in mind, that each capture renders
+    /// the scene six times, so small intervals could significantly decrease performance.
+    Periodic {
+        /// Time (in seconds) between two consecutive captures. Zero means that the probe
+        /// captures its surroundings every frame.
+        #[reflect(min_value = 0.0, step = 0.1)]
+        interval: f32,
+    },
+}
+
+uuid_provider!(ReflectionProbeUpdateMode = "8743a6cb-2b04-4c42-9f3f-b1f766449400");
+
+/// Look and up vectors of the cube map faces in the following order: +X, -X, +Y, -Y, +Z, -Z.
+const CUBE_MAP_FACES: [(Vector3<f32>, Vector3<f32>); 6] = [
+    (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+    (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+    (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+];
+
+/// Reflection probe captures its surroundings in a cube map and uses it for image-based lighting
+/// (reflections and ambient lighting) of the objects within its bounds, instead of the
+/// environment map of the camera.
+///
+/// # Parallax correction
+///
+/// A cube map is captured from a single point, so reflections sampled from it are correct only
+/// for objects at that point. To fix this, reflection vectors are intersected with the bounds of
+/// the probe (see [`ReflectionProbeProjection`]) and the cube map is sampled in the direction of
+/// the intersection point. It works best when the bounds match the geometry around the probe.
+///
+/// # Blending
+///
+/// Reflections fade out near the bounds of the probe over [`ReflectionProbe::blend_distance`],
+/// so overlapping probes are blended smoothly. When probes overlap, smaller ones are drawn on
+/// top of larger ones.
+///
+/// # Performance
+///
+/// Each capture renders the scene six times (once per cube map face), so it is better to use
+/// [`ReflectionProbeUpdateMode::Once`] when possible.
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector3, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         probe::{ReflectionProbeBuilder, ReflectionProbeUpdateMode},
+/// #     },
+/// # };
+/// fn create_probe(graph: &mut Graph) -> Handle<Node> {
+///     ReflectionProbeBuilder::new(BaseBuilder::new())
+///         .with_size(Vector3::new(8.0, 3.0, 12.0))
+///         .with_resolution(256)
+///         .with_update_mode(ReflectionProbeUpdateMode::Periodic { interval: 1.0 })
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Clone, Reflect)]
+pub struct ReflectionProbe {
+    base: Base,
+
+    #[reflect(setter = "set_size")]
+    size: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(setter = "set_projection")]
+    projection: InheritableVariable<ReflectionProbeProjection>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_blend_distance")]
+    blend_distance: InheritableVariable<f32>,
+
+    #[reflect(min_value = 16.0, max_value = 2048.0, setter = "set_resolution")]
+    resolution: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_z_near")]
+    z_near: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_z_far")]
+    z_far: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_update_mode")]
+    update_mode: InheritableVariable<ReflectionProbeUpdateMode>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    need_update: Cell<bool>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    elapsed_time: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    capture_position: Option<Vector3<f32>>,
+}
+
+impl Deref for ReflectionProbe {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ReflectionProbe {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for ReflectionProbe {
+    fn default() -> Self {
+        ReflectionProbeBuilder::new(BaseBuilder::new()).build_reflection_probe()
+    }
+}
+
+impl TypeUuidProvider for ReflectionProbe {
+    fn type_uuid() -> Uuid {
+        uuid!("7056d8e1-7192-45a1-a4a4-a2519eb7d8f1")
+    }
+}
+
+impl ReflectionProbe {
+    /// Sets new size of the bounds of the probe in local coordinates.
+    pub fn set_size(&mut self, size: Vector3<f32>) -> Vector3<f32> {
+        self.size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns current size of the bounds of the probe in local coordinates.
+    pub fn size(&self) -> Vector3<f32> {
+        *self.size
+    }
+
+    /// Sets new projection of the reflections.
+    pub fn set_projection(
+        &mut self,
+        projection: ReflectionProbeProjection,
+    ) -> ReflectionProbeProjection {
+        self.projection.set_value_and_mark_modified(projection)
+    }
+
+    /// Returns current projection of the reflections.
+    pub fn projection(&self) -> ReflectionProbeProjection {
+        *self.projection
+    }
+
+    /// Sets new distance (in local coordinates) from the bounds of the probe, at which the
+    /// reflections fade out.
+    pub fn set_blend_distance(&mut self, distance: f32) -> f32 {
+        self.blend_distance
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns current distance from the bounds of the probe, at which the reflections fade out.
+    pub fn blend_distance(&self) -> f32 {
+        *self.blend_distance
+    }
+
+    /// Sets new size of each face of the cube map. Large values increase the quality of the
+    /// reflections, but require more memory and time to capture.
+    pub fn set_resolution(&mut self, resolution: u32) -> u32 {
+        self.need_update.set(true);
+        self.resolution
+            .set_value_and_mark_modified(resolution.clamp(16, 2048))
+    }
+
+    /// Returns current size of each face of the cube map.
+    pub fn resolution(&self) -> u32 {
+        *self.resolution
+    }
+
+    /// Sets new location of the near clipping plane of the capture.
+    pub fn set_z_near(&mut self, z_near: f32) -> f32 {
+        self.need_update.set(true);
+        self.z_near.set_value_and_mark_modified(z_near)
+    }
+
+    /// Returns current location of the near clipping plane of the capture.
+    pub fn z_near(&self) -> f32 {
+        *self.z_near
+    }
+
+    /// Sets new location of the far clipping plane of the capture.
+    pub fn set_z_far(&mut self, z_far: f32) -> f32 {
+        self.need_update.set(true);
+        self.z_far.set_value_and_mark_modified(z_far)
+    }
+
+    /// Returns current location of the far clipping plane of the capture.
+    pub fn z_far(&self) -> f32 {
+        *self.z_far
+    }
+
+    /// Sets new update mode of the probe.
+    pub fn set_update_mode(
+        &mut self,
+        update_mode: ReflectionProbeUpdateMode,
+    ) -> ReflectionProbeUpdateMode {
+        self.elapsed_time = 0.0;
+        self.update_mode.set_value_and_mark_modified(update_mode)
+    }
+
+    /// Returns current update mode of the probe.
+    pub fn update_mode(&self) -> ReflectionProbeUpdateMode {
+        *self.update_mode
+    }
+
+    /// Forces the probe to capture its surroundings on the next frame, regardless of its update
+    /// mode. It could be useful to refresh a baked probe after some changes in the environment.
+    pub fn force_update(&self) {
+        self.need_update.set(true);
+    }
+
+    /// Returns `true` if the probe will capture its surroundings on the next frame.
+    pub fn is_update_needed(&self) -> bool {
+        self.need_update.get()
+    }
+
+    /// Returns `true` if the probe needs to capture its surroundings and resets the flag.
+    pub(crate) fn take_update_request(&self) -> bool {
+        self.need_update.replace(false)
+    }
+
+    /// Creates six temporary cameras, that see the surroundings of the probe. The cameras are
+    /// created in the order of the cube map faces: +X, -X, +Y, -Y, +Z, -Z. Every other setting
+    /// (sky box, exposure, etc.) is taken from the given camera.
+    pub(crate) fn make_capture_cameras(&self, camera: &Camera) -> [Camera; 6] {
+        let position = self.global_position();
+        let z_near = *self.z_near;
+        let z_far = *self.z_far;
+        let projection_matrix =
+            Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, z_near, z_far);
+
+        CUBE_MAP_FACES.map(|(look, up)| {
+            let global_transform = Matrix4::from_columns(&[
+                up.cross(&look).push(0.0),
+                up.push(0.0),
+                look.push(0.0),
+                position.push(1.0),
+            ]);
+            let view_matrix =
+                Matrix4::look_at_rh(&Point3::from(position), &Point3::from(position + look), &up);
+
+            let mut capture_camera = camera.clone();
+            capture_camera.set_projection(Projection::Perspective(PerspectiveProjection {
+                fov: std::f32::consts::FRAC_PI_2,
+                z_near,
+                z_far,
+            }));
+            capture_camera.global_transform.set(global_transform);
+            capture_camera.set_matrices(view_matrix, projection_matrix);
+            capture_camera.set_render_target(None);
+            capture_camera
+        })
+    }
+}
+
+impl NodeTrait for ReflectionProbe {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_size = self.size.scale(0.5);
+        AxisAlignedBoundingBox::from_min_max(-half_size, half_size)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        // The cube map is captured at the position of the probe, so it must be captured again
+        // when the probe moves.
+        let position = self.global_position();
+        if self.capture_position != Some(position) {
+            self.capture_position = Some(position);
+            self.need_update.set(true);
+        }
+
+        if let ReflectionProbeUpdateMode::Periodic { interval } = *self.update_mode {
+            self.elapsed_time += context.dt;
+            if self.elapsed_time >= interval {
+                self.elapsed_time = 0.0;
+                self.need_update.set(true);
+            }
+        }
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &self.local_bounding_box(),
+            self.global_transform(),
+            Color::opaque(255, 200, 0),
+        );
+        ctx.draw_wire_sphere(self.global_position(), 0.1, 16, Color::opaque(255, 200, 0));
+    }
+}
+
+/// Allows you to create a reflection probe in declarative manner.
+pub struct ReflectionProbeBuilder {
+    base_builder: BaseBuilder,
+    size: Vector3<f32>,
+    projection: ReflectionProbeProjection,
+    blend_distance: f32,
+    resolution: u32,
+    z_near: f32,
+    z_far: f32,
+    update_mode: ReflectionProbeUpdateMode,
+}
+
+impl ReflectionProbeBuilder {
+    /// Creates new reflection probe builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            size: Vector3::new(10.0, 10.0, 10.0),
+            projection: Default::default(),
+            blend_distance: 1.0,
+            resolution: 128,
+            z_near: 0.1,
+            z_far: 100.0,
+            update_mode: Default::default(),
+        }
+    }
+
+    /// Sets desired size of the bounds of the probe.
+    pub fn with_size(mut self, size: Vector3<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets desired projection of the reflections.
+    pub fn with_projection(mut self, projection: ReflectionProbeProjection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Sets desired distance from the bounds of the probe, at which the reflections fade out.
+    pub fn with_blend_distance(mut self, distance: f32) -> Self {
+        self.blend_distance = distance.max(0.0);
+        self
+    }
+
+    /// Sets desired size of each face of the cube map.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution.clamp(16, 2048);
+        self
+    }
+
+    /// Sets desired location of the near clipping plane of the capture.
+    pub fn with_z_near(mut self, z_near: f32) -> Self {
+        self.z_near = z_near;
+        self
+    }
+
+    /// Sets desired location of the far clipping plane of the capture.
+    pub fn with_z_far(mut self, z_far: f32) -> Self {
+        self.z_far = z_far;
+        self
+    }
+
+    /// Sets desired update mode of the probe.
+    pub fn with_update_mode(mut self, update_mode: ReflectionProbeUpdateMode) -> Self {
+        self.update_mode = update_mode;
+        self
+    }
+
+    /// Creates new reflection probe instance.
+    pub fn build_reflection_probe(self) -> ReflectionProbe {
+        ReflectionProbe {
+            base: self.base_builder.build_base(),
+            size: self.size.into(),
+            projection: self.projection.into(),
+            blend_distance: self.blend_distance.into(),
+            resolution: self.resolution.into(),
+            z_near: self.z_near.into(),
+            z_far: self.z_far.into(),
+            update_mode: self.update_mode.into(),
+            need_update: Cell::new(true),
+            elapsed_time: 0.0,
+            capture_position: None,
+        }
+    }
+
+    /// Creates new reflection probe node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_reflection_probe())
+    }
+
+    /// Creates new reflection probe node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Point3, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            camera::CameraBuilder,
+            graph::Graph,
+            node::Node,
+            probe::{ReflectionProbe, ReflectionProbeBuilder, ReflectionProbeUpdateMode},
+            transform::TransformBuilder,
+        },
+    };
+
+    fn probe_ref(graph: &Graph, probe: Handle<Node>) -> &ReflectionProbe {
+        graph[probe].cast::<ReflectionProbe>().unwrap()
+    }
+
+    #[test]
+    fn test_capture_cameras() {
+        let mut graph = Graph::new();
+        let position = Vector3::new(1.0, 2.0, 3.0);
+        let probe = ReflectionProbeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        let camera = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.update(Vector2::new(100.0, 100.0), 0.0, Default::default());
+
+        let cameras = graph[probe]
+            .cast::<ReflectionProbe>()
+            .unwrap()
+            .make_capture_cameras(graph[camera].as_camera());
+
+        let directions = [
+            Vector3::x(),
+            -Vector3::x(),
+            Vector3::y(),
+            -Vector3::y(),
+            Vector3::z(),
+            -Vector3::z(),
+        ];
+        for (capture_camera, direction) in cameras.iter().zip(directions) {
+            assert_eq!(capture_camera.global_position(), position);
+            assert!((capture_camera.look_vector().normalize() - direction).norm() < 1.0e-5);
+
+            // A point in the direction of the face must be projected in the center of the face.
+            let clip = capture_camera.view_projection_matrix()
+                * Point3::from(position + direction.scale(10.0)).to_homogeneous();
+            let projected = clip.xyz().scale(1.0 / clip.w);
+            assert!(projected.xy().norm() < 1.0e-5);
+            assert!(projected.z > -1.0 && projected.z < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_update_requests() {
+        let mut graph = Graph::new();
+        let probe = ReflectionProbeBuilder::new(BaseBuilder::new())
+            .with_update_mode(ReflectionProbeUpdateMode::Periodic { interval: 1.0 })
+            .build(&mut graph);
+
+        // New probe must be captured on the first frame.
+        graph.update(Vector2::new(100.0, 100.0), 0.5, Default::default());
+        assert!(probe_ref(&graph, probe).take_update_request());
+        assert!(!probe_ref(&graph, probe).take_update_request());
+
+        graph.update(Vector2::new(100.0, 100.0), 0.6, Default::default());
+        assert!(probe_ref(&graph, probe).take_update_request());
+
+        // Moved probe must be captured again.
+        graph[probe]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 1.0, 0.0));
+        graph.update(Vector2::new(100.0, 100.0), 0.1, Default::default());
+        assert!(probe_ref(&graph, probe).take_update_request());
+
+        probe_ref(&graph, probe).force_update();
+        assert!(probe_ref(&graph, probe).is_update_needed());
+    }
+}
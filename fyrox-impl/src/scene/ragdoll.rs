@@ -34,14 +34,16 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
-    graph::BaseSceneGraph,
+    graph::{BaseSceneGraph, SceneGraph},
     impl_query_component,
     scene::{
         base::{Base, BaseBuilder},
-        collider::Collider,
+        collider::{Collider, ColliderBuilder, ColliderShape},
         graph::Graph,
+        joint::{BallJoint, JointBuilder, JointParams},
         node::{Node, NodeTrait, UpdateContext},
-        rigidbody::{RigidBody, RigidBodyType},
+        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        transform::TransformBuilder,
     },
 };
 use std::{
@@ -226,7 +228,43 @@ impl Limb {
 /// Manual creation of such ragdoll is very tedious and counterproductive. That's why the best way
 /// to create a ragdoll is to use the editor, and the ragdoll wizard in particular. However, if
 /// you're brave enough you can read this code <https://github.com/FyroxEngine/Fyrox/blob/master/editor/src/utils/ragdoll.rs> -
-/// it creates a ragdoll using a humanoid skeleton.  
+/// it creates a ragdoll using a humanoid skeleton.
+///
+/// A ragdoll could also be created at runtime from any skeleton using [`RagdollBuilder::build_from_skeleton`].
+/// It creates a rigid body with a capsule collider for every major bone (see [`SkeletonRagdollOptions`])
+/// and connects the bodies using ball joints.
+///
+/// ## Switching between animation and physics
+///
+/// Use [`Ragdoll::set_active`] to switch between animated (inactive) and physics-driven (active) pose.
+/// When the ragdoll is deactivated, the bones are blended from the last ragdoll pose to the animated
+/// pose over [`Ragdoll::blend_time`] seconds, so a character could smoothly get up after a fall. Keep
+/// in mind, that the blending expects the animation to be applied before the ragdoll is updated.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     graph::SceneGraph,
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         node::Node,
+/// #         ragdoll::{Ragdoll, RagdollBuilder, SkeletonRagdollOptions},
+/// #     },
+/// # };
+/// fn make_ragdoll(graph: &mut Graph, hips: Handle<Node>) -> Handle<Node> {
+///     RagdollBuilder::new(BaseBuilder::new().with_name("Ragdoll"))
+///         .with_active(false)
+///         .with_blend_time(0.5)
+///         .build_from_skeleton(graph, hips, &SkeletonRagdollOptions::default())
+/// }
+///
+/// fn on_death(graph: &mut Graph, ragdoll: Handle<Node>) {
+///     if let Some(ragdoll) = graph.try_get_mut_of_type::<Ragdoll>(ragdoll) {
+///         ragdoll.set_active(true);
+///     }
+/// }
+/// ```
 #[derive(Clone, Reflect, Visit, Debug, Default)]
 #[visit(optional)]
 pub struct Ragdoll {
@@ -243,8 +281,99 @@ pub struct Ragdoll {
     /// A flag, that defines whether the ragdoll will deactivate colliders when it is not active or not.
     /// This option could be useful if you want to disable physics of limbs while the ragdoll is active.
     pub deactivate_colliders: InheritableVariable<bool>,
+    /// Amount of time (in seconds) that is used to blend the bones from the ragdoll pose to the
+    /// animated pose, when the ragdoll is deactivated. Zero means instant switch.
+    #[reflect(min_value = 0.0)]
+    pub blend_time: InheritableVariable<f32>,
     #[reflect(hidden)]
     prev_enabled: bool,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    ragdoll_pose: Vec<BonePose>,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pose_blend: Option<PoseBlend>,
+}
+
+// Local position and rotation of a limb bone.
+type BonePose = (Handle<Node>, Vector3<f32>, UnitQuaternion<f32>);
+
+#[derive(Clone, Debug)]
+struct PoseBlend {
+    elapsed: f32,
+    // The last pose of the active ragdoll.
+    poses: Vec<BonePose>,
+}
+
+impl Ragdoll {
+    /// Activates or deactivates the ragdoll. Active ragdoll drives the bones using physics, inactive
+    /// ragdoll lets the bones to be animated. See [`Self::blend_time`] for more info.
+    pub fn set_active(&mut self, active: bool) {
+        self.is_active.set_value_and_mark_modified(active);
+    }
+
+    /// Returns `true` if the ragdoll is active (drives the bones using physics).
+    pub fn is_active(&self) -> bool {
+        *self.is_active
+    }
+
+    /// Sets the amount of time (in seconds) that is used to blend the bones from the ragdoll pose
+    /// to the animated pose, when the ragdoll is deactivated.
+    pub fn set_blend_time(&mut self, time: f32) -> f32 {
+        self.blend_time.set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns the amount of time (in seconds) that is used to blend the bones from the ragdoll pose
+    /// to the animated pose.
+    pub fn blend_time(&self) -> f32 {
+        *self.blend_time
+    }
+
+    /// Returns `true` if the ragdoll is blending its bones back to the animated pose.
+    pub fn is_blending(&self) -> bool {
+        self.pose_blend.is_some()
+    }
+
+    fn blend_to_animated_pose(&mut self, ctx: &mut UpdateContext) {
+        if *self.is_active {
+            self.pose_blend = None;
+            return;
+        }
+
+        let Some(pose_blend) = self.pose_blend.as_mut() else {
+            return;
+        };
+
+        pose_blend.elapsed += ctx.dt;
+        let t = if *self.blend_time > 0.0 {
+            (pose_blend.elapsed / *self.blend_time).min(1.0)
+        } else {
+            1.0
+        };
+
+        for (bone, position, rotation) in pose_blend.poses.iter() {
+            if let Some(bone) = ctx.nodes.try_borrow_mut(*bone) {
+                let transform = bone.local_transform_mut();
+                let animated_position = **transform.position();
+                let animated_rotation = **transform.rotation();
+                transform
+                    .set_position(position.lerp(&animated_position, t))
+                    .set_rotation(rotation.slerp(&animated_rotation, t));
+            }
+        }
+
+        if t >= 1.0 {
+            self.pose_blend = None;
+        }
+
+        Graph::update_hierarchical_data_recursively(
+            ctx.nodes,
+            ctx.sound_context,
+            ctx.physics,
+            ctx.physics2d,
+            self.root_limb.bone,
+        );
+    }
 }
 
 impl Deref for Ragdoll {
@@ -296,8 +425,17 @@ impl NodeTrait for Ragdoll {
                 new_ang_vel = Some(character_rigid_body.ang_vel());
             }
         }
+
+        if !*self.is_active && self.prev_enabled && !self.ragdoll_pose.is_empty() {
+            self.pose_blend = Some(PoseBlend {
+                elapsed: 0.0,
+                poses: std::mem::take(&mut self.ragdoll_pose),
+            });
+        }
         self.prev_enabled = *self.is_active;
 
+        self.blend_to_animated_pose(ctx);
+
         self.root_limb.iterate_recursive(&mut |limb| {
             let mbc = ctx.nodes.begin_multi_borrow();
 
@@ -411,6 +549,21 @@ impl NodeTrait for Ragdoll {
             }
         });
 
+        // Remember the ragdoll pose, so it can be blended with the animated pose on deactivation.
+        self.ragdoll_pose.clear();
+        if *self.is_active && *self.blend_time > 0.0 {
+            self.root_limb.iterate_recursive(&mut |limb| {
+                if let Some(bone) = ctx.nodes.try_borrow(limb.bone) {
+                    let transform = bone.local_transform();
+                    self.ragdoll_pose.push((
+                        limb.bone,
+                        **transform.position(),
+                        **transform.rotation(),
+                    ));
+                }
+            });
+        }
+
         if let Some(root_limb_body) = ctx.nodes.try_borrow(self.root_limb.bone) {
             let position = root_limb_body.global_position();
             if let Some(character_rigid_body) = ctx
@@ -433,6 +586,109 @@ impl NodeTrait for Ragdoll {
     }
 }
 
+/// Defines which bones of a skeleton will become limbs of a ragdoll and how the limbs will look
+/// like. See [`RagdollBuilder::build_from_skeleton`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkeletonRagdollOptions {
+    /// A set of case-insensitive name patterns. A bone becomes a limb if its name contains any of
+    /// the patterns. An empty set means that every bone with children will become a limb. The
+    /// default set of patterns covers major bones of the most common humanoid skeletons (hips,
+    /// spine, head, arms and legs).
+    pub bone_name_patterns: Vec<String>,
+    /// Radius of the capsule collider of a limb relative to the length of the limb.
+    pub radius_scale: f32,
+    /// Total mass of all limbs. The mass is distributed across limbs proportionally to their length.
+    pub total_mass: f32,
+    /// Maximum angle (in radians) of rotation of every joint around each axis. [`None`] means that
+    /// there's no limits.
+    pub joint_limit: Option<f32>,
+}
+
+impl Default for SkeletonRagdollOptions {
+    fn default() -> Self {
+        Self {
+            bone_name_patterns: [
+                "hips", "pelvis", "spine", "chest", "head", "arm", "thigh", "leg", "calf", "shin",
+            ]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+            radius_scale: 0.25,
+            total_mass: 70.0,
+            joint_limit: Some(std::f32::consts::FRAC_PI_4),
+        }
+    }
+}
+
+impl SkeletonRagdollOptions {
+    fn is_major_bone(&self, name: &str) -> bool {
+        if self.bone_name_patterns.is_empty() {
+            return true;
+        }
+        let name = name.to_lowercase();
+        self.bone_name_patterns
+            .iter()
+            .any(|pattern| name.contains(&pattern.to_lowercase()))
+    }
+}
+
+struct LimbSegment {
+    bone: Handle<Node>,
+    parent: Option<usize>,
+    begin: Vector3<f32>,
+    end: Vector3<f32>,
+}
+
+fn collect_limb_segments(
+    graph: &Graph,
+    bone: Handle<Node>,
+    mut parent: Option<usize>,
+    options: &SkeletonRagdollOptions,
+    segments: &mut Vec<LimbSegment>,
+) {
+    let Some(bone_ref) = graph.try_get(bone) else {
+        return;
+    };
+
+    if options.is_major_bone(bone_ref.name()) && !bone_ref.children().is_empty() {
+        // A limb spans from the bone to the center of its children.
+        let begin = bone_ref.global_position();
+        let end = bone_ref
+            .children()
+            .iter()
+            .map(|child| graph[*child].global_position())
+            .sum::<Vector3<f32>>()
+            .scale(1.0 / bone_ref.children().len() as f32);
+
+        if begin.metric_distance(&end) > f32::EPSILON {
+            segments.push(LimbSegment {
+                bone,
+                parent,
+                begin,
+                end,
+            });
+            parent = Some(segments.len() - 1);
+        }
+    }
+
+    for child in bone_ref.children() {
+        collect_limb_segments(graph, *child, parent, options, segments);
+    }
+}
+
+fn make_limb(index: usize, segments: &[LimbSegment], bodies: &[Handle<Node>]) -> Limb {
+    Limb {
+        bone: segments[index].bone,
+        physical_bone: bodies[index],
+        children: segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.parent == Some(index))
+            .map(|(child_index, _)| make_limb(child_index, segments, bodies))
+            .collect(),
+    }
+}
+
 /// Ragdoll builder creates [`Ragdoll`] scene nodes.
 pub struct RagdollBuilder {
     base_builder: BaseBuilder,
@@ -440,6 +696,7 @@ pub struct RagdollBuilder {
     is_active: bool,
     deactivate_colliders: bool,
     root_limb: Limb,
+    blend_time: f32,
 }
 
 impl RagdollBuilder {
@@ -451,6 +708,7 @@ impl RagdollBuilder {
             is_active: true,
             deactivate_colliders: false,
             root_limb: Default::default(),
+            blend_time: 0.0,
         }
     }
 
@@ -478,6 +736,13 @@ impl RagdollBuilder {
         self
     }
 
+    /// Sets the desired amount of time (in seconds) that is used to blend the bones from the ragdoll
+    /// pose to the animated pose.
+    pub fn with_blend_time(mut self, time: f32) -> Self {
+        self.blend_time = time.max(0.0);
+        self
+    }
+
     /// Builds the ragdoll.
     pub fn build_ragdoll(self) -> Ragdoll {
         Ragdoll {
@@ -486,7 +751,10 @@ impl RagdollBuilder {
             is_active: self.is_active.into(),
             root_limb: self.root_limb.into(),
             deactivate_colliders: self.deactivate_colliders.into(),
+            blend_time: self.blend_time.into(),
             prev_enabled: self.is_active,
+            ragdoll_pose: Default::default(),
+            pose_blend: None,
         }
     }
 
@@ -499,4 +767,219 @@ impl RagdollBuilder {
     pub fn build(self, graph: &mut Graph) -> Handle<Node> {
         graph.add_node(self.build_node())
     }
+
+    /// Creates the ragdoll node and automatically creates its limbs using the skeleton starting from the
+    /// given root bone (usually hips). Every major bone (see [`SkeletonRagdollOptions`]) gets a rigid body
+    /// with a capsule collider that spans from the bone to its children, and every body is connected to
+    /// the body of its parent limb using a ball joint. The bodies and joints are linked to the ragdoll
+    /// node. Root limb of the builder is ignored.
+    pub fn build_from_skeleton(
+        self,
+        graph: &mut Graph,
+        root_bone: Handle<Node>,
+        options: &SkeletonRagdollOptions,
+    ) -> Handle<Node> {
+        graph.update_hierarchical_data();
+
+        let mut segments = Vec::new();
+        collect_limb_segments(graph, root_bone, None, options, &mut segments);
+
+        let ragdoll = self.build(graph);
+        let ragdoll_transform_inv = graph[ragdoll]
+            .global_transform()
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity);
+
+        let total_length = segments
+            .iter()
+            .map(|segment| segment.begin.metric_distance(&segment.end))
+            .sum::<f32>()
+            .max(f32::EPSILON);
+
+        let mut bodies = Vec::with_capacity(segments.len());
+        for segment in segments.iter() {
+            let bone_ref = &graph[segment.bone];
+            let name = bone_ref.name().to_owned();
+            let bone_rotation = UnitQuaternion::from_matrix_eps(
+                &bone_ref.global_transform().basis(),
+                f32::EPSILON,
+                16,
+                Default::default(),
+            );
+            let relative_transform = ragdoll_transform_inv * bone_ref.global_transform();
+
+            // Collider is defined in local coordinates of the body, which is oriented as the bone.
+            let delta = segment.end - segment.begin;
+            let length = delta.norm();
+            let direction = bone_rotation.inverse_transform_vector(&delta.scale(1.0 / length));
+            let radius = (length * options.radius_scale).min(length * 0.5);
+
+            let collider =
+                ColliderBuilder::new(BaseBuilder::new().with_name(format!("{name}Collider")))
+                    .with_shape(ColliderShape::capsule(
+                        direction.scale(radius),
+                        direction.scale(length - radius),
+                        radius,
+                    ))
+                    .build(graph);
+
+            let body = RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("{name}Body"))
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(relative_transform.position())
+                            .with_local_rotation(UnitQuaternion::from_matrix_eps(
+                                &relative_transform.basis(),
+                                f32::EPSILON,
+                                16,
+                                Default::default(),
+                            ))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_mass(options.total_mass * length / total_length)
+            .with_body_type(RigidBodyType::KinematicPositionBased)
+            .build(graph);
+
+            graph.link_nodes(body, ragdoll);
+
+            if let Some(parent) = segment.parent {
+                let mut joint = BallJoint::default();
+                if let Some(limit) = options.joint_limit {
+                    joint.x_limits_enabled = true;
+                    joint.y_limits_enabled = true;
+                    joint.z_limits_enabled = true;
+                    joint.x_limits_angles = -limit..limit;
+                    joint.y_limits_angles = -limit..limit;
+                    joint.z_limits_angles = -limit..limit;
+                }
+
+                let joint = JointBuilder::new(
+                    BaseBuilder::new()
+                        .with_name(format!("{name}Joint"))
+                        .with_local_transform(
+                            TransformBuilder::new()
+                                .with_local_position(relative_transform.position())
+                                .build(),
+                        ),
+                )
+                .with_params(JointParams::BallJoint(joint))
+                .with_body1(bodies[parent])
+                .with_body2(body)
+                .with_auto_rebinding_enabled(false)
+                .with_contacts_enabled(false)
+                .build(graph);
+
+                graph.link_nodes(joint, ragdoll);
+            }
+
+            bodies.push(body);
+        }
+
+        let mut roots = segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.parent.is_none())
+            .map(|(index, _)| make_limb(index, &segments, &bodies))
+            .collect::<Vec<_>>();
+
+        let root_limb = if roots.len() == 1 {
+            roots.pop().unwrap()
+        } else {
+            Limb {
+                children: roots,
+                ..Default::default()
+            }
+        };
+
+        if let Some(ragdoll) = graph.try_get_mut_of_type::<Ragdoll>(ragdoll) {
+            ragdoll.root_limb.set_value_and_mark_modified(root_limb);
+        }
+
+        ragdoll
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        graph::{BaseSceneGraph, SceneGraph},
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            joint::Joint,
+            node::Node,
+            pivot::PivotBuilder,
+            ragdoll::{Ragdoll, RagdollBuilder, SkeletonRagdollOptions},
+            rigidbody::RigidBody,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn make_bone(graph: &mut Graph, name: &str, position: Vector3<f32>) -> Handle<Node> {
+        PivotBuilder::new(
+            BaseBuilder::new().with_name(name).with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(graph)
+    }
+
+    #[test]
+    fn test_ragdoll_from_skeleton() {
+        let mut graph = Graph::new();
+
+        let hips = make_bone(&mut graph, "Hips", Vector3::new(0.0, 1.0, 0.0));
+        let spine = make_bone(&mut graph, "Spine", Vector3::new(0.0, 0.5, 0.0));
+        let head = make_bone(&mut graph, "Head", Vector3::new(0.0, 0.3, 0.0));
+        let head_end = make_bone(&mut graph, "HeadTop_End", Vector3::new(0.0, 0.2, 0.0));
+        let up_leg = make_bone(&mut graph, "LeftUpLeg", Vector3::new(0.2, 0.0, 0.0));
+        let leg = make_bone(&mut graph, "LeftLeg", Vector3::new(0.0, -0.5, 0.0));
+        let foot = make_bone(&mut graph, "LeftFoot", Vector3::new(0.0, -0.5, 0.0));
+        let toe = make_bone(&mut graph, "LeftToe", Vector3::new(0.0, 0.0, 0.1));
+        for (child, parent) in [
+            (spine, hips),
+            (head, spine),
+            (head_end, head),
+            (up_leg, hips),
+            (leg, up_leg),
+            (foot, leg),
+            (toe, foot),
+        ] {
+            graph.link_nodes(child, parent);
+        }
+
+        let ragdoll = RagdollBuilder::new(BaseBuilder::new()).build_from_skeleton(
+            &mut graph,
+            hips,
+            &SkeletonRagdollOptions::default(),
+        );
+
+        let ragdoll_ref = graph.try_get_of_type::<Ragdoll>(ragdoll).unwrap();
+        let root_limb = &*ragdoll_ref.root_limb;
+        assert_eq!(root_limb.bone, hips);
+        assert_eq!(root_limb.children.len(), 2);
+
+        let mut limb_bones = Vec::new();
+        root_limb.iterate_recursive(&mut |limb| {
+            assert!(graph
+                .try_get_of_type::<RigidBody>(limb.physical_bone)
+                .is_some());
+            limb_bones.push(limb.bone);
+        });
+        // The foot is not a major bone, the end of the head has no children.
+        assert_eq!(limb_bones, vec![hips, spine, head, up_leg, leg]);
+
+        let joints = graph[ragdoll]
+            .children()
+            .iter()
+            .filter(|child| graph.try_get_of_type::<Joint>(**child).is_some())
+            .count();
+        assert_eq!(joints, 4);
+    }
 }
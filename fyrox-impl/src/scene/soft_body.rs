@@ -0,0 +1,888 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Soft body is a deformable object (jelly, inflatable toy, etc.) simulated using position-based
+//! dynamics. See [`SoftBody`] docs for more info and usage examples.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    graph::BaseSceneGraph,
+    impl_query_component,
+    renderer::{self, bundle::RenderContext},
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::InteractionGroups,
+        debug::{Line, SceneDrawingContext},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait, VertexWriteTrait},
+            surface::Surface,
+            RenderPath,
+        },
+        node::{Node, NodeTrait, RdcControlFlow, UpdateContext},
+    },
+};
+use fxhash::FxHashMap;
+use std::ops::{Deref, DerefMut};
+
+// Vertices closer than this distance are merged into a single particle.
+const WELD_PRECISION: f32 = 1.0e-4;
+
+#[derive(Clone, Debug, Default)]
+struct DistanceConstraint {
+    a: usize,
+    b: usize,
+    rest_length: f32,
+}
+
+/// Simulation state of a soft body, everything is in world space.
+#[derive(Clone, Debug, Default)]
+struct SoftBodyState {
+    positions: Vec<Vector3<f32>>,
+    prev_positions: Vec<Vector3<f32>>,
+    // Maps every vertex of the surface to a particle.
+    vertex_to_particle: Vec<usize>,
+    triangles: Vec<[usize; 3]>,
+    edges: Vec<DistanceConstraint>,
+    rest_volume: f32,
+}
+
+struct SimulationParameters {
+    stiffness: f32,
+    pressure: f32,
+    damping: f32,
+}
+
+impl SoftBodyState {
+    fn new(
+        vertices: &[Vector3<f32>],
+        triangles: &[TriangleDefinition],
+        transform: &Matrix4<f32>,
+    ) -> Self {
+        let mut positions = Vec::new();
+        let mut particle_map = FxHashMap::default();
+        let vertex_to_particle = vertices
+            .iter()
+            .map(|vertex| {
+                let position = transform.transform_point(&(*vertex).into()).coords;
+                let key = vertex.map(|c| (c / WELD_PRECISION).round() as i64);
+                *particle_map.entry(key).or_insert_with(|| {
+                    positions.push(position);
+                    positions.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let triangles = triangles
+            .iter()
+            .filter_map(|triangle| {
+                let [a, b, c] = triangle
+                    .0
+                    .map(|i| vertex_to_particle.get(i as usize).copied());
+                let triangle = [a?, b?, c?];
+                // Skip triangles that are degenerate after welding.
+                (triangle[0] != triangle[1]
+                    && triangle[1] != triangle[2]
+                    && triangle[2] != triangle[0])
+                    .then_some(triangle)
+            })
+            .collect::<Vec<_>>();
+
+        let mut edge_set = FxHashMap::default();
+        for triangle in triangles.iter() {
+            for (a, b) in [
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                edge_set
+                    .entry((a.min(b), a.max(b)))
+                    .or_insert_with(|| DistanceConstraint {
+                        a,
+                        b,
+                        rest_length: positions[a].metric_distance(&positions[b]),
+                    });
+            }
+        }
+        let mut edges = edge_set.into_values().collect::<Vec<_>>();
+        // Make the simulation deterministic.
+        edges.sort_by_key(|edge| (edge.a, edge.b));
+
+        let rest_volume = volume(&positions, &triangles);
+
+        Self {
+            prev_positions: positions.clone(),
+            positions,
+            vertex_to_particle,
+            triangles,
+            edges,
+            rest_volume,
+        }
+    }
+
+    fn integrate(&mut self, dt: f32, gravity: Vector3<f32>, damping: f32) {
+        let damping = (1.0 - damping * dt).clamp(0.0, 1.0);
+        for (position, prev_position) in self
+            .positions
+            .iter_mut()
+            .zip(self.prev_positions.iter_mut())
+        {
+            let velocity = (*position - *prev_position).scale(damping);
+            *prev_position = *position;
+            *position += velocity + gravity.scale(dt * dt);
+        }
+    }
+
+    fn solve_constraints(&mut self, parameters: &SimulationParameters) {
+        for edge in self.edges.iter() {
+            let delta = self.positions[edge.b] - self.positions[edge.a];
+            let length = delta.norm();
+            if length <= f32::EPSILON {
+                continue;
+            }
+            // Both particles have the same mass, so the correction is split equally.
+            let correction =
+                delta.scale((length - edge.rest_length) / length * 0.5 * parameters.stiffness);
+            self.positions[edge.a] += correction;
+            self.positions[edge.b] -= correction;
+        }
+
+        if parameters.pressure > 0.0 && self.rest_volume.abs() > f32::EPSILON {
+            let mut gradients = vec![Vector3::<f32>::zeros(); self.positions.len()];
+            for &[a, b, c] in self.triangles.iter() {
+                let (pa, pb, pc) = (self.positions[a], self.positions[b], self.positions[c]);
+                gradients[a] += pb.cross(&pc).scale(1.0 / 6.0);
+                gradients[b] += pc.cross(&pa).scale(1.0 / 6.0);
+                gradients[c] += pa.cross(&pb).scale(1.0 / 6.0);
+            }
+
+            let denominator = gradients.iter().map(|g| g.norm_squared()).sum::<f32>();
+            if denominator > f32::EPSILON {
+                let error = volume(&self.positions, &self.triangles)
+                    - self.rest_volume * parameters.pressure;
+                let lambda = -error / denominator;
+                for (position, gradient) in self.positions.iter_mut().zip(gradients.iter()) {
+                    *position += gradient.scale(lambda);
+                }
+            }
+        }
+    }
+
+    fn solve_collision(
+        &mut self,
+        index: usize,
+        projection: Vector3<f32>,
+        is_inside: bool,
+        radius: f32,
+        friction: f32,
+    ) {
+        let position = self.positions[index];
+        let delta = position - projection;
+        let distance = delta.norm();
+        if distance <= f32::EPSILON {
+            return;
+        }
+
+        let (normal, penetration) = if is_inside {
+            (-delta.scale(1.0 / distance), distance + radius)
+        } else if distance < radius {
+            (delta.scale(1.0 / distance), radius - distance)
+        } else {
+            return;
+        };
+
+        let mut new_position = position + normal.scale(penetration);
+
+        // Cancel out a part of the tangential motion to simulate friction.
+        let displacement = new_position - self.prev_positions[index];
+        let tangential = displacement - normal.scale(displacement.dot(&normal));
+        new_position -= tangential.scale(friction.clamp(0.0, 1.0));
+
+        self.positions[index] = new_position;
+    }
+
+    fn particle_normals(&self) -> Vec<Vector3<f32>> {
+        let mut normals = vec![Vector3::<f32>::zeros(); self.positions.len()];
+        for &[a, b, c] in self.triangles.iter() {
+            // Area-weighted face normal.
+            let normal = (self.positions[b] - self.positions[a])
+                .cross(&(self.positions[c] - self.positions[a]));
+            normals[a] += normal;
+            normals[b] += normal;
+            normals[c] += normal;
+        }
+        for normal in normals.iter_mut() {
+            *normal = normal
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::y);
+        }
+        normals
+    }
+}
+
+fn volume(positions: &[Vector3<f32>], triangles: &[[usize; 3]]) -> f32 {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| positions[a].cross(&positions[b]).dot(&positions[c]))
+        .sum::<f32>()
+        / 6.0
+}
+
+/// Soft body is a deformable object (jelly, inflatable toy, etc.) simulated using position-based
+/// dynamics (PBD). The body is built from a surface: every unique vertex position of the surface
+/// becomes a particle and every edge of the triangles becomes a distance constraint between two
+/// particles. Closed surfaces could also keep (or change) their volume, which makes them behave
+/// like inflatable objects.
+///
+/// ## Simulation
+///
+/// The simulation is done in world space. The body is placed at the position of the node on the
+/// first update, after that the transform of the node is ignored - use [`SoftBody::reset`] to
+/// move the body to the node again. The simulation state is not saved, so the body will return to
+/// its rest shape after loading a scene.
+///
+/// [`SoftBody::stiffness`] defines how well the body keeps its shape, [`SoftBody::pressure`] is a
+/// multiplier of the rest volume of the body that the body tries to keep (zero disables volume
+/// preservation, values greater than one inflate the body). [`SoftBody::substeps`] defines how many
+/// times the simulation is performed per update, more substeps means stiffer and more stable body.
+///
+/// ## Collisions
+///
+/// The particles collide with static geometry only - colliders that are not attached to dynamic
+/// or kinematic rigid bodies. Every particle is a sphere of [`SoftBody::collision_radius`],
+/// [`SoftBody::friction`] defines how much tangential motion is lost on contact.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     asset::untyped::ResourceKind,
+/// #     core::{algebra::Matrix4, pool::Handle},
+/// #     scene::{
+/// #         base::BaseBuilder,
+/// #         graph::Graph,
+/// #         mesh::surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+/// #         node::Node,
+/// #         soft_body::SoftBodyBuilder,
+/// #     },
+/// # };
+/// fn make_jelly(graph: &mut Graph) -> Handle<Node> {
+///     SoftBodyBuilder::new(BaseBuilder::new().with_name("Jelly"))
+///         .with_surface(
+///             SurfaceBuilder::new(SurfaceResource::new_ok(
+///                 ResourceKind::Embedded,
+///                 SurfaceData::make_sphere(8, 8, 0.5, &Matrix4::identity()),
+///             ))
+///             .build(),
+///         )
+///         .with_stiffness(0.5)
+///         .with_pressure(1.0)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Clone, Reflect, Visit, Debug)]
+#[visit(optional)]
+pub struct SoftBody {
+    base: Base,
+
+    /// A surface, that defines the rest shape of the body and its material.
+    pub surface: InheritableVariable<Surface>,
+
+    /// Stiffness of the distance constraints in `[0; 1]` range.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub stiffness: InheritableVariable<f32>,
+
+    /// A multiplier of the rest volume, that the body tries to keep. Zero disables volume
+    /// preservation.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub pressure: InheritableVariable<f32>,
+
+    /// Defines how fast the particles lose their velocity.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub damping: InheritableVariable<f32>,
+
+    /// Amount of the simulation steps per update.
+    #[reflect(min_value = 1.0, max_value = 64.0)]
+    pub substeps: InheritableVariable<u32>,
+
+    /// Radius of the particles, that is used for collisions with static geometry.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub collision_radius: InheritableVariable<f32>,
+
+    /// Friction coefficient in `[0; 1]` range, that defines how much tangential motion of a
+    /// particle is lost on contact with static geometry.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub friction: InheritableVariable<f32>,
+
+    /// Collision groups, that are used to filter static geometry.
+    pub collision_groups: InheritableVariable<InteractionGroups>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    state: Option<SoftBodyState>,
+}
+
+impl Default for SoftBody {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            surface: Default::default(),
+            stiffness: 0.5.into(),
+            pressure: 1.0.into(),
+            damping: 0.5.into(),
+            substeps: 8.into(),
+            collision_radius: 0.02.into(),
+            friction: 0.5.into(),
+            collision_groups: Default::default(),
+            state: None,
+        }
+    }
+}
+
+impl Deref for SoftBody {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for SoftBody {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for SoftBody {
+    fn type_uuid() -> Uuid {
+        uuid!("12ac4455-3b67-47b0-a771-38911500bec0")
+    }
+}
+
+impl SoftBody {
+    /// Sets a new surface of the body. The body will be reset to the new rest shape.
+    pub fn set_surface(&mut self, surface: Surface) -> Surface {
+        self.reset();
+        self.surface.set_value_and_mark_modified(surface)
+    }
+
+    /// Returns the surface of the body.
+    pub fn surface(&self) -> &Surface {
+        &self.surface
+    }
+
+    /// Sets stiffness of the body in `[0; 1]` range.
+    pub fn set_stiffness(&mut self, stiffness: f32) -> f32 {
+        self.stiffness
+            .set_value_and_mark_modified(stiffness.clamp(0.0, 1.0))
+    }
+
+    /// Returns stiffness of the body.
+    pub fn stiffness(&self) -> f32 {
+        *self.stiffness
+    }
+
+    /// Sets a multiplier of the rest volume, that the body tries to keep.
+    pub fn set_pressure(&mut self, pressure: f32) -> f32 {
+        self.pressure.set_value_and_mark_modified(pressure.max(0.0))
+    }
+
+    /// Returns a multiplier of the rest volume, that the body tries to keep.
+    pub fn pressure(&self) -> f32 {
+        *self.pressure
+    }
+
+    /// Sets damping of the body.
+    pub fn set_damping(&mut self, damping: f32) -> f32 {
+        self.damping.set_value_and_mark_modified(damping.max(0.0))
+    }
+
+    /// Returns damping of the body.
+    pub fn damping(&self) -> f32 {
+        *self.damping
+    }
+
+    /// Sets amount of the simulation steps per update.
+    pub fn set_substeps(&mut self, substeps: u32) -> u32 {
+        self.substeps.set_value_and_mark_modified(substeps.max(1))
+    }
+
+    /// Returns amount of the simulation steps per update.
+    pub fn substeps(&self) -> u32 {
+        *self.substeps
+    }
+
+    /// Sets radius of the particles, that is used for collisions with static geometry.
+    pub fn set_collision_radius(&mut self, radius: f32) -> f32 {
+        self.collision_radius
+            .set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns radius of the particles, that is used for collisions with static geometry.
+    pub fn collision_radius(&self) -> f32 {
+        *self.collision_radius
+    }
+
+    /// Sets friction coefficient in `[0; 1]` range.
+    pub fn set_friction(&mut self, friction: f32) -> f32 {
+        self.friction
+            .set_value_and_mark_modified(friction.clamp(0.0, 1.0))
+    }
+
+    /// Returns friction coefficient.
+    pub fn friction(&self) -> f32 {
+        *self.friction
+    }
+
+    /// Sets collision groups, that are used to filter static geometry.
+    pub fn set_collision_groups(&mut self, groups: InteractionGroups) -> InteractionGroups {
+        self.collision_groups.set_value_and_mark_modified(groups)
+    }
+
+    /// Returns collision groups, that are used to filter static geometry.
+    pub fn collision_groups(&self) -> InteractionGroups {
+        *self.collision_groups
+    }
+
+    /// Resets the body to its rest shape at the current position of the node. The reset is done on
+    /// the next update.
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    /// Returns `true` if the body was placed in the world and is being simulated.
+    pub fn is_simulated(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Returns world-space positions of the particles of the body. The slice is empty until the
+    /// body is simulated.
+    pub fn particles(&self) -> &[Vector3<f32>] {
+        self.state
+            .as_ref()
+            .map(|state| state.positions.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns current volume of the body (zero if the body is not simulated).
+    pub fn volume(&self) -> f32 {
+        self.state
+            .as_ref()
+            .map(|state| volume(&state.positions, &state.triangles))
+            .unwrap_or_default()
+    }
+
+    /// Returns rest volume of the body (zero if the body is not simulated).
+    pub fn rest_volume(&self) -> f32 {
+        self.state
+            .as_ref()
+            .map(|state| state.rest_volume)
+            .unwrap_or_default()
+    }
+
+    /// Moves every particle by the given offset without changing their velocities. Could be used to
+    /// teleport the body.
+    pub fn translate(&mut self, offset: Vector3<f32>) {
+        if let Some(state) = self.state.as_mut() {
+            for (position, prev_position) in state
+                .positions
+                .iter_mut()
+                .zip(state.prev_positions.iter_mut())
+            {
+                *position += offset;
+                *prev_position += offset;
+            }
+        }
+    }
+
+    /// Adds the given velocity to every particle of the body. Could be used to push the body.
+    pub fn apply_velocity(&mut self, velocity: Vector3<f32>, dt: f32) {
+        if let Some(state) = self.state.as_mut() {
+            for prev_position in state.prev_positions.iter_mut() {
+                *prev_position -= velocity.scale(dt);
+            }
+        }
+    }
+
+    fn make_state(&self) -> SoftBodyState {
+        let data = self.surface.data_ref().data_ref();
+        let vertices = data
+            .vertex_buffer
+            .iter()
+            .map(|vertex| {
+                vertex
+                    .read_3_f32(VertexAttributeUsage::Position)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        SoftBodyState::new(
+            &vertices,
+            data.geometry_buffer.triangles_ref(),
+            &self.global_transform(),
+        )
+    }
+}
+
+impl NodeTrait for SoftBody {
+    impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let mut bounding_box = AxisAlignedBoundingBox::default();
+        let data = self.surface.data_ref().data_ref();
+        for vertex in data.vertex_buffer.iter() {
+            if let Ok(position) = vertex.read_3_f32(VertexAttributeUsage::Position) {
+                bounding_box.add_point(position);
+            }
+        }
+        bounding_box
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        match self.state.as_ref() {
+            Some(state) => {
+                let mut bounding_box = AxisAlignedBoundingBox::default();
+                for position in state.positions.iter() {
+                    bounding_box.add_point(*position);
+                }
+                bounding_box.inflate(Vector3::repeat(*self.collision_radius));
+                bounding_box
+            }
+            None => self
+                .local_bounding_box()
+                .transform(&self.global_transform()),
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if !self.should_be_rendered(ctx.frustum) {
+            return RdcControlFlow::Continue;
+        }
+
+        if renderer::is_shadow_pass(ctx.render_pass_name) && !self.cast_shadows() {
+            return RdcControlFlow::Continue;
+        }
+
+        let data = self.surface.data_ref().data_ref();
+        let global_transform = self.global_transform();
+        let state = self.state.as_ref();
+        let normals = state.map(|state| state.particle_normals());
+
+        ctx.storage.push_triangles(
+            &data.vertex_buffer.layout_descriptor().collect::<Vec<_>>(),
+            self.surface.material(),
+            RenderPath::Deferred,
+            0,
+            self.handle(),
+            &mut |mut vertex_buffer, mut triangle_buffer| {
+                let start_vertex_index = vertex_buffer.vertex_count();
+
+                for (i, vertex) in data.vertex_buffer.iter().enumerate() {
+                    let bytes = vertex.transform(&mut |mut vertex| {
+                        let (position, normal) = match (state, normals.as_ref()) {
+                            (Some(state), Some(normals)) => {
+                                let particle = state.vertex_to_particle[i];
+                                (state.positions[particle], normals[particle])
+                            }
+                            _ => {
+                                let position = vertex
+                                    .read_3_f32(VertexAttributeUsage::Position)
+                                    .unwrap_or_default();
+                                let normal = vertex
+                                    .read_3_f32(VertexAttributeUsage::Normal)
+                                    .unwrap_or_default();
+                                (
+                                    global_transform.transform_point(&position.into()).coords,
+                                    global_transform.transform_vector(&normal),
+                                )
+                            }
+                        };
+                        let _ = vertex.write_3_f32(VertexAttributeUsage::Position, position);
+                        let _ = vertex.write_3_f32(VertexAttributeUsage::Normal, normal);
+                    });
+                    vertex_buffer.push_vertex_raw(&bytes).unwrap();
+                }
+
+                triangle_buffer.push_triangles_with_offset(
+                    start_vertex_index,
+                    data.geometry_buffer.triangles_ref(),
+                )
+            },
+        );
+
+        RdcControlFlow::Continue
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        if let Some(state) = self.state.as_ref() {
+            for edge in state.edges.iter() {
+                ctx.add_line(Line {
+                    begin: state.positions[edge.a],
+                    end: state.positions[edge.b],
+                    color: Color::opaque(255, 0, 255),
+                });
+            }
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if self.state.is_none() {
+            self.state = Some(self.make_state());
+        }
+
+        let parameters = SimulationParameters {
+            stiffness: *self.stiffness,
+            pressure: *self.pressure,
+            damping: *self.damping,
+        };
+        let substeps = (*self.substeps).max(1);
+        let dt = ctx.dt / substeps as f32;
+        let gravity = *ctx.physics.gravity;
+        let radius = *self.collision_radius;
+        let friction = *self.friction;
+        let groups = *self.collision_groups;
+
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        let mut contacts = Vec::new();
+        for _ in 0..substeps {
+            state.integrate(dt, gravity, parameters.damping);
+            state.solve_constraints(&parameters);
+
+            contacts.clear();
+            ctx.physics.project_points_on_fixed_colliders(
+                &state.positions,
+                groups,
+                |index, projection, is_inside| contacts.push((index, projection, is_inside)),
+            );
+            for (index, projection, is_inside) in contacts.iter() {
+                state.solve_collision(*index, projection.coords, *is_inside, radius, friction);
+            }
+        }
+    }
+}
+
+/// Allows you to create [`SoftBody`] scene nodes.
+pub struct SoftBodyBuilder {
+    base_builder: BaseBuilder,
+    surface: Surface,
+    stiffness: f32,
+    pressure: f32,
+    damping: f32,
+    substeps: u32,
+    collision_radius: f32,
+    friction: f32,
+    collision_groups: InteractionGroups,
+}
+
+impl SoftBodyBuilder {
+    /// Creates new soft body builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            surface: Default::default(),
+            stiffness: 0.5,
+            pressure: 1.0,
+            damping: 0.5,
+            substeps: 8,
+            collision_radius: 0.02,
+            friction: 0.5,
+            collision_groups: Default::default(),
+        }
+    }
+
+    /// Sets the surface, that defines the rest shape of the body and its material.
+    pub fn with_surface(mut self, surface: Surface) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Sets desired stiffness of the body.
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Sets desired pressure of the body.
+    pub fn with_pressure(mut self, pressure: f32) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    /// Sets desired damping of the body.
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets desired amount of the simulation steps per update.
+    pub fn with_substeps(mut self, substeps: u32) -> Self {
+        self.substeps = substeps;
+        self
+    }
+
+    /// Sets desired collision radius of the particles.
+    pub fn with_collision_radius(mut self, radius: f32) -> Self {
+        self.collision_radius = radius;
+        self
+    }
+
+    /// Sets desired friction coefficient.
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// Sets desired collision groups.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Creates new soft body.
+    pub fn build_soft_body(self) -> SoftBody {
+        SoftBody {
+            base: self.base_builder.build_base(),
+            surface: self.surface.into(),
+            stiffness: self.stiffness.clamp(0.0, 1.0).into(),
+            pressure: self.pressure.max(0.0).into(),
+            damping: self.damping.max(0.0).into(),
+            substeps: self.substeps.max(1).into(),
+            collision_radius: self.collision_radius.max(0.0).into(),
+            friction: self.friction.clamp(0.0, 1.0).into(),
+            collision_groups: self.collision_groups.into(),
+            state: None,
+        }
+    }
+
+    /// Creates new soft body node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_soft_body())
+    }
+
+    /// Creates new soft body node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::{Matrix4, Vector2, Vector3},
+        graph::SceneGraph,
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            mesh::surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            soft_body::{SoftBody, SoftBodyBuilder},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_welding() {
+        let mut graph = Graph::new();
+        let body = SoftBodyBuilder::new(BaseBuilder::new())
+            .with_surface(
+                SurfaceBuilder::new(SurfaceResource::new_ok(
+                    ResourceKind::Embedded,
+                    SurfaceData::make_cube(Matrix4::identity()),
+                ))
+                .build(),
+            )
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let body = graph.try_get_of_type::<SoftBody>(body).unwrap();
+        // Split vertices of the faces of the cube must be merged into 8 particles.
+        assert_eq!(body.particles().len(), 8);
+        assert!((body.rest_volume() - 1.0).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_collision_with_static_geometry() {
+        let mut graph = Graph::new();
+
+        let ground_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(10.0, 0.5, 10.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[ground_collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let body = SoftBodyBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_surface(
+            SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_sphere(8, 8, 0.5, &Matrix4::identity()),
+            ))
+            .build(),
+        )
+        .with_collision_radius(0.01)
+        .build(&mut graph);
+
+        for _ in 0..240 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let body = graph.try_get_of_type::<SoftBody>(body).unwrap();
+        // The body must rest on the ground instead of falling through it.
+        for particle in body.particles() {
+            assert!(particle.y > -0.05);
+        }
+        assert!(body.particles().iter().any(|p| p.y < 0.1));
+        // The volume must be preserved reasonably well.
+        assert!(body.volume() > body.rest_volume() * 0.5);
+    }
+}
@@ -26,7 +26,11 @@ use crate::{
         pool::Handle,
         visitor::prelude::*,
     },
-    scene::{node::Node, sound::Sound},
+    scene::{
+        graph::{physics::PhysicsWorld, NodePool},
+        node::Node,
+        sound::{occlusion::SoundOcclusion, Sound},
+    },
 };
 use fxhash::FxHashSet;
 use fyrox_sound::{
@@ -42,6 +46,8 @@ use std::{sync::MutexGuard, time::Duration};
 pub struct SoundContext {
     #[visit(optional)]
     pub(crate) native: fyrox_sound::context::SoundContext,
+    #[visit(optional)]
+    occlusion: SoundOcclusion,
 }
 
 /// Proxy for guarded access to the sound context.
@@ -124,7 +130,10 @@ impl Default for SoundContext {
         // There's no need to serialize native sources, because they'll be re-created automatically.
         state.serialization_options.skip_sources = true;
         drop(state);
-        Self { native }
+        Self {
+            native,
+            occlusion: Default::default(),
+        }
     }
 }
 
@@ -137,9 +146,24 @@ impl SoundContext {
     pub fn deep_clone(&self) -> Self {
         Self {
             native: self.native.deep_clone(),
+            occlusion: self.occlusion.clone(),
         }
     }
 
+    /// Sets new sound occlusion settings. See [`SoundOcclusion`] docs for more info.
+    pub fn set_occlusion(&mut self, occlusion: SoundOcclusion) {
+        self.occlusion = occlusion;
+    }
+
+    /// Returns current sound occlusion settings.
+    pub fn occlusion(&self) -> &SoundOcclusion {
+        &self.occlusion
+    }
+
+    pub(crate) fn update_occlusion(&self, pool: &NodePool, physics: &PhysicsWorld, dt: f32) {
+        self.occlusion.update(&self.native, pool, physics, dt);
+    }
+
     /// Returns locked inner state of the sound context.
     pub fn state(&self) -> SoundContextGuard {
         SoundContextGuard {
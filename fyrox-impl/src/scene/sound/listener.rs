@@ -100,11 +100,17 @@ impl NodeTrait for Listener {
         Self::type_uuid()
     }
 
-    fn sync_native(&self, _self_handle: Handle<Node>, context: &mut SyncContext) {
+    fn sync_native(&self, self_handle: Handle<Node>, context: &mut SyncContext) {
         if !self.is_globally_enabled() {
             return;
         }
 
+        if let Some(active_listener) = context.switches.and_then(|s| s.active_listener) {
+            if active_listener != self_handle {
+                return;
+            }
+        }
+
         let mut state = context.sound_context.native.state();
         let native = state.listener_mut();
         native.set_position(self.global_position());
@@ -68,6 +68,7 @@ use std::{
 
 pub mod context;
 pub mod listener;
+pub mod occlusion;
 
 /// Sound source.
 #[derive(Visit, Reflect, Debug)]
@@ -126,6 +127,11 @@ pub struct Sound {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    // Current (smoothed) occlusion factor of the sound. See `SoundOcclusion` docs for more info.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) occlusion: Cell<f32>,
 }
 
 impl Deref for Sound {
@@ -160,6 +166,7 @@ impl Default for Sound {
             spatial_blend: InheritableVariable::new_modified(1.0),
             audio_bus: InheritableVariable::new_modified(AudioBusGraph::PRIMARY_BUS.to_string()),
             native: Default::default(),
+            occlusion: Default::default(),
         }
     }
 }
@@ -183,6 +190,7 @@ impl Clone for Sound {
             audio_bus: self.audio_bus.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            occlusion: Default::default(),
         }
     }
 }
@@ -579,6 +587,7 @@ impl SoundBuilder {
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
             native: Default::default(),
+            occlusion: Default::default(),
         }
     }
 
@@ -0,0 +1,304 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Sound occlusion is an optional system, that simulates obstruction of sounds by scene geometry.
+//! See [`SoundOcclusion`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Point3, arrayvec::ArrayVec, math::lerpf, pool::Handle, reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::Base,
+        collider::InteractionGroups,
+        graph::{
+            physics::{Intersection, PhysicsWorld, RayCastOptions},
+            NodePool,
+        },
+        node::Node,
+        sound::Sound,
+    },
+};
+use fyrox_sound::source::Status;
+
+/// Upper frequency of the human hearing range. It is used as the cutoff frequency of a sound that is
+/// not occluded at all.
+const MAX_CUTOFF_FREQUENCY: f32 = 20000.0;
+
+/// Sound occlusion is an optional system, that simulates obstruction of sounds by scene geometry. When
+/// enabled, it casts a ray from the listener to every playing 3D sound every frame and counts the
+/// colliders that block the path. Each obstacle increases the occlusion factor of the sound by
+/// [`Self::obstacle_occlusion`], fully occluded sound is attenuated by [`Self::occluded_gain`] and is
+/// passed through a low-pass filter with [`Self::occluded_cutoff_frequency`]. The occlusion factor is
+/// changed smoothly (see [`Self::transition_time`]) to prevent clicks.
+///
+/// Colliders that belong to the same rigid body as the sound (or any of its ancestors) are ignored,
+/// as well as colliders, that contain the listener. Only 3D physics is used. Spatial blend of a sound
+/// scales its occlusion, which means that 2D sounds are never occluded.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct SoundOcclusion {
+    /// Whether the occlusion is enabled or not. Default is `false`.
+    pub enabled: bool,
+
+    /// Gain multiplier of a fully occluded sound. Default is 0.4.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub occluded_gain: f32,
+
+    /// Cutoff frequency (in Hz) of a low-pass filter, that is applied to a fully occluded sound.
+    /// Default is 800 Hz.
+    #[reflect(min_value = 0.0, max_value = 20000.0)]
+    pub occluded_cutoff_frequency: f32,
+
+    /// Amount of occlusion, that is added by every obstacle between the listener and a sound. The
+    /// total occlusion is clamped to `[0; 1]` range. Default is 0.5, which means that two obstacles
+    /// occlude the sound completely.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub obstacle_occlusion: f32,
+
+    /// Time (in seconds) that is needed to fully change the occlusion of a sound. Default is 0.15 s.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub transition_time: f32,
+
+    /// Collision groups of colliders that could occlude sounds.
+    pub groups: InteractionGroups,
+}
+
+impl Default for SoundOcclusion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            occluded_gain: 0.4,
+            occluded_cutoff_frequency: 800.0,
+            obstacle_occlusion: 0.5,
+            transition_time: 0.15,
+            groups: Default::default(),
+        }
+    }
+}
+
+// Checks whether the collider is an ancestor of the node or it is attached to one of its ancestors.
+fn is_own_collider(pool: &NodePool, node: &Base, collider: Handle<Node>) -> bool {
+    let collider_body = pool
+        .try_borrow(collider)
+        .map(|c| c.parent())
+        .unwrap_or_default();
+    let mut ancestor = node.parent();
+    while let Some(ancestor_ref) = pool.try_borrow(ancestor) {
+        if ancestor == collider || ancestor == collider_body {
+            return true;
+        }
+        ancestor = ancestor_ref.parent();
+    }
+    false
+}
+
+impl SoundOcclusion {
+    fn target_occlusion(
+        &self,
+        pool: &NodePool,
+        physics: &PhysicsWorld,
+        listener_position: Point3<f32>,
+        sound: &Sound,
+    ) -> f32 {
+        let direction = sound.global_position() - listener_position.coords;
+        let distance = direction.norm();
+        if distance <= f32::EPSILON {
+            return 0.0;
+        }
+
+        let mut query_buffer = ArrayVec::<Intersection, 64>::new();
+        physics.cast_ray(
+            RayCastOptions {
+                ray_origin: listener_position,
+                ray_direction: direction,
+                max_len: distance,
+                groups: self.groups,
+                sort_results: false,
+            },
+            &mut query_buffer,
+        );
+
+        let obstacles = query_buffer
+            .iter()
+            // Zero time of impact means that the listener is inside the collider.
+            .filter(|i| i.toi > 0.0 && !is_own_collider(pool, sound, i.collider))
+            .count();
+
+        (obstacles as f32 * self.obstacle_occlusion).min(1.0) * sound.spatial_blend()
+    }
+
+    pub(crate) fn update(
+        &self,
+        native: &fyrox_sound::context::SoundContext,
+        pool: &NodePool,
+        physics: &PhysicsWorld,
+        dt: f32,
+    ) {
+        let mut state = native.state();
+        let listener_position = Point3::from(state.listener().position());
+        let max_delta = if self.transition_time > 0.0 {
+            dt / self.transition_time
+        } else {
+            1.0
+        };
+
+        for sound in pool.iter().filter_map(|n| n.cast::<Sound>()) {
+            let Some(source) = state.try_get_source_mut(sound.native.get()) else {
+                continue;
+            };
+
+            let current = sound.occlusion.get();
+            let occlusion = if !self.enabled {
+                if current == 0.0 {
+                    continue;
+                }
+                0.0
+            } else if source.status() != Status::Playing {
+                continue;
+            } else {
+                let target = self.target_occlusion(pool, physics, listener_position, sound);
+                current + (target - current).clamp(-max_delta, max_delta)
+            };
+
+            sound.occlusion.set(occlusion);
+            if occlusion > 0.0 {
+                let gain = lerpf(1.0, self.occluded_gain, occlusion);
+                // Interpolate the frequency in logarithmic space, because that's how humans
+                // perceive frequencies.
+                let cutoff_frequency = MAX_CUTOFF_FREQUENCY
+                    * (self.occluded_cutoff_frequency.max(1.0) / MAX_CUTOFF_FREQUENCY)
+                        .powf(occlusion);
+                source.set_occlusion(gain, Some(cutoff_frequency));
+            } else {
+                source.set_occlusion(1.0, None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            node::Node,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            sound::{occlusion::SoundOcclusion, Sound, SoundBuilder, Status},
+            transform::TransformBuilder,
+        },
+    };
+
+    fn add_static_body(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        shape: ColliderShape,
+        children: &[Handle<Node>],
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(shape)
+            .build(graph);
+        let mut children = children.to_vec();
+        children.push(collider);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&children),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(graph)
+    }
+
+    fn add_sound(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_status(Status::Playing)
+        .build(graph)
+    }
+
+    fn occlusion(graph: &Graph, sound: Handle<Node>) -> (f32, (f32, Option<f32>)) {
+        let sound = graph[sound].cast::<Sound>().unwrap();
+        let native = graph
+            .sound_context
+            .native
+            .state()
+            .source(sound.native.get())
+            .occlusion();
+        (sound.occlusion.get(), native)
+    }
+
+    #[test]
+    fn test_sound_occlusion() {
+        let mut graph = Graph::new();
+        graph.sound_context.set_occlusion(SoundOcclusion {
+            enabled: true,
+            transition_time: 0.0,
+            ..Default::default()
+        });
+
+        // A wall between the listener (at the origin) and the sound.
+        add_static_body(
+            &mut graph,
+            Vector3::new(0.0, 0.0, 5.0),
+            ColliderShape::cuboid(5.0, 5.0, 0.5),
+            &[],
+        );
+        let occluded = add_sound(&mut graph, Vector3::new(0.0, 0.0, 10.0));
+
+        // The sound is inside its own collider, which must not occlude it.
+        let free = add_sound(&mut graph, Vector3::default());
+        add_static_body(
+            &mut graph,
+            Vector3::new(-5.0, 0.0, 0.0),
+            ColliderShape::ball(1.0),
+            &[free],
+        );
+
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let (factor, (gain, cutoff_frequency)) = occlusion(&graph, occluded);
+        assert_eq!(factor, 0.5);
+        assert!((gain - 0.7).abs() < 1.0e-6);
+        assert!(cutoff_frequency.unwrap() < 20000.0);
+
+        assert_eq!(occlusion(&graph, free), (0.0, (1.0, None)));
+
+        graph.sound_context.set_occlusion(Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        assert_eq!(occlusion(&graph, occluded), (0.0, (1.0, None)));
+    }
+}
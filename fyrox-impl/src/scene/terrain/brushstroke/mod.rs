@@ -76,6 +76,43 @@ fn mask_raise(original: u8, amount: f32) -> u8 {
     (original as f32 + amount * 255.0).clamp(0.0, 255.0) as u8
 }
 
+/// Returns smooth value noise in `[-1.0; 1.0]` range for the given pixel. The noise is
+/// deterministic, so the same pixel always gets the same value for the same scale and seed.
+fn value_noise(position: Vector2<i32>, scale: f32, seed: u32) -> f32 {
+    fn lattice_value(x: i32, y: i32, seed: u32) -> f32 {
+        let mut hash = (x as u32).wrapping_mul(0x8da6_b343)
+            ^ (y as u32).wrapping_mul(0xd816_3841)
+            ^ seed.wrapping_mul(0xcb1a_b31f);
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(0x5bd1_e995);
+        hash ^= hash >> 15;
+        hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+    fn smoothstep(t: f32) -> f32 {
+        t * t * (3.0 - 2.0 * t)
+    }
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+    let scale = scale.max(1.0);
+    let x = position.x as f32 / scale;
+    let y = position.y as f32 / scale;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (smoothstep(x - x0), smoothstep(y - y0));
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let top = lerp(
+        lattice_value(ix, iy, seed),
+        lattice_value(ix + 1, iy, seed),
+        tx,
+    );
+    let bottom = lerp(
+        lattice_value(ix, iy + 1, seed),
+        lattice_value(ix + 1, iy + 1, seed),
+        tx,
+    );
+    lerp(top, bottom, ty)
+}
+
 #[inline]
 fn mask_lerp(original: u8, value: f32, t: f32) -> u8 {
     let original = original as f32;
@@ -562,6 +599,11 @@ impl BrushStroke {
             BrushMode::Smooth { kernel_radius } => {
                 self.smooth_height(position, kernel_radius, original, alpha)
             }
+            BrushMode::Noise {
+                amount,
+                scale,
+                seed,
+            } => original + amount * alpha * value_noise(position, scale, seed),
         };
         self.height_pixels.set_latest(position, result);
     }
@@ -581,6 +623,14 @@ impl BrushStroke {
             BrushMode::Smooth { kernel_radius } => {
                 self.smooth_mask(position, kernel_radius, original, alpha)
             }
+            BrushMode::Noise {
+                amount,
+                scale,
+                seed,
+            } => mask_raise(
+                original,
+                amount * alpha * value_noise(position, scale, seed),
+            ),
         };
         self.mask_pixels.set_latest(position, result);
     }
@@ -748,6 +798,15 @@ pub enum BrushMode {
         /// 2 means using a 5x5 square of pixels. And so on.
         kernel_radius: u32,
     },
+    /// Raises and lowers the value randomly, adding natural-looking irregularities.
+    Noise {
+        /// The maximum offset to change the value by
+        amount: f32,
+        /// Size of the noise features in pixels of the modified data.
+        scale: f32,
+        /// A seed of the noise. Different seeds produce different patterns.
+        seed: u32,
+    },
 }
 
 uuid_provider!(BrushMode = "48ad4cac-05f3-485a-b2a3-66812713841f");
@@ -1043,3 +1102,24 @@ impl ChunkData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_is_deterministic_and_bounded() {
+        for x in -20..20 {
+            for y in -20..20 {
+                let position = Vector2::new(x, y);
+                let value = value_noise(position, 4.0, 7);
+                assert!((-1.0..=1.0).contains(&value));
+                assert_eq!(value, value_noise(position, 4.0, 7));
+            }
+        }
+        assert_ne!(
+            value_noise(Vector2::new(3, 5), 4.0, 1),
+            value_noise(Vector2::new(3, 5), 4.0, 2)
+        );
+    }
+}
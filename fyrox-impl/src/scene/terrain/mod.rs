@@ -25,6 +25,7 @@ use crate::{
     core::{
         algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
         arrayvec::ArrayVec,
+        color::Color,
         log::Log,
         math::{aabb::AxisAlignedBoundingBox, ray::Ray, ray_rect_intersection, Rect},
         parking_lot::Mutex,
@@ -1778,6 +1779,33 @@ impl Terrain {
         project(self.global_transform(), p)
     }
 
+    /// Projects given **world-space** point on the surface of the terrain along the up axis of the terrain
+    /// and returns the world-space point on the surface. It could be used to place objects on the terrain.
+    /// Returns `None` if the point is outside the terrain or if there's a hole in the terrain at the point.
+    pub fn surface_point(&self, p: Vector3<f32>) -> Option<Vector3<f32>> {
+        let global_transform = self.global_transform();
+        let local = project(global_transform, p)?;
+
+        let chunk_size = self.chunk_size();
+        let grid_position = Vector2::new(
+            (local.x / chunk_size.x).floor() as i32,
+            (local.y / chunk_size.y).floor() as i32,
+        );
+        self.find_chunk(grid_position)?;
+
+        if self.holes_enabled && self.interpolate_value(local, BrushTarget::HoleMask) < 0.5 {
+            return None;
+        }
+
+        let height = self.interpolate_value(local, BrushTarget::HeightMap);
+
+        Some(
+            global_transform
+                .transform_point(&Point3::new(local.x, height, local.y))
+                .coords,
+        )
+    }
+
     /// Convert from local 2D to height pixel position.
     pub fn local_to_height_pixel(&self, p: Vector2<f32>) -> Vector2<f32> {
         let scale = self.height_grid_scale();
@@ -2691,6 +2719,8 @@ impl NodeTrait for Terrain {
                                 blend_shapes_weights: Default::default(),
                                 element_range: ElementRange::Full,
                                 node_handle: self.handle(),
+                                property_block: None,
+                                color: Color::WHITE,
                             },
                         );
                     } else {
@@ -2707,6 +2737,8 @@ impl NodeTrait for Terrain {
                                         blend_shapes_weights: Default::default(),
                                         element_range: self.geometry.quadrants[i],
                                         node_handle: self.handle(),
+                                        property_block: None,
+                                        color: Color::WHITE,
                                     },
                                 );
                             }
@@ -2903,6 +2935,29 @@ impl TerrainBuilder {
 mod tests {
     use super::*;
 
+    #[test]
+    fn surface_point() {
+        let mut node = TerrainBuilder::new(BaseBuilder::new())
+            .with_chunk_size(Vector2::new(16.0, 16.0))
+            .with_width_chunks(0..1)
+            .with_length_chunks(0..1)
+            .build_node();
+        let terrain = node.cast_mut::<Terrain>().unwrap();
+        terrain.for_each_height_map_pixel(|height, position| *height = position.x * 0.5);
+
+        let point = terrain
+            .surface_point(Vector3::new(4.0, 100.0, 5.0))
+            .unwrap();
+        assert!((point - Vector3::new(4.0, 2.0, 5.0)).norm() < 0.001);
+
+        assert!(terrain
+            .surface_point(Vector3::new(-1.0, 0.0, 5.0))
+            .is_none());
+        assert!(terrain
+            .surface_point(Vector3::new(4.0, 0.0, 17.0))
+            .is_none());
+    }
+
     #[test]
     fn power_of_two() {
         assert!(!is_power_of_two(0));
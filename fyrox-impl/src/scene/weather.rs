@@ -0,0 +1,518 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Weather effects, such as rain, snow, wetness of surfaces and lightning. See [`Weather`] docs for
+//! more info.
+
+use crate::{
+    core::{
+        algebra::Vector3, color::Color, pool::Handle, rand::Rng, reflect::prelude::*,
+        uuid_provider, visitor::prelude::*,
+    },
+    graph::SceneGraph,
+    scene::{
+        base::BaseBuilder,
+        camera::Camera,
+        graph::Graph,
+        light::BaseLight,
+        mesh::Mesh,
+        node::Node,
+        particle_system::{
+            emitter::{
+                base::{BaseEmitterBuilder, ParticleSimulationMode},
+                cuboid::CuboidEmitterBuilder,
+            },
+            ParticleSystem, ParticleSystemBuilder,
+        },
+    },
+};
+use std::collections::VecDeque;
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Kind of precipitation.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum Precipitation {
+    /// No precipitation.
+    #[default]
+    None,
+    /// Rain, it makes surfaces wet.
+    Rain,
+    /// Snow.
+    Snow,
+}
+
+uuid_provider!(Precipitation = "5a0e2b63-4f51-4f6e-a2a4-1b3fb0b7a8a5");
+
+/// Precipitation layer is a particle system, that follows the active camera and emits rain drops or
+/// snowflakes around it. Use [`PrecipitationLayer::build`] to create a layer with reasonable
+/// defaults, or use any other particle system with one or more emitters.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct PrecipitationLayer {
+    /// A handle of a particle system. The particle system should be a direct child of the root
+    /// node of the scene, because it is moved in world coordinates.
+    pub particle_system: Handle<Node>,
+
+    /// Spawn rate (particles per second) of every emitter of the particle system at full intensity.
+    pub spawn_rate: u32,
+
+    /// Acceleration of the particles without wind.
+    pub acceleration: Vector3<f32>,
+
+    /// Defines how much the wind affects the particles. Rain drops are heavy and are barely affected
+    /// by the wind, while snowflakes are light and could be carried by the wind.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub wind_influence: f32,
+
+    /// Offset of the particle system relative to the active camera.
+    pub offset: Vector3<f32>,
+}
+
+impl PrecipitationLayer {
+    /// Creates a layer with default parameters for the given kind of precipitation.
+    pub fn new(precipitation: Precipitation) -> Self {
+        match precipitation {
+            Precipitation::Snow => Self {
+                particle_system: Default::default(),
+                spawn_rate: 600,
+                acceleration: Vector3::new(0.0, -0.5, 0.0),
+                wind_influence: 1.0,
+                offset: Vector3::new(0.0, 8.0, 0.0),
+            },
+            Precipitation::Rain | Precipitation::None => Self {
+                particle_system: Default::default(),
+                spawn_rate: 3000,
+                acceleration: Vector3::new(0.0, -9.81, 0.0),
+                wind_influence: 0.25,
+                offset: Vector3::new(0.0, 8.0, 0.0),
+            },
+        }
+    }
+
+    /// Creates a particle system that could be used as a precipitation layer and assigns it to the
+    /// layer. The particles are simulated on GPU, which allows to have thousands of them.
+    pub fn build(&mut self, precipitation: Precipitation, graph: &mut Graph) -> Handle<Node> {
+        let (name, size, velocity, lifetime) = match precipitation {
+            Precipitation::Snow => ("Snow", 0.03..0.05, -0.02..-0.01, 6.0..8.0),
+            Precipitation::Rain | Precipitation::None => {
+                ("Rain", 0.01..0.015, -0.3..-0.25, 0.8..1.0)
+            }
+        };
+
+        self.particle_system = ParticleSystemBuilder::new(BaseBuilder::new().with_name(name))
+            .with_acceleration(self.acceleration)
+            .with_visible_distance(f32::MAX)
+            .with_emitters(vec![CuboidEmitterBuilder::new(
+                BaseEmitterBuilder::new()
+                    .with_spawn_rate(0)
+                    .with_max_particles(20000)
+                    .with_lifetime_range(lifetime)
+                    .with_size_range(size)
+                    .with_y_velocity_range(velocity)
+                    .with_simulation_mode(ParticleSimulationMode::Gpu)
+                    .resurrect_particles(true),
+            )
+            .with_width(30.0)
+            .with_height(1.0)
+            .with_depth(30.0)
+            .build()])
+            .build(graph);
+
+        self.particle_system
+    }
+
+    fn update(&self, graph: &mut Graph, intensity: f32, wind: Vector3<f32>, camera: Vector3<f32>) {
+        let Some(particle_system) =
+            graph.try_get_mut_of_type::<ParticleSystem>(self.particle_system)
+        else {
+            return;
+        };
+
+        let spawn_rate = (self.spawn_rate as f32 * intensity).round() as u32;
+        for emitter in particle_system.emitters.get_value_mut_silent().iter_mut() {
+            if emitter.spawn_rate() != spawn_rate {
+                emitter.set_spawn_rate(spawn_rate);
+            }
+        }
+
+        let acceleration = self.acceleration + wind.scale(self.wind_influence);
+        if particle_system.acceleration() != acceleration {
+            particle_system.set_acceleration(acceleration);
+        }
+
+        particle_system
+            .local_transform_mut()
+            .set_position(camera + self.offset);
+    }
+}
+
+/// An event, produced by [`Weather`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WeatherEvent {
+    /// A lightning flash has started. It could be used to play a thunder sound (usually with some
+    /// delay).
+    LightningFlash,
+}
+
+/// Weather controls common weather effects of a scene:
+///
+/// - Precipitation (rain or snow) - see [`PrecipitationLayer`] docs.
+/// - Wetness of surfaces. Wetness slowly increases while it is raining and decreases otherwise. It
+/// is passed to the `wetness` property of materials of every mesh in the scene (if their shaders have
+/// it, the standard shaders do), wet surfaces are darker and glossier.
+/// - Wind, that affects precipitation.
+/// - Lightning flashes. Flashes occur randomly with the given frequency (or on demand, see
+/// [`Self::flash`]), each flash briefly lights up the scene using a light source and produces
+/// [`WeatherEvent::LightningFlash`] event. Usually the light source is a directional light with
+/// zero intensity.
+///
+/// Weather is updated only when the entire scene graph is updated (i.e. there are no node
+/// overrides in update switches), so it does not affect scenes in the editor.
+#[derive(Clone, Debug, Visit, Reflect)]
+pub struct Weather {
+    /// Current precipitation.
+    pub precipitation: Precipitation,
+
+    /// Intensity of the precipitation in `[0; 1]` range.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub intensity: f32,
+
+    /// A layer, that is used for rain.
+    pub rain: PrecipitationLayer,
+
+    /// A layer, that is used for snow.
+    pub snow: PrecipitationLayer,
+
+    /// Wind velocity. It affects the precipitation.
+    pub wind: Vector3<f32>,
+
+    /// Current wetness of surfaces in `[0; 1]` range.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub wetness: f32,
+
+    /// Speed (per second) with which wetness increases during the rain of full intensity.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub wetting_speed: f32,
+
+    /// Speed (per second) with which wetness decreases when there's no rain.
+    #[reflect(min_value = 0.0, step = 0.01)]
+    pub drying_speed: f32,
+
+    /// Average amount of lightning flashes per minute. Zero disables random flashes.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub lightning_frequency: f32,
+
+    /// A handle of a light source, that is used to light up the scene during a flash.
+    pub lightning_light: Handle<Node>,
+
+    /// Intensity of the light source at the peak of a flash.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub lightning_intensity: f32,
+
+    /// Duration of a flash in seconds.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub lightning_duration: f32,
+
+    /// Color of the light source during a flash.
+    pub lightning_color: Color,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    time_to_next_flash: Option<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    flash_time: Option<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    applied_wetness: Option<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    events: VecDeque<WeatherEvent>,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            precipitation: Default::default(),
+            intensity: 1.0,
+            rain: PrecipitationLayer::new(Precipitation::Rain),
+            snow: PrecipitationLayer::new(Precipitation::Snow),
+            wind: Default::default(),
+            wetness: 0.0,
+            wetting_speed: 0.05,
+            drying_speed: 0.01,
+            lightning_frequency: 0.0,
+            lightning_light: Default::default(),
+            lightning_intensity: 5.0,
+            lightning_duration: 0.25,
+            lightning_color: Color::opaque(220, 230, 255),
+            time_to_next_flash: None,
+            flash_time: None,
+            applied_wetness: None,
+            events: Default::default(),
+        }
+    }
+}
+
+impl Weather {
+    /// Starts a lightning flash immediately.
+    pub fn flash(&mut self) {
+        self.flash_time = Some(0.0);
+        self.events.push_back(WeatherEvent::LightningFlash);
+    }
+
+    /// Returns `true` if there's a lightning flash in progress.
+    pub fn is_flashing(&self) -> bool {
+        self.flash_time.is_some()
+    }
+
+    /// Pops the oldest weather event.
+    pub fn pop_event(&mut self) -> Option<WeatherEvent> {
+        self.events.pop_front()
+    }
+
+    fn random_flash_interval(&self) -> Option<f32> {
+        if self.lightning_frequency > 0.0 {
+            // Exponential distribution gives natural irregular intervals between flashes.
+            let u = crate::rand::thread_rng().gen_range(0.0f32..1.0);
+            Some(-(1.0 - u).ln() * 60.0 / self.lightning_frequency)
+        } else {
+            None
+        }
+    }
+
+    fn update_lightning(&mut self, graph: &mut Graph, dt: f32) {
+        if self.lightning_frequency > 0.0 {
+            let time = match self.time_to_next_flash {
+                Some(time) => time - dt,
+                None => self.random_flash_interval().unwrap_or_default(),
+            };
+            if time <= 0.0 {
+                self.flash();
+                self.time_to_next_flash = self.random_flash_interval();
+            } else {
+                self.time_to_next_flash = Some(time);
+            }
+        } else {
+            self.time_to_next_flash = None;
+        }
+
+        let Some(flash_time) = self.flash_time else {
+            return;
+        };
+
+        let flash_time = flash_time + dt;
+        let intensity = if flash_time >= self.lightning_duration {
+            self.flash_time = None;
+            0.0
+        } else {
+            self.flash_time = Some(flash_time);
+            // Sharp start and fast decay with a bit of flicker in the middle.
+            let t = flash_time / self.lightning_duration;
+            let flicker = if (0.3..0.45).contains(&t) { 0.4 } else { 1.0 };
+            self.lightning_intensity * (1.0 - t) * (1.0 - t) * flicker
+        };
+
+        if let Some(light) = graph
+            .try_get_mut(self.lightning_light)
+            .and_then(|n| n.query_component_mut::<BaseLight>())
+        {
+            light.set_color(self.lightning_color);
+            light.set_intensity(intensity);
+        }
+    }
+
+    fn update_wetness(&mut self, graph: &mut Graph, dt: f32) {
+        if self.precipitation == Precipitation::Rain {
+            self.wetness += self.wetting_speed * self.intensity * dt;
+        } else {
+            self.wetness -= self.drying_speed * dt;
+        }
+        self.wetness = self.wetness.clamp(0.0, 1.0);
+
+        // Changing material properties is relatively expensive, so do this only on significant
+        // changes.
+        if let Some(applied) = self.applied_wetness {
+            let settled = self.wetness == 0.0 || self.wetness == 1.0;
+            if applied == self.wetness || (!settled && (applied - self.wetness).abs() < 0.01) {
+                return;
+            }
+        }
+
+        for mesh in graph.linear_iter().filter_map(|n| n.cast::<Mesh>()) {
+            for surface in mesh.surfaces() {
+                // Shaders that don't support wetness are simply ignored.
+                let _ = surface
+                    .material()
+                    .data_ref()
+                    .try_set_property("wetness", self.wetness);
+            }
+        }
+
+        self.applied_wetness = Some(self.wetness);
+    }
+
+    /// Updates the weather. This method is called automatically by the scene.
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) {
+        let camera = graph
+            .linear_iter()
+            .filter_map(|n| n.cast::<Camera>())
+            .find(|c| c.is_globally_enabled())
+            .map(|c| c.global_position())
+            .unwrap_or_default();
+
+        let rain = if self.precipitation == Precipitation::Rain {
+            self.intensity
+        } else {
+            0.0
+        };
+        self.rain.update(graph, rain, self.wind, camera);
+
+        let snow = if self.precipitation == Precipitation::Snow {
+            self.intensity
+        } else {
+            0.0
+        };
+        self.snow.update(graph, snow, self.wind, camera);
+
+        self.update_wetness(graph, dt);
+        self.update_lightning(graph, dt);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::untyped::ResourceKind,
+        core::algebra::{Matrix4, Vector3},
+        material::{Material, MaterialProperty, MaterialResource},
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            light::{directional::DirectionalLightBuilder, BaseLight, BaseLightBuilder},
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                MeshBuilder,
+            },
+            particle_system::ParticleSystem,
+            weather::{Precipitation, Weather, WeatherEvent},
+        },
+    };
+
+    #[test]
+    fn test_precipitation_and_wetness() {
+        let mut graph = Graph::new();
+        let material = MaterialResource::new_ok(ResourceKind::Embedded, Material::standard());
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_material(material.clone())
+            .build()])
+            .build(&mut graph);
+
+        let mut weather = Weather {
+            precipitation: Precipitation::Rain,
+            intensity: 0.5,
+            wind: Vector3::new(4.0, 0.0, 0.0),
+            wetting_speed: 0.5,
+            ..Default::default()
+        };
+        let mut rain = weather.rain.clone();
+        let rain_layer = rain.build(Precipitation::Rain, &mut graph);
+        weather.rain = rain;
+
+        weather.update(&mut graph, 1.0);
+
+        let particle_system = graph[rain_layer].cast::<ParticleSystem>().unwrap();
+        assert_eq!(
+            particle_system.emitters[0].spawn_rate(),
+            weather.rain.spawn_rate / 2
+        );
+        assert_eq!(
+            particle_system.acceleration(),
+            Vector3::new(1.0, -9.81, 0.0)
+        );
+        assert_eq!(weather.wetness, 0.25);
+        assert_eq!(
+            material.data_ref().property_ref("wetness"),
+            Some(&MaterialProperty::Float(0.25))
+        );
+
+        weather.precipitation = Precipitation::None;
+        weather.drying_speed = 0.25;
+        weather.update(&mut graph, 1.0);
+
+        let particle_system = graph[rain_layer].cast::<ParticleSystem>().unwrap();
+        assert_eq!(particle_system.emitters[0].spawn_rate(), 0);
+        assert_eq!(weather.wetness, 0.0);
+        assert_eq!(
+            material.data_ref().property_ref("wetness"),
+            Some(&MaterialProperty::Float(0.0))
+        );
+    }
+
+    #[test]
+    fn test_lightning() {
+        let mut graph = Graph::new();
+        let light = DirectionalLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()))
+            .build(&mut graph);
+
+        let mut weather = Weather {
+            lightning_light: light,
+            lightning_duration: 1.0,
+            ..Default::default()
+        };
+
+        weather.flash();
+        assert_eq!(weather.pop_event(), Some(WeatherEvent::LightningFlash));
+
+        weather.update(&mut graph, 0.1);
+        assert!(weather.is_flashing());
+        let intensity = graph[light]
+            .query_component_ref::<BaseLight>()
+            .unwrap()
+            .intensity();
+        assert!(intensity > 0.0);
+
+        weather.update(&mut graph, 1.0);
+        assert!(!weather.is_flashing());
+        let intensity = graph[light]
+            .query_component_ref::<BaseLight>()
+            .unwrap()
+            .intensity();
+        assert_eq!(intensity, 0.0);
+    }
+}
@@ -59,7 +59,37 @@ impl ScriptConstructorContainer {
         ScriptConstructorContainer::default()
     }
 
-    /// Adds new type constructor for a given type.
+    /// Adds new type constructor for a given type. The name is used by the editor to show the
+    /// script in the list of available scripts. Scripts are usually registered in
+    /// [`crate::plugin::Plugin::register`]:
+    ///
+    /// ```rust
+    /// # use fyrox_impl::{
+    /// #     core::{reflect::prelude::*, type_traits::prelude::*, visitor::prelude::*},
+    /// #     plugin::{Plugin, PluginRegistrationContext},
+    /// #     script::ScriptTrait,
+    /// # };
+    /// #
+    /// #[derive(Visit, Reflect, Clone, Default, Debug, TypeUuidProvider, ComponentProvider)]
+    /// #[type_uuid(id = "8d5d6e2b-8f8b-4bd7-9d6e-1a8f57a3e9f1")]
+    /// struct Door {
+    ///     open: bool,
+    /// }
+    ///
+    /// impl ScriptTrait for Door {}
+    ///
+    /// #[derive(Visit, Reflect, Default, Debug)]
+    /// struct Game {}
+    ///
+    /// impl Plugin for Game {
+    ///     fn register(&self, context: PluginRegistrationContext) {
+    ///         context
+    ///             .serialization_context
+    ///             .script_constructors
+    ///             .add::<Door>("Door");
+    ///     }
+    /// }
+    /// ```
     ///
     /// # Panic
     ///
@@ -68,7 +98,7 @@ impl ScriptConstructorContainer {
     where
         T: TypeUuidProvider + ScriptTrait + Default,
     {
-        let old = self.map.lock().insert(
+        if let Err(err) = self.add_custom(
             T::type_uuid(),
             ScriptConstructor {
                 constructor: Box::new(|| Script::new(T::default())),
@@ -76,9 +106,9 @@ impl ScriptConstructorContainer {
                 source_path: T::source_path(),
                 assembly_name: T::type_assembly_name(),
             },
-        );
-
-        assert!(old.is_none());
+        ) {
+            panic!("{err}");
+        }
 
         self
     }
@@ -119,3 +149,46 @@ impl ScriptConstructorContainer {
         self.map.lock()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            impl_component_provider, reflect::prelude::*, visitor::prelude::*, TypeUuidProvider,
+        },
+        script::{constructor::ScriptConstructorContainer, ScriptTrait},
+    };
+    use fyrox_core::uuid_provider;
+
+    #[derive(Reflect, Visit, Debug, Clone, Default)]
+    struct MyScript {
+        field: f32,
+    }
+
+    impl_component_provider!(MyScript);
+    uuid_provider!(MyScript = "2b6d9d8c-6b0e-4b84-9f6b-0b8f3ee5c1a7");
+
+    impl ScriptTrait for MyScript {}
+
+    #[test]
+    fn test_script_constructors() {
+        let container = ScriptConstructorContainer::new();
+        container.add::<MyScript>("My Script");
+
+        assert_eq!(container.map()[&MyScript::type_uuid()].name, "My Script");
+
+        let script = container.try_create(&MyScript::type_uuid()).unwrap();
+        assert!(script.cast::<MyScript>().is_some());
+
+        container.remove(MyScript::type_uuid());
+        assert!(container.try_create(&MyScript::type_uuid()).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_script_constructors_duplicate() {
+        let container = ScriptConstructorContainer::new();
+        container.add::<MyScript>("My Script");
+        container.add::<MyScript>("My Script 2");
+    }
+}
@@ -33,7 +33,10 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
-    engine::{task::TaskPoolHandler, GraphicsContext, ScriptMessageDispatcher},
+    engine::{
+        gamepad::GamepadEvent, simulation::SimulationRng, task::TaskPoolHandler, GraphicsContext,
+        ScriptMessageDispatcher,
+    },
     event::Event,
     gui::UiContainer,
     plugin::{Plugin, PluginContainer},
@@ -129,7 +132,33 @@ pub enum ScriptMessageKind {
     Global,
 }
 
-/// A script message sender.
+/// A script message sender. Script messages is a way of communication between scripts of different
+/// nodes (or between scripts and other parts of a game, such as plugins) without borrowing each
+/// other directly. Messages are queued and delivered in the update loop of the scene, after all
+/// scripts were updated. A message could be addressed to a specific node, to a hierarchy of nodes
+/// or broadcast to every node, see [`ScriptMessageKind`] for more info. A script receives messages
+/// only of the types it is subscribed to (see [`ScriptMessageDispatcher::subscribe_to`]) in its
+/// [`ScriptTrait::on_message`] method.
+///
+/// Scripts have access to the sender of their scene via `ctx.message_sender`, any other code could
+/// get it using [`crate::engine::ScriptProcessor::message_sender`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::pool::Handle,
+/// #     scene::node::Node,
+/// #     script::{RoutingStrategy, ScriptMessageSender},
+/// # };
+/// #[derive(Debug)]
+/// struct Damage(u32);
+///
+/// fn explode(sender: &ScriptMessageSender, vehicle: Handle<Node>) {
+///     // Every node of the vehicle, that is subscribed to `Damage` messages, will receive it.
+///     sender.send_hierarchical(vehicle, RoutingStrategy::Down, Damage(30));
+/// }
+/// ```
 #[derive(Clone)]
 pub struct ScriptMessageSender {
     pub(crate) sender: Sender<ScriptMessage>,
@@ -396,6 +425,10 @@ pub struct ScriptContext<'a, 'b, 'c> {
     /// get a reference to it.
     pub user_interfaces: &'a mut UiContainer,
 
+    /// Seedable random number generator of the engine, that should be used for every random decision
+    /// that affects the game state. See [`SimulationRng`] docs for more info.
+    pub rng: &'a mut SimulationRng,
+
     /// Index of the script. Never save this index, it is only valid while this context exists!
     pub script_index: usize,
 }
@@ -539,6 +572,10 @@ pub struct ScriptDeinitContext<'a, 'b, 'c> {
     /// get a reference to it.
     pub user_interfaces: &'a mut UiContainer,
 
+    /// Seedable random number generator of the engine, that should be used for every random decision
+    /// that affects the game state. See [`SimulationRng`] docs for more info.
+    pub rng: &'a mut SimulationRng,
+
     /// Index of the script. Never save this index, it is only valid while this context exists!
     pub script_index: usize,
 }
@@ -599,6 +636,15 @@ pub trait ScriptTrait: BaseScript + ComponentProvider {
     ) {
     }
 
+    /// Called when a gamepad was connected or disconnected, or when its state has changed. Gamepad
+    /// events are delivered the same way as OS events, see [`ScriptTrait::on_os_event`].
+    fn on_gamepad_event(
+        &mut self,
+        #[allow(unused_variables)] event: &GamepadEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) {
+    }
+
     /// Performs a single update tick of the script. The method may be called multiple times per frame, but it is guaranteed
     /// that the rate of call is stable and by default it will be called 60 times per second, but can be changed by using
     /// [`crate::engine::executor::Executor::set_desired_update_rate`] method.
@@ -435,6 +435,23 @@ impl<T: VertexDataProvider> Graph<T> {
         to: usize,
         path: &mut Vec<usize>,
     ) -> Result<PathKind, PathError> {
+        self.build_filtered_indexed_path(from, to, |_| true, path)
+    }
+
+    /// Does the same as [`Self::build_indexed_path`], but allows you to exclude some vertices from the search.
+    /// The `filter` is called for every vertex that the search tries to enter (except the starting vertex) and
+    /// the vertex is skipped if the filter returns `false`. If the end vertex is excluded, the method returns
+    /// [`PathKind::Partial`] path that ends at a vertex which is closest to the end.
+    pub fn build_filtered_indexed_path<F>(
+        &self,
+        from: usize,
+        to: usize,
+        mut filter: F,
+        path: &mut Vec<usize>,
+    ) -> Result<PathKind, PathError>
+    where
+        F: FnMut(usize) -> bool,
+    {
         path.clear();
 
         if self.vertices.is_empty() {
@@ -504,7 +521,7 @@ impl<T: VertexDataProvider> Graph<T> {
                 }
 
                 // avoids going in circles
-                if searched_vertices[neighbour_index] {
+                if searched_vertices[neighbour_index] || !filter(neighbour_index) {
                     continue;
                 }
 
@@ -0,0 +1,345 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Module to bake multi-angle atlases for impostors. See [`bake`] docs for more info.
+
+use crate::{
+    asset::untyped::ResourceKind,
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        pool::Handle,
+    },
+    material::{Material, MaterialResource},
+    renderer::{framework::error::FrameworkError, Renderer},
+    resource::texture::{TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension},
+    scene::{
+        base::BaseBuilder,
+        camera::{CameraBuilder, Exposure, OrthographicProjection, Projection, SkyBoxKind},
+        graph::Graph,
+        impostor::{self, Impostor},
+        light::{directional::DirectionalLightBuilder, BaseLightBuilder},
+        node::Node,
+        transform::{Transform, TransformBuilder},
+        Scene,
+    },
+};
+use std::fmt::{Display, Formatter};
+
+/// Options of impostor baking.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImpostorBakeOptions {
+    /// Amount of frames (views from different angles around the Y axis) in the atlas.
+    pub frames: u32,
+    /// Size of a single frame in pixels.
+    pub frame_size: u32,
+}
+
+impl Default for ImpostorBakeOptions {
+    fn default() -> Self {
+        Self {
+            frames: 8,
+            frame_size: 256,
+        }
+    }
+}
+
+/// An error that may occur during impostor baking.
+#[derive(Debug)]
+pub enum ImpostorBakeError {
+    /// The node and its descendants do not have any visible geometry.
+    EmptyBounds,
+    /// The renderer failed to render a frame.
+    Framework(FrameworkError),
+}
+
+impl Display for ImpostorBakeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImpostorBakeError::EmptyBounds => {
+                write!(f, "The node and its descendants do not have any geometry.")
+            }
+            ImpostorBakeError::Framework(e) => {
+                write!(f, "Failed to render an impostor frame. Reason: {e}")
+            }
+        }
+    }
+}
+
+impl From<FrameworkError> for ImpostorBakeError {
+    fn from(e: FrameworkError) -> Self {
+        Self::Framework(e)
+    }
+}
+
+/// A result of impostor baking.
+#[derive(Clone, Debug)]
+pub struct ImpostorAtlas {
+    /// Atlas texture with all the frames, see [`impostor::frame_uv_rect`] for its layout.
+    pub texture: TextureResource,
+    /// Amount of frames in the atlas.
+    pub frames: u32,
+    /// Size of a frame in local coordinates of the baked node.
+    pub size: f32,
+    /// Center of the frames in local coordinates of the baked node.
+    pub center: Vector3<f32>,
+}
+
+impl ImpostorAtlas {
+    /// Creates a new sprite material with the atlas.
+    pub fn make_material(&self) -> MaterialResource {
+        let mut material = Material::standard_sprite();
+        material.bind("diffuseTexture", self.texture.clone());
+        MaterialResource::new_ok(ResourceKind::Embedded, material)
+    }
+
+    /// Applies the atlas to the given impostor. The impostor must have the same local coordinate
+    /// system as the baked node (it is usually its parent with identity local transform of the baked
+    /// node).
+    pub fn apply(&self, impostor: &mut Impostor) {
+        impostor.set_material(self.make_material());
+        impostor.set_frames(self.frames);
+        impostor.set_size(self.size);
+        impostor.set_center(self.center);
+    }
+}
+
+/// Renders the given node with all its descendants from [`ImpostorBakeOptions::frames`] angles
+/// around the local Y axis of the node and packs the frames in a single atlas, which can then be
+/// used by an [`Impostor`] node.
+///
+/// The node is rendered in a temporary scene with a single directional light, using orthographic
+/// projection. Transparency of the frames is restored by rendering each frame twice on black and
+/// white backgrounds.
+pub fn bake(
+    renderer: &mut Renderer,
+    graph: &Graph,
+    root: Handle<Node>,
+    options: &ImpostorBakeOptions,
+) -> Result<ImpostorAtlas, ImpostorBakeError> {
+    let frames = options.frames.max(1);
+    let frame_size = options.frame_size.max(1);
+    let frame_size_f = Vector2::repeat(frame_size as f32);
+
+    let mut scene = Scene::new();
+    scene.rendering_options.render_target =
+        Some(TextureResource::new_render_target(frame_size, frame_size));
+
+    let (copy, _) = graph.copy_node(
+        root,
+        &mut scene.graph,
+        &mut |_, _| true,
+        &mut |_, _| {},
+        &mut |_, _, node| {
+            // Existing impostors must not replace the geometry that is being baked.
+            if let Some(impostor) = node.cast_mut::<Impostor>() {
+                impostor.set_distance(f32::MAX);
+            }
+        },
+    );
+    // The atlas is baked in local coordinates of the node.
+    *scene.graph[copy].local_transform_mut() = Transform::identity();
+
+    DirectionalLightBuilder::new(BaseLightBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_rotation(
+                    UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 30.0f32.to_radians())
+                        * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 60.0f32.to_radians()),
+                )
+                .build(),
+        ),
+    ))
+    .build(&mut scene.graph);
+
+    scene.update(frame_size_f, 0.0, Default::default());
+
+    let aabb = scene
+        .graph
+        .aabb_of_descendants(copy, |_, _| true)
+        .ok_or(ImpostorBakeError::EmptyBounds)?;
+    let center = aabb.center();
+    let horizontal_radius = aabb
+        .corners()
+        .iter()
+        .map(|c| Vector2::new(c.x - center.x, c.z - center.z).norm())
+        .fold(0.0f32, f32::max);
+    let size = 2.0 * horizontal_radius.max(aabb.half_extents().y);
+    if size <= f32::EPSILON {
+        return Err(ImpostorBakeError::EmptyBounds);
+    }
+
+    let camera_distance = size * 2.0;
+    let camera = CameraBuilder::new(BaseBuilder::new())
+        .with_projection(Projection::Orthographic(OrthographicProjection {
+            z_near: 0.0,
+            z_far: camera_distance * 2.0,
+            vertical_size: size * 0.5,
+        }))
+        .with_specific_skybox(SkyBoxKind::None)
+        .with_exposure(Exposure::Manual(std::f32::consts::E))
+        .build(&mut scene.graph);
+
+    let columns = impostor::atlas_columns(frames);
+    let atlas_size = columns * frame_size;
+    let mut atlas = vec![0u8; (atlas_size * atlas_size * 4) as usize];
+
+    let temp_handle = Handle::new(u32::MAX, u32::MAX);
+
+    let mut render_frames = || -> Result<(), FrameworkError> {
+        for frame in 0..frames {
+            let angle = frame as f32 * std::f32::consts::TAU / frames as f32;
+            scene.graph[camera]
+                .local_transform_mut()
+                .set_position(
+                    center + Vector3::new(angle.sin(), 0.0, angle.cos()) * camera_distance,
+                )
+                .set_rotation(UnitQuaternion::from_axis_angle(
+                    &Vector3::y_axis(),
+                    angle + std::f32::consts::PI,
+                ));
+
+            let black = render_frame(
+                renderer,
+                &mut scene,
+                temp_handle,
+                frame_size_f,
+                Color::BLACK,
+            )?;
+            let white = render_frame(
+                renderer,
+                &mut scene,
+                temp_handle,
+                frame_size_f,
+                Color::WHITE,
+            )?;
+            let frame_pixels = restore_transparency(&black, &white);
+
+            let column = frame % columns;
+            let row = frame / columns;
+            let len = (frame_size * 4) as usize;
+            for y in 0..frame_size {
+                // Frame buffer rows go from bottom to top, while the atlas rows go from top to
+                // bottom.
+                let src = ((frame_size - 1 - y) * frame_size * 4) as usize;
+                let dest =
+                    (((row * frame_size + y) * atlas_size + column * frame_size) * 4) as usize;
+                atlas[dest..(dest + len)].copy_from_slice(&frame_pixels[src..(src + len)]);
+            }
+        }
+        Ok(())
+    };
+    let result = render_frames();
+
+    // TODO: This is a hack, refactor `render_scene` method to accept render data from
+    // outside, instead of messing around with these temporary handles.
+    renderer.scene_data_map.remove(&temp_handle);
+
+    result?;
+
+    let texture = TextureResource::from_bytes(
+        TextureKind::Rectangle {
+            width: atlas_size,
+            height: atlas_size,
+        },
+        TexturePixelKind::RGBA8,
+        atlas,
+        ResourceKind::Embedded,
+    )
+    .unwrap();
+
+    Ok(ImpostorAtlas {
+        texture,
+        frames,
+        size,
+        center,
+    })
+}
+
+fn render_frame(
+    renderer: &mut Renderer,
+    scene: &mut Scene,
+    temp_handle: Handle<Scene>,
+    frame_size: Vector2<f32>,
+    clear_color: Color,
+) -> Result<Vec<u8>, FrameworkError> {
+    scene.rendering_options.clear_color = Some(clear_color);
+    scene.update(frame_size, 0.0, Default::default());
+    Ok(renderer
+        .render_scene(temp_handle, scene, 0.0)?
+        .ldr_scene_frame_texture()
+        .borrow_mut()
+        .read_pixels())
+}
+
+/// Calculates transparency of every pixel by comparing the same image rendered on black and white
+/// backgrounds. Returns RGBA pixels with straight (not premultiplied) alpha.
+fn restore_transparency(black: &[u8], white: &[u8]) -> Vec<u8> {
+    let difference = |b: &[u8], w: &[u8]| {
+        (0..3)
+            .map(|i| w[i] as f32 - b[i] as f32)
+            .sum::<f32>()
+            .max(0.0)
+            / 3.0
+    };
+
+    // Background may not be pure white after tone mapping, so the largest difference is used as the
+    // difference of fully transparent pixels.
+    let max_difference = black
+        .chunks_exact(4)
+        .zip(white.chunks_exact(4))
+        .map(|(b, w)| difference(b, w))
+        .fold(0.0f32, f32::max)
+        .max(1.0);
+
+    black
+        .chunks_exact(4)
+        .zip(white.chunks_exact(4))
+        .flat_map(|(b, w)| {
+            let alpha = 1.0 - (difference(b, w) / max_difference).min(1.0);
+            let color = |i: usize| {
+                if alpha > 0.0 {
+                    (b[i] as f32 / alpha).min(255.0) as u8
+                } else {
+                    0
+                }
+            };
+            [color(0), color(1), color(2), (alpha * 255.0) as u8]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::impostor::restore_transparency;
+
+    #[test]
+    fn test_restore_transparency() {
+        // Opaque red, fully transparent and half-transparent white pixels.
+        let black = [255, 0, 0, 255, 0, 0, 0, 255, 128, 128, 128, 255];
+        let white = [255, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255];
+
+        let pixels = restore_transparency(&black, &white);
+        assert_eq!(&pixels[0..4], &[255, 0, 0, 255]);
+        assert_eq!(pixels[7], 0);
+        assert!((pixels[11] as i32 - 128).abs() <= 1);
+        assert!(pixels[8] >= 254);
+    }
+}
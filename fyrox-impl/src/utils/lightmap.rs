@@ -175,9 +175,38 @@ struct Instance {
     owner: Handle<Node>,
     source_data: SurfaceResource,
     data: Option<lightmap::input::Mesh>,
+    geometry: Option<SurfaceGeometry>,
     transform: Matrix4<f32>,
 }
 
+/// A set of parameters, that defines quality of a lightmap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightmapSettings {
+    /// Amount of texels per unit of area. The more the value, the more detailed lightmap will be
+    /// produced, but the generation time will increase quadratically.
+    pub texels_per_unit: u32,
+    /// Relative spacing between UV elements generated by the built-in UV mapper.
+    pub uv_spacing: f32,
+    /// Amount of indirect light bounces. Zero means that only direct lighting is calculated.
+    /// Indirect lighting is approximated with virtual point lights, that are placed on lit
+    /// surfaces, and does not take occlusion into account.
+    pub bounces: u32,
+    /// Whether to smooth out the noise of the lightmap or not. The filter preserves edges between
+    /// surfaces with different orientation.
+    pub denoise: bool,
+}
+
+impl Default for LightmapSettings {
+    fn default() -> Self {
+        Self {
+            texels_per_unit: 64,
+            uv_spacing: 0.005,
+            bounces: 0,
+            denoise: false,
+        }
+    }
+}
+
 /// Small helper that allows you stop lightmap generation in any time.
 #[derive(Clone, Default)]
 pub struct CancellationToken(pub Arc<AtomicBool>);
@@ -211,6 +240,10 @@ pub enum ProgressStage {
     GeometryCaching = 2,
     /// Actual lightmap generation.
     CalculatingLight = 3,
+    /// Calculating bounces of light.
+    IndirectLighting = 4,
+    /// Smoothing out the noise.
+    Denoising = 5,
 }
 
 impl Display for ProgressStage {
@@ -228,6 +261,12 @@ impl Display for ProgressStage {
             ProgressStage::CalculatingLight => {
                 write!(f, "Calculating Light")
             }
+            ProgressStage::IndirectLighting => {
+                write!(f, "Calculating Indirect Light")
+            }
+            ProgressStage::Denoising => {
+                write!(f, "Denoising")
+            }
         }
     }
 }
@@ -259,6 +298,8 @@ impl ProgressData {
             1 => ProgressStage::UvGeneration,
             2 => ProgressStage::GeometryCaching,
             3 => ProgressStage::CalculatingLight,
+            4 => ProgressStage::IndirectLighting,
+            5 => ProgressStage::Denoising,
             _ => unreachable!(),
         }
     }
@@ -470,6 +511,7 @@ impl LightmapInputData {
                         transform: global_transform,
                         // Calculated down below.
                         data: None,
+                        geometry: None,
                     });
                 }
             }
@@ -520,6 +562,30 @@ impl Lightmap {
         cancellation_token: CancellationToken,
         progress_indicator: ProgressIndicator,
     ) -> Result<Self, LightmapGenerationError> {
+        Self::new_with_settings(
+            data,
+            &LightmapSettings {
+                texels_per_unit,
+                uv_spacing,
+                ..Default::default()
+            },
+            cancellation_token,
+            progress_indicator,
+        )
+    }
+
+    /// Does the same as [`Self::new`], but allows to specify additional settings, such as amount
+    /// of indirect light bounces and denoising. See [`LightmapSettings`] docs for more info.
+    pub fn new_with_settings(
+        data: LightmapInputData,
+        settings: &LightmapSettings,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Self, LightmapGenerationError> {
+        let texels_per_unit = settings.texels_per_unit;
+        let uv_spacing = settings.uv_spacing;
+        let need_texels = settings.bounces > 0 || settings.denoise;
+
         let LightmapInputData {
             data_set,
             mut instances,
@@ -596,6 +662,25 @@ impl Lightmap {
                         })
                         .collect::<Vec<_>>();
 
+                    if need_texels {
+                        instance.geometry = Some(SurfaceGeometry {
+                            vertices: world_vertices
+                                .iter()
+                                .map(|v| TexelVertex {
+                                    position: v.world_position,
+                                    normal: v.world_normal,
+                                    tex_coord: v.second_tex_coord,
+                                })
+                                .collect(),
+                            triangles: data
+                                .geometry_buffer
+                                .triangles_ref()
+                                .iter()
+                                .map(|t| t.0)
+                                .collect(),
+                        });
+                    }
+
                     instance.data = Some(
                         lightmap::input::Mesh::new(
                             world_vertices,
@@ -623,18 +708,63 @@ impl Lightmap {
             .filter_map(|i| i.data.take())
             .collect::<Vec<_>>();
         let light_definitions = lights.values().cloned().collect::<Vec<_>>();
-        for (mesh, instance) in meshes.iter().zip(instances.iter()) {
+        let mut images = Vec::with_capacity(meshes.len());
+        for mesh in meshes.iter() {
             if cancellation_token.is_cancelled() {
                 return Err(LightmapGenerationError::Cancelled);
             }
 
-            let lightmap = generate_lightmap(mesh, &meshes, &light_definitions, texels_per_unit);
+            images.push(LightmapImage::new(
+                mesh,
+                &meshes,
+                &light_definitions,
+                texels_per_unit,
+            ));
+
+            progress_indicator.advance_progress();
+        }
+
+        if need_texels {
+            let texels = instances
+                .par_iter()
+                .zip(images.par_iter())
+                .map(|(instance, image)| match instance.geometry.as_ref() {
+                    Some(geometry) => rasterize_texels(geometry, image.width, image.height),
+                    None => vec![None; image.pixels.len()],
+                })
+                .collect::<Vec<_>>();
+
+            if settings.bounces > 0 {
+                add_indirect_lighting(
+                    &mut images,
+                    &texels,
+                    settings,
+                    &cancellation_token,
+                    &progress_indicator,
+                )?;
+            }
+
+            if settings.denoise {
+                progress_indicator.set_stage(ProgressStage::Denoising, images.len() as u32);
+
+                images
+                    .par_iter_mut()
+                    .zip(texels.par_iter())
+                    .for_each(|(image, texels)| {
+                        image.denoise(texels);
+                        progress_indicator.advance_progress();
+                    });
+            }
+        }
+
+        for (image, instance) in images.into_iter().zip(instances.iter()) {
             map.entry(instance.owner).or_default().push(LightmapEntry {
-                texture: Some(TextureResource::new_ok(Default::default(), lightmap)),
+                texture: Some(TextureResource::new_ok(
+                    Default::default(),
+                    image.into_texture(),
+                )),
                 lights: lights.keys().cloned().collect(),
             });
-
-            progress_indicator.advance_progress();
         }
 
         Ok(Self { map, patches })
@@ -667,37 +797,322 @@ impl Lightmap {
     }
 }
 
-/// Generates lightmap for given surface data with specified transform.
-///
-/// # Performance
-///
-/// This method is has linear complexity - the more complex mesh you pass, the more
-/// time it will take. Required time increases drastically if you enable shadows and
-/// global illumination (TODO), because in this case your data will be raytraced.
-fn generate_lightmap(
-    mesh: &lightmap::input::Mesh,
-    other_meshes: &[lightmap::input::Mesh],
-    lights: &[LightDefinition],
-    texels_per_unit: u32,
-) -> Texture {
-    let map = lightmap::LightMap::new(mesh, other_meshes, lights, texels_per_unit as usize);
-
-    Texture::from_bytes(
-        TextureKind::Rectangle {
-            width: map.width as u32,
-            height: map.height as u32,
-        },
-        TexturePixelKind::RGB8,
-        map.pixels,
-    )
-    .unwrap()
+/// Lightmap of a single surface instance with colors in `[0; 1]` range (values above one are
+/// possible after adding indirect lighting, they're clamped when the texture is created).
+struct LightmapImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector3<f32>>,
+}
+
+impl LightmapImage {
+    /// Generates lightmap for given mesh with direct lighting only.
+    ///
+    /// # Performance
+    ///
+    /// This method is has linear complexity - the more complex mesh you pass, the more
+    /// time it will take. Required time increases drastically if you enable shadows,
+    /// because in this case your data will be raytraced.
+    fn new(
+        mesh: &lightmap::input::Mesh,
+        other_meshes: &[lightmap::input::Mesh],
+        lights: &[LightDefinition],
+        texels_per_unit: u32,
+    ) -> Self {
+        let map = lightmap::LightMap::new(mesh, other_meshes, lights, texels_per_unit as usize);
+
+        Self {
+            width: map.width,
+            height: map.height,
+            pixels: map
+                .pixels
+                .chunks_exact(3)
+                .map(|rgb| Vector3::new(rgb[0], rgb[1], rgb[2]).map(|c| c as f32 / 255.0))
+                .collect(),
+        }
+    }
+
+    fn into_texture(self) -> Texture {
+        Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: self.width as u32,
+                height: self.height as u32,
+            },
+            TexturePixelKind::RGB8,
+            self.pixels
+                .iter()
+                .flat_map(|p| [p.x, p.y, p.z])
+                .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    /// Smooths out the noise using cross-bilateral filter, that takes colors and normals of the
+    /// texels into account to preserve edges.
+    fn denoise(&mut self, texels: &[Option<Texel>]) {
+        const RADIUS: isize = 2;
+        const COLOR_SIGMA: f32 = 0.1;
+
+        let source = self.pixels.clone();
+        for y in 0..self.height as isize {
+            for x in 0..self.width as isize {
+                let index = y as usize * self.width + x as usize;
+                let Some(center) = texels[index] else {
+                    continue;
+                };
+
+                let mut sum = Vector3::default();
+                let mut weight_sum = 0.0;
+                for dy in -RADIUS..=RADIUS {
+                    for dx in -RADIUS..=RADIUS {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if nx < 0
+                            || ny < 0
+                            || nx >= self.width as isize
+                            || ny >= self.height as isize
+                        {
+                            continue;
+                        }
+
+                        let neighbour_index = ny as usize * self.width + nx as usize;
+                        let Some(neighbour) = texels[neighbour_index] else {
+                            continue;
+                        };
+
+                        let spatial = (-((dx * dx + dy * dy) as f32)
+                            / (2.0 * (RADIUS * RADIUS) as f32))
+                            .exp();
+                        let color_difference = source[neighbour_index] - source[index];
+                        let color = (-color_difference.norm_squared()
+                            / (2.0 * COLOR_SIGMA * COLOR_SIGMA))
+                            .exp();
+                        let normal = center.normal.dot(&neighbour.normal).max(0.0).powi(8);
+
+                        let weight = spatial * color * normal;
+                        sum += source[neighbour_index] * weight;
+                        weight_sum += weight;
+                    }
+                }
+
+                if weight_sum > 0.0 {
+                    self.pixels[index] = sum / weight_sum;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct TexelVertex {
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+    tex_coord: Vector2<f32>,
+}
+
+/// World space geometry of a surface instance with lightmap texture coordinates.
+struct SurfaceGeometry {
+    vertices: Vec<TexelVertex>,
+    triangles: Vec<[u32; 3]>,
+}
+
+/// World space position and normal of a lightmap texel.
+#[derive(Copy, Clone, Debug)]
+struct Texel {
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+}
+
+fn edge_function(a: Vector2<f32>, b: Vector2<f32>, p: Vector2<f32>) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Calculates world space position and normal of every texel of a lightmap, that is covered by
+/// the surface. Texels, that are not covered by any triangle, are `None`.
+fn rasterize_texels(geometry: &SurfaceGeometry, width: usize, height: usize) -> Vec<Option<Texel>> {
+    let mut texels = vec![None; width * height];
+    if width == 0 || height == 0 {
+        return texels;
+    }
+
+    let size = Vector2::new(width as f32, height as f32);
+    for triangle in geometry.triangles.iter() {
+        let (Some(a), Some(b), Some(c)) = (
+            geometry.vertices.get(triangle[0] as usize),
+            geometry.vertices.get(triangle[1] as usize),
+            geometry.vertices.get(triangle[2] as usize),
+        ) else {
+            continue;
+        };
+
+        let pa = a.tex_coord.component_mul(&size);
+        let pb = b.tex_coord.component_mul(&size);
+        let pc = c.tex_coord.component_mul(&size);
+
+        let area = edge_function(pa, pb, pc);
+        if area.abs() <= f32::EPSILON {
+            continue;
+        }
+
+        let min = pa.inf(&pb).inf(&pc);
+        let max = pa.sup(&pb).sup(&pc);
+        let x_range = (min.x.floor().max(0.0) as usize)..=(max.x.ceil() as usize).min(width - 1);
+        let y_range = (min.y.floor().max(0.0) as usize)..=(max.y.ceil() as usize).min(height - 1);
+
+        for y in y_range {
+            for x in x_range.clone() {
+                let p = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let wa = edge_function(pb, pc, p) / area;
+                let wb = edge_function(pc, pa, p) / area;
+                let wc = 1.0 - wa - wb;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+
+                texels[y * width + x] = Some(Texel {
+                    position: a.position * wa + b.position * wb + c.position * wc,
+                    normal: (a.normal * wa + b.normal * wb + c.normal * wc)
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or(a.normal),
+                });
+            }
+        }
+    }
+
+    texels
+}
+
+/// Share of the incoming light, that is reflected by surfaces. Albedo of the surfaces is unknown
+/// to the lightmapper, so an average value is used.
+const REFLECTANCE: f32 = 0.5;
+
+/// Max amount of virtual point lights per bounce.
+const MAX_VIRTUAL_LIGHTS: usize = 1024;
+
+struct VirtualLight {
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+    flux: Vector3<f32>,
+}
+
+/// Places virtual point lights on the lit texels. Every light represents the light reflected by a
+/// patch of `stride` texels.
+fn gather_virtual_lights(
+    sources: &[Vec<Vector3<f32>>],
+    texels: &[Vec<Option<Texel>>],
+    texel_area: f32,
+) -> Vec<VirtualLight> {
+    let covered = texels.iter().flatten().filter(|t| t.is_some()).count();
+    if covered == 0 {
+        return Vec::new();
+    }
+
+    let stride = (covered + MAX_VIRTUAL_LIGHTS - 1) / MAX_VIRTUAL_LIGHTS;
+    let mut lights = Vec::new();
+    for (source, texels) in sources.iter().zip(texels) {
+        for (color, texel) in source
+            .iter()
+            .zip(texels)
+            .filter_map(|(color, texel)| texel.map(|texel| (color, texel)))
+            .step_by(stride)
+        {
+            if color.max() > 0.0 {
+                lights.push(VirtualLight {
+                    position: texel.position,
+                    normal: texel.normal,
+                    flux: color * (REFLECTANCE * stride as f32 * texel_area),
+                });
+            }
+        }
+    }
+    lights
+}
+
+fn indirect_irradiance(texel: &Texel, lights: &[VirtualLight]) -> Vector3<f32> {
+    // Prevents singularities when a light is very close to a texel.
+    const MIN_SQR_DISTANCE: f32 = 0.01;
+
+    let mut irradiance = Vector3::default();
+    for light in lights {
+        let delta = light.position - texel.position;
+        let sqr_distance = delta.norm_squared();
+        if sqr_distance <= f32::EPSILON {
+            continue;
+        }
+        let direction = delta / sqr_distance.sqrt();
+        let cos_receiver = texel.normal.dot(&direction);
+        let cos_emitter = -light.normal.dot(&direction);
+        if cos_receiver > 0.0 && cos_emitter > 0.0 {
+            irradiance += light.flux
+                * (cos_receiver * cos_emitter
+                    / (std::f32::consts::PI * sqr_distance.max(MIN_SQR_DISTANCE)));
+        }
+    }
+    irradiance
+}
+
+/// Adds indirect lighting to the images using instant radiosity - every bounce places virtual
+/// point lights on the surfaces lit by the previous bounce.
+fn add_indirect_lighting(
+    images: &mut [LightmapImage],
+    texels: &[Vec<Option<Texel>>],
+    settings: &LightmapSettings,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<(), LightmapGenerationError> {
+    progress_indicator.set_stage(
+        ProgressStage::IndirectLighting,
+        settings.bounces * images.len() as u32,
+    );
+
+    let texel_area = 1.0 / (settings.texels_per_unit.max(1) as f32).powi(2);
+    let mut sources = images
+        .iter()
+        .map(|image| image.pixels.clone())
+        .collect::<Vec<_>>();
+
+    for _ in 0..settings.bounces {
+        let lights = gather_virtual_lights(&sources, texels, texel_area);
+
+        let bounce = texels
+            .par_iter()
+            .map(|texels| {
+                if cancellation_token.is_cancelled() {
+                    return Err(LightmapGenerationError::Cancelled);
+                }
+
+                let light = texels
+                    .iter()
+                    .map(|texel| {
+                        texel
+                            .map(|texel| indirect_irradiance(&texel, &lights))
+                            .unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>();
+
+                progress_indicator.advance_progress();
+
+                Ok(light)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (image, bounce) in images.iter_mut().zip(bounce.iter()) {
+            for (pixel, light) in image.pixels.iter_mut().zip(bounce) {
+                *pixel += *light;
+            }
+        }
+
+        sources = bounce;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         asset::ResourceData,
-        core::algebra::{Matrix4, Vector3},
+        core::algebra::{Matrix4, Vector2, Vector3},
         scene::{
             base::BaseBuilder,
             light::{point::PointLightBuilder, BaseLightBuilder},
@@ -709,7 +1124,10 @@ mod test {
             transform::TransformBuilder,
             Scene,
         },
-        utils::lightmap::{Lightmap, LightmapInputData},
+        utils::lightmap::{
+            rasterize_texels, Lightmap, LightmapImage, LightmapInputData, SurfaceGeometry,
+            TexelVertex,
+        },
     };
     use fyrox_resource::untyped::ResourceKind;
     use std::path::Path;
@@ -763,4 +1181,43 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_rasterize_and_denoise() {
+        let vertex = |x: f32, y: f32| TexelVertex {
+            position: Vector3::new(x, 0.0, y),
+            normal: Vector3::y(),
+            tex_coord: Vector2::new(x, y),
+        };
+
+        // A quad, that covers the left half of the lightmap.
+        let geometry = SurfaceGeometry {
+            vertices: vec![
+                vertex(0.0, 0.0),
+                vertex(0.5, 0.0),
+                vertex(0.5, 1.0),
+                vertex(0.0, 1.0),
+            ],
+            triangles: vec![[0, 1, 2], [0, 2, 3]],
+        };
+
+        let texels = rasterize_texels(&geometry, 8, 8);
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(texels[y * 8 + x].is_some(), x < 4);
+            }
+        }
+
+        let mut image = LightmapImage {
+            width: 8,
+            height: 8,
+            pixels: (0..64)
+                .map(|i| Vector3::repeat(if i % 2 == 0 { 0.5 } else { 0.52 }))
+                .collect(),
+        };
+        image.denoise(&texels);
+        // Noise is smoothed out on covered texels, but uncovered ones are left untouched.
+        assert!((image.pixels[1].x - image.pixels[0].x).abs() < 0.02);
+        assert_eq!(image.pixels[7].x, 0.52);
+    }
 }
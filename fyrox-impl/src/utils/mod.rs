@@ -24,16 +24,19 @@
 
 pub mod astar;
 pub mod behavior;
+pub mod impostor;
 pub mod lightmap;
 pub mod navmesh;
+pub mod navmesh_bake;
 pub mod raw_mesh;
 pub mod uvgen;
 
 use crate::{
     core::algebra::{Vector2, Vector3},
+    engine::gamepad::{GamepadButton, GamepadEvent, GamepadEventKind},
     event::{ElementState, MouseScrollDelta, WindowEvent},
     gui::{
-        message,
+        gamepad, message,
         message::{ButtonState, KeyboardModifiers, OsEvent},
     },
     keyboard::{KeyCode, ModifiersState},
@@ -578,6 +581,28 @@ pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
     }
 }
 
+/// Translates gamepad event to a fyrox-ui gamepad button event, that could be passed to
+/// [`fyrox_ui::gamepad::GamepadNavigation::process_button`]. Only button presses and releases of
+/// the directional pad and the `South` (accept) and `East` (cancel) buttons are translated.
+pub fn translate_gamepad_event(
+    event: &GamepadEvent,
+) -> Option<(gamepad::GamepadButton, ButtonState)> {
+    if let GamepadEventKind::ButtonInput { button, state } = event.kind {
+        let button = match button {
+            GamepadButton::DPadUp => gamepad::GamepadButton::DPadUp,
+            GamepadButton::DPadDown => gamepad::GamepadButton::DPadDown,
+            GamepadButton::DPadLeft => gamepad::GamepadButton::DPadLeft,
+            GamepadButton::DPadRight => gamepad::GamepadButton::DPadRight,
+            GamepadButton::South => gamepad::GamepadButton::Accept,
+            GamepadButton::East => gamepad::GamepadButton::Cancel,
+            _ => return None,
+        };
+        Some((button, state))
+    } else {
+        None
+    }
+}
+
 /// Translates keyboard modifiers to fyrox-ui keyboard modifiers.
 pub fn translate_keyboard_modifiers(modifiers: ModifiersState) -> KeyboardModifiers {
     KeyboardModifiers {
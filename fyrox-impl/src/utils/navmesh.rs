@@ -27,15 +27,19 @@
 
 use crate::{
     core::{
-        algebra::{Point3, Vector3},
+        algebra::{Matrix4, Point3, Vector3},
         arrayvec::ArrayVec,
+        color::Color,
         math::{self, plane::Plane, ray::Ray, PositionProvider, TriangleDefinition, Vector3Ext},
         reflect::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
-    scene::mesh::{
-        buffer::{VertexAttributeUsage, VertexReadTrait},
-        Mesh,
+    scene::{
+        debug::{Line, SceneDrawingContext},
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            Mesh,
+        },
     },
     utils::{
         astar::{Graph, GraphVertex, PathError, PathKind, VertexData, VertexDataProvider},
@@ -589,6 +593,184 @@ impl Navmesh {
         self.graph.build_positional_path(from, to, path)
     }
 
+    /// Returns the slope (in radians) of a triangle with the given index, which is an angle between the
+    /// normal of the triangle and the up axis (Y). Flat triangles have zero slope, vertical - `π / 2`.
+    pub fn triangle_slope(&self, index: usize) -> Option<f32> {
+        let triangle = self.triangles.get(index)?;
+        let a = self.vertices[triangle[0] as usize];
+        let b = self.vertices[triangle[1] as usize];
+        let c = self.vertices[triangle[2] as usize];
+        let normal = (b - a).cross(&(c - a)).try_normalize(f32::EPSILON)?;
+        Some(normal.y.abs().min(1.0).acos())
+    }
+
+    /// Tries to find a path between two arbitrary points. The points are projected on the navmesh first,
+    /// then A* search finds a sequence of triangles between the projections, and finally the sequence is
+    /// turned into a shortest polyline (string-pulling) that goes through the triangles. `radius` defines
+    /// a distance to keep from the corners of the navmesh, `max_slope` (in radians) defines the steepest
+    /// triangle that could be traversed (see [`Self::triangle_slope`]), use `std::f32::consts::FRAC_PI_2`
+    /// to disable this limit.
+    ///
+    /// If the end point cannot be reached, the method returns [`PathKind::Partial`] with a path to a
+    /// point that is as close to the end point as possible.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use fyrox_impl::utils::navmesh::Navmesh;
+    /// use fyrox_impl::core::algebra::Vector3;
+    ///
+    /// fn find_walkable_path(navmesh: &Navmesh, begin: Vector3<f32>, end: Vector3<f32>) -> Vec<Vector3<f32>> {
+    ///     let mut path = Vec::new();
+    ///     // Keep 0.3 meters away from walls and avoid slopes steeper than 45 degrees.
+    ///     let _ = navmesh.query_path(begin, end, 0.3, 45.0f32.to_radians(), &mut path);
+    ///     path
+    /// }
+    /// ```
+    pub fn query_path(
+        &self,
+        from: Vector3<f32>,
+        to: Vector3<f32>,
+        radius: f32,
+        max_slope: f32,
+        path: &mut Vec<Vector3<f32>>,
+    ) -> Result<PathKind, PathError> {
+        path.clear();
+
+        let (src_point_on_navmesh, src_triangle) =
+            self.query_closest(from).ok_or(PathError::Empty)?;
+        let (mut dest_point_on_navmesh, dest_triangle) =
+            self.query_closest(to).ok_or(PathError::Empty)?;
+
+        if src_triangle == dest_triangle {
+            path.push(src_point_on_navmesh);
+            path.push(dest_point_on_navmesh);
+
+            return Ok(PathKind::Full);
+        }
+
+        let mut path_triangle_indices = Vec::new();
+        let path_kind = self.graph.build_filtered_indexed_path(
+            src_triangle,
+            dest_triangle,
+            |index| {
+                self.triangle_slope(index)
+                    .map_or(true, |slope| slope <= max_slope)
+            },
+            &mut path_triangle_indices,
+        )?;
+
+        path_triangle_indices.reverse();
+
+        if path_kind == PathKind::Partial {
+            // The end is unreachable, so go to the closest point on the last reachable triangle.
+            let mut closest = None;
+            let mut closest_distance = f32::MAX;
+            self.query_closest_internal(
+                &mut closest,
+                &mut closest_distance,
+                path_triangle_indices.last().cloned().into_iter(),
+                to,
+            );
+            if let Some((closest, _)) = closest {
+                dest_point_on_navmesh = closest;
+            }
+        }
+
+        self.straighten_path(
+            src_point_on_navmesh,
+            dest_point_on_navmesh,
+            &path_triangle_indices,
+            radius,
+            path,
+        );
+
+        Ok(path_kind)
+    }
+
+    fn straighten_path(
+        &self,
+        src_position: Vector3<f32>,
+        dest_position: Vector3<f32>,
+        path_triangles: &[usize],
+        radius: f32,
+        path: &mut Vec<Vector3<f32>>,
+    ) {
+        path.push(src_position);
+
+        if path_triangles.len() > 1 {
+            let mut funnel_apex = src_position;
+            let mut funnel_vertices = [funnel_apex; 2];
+            let mut side_indices = [0; 2];
+            let side_signs = [1.0, -1.0];
+
+            let mut i = 0;
+            while i < path_triangles.len() {
+                let portal_vertices = if i + 1 < path_triangles.len() {
+                    let portal = self
+                        .portal_between(path_triangles[i], path_triangles[i + 1])
+                        .unwrap();
+
+                    let mut left = self.vertices[portal.left];
+                    let mut right = self.vertices[portal.right];
+
+                    if radius > 0.0 {
+                        let delta = right - left;
+                        let len = delta.norm();
+                        let offset = delta.scale(radius.min(len * 0.5) / len);
+
+                        left += offset;
+                        right -= offset;
+                    }
+
+                    [left, right]
+                } else {
+                    [dest_position, dest_position]
+                };
+
+                for current in 0..2 {
+                    let opposite = 1 - current;
+                    let side_sign = side_signs[current];
+                    if side_sign
+                        * triangle_area_2d(
+                            funnel_apex,
+                            funnel_vertices[current],
+                            portal_vertices[current],
+                        )
+                        >= 0.0
+                    {
+                        if funnel_apex == funnel_vertices[current]
+                            || side_sign
+                                * triangle_area_2d(
+                                    funnel_apex,
+                                    funnel_vertices[opposite],
+                                    portal_vertices[current],
+                                )
+                                < 0.0
+                        {
+                            funnel_vertices[current] = portal_vertices[current];
+                            side_indices[current] = i;
+                        } else {
+                            funnel_apex = funnel_vertices[opposite];
+                            funnel_vertices = [funnel_apex; 2];
+
+                            path.push(funnel_apex);
+
+                            i = side_indices[opposite];
+                            side_indices[current] = i;
+
+                            break;
+                        }
+                    }
+                }
+
+                i += 1;
+            }
+        }
+
+        path.push(dest_position);
+    }
+
     /// Tries to pick a triangle by given ray. Returns closest result.
     pub fn ray_cast(&self, ray: Ray) -> Option<(Vector3<f32>, usize)> {
         let mut buffer = ArrayVec::<usize, 128>::new();
@@ -665,6 +847,7 @@ pub struct NavmeshAgent {
     path_dirty: bool,
     radius: f32,
     interpolator: f32,
+    max_slope: f32,
 }
 
 impl Default for NavmeshAgent {
@@ -688,6 +871,7 @@ impl NavmeshAgent {
             path_dirty: true,
             radius: 0.2,
             interpolator: 0.0,
+            max_slope: std::f32::consts::FRAC_PI_2,
         }
     }
 
@@ -736,135 +920,66 @@ impl NavmeshAgent {
     pub fn radius(&self) -> f32 {
         self.radius
     }
+
+    /// Sets the steepest slope (in radians) of the navmesh triangles that the agent can walk on. Steeper
+    /// triangles will be avoided when calculating the path. The default value is `π / 2`, which means
+    /// that there's no limit.
+    pub fn set_max_slope(&mut self, max_slope: f32) {
+        if self.max_slope != max_slope {
+            self.max_slope = max_slope;
+            self.path_dirty = true;
+        }
+    }
+
+    /// Returns the steepest slope (in radians) that the agent can walk on. See [`Self::set_max_slope`]
+    /// for more info.
+    pub fn max_slope(&self) -> f32 {
+        self.max_slope
+    }
+
+    /// Draws the current path of the agent and its radius using the given drawing context. Could be useful
+    /// for debugging purposes.
+    pub fn debug_draw(&self, ctx: &mut SceneDrawingContext, color: Color) {
+        for segment in self.path.windows(2) {
+            ctx.add_line(Line {
+                begin: segment[0],
+                end: segment[1],
+                color,
+            });
+        }
+
+        // Draw the radius as a circle that lies on the XZ plane.
+        ctx.draw_circle(
+            Vector3::default(),
+            self.radius.max(0.01),
+            16,
+            Matrix4::new_translation(&self.position)
+                * Matrix4::from_axis_angle(&Vector3::x_axis(), std::f32::consts::FRAC_PI_2),
+            color,
+        );
+    }
 }
 
 impl NavmeshAgent {
     /// Calculates path from point A to point B. In most cases there is no need to use this method
-    /// directly, because `update` will call it anyway if target position has moved.
+    /// directly, because `update` will call it anyway if target position has moved. The path respects
+    /// the radius and the max slope of the agent, see [`Navmesh::query_path`] for more info.
     pub fn calculate_path(
         &mut self,
         navmesh: &Navmesh,
         src_point: Vector3<f32>,
         dest_point: Vector3<f32>,
     ) -> Result<PathKind, PathError> {
-        self.path.clear();
-
         self.current = 0;
         self.interpolator = 0.0;
 
-        if let Some((src_point_on_navmesh, src_triangle)) = navmesh.query_closest(src_point) {
-            if let Some((dest_point_on_navmesh, dest_triangle)) = navmesh.query_closest(dest_point)
-            {
-                if src_triangle == dest_triangle {
-                    self.path.push(src_point_on_navmesh);
-                    self.path.push(dest_point_on_navmesh);
-
-                    return Ok(PathKind::Full);
-                }
-
-                let mut path_triangle_indices = Vec::new();
-                let path_kind = navmesh.graph.build_indexed_path(
-                    src_triangle,
-                    dest_triangle,
-                    &mut path_triangle_indices,
-                )?;
-
-                path_triangle_indices.reverse();
-
-                self.straighten_path(
-                    navmesh,
-                    src_point_on_navmesh,
-                    dest_point_on_navmesh,
-                    &path_triangle_indices,
-                );
-
-                return Ok(path_kind);
-            }
-        }
-
-        Err(PathError::Empty)
-    }
-
-    fn straighten_path(
-        &mut self,
-        navmesh: &Navmesh,
-        src_position: Vector3<f32>,
-        dest_position: Vector3<f32>,
-        path_triangles: &[usize],
-    ) {
-        self.path.push(src_position);
-
-        if path_triangles.len() > 1 {
-            let mut funnel_apex = src_position;
-            let mut funnel_vertices = [funnel_apex; 2];
-            let mut side_indices = [0; 2];
-            let side_signs = [1.0, -1.0];
-
-            let mut i = 0;
-            while i < path_triangles.len() {
-                let portal_vertices = if i + 1 < path_triangles.len() {
-                    let portal = navmesh
-                        .portal_between(path_triangles[i], path_triangles[i + 1])
-                        .unwrap();
-
-                    let mut left = navmesh.vertices[portal.left];
-                    let mut right = navmesh.vertices[portal.right];
-
-                    if self.radius > 0.0 {
-                        let delta = right - left;
-                        let len = delta.norm();
-                        let offset = delta.scale(self.radius.min(len * 0.5) / len);
-
-                        left += offset;
-                        right -= offset;
-                    }
-
-                    [left, right]
-                } else {
-                    [dest_position, dest_position]
-                };
-
-                for current in 0..2 {
-                    let opposite = 1 - current;
-                    let side_sign = side_signs[current];
-                    if side_sign
-                        * triangle_area_2d(
-                            funnel_apex,
-                            funnel_vertices[current],
-                            portal_vertices[current],
-                        )
-                        >= 0.0
-                    {
-                        if funnel_apex == funnel_vertices[current]
-                            || side_sign
-                                * triangle_area_2d(
-                                    funnel_apex,
-                                    funnel_vertices[opposite],
-                                    portal_vertices[current],
-                                )
-                                < 0.0
-                        {
-                            funnel_vertices[current] = portal_vertices[current];
-                            side_indices[current] = i;
-                        } else {
-                            funnel_apex = funnel_vertices[opposite];
-                            funnel_vertices = [funnel_apex; 2];
-
-                            self.path.push(funnel_apex);
-
-                            i = side_indices[opposite];
-                            side_indices[current] = i;
-
-                            break;
-                        }
-                    }
-                }
-
-                i += 1;
-            }
-        }
-
-        self.path.push(dest_position);
+        navmesh.query_path(
+            src_point,
+            dest_point,
+            self.radius,
+            self.max_slope,
+            &mut self.path,
+        )
     }
 
     /// Performs single update tick that moves agent to the target along the path (which is automatically
@@ -934,6 +1049,8 @@ pub struct NavmeshAgentBuilder {
     target: Vector3<f32>,
     recalculation_threshold: f32,
     speed: f32,
+    radius: f32,
+    max_slope: f32,
 }
 
 impl Default for NavmeshAgentBuilder {
@@ -950,6 +1067,8 @@ impl NavmeshAgentBuilder {
             target: Default::default(),
             recalculation_threshold: 0.25,
             speed: 1.5,
+            radius: 0.2,
+            max_slope: std::f32::consts::FRAC_PI_2,
         }
     }
 
@@ -977,6 +1096,19 @@ impl NavmeshAgentBuilder {
         self
     }
 
+    /// Sets new desired radius of the agent being built. See [`NavmeshAgent::set_radius`] for more info.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets new desired max slope (in radians) of the agent being built. See [`NavmeshAgent::set_max_slope`]
+    /// for more info.
+    pub fn with_max_slope(mut self, max_slope: f32) -> Self {
+        self.max_slope = max_slope;
+        self
+    }
+
     /// Build the agent.
     pub fn build(self) -> NavmeshAgent {
         NavmeshAgent {
@@ -986,6 +1118,8 @@ impl NavmeshAgentBuilder {
             last_target_position: self.target,
             recalculation_threshold: self.recalculation_threshold,
             speed: self.speed,
+            radius: self.radius,
+            max_slope: self.max_slope,
             ..Default::default()
         }
     }
@@ -995,7 +1129,10 @@ impl NavmeshAgentBuilder {
 mod test {
     use crate::{
         core::{algebra::Vector3, math::TriangleDefinition},
-        utils::navmesh::{Navmesh, NavmeshAgent},
+        utils::{
+            astar::PathKind,
+            navmesh::{Navmesh, NavmeshAgent},
+        },
     };
 
     #[test]
@@ -1054,4 +1191,53 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_navmesh_max_slope() {
+        // Flat quad, then a steep ramp (~63 degrees), then another flat quad on top of the ramp.
+        let navmesh = Navmesh::new(
+            vec![
+                TriangleDefinition([0, 1, 3]),
+                TriangleDefinition([1, 2, 3]),
+                TriangleDefinition([2, 5, 3]),
+                TriangleDefinition([2, 4, 5]),
+                TriangleDefinition([4, 7, 5]),
+                TriangleDefinition([4, 6, 7]),
+            ],
+            vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(0.0, 0.0, 1.0),
+                Vector3::new(1.0, 0.0, 1.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(2.0, 2.0, 1.0),
+                Vector3::new(2.0, 2.0, 0.0),
+                Vector3::new(3.0, 2.0, 1.0),
+                Vector3::new(3.0, 2.0, 0.0),
+            ],
+        );
+
+        assert_eq!(navmesh.triangle_slope(0), Some(0.0));
+        assert!(navmesh.triangle_slope(2).unwrap() > 60.0f32.to_radians());
+
+        let from = Vector3::new(0.1, 0.0, 0.1);
+        let to = Vector3::new(2.9, 2.0, 0.9);
+        let mut path = Vec::new();
+
+        assert_eq!(
+            navmesh
+                .query_path(from, to, 0.0, std::f32::consts::FRAC_PI_2, &mut path)
+                .unwrap(),
+            PathKind::Full
+        );
+        assert_eq!(path.last(), Some(&to));
+
+        assert_eq!(
+            navmesh
+                .query_path(from, to, 0.0, 45.0f32.to_radians(), &mut path)
+                .unwrap(),
+            PathKind::Partial
+        );
+        // The agent must stop at the bottom of the ramp.
+        assert!(path.iter().all(|p| p.x <= 1.0 && p.y == 0.0));
+    }
 }
@@ -0,0 +1,512 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Navmesh baking - automatic generation of navigational meshes from arbitrary level geometry.
+//! See [`bake_navmesh`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector2, Vector3},
+        arrayvec::ArrayVec,
+        math::TriangleDefinition,
+        reflect::prelude::*,
+    },
+    scene::mesh::{
+        buffer::{VertexAttributeUsage, VertexReadTrait},
+        Mesh,
+    },
+    utils::navmesh::Navmesh,
+};
+use fxhash::FxHashMap;
+use std::ops::Range;
+
+/// A set of parameters, that defines which parts of the geometry are walkable for an agent.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct NavmeshBakeSettings {
+    /// Size of a cell (in meters) of the grid, that is used to sample the geometry. Smaller cells
+    /// give more precise navmesh, but with a lot more triangles.
+    #[reflect(min_value = 0.01, step = 0.05)]
+    pub cell_size: f32,
+    /// Radius of an agent (in meters). Walkable area is shrunk by the radius, so the agent won't
+    /// intersect walls while moving along the navmesh.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_radius: f32,
+    /// Height of an agent (in meters). Areas with lower ceiling are not walkable.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub agent_height: f32,
+    /// The steepest slope (in radians), that is still walkable.
+    #[reflect(min_value = 0.0, max_value = 1.57, step = 0.05)]
+    pub max_slope: f32,
+    /// Max height (in meters) of a step, that an agent is able to climb.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub max_step_height: f32,
+}
+
+impl Default for NavmeshBakeSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.25,
+            agent_radius: 0.3,
+            agent_height: 1.8,
+            max_slope: std::f32::consts::FRAC_PI_4,
+            max_step_height: 0.3,
+        }
+    }
+}
+
+// A solid vertical range of a cell of the grid.
+#[derive(Copy, Clone, Debug)]
+struct Span {
+    min: f32,
+    max: f32,
+    walkable: bool,
+}
+
+/// Appends world-space triangles of every surface of the given mesh to the given list. It could be
+/// used to collect the input geometry for [`bake_navmesh`].
+pub fn collect_mesh_triangles(mesh: &Mesh, triangles: &mut Vec<[Vector3<f32>; 3]>) {
+    let global_transform = mesh.global_transform();
+    for surface in mesh.surfaces() {
+        let data = surface.data();
+        let data = data.data_ref();
+        let vertex_buffer = &data.vertex_buffer;
+        let position = |index: u32| {
+            vertex_buffer
+                .get(index as usize)
+                .and_then(|vertex| vertex.read_3_f32(VertexAttributeUsage::Position).ok())
+                .map(|position| {
+                    global_transform
+                        .transform_point(&Point3::from(position))
+                        .coords
+                })
+        };
+        for triangle in data.geometry_buffer.iter() {
+            if let (Some(a), Some(b), Some(c)) = (
+                position(triangle[0]),
+                position(triangle[1]),
+                position(triangle[2]),
+            ) {
+                triangles.push([a, b, c]);
+            }
+        }
+    }
+}
+
+/// Bakes a navigational mesh from the given set of world-space triangles. The geometry is sampled
+/// on a regular grid, every cell of the grid could have multiple walkable surfaces (for example,
+/// multi-floor buildings). A surface is walkable, if its slope is less than the max slope, it has
+/// enough free space above for an agent and it is far enough from the edges of the walkable area.
+/// Walkable surfaces of neighbouring cells are connected if the difference between their heights
+/// is less than the max step height.
+///
+/// Every walkable cell produces a quad, so the amount of triangles in the resulting navmesh depends
+/// on the cell size.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::algebra::Vector3,
+/// #     utils::navmesh_bake::{bake_navmesh, NavmeshBakeSettings},
+/// # };
+/// let floor = [
+///     [Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 4.0), Vector3::new(4.0, 0.0, 4.0)],
+///     [Vector3::new(0.0, 0.0, 0.0), Vector3::new(4.0, 0.0, 4.0), Vector3::new(4.0, 0.0, 0.0)],
+/// ];
+/// let navmesh = bake_navmesh(&floor, &NavmeshBakeSettings::default());
+/// assert!(!navmesh.triangles().is_empty());
+/// ```
+pub fn bake_navmesh(triangles: &[[Vector3<f32>; 3]], settings: &NavmeshBakeSettings) -> Navmesh {
+    let Some((min, max)) = bounds(triangles) else {
+        return Navmesh::default();
+    };
+
+    let cell_size = settings.cell_size.max(0.01);
+    let width = (((max.x - min.x) / cell_size).ceil() as usize).max(1);
+    let depth = (((max.z - min.z) / cell_size).ceil() as usize).max(1);
+    let cell_index = |coord: f32, origin: f32, count: usize| {
+        (((coord - origin) / cell_size).floor().max(0.0) as usize).min(count - 1)
+    };
+
+    // Rasterize the triangles into columns of solid spans.
+    let min_normal_y = settings.max_slope.cos();
+    let mut columns = vec![Vec::new(); width * depth];
+    for triangle in triangles {
+        let [a, b, c] = *triangle;
+        let Some(normal) = (b - a).cross(&(c - a)).try_normalize(f32::EPSILON) else {
+            continue;
+        };
+        let walkable = normal.y.abs() >= min_normal_y;
+        let triangle_min = a.inf(&b).inf(&c);
+        let triangle_max = a.sup(&b).sup(&c);
+        for z in cell_index(triangle_min.z, min.z, depth)..=cell_index(triangle_max.z, min.z, depth)
+        {
+            for x in
+                cell_index(triangle_min.x, min.x, width)..=cell_index(triangle_max.x, min.x, width)
+            {
+                let cell_min =
+                    Vector2::new(min.x + x as f32 * cell_size, min.z + z as f32 * cell_size);
+                if let Some((span_min, span_max)) = clip_to_cell(triangle, cell_min, cell_size) {
+                    columns[z * width + x].push(Span {
+                        min: span_min,
+                        max: span_max,
+                        walkable,
+                    });
+                }
+            }
+        }
+    }
+
+    // Find the surfaces, that have enough free space above.
+    let mut heights = Vec::new();
+    let mut cells = Vec::with_capacity(columns.len());
+    for column in columns.iter_mut() {
+        merge_spans(column, settings.max_step_height);
+        let start = heights.len();
+        for (index, span) in column.iter().enumerate() {
+            let clearance = column
+                .get(index + 1)
+                .map_or(f32::MAX, |next| next.min - span.max);
+            if span.walkable && clearance >= settings.agent_height {
+                heights.push(span.max);
+            }
+        }
+        cells.push(start..heights.len());
+    }
+
+    let grid = Grid {
+        width,
+        depth,
+        cells,
+        heights,
+    };
+
+    // Remove the surfaces, that are too close to the edges of the walkable area.
+    let radius = (settings.agent_radius / cell_size).ceil() as i32;
+    let mut walkable = vec![true; grid.heights.len()];
+    for z in 0..depth {
+        for x in 0..width {
+            for surface in grid.cells[z * width + x].clone() {
+                walkable[surface] =
+                    grid.is_area_walkable(x, z, surface, radius, settings.max_step_height);
+            }
+        }
+    }
+
+    grid.triangulate(&walkable, min, cell_size, settings.max_step_height)
+}
+
+struct Grid {
+    width: usize,
+    depth: usize,
+    // Range of the surfaces of every cell.
+    cells: Vec<Range<usize>>,
+    heights: Vec<f32>,
+}
+
+impl Grid {
+    fn find_surface(&self, x: i32, z: i32, height: f32, tolerance: f32) -> Option<usize> {
+        if x < 0 || z < 0 || x as usize >= self.width || z as usize >= self.depth {
+            return None;
+        }
+        self.cells[z as usize * self.width + x as usize]
+            .clone()
+            .find(|surface| (self.heights[*surface] - height).abs() <= tolerance)
+    }
+
+    // Checks whether every cell in the given radius around the surface has a connected surface.
+    fn is_area_walkable(
+        &self,
+        x: usize,
+        z: usize,
+        surface: usize,
+        radius: i32,
+        max_step_height: f32,
+    ) -> bool {
+        let height = self.heights[surface];
+        for dz in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dz * dz > radius * radius {
+                    continue;
+                }
+                let tolerance = max_step_height * dx.abs().max(dz.abs()) as f32;
+                if self
+                    .find_surface(x as i32 + dx, z as i32 + dz, height, tolerance)
+                    .is_none()
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn triangulate(
+        &self,
+        walkable: &[bool],
+        origin: Vector3<f32>,
+        cell_size: f32,
+        max_step_height: f32,
+    ) -> Navmesh {
+        // Every walkable surface has four corners, the corners of neighbouring surfaces are merged
+        // into a single vertex if the surfaces are connected.
+        let mut parents = (0..walkable.len() * 4).collect::<Vec<_>>();
+        let mut corner = Vec::with_capacity(4);
+        for cz in 0..=self.depth {
+            for cx in 0..=self.width {
+                corner.clear();
+                // Cells around the corner and the index of the corner in each cell.
+                for (dx, dz, slot) in [(-1, -1, 2), (0, -1, 3), (-1, 0, 1), (0, 0, 0)] {
+                    let (x, z) = (cx as i32 + dx, cz as i32 + dz);
+                    if x < 0 || z < 0 || x as usize >= self.width || z as usize >= self.depth {
+                        continue;
+                    }
+                    for surface in self.cells[z as usize * self.width + x as usize].clone() {
+                        if walkable[surface] {
+                            corner.push((surface * 4 + slot, self.heights[surface]));
+                        }
+                    }
+                }
+                for (i, (a, a_height)) in corner.iter().enumerate() {
+                    for (b, b_height) in corner.iter().skip(i + 1) {
+                        if (a_height - b_height).abs() <= max_step_height {
+                            union(&mut parents, *a, *b);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut vertices = Vec::new();
+        let mut vertex_heights = Vec::<(f32, usize)>::new();
+        let mut vertex_map = FxHashMap::default();
+        let mut triangles = Vec::new();
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                for surface in self.cells[z * self.width + x].clone() {
+                    if !walkable[surface] {
+                        continue;
+                    }
+                    let mut quad = [0; 4];
+                    for (slot, (dx, dz)) in [(0, 0), (1, 0), (1, 1), (0, 1)].into_iter().enumerate()
+                    {
+                        let root = find(&mut parents, surface * 4 + slot);
+                        let index = *vertex_map.entry(root).or_insert_with(|| {
+                            vertices.push(Vector3::new(
+                                origin.x + (x + dx) as f32 * cell_size,
+                                0.0,
+                                origin.z + (z + dz) as f32 * cell_size,
+                            ));
+                            vertex_heights.push((0.0, 0));
+                            vertices.len() as u32 - 1
+                        });
+                        let (sum, count) = &mut vertex_heights[index as usize];
+                        *sum += self.heights[surface];
+                        *count += 1;
+                        quad[slot] = index;
+                    }
+                    triangles.push(TriangleDefinition([quad[0], quad[2], quad[1]]));
+                    triangles.push(TriangleDefinition([quad[0], quad[3], quad[2]]));
+                }
+            }
+        }
+
+        for (vertex, (sum, count)) in vertices.iter_mut().zip(vertex_heights) {
+            vertex.y = sum / count as f32;
+        }
+
+        Navmesh::new(triangles, vertices)
+    }
+}
+
+fn find(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let a = find(parents, a);
+    let b = find(parents, b);
+    if a != b {
+        parents[b] = a;
+    }
+}
+
+fn bounds(triangles: &[[Vector3<f32>; 3]]) -> Option<(Vector3<f32>, Vector3<f32>)> {
+    triangles.iter().flatten().fold(None, |bounds, point| {
+        Some(bounds.map_or(
+            (*point, *point),
+            |(min, max): (Vector3<f32>, Vector3<f32>)| (min.inf(point), max.sup(point)),
+        ))
+    })
+}
+
+// Merges overlapping spans of a column. Merged span is walkable if its top belongs to a walkable
+// span, small bumps (less than the max step height) on a walkable surface keep it walkable.
+fn merge_spans(spans: &mut Vec<Span>, max_step_height: f32) {
+    spans.sort_by(|a, b| a.min.total_cmp(&b.min));
+    let mut merged = Vec::<Span>::with_capacity(spans.len());
+    for span in spans.drain(..) {
+        match merged.last_mut() {
+            Some(last) if span.min <= last.max => {
+                let difference = span.max - last.max;
+                if difference.abs() <= max_step_height {
+                    last.walkable |= span.walkable;
+                } else if difference > 0.0 {
+                    last.walkable = span.walkable;
+                }
+                last.max = last.max.max(span.max);
+            }
+            _ => merged.push(span),
+        }
+    }
+    *spans = merged;
+}
+
+// Clips a triangle by the vertical column of a cell and returns vertical extents of the clipped
+// polygon, if any.
+fn clip_to_cell(
+    triangle: &[Vector3<f32>; 3],
+    cell_min: Vector2<f32>,
+    cell_size: f32,
+) -> Option<(f32, f32)> {
+    let mut polygon = triangle.iter().cloned().collect::<ArrayVec<_, 8>>();
+    for (axis, bound, sign) in [
+        (0, cell_min.x, 1.0),
+        (0, cell_min.x + cell_size, -1.0),
+        (2, cell_min.y, 1.0),
+        (2, cell_min.y + cell_size, -1.0),
+    ] {
+        polygon = clip_polygon(&polygon, axis, bound, sign);
+        if polygon.is_empty() {
+            return None;
+        }
+    }
+    Some(
+        polygon
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), point| {
+                (min.min(point.y), max.max(point.y))
+            }),
+    )
+}
+
+// Sutherland-Hodgman clipping of a convex polygon by an axis-aligned plane.
+fn clip_polygon(
+    polygon: &[Vector3<f32>],
+    axis: usize,
+    bound: f32,
+    sign: f32,
+) -> ArrayVec<Vector3<f32>, 8> {
+    let mut result = ArrayVec::new();
+    for (index, a) in polygon.iter().enumerate() {
+        let b = polygon[(index + 1) % polygon.len()];
+        let a_distance = (a[axis] - bound) * sign;
+        let b_distance = (b[axis] - bound) * sign;
+        if a_distance >= 0.0 {
+            result.push(*a);
+        }
+        if (a_distance >= 0.0) != (b_distance >= 0.0) {
+            result.push(a.lerp(&b, a_distance / (a_distance - b_distance)));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        utils::navmesh_bake::{bake_navmesh, NavmeshBakeSettings},
+    };
+
+    fn quad(min: Vector3<f32>, size: f32) -> [[Vector3<f32>; 3]; 2] {
+        let a = min;
+        let b = min + Vector3::new(0.0, 0.0, size);
+        let c = min + Vector3::new(size, 0.0, size);
+        let d = min + Vector3::new(size, 0.0, 0.0);
+        [[a, b, c], [a, c, d]]
+    }
+
+    #[test]
+    fn test_flat_floor() {
+        let settings = NavmeshBakeSettings {
+            cell_size: 0.5,
+            agent_radius: 0.0,
+            ..Default::default()
+        };
+        let navmesh = bake_navmesh(&quad(Vector3::default(), 10.0), &settings);
+        assert_eq!(navmesh.triangles().len(), 20 * 20 * 2);
+        assert_eq!(navmesh.vertices().len(), 21 * 21);
+
+        let settings = NavmeshBakeSettings {
+            agent_radius: 1.0,
+            ..settings
+        };
+        let navmesh = bake_navmesh(&quad(Vector3::default(), 10.0), &settings);
+        assert_eq!(navmesh.triangles().len(), 16 * 16 * 2);
+        assert_eq!(navmesh.vertices().len(), 17 * 17);
+    }
+
+    #[test]
+    fn test_low_ceiling() {
+        let settings = NavmeshBakeSettings {
+            cell_size: 0.5,
+            agent_radius: 0.0,
+            agent_height: 2.0,
+            ..Default::default()
+        };
+        let mut triangles = quad(Vector3::default(), 10.0).to_vec();
+        // A ceiling, that covers the first 10 columns of cells.
+        let ceiling = [
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 10.0),
+            Vector3::new(4.9, 1.0, 10.0),
+            Vector3::new(4.9, 1.0, 0.0),
+        ];
+        triangles.push([ceiling[0], ceiling[1], ceiling[2]]);
+        triangles.push([ceiling[0], ceiling[2], ceiling[3]]);
+        let navmesh = bake_navmesh(&triangles, &settings);
+        // Half of the floor and the top of the ceiling.
+        assert_eq!(navmesh.triangles().len(), 2 * 10 * 20 * 2);
+        assert!(navmesh
+            .vertices()
+            .iter()
+            .all(|vertex| vertex.y == 1.0 || vertex.x >= 5.0));
+    }
+
+    #[test]
+    fn test_steep_slope_is_not_walkable() {
+        let settings = NavmeshBakeSettings {
+            cell_size: 0.5,
+            agent_radius: 0.0,
+            ..Default::default()
+        };
+        let slope = [[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 10.0, 1.0),
+            Vector3::new(1.0, 10.0, 1.0),
+        ]];
+        assert!(bake_navmesh(&slope, &settings).triangles().is_empty());
+    }
+}
@@ -518,27 +518,20 @@ where
     }
 }
 
-/// Collects all resources used by a given entity. Internally, it uses reflection to iterate over
-/// each field of every descendant sub-object of the entity. This function could be used to collect
-/// all resources used by an object, which could be useful if you're building a resource dependency
-/// analyzer.
-pub fn collect_used_resources(
-    entity: &dyn Reflect,
-    resources_collection: &mut FxHashSet<UntypedResource>,
-) {
-    #[inline(always)]
-    fn type_is<T: Reflect>(entity: &dyn Reflect) -> bool {
-        let mut types_match = false;
-        entity.downcast_ref::<T>(&mut |v| {
-            types_match = v.is_some();
-        });
-        types_match
-    }
-
-    // Skip potentially large chunks of numeric data, that definitely cannot contain any resources.
-    // TODO: This is a brute-force solution which does not include all potential types with plain
-    // data.
-    let mut finished = type_is::<Vec<u8>>(entity)
+#[inline(always)]
+fn type_is<T: Reflect>(entity: &dyn Reflect) -> bool {
+    let mut types_match = false;
+    entity.downcast_ref::<T>(&mut |v| {
+        types_match = v.is_some();
+    });
+    types_match
+}
+
+// Skip potentially large chunks of numeric data, that definitely cannot contain any resources.
+// TODO: This is a brute-force solution which does not include all potential types with plain
+// data.
+fn is_plain_data(entity: &dyn Reflect) -> bool {
+    type_is::<Vec<u8>>(entity)
         || type_is::<Vec<u16>>(entity)
         || type_is::<Vec<u32>>(entity)
         || type_is::<Vec<u64>>(entity)
@@ -547,7 +540,18 @@ pub fn collect_used_resources(
         || type_is::<Vec<i32>>(entity)
         || type_is::<Vec<i64>>(entity)
         || type_is::<Vec<f32>>(entity)
-        || type_is::<Vec<f64>>(entity);
+        || type_is::<Vec<f64>>(entity)
+}
+
+/// Collects all resources used by a given entity. Internally, it uses reflection to iterate over
+/// each field of every descendant sub-object of the entity. This function could be used to collect
+/// all resources used by an object, which could be useful if you're building a resource dependency
+/// analyzer.
+pub fn collect_used_resources(
+    entity: &dyn Reflect,
+    resources_collection: &mut FxHashSet<UntypedResource>,
+) {
+    let mut finished = is_plain_data(entity);
 
     if finished {
         return;
@@ -615,3 +619,123 @@ pub fn collect_used_resources(
         }
     })
 }
+
+/// Checks whether a given entity references the given resource. Unlike [`collect_used_resources`],
+/// this function also checks the contents of embedded resources used by the entity (for example,
+/// textures of embedded materials).
+pub fn is_resource_used(entity: &dyn Reflect, resource: &UntypedResource) -> bool {
+    let mut resources = FxHashSet::default();
+    collect_used_resources(entity, &mut resources);
+    resources.contains(resource)
+        || resources
+            .iter()
+            .filter(|used| used.is_embedded())
+            .any(|embedded| {
+                let mut used = false;
+                if let ResourceState::Ok(ref data) = embedded.0.lock().state {
+                    data.as_reflect(&mut |data| used = is_resource_used(data, resource));
+                }
+                used
+            })
+}
+
+/// Replaces every reference to the `what` resource with the `with` resource in a given entity and
+/// returns the amount of replaced references. Internally, it uses reflection to iterate over each
+/// field of every descendant sub-object of the entity, so fields marked with `#[reflect(hidden)]`
+/// attribute will be ignored. Contents of embedded resources used by the entity are processed as
+/// well. Inheritable variables that contain at least one replaced reference are marked as modified,
+/// so the new references won't be overwritten by property inheritance.
+///
+/// # Important notes
+///
+/// This function does not check whether the resources have the same type. Replacing a resource
+/// with a resource of other type will make typed resources ([`Resource`]) invalid, so it is up to
+/// the caller to check that [`UntypedResource::type_uuid`] of both resources match.
+pub fn replace_used_resource(
+    entity: &mut dyn Reflect,
+    what: &UntypedResource,
+    with: &UntypedResource,
+) -> usize {
+    if is_plain_data(entity) {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut finished = false;
+
+    entity.downcast_mut::<UntypedResource>(&mut |v| {
+        if let Some(resource) = v {
+            if resource == what {
+                *resource = with.clone();
+                count += 1;
+            } else if resource.is_embedded() {
+                if let ResourceState::Ok(ref mut data) = resource.0.lock().state {
+                    data.as_reflect_mut(&mut |data| {
+                        count += replace_used_resource(data, what, with)
+                    });
+                }
+            }
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_array_mut(&mut |array| {
+        if let Some(array) = array {
+            for i in 0..array.reflect_len() {
+                if let Some(item) = array.reflect_index_mut(i) {
+                    count += replace_used_resource(item, what, with);
+                }
+            }
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_inheritable_variable_mut(&mut |inheritable| {
+        if let Some(inheritable) = inheritable {
+            let replaced = replace_used_resource(inheritable.inner_value_mut(), what, with);
+            if replaced > 0 {
+                inheritable.mark_modified();
+            }
+            count += replaced;
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_hash_map_mut(&mut |hash_map| {
+        if let Some(hash_map) = hash_map {
+            for i in 0..hash_map.reflect_len() {
+                if let Some(value) = hash_map.reflect_get_nth_value_mut(i) {
+                    count += replace_used_resource(value, what, with);
+                }
+            }
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.fields_mut(&mut |fields| {
+        for field in fields {
+            count += replace_used_resource(*field, what, with);
+        }
+    });
+
+    count
+}
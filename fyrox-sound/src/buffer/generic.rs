@@ -43,7 +43,7 @@
 
 #![allow(clippy::manual_range_contains)]
 
-use crate::{buffer::DataSource, decoder::Decoder};
+use crate::{buffer::DataSource, caption::CaptionTrack, decoder::Decoder};
 use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
 use std::time::Duration;
 
@@ -61,6 +61,8 @@ pub struct GenericBuffer {
     pub(crate) sample_rate: usize,
     #[visit(skip)]
     pub(crate) channel_duration_in_samples: usize,
+    #[visit(skip)]
+    pub(crate) captions: Option<CaptionTrack>,
 }
 
 impl GenericBuffer {
@@ -93,6 +95,7 @@ impl GenericBuffer {
                         samples,
                         channel_count,
                         sample_rate,
+                        captions: None,
                     })
                 }
             }
@@ -126,6 +129,7 @@ impl GenericBuffer {
                     channel_count: decoder.get_channel_count(),
                     channel_duration_in_samples: decoder.channel_duration_in_samples(),
                     samples: decoder.into_samples(),
+                    captions: None,
                 })
             }
         }
@@ -174,4 +178,17 @@ impl GenericBuffer {
     pub fn channel_duration_in_samples(&self) -> usize {
         self.channel_duration_in_samples
     }
+
+    /// Returns a reference to the caption track of the buffer (if any).
+    #[inline]
+    pub fn captions(&self) -> Option<&CaptionTrack> {
+        self.captions.as_ref()
+    }
+
+    /// Sets new caption track of the buffer. Sound context will emit caption events for every sound
+    /// source that plays this buffer. See [`CaptionTrack`] docs for more info.
+    #[inline]
+    pub fn set_captions(&mut self, captions: Option<CaptionTrack>) -> Option<CaptionTrack> {
+        std::mem::replace(&mut self.captions, captions)
+    }
 }
@@ -20,8 +20,11 @@
 
 //! Sound buffer loader.
 
-use crate::buffer::{DataSource, SoundBuffer};
-use fyrox_core::{reflect::prelude::*, uuid::Uuid, TypeUuidProvider};
+use crate::{
+    buffer::{DataSource, SoundBuffer},
+    caption::CaptionTrack,
+};
+use fyrox_core::{log::Log, reflect::prelude::*, uuid::Uuid, TypeUuidProvider};
 use fyrox_resource::{
     io::ResourceIo,
     loader::{BoxedImportOptionsLoaderFuture, BoxedLoaderFuture, LoaderPayload, ResourceLoader},
@@ -36,8 +39,12 @@ use std::{path::PathBuf, sync::Arc};
 /// Defines sound buffer resource import options.
 #[derive(Clone, Deserialize, Serialize, Default, Debug, Reflect)]
 pub struct SoundBufferImportOptions {
-    /// Whether the buffer is streaming or not.
-    pub stream: bool,
+    /// Whether the buffer is streaming or not. Streaming buffers decode their data in small blocks
+    /// during playback instead of decoding everything at once on loading. It is the best option for
+    /// long sounds (music, ambient, etc.), since decoded samples take a lot of memory. See
+    /// [`crate::buffer::streaming`] module docs for more info.
+    #[serde(alias = "stream")]
+    pub streaming: bool,
 }
 
 impl ImportOptions for SoundBufferImportOptions {}
@@ -71,14 +78,36 @@ impl ResourceLoader for SoundBufferLoader {
                 .await
                 .map_err(LoadError::new)?;
 
-            let result = if import_options.stream {
-                SoundBuffer::raw_streaming(source)
-            } else {
-                SoundBuffer::raw_generic(source)
-            };
+            let result = SoundBuffer::raw(source, import_options.streaming);
 
             match result {
-                Ok(buffer) => Ok(LoaderPayload::new(buffer)),
+                Ok(mut buffer) => {
+                    // Captions are stored in a separate file with the same name.
+                    let captions_path = path.with_extension("srt");
+                    if io.exists(&captions_path).await {
+                        match io.load_file(&captions_path).await {
+                            Ok(data) => {
+                                match CaptionTrack::from_srt(&String::from_utf8_lossy(&data)) {
+                                    Ok(captions) => {
+                                        buffer.set_captions(Some(captions));
+                                    }
+                                    Err(err) => Log::warn(format!(
+                                        "Unable to load captions {}. Reason: {}",
+                                        captions_path.display(),
+                                        err
+                                    )),
+                                }
+                            }
+                            Err(err) => Log::warn(format!(
+                                "Unable to load captions {}. Reason: {:?}",
+                                captions_path.display(),
+                                err
+                            )),
+                        }
+                    }
+
+                    Ok(LoaderPayload::new(buffer))
+                }
                 Err(_) => Err(LoadError::new("Invalid data source.")),
             }
         })
@@ -215,6 +215,13 @@ pub trait SoundBufferResourceExtension {
 
     /// Tries to create new generic sound buffer from a given data source.
     fn new_generic(data_source: DataSource) -> Result<Resource<SoundBuffer>, DataSource>;
+
+    /// Tries to create new streaming or generic sound buffer from a given data source, depending on
+    /// the `streaming` flag.
+    fn new_with_streaming(
+        data_source: DataSource,
+        streaming: bool,
+    ) -> Result<Resource<SoundBuffer>, DataSource>;
 }
 
 impl SoundBufferResourceExtension for SoundBufferResource {
@@ -233,6 +240,17 @@ impl SoundBufferResourceExtension for SoundBufferResource {
             SoundBuffer::Generic(GenericBuffer::new(data_source)?),
         ))
     }
+
+    fn new_with_streaming(
+        data_source: DataSource,
+        streaming: bool,
+    ) -> Result<Resource<SoundBuffer>, DataSource> {
+        let path = data_source.path_owned();
+        Ok(Resource::new_ok(
+            path.into(),
+            SoundBuffer::raw(data_source, streaming)?,
+        ))
+    }
 }
 
 impl TypeUuidProvider for SoundBuffer {
@@ -253,6 +271,21 @@ impl SoundBuffer {
     pub fn raw_generic(data_source: DataSource) -> Result<Self, DataSource> {
         Ok(Self::Generic(GenericBuffer::new(data_source)?))
     }
+
+    /// Tries to create new streaming or generic sound buffer from a given data source, depending on
+    /// the `streaming` flag.
+    pub fn raw(data_source: DataSource, streaming: bool) -> Result<Self, DataSource> {
+        if streaming {
+            Self::raw_streaming(data_source)
+        } else {
+            Self::raw_generic(data_source)
+        }
+    }
+
+    /// Returns `true` if the buffer is streaming, `false` - otherwise.
+    pub fn is_streaming(&self) -> bool {
+        matches!(self, Self::Streaming(_))
+    }
 }
 
 impl Default for SoundBuffer {
@@ -42,6 +42,14 @@
 //! }
 //! ```
 //!
+//! Streaming buffers are also created by the resource manager, when the `streaming` flag is set in the
+//! import options of a sound file (see [`super::loader::SoundBufferImportOptions`]).
+//!
+//! Blocks of samples are decoded on demand on the mixer thread, each block contains
+//! [`StreamingBuffer::STREAM_SAMPLE_COUNT`] samples per channel. Playback position of a sound source
+//! with a streaming buffer can be changed at any time using [`crate::source::SoundSource::set_playback_time`],
+//! the decoder will be moved to the requested position and the next block will be decoded from it.
+//!
 //! # Notes
 //!
 //! Streaming buffer cannot be shared across multiple source. On attempt to create a source with a streaming
@@ -196,6 +204,7 @@ impl StreamingBuffer {
                 sample_rate: streaming_source.sample_rate(),
                 channel_count: streaming_source.channel_count(),
                 channel_duration_in_samples: streaming_source.channel_duration_in_samples(),
+                captions: None,
             },
             use_count: 0,
             streaming_source,
@@ -213,9 +222,14 @@ impl StreamingBuffer {
         self.streaming_source.rewind()
     }
 
+    /// Moves the decoder to the given location and loads the block that starts from it. Location is
+    /// clamped to the duration of the buffer. The exact position depends on the data source: WAV files
+    /// are seekable per-sample, while Vorbis/OGG files are seekable per-page.
     #[inline]
-    pub(crate) fn time_seek(&mut self, location: Duration) {
-        self.streaming_source.time_seek(location);
+    pub(crate) fn seek(&mut self, location: Duration) {
+        self.streaming_source
+            .time_seek(location.min(self.generic.duration()));
+        self.read_next_block();
     }
 }
 
@@ -235,3 +249,79 @@ impl DerefMut for StreamingBuffer {
         &mut self.generic
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{
+            streaming::StreamingBuffer, DataSource, SoundBufferResource,
+            SoundBufferResourceExtension,
+        },
+        context::SAMPLE_RATE,
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use std::{io::Cursor, time::Duration};
+
+    const SAMPLE_COUNT: usize = 3 * StreamingBuffer::STREAM_SAMPLE_COUNT + 1000;
+
+    fn sample(index: usize) -> f32 {
+        index as f32 / SAMPLE_COUNT as f32
+    }
+
+    // Creates a source that plays a mono ramp signal from a streaming buffer.
+    fn make_ramp_source() -> SoundSource {
+        let mut data = Cursor::new(Vec::new());
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: SAMPLE_RATE,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+        for i in 0..SAMPLE_COUNT {
+            writer.write_sample(sample(i)).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let buffer = SoundBufferResource::new_with_streaming(
+            DataSource::from_memory(data.into_inner()),
+            true,
+        )
+        .unwrap();
+        assert!(buffer.data_ref().is_streaming());
+
+        SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_streaming_buffer_seek() {
+        let mut source = make_ramp_source();
+
+        let position = 2 * StreamingBuffer::STREAM_SAMPLE_COUNT - 100;
+        source.set_playback_time(Duration::from_secs_f64(
+            position as f64 / SAMPLE_RATE as f64,
+        ));
+
+        // Render across the boundary of the blocks.
+        source.render(StreamingBuffer::STREAM_SAMPLE_COUNT + 200);
+        for (i, (left, right)) in source.frame_samples().iter().enumerate() {
+            assert_eq!(*left, sample(position + i));
+            assert_eq!(*right, sample(position + i));
+        }
+
+        // Seek backwards.
+        source.set_playback_time(Duration::from_secs(0));
+        source.render(10);
+        assert_eq!(source.frame_samples()[0], (sample(0), sample(0)));
+        assert_eq!(source.frame_samples()[9], (sample(9), sample(9)));
+
+        // Seek to the end.
+        source.set_playback_time(Duration::from_secs(100));
+        assert_eq!(source.status(), Status::Stopped);
+        assert_eq!(source.playback_time(), Duration::from_secs(0));
+    }
+}
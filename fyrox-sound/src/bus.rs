@@ -381,6 +381,22 @@ impl AudioBusGraph {
         })
     }
 
+    /// Tries to find an audio bus by its name. Returns a pair of handle and a reference to the first
+    /// audio bus with the given name.
+    pub fn find_bus_by_name(&self, name: &str) -> Option<(Handle<AudioBus>, &AudioBus)> {
+        self.buses.pair_iter().find(|(_, bus)| bus.name == name)
+    }
+
+    /// Tries to find an audio bus by its name. Returns a pair of handle and a reference to the first
+    /// audio bus with the given name. It could be used to change gain of a specific group of sounds,
+    /// for example, to implement music and sound effects volume settings.
+    pub fn find_bus_by_name_mut(
+        &mut self,
+        name: &str,
+    ) -> Option<(Handle<AudioBus>, &mut AudioBus)> {
+        self.buses.pair_iter_mut().find(|(_, bus)| bus.name == name)
+    }
+
     /// Removes an audio bus at the given handle.
     pub fn remove_bus(&mut self, handle: Handle<AudioBus>) -> AudioBus {
         assert_ne!(handle, self.root);
@@ -484,37 +500,39 @@ impl AudioBusGraph {
     }
 
     pub(crate) fn end_render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
-        let mut leafs = Vec::new();
-        for (handle, bus) in self.buses.pair_iter_mut() {
-            bus.apply_effects();
-
-            if bus.child_buses.is_empty() {
-                leafs.push(handle);
+        // Collect the buses in depth-first order, parent buses are always placed before their children.
+        let mut order = Vec::with_capacity(self.len());
+        let mut stack = vec![self.root];
+        while let Some(handle) = stack.pop() {
+            if let Some(bus) = self.buses.try_borrow(handle) {
+                order.push(handle);
+                stack.extend_from_slice(&bus.child_buses);
             }
         }
 
-        for mut leaf in leafs {
-            while leaf.is_some() {
-                let ctx = self.buses.begin_multi_borrow();
-
-                let leaf_ref = ctx.try_get_mut(leaf).expect("Malformed bus graph!");
-
-                let input_buffer = leaf_ref.ping_pong_buffer.input_ref();
-                let leaf_gain = leaf_ref.gain;
-                let mut parent_buffer = ctx.try_get_mut(leaf_ref.parent_bus);
-                let output_buffer = parent_buffer
-                    .as_mut()
-                    .map(|parent| parent.ping_pong_buffer.input_mut())
-                    // Special case for the root bus - it writes directly to the output device buffer.
-                    .unwrap_or(&mut *output_device_buffer);
-                for ((input_left, input_right), (output_left, output_right)) in
-                    input_buffer.iter().zip(output_buffer)
-                {
-                    *output_left += *input_left * leaf_gain;
-                    *output_right += *input_right * leaf_gain;
-                }
-
-                leaf = leaf_ref.parent_bus;
+        // Process the buses in reversed order, so every bus applies its effects to the mix of its own
+        // input and the output of its children.
+        for handle in order.into_iter().rev() {
+            let ctx = self.buses.begin_multi_borrow();
+
+            let mut bus_ref = ctx.try_get_mut(handle).expect("Malformed bus graph!");
+            bus_ref.apply_effects();
+
+            let bus_gain = bus_ref.gain;
+            let mut parent_buffer = ctx.try_get_mut(bus_ref.parent_bus);
+            let output_buffer = parent_buffer
+                .as_mut()
+                .map(|parent| parent.ping_pong_buffer.input_mut())
+                // Special case for the root bus - it writes directly to the output device buffer.
+                .unwrap_or(&mut *output_device_buffer);
+            for ((input_left, input_right), (output_left, output_right)) in bus_ref
+                .ping_pong_buffer
+                .input_ref()
+                .iter()
+                .zip(output_buffer)
+            {
+                *output_left += *input_left * bus_gain;
+                *output_right += *input_right * bus_gain;
             }
         }
     }
@@ -604,6 +622,40 @@ mod test {
 
         graph.end_render(&mut output_buffer);
 
-        assert_eq!(output_buffer[0], (0.75, 0.75));
+        // Output of Bus2 (0.5) is mixed with the input of Bus1 and then passes through its effects.
+        assert_eq!(output_buffer[0], (0.375, 0.375));
+    }
+
+    #[test]
+    fn test_sibling_buses_data_flow() {
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+
+        let mut graph = AudioBusGraph::new();
+
+        let mut bus1 = AudioBus::new("Bus1".to_string());
+        bus1.set_gain(0.5);
+        let bus1 = graph.add_bus(bus1, graph.root);
+        let bus2 = graph.add_bus(AudioBus::new("Bus2".to_string()), bus1);
+        let bus3 = graph.add_bus(AudioBus::new("Bus3".to_string()), bus1);
+
+        graph.begin_render(output_buffer.len());
+
+        // Simulate output of sound sources to each bus.
+        for bus in [bus2, bus3] {
+            for (left, right) in graph.buses[bus].input_buffer() {
+                *left = 1.0;
+                *right = 1.0;
+            }
+        }
+
+        graph.end_render(&mut output_buffer);
+
+        // Each bus must be mixed into its parent exactly once.
+        assert_eq!(output_buffer[0], (1.0, 1.0));
+
+        let (handle, bus) = graph.find_bus_by_name("Bus3").unwrap();
+        assert_eq!(handle, bus3);
+        assert_eq!(bus.name(), "Bus3");
+        assert!(graph.find_bus_by_name("Music").is_none());
     }
 }
@@ -0,0 +1,264 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Captions (subtitles) for sounds. Sound buffers can carry a [`CaptionTrack`], sound context emits
+//! [`CaptionEvent`]s while a sound with captions is playing. See [`CaptionTrack`] docs for more info.
+
+use crate::{pool::Handle, source::SoundSource};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// A single caption line.
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct Caption {
+    /// Time (in seconds) at which the caption should be shown.
+    pub start: f32,
+    /// Time (in seconds) at which the caption should be hidden.
+    pub end: f32,
+    /// Name of the speaker, empty if unknown.
+    pub speaker: String,
+    /// Text of the caption.
+    pub text: String,
+}
+
+impl Caption {
+    /// Returns `true` if the caption should be visible at the given playback time.
+    pub fn is_active(&self, time: Duration) -> bool {
+        let time = time.as_secs_f32();
+        time >= self.start && time < self.end
+    }
+
+    /// Returns duration of the caption in seconds.
+    pub fn duration(&self) -> f32 {
+        (self.end - self.start).max(0.0)
+    }
+}
+
+/// An error, that may occur during caption track parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptionParseError {
+    /// Line (starting from 1) at which the error has occurred.
+    pub line: usize,
+    /// Description of the error.
+    pub description: String,
+}
+
+impl Display for CaptionParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unable to parse captions at line {}: {}",
+            self.line, self.description
+        )
+    }
+}
+
+impl std::error::Error for CaptionParseError {}
+
+/// Caption track is a set of captions (subtitles) of a sound. Caption tracks are usually loaded
+/// together with sound buffers: if there's an `.srt` file with the same name as a sound file (for
+/// example `dialog.ogg` and `dialog.srt`), it will be loaded automatically. It can also be
+/// set manually using [`crate::buffer::generic::GenericBuffer::set_captions`].
+///
+/// ## Format
+///
+/// The track uses [SubRip](https://en.wikipedia.org/wiki/SubRip) format, optionally a speaker name could be
+/// specified in square brackets at the beginning of a caption text:
+///
+/// ```text
+/// 1
+/// 00:00:00,500 --> 00:00:02,000
+/// [Guard] Halt! Who goes there?
+///
+/// 2
+/// 00:00:02,500 --> 00:00:04,000
+/// (footsteps)
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Visit, Reflect)]
+pub struct CaptionTrack {
+    /// A list of captions sorted by their start time.
+    pub captions: Vec<Caption>,
+}
+
+fn parse_timestamp(s: &str) -> Option<f32> {
+    let (hms, millis) = s.trim().split_once([',', '.'])?;
+    let mut parts = hms.split(':');
+    let hours = parts.next()?.parse::<u32>().ok()?;
+    let minutes = parts.next()?.parse::<u32>().ok()?;
+    let seconds = parts.next()?.parse::<u32>().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let millis = millis.parse::<u32>().ok()?;
+    Some((hours * 3600 + minutes * 60 + seconds) as f32 + millis as f32 / 1000.0)
+}
+
+impl CaptionTrack {
+    /// Creates new caption track from the given set of captions.
+    pub fn new(mut captions: Vec<Caption>) -> Self {
+        captions.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Self { captions }
+    }
+
+    /// Parses a caption track in SubRip (`.srt`) format.
+    pub fn from_srt(source: &str) -> Result<Self, CaptionParseError> {
+        let mut captions = Vec::new();
+        let mut lines = source
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .enumerate()
+            .peekable();
+
+        loop {
+            // Skip empty lines between blocks.
+            while lines.next_if(|(_, l)| l.trim().is_empty()).is_some() {}
+
+            let Some((index_line, _)) = lines.next() else {
+                break;
+            };
+
+            let Some((timing_line, timing)) = lines.next() else {
+                return Err(CaptionParseError {
+                    line: index_line + 1,
+                    description: "Unexpected end of file, timing line expected.".to_string(),
+                });
+            };
+
+            let error = |description: &str| CaptionParseError {
+                line: timing_line + 1,
+                description: description.to_string(),
+            };
+
+            let (start, end) = timing
+                .split_once("-->")
+                .ok_or_else(|| error("Timing line must be in `start --> end` format."))?;
+            let start = parse_timestamp(start).ok_or_else(|| error("Invalid start time."))?;
+            // End time could be followed by optional positioning information.
+            let end = end
+                .split_whitespace()
+                .next()
+                .and_then(parse_timestamp)
+                .ok_or_else(|| error("Invalid end time."))?;
+
+            let mut text = String::new();
+            while let Some((_, line)) = lines.next_if(|(_, l)| !l.trim().is_empty()) {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(line.trim());
+            }
+
+            let mut speaker = String::new();
+            if let Some(rest) = text.strip_prefix('[') {
+                if let Some((name, rest)) = rest.split_once(']') {
+                    speaker = name.trim().to_string();
+                    text = rest.trim_start().to_string();
+                }
+            }
+
+            captions.push(Caption {
+                start,
+                end,
+                speaker,
+                text,
+            });
+        }
+
+        Ok(Self::new(captions))
+    }
+
+    /// Returns an index of the caption, that should be visible at the given playback time.
+    pub fn active_caption_index(&self, time: Duration) -> Option<usize> {
+        self.captions.iter().position(|c| c.is_active(time))
+    }
+
+    /// Returns a reference to the caption, that should be visible at the given playback time.
+    pub fn active_caption(&self, time: Duration) -> Option<&Caption> {
+        self.active_caption_index(time)
+            .and_then(|i| self.captions.get(i))
+    }
+}
+
+/// An event, that is emitted by a sound context when a caption of a playing sound source should be
+/// shown or hidden. Use [`crate::context::State::subscribe_to_captions`] to receive such events.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CaptionEvent {
+    /// A caption should be shown.
+    Started {
+        /// A handle of the sound source, that emitted the event.
+        source: Handle<SoundSource>,
+        /// The caption that should be shown.
+        caption: Caption,
+    },
+    /// A caption should be hidden.
+    Ended {
+        /// A handle of the sound source, that emitted the event.
+        source: Handle<SoundSource>,
+        /// The caption that should be hidden.
+        caption: Caption,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use crate::caption::{Caption, CaptionTrack};
+    use std::time::Duration;
+
+    #[test]
+    fn test_srt_parsing() {
+        let track = CaptionTrack::from_srt(
+            "1\n\
+            00:00:00,500 --> 00:00:02,000\n\
+            [Guard] Halt!\n\
+            Who goes there?\n\
+            \n\
+            2\n\
+            00:01:02,250 --> 00:01:04,000 X1:10\n\
+            (footsteps)\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            track.captions,
+            vec![
+                Caption {
+                    start: 0.5,
+                    end: 2.0,
+                    speaker: "Guard".to_string(),
+                    text: "Halt!\nWho goes there?".to_string(),
+                },
+                Caption {
+                    start: 62.25,
+                    end: 64.0,
+                    speaker: Default::default(),
+                    text: "(footsteps)".to_string(),
+                }
+            ]
+        );
+
+        assert_eq!(track.active_caption_index(Duration::from_secs(1)), Some(0));
+        assert_eq!(track.active_caption_index(Duration::from_secs(10)), None);
+
+        assert!(CaptionTrack::from_srt("1\n00:00:00 --> 00:00:01,000\nText").is_err());
+    }
+}
@@ -32,6 +32,7 @@
 
 use crate::bus::AudioBusGraph;
 use crate::{
+    caption::CaptionEvent,
     listener::Listener,
     pool::Ticket,
     renderer::{render_source_default, Renderer},
@@ -44,7 +45,7 @@ use fyrox_core::{
     visitor::prelude::*,
 };
 use std::{
-    sync::{Arc, Mutex, MutexGuard},
+    sync::{mpsc::Sender, Arc, Mutex, MutexGuard},
     time::Duration,
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
@@ -138,6 +139,8 @@ pub struct State {
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
     paused: bool,
+    #[reflect(hidden)]
+    caption_subscribers: Vec<Sender<CaptionEvent>>,
     /// A set of flags, that can be used to define what should be skipped during the
     /// serialization of a sound context.
     #[reflect(hidden)]
@@ -274,6 +277,63 @@ impl State {
         &mut self.bus_graph
     }
 
+    /// Adds new subscriber, that will receive caption events of every playing sound source (see
+    /// [`CaptionEvent`] docs for more info). The subscriber will be removed automatically, when
+    /// the receiving side of the channel is dropped.
+    pub fn subscribe_to_captions(&mut self, sender: Sender<CaptionEvent>) {
+        self.caption_subscribers.push(sender);
+    }
+
+    fn update_captions(&mut self) {
+        if self.caption_subscribers.is_empty() {
+            return;
+        }
+
+        let mut events = Vec::new();
+        for (handle, source) in self.sources.pair_iter_mut() {
+            let Some(buffer) = source.buffer() else {
+                continue;
+            };
+            let mut state = buffer.state();
+            let Some(captions) = state.data().and_then(|buffer| buffer.captions()) else {
+                continue;
+            };
+
+            let new_caption = if source.status() == Status::Stopped {
+                None
+            } else {
+                captions.active_caption_index(source.playback_time())
+            };
+
+            if new_caption == source.active_caption {
+                continue;
+            }
+
+            if let Some(caption) = source.active_caption.and_then(|i| captions.captions.get(i)) {
+                events.push(CaptionEvent::Ended {
+                    source: handle,
+                    caption: caption.clone(),
+                });
+            }
+            if let Some(caption) = new_caption.and_then(|i| captions.captions.get(i)) {
+                events.push(CaptionEvent::Started {
+                    source: handle,
+                    caption: caption.clone(),
+                });
+            }
+
+            source.active_caption = new_caption;
+        }
+
+        if !events.is_empty() {
+            self.caption_subscribers.retain(|subscriber| {
+                events
+                    .iter()
+                    .all(|event| subscriber.send(event.clone()).is_ok())
+            });
+        }
+    }
+
     pub(crate) fn render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
         let last_time = fyrox_core::instant::Instant::now();
 
@@ -318,6 +378,8 @@ impl State {
             }
 
             self.bus_graph.end_render(output_device_buffer);
+
+            self.update_captions();
         }
 
         self.render_duration = fyrox_core::instant::Instant::now() - last_time;
@@ -347,6 +409,7 @@ impl SoundContext {
                 bus_graph: AudioBusGraph::new(),
                 distance_model: DistanceModel::InverseDistance,
                 paused: false,
+                caption_subscribers: Default::default(),
                 serialization_options: Default::default(),
             }))),
         }
@@ -19,6 +19,7 @@
 // SOFTWARE.
 
 use crate::{buffer::DataSource, error::SoundError};
+use fyrox_core::log::Log;
 use lewton::{inside_ogg::read_headers, inside_ogg::OggStreamReader, samples::InterleavedSamples};
 use ogg::PacketReader;
 use std::{
@@ -102,9 +103,19 @@ fn total_duration_in_samples(source: &mut DataSource) -> usize {
 }
 
 impl OggDecoder {
-    pub fn new(mut source: DataSource) -> Result<Self, DataSource> {
+    pub fn new(source: DataSource) -> Result<Self, DataSource> {
+        Self::with_duration(source, None)
+    }
+
+    // Duration calculation requires to read every packet of the source, so it is done only once
+    // and then reused on rewinding (which happens on the mixer thread for streaming buffers).
+    fn with_duration(
+        mut source: DataSource,
+        channel_duration_in_samples: Option<usize>,
+    ) -> Result<Self, DataSource> {
         if is_vorbis_ogg(&mut source) {
-            let channel_duration_in_samples = total_duration_in_samples(&mut source);
+            let channel_duration_in_samples = channel_duration_in_samples
+                .unwrap_or_else(|| total_duration_in_samples(&mut source));
 
             let mut reader = OggStreamReader::new(source).unwrap();
 
@@ -133,7 +144,7 @@ impl OggDecoder {
         // For more info see - https://github.com/RustAudio/lewton/issues/73
         let mut source = self.reader.take().unwrap().into_inner().into_inner();
         source.rewind()?;
-        *self = match Self::new(source) {
+        *self = match Self::with_duration(source, Some(self.channel_duration_in_samples)) {
             Ok(ogg_decoder) => ogg_decoder,
             // Drop source here, this will invalidate decoder and it can't produce any
             // samples anymore. This is unrecoverable error, but *should* never happen
@@ -143,18 +154,30 @@ impl OggDecoder {
         Ok(())
     }
 
+    fn seek_absgp(&mut self, sample_index: u64) -> bool {
+        self.reader
+            .as_mut()
+            .is_some_and(|reader| reader.seek_absgp_pg(sample_index).is_ok())
+    }
+
+    /// Seeks to the given location with the page granularity, which means that decoding will continue
+    /// from the beginning of the page that contains the requested sample.
     pub fn time_seek(&mut self, location: Duration) {
+        // Samples of the packet that was decoded before seeking are no longer valid.
+        self.samples = Vec::new().into_iter();
+
+        let sample_index = (location.as_secs_f64() * self.sample_rate as f64).round() as u64;
+
         // seek_absgp_pg seems to be bugged - it fails at seeking when all packets were read already.
+        // In this case the decoder is re-created and seeking is done once again.
         // For more info see - https://github.com/RustAudio/lewton/issues/73
-        let sample_index = location.as_secs_f64() * self.sample_rate as f64;
-        if self
-            .reader
-            .as_mut()
-            .unwrap()
-            .seek_absgp_pg(sample_index as u64)
-            .is_err()
+        if !self.seek_absgp(sample_index)
+            && (self.rewind().is_err() || !self.seek_absgp(sample_index))
         {
-            println!("Failed to seek vorbis/ogg, see https://github.com/RustAudio/lewton/issues/73")
+            Log::warn(format!(
+                "Failed to seek vorbis/ogg source to {} sample.",
+                sample_index
+            ));
         }
     }
 
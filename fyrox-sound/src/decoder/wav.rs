@@ -97,7 +97,7 @@ impl WavDecoder {
     pub fn time_seek(&mut self, location: Duration) {
         let _ = self
             .reader
-            .seek((location.as_secs_f64() * self.reader.spec().sample_rate as f64) as u32);
+            .seek((location.as_secs_f64() * self.reader.spec().sample_rate as f64).round() as u32);
     }
 
     pub fn channel_duration_in_samples(&self) -> usize {
@@ -0,0 +1,234 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Dynamic range compressor module.
+//!
+//! # Overview
+//!
+//! Compressor reduces the volume of loud sounds, that are above specified threshold, thus narrowing
+//! dynamic range of the signal. It is useful to prevent clipping when there are lots of loud sounds
+//! playing at the same time (explosions, gun shots, etc.) and to make quiet sounds more audible when
+//! used together with makeup gain.
+//!
+//! # Usage
+//!
+//! ```
+//! use fyrox_sound::context::SoundContext;
+//! use fyrox_sound::effects::compressor::Compressor;
+//! use fyrox_sound::effects::Effect;
+//!
+//! fn add_compressor(context: &mut SoundContext) {
+//!     let mut compressor = Compressor::default();
+//!     compressor.set_threshold_db(-12.0);
+//!     compressor.set_ratio(6.0);
+//!     context.state().bus_graph_mut().primary_bus_mut().add_effect(Effect::Compressor(compressor));
+//! }
+//! ```
+
+use crate::{context::SAMPLE_RATE, effects::EffectRenderTrait};
+use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+
+fn db_to_gain(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.max(f32::EPSILON).log10()
+}
+
+// Returns a coefficient of the exponential smoothing for the given time.
+fn smoothing_coefficient(time_ms: f32) -> f32 {
+    if time_ms <= 0.0 {
+        0.0
+    } else {
+        (-1000.0 / (time_ms * SAMPLE_RATE as f32)).exp()
+    }
+}
+
+/// Dynamic range compressor. See module docs for more info.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect)]
+pub struct Compressor {
+    #[reflect(
+        description = "A level (in decibels) above which the signal will be compressed.",
+        setter = "set_threshold_db"
+    )]
+    threshold_db: f32,
+
+    #[reflect(
+        description = "Compression ratio. For example, ratio of 4.0 means that each 4 dB above the threshold \
+        will be reduced to 1 dB.",
+        setter = "set_ratio"
+    )]
+    ratio: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) that is needed for the compressor to react on loud signal.",
+        setter = "set_attack_time_ms"
+    )]
+    attack_time_ms: f32,
+
+    #[reflect(
+        description = "Time (in milliseconds) that is needed for the compressor to stop compression once \
+        the signal became quiet.",
+        setter = "set_release_time_ms"
+    )]
+    release_time_ms: f32,
+
+    #[reflect(
+        description = "Gain (in decibels) that will be applied to the compressed signal.",
+        setter = "set_makeup_gain_db"
+    )]
+    makeup_gain_db: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    gain_reduction_db: f32,
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self {
+            threshold_db: -18.0,
+            ratio: 4.0,
+            attack_time_ms: 10.0,
+            release_time_ms: 100.0,
+            makeup_gain_db: 0.0,
+            gain_reduction_db: 0.0,
+        }
+    }
+}
+
+impl Compressor {
+    /// Sets a level (in decibels) above which the signal will be compressed. Usually it is in
+    /// `[-60.0..0.0]` range.
+    pub fn set_threshold_db(&mut self, threshold_db: f32) {
+        self.threshold_db = threshold_db;
+    }
+
+    /// Returns current threshold of the compressor in decibels.
+    pub fn threshold_db(&self) -> f32 {
+        self.threshold_db
+    }
+
+    /// Sets compression ratio. For example, ratio of 4.0 means that each 4 dB above the threshold will
+    /// be reduced to 1 dB. Ratio cannot be less than 1.0.
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Returns current compression ratio.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Sets time (in milliseconds) that is needed for the compressor to react on loud signal.
+    pub fn set_attack_time_ms(&mut self, attack_time_ms: f32) {
+        self.attack_time_ms = attack_time_ms.max(0.0);
+    }
+
+    /// Returns current attack time in milliseconds.
+    pub fn attack_time_ms(&self) -> f32 {
+        self.attack_time_ms
+    }
+
+    /// Sets time (in milliseconds) that is needed for the compressor to stop compression once the
+    /// signal became quiet.
+    pub fn set_release_time_ms(&mut self, release_time_ms: f32) {
+        self.release_time_ms = release_time_ms.max(0.0);
+    }
+
+    /// Returns current release time in milliseconds.
+    pub fn release_time_ms(&self) -> f32 {
+        self.release_time_ms
+    }
+
+    /// Sets gain (in decibels) that will be applied to the compressed signal. It could be used to
+    /// compensate the loss of volume after compression.
+    pub fn set_makeup_gain_db(&mut self, makeup_gain_db: f32) {
+        self.makeup_gain_db = makeup_gain_db;
+    }
+
+    /// Returns current makeup gain in decibels.
+    pub fn makeup_gain_db(&self) -> f32 {
+        self.makeup_gain_db
+    }
+}
+
+impl EffectRenderTrait for Compressor {
+    fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
+        let attack = smoothing_coefficient(self.attack_time_ms);
+        let release = smoothing_coefficient(self.release_time_ms);
+        let slope = 1.0 - 1.0 / self.ratio.max(1.0);
+
+        for ((input_left, input_right), (output_left, output_right)) in
+            input.iter().zip(output.iter_mut())
+        {
+            // Both channels are compressed equally to keep the stereo image.
+            let level_db = gain_to_db(input_left.abs().max(input_right.abs()));
+            let target_reduction_db = (level_db - self.threshold_db).max(0.0) * slope;
+
+            let coefficient = if target_reduction_db > self.gain_reduction_db {
+                attack
+            } else {
+                release
+            };
+            self.gain_reduction_db =
+                target_reduction_db + coefficient * (self.gain_reduction_db - target_reduction_db);
+
+            let gain = db_to_gain(self.makeup_gain_db - self.gain_reduction_db);
+            *output_left = *input_left * gain;
+            *output_right = *input_right * gain;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effects::{compressor::Compressor, EffectRenderTrait};
+
+    fn render_constant(compressor: &mut Compressor, amplitude: f32) -> (f32, f32) {
+        let input = vec![(amplitude, -amplitude); 44100];
+        let mut output = vec![(0.0, 0.0); input.len()];
+        compressor.render(&input, &mut output);
+        *output.last().unwrap()
+    }
+
+    #[test]
+    fn test_compressor() {
+        let mut compressor = Compressor::default();
+
+        // Quiet signal must pass through untouched.
+        let quiet = 0.05;
+        let (left, right) = render_constant(&mut compressor, quiet);
+        assert!((left - quiet).abs() < 1.0e-4);
+        assert!((right + quiet).abs() < 1.0e-4);
+
+        // 0 dB signal is 18 dB above the threshold, with 4:1 ratio it must be reduced to 4.5 dB
+        // above the threshold.
+        let expected = 10.0f32.powf((-18.0 + 18.0 / 4.0) / 20.0);
+        let (left, right) = render_constant(&mut compressor, 1.0);
+        assert!((left - expected).abs() < 1.0e-3);
+        assert!((right + expected).abs() < 1.0e-3);
+
+        // The compressor must release once the signal is quiet again.
+        let (left, _) = render_constant(&mut compressor, quiet);
+        assert!((left - quiet).abs() < 1.0e-4);
+    }
+}
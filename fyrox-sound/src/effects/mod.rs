@@ -21,6 +21,7 @@
 //! Contins everything related to audio effects that can be applied to an audio bus.
 
 use crate::{
+    effects::compressor::Compressor,
     effects::filter::{
         AllPassFilterEffect, BandPassFilterEffect, HighPassFilterEffect, HighShelfFilterEffect,
         LowPassFilterEffect, LowShelfFilterEffect,
@@ -30,6 +31,7 @@ use crate::{
 use fyrox_core::{reflect::prelude::*, uuid_provider, visitor::prelude::*};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod compressor;
 pub mod filter;
 pub mod reverb;
 
@@ -86,6 +88,8 @@ pub enum Effect {
     LowShelfFilter(LowShelfFilterEffect),
     /// See [`HighShelfFilterEffect`] docs for more info.
     HighShelfFilter(HighShelfFilterEffect),
+    /// See [`Compressor`] docs for more info.
+    Compressor(Compressor),
 }
 
 uuid_provider!(Effect = "fc52e441-d1ec-4881-937c-9e2e53a6d621");
@@ -111,6 +115,7 @@ macro_rules! static_dispatch {
             Effect::AllPassFilter(v) => v.$func($($args),*),
             Effect::LowShelfFilter(v) => v.$func($($args),*),
             Effect::HighShelfFilter(v) => v.$func($($args),*),
+            Effect::Compressor(v) => v.$func($($args),*),
         }
     };
 }
@@ -91,6 +91,7 @@ pub mod buffer;
 pub mod context;
 
 pub mod bus;
+pub mod caption;
 pub mod dsp;
 pub mod effects;
 pub mod engine;
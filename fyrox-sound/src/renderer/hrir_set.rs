@@ -0,0 +1,436 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Impulse response sets. Allows to build HRIR spheres from custom sets of head-related impulse responses
+//! (for example, exported from SOFA files or recorded manually) instead of using pre-built spheres.
+//!
+//! # Overview
+//!
+//! An impulse response set is a number of measurement points, where each point has a direction and a pair of
+//! impulse responses for left and right ears. Points are triangulated into a sphere (using convex hull), so
+//! they should be distributed more or less evenly around the listener, otherwise there will be large
+//! triangles and poor interpolation between them.
+//!
+//! Directions must be given in the same coordinate system that is used by HRIR spheres: it is the listener
+//! space and the direction points from a sound source to the listener.
+//!
+//! # Loading
+//!
+//! Impulse response sets could be loaded from `.irset` files using resource manager. Such files are RON
+//! manifests that list stereo WAV (or OGG) files (left and right channels contain impulse responses for left and
+//! right ears respectively) with their directions. Paths are relative to the manifest:
+//!
+//! ```ron
+//! (
+//!     points: [
+//!         (direction: (0.0, 0.0, -1.0), path: "ir/front.wav"),
+//!         (direction: (0.0, 0.0, 1.0), path: "ir/back.wav"),
+//!         (direction: (1.0, 0.0, 0.0), path: "ir/side_a.wav"),
+//!         (direction: (-1.0, 0.0, 0.0), path: "ir/side_b.wav"),
+//!         (direction: (0.0, -1.0, 0.0), path: "ir/above.wav"),
+//!         (direction: (0.0, 1.0, 0.0), path: "ir/below.wav"),
+//!     ],
+//! )
+//! ```
+//!
+//! All the files must have the same sample rate and length. Loaded set is converted to an ordinary HRIR sphere
+//! resource, so it could be used with [`super::hrtf::HrtfRenderer`] directly.
+//!
+//! SOFA files are not supported directly, they must be converted into a set of WAV files first (there are
+//! lots of tools that can do this, for example - SOFA API for Matlab/Octave or `python-sofa`).
+
+use crate::{
+    buffer::DataSource, context, decoder::Decoder, renderer::hrtf::HrirSphereResourceData,
+};
+use fyrox_core::{
+    algebra::Vector3,
+    byteorder::{LittleEndian, WriteBytesExt},
+    uuid::Uuid,
+    TypeUuidProvider,
+};
+use fyrox_resource::{
+    io::ResourceIo,
+    loader::{BoxedLoaderFuture, LoaderPayload, ResourceLoader},
+    state::LoadError,
+};
+use hrtf::HrirSphere;
+use serde::Deserialize;
+use std::{
+    fmt::{Display, Formatter},
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// An error that may occur when building an HRIR sphere from an impulse response set.
+#[derive(Debug)]
+pub enum HrirSetError {
+    /// There must be at least four points that do not lie on the same plane.
+    NotEnoughPoints,
+
+    /// Impulse responses of a point have different length than the first impulse response in the set.
+    LengthMismatch {
+        /// Index of the point.
+        point: usize,
+    },
+
+    /// Impulse responses are empty.
+    EmptyImpulseResponse,
+
+    /// An impulse response file has unsupported format, or does not have two channels.
+    UnsupportedFile(PathBuf),
+
+    /// An impulse response file has different sample rate than the rest of the files.
+    SampleRateMismatch(PathBuf),
+
+    /// Unable to create HRIR sphere.
+    Hrtf(hrtf::HrtfError),
+}
+
+impl Display for HrirSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HrirSetError::NotEnoughPoints => write!(
+                f,
+                "there must be at least four points that do not lie on the same plane"
+            ),
+            HrirSetError::LengthMismatch { point } => {
+                write!(
+                    f,
+                    "impulse responses of point {point} have different length"
+                )
+            }
+            HrirSetError::EmptyImpulseResponse => write!(f, "impulse responses are empty"),
+            HrirSetError::UnsupportedFile(path) => {
+                write!(f, "{} is not a stereo wav or ogg file", path.display())
+            }
+            HrirSetError::SampleRateMismatch(path) => {
+                write!(f, "{} has different sample rate", path.display())
+            }
+            HrirSetError::Hrtf(err) => write!(f, "unable to create hrir sphere: {err:?}"),
+        }
+    }
+}
+
+impl std::error::Error for HrirSetError {}
+
+/// A measurement point of an impulse response set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HrirSetPoint {
+    /// Direction from a sound source to the listener in the listener space. Does not need to be normalized.
+    pub direction: Vector3<f32>,
+    /// Impulse response for the left ear.
+    pub left: Vec<f32>,
+    /// Impulse response for the right ear.
+    pub right: Vec<f32>,
+}
+
+/// A set of head-related impulse responses, see module docs for more info.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HrirSet {
+    /// Sample rate of the impulse responses.
+    pub sample_rate: u32,
+    /// Measurement points.
+    pub points: Vec<HrirSetPoint>,
+}
+
+impl HrirSet {
+    /// Creates new impulse response set with the given sample rate and points.
+    pub fn new(sample_rate: u32, points: Vec<HrirSetPoint>) -> Self {
+        Self {
+            sample_rate,
+            points,
+        }
+    }
+
+    /// Triangulates the set and creates new HRIR sphere, that could be used for rendering. Impulse responses
+    /// will be resampled to the given device sample rate.
+    pub fn to_hrir_sphere(&self, device_sample_rate: u32) -> Result<HrirSphere, HrirSetError> {
+        let length = self
+            .points
+            .first()
+            .map(|point| point.left.len())
+            .ok_or(HrirSetError::NotEnoughPoints)?;
+        if length == 0 {
+            return Err(HrirSetError::EmptyImpulseResponse);
+        }
+        for (index, point) in self.points.iter().enumerate() {
+            if point.left.len() != length || point.right.len() != length {
+                return Err(HrirSetError::LengthMismatch { point: index });
+            }
+        }
+
+        let directions = self
+            .points
+            .iter()
+            .map(|point| {
+                point
+                    .direction
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::zeros)
+            })
+            .collect::<Vec<_>>();
+        let triangles = convex_hull(&directions).ok_or(HrirSetError::NotEnoughPoints)?;
+
+        // Serialize the set in the binary format of HRIR spheres, this is the only way to create a sphere.
+        let mut bytes = Vec::new();
+        write_hrir_sphere(
+            &mut bytes,
+            self.sample_rate,
+            length,
+            &triangles,
+            &directions,
+            &self.points,
+        )
+        .expect("writing to memory must not fail");
+
+        HrirSphere::new(Cursor::new(bytes), device_sample_rate).map_err(HrirSetError::Hrtf)
+    }
+}
+
+fn write_hrir_sphere(
+    writer: &mut Vec<u8>,
+    sample_rate: u32,
+    length: usize,
+    triangles: &[[u32; 3]],
+    directions: &[Vector3<f32>],
+    points: &[HrirSetPoint],
+) -> std::io::Result<()> {
+    writer.extend_from_slice(b"HRIR");
+    writer.write_u32::<LittleEndian>(sample_rate)?;
+    writer.write_u32::<LittleEndian>(length as u32)?;
+    writer.write_u32::<LittleEndian>(directions.len() as u32)?;
+    writer.write_u32::<LittleEndian>(triangles.len() as u32 * 3)?;
+    for index in triangles.iter().flatten() {
+        writer.write_u32::<LittleEndian>(*index)?;
+    }
+    for direction in directions {
+        writer.write_f32::<LittleEndian>(direction.x)?;
+        writer.write_f32::<LittleEndian>(direction.y)?;
+        writer.write_f32::<LittleEndian>(direction.z)?;
+    }
+    for point in points {
+        for sample in point.left.iter().chain(point.right.iter()) {
+            writer.write_f32::<LittleEndian>(*sample)?;
+        }
+    }
+    Ok(())
+}
+
+/// Builds convex hull of the given points using incremental algorithm. Returns a list of triangles with
+/// counter-clockwise winding (when looking from the outside). Returns `None` if all the points lie on the
+/// same plane. Points that lie inside the hull are ignored.
+fn convex_hull(points: &[Vector3<f32>]) -> Option<Vec<[u32; 3]>> {
+    const EPSILON: f32 = 1.0e-6;
+
+    let farthest = |from: &dyn Fn(&Vector3<f32>) -> f32| {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, from(p)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, distance)| *distance > EPSILON)
+            .map(|(i, _)| i)
+    };
+
+    // Initial tetrahedron.
+    let a = 0;
+    let pa = *points.get(a)?;
+    let b = farthest(&|p| (p - pa).norm())?;
+    let pb = points[b];
+    let ab = (pb - pa).normalize();
+    let c = farthest(&|p| (p - pa).cross(&ab).norm())?;
+    let pc = points[c];
+    let normal = (pb - pa).cross(&(pc - pa)).normalize();
+    let d = farthest(&|p| (p - pa).dot(&normal).abs())?;
+
+    let mut faces = if (points[d] - pa).dot(&normal) > 0.0 {
+        vec![[a, c, b], [a, b, d], [b, c, d], [c, a, d]]
+    } else {
+        vec![[a, b, c], [a, d, b], [b, d, c], [c, d, a]]
+    };
+
+    let face_normal = |face: &[usize; 3]| {
+        (points[face[1]] - points[face[0]]).cross(&(points[face[2]] - points[face[0]]))
+    };
+
+    for (i, point) in points.iter().enumerate() {
+        if i == a || i == b || i == c || i == d {
+            continue;
+        }
+
+        let (visible, hidden): (Vec<_>, Vec<_>) = faces
+            .into_iter()
+            .partition(|face| face_normal(face).dot(&(point - points[face[0]])) > EPSILON);
+
+        if visible.is_empty() {
+            faces = hidden;
+            continue;
+        }
+
+        // Horizon consists of the edges of visible faces, that are not shared with other visible faces.
+        let edges = visible
+            .iter()
+            .flat_map(|face| [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])])
+            .collect::<Vec<_>>();
+
+        faces = hidden;
+        for &(from, to) in edges.iter() {
+            if !edges.contains(&(to, from)) {
+                faces.push([from, to, i]);
+            }
+        }
+    }
+
+    Some(
+        faces
+            .into_iter()
+            .map(|face| [face[0] as u32, face[1] as u32, face[2] as u32])
+            .collect(),
+    )
+}
+
+#[derive(Deserialize)]
+struct HrirSetManifestPoint {
+    direction: (f32, f32, f32),
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct HrirSetManifest {
+    points: Vec<HrirSetManifestPoint>,
+}
+
+async fn load_hrir_set(path: &Path, io: &dyn ResourceIo) -> Result<HrirSet, LoadError> {
+    let manifest = io.load_file(path).await.map_err(LoadError::new)?;
+    let manifest = ron::de::from_bytes::<HrirSetManifest>(&manifest).map_err(LoadError::new)?;
+    let folder = path.parent().unwrap_or(Path::new(""));
+
+    let mut set = HrirSet::default();
+    for (index, point) in manifest.points.into_iter().enumerate() {
+        let ir_path = folder.join(&point.path);
+        let bytes = io.load_file(&ir_path).await.map_err(LoadError::new)?;
+        let decoder = Decoder::new(DataSource::from_memory(bytes))
+            .map_err(|_| LoadError::new(HrirSetError::UnsupportedFile(ir_path.clone())))?;
+        if decoder.get_channel_count() != 2 {
+            return Err(LoadError::new(HrirSetError::UnsupportedFile(ir_path)));
+        }
+
+        let sample_rate = decoder.get_sample_rate() as u32;
+        if index == 0 {
+            set.sample_rate = sample_rate;
+        } else if set.sample_rate != sample_rate {
+            return Err(LoadError::new(HrirSetError::SampleRateMismatch(ir_path)));
+        }
+
+        let samples = decoder.into_samples();
+        let (x, y, z) = point.direction;
+        set.points.push(HrirSetPoint {
+            direction: Vector3::new(x, y, z),
+            left: samples.iter().step_by(2).cloned().collect(),
+            right: samples.iter().skip(1).step_by(2).cloned().collect(),
+        });
+    }
+
+    Ok(set)
+}
+
+/// Loader for impulse response sets (`.irset` manifests), see module docs for more info. Produces HRIR sphere
+/// resources.
+pub struct HrirSetLoader;
+
+impl ResourceLoader for HrirSetLoader {
+    fn extensions(&self) -> &[&str] {
+        &["irset"]
+    }
+
+    fn data_type_uuid(&self) -> Uuid {
+        <HrirSphereResourceData as TypeUuidProvider>::type_uuid()
+    }
+
+    fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let set = load_hrir_set(&path, &*io).await?;
+            let hrir_sphere = set
+                .to_hrir_sphere(context::SAMPLE_RATE)
+                .map_err(LoadError::new)?;
+            Ok(LoaderPayload::new(
+                HrirSphereResourceData::from_hrir_sphere(hrir_sphere),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::hrir_set::{convex_hull, HrirSet, HrirSetError, HrirSetPoint};
+    use fyrox_core::algebra::Vector3;
+
+    fn octahedron() -> Vec<Vector3<f32>> {
+        vec![
+            Vector3::x(),
+            -Vector3::x(),
+            Vector3::y(),
+            -Vector3::y(),
+            Vector3::z(),
+            -Vector3::z(),
+        ]
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let mut points = octahedron();
+        // Inner point must be ignored.
+        points.push(Vector3::new(0.1, 0.1, 0.1));
+
+        let triangles = convex_hull(&points).unwrap();
+        assert_eq!(triangles.len(), 8);
+        assert!(triangles.iter().flatten().all(|&i| i != 6));
+
+        // Every triangle must be oriented outwards.
+        for triangle in triangles {
+            let [a, b, c] = triangle.map(|i| points[i as usize]);
+            let center = (a + b + c) / 3.0;
+            assert!((b - a).cross(&(c - a)).dot(&center) > 0.0);
+        }
+
+        assert!(convex_hull(&[Vector3::x(), Vector3::y(), -Vector3::x()]).is_none());
+    }
+
+    #[test]
+    fn test_hrir_set_validation() {
+        let set = HrirSet::new(
+            44100,
+            octahedron()
+                .into_iter()
+                .enumerate()
+                .map(|(i, direction)| HrirSetPoint {
+                    direction,
+                    left: vec![1.0; if i == 3 { 16 } else { 32 }],
+                    right: vec![1.0; 32],
+                })
+                .collect(),
+        );
+        assert!(matches!(
+            set.to_hrir_sphere(44100),
+            Err(HrirSetError::LengthMismatch { point: 3 })
+        ));
+    }
+}
@@ -34,6 +34,9 @@
 //! for left and right ears which will be used to modify samples from each spatial sound source to create binaural
 //! sound. HRIR spheres can be found [here](https://github.com/mrDIMAS/hrir_sphere_builder/tree/master/hrtf_base/IRCAM)
 //!
+//! HRIR spheres could also be built from custom impulse response sets (for example, the ones that were
+//! exported from SOFA files), see [`super::hrir_set`] module docs for more info.
+//!
 //! # Usage
 //!
 //! To use HRTF you need to change default renderer to HRTF renderer like so:
@@ -209,6 +212,14 @@ pub struct HrirSphereResourceData {
     hrir_sphere: Option<HrirSphere>,
 }
 
+impl HrirSphereResourceData {
+    pub(crate) fn from_hrir_sphere(hrir_sphere: HrirSphere) -> Self {
+        Self {
+            hrir_sphere: Some(hrir_sphere),
+        }
+    }
+}
+
 impl Debug for HrirSphereResourceData {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("HrirSphereResourceData").finish()
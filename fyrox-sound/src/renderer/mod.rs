@@ -39,6 +39,7 @@ use fyrox_core::{
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod hrir_set;
 pub mod hrtf;
 
 /// See module docs.
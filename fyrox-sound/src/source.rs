@@ -51,12 +51,13 @@
 use crate::{
     buffer::{streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
     bus::AudioBusGraph,
-    context::DistanceModel,
+    context::{DistanceModel, SAMPLE_RATE},
     error::SoundError,
     listener::Listener,
 };
 use fyrox_core::{
     algebra::Vector3,
+    math::lerpf,
     reflect::prelude::*,
     uuid_provider,
     visitor::{Visit, VisitResult, Visitor},
@@ -158,6 +159,27 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    // Index of a caption, that is currently shown.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) active_caption: Option<usize>,
+    // Gain and low-pass cutoff frequency that are used to simulate obstruction of the sound by
+    // some geometry. They're set by a user (or by a scene) every frame, so there's no need to save
+    // them.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_gain: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_cutoff_frequency: Option<f32>,
+    // Occlusion gain and filter coefficient of the previous frame, they're used for interpolation.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    last_occlusion: Option<(f32, f32)>,
+    // Last output sample of the occlusion low-pass filter.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    occlusion_filter_state: (f32, f32),
 }
 
 impl Default for SoundSource {
@@ -188,6 +210,11 @@ impl Default for SoundSource {
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            active_caption: None,
+            occlusion_gain: 1.0,
+            occlusion_cutoff_frequency: None,
+            last_occlusion: None,
+            occlusion_filter_state: (0.0, 0.0),
         }
     }
 }
@@ -425,6 +452,20 @@ impl SoundSource {
         &self.bus
     }
 
+    /// Sets the gain multiplier and the cutoff frequency (in Hz) of a low-pass filter, that are used
+    /// to simulate obstruction of the sound by some geometry between the source and the listener.
+    /// Gain of 1.0 and `None` cutoff frequency disable the effect (default). Any changes are
+    /// interpolated over a render frame to prevent clicks.
+    pub fn set_occlusion(&mut self, gain: f32, cutoff_frequency: Option<f32>) {
+        self.occlusion_gain = gain.max(0.0);
+        self.occlusion_cutoff_frequency = cutoff_frequency.map(|f| f.max(0.0));
+    }
+
+    /// Returns the occlusion gain multiplier and the cutoff frequency of the occlusion low-pass filter.
+    pub fn occlusion(&self) -> (f32, Option<f32>) {
+        (self.occlusion_gain, self.occlusion_cutoff_frequency)
+    }
+
     // Distance models were taken from OpenAL Specification because it looks like they're
     // standard in industry and there is no need to reinvent it.
     // https://www.openal.org/documentation/openal-1.1-specification.pdf
@@ -479,32 +520,35 @@ impl SoundSource {
         Duration::from_secs(0)
     }
 
-    /// Sets playback duration.
+    /// Sets playback duration. The time is clamped to the duration of the buffer. Setting the time to
+    /// the end of a streaming buffer rewinds it and stops the source, unless it is looping.
     pub fn set_playback_time(&mut self, time: Duration) {
         if let Some(buffer) = self.buffer.as_ref() {
             if let Some(buffer) = buffer.state().data() {
-                if let SoundBuffer::Streaming(ref mut streaming) = *buffer {
-                    // Make sure decoder is at right position.
-                    streaming.time_seek(time.clamp(Duration::from_secs(0), streaming.duration()));
-                }
+                let sample_rate = buffer.sample_rate() as f64;
                 // Set absolute position first.
-                self.playback_pos = (time.as_secs_f64() * buffer.sample_rate as f64)
-                    .clamp(0.0, buffer.duration().as_secs_f64());
+                self.playback_pos = (time.as_secs_f64() * sample_rate)
+                    .clamp(0.0, buffer.channel_duration_in_samples() as f64);
                 // Then adjust buffer read position.
                 self.buf_read_pos = match *buffer {
                     SoundBuffer::Streaming(ref mut streaming) => {
-                        // Make sure to load correct data into buffer from decoder.
-                        streaming.read_next_block();
-                        // Streaming sources has different buffer read position because
-                        // buffer contains only small portion of data.
-                        self.playback_pos % (StreamingBuffer::STREAM_SAMPLE_COUNT as f64)
+                        // Streaming buffer contains only small portion of data, so the decoder must be
+                        // moved to the new position and the block that starts from it must be loaded.
+                        self.playback_pos = self.playback_pos.round();
+                        streaming.seek(Duration::from_secs_f64(self.playback_pos / sample_rate));
+                        if streaming.is_empty() {
+                            // There is nothing left to play.
+                            let _ = streaming.rewind();
+                            streaming.read_next_block();
+                            self.playback_pos = 0.0;
+                            if !self.looping {
+                                self.status = Status::Stopped;
+                            }
+                        }
+                        0.0
                     }
                     SoundBuffer::Generic(_) => self.playback_pos,
                 };
-                assert!(
-                    self.buf_read_pos * (buffer.channel_count() as f64)
-                        < buffer.samples().len() as f64
-                );
             }
         }
     }
@@ -526,6 +570,44 @@ impl SoundSource {
         }
         // Fill the remaining part of frame_samples.
         self.frame_samples.resize(amount, (0.0, 0.0));
+
+        self.apply_occlusion();
+    }
+
+    fn apply_occlusion(&mut self) {
+        let gain = self.occlusion_gain;
+        // Coefficient of one-pole low-pass filter, 1.0 means that the filter passes the signal as is.
+        let coefficient = self
+            .occlusion_cutoff_frequency
+            .map_or(1.0, |cutoff_frequency| {
+                1.0 - (-2.0 * std::f32::consts::PI * cutoff_frequency / SAMPLE_RATE as f32).exp()
+            });
+        let (last_gain, last_coefficient) = self.last_occlusion.unwrap_or((gain, coefficient));
+        self.last_occlusion = Some((gain, coefficient));
+
+        if gain == 1.0 && last_gain == 1.0 && coefficient == 1.0 && last_coefficient == 1.0 {
+            // Keep the filter state up-to-date, so the filter could be enabled without clicks.
+            if let Some(last) = self.frame_samples.last() {
+                self.occlusion_filter_state = *last;
+            }
+            return;
+        }
+
+        let step = 1.0 / self.frame_samples.len().max(1) as f32;
+        let mut t = 0.0;
+        let (mut left, mut right) = self.occlusion_filter_state;
+        for (sample_left, sample_right) in self.frame_samples.iter_mut() {
+            // Interpolation is very important here, otherwise there will be clicks when
+            // the occlusion changes significantly between frames.
+            let k = lerpf(last_coefficient, coefficient, t);
+            let g = lerpf(last_gain, gain, t);
+            left += k * (*sample_left - left);
+            right += k * (*sample_right - right);
+            *sample_left = left * g;
+            *sample_right = right * g;
+            t += step;
+        }
+        self.occlusion_filter_state = (left, right);
     }
 
     fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize) {
@@ -891,3 +973,37 @@ impl SoundSourceBuilder {
         Ok(source)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::source::SoundSource;
+
+    #[test]
+    fn test_occlusion() {
+        let mut source = SoundSource::default();
+
+        // Gain only.
+        source.set_occlusion(0.5, None);
+        source.frame_samples = vec![(1.0, 1.0); 64];
+        source.apply_occlusion();
+        assert!(source.frame_samples.iter().all(|s| *s == (0.5, 0.5)));
+
+        // Low-pass filter must suppress high frequencies.
+        source.set_occlusion(1.0, Some(200.0));
+        for _ in 0..4 {
+            source.frame_samples = (0..64)
+                .map(|i| if i % 2 == 0 { (1.0, 1.0) } else { (-1.0, -1.0) })
+                .collect();
+            source.apply_occlusion();
+        }
+        assert!(source.frame_samples.iter().all(|s| s.0.abs() < 0.05));
+
+        // Disabled occlusion must not change the signal.
+        source.set_occlusion(1.0, None);
+        source.frame_samples = vec![(1.0, 1.0); 64];
+        source.apply_occlusion();
+        source.frame_samples = vec![(1.0, 1.0); 64];
+        source.apply_occlusion();
+        assert!(source.frame_samples.iter().all(|s| *s == (1.0, 1.0)));
+    }
+}
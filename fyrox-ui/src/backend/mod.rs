@@ -0,0 +1,115 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Drawing backends are responsible for turning the drawing commands, produced by a user interface,
+//! into pixels. The library itself is graphics API agnostic, it only produces a list of drawing
+//! commands (see [`DrawingContext`]) and every backend decides how to draw them. See
+//! [`DrawBackend`] docs for more info.
+//!
+//! The library provides a reference CPU-only backend - [`software::SoftwareRenderer`]. It does not
+//! need any graphics context, so it could be used to render user interfaces in tools, in other
+//! engines, or in automated tests.
+
+use crate::{
+    core::algebra::Vector2,
+    draw::{Command, DrawingContext},
+    UserInterface,
+};
+
+pub mod software;
+
+/// A drawing backend, that draws the commands of a [`DrawingContext`]. The only required method is
+/// [`Self::draw_command`], the rest of the methods have sensible default implementations.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox_ui::{
+///     backend::DrawBackend,
+///     core::algebra::Vector2,
+///     draw::{Command, DrawingContext},
+/// };
+///
+/// // A backend, that only counts the triangles.
+/// #[derive(Default)]
+/// struct TriangleCounter {
+///     triangles: usize,
+/// }
+///
+/// impl DrawBackend for TriangleCounter {
+///     type Error = ();
+///
+///     fn begin_frame(&mut self, _frame_size: Vector2<f32>) -> Result<(), Self::Error> {
+///         self.triangles = 0;
+///         Ok(())
+///     }
+///
+///     fn draw_command(
+///         &mut self,
+///         _drawing_context: &DrawingContext,
+///         command: &Command,
+///     ) -> Result<(), Self::Error> {
+///         self.triangles += command.triangles.len();
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait DrawBackend {
+    /// A type of errors, that could happen during drawing.
+    type Error;
+
+    /// Prepares the backend for a new frame of the given size (in pixels).
+    fn begin_frame(&mut self, frame_size: Vector2<f32>) -> Result<(), Self::Error> {
+        let _ = frame_size;
+        Ok(())
+    }
+
+    /// Draws a single command. Vertices and triangles of the command must be fetched from the
+    /// given drawing context.
+    fn draw_command(
+        &mut self,
+        drawing_context: &DrawingContext,
+        command: &Command,
+    ) -> Result<(), Self::Error>;
+
+    /// Finishes the current frame.
+    fn end_frame(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Draws every command of the drawing context in a frame of the given size (in pixels).
+    fn render(
+        &mut self,
+        drawing_context: &DrawingContext,
+        frame_size: Vector2<f32>,
+    ) -> Result<(), Self::Error> {
+        self.begin_frame(frame_size)?;
+        for command in drawing_context.get_commands() {
+            self.draw_command(drawing_context, command)?;
+        }
+        self.end_frame()
+    }
+
+    /// Draws the current drawing context of the user interface. Keep in mind, that the user
+    /// interface must be drawn first using [`UserInterface::draw`].
+    fn render_ui(&mut self, ui: &UserInterface) -> Result<(), Self::Error> {
+        self.render(ui.get_drawing_context(), ui.screen_size())
+    }
+}
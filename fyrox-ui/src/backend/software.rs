@@ -0,0 +1,505 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reference CPU-only drawing backend. See [`SoftwareRenderer`] docs for more info.
+
+use crate::{
+    backend::DrawBackend,
+    brush::{Brush, GradientPoint},
+    core::{
+        algebra::{Vector2, Vector4},
+        color::Color,
+        math::Rect,
+    },
+    draw::{Command, CommandTexture, DrawingContext, Vertex},
+};
+use fyrox_resource::untyped::UntypedResource;
+use std::convert::Infallible;
+
+/// A source of pixels of the textures, that are used by widgets (for example, by images). The user
+/// interface library does not know anything about texture formats, so it is up to the user to
+/// provide the pixels.
+pub trait TextureSource {
+    /// Returns a color of the texture at the given texture coordinates. `None` means that the
+    /// texture is not available, such textures are treated as white ones.
+    fn sample(&self, texture: &UntypedResource, tex_coord: Vector2<f32>) -> Option<Color>;
+}
+
+enum Sampler<'a> {
+    None,
+    Font { pixels: Vec<u8>, page_size: usize },
+    Texture(&'a UntypedResource),
+}
+
+impl<'a> Sampler<'a> {
+    fn new(texture: &'a CommandTexture) -> Self {
+        match texture {
+            CommandTexture::None => Sampler::None,
+            CommandTexture::Texture(texture) => Sampler::Texture(texture),
+            CommandTexture::Font {
+                font,
+                height,
+                page_index,
+            } => {
+                let mut state = font.state();
+                let Some(font) = state.data() else {
+                    return Sampler::None;
+                };
+                let page_size = font.page_size();
+                match font
+                    .atlases
+                    .get(height)
+                    .and_then(|atlas| atlas.pages.get(*page_index))
+                {
+                    Some(page) => Sampler::Font {
+                        pixels: page.pixels.clone(),
+                        page_size,
+                    },
+                    None => Sampler::None,
+                }
+            }
+        }
+    }
+}
+
+struct BrushSampler<'a> {
+    brush: &'a Brush,
+    bounds: Rect<f32>,
+}
+
+impl BrushSampler<'_> {
+    fn sample(&self, position: Vector2<f32>) -> Vector4<f32> {
+        // Gradients are defined in normalized coordinates of the command bounds.
+        let local = Vector2::new(
+            normalize(position.x - self.bounds.x(), self.bounds.w()),
+            normalize(position.y - self.bounds.y(), self.bounds.h()),
+        );
+        match self.brush {
+            // Color of solid brushes is baked into vertices.
+            Brush::Solid(_) => Vector4::new(1.0, 1.0, 1.0, 1.0),
+            Brush::LinearGradient { from, to, stops } => {
+                let direction = to - from;
+                let length_squared = direction.norm_squared();
+                let t = if length_squared > 0.0 {
+                    ((local - from).dot(&direction) / length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                gradient_color(stops, t)
+            }
+            Brush::RadialGradient { center, stops } => {
+                gradient_color(stops, (local - center).norm().clamp(0.0, 1.0))
+            }
+        }
+    }
+}
+
+fn normalize(value: f32, size: f32) -> f32 {
+    if size > 0.0 {
+        value / size
+    } else {
+        0.0
+    }
+}
+
+fn gradient_color(stops: &[GradientPoint], t: f32) -> Vector4<f32> {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Vector4::new(1.0, 1.0, 1.0, 1.0);
+    };
+    if t <= first.stop {
+        return first.color.as_frgba();
+    }
+    for pair in stops.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        if t <= b.stop {
+            let k = normalize(t - a.stop, b.stop - a.stop);
+            return a.color.as_frgba().lerp(&b.color.as_frgba(), k);
+        }
+    }
+    last.color.as_frgba()
+}
+
+fn to_color(color: Vector4<f32>) -> Color {
+    let c = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::from_rgba(c(color.x), c(color.y), c(color.z), c(color.w))
+}
+
+fn edge(a: Vector2<f32>, b: Vector2<f32>, p: Vector2<f32>) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+// Top-left fill rule, it guarantees that the pixels on the shared edges of adjacent triangles are
+// drawn only once.
+fn is_top_left(a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    (a.y == b.y && b.x > a.x) || b.y < a.y
+}
+
+fn is_inside(w: f32, a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    w > 0.0 || (w == 0.0 && is_top_left(a, b))
+}
+
+/// Reference CPU-only drawing backend, that rasterizes drawing commands into an array of pixels. It
+/// supports every feature of the user interface: solid and gradient brushes, text, opacity,
+/// clipping bounds and clipping geometry. It is not meant to be fast, it is meant to be simple
+/// and to not depend on any graphics API. Typical use cases are tools, that need to produce images
+/// of widgets, and automated tests of user interfaces.
+///
+/// Pixels of the user textures (for example, of images) are unknown to the library, use
+/// [`Self::set_texture_source`] to provide them. Such textures are treated as white ones by
+/// default.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox_ui::{
+///     backend::{software::SoftwareRenderer, DrawBackend},
+///     border::BorderBuilder,
+///     brush::Brush,
+///     core::{algebra::Vector2, color::Color},
+///     widget::WidgetBuilder,
+///     UserInterface,
+/// };
+///
+/// let screen_size = Vector2::new(64.0, 64.0);
+/// let mut ui = UserInterface::new(screen_size);
+/// BorderBuilder::new(
+///     WidgetBuilder::new()
+///         .with_width(64.0)
+///         .with_height(64.0)
+///         .with_background(Brush::Solid(Color::RED)),
+/// )
+/// .build(&mut ui.build_ctx());
+///
+/// ui.update(screen_size, 0.016, &Default::default());
+/// ui.draw();
+///
+/// let mut renderer = SoftwareRenderer::new(64, 64);
+/// renderer.render_ui(&ui).unwrap();
+/// assert_eq!(renderer.pixel(32, 32), Some(Color::RED));
+/// ```
+pub struct SoftwareRenderer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    clear_color: Color,
+    texture_source: Option<Box<dyn TextureSource>>,
+}
+
+impl SoftwareRenderer {
+    /// Creates a new renderer with a frame of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::TRANSPARENT; width * height],
+            clear_color: Color::TRANSPARENT,
+            texture_source: None,
+        }
+    }
+
+    /// Sets a color, that is used to clear the frame at the beginning of each frame. Default is
+    /// [`Color::TRANSPARENT`].
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+    }
+
+    /// Returns current clear color.
+    pub fn clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    /// Sets a new source of pixels of user textures. See [`TextureSource`] docs for more info.
+    pub fn set_texture_source(&mut self, source: Option<Box<dyn TextureSource>>) {
+        self.texture_source = source;
+    }
+
+    /// Changes the size of the frame. The content of the frame is cleared.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![self.clear_color; width * height];
+    }
+
+    /// Returns width of the frame in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns height of the frame in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixels of the frame, row by row starting from the top-left corner.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns a pixel at the given coordinates or `None` if the coordinates are out of bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x < self.width {
+            self.pixels.get(y * self.width + x).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the pixels of the frame as a raw RGBA8 array, it could be used to save the frame
+    /// as an image.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|color| [color.r, color.g, color.b, color.a])
+            .collect()
+    }
+
+    fn blend(&mut self, x: usize, y: usize, source: Vector4<f32>) {
+        let index = y * self.width + x;
+        let dest = self.pixels[index].as_frgba();
+        let alpha = source.w + dest.w * (1.0 - source.w);
+        if alpha <= 0.0 {
+            self.pixels[index] = Color::TRANSPARENT;
+            return;
+        }
+        let rgb = (source.xyz() * source.w + dest.xyz() * dest.w * (1.0 - source.w)) / alpha;
+        self.pixels[index] = to_color(Vector4::new(rgb.x, rgb.y, rgb.z, alpha));
+    }
+
+    fn sample_texture(&self, sampler: &Sampler, tex_coord: Vector2<f32>) -> Vector4<f32> {
+        match sampler {
+            Sampler::None => Vector4::new(1.0, 1.0, 1.0, 1.0),
+            Sampler::Font { pixels, page_size } => {
+                let size = *page_size;
+                let coord = |v: f32| ((v * size as f32) as usize).min(size.saturating_sub(1));
+                let value = pixels
+                    .get(coord(tex_coord.y) * size + coord(tex_coord.x))
+                    .map_or(0.0, |v| *v as f32 / 255.0);
+                // Glyphs are stored in a single channel, it is used as alpha.
+                Vector4::new(1.0, 1.0, 1.0, value)
+            }
+            Sampler::Texture(texture) => self
+                .texture_source
+                .as_ref()
+                .and_then(|source| source.sample(texture, tex_coord))
+                .map_or(Vector4::new(1.0, 1.0, 1.0, 1.0), |color| color.as_frgba()),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn rasterize(
+        &mut self,
+        a: &Vertex,
+        b: &Vertex,
+        c: &Vertex,
+        clip: (Vector2<f32>, Vector2<f32>),
+        command: &Command,
+        sampler: &Sampler,
+        brush: &BrushSampler,
+        opacity: f32,
+    ) {
+        // Make sure that every triangle has the same winding.
+        let (b, c) = if edge(a.pos, b.pos, c.pos) < 0.0 {
+            (c, b)
+        } else {
+            (b, c)
+        };
+        let area = edge(a.pos, b.pos, c.pos);
+        if area <= 0.0 {
+            return;
+        }
+
+        let min = a.pos.inf(&b.pos).inf(&c.pos).sup(&clip.0);
+        let max = a.pos.sup(&b.pos).sup(&c.pos).inf(&clip.1);
+        if min.x >= max.x || min.y >= max.y {
+            return;
+        }
+
+        for y in min.y.floor() as usize..max.y.ceil() as usize {
+            for x in min.x.floor() as usize..max.x.ceil() as usize {
+                let p = Vector2::new(x as f32 + 0.5, y as f32 + 0.5);
+                if p.x < clip.0.x || p.y < clip.0.y || p.x >= clip.1.x || p.y >= clip.1.y {
+                    continue;
+                }
+
+                let wa = edge(b.pos, c.pos, p);
+                let wb = edge(c.pos, a.pos, p);
+                let wc = edge(a.pos, b.pos, p);
+                if !is_inside(wa, b.pos, c.pos)
+                    || !is_inside(wb, c.pos, a.pos)
+                    || !is_inside(wc, a.pos, b.pos)
+                {
+                    continue;
+                }
+
+                if let Some(clipping_geometry) = command.clipping_geometry.as_ref() {
+                    if !clipping_geometry.is_contains_point(p) {
+                        continue;
+                    }
+                }
+
+                let (wa, wb, wc) = (wa / area, wb / area, wc / area);
+                let tex_coord = a.tex_coord * wa + b.tex_coord * wb + c.tex_coord * wc;
+                let vertex_color =
+                    a.color.as_frgba() * wa + b.color.as_frgba() * wb + c.color.as_frgba() * wc;
+
+                let mut color = brush
+                    .sample(p)
+                    .component_mul(&self.sample_texture(sampler, tex_coord))
+                    .component_mul(&vertex_color);
+                color.w *= opacity;
+
+                if color.w > 0.0 {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+impl DrawBackend for SoftwareRenderer {
+    type Error = Infallible;
+
+    fn begin_frame(&mut self, frame_size: Vector2<f32>) -> Result<(), Self::Error> {
+        let width = frame_size.x.max(0.0).round() as usize;
+        let height = frame_size.y.max(0.0).round() as usize;
+        if width != self.width || height != self.height {
+            self.resize(width, height);
+        } else {
+            self.pixels.fill(self.clear_color);
+        }
+        Ok(())
+    }
+
+    fn draw_command(
+        &mut self,
+        drawing_context: &DrawingContext,
+        command: &Command,
+    ) -> Result<(), Self::Error> {
+        let clip_min = command.clip_bounds.position.sup(&Vector2::default());
+        let clip_max = (command.clip_bounds.position + command.clip_bounds.size)
+            .inf(&Vector2::new(self.width as f32, self.height as f32));
+        let sampler = Sampler::new(&command.texture);
+        let brush = BrushSampler {
+            brush: &command.brush,
+            bounds: command.bounds,
+        };
+        // Opacity of solid brushes is baked into vertices.
+        let opacity = match command.brush {
+            Brush::Solid(_) => 1.0,
+            _ => command.opacity,
+        };
+
+        for triangle in &drawing_context.get_triangles()[command.triangles.clone()] {
+            if let Some((a, b, c)) = drawing_context.triangle_points(triangle) {
+                self.rasterize(
+                    a,
+                    b,
+                    c,
+                    (clip_min, clip_max),
+                    command,
+                    &sampler,
+                    &brush,
+                    opacity,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        backend::{software::SoftwareRenderer, DrawBackend},
+        border::BorderBuilder,
+        brush::Brush,
+        core::{algebra::Vector2, color::Color, math::Rect},
+        draw::{CommandTexture, Draw, DrawingContext},
+        text::TextBuilder,
+        widget::WidgetBuilder,
+        HorizontalAlignment, Thickness, UserInterface, VerticalAlignment,
+    };
+
+    #[test]
+    fn test_solid_widgets() {
+        let screen_size = Vector2::new(32.0, 32.0);
+        let mut ui = UserInterface::new(screen_size);
+        BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_width(16.0)
+                .with_height(16.0)
+                .with_horizontal_alignment(HorizontalAlignment::Left)
+                .with_vertical_alignment(VerticalAlignment::Top)
+                .with_background(Brush::Solid(Color::RED)),
+        )
+        .with_stroke_thickness(Thickness::uniform(0.0))
+        .build(&mut ui.build_ctx());
+        TextBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(0.0, 16.0))
+                .with_foreground(Brush::Solid(Color::WHITE)),
+        )
+        .with_text("##")
+        .build(&mut ui.build_ctx());
+
+        ui.update(screen_size, 0.016, &Default::default());
+        ui.draw();
+
+        let mut renderer = SoftwareRenderer::new(1, 1);
+        renderer.set_clear_color(Color::BLACK);
+        renderer.render_ui(&ui).unwrap();
+
+        assert_eq!(renderer.width(), 32);
+        assert_eq!(renderer.height(), 32);
+        assert_eq!(renderer.pixel(0, 0), Some(Color::RED));
+        assert_eq!(renderer.pixel(15, 15), Some(Color::RED));
+        assert_eq!(renderer.pixel(16, 8), Some(Color::BLACK));
+        assert_eq!(renderer.pixel(32, 0), None);
+
+        // Text should produce at least some non-black pixels.
+        assert!((16..32)
+            .flat_map(|y| (0..32).map(move |x| (x, y)))
+            .any(|(x, y)| renderer.pixel(x, y) != Some(Color::BLACK)));
+    }
+
+    #[test]
+    fn test_clipping_and_blending() {
+        let mut ctx = DrawingContext::new();
+        ctx.push_opacity(0.5);
+        ctx.push_rect_filled(&Rect::new(0.0, 0.0, 8.0, 8.0), None);
+        ctx.commit(
+            Rect::new(0.0, 0.0, 4.0, 8.0),
+            Brush::Solid(Color::WHITE),
+            CommandTexture::None,
+            None,
+        );
+
+        let mut renderer = SoftwareRenderer::new(8, 8);
+        renderer.set_clear_color(Color::BLACK);
+        renderer.render(&ctx, Vector2::new(8.0, 8.0)).unwrap();
+
+        // Only the left half must be drawn.
+        assert_eq!(renderer.pixel(0, 0), Some(Color::opaque(128, 128, 128)));
+        assert_eq!(renderer.pixel(3, 7), Some(Color::opaque(128, 128, 128)));
+        assert_eq!(renderer.pixel(4, 0), Some(Color::BLACK));
+        assert_eq!(renderer.to_rgba8().len(), 8 * 8 * 4);
+    }
+}
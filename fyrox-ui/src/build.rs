@@ -22,8 +22,8 @@
 //! more info.
 
 use crate::{
-    core::pool::Handle, font::FontResource, message::UiMessage, RestrictionEntry, UiNode,
-    UserInterface,
+    core::pool::Handle, font::FontResource, localization::LocalizationManager, message::UiMessage,
+    RestrictionEntry, UiNode, UserInterface,
 };
 use fyrox_graph::BaseSceneGraph;
 use std::{
@@ -115,6 +115,11 @@ impl<'a> BuildContext<'a> {
         self.ui.default_font.clone()
     }
 
+    /// Returns localization manager of the UI.
+    pub fn localization(&self) -> &LocalizationManager {
+        &self.ui.localization
+    }
+
     /// Returns current message sender of the UI, that is used for message passing mechanism. You can
     /// send messages for your widgets inside your builders, however this has limited use and should
     /// be avoided in the favor of explicit state modification to not overload message pipeline.
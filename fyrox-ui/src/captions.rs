@@ -0,0 +1,375 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Caption panel is used to show subtitles and closed captions (for example, captions of sounds). See
+//! [`CaptionPanel`] widget docs for more info and usage examples.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        color::Color, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    define_constructor,
+    formatted_text::WrapMode,
+    message::{MessageDirection, UiMessage},
+    stack_panel::StackPanelBuilder,
+    text::TextBuilder,
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, HorizontalAlignment, Orientation, UiNode, UserInterface,
+};
+use fyrox_core::uuid_provider;
+use fyrox_core::variable::InheritableVariable;
+use std::ops::{Deref, DerefMut};
+
+/// A set of messages, that can be used to modify the contents of a [`CaptionPanel`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptionPanelMessage {
+    /// Shows a caption with the given id. If there's already a caption with the same id, it will be
+    /// replaced.
+    Show {
+        /// Unique id of the caption, it could be used later to hide the caption.
+        id: u64,
+        /// Name of the speaker, could be empty.
+        speaker: String,
+        /// Text of the caption.
+        text: String,
+        /// Time (in seconds) after which the caption will be hidden automatically. `None` means
+        /// that the caption will be shown until it is hidden explicitly.
+        duration: Option<f32>,
+    },
+    /// Hides a caption with the given id.
+    Hide(u64),
+    /// Hides every caption.
+    Clear,
+}
+
+impl CaptionPanelMessage {
+    define_constructor!(
+        /// Creates [`CaptionPanelMessage::Show`].
+        CaptionPanelMessage:Show => fn show(id: u64, speaker: String, text: String, duration: Option<f32>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CaptionPanelMessage::Hide`].
+        CaptionPanelMessage:Hide => fn hide(u64), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CaptionPanelMessage::Clear`].
+        CaptionPanelMessage:Clear => fn clear(), layout: false
+    );
+}
+
+/// A caption, that is currently shown by a [`CaptionPanel`].
+#[derive(Default, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct CaptionLine {
+    /// Unique id of the caption.
+    pub id: u64,
+    /// Remaining time (in seconds) of the caption, `None` - infinite.
+    pub time_left: Option<f32>,
+    /// A widget, that shows the caption.
+    pub widget: Handle<UiNode>,
+}
+
+/// Caption panel shows a list of captions (subtitles), each caption could have a speaker name, that
+/// is drawn using a separate brush. Captions are added and removed using [`CaptionPanelMessage`]s, they
+/// could also be hidden automatically after some time. The panel is usually fed with caption events of
+/// sound sources, but it can show any text.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     captions::{CaptionPanelBuilder, CaptionPanelMessage},
+/// #     core::pool::Handle,
+/// #     message::MessageDirection,
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode, UserInterface,
+/// # };
+/// fn create_caption_panel(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     CaptionPanelBuilder::new(WidgetBuilder::new())
+///         .with_max_lines(2)
+///         .build(ctx)
+/// }
+///
+/// fn say_hello(panel: Handle<UiNode>, ui: &UserInterface) {
+///     ui.send_message(CaptionPanelMessage::show(
+///         panel,
+///         MessageDirection::ToWidget,
+///         0,
+///         "Guard".to_string(),
+///         "Halt! Who goes there?".to_string(),
+///         Some(3.0),
+///     ));
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct CaptionPanel {
+    /// Base widget of the caption panel.
+    pub widget: Widget,
+    /// A panel, that holds caption lines.
+    pub panel: InheritableVariable<Handle<UiNode>>,
+    /// Brush, that is used to draw names of speakers.
+    pub speaker_brush: InheritableVariable<Brush>,
+    /// Maximum amount of lines that can be shown at once. The oldest lines will be removed if the
+    /// limit is exceeded.
+    pub max_lines: InheritableVariable<usize>,
+    /// A list of currently shown captions.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub lines: Vec<CaptionLine>,
+}
+
+crate::define_widget_deref!(CaptionPanel);
+
+uuid_provider!(CaptionPanel = "a14a92ff-ddf9-4bb2-a382-eb35d26fd113");
+
+impl Control for CaptionPanel {
+    fn update(&mut self, dt: f32, ui: &mut UserInterface) {
+        self.lines.retain_mut(|line| {
+            if let Some(time_left) = line.time_left.as_mut() {
+                *time_left -= dt;
+                if *time_left <= 0.0 {
+                    ui.send_message(WidgetMessage::remove(
+                        line.widget,
+                        MessageDirection::ToWidget,
+                    ));
+                    return false;
+                }
+            }
+            true
+        });
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if message.destination() == self.handle && message.direction() == MessageDirection::ToWidget
+        {
+            if let Some(msg) = message.data::<CaptionPanelMessage>() {
+                match msg {
+                    CaptionPanelMessage::Show {
+                        id,
+                        speaker,
+                        text,
+                        duration,
+                    } => {
+                        self.remove_line(ui, |line| line.id == *id);
+
+                        let widget = make_line(
+                            &mut ui.build_ctx(),
+                            speaker,
+                            text,
+                            (*self.speaker_brush).clone(),
+                        );
+                        ui.send_message(WidgetMessage::link(
+                            widget,
+                            MessageDirection::ToWidget,
+                            *self.panel,
+                        ));
+                        self.lines.push(CaptionLine {
+                            id: *id,
+                            time_left: *duration,
+                            widget,
+                        });
+
+                        while self.lines.len() > (*self.max_lines).max(1) {
+                            let line = self.lines.remove(0);
+                            ui.send_message(WidgetMessage::remove(
+                                line.widget,
+                                MessageDirection::ToWidget,
+                            ));
+                        }
+                    }
+                    CaptionPanelMessage::Hide(id) => {
+                        self.remove_line(ui, |line| line.id == *id);
+                    }
+                    CaptionPanelMessage::Clear => {
+                        self.remove_line(ui, |_| true);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl CaptionPanel {
+    fn remove_line<F>(&mut self, ui: &UserInterface, mut predicate: F)
+    where
+        F: FnMut(&CaptionLine) -> bool,
+    {
+        self.lines.retain(|line| {
+            if predicate(line) {
+                ui.send_message(WidgetMessage::remove(
+                    line.widget,
+                    MessageDirection::ToWidget,
+                ));
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+fn make_line(
+    ctx: &mut BuildContext,
+    speaker: &str,
+    text: &str,
+    speaker_brush: Brush,
+) -> Handle<UiNode> {
+    let mut builder = WidgetBuilder::new().with_horizontal_alignment(HorizontalAlignment::Center);
+    if !speaker.is_empty() {
+        builder = builder.with_child(
+            TextBuilder::new(WidgetBuilder::new().with_foreground(speaker_brush))
+                .with_text(format!("{speaker}: "))
+                .with_shadow(true)
+                .build(ctx),
+        );
+    }
+    StackPanelBuilder::new(
+        builder.with_child(
+            TextBuilder::new(WidgetBuilder::new())
+                .with_text(text)
+                .with_wrap(WrapMode::Word)
+                .with_shadow(true)
+                .build(ctx),
+        ),
+    )
+    .with_orientation(Orientation::Horizontal)
+    .build(ctx)
+}
+
+/// Caption panel builder creates [`CaptionPanel`] widget instances and adds them to the user interface.
+pub struct CaptionPanelBuilder {
+    widget_builder: WidgetBuilder,
+    speaker_brush: Brush,
+    max_lines: usize,
+}
+
+impl CaptionPanelBuilder {
+    /// Creates new builder instance.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            speaker_brush: Brush::Solid(Color::opaque(255, 215, 0)),
+            max_lines: 3,
+        }
+    }
+
+    /// Sets the desired brush, that will be used to draw names of speakers.
+    pub fn with_speaker_brush(mut self, brush: Brush) -> Self {
+        self.speaker_brush = brush;
+        self
+    }
+
+    /// Sets the desired maximum amount of lines, that can be shown at once.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines;
+        self
+    }
+
+    /// Finishes caption panel creation and adds the new instance to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let panel = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+
+        let caption_panel = CaptionPanel {
+            widget: self
+                .widget_builder
+                .with_need_update(true)
+                .with_child(panel)
+                .build(),
+            panel: panel.into(),
+            speaker_brush: self.speaker_brush.into(),
+            max_lines: self.max_lines.into(),
+            lines: Default::default(),
+        };
+
+        ctx.add_node(UiNode::new(caption_panel))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        captions::{CaptionPanel, CaptionPanelBuilder, CaptionPanelMessage},
+        core::algebra::Vector2,
+        message::MessageDirection,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    #[test]
+    fn test_caption_expiration() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let panel = CaptionPanelBuilder::new(WidgetBuilder::new())
+            .with_max_lines(2)
+            .build(&mut ui.build_ctx());
+
+        for (id, duration) in [(0, Some(1.0)), (1, None), (2, None)] {
+            ui.send_message(CaptionPanelMessage::show(
+                panel,
+                MessageDirection::ToWidget,
+                id,
+                "Speaker".to_string(),
+                "Text".to_string(),
+                duration,
+            ));
+        }
+        while ui.poll_message().is_some() {}
+
+        let ids = |ui: &UserInterface| {
+            ui.node(panel)
+                .query_component::<CaptionPanel>()
+                .unwrap()
+                .lines
+                .iter()
+                .map(|l| l.id)
+                .collect::<Vec<_>>()
+        };
+
+        // The oldest line is removed, because of the limit.
+        assert_eq!(ids(&ui), vec![1, 2]);
+
+        ui.send_message(CaptionPanelMessage::hide(
+            panel,
+            MessageDirection::ToWidget,
+            1,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(ids(&ui), vec![2]);
+
+        ui.send_message(CaptionPanelMessage::show(
+            panel,
+            MessageDirection::ToWidget,
+            3,
+            String::new(),
+            "Text".to_string(),
+            Some(0.5),
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 1.0, &Default::default());
+        while ui.poll_message().is_some() {}
+        assert_eq!(ids(&ui), vec![2]);
+    }
+}
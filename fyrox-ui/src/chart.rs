@@ -0,0 +1,763 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Chart widget is used to plot one or more series of values as lines or bars. It could be used to
+//! visualize profiling data, statistics, telemetry, etc. See [`Chart`] docs for more info and usage
+//! examples.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::{Point2, Vector2},
+        color::Color,
+        math::Rect,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    define_constructor,
+    draw::{CommandTexture, Draw, DrawingContext},
+    formatted_text::{FormattedText, FormattedTextBuilder},
+    message::{MessageDirection, MouseButton, UiMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use fyrox_core::uuid_provider;
+use fyrox_core::variable::InheritableVariable;
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Defines how a series of values is drawn.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum ChartSeriesKind {
+    /// Values are connected with straight lines.
+    #[default]
+    Line,
+    /// Every value is drawn as a vertical bar, that starts at zero.
+    Bar,
+}
+
+uuid_provider!(ChartSeriesKind = "7feb56ac-b45d-4614-b916-1a34948aea7a");
+
+/// A named set of points, that is drawn using the same brush.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct ChartSeries {
+    /// Name of the series, it is shown in the legend of the chart.
+    pub name: String,
+    /// Defines how the series is drawn.
+    pub kind: ChartSeriesKind,
+    /// Brush, that is used to draw the series.
+    pub brush: Brush,
+    /// Points of the series. X coordinates of the points should go in ascending order.
+    pub points: Vec<Vector2<f32>>,
+}
+
+impl Default for ChartSeries {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            kind: Default::default(),
+            brush: Brush::Solid(Color::opaque(0, 162, 232)),
+            points: Default::default(),
+        }
+    }
+}
+
+impl ChartSeries {
+    /// Creates new series with the given name, kind and brush.
+    pub fn new<S: AsRef<str>>(name: S, kind: ChartSeriesKind, brush: Brush) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            kind,
+            brush,
+            points: Default::default(),
+        }
+    }
+
+    /// Sets the desired points of the series.
+    pub fn with_points(mut self, points: Vec<Vector2<f32>>) -> Self {
+        self.points = points;
+        self
+    }
+
+    /// Calculates bounds of every point of the series. Returns `None` if the series is empty.
+    pub fn bounds(&self) -> Option<Rect<f32>> {
+        let first = self.points.first()?;
+        let mut bounds = Rect::new(first.x, first.y, 0.0, 0.0);
+        for point in self.points.iter() {
+            bounds.push(*point);
+        }
+        if self.kind == ChartSeriesKind::Bar {
+            bounds.push(Vector2::new(bounds.x(), 0.0));
+        }
+        Some(bounds)
+    }
+}
+
+/// A set of messages, that can be used to modify a [`Chart`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChartMessage {
+    /// Replaces every series of the chart.
+    Series(Vec<ChartSeries>),
+    /// Adds a point to the series with the given index. If the amount of points exceeds the given
+    /// limit, the oldest points will be removed. This message is useful to plot "live" data, such as
+    /// frame time.
+    PushPoint {
+        /// Index of a series.
+        series: usize,
+        /// A new point.
+        point: Vector2<f32>,
+        /// Maximum amount of points in the series.
+        max_points: usize,
+    },
+    /// Sets visible area of the chart (in the space of values).
+    ViewBounds(Rect<f32>),
+    /// Changes visible area of the chart, so every point of every series will be visible.
+    FitToData,
+}
+
+impl ChartMessage {
+    define_constructor!(
+        /// Creates [`ChartMessage::Series`].
+        ChartMessage:Series => fn series(Vec<ChartSeries>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`ChartMessage::PushPoint`].
+        ChartMessage:PushPoint => fn push_point(series: usize, point: Vector2<f32>, max_points: usize), layout: false
+    );
+    define_constructor!(
+        /// Creates [`ChartMessage::ViewBounds`].
+        ChartMessage:ViewBounds => fn view_bounds(Rect<f32>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`ChartMessage::FitToData`].
+        ChartMessage:FitToData => fn fit_to_data(), layout: false
+    );
+}
+
+/// Chart is a widget, that plots one or more series of values as lines or bars together with
+/// coordinate grid, axes and legend. The visible area of the chart can be zoomed using mouse wheel
+/// (hold `Shift` or `Control` to zoom only horizontally or vertically) and panned by dragging it with
+/// left or middle mouse button.
+///
+/// ## Example
+///
+/// The following example creates a chart with a single line series and then adds a new point to
+/// it, keeping at most 100 last points.
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     brush::Brush,
+/// #     chart::{ChartBuilder, ChartMessage, ChartSeries, ChartSeriesKind},
+/// #     core::{algebra::Vector2, color::Color, pool::Handle},
+/// #     message::MessageDirection,
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode, UserInterface,
+/// # };
+/// fn create_frame_time_chart(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     ChartBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(150.0))
+///         .with_series(vec![ChartSeries::new(
+///             "Frame Time",
+///             ChartSeriesKind::Line,
+///             Brush::Solid(Color::GREEN),
+///         )])
+///         .build(ctx)
+/// }
+///
+/// fn add_frame_time(chart: Handle<UiNode>, frame: usize, frame_time: f32, ui: &UserInterface) {
+///     ui.send_message(ChartMessage::push_point(
+///         chart,
+///         MessageDirection::ToWidget,
+///         0,
+///         Vector2::new(frame as f32, frame_time),
+///         100,
+///     ));
+///     ui.send_message(ChartMessage::fit_to_data(chart, MessageDirection::ToWidget));
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct Chart {
+    /// Base widget of the chart.
+    pub widget: Widget,
+    /// A set of series of the chart.
+    pub series: InheritableVariable<Vec<ChartSeries>>,
+    /// Visible area of the chart in the space of values.
+    pub view_bounds: InheritableVariable<Rect<f32>>,
+    /// Brush, that is used to draw the grid and the axes.
+    pub grid_brush: InheritableVariable<Brush>,
+    /// Approximate distance between grid lines (in pixels).
+    pub grid_spacing: InheritableVariable<f32>,
+    /// Defines whether the values at grid lines should be shown or not.
+    pub show_values: InheritableVariable<bool>,
+    /// Defines whether the legend (names of series) should be shown or not.
+    pub show_legend: InheritableVariable<bool>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    text: RefCell<FormattedText>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    drag_context: Option<DragContext>,
+}
+
+#[derive(Clone, Debug)]
+struct DragContext {
+    initial_mouse_pos: Vector2<f32>,
+    initial_view_bounds: Rect<f32>,
+}
+
+crate::define_widget_deref!(Chart);
+
+uuid_provider!(Chart = "b7ebe742-ca56-401b-a2ae-0bcbec4c1e5f");
+
+/// Calculates "nice" (1, 2, 5 multiplied by a power of ten) step between grid lines, so that the
+/// given range will be split into approximately `count` parts.
+fn nice_step(range: f32, count: f32) -> f32 {
+    let raw_step = range / count.max(1.0);
+    if !raw_step.is_finite() || raw_step <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10.0f32.powf(raw_step.log10().floor());
+    let normalized = raw_step / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn grid_steps(min: f32, max: f32, step: f32) -> impl Iterator<Item = f32> {
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(move |i| i as f32 * step)
+}
+
+impl Chart {
+    /// Transforms a point from the space of values to the local space of the widget.
+    pub fn value_to_local(&self, value: Vector2<f32>) -> Vector2<f32> {
+        let bounds = self.bounding_rect();
+        let view = *self.view_bounds;
+        Vector2::new(
+            bounds.x() + (value.x - view.x()) / view.w() * bounds.w(),
+            bounds.y() + bounds.h() - (value.y - view.y()) / view.h() * bounds.h(),
+        )
+    }
+
+    /// Transforms a point from the local space of the widget to the space of values.
+    pub fn local_to_value(&self, point: Vector2<f32>) -> Vector2<f32> {
+        let bounds = self.bounding_rect();
+        let view = *self.view_bounds;
+        Vector2::new(
+            view.x() + (point.x - bounds.x()) / bounds.w() * view.w(),
+            view.y() + (bounds.y() + bounds.h() - point.y) / bounds.h() * view.h(),
+        )
+    }
+
+    /// Transforms a point from the space of values to the screen space.
+    pub fn value_to_screen(&self, value: Vector2<f32>) -> Vector2<f32> {
+        self.visual_transform
+            .transform_point(&Point2::from(self.value_to_local(value)))
+            .coords
+    }
+
+    /// Transforms a point from the screen space to the space of values.
+    pub fn screen_to_value(&self, point: Vector2<f32>) -> Vector2<f32> {
+        self.local_to_value(self.screen_to_local(point))
+    }
+
+    /// Calculates bounds of every point of every series. Returns `None` if there's no points.
+    pub fn data_bounds(&self) -> Option<Rect<f32>> {
+        let mut result: Option<Rect<f32>> = None;
+        for bounds in self.series.iter().filter_map(|s| s.bounds()) {
+            match result.as_mut() {
+                Some(result) => result.extend_to_contain(bounds),
+                None => result = Some(bounds),
+            }
+        }
+        result
+    }
+
+    fn set_view_bounds(&mut self, bounds: Rect<f32>) {
+        // Prevent degenerate view, it will cause division by zero.
+        let bounds = Rect::new(
+            bounds.x(),
+            bounds.y(),
+            bounds.w().max(f32::EPSILON),
+            bounds.h().max(f32::EPSILON),
+        );
+        self.view_bounds.set_value_and_mark_modified(bounds);
+    }
+
+    fn fit_to_data(&mut self) {
+        if let Some(bounds) = self.data_bounds() {
+            // Add a small margin, so the extreme points won't be drawn exactly at the borders.
+            let margin_x = if bounds.w() > 0.0 {
+                bounds.w() * 0.05
+            } else {
+                1.0
+            };
+            let margin_y = if bounds.h() > 0.0 {
+                bounds.h() * 0.05
+            } else {
+                1.0
+            };
+            self.set_view_bounds(Rect::new(
+                bounds.x() - margin_x,
+                bounds.y() - margin_y,
+                bounds.w() + 2.0 * margin_x,
+                bounds.h() + 2.0 * margin_y,
+            ));
+        }
+    }
+
+    fn draw_grid(&self, ctx: &mut DrawingContext) {
+        let bounds = self.bounding_rect();
+        let view = *self.view_bounds;
+        let spacing = self.grid_spacing.max(1.0);
+        let step_x = nice_step(view.w(), bounds.w() / spacing);
+        let step_y = nice_step(view.h(), bounds.h() / spacing);
+
+        for x in grid_steps(view.x(), view.x() + view.w(), step_x) {
+            let local_x = self.value_to_local(Vector2::new(x, 0.0)).x;
+            ctx.push_line(
+                Vector2::new(local_x, bounds.y()),
+                Vector2::new(local_x, bounds.y() + bounds.h()),
+                if x == 0.0 { 2.0 } else { 1.0 },
+            );
+        }
+        for y in grid_steps(view.y(), view.y() + view.h(), step_y) {
+            let local_y = self.value_to_local(Vector2::new(0.0, y)).y;
+            ctx.push_line(
+                Vector2::new(bounds.x(), local_y),
+                Vector2::new(bounds.x() + bounds.w(), local_y),
+                if y == 0.0 { 2.0 } else { 1.0 },
+            );
+        }
+        ctx.commit(
+            self.clip_bounds(),
+            (*self.grid_brush).clone(),
+            CommandTexture::None,
+            None,
+        );
+
+        if *self.show_values {
+            let mut text = self.text.borrow_mut();
+            for x in grid_steps(view.x(), view.x() + view.w(), step_x) {
+                text.set_text(format!("{x}")).build();
+                let position = self.value_to_local(Vector2::new(x, view.y()));
+                ctx.draw_text(
+                    self.clip_bounds(),
+                    Vector2::new(position.x + 2.0, position.y - text.font_size() - 2.0),
+                    &text,
+                );
+            }
+            for y in grid_steps(view.y(), view.y() + view.h(), step_y) {
+                text.set_text(format!("{y}")).build();
+                ctx.draw_text(
+                    self.clip_bounds(),
+                    self.value_to_local(Vector2::new(view.x(), y)) + Vector2::new(2.0, 0.0),
+                    &text,
+                );
+            }
+        }
+    }
+
+    fn draw_series(&self, ctx: &mut DrawingContext) {
+        let bar_series_count = self
+            .series
+            .iter()
+            .filter(|s| s.kind == ChartSeriesKind::Bar)
+            .count();
+        let mut bar_series_index = 0;
+
+        for series in self.series.iter() {
+            match series.kind {
+                ChartSeriesKind::Line => {
+                    for segment in series.points.windows(2) {
+                        ctx.push_line(
+                            self.value_to_local(segment[0]),
+                            self.value_to_local(segment[1]),
+                            2.0,
+                        );
+                    }
+                }
+                ChartSeriesKind::Bar => {
+                    // Bars of different series are placed side by side, so they won't overlap.
+                    let min_distance = series
+                        .points
+                        .windows(2)
+                        .map(|segment| (segment[1].x - segment[0].x).abs())
+                        .filter(|d| *d > 0.0)
+                        .min_by(|a, b| a.total_cmp(b))
+                        .unwrap_or(1.0);
+                    let group_width = min_distance * 0.8;
+                    let bar_width = group_width / bar_series_count as f32;
+                    let offset = -group_width * 0.5 + bar_width * bar_series_index as f32;
+
+                    for point in series.points.iter() {
+                        let a = self.value_to_local(Vector2::new(point.x + offset, point.y));
+                        let b =
+                            self.value_to_local(Vector2::new(point.x + offset + bar_width, 0.0));
+                        ctx.push_rect_filled(
+                            &Rect::new(
+                                a.x.min(b.x),
+                                a.y.min(b.y),
+                                (b.x - a.x).abs().max(1.0),
+                                (b.y - a.y).abs(),
+                            ),
+                            None,
+                        );
+                    }
+
+                    bar_series_index += 1;
+                }
+            }
+            ctx.commit(
+                self.clip_bounds(),
+                series.brush.clone(),
+                CommandTexture::None,
+                None,
+            );
+        }
+    }
+
+    fn draw_legend(&self, ctx: &mut DrawingContext) {
+        let bounds = self.bounding_rect();
+        let mut text = self.text.borrow_mut();
+        let mut position = Vector2::new(bounds.x() + bounds.w() - 4.0, bounds.y() + 4.0);
+        for series in self.series.iter() {
+            text.set_text(&series.name).build();
+            let size = text.font_size();
+            let width = text.get_range_width(0..series.name.chars().count());
+            let left = position.x - width - size - 4.0;
+            ctx.push_rect_filled(&Rect::new(left, position.y, size, size), None);
+            ctx.commit(
+                self.clip_bounds(),
+                series.brush.clone(),
+                CommandTexture::None,
+                None,
+            );
+            ctx.draw_text(
+                self.clip_bounds(),
+                Vector2::new(left + size + 4.0, position.y),
+                &text,
+            );
+            position.y += size + 2.0;
+        }
+    }
+}
+
+impl Control for Chart {
+    fn draw(&self, ctx: &mut DrawingContext) {
+        ctx.push_rect_filled(&self.bounding_rect(), None);
+        ctx.commit(
+            self.clip_bounds(),
+            self.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        self.draw_grid(ctx);
+        self.draw_series(ctx);
+        if *self.show_legend {
+            self.draw_legend(ctx);
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if message.destination() == self.handle {
+            if let Some(msg) = message.data::<WidgetMessage>() {
+                match msg {
+                    WidgetMessage::MouseDown { pos, button }
+                        if *button == MouseButton::Left || *button == MouseButton::Middle =>
+                    {
+                        self.drag_context = Some(DragContext {
+                            initial_mouse_pos: *pos,
+                            initial_view_bounds: *self.view_bounds,
+                        });
+                        ui.capture_mouse(self.handle);
+                        message.set_handled(true);
+                    }
+                    WidgetMessage::MouseUp { .. } if self.drag_context.is_some() => {
+                        self.drag_context = None;
+                        ui.release_mouse_capture();
+                        message.set_handled(true);
+                    }
+                    WidgetMessage::MouseMove { pos, .. } => {
+                        if let Some(drag_context) = self.drag_context.as_ref() {
+                            let screen_bounds = self.screen_bounds();
+                            let view = drag_context.initial_view_bounds;
+                            let d = *pos - drag_context.initial_mouse_pos;
+                            // Dragging right moves the view left. Remember that Y axis of the
+                            // chart points up, while Y axis of the screen points down.
+                            let delta = Vector2::new(
+                                -d.x / screen_bounds.w() * view.w(),
+                                d.y / screen_bounds.h() * view.h(),
+                            );
+                            ui.send_message(ChartMessage::view_bounds(
+                                self.handle,
+                                MessageDirection::ToWidget,
+                                Rect::new(
+                                    view.x() + delta.x,
+                                    view.y() + delta.y,
+                                    view.w(),
+                                    view.h(),
+                                ),
+                            ));
+                            message.set_handled(true);
+                        }
+                    }
+                    WidgetMessage::MouseWheel { pos, amount } => {
+                        let k = if *amount < 0.0 { 1.1 } else { 0.9 };
+                        let scale = if ui.keyboard_modifiers().shift {
+                            Vector2::new(k, 1.0)
+                        } else if ui.keyboard_modifiers().control {
+                            Vector2::new(1.0, k)
+                        } else {
+                            Vector2::new(k, k)
+                        };
+
+                        // Keep the value under the cursor at the same place.
+                        let pivot = self.screen_to_value(*pos);
+                        let view = *self.view_bounds;
+                        let w = view.w() * scale.x;
+                        let h = view.h() * scale.y;
+                        ui.send_message(ChartMessage::view_bounds(
+                            self.handle,
+                            MessageDirection::ToWidget,
+                            Rect::new(
+                                pivot.x - (pivot.x - view.x()) * scale.x,
+                                pivot.y - (pivot.y - view.y()) * scale.y,
+                                w,
+                                h,
+                            ),
+                        ));
+                        message.set_handled(true);
+                    }
+                    _ => (),
+                }
+            } else if let Some(msg) = message.data::<ChartMessage>() {
+                if message.direction() == MessageDirection::ToWidget {
+                    match msg {
+                        ChartMessage::Series(series) => {
+                            self.series.set_value_and_mark_modified(series.clone());
+                        }
+                        ChartMessage::PushPoint {
+                            series,
+                            point,
+                            max_points,
+                        } => {
+                            if let Some(series) = self
+                                .series
+                                .get_value_mut_and_mark_modified()
+                                .get_mut(*series)
+                            {
+                                series.points.push(*point);
+                                if series.points.len() > *max_points {
+                                    let excess = series.points.len() - *max_points;
+                                    series.points.drain(..excess);
+                                }
+                            }
+                        }
+                        ChartMessage::ViewBounds(bounds) => {
+                            if *bounds != *self.view_bounds {
+                                self.set_view_bounds(*bounds);
+                                ui.send_message(message.reverse());
+                            }
+                        }
+                        ChartMessage::FitToData => {
+                            self.fit_to_data();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Chart builder creates [`Chart`] widget instances and adds them to the user interface.
+pub struct ChartBuilder {
+    widget_builder: WidgetBuilder,
+    series: Vec<ChartSeries>,
+    view_bounds: Option<Rect<f32>>,
+    grid_brush: Brush,
+    grid_spacing: f32,
+    show_values: bool,
+    show_legend: bool,
+}
+
+impl ChartBuilder {
+    /// Creates new builder instance.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            series: Default::default(),
+            view_bounds: None,
+            grid_brush: Brush::Solid(Color::from_rgba(110, 110, 110, 80)),
+            grid_spacing: 50.0,
+            show_values: true,
+            show_legend: true,
+        }
+    }
+
+    /// Sets the desired series of the chart.
+    pub fn with_series(mut self, series: Vec<ChartSeries>) -> Self {
+        self.series = series;
+        self
+    }
+
+    /// Sets the desired visible area of the chart (in the space of values). If not set, the chart
+    /// will fit the visible area to the series.
+    pub fn with_view_bounds(mut self, bounds: Rect<f32>) -> Self {
+        self.view_bounds = Some(bounds);
+        self
+    }
+
+    /// Sets the desired brush of the grid and the axes.
+    pub fn with_grid_brush(mut self, brush: Brush) -> Self {
+        self.grid_brush = brush;
+        self
+    }
+
+    /// Sets the desired approximate distance between grid lines (in pixels).
+    pub fn with_grid_spacing(mut self, spacing: f32) -> Self {
+        self.grid_spacing = spacing;
+        self
+    }
+
+    /// Defines whether the values at grid lines should be shown or not.
+    pub fn with_show_values(mut self, show: bool) -> Self {
+        self.show_values = show;
+        self
+    }
+
+    /// Defines whether the legend should be shown or not.
+    pub fn with_show_legend(mut self, show: bool) -> Self {
+        self.show_legend = show;
+        self
+    }
+
+    /// Finishes chart creation and adds the new instance to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let mut chart = Chart {
+            widget: self
+                .widget_builder
+                .with_background(Brush::Solid(Color::from_rgba(30, 30, 30, 200)))
+                .build(),
+            series: self.series.into(),
+            view_bounds: Rect::new(0.0, 0.0, 1.0, 1.0).into(),
+            grid_brush: self.grid_brush.into(),
+            grid_spacing: self.grid_spacing.into(),
+            show_values: self.show_values.into(),
+            show_legend: self.show_legend.into(),
+            text: RefCell::new(
+                FormattedTextBuilder::new(ctx.default_font())
+                    .with_brush(Brush::Solid(Color::opaque(160, 160, 160)))
+                    .build(),
+            ),
+            drag_context: None,
+        };
+
+        match self.view_bounds {
+            Some(view_bounds) => chart.set_view_bounds(view_bounds),
+            None => chart.fit_to_data(),
+        }
+
+        ctx.add_node(UiNode::new(chart))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chart::{nice_step, Chart, ChartBuilder, ChartSeries, ChartSeriesKind},
+        core::{algebra::Vector2, math::Rect},
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    #[test]
+    fn test_nice_step() {
+        assert_eq!(nice_step(10.0, 10.0), 1.0);
+        assert_eq!(nice_step(10.0, 4.0), 5.0);
+        assert_eq!(nice_step(1.0, 6.0), 0.2);
+        assert_eq!(nice_step(0.0, 6.0), 1.0);
+    }
+
+    #[test]
+    fn test_chart_transforms() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let chart_handle =
+            ChartBuilder::new(WidgetBuilder::new().with_width(200.0).with_height(100.0))
+                .with_series(vec![ChartSeries::new(
+                    "Test",
+                    ChartSeriesKind::Bar,
+                    Default::default(),
+                )
+                .with_points(vec![Vector2::new(1.0, 2.0), Vector2::new(3.0, 4.0)])])
+                .with_view_bounds(Rect::new(0.0, 0.0, 10.0, 10.0))
+                .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0, &Default::default());
+
+        let chart = ui.node(chart_handle).query_component::<Chart>().unwrap();
+        let screen = chart.value_to_screen(Vector2::new(5.0, 5.0));
+        assert_eq!(screen, Vector2::new(100.0, 50.0));
+        assert_eq!(chart.screen_to_value(screen), Vector2::new(5.0, 5.0));
+        assert_eq!(chart.data_bounds(), Some(Rect::new(1.0, 0.0, 2.0, 4.0)));
+
+        // Drawing is done in local space, the visual transform is applied only once by the
+        // drawing context.
+        ui.set_scale(2.0);
+        ui.update(screen_size, 0.0, &Default::default());
+        let chart = ui.node(chart_handle).query_component::<Chart>().unwrap();
+        let value = Vector2::new(5.0, 5.0);
+        assert_eq!(chart.value_to_local(value), Vector2::new(100.0, 50.0));
+        let screen = chart.value_to_screen(value);
+        assert_eq!(screen, Vector2::new(200.0, 100.0));
+        assert_eq!(chart.screen_to_value(screen), value);
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Rope-based text storage of the code editor. See [`TextBuffer`] docs for more info.
+
+use crate::{core::visitor::prelude::*, formatted_text::Position};
+use ropey::Rope;
+use std::ops::Range;
+
+/// Text storage of the code editor. It is backed by a rope, so insertions and removals stay
+/// cheap even for very large texts. Lines are separated by `\n`, the last line of a text ending
+/// with a line break is an empty line.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TextBuffer {
+    rope: Rope,
+}
+
+impl Visit for TextBuffer {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut text = self.text();
+        text.visit(name, visitor)?;
+        if visitor.is_reading() {
+            self.rope = Rope::from_str(&text);
+        }
+        Ok(())
+    }
+}
+
+impl TextBuffer {
+    /// Creates a new buffer with the given text.
+    pub fn new(text: &str) -> Self {
+        Self {
+            rope: Rope::from_str(text),
+        }
+    }
+
+    /// Returns the whole text of the buffer.
+    pub fn text(&self) -> String {
+        self.rope.to_string()
+    }
+
+    /// Returns the total amount of chars in the buffer.
+    pub fn len_chars(&self) -> usize {
+        self.rope.len_chars()
+    }
+
+    /// Returns `true` if the buffer has no text.
+    pub fn is_empty(&self) -> bool {
+        self.rope.len_chars() == 0
+    }
+
+    /// Returns the amount of lines in the buffer, it is always at least one.
+    pub fn line_count(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    /// Returns the amount of chars in the given line excluding the line break.
+    pub fn line_len(&self, line: usize) -> usize {
+        if line >= self.line_count() {
+            return 0;
+        }
+        let slice = self.rope.line(line);
+        let mut len = slice.len_chars();
+        while len > 0 && matches!(slice.char(len - 1), '\n' | '\r') {
+            len -= 1;
+        }
+        len
+    }
+
+    /// Returns the text of the given line excluding the line break.
+    pub fn line(&self, line: usize) -> String {
+        if line >= self.line_count() {
+            return Default::default();
+        }
+        self.rope
+            .line(line)
+            .slice(..self.line_len(line))
+            .to_string()
+    }
+
+    /// Clamps the position, so it points to an existing char (or to the end of a line).
+    pub fn clamp_position(&self, position: Position) -> Position {
+        let line = position.line.min(self.line_count() - 1);
+        Position {
+            line,
+            offset: position.offset.min(self.line_len(line)),
+        }
+    }
+
+    /// Converts the position to the index of a char in the whole text.
+    pub fn position_to_char_index(&self, position: Position) -> usize {
+        let position = self.clamp_position(position);
+        self.rope.line_to_char(position.line) + position.offset
+    }
+
+    /// Converts the index of a char in the whole text to the position.
+    pub fn char_index_to_position(&self, index: usize) -> Position {
+        let index = index.min(self.len_chars());
+        let line = self.rope.char_to_line(index);
+        Position {
+            line,
+            offset: index - self.rope.line_to_char(line),
+        }
+    }
+
+    /// Returns the position right after the last char of the text.
+    pub fn end_position(&self) -> Position {
+        let line = self.line_count() - 1;
+        Position {
+            line,
+            offset: self.line_len(line),
+        }
+    }
+
+    /// Inserts the string at the given position and returns the position right after the
+    /// inserted text.
+    pub fn insert(&mut self, position: Position, text: &str) -> Position {
+        let index = self.position_to_char_index(position);
+        self.rope.insert(index, text);
+        self.char_index_to_position(index + text.chars().count())
+    }
+
+    /// Removes the text in the given range of positions (the order of positions is not important).
+    pub fn remove(&mut self, range: Range<Position>) {
+        let range = self.char_range(range);
+        self.rope.remove(range);
+    }
+
+    /// Returns the text in the given range of positions (the order of positions is not important).
+    pub fn text_range(&self, range: Range<Position>) -> String {
+        self.rope.slice(self.char_range(range)).to_string()
+    }
+
+    fn char_range(&self, range: Range<Position>) -> Range<usize> {
+        let a = self.position_to_char_index(range.start);
+        let b = self.position_to_char_index(range.end);
+        a.min(b)..a.max(b)
+    }
+
+    /// Searches for the next occurrence of the query starting from the given position. The search
+    /// wraps around the end of the text. Queries spanning multiple lines are not supported.
+    pub fn find(&self, query: &str, from: Position) -> Option<Range<Position>> {
+        if query.is_empty() || query.contains('\n') {
+            return None;
+        }
+
+        let from = self.clamp_position(from);
+        let line_count = self.line_count();
+        // The starting line is visited twice: the part after the position first and the part
+        // before the position after wrapping around.
+        for i in 0..=line_count {
+            let line = (from.line + i) % line_count;
+            let found = find_in_line(&self.line(line), query)
+                .into_iter()
+                .find(|range| {
+                    if i == 0 {
+                        range.start >= from.offset
+                    } else if i == line_count {
+                        range.start < from.offset
+                    } else {
+                        true
+                    }
+                });
+            if let Some(range) = found {
+                return Some(
+                    Position {
+                        line,
+                        offset: range.start,
+                    }..Position {
+                        line,
+                        offset: range.end,
+                    },
+                );
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns char ranges of every non-overlapping occurrence of the query in the line.
+pub fn find_in_line(line: &str, query: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+
+    let query_len = query.chars().count();
+    let mut last_byte = 0;
+    let mut last_char = 0;
+    for (byte_index, _) in line.match_indices(query) {
+        last_char += line[last_byte..byte_index].chars().count();
+        last_byte = byte_index;
+        ranges.push(last_char..last_char + query_len);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        code_editor::buffer::{find_in_line, TextBuffer},
+        formatted_text::Position,
+    };
+
+    fn pos(line: usize, offset: usize) -> Position {
+        Position { line, offset }
+    }
+
+    #[test]
+    fn test_text_buffer_editing() {
+        let mut buffer = TextBuffer::new("fn main() {\r\n}\n");
+        assert_eq!(buffer.line_count(), 3);
+        assert_eq!(buffer.line(0), "fn main() {");
+        assert_eq!(buffer.line_len(0), 11);
+        assert_eq!(buffer.line(2), "");
+        assert_eq!(buffer.end_position(), pos(2, 0));
+
+        let end = buffer.insert(pos(0, 11), "\n    foo();");
+        assert_eq!(end, pos(1, 10));
+        assert_eq!(buffer.line(1), "    foo();");
+
+        buffer.remove(pos(1, 10)..pos(1, 4));
+        assert_eq!(buffer.line(1), "    ");
+        assert_eq!(buffer.text_range(pos(0, 10)..pos(1, 1)), "{\n ");
+        assert_eq!(buffer.clamp_position(pos(10, 10)), pos(3, 0));
+        assert_eq!(buffer.char_index_to_position(13), pos(1, 1));
+    }
+
+    #[test]
+    fn test_text_buffer_search() {
+        let buffer = TextBuffer::new("abc abc\nxyz\nabc");
+        assert_eq!(find_in_line("ёabc abc", "abc"), vec![1..4, 5..8]);
+        assert_eq!(buffer.find("abc", pos(0, 1)), Some(pos(0, 4)..pos(0, 7)));
+        assert_eq!(buffer.find("abc", pos(0, 5)), Some(pos(2, 0)..pos(2, 3)));
+        assert_eq!(buffer.find("xyz", pos(2, 3)), Some(pos(1, 0)..pos(1, 3)));
+        assert_eq!(buffer.find("abc", pos(2, 1)), Some(pos(0, 0)..pos(0, 3)));
+        assert_eq!(buffer.find("nothing", pos(0, 0)), None);
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Syntax highlighting of the code editor. See [`TokenProvider`] docs for more info.
+
+use crate::core::{color::Color, reflect::prelude::*, visitor::prelude::*};
+use fxhash::FxHashSet;
+use std::{fmt::Debug, ops::Range};
+
+/// Kind of a token, it defines the color of the token in [`SyntaxTheme`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Visit, Reflect)]
+pub enum TokenKind {
+    /// Plain text, such as identifiers.
+    #[default]
+    Text,
+    /// Keyword of a language.
+    Keyword,
+    /// Built-in type of a language.
+    Type,
+    /// Numeric literal.
+    Number,
+    /// String literal.
+    String,
+    /// Comment.
+    Comment,
+    /// Operators, brackets, separators, etc.
+    Punctuation,
+}
+
+/// A highlighted part of a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    /// Range of chars of the line, that belongs to the token.
+    pub range: Range<usize>,
+    /// Kind of the token.
+    pub kind: TokenKind,
+}
+
+/// Token provider splits lines of a code editor into highlighted tokens. Lines are tokenized
+/// independently and only when they are visible, so the cost of highlighting does not depend on
+/// the size of the text. Chars that are not covered by any token are drawn as [`TokenKind::Text`].
+pub trait TokenProvider: Debug + Send + Sync {
+    /// Splits the line into tokens. Tokens must be sorted and must not overlap.
+    fn tokenize_line(&self, line: &str, tokens: &mut Vec<Token>);
+}
+
+/// Simple token provider that highlights keywords, types, numbers, strings and line comments of
+/// C-like languages.
+#[derive(Clone, Debug, Default)]
+pub struct KeywordTokenProvider {
+    /// A set of keywords of a language.
+    pub keywords: FxHashSet<String>,
+    /// A set of built-in types of a language.
+    pub types: FxHashSet<String>,
+    /// A prefix of line comments.
+    pub line_comment: String,
+}
+
+const GLSL_KEYWORDS: &str = "if else for while do break continue return discard switch case \
+    default struct uniform in out inout const layout flat smooth precision highp mediump lowp true \
+    false";
+
+const GLSL_TYPES: &str = "void bool int uint float vec2 vec3 vec4 ivec2 ivec3 ivec4 uvec2 uvec3 \
+    uvec4 bvec2 bvec3 bvec4 mat2 mat3 mat4 sampler2D sampler3D samplerCube usampler2D \
+    sampler2DArray";
+
+const RUST_KEYWORDS: &str = "as break const continue crate else enum extern false fn for if impl \
+    in let loop match mod move mut pub ref return self Self static struct super trait true type \
+    unsafe use where while async await dyn";
+
+const RUST_TYPES: &str = "bool char str u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 \
+    f64 String Vec Option Result Box";
+
+impl KeywordTokenProvider {
+    /// Creates a new token provider.
+    pub fn new<'a>(
+        keywords: impl IntoIterator<Item = &'a str>,
+        types: impl IntoIterator<Item = &'a str>,
+        line_comment: &str,
+    ) -> Self {
+        Self {
+            keywords: keywords.into_iter().map(|k| k.to_string()).collect(),
+            types: types.into_iter().map(|t| t.to_string()).collect(),
+            line_comment: line_comment.to_string(),
+        }
+    }
+
+    /// Creates a token provider for GLSL shaders.
+    pub fn glsl() -> Self {
+        Self::new(
+            GLSL_KEYWORDS.split_whitespace(),
+            GLSL_TYPES.split_whitespace(),
+            "//",
+        )
+    }
+
+    /// Creates a token provider for Rust scripts.
+    pub fn rust() -> Self {
+        Self::new(
+            RUST_KEYWORDS.split_whitespace(),
+            RUST_TYPES.split_whitespace(),
+            "//",
+        )
+    }
+}
+
+impl TokenProvider for KeywordTokenProvider {
+    fn tokenize_line(&self, line: &str, tokens: &mut Vec<Token>) {
+        let chars = line.chars().collect::<Vec<_>>();
+        let comment = self.line_comment.chars().collect::<Vec<_>>();
+
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let start = i;
+            let kind = if !comment.is_empty() && chars[i..].starts_with(&comment) {
+                i = chars.len();
+                TokenKind::Comment
+            } else if c.is_alphabetic() || c == '_' {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word = chars[start..i].iter().collect::<String>();
+                if self.keywords.contains(&word) {
+                    TokenKind::Keyword
+                } else if self.types.contains(&word) {
+                    TokenKind::Type
+                } else {
+                    TokenKind::Text
+                }
+            } else if c.is_ascii_digit() {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                TokenKind::Number
+            } else if c == '"' {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    // Skip escaped chars.
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(chars.len());
+                TokenKind::String
+            } else if c.is_ascii_punctuation() {
+                i += 1;
+                TokenKind::Punctuation
+            } else {
+                i += 1;
+                continue;
+            };
+
+            if kind != TokenKind::Text {
+                tokens.push(Token {
+                    range: start..i,
+                    kind,
+                });
+            }
+        }
+    }
+}
+
+/// Colors of the code editor.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct SyntaxTheme {
+    /// Color of plain text.
+    pub text: Color,
+    /// Color of keywords.
+    pub keyword: Color,
+    /// Color of built-in types.
+    pub type_name: Color,
+    /// Color of numeric literals.
+    pub number: Color,
+    /// Color of string literals.
+    pub string: Color,
+    /// Color of comments.
+    pub comment: Color,
+    /// Color of punctuation.
+    pub punctuation: Color,
+    /// Color of line numbers.
+    pub line_number: Color,
+    /// Background color of the line numbers column.
+    pub gutter: Color,
+    /// Color of selected text background.
+    pub selection: Color,
+    /// Background color of search matches.
+    pub search_match: Color,
+    /// Color of the caret.
+    pub caret: Color,
+}
+
+impl Default for SyntaxTheme {
+    fn default() -> Self {
+        Self {
+            text: Color::opaque(220, 220, 220),
+            keyword: Color::opaque(204, 120, 50),
+            type_name: Color::opaque(78, 201, 176),
+            number: Color::opaque(104, 151, 187),
+            string: Color::opaque(106, 135, 89),
+            comment: Color::opaque(128, 128, 128),
+            punctuation: Color::opaque(180, 180, 180),
+            line_number: Color::opaque(110, 110, 110),
+            gutter: Color::opaque(35, 35, 35),
+            selection: Color::from_rgba(80, 120, 200, 120),
+            search_match: Color::from_rgba(200, 170, 50, 90),
+            caret: Color::WHITE,
+        }
+    }
+}
+
+impl SyntaxTheme {
+    /// Returns the color of the given kind of tokens.
+    pub fn color(&self, kind: TokenKind) -> Color {
+        match kind {
+            TokenKind::Text => self.text,
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Type => self.type_name,
+            TokenKind::Number => self.number,
+            TokenKind::String => self.string,
+            TokenKind::Comment => self.comment,
+            TokenKind::Punctuation => self.punctuation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::code_editor::highlight::{KeywordTokenProvider, Token, TokenKind, TokenProvider};
+
+    #[test]
+    fn test_keyword_token_provider() {
+        let mut tokens = Vec::new();
+        KeywordTokenProvider::glsl().tokenize_line("float x = 1.5; // \"comment\"", &mut tokens);
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    range: 0..5,
+                    kind: TokenKind::Type
+                },
+                Token {
+                    range: 8..9,
+                    kind: TokenKind::Punctuation
+                },
+                Token {
+                    range: 10..13,
+                    kind: TokenKind::Number
+                },
+                Token {
+                    range: 13..14,
+                    kind: TokenKind::Punctuation
+                },
+                Token {
+                    range: 15..27,
+                    kind: TokenKind::Comment
+                },
+            ]
+        );
+
+        tokens.clear();
+        KeywordTokenProvider::rust().tokenize_line(r#"let s = "a\"b";"#, &mut tokens);
+        assert_eq!(tokens[0].kind, TokenKind::Keyword);
+        assert_eq!(tokens[2].range, 8..14);
+        assert_eq!(tokens[2].kind, TokenKind::String);
+    }
+}
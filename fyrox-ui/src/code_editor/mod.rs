@@ -0,0 +1,1033 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Code editor is a multi-line text editor with line numbers, syntax highlighting and search. See
+//! [`CodeEditor`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::{Point2, Vector2},
+        color::Color,
+        math::Rect,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid_provider,
+        visitor::prelude::*,
+    },
+    define_constructor,
+    draw::{CommandTexture, Draw, DrawingContext},
+    font::{Font, FontResource},
+    formatted_text::Position,
+    message::{CursorIcon, KeyCode, MessageDirection, MouseButton, UiMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use copypasta::ClipboardProvider;
+use std::{
+    ops::{Deref, DerefMut, Range},
+    sync::Arc,
+};
+
+pub mod buffer;
+pub mod highlight;
+
+pub use buffer::TextBuffer;
+pub use highlight::{KeywordTokenProvider, SyntaxTheme, Token, TokenKind, TokenProvider};
+
+/// A message that could be used to alternate code editor's state or receive changes from it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeEditorMessage {
+    /// Replaces the text of the editor. The editor sends this message back with the new text when
+    /// it loses focus after the text was changed.
+    Text(String),
+    /// Highlights every occurrence of the query and selects the next one after the caret. Empty
+    /// query removes the highlighting.
+    Search(String),
+    /// Selects the next occurrence of the current search query.
+    FindNext,
+    /// Moves the caret to the beginning of the given (zero-based) line.
+    GoToLine(usize),
+    /// Shows or hides line numbers.
+    ShowLineNumbers(bool),
+    /// Enables or disables an ability to edit the text.
+    Editable(bool),
+}
+
+impl CodeEditorMessage {
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::Text`].
+        CodeEditorMessage:Text => fn text(String), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::Search`].
+        CodeEditorMessage:Search => fn search(String), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::FindNext`].
+        CodeEditorMessage:FindNext => fn find_next(), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::GoToLine`].
+        CodeEditorMessage:GoToLine => fn go_to_line(usize), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::ShowLineNumbers`].
+        CodeEditorMessage:ShowLineNumbers => fn show_line_numbers(bool), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CodeEditorMessage::Editable`].
+        CodeEditorMessage:Editable => fn editable(bool), layout: false
+    );
+}
+
+const GUTTER_PADDING: f32 = 6.0;
+const TEXT_PADDING: f32 = 4.0;
+const CARET_WIDTH: f32 = 2.0;
+const WHEEL_LINES: f32 = 3.0;
+
+/// Code editor is a multi-line text editor, that is meant to be used to edit source code, such as
+/// shaders or scripts, and as an input field of in-game consoles.
+///
+/// ## Features
+///
+/// - The text is stored in a rope (see [`TextBuffer`]) and only visible lines are laid out and
+/// drawn, so the editor stays responsive with large files.
+/// - Syntax highlighting is done by a [`TokenProvider`], colors are defined by a [`SyntaxTheme`].
+/// [`KeywordTokenProvider`] can be used for simple C-like languages.
+/// - Line numbers, search (see [`CodeEditorMessage::Search`] and `F3` key), clipboard and
+/// auto-indentation of new lines.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     code_editor::{CodeEditorBuilder, KeywordTokenProvider},
+/// #     core::pool::Handle,
+/// #     widget::WidgetBuilder,
+/// #     UiNode, UserInterface,
+/// # };
+/// # use std::sync::Arc;
+/// fn create_shader_editor(ui: &mut UserInterface) -> Handle<UiNode> {
+///     CodeEditorBuilder::new(WidgetBuilder::new())
+///         .with_text("void main() {\n    gl_FragColor = vec4(1.0);\n}")
+///         .with_token_provider(Arc::new(KeywordTokenProvider::glsl()))
+///         .build(&mut ui.build_ctx())
+/// }
+/// ```
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct CodeEditor {
+    /// Base widget of the code editor.
+    pub widget: Widget,
+    /// Text of the editor.
+    #[reflect(hidden)]
+    pub buffer: TextBuffer,
+    /// Current position of the caret.
+    pub caret_position: Position,
+    /// Position where the current selection begins, the selection ends at the caret.
+    pub selection_anchor: Option<Position>,
+    /// Font of the text.
+    pub font: FontResource,
+    /// Size of the font.
+    pub font_size: f32,
+    /// Colors of the editor.
+    pub theme: SyntaxTheme,
+    /// Token provider that is used for syntax highlighting.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub token_provider: Option<Arc<dyn TokenProvider>>,
+    /// Current search query.
+    pub search_query: String,
+    /// `true` if line numbers are visible.
+    pub show_line_numbers: bool,
+    /// `true` if the text could be edited.
+    pub editable: bool,
+    /// Amount of spaces inserted by `Tab` key, also defines the width of tab chars.
+    pub tab_size: usize,
+    /// Position of the local "camera" (viewing rectangle) of the editor.
+    pub view_position: Vector2<f32>,
+    /// Whether the caret is visible or not.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub caret_visible: bool,
+    /// Internal blinking timer.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub blink_timer: f32,
+    /// `true` if the editor is focused.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub has_focus: bool,
+    /// `true` if the editor is in selection mode.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub selecting: bool,
+    /// `true` if the text was changed since the last commit.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub modified: bool,
+}
+
+crate::define_widget_deref!(CodeEditor);
+
+uuid_provider!(CodeEditor = "9d3fa531-b01b-4cf3-9e51-c2e4efac29e3");
+
+fn char_advance(font: &mut Font, c: char, font_size: f32, tab_size: usize) -> f32 {
+    if c == '\t' {
+        font.glyph_advance(' ', font_size) * tab_size as f32
+    } else {
+        font.glyph_advance(c, font_size)
+    }
+}
+
+fn text_width(font: &mut Font, chars: &[char], font_size: f32, tab_size: usize) -> f32 {
+    chars
+        .iter()
+        .map(|c| char_advance(font, *c, font_size, tab_size))
+        .sum()
+}
+
+/// Pushes glyphs and commits them in batches of the same color and atlas page.
+struct GlyphPainter<'a> {
+    font: &'a mut Font,
+    font_resource: &'a FontResource,
+    font_size: f32,
+    tab_size: usize,
+    clip_bounds: Rect<f32>,
+    color: Color,
+    page_index: usize,
+}
+
+impl GlyphPainter<'_> {
+    fn flush(&self, ctx: &mut DrawingContext) {
+        ctx.commit(
+            self.clip_bounds,
+            Brush::Solid(self.color),
+            CommandTexture::Font {
+                font: self.font_resource.clone(),
+                page_index: self.page_index,
+                height: self.font_size.into(),
+            },
+            None,
+        );
+    }
+
+    fn push_char(
+        &mut self,
+        ctx: &mut DrawingContext,
+        c: char,
+        color: Color,
+        position: Vector2<f32>,
+    ) -> f32 {
+        if c == '\t' {
+            return char_advance(self.font, c, self.font_size, self.tab_size);
+        }
+
+        let ascender = self.font.ascender(self.font_size);
+        let Some(glyph) = self.font.glyph(c, self.font_size) else {
+            return self.font_size;
+        };
+
+        let advance = glyph.advance;
+        let page_index = glyph.page_index;
+        let tex_coords = glyph.tex_coords;
+        let rect = Rect::new(
+            position.x + glyph.left.floor(),
+            position.y + ascender.floor() - glyph.top.floor() - glyph.bitmap_height as f32,
+            glyph.bitmap_width as f32,
+            glyph.bitmap_height as f32,
+        );
+
+        // Whitespace has no image.
+        if rect.w() > 0.0 && rect.h() > 0.0 {
+            if color != self.color || page_index != self.page_index {
+                self.flush(ctx);
+                self.color = color;
+                self.page_index = page_index;
+            }
+            ctx.push_rect_filled(&rect, Some(&tex_coords));
+        }
+
+        advance
+    }
+}
+
+impl CodeEditor {
+    /// Returns the whole text of the editor.
+    pub fn text(&self) -> String {
+        self.buffer.text()
+    }
+
+    /// Returns the selected range of positions (sorted), if any.
+    pub fn selection_range(&self) -> Option<Range<Position>> {
+        let anchor = self.selection_anchor?;
+        if anchor == self.caret_position {
+            None
+        } else {
+            Some(anchor.min(self.caret_position)..anchor.max(self.caret_position))
+        }
+    }
+
+    /// Returns the selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection_range()
+            .map(|range| self.buffer.text_range(range))
+    }
+
+    fn with_font<R>(&self, func: impl FnOnce(&mut Font) -> R) -> Option<R> {
+        let mut state = self.font.state();
+        state.data().map(func)
+    }
+
+    fn line_height(&self, font: &Font) -> f32 {
+        font.ascender(self.font_size) - font.descender(self.font_size)
+    }
+
+    fn gutter_width(&self, font: &mut Font) -> f32 {
+        if self.show_line_numbers {
+            let digits = self.buffer.line_count().to_string().len().max(2);
+            font.glyph_advance('0', self.font_size) * digits as f32 + 2.0 * GUTTER_PADDING
+        } else {
+            0.0
+        }
+    }
+
+    fn offset_to_x(&self, font: &mut Font, position: Position) -> f32 {
+        let line = self.buffer.line(position.line).chars().collect::<Vec<_>>();
+        let offset = position.offset.min(line.len());
+        text_width(font, &line[..offset], self.font_size, self.tab_size)
+    }
+
+    /// Converts the point in screen coordinates to the nearest position in the text.
+    pub fn screen_pos_to_text_pos(&self, screen_point: Vector2<f32>) -> Option<Position> {
+        let local = self
+            .visual_transform()
+            .try_inverse()?
+            .transform_point(&Point2::from(screen_point))
+            .coords;
+
+        self.with_font(|font| {
+            let line_height = self.line_height(font);
+            let text_x = self.gutter_width(font) + TEXT_PADDING - self.view_position.x;
+            let line = ((local.y + self.view_position.y) / line_height).max(0.0) as usize;
+            let line = line.min(self.buffer.line_count() - 1);
+
+            let mut x = text_x;
+            let mut offset = 0;
+            for c in self.buffer.line(line).chars() {
+                let advance = char_advance(font, c, self.font_size, self.tab_size);
+                if local.x < x + advance * 0.5 {
+                    break;
+                }
+                x += advance;
+                offset += 1;
+            }
+
+            Position { line, offset }
+        })
+    }
+
+    fn reset_blink(&mut self) {
+        self.caret_visible = true;
+        self.blink_timer = 0.0;
+    }
+
+    fn clamp_view_position(&mut self, line_height: f32) {
+        let content_height = self.buffer.line_count() as f32 * line_height;
+        let max_y = (content_height - self.actual_local_size().y).max(0.0);
+        self.view_position.y = self.view_position.y.clamp(0.0, max_y);
+        self.view_position.x = self.view_position.x.max(0.0);
+    }
+
+    /// Scrolls the view, so the caret is visible.
+    pub fn ensure_caret_visible(&mut self) {
+        let Some((caret_x, line_height, gutter_width)) = self.with_font(|font| {
+            (
+                self.offset_to_x(font, self.caret_position),
+                self.line_height(font),
+                self.gutter_width(font),
+            )
+        }) else {
+            return;
+        };
+
+        let size = self.actual_local_size();
+        let caret_y = self.caret_position.line as f32 * line_height;
+        if caret_y < self.view_position.y {
+            self.view_position.y = caret_y;
+        } else if caret_y + line_height > self.view_position.y + size.y {
+            self.view_position.y = caret_y + line_height - size.y;
+        }
+
+        let text_area_width = (size.x - gutter_width - TEXT_PADDING - CARET_WIDTH).max(0.0);
+        if caret_x < self.view_position.x {
+            self.view_position.x = caret_x;
+        } else if caret_x > self.view_position.x + text_area_width {
+            self.view_position.x = caret_x - text_area_width;
+        }
+
+        self.clamp_view_position(line_height);
+    }
+
+    fn move_caret(&mut self, position: Position, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.caret_position);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+        self.caret_position = self.buffer.clamp_position(position);
+        self.reset_blink();
+        self.ensure_caret_visible();
+    }
+
+    fn move_caret_x(&mut self, offset: isize, select: bool) {
+        let index = self.buffer.position_to_char_index(self.caret_position);
+        let index = index.saturating_add_signed(offset);
+        self.move_caret(self.buffer.char_index_to_position(index), select);
+    }
+
+    fn move_caret_y(&mut self, offset: isize, select: bool) {
+        let line = self.caret_position.line.saturating_add_signed(offset);
+        self.move_caret(
+            Position {
+                line: line.min(self.buffer.line_count() - 1),
+                offset: self.caret_position.offset,
+            },
+            select,
+        );
+    }
+
+    fn visible_line_count(&self) -> usize {
+        self.with_font(|font| self.line_height(font))
+            .map(|line_height| (self.actual_local_size().y / line_height) as usize)
+            .unwrap_or_default()
+            .max(1)
+    }
+
+    fn remove_selection(&mut self) -> bool {
+        if let Some(range) = self.selection_range() {
+            self.buffer.remove(range.clone());
+            self.selection_anchor = None;
+            self.caret_position = range.start;
+            self.modified = true;
+            true
+        } else {
+            self.selection_anchor = None;
+            false
+        }
+    }
+
+    fn insert_str(&mut self, text: &str) {
+        if !self.editable {
+            return;
+        }
+        self.remove_selection();
+        self.caret_position = self.buffer.insert(self.caret_position, text);
+        self.modified = true;
+        self.reset_blink();
+        self.ensure_caret_visible();
+    }
+
+    fn remove_char(&mut self, forward: bool) {
+        if !self.editable {
+            return;
+        }
+        if !self.remove_selection() {
+            let index = self.buffer.position_to_char_index(self.caret_position);
+            let range = if forward {
+                index..(index + 1).min(self.buffer.len_chars())
+            } else {
+                index.saturating_sub(1)..index
+            };
+            if !range.is_empty() {
+                let start = self.buffer.char_index_to_position(range.start);
+                let end = self.buffer.char_index_to_position(range.end);
+                self.buffer.remove(start..end);
+                self.caret_position = start;
+                self.modified = true;
+            }
+        }
+        self.reset_blink();
+        self.ensure_caret_visible();
+    }
+
+    fn insert_new_line(&mut self) {
+        // Keep indentation of the current line.
+        let indentation = self
+            .buffer
+            .line(self.caret_position.line)
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .take(self.caret_position.offset)
+            .collect::<String>();
+        self.insert_str(&format!("\n{indentation}"));
+    }
+
+    fn find_next(&mut self) {
+        if let Some(range) = self.buffer.find(&self.search_query, self.caret_position) {
+            self.move_caret(range.start, false);
+            self.move_caret(range.end, true);
+        }
+    }
+
+    fn commit_if_modified(&mut self, ui: &UserInterface) {
+        if self.modified {
+            self.modified = false;
+            ui.send_message(CodeEditorMessage::text(
+                self.handle,
+                MessageDirection::FromWidget,
+                self.buffer.text(),
+            ));
+        }
+    }
+}
+
+impl Control for CodeEditor {
+    fn measure_override(&self, _ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
+        // The editor takes all available space, the text is scrolled inside it.
+        let default_size = self.font_size * 10.0;
+        Vector2::new(
+            if available_size.x.is_finite() {
+                available_size.x
+            } else {
+                default_size
+            },
+            if available_size.y.is_finite() {
+                available_size.y
+            } else {
+                default_size
+            },
+        )
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.bounding_rect();
+        let clip_bounds = self.clip_bounds();
+        drawing_context.push_rect_filled(&bounds, None);
+        drawing_context.commit(
+            clip_bounds,
+            self.widget.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        let mut font_state = self.font.state();
+        let Some(font) = font_state.data() else {
+            return;
+        };
+
+        let line_height = self.line_height(font);
+        let gutter_width = self.gutter_width(font);
+        let text_x = bounds.x() + gutter_width + TEXT_PADDING - self.view_position.x;
+        let first_line = (self.view_position.y / line_height).max(0.0) as usize;
+        let last_line = (((self.view_position.y + bounds.h()) / line_height).ceil() as usize)
+            .min(self.buffer.line_count());
+        let line_y = |line: usize| bounds.y() + line as f32 * line_height - self.view_position.y;
+
+        let lines = (first_line..last_line)
+            .map(|line| (line, self.buffer.line(line)))
+            .collect::<Vec<_>>();
+        let line_chars = lines
+            .iter()
+            .map(|(_, line)| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // Selection.
+        if let Some(selection) = self.selection_range() {
+            for ((line, _), chars) in lines.iter().zip(line_chars.iter()) {
+                if *line < selection.start.line || *line > selection.end.line {
+                    continue;
+                }
+                let begin = if *line == selection.start.line {
+                    selection.start.offset.min(chars.len())
+                } else {
+                    0
+                };
+                let end = if *line == selection.end.line {
+                    selection.end.offset.min(chars.len())
+                } else {
+                    chars.len()
+                };
+                let x = text_width(font, &chars[..begin], self.font_size, self.tab_size);
+                let mut width = text_width(font, &chars[begin..end], self.font_size, self.tab_size);
+                if *line != selection.end.line {
+                    // Show that the line break is selected too.
+                    width += font.glyph_advance(' ', self.font_size);
+                }
+                drawing_context.push_rect_filled(
+                    &Rect::new(text_x + x, line_y(*line), width, line_height),
+                    None,
+                );
+            }
+            drawing_context.commit(
+                clip_bounds,
+                Brush::Solid(self.theme.selection),
+                CommandTexture::None,
+                None,
+            );
+        }
+
+        // Search matches.
+        for ((line, text), chars) in lines.iter().zip(line_chars.iter()) {
+            for range in buffer::find_in_line(text, &self.search_query) {
+                let x = text_width(font, &chars[..range.start], self.font_size, self.tab_size);
+                let width = text_width(font, &chars[range], self.font_size, self.tab_size);
+                drawing_context.push_rect_filled(
+                    &Rect::new(text_x + x, line_y(*line), width, line_height),
+                    None,
+                );
+            }
+        }
+        drawing_context.commit(
+            clip_bounds,
+            Brush::Solid(self.theme.search_match),
+            CommandTexture::None,
+            None,
+        );
+
+        let caret_x = if self.caret_visible {
+            Some(text_x + self.offset_to_x(font, self.caret_position))
+        } else {
+            None
+        };
+
+        let mut painter = GlyphPainter {
+            font,
+            font_resource: &self.font,
+            font_size: self.font_size,
+            tab_size: self.tab_size,
+            clip_bounds,
+            color: self.theme.text,
+            page_index: 0,
+        };
+
+        // Text.
+        let mut tokens = Vec::new();
+        for ((line, text), chars) in lines.iter().zip(line_chars.iter()) {
+            tokens.clear();
+            if let Some(token_provider) = self.token_provider.as_ref() {
+                token_provider.tokenize_line(text, &mut tokens);
+            }
+
+            let mut line_tokens = tokens.iter().peekable();
+            let mut x = text_x;
+            for (i, c) in chars.iter().enumerate() {
+                if x > bounds.x() + bounds.w() {
+                    break;
+                }
+
+                while line_tokens.next_if(|token| token.range.end <= i).is_some() {}
+                let kind = line_tokens
+                    .peek()
+                    .filter(|token| token.range.contains(&i))
+                    .map_or(TokenKind::Text, |token| token.kind);
+
+                x += painter.push_char(
+                    drawing_context,
+                    *c,
+                    self.theme.color(kind),
+                    Vector2::new(x, line_y(*line)),
+                );
+            }
+        }
+        painter.flush(drawing_context);
+
+        // Line numbers are drawn on top of the text, so the scrolled text is hidden behind them.
+        if self.show_line_numbers {
+            drawing_context.push_rect_filled(
+                &Rect::new(bounds.x(), bounds.y(), gutter_width, bounds.h()),
+                None,
+            );
+            drawing_context.commit(
+                clip_bounds,
+                Brush::Solid(self.theme.gutter),
+                CommandTexture::None,
+                None,
+            );
+
+            for (line, _) in lines.iter() {
+                let number = (line + 1).to_string().chars().collect::<Vec<_>>();
+                let width = text_width(painter.font, &number, self.font_size, self.tab_size);
+                let mut x = bounds.x() + gutter_width - GUTTER_PADDING - width;
+                for c in number {
+                    x += painter.push_char(
+                        drawing_context,
+                        c,
+                        self.theme.line_number,
+                        Vector2::new(x, line_y(*line)),
+                    );
+                }
+            }
+            painter.flush(drawing_context);
+        }
+
+        if let Some(caret_x) = caret_x {
+            if caret_x >= bounds.x() + gutter_width {
+                drawing_context.push_rect_filled(
+                    &Rect::new(
+                        caret_x,
+                        line_y(self.caret_position.line),
+                        CARET_WIDTH,
+                        line_height,
+                    ),
+                    None,
+                );
+                drawing_context.commit(
+                    clip_bounds,
+                    Brush::Solid(self.theme.caret),
+                    CommandTexture::None,
+                    None,
+                );
+            }
+        }
+    }
+
+    fn update(&mut self, dt: f32, _ui: &mut UserInterface) {
+        if self.has_focus {
+            self.blink_timer += dt;
+            if self.blink_timer >= 0.5 {
+                self.blink_timer = 0.0;
+                self.caret_visible = !self.caret_visible;
+            }
+        } else {
+            self.caret_visible = false;
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if message.destination() != self.handle() {
+            return;
+        }
+
+        if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::Text(text)
+                    if !ui.keyboard_modifiers().control && !ui.keyboard_modifiers().alt =>
+                {
+                    let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+                    if !text.is_empty() {
+                        self.insert_str(&text);
+                    }
+                }
+                WidgetMessage::KeyDown(code) => {
+                    let modifiers = ui.keyboard_modifiers();
+                    let select = modifiers.shift;
+                    match code {
+                        KeyCode::ArrowLeft => self.move_caret_x(-1, select),
+                        KeyCode::ArrowRight => self.move_caret_x(1, select),
+                        KeyCode::ArrowUp => self.move_caret_y(-1, select),
+                        KeyCode::ArrowDown => self.move_caret_y(1, select),
+                        KeyCode::PageUp => {
+                            self.move_caret_y(-(self.visible_line_count() as isize), select)
+                        }
+                        KeyCode::PageDown => {
+                            self.move_caret_y(self.visible_line_count() as isize, select)
+                        }
+                        KeyCode::Home => {
+                            let position = if modifiers.control {
+                                Position::default()
+                            } else {
+                                Position {
+                                    line: self.caret_position.line,
+                                    offset: 0,
+                                }
+                            };
+                            self.move_caret(position, select);
+                        }
+                        KeyCode::End => {
+                            let position = if modifiers.control {
+                                self.buffer.end_position()
+                            } else {
+                                Position {
+                                    line: self.caret_position.line,
+                                    offset: self.buffer.line_len(self.caret_position.line),
+                                }
+                            };
+                            self.move_caret(position, select);
+                        }
+                        KeyCode::Backspace => self.remove_char(false),
+                        KeyCode::Delete => self.remove_char(true),
+                        KeyCode::Enter | KeyCode::NumpadEnter if self.editable => {
+                            self.insert_new_line()
+                        }
+                        KeyCode::Tab if self.editable => {
+                            self.insert_str(&" ".repeat(self.tab_size));
+                        }
+                        KeyCode::F3 => self.find_next(),
+                        KeyCode::KeyA if modifiers.control => {
+                            self.move_caret(Position::default(), false);
+                            self.move_caret(self.buffer.end_position(), true);
+                        }
+                        KeyCode::KeyC | KeyCode::KeyX if modifiers.control => {
+                            if let Some(text) = self.selected_text() {
+                                if let Some(mut clipboard) = ui.clipboard_mut() {
+                                    let _ = clipboard.set_contents(text);
+                                }
+                                if *code == KeyCode::KeyX {
+                                    self.remove_char(false);
+                                }
+                            }
+                        }
+                        KeyCode::KeyV if modifiers.control => {
+                            let content = ui
+                                .clipboard_mut()
+                                .and_then(|mut clipboard| clipboard.get_contents().ok());
+                            if let Some(content) = content {
+                                self.insert_str(&content.replace("\r\n", "\n"));
+                            }
+                        }
+                        _ => (),
+                    }
+
+                    // Code editor "eats" all input, the same as text box.
+                    message.set_handled(true);
+                }
+                WidgetMessage::MouseDown { pos, button } => {
+                    if *button == MouseButton::Left {
+                        if let Some(position) = self.screen_pos_to_text_pos(*pos) {
+                            self.move_caret(position, ui.keyboard_modifiers().shift);
+                        }
+                        self.selecting = true;
+                        self.has_focus = true;
+                        ui.capture_mouse(self.handle());
+                    }
+                }
+                WidgetMessage::MouseMove { pos, .. } => {
+                    if self.selecting {
+                        if let Some(position) = self.screen_pos_to_text_pos(*pos) {
+                            self.move_caret(position, true);
+                        }
+                    }
+                }
+                WidgetMessage::MouseUp { .. } => {
+                    if self.selecting {
+                        self.selecting = false;
+                        ui.release_mouse_capture();
+                    }
+                }
+                WidgetMessage::MouseWheel { amount, .. } => {
+                    if let Some(line_height) = self.with_font(|font| self.line_height(font)) {
+                        self.view_position.y -= amount * line_height * WHEEL_LINES;
+                        self.clamp_view_position(line_height);
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::Focus => {
+                    if message.direction() == MessageDirection::FromWidget {
+                        self.has_focus = true;
+                        self.reset_blink();
+                    }
+                }
+                WidgetMessage::Unfocus => {
+                    if message.direction() == MessageDirection::FromWidget {
+                        self.has_focus = false;
+                        self.commit_if_modified(ui);
+                    }
+                }
+                _ => (),
+            }
+        } else if let Some(msg) = message.data::<CodeEditorMessage>() {
+            if message.direction() == MessageDirection::ToWidget {
+                match msg {
+                    CodeEditorMessage::Text(text) => {
+                        if self.buffer.text() != *text {
+                            self.buffer = TextBuffer::new(text);
+                            self.selection_anchor = None;
+                            self.caret_position = self.buffer.clamp_position(self.caret_position);
+                            self.modified = false;
+                            self.ensure_caret_visible();
+                        }
+                    }
+                    CodeEditorMessage::Search(query) => {
+                        self.search_query.clone_from(query);
+                        // Start from the beginning of the selection, so the selected match is
+                        // kept selected while the query is being typed.
+                        if let Some(selection) = self.selection_range() {
+                            self.caret_position = selection.start;
+                        }
+                        self.find_next();
+                    }
+                    CodeEditorMessage::FindNext => self.find_next(),
+                    &CodeEditorMessage::GoToLine(line) => {
+                        self.move_caret(Position { line, offset: 0 }, false);
+                    }
+                    &CodeEditorMessage::ShowLineNumbers(show) => {
+                        self.show_line_numbers = show;
+                    }
+                    &CodeEditorMessage::Editable(editable) => {
+                        self.editable = editable;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Code editor builder creates [`CodeEditor`] instances and adds them to the user interface.
+pub struct CodeEditorBuilder {
+    widget_builder: WidgetBuilder,
+    text: String,
+    font: Option<FontResource>,
+    font_size: f32,
+    theme: SyntaxTheme,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    show_line_numbers: bool,
+    editable: bool,
+    tab_size: usize,
+}
+
+impl CodeEditorBuilder {
+    /// Creates new code editor builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            text: Default::default(),
+            font: None,
+            font_size: 14.0,
+            theme: Default::default(),
+            token_provider: None,
+            show_line_numbers: true,
+            editable: true,
+            tab_size: 4,
+        }
+    }
+
+    /// Sets the initial text of the editor.
+    pub fn with_text<S: AsRef<str>>(mut self, text: S) -> Self {
+        self.text = text.as_ref().to_owned();
+        self
+    }
+
+    /// Sets the font of the editor. Monospace fonts are recommended.
+    pub fn with_font(mut self, font: FontResource) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the size of the font.
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Sets the colors of the editor.
+    pub fn with_theme(mut self, theme: SyntaxTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets the token provider that will be used for syntax highlighting.
+    pub fn with_token_provider(mut self, token_provider: Arc<dyn TokenProvider>) -> Self {
+        self.token_provider = Some(token_provider);
+        self
+    }
+
+    /// Shows or hides line numbers. Default is `true`.
+    pub fn with_line_numbers(mut self, show: bool) -> Self {
+        self.show_line_numbers = show;
+        self
+    }
+
+    /// Enables or disables editing. Default is `true`.
+    pub fn with_editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Sets the amount of spaces inserted by `Tab` key. Default is `4`.
+    pub fn with_tab_size(mut self, tab_size: usize) -> Self {
+        self.tab_size = tab_size;
+        self
+    }
+
+    /// Finishes code editor building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let code_editor = CodeEditor {
+            widget: self
+                .widget_builder
+                .with_accepts_input(true)
+                .with_need_update(true)
+                .with_cursor(Some(CursorIcon::Text))
+                .build(),
+            buffer: TextBuffer::new(&self.text),
+            caret_position: Default::default(),
+            selection_anchor: None,
+            font: self.font.unwrap_or_else(|| ctx.default_font()),
+            font_size: self.font_size,
+            theme: self.theme,
+            token_provider: self.token_provider,
+            search_query: Default::default(),
+            show_line_numbers: self.show_line_numbers,
+            editable: self.editable,
+            tab_size: self.tab_size,
+            view_position: Default::default(),
+            caret_visible: false,
+            blink_timer: 0.0,
+            has_focus: false,
+            selecting: false,
+            modified: false,
+        };
+
+        ctx.add_node(UiNode::new(code_editor))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        code_editor::{CodeEditor, CodeEditorBuilder, CodeEditorMessage},
+        core::algebra::Vector2,
+        formatted_text::Position,
+        message::MessageDirection,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    #[test]
+    fn test_code_editor_search() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let editor = CodeEditorBuilder::new(WidgetBuilder::new())
+            .with_text("let a = 1;\nlet b = a;")
+            .build(&mut ui.build_ctx());
+
+        ui.send_message(CodeEditorMessage::search(
+            editor,
+            MessageDirection::ToWidget,
+            "let".to_string(),
+        ));
+        ui.send_message(CodeEditorMessage::find_next(
+            editor,
+            MessageDirection::ToWidget,
+        ));
+        while ui.poll_message().is_some() {}
+
+        let editor_ref = ui.node(editor).cast::<CodeEditor>().unwrap();
+        assert_eq!(editor_ref.selected_text().as_deref(), Some("let"));
+        assert_eq!(editor_ref.caret_position, Position { line: 1, offset: 3 });
+    }
+}
@@ -0,0 +1,559 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Immediate-mode helpers for in-game debug tweak menus. See [`DebugMenu`] docs for more info and
+//! usage examples.
+
+#![warn(missing_docs)]
+
+use crate::{
+    button::{ButtonBuilder, ButtonMessage},
+    check_box::{CheckBoxBuilder, CheckBoxMessage},
+    core::{algebra::Vector2, pool::Handle},
+    grid::{Column, GridBuilder, Row},
+    message::{MessageDirection, UiMessage},
+    scroll_bar::{ScrollBarBuilder, ScrollBarMessage},
+    scroll_viewer::ScrollViewerBuilder,
+    stack_panel::StackPanelBuilder,
+    text::{TextBuilder, TextMessage},
+    widget::{WidgetBuilder, WidgetMessage},
+    window::{WindowBuilder, WindowTitle},
+    Thickness, UiNode, UserInterface, VerticalAlignment,
+};
+use fyrox_graph::BaseSceneGraph;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq)]
+enum DebugMenuItemKind {
+    Slider { shown: f32 },
+    CheckBox { shown: bool },
+    Button,
+    Label { shown: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingChange {
+    Value(f32),
+    Checked(bool),
+    Click,
+}
+
+#[derive(Debug)]
+struct DebugMenuItem {
+    id: String,
+    kind: DebugMenuItemKind,
+    container: Handle<UiNode>,
+    widget: Handle<UiNode>,
+    pending: Option<PendingChange>,
+    last_frame: u64,
+}
+
+/// Debug menu is a lightweight immediate-mode layer over the retained-mode widgets. It allows you to
+/// declare sliders, check boxes, buttons and labels in code every frame, which is very handy for
+/// rapid gameplay tuning. The menu creates, updates and destroys its widgets automatically: an item
+/// that was not declared during a frame will be removed at the end of it.
+///
+/// Every item is identified by its label, so labels must be unique within a single menu.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_ui::{debug_menu::DebugMenu, message::UiMessage, UserInterface};
+/// struct Game {
+///     debug_menu: DebugMenu,
+///     player_speed: f32,
+///     god_mode: bool,
+/// }
+///
+/// impl Game {
+///     fn new(ui: &mut UserInterface) -> Self {
+///         Self {
+///             debug_menu: DebugMenu::new("Debug", ui),
+///             player_speed: 5.0,
+///             god_mode: false,
+///         }
+///     }
+///
+///     // Must be called for every message of the user interface.
+///     fn on_ui_message(&mut self, message: &UiMessage) {
+///         self.debug_menu.handle_ui_message(message);
+///     }
+///
+///     // Must be called every frame.
+///     fn update(&mut self, ui: &mut UserInterface) {
+///         let menu = &mut self.debug_menu;
+///         menu.begin();
+///         menu.slider(ui, "Player Speed", &mut self.player_speed, 0.0..=20.0);
+///         menu.checkbox(ui, "God Mode", &mut self.god_mode);
+///         if menu.button(ui, "Respawn") {
+///             // Respawn the player.
+///         }
+///         menu.label(ui, "Speed", &format!("{:.2}", self.player_speed));
+///         menu.end(ui);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DebugMenu {
+    window: Handle<UiNode>,
+    panel: Handle<UiNode>,
+    items: Vec<DebugMenuItem>,
+    frame: u64,
+    // Index of the next item declared in the current frame.
+    cursor: usize,
+}
+
+impl DebugMenu {
+    /// Creates a new debug menu window with the given title.
+    pub fn new(title: &str, ui: &mut UserInterface) -> Self {
+        let ctx = &mut ui.build_ctx();
+        let panel = StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+        let window = WindowBuilder::new(
+            WidgetBuilder::new()
+                .with_width(300.0)
+                .with_height(400.0)
+                .with_desired_position(Vector2::new(10.0, 10.0)),
+        )
+        .with_title(WindowTitle::text(title))
+        .can_close(false)
+        .with_content(
+            ScrollViewerBuilder::new(WidgetBuilder::new())
+                .with_content(panel)
+                .build(ctx),
+        )
+        .build(ctx);
+
+        Self {
+            window,
+            panel,
+            items: Default::default(),
+            frame: 0,
+            cursor: 0,
+        }
+    }
+
+    /// Returns a handle of the window of the menu.
+    pub fn window(&self) -> Handle<UiNode> {
+        self.window
+    }
+
+    /// Starts a new frame. Must be called before declaring any items.
+    pub fn begin(&mut self) {
+        self.frame += 1;
+        self.cursor = 0;
+    }
+
+    /// Finishes the current frame and removes every item, that was not declared in it.
+    pub fn end(&mut self, ui: &UserInterface) {
+        let frame = self.frame;
+        self.items.retain(|item| {
+            let alive = item.last_frame == frame;
+            if !alive {
+                ui.send_message(WidgetMessage::remove(
+                    item.container,
+                    MessageDirection::ToWidget,
+                ));
+            }
+            alive
+        });
+    }
+
+    /// Destroys the menu window with all its items.
+    pub fn destroy(self, ui: &UserInterface) {
+        ui.send_message(WidgetMessage::remove(
+            self.window,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    /// Collects changes made by a user. Must be called for every message of the user interface.
+    pub fn handle_ui_message(&mut self, message: &UiMessage) {
+        if message.direction() != MessageDirection::FromWidget {
+            return;
+        }
+
+        let Some(item) = self
+            .items
+            .iter_mut()
+            .find(|item| item.widget == message.destination())
+        else {
+            return;
+        };
+
+        if let Some(ScrollBarMessage::Value(value)) = message.data() {
+            item.pending = Some(PendingChange::Value(*value));
+        } else if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            item.pending = Some(PendingChange::Checked(*value));
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            item.pending = Some(PendingChange::Click);
+        }
+    }
+
+    fn item_index(
+        &mut self,
+        ui: &mut UserInterface,
+        id: &str,
+        make_item: impl FnOnce(
+            &mut UserInterface,
+        ) -> (DebugMenuItemKind, Handle<UiNode>, Handle<UiNode>),
+    ) -> usize {
+        let index = match self.items.iter().position(|item| item.id == id) {
+            Some(index) => index,
+            None => {
+                let (kind, container, widget) = make_item(ui);
+                ui.link_nodes(container, self.panel, false);
+                self.items.push(DebugMenuItem {
+                    id: id.to_string(),
+                    kind,
+                    container,
+                    widget,
+                    pending: None,
+                    last_frame: 0,
+                });
+                self.items.len() - 1
+            }
+        };
+
+        // Keep the items in the order of declaration, so an item that is shown again (or for the
+        // first time) appears at its place instead of the end of the menu. Items before the cursor
+        // were already declared in this frame, an index before the cursor means a duplicate id.
+        let index = if index > self.cursor {
+            let item = self.items.remove(index);
+            self.items.insert(self.cursor, item);
+            self.sync_widgets_order(ui);
+            self.cursor
+        } else {
+            index
+        };
+        self.cursor = self.cursor.max(index + 1);
+
+        self.items[index].last_frame = self.frame;
+        index
+    }
+
+    fn sync_widgets_order(&self, ui: &mut UserInterface) {
+        let mut children = self
+            .items
+            .iter()
+            .map(|item| item.container)
+            .collect::<Vec<_>>();
+        // Widgets of the removed items stay until their removal messages are processed.
+        let removed = ui
+            .node(self.panel)
+            .children()
+            .iter()
+            .filter(|child| !children.contains(child))
+            .cloned()
+            .collect::<Vec<_>>();
+        children.extend(removed);
+        ui.node_mut(self.panel).set_children(children);
+    }
+
+    /// Declares a slider that edits the given value within the given range. Returns `true` if the
+    /// value was changed by a user.
+    pub fn slider(
+        &mut self,
+        ui: &mut UserInterface,
+        label: &str,
+        value: &mut f32,
+        range: RangeInclusive<f32>,
+    ) -> bool {
+        let initial = *value;
+        let index = self.item_index(ui, label, |ui| {
+            let ctx = &mut ui.build_ctx();
+            let slider = ScrollBarBuilder::new(WidgetBuilder::new().on_column(1).with_height(20.0))
+                .with_min(*range.start())
+                .with_max(*range.end())
+                .with_step((range.end() - range.start()) / 100.0)
+                .with_value(initial)
+                .show_value(true)
+                .build(ctx);
+            let container = make_labelled_row(label, slider, ui);
+            (
+                DebugMenuItemKind::Slider { shown: initial },
+                container,
+                slider,
+            )
+        });
+
+        let item = &mut self.items[index];
+        let DebugMenuItemKind::Slider { ref mut shown } = item.kind else {
+            return false;
+        };
+
+        if let Some(PendingChange::Value(new_value)) = item.pending.take() {
+            *shown = new_value;
+            if new_value != *value {
+                *value = new_value;
+                return true;
+            }
+        } else if *shown != *value {
+            *shown = *value;
+            ui.send_message(ScrollBarMessage::value(
+                item.widget,
+                MessageDirection::ToWidget,
+                *value,
+            ));
+        }
+
+        false
+    }
+
+    /// Declares a check box that edits the given flag. Returns `true` if the flag was changed by
+    /// a user.
+    pub fn checkbox(&mut self, ui: &mut UserInterface, label: &str, value: &mut bool) -> bool {
+        let initial = *value;
+        let index = self.item_index(ui, label, |ui| {
+            let ctx = &mut ui.build_ctx();
+            let check_box =
+                CheckBoxBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(1.0)))
+                    .checked(Some(initial))
+                    .with_content(
+                        TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::left(2.0)))
+                            .with_vertical_text_alignment(VerticalAlignment::Center)
+                            .with_text(label)
+                            .build(ctx),
+                    )
+                    .build(ctx);
+            (
+                DebugMenuItemKind::CheckBox { shown: initial },
+                check_box,
+                check_box,
+            )
+        });
+
+        let item = &mut self.items[index];
+        let DebugMenuItemKind::CheckBox { ref mut shown } = item.kind else {
+            return false;
+        };
+
+        if let Some(PendingChange::Checked(new_value)) = item.pending.take() {
+            *shown = new_value;
+            if new_value != *value {
+                *value = new_value;
+                return true;
+            }
+        } else if *shown != *value {
+            *shown = *value;
+            ui.send_message(CheckBoxMessage::checked(
+                item.widget,
+                MessageDirection::ToWidget,
+                Some(*value),
+            ));
+        }
+
+        false
+    }
+
+    /// Declares a button. Returns `true` if the button was clicked since the previous frame.
+    pub fn button(&mut self, ui: &mut UserInterface, label: &str) -> bool {
+        let index = self.item_index(ui, label, |ui| {
+            let button = ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .with_height(24.0)
+                    .with_margin(Thickness::uniform(1.0)),
+            )
+            .with_text(label)
+            .build(&mut ui.build_ctx());
+            (DebugMenuItemKind::Button, button, button)
+        });
+
+        matches!(self.items[index].pending.take(), Some(PendingChange::Click))
+    }
+
+    /// Declares a read-only label with the given text. The id is used to identify the label.
+    pub fn label(&mut self, ui: &mut UserInterface, id: &str, text: &str) {
+        let index = self.item_index(ui, id, |ui| {
+            let ctx = &mut ui.build_ctx();
+            let text_widget = TextBuilder::new(WidgetBuilder::new().on_column(1))
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .with_text(text)
+                .build(ctx);
+            let container = make_labelled_row(id, text_widget, ui);
+            let shown = text.to_string();
+            (DebugMenuItemKind::Label { shown }, container, text_widget)
+        });
+
+        let item = &mut self.items[index];
+        if let DebugMenuItemKind::Label { ref mut shown } = item.kind {
+            if shown != text {
+                *shown = text.to_string();
+                ui.send_message(TextMessage::text(
+                    item.widget,
+                    MessageDirection::ToWidget,
+                    text.to_string(),
+                ));
+            }
+        }
+    }
+}
+
+fn make_labelled_row(
+    label: &str,
+    content: Handle<UiNode>,
+    ui: &mut UserInterface,
+) -> Handle<UiNode> {
+    let ctx = &mut ui.build_ctx();
+    GridBuilder::new(
+        WidgetBuilder::new()
+            .with_margin(Thickness::uniform(1.0))
+            .with_child(
+                TextBuilder::new(WidgetBuilder::new().on_column(0))
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .with_text(label)
+                    .build(ctx),
+            )
+            .with_child(content),
+    )
+    .add_row(Row::auto())
+    .add_column(Column::strict(110.0))
+    .add_column(Column::stretch())
+    .build(ctx)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        button::ButtonMessage, core::algebra::Vector2, debug_menu::DebugMenu,
+        message::MessageDirection, scroll_bar::ScrollBarMessage, text::Text, UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    fn poll(ui: &mut UserInterface, menu: &mut DebugMenu) {
+        while let Some(message) = ui.poll_message() {
+            menu.handle_ui_message(&message);
+        }
+    }
+
+    #[test]
+    fn test_debug_menu() {
+        let mut ui = UserInterface::new(Vector2::new(400.0, 400.0));
+        let mut menu = DebugMenu::new("Debug", &mut ui);
+
+        let mut speed = 1.0;
+        let mut god_mode = false;
+
+        menu.begin();
+        assert!(!menu.slider(&mut ui, "Speed", &mut speed, 0.0..=10.0));
+        assert!(!menu.checkbox(&mut ui, "God Mode", &mut god_mode));
+        assert!(!menu.button(&mut ui, "Respawn"));
+        menu.end(&ui);
+        poll(&mut ui, &mut menu);
+        assert_eq!(menu.items.len(), 3);
+
+        // Emulate user input.
+        let slider = menu.items[0].widget;
+        let button = menu.items[2].widget;
+        ui.send_message(ScrollBarMessage::value(
+            slider,
+            MessageDirection::FromWidget,
+            5.0,
+        ));
+        ui.send_message(ButtonMessage::click(button, MessageDirection::FromWidget));
+        poll(&mut ui, &mut menu);
+
+        menu.begin();
+        assert!(menu.slider(&mut ui, "Speed", &mut speed, 0.0..=10.0));
+        assert_eq!(speed, 5.0);
+        assert!(menu.button(&mut ui, "Respawn"));
+        menu.end(&ui);
+        poll(&mut ui, &mut menu);
+
+        // The check box was not declared in the last frame, so it must be removed.
+        assert_eq!(menu.items.len(), 2);
+        assert_eq!(ui.node(menu.panel).children().len(), 2);
+
+        // Changes made in code must not be reported as user changes.
+        speed = 2.0;
+        menu.begin();
+        assert!(!menu.slider(&mut ui, "Speed", &mut speed, 0.0..=10.0));
+        assert!(!menu.button(&mut ui, "Respawn"));
+        menu.end(&ui);
+        poll(&mut ui, &mut menu);
+
+        menu.begin();
+        assert!(!menu.slider(&mut ui, "Speed", &mut speed, 0.0..=10.0));
+        assert_eq!(speed, 2.0);
+        menu.end(&ui);
+    }
+
+    #[test]
+    fn test_debug_menu_label() {
+        let mut ui = UserInterface::new(Vector2::new(400.0, 400.0));
+        let mut menu = DebugMenu::new("Debug", &mut ui);
+
+        menu.begin();
+        menu.label(&mut ui, "FPS", "60");
+        menu.end(&ui);
+        poll(&mut ui, &mut menu);
+
+        let text = |ui: &UserInterface, menu: &DebugMenu| {
+            ui.node(menu.items[0].widget)
+                .query_component::<Text>()
+                .unwrap()
+                .text()
+        };
+        assert_eq!(text(&ui, &menu), "60");
+
+        menu.begin();
+        menu.label(&mut ui, "FPS", "30");
+        menu.end(&ui);
+        poll(&mut ui, &mut menu);
+        assert_eq!(text(&ui, &menu), "30");
+    }
+
+    #[test]
+    fn test_debug_menu_declaration_order() {
+        let mut ui = UserInterface::new(Vector2::new(400.0, 400.0));
+        let mut menu = DebugMenu::new("Debug", &mut ui);
+
+        let declare = |ui: &mut UserInterface, menu: &mut DebugMenu, show_b: bool| {
+            menu.begin();
+            menu.button(ui, "A");
+            if show_b {
+                menu.button(ui, "B");
+            }
+            menu.button(ui, "C");
+            menu.end(ui);
+            poll(ui, menu);
+        };
+
+        let assert_order = |ui: &UserInterface, menu: &DebugMenu, expected: &[&str]| {
+            let ids = menu.items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>();
+            assert_eq!(ids, expected);
+            let containers = menu.items.iter().map(|i| i.container).collect::<Vec<_>>();
+            assert_eq!(ui.node(menu.panel).children(), containers.as_slice());
+        };
+
+        declare(&mut ui, &mut menu, false);
+        assert_order(&ui, &menu, &["A", "C"]);
+
+        // An item that is shown for the first time must appear at its place.
+        declare(&mut ui, &mut menu, true);
+        assert_order(&ui, &menu, &["A", "B", "C"]);
+
+        declare(&mut ui, &mut menu, false);
+        assert_order(&ui, &menu, &["A", "C"]);
+
+        // The same for an item that is shown again.
+        declare(&mut ui, &mut menu, true);
+        assert_order(&ui, &menu, &["A", "B", "C"]);
+    }
+}
@@ -55,6 +55,10 @@ pub enum TileContentDescriptor {
     #[default]
     Empty,
     Window(ImmutableString),
+    MultiWindow {
+        index: usize,
+        windows: Vec<ImmutableString>,
+    },
     SplitTiles(SplitTilesDescriptor),
 }
 
@@ -67,6 +71,13 @@ impl TileContentDescriptor {
                     .map(|w| w.name.clone())
                     .unwrap_or_default(),
             ),
+            TileContent::MultiWindow { index, windows } => Self::MultiWindow {
+                index: *index,
+                windows: windows
+                    .iter()
+                    .map(|w| ui.try_get(*w).map(|w| w.name.clone()).unwrap_or_default())
+                    .collect(),
+            },
             TileContent::VerticalTiles { splitter, tiles } => {
                 Self::SplitTiles(SplitTilesDescriptor {
                     splitter: *splitter,
@@ -85,6 +96,43 @@ impl TileContentDescriptor {
     }
 }
 
+fn find_and_open_window(
+    window_name: &ImmutableString,
+    ui: &mut UserInterface,
+    windows: &[Handle<UiNode>],
+) -> Handle<UiNode> {
+    if window_name.is_empty() {
+        Log::warn(
+            "Window name is empty, wrong widget will be used as a \
+        tile content. Assign a unique name to the window used in a docking \
+        manager!",
+        );
+    }
+
+    let mut window_handle = ui.find_handle(ui.root(), &mut |n| n.name == *window_name);
+
+    if window_handle.is_none() {
+        for other_window_handle in windows.iter().cloned() {
+            if let Some(window_node) = ui.try_get(other_window_handle) {
+                if &window_node.name == window_name {
+                    window_handle = other_window_handle;
+                }
+            }
+        }
+    }
+
+    if window_handle.is_some() {
+        ui.send_message(WindowMessage::open(
+            window_handle,
+            MessageDirection::ToWidget,
+            false,
+            true,
+        ));
+    }
+
+    window_handle
+}
+
 #[derive(Debug, PartialEq, Clone, Visit, Default, Serialize, Deserialize)]
 pub struct TileDescriptor {
     pub content: TileContentDescriptor,
@@ -116,40 +164,33 @@ impl TileDescriptor {
             .with_content(match &self.content {
                 TileContentDescriptor::Empty => TileContent::Empty,
                 TileContentDescriptor::Window(window_name) => {
-                    if window_name.is_empty() {
-                        Log::warn(
-                            "Window name is empty, wrong widget will be used as a \
-                        tile content. Assign a unique name to the window used in a docking \
-                        manager!",
-                        );
-                    }
-
-                    let mut window_handle =
-                        ui.find_handle(ui.root(), &mut |n| n.name == *window_name);
-
-                    if window_handle.is_none() {
-                        for other_window_handle in windows.iter().cloned() {
-                            if let Some(window_node) = ui.try_get(other_window_handle) {
-                                if &window_node.name == window_name {
-                                    window_handle = other_window_handle;
-                                }
-                            }
-                        }
-                    }
+                    let window_handle = find_and_open_window(window_name, ui, windows);
 
                     if window_handle.is_some() {
-                        ui.send_message(WindowMessage::open(
-                            window_handle,
-                            MessageDirection::ToWidget,
-                            false,
-                            true,
-                        ));
-
                         TileContent::Window(window_handle)
                     } else {
                         TileContent::Empty
                     }
                 }
+                TileContentDescriptor::MultiWindow {
+                    index,
+                    windows: window_names,
+                } => {
+                    let tab_windows = window_names
+                        .iter()
+                        .map(|name| find_and_open_window(name, ui, windows))
+                        .filter(|handle| handle.is_some())
+                        .collect::<Vec<_>>();
+
+                    match tab_windows.len() {
+                        0 => TileContent::Empty,
+                        1 => TileContent::Window(tab_windows[0]),
+                        len => TileContent::MultiWindow {
+                            index: (*index).min(len - 1),
+                            windows: tab_windows,
+                        },
+                    }
+                }
                 TileContentDescriptor::SplitTiles(split_tiles) => match split_tiles.orientation {
                     Orientation::Vertical => TileContent::VerticalTiles {
                         splitter: split_tiles.splitter,
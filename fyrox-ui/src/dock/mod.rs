@@ -91,6 +91,17 @@ impl Control for DockingManager {
                                         windows.push(window);
                                     }
                                 }
+                                TileContent::MultiWindow {
+                                    windows: ref tab_windows,
+                                    ..
+                                } => {
+                                    windows.extend(
+                                        tab_windows
+                                            .iter()
+                                            .filter(|w| ui.try_get(**w).is_some())
+                                            .cloned(),
+                                    );
+                                }
                                 TileContent::VerticalTiles { tiles, .. }
                                 | TileContent::HorizontalTiles { tiles, .. } => {
                                     stack.extend_from_slice(&tiles);
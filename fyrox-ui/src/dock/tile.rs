@@ -21,15 +21,20 @@
 use crate::{
     border::BorderBuilder,
     brush::Brush,
+    button::{Button, ButtonBuilder, ButtonMessage},
     core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
     core::{reflect::prelude::*, type_traits::prelude::*, visitor::prelude::*},
+    decorator::{DecoratorBuilder, DecoratorMessage},
     define_constructor,
     dock::DockingManager,
     grid::{Column, GridBuilder, Row},
     message::{CursorIcon, MessageDirection, UiMessage},
+    stack_panel::StackPanelBuilder,
+    text::Text,
     widget::{Widget, WidgetBuilder, WidgetMessage},
     window::{Window, WindowMessage},
-    BuildContext, Control, Thickness, UiNode, UserInterface,
+    BuildContext, Control, Orientation, Thickness, UiNode, UserInterface, BRUSH_DARKER,
+    BRUSH_LIGHT, BRUSH_LIGHTER, BRUSH_LIGHTEST,
 };
 use fyrox_core::uuid_provider;
 use fyrox_graph::{BaseSceneGraph, SceneGraph};
@@ -41,6 +46,9 @@ use std::{
 #[derive(Debug, Clone, PartialEq)]
 pub enum TileMessage {
     Content(TileContent),
+    /// Makes a window with the given index active in a tile with [`TileContent::MultiWindow`]
+    /// content. Other windows of the tile will be hidden.
+    ActiveTab(usize),
     /// Internal. Do not use.
     Split {
         window: Handle<UiNode>,
@@ -51,6 +59,7 @@ pub enum TileMessage {
 
 impl TileMessage {
     define_constructor!(TileMessage:Content => fn content(TileContent), layout: false);
+    define_constructor!(TileMessage:ActiveTab => fn active_tab(usize), layout: false);
     define_constructor!(TileMessage:Split => fn split(window: Handle<UiNode>,
         direction: SplitDirection,
         first: bool), layout: false);
@@ -61,6 +70,12 @@ pub enum TileContent {
     #[default]
     Empty,
     Window(Handle<UiNode>),
+    /// A set of windows shown as tabs, only the window at `index` is visible at a time. Windows
+    /// dropped onto the center anchor of a docked window are added here.
+    MultiWindow {
+        index: usize,
+        windows: Vec<Handle<UiNode>>,
+    },
     VerticalTiles {
         splitter: f32,
         /// Docking system requires tiles to be handles to Tile instances.
@@ -81,6 +96,67 @@ impl TileContent {
     pub fn is_empty(&self) -> bool {
         matches!(self, TileContent::Empty)
     }
+
+    /// Checks whether the tile content can accept a docked window, that is it is not split.
+    pub fn can_dock(&self) -> bool {
+        matches!(
+            self,
+            TileContent::Empty | TileContent::Window(_) | TileContent::MultiWindow { .. }
+        )
+    }
+
+    /// Checks whether the given window is docked directly in the tile content.
+    pub fn contains_window(&self, window: Handle<UiNode>) -> bool {
+        match self {
+            TileContent::Window(docked) => *docked == window,
+            TileContent::MultiWindow { windows, .. } => windows.contains(&window),
+            _ => false,
+        }
+    }
+
+    /// Creates a new content without the given window. A tab set with a single window left is
+    /// collapsed into [`TileContent::Window`].
+    pub fn without_window(&self, window: Handle<UiNode>) -> TileContent {
+        match self {
+            TileContent::Window(docked) if *docked == window => TileContent::Empty,
+            TileContent::MultiWindow { index, windows } => {
+                let windows = windows
+                    .iter()
+                    .cloned()
+                    .filter(|w| *w != window)
+                    .collect::<Vec<_>>();
+                match windows.len() {
+                    0 => TileContent::Empty,
+                    1 => TileContent::Window(windows[0]),
+                    len => TileContent::MultiWindow {
+                        index: (*index).min(len - 1),
+                        windows,
+                    },
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Creates a new content with the given window added as a new active tab.
+    pub fn with_window(&self, window: Handle<UiNode>) -> TileContent {
+        match self {
+            TileContent::Empty => TileContent::Window(window),
+            TileContent::Window(docked) => TileContent::MultiWindow {
+                index: 1,
+                windows: vec![*docked, window],
+            },
+            TileContent::MultiWindow { windows, .. } => {
+                let mut windows = windows.clone();
+                windows.push(window);
+                TileContent::MultiWindow {
+                    index: windows.len() - 1,
+                    windows,
+                }
+            }
+            _ => self.clone(),
+        }
+    }
 }
 
 fn send_visibility(ui: &UserInterface, destination: Handle<UiNode>, visible: bool) {
@@ -124,6 +200,10 @@ pub struct Tile {
     pub splitter: Handle<UiNode>,
     pub dragging_splitter: bool,
     pub drop_anchor: Cell<Handle<UiNode>>,
+    #[visit(optional)]
+    pub tab_bar: Handle<UiNode>,
+    #[visit(optional)]
+    pub tab_buttons: Vec<Handle<UiNode>>,
 }
 
 crate::define_widget_deref!(Tile);
@@ -162,6 +242,18 @@ impl Control for Tile {
                         available_size
                     }
                 }
+                TileContent::MultiWindow { ref windows, .. } => {
+                    // Tab bar is always added before the windows, so it is already measured here.
+                    if windows.contains(&child_handle) {
+                        let tab_bar_height = ui.node(self.tab_bar).desired_size().y;
+                        Vector2::new(
+                            available_size.x,
+                            (available_size.y - tab_bar_height).max(0.0),
+                        )
+                    } else {
+                        available_size
+                    }
+                }
                 _ => available_size,
             };
 
@@ -236,6 +328,21 @@ impl Control for Tile {
                         full_bounds
                     }
                 }
+                TileContent::MultiWindow { ref windows, .. } => {
+                    let tab_bar_height = ui.node(self.tab_bar).desired_size().y;
+                    if windows.contains(&child_handle) {
+                        Rect::new(
+                            0.0,
+                            tab_bar_height,
+                            final_size.x,
+                            (final_size.y - tab_bar_height).max(0.0),
+                        )
+                    } else if self.tab_bar == child_handle {
+                        Rect::new(0.0, 0.0, final_size.x, tab_bar_height)
+                    } else {
+                        full_bounds
+                    }
+                }
                 _ => full_bounds,
             };
 
@@ -276,6 +383,29 @@ impl Control for Tile {
                                 // Make the window size undefined, so it will be stretched to the tile
                                 // size correctly.
                                 send_size(ui, window, f32::NAN, f32::NAN);
+
+                                // The window could be hidden as an inactive tab before.
+                                send_visibility(ui, window, true);
+                            }
+                            TileContent::MultiWindow { index, windows } => {
+                                for (i, &window) in windows.iter().enumerate() {
+                                    ui.send_message(WidgetMessage::link(
+                                        window,
+                                        MessageDirection::ToWidget,
+                                        self.handle(),
+                                    ));
+
+                                    ui.send_message(WindowMessage::can_resize(
+                                        window,
+                                        MessageDirection::ToWidget,
+                                        false,
+                                    ));
+
+                                    send_size(ui, window, f32::NAN, f32::NAN);
+                                    send_visibility(ui, window, i == *index);
+                                }
+
+                                send_visibility(ui, self.splitter, false);
                             }
                             TileContent::VerticalTiles { tiles, .. }
                             | TileContent::HorizontalTiles { tiles, .. } => {
@@ -319,13 +449,33 @@ impl Control for Tile {
                                 }
                             }
                         }
+
+                        self.rebuild_tabs(ui);
+                    }
+                    &TileMessage::ActiveTab(active_tab) => {
+                        if let TileContent::MultiWindow {
+                            ref mut index,
+                            ref windows,
+                        } = self.content
+                        {
+                            if active_tab < windows.len() {
+                                *index = active_tab;
+                                for (i, &window) in windows.iter().enumerate() {
+                                    send_visibility(ui, window, i == active_tab);
+                                }
+                                self.sync_tab_selection(ui);
+                            }
+                        }
                     }
                     &TileMessage::Split {
                         window,
                         direction,
                         first,
                     } => {
-                        if matches!(self.content, TileContent::Window(_)) {
+                        if matches!(
+                            self.content,
+                            TileContent::Window(_) | TileContent::MultiWindow { .. }
+                        ) {
                             self.split(ui, window, direction, first);
                         }
                     }
@@ -401,6 +551,25 @@ impl Control for Tile {
                                                 // Splitter must be hidden.
                                                 send_visibility(ui, self.splitter, false);
                                             }
+                                            TileContent::MultiWindow { index, ref windows } => {
+                                                // Same as above, but for every tab of the sub tile.
+                                                for &window in windows {
+                                                    ui.send_message(WidgetMessage::unlink(
+                                                        window,
+                                                        MessageDirection::ToWidget,
+                                                    ));
+                                                }
+
+                                                ui.send_message(TileMessage::content(
+                                                    self.handle,
+                                                    MessageDirection::ToWidget,
+                                                    TileContent::MultiWindow {
+                                                        index,
+                                                        windows: windows.clone(),
+                                                    },
+                                                ));
+                                                send_visibility(ui, self.splitter, false);
+                                            }
                                             // In case if we have a split tile (vertically or horizontally) left in current tile
                                             // (which is split too) we must set content of current tile to content of sub tile.
                                             TileContent::VerticalTiles {
@@ -467,18 +636,16 @@ impl Control for Tile {
             match msg {
                 WindowMessage::Move(_) => {
                     // Check if we dragging child window.
-                    let content_moved = match self.content {
-                        TileContent::Window(window) => window == message.destination(),
-                        _ => false,
-                    };
+                    let content_moved = self.content.contains_window(message.destination());
 
                     if content_moved {
                         if let Some(window) = ui.node(message.destination()).cast::<Window>() {
                             if window.drag_delta.norm() > 20.0 {
+                                // Other tabs (if any) stay docked.
                                 ui.send_message(TileMessage::content(
                                     self.handle,
                                     MessageDirection::ToWidget,
-                                    TileContent::Empty,
+                                    self.content.without_window(message.destination()),
                                 ));
 
                                 ui.send_message(WidgetMessage::unlink(
@@ -552,6 +719,14 @@ impl Control for Tile {
                                                 MessageDirection::ToWidget,
                                             ));
                                         }
+                                        TileContent::MultiWindow { ref windows, .. } => {
+                                            for &window in windows {
+                                                ui.send_message(WidgetMessage::unlink(
+                                                    window,
+                                                    MessageDirection::ToWidget,
+                                                ));
+                                            }
+                                        }
                                         TileContent::VerticalTiles {
                                             tiles: sub_tiles, ..
                                         }
@@ -595,10 +770,47 @@ impl Control for Tile {
                             }
                         }
                     }
+                    TileContent::MultiWindow { .. } => {
+                        let closed_window = message.destination();
+
+                        if self.content.contains_window(closed_window) {
+                            ui.send_message(WidgetMessage::unlink(
+                                closed_window,
+                                MessageDirection::ToWidget,
+                            ));
+
+                            ui.send_message(TileMessage::content(
+                                self.handle,
+                                MessageDirection::ToWidget,
+                                self.content.without_window(closed_window),
+                            ));
+
+                            if let Some((_, docking_manager)) =
+                                ui.find_component_up::<DockingManager>(self.parent())
+                            {
+                                docking_manager
+                                    .floating_windows
+                                    .borrow_mut()
+                                    .push(closed_window);
+                            }
+                        }
+                    }
                     _ => {}
                 },
                 _ => (),
             }
+        } else if let Some(ButtonMessage::Click) = message.data() {
+            if let Some(tab) = self
+                .tab_buttons
+                .iter()
+                .position(|button| *button == message.destination())
+            {
+                ui.send_message(TileMessage::active_tab(
+                    self.handle,
+                    MessageDirection::ToWidget,
+                    tab,
+                ));
+            }
         }
     }
 
@@ -618,7 +830,7 @@ impl Control for Tile {
                 {
                     match msg {
                         &WindowMessage::Move(_) => {
-                            if let TileContent::Empty | TileContent::Window(_) = self.content {
+                            if self.content.can_dock() {
                                 // Show anchors.
                                 for &anchor in &self.anchors() {
                                     send_visibility(ui, anchor, true);
@@ -626,7 +838,7 @@ impl Control for Tile {
                             }
 
                             // Window can be docked only if current tile is not split already.
-                            if let TileContent::Empty | TileContent::Window(_) = self.content {
+                            if self.content.can_dock() {
                                 // When window is being dragged, we should check which tile can accept it.
                                 let pos = ui.cursor_position;
                                 for &anchor in &self.anchors() {
@@ -677,7 +889,7 @@ impl Control for Tile {
                                             ));
                                         }
                                     }
-                                    TileContent::Window(_) => {
+                                    TileContent::Window(_) | TileContent::MultiWindow { .. } => {
                                         if self.drop_anchor.get() == self.left_anchor {
                                             // Split horizontally, dock to left.
                                             ui.send_message(TileMessage::split(
@@ -714,6 +926,13 @@ impl Control for Tile {
                                                 SplitDirection::Vertical,
                                                 false,
                                             ));
+                                        } else if self.drop_anchor.get() == self.center_anchor {
+                                            // Dock as a new tab.
+                                            ui.send_message(TileMessage::content(
+                                                self.handle,
+                                                MessageDirection::ToWidget,
+                                                self.content.with_window(message.destination()),
+                                            ));
                                         }
                                     }
                                     // Rest cannot accept windows.
@@ -754,8 +973,8 @@ impl Tile {
         first: bool,
     ) {
         let existing_content = match self.content {
-            TileContent::Window(existing_window) => existing_window,
-            _ => Handle::NONE,
+            TileContent::Window(_) | TileContent::MultiWindow { .. } => self.content.clone(),
+            _ => TileContent::Empty,
         };
 
         let first_tile = TileBuilder::new(WidgetBuilder::new())
@@ -778,11 +997,11 @@ impl Tile {
             })
             .build(&mut ui.build_ctx());
 
-        if existing_content.is_some() {
+        if !existing_content.is_empty() {
             ui.send_message(TileMessage::content(
                 if first { second_tile } else { first_tile },
                 MessageDirection::ToWidget,
-                TileContent::Window(existing_content),
+                existing_content,
             ));
         }
 
@@ -801,6 +1020,89 @@ impl Tile {
             },
         ));
     }
+
+    fn rebuild_tabs(&mut self, ui: &mut UserInterface) {
+        for button in self.tab_buttons.drain(..) {
+            ui.send_message(WidgetMessage::remove(button, MessageDirection::ToWidget));
+        }
+
+        if let TileContent::MultiWindow { index, ref windows } = self.content {
+            self.tab_buttons = make_tab_buttons(&mut ui.build_ctx(), windows, index);
+            for &button in &self.tab_buttons {
+                ui.send_message(WidgetMessage::link(
+                    button,
+                    MessageDirection::ToWidget,
+                    self.tab_bar,
+                ));
+            }
+        }
+
+        send_visibility(
+            ui,
+            self.tab_bar,
+            matches!(self.content, TileContent::MultiWindow { .. }),
+        );
+    }
+
+    fn sync_tab_selection(&self, ui: &UserInterface) {
+        if let TileContent::MultiWindow { index, .. } = self.content {
+            for (i, &button) in self.tab_buttons.iter().enumerate() {
+                if let Some(button) = ui.try_get(button).and_then(|b| b.cast::<Button>()) {
+                    ui.send_message(DecoratorMessage::select(
+                        *button.decorator,
+                        MessageDirection::ToWidget,
+                        i == index,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn window_title(ui: &UserInterface, window: Handle<UiNode>) -> String {
+    let Some(window_ref) = ui.try_get(window) else {
+        return Default::default();
+    };
+
+    window_ref
+        .cast::<Window>()
+        .and_then(|w| ui.try_get(w.title))
+        .and_then(|title| title.cast::<Text>())
+        .map(|text| text.text())
+        .unwrap_or_else(|| window_ref.name.to_string())
+}
+
+fn make_tab_buttons(
+    ctx: &mut BuildContext,
+    windows: &[Handle<UiNode>],
+    index: usize,
+) -> Vec<Handle<UiNode>> {
+    windows
+        .iter()
+        .enumerate()
+        .map(|(i, &window)| {
+            let title = window_title(ctx.inner(), window);
+            let back = DecoratorBuilder::new(
+                BorderBuilder::new(WidgetBuilder::new().with_foreground(BRUSH_DARKER))
+                    .with_pad_by_corner_radius(false)
+                    .with_corner_radius(4.0)
+                    .with_stroke_thickness(Thickness::uniform(1.0)),
+            )
+            .with_normal_brush(BRUSH_LIGHT)
+            .with_hover_brush(BRUSH_LIGHTER)
+            .with_pressed_brush(BRUSH_LIGHTEST)
+            .with_selected(i == index)
+            .build(ctx);
+            ButtonBuilder::new(
+                WidgetBuilder::new()
+                    .with_min_size(Vector2::new(60.0, 22.0))
+                    .with_margin(Thickness::uniform(1.0)),
+            )
+            .with_back(back)
+            .with_text(&title)
+            .build(ctx)
+        })
+        .collect()
 }
 
 pub struct TileBuilder {
@@ -895,7 +1197,13 @@ impl TileBuilder {
         .with_stroke_thickness(Thickness::uniform(0.0))
         .build(ctx);
 
-        if let TileContent::Window(window) = self.content {
+        let docked_windows = match self.content {
+            TileContent::Window(window) => vec![window],
+            TileContent::MultiWindow { ref windows, .. } => windows.clone(),
+            _ => vec![],
+        };
+
+        for (i, &window) in docked_windows.iter().enumerate() {
             if let Some(window) = ctx[window].cast_mut::<Window>() {
                 // Every docked window must be non-resizable (it means that it cannot be resized by user
                 // and it still can be resized by a proper message).
@@ -906,13 +1214,32 @@ impl TileBuilder {
                 window.width.set_value_and_mark_modified(f32::NAN);
                 window.height.set_value_and_mark_modified(f32::NAN);
             }
+
+            // Only the active tab is visible.
+            if let TileContent::MultiWindow { index, .. } = self.content {
+                ctx[window].set_visibility(i == index);
+            }
         }
 
+        let tab_buttons = match self.content {
+            TileContent::MultiWindow { index, ref windows } => {
+                make_tab_buttons(ctx, windows, index)
+            }
+            _ => vec![],
+        };
+
+        let tab_bar = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(matches!(self.content, TileContent::MultiWindow { .. }))
+                .with_children(tab_buttons.iter().cloned()),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+
         let children = match self.content {
-            TileContent::Window(window) => vec![window],
             TileContent::VerticalTiles { tiles, .. } => vec![tiles[0], tiles[1]],
             TileContent::HorizontalTiles { tiles, .. } => vec![tiles[0], tiles[1]],
-            _ => vec![],
+            _ => docked_windows,
         };
 
         let tile = Tile {
@@ -921,6 +1248,7 @@ impl TileBuilder {
                 .with_preview_messages(true)
                 .with_child(grid)
                 .with_child(splitter)
+                .with_child(tab_bar)
                 .with_children(children)
                 .build(),
             left_anchor,
@@ -932,8 +1260,43 @@ impl TileBuilder {
             splitter,
             dragging_splitter: false,
             drop_anchor: Default::default(),
+            tab_bar,
+            tab_buttons,
         };
 
         ctx.add_node(UiNode::new(tile))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{core::pool::Handle, dock::TileContent};
+
+    #[test]
+    fn test_tabs() {
+        let a = Handle::new(1, 1);
+        let b = Handle::new(2, 1);
+        let c = Handle::new(3, 1);
+
+        let content = TileContent::Window(a).with_window(b).with_window(c);
+        assert_eq!(
+            content,
+            TileContent::MultiWindow {
+                index: 2,
+                windows: vec![a, b, c]
+            }
+        );
+        assert!(content.contains_window(b));
+
+        let content = content.without_window(c);
+        assert_eq!(
+            content,
+            TileContent::MultiWindow {
+                index: 1,
+                windows: vec![a, b]
+            }
+        );
+        assert_eq!(content.without_window(a), TileContent::Window(b));
+        assert_eq!(TileContent::Window(a).without_window(a), TileContent::Empty);
+    }
+}
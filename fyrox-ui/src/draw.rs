@@ -24,7 +24,7 @@ use crate::{
     core::{
         algebra::{Matrix3, Point2, Vector2},
         color::Color,
-        math::{self, Rect, TriangleDefinition},
+        math::{self, OptionRect, Rect, TriangleDefinition},
     },
     font::FontResource,
     formatted_text::FormattedText,
@@ -53,7 +53,7 @@ impl Vertex {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum CommandTexture {
     None,
     Texture(UntypedResource),
@@ -125,7 +125,10 @@ pub struct Command {
     pub clip_bounds: Rect<f32>,
     /// Total bounds of command's geometry. Screen-space.
     pub bounds: Rect<f32>,
-    /// Brush defines visual appearance of rendered geometry.
+    /// Brush defines visual appearance of rendered geometry. Color of solid brushes (as well as
+    /// the opacity) is baked into vertex colors of the command's geometry, so commands with solid
+    /// brushes of different colors could be drawn in a single draw call. See
+    /// [`DrawingContext::batches`] for more info.
     pub brush: Brush,
     pub texture: CommandTexture,
     pub triangles: Range<usize>,
@@ -134,6 +137,101 @@ pub struct Command {
     pub clipping_geometry: Option<ClippingGeometry>,
 }
 
+impl Command {
+    fn is_batchable(&self) -> bool {
+        matches!(self.brush, Brush::Solid(_)) && self.clipping_geometry.is_none()
+    }
+
+    fn needs_clipping(&self) -> bool {
+        !is_rect_inside(&self.bounds, &self.clip_bounds)
+    }
+}
+
+#[inline(always)]
+fn modulate(a: u8, b: u8) -> u8 {
+    ((a as u32 * b as u32 + 127) / 255) as u8
+}
+
+fn is_rect_inside(rect: &Rect<f32>, outer: &Rect<f32>) -> bool {
+    rect.position.x >= outer.position.x
+        && rect.position.y >= outer.position.y
+        && rect.position.x + rect.size.x <= outer.position.x + outer.size.x
+        && rect.position.y + rect.size.y <= outer.position.y + outer.size.y
+}
+
+/// A range of consecutive commands, that could be drawn using a single draw call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandBatch {
+    /// A range of commands of the batch in the command buffer.
+    pub commands: Range<usize>,
+    /// A range of triangles of the batch in the triangle buffer.
+    pub triangles: Range<usize>,
+    /// Clipping bounds of the whole batch, should be used for scissor-test. Screen-space.
+    pub clip_bounds: Rect<f32>,
+}
+
+struct PendingBatch {
+    batch: CommandBatch,
+    // Clipping bounds of the commands, whose geometry goes beyond their clipping bounds.
+    scissor: Option<Rect<f32>>,
+    geometry_bounds: Rect<f32>,
+}
+
+impl PendingBatch {
+    fn new(index: usize, command: &Command) -> Self {
+        Self {
+            batch: CommandBatch {
+                commands: index..index + 1,
+                triangles: command.triangles.clone(),
+                clip_bounds: command.clip_bounds,
+            },
+            scissor: if command.needs_clipping() {
+                Some(command.clip_bounds)
+            } else {
+                None
+            },
+            geometry_bounds: command.bounds,
+        }
+    }
+
+    fn try_push(&mut self, command: &Command) -> bool {
+        if self.batch.triangles.end != command.triangles.start {
+            return false;
+        }
+
+        // Every command of a batch must be clipped correctly by the same scissor box.
+        if command.needs_clipping() {
+            match self.scissor {
+                Some(scissor) if scissor != command.clip_bounds => return false,
+                None if !is_rect_inside(&self.geometry_bounds, &command.clip_bounds) => {
+                    return false
+                }
+                _ => self.scissor = Some(command.clip_bounds),
+            }
+        } else if let Some(scissor) = self.scissor {
+            if !is_rect_inside(&command.bounds, &scissor) {
+                return false;
+            }
+        }
+
+        self.batch.commands.end += 1;
+        self.batch.triangles.end = command.triangles.end;
+        self.batch
+            .clip_bounds
+            .extend_to_contain(command.clip_bounds);
+        self.geometry_bounds.extend_to_contain(command.bounds);
+
+        true
+    }
+
+    fn finish(mut self) -> CommandBatch {
+        if let Some(scissor) = self.scissor {
+            self.batch.clip_bounds = scissor;
+        }
+        self.batch
+    }
+}
+
 pub trait Draw {
     fn push_vertex(&mut self, pos: Vector2<f32>, tex_coord: Vector2<f32>) {
         self.push_vertex_raw(Vertex::new(pos, tex_coord))
@@ -886,13 +984,63 @@ impl DrawingContext {
     }
 
     fn bounds_of(&self, range: Range<usize>) -> Rect<f32> {
-        let mut bounds = Rect::new(f32::MAX, f32::MAX, 0.0, 0.0);
+        let mut bounds = OptionRect::default();
         for i in range {
             for &k in self.triangle_buffer[i].as_ref() {
                 bounds.push(self.vertex_buffer[k as usize].pos);
             }
         }
-        bounds
+        bounds.unwrap_or_default()
+    }
+
+    fn vertex_range_of(&self, triangles: Range<usize>) -> Range<usize> {
+        let mut min = usize::MAX;
+        let mut max = 0;
+        for triangle in &self.triangle_buffer[triangles] {
+            for &index in triangle.as_ref() {
+                min = min.min(index as usize);
+                max = max.max(index as usize);
+            }
+        }
+        if min > max {
+            0..0
+        } else {
+            min..max + 1
+        }
+    }
+
+    /// Splits the command buffer into batches of consecutive commands, that could be drawn using
+    /// a single draw call. Only commands with solid brushes, without clipping geometry and with
+    /// equal texture keys could be merged together. `texture_keys` must contain a key of a texture
+    /// for every command, it allows renderers to decide which textures are the same (for example,
+    /// if multiple images are packed into a texture atlas). Use [`Self::command_texture_keys`] to
+    /// batch commands by their textures.
+    pub fn batches<K: PartialEq>(&self, texture_keys: &[K]) -> Vec<CommandBatch> {
+        assert_eq!(texture_keys.len(), self.command_buffer.len());
+
+        let mut batches = Vec::new();
+        let mut pending: Option<PendingBatch> = None;
+        for (index, command) in self.command_buffer.iter().enumerate() {
+            if let Some(current) = pending.as_mut() {
+                let previous = index - 1;
+                if self.command_buffer[previous].is_batchable()
+                    && command.is_batchable()
+                    && texture_keys[previous] == texture_keys[index]
+                    && current.try_push(command)
+                {
+                    continue;
+                }
+                batches.extend(pending.take().map(PendingBatch::finish));
+            }
+            pending = Some(PendingBatch::new(index, command));
+        }
+        batches.extend(pending.map(PendingBatch::finish));
+        batches
+    }
+
+    /// Returns textures of every command, could be used as keys for [`Self::batches`].
+    pub fn command_texture_keys(&self) -> Vec<&CommandTexture> {
+        self.command_buffer.iter().map(|cmd| &cmd.texture).collect()
     }
 
     pub fn commit(
@@ -907,6 +1055,21 @@ impl DrawingContext {
             let bounds = self.bounds_of(triangles.clone());
 
             let opacity = *self.opacity_stack.last().unwrap();
+
+            // Bake solid color and opacity into the vertices, so there's no need to pass them
+            // to the shader and the command could be batched with the others.
+            if let Brush::Solid(color) = brush {
+                for index in self.vertex_range_of(triangles.clone()) {
+                    let vertex = &mut self.vertex_buffer[index];
+                    vertex.color = Color::from_rgba(
+                        modulate(vertex.color.r, color.r),
+                        modulate(vertex.color.g, color.g),
+                        modulate(vertex.color.b, color.b),
+                        (modulate(vertex.color.a, color.a) as f32 * opacity).round() as u8,
+                    );
+                }
+            }
+
             self.command_buffer.push(Command {
                 clip_bounds,
                 bounds,
@@ -926,8 +1089,6 @@ impl DrawingContext {
         position: Vector2<f32>,
         formatted_text: &FormattedText,
     ) {
-        let font = formatted_text.get_font();
-
         #[inline(always)]
         fn draw(
             formatted_text: &FormattedText,
@@ -936,31 +1097,39 @@ impl DrawingContext {
             position: Vector2<f32>,
             dilation: f32,
             offset: Vector2<f32>,
-            brush: Brush,
-            font: &FontResource,
+            brush: Option<Brush>,
         ) {
-            let Some(mut current_page_index) = formatted_text
+            let Some((mut current_page_index, mut current_run)) = formatted_text
                 .get_glyphs()
                 .first()
-                .map(|g| g.atlas_page_index)
+                .map(|g| (g.atlas_page_index, g.run))
             else {
                 return;
             };
 
+            // Commits a batch of glyphs that share the same atlas page and style run. The brush
+            // of the run is ignored if there's an explicit brush (for shadows).
+            let commit = |ctx: &mut DrawingContext, page_index: usize, run: Option<usize>| {
+                let (font, font_size, run_brush) = formatted_text.run_style(run);
+                ctx.commit(
+                    clip_bounds,
+                    brush.clone().unwrap_or(run_brush),
+                    CommandTexture::Font {
+                        font,
+                        page_index,
+                        height: font_size.into(),
+                    },
+                    None,
+                );
+            };
+
             for element in formatted_text.get_glyphs() {
-                // If we've switched to another atlas page, commit the text and start a new batch.
-                if current_page_index != element.atlas_page_index {
-                    ctx.commit(
-                        clip_bounds,
-                        brush.clone(),
-                        CommandTexture::Font {
-                            font: font.clone(),
-                            page_index: current_page_index,
-                            height: formatted_text.font_size().into(),
-                        },
-                        None,
-                    );
+                // If we've switched to another atlas page or style, commit the text and start
+                // a new batch.
+                if current_page_index != element.atlas_page_index || current_run != element.run {
+                    commit(ctx, current_page_index, current_run);
                     current_page_index = element.atlas_page_index;
+                    current_run = element.run;
                 }
 
                 let bounds = element.bounds;
@@ -977,16 +1146,7 @@ impl DrawingContext {
             }
 
             // Commit the rest.
-            ctx.commit(
-                clip_bounds,
-                brush,
-                CommandTexture::Font {
-                    font: font.clone(),
-                    page_index: current_page_index,
-                    height: formatted_text.font_size().into(),
-                },
-                None,
-            );
+            commit(ctx, current_page_index, current_run);
         }
 
         // Draw shadow, if any.
@@ -998,8 +1158,7 @@ impl DrawingContext {
                 position,
                 *formatted_text.shadow_dilation,
                 *formatted_text.shadow_offset,
-                (*formatted_text.shadow_brush).clone(),
-                &font,
+                Some((*formatted_text.shadow_brush).clone()),
             );
         }
 
@@ -1010,8 +1169,72 @@ impl DrawingContext {
             position,
             0.0,
             Default::default(),
-            formatted_text.brush(),
-            &font,
+            None,
         );
+
+        // Inline images are drawn on top of the text.
+        for image in formatted_text.get_images() {
+            let Some(texture) = image.texture.as_ref() else {
+                continue;
+            };
+            let bounds = image.bounds.translate(position);
+            self.push_rect_filled(&bounds, None);
+            self.commit(
+                clip_bounds,
+                Brush::Solid(Color::WHITE),
+                CommandTexture::Texture(texture.clone()),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        brush::Brush,
+        core::{color::Color, math::Rect},
+        draw::{CommandTexture, Draw, DrawingContext},
+    };
+
+    #[test]
+    fn test_command_batching() {
+        let mut ctx = DrawingContext::new();
+        let clip_bounds = Rect::new(0.0, 0.0, 100.0, 100.0);
+
+        ctx.push_rect_filled(&Rect::new(0.0, 0.0, 10.0, 10.0), None);
+        ctx.commit(
+            clip_bounds,
+            Brush::Solid(Color::RED),
+            CommandTexture::None,
+            None,
+        );
+        ctx.push_rect_filled(&Rect::new(20.0, 0.0, 10.0, 10.0), None);
+        ctx.commit(
+            Rect::new(20.0, 0.0, 10.0, 10.0),
+            Brush::Solid(Color::GREEN),
+            CommandTexture::None,
+            None,
+        );
+        // Goes beyond its clipping bounds, which are different from the clipping bounds of the
+        // batch.
+        ctx.push_rect_filled(&Rect::new(90.0, 0.0, 20.0, 10.0), None);
+        ctx.commit(
+            Rect::new(50.0, 0.0, 50.0, 50.0),
+            Brush::Solid(Color::BLUE),
+            CommandTexture::None,
+            None,
+        );
+
+        // Solid colors must be baked into the vertices.
+        assert_eq!(ctx.get_vertices()[0].color, Color::RED);
+        assert_eq!(ctx.get_vertices()[4].color, Color::GREEN);
+
+        let batches = ctx.batches(&ctx.command_texture_keys());
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].commands, 0..2);
+        assert_eq!(batches[0].triangles, 0..4);
+        assert_eq!(batches[1].commands, 2..3);
+        assert_eq!(batches[1].clip_bounds, Rect::new(50.0, 0.0, 50.0, 50.0));
     }
 }
@@ -76,6 +76,8 @@ impl Debug for Page {
 pub struct Atlas {
     pub glyphs: Vec<FontGlyph>,
     pub char_map: FxHashMap<char, usize>,
+    /// Maps indices of glyphs in the font to the indices of the rasterized glyphs in the atlas.
+    pub index_map: FxHashMap<u16, usize>,
     pub pages: Vec<Page>,
 }
 
@@ -87,116 +89,137 @@ impl Atlas {
         height: FontHeight,
         page_size: usize,
     ) -> Option<&FontGlyph> {
-        let border = 2;
-
         match self.char_map.get(&unicode) {
-            Some(glyph_index) => {
-                return self.glyphs.get(*glyph_index);
-            }
+            Some(glyph_index) => self.glyphs.get(*glyph_index),
             None => {
                 // Char might be missing, because it wasn't requested earlier. Try to find
                 // it in the inner font and render/pack it.
+                let char_index = font.chars().get(&unicode)?;
+                let glyph_index = self.rasterize(font, char_index.get(), height, page_size)?;
+
+                // Map the new glyph to its unicode position.
+                self.char_map.insert(unicode, glyph_index);
+
+                self.glyphs.get(glyph_index)
+            }
+        }
+    }
+
+    fn glyph_by_index(
+        &mut self,
+        font: &fontdue::Font,
+        index: u16,
+        height: FontHeight,
+        page_size: usize,
+    ) -> Option<&FontGlyph> {
+        let glyph_index = self.rasterize(font, index, height, page_size)?;
+        self.glyphs.get(glyph_index)
+    }
+
+    fn rasterize(
+        &mut self,
+        font: &fontdue::Font,
+        index: u16,
+        height: FontHeight,
+        page_size: usize,
+    ) -> Option<usize> {
+        let border = 2;
 
-                if let Some(char_index) = font.chars().get(&unicode) {
-                    let (metrics, glyph_raster) =
-                        font.rasterize_indexed(char_index.get(), height.0);
-
-                    // Find a page, that is capable to fit the new character or create a new
-                    // page and put the character there.
-                    let mut placement_info =
-                        self.pages
-                            .iter_mut()
-                            .enumerate()
-                            .find_map(|(page_index, page)| {
-                                page.rect_packer
-                                    .find_free(metrics.width + border, metrics.height + border)
-                                    .map(|bounds| (page_index, bounds))
-                            });
-
-                    // No space for the character in any of the existing pages, create a new page.
-                    if placement_info.is_none() {
-                        let mut page = Page {
-                            pixels: vec![0; page_size * page_size],
-                            texture: None,
-                            rect_packer: RectPacker::new(page_size, page_size),
-                            modified: true,
-                        };
-
-                        let page_index = self.pages.len();
-
-                        match page
-                            .rect_packer
-                            .find_free(metrics.width + border, metrics.height + border)
-                        {
-                            Some(bounds) => {
-                                placement_info = Some((page_index, bounds));
-
-                                self.pages.push(page);
-                            }
-                            None => {
-                                // No free space in the given page size (requested glyph is too big).
-                                return None;
-                            }
-                        }
-                    }
-
-                    let (page_index, placement_rect) = placement_info?;
-                    let page = &mut self.pages[page_index];
-                    let glyph_index = self.glyphs.len();
-
-                    // Raise a flag to notify users that the content of the page has changed, and
-                    // it should be re-uploaded to GPU (if needed).
-                    page.modified = true;
-
-                    let mut glyph = FontGlyph {
-                        left: metrics.xmin as f32,
-                        top: metrics.ymin as f32,
-                        advance: metrics.advance_width,
-                        tex_coords: Default::default(),
-                        bitmap_width: metrics.width,
-                        bitmap_height: metrics.height,
-                        page_index,
-                    };
-
-                    let k = 1.0 / page_size as f32;
-
-                    let bw = placement_rect.w().saturating_sub(border);
-                    let bh = placement_rect.h().saturating_sub(border);
-                    let bx = placement_rect.x() + border / 2;
-                    let by = placement_rect.y() + border / 2;
-
-                    let tw = bw as f32 * k;
-                    let th = bh as f32 * k;
-                    let tx = bx as f32 * k;
-                    let ty = by as f32 * k;
-
-                    glyph.tex_coords[0] = Vector2::new(tx, ty);
-                    glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
-                    glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
-                    glyph.tex_coords[3] = Vector2::new(tx, ty + th);
-
-                    let row_end = by + bh;
-                    let col_end = bx + bw;
-
-                    // Copy glyph pixels to the atlas pixels
-                    for (src_row, row) in (by..row_end).enumerate() {
-                        for (src_col, col) in (bx..col_end).enumerate() {
-                            page.pixels[row * page_size + col] =
-                                glyph_raster[src_row * bw + src_col];
-                        }
-                    }
-
-                    self.glyphs.push(glyph);
-
-                    // Map the new glyph to its unicode position.
-                    self.char_map.insert(unicode, glyph_index);
-
-                    self.glyphs.get(glyph_index)
-                } else {
-                    None
+        if let Some(glyph_index) = self.index_map.get(&index) {
+            return Some(*glyph_index);
+        }
+
+        let (metrics, glyph_raster) = font.rasterize_indexed(index, height.0);
+
+        // Find a page, that is capable to fit the new character or create a new
+        // page and put the character there.
+        let mut placement_info =
+            self.pages
+                .iter_mut()
+                .enumerate()
+                .find_map(|(page_index, page)| {
+                    page.rect_packer
+                        .find_free(metrics.width + border, metrics.height + border)
+                        .map(|bounds| (page_index, bounds))
+                });
+
+        // No space for the character in any of the existing pages, create a new page.
+        if placement_info.is_none() {
+            let mut page = Page {
+                pixels: vec![0; page_size * page_size],
+                texture: None,
+                rect_packer: RectPacker::new(page_size, page_size),
+                modified: true,
+            };
+
+            let page_index = self.pages.len();
+
+            match page
+                .rect_packer
+                .find_free(metrics.width + border, metrics.height + border)
+            {
+                Some(bounds) => {
+                    placement_info = Some((page_index, bounds));
+
+                    self.pages.push(page);
+                }
+                None => {
+                    // No free space in the given page size (requested glyph is too big).
+                    return None;
                 }
             }
         }
+
+        let (page_index, placement_rect) = placement_info?;
+        let page = &mut self.pages[page_index];
+        let glyph_index = self.glyphs.len();
+
+        // Raise a flag to notify users that the content of the page has changed, and
+        // it should be re-uploaded to GPU (if needed).
+        page.modified = true;
+
+        let mut glyph = FontGlyph {
+            left: metrics.xmin as f32,
+            top: metrics.ymin as f32,
+            advance: metrics.advance_width,
+            tex_coords: Default::default(),
+            bitmap_width: metrics.width,
+            bitmap_height: metrics.height,
+            page_index,
+        };
+
+        let k = 1.0 / page_size as f32;
+
+        let bw = placement_rect.w().saturating_sub(border);
+        let bh = placement_rect.h().saturating_sub(border);
+        let bx = placement_rect.x() + border / 2;
+        let by = placement_rect.y() + border / 2;
+
+        let tw = bw as f32 * k;
+        let th = bh as f32 * k;
+        let tx = bx as f32 * k;
+        let ty = by as f32 * k;
+
+        glyph.tex_coords[0] = Vector2::new(tx, ty);
+        glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
+        glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
+        glyph.tex_coords[3] = Vector2::new(tx, ty + th);
+
+        let row_end = by + bh;
+        let col_end = bx + bw;
+
+        // Copy glyph pixels to the atlas pixels
+        for (src_row, row) in (by..row_end).enumerate() {
+            for (src_col, col) in (bx..col_end).enumerate() {
+                page.pixels[row * page_size + col] = glyph_raster[src_row * bw + src_col];
+            }
+        }
+
+        self.glyphs.push(glyph);
+
+        self.index_map.insert(index, glyph_index);
+
+        Some(glyph_index)
     }
 }
 
@@ -209,6 +232,9 @@ pub struct Font {
     pub atlases: FxHashMap<FontHeight, Atlas>,
     #[visit(skip)]
     pub page_size: usize,
+    /// Raw data of the font, it is used for text shaping.
+    #[visit(skip)]
+    pub data: Vec<u8>,
 }
 
 uuid_provider!(Font = "692fec79-103a-483c-bb0b-9fc3a349cb48");
@@ -277,11 +303,14 @@ impl Font {
         data: impl Deref<Target = [u8]>,
         page_size: usize,
     ) -> Result<Self, &'static str> {
-        let fontdue_font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
+        let data = data.to_vec();
+        let fontdue_font =
+            fontdue::Font::from_bytes(data.as_slice(), fontdue::FontSettings::default())?;
         Ok(Font {
             inner: Some(fontdue_font),
             atlases: Default::default(),
             page_size,
+            data,
         })
     }
 
@@ -306,23 +335,50 @@ impl Font {
     /// in the atlas could be rendered at any page in the atlas.
     #[inline]
     pub fn glyph(&mut self, unicode: char, height: f32) -> Option<&FontGlyph> {
+        self.atlases.entry(FontHeight(height)).or_default().glyph(
+            self.inner
+                .as_ref()
+                .expect("Font reader must be initialized!"),
+            unicode,
+            FontHeight(height),
+            self.page_size,
+        )
+    }
+
+    /// The same as [`Self::glyph`], but uses an index of the glyph in the font instead of a unicode
+    /// position. It is used to get glyphs produced by text shaping (ligatures, contextual forms,
+    /// etc.), that could have no unicode representation at all.
+    #[inline]
+    pub fn glyph_by_index(&mut self, index: u16, height: f32) -> Option<&FontGlyph> {
         self.atlases
             .entry(FontHeight(height))
-            .or_insert_with(|| Atlas {
-                glyphs: Default::default(),
-                char_map: Default::default(),
-                pages: Default::default(),
-            })
-            .glyph(
+            .or_default()
+            .glyph_by_index(
                 self.inner
                     .as_ref()
                     .expect("Font reader must be initialized!"),
-                unicode,
+                index,
                 FontHeight(height),
                 self.page_size,
             )
     }
 
+    /// Checks whether the font has a representation for the given unicode position. It is used to
+    /// pick a font from a fallback chain.
+    #[inline]
+    pub fn has_glyph(&self, unicode: char) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|font| font.chars().contains_key(&unicode))
+    }
+
+    /// Creates a face for text shaping. Returns [`None`] if the font has no raw data (for example,
+    /// if it was created manually).
+    #[inline]
+    pub fn shaping_face(&self) -> Option<rustybuzz::Face> {
+        rustybuzz::Face::from_slice(&self.data, 0)
+    }
+
     #[inline]
     pub fn ascender(&self, height: f32) -> f32 {
         self.inner
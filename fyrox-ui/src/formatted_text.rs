@@ -26,10 +26,13 @@ use crate::{
 };
 use fyrox_core::uuid_provider;
 use fyrox_core::variable::InheritableVariable;
+use fyrox_resource::untyped::UntypedResource;
 use std::ops::Range;
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+mod shaping;
 mod textwrapper;
+use shaping::*;
 use textwrapper::*;
 
 /// Defines a position in the text. It is just a coordinates of a character in text.
@@ -47,6 +50,9 @@ pub struct TextGlyph {
     pub bounds: Rect<f32>,
     pub tex_coords: [Vector2<f32>; 4],
     pub atlas_page_index: usize,
+    /// Index of the resolved style run the glyph belongs to (see [`FormattedText::run_style`]),
+    /// `None` if the glyph uses the default style of the text.
+    pub run: Option<usize>,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -120,6 +126,162 @@ pub enum WrapMode {
 
 uuid_provider!(WrapMode = "f1290ceb-3fee-461f-a1e9-f9450bd06805");
 
+/// A character that should be used in the text as a placeholder for inline images. It is not
+/// mandatory, any character covered by a run with an image will be replaced by the image, but
+/// this one is guaranteed to have no meaning for the text wrapper.
+pub const IMAGE_PLACEHOLDER: char = '\u{FFFC}';
+
+/// An image that is embedded in the text and flows with it like any other character.
+#[derive(Clone, Debug, PartialEq, Default, Visit, Reflect)]
+pub struct InlineImage {
+    /// A texture of the image.
+    pub texture: Option<UntypedResource>,
+    /// Size of the image in local units. The image is placed on the baseline of the line.
+    pub size: Vector2<f32>,
+}
+
+/// A range of characters with its own style. Every property of the run is optional, the text
+/// uses its own properties for everything that is not specified. If runs overlap, properties of
+/// the later runs take precedence.
+#[derive(Clone, Debug, PartialEq, Default, Visit, Reflect)]
+pub struct TextRun {
+    /// A range of characters (not bytes) that is covered by the run.
+    pub range: Range<usize>,
+    /// A font of the run.
+    pub font: Option<FontResource>,
+    /// A font size of the run.
+    pub font_size: Option<f32>,
+    /// A brush of the run.
+    pub brush: Option<Brush>,
+    /// An identifier of a hyperlink, that will be reported when the user clicks on the run.
+    pub link: Option<String>,
+    /// An image that will replace every character of the run.
+    pub image: Option<InlineImage>,
+}
+
+impl TextRun {
+    /// Creates a new run that covers the given range of characters and uses the default style.
+    pub fn new(range: Range<usize>) -> Self {
+        Self {
+            range,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the desired font of the run.
+    pub fn with_font(mut self, font: FontResource) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the desired font size of the run.
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Sets the desired brush of the run.
+    pub fn with_brush(mut self, brush: Brush) -> Self {
+        self.brush = Some(brush);
+        self
+    }
+
+    /// Turns the run into a hyperlink with the given identifier.
+    pub fn with_link<S: AsRef<str>>(mut self, link: S) -> Self {
+        self.link = Some(link.as_ref().to_owned());
+        self
+    }
+
+    /// Replaces every character of the run with the given image.
+    pub fn with_image(mut self, texture: Option<UntypedResource>, size: Vector2<f32>) -> Self {
+        self.image = Some(InlineImage { texture, size });
+        self
+    }
+}
+
+/// A helper that assembles a text and a set of runs piece by piece, so there's no need to
+/// calculate character ranges manually.
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     brush::Brush,
+/// #     core::{algebra::Vector2, color::Color},
+/// #     formatted_text::{RichTextBuilder, TextRun},
+/// # };
+/// let (text, runs) = RichTextBuilder::new()
+///     .push_text("You have found ")
+///     .push_styled("the Sword", TextRun::default().with_brush(Brush::Solid(Color::RED)))
+///     .push_image(None, Vector2::new(16.0, 16.0))
+///     .push_text(". ")
+///     .push_link("Read more", "sword_info")
+///     .build();
+/// assert_eq!(runs.len(), 3);
+/// ```
+#[derive(Default)]
+pub struct RichTextBuilder {
+    text: String,
+    length: usize,
+    runs: Vec<TextRun>,
+}
+
+impl RichTextBuilder {
+    /// Creates a new empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a text with the default style.
+    pub fn push_text<S: AsRef<str>>(mut self, text: S) -> Self {
+        self.append(text.as_ref());
+        self
+    }
+
+    /// Appends a text with the style of the given run. The range of the run is ignored.
+    pub fn push_styled<S: AsRef<str>>(mut self, text: S, mut style: TextRun) -> Self {
+        style.range = self.append(text.as_ref());
+        self.runs.push(style);
+        self
+    }
+
+    /// Appends a hyperlink with the given identifier.
+    pub fn push_link<S: AsRef<str>, L: AsRef<str>>(self, text: S, link: L) -> Self {
+        self.push_styled(text, TextRun::default().with_link(link))
+    }
+
+    /// Appends an inline image of the given size.
+    pub fn push_image(self, texture: Option<UntypedResource>, size: Vector2<f32>) -> Self {
+        let placeholder = IMAGE_PLACEHOLDER.to_string();
+        self.push_styled(placeholder, TextRun::default().with_image(texture, size))
+    }
+
+    fn append(&mut self, text: &str) -> Range<usize> {
+        let begin = self.length;
+        self.text.push_str(text);
+        self.length += text.chars().count();
+        begin..self.length
+    }
+
+    /// Returns the final text and its runs.
+    pub fn build(self) -> (String, Vec<TextRun>) {
+        (self.text, self.runs)
+    }
+}
+
+/// Bounds of an inline image, calculated by [`FormattedText::build`].
+#[derive(Clone, Debug, Default)]
+pub struct TextImage {
+    pub bounds: Rect<f32>,
+    pub texture: Option<UntypedResource>,
+}
+
+/// Bounds of a part of a hyperlink on a single line, calculated by [`FormattedText::build`].
+#[derive(Clone, Debug, Default)]
+pub struct TextLink {
+    pub bounds: Rect<f32>,
+    /// Index of the resolved style run that defines the hyperlink.
+    pub run: usize,
+}
+
 struct GlyphMetrics<'a> {
     font: &'a mut Font,
     size: f32,
@@ -149,7 +311,35 @@ impl<'a> GlyphMetrics<'a> {
 fn build_glyph(metrics: &mut GlyphMetrics, x: f32, y: f32, character: char) -> (TextGlyph, f32) {
     let ascender = metrics.ascender();
     let font_size = metrics.size;
-    match metrics.glyph(character) {
+    make_text_glyph(metrics.glyph(character), ascender, font_size, x, y)
+}
+
+fn build_shaped_glyph(
+    metrics: &mut GlyphMetrics,
+    x: f32,
+    y: f32,
+    shaped: &ShapedGlyph,
+) -> (TextGlyph, f32) {
+    let ascender = metrics.ascender();
+    let font_size = metrics.size;
+    let glyph = metrics.font.glyph_by_index(shaped.index, font_size);
+    make_text_glyph(
+        glyph,
+        ascender,
+        font_size,
+        x + shaped.x_offset,
+        y - shaped.y_offset,
+    )
+}
+
+fn make_text_glyph(
+    glyph: Option<&FontGlyph>,
+    ascender: f32,
+    font_size: f32,
+    x: f32,
+    y: f32,
+) -> (TextGlyph, f32) {
+    match glyph {
         Some(glyph) => {
             // Insert glyph
             let rect = Rect::new(
@@ -162,6 +352,7 @@ fn build_glyph(metrics: &mut GlyphMetrics, x: f32, y: f32, character: char) -> (
                 bounds: rect,
                 tex_coords: glyph.tex_coords,
                 atlas_page_index: glyph.page_index,
+                run: None,
             };
             (text_glyph, glyph.advance)
         }
@@ -172,6 +363,7 @@ fn build_glyph(metrics: &mut GlyphMetrics, x: f32, y: f32, character: char) -> (
                 bounds: rect,
                 tex_coords: [Vector2::default(); 4],
                 atlas_page_index: 0,
+                run: None,
             };
             (text_glyph, rect.w())
         }
@@ -211,6 +403,11 @@ pub struct FormattedText {
     #[visit(skip)]
     #[reflect(hidden)]
     glyphs: Vec<TextGlyph>,
+    // Advances of every char of the text, calculated by text shaping. Empty if the text does not
+    // need shaping.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    char_advances: Vec<f32>,
     vertical_alignment: InheritableVariable<VerticalAlignment>,
     horizontal_alignment: InheritableVariable<HorizontalAlignment>,
     brush: InheritableVariable<Brush>,
@@ -225,6 +422,23 @@ pub struct FormattedText {
     pub shadow_brush: InheritableVariable<Brush>,
     pub shadow_dilation: InheritableVariable<f32>,
     pub shadow_offset: InheritableVariable<Vector2<f32>>,
+    #[visit(optional)]
+    runs: InheritableVariable<Vec<TextRun>>,
+    /// Fonts that are used for the characters that are missing in the main font (or in the font
+    /// of a run), the first font that has a character wins.
+    #[visit(optional)]
+    fallback_fonts: InheritableVariable<Vec<FontResource>>,
+    // Runs with overlapping runs merged and fallback fonts assigned, glyphs refer to them.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    resolved_runs: Vec<TextRun>,
+    // Inline images and hyperlink regions of the styled text.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    images: Vec<TextImage>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    links: Vec<TextLink>,
 }
 
 impl FormattedText {
@@ -384,7 +598,7 @@ impl FormattedText {
             if offset >= position.offset {
                 break;
             }
-            if let Some(advance) = raw_text.get(char_index).map(|c| metrics.advance(*c)) {
+            if let Some(advance) = self.char_advance(&mut metrics, raw_text, char_index) {
                 caret_pos.x += advance;
             } else {
                 caret_pos.x += metrics.size;
@@ -422,7 +636,7 @@ impl FormattedText {
         let mut min_index: usize = 0;
         let raw_text = self.get_raw_text();
         for (offset, char_index) in (line.begin..line.end).enumerate() {
-            if let Some(advance) = raw_text.get(char_index).map(|c| metrics.advance(*c)) {
+            if let Some(advance) = self.char_advance(&mut metrics, raw_text, char_index) {
                 glyph_x += advance;
             } else {
                 glyph_x += self.font_size();
@@ -439,10 +653,74 @@ impl FormattedText {
         }
     }
 
+    fn char_advance(&self, metrics: &mut GlyphMetrics, text: &[char], index: usize) -> Option<f32> {
+        if self.char_advances.len() == text.len() {
+            self.char_advances.get(index).cloned()
+        } else {
+            text.get(index).map(|c| metrics.advance(*c))
+        }
+    }
+
     pub fn get_glyphs(&self) -> &[TextGlyph] {
         &self.glyphs
     }
 
+    /// Returns inline images of the text, calculated by the last [`Self::build`] call.
+    pub fn get_images(&self) -> &[TextImage] {
+        &self.images
+    }
+
+    /// Returns hyperlink regions of the text, calculated by the last [`Self::build`] call.
+    pub fn get_links(&self) -> &[TextLink] {
+        &self.links
+    }
+
+    /// Returns an identifier of a hyperlink at the given point in local coordinates, if any.
+    pub fn link_at(&self, point: Vector2<f32>) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|link| link.bounds.contains(point))
+            .and_then(|link| self.resolved_runs.get(link.run))
+            .and_then(|run| run.link.as_deref())
+    }
+
+    /// Returns a font, a font size and a brush of the resolved style run with the given index (see
+    /// [`TextGlyph::run`]). The properties of the text are used for everything that is not
+    /// specified by the run.
+    pub fn run_style(&self, run: Option<usize>) -> (FontResource, f32, Brush) {
+        let run = run.and_then(|index| self.resolved_runs.get(index));
+        (
+            run.and_then(|run| run.font.clone())
+                .unwrap_or_else(|| self.get_font()),
+            run.and_then(|run| run.font_size)
+                .unwrap_or_else(|| self.font_size()),
+            run.and_then(|run| run.brush.clone())
+                .unwrap_or_else(|| self.brush()),
+        )
+    }
+
+    pub fn runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /// Sets style runs of the text. Runs are not adjusted when the text is edited, so they're
+    /// meant to be used with the text that is set as a whole.
+    pub fn set_runs(&mut self, runs: Vec<TextRun>) -> &mut Self {
+        self.runs.set_value_and_mark_modified(runs);
+        self
+    }
+
+    pub fn fallback_fonts(&self) -> &[FontResource] {
+        &self.fallback_fonts
+    }
+
+    /// Sets a chain of fallback fonts, that will be used to render the characters, that are missing
+    /// in the main font. It allows to mix scripts (for example, Latin and CJK) in the same text.
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontResource>) -> &mut Self {
+        self.fallback_fonts.set_value_and_mark_modified(fonts);
+        self
+    }
+
     pub fn get_font(&self) -> FontResource {
         (*self.font).clone()
     }
@@ -523,8 +801,8 @@ impl FormattedText {
             };
             for index in range {
                 // We can't trust the range values, check to prevent panic.
-                if let Some(glyph) = self.text.get(index) {
-                    width += metrics.advance(*glyph);
+                if let Some(advance) = self.char_advance(&mut metrics, &self.text, index) {
+                    width += advance;
                 }
             }
         }
@@ -594,8 +872,78 @@ impl FormattedText {
         self
     }
 
+    // Aligns lines according to desired alignment.
+    fn align_lines(lines: &mut [TextLine], alignment: HorizontalAlignment, width: f32) {
+        for line in lines.iter_mut() {
+            match alignment {
+                HorizontalAlignment::Left => line.x_offset = 0.0,
+                HorizontalAlignment::Center => {
+                    if width.is_infinite() {
+                        line.x_offset = 0.0;
+                    } else {
+                        line.x_offset = 0.5 * (width - line.width).max(0.0);
+                    }
+                }
+                HorizontalAlignment::Right => {
+                    if width.is_infinite() {
+                        line.x_offset = 0.0;
+                    } else {
+                        line.x_offset = (width - line.width).max(0.0)
+                    }
+                }
+                HorizontalAlignment::Stretch => line.x_offset = 0.0,
+            }
+        }
+    }
+
+    fn vertical_offset(&self, total_height: f32) -> f32 {
+        match *self.vertical_alignment {
+            VerticalAlignment::Top => 0.0,
+            VerticalAlignment::Center => {
+                if self.constraint.y.is_infinite() {
+                    0.0
+                } else {
+                    (self.constraint.y - total_height).max(0.0) * 0.5
+                }
+            }
+            VerticalAlignment::Bottom => {
+                if self.constraint.y.is_infinite() {
+                    0.0
+                } else {
+                    (self.constraint.y - total_height).max(0.0)
+                }
+            }
+            VerticalAlignment::Stretch => 0.0,
+        }
+    }
+
     pub fn build(&mut self) -> Vector2<f32> {
-        let mut font_state = self.font.state();
+        self.images.clear();
+        self.links.clear();
+        self.resolved_runs.clear();
+
+        let needs_shaping = self.mask_char.is_none() && needs_shaping(&self.text);
+        if self.mask_char.is_none()
+            && (!self.runs.is_empty() || (!needs_shaping && self.has_missing_glyphs(&self.font)))
+        {
+            return self.build_styled();
+        }
+
+        // Shaped text is rendered using a single font, so the first font of the fallback chain
+        // that has every character is used.
+        let font = if needs_shaping {
+            self.shaping_font()
+        } else {
+            self.get_font()
+        };
+        if font != *self.font {
+            self.resolved_runs.push(TextRun {
+                font: Some(font.clone()),
+                ..Default::default()
+            });
+        }
+
+        let mut font_state = font.state();
         let Some(font) = font_state.data() else {
             return Default::default();
         };
@@ -605,6 +953,20 @@ impl FormattedText {
         };
         let line_height: f32 = metrics.ascender();
 
+        // Complex scripts require text shaping, which gives the advance of each char.
+        let string = if needs_shaping {
+            Some(self.text.iter().collect::<String>())
+        } else {
+            None
+        };
+        let shaper = string.as_deref().map(Shaper::new);
+        self.char_advances = shaper
+            .as_ref()
+            .and_then(|shaper| shaper.advances(metrics.font, metrics.size))
+            .unwrap_or_default();
+        // Shaping is impossible if the font has no raw data.
+        let shaper = shaper.filter(|_| !self.char_advances.is_empty());
+
         self.lines.clear();
         let sink = WrapSink {
             lines: &mut self.lines,
@@ -622,6 +984,14 @@ impl FormattedText {
                 ),
                 WrapMode::Word => wrap_mask(WordWrap::new(sink), self.text.len(), mask, advance),
             }
+        } else if shaper.is_some() {
+            let text = self.text.as_slice();
+            let advances = self.char_advances.as_slice();
+            match *self.wrap {
+                WrapMode::NoWrap => wrap_shaped(NoWrap::new(sink), text, advances),
+                WrapMode::Letter => wrap_shaped(LetterWrap::new(sink), text, advances),
+                WrapMode::Word => wrap_shaped(WordWrap::new(sink), text, advances),
+            }
         } else {
             match *self.wrap {
                 WrapMode::NoWrap => wrap(NoWrap::new(sink), &mut metrics, self.text.as_slice()),
@@ -631,49 +1001,16 @@ impl FormattedText {
         }
 
         let total_height = line_height * self.lines.len() as f32;
-        // Align lines according to desired alignment.
-        for line in self.lines.iter_mut() {
-            match *self.horizontal_alignment {
-                HorizontalAlignment::Left => line.x_offset = 0.0,
-                HorizontalAlignment::Center => {
-                    if self.constraint.x.is_infinite() {
-                        line.x_offset = 0.0;
-                    } else {
-                        line.x_offset = 0.5 * (self.constraint.x - line.width).max(0.0);
-                    }
-                }
-                HorizontalAlignment::Right => {
-                    if self.constraint.x.is_infinite() {
-                        line.x_offset = 0.0;
-                    } else {
-                        line.x_offset = (self.constraint.x - line.width).max(0.0)
-                    }
-                }
-                HorizontalAlignment::Stretch => line.x_offset = 0.0,
-            }
-        }
+        Self::align_lines(
+            &mut self.lines,
+            *self.horizontal_alignment,
+            self.constraint.x,
+        );
 
         // Generate glyphs for each text line.
         self.glyphs.clear();
 
-        let cursor_y_start = match *self.vertical_alignment {
-            VerticalAlignment::Top => 0.0,
-            VerticalAlignment::Center => {
-                if self.constraint.y.is_infinite() {
-                    0.0
-                } else {
-                    (self.constraint.y - total_height).max(0.0) * 0.5
-                }
-            }
-            VerticalAlignment::Bottom => {
-                if self.constraint.y.is_infinite() {
-                    0.0
-                } else {
-                    (self.constraint.y - total_height).max(0.0)
-                }
-            }
-            VerticalAlignment::Stretch => 0.0,
-        };
+        let cursor_y_start = self.vertical_offset(total_height);
 
         let mut y: f32 = cursor_y_start;
         for line in self.lines.iter_mut() {
@@ -684,6 +1021,16 @@ impl FormattedText {
                     self.glyphs.push(glyph);
                     x += advance;
                 }
+            } else if let Some(shaper) = shaper.as_ref() {
+                // Glyphs of the shaped text are already in visual order and have their own
+                // advances.
+                let shaped_glyphs =
+                    shaper.shape_line(metrics.font, metrics.size, line.begin..line.end);
+                for shaped in shaped_glyphs.iter() {
+                    let (glyph, _) = build_shaped_glyph(&mut metrics, x, y, shaped);
+                    self.glyphs.push(glyph);
+                    x += shaped.x_advance;
+                }
             } else {
                 for c in self.text.iter().take(line.end).skip(line.begin).cloned() {
                     match c {
@@ -703,6 +1050,12 @@ impl FormattedText {
             y += line_height;
         }
 
+        if !self.resolved_runs.is_empty() {
+            for glyph in self.glyphs.iter_mut() {
+                glyph.run = Some(0);
+            }
+        }
+
         let size_x = self
             .lines
             .iter()
@@ -713,6 +1066,283 @@ impl FormattedText {
         let size_y = total_height - metrics.descender();
         Vector2::new(size_x, size_y)
     }
+
+    // Checks whether the text has characters, that are missing in the given font, but could be
+    // found in the fallback fonts.
+    fn has_missing_glyphs(&self, font: &FontResource) -> bool {
+        if self.fallback_fonts.is_empty() {
+            return false;
+        }
+        let mut state = font.state();
+        let Some(font) = state.data() else {
+            return false;
+        };
+        self.text
+            .iter()
+            .any(|c| needs_glyph(*c) && !font.has_glyph(*c))
+    }
+
+    fn shaping_font(&self) -> FontResource {
+        std::iter::once(&*self.font)
+            .chain(self.fallback_fonts.iter())
+            .find(|font| {
+                let mut state = font.state();
+                state.data().is_some_and(|font| {
+                    self.text
+                        .iter()
+                        .all(|c| !needs_glyph(*c) || font.has_glyph(*c))
+                })
+            })
+            .unwrap_or(&*self.font)
+            .clone()
+    }
+
+    fn fallback_font(&self, font: &FontResource, c: char) -> Option<FontResource> {
+        if self.fallback_fonts.is_empty() || !needs_glyph(c) || font_has_glyph(font, c) {
+            return None;
+        }
+        self.fallback_fonts
+            .iter()
+            .find(|font| font_has_glyph(font, c))
+            .cloned()
+    }
+
+    fn intern_run(&mut self, run: &TextRun) -> usize {
+        match self.resolved_runs.iter().rposition(|other| other == run) {
+            Some(index) => index,
+            None => {
+                self.resolved_runs.push(run.clone());
+                self.resolved_runs.len() - 1
+            }
+        }
+    }
+
+    // Merges overlapping runs and assigns fallback fonts to the characters, that are missing in the
+    // fonts of their runs. Returns an index of the resolved run of every character.
+    // Resolved runs have no range, so the characters with the same style share the same run.
+    fn resolve_runs(&mut self) -> Vec<Option<usize>> {
+        let length = self.text.len();
+        // Boundaries of the intervals, where the set of covering runs stays the same.
+        let mut bounds = vec![0, length];
+        for run in self.runs.iter() {
+            bounds.push(run.range.start.min(length));
+            bounds.push(run.range.end.min(length));
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut char_runs = vec![None; length];
+        for interval in bounds.windows(2) {
+            let (begin, end) = (interval[0], interval[1]);
+            let mut style: Option<TextRun> = None;
+            for run in self
+                .runs
+                .iter()
+                .filter(|run| run.range.start <= begin && end <= run.range.end)
+            {
+                let style = style.get_or_insert_with(TextRun::default);
+                if run.font.is_some() {
+                    style.font.clone_from(&run.font);
+                }
+                if run.font_size.is_some() {
+                    style.font_size = run.font_size;
+                }
+                if run.brush.is_some() {
+                    style.brush.clone_from(&run.brush);
+                }
+                if run.link.is_some() {
+                    style.link.clone_from(&run.link);
+                }
+                if run.image.is_some() {
+                    style.image.clone_from(&run.image);
+                }
+            }
+
+            let font = style
+                .as_ref()
+                .and_then(|run| run.font.clone())
+                .unwrap_or_else(|| self.get_font());
+            let is_image = style.as_ref().is_some_and(|run| run.image.is_some());
+            let mut interned = None;
+            for (index, char_run) in char_runs.iter_mut().enumerate().take(end).skip(begin) {
+                let fallback = if is_image {
+                    None
+                } else {
+                    self.fallback_font(&font, self.text[index])
+                };
+                *char_run = match (fallback, style.as_ref()) {
+                    (Some(fallback), _) => {
+                        let mut run = style.clone().unwrap_or_default();
+                        run.font = Some(fallback);
+                        Some(self.intern_run(&run))
+                    }
+                    (None, Some(run)) => {
+                        Some(*interned.get_or_insert_with(|| self.intern_run(run)))
+                    }
+                    (None, None) => None,
+                };
+            }
+        }
+        char_runs
+    }
+
+    fn run_image(&self, run: Option<usize>) -> Option<&InlineImage> {
+        run.and_then(|index| self.resolved_runs.get(index))
+            .and_then(|run| run.image.as_ref())
+    }
+
+    // Returns ascender and descender of the run. Images are placed on the baseline, so they have
+    // no descender.
+    fn run_vertical_metrics(&self, run: Option<usize>) -> (f32, f32) {
+        if let Some(image) = self.run_image(run) {
+            return (image.size.y, 0.0);
+        }
+        let (font, size, _) = self.run_style(run);
+        let mut state = font.state();
+        state
+            .data()
+            .map(|font| (font.ascender(size), font.descender(size)))
+            .unwrap_or((size, 0.0))
+    }
+
+    // Styled text is never shaped, every character is laid out using the font of its run and
+    // every line is tall enough to fit the tallest run on it. All runs of a line share the same
+    // baseline.
+    fn build_styled(&mut self) -> Vector2<f32> {
+        let char_runs = self.resolve_runs();
+
+        let mut advances = Vec::with_capacity(self.text.len());
+        for (range, run) in styled_segments(&char_runs, 0..self.text.len()) {
+            if let Some(image) = self.run_image(run) {
+                advances.extend(range.map(|_| image.size.x));
+                continue;
+            }
+            let (font, size, _) = self.run_style(run);
+            let mut state = font.state();
+            match state.data() {
+                Some(font) => {
+                    let mut metrics = GlyphMetrics { font, size };
+                    advances.extend(self.text[range].iter().map(|c| metrics.advance(*c)));
+                }
+                None => advances.extend(range.map(|_| size)),
+            }
+        }
+        self.char_advances = advances;
+
+        self.lines.clear();
+        let sink = WrapSink {
+            lines: &mut self.lines,
+            max_width: self.constraint.x,
+        };
+        let text = self.text.as_slice();
+        let advances = self.char_advances.as_slice();
+        match *self.wrap {
+            WrapMode::NoWrap => wrap_shaped(NoWrap::new(sink), text, advances),
+            WrapMode::Letter => wrap_shaped(LetterWrap::new(sink), text, advances),
+            WrapMode::Word => wrap_shaped(WordWrap::new(sink), text, advances),
+        }
+
+        let mut descenders = Vec::with_capacity(self.lines.len());
+        let mut total_height = 0.0;
+        for line_index in 0..self.lines.len() {
+            let line = self.lines[line_index];
+            let (ascender, descender) = styled_segments(&char_runs, line.begin..line.end)
+                .into_iter()
+                .map(|(_, run)| self.run_vertical_metrics(run))
+                .reduce(|a, b| (a.0.max(b.0), a.1.min(b.1)))
+                .unwrap_or_else(|| self.run_vertical_metrics(None));
+            self.lines[line_index].height = ascender;
+            descenders.push(descender);
+            total_height += ascender;
+        }
+        Self::align_lines(
+            &mut self.lines,
+            *self.horizontal_alignment,
+            self.constraint.x,
+        );
+
+        self.glyphs.clear();
+        let mut y = self.vertical_offset(total_height);
+        for (line_index, descender) in descenders.iter().enumerate() {
+            self.lines[line_index].y_offset = y;
+            let line = self.lines[line_index];
+            let baseline = y + line.height;
+            let mut x = line.x_offset;
+            for (range, run) in styled_segments(&char_runs, line.begin..line.end) {
+                let segment_x = x;
+                if let Some(image) = self.run_image(run).cloned() {
+                    for _ in range {
+                        self.images.push(TextImage {
+                            bounds: Rect::new(
+                                x,
+                                baseline - image.size.y,
+                                image.size.x,
+                                image.size.y,
+                            ),
+                            texture: image.texture.clone(),
+                        });
+                        x += image.size.x;
+                    }
+                } else {
+                    let (font, size, _) = self.run_style(run);
+                    let mut state = font.state();
+                    let mut metrics = state.data().map(|font| GlyphMetrics { font, size });
+                    for index in range {
+                        let c = self.text[index];
+                        if let Some(metrics) = metrics.as_mut().filter(|_| c != '\n') {
+                            let top = baseline - metrics.ascender();
+                            let (mut glyph, _) = build_glyph(metrics, x, top, c);
+                            glyph.run = run;
+                            self.glyphs.push(glyph);
+                        }
+                        x += self.char_advances[index];
+                    }
+                }
+                if let Some(run) = run.filter(|run| self.resolved_runs[*run].link.is_some()) {
+                    self.links.push(TextLink {
+                        bounds: Rect::new(segment_x, y, x - segment_x, line.height - descender),
+                        run,
+                    });
+                }
+            }
+            y += line.height;
+        }
+
+        let size_x = self
+            .lines
+            .iter()
+            .map(|line| line.width)
+            .max_by(f32::total_cmp)
+            .unwrap_or_default();
+        // Minus here is because descender has negative value.
+        let size_y = total_height - descenders.last().cloned().unwrap_or_default();
+        Vector2::new(size_x, size_y)
+    }
+}
+
+// Whitespace and control characters are never drawn, so they don't need a fallback font.
+fn needs_glyph(c: char) -> bool {
+    !c.is_whitespace() && !c.is_control() && c != IMAGE_PLACEHOLDER
+}
+
+fn font_has_glyph(font: &FontResource, c: char) -> bool {
+    font.state().data().is_some_and(|font| font.has_glyph(c))
+}
+
+// Splits the given range of characters into segments that belong to the same style run.
+fn styled_segments(
+    char_runs: &[Option<usize>],
+    range: Range<usize>,
+) -> Vec<(Range<usize>, Option<usize>)> {
+    let mut segments: Vec<(Range<usize>, Option<usize>)> = Vec::new();
+    for index in range {
+        let run = char_runs.get(index).cloned().flatten();
+        match segments.last_mut() {
+            Some((segment, last)) if *last == run => segment.end = index + 1,
+            _ => segments.push((index..index + 1, run)),
+        }
+    }
+    segments
 }
 
 fn wrap<W: TextWrapper>(mut wrapper: W, metrics: &mut GlyphMetrics, text: &[char]) {
@@ -723,6 +1353,13 @@ fn wrap<W: TextWrapper>(mut wrapper: W, metrics: &mut GlyphMetrics, text: &[char
     wrapper.finish();
 }
 
+fn wrap_shaped<W: TextWrapper>(mut wrapper: W, text: &[char], advances: &[f32]) {
+    for (&character, &advance) in text.iter().zip(advances) {
+        wrapper.push(character, advance);
+    }
+    wrapper.finish();
+}
+
 fn wrap_mask<W: TextWrapper>(mut wrapper: W, length: usize, mask_char: char, advance: f32) {
     for _ in 0..length {
         wrapper.push(mask_char, advance);
@@ -744,6 +1381,8 @@ pub struct FormattedTextBuilder {
     shadow_dilation: f32,
     shadow_offset: Vector2<f32>,
     font_size: f32,
+    runs: Vec<TextRun>,
+    fallback_fonts: Vec<FontResource>,
 }
 
 impl FormattedTextBuilder {
@@ -763,6 +1402,8 @@ impl FormattedTextBuilder {
             shadow_dilation: 1.0,
             shadow_offset: Vector2::new(1.0, 1.0),
             font_size: 14.0,
+            runs: Vec::new(),
+            fallback_fonts: Vec::new(),
         }
     }
 
@@ -831,11 +1472,24 @@ impl FormattedTextBuilder {
         self
     }
 
+    /// Sets style runs of the text.
+    pub fn with_runs(mut self, runs: Vec<TextRun>) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Sets a chain of fallback fonts of the text.
+    pub fn with_fallback_fonts(mut self, fonts: Vec<FontResource>) -> Self {
+        self.fallback_fonts = fonts;
+        self
+    }
+
     pub fn build(self) -> FormattedText {
         FormattedText {
             text: self.text.chars().collect::<Vec<char>>().into(),
             lines: Vec::new(),
             glyphs: Vec::new(),
+            char_advances: Vec::new(),
             vertical_alignment: self.vertical_alignment.into(),
             horizontal_alignment: self.horizontal_alignment.into(),
             brush: self.brush.into(),
@@ -848,6 +1502,73 @@ impl FormattedTextBuilder {
             font: self.font.into(),
             shadow_dilation: self.shadow_dilation.into(),
             shadow_offset: self.shadow_offset.into(),
+            runs: self.runs.into(),
+            fallback_fonts: self.fallback_fonts.into(),
+            resolved_runs: Vec::new(),
+            images: Vec::new(),
+            links: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::core::algebra::Vector2;
+    use crate::font::BUILT_IN_FONT;
+    use crate::formatted_text::{
+        styled_segments, FormattedTextBuilder, RichTextBuilder, TextRun, IMAGE_PLACEHOLDER,
+    };
+
+    #[test]
+    fn test_rich_text_builder() {
+        let (text, runs) = RichTextBuilder::new()
+            .push_text("Привет, ")
+            .push_styled("мир", TextRun::default().with_font_size(20.0))
+            .push_image(None, Vector2::new(16.0, 16.0))
+            .push_link("link", "id")
+            .build();
+        assert_eq!(text, format!("Привет, мир{IMAGE_PLACEHOLDER}link"));
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].range, 8..11);
+        assert_eq!(runs[0].font_size, Some(20.0));
+        assert_eq!(runs[1].range, 11..12);
+        assert!(runs[1].image.is_some());
+        assert_eq!(runs[2].range, 12..16);
+        assert_eq!(runs[2].link.as_deref(), Some("id"));
+    }
+
+    #[test]
+    fn test_styled_segments() {
+        let char_runs = [None, None, Some(0), Some(0), Some(1), None];
+        assert_eq!(
+            styled_segments(&char_runs, 0..6),
+            vec![(0..2, None), (2..4, Some(0)), (4..5, Some(1)), (5..6, None)]
+        );
+        assert_eq!(
+            styled_segments(&char_runs, 3..5),
+            vec![(3..4, Some(0)), (4..5, Some(1))]
+        );
+        assert!(styled_segments(&char_runs, 2..2).is_empty());
+    }
+    #[test]
+    fn test_overlapping_runs_merge() {
+        let mut text = FormattedTextBuilder::new(BUILT_IN_FONT.resource())
+            .with_text("abcdef".to_string())
+            .with_runs(vec![
+                TextRun::new(0..4).with_font_size(20.0),
+                TextRun::new(2..6).with_link("id"),
+            ])
+            .build();
+        let char_runs = text.resolve_runs();
+        assert_eq!(
+            char_runs,
+            vec![Some(0), Some(0), Some(1), Some(1), Some(2), Some(2)]
+        );
+        assert_eq!(text.resolved_runs[0].font_size, Some(20.0));
+        assert_eq!(text.resolved_runs[0].link, None);
+        assert_eq!(text.resolved_runs[1].font_size, Some(20.0));
+        assert_eq!(text.resolved_runs[1].link.as_deref(), Some("id"));
+        assert_eq!(text.resolved_runs[2].font_size, None);
+        assert_eq!(text.resolved_runs[2].link.as_deref(), Some("id"));
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Text shaping and bidirectional layout, that are required for complex scripts (Arabic, Hebrew,
+//! Devanagari, etc.).
+
+use crate::font::Font;
+use std::ops::Range;
+use unicode_bidi::BidiInfo;
+
+/// Returns `true` if the text contains characters that require shaping or bidirectional layout.
+/// Scripts before Hebrew in the Unicode table (Latin, Greek, Cyrillic, etc.) are laid out char by
+/// char, which is much faster.
+pub fn needs_shaping(text: &[char]) -> bool {
+    text.iter().any(|c| *c as u32 >= 0x0590)
+}
+
+/// A glyph produced by text shaping.
+#[derive(Clone, Debug)]
+pub struct ShapedGlyph {
+    /// Index of the glyph in the font.
+    pub index: u16,
+    /// Index of the first character of the cluster the glyph belongs to.
+    pub cluster: usize,
+    pub x_advance: f32,
+    pub x_offset: f32,
+    pub y_offset: f32,
+}
+
+fn shape(face: &rustybuzz::Face, text: &str, rtl: bool, scale: f32) -> Vec<ShapedGlyph> {
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+
+    let output = rustybuzz::shape(face, &[], buffer);
+    output
+        .glyph_infos()
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, position)| ShapedGlyph {
+            index: info.glyph_id as u16,
+            cluster: info.cluster as usize,
+            x_advance: position.x_advance as f32 * scale,
+            x_offset: position.x_offset as f32 * scale,
+            y_offset: position.y_offset as f32 * scale,
+        })
+        .collect()
+}
+
+/// Shapes text and splits it in runs with the same direction.
+pub struct Shaper<'a> {
+    text: &'a str,
+    // Byte offset of every char in the text plus the length of the text.
+    char_to_byte: Vec<usize>,
+    bidi: BidiInfo<'a>,
+}
+
+impl<'a> Shaper<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut char_to_byte = text.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        char_to_byte.push(text.len());
+        Self {
+            text,
+            char_to_byte,
+            bidi: BidiInfo::new(text, None),
+        }
+    }
+
+    fn byte_to_char(&self, byte: usize) -> usize {
+        self.char_to_byte
+            .partition_point(|offset| *offset <= byte)
+            .saturating_sub(1)
+    }
+
+    /// Splits the given range of chars of a line in runs with the same direction. Runs are sorted
+    /// in visual order (left to right), each run is defined by its range of chars and a flag, that
+    /// tells whether the run is right-to-left or not.
+    pub fn visual_runs(&self, line: Range<usize>) -> Vec<(Range<usize>, bool)> {
+        let bytes = self.char_to_byte[line.start]..self.char_to_byte[line.end];
+        if bytes.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(paragraph) = self
+            .bidi
+            .paragraphs
+            .iter()
+            .find(|paragraph| paragraph.range.contains(&bytes.start))
+        else {
+            return Vec::new();
+        };
+
+        let bytes = bytes.start..bytes.end.min(paragraph.range.end);
+        let (levels, runs) = self.bidi.visual_runs(paragraph, bytes);
+        runs.into_iter()
+            .map(|run| {
+                let rtl = levels[run.start].is_rtl();
+                (
+                    self.byte_to_char(run.start)..self.byte_to_char(run.end - 1) + 1,
+                    rtl,
+                )
+            })
+            .collect()
+    }
+
+    /// Calculates advance of every char of the text. The advance of a cluster of chars (for example,
+    /// a ligature) is assigned to its first char.
+    pub fn advances(&self, font: &Font, size: f32) -> Option<Vec<f32>> {
+        let face = font.shaping_face()?;
+        let scale = size / face.units_per_em() as f32;
+        let mut advances = vec![0.0; self.char_to_byte.len() - 1];
+        for paragraph in self.bidi.paragraphs.iter() {
+            let (levels, runs) = self.bidi.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                for glyph in shape(&face, &self.text[run.clone()], rtl, scale) {
+                    advances[self.byte_to_char(run.start + glyph.cluster)] += glyph.x_advance;
+                }
+            }
+        }
+        Some(advances)
+    }
+
+    /// Shapes the given range of chars of a line. Glyphs are sorted in visual order (left to
+    /// right).
+    pub fn shape_line(&self, font: &Font, size: f32, line: Range<usize>) -> Vec<ShapedGlyph> {
+        let Some(face) = font.shaping_face() else {
+            return Vec::new();
+        };
+        let scale = size / face.units_per_em() as f32;
+        let mut glyphs = Vec::new();
+        for (run, rtl) in self.visual_runs(line) {
+            let bytes = self.char_to_byte[run.start]..self.char_to_byte[run.end];
+            for mut glyph in shape(&face, &self.text[bytes.clone()], rtl, scale) {
+                glyph.cluster = self.byte_to_char(bytes.start + glyph.cluster);
+                glyphs.push(glyph);
+            }
+        }
+        glyphs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::formatted_text::shaping::{needs_shaping, Shaper};
+
+    #[test]
+    fn test_bidi_visual_runs() {
+        assert!(!needs_shaping(&"Hello".chars().collect::<Vec<_>>()));
+        assert!(needs_shaping(&"שלום".chars().collect::<Vec<_>>()));
+
+        // Left-to-right paragraph.
+        let shaper = Shaper::new("ab שלום");
+        assert_eq!(shaper.visual_runs(0..7), vec![(0..3, false), (3..7, true)]);
+
+        // Right-to-left paragraph, the latin word is placed on the left side.
+        let shaper = Shaper::new("שלום cd");
+        assert_eq!(shaper.visual_runs(0..7), vec![(5..7, false), (0..5, true)]);
+    }
+}
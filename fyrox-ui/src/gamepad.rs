@@ -0,0 +1,264 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Gamepad (controller) support for the user interface. See [`GamepadNavigation`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{algebra::Vector2, pool::Handle},
+    message::{ButtonState, KeyCode, MessageDirection, MouseButton, OsEvent},
+    widget::WidgetMessage,
+    UiNode, UserInterface,
+};
+
+/// A set of gamepad buttons, that are used by the user interface.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// Up button of the directional pad.
+    DPadUp,
+    /// Down button of the directional pad.
+    DPadDown,
+    /// Left button of the directional pad.
+    DPadLeft,
+    /// Right button of the directional pad.
+    DPadRight,
+    /// A button, that "clicks" focused widget (usually `A` or `Cross`).
+    Accept,
+    /// A button, that closes menus, popups, etc. (usually `B` or `Circle`).
+    Cancel,
+}
+
+/// Virtual cursor is a cursor, that is controlled by an analog stick of a gamepad. It emits
+/// synthesized pointer events, so the widgets could not tell the difference between the virtual
+/// cursor and a real mouse.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualCursor {
+    /// Current position of the cursor in screen coordinates.
+    pub position: Vector2<f32>,
+    /// Speed of the cursor in pixels per second.
+    pub speed: f32,
+    /// Stick deflection, below which the stick is considered to be in the neutral position.
+    pub dead_zone: f32,
+    /// A widget, that is used to visualize the cursor (optional). It will be moved to the position of
+    /// the cursor every time when the cursor moves.
+    pub widget: Handle<UiNode>,
+}
+
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        Self {
+            position: Default::default(),
+            speed: 800.0,
+            dead_zone: 0.15,
+            widget: Default::default(),
+        }
+    }
+}
+
+/// Gamepad navigation translates gamepad input into the user interface events. The library does not
+/// read gamepads on its own, it is up to the user to pass gamepad state (gathered with any library
+/// of choice) to [`GamepadNavigation::process_button`] and [`GamepadNavigation::update`].
+///
+/// There are two modes of operation:
+///
+/// - D-pad navigation - directional pad buttons are translated into arrow key presses, so keyboard focus
+/// is moved across widgets by [`crate::navigation::NavigationLayer`]. Accept button is translated into
+/// `Enter` key press and Cancel button into `Escape`.
+/// - Virtual cursor - left analog stick moves a [`VirtualCursor`] and Accept button emits left mouse
+/// button clicks at its position. D-pad navigation works in this mode too.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     core::algebra::Vector2, message::ButtonState,
+/// #     gamepad::{GamepadButton, GamepadNavigation, VirtualCursor}, UserInterface,
+/// # };
+/// fn on_gamepad_input(
+///     navigation: &mut GamepadNavigation,
+///     ui: &mut UserInterface,
+///     left_stick: Vector2<f32>,
+///     accept_pressed: bool,
+///     dt: f32,
+/// ) {
+///     if navigation.virtual_cursor.is_none() {
+///         navigation.virtual_cursor = Some(VirtualCursor::default());
+///     }
+///
+///     navigation.update(ui, left_stick, dt);
+///
+///     if accept_pressed {
+///         navigation.process_button(ui, GamepadButton::Accept, ButtonState::Pressed);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GamepadNavigation {
+    /// Optional virtual cursor. `None` means that only D-pad navigation is used.
+    pub virtual_cursor: Option<VirtualCursor>,
+}
+
+impl GamepadNavigation {
+    /// Creates new gamepad navigation with D-pad navigation only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new gamepad navigation with the given virtual cursor.
+    pub fn with_virtual_cursor(virtual_cursor: VirtualCursor) -> Self {
+        Self {
+            virtual_cursor: Some(virtual_cursor),
+        }
+    }
+
+    /// Translates gamepad button state change into the user interface events. Returns `true` if
+    /// the event was processed by the user interface.
+    pub fn process_button(
+        &mut self,
+        ui: &mut UserInterface,
+        button: GamepadButton,
+        state: ButtonState,
+    ) -> bool {
+        let key = match button {
+            GamepadButton::DPadUp => KeyCode::ArrowUp,
+            GamepadButton::DPadDown => KeyCode::ArrowDown,
+            GamepadButton::DPadLeft => KeyCode::ArrowLeft,
+            GamepadButton::DPadRight => KeyCode::ArrowRight,
+            GamepadButton::Accept => {
+                if self.virtual_cursor.is_some() {
+                    return ui.process_os_event(&OsEvent::MouseInput {
+                        button: MouseButton::Left,
+                        state,
+                    });
+                }
+                KeyCode::Enter
+            }
+            GamepadButton::Cancel => KeyCode::Escape,
+        };
+
+        ui.process_os_event(&OsEvent::KeyboardInput {
+            button: key,
+            state,
+            text: Default::default(),
+        })
+    }
+
+    /// Moves virtual cursor (if any) using the given analog stick deflection. Stick deflection must
+    /// be in `[-1.0; 1.0]` range on each axis, positive Y axis points down. Returns `true` if the
+    /// cursor was moved.
+    pub fn update(&mut self, ui: &mut UserInterface, stick: Vector2<f32>, dt: f32) -> bool {
+        let Some(cursor) = self.virtual_cursor.as_mut() else {
+            return false;
+        };
+
+        if stick.norm() <= cursor.dead_zone {
+            return false;
+        }
+
+        let screen_size = ui.screen_size();
+        let new_position = cursor.position + stick.scale(cursor.speed * dt);
+        cursor.position = Vector2::new(
+            new_position.x.clamp(0.0, screen_size.x),
+            new_position.y.clamp(0.0, screen_size.y),
+        );
+
+        ui.process_os_event(&OsEvent::CursorMoved {
+            position: cursor.position,
+        });
+
+        if cursor.widget.is_some() {
+            ui.send_message(WidgetMessage::desired_position(
+                cursor.widget,
+                MessageDirection::ToWidget,
+                ui.screen_to_root_canvas_space(cursor.position),
+            ));
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        button::ButtonBuilder,
+        core::algebra::Vector2,
+        gamepad::{GamepadButton, GamepadNavigation, VirtualCursor},
+        message::ButtonState,
+        navigation::NavigationLayerBuilder,
+        stack_panel::StackPanelBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+
+    #[test]
+    fn test_dpad_navigation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let ctx = &mut ui.build_ctx();
+        let first = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .with_height(30.0)
+                .with_tab_index(Some(0)),
+        )
+        .build(ctx);
+        let second = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .with_height(30.0)
+                .with_tab_index(Some(1)),
+        )
+        .build(ctx);
+        NavigationLayerBuilder::new(
+            WidgetBuilder::new().with_child(
+                StackPanelBuilder::new(WidgetBuilder::new().with_child(first).with_child(second))
+                    .build(ctx),
+            ),
+        )
+        .with_initial_focus(first)
+        .build(ctx);
+
+        ui.update(screen_size, 0.0, &Default::default());
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, first);
+
+        let mut navigation = GamepadNavigation::new();
+        navigation.process_button(&mut ui, GamepadButton::DPadDown, ButtonState::Pressed);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, second);
+    }
+
+    #[test]
+    fn test_virtual_cursor() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let mut navigation = GamepadNavigation::with_virtual_cursor(VirtualCursor {
+            position: Vector2::new(100.0, 100.0),
+            speed: 100.0,
+            ..Default::default()
+        });
+
+        // Inside the dead zone.
+        assert!(!navigation.update(&mut ui, Vector2::new(0.1, 0.0), 1.0));
+
+        assert!(navigation.update(&mut ui, Vector2::new(1.0, 0.0), 1.0));
+        assert_eq!(ui.cursor_position(), Vector2::new(200.0, 100.0));
+    }
+}
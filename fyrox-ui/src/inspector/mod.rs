@@ -350,6 +350,23 @@ impl PropertyChanged {
         path
     }
 
+    /// Applies the change to the given object, which usually is the same object that was used to
+    /// create the inspector context. This is the simplest way of keeping an inspected object in sync
+    /// with its inspector. On success, returns a value that was removed from a collection (if any),
+    /// otherwise returns the action that could not be applied.
+    pub fn apply(
+        &self,
+        target: &mut dyn Reflect,
+    ) -> Result<Option<Box<dyn Reflect>>, PropertyAction> {
+        let mut result = Err(PropertyAction::Revert);
+        PropertyAction::from_field_kind(&self.value).apply(
+            &self.path(),
+            target,
+            &mut |action_result| result = action_result,
+        );
+        result
+    }
+
     pub fn is_inheritable(&self) -> bool {
         match self.value {
             FieldKind::Collection(ref collection_changed) => match **collection_changed {
@@ -473,6 +490,52 @@ pub trait InspectorEnvironment: Any + Send + Sync {
 ///         .build(ctx)
 /// }
 /// ```
+///
+/// ## Editing
+///
+/// The inspector does not modify the inspected object by itself, instead it sends
+/// [`InspectorMessage::PropertyChanged`] message every time when a user changes a value. The simplest
+/// way of applying such changes is to use [`PropertyChanged::apply`] method. A context for an
+/// object could also be created with [`InspectorContext::for_object`] which uses sensible defaults,
+/// which makes the inspector a handy tool for quick debug tweak panels or settings screens:
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     core::{pool::Handle, reflect::prelude::*},
+/// #     inspector::{
+/// #         editors::PropertyEditorDefinitionContainer, InspectorBuilder, InspectorContext,
+/// #         InspectorMessage,
+/// #     },
+/// #     message::{MessageDirection, UiMessage},
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode,
+/// # };
+/// # use std::sync::Arc;
+/// #[derive(Reflect, Debug, Clone, Default)]
+/// struct GraphicsSettings {
+///     fov: f32,
+///     vsync: bool,
+/// }
+///
+/// fn create_settings_panel(settings: &GraphicsSettings, ctx: &mut BuildContext) -> Handle<UiNode> {
+///     let context = InspectorContext::for_object(
+///         settings,
+///         ctx,
+///         Arc::new(PropertyEditorDefinitionContainer::with_default_editors()),
+///     );
+///     InspectorBuilder::new(WidgetBuilder::new())
+///         .with_context(context)
+///         .build(ctx)
+/// }
+///
+/// fn on_ui_message(settings: &mut GraphicsSettings, panel: Handle<UiNode>, message: &UiMessage) {
+///     if let Some(InspectorMessage::PropertyChanged(args)) = message.data() {
+///         if message.destination() == panel && message.direction() == MessageDirection::FromWidget {
+///             let _ = args.apply(settings);
+///         }
+///     }
+/// }
+/// ```
 #[derive(Default, Clone, Visit, Reflect, Debug, ComponentProvider)]
 pub struct Inspector {
     pub widget: Widget,
@@ -974,6 +1037,29 @@ impl InspectorContext {
         }
     }
 
+    /// Same as [`Self::from_object`], but uses sensible defaults for most of the parameters. This
+    /// is the easiest way of creating a property grid for an arbitrary object, for example for debug
+    /// tweak panels or settings screens. Changes made by a user are reported by the inspector in
+    /// [`InspectorMessage::PropertyChanged`] messages and could be applied back to the object using
+    /// [`PropertyChanged::apply`].
+    pub fn for_object(
+        object: &dyn Reflect,
+        ctx: &mut BuildContext,
+        definition_container: Arc<PropertyEditorDefinitionContainer>,
+    ) -> Self {
+        Self::from_object(
+            object,
+            ctx,
+            definition_container,
+            None,
+            1,
+            0,
+            false,
+            Default::default(),
+            150.0,
+        )
+    }
+
     /// Update the widgest to reflect the value of the given object.
     /// We will iterate through the fields and find the appropriate [PropertyEditorDefinition](editors::PropertyEditorDefinition)
     /// for each field. We call [create_message](editors::PropertyEditorDefinition::create_message) to get each property editor
@@ -1197,3 +1283,94 @@ impl InspectorBuilder {
         ctx.add_node(UiNode::new(canvas))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, reflect::prelude::*},
+        inspector::{
+            editors::PropertyEditorDefinitionContainer, FieldKind, InspectorBuilder,
+            InspectorContext, InspectorMessage, PropertyChanged,
+        },
+        message::MessageDirection,
+        numeric::NumericUpDownMessage,
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use std::{any::TypeId, sync::Arc};
+
+    #[derive(Reflect, Debug, Clone, Default)]
+    struct Settings {
+        volume: f32,
+        name: String,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn test_property_changed_apply() {
+        let mut settings = Settings {
+            values: vec![1, 2, 3],
+            ..Default::default()
+        };
+
+        let changed = PropertyChanged {
+            name: "volume".to_string(),
+            owner_type_id: TypeId::of::<Settings>(),
+            value: FieldKind::object(0.5f32),
+        };
+        assert!(changed.apply(&mut settings).is_ok());
+        assert_eq!(settings.volume, 0.5);
+
+        let changed = PropertyChanged {
+            name: "name".to_string(),
+            owner_type_id: TypeId::of::<Settings>(),
+            value: FieldKind::object("Player".to_string()),
+        };
+        assert!(changed.apply(&mut settings).is_ok());
+        assert_eq!(settings.name, "Player");
+
+        // Type mismatch must not modify the object.
+        let changed = PropertyChanged {
+            name: "volume".to_string(),
+            owner_type_id: TypeId::of::<Settings>(),
+            value: FieldKind::object(1u32),
+        };
+        assert!(changed.apply(&mut settings).is_err());
+        assert_eq!(settings.volume, 0.5);
+    }
+
+    #[test]
+    fn test_inspector_for_object() {
+        let mut ui = UserInterface::new(Vector2::new(200.0, 200.0));
+        let mut settings = Settings::default();
+
+        let ctx = &mut ui.build_ctx();
+        let context = InspectorContext::for_object(
+            &settings,
+            ctx,
+            Arc::new(PropertyEditorDefinitionContainer::with_default_editors()),
+        );
+        let volume_editor = context.find_property_editor_widget("volume");
+        assert!(volume_editor.is_some());
+        let inspector = InspectorBuilder::new(WidgetBuilder::new())
+            .with_context(context)
+            .build(ctx);
+
+        // Emulate user input.
+        ui.send_message(NumericUpDownMessage::value(
+            volume_editor,
+            MessageDirection::FromWidget,
+            0.25f32,
+        ));
+
+        while let Some(message) = ui.poll_message() {
+            if let Some(InspectorMessage::PropertyChanged(args)) = message.data() {
+                if message.destination() == inspector {
+                    args.apply(&mut settings).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(settings.volume, 0.25);
+    }
+}
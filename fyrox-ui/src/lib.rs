@@ -208,16 +208,21 @@ use message::TouchPhase;
 pub mod absm;
 mod alignment;
 pub mod animation;
+pub mod backend;
 pub mod bit;
 pub mod border;
 pub mod brush;
 mod build;
 pub mod button;
 pub mod canvas;
+pub mod captions;
+pub mod chart;
 pub mod check_box;
+pub mod code_editor;
 pub mod color;
 mod control;
 pub mod curve;
+pub mod debug_menu;
 pub mod decorator;
 pub mod dock;
 pub mod draw;
@@ -227,12 +232,14 @@ pub mod expander;
 pub mod file_browser;
 pub mod font;
 pub mod formatted_text;
+pub mod gamepad;
 pub mod grid;
 pub mod image;
 pub mod inspector;
 pub mod key;
 pub mod list_view;
 pub mod loader;
+pub mod localization;
 pub mod matrix;
 pub mod menu;
 pub mod message;
@@ -240,6 +247,7 @@ pub mod messagebox;
 pub mod navigation;
 pub mod nine_patch;
 mod node;
+pub mod node_graph;
 pub mod numeric;
 pub mod path;
 pub mod popup;
@@ -262,6 +270,7 @@ pub mod utils;
 pub mod uuid;
 pub mod vec;
 pub mod vector_image;
+pub mod virtual_list_view;
 pub mod widget;
 pub mod window;
 pub mod wrap_panel;
@@ -284,11 +293,14 @@ use crate::{
     draw::{CommandTexture, Draw, DrawingContext},
     font::FontResource,
     font::BUILT_IN_FONT,
+    localization::LocalizationManager,
     message::{
         ButtonState, CursorIcon, KeyboardModifiers, MessageDirection, MouseButton, OsEvent,
         UiMessage,
     },
     popup::{Placement, PopupMessage},
+    text::{Text, TextMessage},
+    text_box::TextBox,
     widget::{Widget, WidgetBuilder, WidgetMessage},
 };
 use copypasta::ClipboardContext;
@@ -615,6 +627,7 @@ pub enum LayoutEvent {
     MeasurementInvalidated(Handle<UiNode>),
     ArrangementInvalidated(Handle<UiNode>),
     VisibilityChanged(Handle<UiNode>),
+    TransformChanged(Handle<UiNode>),
 }
 
 #[derive(Clone, Debug, Visit, Reflect, Default)]
@@ -678,6 +691,7 @@ pub struct UiUpdateSwitches {
 #[derive(Reflect, Debug)]
 pub struct UserInterface {
     screen_size: Vector2<f32>,
+    scale: f32,
     nodes: Pool<UiNode, WidgetContainer>,
     #[reflect(hidden)]
     drawing_context: DrawingContext,
@@ -711,8 +725,23 @@ pub struct UserInterface {
     #[reflect(hidden)]
     layout_events_sender: Sender<LayoutEvent>,
     need_update_global_transform: bool,
+    // Roots of subtrees, that need their visual transforms and clip bounds to be recalculated.
+    // Only these subtrees are updated after the layout pass, unless the entire tree needs an
+    // update.
+    #[reflect(hidden)]
+    layout_dirty_roots: RefCell<FxHashSet<Handle<UiNode>>>,
+    // Nodes, that were invalidated during the processing of layout events. Ancestors of such
+    // nodes are already invalidated, so there's no need to walk up the tree again.
+    #[reflect(hidden)]
+    measure_invalidated: FxHashSet<Handle<UiNode>>,
+    #[reflect(hidden)]
+    arrange_invalidated: FxHashSet<Handle<UiNode>>,
     #[reflect(hidden)]
     pub default_font: FontResource,
+    /// Localization manager of the UI, it holds string tables of every locale and a chain of
+    /// fallback fonts. See [`LocalizationManager`] docs for more info.
+    #[reflect(hidden)]
+    pub localization: LocalizationManager,
     #[reflect(hidden)]
     double_click_entries: FxHashMap<MouseButton, DoubleClickEntry>,
     pub double_click_time_slice: f32,
@@ -751,6 +780,7 @@ impl Visit for UserInterface {
             for node in self.nodes.iter() {
                 self.methods_registry.register(node.deref());
             }
+            self.need_update_global_transform = true;
         }
 
         Ok(())
@@ -770,6 +800,7 @@ impl Clone for UserInterface {
 
         Self {
             screen_size: self.screen_size,
+            scale: self.scale,
             nodes,
             drawing_context: self.drawing_context.clone(),
             visual_debug: self.visual_debug,
@@ -794,7 +825,11 @@ impl Clone for UserInterface {
             layout_events_receiver,
             layout_events_sender,
             need_update_global_transform: self.need_update_global_transform,
+            layout_dirty_roots: self.layout_dirty_roots.clone(),
+            measure_invalidated: Default::default(),
+            arrange_invalidated: Default::default(),
             default_font: self.default_font.clone(),
+            localization: self.localization.clone(),
             double_click_entries: self.double_click_entries.clone(),
             double_click_time_slice: self.double_click_time_slice,
         }
@@ -1066,6 +1101,7 @@ impl UserInterface {
         let (layout_events_sender, layout_events_receiver) = mpsc::channel();
         let mut ui = UserInterface {
             screen_size,
+            scale: 1.0,
             sender,
             receiver,
             visual_debug: false,
@@ -1090,7 +1126,11 @@ impl UserInterface {
             layout_events_receiver,
             layout_events_sender,
             need_update_global_transform: Default::default(),
+            layout_dirty_roots: Default::default(),
+            measure_invalidated: Default::default(),
+            arrange_invalidated: Default::default(),
             default_font: BUILT_IN_FONT.resource(),
+            localization: Default::default(),
             double_click_entries: Default::default(),
             double_click_time_slice: 0.5, // 500 ms is standard in most operating systems.
         };
@@ -1190,7 +1230,7 @@ impl UserInterface {
                 let visual_transform = if let Some(parent) = parent {
                     parent.visual_transform * widget.render_transform * layout_transform
                 } else {
-                    widget.render_transform * layout_transform
+                    Matrix3::new_scaling(self.scale) * widget.render_transform * layout_transform
                 };
 
                 widget.visual_transform = visual_transform;
@@ -1206,36 +1246,78 @@ impl UserInterface {
         self.screen_size = screen_size;
     }
 
+    /// Returns the size of the screen in the units of the root canvas, that is the screen size divided
+    /// by the UI scale. Use it instead of [`Self::screen_size`] when positioning widgets on the root
+    /// canvas.
+    pub fn logical_screen_size(&self) -> Vector2<f32> {
+        self.screen_size.scale(1.0 / self.scale)
+    }
+
+    /// Returns current global scale of the user interface.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Sets new global scale of the user interface. Every widget will be scaled by the given value, which
+    /// could be used to make the user interface readable on high DPI screens or by people with impaired
+    /// vision. Layout will be recalculated on next update. The value is clamped to `[0.1, 10.0]` range.
+    pub fn set_scale(&mut self, scale: f32) {
+        let scale = scale.clamp(0.1, 10.0);
+        if self.scale != scale {
+            self.scale = scale;
+            self.invalidate_layout();
+            self.need_update_global_transform = true;
+        }
+    }
+
     fn handle_layout_events(&mut self) {
-        fn invalidate_recursive_up(
+        fn invalidate_up(
             nodes: &Pool<UiNode, WidgetContainer>,
-            node: Handle<UiNode>,
+            invalidated: &mut FxHashSet<Handle<UiNode>>,
+            mut node: Handle<UiNode>,
             callback: fn(&UiNode),
         ) {
-            if let Some(node_ref) = nodes.try_borrow(node) {
+            // Stop as soon as an already invalidated node is found, the rest of the path to the
+            // root was invalidated by a previous event.
+            while invalidated.insert(node) {
+                let Some(node_ref) = nodes.try_borrow(node) else {
+                    break;
+                };
                 (callback)(node_ref);
-                if node_ref.parent().is_some() {
-                    invalidate_recursive_up(nodes, node_ref.parent(), callback);
-                }
+                node = node_ref.parent();
             }
         }
 
+        self.measure_invalidated.clear();
+        self.arrange_invalidated.clear();
+
         while let Ok(layout_event) = self.layout_events_receiver.try_recv() {
             match layout_event {
                 LayoutEvent::MeasurementInvalidated(node) => {
-                    invalidate_recursive_up(&self.nodes, node, |node_ref| {
-                        node_ref.measure_valid.set(false)
-                    });
+                    invalidate_up(
+                        &self.nodes,
+                        &mut self.measure_invalidated,
+                        node,
+                        |node_ref| node_ref.measure_valid.set(false),
+                    );
                 }
                 LayoutEvent::ArrangementInvalidated(node) => {
-                    invalidate_recursive_up(&self.nodes, node, |node_ref| {
-                        node_ref.arrange_valid.set(false)
-                    });
-                    self.need_update_global_transform = true;
+                    invalidate_up(
+                        &self.nodes,
+                        &mut self.arrange_invalidated,
+                        node,
+                        |node_ref| node_ref.arrange_valid.set(false),
+                    );
+                    self.layout_dirty_roots.get_mut().insert(node);
                 }
                 LayoutEvent::VisibilityChanged(node) => {
                     self.update_global_visibility(node);
                 }
+                LayoutEvent::TransformChanged(node) => {
+                    // Layout of the node is still valid, only visual transforms of its subtree
+                    // must be updated.
+                    self.layout_dirty_roots.get_mut().insert(node);
+                }
             }
         }
     }
@@ -1251,23 +1333,56 @@ impl UserInterface {
 
         self.handle_layout_events();
 
-        self.measure_node(self.root_canvas, screen_size);
-        let arrangement_changed = self.arrange_node(
+        let logical_screen_size = self.logical_screen_size();
+        self.measure_node(self.root_canvas, logical_screen_size);
+        self.arrange_node(
             self.root_canvas,
-            &Rect::new(0.0, 0.0, screen_size.x, screen_size.y),
+            &Rect::new(0.0, 0.0, logical_screen_size.x, logical_screen_size.y),
         );
 
+        let mut dirty_roots = std::mem::take(self.layout_dirty_roots.get_mut());
+
         if self.need_update_global_transform {
             self.update_visual_transform(self.root_canvas);
-            self.need_update_global_transform = false;
-        }
-
-        if arrangement_changed {
             self.calculate_clip_bounds(
                 self.root_canvas,
                 Rect::new(0.0, 0.0, self.screen_size.x, self.screen_size.y),
             );
+            self.need_update_global_transform = false;
+        } else {
+            for &root in dirty_roots.iter() {
+                let Some(root_ref) = self.nodes.try_borrow(root) else {
+                    continue;
+                };
+
+                // Skip the subtree if it is a part of another dirty subtree.
+                let mut parent = root_ref.parent();
+                let mut is_nested = false;
+                while let Some(parent_ref) = self.nodes.try_borrow(parent) {
+                    if dirty_roots.contains(&parent) {
+                        is_nested = true;
+                        break;
+                    }
+                    parent = parent_ref.parent();
+                }
+                if is_nested {
+                    continue;
+                }
+
+                let parent_bounds = self
+                    .nodes
+                    .try_borrow(root_ref.parent())
+                    .map(|parent_ref| parent_ref.clip_bounds.get())
+                    .unwrap_or_else(|| Rect::new(0.0, 0.0, self.screen_size.x, self.screen_size.y));
+
+                self.update_visual_transform(root);
+                self.calculate_clip_bounds(root, parent_bounds);
+            }
         }
+
+        // Keep the memory of the set to not allocate it on every update.
+        dirty_roots.clear();
+        *self.layout_dirty_roots.get_mut() = dirty_roots;
     }
 
     pub fn update(&mut self, screen_size: Vector2<f32>, dt: f32, switches: &UiUpdateSwitches) {
@@ -1467,7 +1582,9 @@ impl UserInterface {
                 origin.y = origin.y.floor();
             }
 
-            node.commit_arrange(origin, size);
+            if node.commit_arrange(origin, size) {
+                self.layout_dirty_roots.borrow_mut().insert(handle);
+            }
         }
 
         true
@@ -1707,6 +1824,37 @@ impl UserInterface {
         self.sender.send(message).unwrap()
     }
 
+    /// Switches the current locale of the [localization manager](LocalizationManager) and updates
+    /// every text widget, that is bound to a localized string. See [`Text`] docs for more info.
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.localization.set_locale(locale);
+        for (handle, node) in self.nodes.pair_iter() {
+            if let Some(key) = node.cast::<Text>().and_then(|text| text.localization_key()) {
+                self.send_message(TextMessage::localized(
+                    handle,
+                    MessageDirection::ToWidget,
+                    key.to_owned(),
+                ));
+            }
+        }
+    }
+
+    /// Sets a chain of fallback fonts of the [localization manager](LocalizationManager) and
+    /// applies it to every text and text box in the UI. Widgets, that will be created later, will
+    /// use the chain as well.
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontResource>) {
+        for (handle, node) in self.nodes.pair_iter() {
+            if node.cast::<Text>().is_some() || node.cast::<TextBox>().is_some() {
+                self.send_message(TextMessage::fallback_fonts(
+                    handle,
+                    MessageDirection::ToWidget,
+                    fonts.clone(),
+                ));
+            }
+        }
+        self.localization.set_fallback_fonts(fonts);
+    }
+
     // Puts node at the end of children list of a parent node.
     //
     // # Notes
@@ -3422,12 +3570,13 @@ mod test {
     use crate::message::{ButtonState, KeyCode};
     use crate::{
         border::BorderBuilder,
-        core::algebra::{Rotation2, UnitComplex, Vector2},
+        core::algebra::{Matrix3, Rotation2, UnitComplex, Vector2},
         message::MessageDirection,
+        stack_panel::StackPanelBuilder,
         text_box::TextBoxBuilder,
         transform_size,
         widget::{WidgetBuilder, WidgetMessage},
-        OsEvent, UserInterface,
+        OsEvent, Thickness, UserInterface,
     };
     use fyrox_graph::BaseSceneGraph;
 
@@ -3460,6 +3609,86 @@ mod test {
         assert_eq!(actual_position, expected_position);
     }
 
+    #[test]
+    fn test_ui_scale() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        ui.set_scale(2.0);
+        let widget = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(10.0, 20.0))
+                .with_width(100.0)
+                .with_height(50.0),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0, &Default::default());
+        assert_eq!(ui.logical_screen_size(), Vector2::new(500.0, 500.0));
+        assert_eq!(
+            ui.node(widget).screen_bounds(),
+            crate::core::math::Rect::new(20.0, 40.0, 200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_incremental_layout() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let first = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(50.0))
+            .build(&mut ui.build_ctx());
+        let second = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(50.0))
+            .build(&mut ui.build_ctx());
+        StackPanelBuilder::new(WidgetBuilder::new().with_children([first, second]))
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0, &Default::default());
+        assert_eq!(
+            ui.node(second).screen_bounds(),
+            crate::core::math::Rect::new(0.0, 50.0, 100.0, 50.0)
+        );
+
+        // Only the first widget is invalidated, but the second one must be moved too.
+        ui.send_message(WidgetMessage::height(
+            first,
+            MessageDirection::ToWidget,
+            100.0,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0, &Default::default());
+        assert_eq!(
+            ui.node(second).screen_bounds(),
+            crate::core::math::Rect::new(0.0, 100.0, 100.0, 50.0)
+        );
+        assert!(ui.layout_dirty_roots.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_render_transform_updates_visual_transform() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let child = BorderBuilder::new(WidgetBuilder::new().with_width(100.0).with_height(50.0))
+            .build(&mut ui.build_ctx());
+        let parent = BorderBuilder::new(WidgetBuilder::new().with_child(child))
+            .with_stroke_thickness(Thickness::zero())
+            .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0, &Default::default());
+        assert_eq!(
+            ui.node(child).screen_bounds(),
+            crate::core::math::Rect::new(0.0, 0.0, 100.0, 50.0)
+        );
+
+        // Render transform does not affect layout, but the whole subtree must be moved.
+        ui.send_message(WidgetMessage::render_transform(
+            parent,
+            MessageDirection::ToWidget,
+            Matrix3::new_translation(&Vector2::new(10.0, 20.0)),
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0, &Default::default());
+        assert_eq!(
+            ui.node(child).screen_bounds(),
+            crate::core::math::Rect::new(10.0, 20.0, 100.0, 50.0)
+        );
+    }
+
     #[test]
     fn test_keyboard_focus() {
         let screen_size = Vector2::new(1000.0, 1000.0);
@@ -0,0 +1,309 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Localization subsystem. It consists of per-locale string tables and a manager, that picks a
+//! string for the current locale. See [`LocalizationManager`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{core::io::FileLoadError, font::FontResource};
+use fxhash::FxHashMap;
+use fyrox_resource::io::ResourceIo;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+
+/// An error, that may occur during string table loading.
+#[derive(Debug)]
+pub enum LocalizationError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+    /// A line of the table has invalid syntax.
+    Syntax {
+        /// Number of the line (starting from 1).
+        line: usize,
+        /// Description of the error.
+        description: String,
+    },
+}
+
+impl Display for LocalizationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalizationError::Io(err) => write!(f, "An i/o error has occurred: {err:?}"),
+            LocalizationError::Syntax { line, description } => {
+                write!(f, "Syntax error at line {line}: {description}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LocalizationError {}
+
+impl From<FileLoadError> for LocalizationError {
+    fn from(err: FileLoadError) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A set of localized strings of a single locale. String tables could be loaded from a simple text
+/// format, where every line is a `key = value` pair:
+///
+/// ```text
+/// # Comments start with the hash sign.
+/// menu.new_game = New Game
+/// menu.quit = Quit
+/// dialog.greeting = Hello!\nHow are you?
+/// ```
+///
+/// Leading and trailing whitespace of keys and values is ignored, `\n` and `\\` sequences in values
+/// are replaced with a line break and a backslash respectively.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StringTable {
+    /// Locale of the table (for example, `en-US` or `ja`).
+    pub locale: String,
+    /// Localized strings of the table.
+    pub strings: FxHashMap<String, String>,
+}
+
+impl StringTable {
+    /// Creates an empty string table for the given locale.
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            strings: Default::default(),
+        }
+    }
+
+    /// Adds a new string to the table, replacing the previous one with the same key.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.strings.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns a localized string with the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(|value| value.as_str())
+    }
+
+    /// Parses a string table from its textual representation. See [`StringTable`] docs for the
+    /// format description.
+    pub fn parse(locale: impl Into<String>, text: &str) -> Result<Self, LocalizationError> {
+        let mut table = Self::new(locale);
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(LocalizationError::Syntax {
+                    line: index + 1,
+                    description: "expected `key = value` pair".to_string(),
+                });
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(LocalizationError::Syntax {
+                    line: index + 1,
+                    description: "empty key".to_string(),
+                });
+            }
+            table.insert(key, unescape(value.trim()));
+        }
+        Ok(table)
+    }
+
+    /// Loads a string table from the file at the given path.
+    pub async fn from_file(
+        locale: impl Into<String>,
+        path: &Path,
+        io: &dyn ResourceIo,
+    ) -> Result<Self, LocalizationError> {
+        let bytes = io.load_file(path).await?;
+        Self::parse(locale, &String::from_utf8_lossy(&bytes))
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Localization manager holds string tables of every supported locale and picks strings for the
+/// current locale. If a string is missing in the current locale, the fallback locale is used.
+///
+/// The manager is a part of the user interface (see [`crate::UserInterface::localization`]). Text
+/// widgets could be bound to a key of a string table (see
+/// [`crate::text::TextBuilder::with_localized_text`]) and they will be updated automatically when
+/// the locale is changed using [`crate::UserInterface::set_locale`].
+///
+/// The manager also stores a chain of fallback fonts, which is used by text widgets to render
+/// characters that are missing in their main font. It is useful for locales with scripts, that
+/// are not covered by the default font (CJK, Arabic, Hebrew, etc.).
+///
+/// ```rust
+/// use fyrox_ui::localization::{LocalizationManager, StringTable};
+///
+/// let mut manager = LocalizationManager::default();
+/// manager.add_table(StringTable::parse("en", "greeting = Hello").unwrap());
+/// manager.add_table(StringTable::parse("de", "greeting = Hallo").unwrap());
+/// manager.set_fallback_locale("en");
+///
+/// manager.set_locale("de");
+/// assert_eq!(manager.translate("greeting"), Some("Hallo"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LocalizationManager {
+    tables: FxHashMap<String, StringTable>,
+    locale: String,
+    fallback_locale: Option<String>,
+    fallback_fonts: Vec<FontResource>,
+}
+
+impl LocalizationManager {
+    /// Adds a new string table. Strings of the table are merged into the existing table of the same
+    /// locale (if any).
+    pub fn add_table(&mut self, table: StringTable) {
+        match self.tables.get_mut(&table.locale) {
+            Some(existing) => existing.strings.extend(table.strings),
+            None => {
+                self.tables.insert(table.locale.clone(), table);
+            }
+        }
+    }
+
+    /// Returns a string table of the given locale.
+    pub fn table(&self, locale: &str) -> Option<&StringTable> {
+        self.tables.get(locale)
+    }
+
+    /// Returns an iterator over the locales, that have string tables.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(|locale| locale.as_str())
+    }
+
+    /// Sets the current locale. This method does not update existing widgets, use
+    /// [`crate::UserInterface::set_locale`] to switch the language at runtime.
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.locale = locale.into();
+    }
+
+    /// Returns the current locale.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Sets a locale, that is used for the strings missing in the current locale.
+    pub fn set_fallback_locale(&mut self, locale: impl Into<String>) {
+        self.fallback_locale = Some(locale.into());
+    }
+
+    /// Returns a locale, that is used for the strings missing in the current locale.
+    pub fn fallback_locale(&self) -> Option<&str> {
+        self.fallback_locale.as_deref()
+    }
+
+    /// Returns a localized string with the given key in the current locale (or in the fallback
+    /// locale if the string is missing).
+    pub fn translate(&self, key: &str) -> Option<&str> {
+        std::iter::once(self.locale.as_str())
+            .chain(self.fallback_locale.as_deref())
+            .filter_map(|locale| self.tables.get(locale))
+            .find_map(|table| table.get(key))
+    }
+
+    /// Same as [`Self::translate`], but returns the key itself if there's no such string. It makes
+    /// missing translations easy to spot.
+    pub fn translate_or_key<'a>(&'a self, key: &'a str) -> &'a str {
+        self.translate(key).unwrap_or(key)
+    }
+
+    /// Sets a chain of fallback fonts, that will be used by text widgets to render the characters
+    /// missing in their main font.
+    pub fn set_fallback_fonts(&mut self, fonts: Vec<FontResource>) {
+        self.fallback_fonts = fonts;
+    }
+
+    /// Returns a chain of fallback fonts.
+    pub fn fallback_fonts(&self) -> &[FontResource] {
+        &self.fallback_fonts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::localization::{LocalizationError, LocalizationManager, StringTable};
+
+    #[test]
+    fn test_string_table_parsing() {
+        let table = StringTable::parse(
+            "en",
+            "# Comment\n\n menu.play = Play \ndialog.text = First\\nSecond\nequation = a = b\n",
+        )
+        .unwrap();
+        assert_eq!(table.locale, "en");
+        assert_eq!(table.get("menu.play"), Some("Play"));
+        assert_eq!(table.get("dialog.text"), Some("First\nSecond"));
+        assert_eq!(table.get("equation"), Some("a = b"));
+        assert_eq!(table.strings.len(), 3);
+
+        assert!(matches!(
+            StringTable::parse("en", "key = value\ninvalid"),
+            Err(LocalizationError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            StringTable::parse("en", " = value"),
+            Err(LocalizationError::Syntax { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_translation_with_fallback() {
+        let mut manager = LocalizationManager::default();
+        let mut en = StringTable::new("en");
+        en.insert("play", "Play").insert("quit", "Quit");
+        manager.add_table(en);
+        let mut ja = StringTable::new("ja");
+        ja.insert("play", "プレイ");
+        manager.add_table(ja);
+
+        manager.set_locale("ja");
+        assert_eq!(manager.translate("play"), Some("プレイ"));
+        assert_eq!(manager.translate("quit"), None);
+
+        manager.set_fallback_locale("en");
+        assert_eq!(manager.translate("quit"), Some("Quit"));
+        assert_eq!(manager.translate_or_key("missing"), "missing");
+    }
+}
@@ -18,16 +18,22 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-//! A widget, that handles keyboard navigation on its descendant widgets using Tab key. See [`NavigationLayer`]
-//! docs for more info and usage examples.
+//! A widget, that handles keyboard navigation on its descendant widgets using Tab and arrow keys. See
+//! [`NavigationLayer`] docs for more info and usage examples.
 
 #![warn(missing_docs)]
 
 use crate::{
     core::{
-        pool::Handle, reflect::prelude::*, type_traits::prelude::*, variable::InheritableVariable,
+        algebra::{Matrix3, Vector2},
+        math::Rect,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        variable::InheritableVariable,
         visitor::prelude::*,
     },
+    draw::{CommandTexture, Draw, DrawingContext},
     message::{KeyCode, MessageDirection, UiMessage},
     scroll_viewer::{ScrollViewer, ScrollViewerMessage},
     widget::{Widget, WidgetBuilder, WidgetMessage},
@@ -36,8 +42,8 @@ use crate::{
 use fyrox_graph::{BaseSceneGraph, SceneGraph};
 use std::ops::{Deref, DerefMut};
 
-/// A widget, that handles keyboard navigation on its descendant widgets using Tab key. It should
-/// be used as a root widget for an hierarchy, that should support Tab key navigation:
+/// A widget, that handles keyboard navigation on its descendant widgets using Tab and arrow keys. It
+/// should be used as a root widget for an hierarchy, that should support keyboard navigation:
 ///
 /// ```rust
 /// use fyrox_ui::{
@@ -77,7 +83,26 @@ use std::ops::{Deref, DerefMut};
 ///
 /// This example shows how to create a simple confirmation dialog, that allows a user to use Tab key
 /// to cycle from one button to another. A focused button then can be "clicked" using Enter key.
-#[derive(Default, Clone, Visit, Reflect, Debug, TypeUuidProvider, ComponentProvider)]
+///
+/// ## Arrow keys
+///
+/// Arrow keys move keyboard focus to the closest widget (among the ones with tab index) in the respective
+/// direction. It allows to navigate menus and grids of buttons "spatially" without a mouse. Arrow keys
+/// are ignored if they were already handled by a focused widget (for example, a text box uses them to
+/// move its caret). This behaviour can be disabled using [`NavigationLayerBuilder::with_arrow_navigation`].
+///
+/// ## Initial focus
+///
+/// A navigation layer can focus a specific widget when the layer is created and every time when it
+/// becomes visible. Use [`NavigationLayerBuilder::with_initial_focus`] to specify such widget.
+///
+/// ## Focus visual
+///
+/// When keyboard focus is moved using Tab or arrow keys, the navigation layer draws a frame (using
+/// its foreground brush) around the focused widget, so a user could see where the focus is. The
+/// frame is hidden when a mouse button is pressed. This behaviour can be disabled using
+/// [`NavigationLayerBuilder::with_focus_visual`].
+#[derive(Clone, Visit, Reflect, Debug, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "135d347b-5019-4743-906c-6df5c295a3be")]
 pub struct NavigationLayer {
     /// Base widget of the navigation layer.
@@ -86,6 +111,43 @@ pub struct NavigationLayer {
     /// parent widget and send [`crate::scroll_viewer::ScrollViewerMessage::BringIntoView`] message
     /// to a newly focused widget.
     pub bring_into_view: InheritableVariable<bool>,
+    /// A flag, that defines whether arrow keys can be used to move keyboard focus between descendant
+    /// widgets.
+    #[visit(optional)]
+    pub arrow_navigation: InheritableVariable<bool>,
+    /// A widget, that will be focused when the navigation layer is created and every time when it
+    /// becomes visible.
+    #[visit(optional)]
+    pub initial_focus: InheritableVariable<Handle<UiNode>>,
+    /// A flag, that defines whether a frame should be drawn around a widget, that was focused using
+    /// keyboard.
+    #[visit(optional)]
+    pub focus_visual: InheritableVariable<bool>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    focus_visual_target: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    focus_visual_bounds: Option<Rect<f32>>,
+}
+
+// Must match the defaults of the builder, so deserialized layers (with missing fields) behave the
+// same as the ones created from code.
+impl Default for NavigationLayer {
+    fn default() -> Self {
+        Self {
+            widget: Widget {
+                need_update: true,
+                ..Default::default()
+            },
+            bring_into_view: Default::default(),
+            arrow_navigation: true.into(),
+            initial_focus: Default::default(),
+            focus_visual: true.into(),
+            focus_visual_target: Default::default(),
+            focus_visual_bounds: None,
+        }
+    }
 }
 
 crate::define_widget_deref!(NavigationLayer);
@@ -96,67 +158,176 @@ struct OrderedHandle {
     handle: Handle<UiNode>,
 }
 
+impl NavigationLayer {
+    fn tab_list(&self, ui: &UserInterface) -> Vec<OrderedHandle> {
+        // Collect all descendant widgets, that supports Tab navigation.
+        let mut tab_list = Vec::new();
+        for &child in self.children() {
+            for descendant in ui.traverse_handle_iter(child) {
+                let descendant_ref = ui.node(descendant);
+
+                if !*descendant_ref.tab_stop && descendant_ref.is_globally_visible() {
+                    if let Some(tab_index) = *descendant_ref.tab_index {
+                        tab_list.push(OrderedHandle {
+                            tab_index,
+                            handle: descendant,
+                        });
+                    }
+                }
+            }
+        }
+        tab_list.sort_by_key(|entry| entry.tab_index);
+        tab_list
+    }
+
+    fn focus(&self, ui: &UserInterface, handle: Handle<UiNode>) {
+        ui.send_message(WidgetMessage::focus(handle, MessageDirection::ToWidget));
+
+        if *self.bring_into_view {
+            // Find a parent scroll viewer.
+            if let Some((scroll_viewer, _)) = ui.find_component_up::<ScrollViewer>(handle) {
+                ui.send_message(ScrollViewerMessage::bring_into_view(
+                    scroll_viewer,
+                    MessageDirection::ToWidget,
+                    handle,
+                ));
+            }
+        }
+    }
+}
+
+fn screen_center(ui: &UserInterface, handle: Handle<UiNode>) -> Vector2<f32> {
+    let bounds = ui.node(handle).screen_bounds();
+    bounds.position + bounds.size.scale(0.5)
+}
+
+/// Searches for the closest widget in the given direction. Distance along the direction has lower
+/// weight than the distance across the direction, so the widgets that lie on the same row (or column)
+/// are preferred.
+fn find_closest_in_direction(
+    ui: &UserInterface,
+    tab_list: &[OrderedHandle],
+    current: Handle<UiNode>,
+    direction: Vector2<f32>,
+) -> Option<Handle<UiNode>> {
+    let origin = screen_center(ui, current);
+    let mut closest = None;
+    let mut closest_score = f32::MAX;
+    for entry in tab_list.iter().filter(|entry| entry.handle != current) {
+        let offset = screen_center(ui, entry.handle) - origin;
+        let along = offset.dot(&direction);
+        if along <= f32::EPSILON {
+            continue;
+        }
+        let across = (offset - direction.scale(along)).norm();
+        let score = along + 2.0 * across;
+        if score < closest_score {
+            closest_score = score;
+            closest = Some(entry.handle);
+        }
+    }
+    closest
+}
+
 impl Control for NavigationLayer {
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        if let Some(bounds) = self.focus_visual_bounds {
+            // The bounds are in screen space already.
+            drawing_context.transform_stack.push(Matrix3::identity());
+            drawing_context.push_rect(&bounds, 2.0);
+            drawing_context.commit(
+                self.clip_bounds(),
+                self.widget.foreground(),
+                CommandTexture::None,
+                None,
+            );
+            drawing_context.transform_stack.pop();
+        }
+    }
+
+    fn update(&mut self, _dt: f32, ui: &mut UserInterface) {
+        self.focus_visual_bounds = if *self.focus_visual
+            && self.focus_visual_target.is_some()
+            && self.focus_visual_target == ui.keyboard_focus_node
+        {
+            ui.try_get(self.focus_visual_target)
+                .filter(|target| target.is_globally_visible())
+                .map(|target| target.screen_bounds())
+        } else {
+            None
+        };
+    }
+
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
-        if let Some(WidgetMessage::KeyDown(KeyCode::Tab)) = message.data() {
-            // Collect all descendant widgets, that supports Tab navigation.
-            let mut tab_list = Vec::new();
-            for &child in self.children() {
-                for descendant in ui.traverse_handle_iter(child) {
-                    let descendant_ref = ui.node(descendant);
-
-                    if !*descendant_ref.tab_stop && descendant_ref.is_globally_visible() {
-                        if let Some(tab_index) = *descendant_ref.tab_index {
-                            tab_list.push(OrderedHandle {
-                                tab_index,
-                                handle: descendant,
-                            });
+        match message.data() {
+            Some(WidgetMessage::KeyDown(KeyCode::Tab)) => {
+                let tab_list = self.tab_list(ui);
+
+                if !tab_list.is_empty() {
+                    let focused_index = tab_list
+                        .iter()
+                        .position(|entry| entry.handle == ui.keyboard_focus_node)
+                        .unwrap_or_default();
+
+                    let next_focused_node_index = if ui.keyboard_modifiers.shift {
+                        let count = tab_list.len() as isize;
+                        let mut prev = (focused_index as isize).saturating_sub(1);
+                        if prev < 0 {
+                            prev += count;
                         }
+                        (prev % count) as usize
+                    } else {
+                        focused_index.saturating_add(1) % tab_list.len()
+                    };
+
+                    if let Some(entry) = tab_list.get(next_focused_node_index) {
+                        self.focus(ui, entry.handle);
+                        self.focus_visual_target = entry.handle;
                     }
                 }
             }
+            Some(WidgetMessage::KeyDown(key_code))
+                if *self.arrow_navigation && !message.handled() =>
+            {
+                let direction = match key_code {
+                    KeyCode::ArrowLeft => Vector2::new(-1.0, 0.0),
+                    KeyCode::ArrowRight => Vector2::new(1.0, 0.0),
+                    KeyCode::ArrowUp => Vector2::new(0.0, -1.0),
+                    KeyCode::ArrowDown => Vector2::new(0.0, 1.0),
+                    _ => return,
+                };
 
-            if !tab_list.is_empty() {
-                tab_list.sort_by_key(|entry| entry.tab_index);
+                let tab_list = self.tab_list(ui);
 
-                let focused_index = tab_list
+                let next = if tab_list
                     .iter()
-                    .position(|entry| entry.handle == ui.keyboard_focus_node)
-                    .unwrap_or_default();
-
-                let next_focused_node_index = if ui.keyboard_modifiers.shift {
-                    let count = tab_list.len() as isize;
-                    let mut prev = (focused_index as isize).saturating_sub(1);
-                    if prev < 0 {
-                        prev += count;
-                    }
-                    (prev % count) as usize
+                    .any(|entry| entry.handle == ui.keyboard_focus_node)
+                {
+                    find_closest_in_direction(ui, &tab_list, ui.keyboard_focus_node, direction)
                 } else {
-                    focused_index.saturating_add(1) % tab_list.len()
+                    // Nothing is focused yet, start from the first widget.
+                    tab_list.first().map(|entry| entry.handle)
                 };
 
-                if let Some(entry) = tab_list.get(next_focused_node_index) {
-                    ui.send_message(WidgetMessage::focus(
-                        entry.handle,
-                        MessageDirection::ToWidget,
-                    ));
-
-                    if *self.bring_into_view {
-                        // Find a parent scroll viewer.
-                        if let Some((scroll_viewer, _)) =
-                            ui.find_component_up::<ScrollViewer>(entry.handle)
-                        {
-                            ui.send_message(ScrollViewerMessage::bring_into_view(
-                                scroll_viewer,
-                                MessageDirection::ToWidget,
-                                entry.handle,
-                            ));
-                        }
-                    }
+                if let Some(next) = next {
+                    self.focus(ui, next);
+                    self.focus_visual_target = next;
+                    message.set_handled(true);
                 }
             }
+            Some(WidgetMessage::MouseDown { .. }) => {
+                self.focus_visual_target = Handle::NONE;
+            }
+            Some(WidgetMessage::Visibility(true))
+                if message.destination() == self.handle()
+                    && message.direction() == MessageDirection::ToWidget
+                    && ui.try_get(*self.initial_focus).is_some() =>
+            {
+                self.focus(ui, *self.initial_focus);
+            }
+            _ => (),
         }
     }
 }
@@ -165,6 +336,9 @@ impl Control for NavigationLayer {
 pub struct NavigationLayerBuilder {
     widget_builder: WidgetBuilder,
     bring_into_view: bool,
+    arrow_navigation: bool,
+    initial_focus: Handle<UiNode>,
+    focus_visual: bool,
 }
 
 impl NavigationLayerBuilder {
@@ -173,16 +347,139 @@ impl NavigationLayerBuilder {
         Self {
             widget_builder,
             bring_into_view: true,
+            arrow_navigation: true,
+            initial_focus: Handle::NONE,
+            focus_visual: true,
         }
     }
 
+    /// Sets whether arrow keys can be used to move keyboard focus between descendant widgets or not.
+    /// Default is `true`.
+    pub fn with_arrow_navigation(mut self, arrow_navigation: bool) -> Self {
+        self.arrow_navigation = arrow_navigation;
+        self
+    }
+
+    /// Sets whether a frame should be drawn around a widget, that was focused using keyboard, or not.
+    /// Default is `true`.
+    pub fn with_focus_visual(mut self, focus_visual: bool) -> Self {
+        self.focus_visual = focus_visual;
+        self
+    }
+
+    /// Sets a widget, that will be focused when the navigation layer is created and every time when
+    /// it becomes visible.
+    pub fn with_initial_focus(mut self, initial_focus: Handle<UiNode>) -> Self {
+        self.initial_focus = initial_focus;
+        self
+    }
+
     /// Finishes navigation layer widget building and adds the instance to the user interface and
     /// returns its handle.
     pub fn build(self, ui: &mut BuildContext) -> Handle<UiNode> {
         let navigation_layer = NavigationLayer {
-            widget: self.widget_builder.build(),
+            widget: self.widget_builder.with_need_update(true).build(),
             bring_into_view: self.bring_into_view.into(),
+            arrow_navigation: self.arrow_navigation.into(),
+            initial_focus: self.initial_focus.into(),
+            focus_visual: self.focus_visual.into(),
+            focus_visual_target: Handle::NONE,
+            focus_visual_bounds: None,
         };
+
+        if self.initial_focus.is_some() {
+            ui.sender()
+                .send(WidgetMessage::focus(
+                    self.initial_focus,
+                    MessageDirection::ToWidget,
+                ))
+                .unwrap();
+        }
+
         ui.add_node(UiNode::new(navigation_layer))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        button::ButtonBuilder,
+        core::algebra::Vector2,
+        message::{ButtonState, KeyCode, OsEvent},
+        navigation::{NavigationLayer, NavigationLayerBuilder},
+        stack_panel::StackPanelBuilder,
+        widget::WidgetBuilder,
+        Orientation, UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    fn press(ui: &mut UserInterface, key: KeyCode) {
+        ui.process_os_event(&OsEvent::KeyboardInput {
+            button: key,
+            state: ButtonState::Pressed,
+            text: Default::default(),
+        });
+        while ui.poll_message().is_some() {}
+    }
+
+    #[test]
+    fn test_default_matches_builder() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let layer = NavigationLayerBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let built = ui.node(layer).cast::<NavigationLayer>().unwrap();
+        let default = NavigationLayer::default();
+        assert_eq!(*built.arrow_navigation, *default.arrow_navigation);
+        assert_eq!(*built.focus_visual, *default.focus_visual);
+        assert_eq!(built.need_update, default.need_update);
+    }
+
+    #[test]
+    fn test_arrow_navigation() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let ctx = &mut ui.build_ctx();
+        let buttons = (0..3)
+            .map(|i| {
+                ButtonBuilder::new(
+                    WidgetBuilder::new()
+                        .with_width(50.0)
+                        .with_height(30.0)
+                        .with_tab_index(Some(i)),
+                )
+                .build(ctx)
+            })
+            .collect::<Vec<_>>();
+        let layer = NavigationLayerBuilder::new(
+            WidgetBuilder::new().with_child(
+                StackPanelBuilder::new(WidgetBuilder::new().with_children(buttons.clone()))
+                    .with_orientation(Orientation::Horizontal)
+                    .build(ctx),
+            ),
+        )
+        .with_initial_focus(buttons[1])
+        .build(ctx);
+
+        ui.update(screen_size, 0.0, &Default::default());
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.keyboard_focus_node, buttons[1]);
+
+        press(&mut ui, KeyCode::ArrowRight);
+        assert_eq!(ui.keyboard_focus_node, buttons[2]);
+
+        // There's nothing to the right of the last button.
+        press(&mut ui, KeyCode::ArrowRight);
+        assert_eq!(ui.keyboard_focus_node, buttons[2]);
+
+        press(&mut ui, KeyCode::ArrowLeft);
+        press(&mut ui, KeyCode::ArrowLeft);
+        assert_eq!(ui.keyboard_focus_node, buttons[0]);
+
+        // The focus was moved using keyboard, so the focus visual must be shown.
+        ui.update(screen_size, 0.0, &Default::default());
+        let layer_ref = ui.node(layer).cast::<NavigationLayer>().unwrap();
+        assert_eq!(
+            layer_ref.focus_visual_bounds,
+            Some(ui.node(buttons[0]).screen_bounds())
+        );
+    }
+}
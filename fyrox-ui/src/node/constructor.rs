@@ -26,7 +26,10 @@ use crate::{
     border::Border,
     button::Button,
     canvas::Canvas,
+    captions::CaptionPanel,
+    chart::Chart,
     check_box::CheckBox,
+    code_editor::CodeEditor,
     color::gradient::{ColorGradientEditor, ColorGradientField, ColorPoint},
     color::{AlphaBar, ColorField, ColorPicker, HueBar, SaturationBrightnessField},
     core::{parking_lot::Mutex, uuid::Uuid, TypeUuidProvider},
@@ -44,6 +47,7 @@ use crate::{
     menu::{ContextMenu, Menu, MenuItem},
     messagebox::MessageBox,
     nine_patch::NinePatch,
+    node_graph::{GraphConnection, GraphNode, GraphSocket, NodeGraph},
     numeric::NumericUpDown,
     path::PathEditor,
     popup::Popup,
@@ -64,6 +68,7 @@ use crate::{
     uuid::UuidEditor,
     vec::VecEditor,
     vector_image::VectorImage,
+    virtual_list_view::VirtualListView,
     window::Window,
     wrap_panel::WrapPanel,
     Control, UiNode,
@@ -113,6 +118,8 @@ impl WidgetConstructorContainer {
         container.add::<Border>();
         container.add::<Button>();
         container.add::<Canvas>();
+        container.add::<CaptionPanel>();
+        container.add::<Chart>();
         container.add::<CheckBox>();
         container.add::<Decorator>();
         container.add::<DropdownList>();
@@ -175,6 +182,7 @@ impl WidgetConstructorContainer {
         container.add::<UuidEditor>();
 
         container.add::<VectorImage>();
+        container.add::<VirtualListView>();
         container.add::<Window>();
         container.add::<WrapPanel>();
         container.add::<ColorGradientField>();
@@ -234,6 +242,11 @@ impl WidgetConstructorContainer {
 
         container.add::<Text>();
         container.add::<TextBox>();
+        container.add::<CodeEditor>();
+        container.add::<NodeGraph>();
+        container.add::<GraphNode>();
+        container.add::<GraphSocket>();
+        container.add::<GraphConnection>();
         container.add::<Screen>();
         container.add::<AnimationPlayer>();
         container.add::<AnimationBlendingStateMachine>();
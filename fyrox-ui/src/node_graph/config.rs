@@ -0,0 +1,73 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Layout of a node graph, that could be saved and restored later. See [`NodeGraphLayout`] docs for
+//! more info.
+
+use crate::core::{algebra::Vector2, uuid::Uuid, visitor::prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Position of a single node of a graph.
+#[derive(Debug, PartialEq, Clone, Visit, Default, Serialize, Deserialize)]
+pub struct NodeLayoutDescriptor {
+    /// Id of the node, see [`super::GraphNode::id`].
+    pub id: Uuid,
+    /// Position of the node in the local coordinates of the graph.
+    pub position: Vector2<f32>,
+}
+
+/// A connection between an output socket of one node and an input socket of another node.
+#[derive(Debug, PartialEq, Clone, Visit, Default, Serialize, Deserialize)]
+pub struct ConnectionDescriptor {
+    /// Id of the node the connection starts from.
+    pub source_node: Uuid,
+    /// Index of the output socket of the source node.
+    pub source_socket: usize,
+    /// Id of the node the connection ends at.
+    pub dest_node: Uuid,
+    /// Index of the input socket of the destination node.
+    pub dest_socket: usize,
+}
+
+/// Layout of a node graph: positions of its nodes, connections between them and the view
+/// parameters. Nodes are identified by their ids, so the layout remains valid between sessions
+/// as long as the graph user keeps the ids of the nodes.
+#[derive(Debug, PartialEq, Clone, Visit, Serialize, Deserialize)]
+pub struct NodeGraphLayout {
+    /// Positions of the nodes.
+    pub nodes: Vec<NodeLayoutDescriptor>,
+    /// Connections between the nodes.
+    pub connections: Vec<ConnectionDescriptor>,
+    /// Position of the view.
+    pub view_position: Vector2<f32>,
+    /// Zoom of the view.
+    pub zoom: f32,
+}
+
+impl Default for NodeGraphLayout {
+    fn default() -> Self {
+        Self {
+            nodes: Default::default(),
+            connections: Default::default(),
+            view_position: Default::default(),
+            zoom: 1.0,
+        }
+    }
+}
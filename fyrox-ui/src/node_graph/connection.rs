@@ -0,0 +1,163 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Connection is a curve between an output socket and an input socket. See [`GraphConnection`] docs
+//! for more info.
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2, color::Color, math::Rect, pool::Handle, reflect::prelude::*,
+        type_traits::prelude::*, uuid_provider, visitor::prelude::*,
+    },
+    define_widget_deref,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::UiMessage,
+    node_graph::socket::GraphSocket,
+    widget::{Widget, WidgetBuilder},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+const DEFAULT_BRUSH: Brush = Brush::Solid(Color::opaque(150, 150, 150));
+
+/// Draws a connection curve between two points.
+pub fn draw_connection(
+    drawing_context: &mut DrawingContext,
+    source: Vector2<f32>,
+    dest: Vector2<f32>,
+    clip_bounds: Rect<f32>,
+    brush: Brush,
+) {
+    let k = 75.0;
+    drawing_context.push_bezier(
+        source,
+        source + Vector2::new(k, 0.0),
+        dest - Vector2::new(k, 0.0),
+        dest,
+        20,
+        4.0,
+    );
+    drawing_context.commit(clip_bounds, brush, CommandTexture::None, None);
+}
+
+/// Connection is a curve between an output socket and an input socket of two graph nodes. Ends
+/// of the curve are updated by [`super::NodeGraph`] on every layout pass, so connections always
+/// follow the nodes they connect.
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct GraphConnection {
+    /// Base widget of the connection.
+    pub widget: Widget,
+    /// Output socket the connection starts from.
+    pub source: Handle<UiNode>,
+    /// Input socket the connection ends at.
+    pub dest: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(super) source_pos: Cell<Vector2<f32>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(super) dest_pos: Cell<Vector2<f32>>,
+}
+
+define_widget_deref!(GraphConnection);
+
+uuid_provider!(GraphConnection = "6a9a46b0-8e45-4241-839b-8ae8dd7530ff");
+
+impl Control for GraphConnection {
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        draw_connection(
+            drawing_context,
+            self.source_pos.get(),
+            self.dest_pos.get(),
+            self.clip_bounds(),
+            self.foreground(),
+        );
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+    }
+}
+
+/// Connection builder creates [`GraphConnection`] instances and adds them to the user interface.
+pub struct GraphConnectionBuilder {
+    widget_builder: WidgetBuilder,
+    source: Handle<UiNode>,
+    dest: Handle<UiNode>,
+}
+
+impl GraphConnectionBuilder {
+    /// Creates new connection builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            source: Default::default(),
+            dest: Default::default(),
+        }
+    }
+
+    /// Sets the output socket the connection starts from.
+    pub fn with_source(mut self, source: Handle<UiNode>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the input socket the connection ends at.
+    pub fn with_dest(mut self, dest: Handle<UiNode>) -> Self {
+        self.dest = dest;
+        self
+    }
+
+    /// Finishes connection building and adds it to the user interface. The connection is drawn
+    /// using the foreground brush of its widget, if the brush is not set, the color of the source
+    /// socket pin is used.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let brush = self
+            .widget_builder
+            .foreground
+            .clone()
+            .or_else(|| {
+                ctx.try_get_node(self.source)
+                    .and_then(|n| n.cast::<GraphSocket>())
+                    .and_then(|s| ctx.try_get_node(s.pin))
+                    .map(|pin| pin.foreground())
+            })
+            .unwrap_or(DEFAULT_BRUSH);
+
+        let connection = GraphConnection {
+            widget: self
+                .widget_builder
+                .with_foreground(brush)
+                .with_hit_test_visibility(false)
+                .with_clip_to_bounds(false)
+                .build(),
+            source: self.source,
+            dest: self.dest,
+            source_pos: Default::default(),
+            dest_pos: Default::default(),
+        };
+
+        ctx.add_node(UiNode::new(connection))
+    }
+}
@@ -0,0 +1,906 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#![warn(missing_docs)]
+
+//! Node graph is a widget that shows a set of nodes with typed sockets and connections between
+//! them. It is meant to be used as a base for various graph editors (state machines, materials,
+//! dialogues, etc.). See [`NodeGraph`] docs for more info.
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::{Matrix3, Vector2},
+        color::Color,
+        math::Rect,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        uuid::Uuid,
+        uuid_provider,
+        visitor::prelude::*,
+    },
+    define_constructor, define_widget_deref,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::{MessageDirection, MouseButton, UiMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
+};
+use fyrox_graph::BaseSceneGraph;
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+pub mod config;
+pub mod connection;
+pub mod node;
+pub mod socket;
+
+pub use config::{ConnectionDescriptor, NodeGraphLayout, NodeLayoutDescriptor};
+pub use connection::{draw_connection, GraphConnection, GraphConnectionBuilder};
+pub use node::{GraphNode, GraphNodeBuilder, GraphNodeMessage};
+pub use socket::{GraphSocket, GraphSocketBuilder, GraphSocketMessage, SocketDirection};
+
+const DEFAULT_BACKGROUND: Brush = Brush::Solid(Color::opaque(40, 40, 40));
+const GRID_BRUSH: Brush = Brush::Solid(Color::opaque(60, 60, 60));
+const NEW_CONNECTION_BRUSH: Brush = Brush::Solid(Color::WHITE);
+
+/// Initial position of a node, that is being dragged.
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, Default)]
+pub struct DragEntry {
+    /// Handle of the dragged node.
+    pub node: Handle<UiNode>,
+    /// Position of the node at the moment when dragging has started.
+    pub initial_position: Vector2<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Visit, Reflect, Default)]
+enum Mode {
+    #[default]
+    Normal,
+    Drag {
+        initial_cursor_position: Vector2<f32>,
+        entries: Vec<DragEntry>,
+    },
+    CreateConnection {
+        source: Handle<UiNode>,
+        source_pos: Vector2<f32>,
+        dest_pos: Vector2<f32>,
+    },
+}
+
+/// A set of messages of [`NodeGraph`] widget.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeGraphMessage {
+    /// Sets new selection of the graph (when sent with [`MessageDirection::ToWidget`]) or notifies
+    /// that the selection has changed (when sent with [`MessageDirection::FromWidget`]).
+    SelectionChanged(Vec<Handle<UiNode>>),
+    /// With [`MessageDirection::FromWidget`], the message is a request of the user to connect two
+    /// compatible sockets. The graph does not connect the sockets on its own, the graph user should
+    /// validate the request and send the message back with [`MessageDirection::ToWidget`], which
+    /// creates a new [`GraphConnection`]. `source` is always an output socket and `dest` is always
+    /// an input socket.
+    Connect {
+        /// Output socket.
+        source: Handle<UiNode>,
+        /// Input socket.
+        dest: Handle<UiNode>,
+    },
+    /// With [`MessageDirection::FromWidget`], the message is a request of the user to remove the
+    /// given connection. With [`MessageDirection::ToWidget`] the connection will be removed.
+    Disconnect(Handle<UiNode>),
+    /// Occurs when the user has finished dragging of a set of nodes. Contains initial positions of
+    /// the nodes, so the graph user could use them to create an undo command. Direction:
+    /// [`MessageDirection::FromWidget`].
+    CommitDrag(Vec<DragEntry>),
+    /// Sets new position and zoom of the view (when sent with [`MessageDirection::ToWidget`]) or
+    /// notifies that the view has changed (when sent with [`MessageDirection::FromWidget`]).
+    View {
+        /// Position of the view.
+        position: Vector2<f32>,
+        /// Zoom of the view.
+        zoom: f32,
+    },
+    /// Applies the given layout to the graph: moves the nodes, recreates the connections and sets
+    /// the view. Direction: [`MessageDirection::ToWidget`].
+    Layout(NodeGraphLayout),
+}
+
+impl NodeGraphMessage {
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::SelectionChanged`] message.
+        NodeGraphMessage:SelectionChanged => fn selection_changed(Vec<Handle<UiNode>>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::Connect`] message.
+        NodeGraphMessage:Connect => fn connect(source: Handle<UiNode>, dest: Handle<UiNode>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::Disconnect`] message.
+        NodeGraphMessage:Disconnect => fn disconnect(Handle<UiNode>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::CommitDrag`] message.
+        NodeGraphMessage:CommitDrag => fn commit_drag(Vec<DragEntry>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::View`] message.
+        NodeGraphMessage:View => fn view(position: Vector2<f32>, zoom: f32), layout: false
+    );
+    define_constructor!(
+        /// Creates [`NodeGraphMessage::Layout`] message.
+        NodeGraphMessage:Layout => fn layout(NodeGraphLayout), layout: false
+    );
+}
+
+/// Node graph is a widget that shows a set of [`GraphNode`]s and [`GraphConnection`]s between
+/// their sockets. It handles everything that is common for graph editors:
+///
+/// - Panning (middle mouse button) and zooming (mouse wheel) of the view.
+/// - Selection of nodes (`Ctrl` adds a node to the selection) and dragging of selected nodes.
+/// - Creation of connections by dragging a pin of a socket to a pin of a compatible socket, and
+/// removal of connections by clicking on a pin with `Alt` key held down.
+/// - Saving and restoring of the layout of the graph, see [`NodeGraphLayout`].
+///
+/// The graph does not own any data model, it only reports user actions via [`NodeGraphMessage`]s
+/// with [`MessageDirection::FromWidget`] direction. It is up to the graph user to apply them to its
+/// data model and send the messages back to update the view. Nodes are added to the graph as its
+/// children.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     core::pool::Handle,
+/// #     node_graph::{GraphNodeBuilder, GraphSocketBuilder, NodeGraphBuilder},
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode,
+/// # };
+/// fn create_graph(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     let socket = || GraphSocketBuilder::new(WidgetBuilder::new()).with_kind("f32");
+///
+///     let node = GraphNodeBuilder::new(WidgetBuilder::new())
+///         .with_title("Add")
+///         .with_input(socket().with_label("A"))
+///         .with_input(socket().with_label("B"))
+///         .with_output(socket())
+///         .build(ctx);
+///
+///     NodeGraphBuilder::new(WidgetBuilder::new().with_child(node)).build(ctx)
+/// }
+/// ```
+#[derive(Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct NodeGraph {
+    /// Base widget of the graph.
+    pub widget: Widget,
+    /// Currently selected nodes.
+    pub selection: Vec<Handle<UiNode>>,
+    /// Position of the view.
+    pub view_position: Vector2<f32>,
+    /// Zoom of the view.
+    pub zoom: f32,
+    initial_view_position: Vector2<f32>,
+    click_position: Vector2<f32>,
+    is_dragging_view: bool,
+    mode: Mode,
+    // A handle to a node that was under the cursor at the moment of release of left mouse button.
+    lmb_released_node: Cell<Handle<UiNode>>,
+}
+
+impl Default for NodeGraph {
+    fn default() -> Self {
+        Self {
+            widget: Default::default(),
+            selection: Default::default(),
+            view_position: Default::default(),
+            zoom: 1.0,
+            initial_view_position: Default::default(),
+            click_position: Default::default(),
+            is_dragging_view: false,
+            mode: Mode::Normal,
+            lmb_released_node: Default::default(),
+        }
+    }
+}
+
+define_widget_deref!(NodeGraph);
+
+uuid_provider!(NodeGraph = "76a72d4f-4360-493b-9332-51986cdfed3f");
+
+impl NodeGraph {
+    /// Returns an iterator over every node of the graph.
+    pub fn nodes<'a>(&'a self, ui: &'a UserInterface) -> impl Iterator<Item = &'a GraphNode> + 'a {
+        self.children().iter().filter_map(|c| {
+            ui.try_get(*c)
+                .and_then(|n| n.query_component::<GraphNode>())
+        })
+    }
+
+    /// Returns an iterator over every connection of the graph.
+    pub fn connections<'a>(
+        &'a self,
+        ui: &'a UserInterface,
+    ) -> impl Iterator<Item = &'a GraphConnection> + 'a {
+        self.children().iter().filter_map(|c| {
+            ui.try_get(*c)
+                .and_then(|n| n.query_component::<GraphConnection>())
+        })
+    }
+
+    /// Returns handles of every connection, that starts or ends at the given socket.
+    pub fn socket_connections(
+        &self,
+        socket: Handle<UiNode>,
+        ui: &UserInterface,
+    ) -> Vec<Handle<UiNode>> {
+        self.connections(ui)
+            .filter(|c| c.source == socket || c.dest == socket)
+            .map(|c| c.handle())
+            .collect()
+    }
+
+    /// Returns handles of every connection, that starts or ends at any socket of the given node.
+    /// Could be used to remove the connections together with the node.
+    pub fn node_connections(
+        &self,
+        node: Handle<UiNode>,
+        ui: &UserInterface,
+    ) -> Vec<Handle<UiNode>> {
+        let Some(node_ref) = ui
+            .try_get(node)
+            .and_then(|n| n.query_component::<GraphNode>())
+        else {
+            return Default::default();
+        };
+
+        node_ref
+            .inputs
+            .iter()
+            .chain(node_ref.outputs.iter())
+            .flat_map(|socket| self.socket_connections(*socket, ui))
+            .collect()
+    }
+
+    /// Searches for a node with the given id.
+    pub fn find_node(&self, id: Uuid, ui: &UserInterface) -> Handle<UiNode> {
+        self.nodes(ui)
+            .find(|n| n.id == id)
+            .map(|n| n.handle())
+            .unwrap_or_default()
+    }
+
+    /// Collects current layout of the graph, it could be saved and applied later using
+    /// [`NodeGraphMessage::Layout`] message.
+    pub fn layout(&self, ui: &UserInterface) -> NodeGraphLayout {
+        let socket_info = |socket: Handle<UiNode>| {
+            let socket_ref = ui.try_get(socket)?.query_component::<GraphSocket>()?;
+            let node_ref = ui
+                .try_get(socket_ref.parent_node)?
+                .query_component::<GraphNode>()?;
+            Some((node_ref.id, socket_ref.index))
+        };
+
+        NodeGraphLayout {
+            nodes: self
+                .nodes(ui)
+                .map(|n| NodeLayoutDescriptor {
+                    id: n.id,
+                    position: n.desired_local_position(),
+                })
+                .collect(),
+            connections: self
+                .connections(ui)
+                .filter_map(|c| {
+                    let (source_node, source_socket) = socket_info(c.source)?;
+                    let (dest_node, dest_socket) = socket_info(c.dest)?;
+                    Some(ConnectionDescriptor {
+                        source_node,
+                        source_socket,
+                        dest_node,
+                        dest_socket,
+                    })
+                })
+                .collect(),
+            view_position: self.view_position,
+            zoom: self.zoom,
+        }
+    }
+
+    fn set_view(&mut self, position: Vector2<f32>, zoom: f32, ui: &UserInterface) {
+        self.view_position = position;
+        self.zoom = zoom;
+
+        let transform =
+            Matrix3::new_translation(&-self.view_position) * Matrix3::new_scaling(self.zoom);
+
+        ui.send_message(WidgetMessage::layout_transform(
+            self.handle(),
+            MessageDirection::ToWidget,
+            transform,
+        ));
+
+        ui.send_message(NodeGraphMessage::view(
+            self.handle(),
+            MessageDirection::FromWidget,
+            position,
+            zoom,
+        ));
+    }
+
+    fn make_drag_mode(&self, ui: &UserInterface) -> Mode {
+        Mode::Drag {
+            initial_cursor_position: self.screen_to_local(ui.cursor_position()),
+            entries: self
+                .selection
+                .iter()
+                .filter_map(|n| ui.try_get(*n))
+                .map(|n| DragEntry {
+                    node: n.handle(),
+                    initial_position: n.desired_local_position(),
+                })
+                .collect(),
+        }
+    }
+
+    fn set_selection(&mut self, new_selection: &[Handle<UiNode>], ui: &UserInterface) {
+        if self.selection != new_selection {
+            for node in self.nodes(ui) {
+                ui.send_message(
+                    GraphNodeMessage::select(
+                        node.handle(),
+                        MessageDirection::ToWidget,
+                        new_selection.contains(&node.handle()),
+                    )
+                    .with_handled(true),
+                );
+            }
+
+            self.selection = new_selection.to_vec();
+
+            ui.send_message(NodeGraphMessage::selection_changed(
+                self.handle(),
+                MessageDirection::FromWidget,
+                self.selection.clone(),
+            ));
+
+            // Make sure to update dragging context if we're in Drag mode.
+            if let Mode::Drag { .. } = self.mode {
+                self.mode = self.make_drag_mode(ui);
+            }
+        }
+    }
+
+    // Searches for a first widget with the given component up on the hierarchy, starting from the
+    // given node and ending at the graph.
+    fn find_up<T: 'static>(&self, node: Handle<UiNode>, ui: &UserInterface) -> Handle<UiNode> {
+        let mut handle = node;
+        while handle.is_some() && handle != self.handle() {
+            let Some(node_ref) = ui.try_get(handle) else {
+                break;
+            };
+            if node_ref.has_component::<T>() {
+                return handle;
+            }
+            handle = node_ref.parent();
+        }
+        Handle::NONE
+    }
+
+    // Calculates the position of the center of the pin of the given socket in the local
+    // coordinates of the graph.
+    fn pin_position(&self, socket: Handle<UiNode>, ui: &UserInterface) -> Option<Vector2<f32>> {
+        let socket_ref = ui.try_get(socket)?.query_component::<GraphSocket>()?;
+        let pin_ref = ui.try_get(socket_ref.pin)?;
+        let mut position = pin_ref.actual_local_position() + pin_ref.actual_local_size().scale(0.5);
+        let mut parent = pin_ref.parent();
+        while parent != self.handle() {
+            let parent_ref = ui.try_get(parent)?;
+            position += parent_ref.actual_local_position();
+            parent = parent_ref.parent();
+        }
+        Some(position)
+    }
+
+    fn connect(&self, source: Handle<UiNode>, dest: Handle<UiNode>, ui: &mut UserInterface) {
+        let connection = GraphConnectionBuilder::new(WidgetBuilder::new())
+            .with_source(source)
+            .with_dest(dest)
+            .build(&mut ui.build_ctx());
+
+        ui.send_message(WidgetMessage::link(
+            connection,
+            MessageDirection::ToWidget,
+            self.handle(),
+        ));
+        // Connections must be drawn behind the nodes.
+        ui.send_message(WidgetMessage::lowermost(
+            connection,
+            MessageDirection::ToWidget,
+        ));
+    }
+
+    fn apply_layout(&mut self, layout: &NodeGraphLayout, ui: &mut UserInterface) {
+        for node in self.nodes(ui) {
+            if let Some(descriptor) = layout.nodes.iter().find(|d| d.id == node.id) {
+                ui.send_message(WidgetMessage::desired_position(
+                    node.handle(),
+                    MessageDirection::ToWidget,
+                    descriptor.position,
+                ));
+            }
+        }
+
+        for connection in self.connections(ui) {
+            ui.send_message(WidgetMessage::remove(
+                connection.handle(),
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let find_socket = |id: Uuid, direction: SocketDirection, index: usize| {
+            self.nodes(ui)
+                .find(|n| n.id == id)
+                .map(|n| n.socket(direction, index))
+                .unwrap_or_default()
+        };
+
+        let connections = layout
+            .connections
+            .iter()
+            .map(|c| {
+                (
+                    find_socket(c.source_node, SocketDirection::Output, c.source_socket),
+                    find_socket(c.dest_node, SocketDirection::Input, c.dest_socket),
+                )
+            })
+            .filter(|(source, dest)| source.is_some() && dest.is_some())
+            .collect::<Vec<_>>();
+
+        for (source, dest) in connections {
+            self.connect(source, dest, ui);
+        }
+
+        self.set_view(layout.view_position, layout.zoom, ui);
+    }
+}
+
+impl Control for NodeGraph {
+    fn draw(&self, ctx: &mut DrawingContext) {
+        let grid_size = 9999.0;
+
+        let grid_bounds = self
+            .widget
+            .bounding_rect()
+            .inflate(grid_size, grid_size)
+            .translate(Vector2::new(grid_size * 0.5, grid_size * 0.5));
+        ctx.push_rect_filled(&grid_bounds, None);
+        ctx.commit(
+            self.clip_bounds(),
+            self.widget.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        ctx.push_grid(self.zoom, Vector2::repeat(50.0), grid_bounds);
+        ctx.commit(self.clip_bounds(), GRID_BRUSH, CommandTexture::None, None);
+
+        if let Mode::CreateConnection {
+            source_pos,
+            dest_pos,
+            ..
+        } = self.mode
+        {
+            draw_connection(
+                ctx,
+                source_pos,
+                dest_pos,
+                self.clip_bounds(),
+                NEW_CONNECTION_BRUSH,
+            );
+        }
+    }
+
+    fn measure_override(&self, ui: &UserInterface, _available_size: Vector2<f32>) -> Vector2<f32> {
+        let size_for_child = Vector2::new(f32::INFINITY, f32::INFINITY);
+
+        for child_handle in self.widget.children() {
+            ui.measure_node(*child_handle, size_for_child);
+        }
+
+        Vector2::default()
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
+        for &child_handle in self.widget.children() {
+            let child = ui.node(child_handle);
+            ui.arrange_node(
+                child_handle,
+                &Rect::new(
+                    child.desired_local_position().x,
+                    child.desired_local_position().y,
+                    child.desired_size().x,
+                    child.desired_size().y,
+                ),
+            );
+        }
+
+        // Nodes are arranged at this point, so the ends of the connections could be synced with
+        // the pins of the sockets.
+        for connection in self.connections(ui) {
+            if let (Some(source_pos), Some(dest_pos)) = (
+                self.pin_position(connection.source, ui),
+                self.pin_position(connection.dest, ui),
+            ) {
+                connection.source_pos.set(source_pos);
+                connection.dest_pos.set(dest_pos);
+            }
+        }
+
+        final_size
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(GraphNodeMessage::Select(true)) = message.data() {
+            if message.direction() == MessageDirection::FromWidget && !message.handled() {
+                let selected_node = message.destination();
+
+                let new_selection = if ui.keyboard_modifiers().control {
+                    let mut selection = self.selection.clone();
+                    selection.push(selected_node);
+                    selection
+                } else {
+                    vec![selected_node]
+                };
+
+                self.set_selection(&new_selection, ui);
+            }
+        } else if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::MouseDown { pos, button } => {
+                    if *button == MouseButton::Middle {
+                        self.is_dragging_view = true;
+                        self.click_position = *pos;
+                        self.initial_view_position = self.view_position;
+
+                        ui.capture_mouse(self.handle());
+                    } else if *button == MouseButton::Left
+                        && !message.handled()
+                        && self.mode == Mode::Normal
+                    {
+                        if self
+                            .find_up::<GraphNode>(message.destination(), ui)
+                            .is_some()
+                        {
+                            self.mode = self.make_drag_mode(ui);
+                        } else {
+                            self.set_selection(&[], ui);
+                        }
+                    }
+                }
+                WidgetMessage::MouseUp { pos, button } => {
+                    if *button == MouseButton::Middle {
+                        self.is_dragging_view = false;
+
+                        ui.release_mouse_capture();
+                    } else if *button == MouseButton::Left {
+                        match std::mem::take(&mut self.mode) {
+                            Mode::Drag {
+                                initial_cursor_position,
+                                entries,
+                            } => {
+                                if self.screen_to_local(*pos) != initial_cursor_position {
+                                    ui.send_message(NodeGraphMessage::commit_drag(
+                                        self.handle(),
+                                        MessageDirection::FromWidget,
+                                        entries,
+                                    ));
+                                }
+                            }
+                            Mode::CreateConnection { source, .. } => {
+                                let dest =
+                                    self.find_up::<GraphSocket>(self.lmb_released_node.get(), ui);
+                                let socket = |handle: Handle<UiNode>| {
+                                    ui.try_get(handle)
+                                        .and_then(|n| n.query_component::<GraphSocket>())
+                                };
+
+                                if let (Some(source_ref), Some(dest_ref)) =
+                                    (socket(source), socket(dest))
+                                {
+                                    if source_ref.is_compatible(dest_ref) {
+                                        // Connections always go from outputs to inputs.
+                                        let (source, dest) = match source_ref.direction {
+                                            SocketDirection::Output => (source, dest),
+                                            SocketDirection::Input => (dest, source),
+                                        };
+
+                                        ui.send_message(NodeGraphMessage::connect(
+                                            self.handle(),
+                                            MessageDirection::FromWidget,
+                                            source,
+                                            dest,
+                                        ));
+                                    }
+                                }
+                            }
+                            Mode::Normal => (),
+                        }
+                    }
+                }
+                WidgetMessage::MouseMove { pos, .. } => {
+                    if self.is_dragging_view {
+                        self.set_view(
+                            self.initial_view_position + (*pos - self.click_position),
+                            self.zoom,
+                            ui,
+                        );
+                    }
+
+                    let local_cursor_position = self.screen_to_local(*pos);
+
+                    match self.mode {
+                        Mode::Drag {
+                            initial_cursor_position,
+                            ref entries,
+                        } => {
+                            for entry in entries.iter() {
+                                ui.send_message(WidgetMessage::desired_position(
+                                    entry.node,
+                                    MessageDirection::ToWidget,
+                                    entry.initial_position
+                                        + (local_cursor_position - initial_cursor_position),
+                                ));
+                            }
+                        }
+                        Mode::CreateConnection {
+                            ref mut dest_pos, ..
+                        } => {
+                            *dest_pos = local_cursor_position;
+                        }
+                        Mode::Normal => (),
+                    }
+                }
+                WidgetMessage::MouseWheel { amount, pos } => {
+                    let cursor_pos = (*pos - self.screen_position()).scale(self.zoom);
+
+                    let zoom = (self.zoom + 0.1 * amount).clamp(0.2, 2.0);
+
+                    let new_cursor_pos = (*pos - self.screen_position()).scale(zoom);
+
+                    self.set_view(
+                        self.view_position - (new_cursor_pos - cursor_pos).scale(zoom),
+                        zoom,
+                        ui,
+                    );
+                }
+                _ => (),
+            }
+        } else if let Some(msg) = message.data::<NodeGraphMessage>() {
+            if message.direction() == MessageDirection::ToWidget
+                && message.destination() == self.handle()
+            {
+                match msg {
+                    NodeGraphMessage::SelectionChanged(new_selection) => {
+                        self.set_selection(new_selection, ui);
+                    }
+                    NodeGraphMessage::Connect { source, dest } => {
+                        self.connect(*source, *dest, ui);
+                    }
+                    NodeGraphMessage::Disconnect(connection) => {
+                        if self.children().contains(connection) {
+                            ui.send_message(WidgetMessage::remove(
+                                *connection,
+                                MessageDirection::ToWidget,
+                            ));
+                        }
+                    }
+                    NodeGraphMessage::View { position, zoom } => {
+                        if self.view_position != *position || self.zoom != *zoom {
+                            self.set_view(*position, *zoom, ui);
+                        }
+                    }
+                    NodeGraphMessage::Layout(layout) => {
+                        self.apply_layout(layout, ui);
+                    }
+                    NodeGraphMessage::CommitDrag(_) => (),
+                }
+            }
+        } else if let Some(msg) = message.data::<GraphSocketMessage>() {
+            if message.direction() == MessageDirection::FromWidget {
+                let socket = message.destination();
+                match msg {
+                    GraphSocketMessage::StartDragging => {
+                        if let Some(source_pos) = self.pin_position(socket, ui) {
+                            self.mode = Mode::CreateConnection {
+                                source: socket,
+                                source_pos,
+                                dest_pos: self.screen_to_local(ui.cursor_position()),
+                            };
+                        }
+                    }
+                    GraphSocketMessage::Disconnect => {
+                        for connection in self.socket_connections(socket, ui) {
+                            ui.send_message(NodeGraphMessage::disconnect(
+                                self.handle(),
+                                MessageDirection::FromWidget,
+                                connection,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn preview_message(&self, ui: &UserInterface, message: &mut UiMessage) {
+        if let Some(WidgetMessage::MouseUp { button, pos }) = message.data() {
+            if *button == MouseButton::Left {
+                self.lmb_released_node.set(ui.hit_test_unrestricted(*pos));
+            }
+        }
+    }
+}
+
+/// Node graph builder creates [`NodeGraph`] instances and adds them to the user interface. Nodes
+/// should be added to the graph as children of its widget.
+pub struct NodeGraphBuilder {
+    widget_builder: WidgetBuilder,
+    view_position: Vector2<f32>,
+    zoom: f32,
+}
+
+impl NodeGraphBuilder {
+    /// Creates new node graph builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            view_position: Default::default(),
+            zoom: 1.0,
+        }
+    }
+
+    /// Sets the initial position and zoom of the view.
+    pub fn with_view(mut self, position: Vector2<f32>, zoom: f32) -> Self {
+        self.view_position = position;
+        self.zoom = zoom;
+        self
+    }
+
+    /// Finishes node graph building and adds it to the user interface.
+    pub fn build(mut self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        if self.widget_builder.background.is_none() {
+            self.widget_builder.background = Some(DEFAULT_BACKGROUND);
+        }
+
+        let graph = NodeGraph {
+            widget: self
+                .widget_builder
+                .with_preview_messages(true)
+                .with_clip_to_bounds(false)
+                .with_layout_transform(
+                    Matrix3::new_translation(&-self.view_position)
+                        * Matrix3::new_scaling(self.zoom),
+                )
+                .build(),
+            view_position: self.view_position,
+            zoom: self.zoom,
+            ..Default::default()
+        };
+
+        ctx.add_node(UiNode::new(graph))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, uuid::Uuid},
+        message::MessageDirection,
+        node_graph::{
+            ConnectionDescriptor, GraphNode, GraphNodeBuilder, GraphSocket, GraphSocketBuilder,
+            NodeGraph, NodeGraphBuilder, NodeGraphLayout, NodeGraphMessage, NodeLayoutDescriptor,
+        },
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    #[test]
+    fn test_node_graph_layout() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let a_id = Uuid::new_v4();
+        let a = GraphNodeBuilder::new(
+            WidgetBuilder::new().with_desired_position(Vector2::new(10.0, 20.0)),
+        )
+        .with_id(a_id)
+        .with_output(GraphSocketBuilder::new(WidgetBuilder::new()).with_kind("f32"))
+        .build(ctx);
+
+        let b_id = Uuid::new_v4();
+        let b = GraphNodeBuilder::new(WidgetBuilder::new())
+            .with_id(b_id)
+            .with_input(GraphSocketBuilder::new(WidgetBuilder::new()).with_kind("bool"))
+            .with_input(GraphSocketBuilder::new(WidgetBuilder::new()).with_kind("f32"))
+            .build(ctx);
+
+        let graph =
+            NodeGraphBuilder::new(WidgetBuilder::new().with_child(a).with_child(b)).build(ctx);
+
+        let source = ui.node(a).cast::<GraphNode>().unwrap().outputs[0];
+        let inputs = ui.node(b).cast::<GraphNode>().unwrap().inputs.clone();
+        let socket = |handle| ui.node(handle).cast::<GraphSocket>().unwrap();
+        assert_eq!(socket(source).parent_node, a);
+        assert!(!socket(source).is_compatible(socket(inputs[0])));
+        assert!(socket(source).is_compatible(socket(inputs[1])));
+
+        ui.send_message(NodeGraphMessage::connect(
+            graph,
+            MessageDirection::ToWidget,
+            source,
+            inputs[1],
+        ));
+        while ui.poll_message().is_some() {}
+
+        let layout = ui.node(graph).cast::<NodeGraph>().unwrap().layout(&ui);
+        assert_eq!(
+            layout.nodes[0],
+            NodeLayoutDescriptor {
+                id: a_id,
+                position: Vector2::new(10.0, 20.0)
+            }
+        );
+        assert_eq!(
+            layout.connections,
+            vec![ConnectionDescriptor {
+                source_node: a_id,
+                source_socket: 0,
+                dest_node: b_id,
+                dest_socket: 1,
+            }]
+        );
+
+        // Connections are recreated from the layout.
+        ui.send_message(NodeGraphMessage::layout(
+            graph,
+            MessageDirection::ToWidget,
+            NodeGraphLayout {
+                connections: Default::default(),
+                ..layout.clone()
+            },
+        ));
+        while ui.poll_message().is_some() {}
+        let graph_ref = ui.node(graph).cast::<NodeGraph>().unwrap();
+        assert!(graph_ref.layout(&ui).connections.is_empty());
+
+        ui.send_message(NodeGraphMessage::layout(
+            graph,
+            MessageDirection::ToWidget,
+            layout.clone(),
+        ));
+        while ui.poll_message().is_some() {}
+        let graph_ref = ui.node(graph).cast::<NodeGraph>().unwrap();
+        assert_eq!(graph_ref.layout(&ui), layout);
+    }
+}
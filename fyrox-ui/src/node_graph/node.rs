@@ -0,0 +1,312 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Graph node is a widget with input and output sockets. See [`GraphNode`] docs for more info.
+
+use crate::{
+    border::BorderBuilder,
+    brush::Brush,
+    core::{
+        color::Color, pool::Handle, reflect::prelude::*, type_traits::prelude::*, uuid::Uuid,
+        uuid_provider, visitor::prelude::*,
+    },
+    define_constructor, define_widget_deref,
+    grid::{Column, GridBuilder, Row},
+    message::{MessageDirection, MouseButton, UiMessage},
+    node_graph::socket::{GraphSocket, GraphSocketBuilder, SocketDirection},
+    stack_panel::StackPanelBuilder,
+    text::TextBuilder,
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, HorizontalAlignment, Thickness, UiNode, UserInterface,
+};
+use std::ops::{Deref, DerefMut};
+
+const NORMAL_BRUSH: Brush = Brush::Solid(Color::opaque(40, 40, 40));
+const SELECTED_BRUSH: Brush = Brush::Solid(Color::opaque(220, 150, 40));
+const BODY_BRUSH: Brush = Brush::Solid(Color::opaque(70, 70, 70));
+const DEFAULT_HEADER_COLOR: Color = Color::opaque(90, 90, 110);
+
+/// A set of messages of [`GraphNode`] widget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphNodeMessage {
+    /// Selects or deselects the node. The node sends this message back when the selection state
+    /// changes.
+    Select(bool),
+}
+
+impl GraphNodeMessage {
+    define_constructor!(
+        /// Creates [`GraphNodeMessage::Select`] message.
+        GraphNodeMessage:Select => fn select(bool), layout: false
+    );
+}
+
+/// Graph node is a widget with a title, a set of input sockets on the left side, a set of output
+/// sockets on the right side and an optional content in the middle. Nodes are identified by their
+/// ids in layouts of a graph, see [`super::NodeGraphLayout`].
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct GraphNode {
+    /// Base widget of the node.
+    pub widget: Widget,
+    /// Unique id of the node.
+    pub id: Uuid,
+    /// Input sockets of the node.
+    pub inputs: Vec<Handle<UiNode>>,
+    /// Output sockets of the node.
+    pub outputs: Vec<Handle<UiNode>>,
+    /// `true` if the node is selected.
+    pub selected: bool,
+    border: Handle<UiNode>,
+}
+
+define_widget_deref!(GraphNode);
+
+uuid_provider!(GraphNode = "a58b3b7b-ec69-4377-b1b8-1e4ffb1caf0a");
+
+impl GraphNode {
+    /// Returns a socket of the node with the given direction and index.
+    pub fn socket(&self, direction: SocketDirection, index: usize) -> Handle<UiNode> {
+        let sockets = match direction {
+            SocketDirection::Input => &self.inputs,
+            SocketDirection::Output => &self.outputs,
+        };
+        sockets.get(index).cloned().unwrap_or_default()
+    }
+}
+
+impl Control for GraphNode {
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::MouseDown { button, .. } => {
+                    if (*button == MouseButton::Left || *button == MouseButton::Right)
+                        && !message.handled()
+                    {
+                        if !self.selected {
+                            ui.send_message(GraphNodeMessage::select(
+                                self.handle(),
+                                MessageDirection::ToWidget,
+                                true,
+                            ));
+                        }
+
+                        ui.capture_mouse(self.handle());
+                    }
+                }
+                WidgetMessage::MouseUp { button, .. } => {
+                    if *button == MouseButton::Left || *button == MouseButton::Right {
+                        ui.release_mouse_capture();
+                    }
+                }
+                _ => (),
+            }
+        } else if let Some(&GraphNodeMessage::Select(selected)) = message.data() {
+            if message.destination() == self.handle()
+                && message.direction() == MessageDirection::ToWidget
+                && self.selected != selected
+            {
+                self.selected = selected;
+
+                ui.send_message(WidgetMessage::foreground(
+                    self.border,
+                    MessageDirection::ToWidget,
+                    if selected {
+                        SELECTED_BRUSH
+                    } else {
+                        NORMAL_BRUSH
+                    },
+                ));
+
+                ui.send_message(message.reverse());
+            }
+        }
+    }
+}
+
+/// Graph node builder creates [`GraphNode`] instances and adds them to the user interface.
+pub struct GraphNodeBuilder {
+    widget_builder: WidgetBuilder,
+    id: Uuid,
+    title: String,
+    header_color: Color,
+    inputs: Vec<GraphSocketBuilder>,
+    outputs: Vec<GraphSocketBuilder>,
+    content: Handle<UiNode>,
+}
+
+impl GraphNodeBuilder {
+    /// Creates new graph node builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            id: Uuid::new_v4(),
+            title: Default::default(),
+            header_color: DEFAULT_HEADER_COLOR,
+            inputs: Default::default(),
+            outputs: Default::default(),
+            content: Default::default(),
+        }
+    }
+
+    /// Sets the id of the node. By default, a random id is used.
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets the title of the node.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+
+    /// Sets the background color of the title of the node.
+    pub fn with_header_color(mut self, color: Color) -> Self {
+        self.header_color = color;
+        self
+    }
+
+    /// Adds an input socket to the node.
+    pub fn with_input(mut self, socket: GraphSocketBuilder) -> Self {
+        self.inputs.push(socket);
+        self
+    }
+
+    /// Adds an output socket to the node.
+    pub fn with_output(mut self, socket: GraphSocketBuilder) -> Self {
+        self.outputs.push(socket);
+        self
+    }
+
+    /// Sets a widget that will be placed between inputs and outputs of the node.
+    pub fn with_content(mut self, content: Handle<UiNode>) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Finishes graph node building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let inputs = self
+            .inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, socket)| {
+                socket
+                    .with_direction(SocketDirection::Input)
+                    .with_index(i)
+                    .build(ctx)
+            })
+            .collect::<Vec<_>>();
+        let outputs = self
+            .outputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, socket)| {
+                socket
+                    .with_direction(SocketDirection::Output)
+                    .with_index(i)
+                    .build(ctx)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(content) = ctx.try_get_node_mut(self.content) {
+            content.set_row(0).set_column(1);
+        }
+
+        let header = BorderBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .with_background(Brush::Solid(self.header_color))
+                .with_child(
+                    TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(4.0)))
+                        .with_text(self.title)
+                        .build(ctx),
+                ),
+        )
+        .with_stroke_thickness(Thickness::uniform(0.0))
+        .build(ctx);
+
+        let body = GridBuilder::new(
+            WidgetBuilder::new()
+                .on_row(1)
+                .with_margin(Thickness::uniform(2.0))
+                .with_child(
+                    StackPanelBuilder::new(
+                        WidgetBuilder::new()
+                            .on_column(0)
+                            .with_children(inputs.iter().cloned()),
+                    )
+                    .build(ctx),
+                )
+                .with_child(self.content)
+                .with_child(
+                    StackPanelBuilder::new(
+                        WidgetBuilder::new()
+                            .on_column(2)
+                            .with_horizontal_alignment(HorizontalAlignment::Right)
+                            .with_children(outputs.iter().cloned()),
+                    )
+                    .build(ctx),
+                ),
+        )
+        .add_row(Row::auto())
+        .add_column(Column::auto())
+        .add_column(Column::stretch())
+        .add_column(Column::auto())
+        .build(ctx);
+
+        let border = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_foreground(NORMAL_BRUSH)
+                .with_background(BODY_BRUSH)
+                .with_child(
+                    GridBuilder::new(WidgetBuilder::new().with_child(header).with_child(body))
+                        .add_row(Row::auto())
+                        .add_row(Row::auto())
+                        .add_column(Column::stretch())
+                        .build(ctx),
+                ),
+        )
+        .with_stroke_thickness(Thickness::uniform(2.0))
+        .with_corner_radius(4.0)
+        .build(ctx);
+
+        let node = GraphNode {
+            widget: self.widget_builder.with_child(border).build(),
+            id: self.id,
+            inputs: inputs.clone(),
+            outputs: outputs.clone(),
+            selected: false,
+            border,
+        };
+
+        let handle = ctx.add_node(UiNode::new(node));
+
+        for socket in inputs.into_iter().chain(outputs) {
+            if let Some(socket) = ctx[socket].cast_mut::<GraphSocket>() {
+                socket.parent_node = handle;
+            }
+        }
+
+        handle
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Socket is a connection point of a graph node. See [`GraphSocket`] docs for more info.
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2, color::Color, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
+        uuid_provider, visitor::prelude::*, ImmutableString,
+    },
+    define_constructor, define_widget_deref,
+    message::{MessageDirection, MouseButton, UiMessage},
+    stack_panel::StackPanelBuilder,
+    text::TextBuilder,
+    vector_image::{Primitive, VectorImageBuilder},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+};
+use std::ops::{Deref, DerefMut};
+
+const RADIUS: f32 = 6.0;
+const DEFAULT_COLOR: Color = Color::opaque(150, 150, 150);
+
+/// A set of messages of [`GraphSocket`] widget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphSocketMessage {
+    /// Occurs when user clicks on the pin of a socket and starts dragging it.
+    StartDragging,
+    /// Occurs when user clicks on the pin of a socket with `Alt` key held down, the graph then asks
+    /// to remove every connection of the socket.
+    Disconnect,
+}
+
+impl GraphSocketMessage {
+    define_constructor!(
+        /// Creates [`GraphSocketMessage::StartDragging`] message.
+        GraphSocketMessage:StartDragging => fn start_dragging(), layout: false
+    );
+    define_constructor!(
+        /// Creates [`GraphSocketMessage::Disconnect`] message.
+        GraphSocketMessage:Disconnect => fn disconnect(), layout: false
+    );
+}
+
+/// Direction of a socket.
+#[derive(Copy, Clone, PartialEq, Hash, Debug, Eq, Visit, Reflect, Default)]
+pub enum SocketDirection {
+    /// Input socket accepts a single connection (newer connection requests replace older ones
+    /// by the graph user).
+    #[default]
+    Input,
+    /// Output socket could have any amount of connections.
+    Output,
+}
+
+/// Socket is a connection point of a graph node. Sockets have a type (`kind`), only sockets of the
+/// same type could be connected. A socket with an empty type is compatible with any other socket.
+#[derive(Default, Clone, Debug, Visit, Reflect, ComponentProvider)]
+pub struct GraphSocket {
+    /// Base widget of the socket.
+    pub widget: Widget,
+    /// Handle of the graph node the socket belongs to.
+    pub parent_node: Handle<UiNode>,
+    /// Direction of the socket.
+    pub direction: SocketDirection,
+    /// Type of the socket, defines which sockets can be connected with it.
+    pub kind: ImmutableString,
+    /// Index of the socket in the list of inputs or outputs of its node.
+    pub index: usize,
+    /// Handle of the pin of the socket, connections are attached to its center.
+    pub pin: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    click_position: Option<Vector2<f32>>,
+}
+
+define_widget_deref!(GraphSocket);
+
+uuid_provider!(GraphSocket = "5eb7bfcd-ca26-49fc-82dc-63eadc1ab1b1");
+
+impl GraphSocket {
+    /// Checks whether the socket can be connected with the other one: the sockets must be of
+    /// different directions, belong to different nodes and have compatible types.
+    pub fn is_compatible(&self, other: &GraphSocket) -> bool {
+        self.direction != other.direction
+            && self.parent_node != other.parent_node
+            && (self.kind.is_empty() || other.kind.is_empty() || self.kind == other.kind)
+    }
+}
+
+impl Control for GraphSocket {
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::MouseDown { button, pos } => {
+                    if *button == MouseButton::Left && message.destination() == self.pin {
+                        if ui.keyboard_modifiers().alt {
+                            ui.send_message(GraphSocketMessage::disconnect(
+                                self.handle(),
+                                MessageDirection::FromWidget,
+                            ));
+                        } else {
+                            self.click_position = Some(*pos);
+                            ui.capture_mouse(self.handle());
+                        }
+
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::MouseUp { button, .. } => {
+                    if *button == MouseButton::Left {
+                        self.click_position = None;
+                        ui.release_mouse_capture();
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::MouseMove { pos, .. } => {
+                    if let Some(click_position) = self.click_position {
+                        if click_position.metric_distance(pos) >= 5.0 {
+                            ui.send_message(GraphSocketMessage::start_dragging(
+                                self.handle(),
+                                MessageDirection::FromWidget,
+                            ));
+
+                            self.click_position = None;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Socket builder creates [`GraphSocket`] instances. Usually, sockets are passed to
+/// [`super::GraphNodeBuilder`], which sets their direction, index and parent node.
+pub struct GraphSocketBuilder {
+    widget_builder: WidgetBuilder,
+    direction: SocketDirection,
+    kind: ImmutableString,
+    label: String,
+    color: Color,
+    index: usize,
+}
+
+impl GraphSocketBuilder {
+    /// Creates new socket builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            direction: SocketDirection::Input,
+            kind: Default::default(),
+            label: Default::default(),
+            color: DEFAULT_COLOR,
+            index: 0,
+        }
+    }
+
+    /// Sets the direction of the socket.
+    pub fn with_direction(mut self, direction: SocketDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Sets the type of the socket.
+    pub fn with_kind(mut self, kind: &str) -> Self {
+        self.kind = kind.into();
+        self
+    }
+
+    /// Sets the text near the pin of the socket.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_owned();
+        self
+    }
+
+    /// Sets the color of the pin, it is also used for connections that start from the socket.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the index of the socket.
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Finishes socket building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let pin = VectorImageBuilder::new(
+            WidgetBuilder::new()
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_foreground(Brush::Solid(self.color)),
+        )
+        .with_primitives(vec![Primitive::Circle {
+            center: Vector2::new(RADIUS, RADIUS),
+            radius: RADIUS,
+            segments: 16,
+        }])
+        .build(ctx);
+
+        let label = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::left_right(4.0)))
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .with_text(self.label)
+            .build(ctx);
+
+        // Output sockets are placed on the right side of nodes, so the pin is on the right too.
+        let children = match self.direction {
+            SocketDirection::Input => [pin, label],
+            SocketDirection::Output => [label, pin],
+        };
+
+        let panel = StackPanelBuilder::new(WidgetBuilder::new().with_children(children))
+            .with_orientation(Orientation::Horizontal)
+            .build(ctx);
+
+        let socket = GraphSocket {
+            widget: self.widget_builder.with_child(panel).build(),
+            parent_node: Handle::NONE,
+            direction: self.direction,
+            kind: self.kind,
+            index: self.index,
+            pin,
+            click_position: None,
+        };
+
+        ctx.add_node(UiNode::new(socket))
+    }
+}
@@ -27,14 +27,14 @@ use crate::{
     brush::Brush,
     core::{
         algebra::Vector2, color::Color, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
-        visitor::prelude::*,
+        variable::InheritableVariable, visitor::prelude::*,
     },
     define_constructor,
     draw::DrawingContext,
     font::FontResource,
-    formatted_text::{FormattedText, FormattedTextBuilder, WrapMode},
-    message::{MessageDirection, UiMessage},
-    widget::{Widget, WidgetBuilder},
+    formatted_text::{FormattedText, FormattedTextBuilder, TextRun, WrapMode},
+    message::{MessageDirection, MouseButton, UiMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
     BuildContext, Control, HorizontalAlignment, UiNode, UserInterface, VerticalAlignment,
 };
 use fyrox_core::uuid_provider;
@@ -69,6 +69,19 @@ pub enum TextMessage {
     ShadowOffset(Vector2<f32>),
     /// Used to set font height of the widget.
     FontSize(f32),
+    /// Used to set new style runs of the widget. See [Text](Text#rich-text) for usage examples.
+    Runs(Vec<TextRun>),
+    /// Emitted by the widget when a user clicks on a hyperlink. Contains the identifier of the link.
+    /// See [Text](Text#rich-text) for usage examples.
+    LinkClicked(String),
+    /// Used to bind the widget to a key of a string table of the
+    /// [localization manager](crate::localization::LocalizationManager). The text of the widget
+    /// will be replaced with the localized string for the current locale. See
+    /// [Text](Text#localization) for usage examples.
+    Localized(String),
+    /// Used to set a chain of fallback fonts of the widget. See [Text](Text#localization) for more
+    /// info.
+    FallbackFonts(Vec<FontResource>),
 }
 
 impl TextMessage {
@@ -121,6 +134,26 @@ impl TextMessage {
         /// Creates new [`TextMessage::FontSize`] message.
         TextMessage:FontSize => fn font_size(f32), layout: false
     );
+
+    define_constructor!(
+        /// Creates new [`TextMessage::Runs`] message.
+        TextMessage:Runs => fn runs(Vec<TextRun>), layout: false
+    );
+
+    define_constructor!(
+        /// Creates new [`TextMessage::LinkClicked`] message.
+        TextMessage:LinkClicked => fn link_clicked(String), layout: false
+    );
+
+    define_constructor!(
+        /// Creates new [`TextMessage::Localized`] message.
+        TextMessage:Localized => fn localized(String), layout: false
+    );
+
+    define_constructor!(
+        /// Creates new [`TextMessage::FallbackFonts`] message.
+        TextMessage:FallbackFonts => fn fallback_fonts(Vec<FontResource>), layout: false
+    );
 }
 
 /// Text is a simple widget that allows you to print text on screen. It has various options like word wrapping, text
@@ -288,6 +321,94 @@ impl TextMessage {
 /// }
 /// ```
 ///
+/// ## Rich text
+///
+/// Parts of the text could have their own font, size and brush, could be replaced with inline images or could work as
+/// hyperlinks. This is done by style runs ([`TextRun`]), each run covers a range of characters and overrides the style
+/// of the text. Runs could be created manually, but it is much easier to use [`crate::formatted_text::RichTextBuilder`]
+/// for that. Styled text is laid out without text shaping, so it is not suitable for complex scripts.
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     core::{algebra::Vector2, color::Color, pool::Handle},
+/// #     brush::Brush, formatted_text::{RichTextBuilder, TextRun, WrapMode}, text::TextBuilder,
+/// #     widget::WidgetBuilder, UiNode, UserInterface,
+/// # };
+/// # use fyrox_resource::untyped::UntypedResource;
+/// fn create_dialogue_line(ui: &mut UserInterface, icon: UntypedResource) -> Handle<UiNode> {
+///     let (text, runs) = RichTextBuilder::new()
+///         .push_styled(
+///             "Merchant: ",
+///             TextRun::default()
+///                 .with_brush(Brush::Solid(Color::GOLD))
+///                 .with_font_size(18.0),
+///         )
+///         .push_text("That will be 50 ")
+///         .push_image(Some(icon), Vector2::new(16.0, 16.0))
+///         .push_text(". ")
+///         .push_link("What is this?", "ask_about_item")
+///         .build();
+///     TextBuilder::new(WidgetBuilder::new())
+///         .with_wrap(WrapMode::Word)
+///         .with_text(text)
+///         .with_runs(runs)
+///         .build(&mut ui.build_ctx())
+/// }
+/// ```
+///
+/// When a user clicks on a hyperlink, the widget emits [`TextMessage::LinkClicked`] message with the identifier of the
+/// link:
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     core::pool::Handle, message::{MessageDirection, UiMessage}, text::TextMessage, UiNode,
+/// # };
+/// fn handle_link_click(message: &UiMessage, text: Handle<UiNode>) {
+///     if let Some(TextMessage::LinkClicked(link)) = message.data() {
+///         if message.destination() == text && message.direction() == MessageDirection::FromWidget {
+///             println!("{link} was clicked!");
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Localization
+///
+/// Instead of a fixed text, the widget could be bound to a key of a string table of the
+/// [localization manager](crate::localization::LocalizationManager) of the UI. Such text will be updated automatically
+/// when the language is switched by [`UserInterface::set_locale`]:
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     core::pool::Handle, localization::StringTable, text::TextBuilder, widget::WidgetBuilder,
+/// #     UiNode, UserInterface,
+/// # };
+/// fn create_localized_text(ui: &mut UserInterface) -> Handle<UiNode> {
+///     let mut en = StringTable::new("en");
+///     en.insert("menu.play", "Play");
+///     ui.localization.add_table(en);
+///     let mut ja = StringTable::new("ja");
+///     ja.insert("menu.play", "プレイ");
+///     ui.localization.add_table(ja);
+///     ui.localization.set_locale("en");
+///
+///     let text = TextBuilder::new(WidgetBuilder::new())
+///         .with_localized_text("menu.play")
+///         .build(&mut ui.build_ctx());
+///
+///     // Switch the language, the text will show "プレイ" now.
+///     ui.set_locale("ja");
+///
+///     text
+/// }
+/// ```
+///
+/// Default font has a limited set of characters, so scripts like CJK, Arabic or Hebrew require other fonts. Such fonts
+/// could be added as fallback fonts ([`TextBuilder::with_fallback_fonts`], [`TextMessage::FallbackFonts`] or
+/// [`UserInterface::set_fallback_fonts`] for every text in the UI), every character that is missing in the main font
+/// will be taken from the first fallback font that has it. Text that requires shaping (such as Arabic) is rendered
+/// using the first font of the chain that has every character of the text.
+///
 /// ## Messages
 ///
 /// Text widget can accept the following list of messages at runtime (respective constructors are name with small letter -
@@ -302,6 +423,9 @@ impl TextMessage {
 /// - [`TextMessage::ShadowDilation`] - sets "thickness" of the shadows under the tex.
 /// - [`TextMessage::ShadowBrush`] - sets shadow brush (allows you to change color and even make shadow with color gradients).
 /// - [`TextMessage::ShadowOffset`] - sets offset of the shadows.
+/// - [`TextMessage::Runs`] - sets new [style runs](Text#rich-text).
+/// - [`TextMessage::Localized`] - binds the text to a [localized string](Text#localization).
+/// - [`TextMessage::FallbackFonts`] - sets new [fallback fonts](Text#localization).
 ///
 /// An example of changing text at runtime could be something like this:
 ///
@@ -329,6 +453,9 @@ pub struct Text {
     pub widget: Widget,
     /// [`FormattedText`] instance that is used to layout text and generate drawing commands.
     pub formatted_text: RefCell<FormattedText>,
+    /// A key of the localized string, that is used as the text of the widget.
+    #[visit(optional)]
+    pub localization_key: InheritableVariable<Option<String>>,
 }
 
 crate::define_widget_deref!(Text);
@@ -359,12 +486,28 @@ impl Control for Text {
         self.widget.handle_routed_message(ui, message);
 
         if message.destination() == self.handle() {
-            if let Some(msg) = message.data::<TextMessage>() {
+            if let Some(WidgetMessage::MouseDown {
+                pos,
+                button: MouseButton::Left,
+            }) = message.data()
+            {
+                let local_pos = self.screen_to_local(*pos);
+                if let Some(link) = self.formatted_text.borrow().link_at(local_pos) {
+                    ui.send_message(TextMessage::link_clicked(
+                        self.handle(),
+                        MessageDirection::FromWidget,
+                        link.to_owned(),
+                    ));
+                    message.set_handled(true);
+                }
+            } else if let Some(msg) = message.data::<TextMessage>() {
                 let mut text_ref = self.formatted_text.borrow_mut();
                 match msg {
                     TextMessage::Text(text) => {
                         text_ref.set_text(text);
                         drop(text_ref);
+                        // Explicitly set text overrides the localized one.
+                        self.localization_key.set_value_and_mark_modified(None);
                         self.invalidate_layout();
                     }
                     &TextMessage::Wrap(wrap) => {
@@ -430,6 +573,28 @@ impl Control for Text {
                             self.invalidate_layout();
                         }
                     }
+                    TextMessage::Runs(runs) => {
+                        if text_ref.runs() != runs.as_slice() {
+                            text_ref.set_runs(runs.clone());
+                            drop(text_ref);
+                            self.invalidate_layout();
+                        }
+                    }
+                    TextMessage::LinkClicked(_) => {}
+                    TextMessage::Localized(key) => {
+                        text_ref.set_text(ui.localization.translate_or_key(key));
+                        drop(text_ref);
+                        self.localization_key
+                            .set_value_and_mark_modified(Some(key.clone()));
+                        self.invalidate_layout();
+                    }
+                    TextMessage::FallbackFonts(fonts) => {
+                        if text_ref.fallback_fonts() != fonts.as_slice() {
+                            text_ref.set_fallback_fonts(fonts.clone());
+                            drop(text_ref);
+                            self.invalidate_layout();
+                        }
+                    }
                 }
             }
         }
@@ -461,6 +626,16 @@ impl Text {
     pub fn horizontal_alignment(&self) -> HorizontalAlignment {
         self.formatted_text.borrow().horizontal_alignment()
     }
+
+    /// Returns current style runs of the widget.
+    pub fn runs(&self) -> Vec<TextRun> {
+        self.formatted_text.borrow().runs().to_vec()
+    }
+
+    /// Returns a key of the localized string of the widget, if any.
+    pub fn localization_key(&self) -> Option<&str> {
+        self.localization_key.as_deref()
+    }
 }
 
 /// TextBuilder is used to create instances of [`Text`] widget and register them in the user interface.
@@ -476,6 +651,9 @@ pub struct TextBuilder {
     shadow_dilation: f32,
     shadow_offset: Vector2<f32>,
     font_size: f32,
+    runs: Vec<TextRun>,
+    localization_key: Option<String>,
+    fallback_fonts: Option<Vec<FontResource>>,
 }
 
 impl TextBuilder {
@@ -493,6 +671,9 @@ impl TextBuilder {
             shadow_dilation: 1.0,
             shadow_offset: Vector2::new(1.0, 1.0),
             font_size: 14.0,
+            runs: Vec::new(),
+            localization_key: None,
+            fallback_fonts: None,
         }
     }
 
@@ -563,6 +744,26 @@ impl TextBuilder {
         self
     }
 
+    /// Sets the desired style runs of the text. See [Text](Text#rich-text) for more info.
+    pub fn with_runs(mut self, runs: Vec<TextRun>) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Binds the text to a key of a string table of the localization manager. The text will be
+    /// updated automatically on locale change. See [Text](Text#localization) for more info.
+    pub fn with_localized_text<P: AsRef<str>>(mut self, key: P) -> Self {
+        self.localization_key = Some(key.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the desired fallback fonts of the text. If not set, the fallback fonts of the
+    /// localization manager of the UI are used.
+    pub fn with_fallback_fonts(mut self, fonts: Vec<FontResource>) -> Self {
+        self.fallback_fonts = Some(fonts);
+        self
+    }
+
     /// Finishes text widget creation and registers it in the user interface, returning its handle to you.
     pub fn build(mut self, ui: &mut BuildContext) -> Handle<UiNode> {
         let font = if let Some(font) = self.font {
@@ -575,11 +776,19 @@ impl TextBuilder {
             self.widget_builder.foreground = Some(Brush::Solid(Color::opaque(220, 220, 220)));
         }
 
+        let text = match self.localization_key {
+            Some(ref key) => ui.localization().translate_or_key(key).to_owned(),
+            None => self.text.unwrap_or_default(),
+        };
+        let fallback_fonts = self
+            .fallback_fonts
+            .unwrap_or_else(|| ui.localization().fallback_fonts().to_vec());
+
         let text = Text {
             widget: self.widget_builder.build(),
             formatted_text: RefCell::new(
                 FormattedTextBuilder::new(font)
-                    .with_text(self.text.unwrap_or_default())
+                    .with_text(text)
                     .with_vertical_alignment(self.vertical_text_alignment)
                     .with_horizontal_alignment(self.horizontal_text_alignment)
                     .with_wrap(self.wrap)
@@ -588,8 +797,11 @@ impl TextBuilder {
                     .with_shadow_dilation(self.shadow_dilation)
                     .with_shadow_offset(self.shadow_offset)
                     .with_font_size(self.font_size)
+                    .with_runs(self.runs)
+                    .with_fallback_fonts(fallback_fonts)
                     .build(),
             ),
+            localization_key: self.localization_key.into(),
         };
         ui.add_node(UiNode::new(text))
     }
@@ -1408,6 +1408,19 @@ impl Control for TextBox {
                                 ui.send_message(message.reverse());
                             }
                         }
+                        // Style runs are not adjusted on edits, so the text box does not
+                        // support them.
+                        TextMessage::Runs(_) | TextMessage::LinkClicked(_) => {}
+                        // Text box content is edited by a user, so it is never localized.
+                        TextMessage::Localized(_) => {}
+                        TextMessage::FallbackFonts(fonts) => {
+                            if text.fallback_fonts() != fonts.as_slice() {
+                                text.set_fallback_fonts(fonts.clone());
+                                drop(text);
+                                self.invalidate_layout();
+                                ui.send_message(message.reverse());
+                            }
+                        }
                     }
                 }
             } else if let Some(msg) = message.data::<TextBoxMessage>() {
@@ -1646,6 +1659,7 @@ impl TextBoxBuilder {
                     .with_shadow_dilation(self.shadow_dilation)
                     .with_shadow_offset(self.shadow_offset)
                     .with_font_size(self.font_size)
+                    .with_fallback_fonts(ctx.localization().fallback_fonts().to_vec())
                     .build(),
             ),
             selection_range: None.into(),
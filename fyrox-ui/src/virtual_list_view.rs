@@ -0,0 +1,660 @@
+// Copyright (c) 2019-present Dmitry Stepanov and Fyrox Engine contributors.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Virtual list view is used to display lists with huge amount of items. Unlike [`crate::list_view::ListView`],
+//! it creates widgets only for the items that are currently visible. See [`VirtualListView`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2, math::Rect, parking_lot::Mutex, pool::Handle, reflect::prelude::*,
+        type_traits::prelude::*, uuid_provider, visitor::prelude::*,
+    },
+    define_constructor,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::{KeyCode, MessageDirection, MouseButton, UiMessage},
+    scroll_bar::{ScrollBarBuilder, ScrollBarMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, Orientation, UiNode, UserInterface, BRUSH_DARK, BRUSH_LIGHTER,
+};
+use fyrox_graph::BaseSceneGraph;
+use std::{
+    cell::Cell,
+    fmt::{Debug, Formatter},
+    ops::{Deref, DerefMut, Range},
+    sync::Arc,
+};
+
+/// A set of messages that can be used to modify/fetch the state of a [`VirtualListView`] widget at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VirtualListViewMessage {
+    /// A message, that is used to set new amount of items of the list view. Every visible item will be
+    /// re-created using the item provider.
+    ItemCount(usize),
+    /// A message, that is used to re-create every visible item, it should be used when the data, that is
+    /// shown by the list view, has changed.
+    Refresh,
+    /// A message, that is used to either fetch or modify current selection of a [`VirtualListView`] widget.
+    SelectionChanged(Vec<usize>),
+    /// A message, that is used to bring an item with the given index into view.
+    BringItemIntoView(usize),
+}
+
+impl VirtualListViewMessage {
+    define_constructor!(
+        /// Creates [`VirtualListViewMessage::ItemCount`] message.
+        VirtualListViewMessage:ItemCount => fn item_count(usize), layout: false
+    );
+    define_constructor!(
+        /// Creates [`VirtualListViewMessage::Refresh`] message.
+        VirtualListViewMessage:Refresh => fn refresh(), layout: false
+    );
+    define_constructor!(
+        /// Creates [`VirtualListViewMessage::SelectionChanged`] message.
+        VirtualListViewMessage:SelectionChanged => fn selection(Vec<usize>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`VirtualListViewMessage::BringItemIntoView`] message.
+        VirtualListViewMessage:BringItemIntoView => fn bring_item_into_view(usize), layout: false
+    );
+}
+
+/// Item provider is a callback, that creates a widget for an item with the given index. It is called
+/// every time when an item becomes visible.
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct ItemProvider(
+    pub Arc<Mutex<dyn FnMut(usize, &mut BuildContext) -> Handle<UiNode> + Send>>,
+);
+
+impl ItemProvider {
+    /// Creates new item provider from the given callback.
+    pub fn new<F>(provider: F) -> Self
+    where
+        F: FnMut(usize, &mut BuildContext) -> Handle<UiNode> + 'static + Send,
+    {
+        Self(Arc::new(Mutex::new(provider)))
+    }
+}
+
+impl PartialEq for ItemProvider {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(&*self.0, &*other.0)
+    }
+}
+
+impl Debug for ItemProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ItemProvider")
+    }
+}
+
+/// Virtual list view is used to display lists with huge amount of items (inventories, log consoles, etc.).
+/// Every item has the same height and the list view creates widgets only for the items that are currently
+/// visible, so a list with 100k items creates just a few dozens of widgets. Widgets of the items are created
+/// by an [`ItemProvider`] when they become visible and destroyed when they go out of view, so they must not
+/// be used to store any state.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_ui::{
+/// #     core::pool::Handle,
+/// #     text::TextBuilder,
+/// #     virtual_list_view::{ItemProvider, VirtualListViewBuilder},
+/// #     widget::WidgetBuilder,
+/// #     BuildContext, UiNode,
+/// # };
+/// fn create_list(ctx: &mut BuildContext) -> Handle<UiNode> {
+///     VirtualListViewBuilder::new(WidgetBuilder::new())
+///         .with_item_count(100_000)
+///         .with_item_height(20.0)
+///         .with_item_provider(ItemProvider::new(|index, ctx| {
+///             TextBuilder::new(WidgetBuilder::new())
+///                 .with_text(format!("Item {index}"))
+///                 .build(ctx)
+///         }))
+///         .build(ctx)
+/// }
+/// ```
+///
+/// ## Selection
+///
+/// Selection is stored as a set of item indices, so it does not depend on the visible items. Clicking on an
+/// item selects it, `Ctrl` adds an item to the selection and `Shift` selects a range of items. Selection could
+/// also be changed using arrow keys, `PageUp`, `PageDown`, `Home` and `End` keys. Use
+/// [`VirtualListViewMessage::SelectionChanged`] message with [`MessageDirection::ToWidget`] direction to change
+/// the selection and catch the same message with [`MessageDirection::FromWidget`] direction to react on changes
+/// of the selection.
+#[derive(Default, Clone, Visit, Reflect, Debug, ComponentProvider)]
+pub struct VirtualListView {
+    /// Base widget of the list view.
+    pub widget: Widget,
+    /// Total amount of items.
+    pub item_count: usize,
+    /// Height of every item.
+    pub item_height: f32,
+    /// Current selection.
+    pub selection: Vec<usize>,
+    /// Current vertical scroll offset.
+    pub scroll_offset: f32,
+    /// A brush, that is used to highlight selected items.
+    pub selection_brush: Brush,
+    /// Current scroll bar instance.
+    pub scroll_bar: Handle<UiNode>,
+    /// Current item provider.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub item_provider: Option<ItemProvider>,
+    // Pairs of (index, widget) of the items, that are currently visible.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    realized_items: Vec<(usize, Handle<UiNode>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    viewport_height: Cell<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    selection_anchor: Option<usize>,
+}
+
+crate::define_widget_deref!(VirtualListView);
+
+uuid_provider!(VirtualListView = "ded4724a-52d6-4177-9719-f4634feb089c");
+
+impl VirtualListView {
+    /// Returns pairs of (index, widget) of the items, that are currently visible.
+    pub fn realized_items(&self) -> &[(usize, Handle<UiNode>)] {
+        &self.realized_items
+    }
+
+    fn item_height(&self) -> f32 {
+        self.item_height.max(1.0)
+    }
+
+    fn visible_range(&self) -> Range<usize> {
+        let first = (self.scroll_offset / self.item_height()).floor().max(0.0) as usize;
+        let last = ((self.scroll_offset + self.viewport_height.get()) / self.item_height()).ceil()
+            as usize;
+        first.min(self.item_count)..last.min(self.item_count)
+    }
+
+    fn items_per_page(&self) -> usize {
+        ((self.viewport_height.get() / self.item_height()) as usize).max(1)
+    }
+
+    // Destroys the widgets of the items, that went out of view, and creates the widgets of the items,
+    // that became visible.
+    fn sync_items(&mut self, ui: &mut UserInterface, recreate: bool) {
+        let range = self.visible_range();
+
+        self.realized_items.retain(|(index, item)| {
+            let keep = !recreate && range.contains(index);
+            if !keep {
+                ui.send_message(WidgetMessage::remove(*item, MessageDirection::ToWidget));
+            }
+            keep
+        });
+
+        if let Some(provider) = self.item_provider.as_ref() {
+            let mut provider = provider.0.lock();
+            for index in range {
+                if !self.realized_items.iter().any(|(i, _)| *i == index) {
+                    let item = (provider)(index, &mut ui.build_ctx());
+                    ui.send_message(WidgetMessage::link(
+                        item,
+                        MessageDirection::ToWidget,
+                        self.handle,
+                    ));
+                    self.realized_items.push((index, item));
+                }
+            }
+        }
+
+        self.invalidate_arrange();
+    }
+
+    fn bring_into_view(&self, index: usize, ui: &UserInterface) {
+        let top = index as f32 * self.item_height();
+        let bottom = top + self.item_height();
+        let viewport_height = self.viewport_height.get();
+
+        let new_offset = if top < self.scroll_offset {
+            top
+        } else if bottom > self.scroll_offset + viewport_height {
+            bottom - viewport_height
+        } else {
+            return;
+        };
+
+        ui.send_message(ScrollBarMessage::value(
+            self.scroll_bar,
+            MessageDirection::ToWidget,
+            new_offset,
+        ));
+    }
+
+    fn set_selection(&mut self, selection: &[usize], ui: &UserInterface) {
+        if self.selection != selection {
+            self.selection = selection.to_vec();
+            ui.send_message(VirtualListViewMessage::selection(
+                self.handle,
+                MessageDirection::FromWidget,
+                self.selection.clone(),
+            ));
+        }
+    }
+
+    fn fix_selection(&mut self, ui: &UserInterface) {
+        let selection = self
+            .selection
+            .iter()
+            .cloned()
+            .filter(|i| *i < self.item_count)
+            .collect::<Vec<_>>();
+        self.set_selection(&selection, ui);
+        if self.selection_anchor.is_some_and(|a| a >= self.item_count) {
+            self.selection_anchor = None;
+        }
+    }
+
+    fn item_at(&self, screen_position: Vector2<f32>) -> Option<usize> {
+        let local_position = self.screen_to_local(screen_position);
+        let index = ((local_position.y + self.scroll_offset) / self.item_height()).floor();
+        (index >= 0.0 && (index as usize) < self.item_count).then_some(index as usize)
+    }
+
+    fn select_by_click(&mut self, index: usize, ui: &UserInterface) {
+        let modifiers = ui.keyboard_modifiers();
+        let new_selection = match self.selection_anchor {
+            Some(anchor) if modifiers.shift => {
+                (anchor.min(index)..=anchor.max(index)).collect::<Vec<_>>()
+            }
+            _ if modifiers.control => {
+                let mut selection = self.selection.clone();
+                if let Some(position) = selection.iter().position(|i| *i == index) {
+                    selection.remove(position);
+                } else {
+                    selection.push(index);
+                }
+                selection
+            }
+            _ => vec![index],
+        };
+
+        if !modifiers.shift {
+            self.selection_anchor = Some(index);
+        }
+
+        self.set_selection(&new_selection, ui);
+    }
+}
+
+impl Control for VirtualListView {
+    fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
+        ui.measure_node(self.scroll_bar, available_size);
+        let scroll_bar_width = ui.node(self.scroll_bar).desired_size().x;
+
+        let mut width = 0.0f32;
+        for (_, item) in self.realized_items.iter() {
+            ui.measure_node(
+                *item,
+                Vector2::new(available_size.x - scroll_bar_width, self.item_height()),
+            );
+            width = width.max(ui.node(*item).desired_size().x);
+        }
+
+        Vector2::new(
+            (width + scroll_bar_width).min(available_size.x),
+            (self.item_count as f32 * self.item_height()).min(available_size.y),
+        )
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vector2<f32>) -> Vector2<f32> {
+        let scroll_bar_width = ui.node(self.scroll_bar).desired_size().x;
+        let items_width = (final_size.x - scroll_bar_width).max(0.0);
+
+        ui.arrange_node(
+            self.scroll_bar,
+            &Rect::new(items_width, 0.0, scroll_bar_width, final_size.y),
+        );
+
+        for (index, item) in self.realized_items.iter() {
+            ui.arrange_node(
+                *item,
+                &Rect::new(
+                    0.0,
+                    *index as f32 * self.item_height() - self.scroll_offset,
+                    items_width,
+                    self.item_height(),
+                ),
+            );
+        }
+
+        self.viewport_height.set(final_size.y);
+
+        let content_height = self.item_count as f32 * self.item_height();
+        ui.send_message(ScrollBarMessage::max_value(
+            self.scroll_bar,
+            MessageDirection::ToWidget,
+            (content_height - final_size.y).max(0.0),
+        ));
+        ui.send_message(ScrollBarMessage::size_ratio(
+            self.scroll_bar,
+            MessageDirection::ToWidget,
+            if content_height > f32::EPSILON {
+                (final_size.y / content_height).min(1.0)
+            } else {
+                1.0
+            },
+        ));
+
+        final_size
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.bounding_rect();
+        drawing_context.push_rect_filled(&bounds, None);
+        drawing_context.commit(
+            self.clip_bounds(),
+            self.widget.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        let visible_range = self.visible_range();
+        for &index in self.selection.iter() {
+            if visible_range.contains(&index) {
+                drawing_context.push_rect_filled(
+                    &Rect::new(
+                        bounds.x(),
+                        bounds.y() + index as f32 * self.item_height() - self.scroll_offset,
+                        bounds.w(),
+                        self.item_height(),
+                    ),
+                    None,
+                );
+            }
+        }
+        drawing_context.commit(
+            self.clip_bounds(),
+            self.selection_brush.clone(),
+            CommandTexture::None,
+            None,
+        );
+    }
+
+    fn update(&mut self, _dt: f32, ui: &mut UserInterface) {
+        // Viewport size is known only after the layout pass, so the list of visible items is synced
+        // here to catch any changes of the size of the list view.
+        let range = self.visible_range();
+        if self.realized_items.len() != range.len()
+            || self.realized_items.iter().any(|(i, _)| !range.contains(i))
+        {
+            self.sync_items(ui, false);
+        }
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(msg) = message.data::<VirtualListViewMessage>() {
+            if message.destination() == self.handle()
+                && message.direction() == MessageDirection::ToWidget
+            {
+                match msg {
+                    &VirtualListViewMessage::ItemCount(count) => {
+                        self.item_count = count;
+                        self.fix_selection(ui);
+                        self.sync_items(ui, true);
+                        self.invalidate_layout();
+                    }
+                    VirtualListViewMessage::Refresh => {
+                        self.sync_items(ui, true);
+                    }
+                    VirtualListViewMessage::SelectionChanged(selection) => {
+                        self.set_selection(selection, ui);
+                    }
+                    &VirtualListViewMessage::BringItemIntoView(index) => {
+                        if index < self.item_count {
+                            self.bring_into_view(index, ui);
+                        }
+                    }
+                }
+            }
+        } else if let Some(ScrollBarMessage::Value(value)) = message.data() {
+            if message.destination() == self.scroll_bar
+                && message.direction() == MessageDirection::FromWidget
+            {
+                self.scroll_offset = *value;
+                self.sync_items(ui, false);
+            }
+        } else if let Some(msg) = message.data::<WidgetMessage>() {
+            match msg {
+                WidgetMessage::MouseWheel { amount, .. } => {
+                    if !message.handled() {
+                        ui.send_message(ScrollBarMessage::value(
+                            self.scroll_bar,
+                            MessageDirection::ToWidget,
+                            self.scroll_offset - amount * self.item_height() * 3.0,
+                        ));
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::MouseDown { pos, button } => {
+                    if *button == MouseButton::Left
+                        && !message.handled()
+                        && !ui
+                            .node(self.scroll_bar)
+                            .has_descendant(message.destination(), ui)
+                        && message.destination() != self.scroll_bar
+                    {
+                        if let Some(index) = self.item_at(*pos) {
+                            self.select_by_click(index, ui);
+                            message.set_handled(true);
+                        }
+                    }
+                }
+                WidgetMessage::KeyDown(key_code) => {
+                    if !message.handled() && self.item_count > 0 {
+                        let last = self.item_count - 1;
+                        let current = self.selection.last().cloned();
+                        let new_index = match key_code {
+                            KeyCode::ArrowDown => {
+                                Some(current.map_or(0, |i| i.saturating_add(1).min(last)))
+                            }
+                            KeyCode::ArrowUp => Some(current.map_or(0, |i| i.saturating_sub(1))),
+                            KeyCode::PageDown => {
+                                Some(current.map_or(0, |i| {
+                                    i.saturating_add(self.items_per_page()).min(last)
+                                }))
+                            }
+                            KeyCode::PageUp => {
+                                Some(current.map_or(0, |i| i.saturating_sub(self.items_per_page())))
+                            }
+                            KeyCode::Home => Some(0),
+                            KeyCode::End => Some(last),
+                            _ => None,
+                        };
+
+                        if let Some(new_index) = new_index {
+                            self.selection_anchor = Some(new_index);
+                            self.set_selection(&[new_index], ui);
+                            self.bring_into_view(new_index, ui);
+                            message.set_handled(true);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Virtual list view builder is used to create [`VirtualListView`] widget instances and add them to a user
+/// interface.
+pub struct VirtualListViewBuilder {
+    widget_builder: WidgetBuilder,
+    item_count: usize,
+    item_height: f32,
+    item_provider: Option<ItemProvider>,
+    selection_brush: Brush,
+}
+
+impl VirtualListViewBuilder {
+    /// Creates new virtual list view builder.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            item_count: 0,
+            item_height: 20.0,
+            item_provider: None,
+            selection_brush: BRUSH_LIGHTER,
+        }
+    }
+
+    /// Sets the total amount of items.
+    pub fn with_item_count(mut self, count: usize) -> Self {
+        self.item_count = count;
+        self
+    }
+
+    /// Sets the height of every item.
+    pub fn with_item_height(mut self, height: f32) -> Self {
+        self.item_height = height;
+        self
+    }
+
+    /// Sets the item provider, that will be used to create widgets for visible items.
+    pub fn with_item_provider(mut self, provider: ItemProvider) -> Self {
+        self.item_provider = Some(provider);
+        self
+    }
+
+    /// Sets the brush, that will be used to highlight selected items.
+    pub fn with_selection_brush(mut self, brush: Brush) -> Self {
+        self.selection_brush = brush;
+        self
+    }
+
+    /// Finishes virtual list view building and adds it to the user interface.
+    pub fn build(mut self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        if self.widget_builder.background.is_none() {
+            self.widget_builder.background = Some(BRUSH_DARK);
+        }
+
+        let scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new().with_width(16.0))
+            .with_step(self.item_height)
+            .with_orientation(Orientation::Vertical)
+            .build(ctx);
+
+        let list_view = VirtualListView {
+            widget: self
+                .widget_builder
+                .with_accepts_input(true)
+                .with_need_update(true)
+                .with_clip_to_bounds(true)
+                .with_child(scroll_bar)
+                .build(),
+            item_count: self.item_count,
+            item_height: self.item_height,
+            selection: Default::default(),
+            scroll_offset: 0.0,
+            selection_brush: self.selection_brush,
+            scroll_bar,
+            item_provider: self.item_provider,
+            realized_items: Default::default(),
+            viewport_height: Default::default(),
+            selection_anchor: None,
+        };
+
+        ctx.add_node(UiNode::new(list_view))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector2, pool::Handle},
+        message::MessageDirection,
+        text::TextBuilder,
+        virtual_list_view::{
+            ItemProvider, VirtualListView, VirtualListViewBuilder, VirtualListViewMessage,
+        },
+        widget::WidgetBuilder,
+        UiNode, UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    fn realized_indices(ui: &UserInterface, list_view: Handle<UiNode>) -> Vec<usize> {
+        let mut indices = ui
+            .node(list_view)
+            .cast::<VirtualListView>()
+            .unwrap()
+            .realized_items()
+            .iter()
+            .map(|(i, _)| *i)
+            .collect::<Vec<_>>();
+        indices.sort();
+        indices
+    }
+
+    #[test]
+    fn test_virtualization() {
+        let screen_size = Vector2::new(200.0, 200.0);
+        let mut ui = UserInterface::new(screen_size);
+        let list_view = VirtualListViewBuilder::new(WidgetBuilder::new().with_height(100.0))
+            .with_item_count(100_000)
+            .with_item_height(20.0)
+            .with_item_provider(ItemProvider::new(|index, ctx| {
+                TextBuilder::new(WidgetBuilder::new())
+                    .with_text(format!("Item {index}"))
+                    .build(ctx)
+            }))
+            .build(&mut ui.build_ctx());
+
+        ui.update(screen_size, 0.0, &Default::default());
+        while ui.poll_message().is_some() {}
+        assert_eq!(realized_indices(&ui, list_view), vec![0, 1, 2, 3, 4]);
+
+        ui.send_message(VirtualListViewMessage::bring_item_into_view(
+            list_view,
+            MessageDirection::ToWidget,
+            5000,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(
+            realized_indices(&ui, list_view),
+            vec![4996, 4997, 4998, 4999, 5000]
+        );
+
+        ui.send_message(VirtualListViewMessage::item_count(
+            list_view,
+            MessageDirection::ToWidget,
+            3,
+        ));
+        while ui.poll_message().is_some() {}
+        ui.update(screen_size, 0.0, &Default::default());
+        while ui.poll_message().is_some() {}
+        assert_eq!(realized_indices(&ui, list_view), vec![0, 1, 2]);
+    }
+}
@@ -1416,7 +1416,13 @@ impl Widget {
                         }
                     }
                     WidgetMessage::RenderTransform(transform) => {
-                        self.render_transform = *transform;
+                        if &self.render_transform != transform {
+                            self.render_transform = *transform;
+                            if let Some(layout_events_sender) = self.layout_events_sender.as_ref() {
+                                let _ = layout_events_sender
+                                    .send(LayoutEvent::TransformChanged(self.handle));
+                            }
+                        }
                     }
                     WidgetMessage::ZIndex(index) => {
                         if *self.z_index != *index {
@@ -1532,11 +1538,14 @@ impl Widget {
         final_size
     }
 
+    /// Commits the results of arrangement and returns `true` if the position or the size of the
+    /// widget has changed.
     #[inline]
-    pub(crate) fn commit_arrange(&self, position: Vector2<f32>, size: Vector2<f32>) {
-        self.actual_local_size.set(size);
-        self.actual_local_position.set(position);
+    pub(crate) fn commit_arrange(&self, position: Vector2<f32>, size: Vector2<f32>) -> bool {
+        let old_size = self.actual_local_size.replace(size);
+        let old_position = self.actual_local_position.replace(position);
         self.arrange_valid.set(true);
+        old_size != size || old_position != position
     }
 
     #[inline]
@@ -447,8 +447,7 @@ impl Control for Window {
 
                         // Check grips.
                         for grip in self.grips.borrow_mut().iter_mut() {
-                            let offset = self.screen_position();
-                            let screen_bounds = grip.bounds.translate(offset);
+                            let screen_bounds = grip.bounds.transform(&self.visual_transform);
                             if screen_bounds.contains(pos) {
                                 grip.is_dragging = true;
                                 self.initial_position = self.screen_position();
@@ -472,8 +471,7 @@ impl Control for Window {
                         let mut new_cursor = None;
 
                         for grip in self.grips.borrow().iter() {
-                            let offset = self.screen_position();
-                            let screen_bounds = grip.bounds.translate(offset);
+                            let screen_bounds = grip.bounds.transform(&self.visual_transform);
                             if grip.is_dragging || screen_bounds.contains(pos) {
                                 new_cursor = Some(grip.cursor);
                             }
@@ -493,8 +491,11 @@ impl Control for Window {
 
                                 let new_pos = self.initial_position
                                     + Vector2::new(delta.x * dx, delta.y * dy);
-                                let new_size =
-                                    self.initial_size + Vector2::new(delta.x * dw, delta.y * dh);
+                                // The size is in logical units, while the mouse position is in screen
+                                // pixels.
+                                let size_delta = delta / ui.scale();
+                                let new_size = self.initial_size
+                                    + Vector2::new(size_delta.x * dw, size_delta.y * dh);
 
                                 if new_size.x > self.min_width()
                                     && new_size.x < self.max_width()
@@ -829,11 +830,11 @@ impl Control for Window {
                             // application window, thus leaving an opportunity to drag window to some other place.
                             new_pos.x = new_pos.x.clamp(
                                 -(self.actual_local_size().x - safe_border.x).abs(),
-                                (ui.screen_size().x - safe_border.x).abs(),
+                                (ui.logical_screen_size().x - safe_border.x).abs(),
                             );
                             new_pos.y = new_pos
                                 .y
-                                .clamp(0.0, (ui.screen_size().y - safe_border.y).abs());
+                                .clamp(0.0, (ui.logical_screen_size().y - safe_border.y).abs());
                         }
 
                         if self.is_dragging && self.desired_local_position() != new_pos {
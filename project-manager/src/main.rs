@@ -86,6 +86,7 @@ fn main() {
             window_attributes,
             vsync: true,
             msaa_sample_count: None,
+            graphics_server_constructor: Default::default(),
         },
         resource_manager: ResourceManager::new(task_pool.clone()),
         serialization_context,